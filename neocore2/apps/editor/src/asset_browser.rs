@@ -0,0 +1,676 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use newengine_assets::{Model3dReader, Ne3dMesh, TextReader};
+use newengine_core::assets_service::{method, ASSET_SERVICE_ID};
+use newengine_platform_winit::app::config::WinitAppIcon;
+use newengine_platform_winit::egui;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+/// Text previews are cut here; the inspector is not an editor.
+const MAX_PREVIEW_CHARS: usize = 16 * 1024;
+const TILE_SIZE: [f32; 2] = [96.0, 64.0];
+
+const TEXT_TYPE_ID: &str = "kalitech.asset.text";
+const TEXTURE_TYPE_ID: &str = "kalitech.asset.texture";
+const MODEL_TYPE_ID: &str = "kalitech.asset.model3d";
+const AUDIO_TYPE_ID: &str = "kalitech.asset.audio";
+
+#[derive(Debug, Clone, Deserialize)]
+struct BrowseRow {
+    logical_path: String,
+    #[serde(default)]
+    ext: Option<String>,
+    state: String,
+    #[serde(default)]
+    type_id: Option<String>,
+    #[serde(default)]
+    format: Option<String>,
+    #[serde(default)]
+    bytes: Option<u64>,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct InfoResponse {
+    #[serde(default)]
+    type_id: Option<String>,
+    #[serde(default)]
+    meta_json: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LoadResponse {
+    ok: bool,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ViewMode {
+    Tree,
+    Grid,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AssetKind {
+    Text,
+    Image,
+    Mesh,
+    Audio,
+    Other,
+}
+
+impl AssetKind {
+    /// The imported type wins; the extension is a guess for assets not loaded yet.
+    fn of(row: &BrowseRow) -> Self {
+        match row.type_id.as_deref() {
+            Some(TEXT_TYPE_ID) => return Self::Text,
+            Some(TEXTURE_TYPE_ID) => return Self::Image,
+            Some(MODEL_TYPE_ID) => return Self::Mesh,
+            Some(AUDIO_TYPE_ID) => return Self::Audio,
+            _ => {}
+        }
+        match row.ext.as_deref().unwrap_or_default() {
+            "txt" | "json" | "xml" | "html" | "htm" | "md" | "toml" | "ron" | "csv" => Self::Text,
+            "png" | "jpg" | "jpeg" | "bmp" | "tga" | "gif" | "dds" | "webp" => Self::Image,
+            "obj" | "gltf" | "glb" | "fbx" | "ne3d" => Self::Mesh,
+            "wav" | "ogg" | "mp3" | "flac" => Self::Audio,
+            _ => Self::Other,
+        }
+    }
+
+    fn badge(self) -> (&'static str, egui::Color32) {
+        match self {
+            Self::Text => ("TXT", egui::Color32::from_rgb(170, 170, 220)),
+            Self::Image => ("IMG", egui::Color32::from_rgb(120, 200, 160)),
+            Self::Mesh => ("3D", egui::Color32::from_rgb(230, 170, 90)),
+            Self::Audio => ("SND", egui::Color32::from_rgb(200, 130, 210)),
+            Self::Other => ("BIN", egui::Color32::GRAY),
+        }
+    }
+}
+
+/// Folder node of the tree view; files are indices into `AssetBrowser::rows`.
+#[derive(Debug, Default)]
+struct DirNode {
+    dirs: BTreeMap<String, DirNode>,
+    files: Vec<usize>,
+}
+
+struct MeshSummary {
+    vertices: usize,
+    triangles: usize,
+    submeshes: usize,
+    materials: usize,
+    /// Triangles per LOD, LOD 0 first.
+    lod_triangles: Vec<u32>,
+    joints: usize,
+    clips: usize,
+    bbox: Option<([f32; 3], [f32; 3])>,
+}
+
+enum Preview {
+    Text(String),
+    Image {
+        texture: egui::TextureHandle,
+        width: u32,
+        height: u32,
+    },
+    Mesh(MeshSummary),
+    /// No visual preview for this type/container; shows the importer meta instead.
+    Meta(String),
+    Error(String),
+}
+
+/// Editor window over the asset sources: every file with its type and load state, load/reload
+/// through `asset.manager`, and a preview of the selected asset.
+pub struct AssetBrowser {
+    open: bool,
+    mode: ViewMode,
+    filter: String,
+    rows: Vec<BrowseRow>,
+    last_poll: Option<Instant>,
+    error: Option<String>,
+    selected: Option<String>,
+    /// Preview of `selected`; dropped whenever the asset leaves the ready state.
+    preview: Option<(String, Preview)>,
+}
+
+impl Default for AssetBrowser {
+    fn default() -> Self {
+        Self {
+            open: false,
+            mode: ViewMode::Tree,
+            filter: String::new(),
+            rows: Vec::new(),
+            last_poll: None,
+            error: None,
+            selected: None,
+            preview: None,
+        }
+    }
+}
+
+impl AssetBrowser {
+    pub fn toolbar_ui(&mut self, ui: &mut egui::Ui) {
+        ui.toggle_value(&mut self.open, "Assets");
+    }
+
+    fn poll(&mut self) {
+        if self.last_poll.is_some_and(|t| t.elapsed() < POLL_INTERVAL) {
+            return;
+        }
+        self.last_poll = Some(Instant::now());
+
+        let bytes =
+            match newengine_core::call_service_v1(ASSET_SERVICE_ID, method::BROWSE_JSON, &[]) {
+                Ok(b) => b,
+                Err(e) => {
+                    self.error = Some(e);
+                    return;
+                }
+            };
+
+        match serde_json::from_slice::<Vec<BrowseRow>>(&bytes) {
+            Ok(rows) => {
+                self.error = None;
+                self.rows = rows;
+            }
+            Err(e) => self.error = Some(format!("bad {} response: {e}", method::BROWSE_JSON)),
+        }
+    }
+
+    /// `asset.load` / `asset.reload` for `path`; the next poll picks up the new state.
+    fn request(&mut self, call: &str, path: &str) {
+        self.preview = None;
+        self.last_poll = None;
+
+        let resp = newengine_core::call_service_v1(ASSET_SERVICE_ID, call, path.as_bytes())
+            .and_then(|b| {
+                serde_json::from_slice::<LoadResponse>(&b)
+                    .map_err(|e| format!("bad {call} response: {e}"))
+            });
+        match resp {
+            Ok(r) if r.ok => self.error = None,
+            Ok(r) => self.error = r.error.or_else(|| Some(format!("{call} failed"))),
+            Err(e) => self.error = Some(e),
+        }
+    }
+
+    pub fn ui(&mut self, ctx: &egui::Context) {
+        if !self.open {
+            return;
+        }
+        self.poll();
+
+        let mut open = self.open;
+        egui::Window::new("Assets")
+            .id(egui::Id::new("ne_editor_assets"))
+            .open(&mut open)
+            .default_size([900.0, 520.0])
+            .show(ctx, |ui| {
+                self.header_row(ui);
+                ui.separator();
+
+                egui::SidePanel::right("ne_editor_assets_inspector")
+                    .resizable(true)
+                    .default_width(320.0)
+                    .show_inside(ui, |ui| self.inspector_ui(ui));
+
+                egui::CentralPanel::default().show_inside(ui, |ui| {
+                    egui::ScrollArea::vertical()
+                        .auto_shrink([false, false])
+                        .show(ui, |ui| match self.mode {
+                            ViewMode::Tree => self.tree_ui(ui),
+                            ViewMode::Grid => self.grid_ui(ui),
+                        });
+                });
+            });
+        self.open = open;
+    }
+
+    fn header_row(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.selectable_value(&mut self.mode, ViewMode::Tree, "Tree");
+            ui.selectable_value(&mut self.mode, ViewMode::Grid, "Grid");
+            ui.separator();
+
+            ui.label("Filter:");
+            ui.add(egui::TextEdit::singleline(&mut self.filter).desired_width(180.0));
+            if ui.button("Refresh").clicked() {
+                self.last_poll = None;
+            }
+
+            ui.separator();
+            let ready = self.rows.iter().filter(|r| r.state == "ready").count();
+            ui.label(format!("{} files, {ready} loaded", self.rows.len()));
+
+            if let Some(e) = self.error.as_deref() {
+                ui.separator();
+                ui.colored_label(egui::Color32::from_rgb(230, 120, 80), e);
+            }
+        });
+    }
+
+    fn visible_rows(&self) -> Vec<usize> {
+        let filter = self.filter.to_ascii_lowercase();
+        self.rows
+            .iter()
+            .enumerate()
+            .filter(|(_, r)| {
+                filter.is_empty() || r.logical_path.to_ascii_lowercase().contains(&filter)
+            })
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    fn tree_ui(&mut self, ui: &mut egui::Ui) {
+        let mut root = DirNode::default();
+        for i in self.visible_rows() {
+            let mut node = &mut root;
+            let mut parts: Vec<&str> = self.rows[i].logical_path.split('/').collect();
+            parts.pop();
+            for dir in parts {
+                node = node.dirs.entry(dir.to_string()).or_default();
+            }
+            node.files.push(i);
+        }
+
+        // Filtering expands everything so matches are not hidden in collapsed folders.
+        let expand = !self.filter.is_empty();
+        let mut action = None;
+        self.dir_ui(ui, &root, "", expand, &mut action);
+        self.apply(action);
+    }
+
+    fn dir_ui(
+        &self,
+        ui: &mut egui::Ui,
+        node: &DirNode,
+        prefix: &str,
+        expand: bool,
+        action: &mut Option<RowAction>,
+    ) {
+        for (name, child) in &node.dirs {
+            let path = format!("{prefix}{name}/");
+            egui::CollapsingHeader::new(name.as_str())
+                .id_salt(("ne_asset_dir", &path))
+                .default_open(prefix.is_empty())
+                .open(expand.then_some(true))
+                .show(ui, |ui| self.dir_ui(ui, child, &path, expand, action));
+        }
+
+        for &i in &node.files {
+            let row = &self.rows[i];
+            let name = row.logical_path.rsplit('/').next().unwrap_or_default();
+            let selected = self.selected.as_deref() == Some(row.logical_path.as_str());
+
+            ui.horizontal(|ui| {
+                state_dot(ui, &row.state);
+                let (badge, color) = AssetKind::of(row).badge();
+                ui.label(
+                    egui::RichText::new(format!("{badge:<3}"))
+                        .monospace()
+                        .color(color),
+                );
+
+                let mut resp = ui.selectable_label(selected, name);
+                if let Some(e) = row.error.as_deref() {
+                    resp = resp.on_hover_text(e);
+                }
+                if let Some(a) = RowAction::from_response(&resp, row) {
+                    *action = Some(a);
+                }
+            });
+        }
+    }
+
+    fn grid_ui(&mut self, ui: &mut egui::Ui) {
+        let mut action = None;
+        ui.horizontal_wrapped(|ui| {
+            for i in self.visible_rows() {
+                let row = &self.rows[i];
+                let name = row.logical_path.rsplit('/').next().unwrap_or_default();
+                let selected = self.selected.as_deref() == Some(row.logical_path.as_str());
+                let (badge, color) = AssetKind::of(row).badge();
+
+                let mut job = egui::text::LayoutJob::default();
+                job.append(
+                    badge,
+                    0.0,
+                    egui::TextFormat {
+                        color,
+                        font_id: egui::FontId::monospace(16.0),
+                        ..Default::default()
+                    },
+                );
+                job.append(
+                    &format!("\n{name}"),
+                    0.0,
+                    egui::TextFormat {
+                        color: state_color(&row.state),
+                        ..Default::default()
+                    },
+                );
+
+                let resp = ui
+                    .add_sized(TILE_SIZE, egui::SelectableLabel::new(selected, job))
+                    .on_hover_text(&row.logical_path);
+                if let Some(a) = RowAction::from_response(&resp, row) {
+                    action = Some(a);
+                }
+            }
+        });
+        self.apply(action);
+    }
+
+    fn apply(&mut self, action: Option<RowAction>) {
+        match action {
+            Some(RowAction::Select(path)) => {
+                if self.selected.as_deref() != Some(path.as_str()) {
+                    self.preview = None;
+                }
+                self.selected = Some(path);
+            }
+            Some(RowAction::Load(path)) => {
+                self.request(method::LOAD, &path);
+                self.selected = Some(path);
+            }
+            Some(RowAction::Reload(path)) => {
+                self.request(method::RELOAD, &path);
+                self.selected = Some(path);
+            }
+            None => {}
+        }
+    }
+
+    fn inspector_ui(&mut self, ui: &mut egui::Ui) {
+        let Some(path) = self.selected.clone() else {
+            ui.label("Select an asset. Double-click loads (or reloads) it.");
+            return;
+        };
+        let Some(row) = self.rows.iter().find(|r| r.logical_path == path).cloned() else {
+            ui.label(format!("'{path}' is no longer in the asset sources."));
+            return;
+        };
+
+        ui.heading(row.logical_path.rsplit('/').next().unwrap_or_default());
+        egui::Grid::new("ne_editor_assets_info")
+            .num_columns(2)
+            .show(ui, |ui| {
+                ui.label("Path");
+                ui.monospace(&row.logical_path);
+                ui.end_row();
+                ui.label("State");
+                ui.colored_label(state_color(&row.state), &row.state);
+                ui.end_row();
+                if let Some(t) = row.type_id.as_deref() {
+                    ui.label("Type");
+                    ui.monospace(t);
+                    ui.end_row();
+                }
+                if let Some(f) = row.format.as_deref() {
+                    ui.label("Format");
+                    ui.monospace(f);
+                    ui.end_row();
+                }
+                if let Some(b) = row.bytes {
+                    ui.label("Size");
+                    ui.label(format_bytes(b));
+                    ui.end_row();
+                }
+            });
+
+        if let Some(e) = row.error.as_deref() {
+            ui.colored_label(egui::Color32::from_rgb(235, 90, 90), e);
+        }
+
+        ui.horizontal(|ui| {
+            if ui.button("Load").clicked() {
+                self.request(method::LOAD, &path);
+            }
+            let loaded = row.state == "ready" || row.state == "failed";
+            if ui
+                .add_enabled(loaded, egui::Button::new("Reload"))
+                .clicked()
+            {
+                self.request(method::RELOAD, &path);
+            }
+        });
+        ui.separator();
+
+        if row.state != "ready" {
+            self.preview = None;
+            return;
+        }
+        if self.preview.as_ref().is_none_or(|(p, _)| p != &path) {
+            let preview = build_preview(ui.ctx(), &path);
+            self.preview = Some((path, preview));
+        }
+        if let Some((_, preview)) = self.preview.as_ref() {
+            preview_ui(ui, preview);
+        }
+    }
+}
+
+enum RowAction {
+    Select(String),
+    Load(String),
+    Reload(String),
+}
+
+impl RowAction {
+    /// Click selects; double-click loads, or reloads an asset that was already imported.
+    fn from_response(resp: &egui::Response, row: &BrowseRow) -> Option<Self> {
+        let path = row.logical_path.clone();
+        if resp.double_clicked() {
+            return Some(match row.state.as_str() {
+                "ready" | "failed" => Self::Reload(path),
+                _ => Self::Load(path),
+            });
+        }
+        resp.clicked().then_some(Self::Select(path))
+    }
+}
+
+fn build_preview(ctx: &egui::Context, path: &str) -> Preview {
+    let info =
+        newengine_core::call_service_v1(ASSET_SERVICE_ID, method::INFO_JSON, path.as_bytes())
+            .and_then(|b| {
+                serde_json::from_slice::<InfoResponse>(&b)
+                    .map_err(|e| format!("bad {} response: {e}", method::INFO_JSON))
+            });
+    let info = match info {
+        Ok(i) => i,
+        Err(e) => return Preview::Error(e),
+    };
+    let payload =
+        match newengine_core::call_service_v1(ASSET_SERVICE_ID, method::READ, path.as_bytes()) {
+            Ok(b) => b,
+            Err(e) => return Preview::Error(e),
+        };
+    let meta = info.meta_json.unwrap_or_default();
+
+    match info.type_id.as_deref() {
+        Some(TEXT_TYPE_ID) => match TextReader::from_blob_parts(&meta, &payload) {
+            Ok(doc) => {
+                let mut text: String = doc.text.chars().take(MAX_PREVIEW_CHARS).collect();
+                if text.len() < doc.text.len() {
+                    text.push_str("\n…");
+                }
+                Preview::Text(text)
+            }
+            Err(e) => Preview::Error(e.to_string()),
+        },
+        Some(TEXTURE_TYPE_ID) => image_preview(ctx, path, &meta, &payload),
+        Some(MODEL_TYPE_ID) => {
+            let mesh = Model3dReader::from_blob_parts(&meta, &payload)
+                .map_err(|e| e.to_string())
+                .and_then(|m| Ne3dMesh::decode(&m.payload).map_err(|e| e.to_string()));
+            match mesh {
+                Ok(mesh) => Preview::Mesh(mesh_summary(&mesh)),
+                Err(e) => Preview::Error(e),
+            }
+        }
+        _ => Preview::Meta(pretty_json(&meta)),
+    }
+}
+
+/// Image payloads keep their source container; only PNG is decoded here (the same decoder as
+/// the window icon). Other containers show their meta.
+fn image_preview(ctx: &egui::Context, path: &str, meta: &str, payload: &[u8]) -> Preview {
+    let container = serde_json::from_str::<serde_json::Value>(meta)
+        .ok()
+        .and_then(|v| v.get("container")?.as_str().map(str::to_owned));
+    if container.as_deref() != Some("png") {
+        return Preview::Meta(pretty_json(meta));
+    }
+
+    match WinitAppIcon::from_png_bytes(payload) {
+        Ok(img) => {
+            let size = [img.width as usize, img.height as usize];
+            let color = egui::ColorImage::from_rgba_unmultiplied(size, &img.rgba);
+            let texture = ctx.load_texture(
+                format!("ne_asset_preview:{path}"),
+                color,
+                egui::TextureOptions::LINEAR,
+            );
+            Preview::Image {
+                texture,
+                width: img.width,
+                height: img.height,
+            }
+        }
+        Err(e) => Preview::Error(format!("png: {e}")),
+    }
+}
+
+fn mesh_summary(mesh: &Ne3dMesh) -> MeshSummary {
+    let bbox = mesh.positions.iter().fold(None, |acc, p| {
+        let (mut lo, mut hi) = acc.unwrap_or((*p, *p));
+        for k in 0..3 {
+            lo[k] = lo[k].min(p[k]);
+            hi[k] = hi[k].max(p[k]);
+        }
+        Some((lo, hi))
+    });
+    let lod_triangles = if mesh.lods.is_empty() {
+        vec![mesh.indices.len() as u32 / 3]
+    } else {
+        mesh.lods.iter().map(|l| l.index_count / 3).collect()
+    };
+
+    MeshSummary {
+        vertices: mesh.positions.len(),
+        triangles: mesh.lod_range(0).len() / 3,
+        submeshes: mesh.submeshes.len(),
+        materials: mesh.materials.len(),
+        lod_triangles,
+        joints: mesh.skeleton.len(),
+        clips: mesh.clips.len(),
+        bbox,
+    }
+}
+
+fn preview_ui(ui: &mut egui::Ui, preview: &Preview) {
+    match preview {
+        Preview::Text(text) => {
+            egui::ScrollArea::both()
+                .id_salt("ne_editor_assets_text")
+                .auto_shrink([false, false])
+                .show(ui, |ui| {
+                    ui.monospace(text);
+                });
+        }
+        Preview::Image {
+            texture,
+            width,
+            height,
+        } => {
+            ui.label(format!("{width}x{height}"));
+            let max = ui.available_width().max(16.0);
+            let scale = (max / *width as f32).min(1.0);
+            ui.image((
+                texture.id(),
+                egui::vec2(*width as f32 * scale, *height as f32 * scale),
+            ));
+        }
+        Preview::Mesh(m) => {
+            egui::Grid::new("ne_editor_assets_mesh")
+                .num_columns(2)
+                .show(ui, |ui| {
+                    let mut row = |k: &str, v: String| {
+                        ui.label(k);
+                        ui.monospace(v);
+                        ui.end_row();
+                    };
+                    row("Vertices", m.vertices.to_string());
+                    row("Triangles", m.triangles.to_string());
+                    row("Submeshes", m.submeshes.to_string());
+                    row("Materials", m.materials.to_string());
+                    let lods: Vec<String> = m.lod_triangles.iter().map(u32::to_string).collect();
+                    row("LOD tris", lods.join(" / "));
+                    if m.joints > 0 {
+                        row("Joints", m.joints.to_string());
+                        row("Clips", m.clips.to_string());
+                    }
+                    if let Some((lo, hi)) = m.bbox {
+                        row(
+                            "Bounds",
+                            format!(
+                                "{:.2} x {:.2} x {:.2}",
+                                hi[0] - lo[0],
+                                hi[1] - lo[1],
+                                hi[2] - lo[2]
+                            ),
+                        );
+                    }
+                });
+        }
+        Preview::Meta(meta) => {
+            ui.label("No preview for this asset; importer meta:");
+            ui.monospace(meta);
+        }
+        Preview::Error(e) => {
+            ui.colored_label(egui::Color32::from_rgb(235, 90, 90), e);
+        }
+    }
+}
+
+fn state_dot(ui: &mut egui::Ui, state: &str) {
+    let (rect, resp) = ui.allocate_exact_size(egui::vec2(10.0, 10.0), egui::Sense::hover());
+    ui.painter()
+        .circle_filled(rect.center(), 4.0, state_color(state));
+    let _ = resp.on_hover_text(state);
+}
+
+#[inline]
+fn state_color(state: &str) -> egui::Color32 {
+    match state {
+        "ready" => egui::Color32::from_rgb(120, 200, 120),
+        "loading" => egui::Color32::from_rgb(230, 180, 70),
+        "failed" => egui::Color32::from_rgb(235, 90, 90),
+        _ => egui::Color32::GRAY,
+    }
+}
+
+#[inline]
+fn pretty_json(s: &str) -> String {
+    serde_json::from_str::<serde_json::Value>(s)
+        .ok()
+        .and_then(|v| serde_json::to_string_pretty(&v).ok())
+        .unwrap_or_else(|| s.to_string())
+}
+
+#[inline]
+fn format_bytes(b: u64) -> String {
+    match b {
+        0..=1023 => format!("{b} B"),
+        1024..=1_048_575 => format!("{:.1} KiB", b as f64 / 1024.0),
+        _ => format!("{:.1} MiB", b as f64 / (1024.0 * 1024.0)),
+    }
+}
@@ -0,0 +1,316 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use newengine_assets::{AssetBlob, AssetId, AssetState};
+use newengine_core::{Engine, EngineError, EngineResult, StartupConfig};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+const DEFAULT_OUT_DIR: &str = "derived_data";
+const MANIFEST_FILE: &str = "manifest.json";
+/// Default for `--import-timeout`.
+const STALL_TIMEOUT: Duration = Duration::from_secs(120);
+/// Sleep between pumps that produced no asset events, so waiting on reads does not spin a core.
+const IDLE_WAIT: Duration = Duration::from_millis(2);
+
+/// Command line options for `--import <dir> [--out <dir>] [--import-timeout <secs>]`.
+#[derive(Debug, Clone)]
+pub struct BatchImportArgs {
+    pub input_dir: PathBuf,
+    pub out_dir: PathBuf,
+    /// Longest time without any import making progress; then the rest is reported as failed.
+    /// Measured between asset events, so a large tree may take as long as it needs.
+    pub stall_timeout: Duration,
+}
+
+impl BatchImportArgs {
+    /// Returns `Ok(None)` when `--import` is absent (normal editor run).
+    pub fn from_args<I>(args: I) -> Result<Option<Self>, String>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut input_dir: Option<PathBuf> = None;
+        let mut out_dir: Option<PathBuf> = None;
+        let mut stall_timeout = STALL_TIMEOUT;
+
+        let mut it = args.into_iter();
+        while let Some(a) = it.next() {
+            match a.as_str() {
+                "--import" => {
+                    let v = it.next().ok_or("--import requires a directory")?;
+                    input_dir = Some(PathBuf::from(v));
+                }
+                "--out" => {
+                    let v = it.next().ok_or("--out requires a directory")?;
+                    out_dir = Some(PathBuf::from(v));
+                }
+                "--import-timeout" => {
+                    let v = it.next().ok_or("--import-timeout requires seconds")?;
+                    let secs: u64 = v
+                        .parse()
+                        .map_err(|_| format!("--import-timeout: bad seconds '{v}'"))?;
+                    stall_timeout = Duration::from_secs(secs.max(1));
+                }
+                _ => {}
+            }
+        }
+
+        let Some(input_dir) = input_dir else {
+            return Ok(None);
+        };
+
+        Ok(Some(Self {
+            input_dir,
+            out_dir: out_dir.unwrap_or_else(|| PathBuf::from(DEFAULT_OUT_DIR)),
+            stall_timeout,
+        }))
+    }
+
+    /// Points the asset root at the import directory so logical paths are relative to it.
+    pub fn apply_to_startup(&self, startup: &mut StartupConfig) {
+        startup.assets_root = self.input_dir.clone();
+        startup.asset_filesystem_source = true;
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct ManifestEntry {
+    path: String,
+    id: String,
+    ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    type_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    format: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    bytes: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    output: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct Manifest {
+    input_dir: String,
+    total: usize,
+    ok: usize,
+    failed: usize,
+    skipped: Vec<String>,
+    assets: Vec<ManifestEntry>,
+}
+
+/// Runs every registered importer over `args.input_dir`, writes derived data and a manifest.
+///
+/// Returns an error if any asset failed, so the process exits non-zero for CI.
+pub fn run(engine: &mut Engine<()>, args: &BatchImportArgs) -> EngineResult<()> {
+    let am = engine
+        .resources
+        .get::<newengine_core::assets::AssetManager>()
+        .ok_or_else(|| EngineError::other("AssetManager missing in engine.resources"))?;
+    let store = am.store();
+    // Subscribed before the first load so no progress event is missed.
+    let events = store.subscribe_events();
+
+    let exts: HashSet<String> = store
+        .importer_bindings()
+        .into_iter()
+        .map(|b| b.ext)
+        .collect();
+
+    let mut files = Vec::new();
+    collect_files(&args.input_dir, &args.input_dir, &mut files)
+        .map_err(|e| EngineError::other(format!("import: scan failed: {e}")))?;
+    files.sort();
+
+    let mut queued: Vec<(String, AssetId)> = Vec::new();
+    let mut skipped: Vec<String> = Vec::new();
+    let mut entries: Vec<ManifestEntry> = Vec::new();
+
+    for rel in files {
+        let ext = Path::new(&rel)
+            .extension()
+            .map(|e| e.to_string_lossy().to_ascii_lowercase())
+            .unwrap_or_default();
+        if !exts.contains(&ext) {
+            skipped.push(rel);
+            continue;
+        }
+
+        match store.load_path(&rel) {
+            Ok(id) => queued.push((rel, id)),
+            Err(e) => entries.push(ManifestEntry {
+                path: rel,
+                id: String::new(),
+                ok: false,
+                type_id: None,
+                format: None,
+                bytes: None,
+                output: None,
+                error: Some(e.to_string()),
+            }),
+        }
+    }
+
+    log::info!(
+        "import: start dir='{}' queued={} skipped={}",
+        args.input_dir.display(),
+        queued.len(),
+        skipped.len()
+    );
+
+    let t0 = Instant::now();
+    let mut last_progress = t0;
+    while queued
+        .iter()
+        .any(|(_, id)| matches!(store.state(*id), AssetState::Loading))
+    {
+        if last_progress.elapsed() >= args.stall_timeout {
+            log::warn!(
+                "import: no progress for {}s, remaining assets are reported as failed",
+                args.stall_timeout.as_secs()
+            );
+            break;
+        }
+        am.pump();
+        if events.drain().is_empty() {
+            std::thread::sleep(IDLE_WAIT);
+        } else {
+            last_progress = Instant::now();
+        }
+    }
+
+    std::fs::create_dir_all(&args.out_dir).map_err(|e| {
+        EngineError::other(format!(
+            "import: create out dir '{}' failed: {e}",
+            args.out_dir.display()
+        ))
+    })?;
+
+    for (rel, id) in queued {
+        let id_hex = format!("{:032x}", id.to_u128());
+        let entry = match store.state(id) {
+            AssetState::Ready => match store.get_blob(id) {
+                Some(blob) => write_derived(&args.out_dir, &rel, &id_hex, &blob),
+                None => failed_entry(rel, id_hex, "ready but blob is missing".to_string()),
+            },
+            AssetState::Failed(e) => failed_entry(rel, id_hex, e.to_string()),
+            AssetState::Loading | AssetState::Unloaded => {
+                failed_entry(rel, id_hex, "timeout".to_string())
+            }
+        };
+        entries.push(entry);
+    }
+
+    entries.sort_by(|a, b| a.path.cmp(&b.path));
+
+    let failed = entries.iter().filter(|e| !e.ok).count();
+    let manifest = Manifest {
+        input_dir: args.input_dir.to_string_lossy().to_string(),
+        total: entries.len(),
+        ok: entries.len() - failed,
+        failed,
+        skipped,
+        assets: entries,
+    };
+
+    for e in manifest.assets.iter().filter(|e| !e.ok) {
+        log::error!(
+            "import: failed path='{}' err='{}'",
+            e.path,
+            e.error.as_deref().unwrap_or("")
+        );
+    }
+
+    let manifest_path = args.out_dir.join(MANIFEST_FILE);
+    let text = serde_json::to_string_pretty(&manifest)
+        .map_err(|e| EngineError::other(format!("import: manifest serialize failed: {e}")))?;
+    std::fs::write(&manifest_path, text).map_err(|e| {
+        EngineError::other(format!(
+            "import: manifest write failed path='{}' err='{e}'",
+            manifest_path.display()
+        ))
+    })?;
+
+    log::info!(
+        "import: done total={} ok={} failed={} manifest='{}' elapsed_ms={}",
+        manifest.total,
+        manifest.ok,
+        manifest.failed,
+        manifest_path.display(),
+        t0.elapsed().as_millis()
+    );
+
+    if manifest.failed > 0 {
+        return Err(EngineError::other(format!(
+            "import: {} asset(s) failed",
+            manifest.failed
+        )));
+    }
+
+    Ok(())
+}
+
+fn write_derived(out_dir: &Path, rel: &str, id_hex: &str, blob: &AssetBlob) -> ManifestEntry {
+    let file_name = format!("{id_hex}.bin");
+    let meta_name = format!("{id_hex}.meta.json");
+
+    let res = std::fs::write(out_dir.join(&file_name), &blob.payload)
+        .and_then(|_| std::fs::write(out_dir.join(&meta_name), blob.meta_json.as_bytes()));
+
+    match res {
+        Ok(()) => ManifestEntry {
+            path: rel.to_string(),
+            id: id_hex.to_string(),
+            ok: true,
+            type_id: Some(blob.type_id.to_string()),
+            format: Some(blob.format.to_string()),
+            bytes: Some(blob.payload.len() as u64),
+            output: Some(file_name),
+            error: None,
+        },
+        Err(e) => failed_entry(
+            rel.to_string(),
+            id_hex.to_string(),
+            format!("write failed: {e}"),
+        ),
+    }
+}
+
+#[inline]
+fn failed_entry(path: String, id: String, error: String) -> ManifestEntry {
+    ManifestEntry {
+        path,
+        id,
+        ok: false,
+        type_id: None,
+        format: None,
+        bytes: None,
+        output: None,
+        error: Some(error),
+    }
+}
+
+/// Collects files under `dir` as '/'-separated paths relative to `root`.
+fn collect_files(root: &Path, dir: &Path, out: &mut Vec<String>) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let ft = entry.file_type()?;
+
+        if ft.is_dir() {
+            collect_files(root, &path, out)?;
+        } else if ft.is_file() {
+            if let Ok(rel) = path.strip_prefix(root) {
+                let rel = rel
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                out.push(rel);
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,73 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use newengine_platform_winit::egui;
+use std::path::PathBuf;
+
+/// Startup banner shown when the previous session left an unseen crash report.
+#[derive(Debug, Default)]
+pub struct CrashNotice {
+    report: Option<PathBuf>,
+    /// Loaded on first "Show report".
+    text: Option<String>,
+}
+
+impl CrashNotice {
+    #[inline]
+    pub fn new(report: Option<PathBuf>) -> Self {
+        Self { report, text: None }
+    }
+
+    pub fn ui(&mut self, ctx: &egui::Context) {
+        let Some(path) = self.report.clone() else {
+            return;
+        };
+
+        let mut dismiss = false;
+
+        egui::TopBottomPanel::top("ne_editor_crash_notice").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.colored_label(
+                    egui::Color32::from_rgb(230, 120, 80),
+                    "The previous session crashed.",
+                );
+                ui.label(path.display().to_string());
+                ui.separator();
+
+                let label = if self.text.is_some() {
+                    "Hide report"
+                } else {
+                    "Show report"
+                };
+                if ui.button(label).clicked() {
+                    self.text = match self.text.take() {
+                        Some(_) => None,
+                        None => Some(std::fs::read_to_string(&path).unwrap_or_else(|e| {
+                            format!("failed to read '{}': {e}", path.display())
+                        })),
+                    };
+                }
+                if ui.button("Dismiss").clicked() {
+                    dismiss = true;
+                }
+            });
+
+            if let Some(text) = self.text.as_ref() {
+                ui.separator();
+                egui::ScrollArea::vertical()
+                    .max_height(280.0)
+                    .auto_shrink([false, true])
+                    .show(ui, |ui| {
+                        ui.monospace(text.as_str());
+                    });
+            }
+        });
+
+        if dismiss {
+            if let Err(e) = newengine_core::crash::mark_crash_report_seen(&path) {
+                log::warn!("crash: {e}");
+            }
+            self.report = None;
+            self.text = None;
+        }
+    }
+}
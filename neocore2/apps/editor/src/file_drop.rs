@@ -0,0 +1,251 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use newengine_assets::{AssetId, AssetKey, AssetState, AssetStore};
+use newengine_core::assets::AssetManager;
+use newengine_core::host_events::{HostEvent, WindowHostEvent};
+use newengine_core::{EngineResult, EventSub, Module, ModuleCtx};
+use newengine_ui::markup::UiMarkupDoc;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// Asset subdirectory receiving dropped files that live outside the assets root.
+const DROP_DIR: &str = "dropped";
+
+/// Asks the render controller to show another model in the viewport.
+///
+/// The viewport draws NE3D meshes, which OBJ, glTF and binary FBX imports all produce.
+#[derive(Debug, Clone)]
+pub struct ViewportModelRequest {
+    pub logical_path: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DropKind {
+    Model,
+    Markup,
+}
+
+impl DropKind {
+    fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+        match ext.as_str() {
+            "obj" | "gltf" | "glb" => Some(Self::Model),
+            "ui" => Some(Self::Markup),
+            _ => None,
+        }
+    }
+}
+
+struct PendingDrop {
+    kind: DropKind,
+    logical_path: String,
+    id: AssetId,
+}
+
+/// Opens files dropped onto the editor window.
+///
+/// Files outside the assets root are copied into `<assets_root>/dropped/` first, since the
+/// store only resolves logical paths. Models go to the viewport, `.ui` markup replaces the
+/// shared editor document once the import is ready.
+pub struct FileDropModule {
+    assets_root: PathBuf,
+    filesystem_source: bool,
+    shared_doc: Option<Arc<Mutex<Option<UiMarkupDoc>>>>,
+    events: Option<EventSub<HostEvent>>,
+    pending: Vec<PendingDrop>,
+}
+
+impl FileDropModule {
+    #[inline]
+    pub fn new(assets_root: PathBuf, filesystem_source: bool) -> Self {
+        Self {
+            assets_root,
+            filesystem_source,
+            shared_doc: None,
+            events: None,
+            pending: Vec::new(),
+        }
+    }
+
+    #[inline]
+    pub fn with_shared_doc(mut self, shared_doc: Arc<Mutex<Option<UiMarkupDoc>>>) -> Self {
+        self.shared_doc = Some(shared_doc);
+        self
+    }
+
+    /// Maps a dropped file to a logical asset path, copying it under the assets root if needed.
+    fn logical_path_for(&self, path: &Path) -> Result<String, String> {
+        let root = self
+            .assets_root
+            .canonicalize()
+            .map_err(|e| format!("assets root '{}': {e}", self.assets_root.display()))?;
+        let src = path.canonicalize().map_err(|e| e.to_string())?;
+
+        if let Ok(rel) = src.strip_prefix(&root) {
+            return Ok(logical_from_relative(rel));
+        }
+
+        let name = src
+            .file_name()
+            .ok_or_else(|| "path has no file name".to_string())?;
+        let dir = root.join(DROP_DIR);
+        std::fs::create_dir_all(&dir).map_err(|e| format!("create '{}': {e}", dir.display()))?;
+
+        let dst = dir.join(name);
+        std::fs::copy(&src, &dst).map_err(|e| format!("copy to '{}': {e}", dst.display()))?;
+
+        Ok(format!("{DROP_DIR}/{}", name.to_string_lossy()))
+    }
+
+    fn open(&mut self, store: &AssetStore, path: &Path) {
+        let Some(kind) = DropKind::from_path(path) else {
+            log::info!("drop: ignored path='{}' (unsupported type)", path.display());
+            return;
+        };
+
+        if !self.filesystem_source {
+            log::warn!(
+                "drop: ignored path='{}' (asset filesystem source disabled)",
+                path.display()
+            );
+            return;
+        }
+
+        let logical_path = match self.logical_path_for(path) {
+            Ok(p) => p,
+            Err(e) => {
+                log::warn!("drop: rejected path='{}' err='{e}'", path.display());
+                return;
+            }
+        };
+
+        // Dropping the same file again must pick up its new contents.
+        let id = AssetKey::new(&logical_path, 0).id();
+        let res = match store.state(id) {
+            AssetState::Unloaded => store.load_path(&logical_path),
+            _ => store.reload_path(&logical_path),
+        };
+
+        match res {
+            Ok(id) => {
+                log::info!("drop: opening path='{logical_path}' kind={kind:?}");
+                self.pending.retain(|p| p.id != id);
+                self.pending.push(PendingDrop {
+                    kind,
+                    logical_path,
+                    id,
+                });
+            }
+            Err(e) => log::warn!("drop: asset.load failed path='{logical_path}' err='{e}'"),
+        }
+    }
+
+    fn poll_pending<E: Send + 'static>(&mut self, ctx: &mut ModuleCtx<'_, E>) {
+        let mut ready: Vec<PendingDrop> = Vec::new();
+
+        if let Some(am) = ctx.resources().get::<AssetManager>() {
+            let store = am.store();
+            let mut i = 0;
+            while i < self.pending.len() {
+                match store.state(self.pending[i].id) {
+                    AssetState::Ready => ready.push(self.pending.swap_remove(i)),
+                    AssetState::Failed(e) => {
+                        let p = self.pending.swap_remove(i);
+                        log::warn!("drop: import failed path='{}' err='{e}'", p.logical_path);
+                    }
+                    AssetState::Loading | AssetState::Unloaded => i += 1,
+                }
+            }
+
+            for p in ready.iter().filter(|p| p.kind == DropKind::Markup) {
+                self.apply_markup(store, p);
+            }
+        }
+
+        // Last dropped model wins if several finished in the same frame.
+        if let Some(p) = ready.into_iter().rev().find(|p| p.kind == DropKind::Model) {
+            ctx.resources_mut().insert(ViewportModelRequest {
+                logical_path: p.logical_path,
+            });
+        }
+    }
+
+    fn apply_markup(&self, store: &AssetStore, p: &PendingDrop) {
+        let Some(shared_doc) = self.shared_doc.as_ref() else {
+            log::info!("drop: loaded path='{}' (UI disabled)", p.logical_path);
+            return;
+        };
+        let Some(blob) = store.get_blob(p.id) else {
+            return;
+        };
+
+        match UiMarkupDoc::from_blob(&blob) {
+            Ok(doc) => {
+                if let Ok(mut g) = shared_doc.lock() {
+                    *g = Some(doc);
+                }
+                log::info!("drop: ui opened path='{}'", p.logical_path);
+            }
+            Err(e) => log::warn!("drop: ui parse failed path='{}' err='{e}'", p.logical_path),
+        }
+    }
+}
+
+impl<E: Send + 'static> Module<E> for FileDropModule {
+    fn id(&self) -> &'static str {
+        "app.file_drop"
+    }
+
+    fn init(&mut self, ctx: &mut ModuleCtx<'_, E>) -> EngineResult<()> {
+        self.events = Some(ctx.events().subscribe::<HostEvent>());
+        Ok(())
+    }
+
+    fn update(&mut self, ctx: &mut ModuleCtx<'_, E>) -> EngineResult<()> {
+        let mut dropped: Vec<PathBuf> = Vec::new();
+        if let Some(events) = self.events.as_ref() {
+            events.drain(|ev| {
+                if let HostEvent::Window(WindowHostEvent::FileDropped(path)) = ev.as_ref() {
+                    dropped.push(path.clone());
+                }
+            });
+        }
+
+        if !dropped.is_empty() {
+            let Some(store) = ctx
+                .resources()
+                .get::<AssetManager>()
+                .map(|am| Arc::clone(am.store()))
+            else {
+                log::warn!(
+                    "drop: AssetManager missing; {} file(s) ignored",
+                    dropped.len()
+                );
+                return Ok(());
+            };
+
+            for path in dropped.iter() {
+                self.open(&store, path);
+            }
+        }
+
+        if !self.pending.is_empty() {
+            self.poll_pending(ctx);
+        }
+        Ok(())
+    }
+
+    fn shutdown(&mut self, _ctx: &mut ModuleCtx<'_, E>) -> EngineResult<()> {
+        self.events = None;
+        self.pending.clear();
+        Ok(())
+    }
+}
+
+#[inline]
+fn logical_from_relative(rel: &Path) -> String {
+    rel.components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
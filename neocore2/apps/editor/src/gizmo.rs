@@ -0,0 +1,424 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use glam::{Mat4, Quat, Vec2, Vec3};
+use newengine_core::render::{Color4, DebugDraw};
+use newengine_core::{Transform, UndoStack};
+use newengine_platform_winit::egui;
+
+use crate::scene::{matrix_transform, transform_matrix, EditorScene, SetTransform, ViewportCamera};
+use crate::selection::ViewportSelection;
+
+/// Handle length as a fraction of the camera distance, so the gizmo keeps its on-screen size.
+const HANDLE_SCALE: f32 = 0.18;
+/// How close, in points, the pointer must come to a handle to grab it.
+const GRAB_DISTANCE: f32 = 8.0;
+const RING_SEGMENTS: usize = 48;
+/// Smallest scale a drag can shrink an axis to.
+const MIN_SCALE: f32 = 0.001;
+
+const AXIS_COLORS: [Color4; 3] = [
+    [0.95, 0.25, 0.25, 1.0],
+    [0.35, 0.9, 0.3, 1.0],
+    [0.3, 0.5, 1.0, 1.0],
+];
+const ACTIVE_COLOR: Color4 = [1.0, 0.85, 0.2, 1.0];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GizmoMode {
+    #[default]
+    Translate,
+    Rotate,
+    Scale,
+}
+
+impl GizmoMode {
+    #[inline]
+    fn label(self) -> &'static str {
+        match self {
+            Self::Translate => "Move (W)",
+            Self::Rotate => "Rotate (E)",
+            Self::Scale => "Scale (R)",
+        }
+    }
+}
+
+/// Where the press hit a handle.
+#[derive(Debug, Clone, Copy)]
+enum Anchor {
+    /// Parameter along the axis (move, scale).
+    Along(f32),
+    /// Direction from the pivot in the ring plane (rotate).
+    Around(Vec3),
+}
+
+/// A handle being dragged.
+#[derive(Debug, Clone, Copy)]
+struct Drag {
+    object: u32,
+    mode: GizmoMode,
+    axis: usize,
+    /// Transform at the press, relative to the parent; restored on cancel.
+    start: Transform,
+    /// World transform at the press; the drag is applied on top of it.
+    world: Transform,
+    /// World matrix of the parent, to bring the dragged world transform back under it.
+    parent: Mat4,
+    anchor: Anchor,
+}
+
+/// Gizmo of the selected object: draws its handles through [`DebugDraw`], grabs them with rays
+/// cast from the pointer through the viewport camera, and moves the object in the
+/// [`EditorScene`] while they are dragged; a finished drag goes on the [`UndoStack`]. Handles
+/// follow the object's world transform, under its parents. W/E/R switch the mode; Escape or a
+/// right click cancels a drag.
+#[derive(Debug, Default)]
+pub struct GizmoUi {
+    scene: EditorScene,
+    selection: ViewportSelection,
+    mode: GizmoMode,
+    drag: Option<Drag>,
+}
+
+impl GizmoUi {
+    #[inline]
+    pub fn new(scene: EditorScene, selection: ViewportSelection) -> Self {
+        Self {
+            scene,
+            selection,
+            mode: GizmoMode::default(),
+            drag: None,
+        }
+    }
+
+    pub fn toolbar_ui(&mut self, ui: &mut egui::Ui) {
+        for mode in [GizmoMode::Translate, GizmoMode::Rotate, GizmoMode::Scale] {
+            ui.selectable_value(&mut self.mode, mode, mode.label());
+        }
+    }
+
+    /// Runs the gizmo for this frame; `true` when it used the pointer, so the click must not
+    /// pick.
+    pub fn ui(&mut self, ctx: &egui::Context) -> bool {
+        if !ctx.wants_keyboard_input() && self.drag.is_none() {
+            ctx.input(|i| {
+                if i.key_pressed(egui::Key::W) {
+                    self.mode = GizmoMode::Translate;
+                } else if i.key_pressed(egui::Key::E) {
+                    self.mode = GizmoMode::Rotate;
+                } else if i.key_pressed(egui::Key::R) {
+                    self.mode = GizmoMode::Scale;
+                }
+            });
+        }
+
+        let target = self
+            .selection
+            .selected()
+            .and_then(|id| Some((id, self.scene.transform(id)?)));
+        let (Some((id, local)), Some(camera)) = (target, self.scene.camera()) else {
+            self.drag = None;
+            return false;
+        };
+        if self.drag.is_some_and(|d| d.object != id) {
+            self.drag = None;
+        }
+
+        let parent = self.scene.parent_matrix(id);
+        let transform = matrix_transform(parent * transform_matrix(&local));
+
+        let view = View::new(&camera, ctx.pixels_per_point());
+        let pointer = ctx.input(|i| i.pointer.latest_pos());
+        let ray = pointer.and_then(|p| view.ray(p));
+
+        let used = match self.drag {
+            Some(drag) => {
+                self.drag_ui(ctx, drag, ray);
+                true
+            }
+            None => {
+                let over_panel = ctx.is_pointer_over_area();
+                let hovered = pointer
+                    .filter(|_| !over_panel)
+                    .and_then(|p| self.hit(&view, &transform, p));
+                let pressed = ctx.input(|i| i.pointer.primary_pressed());
+                if let (Some(axis), true, Some(ray)) = (hovered, pressed, ray) {
+                    self.drag = self.grab(id, axis, (local, transform, parent), ray);
+                }
+                hovered.is_some()
+            }
+        };
+
+        // The drag may have moved the object this frame.
+        let transform = self
+            .scene
+            .world_matrix(id)
+            .map_or(transform, matrix_transform);
+        let active = self.drag.map(|d| d.axis);
+        self.draw(&view, &transform, active);
+        used
+    }
+
+    /// `at` is the object's transform, its world transform and its parent's world matrix.
+    fn grab(
+        &self,
+        object: u32,
+        axis: usize,
+        at: (Transform, Transform, Mat4),
+        ray: Ray,
+    ) -> Option<Drag> {
+        let (start, world, parent) = at;
+        let pivot = Vec3::from_array(world.translation);
+        let dir = axes(&world)[axis];
+        let anchor = match self.mode {
+            GizmoMode::Translate | GizmoMode::Scale => {
+                Anchor::Along(ray.closest_on_line(pivot, dir)?)
+            }
+            GizmoMode::Rotate => {
+                Anchor::Around((ray.hit_plane(pivot, dir)? - pivot).try_normalize()?)
+            }
+        };
+        Some(Drag {
+            object,
+            mode: self.mode,
+            axis,
+            start,
+            world,
+            parent,
+            anchor,
+        })
+    }
+
+    fn drag_ui(&mut self, ctx: &egui::Context, drag: Drag, ray: Option<Ray>) {
+        let (cancel, released) = ctx.input(|i| {
+            (
+                i.key_pressed(egui::Key::Escape) || i.pointer.secondary_pressed(),
+                !i.pointer.primary_down(),
+            )
+        });
+        if cancel {
+            self.scene.set_transform(drag.object, drag.start);
+            self.drag = None;
+            return;
+        }
+
+        if let Some(world) = ray.and_then(|ray| Self::dragged(&drag, ray)) {
+            let t = matrix_transform(drag.parent.inverse() * transform_matrix(&world));
+            self.scene.set_transform(drag.object, t);
+        }
+        if released {
+            self.drag = None;
+            if let Some(t) = self.scene.transform(drag.object) {
+                if t != drag.start {
+                    log::info!(
+                        "gizmo: {:?} #{} t={:?} r={:?} s={:?}",
+                        drag.mode,
+                        drag.object,
+                        t.translation,
+                        t.rotation,
+                        t.scale
+                    );
+                    let edit = SetTransform::new(self.scene.clone(), drag.object, drag.start, t);
+                    UndoStack::global().record(edit);
+                }
+            }
+        }
+    }
+
+    /// World transform of the dragged object with the pointer ray at `ray`.
+    fn dragged(drag: &Drag, ray: Ray) -> Option<Transform> {
+        let start = drag.world;
+        let pivot = Vec3::from_array(start.translation);
+        let dir = axes(&start)[drag.axis];
+        let mut t = start;
+        match (drag.mode, drag.anchor) {
+            (GizmoMode::Translate, Anchor::Along(s0)) => {
+                let s = ray.closest_on_line(pivot, dir)?;
+                t.translation = (pivot + dir * (s - s0)).to_array();
+            }
+            (GizmoMode::Scale, Anchor::Along(s0)) => {
+                if s0.abs() <= f32::EPSILON {
+                    return None;
+                }
+                let k = ray.closest_on_line(pivot, dir)? / s0;
+                t.scale[drag.axis] = (start.scale[drag.axis] * k).max(MIN_SCALE);
+            }
+            (GizmoMode::Rotate, Anchor::Around(from)) => {
+                let to = (ray.hit_plane(pivot, dir)? - pivot).try_normalize()?;
+                let angle = dir.dot(from.cross(to)).atan2(from.dot(to));
+                let r = Quat::from_axis_angle(dir, angle) * Quat::from_array(start.rotation);
+                t.rotation = r.normalize().to_array();
+            }
+            _ => return None,
+        }
+        Some(t)
+    }
+
+    /// Axis of the handle under `pointer`, if any.
+    fn hit(&self, view: &View, transform: &Transform, pointer: egui::Pos2) -> Option<usize> {
+        let p = Vec2::new(pointer.x, pointer.y);
+        let mut best: Option<(usize, f32)> = None;
+        for (axis, points) in self.handles(view, transform).iter().enumerate() {
+            let screen: Vec<Option<Vec2>> = points.iter().map(|&q| view.to_screen(q)).collect();
+            for pair in screen.windows(2) {
+                let (Some(a), Some(b)) = (pair[0], pair[1]) else {
+                    continue;
+                };
+                let d = distance_to_segment(p, a, b);
+                if d <= GRAB_DISTANCE && best.is_none_or(|(_, bd)| d < bd) {
+                    best = Some((axis, d));
+                }
+            }
+        }
+        best.map(|(axis, _)| axis)
+    }
+
+    /// Polyline of each axis handle in world space.
+    fn handles(&self, view: &View, transform: &Transform) -> [Vec<Vec3>; 3] {
+        let pivot = Vec3::from_array(transform.translation);
+        let len = view.handle_length(pivot);
+        let axes = axes(transform);
+        std::array::from_fn(|i| match self.mode {
+            GizmoMode::Translate | GizmoMode::Scale => vec![pivot, pivot + axes[i] * len],
+            GizmoMode::Rotate => {
+                let (u, v) = (axes[(i + 1) % 3], axes[(i + 2) % 3]);
+                (0..=RING_SEGMENTS)
+                    .map(|k| {
+                        let a = k as f32 / RING_SEGMENTS as f32 * std::f32::consts::TAU;
+                        pivot + (u * a.cos() + v * a.sin()) * len
+                    })
+                    .collect()
+            }
+        })
+    }
+
+    fn draw(&self, view: &View, transform: &Transform, active: Option<usize>) {
+        let dd = DebugDraw::global();
+        let pivot = Vec3::from_array(transform.translation);
+        let len = view.handle_length(pivot);
+        let axes = axes(transform);
+        for (i, points) in self.handles(view, transform).iter().enumerate() {
+            let color = match active == Some(i) {
+                true => ACTIVE_COLOR,
+                false => AXIS_COLORS[i],
+            };
+            for pair in points.windows(2) {
+                dd.line(pair[0].to_array(), pair[1].to_array(), color, 0.0);
+            }
+
+            let tip = pivot + axes[i] * len;
+            match self.mode {
+                GizmoMode::Translate => {
+                    let back = tip - axes[i] * (len * 0.15);
+                    let side = len * 0.05;
+                    for n in [axes[(i + 1) % 3], axes[(i + 2) % 3]] {
+                        dd.line(tip.to_array(), (back + n * side).to_array(), color, 0.0);
+                        dd.line(tip.to_array(), (back - n * side).to_array(), color, 0.0);
+                    }
+                }
+                GizmoMode::Scale => {
+                    dd.cuboid(tip.to_array(), [len * 0.04; 3], color, 0.0);
+                }
+                GizmoMode::Rotate => {}
+            }
+        }
+    }
+}
+
+/// Local axes of `transform`: its rotation applied to X, Y, Z.
+#[inline]
+fn axes(transform: &Transform) -> [Vec3; 3] {
+    let r = Quat::from_array(transform.rotation).normalize();
+    [r * Vec3::X, r * Vec3::Y, r * Vec3::Z]
+}
+
+#[inline]
+fn distance_to_segment(p: Vec2, a: Vec2, b: Vec2) -> f32 {
+    let ab = b - a;
+    let len2 = ab.length_squared();
+    let t = match len2 > 0.0 {
+        true => ((p - a).dot(ab) / len2).clamp(0.0, 1.0),
+        false => 0.0,
+    };
+    p.distance(a + ab * t)
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Ray {
+    origin: Vec3,
+    /// Unit length.
+    dir: Vec3,
+}
+
+impl Ray {
+    /// Parameter along the line `origin + dir * s` (`dir` unit length) of its point closest to
+    /// the ray; `None` when they are parallel.
+    fn closest_on_line(&self, origin: Vec3, dir: Vec3) -> Option<f32> {
+        let w = origin - self.origin;
+        let b = dir.dot(self.dir);
+        let denom = 1.0 - b * b;
+        if denom <= 1e-6 {
+            return None;
+        }
+        Some((b * self.dir.dot(w) - dir.dot(w)) / denom)
+    }
+
+    /// Where the ray crosses the plane through `point` with `normal`, if ahead of it.
+    fn hit_plane(&self, point: Vec3, normal: Vec3) -> Option<Vec3> {
+        let denom = normal.dot(self.dir);
+        if denom.abs() <= 1e-6 {
+            return None;
+        }
+        let t = normal.dot(point - self.origin) / denom;
+        (t >= 0.0).then(|| self.origin + self.dir * t)
+    }
+}
+
+/// The viewport camera in egui points.
+struct View {
+    view_proj: Mat4,
+    inverse: Mat4,
+    eye: Vec3,
+    /// Viewport size in points.
+    size: Vec2,
+}
+
+impl View {
+    fn new(camera: &ViewportCamera, pixels_per_point: f32) -> Self {
+        let view_proj = Mat4::from_cols_array(&camera.view_proj);
+        Self {
+            view_proj,
+            inverse: view_proj.inverse(),
+            eye: Vec3::from_array(camera.eye),
+            size: Vec2::new(camera.width as f32, camera.height as f32) / pixels_per_point,
+        }
+    }
+
+    #[inline]
+    fn handle_length(&self, pivot: Vec3) -> f32 {
+        (self.eye.distance(pivot) * HANDLE_SCALE).max(1e-3)
+    }
+
+    /// `None` behind the camera.
+    fn to_screen(&self, p: Vec3) -> Option<Vec2> {
+        let clip = self.view_proj * p.extend(1.0);
+        if clip.w <= 1e-6 {
+            return None;
+        }
+        let ndc = Vec2::new(clip.x, clip.y) / clip.w;
+        Some((ndc * 0.5 + 0.5) * self.size)
+    }
+
+    /// Ray through `pos`; Vulkan clip space has Y down and depth 0..1.
+    fn ray(&self, pos: egui::Pos2) -> Option<Ray> {
+        if self.size.x <= 0.0 || self.size.y <= 0.0 {
+            return None;
+        }
+        let ndc = Vec2::new(pos.x, pos.y) / self.size * 2.0 - 1.0;
+        let near = self.inverse.project_point3(ndc.extend(0.0));
+        let far = self.inverse.project_point3(ndc.extend(1.0));
+        Some(Ray {
+            origin: near,
+            dir: (far - near).try_normalize()?,
+        })
+    }
+}
@@ -0,0 +1,269 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use newengine_core::{Command, UndoStack};
+use newengine_platform_winit::egui;
+use std::path::PathBuf;
+
+use crate::scene::{AddObject, DeleteObject, EditorScene, Rename, Reparent, SceneObject};
+use crate::selection::ViewportSelection;
+
+/// Drag-and-drop payload of a hierarchy row.
+#[derive(Debug, Clone, Copy)]
+struct DraggedObject(u32);
+
+/// What a row asked for; applied once the tree is drawn.
+#[derive(Debug, Clone)]
+enum Action {
+    Select(u32),
+    StartRename(u32),
+    Rename(u32, String),
+    Reparent(u32, Option<u32>),
+    Delete(u32),
+}
+
+/// Tree of the [`EditorScene`]: click selects, dragging a row onto another reparents it (onto
+/// the space below the tree moves it to the root), the context menu renames and deletes. Edits
+/// go through the [`UndoStack`]; the scene is written back to its asset after each one.
+#[derive(Debug, Default)]
+pub struct HierarchyPanel {
+    scene: EditorScene,
+    selection: ViewportSelection,
+    /// Scene asset; `None` when assets are not read from the filesystem.
+    path: Option<PathBuf>,
+    /// Scene revision last written to `path`.
+    saved: u64,
+    open: bool,
+    /// Row being renamed and the name typed so far.
+    renaming: Option<(u32, String)>,
+    error: Option<String>,
+}
+
+impl HierarchyPanel {
+    pub fn new(scene: EditorScene, selection: ViewportSelection, path: Option<PathBuf>) -> Self {
+        let saved = scene.revision();
+        Self {
+            scene,
+            selection,
+            path,
+            saved,
+            ..Self::default()
+        }
+    }
+
+    pub fn toolbar_ui(&mut self, ui: &mut egui::Ui) {
+        ui.toggle_value(&mut self.open, "Hierarchy");
+    }
+
+    pub fn ui(&mut self, ctx: &egui::Context) {
+        self.autosave(ctx);
+        if !self.open {
+            return;
+        }
+
+        let objects = self.scene.objects();
+        let selected = self.selection.selected();
+        let mut actions: Vec<Action> = Vec::new();
+
+        let mut open = self.open;
+        egui::Window::new("Hierarchy")
+            .id(egui::Id::new("ne_editor_hierarchy"))
+            .open(&mut open)
+            .default_size([280.0, 320.0])
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    if ui.button("Add empty").clicked() {
+                        self.add_empty();
+                    }
+                    let delete = ui.add_enabled(selected.is_some(), egui::Button::new("Delete"));
+                    if let (true, Some(id)) = (delete.clicked(), selected) {
+                        actions.push(Action::Delete(id));
+                    }
+                });
+                if let Some(e) = &self.error {
+                    ui.colored_label(egui::Color32::LIGHT_RED, e.as_str());
+                }
+                ui.separator();
+
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    let roots = objects.iter().filter(|o| is_root(&objects, o));
+                    for o in roots {
+                        self.row_ui(ui, &objects, o, selected, &mut actions);
+                    }
+                    if objects.is_empty() {
+                        ui.weak("The scene is empty.");
+                    }
+
+                    // Rest of the window: dropping here moves to the root.
+                    let (_, dropped) = ui.dnd_drop_zone::<DraggedObject, ()>(
+                        egui::Frame::none().inner_margin(4.0),
+                        |ui| {
+                            ui.set_min_size(egui::vec2(ui.available_width(), 24.0));
+                        },
+                    );
+                    if let Some(d) = dropped {
+                        actions.push(Action::Reparent(d.0, None));
+                    }
+                });
+            });
+        self.open = open;
+
+        for action in actions {
+            self.run(action);
+        }
+    }
+
+    fn row_ui(
+        &mut self,
+        ui: &mut egui::Ui,
+        objects: &[SceneObject],
+        object: &SceneObject,
+        selected: Option<u32>,
+        actions: &mut Vec<Action>,
+    ) {
+        match &mut self.renaming {
+            Some((id, name)) if *id == object.id => {
+                let resp = ui.text_edit_singleline(name);
+                if !resp.has_focus() && !resp.lost_focus() {
+                    resp.request_focus();
+                }
+                if resp.lost_focus() {
+                    match ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+                        true => self.renaming = None,
+                        false => actions.push(Action::Rename(object.id, name.clone())),
+                    }
+                }
+            }
+            _ => {
+                let label = format!("{}  #{}", object.name, object.id);
+                let drag_id = egui::Id::new(("ne_editor_hierarchy_row", object.id));
+                let row = ui.dnd_drag_source(drag_id, DraggedObject(object.id), |ui| {
+                    ui.selectable_label(selected == Some(object.id), label)
+                });
+
+                let target = &row.response;
+                if target.dnd_hover_payload::<DraggedObject>().is_some() {
+                    let stroke = ui.visuals().selection.stroke;
+                    ui.painter().rect_stroke(target.rect, 2.0, stroke);
+                }
+                if let Some(d) = target.dnd_release_payload::<DraggedObject>() {
+                    if d.0 != object.id {
+                        actions.push(Action::Reparent(d.0, Some(object.id)));
+                    }
+                }
+
+                let label = row.inner;
+                if label.clicked() {
+                    actions.push(Action::Select(object.id));
+                }
+                if label.double_clicked() {
+                    actions.push(Action::StartRename(object.id));
+                }
+                label.context_menu(|ui| {
+                    if ui.button("Rename").clicked() {
+                        actions.push(Action::StartRename(object.id));
+                        ui.close_menu();
+                    }
+                    if object.parent.is_some() && ui.button("Move to root").clicked() {
+                        actions.push(Action::Reparent(object.id, None));
+                        ui.close_menu();
+                    }
+                    if ui.button("Delete").clicked() {
+                        actions.push(Action::Delete(object.id));
+                        ui.close_menu();
+                    }
+                });
+            }
+        }
+
+        let children: Vec<&SceneObject> = objects
+            .iter()
+            .filter(|o| o.parent == Some(object.id))
+            .collect();
+        if children.is_empty() {
+            return;
+        }
+        ui.indent(("ne_editor_hierarchy_children", object.id), |ui| {
+            for child in children {
+                self.row_ui(ui, objects, child, selected, actions);
+            }
+        });
+    }
+
+    fn run(&mut self, action: Action) {
+        let res = match action {
+            Action::Select(id) => {
+                self.selection.set_selected(Some(id));
+                Ok(())
+            }
+            Action::StartRename(id) => {
+                let name = self.scene.get(id).map(|o| o.name).unwrap_or_default();
+                self.renaming = Some((id, name));
+                Ok(())
+            }
+            Action::Rename(id, name) => {
+                self.renaming = None;
+                let name = name.trim();
+                match name.is_empty() {
+                    true => Ok(()),
+                    false => Rename::new(self.scene.clone(), id, name)
+                        .and_then(|cmd| UndoStack::global().execute(cmd)),
+                }
+            }
+            Action::Reparent(id, parent) => {
+                let unchanged = self.scene.get(id).is_some_and(|o| o.parent == parent);
+                match unchanged {
+                    true => Ok(()),
+                    false => Reparent::new(self.scene.clone(), id, parent)
+                        .and_then(|cmd| UndoStack::global().execute(cmd)),
+                }
+            }
+            Action::Delete(id) => self.delete(id),
+        };
+        self.error = res.err();
+    }
+
+    fn add_empty(&mut self) {
+        let id = self.scene.next_id();
+        let cmd = AddObject::new(self.scene.clone(), SceneObject::new(id, "Empty"));
+        self.error = UndoStack::global().execute(cmd).err();
+        if self.error.is_none() {
+            self.selection.set_selected(Some(id));
+        }
+    }
+
+    fn delete(&mut self, id: u32) -> Result<(), String> {
+        let mut cmd = DeleteObject::new(self.scene.clone(), id)?;
+        cmd.apply()?;
+        let selected = self.selection.selected();
+        if cmd.removed_ids().any(|removed| Some(removed) == selected) {
+            self.selection.set_selected(None);
+        }
+        UndoStack::global().record(cmd);
+        Ok(())
+    }
+
+    /// Writes the scene asset once an edit is done: not mid-drag, so a gizmo or inspector drag
+    /// is written once.
+    fn autosave(&mut self, ctx: &egui::Context) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        let revision = self.scene.revision();
+        if revision == self.saved || ctx.input(|i| i.pointer.any_down()) {
+            return;
+        }
+        self.saved = revision;
+        match self.scene.save(path) {
+            Ok(()) => log::info!("scene: saved path='{}'", path.display()),
+            Err(e) => log::warn!("scene: save failed path='{}' err='{e}'", path.display()),
+        }
+    }
+}
+
+/// At the root, or under a parent that is gone.
+#[inline]
+fn is_root(objects: &[SceneObject], object: &SceneObject) -> bool {
+    object
+        .parent
+        .is_none_or(|p| objects.iter().all(|o| o.id != p))
+}
@@ -0,0 +1,78 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use newengine_core::UndoStack;
+use newengine_platform_winit::egui;
+
+/// Edits listed in the History window.
+const HISTORY_ROWS: usize = 100;
+
+/// Undo/Redo toolbar buttons and a window over the [`UndoStack`].
+#[derive(Debug, Default)]
+pub struct HistoryPanel {
+    open: bool,
+    error: Option<String>,
+}
+
+impl HistoryPanel {
+    pub fn toolbar_ui(&mut self, ui: &mut egui::Ui) {
+        let stack = UndoStack::global();
+
+        let undo = stack.undo_label();
+        let button = ui.add_enabled(undo.is_some(), egui::Button::new("Undo"));
+        let undo = undo.unwrap_or_default();
+        // No shortcut in the hint: ctrl+z is a console binding users may unbind or rebind.
+        if button.on_hover_text(format!("Undo '{undo}'")).clicked() {
+            self.error = stack.undo().err();
+        }
+
+        let redo = stack.redo_label();
+        let button = ui.add_enabled(redo.is_some(), egui::Button::new("Redo"));
+        let redo = redo.unwrap_or_default();
+        if button.on_hover_text(format!("Redo '{redo}'")).clicked() {
+            self.error = stack.redo().err();
+        }
+
+        ui.toggle_value(&mut self.open, "History");
+    }
+
+    pub fn ui(&mut self, ctx: &egui::Context) {
+        if !self.open {
+            return;
+        }
+
+        let stack = UndoStack::global();
+        let entries = stack.history(HISTORY_ROWS);
+
+        let mut open = self.open;
+        egui::Window::new("History")
+            .id(egui::Id::new("ne_editor_history"))
+            .open(&mut open)
+            .default_size([320.0, 260.0])
+            .show(ctx, |ui| {
+                if let Some(e) = &self.error {
+                    ui.colored_label(egui::Color32::LIGHT_RED, e.as_str());
+                }
+                if entries.is_empty() {
+                    ui.label("No edits yet.");
+                    return;
+                }
+
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    for e in &entries {
+                        // Undone edits wait for redo above the current state.
+                        match e.undone {
+                            true => ui.weak(e.label.as_str()),
+                            false => ui.label(e.label.as_str()),
+                        };
+                    }
+                });
+
+                ui.separator();
+                if ui.button("Clear").clicked() {
+                    stack.clear();
+                    self.error = None;
+                }
+            });
+        self.open = open;
+    }
+}
@@ -0,0 +1,108 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use newengine_assets::{AssetEvent, AssetEventReceiver, AssetId, AssetKey, AssetStore};
+use newengine_ui::markup::UiMarkupDoc;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Watches the editor markup file and swaps the shared document when the asset is reloaded.
+///
+/// File changes trigger `AssetStore::reload_path`; the re-parse happens on
+/// `AssetEvent::Reloaded`, so a manual `asset.reload` from the console is picked up as well.
+pub struct UiMarkupHotReload {
+    store: Arc<AssetStore>,
+    /// Own event stream, so the reload watch does not take events from other consumers.
+    events: AssetEventReceiver,
+    logical_path: String,
+    id: AssetId,
+    fs_path: Option<PathBuf>,
+    last_mtime: Option<SystemTime>,
+    last_poll: Instant,
+}
+
+impl UiMarkupHotReload {
+    /// `fs_path` is the on-disk file backing `logical_path`; `None` disables file polling.
+    pub fn new(store: Arc<AssetStore>, logical_path: &str, fs_path: Option<PathBuf>) -> Self {
+        let last_mtime = fs_path.as_deref().and_then(file_mtime);
+        Self {
+            events: store.subscribe_events(),
+            store,
+            logical_path: logical_path.to_string(),
+            id: AssetKey::new(logical_path, 0).id(),
+            fs_path,
+            last_mtime,
+            last_poll: Instant::now(),
+        }
+    }
+
+    /// Polls the file and applies a finished reload to `shared_doc`.
+    ///
+    /// The previous document is kept on parse errors so a typo does not blank the UI.
+    pub fn poll(&mut self, shared_doc: &Arc<Mutex<Option<UiMarkupDoc>>>) {
+        self.poll_file();
+
+        for ev in self.events.drain() {
+            match ev {
+                AssetEvent::Reloaded { id, .. } if id == self.id => self.apply(shared_doc),
+                AssetEvent::Failed { id, error, .. } if id == self.id => {
+                    log::warn!(
+                        "ui: reload failed path='{}' err='{error}'",
+                        self.logical_path
+                    );
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn poll_file(&mut self) {
+        if self.last_poll.elapsed() < POLL_INTERVAL {
+            return;
+        }
+        self.last_poll = Instant::now();
+
+        let Some(mtime) = self.fs_path.as_deref().and_then(file_mtime) else {
+            return;
+        };
+        if self.last_mtime == Some(mtime) {
+            return;
+        }
+        self.last_mtime = Some(mtime);
+
+        if let Err(e) = self.store.reload_path(&self.logical_path) {
+            log::warn!(
+                "ui: reload request failed path='{}' err='{e}'",
+                self.logical_path
+            );
+        }
+    }
+
+    fn apply(&self, shared_doc: &Arc<Mutex<Option<UiMarkupDoc>>>) {
+        let Some(blob) = self.store.get_blob(self.id) else {
+            return;
+        };
+
+        match UiMarkupDoc::from_blob(&blob) {
+            Ok(doc) => {
+                if let Ok(mut g) = shared_doc.lock() {
+                    *g = Some(doc);
+                }
+                log::info!("ui: reloaded path='{}'", self.logical_path);
+            }
+            Err(e) => {
+                log::warn!(
+                    "ui: reload parse failed path='{}' err='{e}'",
+                    self.logical_path
+                );
+            }
+        }
+    }
+}
+
+#[inline]
+fn file_mtime(p: &std::path::Path) -> Option<SystemTime> {
+    std::fs::metadata(p).and_then(|m| m.modified()).ok()
+}
@@ -0,0 +1,205 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use glam::{EulerRot, Quat};
+use newengine_core::reflect::{
+    component_info, ComponentInfo, ComponentSet, ComponentStore, FieldInfo, FieldKind, FieldValue,
+};
+use newengine_core::UndoStack;
+use newengine_platform_winit::egui;
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+
+use crate::scene::{EditorScene, Rename, SceneObject};
+use crate::selection::ViewportSelection;
+
+/// Change per dragged point of number fields; rotations drag in degrees.
+const NUMBER_SPEED: f64 = 0.01;
+const DEGREE_SPEED: f64 = 0.5;
+
+/// Name and components of the selected scene object. Fields come from the core reflection
+/// registry; edits go through the [`UndoStack`].
+#[derive(Debug, Default)]
+pub struct InspectorPanel {
+    scene: EditorScene,
+    selection: ViewportSelection,
+    open: bool,
+    /// Name being typed; follows the object while the field is not focused.
+    name: String,
+    error: Option<String>,
+}
+
+impl InspectorPanel {
+    #[inline]
+    pub fn new(scene: EditorScene, selection: ViewportSelection) -> Self {
+        Self {
+            scene,
+            selection,
+            ..Self::default()
+        }
+    }
+
+    pub fn toolbar_ui(&mut self, ui: &mut egui::Ui) {
+        ui.toggle_value(&mut self.open, "Inspector");
+    }
+
+    pub fn ui(&mut self, ctx: &egui::Context) {
+        if !self.open {
+            return;
+        }
+
+        let object = self.selection.selected().and_then(|id| self.scene.get(id));
+
+        let mut open = self.open;
+        egui::Window::new("Inspector")
+            .id(egui::Id::new("ne_editor_inspector"))
+            .open(&mut open)
+            .default_size([320.0, 240.0])
+            .show(ctx, |ui| {
+                let Some(object) = object else {
+                    ui.weak("Nothing selected.");
+                    return;
+                };
+                if let Some(e) = &self.error {
+                    ui.colored_label(egui::Color32::LIGHT_RED, e.as_str());
+                }
+
+                self.header_ui(ui, &object);
+                ui.separator();
+                let components = self.scene.components(object.id).unwrap_or_default();
+                for name in components {
+                    let Some(info) = component_info(&name) else {
+                        ui.weak(format!("{name} (not registered)"));
+                        continue;
+                    };
+                    egui::CollapsingHeader::new(info.name.as_str())
+                        .default_open(true)
+                        .show(ui, |ui| self.component_ui(ui, object.id, &info));
+                }
+            });
+        self.open = open;
+    }
+
+    fn header_ui(&mut self, ui: &mut egui::Ui, object: &SceneObject) {
+        let edit_id = egui::Id::new("ne_editor_inspector_name");
+        if !ui.memory(|m| m.has_focus(edit_id)) {
+            self.name.clone_from(&object.name);
+        }
+
+        ui.horizontal(|ui| {
+            ui.label("Name");
+            let resp = ui.add(egui::TextEdit::singleline(&mut self.name).id(edit_id));
+            let cancelled = ui.input(|i| i.key_pressed(egui::Key::Escape));
+            let name = self.name.trim();
+            if resp.lost_focus() && !cancelled && !name.is_empty() && name != object.name {
+                self.error = Rename::new(self.scene.clone(), object.id, name)
+                    .and_then(|cmd| UndoStack::global().execute(cmd))
+                    .err();
+            }
+        });
+
+        let parent = object.parent.and_then(|p| self.scene.get(p));
+        ui.weak(match parent {
+            Some(p) => format!("#{} under '{}' #{}", object.id, p.name, p.id),
+            None => format!("#{} at the root", object.id),
+        });
+    }
+
+    fn component_ui(&mut self, ui: &mut egui::Ui, id: u32, info: &ComponentInfo) {
+        let Some(mut value) = self.scene.get_component(id, &info.name) else {
+            return;
+        };
+        let mut changed: Option<String> = None;
+
+        egui::Grid::new(("ne_editor_inspector", info.name.as_str()))
+            .num_columns(2)
+            .show(ui, |ui| {
+                for field in &info.fields {
+                    ui.label(field.name.as_str());
+                    let edited = match field.get(&*value) {
+                        Some(current) => field_ui(ui, field, current),
+                        None => None,
+                    };
+                    if let Some(v) = edited {
+                        match field.set(&mut *value, v) {
+                            Ok(()) => changed = Some(field.name.clone()),
+                            Err(e) => self.error = Some(e),
+                        }
+                    }
+                    ui.end_row();
+                }
+            });
+
+        let Some(field) = changed else {
+            return;
+        };
+        let store: Arc<dyn ComponentStore> = Arc::new(self.scene.clone());
+        let path = format!("{}.{field}", info.name);
+        self.error = ComponentSet::new(store, id, &info.name, &*value, &path)
+            .and_then(|cmd| UndoStack::global().execute(cmd))
+            .err();
+    }
+}
+
+/// Widget of one field; the new value when it was edited this frame.
+fn field_ui(ui: &mut egui::Ui, field: &FieldInfo, value: FieldValue) -> Option<FieldValue> {
+    let range = field
+        .range
+        .map_or(f32::NEG_INFINITY..=f32::INFINITY, |(min, max)| min..=max);
+    match (field.kind, value) {
+        (FieldKind::Bool, FieldValue::Bool(mut v)) => {
+            let changed = ui.checkbox(&mut v, "").changed();
+            changed.then_some(FieldValue::Bool(v))
+        }
+        (FieldKind::Float, FieldValue::Float(mut v)) => {
+            let changed = numbers_ui(ui, std::slice::from_mut(&mut v), NUMBER_SPEED, range, "");
+            changed.then_some(FieldValue::Float(v))
+        }
+        (FieldKind::Vec3, FieldValue::Vec3(mut v)) => {
+            let changed = numbers_ui(ui, &mut v, NUMBER_SPEED, range, "");
+            changed.then_some(FieldValue::Vec3(v))
+        }
+        (FieldKind::Vec4, FieldValue::Vec4(mut v)) => {
+            let changed = numbers_ui(ui, &mut v, NUMBER_SPEED, range, "");
+            changed.then_some(FieldValue::Vec4(v))
+        }
+        // Shown as XYZ Euler angles in degrees.
+        (FieldKind::Rotation, FieldValue::Vec4(q)) => {
+            let (x, y, z) = Quat::from_array(q).normalize().to_euler(EulerRot::XYZ);
+            let mut e = [x, y, z].map(f32::to_degrees);
+            let changed = numbers_ui(ui, &mut e, DEGREE_SPEED, -180.0..=180.0, "°");
+            changed.then(|| {
+                let [x, y, z] = e.map(f32::to_radians);
+                FieldValue::Vec4(Quat::from_euler(EulerRot::XYZ, x, y, z).to_array())
+            })
+        }
+        (FieldKind::String, FieldValue::String(mut v)) => {
+            let changed = ui.text_edit_singleline(&mut v).changed();
+            changed.then_some(FieldValue::String(v))
+        }
+        (_, other) => {
+            ui.weak(other.to_string());
+            None
+        }
+    }
+}
+
+fn numbers_ui(
+    ui: &mut egui::Ui,
+    values: &mut [f32],
+    speed: f64,
+    range: RangeInclusive<f32>,
+    suffix: &str,
+) -> bool {
+    ui.horizontal(|ui| {
+        let mut changed = false;
+        for v in values {
+            let drag = egui::DragValue::new(v)
+                .speed(speed)
+                .range(range.clone())
+                .suffix(suffix);
+            changed |= ui.add(drag).changed();
+        }
+        changed
+    })
+    .inner
+}
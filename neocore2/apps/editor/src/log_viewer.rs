@@ -0,0 +1,229 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use newengine_modules_logging::{method, LOG_SERVICE_ID};
+use newengine_platform_winit::egui;
+use serde::Deserialize;
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+/// Records kept by the panel; older ones are dropped first.
+const MAX_ROWS: usize = 5000;
+/// Records requested when the panel first opens.
+const INITIAL_TAIL: usize = 500;
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+const LEVELS: [&str; 5] = ["ERROR", "WARN", "INFO", "DEBUG", "TRACE"];
+
+#[derive(Debug, Deserialize)]
+struct LogRow {
+    ts_ms: u64,
+    level: String,
+    target: String,
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct LogTailResponse {
+    next_seq: u64,
+    #[serde(default)]
+    entries: Vec<LogRow>,
+}
+
+/// Editor window over the `engine.log` ring buffer, so logs can be read without a terminal.
+#[derive(Debug)]
+pub struct LogViewer {
+    open: bool,
+    paused: bool,
+    rows: VecDeque<LogRow>,
+    /// `None` until the first successful poll.
+    next_seq: Option<u64>,
+    last_poll: Option<Instant>,
+    error: Option<String>,
+
+    /// Index into [`LEVELS`]; rows more verbose than this are hidden.
+    max_level: usize,
+    target: String,
+    search: String,
+    stick_to_bottom: bool,
+}
+
+impl Default for LogViewer {
+    fn default() -> Self {
+        Self {
+            open: false,
+            paused: false,
+            rows: VecDeque::new(),
+            next_seq: None,
+            last_poll: None,
+            error: None,
+            max_level: 4,
+            target: String::new(),
+            search: String::new(),
+            stick_to_bottom: true,
+        }
+    }
+}
+
+impl LogViewer {
+    pub fn toolbar_ui(&mut self, ui: &mut egui::Ui) {
+        ui.toggle_value(&mut self.open, "Logs");
+    }
+
+    fn poll(&mut self) {
+        if self.paused {
+            return;
+        }
+        if self.last_poll.is_some_and(|t| t.elapsed() < POLL_INTERVAL) {
+            return;
+        }
+        self.last_poll = Some(Instant::now());
+
+        let query = match self.next_seq {
+            Some(seq) => format!("0 since={}", seq.saturating_sub(1)),
+            None => INITIAL_TAIL.to_string(),
+        };
+
+        let bytes = match newengine_core::call_service_v1(
+            LOG_SERVICE_ID,
+            method::TAIL_JSON,
+            query.as_bytes(),
+        ) {
+            Ok(b) => b,
+            Err(e) => {
+                self.error = Some(e.to_string());
+                return;
+            }
+        };
+
+        match serde_json::from_slice::<LogTailResponse>(&bytes) {
+            Ok(resp) => {
+                self.error = None;
+                self.next_seq = Some(resp.next_seq);
+                self.rows.extend(resp.entries);
+                while self.rows.len() > MAX_ROWS {
+                    self.rows.pop_front();
+                }
+            }
+            Err(e) => self.error = Some(format!("bad {} response: {e}", method::TAIL_JSON)),
+        }
+    }
+
+    pub fn ui(&mut self, ctx: &egui::Context) {
+        if !self.open {
+            return;
+        }
+        self.poll();
+
+        let mut open = self.open;
+        egui::Window::new("Logs")
+            .id(egui::Id::new("ne_editor_logs"))
+            .open(&mut open)
+            .default_size([760.0, 380.0])
+            .show(ctx, |ui| {
+                self.header_row(ui);
+                ui.separator();
+                self.rows_ui(ui);
+            });
+        self.open = open;
+    }
+
+    fn header_row(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_salt("ne_editor_logs_level")
+                .selected_text(LEVELS[self.max_level])
+                .show_ui(ui, |ui| {
+                    for (i, name) in LEVELS.iter().enumerate() {
+                        ui.selectable_value(&mut self.max_level, i, *name);
+                    }
+                });
+
+            ui.label("Target:");
+            ui.add(egui::TextEdit::singleline(&mut self.target).desired_width(120.0));
+            ui.label("Search:");
+            ui.add(egui::TextEdit::singleline(&mut self.search).desired_width(160.0));
+
+            ui.separator();
+            let pause = if self.paused { "Resume" } else { "Pause" };
+            if ui.button(pause).clicked() {
+                self.paused = !self.paused;
+            }
+            if ui.button("Clear").clicked() {
+                self.rows.clear();
+            }
+            ui.checkbox(&mut self.stick_to_bottom, "Follow");
+
+            if let Some(e) = self.error.as_deref() {
+                ui.separator();
+                ui.colored_label(egui::Color32::from_rgb(230, 120, 80), e);
+            }
+        });
+    }
+
+    fn rows_ui(&mut self, ui: &mut egui::Ui) {
+        let target = self.target.trim();
+        let search = self.search.to_ascii_lowercase();
+
+        egui::ScrollArea::vertical()
+            .auto_shrink([false, false])
+            .stick_to_bottom(self.stick_to_bottom && !self.paused)
+            .show(ui, |ui| {
+                for r in self.rows.iter().filter(|r| {
+                    level_rank(&r.level) <= self.max_level
+                        && (target.is_empty() || r.target.starts_with(target))
+                        && (search.is_empty() || r.message.to_ascii_lowercase().contains(&search))
+                }) {
+                    row_ui(ui, r);
+                }
+            });
+    }
+}
+
+fn row_ui(ui: &mut egui::Ui, r: &LogRow) {
+    ui.horizontal_wrapped(|ui| {
+        ui.spacing_mut().item_spacing.x = 6.0;
+        ui.monospace(format_time(r.ts_ms));
+        ui.label(
+            egui::RichText::new(format!("{:<5}", r.level))
+                .monospace()
+                .color(level_color(&r.level)),
+        );
+        ui.label(
+            egui::RichText::new(&r.target)
+                .monospace()
+                .color(egui::Color32::GRAY),
+        );
+        ui.monospace(&r.message);
+    });
+}
+
+#[inline]
+fn level_rank(level: &str) -> usize {
+    LEVELS
+        .iter()
+        .position(|l| l.eq_ignore_ascii_case(level))
+        .unwrap_or(LEVELS.len() - 1)
+}
+
+#[inline]
+fn level_color(level: &str) -> egui::Color32 {
+    match level_rank(level) {
+        0 => egui::Color32::from_rgb(235, 90, 90),
+        1 => egui::Color32::from_rgb(230, 180, 70),
+        2 => egui::Color32::from_rgb(120, 200, 120),
+        3 => egui::Color32::from_rgb(110, 160, 230),
+        _ => egui::Color32::GRAY,
+    }
+}
+
+/// `HH:MM:SS.mmm` (UTC).
+#[inline]
+fn format_time(ts_ms: u64) -> String {
+    let ms = ts_ms % 1000;
+    let s = ts_ms / 1000;
+    format!(
+        "{:02}:{:02}:{:02}.{ms:03}",
+        (s / 3600) % 24,
+        (s / 60) % 60,
+        s % 60
+    )
+}
@@ -3,12 +3,23 @@
 use crossbeam_channel::unbounded;
 
 use newengine_core::{
-    AssetManagerConfig, Bus, ConfigPaths, Engine, EngineConfig, EngineError, EngineResult, Services,
-    ShutdownToken, StartupConfig, StartupLoader,
+    install_crash_handler, AssetManagerConfig, Bus, ConfigPaths, CrashConfig, Engine,
+    EngineConfig, EngineError, EngineResult, RunProfile, ServerRunner, Services, ShutdownToken,
+    StartupConfig, StartupLoader, StartupOverrideOrigin, StatsOverlayModule,
 };
-
-use newengine_modules_logging::{ConsoleLoggerConfig, ConsoleLoggerModule};
+use newengine_core::{active_project, project_path, set_active_project, Project};
+
+use newengine_core::plugins::ServiceLimits;
+use newengine_core::render::PostProcessSettings;
+use newengine_localization::{LocalizationApiRef, LocalizationConfig, LocalizationModule};
+use newengine_modules_environment::{EnvironmentConfig, EnvironmentModule, Skybox};
+use newengine_modules_lighting::{Light, LightId, LightingConfig, LightingModule, ShadowConfig};
+use newengine_modules_logging::{install_logger, ConsoleLoggerConfig, ConsoleLoggerModule};
+use newengine_modules_particles::{ParticlesConfig, ParticlesModule};
+use newengine_modules_tilemap::{TilemapConfig, TilemapModule};
+use newengine_modules_terrain::{TerrainConfig, TerrainModule};
 use newengine_modules_render_vulkan_ash::VulkanAshRenderModule;
+use newengine_modules_sprite2d::{Sprite2dConfig, Sprite2dModule};
 
 use newengine_platform_winit::app::config::WinitAppIcon;
 use newengine_platform_winit::{run_winit_app_with_config, WinitAppConfig, WinitWindowPlacement};
@@ -16,14 +27,45 @@ use newengine_platform_winit::{run_winit_app_with_config, WinitAppConfig, WinitW
 use newengine_ui::markup::UiMarkupDoc;
 use newengine_ui::UiBuildFn;
 
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
+mod asset_browser;
+mod batch_import;
+mod crash_notice;
+mod file_drop;
+mod hot_reload;
+mod log_viewer;
+mod plugin_ui;
+mod gizmo;
+mod hierarchy;
+mod history;
+mod inspector;
+mod post_fx;
+mod project;
 mod render_controller;
+mod resources_inspector;
+mod scene;
+mod selection;
 mod ui;
+mod workspace;
 
 const FIXED_DT_MS: u32 = 16;
 const UI_MARKUP_PATH: &str = "ui/editor.xml";
+const WORKSPACES_PATH: &str = "editor.workspaces.json";
+/// Scene asset the hierarchy edits when the project names no startup scene, under the assets
+/// root.
+const SCENE_PATH: &str = "scenes/editor.scene.json";
+/// Projects opened before; per user, so in the working directory rather than a project.
+const RECENT_PROJECTS_PATH: &str = "editor.recent_projects.json";
+/// Per-user settings (console `bind` hotkeys).
+const USER_CONFIG_PATH: &str = "editor.user.json";
+/// Panic/crash reports; the newest unseen one is offered in the editor on next start.
+const CRASH_DIR: &str = "crash_reports";
+/// Module tunables (`config.get`/`config.set`), written back on exit.
+const CONFIG_PATH: &str = "editor.config.toml";
+const UI_LOCALES: &[&str] = &["en", "ru"];
 
 struct AppServices;
 
@@ -55,19 +97,65 @@ fn winit_config_from_startup(startup: &StartupConfig) -> WinitAppConfig {
         size: startup.window_size,
         placement,
         ui_backend: startup.ui_backend.clone(),
+        unfocused_fps: startup.window_unfocused_fps,
         icon: None,
+        input_record: None,
+        input_replay: None,
     }
 }
 
 #[inline]
-fn register_render_from_startup(engine: &mut Engine<()>, startup: &StartupConfig) -> EngineResult<()> {
+fn register_render_from_startup(
+    engine: &mut Engine<()>,
+    startup: &StartupConfig,
+    selection: selection::ViewportSelection,
+    scene: scene::EditorScene,
+) -> EngineResult<()> {
     let backend = startup.render_backend.trim();
 
     if backend.eq_ignore_ascii_case("vulkan_ash") || backend.eq_ignore_ascii_case("vulkan") {
-        engine.register_module(Box::new(VulkanAshRenderModule::new()))?;
+        engine.register_module(Box::new(
+            VulkanAshRenderModule::new()
+                .with_pipeline_cache_dir(startup.render_pipeline_cache_dir.clone()),
+        ))?;
+
+        // 2D sprite layer; the render controller draws its queue over the scene.
+        engine.register_module(Box::new(Sprite2dModule::new(Sprite2dConfig::new())))?;
+        engine.register_module(Box::new(ParticlesModule::new(ParticlesConfig::new())))?;
+        engine.register_module(Box::new(TilemapModule::new(TilemapConfig::new())))?;
+        engine.register_module(Box::new(TerrainModule::new(TerrainConfig::new())))?;
+
+        // Default sun until a scene brings its own lights.
+        let shadows = ShadowConfig::new()
+            .with_cascades(startup.render_shadow_cascades as usize)
+            .with_map_size(startup.render_shadow_map_size)
+            .with_distance(startup.render_shadow_distance)
+            .with_split_lambda(startup.render_shadow_split_lambda);
+        let lighting = LightingModule::new(LightingConfig::new().with_shadows(shadows));
+        lighting.api().insert(
+            LightId(0),
+            Light::directional([-0.4, -0.8, -0.45])
+                .with_intensity(0.85)
+                .with_shadows(true),
+        );
+        engine.register_module(Box::new(lighting))?;
+
+        let environment = EnvironmentModule::new(EnvironmentConfig::new());
+        let skybox = startup.render_skybox.trim();
+        if !skybox.is_empty() {
+            environment.api().set_skybox(Some(Skybox::new(skybox)));
+        }
+        engine.register_module(Box::new(environment))?;
+
+        // Bloom, FXAA and vignette; the render controller applies the cvars every frame.
+        if let Err(e) = PostProcessSettings::new().register_cvars() {
+            log::warn!("post: cvars not registered: {e}");
+        }
 
         engine.register_module(Box::new(
-            render_controller::EditorRenderController::new(startup.render_clear_color),
+            render_controller::EditorRenderController::new(startup.render_clear_color)
+                .with_selection(selection)
+                .with_scene(scene),
         ))?;
 
         return Ok(());
@@ -78,7 +166,23 @@ fn register_render_from_startup(engine: &mut Engine<()>, startup: &StartupConfig
     )))
 }
 
-fn build_engine_from_startup(startup: &StartupConfig) -> EngineResult<Engine<()>> {
+/// Workspace layouts belong to the project: under its root, or beside the assets root when the
+/// editor runs without a `.neproject`, so two games opened from one directory keep their own.
+fn workspaces_path(startup: &StartupConfig) -> PathBuf {
+    if active_project().is_some() {
+        return project_path(WORKSPACES_PATH);
+    }
+    startup
+        .assets_root
+        .parent()
+        .unwrap_or(Path::new(""))
+        .join(WORKSPACES_PATH)
+}
+
+fn build_engine_from_startup(
+    startup: &StartupConfig,
+    profile: RunProfile,
+) -> EngineResult<Engine<()>> {
     let (tx, rx) = unbounded::<()>();
     let bus: Bus<()> = Bus::new(tx, rx);
 
@@ -87,10 +191,33 @@ fn build_engine_from_startup(startup: &StartupConfig) -> EngineResult<Engine<()>
 
     let assets = AssetManagerConfig::new(startup.assets_root.clone())
         .with_pump_steps(startup.asset_pump_steps)
-        .with_filesystem_source(startup.asset_filesystem_source);
+        .with_filesystem_source(startup.asset_filesystem_source)
+        .with_path_case(startup.asset_path_case)
+        .with_cache_dir(startup.asset_cache_dir.clone())
+        .with_engine_root(startup.asset_engine_root.clone())
+        .with_mods_root(startup.asset_mods_root.clone())
+        .with_packs(startup.asset_packs.clone());
+
+    let mut limits = ServiceLimits::default()
+        .with_max_payload_bytes(startup.service_max_payload_bytes as usize)
+        .with_max_calls_per_sec(startup.service_max_calls_per_sec)
+        .with_limit_host(startup.service_limit_host);
+    for (id, bytes) in &startup.service_payload_limits {
+        limits = limits.with_service_payload_limit(id.clone(), *bytes as usize);
+    }
 
-    let config =
-        EngineConfig::new(FIXED_DT_MS, assets).with_plugins_dir(Some(startup.modules_dir.clone()));
+    let config = EngineConfig::new(FIXED_DT_MS, assets)
+        .with_plugins_dir(Some(startup.modules_dir.clone()))
+        .with_disabled_plugins(startup.disabled_plugins.clone())
+        .with_service_limits(limits)
+        .with_user_config_path(Some(USER_CONFIG_PATH.into()))
+        .with_config_path(Some(project_path(CONFIG_PATH)))
+        .with_profile(profile);
+
+    let config = match profile {
+        RunProfile::Server => config.with_tick_rate(startup.server_tick_rate),
+        RunProfile::Client => config,
+    };
 
     let mut engine: Engine<()> = Engine::new_with_config(config, services, bus, shutdown)?;
 
@@ -101,6 +228,18 @@ fn build_engine_from_startup(startup: &StartupConfig) -> EngineResult<Engine<()>
     Ok(engine)
 }
 
+fn register_localization_from_startup(
+    engine: &mut Engine<()>,
+    startup: &StartupConfig,
+) -> EngineResult<LocalizationApiRef> {
+    let config = LocalizationConfig::new(startup.ui_locale.clone())
+        .with_locales(UI_LOCALES.iter().map(|l| l.to_string()).collect());
+    let module = LocalizationModule::new(config);
+    let api = module.api();
+    engine.register_module(Box::new(module))?;
+    Ok(api)
+}
+
 #[inline]
 fn configure_logger(startup: &StartupConfig) -> ConsoleLoggerConfig {
     let mut cfg = ConsoleLoggerConfig::from_env();
@@ -121,15 +260,7 @@ fn configure_logger(startup: &StartupConfig) -> ConsoleLoggerConfig {
 fn bootstrap_logging(startup: &StartupConfig) {
     // Ensure logs are available before Engine::start() and before plugin loading.
     // The ConsoleLoggerModule will later attempt to install the logger and will no-op.
-    let mut builder = env_logger::Builder::new();
-
-    if let Ok(level) = startup.log_level.parse::<log::LevelFilter>() {
-        builder.filter_level(level);
-    } else {
-        builder.filter_level(log::LevelFilter::Info);
-    }
-
-    let _ = builder.try_init();
+    let _ = install_logger(&configure_logger(startup));
 }
 
 fn load_asset_blob_with_timeout(
@@ -202,12 +333,41 @@ fn try_load_window_icon(engine: &Engine<()>, startup: &StartupConfig) -> Option<
 }
 
 fn main() -> EngineResult<()> {
+    // `--project=<file>` (or a bare `.neproject` path) and `--profile=<name>`; without a
+    // project, everything is relative to the working directory as before.
+    let project = match project::project_arg(std::env::args().skip(1)) {
+        Some(path) => Some(
+            Project::load(&path).map_err(|e| EngineError::other(format!("project: {e}")))?,
+        ),
+        None => None,
+    };
+    let build_profile = std::env::args()
+        .skip(1)
+        .find_map(|a| a.strip_prefix("--profile=").map(str::to_owned));
+
     let paths = ConfigPaths::from_startup_str("config.json");
-    let (startup, report) = StartupLoader::load_json(&paths)?;
+    // File, then the project and its profile, then NEWENGINE_* env vars, then
+    // `--section.key=value` args.
+    let (mut startup, report) = StartupLoader::load_with_project(
+        &paths,
+        project.as_ref(),
+        build_profile.as_deref(),
+        std::env::args().skip(1),
+    )?;
+
+    let import_args = batch_import::BatchImportArgs::from_args(std::env::args().skip(1))
+        .map_err(|e| EngineError::other(format!("args: {e}")))?;
+    if let Some(args) = import_args.as_ref() {
+        args.apply_to_startup(&mut startup);
+    }
 
     // Bootstrap logging as early as possible, before any plugin/importer activity.
     bootstrap_logging(&startup);
 
+    // Looked up before this session can write a report of its own.
+    let last_crash = newengine_core::crash::unseen_crash_report(CRASH_DIR.as_ref());
+    install_crash_handler(CrashConfig::new(CRASH_DIR).with_app_name("editor"));
+
     println!(
         "startup: loaded source={:?} file={:?} resolved_from={:?} overrides={}",
         report.source,
@@ -216,15 +376,76 @@ fn main() -> EngineResult<()> {
         report.overrides.len()
     );
     for ov in report.overrides.iter() {
-        println!("startup: override {}: '{}' -> '{}'", ov.key, ov.from, ov.to);
+        match &ov.origin {
+            StartupOverrideOrigin::File => {
+                println!("startup: override {}: '{}' -> '{}'", ov.key, ov.from, ov.to)
+            }
+            StartupOverrideOrigin::Env(src)
+            | StartupOverrideOrigin::Arg(src)
+            | StartupOverrideOrigin::Project(src) => println!(
+                "startup: override {}: '{}' -> '{}' ({src})",
+                ov.key, ov.from, ov.to
+            ),
+        }
+    }
+    for w in report.warnings.iter() {
+        println!("startup: warning {w}");
+    }
+
+    // Workspaces and module settings are per project from here on.
+    let mut recent = project::RecentProjects::load_or_default(RECENT_PROJECTS_PATH);
+    if let Some(p) = &project {
+        recent.push(p.path());
     }
+    // Set by the project (or `--engine.startup_scene=`).
+    let scene_path = startup
+        .startup_scene
+        .clone()
+        .unwrap_or_else(|| SCENE_PATH.to_owned());
+    set_active_project(project);
 
     let startup = Arc::new(startup);
 
-    let mut engine = build_engine_from_startup(&startup)?;
+    let server = std::env::args().skip(1).any(|a| a == "--server");
+    let profile = if server {
+        RunProfile::Server
+    } else {
+        RunProfile::Client
+    };
+
+    let mut engine = build_engine_from_startup(&startup, profile)?;
+
+    // Headless batch import: importers only, no window/render, exit code reflects failures.
+    if let Some(args) = import_args {
+        engine.load_plugins_once()?;
+        let res = batch_import::run(&mut engine, &args);
+        let _ = engine.shutdown();
+        return res;
+    }
+
+    // Dedicated server: fixed-rate ticking, no window/render/UI; SIGTERM shuts down cleanly.
+    if server {
+        engine.load_plugins_once()?;
+        ServerRunner::new(engine).run()?;
+        return Ok(());
+    }
 
     // 1) Register render (backend + controller) so the module set is complete before window creation.
-    register_render_from_startup(&mut engine, &startup)?;
+    // Viewport clicks in the UI are picked by the render controller; the gizmo, hierarchy and
+    // inspector edit the scene objects it draws, and the hierarchy writes them back.
+    let selection = selection::ViewportSelection::default();
+    let scene_file = startup
+        .asset_filesystem_source
+        .then(|| startup.assets_root.join(&scene_path));
+    let scene = match &scene_file {
+        Some(path) => scene::EditorScene::load_or_default(path),
+        None => scene::EditorScene::default(),
+    };
+    // `entity.set` edits the editor scene; entity ids are its object ids.
+    newengine_core::set_component_store(Some(Arc::new(scene.clone())));
+    register_render_from_startup(&mut engine, &startup, selection.clone(), scene.clone())?;
+
+    let localization = register_localization_from_startup(&mut engine, &startup)?;
 
     // 2) Load plugins/importers BEFORE creating winit (required: plugins/providers must exist).
     engine.load_plugins_once()?;
@@ -234,13 +455,17 @@ fn main() -> EngineResult<()> {
 
     let mut winit_cfg = winit_config_from_startup(&startup);
     winit_cfg.icon = icon;
+    for arg in std::env::args().skip(1) {
+        if let Some(path) = arg.strip_prefix("--record-input=") {
+            winit_cfg.input_record = Some(PathBuf::from(path));
+        } else if let Some(path) = arg.strip_prefix("--replay-input=") {
+            winit_cfg.input_replay = Some(PathBuf::from(path));
+        }
+    }
 
-    // UI builder exists immediately; document is loaded after importers are ready.
+    // Document is loaded after importers are ready; the UI builder shares it.
     let shared_doc: Arc<Mutex<Option<UiMarkupDoc>>> = Arc::new(Mutex::new(None));
-    let ui_build: Option<Box<dyn UiBuildFn>> = match startup.ui_backend {
-        newengine_core::startup::UiBackend::Disabled => None,
-        _ => Some(Box::new(ui::EditorUiBuild::new(shared_doc.clone()))),
-    };
+    let mut hot_reload: Option<hot_reload::UiMarkupHotReload> = None;
 
     let startup_for_after = Arc::clone(&startup);
 
@@ -267,7 +492,58 @@ fn main() -> EngineResult<()> {
         if let Ok(mut g) = shared_doc.lock() {
             *g = Some(doc);
         }
+
+        let fs_path = startup
+            .asset_filesystem_source
+            .then(|| startup.assets_root.join(UI_MARKUP_PATH));
+        hot_reload = Some(hot_reload::UiMarkupHotReload::new(
+            Arc::clone(store),
+            UI_MARKUP_PATH,
+            fs_path,
+        ));
+    }
+
+    // Files dropped onto the window are opened via AssetStore (models -> viewport, .ui -> UI).
+    let mut file_drop = file_drop::FileDropModule::new(
+        startup.assets_root.clone(),
+        startup.asset_filesystem_source,
+    );
+    if !matches!(startup.ui_backend, newengine_core::startup::UiBackend::Disabled) {
+        file_drop = file_drop.with_shared_doc(shared_doc.clone());
     }
+    engine.register_module(Box::new(file_drop))?;
+
+    // FPS / frame time / asset / GPU overlay, painted into the UI draw list (F3 or stats.toggle).
+    engine.register_module(Box::new(StatsOverlayModule::new()))?;
+
+    // Last module: snapshots Resources after everyone else's update for the inspector panel.
+    let resources_view = resources_inspector::ResourcesView::default();
+    engine.register_module(Box::new(resources_inspector::ResourcesSnapshotModule::new(
+        resources_view.clone(),
+    )))?;
+
+    let ui_build: Option<Box<dyn UiBuildFn>> = match startup.ui_backend {
+        newengine_core::startup::UiBackend::Disabled => None,
+        _ => Some(Box::new(
+            ui::EditorUiBuild::new(
+                shared_doc.clone(),
+                workspace::Workspaces::load_or_default(workspaces_path(&startup)),
+            )
+            .with_hot_reload(hot_reload)
+            .with_resources_view(resources_view)
+            .with_selection(selection.clone())
+            .with_gizmo(gizmo::GizmoUi::new(scene.clone(), selection.clone()))
+            .with_hierarchy(hierarchy::HierarchyPanel::new(
+                scene.clone(),
+                selection.clone(),
+                scene_file,
+            ))
+            .with_inspector(inspector::InspectorPanel::new(scene, selection))
+            .with_localization(localization)
+            .with_crash_report(last_crash)
+            .with_project(project::ProjectPanel::new(recent, build_profile)),
+        )),
+    };
 
     run_winit_app_with_config(engine, winit_cfg, ui_build, move |_engine| {
         // Window-dependent work is handled by modules via WinitWindowHandles.
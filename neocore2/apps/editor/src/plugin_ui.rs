@@ -0,0 +1,314 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use newengine_core::plugins::host_context::services_generation;
+use newengine_platform_winit::egui;
+use newengine_ui::markup::{
+    UiAction, UiActionRouter, UiEvent, UiEventKind, UiMarkupDoc, UiState, ACTION_ERROR_VAR,
+    ACTION_RESULT_VAR,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Action prefix that runs a console command line, e.g. `command:assets.reload_all`.
+const COMMAND_ACTION_PREFIX: &str = "command:";
+
+/// `"ui"` section of a service `describe()`:
+///
+/// ```json
+/// "ui": {
+///   "panels": [ { "id": "navmesh.panel", "title": "Navmesh", "markup": "<row>...</row>" } ],
+///   "menu": [ { "label": "Rebuild navmesh", "action": "call:navmesh/navmesh.rebuild" } ],
+///   "events": "navmesh.ui_event"
+/// }
+/// ```
+///
+/// Panel markup is the body of an editor window. Actions use the markup grammar (`call:`,
+/// `set:`) plus `command:<console line>`; any other action is sent to the `events` method.
+#[derive(Debug, Default, Deserialize)]
+struct UiContribution {
+    #[serde(default)]
+    panels: Vec<PanelDecl>,
+    #[serde(default)]
+    menu: Vec<MenuDecl>,
+    #[serde(default)]
+    events: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PanelDecl {
+    id: String,
+    #[serde(default)]
+    title: Option<String>,
+    markup: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct MenuDecl {
+    label: String,
+    action: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ServiceDescribe {
+    #[serde(default)]
+    ui: Option<UiContribution>,
+}
+
+/// Payload sent to a plugin's `events` method.
+#[derive(Debug, Serialize)]
+struct UiEventPayload<'a> {
+    panel: Option<&'a str>,
+    target: &'a str,
+    kind: &'static str,
+    value: Option<&'a str>,
+    action: &'a str,
+}
+
+struct PluginPanel {
+    service_id: String,
+    id: String,
+    title: String,
+    doc: UiMarkupDoc,
+}
+
+struct PluginMenuItem {
+    label: String,
+    action: String,
+}
+
+/// Editor side of plugin UI contributions: panels, menu entries and event routing.
+///
+/// Contributions are read from service `describe()` JSON and re-read whenever the service
+/// registry changes, so panels follow plugin load/unload. Panel windows share the editor
+/// `UiState`, which puts their open state into workspace layouts like built-in panels.
+#[derive(Default)]
+pub struct PluginUi {
+    generation: u64,
+    panels: Vec<PluginPanel>,
+    /// Service id -> menu entries.
+    menu: BTreeMap<String, Vec<PluginMenuItem>>,
+    /// Service id -> method receiving custom UI events.
+    event_methods: BTreeMap<String, String>,
+    /// Menu actions picked this frame: `(service_id, action)`.
+    pending: Vec<(String, String)>,
+}
+
+impl PluginUi {
+    fn refresh(&mut self) {
+        let generation = services_generation();
+        if generation == self.generation {
+            return;
+        }
+        self.generation = generation;
+
+        self.panels.clear();
+        self.menu.clear();
+        self.event_methods.clear();
+
+        for service_id in newengine_core::list_service_ids() {
+            let Some(json) = newengine_core::describe_service(&service_id) else {
+                continue;
+            };
+            let ui = match serde_json::from_str::<ServiceDescribe>(&json) {
+                Ok(ServiceDescribe { ui: Some(ui) }) => ui,
+                Ok(_) => continue,
+                Err(e) => {
+                    log::warn!("plugin_ui: bad describe service='{service_id}' err='{e}'");
+                    continue;
+                }
+            };
+            self.add(service_id, ui);
+        }
+    }
+
+    fn add(&mut self, service_id: String, ui: UiContribution) {
+        for p in ui.panels {
+            if self.panels.iter().any(|q| q.id == p.id) {
+                log::warn!(
+                    "plugin_ui: duplicate panel id='{}' service='{service_id}'",
+                    p.id
+                );
+                continue;
+            }
+
+            let title = p.title.unwrap_or_else(|| p.id.clone());
+            let xml = format!(
+                "<ui><window id=\"{}\" title=\"{}\" open=\"false\">{}</window></ui>",
+                xml_escape(&p.id),
+                xml_escape(&title),
+                p.markup
+            );
+            match UiMarkupDoc::parse(&xml) {
+                Ok(doc) => self.panels.push(PluginPanel {
+                    service_id: service_id.clone(),
+                    id: p.id,
+                    title,
+                    doc,
+                }),
+                Err(e) => log::warn!(
+                    "plugin_ui: panel markup failed id='{}' service='{service_id}' err='{e}'",
+                    p.id
+                ),
+            }
+        }
+
+        if !ui.menu.is_empty() {
+            let items = ui
+                .menu
+                .into_iter()
+                .map(|m| PluginMenuItem {
+                    label: m.label,
+                    action: m.action,
+                })
+                .collect();
+            self.menu.insert(service_id.clone(), items);
+        }
+
+        if let Some(method) = ui.events.filter(|m| !m.trim().is_empty()) {
+            self.event_methods.insert(service_id, method);
+        }
+    }
+
+    /// "Plugins" toolbar menu; hidden while no plugin contributes UI.
+    pub fn toolbar_ui(&mut self, ui: &mut egui::Ui, state: &mut UiState) {
+        self.refresh();
+        if self.panels.is_empty() && self.menu.is_empty() {
+            return;
+        }
+
+        ui.menu_button("Plugins", |ui| {
+            for p in self.panels.iter() {
+                let mut open = state.panel_open(&p.id).unwrap_or(false);
+                if ui.checkbox(&mut open, p.title.as_str()).changed() {
+                    state.set_panel_open(p.id.clone(), open);
+                }
+            }
+
+            for (service_id, items) in self.menu.iter() {
+                ui.separator();
+                ui.label(egui::RichText::new(service_id).small().weak());
+                for it in items {
+                    if ui.button(it.label.as_str()).clicked() {
+                        self.pending.push((service_id.clone(), it.action.clone()));
+                        ui.close_menu();
+                    }
+                }
+            }
+        });
+    }
+
+    /// Runs picked menu actions, draws plugin panels and routes their events.
+    ///
+    /// Call after the host document's events were dispatched, so every event left in `state`
+    /// belongs to the panel just drawn.
+    pub fn ui(&mut self, ctx: &egui::Context, state: &mut UiState, router: &mut UiActionRouter) {
+        self.refresh();
+
+        for (service_id, action) in std::mem::take(&mut self.pending) {
+            self.run_menu_action(&service_id, &action, state);
+        }
+
+        for p in self.panels.iter() {
+            p.doc.render_fragment(ctx, state);
+            for ev in router.dispatch(state) {
+                self.route(&p.service_id, Some(&p.id), &ev, state);
+            }
+        }
+    }
+
+    fn run_menu_action(&self, service_id: &str, action: &str, state: &mut UiState) {
+        match UiAction::parse(action) {
+            UiAction::Call {
+                service,
+                method,
+                payload,
+            } => record(
+                state,
+                service,
+                method,
+                newengine_core::call_service_v1(service, method, payload.as_bytes()),
+            ),
+            UiAction::Set { var, value } => state.set_var(var, value),
+            UiAction::Custom(_) => {
+                let ev = UiEvent {
+                    kind: UiEventKind::Click,
+                    target_id: String::new(),
+                    value: None,
+                    actions: std::iter::once(action.to_string()).collect(),
+                };
+                self.route(service_id, None, &ev, state);
+            }
+        }
+    }
+
+    /// Handles the custom actions the markup router left: console commands run here, the
+    /// rest goes to the service's `events` method.
+    fn route(&self, service_id: &str, panel: Option<&str>, ev: &UiEvent, state: &mut UiState) {
+        for action in ev.actions.iter() {
+            if let Some(line) = action.strip_prefix(COMMAND_ACTION_PREFIX) {
+                let res = newengine_core::call_service_v1(
+                    "engine.command",
+                    "command.exec",
+                    line.trim().as_bytes(),
+                );
+                record(state, "engine.command", "command.exec", res);
+                continue;
+            }
+
+            let Some(method) = self.event_methods.get(service_id) else {
+                log::warn!("plugin_ui: unhandled action='{action}' service='{service_id}'");
+                continue;
+            };
+            let payload = UiEventPayload {
+                panel,
+                target: &ev.target_id,
+                kind: event_kind(ev.kind),
+                value: ev.value.as_deref(),
+                action,
+            };
+            let Ok(bytes) = serde_json::to_vec(&payload) else {
+                continue;
+            };
+            let res = newengine_core::call_service_v1(service_id, method, &bytes);
+            record(state, service_id, method, res);
+        }
+    }
+}
+
+/// Mirrors `UiActionRouter`: the response or error lands in the action vars.
+fn record(state: &mut UiState, service: &str, method: &str, res: Result<Vec<u8>, String>) {
+    match res {
+        Ok(bytes) => {
+            state.set_var(
+                ACTION_RESULT_VAR,
+                String::from_utf8_lossy(&bytes).to_string(),
+            );
+            state.vars.remove(ACTION_ERROR_VAR);
+        }
+        Err(e) => state.set_var(ACTION_ERROR_VAR, format!("{service}/{method}: {e}")),
+    }
+}
+
+#[inline]
+fn event_kind(kind: UiEventKind) -> &'static str {
+    match kind {
+        UiEventKind::Click => "click",
+        UiEventKind::Change => "change",
+        UiEventKind::Submit => "submit",
+    }
+}
+
+fn xml_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
@@ -0,0 +1,109 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use newengine_core::render::post_cvar;
+use newengine_core::{
+    cvar_list, undoable_cvar_set, CommandBatch, CvarInfo, CvarSet, CvarValue, UndoStack,
+};
+use newengine_platform_winit::egui;
+
+/// Editor window over the `post.*` cvars; the render controller picks up changes next frame.
+/// Edits are undoable.
+#[derive(Debug, Default)]
+pub struct PostFxPanel {
+    open: bool,
+    error: Option<String>,
+}
+
+impl PostFxPanel {
+    pub fn toolbar_ui(&mut self, ui: &mut egui::Ui) {
+        ui.toggle_value(&mut self.open, "Post FX");
+    }
+
+    pub fn ui(&mut self, ctx: &egui::Context) {
+        if !self.open {
+            return;
+        }
+
+        let mut cvars = cvar_list(post_cvar::PREFIX);
+        cvars.sort_by(|a, b| a.desc.name.cmp(&b.desc.name));
+
+        let mut open = self.open;
+        egui::Window::new("Post FX")
+            .id(egui::Id::new("ne_editor_post_fx"))
+            .open(&mut open)
+            .default_size([360.0, 220.0])
+            .show(ctx, |ui| {
+                if cvars.is_empty() {
+                    ui.label("No post-process cvars registered.");
+                    return;
+                }
+
+                egui::Grid::new("ne_editor_post_fx_grid")
+                    .num_columns(2)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for c in &cvars {
+                            self.row_ui(ui, c);
+                            ui.end_row();
+                        }
+                    });
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    if ui.button("Reset").clicked() {
+                        self.error = Self::reset(&cvars).err();
+                    }
+                    if let Some(e) = &self.error {
+                        ui.colored_label(egui::Color32::LIGHT_RED, e.as_str());
+                    }
+                });
+            });
+        self.open = open;
+    }
+
+    fn row_ui(&mut self, ui: &mut egui::Ui, c: &CvarInfo) {
+        let name = &c.desc.name;
+        let label = name.strip_prefix(post_cvar::PREFIX).unwrap_or(name);
+        ui.label(label).on_hover_text(c.desc.help.as_str());
+
+        let changed = match &c.value {
+            CvarValue::Bool(v) => {
+                let mut v = *v;
+                ui.checkbox(&mut v, "")
+                    .changed()
+                    .then_some(CvarValue::Bool(v))
+            }
+            CvarValue::Float(v) => {
+                let mut v = *v;
+                let min = c.desc.min.unwrap_or(0.0);
+                let max = c.desc.max.unwrap_or(min + 1.0);
+                ui.add(egui::Slider::new(&mut v, min..=max))
+                    .changed()
+                    .then_some(CvarValue::Float(v))
+            }
+            other => {
+                ui.monospace(format!("{other:?}"));
+                None
+            }
+        };
+
+        if let Some(value) = changed {
+            self.error = undoable_cvar_set(name, value).err();
+        }
+    }
+
+    /// Puts every cvar back to its default as one edit.
+    fn reset(cvars: &[CvarInfo]) -> Result<(), String> {
+        let mut batch = CommandBatch::new("reset post fx");
+        for c in cvars {
+            let default = c.desc.kind.from_json(&c.desc.default)?;
+            if c.value != default {
+                batch.push(CvarSet::new(&c.desc.name, default)?);
+            }
+        }
+        if batch.is_empty() {
+            return Ok(());
+        }
+        UndoStack::global().execute(batch)
+    }
+}
@@ -0,0 +1,341 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use newengine_core::project_service::{method, PROJECT_SERVICE_ID};
+use newengine_core::{active_project, export_status, ExportStatus, Project, PROJECT_EXTENSION};
+use newengine_platform_winit::egui;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Entries kept in the recent projects list.
+const RECENT_LIMIT: usize = 10;
+
+/// Status refresh while an export runs on its thread.
+const EXPORT_POLL: Duration = Duration::from_millis(250);
+
+#[derive(Debug, Deserialize)]
+struct ExportStartResp {
+    started: bool,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct RecentFile {
+    #[serde(default)]
+    projects: Vec<PathBuf>,
+}
+
+/// Project files opened in the editor, newest first. Per user, so stored next to the editor
+/// rather than in a project.
+#[derive(Debug, Default)]
+pub struct RecentProjects {
+    path: PathBuf,
+    projects: Vec<PathBuf>,
+}
+
+impl RecentProjects {
+    /// Loads the list from `path`; a missing or broken file is an empty list.
+    pub fn load_or_default(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let projects = match std::fs::read_to_string(&path) {
+            Ok(text) => match serde_json::from_str::<RecentFile>(&text) {
+                Ok(f) => f.projects,
+                Err(e) => {
+                    log::warn!(
+                        "project: recent list unreadable path='{}' err='{e}'",
+                        path.display()
+                    );
+                    Vec::new()
+                }
+            },
+            Err(_) => Vec::new(),
+        };
+        Self { path, projects }
+    }
+
+    #[inline]
+    pub fn projects(&self) -> &[PathBuf] {
+        &self.projects
+    }
+
+    /// Moves `project` to the front and writes the list.
+    pub fn push(&mut self, project: &Path) {
+        self.projects.retain(|p| p != project);
+        self.projects.insert(0, project.to_path_buf());
+        self.projects.truncate(RECENT_LIMIT);
+        self.save();
+    }
+
+    pub fn remove(&mut self, project: &Path) {
+        self.projects.retain(|p| p != project);
+        self.save();
+    }
+
+    fn save(&self) {
+        let file = RecentFile {
+            projects: self.projects.clone(),
+        };
+        let res = serde_json::to_string_pretty(&file)
+            .map_err(|e| e.to_string())
+            .and_then(|text| std::fs::write(&self.path, text).map_err(|e| e.to_string()));
+        if let Err(e) = res {
+            log::warn!(
+                "project: recent list save failed path='{}' err='{e}'",
+                self.path.display()
+            );
+        }
+    }
+}
+
+/// Project file given on the command line: `--project=<file>` or a bare `<file>.neproject`
+/// (what a file manager passes when one is opened with the editor).
+pub fn project_arg(args: impl IntoIterator<Item = String>) -> Option<PathBuf> {
+    args.into_iter()
+        .find_map(|a| match a.strip_prefix("--project=") {
+            Some(path) => Some(PathBuf::from(path)),
+            None => {
+                let path = Path::new(&a);
+                let is_project = path.extension().is_some_and(|e| e == PROJECT_EXTENSION);
+                (!a.starts_with("--") && is_project).then(|| path.to_path_buf())
+            }
+        })
+}
+
+/// Starts another editor on `project`. The caller quits this one: startup settings (asset
+/// roots, plugins) are fixed for the life of the process.
+fn relaunch(project: &Path) -> Result<(), String> {
+    let exe = std::env::current_exe().map_err(|e| format!("current_exe failed: {e}"))?;
+    std::process::Command::new(exe)
+        .arg(format!("--project={}", project.display()))
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("relaunch failed: {e}"))
+}
+
+/// Active project, recent projects and opening another one.
+#[derive(Debug, Default)]
+pub struct ProjectPanel {
+    recent: RecentProjects,
+    /// Profile the editor was started with; `None` is the project's default.
+    profile: Option<String>,
+    open: bool,
+    /// Project file path being typed.
+    input: String,
+    error: Option<String>,
+    /// Profile picked for export; the one the editor runs with until changed.
+    export_profile: Option<String>,
+    export_error: Option<String>,
+}
+
+impl ProjectPanel {
+    #[inline]
+    pub fn new(recent: RecentProjects, profile: Option<String>) -> Self {
+        Self {
+            recent,
+            profile,
+            ..Self::default()
+        }
+    }
+
+    pub fn toolbar_ui(&mut self, ui: &mut egui::Ui) {
+        ui.toggle_value(&mut self.open, "Project");
+    }
+
+    /// Returns true once another project was launched; the caller quits the editor.
+    pub fn ui(&mut self, ctx: &egui::Context) -> bool {
+        if !self.open {
+            return false;
+        }
+
+        let mut pick: Option<PathBuf> = None;
+        let mut forget: Option<PathBuf> = None;
+
+        let mut open = self.open;
+        egui::Window::new("Project")
+            .id(egui::Id::new("ne_editor_project"))
+            .open(&mut open)
+            .default_size([420.0, 320.0])
+            .show(ctx, |ui| {
+                self.active_ui(ui);
+                self.export_ui(ui);
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    let hint = format!("path/to/game.{PROJECT_EXTENSION}");
+                    let edit = egui::TextEdit::singleline(&mut self.input).hint_text(hint);
+                    let resp = ui.add(edit);
+                    let entered =
+                        resp.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                    if (ui.button("Open").clicked() || entered) && !self.input.trim().is_empty() {
+                        pick = Some(PathBuf::from(self.input.trim()));
+                    }
+                });
+                if let Some(e) = &self.error {
+                    ui.colored_label(egui::Color32::LIGHT_RED, e.as_str());
+                }
+
+                ui.separator();
+                ui.label("Recent");
+                if self.recent.projects().is_empty() {
+                    ui.weak("No recent projects.");
+                }
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    for path in self.recent.projects() {
+                        ui.horizontal(|ui| {
+                            let name = path.file_stem().unwrap_or_default().to_string_lossy();
+                            let button = ui.button(name).on_hover_text(path.display().to_string());
+                            if button.clicked() {
+                                pick = Some(path.clone());
+                            }
+                            if ui
+                                .small_button("x")
+                                .on_hover_text("Remove from list")
+                                .clicked()
+                            {
+                                forget = Some(path.clone());
+                            }
+                            ui.weak(path.display().to_string());
+                        });
+                    }
+                });
+            });
+        self.open = open;
+
+        if let Some(path) = forget {
+            self.recent.remove(&path);
+        }
+        let Some(path) = pick else {
+            return false;
+        };
+        // Checked here so a typo shows in the panel instead of in a process that exits.
+        let opened = Project::load(&path).and_then(|p| {
+            relaunch(p.path())?;
+            self.recent.push(p.path());
+            Ok(())
+        });
+        match opened {
+            Ok(()) => {
+                log::info!("project: opening path='{}'", path.display());
+                true
+            }
+            Err(e) => {
+                self.error = Some(e);
+                false
+            }
+        }
+    }
+
+    fn active_ui(&self, ui: &mut egui::Ui) {
+        let Some(project) = active_project() else {
+            ui.weak("No project: running from the working directory config.");
+            return;
+        };
+
+        ui.heading(project.name());
+        egui::Grid::new("ne_editor_project_info")
+            .num_columns(2)
+            .show(ui, |ui| {
+                ui.label("File");
+                ui.label(project.path().display().to_string());
+                ui.end_row();
+
+                let profile = project.profile(self.profile.as_deref()).ok().flatten();
+                ui.label("Profile");
+                ui.label(profile.map_or("-", |(name, _)| name));
+                ui.end_row();
+
+                let assets = &project.file().assets;
+                ui.label("Assets");
+                ui.label(assets.game.as_deref().unwrap_or("(config)"));
+                ui.end_row();
+
+                let scene = project.startup_scene(profile.map(|(_, p)| p));
+                ui.label("Startup scene");
+                ui.label(scene.unwrap_or("(editor default)"));
+                ui.end_row();
+
+                let profiles: Vec<&str> = project.profile_names().collect();
+                ui.label("Profiles");
+                ui.label(profiles.join(", "));
+                ui.end_row();
+            });
+    }
+
+    /// Profile picker, `project.export` and the state of the last export.
+    fn export_ui(&mut self, ui: &mut egui::Ui) {
+        let Some(project) = active_project() else {
+            return;
+        };
+        let status = export_status();
+        if status.is_running() {
+            ui.ctx().request_repaint_after(EXPORT_POLL);
+        }
+
+        ui.separator();
+        ui.horizontal(|ui| {
+            let current = self
+                .export_profile
+                .clone()
+                .or_else(|| self.profile.clone())
+                .or_else(|| project.file().default_profile.clone())
+                .unwrap_or_default();
+            egui::ComboBox::from_id_salt("ne_editor_export_profile")
+                .selected_text(current.as_str())
+                .show_ui(ui, |ui| {
+                    for name in project.profile_names() {
+                        if ui.selectable_label(name == current, name).clicked() {
+                            self.export_profile = Some(name.to_owned());
+                        }
+                    }
+                });
+
+            let button = egui::Button::new("Export");
+            if ui.add_enabled(!status.is_running(), button).clicked() {
+                self.export_error = start(&current).err();
+            }
+        });
+        if let Some(e) = &self.export_error {
+            ui.colored_label(egui::Color32::LIGHT_RED, e.as_str());
+        }
+
+        match &status {
+            ExportStatus::Idle => {}
+            ExportStatus::Running { profile, stage } => {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label(format!("Exporting '{profile}': {stage}"));
+                });
+            }
+            ExportStatus::Done(r) => {
+                let text = format!(
+                    "Exported '{}' to {} ({} assets, {} plugins, {:.1} s)",
+                    r.profile,
+                    r.output.display(),
+                    r.cooked,
+                    r.plugins.len(),
+                    r.duration_ms as f64 / 1000.0
+                );
+                ui.label(text);
+            }
+            ExportStatus::Failed { profile, error } => {
+                let text = format!("Export '{profile}' failed: {error}");
+                ui.colored_label(egui::Color32::LIGHT_RED, text);
+            }
+        }
+    }
+}
+
+/// Starts an export through the project service, which knows the importers to cook with.
+fn start(profile: &str) -> Result<(), String> {
+    let bytes =
+        newengine_core::call_service_v1(PROJECT_SERVICE_ID, method::EXPORT, profile.as_bytes())?;
+    let resp: ExportStartResp = serde_json::from_slice(&bytes)
+        .map_err(|e| format!("bad {} response: {e}", method::EXPORT))?;
+    if resp.started {
+        return Ok(());
+    }
+    Err(resp
+        .error
+        .unwrap_or_else(|| "export failed to start".to_owned()))
+}
@@ -2,18 +2,35 @@
 
 use newengine_core::render::{
     require_render_api, BeginFrameDesc, BindGroupDesc, BindGroupLayoutDesc, BindingKind,
-    BufferBinding, BufferDesc, BufferSlice, BufferUsage, DrawIndexedArgs, Extent2D, IndexFormat,
-    MemoryHint, PipelineDesc, PrimitiveTopology, RectI32, ShaderDesc, ShaderStage, TextureFormat,
-    VertexAttribute, VertexFormat, VertexLayout, Viewport,
+    BufferBinding, BufferDesc, BufferSlice, BufferUsage, CullMode, DebugDraw, DefaultMaterial,
+    DrawIndexedArgs, Extent2D, IndexFormat, MemoryHint, PipelineDesc, PostProcessSettings,
+    PrimitiveTopology, RectI32, ShaderDesc, ShaderStage, TextureFormat, VertexAttribute,
+    VertexDeformation, VertexFormat, VertexLayout, Viewport, DEFORMATION_BIND_GROUP,
+    LIGHTS_BIND_GROUP, MAX_SHADOW_CASCADES, OBJECT_ID_BIND_GROUP, OBJECT_ID_UBO_SIZE,
 };
-use newengine_core::{EngineError, EngineResult, Module, ModuleCtx};
+use newengine_core::{AnimationPlayer, EngineError, EngineResult, Module, ModuleCtx};
+use newengine_modules_environment::{EnvironmentApiRef, ENVIRONMENT_API_ID};
+use newengine_modules_lighting::{LightingApiRef, ShadowCamera, LIGHTING_API_ID};
+use newengine_modules_sprite2d::{Sprite2dApiRef, SPRITE2D_API_ID};
+use newengine_modules_terrain::{TerrainApiRef, TERRAIN_API_ID};
 use newengine_platform_winit::WinitWindowInitSize;
 use newengine_ui::draw::UiDrawList;
 
-use newengine_assets::{AssetState, Model3dFormat, Model3dReader};
+use newengine_assets::{AssetState, MeshAsset, Ne3dMesh};
+
+use crate::file_drop::ViewportModelRequest;
+use crate::scene::{EditorScene, SceneObject, ViewportCamera, VIEWPORT_MODEL_ID};
+use crate::selection::ViewportSelection;
 
 use shaderc::{CompileOptions, Compiler, OptimizationLevel, ShaderKind};
 
+const DEFAULT_MODEL_PATH: &str = "models/demo.obj";
+
+/// Default material object uniform: `view_proj`, `model`, `color`.
+const OBJECT_UBO_SIZE: u64 = 144;
+const SKINNED_MODEL_COLOR: [f32; 4] = [0.85, 0.85, 0.85, 1.0];
+const VIEWPORT_EYE: [f32; 3] = [2.6, 1.8, 2.6];
+
 #[derive(Clone, Copy)]
 struct DemoGpu {
     vb: newengine_core::render::BufferId,
@@ -33,18 +50,69 @@ struct ModelGpu {
 
     vs: newengine_core::render::ShaderId,
     fs: newengine_core::render::ShaderId,
+    /// `false` for the default material shaders, which the backend owns.
+    owns_shaders: bool,
     pipeline: newengine_core::render::PipelineId,
 
     index_count: u32,
 }
 
+/// Extra state of a skinned model: the skin vertex stream and the joint matrices group.
+struct SkinGpu {
+    vb: newengine_core::render::BufferId,
+    bones: newengine_core::render::BufferId,
+    bgl: newengine_core::render::BindGroupLayoutId,
+    bg: newengine_core::render::BindGroupId,
+    /// Set 2 is unused by the lit material but sits below the light list.
+    empty_bgl: newengine_core::render::BindGroupLayoutId,
+    empty_bg: newengine_core::render::BindGroupId,
+    lights_bgl: newengine_core::render::BindGroupLayoutId,
+
+    /// Depth-only pipeline drawing the model into the shadow cascades; one object uniform per
+    /// cascade, `shadow_stride` apart, picked by dynamic offset.
+    shadow_ubo: newengine_core::render::BufferId,
+    shadow_stride: u64,
+    shadow_bgl: newengine_core::render::BindGroupLayoutId,
+    shadow_bg: newengine_core::render::BindGroupId,
+    shadow_pipeline: newengine_core::render::PipelineId,
+
+    /// Centers and scales the model to the viewport; skinned positions stay in bind space.
+    fit: [f32; 16],
+    /// Base color of the first material, if the mesh has one.
+    color: [f32; 4],
+    player: AnimationPlayer,
+}
+
+/// Draws the model into the object-ID pass with its vertex path and object uniform, plus the
+/// id uniform at `id_group`.
+#[derive(Clone, Copy)]
+struct PickGpu {
+    ubo: newengine_core::render::BufferId,
+    bgl: newengine_core::render::BindGroupLayoutId,
+    bg: newengine_core::render::BindGroupId,
+    id_group: u32,
+    /// Unskinned models compile their own; skinned ones use the default material's.
+    fs: Option<newengine_core::render::ShaderId>,
+    pipeline: newengine_core::render::PipelineId,
+}
+
 pub struct EditorRenderController {
     clear_color: [f32; 4],
     last_w: u32,
     last_h: u32,
     demo: Option<DemoGpu>,
     model: Option<ModelGpu>,
+    skin: Option<SkinGpu>,
+    model_path: String,
     model_loaded_once: bool,
+    /// Last settings handed to the backend; `None` until the first frame.
+    post: Option<PostProcessSettings>,
+    selection: Option<ViewportSelection>,
+    /// Built on the first pick of the current model.
+    pick: Option<PickGpu>,
+    /// A pick is out; the object-ID pass runs until it comes back.
+    picking: bool,
+    scene: Option<EditorScene>,
 }
 
 impl EditorRenderController {
@@ -56,15 +124,60 @@ impl EditorRenderController {
             last_h: 0,
             demo: None,
             model: None,
+            skin: None,
+            model_path: DEFAULT_MODEL_PATH.to_string(),
             model_loaded_once: false,
+            post: None,
+            selection: None,
+            pick: None,
+            picking: false,
+            scene: None,
+        }
+    }
+
+    /// Answers viewport clicks of `selection` by picking the object-ID pass.
+    #[inline]
+    pub fn with_selection(mut self, selection: ViewportSelection) -> Self {
+        self.selection = Some(selection);
+        self
+    }
+
+    /// Registers the viewport model in `scene`, draws it at its transform there and publishes
+    /// the viewport camera.
+    #[inline]
+    pub fn with_scene(mut self, scene: EditorScene) -> Self {
+        self.scene = Some(scene);
+        self
+    }
+
+    /// World matrix of the viewport model's scene object, before the spin and fit; `None`
+    /// when it was deleted from the scene, so the model is not drawn.
+    fn placement(&self) -> Option<[f32; 16]> {
+        match &self.scene {
+            Some(s) => s.world_matrix(VIEWPORT_MODEL_ID).map(|m| m.to_cols_array()),
+            None => Some(newengine_core::Transform::IDENTITY.to_matrix()),
         }
     }
 
-    fn load_model_blob(
+    /// Puts the freshly built model into the scene, named after its file, unless the scene
+    /// asset already placed it.
+    fn register_model(&self, model_path: &str) {
+        if let Some(scene) = &self.scene {
+            scene.insert_absent(SceneObject::new(VIEWPORT_MODEL_ID, Self::model_name(model_path)));
+        }
+    }
+
+    #[inline]
+    fn model_name(model_path: &str) -> &str {
+        let file = model_path.rsplit('/').next().unwrap_or(model_path);
+        file.rsplit_once('.').map_or(file, |(stem, _)| stem)
+    }
+
+    fn load_model(
         ctx: &ModuleCtx<'_, impl Send + 'static>,
         logical_path: &str,
         timeout_ms: u64,
-    ) -> EngineResult<Option<std::sync::Arc<newengine_assets::AssetBlob>>> {
+    ) -> EngineResult<Option<std::sync::Arc<MeshAsset>>> {
         let Some(am) = ctx.resources().get::<newengine_core::assets::AssetManager>() else {
             return Ok(None);
         };
@@ -83,7 +196,11 @@ impl EditorRenderController {
         loop {
             am.pump();
             match store.state(id) {
-                AssetState::Ready => return Ok(store.get_blob(id)),
+                AssetState::Ready => {
+                    return store.get_typed::<MeshAsset>(id).map(Some).map_err(|e| {
+                        EngineError::other(format!("model: decode failed: {e}"))
+                    });
+                }
                 AssetState::Failed(e) => {
                     return Err(EngineError::other(format!(
                         "model: import failed path='{logical_path}' err='{e}'"
@@ -100,84 +217,6 @@ impl EditorRenderController {
         }
     }
 
-    fn decode_ne3d_mesh(bytes: &[u8]) -> EngineResult<(Vec<[f32; 3]>, Vec<[f32; 3]>, Vec<u32>)> {
-        fn need<'a>(bytes: &'a [u8], at: usize, len: usize, what: &str) -> EngineResult<&'a [u8]> {
-            let end = at.saturating_add(len);
-            if end > bytes.len() {
-                return Err(EngineError::other(format!("ne3d: truncated while reading {what}")));
-            }
-            Ok(&bytes[at..end])
-        }
-
-        if bytes.len() < 4 + 4 * 4 {
-            return Err(EngineError::other("ne3d: too short"));
-        }
-        if &bytes[0..4] != b"NE3D" {
-            return Err(EngineError::other("ne3d: bad magic"));
-        }
-
-        let mut at = 4usize;
-        let read_u32 = |b: &[u8]| u32::from_le_bytes([b[0], b[1], b[2], b[3]]);
-
-        let ver = read_u32(need(bytes, at, 4, "version")?);
-        at += 4;
-        if ver != 1 {
-            return Err(EngineError::other(format!("ne3d: unsupported version {ver}")));
-        }
-
-        let vtx_count = read_u32(need(bytes, at, 4, "vertex_count")?) as usize;
-        at += 4;
-        let idx_count = read_u32(need(bytes, at, 4, "index_count")?) as usize;
-        at += 4;
-        let flags = read_u32(need(bytes, at, 4, "flags")?);
-        at += 4;
-
-        let has_normals = (flags & 0x1) != 0;
-
-        let mut pos: Vec<[f32; 3]> = Vec::with_capacity(vtx_count);
-        let mut nrm: Vec<[f32; 3]> = Vec::with_capacity(vtx_count);
-
-        for _ in 0..vtx_count {
-            let chunk = need(bytes, at, 12, "positions")?;
-            at += 12;
-            let x = f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
-            let y = f32::from_le_bytes([chunk[4], chunk[5], chunk[6], chunk[7]]);
-            let z = f32::from_le_bytes([chunk[8], chunk[9], chunk[10], chunk[11]]);
-            pos.push([x, y, z]);
-        }
-
-        if has_normals {
-            for _ in 0..vtx_count {
-                let chunk = need(bytes, at, 12, "normals")?;
-                at += 12;
-                let x = f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
-                let y = f32::from_le_bytes([chunk[4], chunk[5], chunk[6], chunk[7]]);
-                let z = f32::from_le_bytes([chunk[8], chunk[9], chunk[10], chunk[11]]);
-                nrm.push([x, y, z]);
-            }
-        } else {
-            nrm.resize(vtx_count, [0.0, 1.0, 0.0]);
-        }
-
-        let has_uvs = (flags & 0x2) != 0;
-        if has_uvs {
-            let uv_bytes = vtx_count
-                .checked_mul(8)
-                .ok_or_else(|| EngineError::other("ne3d: uv overflow"))?;
-            let _ = need(bytes, at, uv_bytes, "uvs")?;
-            at += uv_bytes;
-        }
-
-        let mut idx: Vec<u32> = Vec::with_capacity(idx_count);
-        for _ in 0..idx_count {
-            let chunk = need(bytes, at, 4, "indices")?;
-            at += 4;
-            idx.push(u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]));
-        }
-
-        Ok((pos, nrm, idx))
-    }
-
     #[inline]
     fn mat4_mul(a: [f32; 16], b: [f32; 16]) -> [f32; 16] {
         let mut o = [0.0f32; 16];
@@ -255,6 +294,15 @@ impl EditorRenderController {
         ]
     }
 
+    #[inline]
+    fn mat4_translation(t: [f32; 3]) -> [f32; 16] {
+        [
+            1.0, 0.0, 0.0, 0.0,
+            0.0, 1.0, 0.0, 0.0,
+            0.0, 0.0, 1.0, 0.0,
+            t[0], t[1], t[2], 1.0,
+        ]
+    }
 
     #[inline]
     fn mat4_look_at(eye: [f32; 3], center: [f32; 3], up: [f32; 3]) -> [f32; 16] {
@@ -388,6 +436,375 @@ void main() {
         Ok(())
     }
 
+    fn index_bytes(idx: &[u32]) -> Vec<u8> {
+        let mut ibytes: Vec<u8> = Vec::with_capacity(idx.len() * 4);
+        for i in idx {
+            ibytes.extend_from_slice(&i.to_ne_bytes());
+        }
+        ibytes
+    }
+
+    /// Vertices of unskinned models: position, normal.
+    fn model_vertex_layout() -> VertexLayout {
+        VertexLayout::new(
+            (6 * std::mem::size_of::<f32>()) as u32,
+            vec![
+                VertexAttribute::new(0, 0, VertexFormat::Float32x3),
+                VertexAttribute::new(
+                    1,
+                    (3 * std::mem::size_of::<f32>()) as u32,
+                    VertexFormat::Float32x3,
+                ),
+            ],
+        )
+    }
+
+    /// Default material object uniform bytes.
+    fn object_uniform(view_proj: [f32; 16], model: [f32; 16], color: [f32; 4]) -> Vec<u8> {
+        let mut ubytes: Vec<u8> = Vec::with_capacity(OBJECT_UBO_SIZE as usize);
+        for f in view_proj.iter().chain(model.iter()).chain(color.iter()) {
+            ubytes.extend_from_slice(&f.to_ne_bytes());
+        }
+        ubytes
+    }
+
+    /// Skinned meshes go through the backend's default lit material with GPU skinning:
+    /// stream 0 `default_mesh`, stream 1 `default_skin`, set 1 the joint matrices, set 3 the
+    /// light list of `lighting.api`.
+    fn build_skinned_model(
+        &mut self,
+        r: &mut dyn newengine_core::render::RenderApi,
+        mesh: &Ne3dMesh,
+        fit: [f32; 16],
+    ) -> EngineResult<()> {
+        let deformation = VertexDeformation {
+            skinning: true,
+            morph_targets: false,
+        };
+        let (vs, fs) = r.default_material_shaders(DefaultMaterial::Lit, deformation)?;
+
+        let mut vbytes: Vec<u8> = Vec::with_capacity(mesh.positions.len() * 32);
+        for (i, p) in mesh.positions.iter().enumerate() {
+            let n = mesh.normals.get(i).copied().unwrap_or([0.0, 1.0, 0.0]);
+            let uv = mesh.uvs.get(i).copied().unwrap_or([0.0, 0.0]);
+            for f in p.iter().chain(n.iter()).chain(uv.iter()) {
+                vbytes.extend_from_slice(&f.to_ne_bytes());
+            }
+        }
+
+        let mut sbytes: Vec<u8> = Vec::with_capacity(mesh.positions.len() * 24);
+        for (j, w) in mesh.joints.iter().zip(mesh.weights.iter()) {
+            for v in j {
+                sbytes.extend_from_slice(&v.to_ne_bytes());
+            }
+            for f in w {
+                sbytes.extend_from_slice(&f.to_ne_bytes());
+            }
+        }
+
+        let lod0 = mesh.lod_range(0);
+        let idx = &mesh.indices[lod0.start as usize..lod0.end as usize];
+        let ibytes = Self::index_bytes(idx);
+
+        let vb = r.create_buffer(
+            BufferDesc::new(vbytes.len() as u64, BufferUsage::Vertex, MemoryHint::CpuToGpu)
+                .with_label("editor_model_vb"),
+        )?;
+        r.write_buffer(vb, 0, &vbytes)?;
+
+        let skin_vb = r.create_buffer(
+            BufferDesc::new(sbytes.len() as u64, BufferUsage::Vertex, MemoryHint::CpuToGpu)
+                .with_label("editor_model_skin_vb"),
+        )?;
+        r.write_buffer(skin_vb, 0, &sbytes)?;
+
+        let ib = r.create_buffer(
+            BufferDesc::new(ibytes.len() as u64, BufferUsage::Index, MemoryHint::CpuToGpu)
+                .with_label("editor_model_ib"),
+        )?;
+        r.write_buffer(ib, 0, &ibytes)?;
+
+        let ubo = r.create_buffer(
+            BufferDesc::new(OBJECT_UBO_SIZE, BufferUsage::Uniform, MemoryHint::CpuToGpu)
+                .with_label("editor_model_ubo"),
+        )?;
+        let bgl = r.create_bind_group_layout(
+            BindGroupLayoutDesc::new(vec![BindingKind::UniformBuffer]).with_label("editor_model_bgl"),
+        )?;
+        let bg = r.create_bind_group(
+            BindGroupDesc::new(bgl)
+                .with_label("editor_model_bg")
+                .with_uniform0(BufferBinding::new(ubo, 0, OBJECT_UBO_SIZE)),
+        )?;
+
+        let mut player = AnimationPlayer::from_mesh(mesh);
+        player.play_index(0);
+
+        let bones = player.create_buffer(r)?;
+        player.upload(r, bones)?;
+        let skin_bgl = r.create_bind_group_layout(
+            BindGroupLayoutDesc::new(vec![BindingKind::BoneMatrices])
+                .with_label("editor_model_skin_bgl"),
+        )?;
+        let skin_bg = r.create_bind_group(
+            BindGroupDesc::new(skin_bgl)
+                .with_label("editor_model_skin_bg")
+                .with_bone_matrices(BufferBinding::new(bones, 0, player.buffer_size())),
+        )?;
+        let empty_bgl = r.create_bind_group_layout(
+            BindGroupLayoutDesc::new(Vec::new()).with_label("editor_model_empty_bgl"),
+        )?;
+        let empty_bg = r.create_bind_group(
+            BindGroupDesc::new(empty_bgl).with_label("editor_model_empty_bg"),
+        )?;
+        let lights_bgl = r.create_bind_group_layout(BindGroupLayoutDesc::lights())?;
+
+        let pipeline = r.create_pipeline(
+            PipelineDesc::new(vs, fs, TextureFormat::Bgra8Unorm)
+                .with_depth(TextureFormat::Depth32Float)
+                .with_label("editor_model_skinned_pipeline")
+                .with_topology(PrimitiveTopology::TriangleList)
+                .with_vertex_layouts(vec![
+                    VertexLayout::default_mesh(),
+                    VertexLayout::default_skin(),
+                ])
+                .with_bind_group_layouts(vec![bgl, skin_bgl, empty_bgl, lights_bgl])
+                .with_deformation(deformation),
+        )?;
+
+        let align = r.uniform_offset_alignment().max(1);
+        let shadow_stride = OBJECT_UBO_SIZE.div_ceil(align) * align;
+        let shadow_ubo = r.create_buffer(
+            BufferDesc::new(
+                shadow_stride * MAX_SHADOW_CASCADES as u64,
+                BufferUsage::Uniform,
+                MemoryHint::CpuToGpu,
+            )
+            .with_label("editor_model_shadow_ubo"),
+        )?;
+        let shadow_bgl = r.create_bind_group_layout(
+            BindGroupLayoutDesc::new(vec![BindingKind::UniformBufferDynamic])
+                .with_label("editor_model_shadow_bgl"),
+        )?;
+        let shadow_bg = r.create_bind_group(
+            BindGroupDesc::new(shadow_bgl)
+                .with_label("editor_model_shadow_bg")
+                .with_uniform0(BufferBinding::new(shadow_ubo, 0, OBJECT_UBO_SIZE)),
+        )?;
+        let (depth_vs, depth_fs) = r.default_material_shaders(DefaultMaterial::Depth, deformation)?;
+        // Both faces cast, so open meshes still shadow what is behind them.
+        let shadow_pipeline = r.create_pipeline(
+            PipelineDesc::depth_only(depth_vs, depth_fs, TextureFormat::Depth32Float)
+                .with_label("editor_model_shadow_pipeline")
+                .with_topology(PrimitiveTopology::TriangleList)
+                .with_cull_mode(CullMode::None)
+                .with_vertex_layouts(vec![
+                    VertexLayout::default_mesh(),
+                    VertexLayout::default_skin(),
+                ])
+                .with_bind_group_layouts(vec![shadow_bgl, skin_bgl])
+                .with_deformation(deformation),
+        )?;
+
+        self.model = Some(ModelGpu {
+            vb,
+            ib,
+            ubo,
+            bgl,
+            bg,
+            vs,
+            fs,
+            owns_shaders: false,
+            pipeline,
+            index_count: idx.len() as u32,
+        });
+        self.skin = Some(SkinGpu {
+            vb: skin_vb,
+            bones,
+            bgl: skin_bgl,
+            bg: skin_bg,
+            empty_bgl,
+            empty_bg,
+            lights_bgl,
+            shadow_ubo,
+            shadow_stride,
+            shadow_bgl,
+            shadow_bg,
+            shadow_pipeline,
+            fit,
+            color: mesh.materials.first().map_or(SKINNED_MODEL_COLOR, |m| m.base_color),
+            player,
+        });
+
+        Ok(())
+    }
+
+    /// Object-ID pass state of the current model. Unskinned models reuse their vertex shader
+    /// and mvp uniform with a fragment shader compiled here; skinned ones go through the
+    /// default object-ID material.
+    fn build_pick(&mut self, r: &mut dyn newengine_core::render::RenderApi) -> EngineResult<()> {
+        let Some(model) = self.model else {
+            return Ok(());
+        };
+        if self.pick.is_some() {
+            return Ok(());
+        }
+
+        let ubo = r.create_buffer(
+            BufferDesc::new(OBJECT_ID_UBO_SIZE, BufferUsage::Uniform, MemoryHint::CpuToGpu)
+                .with_label("editor_model_id_ubo"),
+        )?;
+        let mut ubytes = vec![0u8; OBJECT_ID_UBO_SIZE as usize];
+        ubytes[..4].copy_from_slice(&VIEWPORT_MODEL_ID.to_ne_bytes());
+        r.write_buffer(ubo, 0, &ubytes)?;
+        let bgl = r.create_bind_group_layout(
+            BindGroupLayoutDesc::new(vec![BindingKind::UniformBuffer])
+                .with_label("editor_model_id_bgl"),
+        )?;
+        let bg = r.create_bind_group(
+            BindGroupDesc::new(bgl)
+                .with_label("editor_model_id_bg")
+                .with_uniform0(BufferBinding::new(ubo, 0, OBJECT_ID_UBO_SIZE)),
+        )?;
+
+        let (desc, id_group, fs) = if let Some(skin) = &self.skin {
+            let deformation = VertexDeformation {
+                skinning: true,
+                morph_targets: false,
+            };
+            let (vs, fs) = r.default_material_shaders(DefaultMaterial::ObjectId, deformation)?;
+            let desc = PipelineDesc::new(vs, fs, TextureFormat::R32Uint)
+                .with_vertex_layouts(vec![
+                    VertexLayout::default_mesh(),
+                    VertexLayout::default_skin(),
+                ])
+                .with_bind_group_layouts(vec![model.bgl, skin.bgl, bgl])
+                .with_deformation(deformation);
+            (desc, OBJECT_ID_BIND_GROUP, None)
+        } else {
+            const FS_SRC: &str = r#"#version 450
+layout(set = 1, binding = 0) uniform ObjectId {
+    uvec4 id;
+} u_id;
+
+layout(location = 0) out uint o_id;
+
+void main() {
+    o_id = u_id.id.x;
+}
+"#;
+            let compiler = Compiler::new().ok_or_else(|| EngineError::other("shaderc: Compiler"))?;
+            let fs_spv =
+                Self::compile_glsl(&compiler, ShaderKind::Fragment, "editor_model_id.frag", FS_SRC)?;
+            let fs = r.create_shader(
+                ShaderDesc::new(ShaderStage::Fragment, "main", fs_spv)
+                    .with_label("editor_model_id_fs"),
+            )?;
+            let desc = PipelineDesc::new(model.vs, fs, TextureFormat::R32Uint)
+                .with_vertex_layouts(vec![Self::model_vertex_layout()])
+                .with_bind_group_layouts(vec![model.bgl, bgl]);
+            (desc, 1, Some(fs))
+        };
+
+        let pipeline = r.create_pipeline(
+            desc.with_depth(TextureFormat::Depth32Float)
+                .with_label("editor_model_id_pipeline")
+                .with_topology(PrimitiveTopology::TriangleList),
+        )?;
+
+        self.pick = Some(PickGpu {
+            ubo,
+            bgl,
+            bg,
+            id_group,
+            fs,
+            pipeline,
+        });
+        Ok(())
+    }
+
+    /// Draws the model into the frame's object-ID pass. Its uniforms are already written.
+    fn draw_pick(
+        &self,
+        r: &mut dyn newengine_core::render::RenderApi,
+        model: ModelGpu,
+        pick: PickGpu,
+    ) -> EngineResult<()> {
+        r.begin_id_pass()?;
+        let drawn = self.record_pick(r, model, pick);
+        r.end_id_pass()?;
+        drawn
+    }
+
+    fn record_pick(
+        &self,
+        r: &mut dyn newengine_core::render::RenderApi,
+        model: ModelGpu,
+        pick: PickGpu,
+    ) -> EngineResult<()> {
+        r.set_pipeline(pick.pipeline)?;
+        r.set_bind_group(0, model.bg)?;
+        r.set_vertex_buffer(0, BufferSlice::new(model.vb, 0))?;
+        if let Some(skin) = &self.skin {
+            r.set_bind_group(DEFORMATION_BIND_GROUP, skin.bg)?;
+            r.set_vertex_buffer(1, BufferSlice::new(skin.vb, 0))?;
+        }
+        r.set_bind_group(pick.id_group, pick.bg)?;
+        r.set_index_buffer(BufferSlice::new(model.ib, 0), IndexFormat::U32)?;
+        r.draw_indexed(DrawIndexedArgs::new(model.index_count))
+    }
+
+    /// Drops the current model's GPU objects so `build_model` loads `logical_path` next frame.
+    fn open_model(&mut self, r: &mut dyn newengine_core::render::RenderApi, logical_path: String) {
+        if let Some(p) = self.pick.take() {
+            r.destroy_pipeline(p.pipeline);
+            if let Some(fs) = p.fs {
+                r.destroy_shader(fs);
+            }
+            r.destroy_bind_group(p.bg);
+            r.destroy_bind_group_layout(p.bgl);
+            r.destroy_buffer(p.ubo);
+        }
+        if let Some(m) = self.model.take() {
+            r.destroy_pipeline(m.pipeline);
+            r.destroy_bind_group(m.bg);
+            r.destroy_bind_group_layout(m.bgl);
+            if m.owns_shaders {
+                r.destroy_shader(m.vs);
+                r.destroy_shader(m.fs);
+            }
+            r.destroy_buffer(m.ubo);
+            r.destroy_buffer(m.ib);
+            r.destroy_buffer(m.vb);
+        }
+        if let Some(s) = self.skin.take() {
+            r.destroy_pipeline(s.shadow_pipeline);
+            r.destroy_bind_group(s.shadow_bg);
+            r.destroy_bind_group_layout(s.shadow_bgl);
+            r.destroy_buffer(s.shadow_ubo);
+            r.destroy_bind_group(s.empty_bg);
+            r.destroy_bind_group_layout(s.empty_bgl);
+            r.destroy_bind_group_layout(s.lights_bgl);
+            r.destroy_bind_group(s.bg);
+            r.destroy_bind_group_layout(s.bgl);
+            r.destroy_buffer(s.bones);
+            r.destroy_buffer(s.vb);
+        }
+
+        // The selection was the old model.
+        if let Some(selection) = &self.selection {
+            selection.set_selected(None);
+        }
+        // The scene object stays where it is and takes the new model's name.
+        if let Some(scene) = &self.scene {
+            scene.rename(VIEWPORT_MODEL_ID, Self::model_name(&logical_path));
+        }
+
+        log::info!("model: open path='{logical_path}'");
+        self.model_path = logical_path;
+        self.model_loaded_once = false;
+    }
+
     fn build_model(
         &mut self,
         ctx: &ModuleCtx<'_, impl Send + 'static>,
@@ -400,25 +817,27 @@ void main() {
 
         self.model_loaded_once = true;
 
-        const MODEL_PATH: &str = "models/demo.obj";
+        let model_path = self.model_path.clone();
 
-        let Some(blob) = Self::load_model_blob(ctx, MODEL_PATH, 750)? else {
-            log::warn!("model: missing '{MODEL_PATH}'. Add an .obj under assets/models/demo.obj to see 3D.");
+        let Some(model) = Self::load_model(ctx, &model_path, 750)? else {
+            log::warn!("model: missing '{model_path}'. Add an .obj under assets/models/demo.obj to see 3D.");
             return Ok(());
         };
 
-        let model = Model3dReader::from_blob_parts(blob.meta_json.as_ref(), &blob.payload)
-            .map_err(|e| EngineError::other(format!("model: decode failed: {e}")))?;
-
-
-        let (pos, nrm, idx) = Self::decode_ne3d_mesh(&model.payload)?;
+        let mesh = &model.mesh;
+        // The viewport has no LOD selection yet; it always draws LOD 0.
+        let lod0 = mesh.lod_range(0);
+        let (pos, idx) = (
+            &mesh.positions,
+            &mesh.indices[lod0.start as usize..lod0.end as usize],
+        );
         if pos.is_empty() || idx.is_empty() {
             return Err(EngineError::other("model: empty geometry"));
         }
 
         let mut bb_min = [f32::INFINITY; 3];
         let mut bb_max = [f32::NEG_INFINITY; 3];
-        for p in &pos {
+        for p in pos {
             bb_min[0] = bb_min[0].min(p[0]);
             bb_min[1] = bb_min[1].min(p[1]);
             bb_min[2] = bb_min[2].min(p[2]);
@@ -440,10 +859,34 @@ void main() {
         let radius = (0.5 * ext[0].max(ext[1]).max(ext[2])).max(0.001);
         let inv_radius = 1.0 / radius;
 
+        if mesh.is_skinned() {
+            let fit = Self::mat4_mul(
+                Self::mat4_scale_uniform(inv_radius),
+                Self::mat4_translation([-center[0], -center[1], -center[2]]),
+            );
+            self.build_skinned_model(r, &mesh, fit)?;
+            self.register_model(&model_path);
+            log::info!(
+                "model: loaded '{model_path}' vertices={} indices={} joints={} clips={} \
+                 radius={:.3}",
+                pos.len(),
+                idx.len(),
+                mesh.skeleton.len(),
+                mesh.clips.len(),
+                radius
+            );
+            return Ok(());
+        }
+
+        // Meshes without normals light as if facing up.
+        let up = [[0.0, 1.0, 0.0]];
+        let normals: &[[f32; 3]] = if mesh.normals.is_empty() { &up } else { &mesh.normals };
+        let nrm = normals.iter().cycle();
+
         let stride = 6 * std::mem::size_of::<f32>();
         let mut vbytes: Vec<u8> = Vec::with_capacity(pos.len() * stride);
 
-        for (p, n) in pos.iter().zip(nrm.iter()) {
+        for (p, n) in pos.iter().zip(nrm) {
             let px = (p[0] - center[0]) * inv_radius;
             let py = (p[1] - center[1]) * inv_radius;
             let pz = (p[2] - center[2]) * inv_radius;
@@ -456,10 +899,7 @@ void main() {
             vbytes.extend_from_slice(&n[2].to_ne_bytes());
         }
 
-        let mut ibytes: Vec<u8> = Vec::with_capacity(idx.len() * 4);
-        for i in &idx {
-            ibytes.extend_from_slice(&i.to_ne_bytes());
-        }
+        let ibytes = Self::index_bytes(idx);
 
         let vb = r.create_buffer(
             BufferDesc::new(vbytes.len() as u64, BufferUsage::Vertex, MemoryHint::CpuToGpu)
@@ -526,17 +966,7 @@ void main() {
             ShaderDesc::new(ShaderStage::Fragment, "main", fs_spv).with_label("editor_model_fs"),
         )?;
 
-        let layout = VertexLayout::new(
-            stride as u32,
-            vec![
-                VertexAttribute::new(0, 0, VertexFormat::Float32x3),
-                VertexAttribute::new(
-                    1,
-                    (3 * std::mem::size_of::<f32>()) as u32,
-                    VertexFormat::Float32x3,
-                ),
-            ],
-        );
+        let layout = Self::model_vertex_layout();
 
         let pipeline = r.create_pipeline(
             PipelineDesc::new(vs, fs, TextureFormat::Bgra8Unorm)
@@ -571,12 +1001,14 @@ void main() {
             bg,
             vs,
             fs,
+            owns_shaders: true,
             pipeline,
             index_count: idx.len() as u32,
         });
+        self.register_model(&model_path);
 
         log::info!(
-            "model: loaded '{MODEL_PATH}' vertices={} indices={} radius={:.3}",
+            "model: loaded '{model_path}' vertices={} indices={} radius={:.3}",
             pos.len(),
             idx.len(),
             radius
@@ -593,6 +1025,7 @@ impl<E: Send + 'static> Module<E> for EditorRenderController {
 
     fn render(&mut self, ctx: &mut ModuleCtx<'_, E>) -> EngineResult<()> {
         let ui: Option<UiDrawList> = ctx.resources_mut().remove::<UiDrawList>();
+        let open: Option<ViewportModelRequest> = ctx.resources_mut().remove::<ViewportModelRequest>();
 
         let (w, h) = ctx
             .resources()
@@ -611,15 +1044,35 @@ impl<E: Send + 'static> Module<E> for EditorRenderController {
             r.set_ui_draw_list(ui);
         }
 
+        if let Some(req) = open {
+            self.open_model(&mut **r, req.logical_path);
+        }
+
         if w != self.last_w || h != self.last_h {
             self.last_w = w;
             self.last_h = h;
             r.resize(w, h)?;
         }
 
+        // `post.*` cvars change from the console or the Post FX panel at any time.
+        let post = PostProcessSettings::from_cvars();
+        if self.post != Some(post) {
+            self.post = Some(post);
+            if let Err(e) = r.set_post_process(post) {
+                log::warn!("render: post-process settings not applied: {e}");
+            }
+        }
+
         self.build_demo(&mut **r)?;
         if w > 0 && h > 0 {
-            self.build_model(ctx, &mut **r, Extent2D::new(w, h))?;
+            let res = self.build_model(ctx, &mut **r, Extent2D::new(w, h));
+            // A bad dropped file must not take the editor down; the bundled demo model still must load.
+            match res {
+                Err(e) if self.model_path != DEFAULT_MODEL_PATH => {
+                    log::warn!("model: open failed path='{}' err='{e}'", self.model_path);
+                }
+                other => other?,
+            }
         }
 
         r.begin_frame(BeginFrameDesc::new(self.clear_color))?;
@@ -629,32 +1082,192 @@ impl<E: Send + 'static> Module<E> for EditorRenderController {
             r.set_viewport(Viewport::full(extent))?;
             r.set_scissor(RectI32::new(0, 0, w as i32, h as i32))?;
 
-            if let Some(model) = self.model {
+            // Shadow casters go first: the depth pass unsets every binding, and `prepare` uploads
+            // the cascades fitted here along with the light list.
+            let lighting = ctx.api::<LightingApiRef>(LIGHTING_API_ID);
+            let placement = self.placement();
+            if let (Some(lighting), Some(model), Some(skin), Some(placement)) =
+                (lighting, self.model, &self.skin, placement)
+            {
+                let aspect = w as f32 / (h.max(1) as f32);
+                let proj = Self::mat4_perspective(60.0f32.to_radians(), aspect, 0.01, 1000.0);
+                let view = Self::mat4_look_at([2.6, 1.8, 2.6], [0.0, 0.0, 0.0], [0.0, 1.0, 0.0]);
+                let camera = ShadowCamera {
+                    view_proj: Self::mat4_mul(proj, view),
+                    near: 0.01,
+                    far: 1000.0,
+                };
+                let a = (ctx.frame.unwrap().frame_index as f32) * 0.01;
+                let spun = Self::mat4_mul(Self::mat4_rotation_y(a), skin.fit);
+                let model_m = Self::mat4_mul(placement, spun);
+
+                let drawn = lighting.render_shadows(&mut **r, &camera, |r, cascade| {
+                    let offset = cascade.index as u64 * skin.shadow_stride;
+                    let ubytes = Self::object_uniform(cascade.view_proj, model_m, skin.color);
+                    r.write_buffer(skin.shadow_ubo, offset, &ubytes)?;
+                    r.set_pipeline(skin.shadow_pipeline)?;
+                    r.set_bind_group_with_offsets(0, skin.shadow_bg, &[offset as u32])?;
+                    r.set_bind_group(DEFORMATION_BIND_GROUP, skin.bg)?;
+                    r.set_vertex_buffer(0, BufferSlice::new(model.vb, 0))?;
+                    r.set_vertex_buffer(1, BufferSlice::new(skin.vb, 0))?;
+                    r.set_index_buffer(BufferSlice::new(model.ib, 0), IndexFormat::U32)?;
+                    r.draw_indexed(DrawIndexedArgs::new(model.index_count))
+                });
+                if let Err(e) = drawn {
+                    log::warn!("lighting: shadow pass failed: {e}");
+                }
+            }
+
+            // Light list shared by every default-material draw of the frame.
+            let lights = match lighting {
+                Some(lighting) => match lighting.prepare(&mut **r, [2.6, 1.8, 2.6]) {
+                    Ok(bg) => Some(bg),
+                    Err(e) => {
+                        log::warn!("lighting: prepare failed: {e}");
+                        None
+                    }
+                },
+                None => None,
+            };
+
+            // The sky goes first: the main pass has no depth, so later draws cover it.
+            if let Some(environment) = ctx.api::<EnvironmentApiRef>(ENVIRONMENT_API_ID) {
+                let aspect = w as f32 / (h.max(1) as f32);
+                let proj = Self::mat4_perspective(60.0f32.to_radians(), aspect, 0.01, 1000.0);
+                let eye = [2.6, 1.8, 2.6];
+                let view = Self::mat4_look_at(eye, [0.0, 0.0, 0.0], [0.0, 1.0, 0.0]);
+                let view_proj = Self::mat4_mul(proj, view);
+                if let Err(e) = environment.render(&mut **r, extent, view_proj, eye) {
+                    log::warn!("environment: skybox render failed: {e}");
+                }
+            }
+
+            if let (Some(model), Some(placement)) = (self.model, placement) {
                 let aspect = w as f32 / (h.max(1) as f32);
                 let proj = Self::mat4_perspective(60.0f32.to_radians(), aspect, 0.01, 1000.0);
 
                 let a = (ctx.frame.unwrap().frame_index as f32) * 0.01;
-                let rot = Self::mat4_rotation_y(a);
+                let rot = Self::mat4_mul(placement, Self::mat4_rotation_y(a));
                 let view = Self::mat4_look_at([2.6, 1.8, 2.6], [0.0, 0.0, 0.0], [0.0, 1.0, 0.0]);
 
-                let mvp = Self::mat4_mul(Self::mat4_mul(proj, view), rot);
-
-                let mut ubytes: Vec<u8> = Vec::with_capacity(64);
-                for f in mvp {
-                    ubytes.extend_from_slice(&f.to_ne_bytes());
+                if let Some(skin) = self.skin.as_mut() {
+                    skin.player.advance(ctx.frame.map_or(0.0, |f| f.dt));
+                    skin.player.upload(&mut **r, skin.bones)?;
+
+                    let model_m = Self::mat4_mul(rot, skin.fit);
+                    let ubytes = Self::object_uniform(
+                        Self::mat4_mul(proj, view),
+                        model_m,
+                        skin.color,
+                    );
+                    r.write_buffer(model.ubo, 0, &ubytes)?;
+                } else {
+                    let mvp = Self::mat4_mul(Self::mat4_mul(proj, view), rot);
+
+                    let mut ubytes: Vec<u8> = Vec::with_capacity(64);
+                    for f in mvp {
+                        ubytes.extend_from_slice(&f.to_ne_bytes());
+                    }
+                    r.write_buffer(model.ubo, 0, &ubytes)?;
                 }
-                r.write_buffer(model.ubo, 0, &ubytes)?;
 
                 r.set_pipeline(model.pipeline)?;
                 r.set_bind_group(0, model.bg)?;
                 r.set_vertex_buffer(0, BufferSlice::new(model.vb, 0))?;
-                r.set_index_buffer(BufferSlice::new(model.ib, 0), IndexFormat::U32)?;
-                r.draw_indexed(DrawIndexedArgs::new(model.index_count))?;
-            } else if let Some(demo) = self.demo {
+                let mut drawable = true;
+                if let Some(skin) = &self.skin {
+                    r.set_bind_group(DEFORMATION_BIND_GROUP, skin.bg)?;
+                    r.set_vertex_buffer(1, BufferSlice::new(skin.vb, 0))?;
+                    r.set_bind_group(2, skin.empty_bg)?;
+                    match lights {
+                        Some(lights) => r.set_bind_group(LIGHTS_BIND_GROUP, lights)?,
+                        None => drawable = false,
+                    }
+                }
+                if drawable {
+                    r.set_index_buffer(BufferSlice::new(model.ib, 0), IndexFormat::U32)?;
+                    r.draw_indexed(DrawIndexedArgs::new(model.index_count))?;
+                }
+            } else if let Some(demo) = self.demo.filter(|_| self.model.is_none()) {
                 r.set_pipeline(demo.pipeline)?;
                 r.set_vertex_buffer(0, BufferSlice::new(demo.vb, 0))?;
                 r.draw(newengine_core::render::DrawArgs::new(3))?;
             }
+
+            // Viewport clicks: the object-ID pass runs until the pick it serves comes back.
+            if let Some(selection) = self.selection.clone() {
+                if let Some(picked) = r.take_picked() {
+                    self.picking = false;
+                    selection.set_selected(picked.object());
+                    log::info!("selection: pick x={} y={} id={}", picked.x, picked.y, picked.id);
+                }
+                if let Some((x, y)) = selection.take_request() {
+                    match r.pick(x, y) {
+                        Ok(()) => self.picking = true,
+                        Err(e) => log::warn!("selection: pick failed: {e}"),
+                    }
+                }
+                if self.picking {
+                    if let Err(e) = self.build_pick(&mut **r) {
+                        log::warn!("selection: object-ID pass unavailable: {e}");
+                    }
+                    match (self.model, self.pick, placement) {
+                        (Some(model), Some(pick), Some(_)) => {
+                            if let Err(e) = self.draw_pick(&mut **r, model, pick) {
+                                log::warn!("selection: object-ID pass failed: {e}");
+                            }
+                        }
+                        // Nothing to pick from.
+                        _ => {
+                            self.picking = false;
+                            selection.set_selected(None);
+                        }
+                    }
+                }
+            }
+
+            // Terrains added through terrain.api, seen from the viewport camera.
+            let terrain = ctx.api::<TerrainApiRef>(TERRAIN_API_ID);
+            if let (Some(terrain), Some(lights)) = (terrain, lights) {
+                let aspect = w as f32 / (h.max(1) as f32);
+                let proj = Self::mat4_perspective(60.0f32.to_radians(), aspect, 0.01, 1000.0);
+                let eye = [2.6, 1.8, 2.6];
+                let view = Self::mat4_look_at(eye, [0.0, 0.0, 0.0], [0.0, 1.0, 0.0]);
+                let view_proj = Self::mat4_mul(proj, view);
+                if let Err(e) = terrain.render(&mut **r, extent, view_proj, eye, lights) {
+                    log::warn!("terrain: render failed: {e}");
+                }
+            }
+
+            // Draws plugins queued through the engine.render service this frame.
+            newengine_core::render_service::replay_plugin_draws(&mut **r);
+
+            // Sprites queued through sprite2d.api go over the scene.
+            if let Some(sprites) = ctx.api::<Sprite2dApiRef>(SPRITE2D_API_ID) {
+                if let Err(e) = sprites.render(&mut **r, extent) {
+                    log::warn!("sprite2d: render failed: {e}");
+                }
+            }
+        }
+
+        // Debug geometry uses the viewport camera, without the model's spin; the backend
+        // flushes the queue in end_frame. The gizmo casts its rays through the same camera.
+        if w > 0 && h > 0 {
+            let aspect = w as f32 / (h.max(1) as f32);
+            let proj = Self::mat4_perspective(60.0f32.to_radians(), aspect, 0.01, 1000.0);
+            let view = Self::mat4_look_at(VIEWPORT_EYE, [0.0, 0.0, 0.0], [0.0, 1.0, 0.0]);
+            let view_proj = Self::mat4_mul(proj, view);
+            if let Some(dd) = ctx.resources().get::<DebugDraw>() {
+                dd.set_view_proj(view_proj);
+            }
+            if let Some(scene) = &self.scene {
+                scene.set_camera(ViewportCamera {
+                    view_proj,
+                    eye: VIEWPORT_EYE,
+                    width: w,
+                    height: h,
+                });
+            }
         }
 
         r.end_frame()?;
@@ -0,0 +1,153 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use newengine_core::{EngineResult, Module, ModuleCtx, ResourceInfo};
+use newengine_platform_winit::egui;
+use std::sync::{Arc, Mutex};
+
+#[derive(Debug, Default)]
+struct InspectorState {
+    open: bool,
+    rows: Vec<ResourceInfo>,
+    frame: u64,
+}
+
+/// Snapshot of engine `Resources` shared between the engine module and the editor panel.
+#[derive(Debug, Clone, Default)]
+pub struct ResourcesView(Arc<Mutex<InspectorState>>);
+
+/// Copies a `Resources` snapshot into [`ResourcesView`] every frame while the panel is open.
+///
+/// Registered last so entries inserted by other modules during `update` are included.
+pub struct ResourcesSnapshotModule {
+    view: ResourcesView,
+}
+
+impl ResourcesSnapshotModule {
+    #[inline]
+    pub fn new(view: ResourcesView) -> Self {
+        Self { view }
+    }
+}
+
+impl<E: Send + 'static> Module<E> for ResourcesSnapshotModule {
+    fn id(&self) -> &'static str {
+        "app.resources_snapshot"
+    }
+
+    fn update(&mut self, ctx: &mut ModuleCtx<'_, E>) -> EngineResult<()> {
+        let Ok(mut g) = self.view.0.lock() else {
+            return Ok(());
+        };
+        if !g.open {
+            return Ok(());
+        }
+
+        g.rows = ctx.resources().snapshot();
+        g.frame = ctx.frame().map(|f| f.frame_index).unwrap_or(g.frame);
+        Ok(())
+    }
+}
+
+/// Editor window listing typed resources and named APIs, for "module X can't find resource Y"
+/// diagnostics.
+#[derive(Debug, Default)]
+pub struct ResourcesInspector {
+    view: ResourcesView,
+    filter: String,
+}
+
+impl ResourcesInspector {
+    #[inline]
+    pub fn new(view: ResourcesView) -> Self {
+        Self {
+            view,
+            filter: String::new(),
+        }
+    }
+
+    pub fn toolbar_ui(&self, ui: &mut egui::Ui) {
+        let Ok(mut g) = self.view.0.lock() else {
+            return;
+        };
+        ui.toggle_value(&mut g.open, "Resources");
+    }
+
+    pub fn ui(&mut self, ctx: &egui::Context) {
+        let Ok(mut g) = self.view.0.lock() else {
+            return;
+        };
+        if !g.open {
+            return;
+        }
+
+        let state = &mut *g;
+        let filter = self.filter.to_ascii_lowercase();
+
+        egui::Window::new("Resources")
+            .id(egui::Id::new("ne_editor_resources"))
+            .open(&mut state.open)
+            .default_size([520.0, 360.0])
+            .show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Filter:");
+                    ui.text_edit_singleline(&mut self.filter);
+                    ui.separator();
+                    ui.label(format!(
+                        "{} entries, frame {}",
+                        state.rows.len(),
+                        state.frame
+                    ));
+                });
+                ui.separator();
+
+                egui::ScrollArea::vertical()
+                    .auto_shrink([false, false])
+                    .show(ui, |ui| {
+                        for (i, r) in state
+                            .rows
+                            .iter()
+                            .filter(|r| row_matches(r, &filter))
+                            .enumerate()
+                        {
+                            row_ui(ui, i, r);
+                        }
+                    });
+            });
+    }
+}
+
+fn row_ui(ui: &mut egui::Ui, i: usize, r: &ResourceInfo) {
+    let kind = match r.api_id {
+        Some(id) => format!("api '{id}'"),
+        None => "typed".to_string(),
+    };
+    let owner = r.owner.unwrap_or("host");
+    let title = format!("{}  [{kind}, by {owner}]", r.type_name);
+
+    // Values are shown for types registered in the reflection registry.
+    match r.value.as_ref() {
+        Some(value) => {
+            let text = serde_json::to_string_pretty(value).unwrap_or_default();
+            egui::CollapsingHeader::new(title)
+                .id_salt(("ne_res_row", i))
+                .show(ui, |ui| {
+                    ui.monospace(text);
+                });
+        }
+        None => {
+            ui.label(title);
+        }
+    }
+}
+
+#[inline]
+fn row_matches(r: &ResourceInfo, filter: &str) -> bool {
+    if filter.is_empty() {
+        return true;
+    }
+    r.type_name.to_ascii_lowercase().contains(filter)
+        || r.api_id
+            .is_some_and(|id| id.to_ascii_lowercase().contains(filter))
+        || r.owner
+            .is_some_and(|o| o.to_ascii_lowercase().contains(filter))
+}
@@ -0,0 +1,708 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use glam::{Mat4, Quat, Vec3};
+use newengine_core::reflect::{component_info, ComponentStore, TRANSFORM_COMPONENT};
+use newengine_core::{Command, Transform};
+use serde::{Deserialize, Serialize};
+use std::any::Any;
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+/// Id of the viewport model in the object-ID pass and the scene; other objects get ids above.
+pub const VIEWPORT_MODEL_ID: u32 = 1;
+
+/// Scene asset format written by [`EditorScene::save`].
+const SCENE_VERSION: u32 = 1;
+
+/// An object the editor can select and move.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SceneObject {
+    /// Id written by the object-ID pass.
+    pub id: u32,
+    pub name: String,
+    /// `None` for objects at the root.
+    pub parent: Option<u32>,
+    /// Relative to the parent.
+    pub transform: Transform,
+}
+
+impl SceneObject {
+    /// Components every object has, registered with the core reflection registry.
+    pub const COMPONENTS: &'static [&'static str] = &[TRANSFORM_COMPONENT];
+
+    #[inline]
+    pub fn new(id: u32, name: impl Into<String>) -> Self {
+        Self {
+            id,
+            name: name.into(),
+            parent: None,
+            transform: Transform::IDENTITY,
+        }
+    }
+
+    /// Copy of component `name`, as its registered type.
+    pub fn component(&self, name: &str) -> Option<Box<dyn Any + Send>> {
+        match name {
+            TRANSFORM_COMPONENT => Some(Box::new(self.transform)),
+            _ => None,
+        }
+    }
+
+    pub fn set_component(&mut self, name: &str, value: Box<dyn Any + Send>) -> Result<(), String> {
+        match name {
+            TRANSFORM_COMPONENT => {
+                self.transform = *value
+                    .downcast::<Transform>()
+                    .map_err(|_| format!("{name}: wrong component type"))?;
+                Ok(())
+            }
+            _ => Err(format!("scene objects have no {name}")),
+        }
+    }
+}
+
+/// Camera the viewport was last drawn with.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ViewportCamera {
+    /// Column-major, Vulkan clip space.
+    pub view_proj: [f32; 16],
+    pub eye: [f32; 3],
+    /// Viewport size in pixels.
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Debug, Default)]
+struct SceneState {
+    /// Parents may come after their children; the hierarchy keeps this order among siblings.
+    objects: Vec<SceneObject>,
+    camera: Option<ViewportCamera>,
+    /// Bumped by every change to `objects`.
+    revision: u64,
+}
+
+impl SceneState {
+    #[inline]
+    fn get(&self, id: u32) -> Option<&SceneObject> {
+        self.objects.iter().find(|o| o.id == id)
+    }
+
+    #[inline]
+    fn get_mut(&mut self, id: u32) -> Option<&mut SceneObject> {
+        self.objects.iter_mut().find(|o| o.id == id)
+    }
+
+    /// Whether `ancestor` is `id` or one of its parents.
+    fn is_ancestor(&self, ancestor: u32, id: u32) -> bool {
+        let mut cur = Some(id);
+        for _ in 0..=self.objects.len() {
+            match cur {
+                Some(c) if c == ancestor => return true,
+                Some(c) => cur = self.get(c).and_then(|o| o.parent),
+                None => return false,
+            }
+        }
+        false
+    }
+
+    fn world_matrix(&self, id: u32) -> Option<Mat4> {
+        let mut o = self.get(id)?;
+        let mut m = transform_matrix(&o.transform);
+        // Bounded, so a cycle in a hand-edited file cannot hang the viewport.
+        for _ in 0..self.objects.len() {
+            let Some(parent) = o.parent.and_then(|p| self.get(p)) else {
+                break;
+            };
+            m = transform_matrix(&parent.transform) * m;
+            o = parent;
+        }
+        Some(m)
+    }
+}
+
+/// Objects of the viewport, shared between the editor UI, which edits them, and the render
+/// controller, which registers what it loads and draws them where they are.
+///
+/// Objects form a hierarchy through [`SceneObject::parent`]; transforms are relative to the
+/// parent. [`EditorScene::save`] writes the scene asset the editor loads at startup.
+#[derive(Debug, Clone, Default)]
+pub struct EditorScene(Arc<Mutex<SceneState>>);
+
+impl EditorScene {
+    /// Scene asset at `path`; an empty scene when there is none or it cannot be read.
+    pub fn load_or_default(path: &Path) -> Self {
+        let scene = Self::default();
+        match read_file(path) {
+            Ok(Some(objects)) => {
+                if let Ok(mut g) = scene.0.lock() {
+                    g.objects = objects;
+                }
+            }
+            Ok(None) => {}
+            Err(e) => log::warn!("scene: load failed path='{}' err='{e}'", path.display()),
+        }
+        scene
+    }
+
+    /// Writes the objects as a scene asset.
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let file = SceneFile {
+            version: SCENE_VERSION,
+            objects: self
+                .objects()
+                .iter()
+                .map(ObjectEntry::from_object)
+                .collect(),
+        };
+        let text = serde_json::to_string_pretty(&file).map_err(|e| e.to_string())?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        std::fs::write(path, text).map_err(|e| e.to_string())
+    }
+
+    /// Adds `object`, replacing the one with the same id in place.
+    pub fn insert(&self, object: SceneObject) {
+        if let Ok(mut g) = self.0.lock() {
+            match g.get_mut(object.id) {
+                Some(o) => *o = object,
+                None => g.objects.push(object),
+            }
+            g.revision += 1;
+        }
+    }
+
+    /// Adds `object` unless its id is taken; `false` when it was.
+    pub fn insert_absent(&self, object: SceneObject) -> bool {
+        let Ok(mut g) = self.0.lock() else {
+            return false;
+        };
+        if g.get(object.id).is_some() {
+            return false;
+        }
+        g.objects.push(object);
+        g.revision += 1;
+        true
+    }
+
+    /// Snapshot of every object, in hierarchy order.
+    pub fn objects(&self) -> Vec<SceneObject> {
+        self.0.lock().map(|g| g.objects.clone()).unwrap_or_default()
+    }
+
+    pub fn get(&self, id: u32) -> Option<SceneObject> {
+        self.0.lock().ok()?.get(id).cloned()
+    }
+
+    pub fn transform(&self, id: u32) -> Option<Transform> {
+        self.0.lock().ok()?.get(id).map(|o| o.transform)
+    }
+
+    /// Moves object `id`; `false` when there is no such object.
+    pub fn set_transform(&self, id: u32, transform: Transform) -> bool {
+        self.update(id, |o| o.transform = transform)
+    }
+
+    /// `false` when there is no object `id`.
+    pub fn rename(&self, id: u32, name: &str) -> bool {
+        self.update(id, |o| o.name = name.to_string())
+    }
+
+    /// Puts object `id` under `parent` with `transform`, relative to it.
+    pub fn set_parent(
+        &self,
+        id: u32,
+        parent: Option<u32>,
+        transform: Transform,
+    ) -> Result<(), String> {
+        let mut g = self
+            .0
+            .lock()
+            .map_err(|_| "scene lock poisoned".to_string())?;
+        if let Some(p) = parent {
+            if g.get(p).is_none() {
+                return Err(format!("object #{p} is gone"));
+            }
+            if g.is_ancestor(id, p) {
+                return Err(format!("object #{p} is inside #{id}"));
+            }
+        }
+        let o = g
+            .get_mut(id)
+            .ok_or_else(|| format!("object #{id} is gone"))?;
+        o.parent = parent;
+        o.transform = transform;
+        g.revision += 1;
+        Ok(())
+    }
+
+    /// Removes object `id` with everything under it; returns them with the positions they had,
+    /// for [`EditorScene::restore`]. Empty when there is no such object.
+    pub fn remove_tree(&self, id: u32) -> Vec<(usize, SceneObject)> {
+        let Ok(mut g) = self.0.lock() else {
+            return Vec::new();
+        };
+        if g.get(id).is_none() {
+            return Vec::new();
+        }
+        let doomed: HashSet<u32> = g
+            .objects
+            .iter()
+            .filter(|o| g.is_ancestor(id, o.id))
+            .map(|o| o.id)
+            .collect();
+
+        let mut removed = Vec::new();
+        let mut kept = Vec::with_capacity(g.objects.len());
+        for (i, o) in std::mem::take(&mut g.objects).into_iter().enumerate() {
+            match doomed.contains(&o.id) {
+                true => removed.push((i, o)),
+                false => kept.push(o),
+            }
+        }
+        g.objects = kept;
+        g.revision += 1;
+        removed
+    }
+
+    /// Puts back objects taken by [`EditorScene::remove_tree`].
+    pub fn restore(&self, removed: &[(usize, SceneObject)]) {
+        if let Ok(mut g) = self.0.lock() {
+            // Ascending positions, so each lands where it was.
+            for (i, o) in removed {
+                let i = (*i).min(g.objects.len());
+                g.objects.insert(i, o.clone());
+            }
+            g.revision += 1;
+        }
+    }
+
+    /// An id no object has, above [`VIEWPORT_MODEL_ID`].
+    pub fn next_id(&self) -> u32 {
+        let g = self.0.lock();
+        let max = g
+            .map(|g| g.objects.iter().map(|o| o.id).max().unwrap_or(0))
+            .unwrap_or(0);
+        max.max(VIEWPORT_MODEL_ID) + 1
+    }
+
+    /// Model matrix of object `id`: its transform under those of its parents.
+    pub fn world_matrix(&self, id: u32) -> Option<Mat4> {
+        self.0.lock().ok()?.world_matrix(id)
+    }
+
+    /// World matrix of the parent of object `id`; identity at the root.
+    pub fn parent_matrix(&self, id: u32) -> Mat4 {
+        let Ok(g) = self.0.lock() else {
+            return Mat4::IDENTITY;
+        };
+        g.get(id)
+            .and_then(|o| o.parent)
+            .and_then(|p| g.world_matrix(p))
+            .unwrap_or(Mat4::IDENTITY)
+    }
+
+    /// Changes with every edit of the objects.
+    pub fn revision(&self) -> u64 {
+        self.0.lock().map(|g| g.revision).unwrap_or(0)
+    }
+
+    pub fn set_camera(&self, camera: ViewportCamera) {
+        if let Ok(mut g) = self.0.lock() {
+            g.camera = Some(camera);
+        }
+    }
+
+    /// `None` until the viewport has drawn a frame.
+    pub fn camera(&self) -> Option<ViewportCamera> {
+        self.0.lock().ok().and_then(|g| g.camera)
+    }
+
+    fn update(&self, id: u32, f: impl FnOnce(&mut SceneObject)) -> bool {
+        let Ok(mut g) = self.0.lock() else {
+            return false;
+        };
+        match g.get_mut(id) {
+            Some(o) => {
+                f(o);
+                g.revision += 1;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Reached by `entity.set` and the inspector; entity ids are object ids.
+impl ComponentStore for EditorScene {
+    fn components(&self, id: u32) -> Option<Vec<String>> {
+        self.get(id)?;
+        Some(
+            SceneObject::COMPONENTS
+                .iter()
+                .map(|c| c.to_string())
+                .collect(),
+        )
+    }
+
+    fn get_component(&self, id: u32, name: &str) -> Option<Box<dyn Any + Send>> {
+        self.0.lock().ok()?.get(id)?.component(name)
+    }
+
+    fn set_component(&self, id: u32, name: &str, value: Box<dyn Any + Send>) -> Result<(), String> {
+        let mut g = self
+            .0
+            .lock()
+            .map_err(|_| "scene lock poisoned".to_string())?;
+        let o = g
+            .get_mut(id)
+            .ok_or_else(|| format!("object #{id} is gone"))?;
+        o.set_component(name, value)?;
+        g.revision += 1;
+        Ok(())
+    }
+}
+
+#[inline]
+pub fn transform_matrix(t: &Transform) -> Mat4 {
+    Mat4::from_scale_rotation_translation(
+        Vec3::from_array(t.scale),
+        Quat::from_array(t.rotation).normalize(),
+        Vec3::from_array(t.translation),
+    )
+}
+
+/// Translation, rotation and scale of `m`; shear from non-uniformly scaled parents is lost.
+#[inline]
+pub fn matrix_transform(m: Mat4) -> Transform {
+    let (scale, rotation, translation) = m.to_scale_rotation_translation();
+    Transform {
+        translation: translation.to_array(),
+        rotation: rotation.normalize().to_array(),
+        scale: scale.to_array(),
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SceneFile {
+    version: u32,
+    objects: Vec<ObjectEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ObjectEntry {
+    id: u32,
+    name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    parent: Option<u32>,
+    /// Component name -> fields.
+    #[serde(default)]
+    components: BTreeMap<String, serde_json::Value>,
+}
+
+impl ObjectEntry {
+    /// Components are written by their registered serializers.
+    fn from_object(o: &SceneObject) -> Self {
+        let mut components = BTreeMap::new();
+        for name in SceneObject::COMPONENTS {
+            let (Some(info), Some(c)) = (component_info(name), o.component(name)) else {
+                continue;
+            };
+            match info.serialize(&*c) {
+                Ok(v) => {
+                    components.insert(name.to_string(), v);
+                }
+                Err(e) => log::warn!("scene: object #{} {name} not saved: {e}", o.id),
+            }
+        }
+        Self {
+            id: o.id,
+            name: o.name.clone(),
+            parent: o.parent,
+            components,
+        }
+    }
+
+    fn into_object(self) -> Result<SceneObject, String> {
+        let mut o = SceneObject::new(self.id, self.name);
+        o.parent = self.parent;
+        for (name, v) in &self.components {
+            let Some(info) = component_info(name) else {
+                log::warn!("scene: object #{} unknown component '{name}' dropped", o.id);
+                continue;
+            };
+            let c = info
+                .deserialize(v)
+                .map_err(|e| format!("object #{}: {e}", o.id))?;
+            if let Err(e) = o.set_component(name, c) {
+                log::warn!("scene: object #{} component dropped: {e}", o.id);
+            }
+        }
+        Ok(o)
+    }
+}
+
+fn read_file(path: &Path) -> Result<Option<Vec<SceneObject>>, String> {
+    let text = match std::fs::read_to_string(path) {
+        Ok(t) => t,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.to_string()),
+    };
+    let file: SceneFile = serde_json::from_str(&text).map_err(|e| e.to_string())?;
+    if file.version > SCENE_VERSION {
+        return Err(format!("unsupported scene version {}", file.version));
+    }
+
+    let mut state = SceneState::default();
+    for entry in file.objects {
+        let o = entry.into_object()?;
+        if state.get(o.id).is_some() {
+            log::warn!("scene: duplicate object #{} skipped", o.id);
+            continue;
+        }
+        state.objects.push(o);
+    }
+    // Dangling parents and cycles put the object back at the root.
+    for i in 0..state.objects.len() {
+        let (id, parent) = (state.objects[i].id, state.objects[i].parent);
+        let Some(p) = parent else {
+            continue;
+        };
+        if state.get(p).is_none() || state.is_ancestor(id, p) {
+            log::warn!("scene: object #{id} has a bad parent #{p}, moved to the root");
+            state.objects[i].parent = None;
+        }
+    }
+    Ok(Some(state.objects))
+}
+
+/// Undoable transform edit of a scene object.
+///
+/// It only applies over the transform it started from, so it fails instead of moving an
+/// object that was replaced or moved by something else since.
+#[derive(Debug, Clone)]
+pub struct SetTransform {
+    scene: EditorScene,
+    id: u32,
+    before: Transform,
+    after: Transform,
+}
+
+impl SetTransform {
+    #[inline]
+    pub fn new(scene: EditorScene, id: u32, before: Transform, after: Transform) -> Self {
+        Self {
+            scene,
+            id,
+            before,
+            after,
+        }
+    }
+
+    fn replace(&self, from: Transform, to: Transform) -> Result<(), String> {
+        match self.scene.transform(self.id) {
+            Some(t) if t == from => {
+                self.scene.set_transform(self.id, to);
+                Ok(())
+            }
+            Some(_) => Err(format!("object #{} has moved since", self.id)),
+            None => Err(format!("object #{} is gone", self.id)),
+        }
+    }
+}
+
+impl Command for SetTransform {
+    fn label(&self) -> String {
+        format!("transform #{}", self.id)
+    }
+
+    fn apply(&mut self) -> Result<(), String> {
+        self.replace(self.before, self.after)
+    }
+
+    fn revert(&mut self) -> Result<(), String> {
+        self.replace(self.after, self.before)
+    }
+}
+
+/// Undoable rename of a scene object.
+#[derive(Debug, Clone)]
+pub struct Rename {
+    scene: EditorScene,
+    id: u32,
+    before: String,
+    after: String,
+}
+
+impl Rename {
+    pub fn new(scene: EditorScene, id: u32, name: &str) -> Result<Self, String> {
+        let before = scene
+            .get(id)
+            .ok_or_else(|| format!("object #{id} is gone"))?
+            .name;
+        Ok(Self {
+            scene,
+            id,
+            before,
+            after: name.to_string(),
+        })
+    }
+}
+
+impl Command for Rename {
+    fn label(&self) -> String {
+        format!("rename #{} to '{}'", self.id, self.after)
+    }
+
+    fn apply(&mut self) -> Result<(), String> {
+        match self.scene.rename(self.id, &self.after) {
+            true => Ok(()),
+            false => Err(format!("object #{} is gone", self.id)),
+        }
+    }
+
+    fn revert(&mut self) -> Result<(), String> {
+        match self.scene.rename(self.id, &self.before) {
+            true => Ok(()),
+            false => Err(format!("object #{} is gone", self.id)),
+        }
+    }
+}
+
+/// Undoable move of a scene object under another parent; the object stays where it is in the
+/// world, so its transform changes with the parent.
+#[derive(Debug, Clone)]
+pub struct Reparent {
+    scene: EditorScene,
+    id: u32,
+    before: (Option<u32>, Transform),
+    after: (Option<u32>, Transform),
+}
+
+impl Reparent {
+    pub fn new(scene: EditorScene, id: u32, parent: Option<u32>) -> Result<Self, String> {
+        let o = scene
+            .get(id)
+            .ok_or_else(|| format!("object #{id} is gone"))?;
+        let world = scene.world_matrix(id).unwrap_or(Mat4::IDENTITY);
+        let parent_world = match parent {
+            Some(p) => scene
+                .world_matrix(p)
+                .ok_or_else(|| format!("object #{p} is gone"))?,
+            None => Mat4::IDENTITY,
+        };
+        let local = matrix_transform(parent_world.inverse() * world);
+        Ok(Self {
+            scene,
+            id,
+            before: (o.parent, o.transform),
+            after: (parent, local),
+        })
+    }
+}
+
+impl Command for Reparent {
+    fn label(&self) -> String {
+        match self.after.0 {
+            Some(p) => format!("move #{} under #{p}", self.id),
+            None => format!("move #{} to the root", self.id),
+        }
+    }
+
+    fn apply(&mut self) -> Result<(), String> {
+        let (parent, transform) = self.after;
+        self.scene.set_parent(self.id, parent, transform)
+    }
+
+    fn revert(&mut self) -> Result<(), String> {
+        let (parent, transform) = self.before;
+        self.scene.set_parent(self.id, parent, transform)
+    }
+}
+
+/// Undoable creation of a scene object.
+#[derive(Debug, Clone)]
+pub struct AddObject {
+    scene: EditorScene,
+    object: SceneObject,
+}
+
+impl AddObject {
+    #[inline]
+    pub fn new(scene: EditorScene, object: SceneObject) -> Self {
+        Self { scene, object }
+    }
+}
+
+impl Command for AddObject {
+    fn label(&self) -> String {
+        format!("add '{}'", self.object.name)
+    }
+
+    fn apply(&mut self) -> Result<(), String> {
+        match self.scene.insert_absent(self.object.clone()) {
+            true => Ok(()),
+            false => Err(format!("object #{} already exists", self.object.id)),
+        }
+    }
+
+    fn revert(&mut self) -> Result<(), String> {
+        match self.scene.remove_tree(self.object.id).is_empty() {
+            true => Err(format!("object #{} is gone", self.object.id)),
+            false => Ok(()),
+        }
+    }
+}
+
+/// Undoable removal of a scene object with everything under it.
+#[derive(Debug, Clone)]
+pub struct DeleteObject {
+    scene: EditorScene,
+    id: u32,
+    name: String,
+    /// What the last apply took out.
+    removed: Vec<(usize, SceneObject)>,
+}
+
+impl DeleteObject {
+    pub fn new(scene: EditorScene, id: u32) -> Result<Self, String> {
+        let name = scene
+            .get(id)
+            .ok_or_else(|| format!("object #{id} is gone"))?
+            .name;
+        Ok(Self {
+            scene,
+            id,
+            name,
+            removed: Vec::new(),
+        })
+    }
+
+    /// Ids the last apply removed.
+    pub fn removed_ids(&self) -> impl Iterator<Item = u32> + '_ {
+        self.removed.iter().map(|(_, o)| o.id)
+    }
+}
+
+impl Command for DeleteObject {
+    fn label(&self) -> String {
+        format!("delete '{}'", self.name)
+    }
+
+    fn apply(&mut self) -> Result<(), String> {
+        self.removed = self.scene.remove_tree(self.id);
+        match self.removed.is_empty() {
+            true => Err(format!("object #{} is gone", self.id)),
+            false => Ok(()),
+        }
+    }
+
+    fn revert(&mut self) -> Result<(), String> {
+        if self.scene.get(self.id).is_some() {
+            return Err(format!("object #{} exists again", self.id));
+        }
+        self.scene.restore(&self.removed);
+        Ok(())
+    }
+}
@@ -0,0 +1,83 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use newengine_platform_winit::egui;
+use std::sync::{Arc, Mutex};
+
+#[derive(Debug, Default)]
+struct SelectionState {
+    /// Viewport pixel clicked, waiting for the render controller to pick it.
+    request: Option<(u32, u32)>,
+    /// Object id of the last pick; `None` when it hit nothing.
+    selected: Option<u32>,
+}
+
+/// Viewport selection shared between the editor UI, which turns clicks into pick requests,
+/// and the render controller, which answers them through the object-ID pass.
+#[derive(Debug, Clone, Default)]
+pub struct ViewportSelection(Arc<Mutex<SelectionState>>);
+
+impl ViewportSelection {
+    /// Asks for the object under viewport pixel `(x, y)`; replaces a request not yet taken.
+    pub fn request_pick(&self, x: u32, y: u32) {
+        if let Ok(mut g) = self.0.lock() {
+            g.request = Some((x, y));
+        }
+    }
+
+    pub fn take_request(&self) -> Option<(u32, u32)> {
+        self.0.lock().ok().and_then(|mut g| g.request.take())
+    }
+
+    pub fn set_selected(&self, id: Option<u32>) {
+        if let Ok(mut g) = self.0.lock() {
+            g.selected = id;
+        }
+    }
+
+    pub fn selected(&self) -> Option<u32> {
+        self.0.lock().ok().and_then(|g| g.selected)
+    }
+}
+
+/// Picks on primary clicks that land on the viewport rather than on a panel, and shows the
+/// selection in the toolbar.
+#[derive(Debug, Default)]
+pub struct SelectionUi {
+    selection: ViewportSelection,
+}
+
+impl SelectionUi {
+    #[inline]
+    pub fn new(selection: ViewportSelection) -> Self {
+        Self { selection }
+    }
+
+    pub fn toolbar_ui(&mut self, ui: &mut egui::Ui) {
+        match self.selection.selected() {
+            Some(id) => ui.label(format!("Selected: #{id}")),
+            None => ui.weak("Nothing selected"),
+        };
+    }
+
+    pub fn ui(&mut self, ctx: &egui::Context) {
+        let clicked = ctx.input(|i| {
+            i.pointer
+                .primary_clicked()
+                .then(|| i.pointer.interact_pos())
+                .flatten()
+        });
+        let Some(pos) = clicked else {
+            return;
+        };
+        if ctx.is_pointer_over_area() {
+            return;
+        }
+
+        // egui works in points; the ID target is in window pixels.
+        let ppp = ctx.pixels_per_point();
+        let (x, y) = (pos.x * ppp, pos.y * ppp);
+        if x >= 0.0 && y >= 0.0 {
+            self.selection.request_pick(x as u32, y as u32);
+        }
+    }
+}
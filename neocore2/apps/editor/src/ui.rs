@@ -1,11 +1,33 @@
 use newengine_platform_winit::{egui, UiBuildFn};
-use newengine_ui::markup::{UiMarkupDoc, UiState};
+use newengine_ui::markup::{UiActionRouter, UiMarkupDoc, UiState};
 use serde::Deserialize;
 use std::any::Any;
+use std::collections::BTreeMap;
 use std::sync::{Arc, Mutex};
 
+use newengine_localization::LocalizationApiRef;
+
+use crate::asset_browser::AssetBrowser;
+use crate::crash_notice::CrashNotice;
+use crate::gizmo::GizmoUi;
+use crate::hierarchy::HierarchyPanel;
+use crate::history::HistoryPanel;
+use crate::hot_reload::UiMarkupHotReload;
+use crate::inspector::InspectorPanel;
+use crate::log_viewer::LogViewer;
+use crate::plugin_ui::PluginUi;
+use crate::post_fx::PostFxPanel;
+use crate::project::ProjectPanel;
+use crate::resources_inspector::{ResourcesInspector, ResourcesView};
+use crate::selection::{SelectionUi, ViewportSelection};
+use crate::workspace::{ConsoleDock, ConsoleLayout, Workspaces};
+
 use newengine_core::host_events::KeyCode;
 
+/// Scripting plugin bridge for `UiState` vars (`ui_get` / `ui_set` in scripts).
+const SCRIPT_SERVICE_ID: &str = "kalitech.script.v1";
+const SCRIPT_UI_SYNC: &str = "script.ui_sync";
+
 #[derive(Debug, Deserialize, Default)]
 struct InputKeysTakeResponse {
     #[serde(default)]
@@ -21,6 +43,9 @@ struct CommandExecResponse {
     output: Option<String>,
     #[serde(default)]
     error: Option<String>,
+    /// The last paged result has another page (`more`).
+    #[serde(default)]
+    more: bool,
 }
 
 #[derive(Debug, Deserialize, Clone, Default)]
@@ -48,6 +73,8 @@ struct SuggestResponse {
 #[derive(Debug)]
 struct ConsoleUi {
     open: bool,
+    dock: ConsoleDock,
+    extent: f32,
     input: String,
 
     // Keyboard edges are sourced from the Input plugin (DLL), not from egui/winit.
@@ -55,6 +82,8 @@ struct ConsoleUi {
 
     lines: Vec<String>,
     stick_to_bottom: bool,
+    /// Scrolling past the end runs `more` while set.
+    more_pending: bool,
 
     filter: String,
 
@@ -71,12 +100,15 @@ impl Default for ConsoleUi {
     fn default() -> Self {
         Self {
             open: false,
+            dock: ConsoleDock::Bottom,
+            extent: 0.40,
             input: String::new(),
 
             frame_keys_pressed: Vec::new(),
 
             lines: Vec::new(),
             stick_to_bottom: true,
+            more_pending: false,
 
             filter: String::new(),
 
@@ -95,6 +127,23 @@ impl Default for ConsoleUi {
 }
 
 impl ConsoleUi {
+    #[inline]
+    fn layout(&self) -> ConsoleLayout {
+        ConsoleLayout {
+            open: self.open,
+            dock: self.dock,
+            extent: self.extent,
+        }
+    }
+
+    #[inline]
+    fn apply_layout(&mut self, layout: &ConsoleLayout) {
+        self.open = layout.open;
+        self.dock = layout.dock;
+        self.extent = layout.extent.clamp(0.15, 0.85);
+        self.suggest_open = false;
+    }
+
     #[inline]
     fn poll_input_keys(&mut self) {
         self.frame_keys_pressed.clear();
@@ -144,40 +193,53 @@ impl ConsoleUi {
             return;
         }
 
-        let screen_h = ctx.screen_rect().height();
-        let console_h = (screen_h * 0.40).clamp(260.0, 620.0);
+        let screen = ctx.screen_rect();
 
         let bg = egui::Color32::from_rgba_premultiplied(12, 12, 14, 238);
         let stroke = egui::Stroke::new(1.0, egui::Color32::from_gray(60));
+        let frame = egui::Frame::none()
+            .fill(bg)
+            .stroke(stroke)
+            .inner_margin(egui::Margin::symmetric(12.0, 10.0));
+
+        match self.dock {
+            ConsoleDock::Bottom => {
+                let console_h = (screen.height() * self.extent).clamp(260.0, 620.0);
+                egui::TopBottomPanel::bottom("ne_engine_console")
+                    .exact_height(console_h)
+                    .resizable(false)
+                    .frame(frame)
+                    .show(ctx, |ui| self.body(ui));
+            }
+            ConsoleDock::Right => {
+                let console_w = (screen.width() * self.extent).clamp(320.0, 900.0);
+                egui::SidePanel::right("ne_engine_console_right")
+                    .exact_width(console_w)
+                    .resizable(false)
+                    .frame(frame)
+                    .show(ctx, |ui| self.body(ui));
+            }
+        }
+    }
 
-        egui::TopBottomPanel::bottom("ne_engine_console")
-            .exact_height(console_h)
-            .resizable(false)
-            .frame(
-                egui::Frame::none()
-                    .fill(bg)
-                    .stroke(stroke)
-                    .inner_margin(egui::Margin::symmetric(12.0, 10.0)),
-            )
-            .show(ctx, |ui| {
-                self.header_row(ui);
+    fn body(&mut self, ui: &mut egui::Ui) {
+        self.header_row(ui);
 
-                ui.add_space(6.0);
+        ui.add_space(6.0);
 
-                let available = ui.available_height();
-                let log_h = (available * 0.60).max(160.0);
+        let available = ui.available_height();
+        let log_h = (available * 0.60).max(160.0);
 
-                self.log_area(ui, log_h);
+        self.log_area(ui, log_h);
 
-                ui.add_space(6.0);
+        ui.add_space(6.0);
 
-                self.input_row(ui);
+        self.input_row(ui);
 
-                if self.suggest_open && !self.suggest.items.is_empty() {
-                    ui.add_space(4.0);
-                    self.suggest_panel(ui);
-                }
-            });
+        if self.suggest_open && !self.suggest.items.is_empty() {
+            ui.add_space(4.0);
+            self.suggest_panel(ui);
+        }
     }
 
     fn header_row(&mut self, ui: &mut egui::Ui) {
@@ -197,6 +259,9 @@ impl ConsoleUi {
             if ui.button("Services").clicked() {
                 self.exec_line("services");
             }
+            if self.more_pending && ui.button("More").clicked() {
+                self.exec_line("more");
+            }
             if ui.button("Refresh").clicked() {
                 let _ = newengine_core::call_service_v1("engine.command", "command.refresh", &[]);
                 self.push_line("[refreshed]".to_string());
@@ -234,7 +299,7 @@ impl ConsoleUi {
     fn log_area(&mut self, ui: &mut egui::Ui, log_h: f32) {
         let f = self.filter.trim().to_lowercase();
 
-        egui::ScrollArea::vertical()
+        let out = egui::ScrollArea::vertical()
             .max_height(log_h)
             .stick_to_bottom(self.stick_to_bottom)
             .show(ui, |ui| {
@@ -254,6 +319,17 @@ impl ConsoleUi {
                     ui.label(rt);
                 }
             });
+
+        // Scrolling down at the end of a paged listing fetches the next page.
+        if self.more_pending {
+            let at_end =
+                out.state.offset.y + out.inner_rect.height() >= out.content_size.y - 4.0;
+            let hovered = ui.rect_contains_pointer(out.inner_rect);
+            let scrolling_down = ui.input(|i| i.smooth_scroll_delta.y < 0.0);
+            if at_end && hovered && scrolling_down {
+                self.exec_line("more");
+            }
+        }
     }
 
     fn input_row(&mut self, ui: &mut egui::Ui) {
@@ -552,6 +628,7 @@ impl ConsoleUi {
         match newengine_core::call_service_v1("engine.command", "command.exec", line.as_bytes()) {
             Ok(bytes) => match serde_json::from_slice::<CommandExecResponse>(&bytes) {
                 Ok(r) => {
+                    self.more_pending = r.more;
                     if r.ok {
                         let out = r.output.unwrap_or_default();
                         let out = out.trim_end();
@@ -581,21 +658,197 @@ pub struct EditorUiBuild {
     shared_doc: Arc<Mutex<Option<UiMarkupDoc>>>,
     state: UiState,
     console: ConsoleUi,
+    workspaces: Workspaces,
+    hot_reload: Option<UiMarkupHotReload>,
+    resources: ResourcesInspector,
+    logs: LogViewer,
+    assets: AssetBrowser,
+    post_fx: PostFxPanel,
+    history: HistoryPanel,
+    hierarchy: HierarchyPanel,
+    inspector: InspectorPanel,
+    plugin_ui: PluginUi,
+    selection: SelectionUi,
+    gizmo: GizmoUi,
+    router: UiActionRouter,
+    localization: Option<LocalizationApiRef>,
+    localization_generation: Option<u64>,
+    crash_notice: CrashNotice,
+    project: ProjectPanel,
 }
 
 impl EditorUiBuild {
     #[inline]
-    pub fn new(shared_doc: Arc<Mutex<Option<UiMarkupDoc>>>) -> Self {
+    pub fn new(shared_doc: Arc<Mutex<Option<UiMarkupDoc>>>, workspaces: Workspaces) -> Self {
         let mut state = UiState::default();
         state.set_var("app.name", "NewEngine Editor");
+
+        let mut console = ConsoleUi {
+            stick_to_bottom: true,
+            ..Default::default()
+        };
+        console.apply_layout(&workspaces.active().console);
+        workspaces.apply_panels(&mut state);
+
         Self {
             shared_doc,
             state,
-            console: ConsoleUi {
-                open: true,
-                stick_to_bottom: true,
-                ..Default::default()
-            },
+            console,
+            workspaces,
+            hot_reload: None,
+            resources: ResourcesInspector::default(),
+            logs: LogViewer::default(),
+            assets: AssetBrowser::default(),
+            post_fx: PostFxPanel::default(),
+            history: HistoryPanel::default(),
+            hierarchy: HierarchyPanel::default(),
+            inspector: InspectorPanel::default(),
+            plugin_ui: PluginUi::default(),
+            selection: SelectionUi::default(),
+            gizmo: GizmoUi::default(),
+            router: UiActionRouter::new(newengine_core::call_service_v1),
+            localization: None,
+            localization_generation: None,
+            crash_notice: CrashNotice::default(),
+            project: ProjectPanel::default(),
+        }
+    }
+
+    /// Enables live markup reload; `UiState` is kept across document swaps.
+    #[inline]
+    pub fn with_hot_reload(mut self, hot_reload: Option<UiMarkupHotReload>) -> Self {
+        self.hot_reload = hot_reload;
+        self
+    }
+
+    /// Enables the Resources inspector panel fed by `ResourcesSnapshotModule`.
+    #[inline]
+    pub fn with_resources_view(mut self, view: ResourcesView) -> Self {
+        self.resources = ResourcesInspector::new(view);
+        self
+    }
+
+    /// Picks objects with viewport clicks, answered by the render controller.
+    #[inline]
+    pub fn with_selection(mut self, selection: ViewportSelection) -> Self {
+        self.selection = SelectionUi::new(selection);
+        self
+    }
+
+    /// Moves, rotates and scales the selected object with viewport handles.
+    #[inline]
+    pub fn with_gizmo(mut self, gizmo: GizmoUi) -> Self {
+        self.gizmo = gizmo;
+        self
+    }
+
+    /// Scene tree with reparenting, renaming and deletion.
+    #[inline]
+    pub fn with_hierarchy(mut self, hierarchy: HierarchyPanel) -> Self {
+        self.hierarchy = hierarchy;
+        self
+    }
+
+    /// Fields of the selected scene object.
+    #[inline]
+    pub fn with_inspector(mut self, inspector: InspectorPanel) -> Self {
+        self.inspector = inspector;
+        self
+    }
+
+    /// Resolves `@key` markup references through the given string tables.
+    #[inline]
+    pub fn with_localization(mut self, localization: LocalizationApiRef) -> Self {
+        self.localization = Some(localization);
+        self
+    }
+
+    /// Offers the given crash report from the previous session in a banner.
+    #[inline]
+    pub fn with_crash_report(mut self, report: Option<std::path::PathBuf>) -> Self {
+        self.crash_notice = CrashNotice::new(report);
+        self
+    }
+
+    /// Active project and the recent projects list.
+    #[inline]
+    pub fn with_project(mut self, project: ProjectPanel) -> Self {
+        self.project = project;
+        self
+    }
+
+    fn sync_localization(&mut self) {
+        let Some(loc) = self.localization.as_ref() else {
+            return;
+        };
+        let g = loc.read();
+        if self.localization_generation == Some(g.generation()) {
+            return;
+        }
+        self.localization_generation = Some(g.generation());
+        self.state.set_texts(g.resolved_strings());
+    }
+
+    /// Exchanges `UiState` vars with the scripting plugin (no-op when it is not loaded).
+    fn sync_script_vars(&mut self) {
+        let vars: BTreeMap<&str, &str> = self
+            .state
+            .vars
+            .iter()
+            .map(|(k, v)| (k.as_str(), v.as_str()))
+            .collect();
+        let Ok(payload) = serde_json::to_vec(&vars) else {
+            return;
+        };
+
+        let Ok(bytes) =
+            newengine_core::call_service_v1(SCRIPT_SERVICE_ID, SCRIPT_UI_SYNC, &payload)
+        else {
+            return;
+        };
+        if let Ok(writes) = serde_json::from_slice::<BTreeMap<String, String>>(&bytes) {
+            for (k, v) in writes {
+                self.state.set_var(k, v);
+            }
+        }
+    }
+
+    fn switch_workspace(&mut self, name: &str) {
+        self.workspaces.capture(self.console.layout(), &self.state);
+        if !self.workspaces.switch_to(name) {
+            return;
+        }
+        self.console.apply_layout(&self.workspaces.active().console);
+        self.workspaces.apply_panels(&mut self.state);
+        self.workspaces.save();
+    }
+
+    fn toolbar(&mut self, ctx: &egui::Context) {
+        let mut picked: Option<String> = None;
+
+        egui::TopBottomPanel::top("ne_editor_toolbar").show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                picked = self.workspaces.toolbar_ui(ui);
+                ui.separator();
+                self.project.toolbar_ui(ui);
+                self.resources.toolbar_ui(ui);
+                self.logs.toolbar_ui(ui);
+                self.assets.toolbar_ui(ui);
+                self.hierarchy.toolbar_ui(ui);
+                self.inspector.toolbar_ui(ui);
+                self.post_fx.toolbar_ui(ui);
+                self.plugin_ui.toolbar_ui(ui, &mut self.state);
+                ui.separator();
+                self.history.toolbar_ui(ui);
+                ui.separator();
+                self.selection.toolbar_ui(ui);
+                ui.separator();
+                self.gizmo.toolbar_ui(ui);
+            });
+        });
+
+        if let Some(name) = picked {
+            self.switch_workspace(&name);
         }
     }
 }
@@ -606,15 +859,44 @@ impl UiBuildFn for EditorUiBuild {
             return;
         };
 
+        if let Some(hr) = self.hot_reload.as_mut() {
+            hr.poll(&self.shared_doc);
+        }
+
+        self.sync_localization();
+        self.sync_script_vars();
+        self.crash_notice.ui(ctx);
+        self.toolbar(ctx);
+
         let maybe_doc = { self.shared_doc.lock().ok().and_then(|g| g.as_ref().cloned()) };
         if let Some(doc) = maybe_doc {
             doc.render(ctx, &mut self.state);
         }
 
+        self.resources.ui(ctx);
+        self.logs.ui(ctx);
+        self.assets.ui(ctx);
+        self.post_fx.ui(ctx);
+        self.history.ui(ctx);
+        self.hierarchy.ui(ctx);
+        self.inspector.ui(ctx);
+        let reopened = self.project.ui(ctx);
         self.console.ui(ctx);
 
-        if self.state.take_clicked("quit") {
+        // Markup `call:`/`set:` actions run without app glue; custom actions are not used yet.
+        let _ = self.router.dispatch(&mut self.state);
+        self.plugin_ui.ui(ctx, &mut self.state, &mut self.router);
+        // Last, so every panel of this frame counts as "not the viewport"; clicks on gizmo
+        // handles do not pick.
+        if !self.gizmo.ui(ctx) {
+            self.selection.ui(ctx);
+        }
+
+        // Another project runs in a new editor process.
+        if self.state.take_clicked("quit") || reopened {
+            self.workspaces.capture(self.console.layout(), &self.state);
+            self.workspaces.save();
             let _ = newengine_core::call_service_v1("engine.command", "command.exec", b"quit");
         }
     }
-}
\ No newline at end of file
+}
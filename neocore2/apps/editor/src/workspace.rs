@@ -0,0 +1,247 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use newengine_platform_winit::egui;
+use newengine_ui::markup::UiState;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Where the engine console is docked inside the editor window.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConsoleDock {
+    Bottom,
+    Right,
+}
+
+impl Default for ConsoleDock {
+    #[inline]
+    fn default() -> Self {
+        Self::Bottom
+    }
+}
+
+/// Console part of a workspace: visibility, dock side and size as a fraction of the screen.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsoleLayout {
+    #[serde(default)]
+    pub open: bool,
+    #[serde(default)]
+    pub dock: ConsoleDock,
+    #[serde(default = "default_console_extent")]
+    pub extent: f32,
+}
+
+impl Default for ConsoleLayout {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            open: false,
+            dock: ConsoleDock::Bottom,
+            extent: default_console_extent(),
+        }
+    }
+}
+
+#[inline]
+fn default_console_extent() -> f32 {
+    0.40
+}
+
+/// Named editor layout: dock arrangement, open markup panels and console visibility.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspaceLayout {
+    pub name: String,
+    #[serde(default)]
+    pub console: ConsoleLayout,
+    /// Markup window id -> open.
+    #[serde(default)]
+    pub panels: BTreeMap<String, bool>,
+}
+
+impl WorkspaceLayout {
+    #[inline]
+    fn new(name: &str, console: ConsoleLayout) -> Self {
+        Self {
+            name: name.to_string(),
+            console,
+            panels: BTreeMap::new(),
+        }
+    }
+
+    #[inline]
+    fn with_panel(mut self, id: &str, open: bool) -> Self {
+        self.panels.insert(id.to_string(), open);
+        self
+    }
+
+    /// Built-in presets used when no workspace file exists for the project.
+    pub fn presets() -> Vec<WorkspaceLayout> {
+        vec![
+            WorkspaceLayout::new(
+                "Modeling",
+                ConsoleLayout {
+                    open: true,
+                    dock: ConsoleDock::Bottom,
+                    extent: 0.30,
+                },
+            )
+            .with_panel("Stats", false),
+            WorkspaceLayout::new(
+                "UI",
+                ConsoleLayout {
+                    open: false,
+                    dock: ConsoleDock::Right,
+                    extent: 0.30,
+                },
+            )
+            .with_panel("Stats", true),
+            WorkspaceLayout::new(
+                "Debugging",
+                ConsoleLayout {
+                    open: true,
+                    dock: ConsoleDock::Bottom,
+                    extent: 0.45,
+                },
+            )
+            .with_panel("Stats", true),
+        ]
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct WorkspaceFile {
+    active: String,
+    layouts: Vec<WorkspaceLayout>,
+}
+
+/// Owns the project's workspace layouts and the currently active one.
+///
+/// Layouts are stored as JSON in the project (see `workspaces_path` in `main.rs`); the file is
+/// rewritten on switch.
+#[derive(Debug)]
+pub struct Workspaces {
+    path: PathBuf,
+    active: usize,
+    layouts: Vec<WorkspaceLayout>,
+}
+
+impl Workspaces {
+    /// Loads layouts from `path`, falling back to built-in presets on a missing or broken file.
+    pub fn load_or_default(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+
+        let file = match read_file(&path) {
+            Ok(Some(f)) if !f.layouts.is_empty() => Some(f),
+            Ok(_) => None,
+            Err(e) => {
+                log::warn!("workspace: load failed path='{}' err='{e}'", path.display());
+                None
+            }
+        };
+
+        let (active_name, layouts) = match file {
+            Some(f) => (f.active, f.layouts),
+            None => (String::new(), WorkspaceLayout::presets()),
+        };
+
+        let active = layouts
+            .iter()
+            .position(|l| l.name == active_name)
+            .unwrap_or(0);
+
+        Self {
+            path,
+            active,
+            layouts,
+        }
+    }
+
+    #[inline]
+    pub fn active(&self) -> &WorkspaceLayout {
+        &self.layouts[self.active]
+    }
+
+    #[inline]
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.layouts.iter().map(|l| l.name.as_str())
+    }
+
+    /// Records the live console and panel state into the active layout.
+    pub fn capture(&mut self, console: ConsoleLayout, state: &UiState) {
+        let layout = &mut self.layouts[self.active];
+        layout.console = console;
+        for (id, open) in state.panels.iter() {
+            layout.panels.insert(id.clone(), *open);
+        }
+    }
+
+    /// Applies the active layout's panel visibility to the markup state.
+    pub fn apply_panels(&self, state: &mut UiState) {
+        for (id, open) in self.active().panels.iter() {
+            state.set_panel_open(id.clone(), *open);
+        }
+    }
+
+    /// Makes `name` the active layout. Returns false if no layout with that name exists.
+    pub fn switch_to(&mut self, name: &str) -> bool {
+        let Some(idx) = self.layouts.iter().position(|l| l.name == name) else {
+            return false;
+        };
+        self.active = idx;
+        log::info!("workspace: switch name='{name}'");
+        true
+    }
+
+    pub fn save(&self) {
+        let file = WorkspaceFile {
+            active: self.active().name.clone(),
+            layouts: self.layouts.clone(),
+        };
+
+        let text = match serde_json::to_string_pretty(&file) {
+            Ok(t) => t,
+            Err(e) => {
+                log::warn!("workspace: serialize failed err='{e}'");
+                return;
+            }
+        };
+
+        if let Err(e) = std::fs::write(&self.path, text) {
+            log::warn!(
+                "workspace: save failed path='{}' err='{e}'",
+                self.path.display()
+            );
+        }
+    }
+
+    /// Toolbar dropdown. Returns the selected layout name when the user picks a different one.
+    pub fn toolbar_ui(&self, ui: &mut egui::Ui) -> Option<String> {
+        let mut picked: Option<String> = None;
+
+        ui.label("Workspace:");
+        egui::ComboBox::from_id_salt("ne_editor_workspace")
+            .selected_text(self.active().name.as_str())
+            .show_ui(ui, |ui| {
+                for name in self.names() {
+                    let selected = name == self.active().name;
+                    if ui.selectable_label(selected, name).clicked() && !selected {
+                        picked = Some(name.to_string());
+                    }
+                }
+            });
+
+        picked
+    }
+}
+
+fn read_file(path: &Path) -> Result<Option<WorkspaceFile>, String> {
+    let text = match std::fs::read_to_string(path) {
+        Ok(t) => t,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.to_string()),
+    };
+    serde_json::from_str(&text)
+        .map(Some)
+        .map_err(|e| e.to_string())
+}
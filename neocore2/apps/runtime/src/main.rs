@@ -0,0 +1,372 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use crossbeam_channel::unbounded;
+
+use newengine_core::{
+    install_crash_handler, AssetManagerConfig, Bus, ConfigPaths, CrashConfig, Engine, EngineConfig,
+    EngineError, EngineResult, HeadlessRunner, Services, ShutdownToken, StartupConfig,
+    StartupLoader, StartupOverrideOrigin,
+};
+
+use newengine_core::plugins::ServiceLimits;
+use newengine_core::render::PostProcessSettings;
+use newengine_modules_environment::{EnvironmentConfig, EnvironmentModule, Skybox};
+use newengine_modules_lighting::{Light, LightId, LightingConfig, LightingModule, ShadowConfig};
+use newengine_modules_logging::{install_logger, ConsoleLoggerConfig, ConsoleLoggerModule};
+use newengine_modules_particles::{ParticlesConfig, ParticlesModule};
+use newengine_modules_render_vulkan_ash::VulkanAshRenderModule;
+use newengine_modules_splash::{SplashConfig, SplashModule};
+use newengine_modules_sprite2d::{Sprite2dConfig, Sprite2dModule};
+use newengine_modules_terrain::{TerrainConfig, TerrainModule};
+use newengine_modules_tilemap::{TilemapConfig, TilemapModule};
+
+use newengine_platform_winit::app::config::WinitAppIcon;
+use newengine_platform_winit::{run_winit_app_with_config, WinitAppConfig, WinitWindowPlacement};
+
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+mod render;
+
+const FIXED_DT_MS: u32 = 16;
+/// Written by `project.export` next to the executable.
+const CONFIG_FILE: &str = "config.json";
+const CRASH_DIR: &str = "crash_reports";
+/// Window size of the null renderer in `--headless` runs.
+const HEADLESS_SIZE: (u32, u32) = (1280, 720);
+
+struct AppServices;
+
+impl Services for AppServices {
+    #[inline]
+    fn logger(&self) -> &dyn log::Log {
+        log::logger()
+    }
+}
+
+/// Command line of the runtime; `--<section>.<key>=<value>` startup overrides are read by the
+/// loader.
+#[derive(Debug, Default)]
+struct RuntimeArgs {
+    /// No window and no GPU: plugins tick against the null renderer (servers, smoke tests).
+    headless: bool,
+    /// Frame budget of a headless run; runs until `quit` when unset.
+    frames: Option<u64>,
+}
+
+impl RuntimeArgs {
+    fn parse(args: impl IntoIterator<Item = String>) -> EngineResult<Self> {
+        let mut out = Self::default();
+        for arg in args {
+            if arg == "--headless" {
+                out.headless = true;
+            } else if let Some(n) = arg.strip_prefix("--frames=") {
+                let n = n
+                    .parse::<u64>()
+                    .map_err(|e| EngineError::other(format!("args: bad --frames '{n}': {e}")))?;
+                out.frames = Some(n);
+            }
+        }
+        Ok(out)
+    }
+}
+
+/// An exported game is started from anywhere (a shortcut, a file manager), but its config,
+/// packs, plugins and cache sit next to the executable and are named relative to it. Without
+/// a config there (running from a build tree), the working directory is kept.
+fn enter_game_dir() {
+    let Some(dir) = std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(Path::to_path_buf))
+    else {
+        return;
+    };
+    if !dir.join(CONFIG_FILE).is_file() {
+        return;
+    }
+    if let Err(e) = std::env::set_current_dir(&dir) {
+        eprintln!("runtime: failed to enter '{}': {e}", dir.display());
+    }
+}
+
+#[inline]
+fn winit_config_from_startup(startup: &StartupConfig) -> WinitAppConfig {
+    let placement = match startup.window_placement {
+        newengine_core::startup::WindowPlacement::Default => WinitWindowPlacement::OsDefault,
+        newengine_core::startup::WindowPlacement::Centered { offset } => {
+            WinitWindowPlacement::Centered { offset }
+        }
+    };
+
+    WinitAppConfig {
+        title: startup.window_title.clone(),
+        size: startup.window_size,
+        placement,
+        ui_backend: startup.ui_backend.clone(),
+        unfocused_fps: startup.window_unfocused_fps,
+        icon: None,
+        input_record: None,
+        input_replay: None,
+    }
+}
+
+fn register_render_from_startup(
+    engine: &mut Engine<()>,
+    startup: &StartupConfig,
+) -> EngineResult<()> {
+    let backend = startup.render_backend.trim();
+    if !backend.eq_ignore_ascii_case("vulkan_ash") && !backend.eq_ignore_ascii_case("vulkan") {
+        return Err(EngineError::other(format!(
+            "unsupported render backend '{backend}'"
+        )));
+    }
+
+    engine.register_module(Box::new(
+        VulkanAshRenderModule::new()
+            .with_pipeline_cache_dir(startup.render_pipeline_cache_dir.clone()),
+    ))?;
+
+    engine.register_module(Box::new(Sprite2dModule::new(Sprite2dConfig::new())))?;
+    engine.register_module(Box::new(SplashModule::new(splash_config(startup))))?;
+    engine.register_module(Box::new(ParticlesModule::new(ParticlesConfig::new())))?;
+    engine.register_module(Box::new(TilemapModule::new(TilemapConfig::new())))?;
+    engine.register_module(Box::new(TerrainModule::new(TerrainConfig::new())))?;
+
+    // Default sun until the game brings its own lights.
+    let shadows = ShadowConfig::new()
+        .with_cascades(startup.render_shadow_cascades as usize)
+        .with_map_size(startup.render_shadow_map_size)
+        .with_distance(startup.render_shadow_distance)
+        .with_split_lambda(startup.render_shadow_split_lambda);
+    let lighting = LightingModule::new(LightingConfig::new().with_shadows(shadows));
+    lighting.api().insert(
+        LightId(0),
+        Light::directional([-0.4, -0.8, -0.45])
+            .with_intensity(0.85)
+            .with_shadows(true),
+    );
+    engine.register_module(Box::new(lighting))?;
+
+    let environment = EnvironmentModule::new(EnvironmentConfig::new());
+    let skybox = startup.render_skybox.trim();
+    if !skybox.is_empty() {
+        environment.api().set_skybox(Some(Skybox::new(skybox)));
+    }
+    engine.register_module(Box::new(environment))?;
+
+    if let Err(e) = PostProcessSettings::new().register_cvars() {
+        log::warn!("post: cvars not registered: {e}");
+    }
+
+    engine.register_module(Box::new(render::GameRenderController::new(
+        startup.render_clear_color,
+    )))?;
+    Ok(())
+}
+
+/// The splash holds the startup scene and the configured preloads; the scene stays hidden
+/// until they are in.
+fn splash_config(startup: &StartupConfig) -> SplashConfig {
+    let mut preload = startup.splash_preload.clone();
+    preload.extend(startup.startup_scene.clone());
+    SplashConfig::new()
+        .with_image(startup.splash_image.clone())
+        .with_preload(preload)
+        .with_min_duration(Duration::from_millis(startup.splash_min_ms as u64))
+        .with_background(startup.render_clear_color)
+}
+
+fn build_engine_from_startup(startup: &StartupConfig, headless: bool) -> EngineResult<Engine<()>> {
+    let (tx, rx) = unbounded::<()>();
+    let bus: Bus<()> = Bus::new(tx, rx);
+
+    let services: Box<dyn Services> = Box::new(AppServices);
+    let shutdown = ShutdownToken::new();
+
+    let assets = AssetManagerConfig::new(startup.assets_root.clone())
+        .with_pump_steps(startup.asset_pump_steps)
+        .with_filesystem_source(startup.asset_filesystem_source)
+        .with_path_case(startup.asset_path_case)
+        .with_cache_dir(startup.asset_cache_dir.clone())
+        .with_engine_root(startup.asset_engine_root.clone())
+        .with_mods_root(startup.asset_mods_root.clone())
+        .with_packs(startup.asset_packs.clone());
+
+    let mut limits = ServiceLimits::default()
+        .with_max_payload_bytes(startup.service_max_payload_bytes as usize)
+        .with_max_calls_per_sec(startup.service_max_calls_per_sec)
+        .with_limit_host(startup.service_limit_host);
+    for (id, bytes) in &startup.service_payload_limits {
+        limits = limits.with_service_payload_limit(id.clone(), *bytes as usize);
+    }
+
+    // No user bindings or module tunables written back: a shipped game keeps its config.
+    let config = EngineConfig::new(FIXED_DT_MS, assets)
+        .with_plugins_dir(Some(startup.modules_dir.clone()))
+        .with_disabled_plugins(startup.disabled_plugins.clone())
+        .with_service_limits(limits)
+        .with_headless(headless);
+
+    let mut engine: Engine<()> = Engine::new_with_config(config, services, bus, shutdown)?;
+    engine.register_module(Box::new(ConsoleLoggerModule::new(configure_logger(
+        startup,
+    ))))?;
+    Ok(engine)
+}
+
+#[inline]
+fn configure_logger(startup: &StartupConfig) -> ConsoleLoggerConfig {
+    let mut cfg = ConsoleLoggerConfig::from_env();
+
+    // If NEWENGINE_LOG is set, keep it as authoritative (filter string).
+    if cfg.filter.is_some() {
+        return cfg;
+    }
+
+    if let Ok(level) = startup.log_level.parse::<log::LevelFilter>() {
+        cfg.level = level;
+    }
+
+    cfg
+}
+
+fn load_asset_blob_with_timeout(
+    engine: &Engine<()>,
+    logical_path: &str,
+    timeout: Duration,
+) -> EngineResult<std::sync::Arc<newengine_assets::AssetBlob>> {
+    use newengine_assets::AssetState;
+
+    let am = engine
+        .resources
+        .get::<newengine_core::assets::AssetManager>()
+        .ok_or_else(|| EngineError::other("AssetManager missing in engine.resources"))?;
+
+    let store = am.store();
+    let id = store.load_path(logical_path).map_err(|e| {
+        EngineError::other(format!("asset.load failed path='{logical_path}' err='{e}'"))
+    })?;
+
+    let t0 = Instant::now();
+    loop {
+        am.pump();
+
+        match store.state(id) {
+            AssetState::Ready => {
+                return store
+                    .get_blob(id)
+                    .ok_or_else(|| EngineError::other("asset: Ready but blob is missing"));
+            }
+            AssetState::Failed(e) => {
+                return Err(EngineError::other(format!(
+                    "asset: failed path='{logical_path}' err='{e}'"
+                )));
+            }
+            _ => {
+                if t0.elapsed() >= timeout {
+                    return Err(EngineError::other(format!(
+                        "asset: timeout path='{logical_path}' timeout_ms={}",
+                        timeout.as_millis()
+                    )));
+                }
+                std::thread::sleep(Duration::from_millis(1));
+            }
+        }
+    }
+}
+
+fn try_load_window_icon(engine: &Engine<()>, startup: &StartupConfig) -> Option<WinitAppIcon> {
+    let path = startup.window_icon_path.as_deref()?;
+
+    let blob = match load_asset_blob_with_timeout(engine, path, Duration::from_millis(500)) {
+        Ok(b) => b,
+        Err(e) => {
+            log::warn!("window icon: load failed path='{path}' err='{e}'");
+            return None;
+        }
+    };
+
+    match WinitAppIcon::from_png_bytes(&blob.payload) {
+        Ok(icon) => Some(icon),
+        Err(e) => {
+            log::warn!("window icon: decode failed path='{path}' err='{e}'");
+            None
+        }
+    }
+}
+
+fn main() -> EngineResult<()> {
+    enter_game_dir();
+
+    let args = RuntimeArgs::parse(std::env::args().skip(1))?;
+
+    // The exported config, then NEWENGINE_* env vars, then `--section.key=value` args.
+    let paths = ConfigPaths::from_startup_str(CONFIG_FILE);
+    let (startup, report) = StartupLoader::load_with_overrides(&paths, std::env::args().skip(1))?;
+
+    let _ = install_logger(&configure_logger(&startup));
+    install_crash_handler(CrashConfig::new(CRASH_DIR).with_app_name("runtime"));
+
+    log::info!(
+        "runtime: startup source={:?} file={:?} overrides={} headless={}",
+        report.source,
+        report.file,
+        report.overrides.len(),
+        args.headless
+    );
+    for ov in report.overrides.iter() {
+        match &ov.origin {
+            StartupOverrideOrigin::File => {
+                log::info!("startup: override {}: '{}' -> '{}'", ov.key, ov.from, ov.to)
+            }
+            StartupOverrideOrigin::Env(src)
+            | StartupOverrideOrigin::Arg(src)
+            | StartupOverrideOrigin::Project(src) => log::info!(
+                "startup: override {}: '{}' -> '{}' ({src})",
+                ov.key,
+                ov.from,
+                ov.to
+            ),
+        }
+    }
+    for w in report.warnings.iter() {
+        log::warn!("startup: {w}");
+    }
+    if startup.asset_packs.is_empty() && !startup.asset_filesystem_source {
+        log::warn!("runtime: no asset packs and the filesystem source is off; nothing to load");
+    }
+    for pack in startup.asset_packs.iter().filter(|p| !p.is_file()) {
+        log::warn!("runtime: asset pack missing path='{}'", pack.display());
+    }
+
+    if let Some(scene) = &startup.startup_scene {
+        log::info!("runtime: startup scene '{scene}'");
+    }
+
+    let mut engine = build_engine_from_startup(&startup, args.headless)?;
+
+    if args.headless {
+        engine.load_plugins_once()?;
+        let (w, h) = HEADLESS_SIZE;
+        let report = HeadlessRunner::new(engine)
+            .with_null_render(w, h)
+            .with_max_frames(args.frames)
+            .run()?;
+        println!(
+            "runtime: headless run finished exit={:?} frames={}",
+            report.exit, report.frames
+        );
+        return Ok(());
+    }
+
+    register_render_from_startup(&mut engine, &startup)?;
+
+    // Gameplay lives in plugins; they and the importers must exist before the window does.
+    engine.load_plugins_once()?;
+
+    let mut winit_cfg = winit_config_from_startup(&startup);
+    winit_cfg.icon = try_load_window_icon(&engine, &startup);
+
+    run_winit_app_with_config(engine, winit_cfg, None, |_engine| Ok(()))?;
+    Ok(())
+}
@@ -0,0 +1,159 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use glam::Vec3;
+use newengine_camera::{CameraRig, Perspective};
+use newengine_core::render::{
+    require_render_api, BeginFrameDesc, DebugDraw, Extent2D, PostProcessSettings, RectI32, Viewport,
+};
+use newengine_core::{EngineResult, Module, ModuleCtx};
+use newengine_modules_environment::{EnvironmentApiRef, ENVIRONMENT_API_ID};
+use newengine_modules_lighting::{LightingApiRef, LIGHTING_API_ID};
+use newengine_modules_splash::{SplashApiRef, SPLASH_API_ID};
+use newengine_modules_sprite2d::{Sprite2dApiRef, SPRITE2D_API_ID};
+use newengine_modules_terrain::{TerrainApiRef, TERRAIN_API_ID};
+use newengine_platform_winit::WinitWindowInitSize;
+use newengine_ui::draw::UiDrawList;
+
+/// Camera every layer is drawn from until gameplay drives one.
+const CAMERA_EYE: [f32; 3] = [2.6, 1.8, 2.6];
+const CAMERA_FOV_Y_DEG: f32 = 60.0;
+const CAMERA_NEAR: f32 = 0.01;
+const CAMERA_FAR: f32 = 1000.0;
+
+/// Frame of the game: sky, terrain, plugin draws and sprites. None of the editor's viewport
+/// model, picking or gizmo camera. While the splash is up only the sprite layer it draws
+/// into is rendered.
+pub struct GameRenderController {
+    clear_color: [f32; 4],
+    last_w: u32,
+    last_h: u32,
+    /// Last settings handed to the backend; `None` until the first frame.
+    post: Option<PostProcessSettings>,
+}
+
+impl GameRenderController {
+    #[inline]
+    pub fn new(clear_color: [f32; 4]) -> Self {
+        Self {
+            clear_color,
+            last_w: 0,
+            last_h: 0,
+            post: None,
+        }
+    }
+
+    fn view_proj(w: u32, h: u32) -> [f32; 16] {
+        let aspect = w as f32 / (h.max(1) as f32);
+        let proj = Perspective::new(
+            CAMERA_FOV_Y_DEG.to_radians(),
+            aspect,
+            CAMERA_NEAR,
+            CAMERA_FAR,
+        );
+        let rig = CameraRig::from_look_at(Vec3::from(CAMERA_EYE), Vec3::ZERO, Vec3::Y);
+        (proj.matrix_vk() * rig.view_matrix()).to_cols_array()
+    }
+}
+
+impl<E: Send + 'static> Module<E> for GameRenderController {
+    fn id(&self) -> &'static str {
+        "app.render_controller"
+    }
+
+    fn render(&mut self, ctx: &mut ModuleCtx<'_, E>) -> EngineResult<()> {
+        let ui: Option<UiDrawList> = ctx.resources_mut().remove::<UiDrawList>();
+
+        let (w, h) = ctx
+            .resources()
+            .get::<WinitWindowInitSize>()
+            .map(|s| (s.width, s.height))
+            .unwrap_or((0, 0));
+
+        let api = match require_render_api(ctx) {
+            Ok(api) => api,
+            Err(_) => return Ok(()),
+        };
+
+        let mut r = api.lock();
+
+        if let Some(ui) = ui {
+            r.set_ui_draw_list(ui);
+        }
+
+        if w != self.last_w || h != self.last_h {
+            self.last_w = w;
+            self.last_h = h;
+            r.resize(w, h)?;
+        }
+
+        // `post.*` cvars can still be changed from a profile's settings or a plugin.
+        let post = PostProcessSettings::from_cvars();
+        if self.post != Some(post) {
+            self.post = Some(post);
+            if let Err(e) = r.set_post_process(post) {
+                log::warn!("render: post-process settings not applied: {e}");
+            }
+        }
+
+        r.begin_frame(BeginFrameDesc::new(self.clear_color))?;
+
+        if w > 0 && h > 0 {
+            let extent = Extent2D::new(w, h);
+            r.set_viewport(Viewport::full(extent))?;
+            r.set_scissor(RectI32::new(0, 0, w as i32, h as i32))?;
+
+            let view_proj = Self::view_proj(w, h);
+            let splash = ctx
+                .api::<SplashApiRef>(SPLASH_API_ID)
+                .is_some_and(SplashApiRef::is_active);
+
+            let lighting = ctx
+                .api::<LightingApiRef>(LIGHTING_API_ID)
+                .filter(|_| !splash);
+            let lights = match lighting {
+                Some(lighting) => match lighting.prepare(&mut **r, CAMERA_EYE) {
+                    Ok(bg) => Some(bg),
+                    Err(e) => {
+                        log::warn!("lighting: prepare failed: {e}");
+                        None
+                    }
+                },
+                None => None,
+            };
+
+            // The sky goes first: the main pass has no depth, so later draws cover it.
+            let environment = ctx.api::<EnvironmentApiRef>(ENVIRONMENT_API_ID);
+            if let Some(environment) = environment.filter(|_| !splash) {
+                if let Err(e) = environment.render(&mut **r, extent, view_proj, CAMERA_EYE) {
+                    log::warn!("environment: skybox render failed: {e}");
+                }
+            }
+
+            let terrain = ctx.api::<TerrainApiRef>(TERRAIN_API_ID);
+            if let (Some(terrain), Some(lights)) = (terrain, lights) {
+                if let Err(e) = terrain.render(&mut **r, extent, view_proj, CAMERA_EYE, lights) {
+                    log::warn!("terrain: render failed: {e}");
+                }
+            }
+
+            // Gameplay plugins draw through the engine.render service; unreplayed draws are
+            // dropped next frame.
+            if !splash {
+                newengine_core::render_service::replay_plugin_draws(&mut **r);
+            }
+
+            if let Some(sprites) = ctx.api::<Sprite2dApiRef>(SPRITE2D_API_ID) {
+                if let Err(e) = sprites.render(&mut **r, extent) {
+                    log::warn!("sprite2d: render failed: {e}");
+                }
+            }
+
+            if let Some(dd) = ctx.resources().get::<DebugDraw>() {
+                dd.set_view_proj(view_proj);
+            }
+        }
+
+        r.end_frame()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,317 @@
+use crate::types::{AssetBlob, AssetDependency, AssetError};
+use log::{debug, warn};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+const CACHE_MAGIC: &[u8; 4] = b"NEAC";
+
+/// Bumped whenever the entry layout changes; it is also mixed into every key, so stale
+/// entries simply stop matching.
+const CACHE_VERSION: u32 = 1;
+
+/// Extension of cache entry files; `clear` only removes these.
+const CACHE_EXT: &str = "neac";
+
+/// Snapshot of the cache for diagnostics.
+#[derive(Debug, Clone)]
+pub struct CacheStats {
+    pub dir: PathBuf,
+    pub entries: usize,
+    pub bytes: u64,
+    pub hits: u64,
+    pub misses: u64,
+    pub writes: u64,
+}
+
+/// On-disk cache of imported blobs.
+///
+/// Entries are keyed by a hash of the source bytes, the importer `stable_id` and the import
+/// settings, so editing any of them produces a new key and the old entry is never read again.
+#[derive(Debug)]
+pub struct DerivedDataCache {
+    dir: PathBuf,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    writes: AtomicU64,
+}
+
+impl DerivedDataCache {
+    #[inline]
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            writes: AtomicU64::new(0),
+        }
+    }
+
+    #[inline]
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Content key for one import.
+    pub fn key(bytes: &[u8], importer_id: &str, settings: Option<&str>) -> String {
+        let mut h = blake3::Hasher::new();
+        h.update(CACHE_MAGIC);
+        h.update(&CACHE_VERSION.to_le_bytes());
+        hash_str(&mut h, importer_id);
+        match settings {
+            Some(s) => {
+                h.update(&[1]);
+                hash_str(&mut h, s);
+            }
+            None => {
+                h.update(&[0]);
+            }
+        }
+        h.update(&(bytes.len() as u64).to_le_bytes());
+        h.update(bytes);
+        h.finalize().to_hex().to_string()
+    }
+
+    /// Cached blob for `key`. Unreadable or corrupt entries count as misses; corrupt ones are
+    /// deleted so the next import rewrites them.
+    pub fn get(&self, key: &str) -> Option<AssetBlob> {
+        let path = self.entry_path(key);
+        let bytes = match std::fs::read(&path) {
+            Ok(b) => b,
+            Err(_) => {
+                self.misses.fetch_add(1, Ordering::Relaxed);
+                return None;
+            }
+        };
+
+        match decode_entry(&bytes) {
+            Ok(blob) => {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                Some(blob)
+            }
+            Err(e) => {
+                warn!(
+                    target: "assets::cache",
+                    "cache.corrupt path='{}' reason='{}'",
+                    path.display(),
+                    e
+                );
+                let _ = std::fs::remove_file(&path);
+                self.misses.fetch_add(1, Ordering::Relaxed);
+                None
+            }
+        }
+    }
+
+    /// Stores `blob` under `key`. Written to a temporary file first so a crash never leaves a
+    /// truncated entry behind.
+    pub fn put(&self, key: &str, blob: &AssetBlob) -> Result<(), AssetError> {
+        let path = self.entry_path(key);
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| {
+                AssetError::new(format!(
+                    "asset cache: failed to create '{}': {}",
+                    dir.display(),
+                    e
+                ))
+            })?;
+        }
+
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, encode_entry(blob)).map_err(|e| {
+            AssetError::new(format!(
+                "asset cache: failed to write '{}': {}",
+                tmp.display(),
+                e
+            ))
+        })?;
+        std::fs::rename(&tmp, &path).map_err(|e| {
+            let _ = std::fs::remove_file(&tmp);
+            AssetError::new(format!(
+                "asset cache: failed to move '{}': {}",
+                path.display(),
+                e
+            ))
+        })?;
+
+        self.writes.fetch_add(1, Ordering::Relaxed);
+        debug!(
+            target: "assets::cache",
+            "cache.write key={} payload={}",
+            key,
+            blob.payload.len()
+        );
+        Ok(())
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        let mut entries = 0usize;
+        let mut bytes = 0u64;
+        for path in self.entry_files() {
+            if let Ok(m) = std::fs::metadata(&path) {
+                entries += 1;
+                bytes += m.len();
+            }
+        }
+
+        CacheStats {
+            dir: self.dir.clone(),
+            entries,
+            bytes,
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            writes: self.writes.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Removes every entry; returns how many were deleted. Counters are left alone.
+    pub fn clear(&self) -> Result<usize, AssetError> {
+        let mut removed = 0usize;
+        for path in self.entry_files() {
+            std::fs::remove_file(&path).map_err(|e| {
+                AssetError::new(format!(
+                    "asset cache: failed to remove '{}': {}",
+                    path.display(),
+                    e
+                ))
+            })?;
+            removed += 1;
+        }
+        Ok(removed)
+    }
+
+    /// Entries are sharded by the first two hex digits to keep directories small.
+    fn entry_path(&self, key: &str) -> PathBuf {
+        let shard = key.get(..2).unwrap_or("00");
+        let mut p = self.dir.join(shard);
+        p.push(format!("{key}.{CACHE_EXT}"));
+        p
+    }
+
+    fn entry_files(&self) -> Vec<PathBuf> {
+        let mut out = Vec::new();
+        let Ok(shards) = std::fs::read_dir(&self.dir) else {
+            return out;
+        };
+        for shard in shards.flatten() {
+            let Ok(files) = std::fs::read_dir(shard.path()) else {
+                continue;
+            };
+            for f in files.flatten() {
+                let p = f.path();
+                if p.extension().is_some_and(|e| e == CACHE_EXT) {
+                    out.push(p);
+                }
+            }
+        }
+        out
+    }
+}
+
+#[inline]
+fn hash_str(h: &mut blake3::Hasher, s: &str) {
+    h.update(&(s.len() as u64).to_le_bytes());
+    h.update(s.as_bytes());
+}
+
+fn encode_entry(blob: &AssetBlob) -> Vec<u8> {
+    let mut out = Vec::with_capacity(blob.payload.len() + blob.meta_json.len() + 64);
+    out.extend_from_slice(CACHE_MAGIC);
+    out.extend_from_slice(&CACHE_VERSION.to_le_bytes());
+    put_str(&mut out, &blob.type_id);
+    put_str(&mut out, &blob.format);
+    put_str(&mut out, &blob.meta_json);
+
+    out.extend_from_slice(&(blob.dependencies.len() as u32).to_le_bytes());
+    for d in &blob.dependencies {
+        put_str(&mut out, &d.logical_path.to_string_lossy());
+        out.extend_from_slice(&d.settings_hash.to_le_bytes());
+        put_str(&mut out, &d.type_hint);
+        put_str(&mut out, &d.usage);
+    }
+
+    out.extend_from_slice(&(blob.payload.len() as u64).to_le_bytes());
+    out.extend_from_slice(&blob.payload);
+    out
+}
+
+#[inline]
+fn put_str(out: &mut Vec<u8>, s: &str) {
+    out.extend_from_slice(&(s.len() as u32).to_le_bytes());
+    out.extend_from_slice(s.as_bytes());
+}
+
+fn decode_entry(bytes: &[u8]) -> Result<AssetBlob, String> {
+    let mut r = Reader { bytes, pos: 0 };
+    if r.take(4)? != CACHE_MAGIC {
+        return Err("bad magic".to_owned());
+    }
+    let version = r.u32()?;
+    if version != CACHE_VERSION {
+        return Err(format!("unsupported version {version}"));
+    }
+
+    let type_id = r.str()?;
+    let format = r.str()?;
+    let meta_json = r.str()?;
+
+    let dep_count = r.u32()? as usize;
+    let mut dependencies = Vec::with_capacity(dep_count.min(1024));
+    for _ in 0..dep_count {
+        dependencies.push(AssetDependency {
+            logical_path: PathBuf::from(r.str()?),
+            settings_hash: r.u64()?,
+            type_hint: Arc::from(r.str()?),
+            usage: Arc::from(r.str()?),
+        });
+    }
+
+    let payload_len = r.u64()? as usize;
+    let payload = r.take(payload_len)?.to_vec();
+    if r.pos != bytes.len() {
+        return Err("trailing bytes".to_owned());
+    }
+
+    Ok(AssetBlob {
+        type_id: Arc::from(type_id),
+        format: Arc::from(format),
+        payload,
+        meta_json: Arc::from(meta_json),
+        dependencies,
+    })
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], String> {
+        let end = self
+            .pos
+            .checked_add(n)
+            .filter(|&end| end <= self.bytes.len())
+            .ok_or_else(|| "truncated entry".to_owned())?;
+        let s = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(s)
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        let b = self.take(4)?;
+        Ok(u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    fn u64(&mut self) -> Result<u64, String> {
+        let b = self.take(8)?;
+        let mut a = [0u8; 8];
+        a.copy_from_slice(b);
+        Ok(u64::from_le_bytes(a))
+    }
+
+    fn str(&mut self) -> Result<&'a str, String> {
+        let len = self.u32()? as usize;
+        std::str::from_utf8(self.take(len)?).map_err(|_| "invalid utf-8".to_owned())
+    }
+}
@@ -0,0 +1,284 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use crate::texture::{
+    TextureAsset, TextureDesc, TextureFormat, TextureKind, TextureMip, TextureSubresource,
+};
+use crate::types::Asset;
+use serde::Deserialize;
+
+/// Faces of a cubemap in layer order.
+pub const CUBE_FACES: [&str; 6] = ["+x", "-x", "+y", "-y", "+z", "-z"];
+
+/// Upper bound on the side of a generated cube face.
+pub const MAX_CUBE_FACE_SIZE: u32 = 4096;
+
+/// Decoded `.cubemap` manifest (`kalitech.asset.cubemap`): where the faces come from.
+///
+/// Blob layout: `meta_json` is this enum (schema `kalitech.cubemap.meta.v1`), `payload` is
+/// empty. Image paths are relative to the manifest. Whoever loads the images builds the cube
+/// with [`TextureAsset::cube_from_faces`] or [`TextureAsset::cube_from_equirect`].
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CubemapAsset {
+    /// Six square images of one size, in [`CUBE_FACES`] order.
+    Faces { faces: [String; 6] },
+    /// One equirectangular (latitude-longitude) panorama, converted on the CPU; `None` picks
+    /// a face a quarter of the panorama wide.
+    Equirect {
+        image: String,
+        #[serde(default)]
+        face_size: Option<u32>,
+    },
+}
+
+impl Asset for CubemapAsset {
+    #[inline]
+    fn type_name() -> &'static str {
+        "CubemapAsset"
+    }
+}
+
+impl CubemapAsset {
+    /// Images the cubemap is built from.
+    pub fn images(&self) -> impl Iterator<Item = &str> {
+        let images: &[String] = match self {
+            Self::Faces { faces } => faces,
+            Self::Equirect { image, .. } => std::slice::from_ref(image),
+        };
+        images.iter().map(String::as_str)
+    }
+}
+
+/// World direction through texel coordinates `s`, `t` (`-1.0..=1.0`, `t` down the image) of
+/// cube face `face`, unnormalized. Uses the layout GPUs sample cubemaps with.
+#[inline]
+pub fn cube_face_direction(face: usize, s: f32, t: f32) -> [f32; 3] {
+    match face {
+        0 => [1.0, -t, -s],
+        1 => [-1.0, -t, s],
+        2 => [s, 1.0, t],
+        3 => [s, -1.0, -t],
+        4 => [s, -t, 1.0],
+        _ => [-s, -t, -1.0],
+    }
+}
+
+/// Face and `s`, `t` (`-1.0..=1.0`) a cubemap lookup along `dir` lands on; the inverse of
+/// [`cube_face_direction`]. `dir` need not be normalized but must not be zero.
+pub fn cube_direction_face(dir: [f32; 3]) -> (usize, f32, f32) {
+    let [x, y, z] = dir;
+    let (ax, ay, az) = (x.abs(), y.abs(), z.abs());
+    let (face, s, t, m) = if ax >= ay && ax >= az {
+        match x >= 0.0 {
+            true => (0, -z, -y, ax),
+            false => (1, z, -y, ax),
+        }
+    } else if ay >= az {
+        match y >= 0.0 {
+            true => (2, x, z, ay),
+            false => (3, x, -z, ay),
+        }
+    } else {
+        match z >= 0.0 {
+            true => (4, x, -y, az),
+            false => (5, -x, -y, az),
+        }
+    };
+    (face, s / m, t / m)
+}
+
+impl TextureAsset {
+    /// Single-mip `Rgba8Unorm` cubemap from six `size` x `size` RGBA8 faces in
+    /// [`CUBE_FACES`] order, rows top to bottom.
+    pub fn cube_from_faces(size: u32, faces: [Vec<u8>; 6]) -> Result<Self, CubemapReadError> {
+        let face_bytes = size as usize * size as usize * 4;
+        if size == 0 || size > MAX_CUBE_FACE_SIZE {
+            return Err(CubemapReadError::FaceSize(size));
+        }
+        if let Some(i) = faces.iter().position(|f| f.len() != face_bytes) {
+            return Err(CubemapReadError::FaceBytes {
+                face: CUBE_FACES[i],
+                expected: face_bytes,
+                got: faces[i].len(),
+            });
+        }
+
+        let subresources = faces
+            .into_iter()
+            .zip(0..)
+            .map(|(data, layer)| TextureSubresource { layer, data })
+            .collect();
+        Ok(Self {
+            desc: TextureDesc {
+                width: size,
+                height: size,
+                depth: 1,
+                layers: 6,
+                mip_count: 1,
+                format: TextureFormat::Rgba8Unorm,
+                kind: TextureKind::Cube,
+            },
+            mips: vec![TextureMip {
+                width: size,
+                height: size,
+                depth: 1,
+                subresources,
+            }],
+        })
+    }
+
+    /// Cubemap with `face_size` faces resampled (bilinear) from a `width` x `height` RGBA8
+    /// equirectangular panorama. The panorama's center column looks down -Z, its left and
+    /// right edges meet at +Z, and its top row is +Y.
+    pub fn cube_from_equirect(
+        width: u32,
+        height: u32,
+        rgba: &[u8],
+        face_size: u32,
+    ) -> Result<Self, CubemapReadError> {
+        if width == 0 || height == 0 || rgba.len() as u64 != width as u64 * height as u64 * 4 {
+            return Err(CubemapReadError::Panorama {
+                width,
+                height,
+                bytes: rgba.len(),
+            });
+        }
+        if face_size == 0 || face_size > MAX_CUBE_FACE_SIZE {
+            return Err(CubemapReadError::FaceSize(face_size));
+        }
+
+        let texel = |x: i64, y: i64| {
+            let x = x.rem_euclid(width as i64) as usize;
+            let y = y.clamp(0, height as i64 - 1) as usize;
+            let i = (y * width as usize + x) * 4;
+            let p = &rgba[i..i + 4];
+            [p[0] as f32, p[1] as f32, p[2] as f32, p[3] as f32]
+        };
+        let sample = |dir: [f32; 3]| {
+            let [x, y, z] = dir;
+            let len = (x * x + y * y + z * z).sqrt();
+            let u = 0.5 + x.atan2(-z) / std::f32::consts::TAU;
+            let v = (y / len).clamp(-1.0, 1.0).acos() / std::f32::consts::PI;
+
+            let fx = u * width as f32 - 0.5;
+            let fy = v * height as f32 - 0.5;
+            let (x0, y0) = (fx.floor() as i64, fy.floor() as i64);
+            let (tx, ty) = (fx - x0 as f32, fy - y0 as f32);
+            let (a, b) = (texel(x0, y0), texel(x0 + 1, y0));
+            let (c, d) = (texel(x0, y0 + 1), texel(x0 + 1, y0 + 1));
+            std::array::from_fn::<u8, 4, _>(|k| {
+                let top = a[k] + (b[k] - a[k]) * tx;
+                let bottom = c[k] + (d[k] - c[k]) * tx;
+                (top + (bottom - top) * ty).round().clamp(0.0, 255.0) as u8
+            })
+        };
+
+        let faces = std::array::from_fn(|face| {
+            let mut data = Vec::with_capacity(face_size as usize * face_size as usize * 4);
+            for y in 0..face_size {
+                let t = (y as f32 + 0.5) / face_size as f32 * 2.0 - 1.0;
+                for x in 0..face_size {
+                    let s = (x as f32 + 0.5) / face_size as f32 * 2.0 - 1.0;
+                    data.extend_from_slice(&sample(cube_face_direction(face, s, t)));
+                }
+            }
+            data
+        });
+        Self::cube_from_faces(face_size, faces)
+    }
+
+    /// Mip-0 texels of cube face `face` (see [`CUBE_FACES`]); `None` for other kinds of
+    /// texture.
+    pub fn cube_face(&self, face: usize) -> Option<&[u8]> {
+        if self.desc.kind != TextureKind::Cube {
+            return None;
+        }
+        let mip = self.mips.first()?;
+        mip.subresources
+            .iter()
+            .find(|s| s.layer as usize == face)
+            .map(|s| s.data.as_slice())
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum CubemapReadError {
+    #[error("wire: too short")]
+    TooShort,
+    #[error("wire: meta length out of bounds")]
+    MetaOutOfBounds,
+    #[error("wire: meta length too large ({0} bytes)")]
+    MetaTooLarge(usize),
+    #[error("utf8: {0}")]
+    Utf8(String),
+    #[error("meta json: {0}")]
+    MetaJson(String),
+    #[error("cube face size {0} (1..={MAX_CUBE_FACE_SIZE})")]
+    FaceSize(u32),
+    #[error("face {face}: expected {expected} bytes, got {got}")]
+    FaceBytes {
+        face: &'static str,
+        expected: usize,
+        got: usize,
+    },
+    #[error("{width}x{height} panorama with {bytes} bytes")]
+    Panorama {
+        width: u32,
+        height: u32,
+        bytes: usize,
+    },
+}
+
+pub struct CubemapReader;
+
+impl CubemapReader {
+    /// Hard cap to prevent pathological allocations / malformed assets.
+    pub const MAX_META_BYTES: usize = 64 * 1024;
+
+    /// Builds CubemapAsset from split parts:
+    /// - meta_json: blob.meta_json (the manifest)
+    /// - payload: blob.payload (unused)
+    pub fn from_blob_parts(
+        meta_json: &str,
+        _payload: &[u8],
+    ) -> Result<CubemapAsset, CubemapReadError> {
+        let cube: CubemapAsset = serde_json::from_str(meta_json)
+            .map_err(|e| CubemapReadError::MetaJson(e.to_string()))?;
+        if let CubemapAsset::Equirect {
+            face_size: Some(size),
+            ..
+        } = cube
+        {
+            if size == 0 || size > MAX_CUBE_FACE_SIZE {
+                return Err(CubemapReadError::FaceSize(size));
+            }
+        }
+        Ok(cube)
+    }
+
+    /// Decodes importer wire:
+    /// [4] meta_len_le (u32)
+    /// [N] meta_json utf8
+    /// [..] payload bytes (rest)
+    pub fn read_wire(bytes: &[u8]) -> Result<CubemapAsset, CubemapReadError> {
+        if bytes.len() < 4 {
+            return Err(CubemapReadError::TooShort);
+        }
+
+        let meta_len = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize;
+        if meta_len > Self::MAX_META_BYTES {
+            return Err(CubemapReadError::MetaTooLarge(meta_len));
+        }
+
+        let meta_start = 4usize;
+        let meta_end = meta_start.saturating_add(meta_len);
+        if meta_end > bytes.len() {
+            return Err(CubemapReadError::MetaOutOfBounds);
+        }
+
+        let meta_str = std::str::from_utf8(&bytes[meta_start..meta_end])
+            .map_err(|e| CubemapReadError::Utf8(e.to_string()))?;
+
+        Self::from_blob_parts(meta_str, &bytes[meta_end..])
+    }
+}
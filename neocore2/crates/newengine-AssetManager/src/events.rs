@@ -1,16 +1,128 @@
+use crate::handle::LoadGroupId;
 use crate::id::AssetId;
-use std::sync::Arc;
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+use std::sync::{Arc, Weak};
+
+/// Import pipeline stage reported by `AssetEvent::Progress`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportStage {
+    Queued,
+    Reading,
+    Importing,
+}
+
+impl ImportStage {
+    #[inline]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ImportStage::Queued => "queued",
+            ImportStage::Reading => "reading",
+            ImportStage::Importing => "importing",
+        }
+    }
+}
 
 #[derive(Debug, Clone)]
 pub enum AssetEvent {
+    /// Emitted on each stage transition of a pending import.
+    Progress {
+        id: AssetId,
+        stage: ImportStage,
+        bytes_read: u64,
+    },
+    /// Emitted when a pending import was aborted via `AssetStore::cancel`.
+    Cancelled {
+        id: AssetId,
+    },
     Ready {
         id: AssetId,
         type_id: Arc<str>,
         format: Arc<str>,
     },
+    /// Emitted instead of `Ready` when an asset finishes importing after `reload_path`.
+    Reloaded {
+        id: AssetId,
+        type_id: Arc<str>,
+        format: Arc<str>,
+    },
     Failed {
         id: AssetId,
         type_id: Arc<str>,
         error: Arc<str>,
     },
-}
\ No newline at end of file
+    /// Emitted once every member of a `AssetStore::load_group` batch is done.
+    GroupCompleted {
+        group: LoadGroupId,
+        name: Arc<str>,
+        ready: usize,
+        failed: usize,
+        cancelled: usize,
+    },
+}
+
+/// Events a consumer may fall behind by; older ones are dropped first. Applies to the store's
+/// own queue as well, which nothing drains in hosts that only subscribe.
+const QUEUE_CAP: usize = 4096;
+
+type SharedQueue = Arc<Mutex<VecDeque<AssetEvent>>>;
+
+/// Event queue of an `AssetStore`: its own queue for `drain_events`, plus a copy per
+/// subscriber.
+#[derive(Default)]
+pub(crate) struct EventQueue {
+    own: VecDeque<AssetEvent>,
+    subscribers: Vec<Weak<Mutex<VecDeque<AssetEvent>>>>,
+}
+
+impl EventQueue {
+    pub(crate) fn push_back(&mut self, ev: AssetEvent) {
+        self.subscribers.retain(|s| {
+            let Some(q) = s.upgrade() else {
+                return false;
+            };
+            push_capped(&mut q.lock(), ev.clone());
+            true
+        });
+        push_capped(&mut self.own, ev);
+    }
+
+    #[inline]
+    pub(crate) fn drain(&mut self) -> Vec<AssetEvent> {
+        self.own.drain(..).collect()
+    }
+
+    pub(crate) fn subscribe(&mut self) -> AssetEventReceiver {
+        let queue = SharedQueue::default();
+        self.subscribers.push(Arc::downgrade(&queue));
+        AssetEventReceiver { queue }
+    }
+}
+
+#[inline]
+fn push_capped(q: &mut VecDeque<AssetEvent>, ev: AssetEvent) {
+    if q.len() >= QUEUE_CAP {
+        q.pop_front();
+    }
+    q.push_back(ev);
+}
+
+/// Independent copy of an `AssetStore`'s events from `AssetStore::subscribe_events`.
+///
+/// Draining it does not affect `AssetStore::drain_events` or other receivers. Dropping it
+/// unsubscribes.
+pub struct AssetEventReceiver {
+    queue: SharedQueue,
+}
+
+impl AssetEventReceiver {
+    #[inline]
+    pub fn drain(&self) -> Vec<AssetEvent> {
+        self.queue.lock().drain(..).collect()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.queue.lock().is_empty()
+    }
+}
@@ -0,0 +1,140 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use crate::types::Asset;
+use serde_json::Value as JsonValue;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FontFormat {
+    Ttf,
+    Otf,
+    Unknown,
+}
+
+#[derive(Debug, Clone)]
+pub struct FontMeta {
+    pub schema: String,
+    pub container: String,
+    pub family: String,
+
+    pub units_per_em: u16,
+    pub ascender: i16,
+    pub descender: i16,
+    pub line_gap: i16,
+    pub glyph_count: u16,
+}
+
+/// A TrueType/OpenType font; `payload` holds the original file bytes, ready for
+/// `RenderApi::load_font`.
+#[derive(Debug, Clone)]
+pub struct FontAsset {
+    pub format: FontFormat,
+    pub meta: FontMeta,
+    pub payload: Vec<u8>,
+}
+
+impl Asset for FontAsset {
+    #[inline]
+    fn type_name() -> &'static str {
+        "FontAsset"
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum FontReadError {
+    #[error("wire: too short")]
+    TooShort,
+    #[error("wire: meta length out of bounds")]
+    MetaOutOfBounds,
+    #[error("wire: meta length too large ({0} bytes)")]
+    MetaTooLarge(usize),
+    #[error("utf8: {0}")]
+    Utf8(String),
+    #[error("meta json: {0}")]
+    MetaJson(String),
+}
+
+pub struct FontReader;
+
+impl FontReader {
+    /// Hard cap to prevent pathological allocations / malformed assets.
+    pub const MAX_META_BYTES: usize = 64 * 1024;
+
+    /// Builds FontAsset from split parts:
+    /// - meta_json: blob.meta_json
+    /// - payload: blob.payload (original bytes)
+    pub fn from_blob_parts(meta_json: &str, payload: &[u8]) -> Result<FontAsset, FontReadError> {
+        let meta = parse_meta_json(meta_json)?;
+        let format = detect_format(&meta.container);
+        Ok(FontAsset {
+            format,
+            meta,
+            payload: payload.to_vec(),
+        })
+    }
+
+    /// Decodes importer wire:
+    /// [4] meta_len_le (u32)
+    /// [N] meta_json utf8
+    /// [..] payload bytes (rest)
+    pub fn read_wire(bytes: &[u8]) -> Result<FontAsset, FontReadError> {
+        if bytes.len() < 4 {
+            return Err(FontReadError::TooShort);
+        }
+
+        let meta_len = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize;
+        if meta_len > Self::MAX_META_BYTES {
+            return Err(FontReadError::MetaTooLarge(meta_len));
+        }
+
+        let meta_start = 4usize;
+        let meta_end = meta_start.saturating_add(meta_len);
+        if meta_end > bytes.len() {
+            return Err(FontReadError::MetaOutOfBounds);
+        }
+
+        let meta_bytes = &bytes[meta_start..meta_end];
+        let payload = &bytes[meta_end..];
+
+        let meta_str =
+            std::str::from_utf8(meta_bytes).map_err(|e| FontReadError::Utf8(e.to_string()))?;
+
+        Self::from_blob_parts(meta_str, payload)
+    }
+}
+
+fn parse_meta_json(meta_json: &str) -> Result<FontMeta, FontReadError> {
+    let v: JsonValue =
+        serde_json::from_str(meta_json).map_err(|e| FontReadError::MetaJson(e.to_string()))?;
+
+    let str_of = |key: &str| v.get(key).and_then(|x| x.as_str()).unwrap_or("").to_owned();
+    let int_of = |key: &str| v.get(key).and_then(|x| x.as_i64()).unwrap_or(0);
+
+    Ok(FontMeta {
+        schema: str_of("schema"),
+        container: normalize_container(&str_of("container")),
+        family: str_of("family"),
+        units_per_em: int_of("units_per_em") as u16,
+        ascender: int_of("ascender") as i16,
+        descender: int_of("descender") as i16,
+        line_gap: int_of("line_gap") as i16,
+        glyph_count: int_of("glyph_count") as u16,
+    })
+}
+
+#[inline]
+fn normalize_container(raw: &str) -> String {
+    match raw.trim().to_ascii_lowercase().as_str() {
+        "ttf" | "truetype" => "ttf".to_owned(),
+        "otf" | "opentype" => "otf".to_owned(),
+        other => other.to_owned(),
+    }
+}
+
+#[inline]
+fn detect_format(container: &str) -> FontFormat {
+    match container {
+        "ttf" => FontFormat::Ttf,
+        "otf" => FontFormat::Otf,
+        _ => FontFormat::Unknown,
+    }
+}
@@ -0,0 +1,207 @@
+use crate::events::ImportStage;
+use crate::id::AssetId;
+use crate::types::LoadPriority;
+use std::sync::atomic::{AtomicU64, AtomicU8, Ordering};
+use std::sync::Arc;
+
+/// Where a load request is, as seen through a [`LoadHandle`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoadStatus {
+    Queued,
+    Reading,
+    Importing,
+    Ready,
+    Failed,
+    Cancelled,
+}
+
+impl LoadStatus {
+    #[inline]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LoadStatus::Queued => "queued",
+            LoadStatus::Reading => "reading",
+            LoadStatus::Importing => "importing",
+            LoadStatus::Ready => "ready",
+            LoadStatus::Failed => "failed",
+            LoadStatus::Cancelled => "cancelled",
+        }
+    }
+
+    /// Ready, failed or cancelled: nothing more will happen for this request.
+    #[inline]
+    pub fn is_done(self) -> bool {
+        matches!(
+            self,
+            LoadStatus::Ready | LoadStatus::Failed | LoadStatus::Cancelled
+        )
+    }
+
+    #[inline]
+    fn from_u8(v: u8) -> Self {
+        match v {
+            0 => LoadStatus::Queued,
+            1 => LoadStatus::Reading,
+            2 => LoadStatus::Importing,
+            3 => LoadStatus::Ready,
+            4 => LoadStatus::Failed,
+            _ => LoadStatus::Cancelled,
+        }
+    }
+}
+
+impl From<ImportStage> for LoadStatus {
+    #[inline]
+    fn from(stage: ImportStage) -> Self {
+        match stage {
+            ImportStage::Queued => LoadStatus::Queued,
+            ImportStage::Reading => LoadStatus::Reading,
+            ImportStage::Importing => LoadStatus::Importing,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct LoadTracker {
+    id: AssetId,
+    status: AtomicU8,
+    priority: AtomicU8,
+    bytes_read: AtomicU64,
+}
+
+/// Per-request view of a load, updated by `AssetStore::pump`.
+///
+/// Requests for an asset that is already queued share one handle, so every holder sees the same
+/// progress. Cheap to clone.
+#[derive(Debug, Clone)]
+pub struct LoadHandle(Arc<LoadTracker>);
+
+impl LoadHandle {
+    pub(crate) fn new(id: AssetId, status: LoadStatus, priority: LoadPriority) -> Self {
+        Self(Arc::new(LoadTracker {
+            id,
+            status: AtomicU8::new(status as u8),
+            priority: AtomicU8::new(priority as u8),
+            bytes_read: AtomicU64::new(0),
+        }))
+    }
+
+    #[inline]
+    pub fn id(&self) -> AssetId {
+        self.0.id
+    }
+
+    #[inline]
+    pub fn status(&self) -> LoadStatus {
+        LoadStatus::from_u8(self.0.status.load(Ordering::Acquire))
+    }
+
+    #[inline]
+    pub fn is_done(&self) -> bool {
+        self.status().is_done()
+    }
+
+    /// Source bytes read so far; 0 until the request leaves the queue.
+    #[inline]
+    pub fn bytes_read(&self) -> u64 {
+        self.0.bytes_read.load(Ordering::Relaxed)
+    }
+
+    #[inline]
+    pub fn priority(&self) -> LoadPriority {
+        LoadPriority::from_u8(self.0.priority.load(Ordering::Relaxed))
+    }
+
+    #[inline]
+    pub(crate) fn set_status(&self, status: LoadStatus) {
+        self.0.status.store(status as u8, Ordering::Release);
+    }
+
+    #[inline]
+    pub(crate) fn set_bytes_read(&self, bytes: u64) {
+        self.0.bytes_read.store(bytes, Ordering::Relaxed);
+    }
+
+    #[inline]
+    pub(crate) fn set_priority(&self, priority: LoadPriority) {
+        self.0.priority.store(priority as u8, Ordering::Relaxed);
+    }
+}
+
+/// Identifier of a load group, as carried by `AssetEvent::GroupCompleted`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct LoadGroupId(pub(crate) u64);
+
+impl LoadGroupId {
+    #[inline]
+    pub fn to_u64(self) -> u64 {
+        self.0
+    }
+}
+
+#[derive(Debug)]
+struct GroupInner {
+    id: LoadGroupId,
+    name: Arc<str>,
+    handles: Vec<LoadHandle>,
+}
+
+/// A batch of loads tracked together, e.g. everything a loading screen waits for.
+///
+/// `AssetEvent::GroupCompleted` is emitted once every member is done.
+#[derive(Debug, Clone)]
+pub struct LoadGroup(Arc<GroupInner>);
+
+impl LoadGroup {
+    pub(crate) fn new(id: LoadGroupId, name: Arc<str>, handles: Vec<LoadHandle>) -> Self {
+        Self(Arc::new(GroupInner { id, name, handles }))
+    }
+
+    #[inline]
+    pub fn id(&self) -> LoadGroupId {
+        self.0.id
+    }
+
+    #[inline]
+    pub fn name(&self) -> &Arc<str> {
+        &self.0.name
+    }
+
+    #[inline]
+    pub fn handles(&self) -> &[LoadHandle] {
+        &self.0.handles
+    }
+
+    /// Members that are done, out of the total.
+    pub fn done_count(&self) -> (usize, usize) {
+        let done = self.0.handles.iter().filter(|h| h.is_done()).count();
+        (done, self.0.handles.len())
+    }
+
+    /// Completed fraction in `0.0..=1.0`, for progress bars. An empty group is complete.
+    pub fn progress(&self) -> f32 {
+        let (done, total) = self.done_count();
+        if total == 0 {
+            return 1.0;
+        }
+        done as f32 / total as f32
+    }
+
+    #[inline]
+    pub fn is_done(&self) -> bool {
+        self.0.handles.iter().all(LoadHandle::is_done)
+    }
+
+    /// Members that ended in each terminal state: `(ready, failed, cancelled)`.
+    pub fn outcome(&self) -> (usize, usize, usize) {
+        self.0
+            .handles
+            .iter()
+            .fold((0, 0, 0), |(r, f, c), h| match h.status() {
+                LoadStatus::Ready => (r + 1, f, c),
+                LoadStatus::Failed => (r, f + 1, c),
+                LoadStatus::Cancelled => (r, f, c + 1),
+                _ => (r, f, c),
+            })
+    }
+}
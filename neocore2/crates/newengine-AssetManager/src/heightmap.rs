@@ -0,0 +1,143 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use crate::types::Asset;
+use serde_json::Value as JsonValue;
+
+/// A grid of 16-bit height samples, row-major with row 0 first.
+///
+/// Columns run along +x and rows along +z; `0` is the lowest and `u16::MAX` the highest
+/// height, scaled by whoever places the terrain.
+#[derive(Debug, Clone)]
+pub struct HeightmapAsset {
+    pub width: u32,
+    pub height: u32,
+    pub samples: Vec<u16>,
+}
+
+impl Asset for HeightmapAsset {
+    #[inline]
+    fn type_name() -> &'static str {
+        "HeightmapAsset"
+    }
+}
+
+impl HeightmapAsset {
+    /// Fails when `samples` does not hold `width * height` values or the grid is smaller
+    /// than 2x2.
+    pub fn new(width: u32, height: u32, samples: Vec<u16>) -> Result<Self, HeightmapReadError> {
+        if width < 2 || height < 2 || samples.len() as u64 != width as u64 * height as u64 {
+            return Err(HeightmapReadError::Size {
+                width,
+                height,
+                samples: samples.len(),
+            });
+        }
+        Ok(Self {
+            width,
+            height,
+            samples,
+        })
+    }
+
+    /// Sample at column `x`, row `z`, clamped to the grid, in `0.0..=1.0`.
+    #[inline]
+    pub fn sample(&self, x: i64, z: i64) -> f32 {
+        let x = x.clamp(0, self.width as i64 - 1) as usize;
+        let z = z.clamp(0, self.height as i64 - 1) as usize;
+        self.samples[z * self.width as usize + x] as f32 / u16::MAX as f32
+    }
+
+    /// Bilinear height at `u`, `v` in `0.0..=1.0` across the grid, in `0.0..=1.0`.
+    pub fn sample_bilinear(&self, u: f32, v: f32) -> f32 {
+        let fx = u.clamp(0.0, 1.0) * (self.width - 1) as f32;
+        let fz = v.clamp(0.0, 1.0) * (self.height - 1) as f32;
+        let (x0, z0) = (fx.floor() as i64, fz.floor() as i64);
+        let (tx, tz) = (fx - x0 as f32, fz - z0 as f32);
+
+        let top = lerp(self.sample(x0, z0), self.sample(x0 + 1, z0), tx);
+        let bottom = lerp(self.sample(x0, z0 + 1), self.sample(x0 + 1, z0 + 1), tx);
+        lerp(top, bottom, tz)
+    }
+}
+
+#[inline]
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum HeightmapReadError {
+    #[error("wire: too short")]
+    TooShort,
+    #[error("wire: meta length out of bounds")]
+    MetaOutOfBounds,
+    #[error("wire: meta length too large ({0} bytes)")]
+    MetaTooLarge(usize),
+    #[error("utf8: {0}")]
+    Utf8(String),
+    #[error("meta json: {0}")]
+    MetaJson(String),
+    #[error("{width}x{height} heightmap with {samples} samples")]
+    Size {
+        width: u32,
+        height: u32,
+        samples: usize,
+    },
+}
+
+pub struct HeightmapReader;
+
+impl HeightmapReader {
+    /// Hard cap to prevent pathological allocations / malformed assets.
+    pub const MAX_META_BYTES: usize = 64 * 1024;
+
+    /// Builds HeightmapAsset from split parts:
+    /// - meta_json: blob.meta_json (`width`, `height`)
+    /// - payload: blob.payload (little-endian u16 samples)
+    pub fn from_blob_parts(
+        meta_json: &str,
+        payload: &[u8],
+    ) -> Result<HeightmapAsset, HeightmapReadError> {
+        let v: JsonValue = serde_json::from_str(meta_json)
+            .map_err(|e| HeightmapReadError::MetaJson(e.to_string()))?;
+        let dim = |key: &str| {
+            v.get(key)
+                .and_then(JsonValue::as_u64)
+                .map(|n| n.min(u32::MAX as u64) as u32)
+                .ok_or_else(|| HeightmapReadError::MetaJson(format!("missing '{key}'")))
+        };
+        let (width, height) = (dim("width")?, dim("height")?);
+
+        let samples = payload
+            .chunks_exact(2)
+            .map(|b| u16::from_le_bytes([b[0], b[1]]))
+            .collect();
+        HeightmapAsset::new(width, height, samples)
+    }
+
+    /// Decodes importer wire:
+    /// [4] meta_len_le (u32)
+    /// [N] meta_json utf8
+    /// [..] payload bytes (rest)
+    pub fn read_wire(bytes: &[u8]) -> Result<HeightmapAsset, HeightmapReadError> {
+        if bytes.len() < 4 {
+            return Err(HeightmapReadError::TooShort);
+        }
+
+        let meta_len = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize;
+        if meta_len > Self::MAX_META_BYTES {
+            return Err(HeightmapReadError::MetaTooLarge(meta_len));
+        }
+
+        let meta_start = 4usize;
+        let meta_end = meta_start.saturating_add(meta_len);
+        if meta_end > bytes.len() {
+            return Err(HeightmapReadError::MetaOutOfBounds);
+        }
+
+        let meta_str = std::str::from_utf8(&bytes[meta_start..meta_end])
+            .map_err(|e| HeightmapReadError::Utf8(e.to_string()))?;
+
+        Self::from_blob_parts(meta_str, &bytes[meta_end..])
+    }
+}
@@ -1,5 +1,6 @@
 use blake3::Hasher;
 use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 use crate::types::AssetKey;
 
@@ -13,6 +14,12 @@ impl AssetId {
         self.0
     }
 
+    /// Rebuilds an id from [`AssetId::to_u128`], e.g. one passed back by a plugin.
+    #[inline]
+    pub fn from_u128(v: u128) -> Self {
+        Self(v)
+    }
+
     #[inline]
     pub fn from_key(key: &AssetKey) -> Self {
         let mut h = Hasher::new();
@@ -27,24 +34,69 @@ impl AssetId {
     }
 }
 
+/// How letter case participates in asset identity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathCaseMode {
+    /// "UI/Main.ui" and "ui/main.ui" are the same asset (default; matches Windows/macOS FS).
+    Insensitive,
+    /// Case is significant (matches typical Linux FS).
+    Sensitive,
+}
+
+impl Default for PathCaseMode {
+    #[inline]
+    fn default() -> Self {
+        Self::Insensitive
+    }
+}
+
+static PATH_CASE_SENSITIVE: AtomicBool = AtomicBool::new(false);
+
+/// Sets the process-wide case mode used when deriving `AssetId`s.
+///
+/// Must be configured before any asset is requested; ids computed under a different mode
+/// will not match.
 #[inline]
-fn hash_logical_path(h: &mut Hasher, p: &Path) {
-    // Stable across platforms:
-    // - components joined with '/'
-    // - ascii-lower for deterministic extension/path matching on Windows-like FS
-    //   (logical paths should be authored consistently anyway)
-    let mut first = true;
+pub fn set_path_case_mode(mode: PathCaseMode) {
+    PATH_CASE_SENSITIVE.store(mode == PathCaseMode::Sensitive, Ordering::Relaxed);
+}
+
+#[inline]
+pub fn path_case_mode() -> PathCaseMode {
+    if PATH_CASE_SENSITIVE.load(Ordering::Relaxed) {
+        PathCaseMode::Sensitive
+    } else {
+        PathCaseMode::Insensitive
+    }
+}
+
+/// Identity string of a (normalized) logical path: components joined with '/',
+/// case-folded according to [`path_case_mode`].
+///
+/// Folding is ASCII-only, like the filesystems it mirrors for asset names; Unicode lowercasing
+/// depends on the std version and would let ids drift between builds.
+pub(crate) fn canonical_logical_path(p: &Path) -> String {
+    let mut out = String::new();
     for comp in p.components() {
         let s = comp.as_os_str().to_string_lossy();
         if s.is_empty() {
             continue;
         }
-        if !first {
-            h.update(b"/");
+        if !out.is_empty() {
+            out.push('/');
         }
-        first = false;
-        // Avoid locale issues; keep simple deterministic normalization
-        let lower = s.as_bytes().iter().map(|b| b.to_ascii_lowercase()).collect::<Vec<u8>>();
-        h.update(&lower);
+        out.push_str(&s);
+    }
+
+    match path_case_mode() {
+        PathCaseMode::Insensitive => out.to_ascii_lowercase(),
+        PathCaseMode::Sensitive => out,
     }
-}
\ No newline at end of file
+}
+
+#[inline]
+fn hash_logical_path(h: &mut Hasher, p: &Path) {
+    // Stable across platforms: AssetKey already normalized separators and Unicode (NFC),
+    // so identity only depends on the component text and the configured case mode.
+    h.update(canonical_logical_path(p).as_bytes());
+}
@@ -1,20 +1,35 @@
 #![forbid(unsafe_op_in_unsafe_fn)]
 
+pub mod cache;
 pub mod events;
+pub mod handle;
 pub mod id;
 pub mod importers;
+pub mod meta;
+pub mod pack;
 pub mod source;
 pub mod store;
 pub mod texture;
+pub mod typed;
 pub mod types;
+pub mod vfs;
 
 pub mod text_reader;
 pub mod audio;
+pub mod cubemap;
+pub mod font;
+pub mod heightmap;
 pub mod model3d;
+pub mod ne3d;
+pub mod tilemap;
 
-pub use events::AssetEvent;
-pub use id::AssetId;
+pub use cache::{CacheStats, DerivedDataCache};
+pub use events::{AssetEvent, AssetEventReceiver, ImportStage};
+pub use handle::{LoadGroup, LoadGroupId, LoadHandle, LoadStatus};
+pub use id::{path_case_mode, set_path_case_mode, AssetId, PathCaseMode};
 pub use importers::Importer;
+pub use meta::{meta_path, AssetMeta, ASSET_META_EXT, ASSET_META_SCHEMA};
+pub use pack::{PackSource, PackStats, PackWriter, PACK_EXT};
 pub use source::{AssetSource, FileSystemSource};
 pub use store::{AssetStore, BlobImporterDispatch, PumpBudget};
 
@@ -22,12 +37,40 @@ pub use texture::{
     TextureAsset, TextureDesc, TextureFormat, TextureKind, TextureMip, TextureSubresource,
 };
 
+pub use typed::{
+    DecoderRegistry, MeshAsset, TextAsset, CUBEMAP_TYPE_ID, FONT_TYPE_ID, HEIGHTMAP_TYPE_ID,
+    MODEL3D_TYPE_ID, TEXTURE_TYPE_ID, TEXT_TYPE_ID, TILEMAP_TYPE_ID,
+};
+
 pub use types::{
-    Asset, AssetBlob, AssetDependency, AssetError, AssetKey, AssetState, ImporterPriority,
+    Asset, AssetBlob, AssetDependency, AssetError, AssetKey, AssetState, CancelToken,
+    ImporterPriority, LoadPriority,
 };
 
+pub use vfs::{MountInfo, Vfs, ENGINE_MOUNT, GAME_MOUNT, MODS_MOUNT};
+
 pub use text_reader::{TextDocument, TextFormat, TextMeta, TextReadError, TextReader};
 
 pub use audio::{AudioAsset, AudioFormat, AudioMeta, AudioReadError, AudioReader};
 
+pub use cubemap::{
+    cube_direction_face, cube_face_direction, CubemapAsset, CubemapReadError, CubemapReader,
+    CUBE_FACES, MAX_CUBE_FACE_SIZE,
+};
+
+pub use font::{FontAsset, FontFormat, FontMeta, FontReadError, FontReader};
+
+pub use heightmap::{HeightmapAsset, HeightmapReadError, HeightmapReader};
+
 pub use model3d::{Model3dAsset, Model3dFormat, Model3dMeta, Model3dReadError, Model3dReader};
+
+pub use ne3d::{
+    Ne3dAlphaMode, Ne3dChannel, Ne3dChannelPath, Ne3dClip, Ne3dError, Ne3dInterpolation,
+    Ne3dJoint, Ne3dLod, Ne3dMaterial, Ne3dMesh, Ne3dSubmesh, Ne3dTexture, NE3D_VERSION,
+};
+
+pub use tilemap::{
+    ObjectLayer, ObjectShape, TileChunk, TileLayer, TiledMap, TilemapAsset, TilemapDoc,
+    TilemapLayer, TilemapObject, TilemapProperties, TilemapReadError, TilemapReader, Tileset,
+    TilesetRef, TilesetTile, GID_FLIP_D, GID_FLIP_H, GID_FLIP_V, GID_MASK,
+};
\ No newline at end of file
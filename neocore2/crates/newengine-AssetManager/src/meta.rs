@@ -0,0 +1,94 @@
+use crate::types::AssetError;
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Schema tag written into every sidecar.
+pub const ASSET_META_SCHEMA: &str = "kalitech.asset.meta.v1";
+
+/// Extension appended to the asset's full file name: `models/crate.obj.meta`.
+pub const ASSET_META_EXT: &str = "meta";
+
+/// Per-asset importer configuration stored next to the source file.
+///
+/// ```json
+/// {"schema":"kalitech.asset.meta.v1","importer":"kalitech.import.3d.v1","settings":{"lod_count":2}}
+/// ```
+///
+/// `settings` is opaque to the store and handed to the importer through
+/// `AssetKey::import_settings`. `importer` records which importer wrote the defaults.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssetMeta {
+    pub importer: Option<String>,
+    pub settings: Value,
+}
+
+impl AssetMeta {
+    /// Sidecar for an importer's default settings (a JSON object).
+    pub fn with_defaults(importer: &str, default_settings: &str) -> Result<Self, AssetError> {
+        let settings: Value = serde_json::from_str(default_settings).map_err(|e| {
+            AssetError::new(format!(
+                "asset meta: bad default settings from '{importer}': {e}"
+            ))
+        })?;
+        Ok(Self {
+            importer: Some(importer.to_string()),
+            settings,
+        })
+    }
+
+    pub fn parse(bytes: &[u8]) -> Result<Self, AssetError> {
+        let v: Value = serde_json::from_slice(bytes)
+            .map_err(|e| AssetError::new(format!("asset meta: invalid json: {e}")))?;
+        let Some(obj) = v.as_object() else {
+            return Err(AssetError::new("asset meta: expected a JSON object"));
+        };
+
+        if let Some(schema) = obj.get("schema").and_then(Value::as_str) {
+            if schema != ASSET_META_SCHEMA {
+                return Err(AssetError::new(format!(
+                    "asset meta: unsupported schema '{schema}'"
+                )));
+            }
+        }
+
+        let settings = obj.get("settings").cloned().unwrap_or_else(|| json!({}));
+        if !settings.is_object() {
+            return Err(AssetError::new("asset meta: 'settings' must be an object"));
+        }
+
+        Ok(Self {
+            importer: obj
+                .get("importer")
+                .and_then(Value::as_str)
+                .map(str::to_string),
+            settings,
+        })
+    }
+
+    /// Pretty-printed so the file diffs well under version control.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut v = json!({ "schema": ASSET_META_SCHEMA });
+        if let Some(importer) = &self.importer {
+            v["importer"] = Value::from(importer.as_str());
+        }
+        v["settings"] = self.settings.clone();
+        let mut out = serde_json::to_vec_pretty(&v).unwrap_or_default();
+        out.push(b'\n');
+        out
+    }
+
+    /// Compact `settings` JSON as passed to importers.
+    #[inline]
+    pub fn settings_json(&self) -> Arc<str> {
+        Arc::from(self.settings.to_string())
+    }
+}
+
+/// Logical path of the sidecar belonging to `logical_path`.
+pub fn meta_path(logical_path: &Path) -> PathBuf {
+    let mut name = logical_path.as_os_str().to_os_string();
+    name.push(".");
+    name.push(ASSET_META_EXT);
+    PathBuf::from(name)
+}
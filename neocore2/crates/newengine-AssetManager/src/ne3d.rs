@@ -0,0 +1,566 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use thiserror::Error;
+
+pub const NE3D_MAGIC: &[u8; 4] = b"NE3D";
+/// Newest payload version. Older payloads still decode: v1 has no skeleton section, v2 no
+/// material section, v3 no LOD table.
+pub const NE3D_VERSION: u32 = 4;
+
+pub mod ne3d_flags {
+    pub const NORMALS: u32 = 1 << 0;
+    pub const UVS: u32 = 1 << 1;
+    pub const SKIN: u32 = 1 << 2;
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ne3dJoint {
+    pub name: String,
+    /// Always an index lower than the joint's own.
+    pub parent: Option<u32>,
+    pub inverse_bind: [f32; 16],
+    /// Rest pose, local to the parent.
+    pub translation: [f32; 3],
+    pub rotation: [f32; 4],
+    pub scale: [f32; 3],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ne3dChannelPath {
+    Translation,
+    Rotation,
+    Scale,
+}
+
+impl Ne3dChannelPath {
+    /// Floats per key.
+    #[inline]
+    pub const fn width(self) -> usize {
+        match self {
+            Self::Rotation => 4,
+            Self::Translation | Self::Scale => 3,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ne3dInterpolation {
+    Linear,
+    Step,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ne3dChannel {
+    pub joint: u32,
+    pub path: Ne3dChannelPath,
+    pub interpolation: Ne3dInterpolation,
+    /// Seconds, ascending.
+    pub times: Vec<f32>,
+    /// `path.width()` floats per key.
+    pub values: Vec<f32>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ne3dClip {
+    pub name: String,
+    pub duration: f32,
+    pub channels: Vec<Ne3dChannel>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Ne3dAlphaMode {
+    #[default]
+    Opaque,
+    Mask,
+    Blend,
+}
+
+/// glTF-style metallic-roughness material. Texture slots index `Ne3dMesh::textures` and
+/// sample the first uv set.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ne3dMaterial {
+    pub name: String,
+    /// Linear RGBA, multiplied with `base_color_texture`.
+    pub base_color: [f32; 4],
+    pub metallic: f32,
+    pub roughness: f32,
+    pub emissive: [f32; 3],
+    pub alpha_mode: Ne3dAlphaMode,
+    pub alpha_cutoff: f32,
+    pub double_sided: bool,
+    pub base_color_texture: Option<u32>,
+    /// Roughness in G, metallic in B.
+    pub metallic_roughness_texture: Option<u32>,
+    pub normal_texture: Option<u32>,
+    pub normal_scale: f32,
+    pub occlusion_texture: Option<u32>,
+    pub occlusion_strength: f32,
+    pub emissive_texture: Option<u32>,
+}
+
+impl Default for Ne3dMaterial {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            base_color: [1.0; 4],
+            metallic: 1.0,
+            roughness: 1.0,
+            emissive: [0.0; 3],
+            alpha_mode: Ne3dAlphaMode::Opaque,
+            alpha_cutoff: 0.5,
+            double_sided: false,
+            base_color_texture: None,
+            metallic_roughness_texture: None,
+            normal_texture: None,
+            normal_scale: 1.0,
+            occlusion_texture: None,
+            occlusion_strength: 1.0,
+            emissive_texture: None,
+        }
+    }
+}
+
+/// An image embedded in the payload, still encoded (`mime` tells png from jpeg).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ne3dTexture {
+    pub name: String,
+    pub mime: String,
+    pub bytes: Vec<u8>,
+}
+
+/// A range of `Ne3dMesh::indices` drawn with one material.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Ne3dSubmesh {
+    pub first_index: u32,
+    pub index_count: u32,
+    /// `None`: the engine's default material.
+    pub material: Option<u32>,
+}
+
+/// A level of detail: a range of `Ne3dMesh::indices` drawing the shared vertex streams.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ne3dLod {
+    pub first_index: u32,
+    pub index_count: u32,
+    /// Simplification error relative to the mesh extent; 0 for LOD 0.
+    pub error: f32,
+}
+
+/// Decoded NE3D mesh payload, as written by the 3D importer.
+///
+/// Layout (little endian throughout):
+///
+/// ```text
+/// "NE3D" u32 version u32 vertex_count u32 index_count u32 flags
+/// v4+: u32 lod_count, lod_count x { u32 first_index, u32 index_count, f32 error }
+/// f32x3 positions[vertex_count]
+/// f32x3 normals[vertex_count]            flags & NORMALS
+/// f32x2 uvs[vertex_count]                flags & UVS
+/// u16x4 joints[vertex_count]             flags & SKIN (v2)
+/// f32x4 weights[vertex_count]            flags & SKIN (v2)
+/// u32   indices[index_count]
+/// v2+:
+/// u32 joint_count, joint_count x {
+///     str name, i32 parent (-1: root), f32x16 inverse_bind (column-major),
+///     f32x3 translation, f32x4 rotation (xyzw), f32x3 scale }
+/// u32 clip_count, clip_count x {
+///     str name, f32 duration, u32 channel_count, channel_count x {
+///         u32 joint, u8 path, u8 interpolation, u32 key_count,
+///         f32 times[key_count], f32 values[key_count * (3 | 4)] } }
+/// v3+:
+/// u32 material_count, material_count x {
+///     str name, f32x4 base_color, f32 metallic, f32 roughness, f32x3 emissive,
+///     u8 alpha_mode (0 opaque, 1 mask, 2 blend), f32 alpha_cutoff, u8 double_sided,
+///     i32 base_color_tex, i32 metallic_roughness_tex, i32 normal_tex, f32 normal_scale,
+///     i32 occlusion_tex, f32 occlusion_strength, i32 emissive_tex }      (tex -1: none)
+/// u32 texture_count, texture_count x { str name, str mime, u32 byte_len, u8 bytes[byte_len] }
+/// u32 submesh_count, submesh_count x { u32 first_index, u32 index_count, i32 material }
+/// str: u16 byte length + utf8
+/// ```
+///
+/// Without submeshes the whole index range uses the default material. Without LODs it is all
+/// LOD 0; otherwise LOD 0 comes first and each level's submeshes lie inside its range.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Ne3dMesh {
+    pub version: u32,
+    pub positions: Vec<[f32; 3]>,
+    /// Empty when the source had none.
+    pub normals: Vec<[f32; 3]>,
+    pub uvs: Vec<[f32; 2]>,
+    /// Per-vertex joint indices into `skeleton`; empty for static meshes.
+    pub joints: Vec<[u16; 4]>,
+    pub weights: Vec<[f32; 4]>,
+    pub indices: Vec<u32>,
+    pub skeleton: Vec<Ne3dJoint>,
+    pub clips: Vec<Ne3dClip>,
+    pub materials: Vec<Ne3dMaterial>,
+    pub textures: Vec<Ne3dTexture>,
+    pub submeshes: Vec<Ne3dSubmesh>,
+    pub lods: Vec<Ne3dLod>,
+}
+
+#[derive(Debug, Error)]
+pub enum Ne3dError {
+    #[error("ne3d: bad magic")]
+    BadMagic,
+    #[error("ne3d: unsupported version {0}")]
+    UnsupportedVersion(u32),
+    #[error("ne3d: truncated while reading {0}")]
+    Truncated(&'static str),
+    #[error("ne3d: {0}")]
+    Invalid(String),
+}
+
+impl Ne3dMesh {
+    #[inline]
+    pub fn is_skinned(&self) -> bool {
+        !self.joints.is_empty() && !self.skeleton.is_empty()
+    }
+
+    /// Index range of `level`, clamped to the coarsest one; the whole buffer without LODs.
+    pub fn lod_range(&self, level: usize) -> std::ops::Range<u32> {
+        match self.lods.get(level.min(self.lods.len().saturating_sub(1))) {
+            Some(l) => l.first_index..l.first_index + l.index_count,
+            None => 0..self.indices.len() as u32,
+        }
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self, Ne3dError> {
+        let mut r = Reader { bytes, at: 0 };
+
+        if r.take(4, "magic")? != NE3D_MAGIC {
+            return Err(Ne3dError::BadMagic);
+        }
+        let version = r.u32("version")?;
+        if !(1..=NE3D_VERSION).contains(&version) {
+            return Err(Ne3dError::UnsupportedVersion(version));
+        }
+
+        let vertex_count = r.u32("vertex_count")? as usize;
+        let index_count = r.u32("index_count")? as usize;
+        let flags = r.u32("flags")?;
+        let lods = if version >= 4 {
+            let lod_count = r.u32("lod_count")? as usize;
+            r.array(lod_count, "lods", |r| {
+                Ok(Ne3dLod {
+                    first_index: r.u32("lod first_index")?,
+                    index_count: r.u32("lod index_count")?,
+                    error: r.f32("lod error")?,
+                })
+            })?
+        } else {
+            Vec::new()
+        };
+
+        let mut mesh = Ne3dMesh {
+            version,
+            lods,
+            ..Ne3dMesh::default()
+        };
+
+        mesh.positions = r.array(vertex_count, "positions", |r| r.f32s::<3>("positions"))?;
+        if flags & ne3d_flags::NORMALS != 0 {
+            mesh.normals = r.array(vertex_count, "normals", |r| r.f32s::<3>("normals"))?;
+        }
+        if flags & ne3d_flags::UVS != 0 {
+            mesh.uvs = r.array(vertex_count, "uvs", |r| r.f32s::<2>("uvs"))?;
+        }
+        if version >= 2 && flags & ne3d_flags::SKIN != 0 {
+            mesh.joints = r.array(vertex_count, "joints", |r| {
+                Ok([
+                    r.u16("joints")?,
+                    r.u16("joints")?,
+                    r.u16("joints")?,
+                    r.u16("joints")?,
+                ])
+            })?;
+            mesh.weights = r.array(vertex_count, "weights", |r| r.f32s::<4>("weights"))?;
+        }
+        mesh.indices = r.array(index_count, "indices", |r| r.u32("indices"))?;
+
+        if let Some(&bad) = mesh.indices.iter().find(|&&i| i as usize >= vertex_count) {
+            return Err(Ne3dError::Invalid(format!(
+                "index {bad} out of range ({vertex_count} vertices)"
+            )));
+        }
+
+        if version >= 2 {
+            let joint_count = r.u32("joint_count")? as usize;
+            mesh.skeleton = r.array(joint_count, "joints", |r| {
+                Ok(Ne3dJoint {
+                    name: r.str("joint name")?,
+                    parent: r.index("joint parent")?,
+                    inverse_bind: r.f32s::<16>("inverse_bind")?,
+                    translation: r.f32s::<3>("joint translation")?,
+                    rotation: r.f32s::<4>("joint rotation")?,
+                    scale: r.f32s::<3>("joint scale")?,
+                })
+            })?;
+
+            let clip_count = r.u32("clip_count")? as usize;
+            mesh.clips = r.array(clip_count, "clips", Reader::clip)?;
+
+            mesh.validate_skeleton()?;
+        }
+
+        if version >= 3 {
+            let material_count = r.u32("material_count")? as usize;
+            mesh.materials = r.array(material_count, "materials", Reader::material)?;
+            let texture_count = r.u32("texture_count")? as usize;
+            mesh.textures = r.array(texture_count, "textures", |r| {
+                let name = r.str("texture name")?;
+                let mime = r.str("texture mime")?;
+                let len = r.u32("texture byte_len")? as usize;
+                let bytes = r.take(len, "texture bytes")?.to_vec();
+                Ok(Ne3dTexture { name, mime, bytes })
+            })?;
+            let submesh_count = r.u32("submesh_count")? as usize;
+            mesh.submeshes = r.array(submesh_count, "submeshes", |r| {
+                Ok(Ne3dSubmesh {
+                    first_index: r.u32("submesh first_index")?,
+                    index_count: r.u32("submesh index_count")?,
+                    material: r.index("submesh material")?,
+                })
+            })?;
+
+            mesh.validate_materials()?;
+        }
+
+        if let Some(l) = mesh
+            .lods
+            .iter()
+            .find(|l| l.first_index as u64 + l.index_count as u64 > index_count as u64)
+        {
+            return Err(Ne3dError::Invalid(format!(
+                "lod {}..{} exceeds {index_count} indices",
+                l.first_index,
+                l.first_index as u64 + l.index_count as u64
+            )));
+        }
+
+        Ok(mesh)
+    }
+
+    fn validate_materials(&self) -> Result<(), Ne3dError> {
+        let textures = self.textures.len();
+        for m in &self.materials {
+            let slots = [
+                m.base_color_texture,
+                m.metallic_roughness_texture,
+                m.normal_texture,
+                m.occlusion_texture,
+                m.emissive_texture,
+            ];
+            if let Some(t) = slots
+                .into_iter()
+                .flatten()
+                .find(|&t| t as usize >= textures)
+            {
+                return Err(Ne3dError::Invalid(format!(
+                    "material '{}' uses texture {t} of {textures}",
+                    m.name
+                )));
+            }
+        }
+        for s in &self.submeshes {
+            let end = s.first_index as u64 + s.index_count as u64;
+            if end > self.indices.len() as u64 {
+                return Err(Ne3dError::Invalid(format!(
+                    "submesh {}..{end} exceeds {} indices",
+                    s.first_index,
+                    self.indices.len()
+                )));
+            }
+            if s.material
+                .is_some_and(|m| m as usize >= self.materials.len())
+            {
+                return Err(Ne3dError::Invalid(format!(
+                    "submesh material {:?} of {}",
+                    s.material,
+                    self.materials.len()
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_skeleton(&self) -> Result<(), Ne3dError> {
+        let n = self.skeleton.len();
+        for (i, j) in self.skeleton.iter().enumerate() {
+            if let Some(p) = j.parent {
+                if p as usize >= i {
+                    return Err(Ne3dError::Invalid(format!(
+                        "joint {i} has parent {p}; parents must come first"
+                    )));
+                }
+            }
+        }
+        if let Some(j) = self
+            .joints
+            .iter()
+            .flatten()
+            .find(|&&j| j as usize >= n.max(1))
+        {
+            return Err(Ne3dError::Invalid(format!(
+                "vertex joint {j} out of range ({n} joints)"
+            )));
+        }
+        for c in &self.clips {
+            if let Some(ch) = c.channels.iter().find(|ch| ch.joint as usize >= n) {
+                return Err(Ne3dError::Invalid(format!(
+                    "clip '{}' animates joint {} of {n}",
+                    c.name, ch.joint
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    at: usize,
+}
+
+impl<'a> Reader<'a> {
+    #[inline]
+    fn take(&mut self, len: usize, what: &'static str) -> Result<&'a [u8], Ne3dError> {
+        let end = self
+            .at
+            .checked_add(len)
+            .filter(|&e| e <= self.bytes.len())
+            .ok_or(Ne3dError::Truncated(what))?;
+        let s = &self.bytes[self.at..end];
+        self.at = end;
+        Ok(s)
+    }
+
+    #[inline]
+    fn u8(&mut self, what: &'static str) -> Result<u8, Ne3dError> {
+        Ok(self.take(1, what)?[0])
+    }
+
+    #[inline]
+    fn u16(&mut self, what: &'static str) -> Result<u16, Ne3dError> {
+        let b = self.take(2, what)?;
+        Ok(u16::from_le_bytes([b[0], b[1]]))
+    }
+
+    #[inline]
+    fn u32(&mut self, what: &'static str) -> Result<u32, Ne3dError> {
+        let b = self.take(4, what)?;
+        Ok(u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    #[inline]
+    fn i32(&mut self, what: &'static str) -> Result<i32, Ne3dError> {
+        Ok(self.u32(what)? as i32)
+    }
+
+    /// `i32` where any negative value means "none".
+    #[inline]
+    fn index(&mut self, what: &'static str) -> Result<Option<u32>, Ne3dError> {
+        Ok(u32::try_from(self.i32(what)?).ok())
+    }
+
+    #[inline]
+    fn f32(&mut self, what: &'static str) -> Result<f32, Ne3dError> {
+        Ok(f32::from_bits(self.u32(what)?))
+    }
+
+    #[inline]
+    fn f32s<const N: usize>(&mut self, what: &'static str) -> Result<[f32; N], Ne3dError> {
+        let mut out = [0.0; N];
+        for v in &mut out {
+            *v = self.f32(what)?;
+        }
+        Ok(out)
+    }
+
+    fn str(&mut self, what: &'static str) -> Result<String, Ne3dError> {
+        let len = self.u16(what)? as usize;
+        let b = self.take(len, what)?;
+        String::from_utf8(b.to_vec()).map_err(|e| Ne3dError::Invalid(format!("{what}: {e}")))
+    }
+
+    /// Reads `count` items, refusing counts the remaining bytes cannot possibly hold.
+    fn array<T>(
+        &mut self,
+        count: usize,
+        what: &'static str,
+        mut item: impl FnMut(&mut Self) -> Result<T, Ne3dError>,
+    ) -> Result<Vec<T>, Ne3dError> {
+        if count > self.bytes.len() - self.at {
+            return Err(Ne3dError::Truncated(what));
+        }
+        let mut out = Vec::with_capacity(count);
+        for _ in 0..count {
+            out.push(item(self)?);
+        }
+        Ok(out)
+    }
+
+    fn clip(&mut self) -> Result<Ne3dClip, Ne3dError> {
+        let name = self.str("clip name")?;
+        let duration = self.f32("clip duration")?;
+        let channel_count = self.u32("channel_count")? as usize;
+        let channels = self.array(channel_count, "channels", Self::channel)?;
+        Ok(Ne3dClip {
+            name,
+            duration,
+            channels,
+        })
+    }
+
+    fn material(&mut self) -> Result<Ne3dMaterial, Ne3dError> {
+        Ok(Ne3dMaterial {
+            name: self.str("material name")?,
+            base_color: self.f32s::<4>("base_color")?,
+            metallic: self.f32("metallic")?,
+            roughness: self.f32("roughness")?,
+            emissive: self.f32s::<3>("emissive")?,
+            alpha_mode: match self.u8("alpha_mode")? {
+                0 => Ne3dAlphaMode::Opaque,
+                1 => Ne3dAlphaMode::Mask,
+                2 => Ne3dAlphaMode::Blend,
+                m => return Err(Ne3dError::Invalid(format!("unknown alpha mode {m}"))),
+            },
+            alpha_cutoff: self.f32("alpha_cutoff")?,
+            double_sided: self.u8("double_sided")? != 0,
+            base_color_texture: self.index("base_color_texture")?,
+            metallic_roughness_texture: self.index("metallic_roughness_texture")?,
+            normal_texture: self.index("normal_texture")?,
+            normal_scale: self.f32("normal_scale")?,
+            occlusion_texture: self.index("occlusion_texture")?,
+            occlusion_strength: self.f32("occlusion_strength")?,
+            emissive_texture: self.index("emissive_texture")?,
+        })
+    }
+
+    fn channel(&mut self) -> Result<Ne3dChannel, Ne3dError> {
+        let joint = self.u32("channel joint")?;
+        let path = match self.u8("channel path")? {
+            0 => Ne3dChannelPath::Translation,
+            1 => Ne3dChannelPath::Rotation,
+            2 => Ne3dChannelPath::Scale,
+            p => return Err(Ne3dError::Invalid(format!("unknown channel path {p}"))),
+        };
+        let interpolation = match self.u8("channel interpolation")? {
+            0 => Ne3dInterpolation::Linear,
+            1 => Ne3dInterpolation::Step,
+            i => return Err(Ne3dError::Invalid(format!("unknown interpolation {i}"))),
+        };
+        let key_count = self.u32("key_count")? as usize;
+        let times = self.array(key_count, "key times", |r| r.f32("key times"))?;
+        let values = self.array(key_count * path.width(), "key values", |r| {
+            r.f32("key values")
+        })?;
+        Ok(Ne3dChannel {
+            joint,
+            path,
+            interpolation,
+            times,
+            values,
+        })
+    }
+}
@@ -0,0 +1,293 @@
+use crate::source::AssetSource;
+use crate::types::AssetError;
+use log::info;
+use parking_lot::Mutex;
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+const PACK_MAGIC: &[u8; 4] = b"NEPK";
+
+/// Bumped whenever the layout changes; older packs are refused rather than misread.
+const PACK_VERSION: u32 = 1;
+
+/// Extension of pack files.
+pub const PACK_EXT: &str = "nepak";
+
+/// Magic, version, index offset, entry count.
+const HEADER_LEN: u64 = 4 + 4 + 8 + 4;
+
+/// Totals of a written pack.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PackStats {
+    pub entries: usize,
+    /// Payload bytes, without header and index.
+    pub bytes: u64,
+}
+
+#[derive(Debug, Clone)]
+struct PackEntry {
+    offset: u64,
+    len: u64,
+    hash: [u8; 32],
+}
+
+/// Writes a pack: file data first, then the index, then the header is patched to point at it.
+///
+/// Logical paths are stored `/`-separated, so a pack cooked on one platform reads on any.
+pub struct PackWriter {
+    path: PathBuf,
+    out: BufWriter<File>,
+    offset: u64,
+    index: Vec<(String, PackEntry)>,
+    seen: HashSet<String>,
+}
+
+impl PackWriter {
+    pub fn create(path: impl Into<PathBuf>) -> Result<Self, AssetError> {
+        let path = path.into();
+        let file = File::create(&path).map_err(|e| io_error("create", &path, e))?;
+        let mut out = BufWriter::new(file);
+        out.write_all(&[0u8; HEADER_LEN as usize])
+            .map_err(|e| io_error("write", &path, e))?;
+        Ok(Self {
+            path,
+            out,
+            offset: HEADER_LEN,
+            index: Vec::new(),
+            seen: HashSet::new(),
+        })
+    }
+
+    /// Appends one file. Adding the same logical path twice is an error.
+    pub fn add(&mut self, logical_path: &Path, bytes: &[u8]) -> Result<(), AssetError> {
+        let key = pack_key(logical_path);
+        if key.is_empty() {
+            return Err(AssetError::new("PackWriter: empty logical path"));
+        }
+        if !self.seen.insert(key.clone()) {
+            return Err(AssetError::new(format!(
+                "PackWriter: duplicate entry '{}' in '{}'",
+                key,
+                self.path.display()
+            )));
+        }
+
+        self.out
+            .write_all(bytes)
+            .map_err(|e| io_error("write", &self.path, e))?;
+        let entry = PackEntry {
+            offset: self.offset,
+            len: bytes.len() as u64,
+            hash: *blake3::hash(bytes).as_bytes(),
+        };
+        self.offset += entry.len;
+        self.index.push((key, entry));
+        Ok(())
+    }
+
+    /// Writes the index and header and closes the file.
+    pub fn finish(mut self) -> Result<PackStats, AssetError> {
+        let index_offset = self.offset;
+        let mut index = Vec::with_capacity(self.index.len() * 64);
+        for (key, e) in &self.index {
+            index.extend_from_slice(&(key.len() as u32).to_le_bytes());
+            index.extend_from_slice(key.as_bytes());
+            index.extend_from_slice(&e.offset.to_le_bytes());
+            index.extend_from_slice(&e.len.to_le_bytes());
+            index.extend_from_slice(&e.hash);
+        }
+
+        let mut header = Vec::with_capacity(HEADER_LEN as usize);
+        header.extend_from_slice(PACK_MAGIC);
+        header.extend_from_slice(&PACK_VERSION.to_le_bytes());
+        header.extend_from_slice(&index_offset.to_le_bytes());
+        header.extend_from_slice(&(self.index.len() as u32).to_le_bytes());
+
+        let path = self.path.clone();
+        let res: std::io::Result<()> = (|| {
+            self.out.write_all(&index)?;
+            self.out.seek(SeekFrom::Start(0))?;
+            self.out.write_all(&header)?;
+            self.out.flush()?;
+            self.out.get_ref().sync_all()
+        })();
+        res.map_err(|e| io_error("finish", &path, e))?;
+
+        let stats = PackStats {
+            entries: self.index.len(),
+            bytes: index_offset - HEADER_LEN,
+        };
+        info!(
+            target: "assets",
+            "pack.write path='{}' entries={} bytes={}",
+            path.display(),
+            stats.entries,
+            stats.bytes
+        );
+        Ok(stats)
+    }
+}
+
+/// Read-only source serving the files of one pack. Every read is checked against the hash
+/// stored at cook time, so a damaged pack fails loudly instead of feeding garbage to importers.
+pub struct PackSource {
+    path: PathBuf,
+    file: Mutex<File>,
+    entries: HashMap<String, PackEntry>,
+    modified: Option<SystemTime>,
+}
+
+impl PackSource {
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self, AssetError> {
+        let path = path.into();
+        let mut file = File::open(&path).map_err(|e| io_error("open", &path, e))?;
+        let meta = file.metadata().map_err(|e| io_error("stat", &path, e))?;
+        let corrupt = |what: &str| {
+            AssetError::new(format!(
+                "PackSource: corrupt pack '{}': {}",
+                path.display(),
+                what
+            ))
+        };
+
+        let mut header = [0u8; HEADER_LEN as usize];
+        file.read_exact(&mut header)
+            .map_err(|_| corrupt("truncated header"))?;
+        if &header[..4] != PACK_MAGIC {
+            return Err(corrupt("bad magic"));
+        }
+        let version = u32::from_le_bytes(header[4..8].try_into().unwrap_or_default());
+        if version != PACK_VERSION {
+            return Err(corrupt(&format!("unsupported version {version}")));
+        }
+        let index_offset = u64::from_le_bytes(header[8..16].try_into().unwrap_or_default());
+        let count = u32::from_le_bytes(header[16..20].try_into().unwrap_or_default()) as usize;
+        if index_offset < HEADER_LEN || index_offset > meta.len() {
+            return Err(corrupt("index out of range"));
+        }
+
+        let mut index = Vec::new();
+        file.seek(SeekFrom::Start(index_offset))
+            .and_then(|_| file.read_to_end(&mut index))
+            .map_err(|e| io_error("read", &path, e))?;
+
+        let mut entries = HashMap::with_capacity(count.min(1 << 16));
+        let mut r = &index[..];
+        for _ in 0..count {
+            let (key, entry) = read_entry(&mut r).ok_or_else(|| corrupt("truncated index"))?;
+            if entry.offset < HEADER_LEN || entry.offset.saturating_add(entry.len) > index_offset {
+                return Err(corrupt(&format!("entry '{key}' out of range")));
+            }
+            entries.insert(key, entry);
+        }
+
+        info!(
+            target: "assets",
+            "pack.open path='{}' entries={}",
+            path.display(),
+            entries.len()
+        );
+        Ok(Self {
+            path,
+            file: Mutex::new(file),
+            entries,
+            modified: meta.modified().ok(),
+        })
+    }
+
+    #[inline]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl AssetSource for PackSource {
+    #[inline]
+    fn exists(&self, logical_path: &Path) -> bool {
+        self.entries.contains_key(&pack_key(logical_path))
+    }
+
+    fn read(&self, logical_path: &Path) -> Result<Vec<u8>, AssetError> {
+        let key = pack_key(logical_path);
+        let Some(entry) = self.entries.get(&key) else {
+            return Err(AssetError::new(format!(
+                "PackSource: '{}' not in '{}'",
+                key,
+                self.path.display()
+            )));
+        };
+
+        let mut bytes = vec![0u8; entry.len as usize];
+        {
+            let mut f = self.file.lock();
+            f.seek(SeekFrom::Start(entry.offset))
+                .and_then(|_| f.read_exact(&mut bytes))
+                .map_err(|e| io_error("read", &self.path, e))?;
+        }
+        if blake3::hash(&bytes).as_bytes() != &entry.hash {
+            return Err(AssetError::new(format!(
+                "PackSource: corrupt entry '{}' in '{}' (hash mismatch)",
+                key,
+                self.path.display()
+            )));
+        }
+        Ok(bytes)
+    }
+
+    /// The pack's own time: its files change only when it is rebuilt.
+    #[inline]
+    fn modified(&self, logical_path: &Path) -> Option<SystemTime> {
+        self.modified.filter(|_| self.exists(logical_path))
+    }
+
+    fn list(&self) -> Vec<PathBuf> {
+        self.entries.keys().map(PathBuf::from).collect()
+    }
+}
+
+/// `/`-separated form of a logical path, as stored in the index.
+fn pack_key(logical_path: &Path) -> String {
+    logical_path
+        .components()
+        .filter_map(|c| match c {
+            std::path::Component::Normal(s) => Some(s.to_string_lossy()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn read_entry(r: &mut &[u8]) -> Option<(String, PackEntry)> {
+    fn take<'a>(r: &mut &'a [u8], n: usize) -> Option<&'a [u8]> {
+        if r.len() < n {
+            return None;
+        }
+        let (head, tail) = r.split_at(n);
+        *r = tail;
+        Some(head)
+    }
+
+    let len = u32::from_le_bytes(take(r, 4)?.try_into().ok()?) as usize;
+    let key = std::str::from_utf8(take(r, len)?).ok()?.to_owned();
+    let offset = u64::from_le_bytes(take(r, 8)?.try_into().ok()?);
+    let len = u64::from_le_bytes(take(r, 8)?.try_into().ok()?);
+    let hash: [u8; 32] = take(r, 32)?.try_into().ok()?;
+    Some((key, PackEntry { offset, len, hash }))
+}
+
+fn io_error(op: &str, path: &Path, e: std::io::Error) -> AssetError {
+    AssetError::new(format!("pack: failed to {op} '{}': {e}", path.display()))
+}
@@ -1,9 +1,31 @@
 use crate::types::AssetError;
 use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 
 pub trait AssetSource: Send + Sync + 'static {
     fn exists(&self, logical_path: &Path) -> bool;
     fn read(&self, logical_path: &Path) -> Result<Vec<u8>, AssetError>;
+
+    /// Last modification time, if the source can tell. Used for hot reload polling.
+    #[inline]
+    fn modified(&self, _logical_path: &Path) -> Option<SystemTime> {
+        None
+    }
+
+    /// Logical paths of every file the source can serve, for browsing. Sources that cannot
+    /// enumerate their contents keep the default.
+    fn list(&self) -> Vec<PathBuf> {
+        Vec::new()
+    }
+
+    /// Writes a file into the source; used for generated `.meta` sidecars.
+    /// Read-only sources keep the default.
+    fn write(&self, logical_path: &Path, _bytes: &[u8]) -> Result<(), AssetError> {
+        Err(AssetError::new(format!(
+            "source is read-only: '{}'",
+            logical_path.to_string_lossy()
+        )))
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -41,4 +63,54 @@ impl AssetSource for FileSystemSource {
             ))
         })
     }
-}
\ No newline at end of file
+
+    #[inline]
+    fn modified(&self, logical_path: &Path) -> Option<SystemTime> {
+        std::fs::metadata(self.resolve(logical_path))
+            .and_then(|m| m.modified())
+            .ok()
+    }
+
+    fn list(&self) -> Vec<PathBuf> {
+        let mut out = Vec::new();
+        let mut dirs = vec![self.root.clone()];
+        while let Some(dir) = dirs.pop() {
+            let Ok(rd) = std::fs::read_dir(&dir) else {
+                continue;
+            };
+            for entry in rd.flatten() {
+                let p = entry.path();
+                match entry.file_type() {
+                    Ok(t) if t.is_dir() => dirs.push(p),
+                    Ok(t) if t.is_file() => {
+                        if let Ok(rel) = p.strip_prefix(&self.root) {
+                            out.push(rel.to_path_buf());
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        out
+    }
+
+    fn write(&self, logical_path: &Path, bytes: &[u8]) -> Result<(), AssetError> {
+        let p = self.resolve(logical_path);
+        if let Some(dir) = p.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| {
+                AssetError::new(format!(
+                    "FileSystemSource: failed to create '{}': {}",
+                    dir.to_string_lossy(),
+                    e
+                ))
+            })?;
+        }
+        std::fs::write(&p, bytes).map_err(|e| {
+            AssetError::new(format!(
+                "FileSystemSource: failed to write '{}': {}",
+                p.to_string_lossy(),
+                e
+            ))
+        })
+    }
+}
@@ -1,13 +1,21 @@
-use crate::events::AssetEvent;
+use crate::cache::DerivedDataCache;
+use crate::events::{AssetEvent, AssetEventReceiver, EventQueue, ImportStage};
+use crate::handle::{LoadGroup, LoadGroupId, LoadHandle, LoadStatus};
 use crate::id::AssetId;
+use crate::meta::{meta_path, AssetMeta, ASSET_META_EXT};
 use crate::source::AssetSource;
-use crate::types::{AssetBlob, AssetError, AssetKey, AssetState, ImporterPriority};
+use crate::typed::{DecodedValue, DecoderRegistry};
+use crate::types::{
+    AssetBlob, AssetError, AssetKey, AssetState, CancelToken, ImporterPriority, LoadPriority,
+};
+use crate::vfs::{MountInfo, Vfs, GAME_MOUNT};
 use log::{debug, info, warn};
 use parking_lot::Mutex;
-use std::collections::{HashMap, VecDeque};
-use std::path::Path;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::any::TypeId;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Instant, SystemTime};
 
 #[derive(Debug, Clone, Copy)]
 pub struct PumpBudget {
@@ -27,6 +35,17 @@ impl PumpBudget {
 pub trait BlobImporterDispatch: Send + Sync + 'static {
     fn import_blob(&self, bytes: &[u8], key: &AssetKey) -> Result<AssetBlob, AssetError>;
 
+    /// Cancellation-aware import. Long-running importers should poll `cancel` and bail out early.
+    fn import_blob_cancellable(
+        &self,
+        bytes: &[u8],
+        key: &AssetKey,
+        cancel: &CancelToken,
+    ) -> Result<AssetBlob, AssetError> {
+        let _ = cancel;
+        self.import_blob(bytes, key)
+    }
+
     fn output_type_id(&self) -> Arc<str>;
     fn extensions(&self) -> Vec<String>;
 
@@ -37,6 +56,12 @@ pub trait BlobImporterDispatch: Send + Sync + 'static {
 
     /// Stable identifier for tie-break and diagnostics (e.g. "dds_importer@plugin:render").
     fn stable_id(&self) -> Arc<str>;
+
+    /// Default settings (JSON object) written to a new `.meta` sidecar on first import.
+    /// `None`: the importer takes no settings and no sidecar is generated.
+    fn default_settings(&self) -> Option<Arc<str>> {
+        None
+    }
 }
 
 struct PendingRequest {
@@ -45,6 +70,9 @@ struct PendingRequest {
     type_id: Arc<str>,
     importer: Arc<dyn BlobImporterDispatch>,
     importer_id: Arc<str>,
+    cancel: CancelToken,
+    priority: LoadPriority,
+    handle: LoadHandle,
 }
 
 impl std::fmt::Debug for PendingRequest {
@@ -54,6 +82,7 @@ impl std::fmt::Debug for PendingRequest {
             .field("key", &self.key)
             .field("type_id", &self.type_id)
             .field("importer_id", &self.importer_id)
+            .field("priority", &self.priority)
             .finish()
     }
 }
@@ -88,15 +117,37 @@ impl AssetDiagnostics {
     }
 }
 
+#[derive(Debug, Clone)]
+struct KnownPath {
+    canonical: String,
+    logical_path: PathBuf,
+    /// Other spellings already checked against the first one, so each is reported once.
+    aliases: Vec<PathBuf>,
+}
+
 #[derive(Default)]
 struct StoreInner {
-    sources: Vec<Arc<dyn AssetSource>>,
+    /// Copy-on-write so imports can read through a snapshot without holding the lock.
+    vfs: Arc<Vfs>,
     importers_by_ext: HashMap<String, Vec<Arc<dyn BlobImporterDispatch>>>,
     state: HashMap<AssetId, AssetState>,
     blobs: HashMap<AssetId, Arc<AssetBlob>>,
     queue: VecDeque<PendingRequest>,
-    events: VecDeque<AssetEvent>,
+    reloading: HashSet<AssetId>,
+    in_flight: HashMap<AssetId, CancelToken>,
+    /// Handle of each queued or running request, shared by everyone who asked for the asset.
+    handles: HashMap<AssetId, LoadHandle>,
+    /// Groups still waiting for members; completed ones are dropped after their event.
+    groups: Vec<LoadGroup>,
+    next_group: u64,
+    /// First requested spelling per id, used for alias/collision diagnostics and listings.
+    known_paths: HashMap<AssetId, KnownPath>,
+    events: EventQueue,
     diag: AssetDiagnostics,
+    cache: Option<Arc<DerivedDataCache>>,
+    decoders: DecoderRegistry,
+    /// Typed views per asset and Rust type, with the blob they were decoded from.
+    decoded: HashMap<(AssetId, TypeId), (Arc<AssetBlob>, DecodedValue)>,
 }
 
 #[derive(Default)]
@@ -110,10 +161,49 @@ impl AssetStore {
         Self::default()
     }
 
+    /// Pushes `source` onto the `game://` mount.
     #[inline]
     pub fn add_source(&self, source: Arc<dyn AssetSource>) {
+        let _ = self.add_source_to(GAME_MOUNT, source);
+    }
+
+    /// Pushes `source` onto mount `mount`; it overlays sources added there before.
+    pub fn add_source_to(
+        &self,
+        mount: &str,
+        source: Arc<dyn AssetSource>,
+    ) -> Result<(), AssetError> {
         let mut g = self.inner.lock();
-        g.sources.push(source);
+        Arc::make_mut(&mut g.vfs).add_source(mount, source)?;
+        info!(target: "assets", "vfs.source.add mount='{}'", mount);
+        Ok(())
+    }
+
+    /// Creates a mount point, or changes the priority of an existing one. Plain logical paths
+    /// resolve through mounts from the highest priority down.
+    pub fn mount(&self, name: &str, priority: i32) -> Result<(), AssetError> {
+        let mut g = self.inner.lock();
+        Arc::make_mut(&mut g.vfs).mount(name, priority)?;
+        info!(target: "assets", "vfs.mount name='{}' priority={}", name, priority);
+        Ok(())
+    }
+
+    pub fn mounts(&self) -> Vec<MountInfo> {
+        let g = self.inner.lock();
+        g.vfs.mounts()
+    }
+
+    /// Enables the derived-data cache; `None` turns it off.
+    #[inline]
+    pub fn set_cache(&self, cache: Option<Arc<DerivedDataCache>>) {
+        let mut g = self.inner.lock();
+        g.cache = cache;
+    }
+
+    #[inline]
+    pub fn cache(&self) -> Option<Arc<DerivedDataCache>> {
+        let g = self.inner.lock();
+        g.cache.clone()
     }
 
     pub fn add_importer(&self, importer: Arc<dyn BlobImporterDispatch>) {
@@ -173,6 +263,23 @@ impl AssetStore {
         out
     }
 
+    /// Every registered importer once, whatever extensions it is bound to; lets a second
+    /// store (an export cook) import with the same set.
+    pub fn importers(&self) -> Vec<Arc<dyn BlobImporterDispatch>> {
+        let g = self.inner.lock();
+        let mut seen = HashSet::new();
+        let mut out = Vec::new();
+        for list in g.importers_by_ext.values() {
+            for imp in list {
+                if seen.insert(imp.stable_id()) {
+                    out.push(imp.clone());
+                }
+            }
+        }
+        out.sort_by_key(|imp| imp.stable_id());
+        out
+    }
+
     #[inline]
     pub fn state(&self, id: AssetId) -> AssetState {
         let g = self.inner.lock();
@@ -185,26 +292,155 @@ impl AssetStore {
         g.blobs.get(&id).cloned()
     }
 
+    /// Registers a typed decoder for blobs of `type_id` (and `format`; `None` for any).
+    pub fn register_decoder<T, F>(&self, type_id: &str, format: Option<&str>, decode: F)
+    where
+        T: Send + Sync + 'static,
+        F: Fn(&AssetBlob) -> Result<T, AssetError> + Send + Sync + 'static,
+    {
+        let mut g = self.inner.lock();
+        g.decoders.register(type_id, format, decode);
+        g.decoded.retain(|(_, t), _| *t != TypeId::of::<T>());
+    }
+
+    /// Typed view of a ready asset, e.g. `get_typed::<MeshAsset>(id)`.
+    ///
+    /// Decoded values are cached per asset and type, separately from the raw blob, and
+    /// dropped when the blob is replaced by a reload.
+    pub fn get_typed<T: Send + Sync + 'static>(&self, id: AssetId) -> Result<Arc<T>, AssetError> {
+        let (blob, decode) = {
+            let g = self.inner.lock();
+            let Some(blob) = g.blobs.get(&id).cloned() else {
+                return Err(AssetError::new(format!(
+                    "AssetStore: asset {:032x} is not ready",
+                    id.to_u128()
+                )));
+            };
+            if let Some((src, value)) = g.decoded.get(&(id, TypeId::of::<T>())) {
+                if Arc::ptr_eq(src, &blob) {
+                    if let Ok(v) = value.clone().downcast::<T>() {
+                        return Ok(v);
+                    }
+                }
+            }
+            let decode = g.decoders.decoder_for::<T>(&blob)?;
+            (blob, decode)
+        };
+
+        let value = decode(&blob)?;
+        let typed = value
+            .clone()
+            .downcast::<T>()
+            .map_err(|_| AssetError::new("AssetStore: decoder returned an unexpected type"))?;
+
+        debug!(
+            target: "assets",
+            "asset.decoded id={:032x} as='{}'",
+            id.to_u128(),
+            std::any::type_name::<T>()
+        );
+
+        let mut g = self.inner.lock();
+        if g.blobs.get(&id).is_some_and(|b| Arc::ptr_eq(b, &blob)) {
+            g.decoded.insert((id, TypeId::of::<T>()), (blob, value));
+        }
+        Ok(typed)
+    }
+
     #[inline]
     pub fn drain_events(&self) -> Vec<AssetEvent> {
         let mut g = self.inner.lock();
-        g.events.drain(..).collect()
+        g.events.drain()
     }
 
+    /// Separate event stream, for consumers that must not steal events from `drain_events`.
+    #[inline]
+    pub fn subscribe_events(&self) -> AssetEventReceiver {
+        self.inner.lock().events.subscribe()
+    }
+
+    #[inline]
     pub fn load(&self, key: AssetKey) -> Result<AssetId, AssetError> {
+        self.load_with_priority(key, LoadPriority::Normal)
+            .map(|h| h.id())
+    }
+
+    /// Enqueues `key` ahead of every queued request with a lower priority.
+    ///
+    /// Asking again for an asset that is still queued returns the same handle and raises its
+    /// priority if the new one is higher. Ready and failed assets get an already-finished handle.
+    pub fn load_with_priority(
+        &self,
+        key: AssetKey,
+        priority: LoadPriority,
+    ) -> Result<LoadHandle, AssetError> {
         let id = key.id();
 
         info!(
             target: "assets",
-            "asset.load request id={:032x} path='{}'",
+            "asset.load request id={:032x} path='{}' priority={}",
             id.to_u128(),
-            key.logical_path.display()
+            key.logical_path.display(),
+            priority.as_str()
         );
 
         let mut g = self.inner.lock();
+
+        let canonical = key.canonical_path();
+        match g.known_paths.get(&id).cloned() {
+            Some(known) if known.canonical != canonical => {
+                return Err(collision(id, &known.logical_path, &key.logical_path));
+            }
+            Some(known)
+                if known.logical_path != key.logical_path
+                    && !known.aliases.contains(&key.logical_path) =>
+            {
+                // Spellings that fold to one id are only an alias if they name the same
+                // file; two real files ("Tex.png", "tex.png") would silently share an asset.
+                let vfs = g.vfs.clone();
+                drop(g);
+                if distinct_files(&vfs, &known.logical_path, &key.logical_path) {
+                    return Err(collision(id, &known.logical_path, &key.logical_path));
+                }
+                warn!(
+                    target: "assets",
+                    "asset.key alias id={:032x} first='{}' now='{}' (same asset, inconsistent spelling)",
+                    id.to_u128(),
+                    known.logical_path.display(),
+                    key.logical_path.display()
+                );
+                g = self.inner.lock();
+                if let Some(k) = g.known_paths.get_mut(&id) {
+                    k.aliases.push(key.logical_path.clone());
+                }
+            }
+            Some(_) => {}
+            None => {
+                g.known_paths.insert(
+                    id,
+                    KnownPath {
+                        canonical,
+                        logical_path: key.logical_path.clone(),
+                        aliases: Vec::new(),
+                    },
+                );
+            }
+        }
+
         match g.state.get(&id) {
-            Some(AssetState::Ready) | Some(AssetState::Loading) | Some(AssetState::Failed(_)) => {
-                return Ok(id)
+            Some(AssetState::Ready) => return Ok(LoadHandle::new(id, LoadStatus::Ready, priority)),
+            Some(AssetState::Failed(_)) => {
+                return Ok(LoadHandle::new(id, LoadStatus::Failed, priority))
+            }
+            Some(AssetState::Loading) => {
+                if let Some(handle) = g.handles.get(&id).cloned() {
+                    if priority > handle.priority() {
+                        promote(&mut g.queue, id, priority);
+                        handle.set_priority(priority);
+                    }
+                    return Ok(handle);
+                }
+                return Ok(LoadHandle::new(id, LoadStatus::Queued, priority));
             }
             _ => {}
         }
@@ -252,15 +488,104 @@ impl AssetStore {
 
         g.state.insert(id, AssetState::Loading);
         let importer_id = importer.stable_id();
-        g.queue.push_back(PendingRequest {
+        let cancel = CancelToken::new();
+        g.in_flight.insert(id, cancel.clone());
+        let handle = LoadHandle::new(id, LoadStatus::Queued, priority);
+        g.handles.insert(id, handle.clone());
+        enqueue(
+            &mut g.queue,
+            PendingRequest {
+                id,
+                key,
+                type_id,
+                importer,
+                importer_id,
+                cancel,
+                priority,
+                handle: handle.clone(),
+            },
+        );
+        g.events.push_back(AssetEvent::Progress {
             id,
-            key,
-            type_id,
-            importer,
-            importer_id,
+            stage: ImportStage::Queued,
+            bytes_read: 0,
         });
 
-        Ok(id)
+        Ok(handle)
+    }
+
+    /// Loads every key with `priority` and tracks them as one group; `AssetEvent::GroupCompleted`
+    /// follows from `pump` once all members are done.
+    ///
+    /// A key that cannot be enqueued (no importer, bad path) counts as failed rather than
+    /// failing the whole group, so a loading screen always gets its completion event.
+    pub fn load_group<I>(&self, name: &str, keys: I, priority: LoadPriority) -> LoadGroup
+    where
+        I: IntoIterator<Item = AssetKey>,
+    {
+        let handles: Vec<LoadHandle> = keys
+            .into_iter()
+            .map(|key| {
+                let id = key.id();
+                self.load_with_priority(key, priority).unwrap_or_else(|e| {
+                    warn!(
+                        target: "assets",
+                        "group.member_failed group='{}' id={:032x} error='{}'",
+                        name,
+                        id.to_u128(),
+                        e
+                    );
+                    LoadHandle::new(id, LoadStatus::Failed, priority)
+                })
+            })
+            .collect();
+
+        let mut g = self.inner.lock();
+        g.next_group += 1;
+        let group = LoadGroup::new(LoadGroupId(g.next_group), Arc::from(name), handles);
+        g.groups.push(group.clone());
+
+        info!(
+            target: "assets",
+            "group.start id={} name='{}' members={} priority={}",
+            group.id().to_u64(),
+            name,
+            group.handles().len(),
+            priority.as_str()
+        );
+        group
+    }
+
+    /// Emits `GroupCompleted` for every group whose members are all done.
+    fn complete_groups(&self) {
+        let mut g = self.inner.lock();
+        if g.groups.is_empty() {
+            return;
+        }
+
+        let (done, pending): (Vec<LoadGroup>, Vec<LoadGroup>) =
+            std::mem::take(&mut g.groups).into_iter().partition(LoadGroup::is_done);
+        g.groups = pending;
+
+        for group in done {
+            let (ready, failed, cancelled) = group.outcome();
+            info!(
+                target: "assets::events",
+                "group.completed id={} name='{}' ready={} failed={} cancelled={}",
+                group.id().to_u64(),
+                group.name(),
+                ready,
+                failed,
+                cancelled
+            );
+            g.events.push_back(AssetEvent::GroupCompleted {
+                group: group.id(),
+                name: group.name().clone(),
+                ready,
+                failed,
+                cancelled,
+            });
+        }
     }
 
     pub fn pump(&self, budget: PumpBudget) {
@@ -288,9 +613,19 @@ impl AssetStore {
             }
 
             if let Err(err) = self.process_one(req) {
+                if err.cancelled {
+                    self.finish_cancelled(err.id);
+                    continue;
+                }
+
                 {
                     let mut g = self.inner.lock();
                     g.diag.pump_failed += 1;
+                    g.reloading.remove(&err.id);
+                    g.in_flight.remove(&err.id);
+                    if let Some(h) = g.handles.remove(&err.id) {
+                        h.set_status(LoadStatus::Failed);
+                    }
                     g.state.insert(err.id, AssetState::Failed(err.error.clone()));
                     g.events.push_back(AssetEvent::Failed {
                         id: err.id,
@@ -309,6 +644,8 @@ impl AssetStore {
             }
         }
 
+        self.complete_groups();
+
         let dt = pump_t0.elapsed();
         let (total, ok, fail, bytes, io_us, imp_us) = {
             let g = self.inner.lock();
@@ -338,21 +675,20 @@ impl AssetStore {
     }
 
     fn process_one(&self, req: PendingRequest) -> Result<(), ProcessError> {
-        let sources = {
+        let (vfs, cache) = {
             let g = self.inner.lock();
-            g.sources.clone()
+            (g.vfs.clone(), g.cache.clone())
         };
 
-        let importer = req.importer;
+        let importer = req.importer.clone();
+
+        req.check_cancelled()?;
+        self.push_progress(req.id, ImportStage::Reading, 0);
 
         let io_t0 = Instant::now();
-        let bytes = read_from_any_source_list(&sources, &req.key.logical_path).map_err(|e| {
-            ProcessError {
-                id: req.id,
-                type_id: req.type_id.clone(),
-                error: Arc::from(e.msg().to_string()),
-            }
-        })?;
+        let bytes = vfs
+            .read(&req.key.logical_path)
+            .map_err(|e| ProcessError::failed(req.id, &req.type_id, e.msg()))?;
         let io_dt = io_t0.elapsed();
 
         {
@@ -361,6 +697,13 @@ impl AssetStore {
             g.diag.io_time_us += io_dt.as_micros() as u64;
         }
 
+        let key = self
+            .key_with_import_settings(&vfs, &req.key, importer.as_ref())
+            .map_err(|e| ProcessError::failed(req.id, &req.type_id, e.msg()))?;
+
+        req.check_cancelled()?;
+        self.push_progress(req.id, ImportStage::Importing, bytes.len() as u64);
+
         debug!(
             target: "assets::io",
             "io.read id={:032x} path='{}' bytes={} dt_us={}",
@@ -370,14 +713,51 @@ impl AssetStore {
             io_dt.as_micros()
         );
 
+        let cache_key = cache.as_ref().map(|_| {
+            DerivedDataCache::key(&bytes, &importer.stable_id(), key.import_settings.as_deref())
+        });
+        let cached = cache.as_ref().zip(cache_key.as_deref()).and_then(|(c, k)| c.get(k));
+
         let imp_t0 = Instant::now();
-        let blob = importer.import_blob(&bytes, &req.key).map_err(|e| ProcessError {
-            id: req.id,
-            type_id: req.type_id.clone(),
-            error: Arc::from(e.msg().to_string()),
-        })?;
+        let blob = match cached {
+            Some(blob) => {
+                debug!(
+                    target: "assets::cache",
+                    "cache.hit id={:032x} path='{}' key={}",
+                    req.id.to_u128(),
+                    req.key.logical_path.display(),
+                    cache_key.as_deref().unwrap_or_default()
+                );
+                blob
+            }
+            None => {
+                let blob = importer
+                    .import_blob_cancellable(&bytes, &key, &req.cancel)
+                    .map_err(|e| {
+                        if req.cancel.is_cancelled() {
+                            ProcessError::cancelled(req.id, &req.type_id)
+                        } else {
+                            ProcessError::failed(req.id, &req.type_id, e.msg())
+                        }
+                    })?;
+                if let (Some(c), Some(k)) = (cache.as_ref(), cache_key.as_deref()) {
+                    if let Err(e) = c.put(k, &blob) {
+                        warn!(
+                            target: "assets::cache",
+                            "cache.write_failed path='{}' reason='{}'",
+                            req.key.logical_path.display(),
+                            e
+                        );
+                    }
+                }
+                blob
+            }
+        };
         let imp_dt = imp_t0.elapsed();
 
+        // A cancel that lands during import discards the result.
+        req.check_cancelled()?;
+
         {
             let mut g = self.inner.lock();
             g.diag.import_time_us += imp_dt.as_micros() as u64;
@@ -400,13 +780,26 @@ impl AssetStore {
         {
             let mut g = self.inner.lock();
             g.diag.pump_success += 1;
+            g.in_flight.remove(&req.id);
+            g.handles.remove(&req.id);
+            req.handle.set_status(LoadStatus::Ready);
+            g.decoded.retain(|(id, _), _| *id != req.id);
             g.blobs.insert(req.id, blob);
             g.state.insert(req.id, AssetState::Ready);
-            g.events.push_back(AssetEvent::Ready {
-                id: req.id,
-                type_id: req.type_id.clone(),
-                format: format.clone(),
-            });
+            let ev = if g.reloading.remove(&req.id) {
+                AssetEvent::Reloaded {
+                    id: req.id,
+                    type_id: req.type_id.clone(),
+                    format: format.clone(),
+                }
+            } else {
+                AssetEvent::Ready {
+                    id: req.id,
+                    type_id: req.type_id.clone(),
+                    format: format.clone(),
+                }
+            };
+            g.events.push_back(ev);
         }
 
         info!(
@@ -422,14 +815,122 @@ impl AssetStore {
     }
 }
 
+impl AssetStore {
+    /// Attaches the `.meta` sidecar settings to `key`.
+    ///
+    /// Without a sidecar the importer's defaults are used and written next to the asset, so
+    /// the file shows up for editing after the first import. A sidecar that fails to parse
+    /// fails the import rather than silently falling back to defaults.
+    fn key_with_import_settings(
+        &self,
+        vfs: &Vfs,
+        key: &AssetKey,
+        importer: &dyn BlobImporterDispatch,
+    ) -> Result<AssetKey, AssetError> {
+        let path = meta_path(&key.logical_path);
+
+        if vfs.exists(&path) {
+            let meta = AssetMeta::parse(&vfs.read(&path)?).map_err(|e| {
+                AssetError::new(format!("{}: {}", path.to_string_lossy(), e.msg()))
+            })?;
+            return Ok(key.clone().with_import_settings(meta.settings_json()));
+        }
+
+        let Some(defaults) = importer.default_settings() else {
+            return Ok(key.clone());
+        };
+        let meta = AssetMeta::with_defaults(&importer.stable_id(), &defaults)?;
+
+        if vfs.exists(&key.logical_path) {
+            match vfs.write_beside(&key.logical_path, meta_path, &meta.to_bytes()) {
+                Ok(()) => info!(
+                    target: "assets",
+                    "meta.generated path='{}' importer='{}'",
+                    path.display(),
+                    importer.stable_id()
+                ),
+                Err(e) => debug!(
+                    target: "assets",
+                    "meta.skipped path='{}' reason='{}'",
+                    path.display(),
+                    e
+                ),
+            }
+        }
+
+        Ok(key.clone().with_import_settings(meta.settings_json()))
+    }
+}
+
 #[derive(Debug)]
 struct ProcessError {
     id: AssetId,
     type_id: Arc<str>,
     error: Arc<str>,
+    cancelled: bool,
+}
+
+impl ProcessError {
+    #[inline]
+    fn failed(id: AssetId, type_id: &Arc<str>, error: &str) -> Self {
+        Self {
+            id,
+            type_id: type_id.clone(),
+            error: Arc::from(error),
+            cancelled: false,
+        }
+    }
+
+    #[inline]
+    fn cancelled(id: AssetId, type_id: &Arc<str>) -> Self {
+        Self {
+            id,
+            type_id: type_id.clone(),
+            error: Arc::from("cancelled"),
+            cancelled: true,
+        }
+    }
+}
+
+impl PendingRequest {
+    #[inline]
+    fn check_cancelled(&self) -> Result<(), ProcessError> {
+        if self.cancel.is_cancelled() {
+            return Err(ProcessError::cancelled(self.id, &self.type_id));
+        }
+        Ok(())
+    }
 }
 
 #[inline]
+fn collision(id: AssetId, first: &Path, now: &Path) -> AssetError {
+    warn!(
+        target: "assets",
+        "asset.key collision id={:032x} first='{}' now='{}'",
+        id.to_u128(),
+        first.display(),
+        now.display()
+    );
+    AssetError::new(format!(
+        "AssetStore: asset id collision between '{}' and '{}'",
+        first.display(),
+        now.display()
+    ))
+}
+
+/// True when `a` and `b` are served by different files. A case-insensitive filesystem lists a
+/// file under one spelling only, so `a`/`b` differing in case both appear only if both exist.
+fn distinct_files(vfs: &Vfs, a: &Path, b: &Path) -> bool {
+    let (Some((sa, ia)), Some((sb, ib))) = (vfs.resolve(a), vfs.resolve(b)) else {
+        return false;
+    };
+    if !Arc::ptr_eq(sa, sb) {
+        return true;
+    }
+    let listed = sa.list();
+    listed.contains(&ia) && listed.contains(&ib)
+}
+
 fn extension_ascii_lower(p: &Path) -> Option<String> {
     let ext = p.extension()?.to_string_lossy();
     if ext.is_empty() {
@@ -443,27 +944,6 @@ fn normalize_ext(ext: &str) -> String {
     ext.trim().trim_start_matches('.').to_ascii_lowercase()
 }
 
-#[inline]
-fn read_from_any_source_list(
-    sources: &[Arc<dyn AssetSource>],
-    logical_path: &Path,
-) -> Result<Vec<u8>, AssetError> {
-    if sources.is_empty() {
-        return Err(AssetError::new("AssetStore: no sources registered"));
-    }
-
-    for s in sources {
-        if s.exists(logical_path) {
-            return s.read(logical_path);
-        }
-    }
-
-    Err(AssetError::new(format!(
-        "AssetStore: asset not found in any source: '{}'",
-        logical_path.to_string_lossy()
-    )))
-}
-
 /// Utility: stable single-line preview for logs/UI.
 ///
 /// - Limits by char count (not bytes)
@@ -525,7 +1005,7 @@ impl AssetStore {
     pub fn stats_snapshot(&self) -> AssetStoreStats {
         let g = self.inner.lock();
 
-        let sources = g.sources.len();
+        let sources = g.vfs.source_count();
         let importers = g.importers_by_ext.values().map(|v| v.len()).sum::<usize>();
         let importers_bindings = g
             .importers_by_ext
@@ -557,12 +1037,33 @@ impl AssetStore {
     /// Lists assets currently known to the store (state map), limited by `limit`.
     /// Intended for console/UI; avoids exposing internal types.
     pub fn list_snapshot(&self, limit: usize) -> Vec<AssetEntrySnapshot> {
-        let g = self.inner.lock();
+        self.list_page(None, limit).0
+    }
 
-        let mut out = Vec::new();
-        out.reserve(g.state.len().min(limit));
+    /// Lists assets in id order after `after`, at most `limit` of them, plus the id to pass
+    /// as `after` for the next page (`None` on the last page).
+    pub fn list_page(
+        &self,
+        after: Option<u128>,
+        limit: usize,
+    ) -> (Vec<AssetEntrySnapshot>, Option<u128>) {
+        let g = self.inner.lock();
 
-        for (id, st) in g.state.iter().take(limit) {
+        let mut ids: Vec<AssetId> = g
+            .state
+            .keys()
+            .filter(|id| after.is_none_or(|a| id.to_u128() > a))
+            .copied()
+            .collect();
+        ids.sort_unstable();
+        let more = ids.len() > limit;
+        ids.truncate(limit);
+
+        let mut out = Vec::with_capacity(ids.len());
+        for id in ids.iter() {
+            let Some(st) = g.state.get(id) else {
+                continue;
+            };
             let id_u128 = id.to_u128();
 
             let (type_id, format, bytes) = match g.blobs.get(id) {
@@ -581,12 +1082,15 @@ impl AssetStore {
                 crate::types::AssetState::Failed(e) => format!("failed: {}", e),
             };
 
-            // Path is not stored in the state map. We can only show id-based entry here.
-            // The canonical path is available at load-time via AssetKey, but not retained.
-            // For console use, we support list by id and state; path is available via commands like asset.load/reload.
+            let path = g
+                .known_paths
+                .get(id)
+                .map(|k| k.logical_path.to_string_lossy().replace('\\', "/"))
+                .unwrap_or_default();
+
             out.push(AssetEntrySnapshot {
                 id_u128,
-                path: String::new(),
+                path,
                 state: state_str,
                 type_id,
                 format,
@@ -594,7 +1098,8 @@ impl AssetStore {
             });
         }
 
-        out
+        let next = if more { ids.last().map(|id| id.to_u128()) } else { None };
+        (out, next)
     }
 
     /// Convenience: enqueue load by logical path with settings_hash=0.
@@ -606,6 +1111,7 @@ impl AssetStore {
     /// Convenience: attempt "reload" semantics:
     /// - mark asset Unloaded and drop cached blob (if any)
     /// - enqueue new load
+    /// - completion is reported as `AssetEvent::Reloaded`
     pub fn reload_path(&self, logical_path: &str) -> Result<crate::id::AssetId, crate::types::AssetError> {
         let key = AssetKey::new(logical_path, 0);
         let id = key.id();
@@ -613,10 +1119,68 @@ impl AssetStore {
         {
             let mut g = self.inner.lock();
             g.blobs.remove(&id);
+            g.decoded.retain(|(i, _), _| *i != id);
             g.state.insert(id, crate::types::AssetState::Unloaded);
+            g.reloading.insert(id);
         }
 
-        self.load(key)
+        self.load(key).inspect_err(|_| {
+            self.inner.lock().reloading.remove(&id);
+        })
+    }
+
+    /// Raw bytes of `logical_path` from the source that serves it, without importing or
+    /// caching; for small text files read synchronously (console `.cfg` scripts).
+    pub fn read_source(&self, logical_path: &str) -> Result<Vec<u8>, AssetError> {
+        let vfs = {
+            let g = self.inner.lock();
+            g.vfs.clone()
+        };
+        vfs.read(Path::new(logical_path))
+    }
+
+    /// Modification time of `logical_path` in the source that serves it, or of its `.meta`
+    /// sidecar when that is newer (settings edits count as changes for hot reload).
+    ///
+    /// `None` when no source has the file or the source does not track times.
+    pub fn source_modified(&self, logical_path: &str) -> Option<SystemTime> {
+        let vfs = {
+            let g = self.inner.lock();
+            g.vfs.clone()
+        };
+
+        let modified = |path: &Path| vfs.modified(path);
+        let path = Path::new(logical_path);
+        let asset = modified(path)?;
+        Some(modified(&meta_path(path)).map_or(asset, |m| m.max(asset)))
+    }
+
+    /// Logical paths (`/`-separated) of every file across all mounts, sorted and without
+    /// duplicates; overridden files appear once. `.meta` sidecars are left out; they belong
+    /// to the asset next to them.
+    pub fn source_paths(&self) -> Vec<String> {
+        let vfs = {
+            let g = self.inner.lock();
+            g.vfs.clone()
+        };
+
+        let mut out: Vec<String> = vfs
+            .list()
+            .into_iter()
+            .filter(|p| {
+                !p.extension()
+                    .is_some_and(|e| e.eq_ignore_ascii_case(ASSET_META_EXT))
+            })
+            .map(|p| {
+                p.components()
+                    .map(|c| c.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/")
+            })
+            .collect();
+        out.sort();
+        out.dedup();
+        out
     }
 
     /// Returns the current queue length (for console/UI).
@@ -626,3 +1190,83 @@ impl AssetStore {
         g.queue.len()
     }
 }
+
+impl AssetStore {
+    /// Requests cancellation of a queued or running import.
+    ///
+    /// Queued requests are dropped immediately; a running import stops at its next stage
+    /// boundary, or earlier when the importer polls the token (plugin importers do through
+    /// `HostApiV1::import_cancelled`). The asset returns to `Unloaded` and
+    /// `AssetEvent::Cancelled` is emitted. Returns false if nothing was pending.
+    pub fn cancel(&self, id: AssetId) -> bool {
+        let dequeued = {
+            let mut g = self.inner.lock();
+            let Some(token) = g.in_flight.get(&id).cloned() else {
+                return false;
+            };
+            token.cancel();
+
+            let before = g.queue.len();
+            g.queue.retain(|r| r.id != id);
+            g.queue.len() != before
+        };
+
+        info!(target: "assets", "asset.cancel id={:032x} queued={}", id.to_u128(), dequeued);
+
+        if dequeued {
+            self.finish_cancelled(id);
+        }
+        true
+    }
+
+    /// Convenience: cancel by logical path with settings_hash=0.
+    #[inline]
+    pub fn cancel_path(&self, logical_path: &str) -> bool {
+        self.cancel(AssetKey::new(logical_path, 0).id())
+    }
+
+    fn finish_cancelled(&self, id: AssetId) {
+        let mut g = self.inner.lock();
+        g.in_flight.remove(&id);
+        g.reloading.remove(&id);
+        if let Some(h) = g.handles.remove(&id) {
+            h.set_status(LoadStatus::Cancelled);
+        }
+        g.state.insert(id, AssetState::Unloaded);
+        g.events.push_back(AssetEvent::Cancelled { id });
+    }
+
+    #[inline]
+    fn push_progress(&self, id: AssetId, stage: ImportStage, bytes_read: u64) {
+        let mut g = self.inner.lock();
+        if let Some(h) = g.handles.get(&id) {
+            h.set_status(stage.into());
+            h.set_bytes_read(bytes_read);
+        }
+        g.events.push_back(AssetEvent::Progress {
+            id,
+            stage,
+            bytes_read,
+        });
+    }
+}
+
+/// Inserts behind every request of the same or higher priority (FIFO within a class).
+fn enqueue(queue: &mut VecDeque<PendingRequest>, req: PendingRequest) {
+    let at = queue
+        .iter()
+        .position(|r| r.priority < req.priority)
+        .unwrap_or(queue.len());
+    queue.insert(at, req);
+}
+
+/// Moves a queued request up to `priority`. A request already running is left alone.
+fn promote(queue: &mut VecDeque<PendingRequest>, id: AssetId, priority: LoadPriority) {
+    let Some(pos) = queue.iter().position(|r| r.id == id) else {
+        return;
+    };
+    if let Some(mut req) = queue.remove(pos) {
+        req.priority = priority;
+        enqueue(queue, req);
+    }
+}
@@ -69,4 +69,173 @@ impl Asset for TextureAsset {
     fn type_name() -> &'static str {
         "TextureAsset"
     }
-}
\ No newline at end of file
+}
+impl TextureFormat {
+    /// Bytes per 4x4 block for BCn, per pixel for `Rgba8Unorm`.
+    #[inline]
+    fn unit_bytes(self) -> usize {
+        match self {
+            TextureFormat::Rgba8Unorm => 4,
+            TextureFormat::Bc1RgbUnorm
+            | TextureFormat::Bc1RgbaUnorm
+            | TextureFormat::Bc4Unorm => 8,
+            TextureFormat::Bc2Unorm
+            | TextureFormat::Bc3Unorm
+            | TextureFormat::Bc5Unorm
+            | TextureFormat::Bc7Unorm => 16,
+        }
+    }
+
+    /// Byte size of one `width`x`height` slice.
+    #[inline]
+    pub fn slice_bytes(self, width: u32, height: u32) -> usize {
+        let (w, h) = (width.max(1) as usize, height.max(1) as usize);
+        match self {
+            TextureFormat::Rgba8Unorm => w * h * 4,
+            _ => w.div_ceil(4) * h.div_ceil(4) * self.unit_bytes(),
+        }
+    }
+}
+
+const DDS_HEADER_BYTES: usize = 128;
+const DDS_DX10_BYTES: usize = 20;
+const DDPF_FOURCC: u32 = 0x4;
+const DDPF_RGB: u32 = 0x40;
+const DDSCAPS2_CUBEMAP: u32 = 0x200;
+const DDSCAPS2_VOLUME: u32 = 0x20_0000;
+const DX10_MISC_TEXTURECUBE: u32 = 0x4;
+
+/// How texels are stored relative to [`TextureFormat`]; BGRA is swizzled on load.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum DdsLayout {
+    Native,
+    Bgra8,
+}
+
+impl TextureAsset {
+    /// Parses a DDS container (BC1-5/BC7, RGBA8/BGRA8, cubemaps, arrays, volumes).
+    ///
+    /// BGRA8 data is swizzled to RGBA8 so every uncompressed result is `Rgba8Unorm`.
+    pub fn from_dds(bytes: &[u8]) -> Result<Self, String> {
+        if bytes.len() < DDS_HEADER_BYTES || &bytes[0..4] != b"DDS " {
+            return Err("dds: bad magic".to_owned());
+        }
+        let u32_at =
+            |o: usize| u32::from_le_bytes([bytes[o], bytes[o + 1], bytes[o + 2], bytes[o + 3]]);
+
+        let height = u32_at(12);
+        let width = u32_at(16);
+        let caps2 = u32_at(112);
+        let volume = caps2 & DDSCAPS2_VOLUME != 0;
+        let depth = if volume { u32_at(24).max(1) } else { 1 };
+        let mip_count = u32_at(28).max(1);
+        let pf_flags = u32_at(80);
+        let fourcc = &bytes[84..88];
+
+        let mut data_at = DDS_HEADER_BYTES;
+        let mut layers = 1u32;
+        let mut cube = caps2 & DDSCAPS2_CUBEMAP != 0;
+
+        let (format, layout) = if pf_flags & DDPF_FOURCC != 0 && fourcc == b"DX10" {
+            if bytes.len() < DDS_HEADER_BYTES + DDS_DX10_BYTES {
+                return Err("dds: truncated DX10 header".to_owned());
+            }
+            data_at += DDS_DX10_BYTES;
+            cube |= u32_at(136) & DX10_MISC_TEXTURECUBE != 0;
+            layers = u32_at(140).max(1);
+            dxgi_format(u32_at(128))?
+        } else if pf_flags & DDPF_FOURCC != 0 {
+            let f = match fourcc {
+                b"DXT1" => TextureFormat::Bc1RgbaUnorm,
+                b"DXT2" | b"DXT3" => TextureFormat::Bc2Unorm,
+                b"DXT4" | b"DXT5" => TextureFormat::Bc3Unorm,
+                b"ATI1" | b"BC4U" => TextureFormat::Bc4Unorm,
+                b"ATI2" | b"BC5U" => TextureFormat::Bc5Unorm,
+                other => {
+                    return Err(format!(
+                        "dds: unsupported fourcc '{}'",
+                        String::from_utf8_lossy(other)
+                    ))
+                }
+            };
+            (f, DdsLayout::Native)
+        } else if pf_flags & DDPF_RGB != 0 && u32_at(88) == 32 {
+            match (u32_at(92), u32_at(100)) {
+                (0xff, 0xff_0000) => (TextureFormat::Rgba8Unorm, DdsLayout::Native),
+                (0xff_0000, 0xff) => (TextureFormat::Rgba8Unorm, DdsLayout::Bgra8),
+                _ => return Err("dds: unsupported 32-bit channel masks".to_owned()),
+            }
+        } else {
+            return Err("dds: unsupported pixel format".to_owned());
+        };
+
+        if cube {
+            layers *= 6;
+        }
+
+        let mut mips: Vec<TextureMip> = (0..mip_count)
+            .map(|m| TextureMip {
+                width: (width >> m).max(1),
+                height: (height >> m).max(1),
+                depth: (depth >> m).max(1),
+                subresources: Vec::with_capacity(layers as usize),
+            })
+            .collect();
+
+        // DDS stores every mip of layer 0, then every mip of layer 1, and so on.
+        let mut at = data_at;
+        for layer in 0..layers {
+            for mip in mips.iter_mut() {
+                let len = format.slice_bytes(mip.width, mip.height) * mip.depth as usize;
+                let end = at
+                    .checked_add(len)
+                    .filter(|&e| e <= bytes.len())
+                    .ok_or_else(|| format!("dds: truncated data (layer {layer})"))?;
+                let mut data = bytes[at..end].to_vec();
+                if layout == DdsLayout::Bgra8 {
+                    for px in data.chunks_exact_mut(4) {
+                        px.swap(0, 2);
+                    }
+                }
+                mip.subresources.push(TextureSubresource { layer, data });
+                at = end;
+            }
+        }
+
+        let kind = if cube {
+            TextureKind::Cube
+        } else if volume {
+            TextureKind::Tex3D
+        } else {
+            TextureKind::Tex2D
+        };
+
+        Ok(Self {
+            desc: TextureDesc {
+                width,
+                height,
+                depth,
+                layers,
+                mip_count,
+                format,
+                kind,
+            },
+            mips,
+        })
+    }
+}
+
+fn dxgi_format(v: u32) -> Result<(TextureFormat, DdsLayout), String> {
+    let f = match v {
+        28 | 29 => (TextureFormat::Rgba8Unorm, DdsLayout::Native),
+        87 | 91 => (TextureFormat::Rgba8Unorm, DdsLayout::Bgra8),
+        71 | 72 => (TextureFormat::Bc1RgbaUnorm, DdsLayout::Native),
+        74 | 75 => (TextureFormat::Bc2Unorm, DdsLayout::Native),
+        77 | 78 => (TextureFormat::Bc3Unorm, DdsLayout::Native),
+        80 => (TextureFormat::Bc4Unorm, DdsLayout::Native),
+        83 => (TextureFormat::Bc5Unorm, DdsLayout::Native),
+        98 | 99 => (TextureFormat::Bc7Unorm, DdsLayout::Native),
+        other => return Err(format!("dds: unsupported DXGI format {other}")),
+    };
+    Ok(f)
+}
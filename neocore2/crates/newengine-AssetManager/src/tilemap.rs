@@ -0,0 +1,324 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use crate::types::Asset;
+use serde::Deserialize;
+use serde_json::{Map, Value as JsonValue};
+
+/// Tiled gid flag: tile mirrored left-right.
+pub const GID_FLIP_H: u32 = 0x8000_0000;
+/// Tiled gid flag: tile mirrored top-bottom.
+pub const GID_FLIP_V: u32 = 0x4000_0000;
+/// Tiled gid flag: tile mirrored along its top-left to bottom-right diagonal.
+pub const GID_FLIP_D: u32 = 0x2000_0000;
+/// Bits of a gid left once the flip flags (and the hexagonal rotation bit) are cleared.
+pub const GID_MASK: u32 = 0x0FFF_FFFF;
+
+/// Properties as typed in Tiled: int/float/bool keep their JSON type, the rest are strings.
+pub type TilemapProperties = Map<String, JsonValue>;
+
+/// Decoded Tiled map or tileset (`kalitech.asset.tilemap`).
+///
+/// Blob layout: `meta_json` is the whole document (schema `kalitech.tilemap.meta.v1`) except
+/// tile data; `payload` holds the gids of every tile chunk as little-endian u32, each chunk
+/// `chunk_size * chunk_size` gids in rows, found at [`TileChunk::offset`] (in gids). Gid 0 is
+/// empty; other gids keep the Tiled flip flags (`GID_FLIP_*`). Relative paths (tileset
+/// `source`, tileset `image`) are relative to the file that names them.
+#[derive(Debug, Clone)]
+pub struct TilemapAsset {
+    pub doc: TilemapDoc,
+    pub gids: Vec<u32>,
+}
+
+impl Asset for TilemapAsset {
+    #[inline]
+    fn type_name() -> &'static str {
+        "TilemapAsset"
+    }
+}
+
+impl TilemapAsset {
+    /// Gids of `chunk` (`chunk_size²`, rows top to bottom); empty when out of range.
+    pub fn chunk_gids(&self, chunk_size: u32, chunk: &TileChunk) -> &[u32] {
+        let len = (chunk_size * chunk_size) as usize;
+        self.gids
+            .get(chunk.offset as usize..chunk.offset as usize + len)
+            .unwrap_or(&[])
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TilemapDoc {
+    Map(TiledMap),
+    /// External tileset (`.tsx`).
+    Tileset {
+        tileset: Tileset,
+    },
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TiledMap {
+    pub orientation: String,
+    #[serde(default)]
+    pub render_order: String,
+    /// Size in tiles; meaningless for infinite maps.
+    pub width: u32,
+    pub height: u32,
+    /// Grid cell in pixels.
+    pub tile_width: u32,
+    pub tile_height: u32,
+    #[serde(default)]
+    pub infinite: bool,
+    pub chunk_size: u32,
+    /// `#AARRGGBB` or `#RRGGBB`.
+    #[serde(default)]
+    pub background: Option<String>,
+    #[serde(default)]
+    pub properties: TilemapProperties,
+    #[serde(default)]
+    pub tilesets: Vec<TilesetRef>,
+    /// Bottom to top, groups flattened.
+    #[serde(default)]
+    pub layers: Vec<TilemapLayer>,
+}
+
+/// Tileset used by a map from `first_gid` on: either embedded or in an external `.tsx`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct TilesetRef {
+    pub first_gid: u32,
+    #[serde(default)]
+    pub source: Option<String>,
+    #[serde(default)]
+    pub tileset: Option<Tileset>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Tileset {
+    pub name: String,
+    pub tile_width: u32,
+    pub tile_height: u32,
+    pub tile_count: u32,
+    pub columns: u32,
+    #[serde(default)]
+    pub spacing: u32,
+    #[serde(default)]
+    pub margin: u32,
+    /// Pixels added to every tile's drawing position (x right, y down).
+    #[serde(default)]
+    pub tile_offset: [f32; 2],
+    pub image: String,
+    pub image_width: u32,
+    pub image_height: u32,
+    #[serde(default)]
+    pub properties: TilemapProperties,
+    /// Only tiles with a class, properties or collision shapes.
+    #[serde(default)]
+    pub tiles: Vec<TilesetTile>,
+}
+
+impl Tileset {
+    /// Pixel rectangle `[x, y, w, h]` of tile `local_id` in the tileset image.
+    pub fn tile_rect(&self, local_id: u32) -> [u32; 4] {
+        let columns = self.columns.max(1);
+        let (col, row) = (local_id % columns, local_id / columns);
+        [
+            self.margin + col * (self.tile_width + self.spacing),
+            self.margin + row * (self.tile_height + self.spacing),
+            self.tile_width,
+            self.tile_height,
+        ]
+    }
+
+    #[inline]
+    pub fn tile(&self, local_id: u32) -> Option<&TilesetTile> {
+        self.tiles.iter().find(|t| t.id == local_id)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TilesetTile {
+    pub id: u32,
+    #[serde(default)]
+    pub class: Option<String>,
+    #[serde(default)]
+    pub properties: TilemapProperties,
+    /// Shapes from Tiled's collision editor, in tile pixels.
+    #[serde(default)]
+    pub collision: Vec<TilemapObject>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TilemapLayer {
+    Tiles(TileLayer),
+    Objects(ObjectLayer),
+}
+
+impl TilemapLayer {
+    #[inline]
+    pub fn name(&self) -> &str {
+        match self {
+            TilemapLayer::Tiles(l) => &l.name,
+            TilemapLayer::Objects(l) => &l.name,
+        }
+    }
+
+    #[inline]
+    pub fn properties(&self) -> &TilemapProperties {
+        match self {
+            TilemapLayer::Tiles(l) => &l.properties,
+            TilemapLayer::Objects(l) => &l.properties,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TileLayer {
+    pub id: u32,
+    /// `/`-joined with the names of enclosing groups.
+    pub name: String,
+    pub visible: bool,
+    pub opacity: f32,
+    /// Pixels, including group offsets.
+    pub offset: [f32; 2],
+    #[serde(default)]
+    pub properties: TilemapProperties,
+    /// Chunks holding at least one tile.
+    #[serde(default)]
+    pub chunks: Vec<TileChunk>,
+}
+
+/// `chunk_size²` tiles whose top-left tile is at (`x`, `y`) in tile coordinates (y down).
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct TileChunk {
+    pub x: i32,
+    pub y: i32,
+    /// Index of the chunk's first gid in [`TilemapAsset::gids`].
+    pub offset: u32,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ObjectLayer {
+    pub id: u32,
+    pub name: String,
+    pub visible: bool,
+    pub opacity: f32,
+    pub offset: [f32; 2],
+    #[serde(default)]
+    pub properties: TilemapProperties,
+    #[serde(default)]
+    pub objects: Vec<TilemapObject>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ObjectShape {
+    Rect,
+    Ellipse,
+    Point,
+    Polygon,
+    Polyline,
+}
+
+/// Object in map pixels (y down); `x`, `y` is the top-left corner, or the bottom-left one for
+/// tile objects (`gid` set).
+#[derive(Debug, Clone, Deserialize)]
+pub struct TilemapObject {
+    pub id: u32,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub class: String,
+    pub x: f32,
+    pub y: f32,
+    #[serde(default)]
+    pub width: f32,
+    #[serde(default)]
+    pub height: f32,
+    /// Degrees clockwise around `x`, `y`.
+    #[serde(default)]
+    pub rotation: f32,
+    #[serde(default = "default_true")]
+    pub visible: bool,
+    #[serde(default)]
+    pub gid: Option<u32>,
+    pub shape: ObjectShape,
+    /// Polygon and polyline vertices relative to `x`, `y`.
+    #[serde(default)]
+    pub points: Vec<[f32; 2]>,
+    #[serde(default)]
+    pub properties: TilemapProperties,
+}
+
+#[inline]
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum TilemapReadError {
+    #[error("wire: too short")]
+    TooShort,
+    #[error("wire: meta length out of bounds")]
+    MetaOutOfBounds,
+    #[error("wire: meta length too large ({0} bytes)")]
+    MetaTooLarge(usize),
+    #[error("utf8: {0}")]
+    Utf8(String),
+    #[error("meta json: {0}")]
+    MetaJson(String),
+    #[error("payload length {0} is not a multiple of 4")]
+    Payload(usize),
+}
+
+pub struct TilemapReader;
+
+impl TilemapReader {
+    /// Hard cap to prevent pathological allocations / malformed assets.
+    pub const MAX_META_BYTES: usize = 16 * 1024 * 1024;
+
+    /// Builds TilemapAsset from split parts:
+    /// - meta_json: blob.meta_json
+    /// - payload: blob.payload (chunk gids)
+    pub fn from_blob_parts(
+        meta_json: &str,
+        payload: &[u8],
+    ) -> Result<TilemapAsset, TilemapReadError> {
+        let doc: TilemapDoc = serde_json::from_str(meta_json)
+            .map_err(|e| TilemapReadError::MetaJson(e.to_string()))?;
+        if payload.len() % 4 != 0 {
+            return Err(TilemapReadError::Payload(payload.len()));
+        }
+        let gids = payload
+            .chunks_exact(4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect();
+        Ok(TilemapAsset { doc, gids })
+    }
+
+    /// Decodes importer wire:
+    /// [4] meta_len_le (u32)
+    /// [N] meta_json utf8
+    /// [..] payload bytes (rest)
+    pub fn read_wire(bytes: &[u8]) -> Result<TilemapAsset, TilemapReadError> {
+        if bytes.len() < 4 {
+            return Err(TilemapReadError::TooShort);
+        }
+
+        let meta_len = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as usize;
+        if meta_len > Self::MAX_META_BYTES {
+            return Err(TilemapReadError::MetaTooLarge(meta_len));
+        }
+
+        let meta_start = 4usize;
+        let meta_end = meta_start.saturating_add(meta_len);
+        if meta_end > bytes.len() {
+            return Err(TilemapReadError::MetaOutOfBounds);
+        }
+
+        let meta_str = std::str::from_utf8(&bytes[meta_start..meta_end])
+            .map_err(|e| TilemapReadError::Utf8(e.to_string()))?;
+
+        Self::from_blob_parts(meta_str, &bytes[meta_end..])
+    }
+}
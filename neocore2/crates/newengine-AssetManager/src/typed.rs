@@ -0,0 +1,189 @@
+use crate::cubemap::{CubemapAsset, CubemapReader};
+use crate::font::{FontAsset, FontReader};
+use crate::heightmap::{HeightmapAsset, HeightmapReader};
+use crate::model3d::{Model3dMeta, Model3dReader};
+use crate::ne3d::Ne3dMesh;
+use crate::text_reader::{TextDocument, TextReader};
+use crate::texture::TextureAsset;
+use crate::tilemap::{TilemapAsset, TilemapReader};
+use crate::types::{Asset, AssetBlob, AssetError};
+use serde_json::Value;
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// `type_id` of blobs from the text importer.
+pub const TEXT_TYPE_ID: &str = "kalitech.asset.text";
+/// `type_id` of blobs from the image importer.
+pub const TEXTURE_TYPE_ID: &str = "kalitech.asset.texture";
+/// `type_id` of blobs from the 3D importer.
+pub const MODEL3D_TYPE_ID: &str = "kalitech.asset.model3d";
+/// `type_id` of blobs from the font importer.
+pub const FONT_TYPE_ID: &str = "kalitech.asset.font";
+/// `type_id` of blobs from the Tiled map importer.
+pub const TILEMAP_TYPE_ID: &str = "kalitech.asset.tilemap";
+/// `type_id` of blobs from the heightmap importer.
+pub const HEIGHTMAP_TYPE_ID: &str = "kalitech.asset.heightmap";
+/// `type_id` of blobs from the cubemap manifest importer.
+pub const CUBEMAP_TYPE_ID: &str = "kalitech.asset.cubemap";
+
+/// Decoded text asset.
+pub type TextAsset = TextDocument;
+
+impl Asset for TextDocument {
+    #[inline]
+    fn type_name() -> &'static str {
+        "TextAsset"
+    }
+}
+
+/// Decoded 3D asset: importer meta plus the NE3D mesh.
+#[derive(Debug, Clone)]
+pub struct MeshAsset {
+    pub meta: Model3dMeta,
+    pub mesh: Ne3dMesh,
+}
+
+impl Asset for MeshAsset {
+    #[inline]
+    fn type_name() -> &'static str {
+        "MeshAsset"
+    }
+}
+
+pub(crate) type DecodedValue = Arc<dyn Any + Send + Sync>;
+type DecodeFn = dyn Fn(&AssetBlob) -> Result<DecodedValue, AssetError> + Send + Sync;
+
+#[derive(Clone)]
+struct DecoderEntry {
+    output: TypeId,
+    output_name: &'static str,
+    decode: Arc<DecodeFn>,
+}
+
+/// Blob decoders keyed by `(type_id, format)`; a `None` format matches any format of the type.
+///
+/// Comes with decoders for [`TextAsset`], [`MeshAsset`], [`FontAsset`], [`TilemapAsset`],
+/// [`HeightmapAsset`], [`CubemapAsset`] and [`TextureAsset`] (DDS containers).
+/// Registering for the same key replaces the previous decoder.
+pub struct DecoderRegistry {
+    by_key: HashMap<(String, Option<String>), DecoderEntry>,
+}
+
+impl Default for DecoderRegistry {
+    fn default() -> Self {
+        let mut r = Self {
+            by_key: HashMap::new(),
+        };
+        r.register::<TextAsset, _>(TEXT_TYPE_ID, None, decode_text);
+        r.register::<MeshAsset, _>(MODEL3D_TYPE_ID, None, decode_mesh);
+        r.register::<TextureAsset, _>(TEXTURE_TYPE_ID, None, decode_texture);
+        r.register::<FontAsset, _>(FONT_TYPE_ID, None, decode_font);
+        r.register::<TilemapAsset, _>(TILEMAP_TYPE_ID, None, decode_tilemap);
+        r.register::<HeightmapAsset, _>(HEIGHTMAP_TYPE_ID, None, decode_heightmap);
+        r.register::<CubemapAsset, _>(CUBEMAP_TYPE_ID, None, decode_cubemap);
+        r
+    }
+}
+
+impl std::fmt::Debug for DecoderRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map()
+            .entries(self.by_key.iter().map(|(k, v)| (k, v.output_name)))
+            .finish()
+    }
+}
+
+impl DecoderRegistry {
+    pub fn register<T, F>(&mut self, type_id: &str, format: Option<&str>, decode: F)
+    where
+        T: Send + Sync + 'static,
+        F: Fn(&AssetBlob) -> Result<T, AssetError> + Send + Sync + 'static,
+    {
+        let entry = DecoderEntry {
+            output: TypeId::of::<T>(),
+            output_name: std::any::type_name::<T>(),
+            decode: Arc::new(move |blob| decode(blob).map(|v| Arc::new(v) as DecodedValue)),
+        };
+        self.by_key
+            .insert((type_id.to_string(), format.map(str::to_string)), entry);
+    }
+
+    /// Decoder for `blob`; errors when none fits or it produces something other than `T`.
+    pub(crate) fn decoder_for<T: 'static>(
+        &self,
+        blob: &AssetBlob,
+    ) -> Result<Arc<DecodeFn>, AssetError> {
+        let exact = (blob.type_id.to_string(), Some(blob.format.to_string()));
+        let any = (blob.type_id.to_string(), None);
+        let Some(entry) = self.by_key.get(&exact).or_else(|| self.by_key.get(&any)) else {
+            return Err(AssetError::new(format!(
+                "no decoder for type '{}' format '{}'",
+                blob.type_id, blob.format
+            )));
+        };
+
+        if entry.output != TypeId::of::<T>() {
+            return Err(AssetError::new(format!(
+                "decoder for type '{}' produces {}, not {}",
+                blob.type_id,
+                entry.output_name,
+                std::any::type_name::<T>()
+            )));
+        }
+        Ok(entry.decode.clone())
+    }
+}
+
+fn decode_text(blob: &AssetBlob) -> Result<TextAsset, AssetError> {
+    TextReader::from_blob_parts(&blob.meta_json, &blob.payload)
+        .map_err(|e| AssetError::new(format!("text: {e}")))
+}
+
+fn decode_mesh(blob: &AssetBlob) -> Result<MeshAsset, AssetError> {
+    let model = Model3dReader::from_blob_parts(&blob.meta_json, &blob.payload)
+        .map_err(|e| AssetError::new(format!("model3d: {e}")))?;
+    let mesh = Ne3dMesh::decode(&model.payload).map_err(|e| AssetError::new(e.to_string()))?;
+    Ok(MeshAsset {
+        meta: model.meta,
+        mesh,
+    })
+}
+
+fn decode_font(blob: &AssetBlob) -> Result<FontAsset, AssetError> {
+    FontReader::from_blob_parts(&blob.meta_json, &blob.payload)
+        .map_err(|e| AssetError::new(format!("font: {e}")))
+}
+
+fn decode_tilemap(blob: &AssetBlob) -> Result<TilemapAsset, AssetError> {
+    TilemapReader::from_blob_parts(&blob.meta_json, &blob.payload)
+        .map_err(|e| AssetError::new(format!("tilemap: {e}")))
+}
+
+fn decode_heightmap(blob: &AssetBlob) -> Result<HeightmapAsset, AssetError> {
+    HeightmapReader::from_blob_parts(&blob.meta_json, &blob.payload)
+        .map_err(|e| AssetError::new(format!("heightmap: {e}")))
+}
+
+fn decode_cubemap(blob: &AssetBlob) -> Result<CubemapAsset, AssetError> {
+    CubemapReader::from_blob_parts(&blob.meta_json, &blob.payload)
+        .map_err(|e| AssetError::new(format!("cubemap: {e}")))
+}
+
+/// Only DDS payloads are decoded here: other containers are compressed images whose decoders
+/// live outside this crate, so hosts register their own decoder for them.
+fn decode_texture(blob: &AssetBlob) -> Result<TextureAsset, AssetError> {
+    let meta: Value = serde_json::from_str(&blob.meta_json)
+        .map_err(|e| AssetError::new(format!("texture: bad meta json: {e}")))?;
+    let container = meta
+        .get("container")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+
+    match container {
+        "dds" => TextureAsset::from_dds(&blob.payload).map_err(AssetError::new),
+        other => Err(AssetError::new(format!(
+            "texture: no CPU decoder for container '{other}'"
+        ))),
+    }
+}
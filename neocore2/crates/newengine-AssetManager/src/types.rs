@@ -1,11 +1,17 @@
 use crate::id::AssetId;
-use std::path::{Component, PathBuf};
+use crate::vfs::{split_mount, MOUNT_SEPARATOR};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use unicode_normalization::UnicodeNormalization;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct AssetKey {
     pub logical_path: PathBuf,
     pub settings_hash: u64,
+    /// Importer settings (JSON object) from the asset's `.meta` sidecar. Filled in by
+    /// `AssetStore` right before import; `None` means importer defaults. Not part of the id.
+    pub import_settings: Option<Arc<str>>,
 }
 
 impl AssetKey {
@@ -16,13 +22,26 @@ impl AssetKey {
         Self {
             logical_path: p,
             settings_hash,
+            import_settings: None,
         }
     }
 
+    #[inline]
+    pub fn with_import_settings(mut self, settings: Arc<str>) -> Self {
+        self.import_settings = Some(settings);
+        self
+    }
+
     #[inline]
     pub fn id(&self) -> AssetId {
         AssetId::from_key(self)
     }
+
+    /// Identity form of the path ('/'-joined, case-folded per `PathCaseMode`).
+    #[inline]
+    pub fn canonical_path(&self) -> String {
+        crate::id::canonical_logical_path(&self.logical_path)
+    }
 }
 
 /// Marker trait for typed, CPU-side assets (optional layer).
@@ -81,6 +100,27 @@ impl std::fmt::Display for AssetError {
 
 impl std::error::Error for AssetError {}
 
+/// Cooperative cancellation flag shared between the store and a running import.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline]
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Release);
+    }
+
+    #[inline]
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Acquire)
+    }
+}
+
 /// Importer priority (host-defined). Higher wins.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub struct ImporterPriority(pub i32);
@@ -92,6 +132,48 @@ impl ImporterPriority {
     }
 }
 
+/// Scheduling class of a load request. Higher classes leave the queue first; requests of the
+/// same class keep FIFO order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+#[repr(u8)]
+pub enum LoadPriority {
+    /// Background streaming and prefetch.
+    Streaming = 0,
+    #[default]
+    Normal = 1,
+    /// Needed on screen now: UI, loading screens.
+    Ui = 2,
+}
+
+impl LoadPriority {
+    #[inline]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LoadPriority::Streaming => "streaming",
+            LoadPriority::Normal => "normal",
+            LoadPriority::Ui => "ui",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "streaming" | "low" => Some(LoadPriority::Streaming),
+            "normal" => Some(LoadPriority::Normal),
+            "ui" | "high" => Some(LoadPriority::Ui),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn from_u8(v: u8) -> Self {
+        match v {
+            0 => LoadPriority::Streaming,
+            1 => LoadPriority::Normal,
+            _ => LoadPriority::Ui,
+        }
+    }
+}
+
 #[derive(Debug)]
 enum NormalizePathError {
     Invalid,
@@ -99,24 +181,50 @@ enum NormalizePathError {
 
 /// Logical path contract:
 /// - relative
-/// - no root/prefix
+/// - no root/prefix (including "C:" drive prefixes)
 /// - no '.' or '..'
-/// - platform-stable via components joining
+/// - '\\' and '/' are both separators
+/// - components are Unicode NFC (so macOS-decomposed names match authored ones)
+/// - an optional VFS mount prefix (`game://`) is kept; the rest follows the rules above
+///
+/// Case is preserved here (filesystem sources may be case-sensitive); case folding for
+/// identity happens in `AssetId::from_key`.
 #[inline]
 fn normalize_logical_path(p: PathBuf) -> Result<PathBuf, NormalizePathError> {
+    let raw = p.to_string_lossy();
+
+    if let Some((mount, rest)) = split_mount(&raw) {
+        if split_mount(rest).is_some() {
+            return Err(NormalizePathError::Invalid);
+        }
+        let rest = normalize_logical_path(PathBuf::from(rest))?;
+        let rest = rest
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        return Ok(PathBuf::from(format!("{mount}{MOUNT_SEPARATOR}{rest}")));
+    }
+
+    if raw.starts_with('/') || raw.starts_with('\\') || has_drive_prefix(&raw) {
+        return Err(NormalizePathError::Invalid);
+    }
+
     let mut out = PathBuf::new();
 
-    for c in p.components() {
-        match c {
-            Component::Normal(x) => out.push(x),
-            Component::CurDir => {}
-            Component::ParentDir => {
+    for comp in raw.split(|c| c == '/' || c == '\\') {
+        match comp {
+            "" | "." => {}
+            ".." => {
                 // Reject traversal deterministically to keep AssetId stable and non-ambiguous.
                 return Err(NormalizePathError::Invalid);
             }
-            Component::Prefix(_) | Component::RootDir => {
-                // Strip absolute prefixes for logical path stability.
-                return Err(NormalizePathError::Invalid);
+            c => {
+                if c.is_ascii() {
+                    out.push(c);
+                } else {
+                    out.push(c.nfc().collect::<String>());
+                }
             }
         }
     }
@@ -126,4 +234,10 @@ fn normalize_logical_path(p: PathBuf) -> Result<PathBuf, NormalizePathError> {
     }
 
     Ok(out)
-}
\ No newline at end of file
+}
+
+#[inline]
+fn has_drive_prefix(s: &str) -> bool {
+    let b = s.as_bytes();
+    b.len() >= 2 && b[0].is_ascii_alphabetic() && b[1] == b':'
+}
@@ -0,0 +1,211 @@
+use crate::source::AssetSource;
+use crate::types::AssetError;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::SystemTime;
+
+/// Engine default assets (fallback shaders, placeholder textures, editor icons).
+pub const ENGINE_MOUNT: &str = "engine";
+/// Game content; `AssetStore::add_source` mounts here.
+pub const GAME_MOUNT: &str = "game";
+/// Mod content, overriding game and engine files with the same logical path.
+pub const MODS_MOUNT: &str = "mods";
+
+const ENGINE_PRIORITY: i32 = 0;
+const GAME_PRIORITY: i32 = 100;
+const MODS_PRIORITY: i32 = 200;
+
+/// Separator between a mount name and the path inside it: `game://textures/stone.png`.
+pub const MOUNT_SEPARATOR: &str = "://";
+
+/// Splits `mount://rest` into `(mount, rest)`.
+///
+/// Mount names are at least two characters of `[a-z0-9_-]`, so Windows drive prefixes never
+/// parse as mounts.
+pub fn split_mount(path: &str) -> Option<(&str, &str)> {
+    let (mount, rest) = path.split_once(MOUNT_SEPARATOR)?;
+    is_valid_mount_name(mount).then_some((mount, rest))
+}
+
+#[inline]
+pub fn is_valid_mount_name(name: &str) -> bool {
+    name.len() >= 2
+        && name
+            .bytes()
+            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'_' || b == b'-')
+}
+
+/// One mount point as reported by [`Vfs::mounts`].
+#[derive(Debug, Clone)]
+pub struct MountInfo {
+    pub name: String,
+    pub priority: i32,
+    pub sources: usize,
+}
+
+#[derive(Clone)]
+struct Mount {
+    name: Arc<str>,
+    priority: i32,
+    /// Top of the stack first: the most recently added source wins.
+    sources: Vec<Arc<dyn AssetSource>>,
+}
+
+/// Mount-point file system over [`AssetSource`] stacks.
+///
+/// A path with a mount prefix (`engine://ui/cursor.png`) is looked up in that mount only.
+/// A plain path (`ui/cursor.png`) goes through every mount from the highest priority down,
+/// so a file under `mods://` overrides the same path under `game://`, which in turn
+/// overrides `engine://`. Within a mount, later sources overlay earlier ones.
+///
+/// The default value has the `engine`, `game` and `mods` mounts, all empty.
+#[derive(Clone)]
+pub struct Vfs {
+    /// Sorted by descending priority; equal priorities keep mount order.
+    mounts: Vec<Mount>,
+}
+
+impl Default for Vfs {
+    fn default() -> Self {
+        let mut vfs = Self { mounts: Vec::new() };
+        for (name, priority) in [
+            (ENGINE_MOUNT, ENGINE_PRIORITY),
+            (GAME_MOUNT, GAME_PRIORITY),
+            (MODS_MOUNT, MODS_PRIORITY),
+        ] {
+            let _ = vfs.mount(name, priority);
+        }
+        vfs
+    }
+}
+
+impl std::fmt::Debug for Vfs {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.mounts()).finish()
+    }
+}
+
+impl Vfs {
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates mount `name`, or changes its priority if it already exists.
+    pub fn mount(&mut self, name: &str, priority: i32) -> Result<(), AssetError> {
+        if !is_valid_mount_name(name) {
+            return Err(AssetError::new(format!("vfs: invalid mount name '{name}'")));
+        }
+
+        match self.mounts.iter_mut().find(|m| &*m.name == name) {
+            Some(m) => m.priority = priority,
+            None => self.mounts.push(Mount {
+                name: Arc::from(name),
+                priority,
+                sources: Vec::new(),
+            }),
+        }
+        self.mounts.sort_by_key(|m| std::cmp::Reverse(m.priority));
+        Ok(())
+    }
+
+    /// Pushes `source` on top of the stack of mount `name`.
+    pub fn add_source(
+        &mut self,
+        name: &str,
+        source: Arc<dyn AssetSource>,
+    ) -> Result<(), AssetError> {
+        let Some(m) = self.mounts.iter_mut().find(|m| &*m.name == name) else {
+            return Err(AssetError::new(format!("vfs: no mount '{name}'")));
+        };
+        m.sources.insert(0, source);
+        Ok(())
+    }
+
+    pub fn mounts(&self) -> Vec<MountInfo> {
+        self.mounts
+            .iter()
+            .map(|m| MountInfo {
+                name: m.name.to_string(),
+                priority: m.priority,
+                sources: m.sources.len(),
+            })
+            .collect()
+    }
+
+    /// Total number of sources across all mounts.
+    #[inline]
+    pub fn source_count(&self) -> usize {
+        self.mounts.iter().map(|m| m.sources.len()).sum()
+    }
+
+    /// Source that serves `logical_path`, with the path to pass to it.
+    pub fn resolve(&self, logical_path: &Path) -> Option<(&Arc<dyn AssetSource>, PathBuf)> {
+        let raw = logical_path.to_string_lossy();
+        let (mounts, inner): (Vec<&Mount>, PathBuf) = match split_mount(&raw) {
+            Some((name, rest)) => (
+                self.mounts.iter().filter(|m| &*m.name == name).collect(),
+                PathBuf::from(rest.trim_start_matches(['/', '\\'])),
+            ),
+            None => (self.mounts.iter().collect(), logical_path.to_path_buf()),
+        };
+
+        mounts
+            .into_iter()
+            .flat_map(|m| m.sources.iter())
+            .find(|s| s.exists(&inner))
+            .map(|s| (s, inner))
+    }
+
+    #[inline]
+    pub fn exists(&self, logical_path: &Path) -> bool {
+        self.resolve(logical_path).is_some()
+    }
+
+    pub fn read(&self, logical_path: &Path) -> Result<Vec<u8>, AssetError> {
+        if self.source_count() == 0 {
+            return Err(AssetError::new("AssetStore: no sources registered"));
+        }
+
+        match self.resolve(logical_path) {
+            Some((s, inner)) => s.read(&inner),
+            None => Err(AssetError::new(format!(
+                "AssetStore: asset not found in any source: '{}'",
+                logical_path.to_string_lossy()
+            ))),
+        }
+    }
+
+    #[inline]
+    pub fn modified(&self, logical_path: &Path) -> Option<SystemTime> {
+        self.resolve(logical_path)
+            .and_then(|(s, inner)| s.modified(&inner))
+    }
+
+    /// Writes `bytes` next to `beside` in whichever source serves `beside`, e.g. a `.meta`
+    /// sidecar into the mod that overrides the asset.
+    pub fn write_beside(
+        &self,
+        beside: &Path,
+        name: impl FnOnce(&Path) -> PathBuf,
+        bytes: &[u8],
+    ) -> Result<(), AssetError> {
+        let Some((s, inner)) = self.resolve(beside) else {
+            return Err(AssetError::new(format!(
+                "vfs: '{}' not found",
+                beside.to_string_lossy()
+            )));
+        };
+        s.write(&name(&inner), bytes)
+    }
+
+    /// Plain logical paths of every file across all mounts. A path present in several
+    /// mounts is listed once per mount; callers dedupe.
+    pub fn list(&self) -> Vec<PathBuf> {
+        self.mounts
+            .iter()
+            .flat_map(|m| m.sources.iter())
+            .flat_map(|s| s.list())
+            .collect()
+    }
+}
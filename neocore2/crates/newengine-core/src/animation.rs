@@ -0,0 +1,273 @@
+use crate::error::EngineResult;
+use crate::interp::{slerp, Transform};
+use crate::render::{BufferDesc, BufferId, BufferUsage, MemoryHint, RenderApi};
+
+use newengine_assets::{
+    Ne3dChannel, Ne3dChannelPath, Ne3dClip, Ne3dInterpolation, Ne3dJoint, Ne3dMesh,
+};
+
+/// Bytes per joint matrix in a `BindingKind::BoneMatrices` buffer.
+pub const JOINT_MATRIX_SIZE: u64 = 64;
+
+/// Plays the clips of a skinned NE3D mesh and produces its joint matrices.
+///
+/// Owners call [`AnimationPlayer::advance`] once per frame and [`AnimationPlayer::upload`] the
+/// result into the storage buffer bound as `BindingKind::BoneMatrices`. Without a playing clip
+/// the skeleton stays in its rest pose.
+#[derive(Debug, Clone)]
+pub struct AnimationPlayer {
+    skeleton: Vec<Ne3dJoint>,
+    clips: Vec<Ne3dClip>,
+    current: Option<usize>,
+    time: f32,
+    speed: f32,
+    looping: bool,
+    paused: bool,
+    local: Vec<Transform>,
+    global: Vec<[f32; 16]>,
+    /// `global * inverse_bind`, one per joint.
+    skin: Vec<[f32; 16]>,
+}
+
+impl AnimationPlayer {
+    /// `skeleton` must list parents before children, as `Ne3dMesh::decode` guarantees.
+    pub fn new(skeleton: Vec<Ne3dJoint>, clips: Vec<Ne3dClip>) -> Self {
+        let n = skeleton.len();
+        let mut p = Self {
+            skeleton,
+            clips,
+            current: None,
+            time: 0.0,
+            speed: 1.0,
+            looping: true,
+            paused: false,
+            local: Vec::with_capacity(n),
+            global: vec![IDENTITY; n],
+            skin: vec![IDENTITY; n],
+        };
+        p.reset_pose();
+        p.compute_matrices();
+        p
+    }
+
+    #[inline]
+    pub fn from_mesh(mesh: &Ne3dMesh) -> Self {
+        Self::new(mesh.skeleton.clone(), mesh.clips.clone())
+    }
+
+    #[inline]
+    pub fn joint_count(&self) -> usize {
+        self.skeleton.len()
+    }
+
+    pub fn clip_names(&self) -> impl Iterator<Item = &str> {
+        self.clips.iter().map(|c| c.name.as_str())
+    }
+
+    #[inline]
+    pub fn current_clip(&self) -> Option<&str> {
+        self.current.map(|i| self.clips[i].name.as_str())
+    }
+
+    /// Seconds into the current clip.
+    #[inline]
+    pub fn time(&self) -> f32 {
+        self.time
+    }
+
+    /// Starts `name` from the beginning. Returns `false` when the mesh has no such clip.
+    pub fn play(&mut self, name: &str) -> bool {
+        match self.clips.iter().position(|c| c.name == name) {
+            Some(i) => self.play_index(i),
+            None => false,
+        }
+    }
+
+    pub fn play_index(&mut self, index: usize) -> bool {
+        if index >= self.clips.len() {
+            return false;
+        }
+        self.current = Some(index);
+        self.time = 0.0;
+        self.paused = false;
+        self.sample();
+        true
+    }
+
+    /// Stops playback and returns to the rest pose.
+    pub fn stop(&mut self) {
+        self.current = None;
+        self.time = 0.0;
+        self.reset_pose();
+        self.compute_matrices();
+    }
+
+    #[inline]
+    pub fn set_paused(&mut self, paused: bool) {
+        self.paused = paused;
+    }
+
+    #[inline]
+    pub fn set_looping(&mut self, looping: bool) {
+        self.looping = looping;
+    }
+
+    /// Playback rate; negative plays backwards.
+    #[inline]
+    pub fn set_speed(&mut self, speed: f32) {
+        self.speed = speed;
+    }
+
+    pub fn advance(&mut self, dt: f32) {
+        let Some(i) = self.current else { return };
+        if self.paused {
+            return;
+        }
+
+        let duration = self.clips[i].duration;
+        self.time += dt * self.speed;
+        if duration <= 0.0 {
+            self.time = 0.0;
+        } else if self.looping {
+            self.time = self.time.rem_euclid(duration);
+        } else {
+            self.time = self.time.clamp(0.0, duration);
+        }
+        self.sample();
+    }
+
+    /// Column-major `global * inverse_bind` per joint, in skeleton order.
+    #[inline]
+    pub fn joint_matrices(&self) -> &[[f32; 16]] {
+        &self.skin
+    }
+
+    /// Size of the storage buffer [`AnimationPlayer::upload`] writes into.
+    #[inline]
+    pub fn buffer_size(&self) -> u64 {
+        self.skeleton.len().max(1) as u64 * JOINT_MATRIX_SIZE
+    }
+
+    /// A CPU-writable storage buffer sized for this skeleton.
+    pub fn create_buffer(&self, r: &mut dyn RenderApi) -> EngineResult<BufferId> {
+        r.create_buffer(
+            BufferDesc::new(
+                self.buffer_size(),
+                BufferUsage::Storage,
+                MemoryHint::CpuToGpu,
+            )
+            .with_label("joint_matrices"),
+        )
+    }
+
+    pub fn upload(&self, r: &mut dyn RenderApi, buffer: BufferId) -> EngineResult<()> {
+        let mut bytes = Vec::with_capacity(self.buffer_size() as usize);
+        for m in &self.skin {
+            for v in m {
+                bytes.extend_from_slice(&v.to_le_bytes());
+            }
+        }
+        if bytes.is_empty() {
+            IDENTITY
+                .iter()
+                .for_each(|v| bytes.extend_from_slice(&v.to_le_bytes()));
+        }
+        r.write_buffer(buffer, 0, &bytes)
+    }
+
+    fn reset_pose(&mut self) {
+        self.local.clear();
+        self.local.extend(self.skeleton.iter().map(|j| Transform {
+            translation: j.translation,
+            rotation: j.rotation,
+            scale: j.scale,
+        }));
+    }
+
+    fn sample(&mut self) {
+        self.reset_pose();
+        if let Some(i) = self.current {
+            for ch in self.clips[i]
+                .channels
+                .iter()
+                .filter(|c| !c.times.is_empty())
+            {
+                let Some(local) = self.local.get_mut(ch.joint as usize) else {
+                    continue;
+                };
+                match ch.path {
+                    Ne3dChannelPath::Translation => local.translation = sample_vec3(ch, self.time),
+                    Ne3dChannelPath::Scale => local.scale = sample_vec3(ch, self.time),
+                    Ne3dChannelPath::Rotation => local.rotation = sample_quat(ch, self.time),
+                }
+            }
+        }
+        self.compute_matrices();
+    }
+
+    fn compute_matrices(&mut self) {
+        for i in 0..self.skeleton.len() {
+            let local = self.local[i].to_matrix();
+            self.global[i] = match self.skeleton[i].parent {
+                Some(p) => mat4_mul(&self.global[p as usize], &local),
+                None => local,
+            };
+            self.skin[i] = mat4_mul(&self.global[i], &self.skeleton[i].inverse_bind);
+        }
+    }
+}
+
+/// Keys around `t`: `(a, b, blend)`, clamped to the first/last key.
+fn keys(ch: &Ne3dChannel, t: f32) -> (usize, usize, f32) {
+    let n = ch.times.len();
+    if n <= 1 || t <= ch.times[0] {
+        return (0, 0, 0.0);
+    }
+    if t >= ch.times[n - 1] {
+        return (n - 1, n - 1, 0.0);
+    }
+    let b = ch.times.partition_point(|&k| k <= t);
+    let a = b - 1;
+    if ch.interpolation == Ne3dInterpolation::Step {
+        return (a, a, 0.0);
+    }
+    let span = ch.times[b] - ch.times[a];
+    let f = if span > f32::EPSILON {
+        (t - ch.times[a]) / span
+    } else {
+        0.0
+    };
+    (a, b, f)
+}
+
+fn sample_vec3(ch: &Ne3dChannel, t: f32) -> [f32; 3] {
+    let (a, b, f) = keys(ch, t);
+    let va = &ch.values[a * 3..a * 3 + 3];
+    let vb = &ch.values[b * 3..b * 3 + 3];
+    [0, 1, 2].map(|k| va[k] + (vb[k] - va[k]) * f)
+}
+
+fn sample_quat(ch: &Ne3dChannel, t: f32) -> [f32; 4] {
+    let (a, b, f) = keys(ch, t);
+    let qa = [0, 1, 2, 3].map(|k| ch.values[a * 4 + k]);
+    let qb = [0, 1, 2, 3].map(|k| ch.values[b * 4 + k]);
+    slerp(qa, qb, f)
+}
+
+const IDENTITY: [f32; 16] = [
+    1.0, 0.0, 0.0, 0.0, //
+    0.0, 1.0, 0.0, 0.0, //
+    0.0, 0.0, 1.0, 0.0, //
+    0.0, 0.0, 0.0, 1.0,
+];
+
+/// Column-major `a * b`.
+fn mat4_mul(a: &[f32; 16], b: &[f32; 16]) -> [f32; 16] {
+    let mut out = [0.0; 16];
+    for c in 0..4 {
+        for r in 0..4 {
+            out[c * 4 + r] = (0..4).map(|k| a[k * 4 + r] * b[c * 4 + k]).sum();
+        }
+    }
+    out
+}
@@ -1,16 +1,41 @@
 use log::info;
+use serde::{Deserialize, Serialize};
 use newengine_assets::{
-    AssetBlob, AssetError, AssetEvent, AssetId, AssetKey, AssetSource, AssetState, AssetStore,
-    BlobImporterDispatch, FileSystemSource, PumpBudget,
+    AssetBlob, AssetError, AssetEvent, AssetEventReceiver, AssetId, AssetKey, AssetSource, AssetState, AssetStore,
+    BlobImporterDispatch, DerivedDataCache, FileSystemSource, LoadGroup, LoadHandle,
+    LoadPriority, MountInfo, PackSource, PathCaseMode, PumpBudget, ENGINE_MOUNT, GAME_MOUNT,
+    MODS_MOUNT,
 };
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// `engine.config` section with asset tunables that can change at runtime.
+pub const ASSETS_CONFIG_SECTION: &str = "assets";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssetTunables {
+    /// Import steps per frame (`AssetManager::set_budget`).
+    pub pump_steps: u32,
+}
 
 #[derive(Debug, Clone)]
 pub struct AssetManagerConfig {
     pub root: PathBuf,
     pub pump_steps: u32,
     pub enable_filesystem_source: bool,
+    /// Case handling for logical paths when deriving asset ids. Process-wide.
+    pub path_case: PathCaseMode,
+    /// Directory for cached import results; `None` disables the cache.
+    pub cache_dir: Option<PathBuf>,
+    /// Filesystem root mounted at `engine://` (engine default assets).
+    pub engine_root: Option<PathBuf>,
+    /// Filesystem root mounted at `mods://`, overriding game and engine files.
+    pub mods_root: Option<PathBuf>,
+    /// Asset packs, mounted before the filesystem roots so loose files override them. A pack
+    /// named after a mount (`engine.nepak`, `mods.nepak`) goes there, any other to `game://`.
+    pub packs: Vec<PathBuf>,
 }
 
 impl AssetManagerConfig {
@@ -20,6 +45,11 @@ impl AssetManagerConfig {
             root,
             pump_steps: 8,
             enable_filesystem_source: true,
+            path_case: PathCaseMode::default(),
+            cache_dir: None,
+            engine_root: None,
+            mods_root: None,
+            packs: Vec::new(),
         }
     }
 
@@ -34,12 +64,43 @@ impl AssetManagerConfig {
         self.enable_filesystem_source = enabled;
         self
     }
+
+    #[inline]
+    pub fn with_path_case(mut self, mode: PathCaseMode) -> Self {
+        self.path_case = mode;
+        self
+    }
+
+    #[inline]
+    pub fn with_cache_dir(mut self, dir: Option<PathBuf>) -> Self {
+        self.cache_dir = dir;
+        self
+    }
+
+    #[inline]
+    pub fn with_engine_root(mut self, dir: Option<PathBuf>) -> Self {
+        self.engine_root = dir;
+        self
+    }
+
+    #[inline]
+    pub fn with_mods_root(mut self, dir: Option<PathBuf>) -> Self {
+        self.mods_root = dir;
+        self
+    }
+
+    #[inline]
+    pub fn with_packs(mut self, packs: Vec<PathBuf>) -> Self {
+        self.packs = packs;
+        self
+    }
 }
 
 pub struct AssetManager {
     store: Arc<AssetStore>,
     budget: PumpBudget,
     importers_dir: PathBuf,
+    last_pump_us: AtomicU64,
 }
 
 impl AssetManager {
@@ -50,7 +111,13 @@ impl AssetManager {
 
     #[inline]
     pub fn new_with_config(config: AssetManagerConfig) -> Self {
-        info!(target: "assets", "manager.init root='{}'", config.root.display());
+        info!(
+            target: "assets",
+            "manager.init root='{}' path_case={:?}",
+            config.root.display(),
+            config.path_case
+        );
+        newengine_assets::set_path_case_mode(config.path_case);
 
         let importers_dir = default_importers_dir();
         if let Err(e) = std::fs::create_dir_all(&importers_dir) {
@@ -70,6 +137,32 @@ impl AssetManager {
 
         let store = Arc::new(AssetStore::new());
 
+        for path in &config.packs {
+            let mount = match path.file_stem().and_then(|s| s.to_str()) {
+                Some(ENGINE_MOUNT) => ENGINE_MOUNT,
+                Some(MODS_MOUNT) => MODS_MOUNT,
+                _ => GAME_MOUNT,
+            };
+            match PackSource::open(path) {
+                Ok(pack) => {
+                    info!(
+                        target: "assets",
+                        "manager.source.register kind='pack' mount='{}' path='{}' entries={}",
+                        mount,
+                        path.display(),
+                        pack.len()
+                    );
+                    let _ = store.add_source_to(mount, Arc::new(pack));
+                }
+                Err(e) => log::warn!(
+                    target: "assets",
+                    "manager.pack.open failed path='{}' err='{}'",
+                    path.display(),
+                    e
+                ),
+            }
+        }
+
         if config.enable_filesystem_source {
             info!(
                 target: "assets",
@@ -77,6 +170,28 @@ impl AssetManager {
                 config.root.display()
             );
             store.add_source(Arc::new(FileSystemSource::new(config.root)));
+
+            let extra = [
+                (ENGINE_MOUNT, config.engine_root),
+                (MODS_MOUNT, config.mods_root),
+            ];
+            for (mount, root) in extra {
+                let Some(root) = root else {
+                    continue;
+                };
+                info!(
+                    target: "assets",
+                    "manager.source.register kind='filesystem' mount='{}' root='{}'",
+                    mount,
+                    root.display()
+                );
+                let _ = store.add_source_to(mount, Arc::new(FileSystemSource::new(root)));
+            }
+        }
+
+        if let Some(dir) = config.cache_dir {
+            info!(target: "assets", "manager.cache dir='{}'", dir.display());
+            store.set_cache(Some(Arc::new(DerivedDataCache::new(dir))));
         }
 
         let steps = config.pump_steps.max(1);
@@ -87,6 +202,7 @@ impl AssetManager {
             store,
             budget,
             importers_dir,
+            last_pump_us: AtomicU64::new(0),
         }
     }
 
@@ -104,12 +220,33 @@ impl AssetManager {
         &self.store
     }
 
-    /// Registers an additional asset source.
+    /// Registers an additional asset source on the `game://` mount.
     #[inline]
     pub fn add_source(&self, source: Arc<dyn AssetSource>) {
         self.store.add_source(source);
     }
 
+    /// Registers an asset source on a specific VFS mount (`engine`, `game`, `mods`, ...).
+    #[inline]
+    pub fn add_source_to(
+        &self,
+        mount: &str,
+        source: Arc<dyn AssetSource>,
+    ) -> Result<(), AssetError> {
+        self.store.add_source_to(mount, source)
+    }
+
+    /// Creates a VFS mount point or changes its priority.
+    #[inline]
+    pub fn mount(&self, name: &str, priority: i32) -> Result<(), AssetError> {
+        self.store.mount(name, priority)
+    }
+
+    #[inline]
+    pub fn mounts(&self) -> Vec<MountInfo> {
+        self.store.mounts()
+    }
+
     /// Registers a type-erased importer dispatch (usually a plugin-backed service adapter).
     #[inline]
     pub fn add_importer(&self, importer: Arc<dyn BlobImporterDispatch>) {
@@ -122,6 +259,25 @@ impl AssetManager {
         self.store.load(key)
     }
 
+    /// Enqueues an import request ahead of lower priorities; the handle reports progress.
+    #[inline]
+    pub fn load_with_priority(
+        &self,
+        key: AssetKey,
+        priority: LoadPriority,
+    ) -> Result<LoadHandle, AssetError> {
+        self.store.load_with_priority(key, priority)
+    }
+
+    /// Loads a batch tracked as one group (`AssetEvent::GroupCompleted` when all are done).
+    #[inline]
+    pub fn load_group<I>(&self, name: &str, keys: I, priority: LoadPriority) -> LoadGroup
+    where
+        I: IntoIterator<Item = AssetKey>,
+    {
+        self.store.load_group(name, keys, priority)
+    }
+
     #[inline]
     pub fn state(&self, id: AssetId) -> AssetState {
         self.store.state(id)
@@ -132,11 +288,24 @@ impl AssetManager {
         self.store.get_blob(id)
     }
 
+    /// Typed view of a ready asset (`TextAsset`, `MeshAsset`, `TextureAsset`, or any type with
+    /// a registered decoder), cached until the asset is reloaded.
+    #[inline]
+    pub fn get_typed<T: Send + Sync + 'static>(&self, id: AssetId) -> Result<Arc<T>, AssetError> {
+        self.store.get_typed(id)
+    }
+
     #[inline]
     pub fn drain_events(&self) -> Vec<AssetEvent> {
         self.store.drain_events()
     }
 
+    /// Own copy of the event stream; see `AssetStore::subscribe_events`.
+    #[inline]
+    pub fn subscribe_events(&self) -> AssetEventReceiver {
+        self.store.subscribe_events()
+    }
+
     #[inline]
     pub fn set_budget(&mut self, steps: u32) {
         let steps = steps.max(1);
@@ -146,7 +315,20 @@ impl AssetManager {
 
     #[inline]
     pub fn pump(&self) {
+        let t0 = Instant::now();
         self.store.pump(self.budget);
+        self.last_pump_us.store(t0.elapsed().as_micros() as u64, Ordering::Relaxed);
+    }
+
+    /// Wall time spent in the most recent [`AssetManager::pump`].
+    #[inline]
+    pub fn last_pump_time(&self) -> Duration {
+        Duration::from_micros(self.last_pump_us.load(Ordering::Relaxed))
+    }
+
+    #[inline]
+    pub fn budget_steps(&self) -> u32 {
+        self.budget.steps
     }
 
     /// Convenience: pump and return any produced events.
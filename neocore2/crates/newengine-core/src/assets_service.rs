@@ -5,13 +5,19 @@ use abi_stable::std_types::{RResult, RString};
 use newengine_assets::store::ImporterBindingInfo;
 use newengine_assets::types::{AssetKey, AssetState};
 use newengine_assets::AssetStore;
-use newengine_plugin_api::{Blob, CapabilityId, MethodName, ServiceV1, ServiceV1Dyn};
+use newengine_plugin_api::{
+    guarded_service, split_page_args, Blob, CapabilityId, MethodName, ServiceV1, ServiceV1Dyn,
+};
 use serde::Serialize;
 use serde_json::json;
 use std::sync::Arc;
 
 pub const ASSET_SERVICE_ID: &str = "asset.manager";
 
+/// Page size of `asset.list_json` without a `limit=`.
+const LIST_PAGE: usize = 256;
+const LIST_PAGE_MAX: usize = 4096;
+
 pub mod method {
     pub const STATS_JSON: &str = "asset.stats_json";
     pub const IMPORTERS_JSON: &str = "asset.importers_json";
@@ -19,6 +25,12 @@ pub mod method {
     pub const INFO_JSON: &str = "asset.info_json";
     pub const LOAD: &str = "asset.load";
     pub const RELOAD: &str = "asset.reload";
+    pub const CANCEL: &str = "asset.cancel";
+    pub const READ: &str = "asset.read";
+    pub const BROWSE_JSON: &str = "asset.browse_json";
+    pub const CACHE_STATS_JSON: &str = "asset.cache_stats_json";
+    pub const CACHE_CLEAR: &str = "asset.cache_clear";
+    pub const MOUNTS_JSON: &str = "asset.mounts_json";
 }
 
 #[derive(Debug, Serialize)]
@@ -49,6 +61,13 @@ struct AssetListItem {
     bytes: Option<u64>,
 }
 
+/// One page of `asset.list_json`.
+#[derive(Debug, Serialize)]
+struct AssetListResp {
+    items: Vec<AssetListItem>,
+    next_cursor: Option<String>,
+}
+
 #[derive(Debug, Serialize)]
 struct AssetInfoResp {
     ok: bool,
@@ -59,9 +78,24 @@ struct AssetInfoResp {
     type_id: Option<String>,
     format: Option<String>,
     bytes: Option<u64>,
+    /// Source file modification time (unix ms); changes signal that a reload is due.
+    modified_unix_ms: Option<u64>,
     importer_candidates: Vec<ImporterBindingResp>,
     queue_len: usize,
     error: Option<String>,
+    /// Importer meta of the ready blob; previews need it to interpret `asset.read` bytes.
+    meta_json: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct AssetBrowseItem {
+    logical_path: String,
+    ext: Option<String>,
+    state: String,
+    type_id: Option<String>,
+    format: Option<String>,
+    bytes: Option<u64>,
+    error: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -71,6 +105,38 @@ struct LoadResp {
     error: Option<String>,
 }
 
+#[derive(Debug, Serialize)]
+struct CancelResp {
+    ok: bool,
+    id_u128: String,
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct CacheStatsResp {
+    enabled: bool,
+    dir: Option<String>,
+    entries: usize,
+    bytes: u64,
+    hits: u64,
+    misses: u64,
+    writes: u64,
+}
+
+#[derive(Debug, Serialize)]
+struct CacheClearResp {
+    ok: bool,
+    removed: usize,
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct MountResp {
+    name: String,
+    priority: i32,
+    sources: usize,
+}
+
 pub struct AssetManagerService {
     store: Arc<AssetStore>,
 }
@@ -93,10 +159,16 @@ impl ServiceV1 for AssetManagerService {
           "methods": [
             { "name": method::STATS_JSON, "payload": "empty", "returns": "json AssetStatsResp" },
             { "name": method::IMPORTERS_JSON, "payload": "empty", "returns": "json [ImporterBindingResp]" },
-            { "name": method::LIST_JSON, "payload": "empty", "returns": "json [AssetListItem]" },
+            { "name": method::LIST_JSON, "payload": "utf8 '[cursor=..] [limit=n]'", "returns": "json AssetListResp", "paged": true },
             { "name": method::INFO_JSON, "payload": "utf8 logical_path", "returns": "json AssetInfoResp" },
             { "name": method::LOAD, "payload": "utf8 logical_path", "returns": "json LoadResp" },
-            { "name": method::RELOAD, "payload": "utf8 logical_path", "returns": "json LoadResp" }
+            { "name": method::RELOAD, "payload": "utf8 logical_path", "returns": "json LoadResp" },
+            { "name": method::CANCEL, "payload": "utf8 logical_path", "returns": "json CancelResp" },
+            { "name": method::READ, "payload": "utf8 logical_path", "returns": "imported blob payload bytes (error unless ready)" },
+            { "name": method::BROWSE_JSON, "payload": "empty", "returns": "json [AssetBrowseItem]" },
+            { "name": method::CACHE_STATS_JSON, "payload": "empty", "returns": "json CacheStatsResp" },
+            { "name": method::CACHE_CLEAR, "payload": "empty", "returns": "json CacheClearResp" },
+            { "name": method::MOUNTS_JSON, "payload": "empty", "returns": "json [MountResp]" }
           ],
           "console": {
             "commands": [
@@ -118,11 +190,12 @@ impl ServiceV1 for AssetManagerService {
               },
               {
                 "name": "asset.list",
-                "help": "List known assets snapshot (ids/states)",
+                "help": "List known assets (ids/states), a page at a time; 'more' continues",
+                "usage": "asset.list [limit=<n>]",
                 "kind": "service_call",
                 "service_id": ASSET_SERVICE_ID,
                 "method": method::LIST_JSON,
-                "payload": "empty"
+                "payload": "raw"
               },
               {
                 "name": "asset.info",
@@ -150,6 +223,47 @@ impl ServiceV1 for AssetManagerService {
                 "service_id": ASSET_SERVICE_ID,
                 "method": method::RELOAD,
                 "payload": "raw"
+              },
+              {
+                "name": "asset.cancel",
+                "help": "Cancel a pending import: asset.cancel <logical_path>",
+                "usage": "asset.cancel <logical_path>",
+                "kind": "service_call",
+                "service_id": ASSET_SERVICE_ID,
+                "method": method::CANCEL,
+                "payload": "raw"
+              },
+              {
+                "name": "asset.browse",
+                "help": "List every file in the asset sources with its load state",
+                "kind": "service_call",
+                "service_id": ASSET_SERVICE_ID,
+                "method": method::BROWSE_JSON,
+                "payload": "empty"
+              },
+              {
+                "name": "asset.cache.stats",
+                "help": "Derived-data cache stats (entries, size, hits/misses)",
+                "kind": "service_call",
+                "service_id": ASSET_SERVICE_ID,
+                "method": method::CACHE_STATS_JSON,
+                "payload": "empty"
+              },
+              {
+                "name": "asset.cache.clear",
+                "help": "Delete all cached import results",
+                "kind": "service_call",
+                "service_id": ASSET_SERVICE_ID,
+                "method": method::CACHE_CLEAR,
+                "payload": "empty"
+              },
+              {
+                "name": "asset.mounts",
+                "help": "VFS mount points in lookup order, with source counts",
+                "kind": "service_call",
+                "service_id": ASSET_SERVICE_ID,
+                "method": method::MOUNTS_JSON,
+                "payload": "empty"
               }
             ]
           }
@@ -191,8 +305,17 @@ impl ServiceV1 for AssetManagerService {
                 RResult::ROk(Blob::from(bytes))
             }
             method::LIST_JSON => {
-                let list = self.store.list_snapshot(256);
-                let resp: Vec<AssetListItem> = list
+                let arg = String::from_utf8_lossy(payload.as_slice());
+                let (cursor, limit, _) = split_page_args(&arg);
+                let after = match cursor.map(|c| u128::from_str_radix(c, 16)) {
+                    Some(Ok(id)) => Some(id),
+                    Some(Err(_)) => return RResult::RErr(RString::from("bad cursor")),
+                    None => None,
+                };
+                let limit = limit.unwrap_or(LIST_PAGE).clamp(1, LIST_PAGE_MAX);
+
+                let (list, next) = self.store.list_page(after, limit);
+                let items: Vec<AssetListItem> = list
                     .into_iter()
                     .map(|x| AssetListItem {
                         id_u128: format!("{:032x}", x.id_u128),
@@ -202,6 +325,10 @@ impl ServiceV1 for AssetManagerService {
                         bytes: x.bytes,
                     })
                     .collect();
+                let resp = AssetListResp {
+                    items,
+                    next_cursor: next.map(|id| format!("{id:032x}")),
+                };
                 let bytes = serde_json::to_vec(&resp).unwrap_or_default();
                 RResult::ROk(Blob::from(bytes))
             }
@@ -219,9 +346,11 @@ impl ServiceV1 for AssetManagerService {
                         type_id: None,
                         format: None,
                         bytes: None,
+                        modified_unix_ms: None,
                         importer_candidates: Vec::new(),
                         queue_len: self.store.queue_len(),
                         error: Some("empty path".into()),
+                        meta_json: None,
                     })
                         .unwrap_or_default();
                     return RResult::ROk(Blob::from(bytes));
@@ -244,15 +373,22 @@ impl ServiceV1 for AssetManagerService {
                     AssetState::Failed(e) => ("failed".to_string(), Some(e.to_string())),
                 };
 
-                let (type_id, format, bytes_len) = match self.store.get_blob(id) {
+                let (type_id, format, bytes_len, meta_json) = match self.store.get_blob(id) {
                     Some(b) => (
                         Some(b.type_id.to_string()),
                         Some(b.format.to_string()),
                         Some(b.payload.len() as u64),
+                        Some(b.meta_json.to_string()),
                     ),
-                    None => (None, None, None),
+                    None => (None, None, None, None),
                 };
 
+                let modified_unix_ms = self
+                    .store
+                    .source_modified(&logical_path)
+                    .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                    .map(|d| d.as_millis() as u64);
+
                 let mut importer_candidates = Vec::new();
                 if let Some(ext) = ext.as_ref() {
                     for b in self.store.importer_bindings() {
@@ -281,9 +417,11 @@ impl ServiceV1 for AssetManagerService {
                     type_id,
                     format,
                     bytes: bytes_len,
+                    modified_unix_ms,
                     importer_candidates,
                     queue_len: self.store.queue_len(),
                     error: state_err,
+                    meta_json,
                 };
 
                 let bytes = serde_json::to_vec(&resp).unwrap_or_default();
@@ -355,6 +493,132 @@ impl ServiceV1 for AssetManagerService {
                     }
                 }
             }
+            method::CANCEL => {
+                let path = String::from_utf8_lossy(payload.as_slice()).trim().to_string();
+                let id = AssetKey::new(&path, 0).id();
+                let cancelled = !path.is_empty() && self.store.cancel(id);
+
+                let bytes = serde_json::to_vec(&CancelResp {
+                    ok: cancelled,
+                    id_u128: format!("{:032x}", id.to_u128()),
+                    error: (!cancelled).then(|| "no pending import for path".to_string()),
+                })
+                    .unwrap_or_default();
+                RResult::ROk(Blob::from(bytes))
+            }
+            method::READ => {
+                let path = String::from_utf8_lossy(payload.as_slice()).trim().to_string();
+                let id = AssetKey::new(&path, 0).id();
+
+                match self.store.get_blob(id) {
+                    Some(b) => RResult::ROk(Blob::from(b.payload.clone())),
+                    None => {
+                        let state = match self.store.state(id) {
+                            AssetState::Unloaded => "unloaded".to_string(),
+                            AssetState::Loading => "loading".to_string(),
+                            AssetState::Ready => "ready".to_string(),
+                            AssetState::Failed(e) => format!("failed: {e}"),
+                        };
+                        RResult::RErr(RString::from(format!("asset not ready: {path} ({state})")))
+                    }
+                }
+            }
+            method::BROWSE_JSON => {
+                use std::path::Path;
+
+                let resp: Vec<AssetBrowseItem> = self
+                    .store
+                    .source_paths()
+                    .into_iter()
+                    .map(|logical_path| {
+                        let id = AssetKey::new(&logical_path, 0).id();
+                        let ext = Path::new(&logical_path)
+                            .extension()
+                            .and_then(|s| s.to_str())
+                            .map(|s| s.to_ascii_lowercase());
+                        let (state, error) = match self.store.state(id) {
+                            AssetState::Unloaded => ("unloaded".to_string(), None),
+                            AssetState::Loading => ("loading".to_string(), None),
+                            AssetState::Ready => ("ready".to_string(), None),
+                            AssetState::Failed(e) => ("failed".to_string(), Some(e.to_string())),
+                        };
+                        let blob = self.store.get_blob(id);
+                        AssetBrowseItem {
+                            logical_path,
+                            ext,
+                            state,
+                            type_id: blob.as_ref().map(|b| b.type_id.to_string()),
+                            format: blob.as_ref().map(|b| b.format.to_string()),
+                            bytes: blob.as_ref().map(|b| b.payload.len() as u64),
+                            error,
+                        }
+                    })
+                    .collect();
+                let bytes = serde_json::to_vec(&resp).unwrap_or_default();
+                RResult::ROk(Blob::from(bytes))
+            }
+            method::CACHE_STATS_JSON => {
+                let resp = match self.store.cache() {
+                    Some(cache) => {
+                        let s = cache.stats();
+                        CacheStatsResp {
+                            enabled: true,
+                            dir: Some(s.dir.display().to_string()),
+                            entries: s.entries,
+                            bytes: s.bytes,
+                            hits: s.hits,
+                            misses: s.misses,
+                            writes: s.writes,
+                        }
+                    }
+                    None => CacheStatsResp {
+                        enabled: false,
+                        dir: None,
+                        entries: 0,
+                        bytes: 0,
+                        hits: 0,
+                        misses: 0,
+                        writes: 0,
+                    },
+                };
+                let bytes = serde_json::to_vec(&resp).unwrap_or_default();
+                RResult::ROk(Blob::from(bytes))
+            }
+            method::CACHE_CLEAR => {
+                let resp = match self.store.cache().map(|c| c.clear()) {
+                    Some(Ok(removed)) => CacheClearResp {
+                        ok: true,
+                        removed,
+                        error: None,
+                    },
+                    Some(Err(e)) => CacheClearResp {
+                        ok: false,
+                        removed: 0,
+                        error: Some(e.to_string()),
+                    },
+                    None => CacheClearResp {
+                        ok: false,
+                        removed: 0,
+                        error: Some("asset cache is disabled".to_string()),
+                    },
+                };
+                let bytes = serde_json::to_vec(&resp).unwrap_or_default();
+                RResult::ROk(Blob::from(bytes))
+            }
+            method::MOUNTS_JSON => {
+                let resp: Vec<MountResp> = self
+                    .store
+                    .mounts()
+                    .into_iter()
+                    .map(|m| MountResp {
+                        name: m.name,
+                        priority: m.priority,
+                        sources: m.sources,
+                    })
+                    .collect();
+                let bytes = serde_json::to_vec(&resp).unwrap_or_default();
+                RResult::ROk(Blob::from(bytes))
+            }
             _ => RResult::RErr(RString::from(format!("unknown method: {m}"))),
         }
     }
@@ -363,8 +627,7 @@ impl ServiceV1 for AssetManagerService {
 /// Register asset manager service into host services.
 pub fn register_asset_manager_service(asset_store: Arc<AssetStore>) {
     let svc = AssetManagerService::new(asset_store);
-    let dyn_svc: ServiceV1Dyn<'static> =
-        ServiceV1Dyn::from_value(svc, abi_stable::sabi_trait::TD_Opaque);
+    let dyn_svc: ServiceV1Dyn<'static> = guarded_service(svc);
 
     let _ = host_api::host_register_service_impl(dyn_svc, false);
 }
\ No newline at end of file
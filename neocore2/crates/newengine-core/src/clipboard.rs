@@ -0,0 +1,59 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use std::sync::{Mutex, OnceLock};
+
+/// Text clipboard backend. The platform layer installs the system one (see
+/// [`install_clipboard_backend`]); until then a process-local buffer is used so copy/paste
+/// still works inside the engine (headless, tests, tools).
+pub trait ClipboardBackend: Send {
+    fn get_text(&mut self) -> Result<String, String>;
+    fn set_text(&mut self, text: &str) -> Result<(), String>;
+}
+
+#[derive(Default)]
+struct MemoryClipboard {
+    text: String,
+}
+
+impl ClipboardBackend for MemoryClipboard {
+    #[inline]
+    fn get_text(&mut self) -> Result<String, String> {
+        Ok(self.text.clone())
+    }
+
+    #[inline]
+    fn set_text(&mut self, text: &str) -> Result<(), String> {
+        self.text.clear();
+        self.text.push_str(text);
+        Ok(())
+    }
+}
+
+static CLIPBOARD: OnceLock<Mutex<Box<dyn ClipboardBackend>>> = OnceLock::new();
+
+#[inline]
+fn clipboard() -> &'static Mutex<Box<dyn ClipboardBackend>> {
+    CLIPBOARD.get_or_init(|| Mutex::new(Box::new(MemoryClipboard::default())))
+}
+
+/// Replaces the active clipboard backend (process-wide).
+pub fn install_clipboard_backend(backend: Box<dyn ClipboardBackend>) {
+    match clipboard().lock() {
+        Ok(mut g) => *g = backend,
+        Err(_) => log::warn!("clipboard.install failed: mutex poisoned"),
+    }
+}
+
+pub fn clipboard_get() -> Result<String, String> {
+    clipboard()
+        .lock()
+        .map_err(|_| "clipboard mutex poisoned".to_string())?
+        .get_text()
+}
+
+pub fn clipboard_set(text: &str) -> Result<(), String> {
+    clipboard()
+        .lock()
+        .map_err(|_| "clipboard mutex poisoned".to_string())?
+        .set_text(text)
+}
@@ -0,0 +1,287 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// Change notifications are published on `config.<section>` (plugin sinks and `EventHub`
+/// topic subscribers) with a JSON [`ConfigChange`] payload.
+pub const CONFIG_TOPIC_PREFIX: &str = "config.";
+
+/// Topic carrying changes of `section`.
+#[inline]
+pub fn config_topic(section: &str) -> String {
+    format!("{CONFIG_TOPIC_PREFIX}{section}")
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigChange {
+    pub section: String,
+    pub key: String,
+    pub value: Value,
+}
+
+/// Checks a merged section against the type it was declared with.
+type Validator = fn(&Map<String, Value>) -> Result<(), String>;
+
+#[derive(Default)]
+struct Section {
+    /// Values loaded from the file or set at runtime; only these are persisted.
+    values: Map<String, Value>,
+    /// Declared defaults; empty for sections nobody declared (yet).
+    defaults: Map<String, Value>,
+    validate: Option<Validator>,
+}
+
+impl Section {
+    fn merged(&self) -> Map<String, Value> {
+        let mut m = self.defaults.clone();
+        for (k, v) in self.values.iter() {
+            m.insert(k.clone(), v.clone());
+        }
+        m
+    }
+}
+
+#[derive(Default)]
+struct ConfigState {
+    sections: BTreeMap<String, Section>,
+    path: Option<PathBuf>,
+    dirty: bool,
+}
+
+/// Per-module configuration sections backed by a JSON or TOML file.
+///
+/// Modules declare a section with a serde type (`declare`/`declare_with`) and get the
+/// effective value back; console `config.set` edits are type-checked against it, published
+/// on `config.<section>` and written back on engine shutdown.
+#[derive(Clone)]
+pub struct ConfigApi(Arc<Mutex<ConfigState>>);
+
+impl ConfigApi {
+    fn new() -> Self {
+        Self(Arc::new(Mutex::new(ConfigState::default())))
+    }
+
+    /// Attaches the config file and loads its sections. A missing file is not an error.
+    ///
+    /// The format follows the extension: `.toml`, otherwise JSON. Top-level tables are sections.
+    pub fn load(&self, path: PathBuf) -> Result<usize, String> {
+        let root = read_root(&path)?;
+
+        let mut g = self.lock()?;
+        for (name, v) in root {
+            let Value::Object(values) = v else {
+                log::warn!("config: skipped non-table entry '{name}'");
+                continue;
+            };
+            g.sections.entry(name).or_default().values = values;
+        }
+        g.path = Some(path);
+        g.dirty = false;
+
+        Ok(g.sections.len())
+    }
+
+    /// Declares `section` with `T::default()` as defaults; see [`ConfigApi::declare_with`].
+    #[inline]
+    pub fn declare<T>(&self, section: &str) -> T
+    where
+        T: Serialize + DeserializeOwned + Default,
+    {
+        self.declare_with(section, T::default())
+    }
+
+    /// Declares `section` with the given defaults and returns the effective value.
+    ///
+    /// File values that do not fit `T` are reported and the defaults are used instead.
+    pub fn declare_with<T>(&self, section: &str, defaults: T) -> T
+    where
+        T: Serialize + DeserializeOwned,
+    {
+        let defaults_map = match serde_json::to_value(&defaults) {
+            Ok(Value::Object(m)) => m,
+            _ => {
+                log::warn!("config: section '{section}' defaults are not a struct/map");
+                return defaults;
+            }
+        };
+
+        let merged = {
+            let Ok(mut g) = self.lock() else {
+                return defaults;
+            };
+            let s = g.sections.entry(section.to_string()).or_default();
+            s.defaults = defaults_map;
+            s.validate = Some(validate_as::<T>);
+            s.merged()
+        };
+
+        match serde_json::from_value::<T>(Value::Object(merged)) {
+            Ok(v) => v,
+            Err(e) => {
+                log::warn!("config: section '{section}' invalid, using defaults: {e}");
+                defaults
+            }
+        }
+    }
+
+    /// Effective value of a section (defaults overlaid with file/runtime values).
+    pub fn get<T: DeserializeOwned>(&self, section: &str) -> Option<T> {
+        let merged = self.section_json(section)?;
+        serde_json::from_value(Value::Object(merged)).ok()
+    }
+
+    pub fn section_json(&self, section: &str) -> Option<Map<String, Value>> {
+        let g = self.lock().ok()?;
+        g.sections.get(section).map(Section::merged)
+    }
+
+    pub fn get_value(&self, section: &str, key: &str) -> Option<Value> {
+        self.section_json(section)?.remove(key)
+    }
+
+    #[inline]
+    pub fn set<T: Serialize>(&self, section: &str, key: &str, value: &T) -> Result<(), String> {
+        let value = serde_json::to_value(value)
+            .map_err(|e| format!("config: encode '{section}.{key}' failed: {e}"))?;
+        self.set_value(section, key, value)
+    }
+
+    /// Sets one key and notifies `config.<section>` subscribers.
+    ///
+    /// Declared sections reject unknown keys and values that do not fit their type.
+    pub fn set_value(&self, section: &str, key: &str, value: Value) -> Result<(), String> {
+        {
+            let mut g = self.lock()?;
+            let s = g.sections.entry(section.to_string()).or_default();
+
+            if let Some(validate) = s.validate {
+                if !s.defaults.contains_key(key) {
+                    return Err(format!("unknown key '{key}' in section '{section}'"));
+                }
+                let mut merged = s.merged();
+                merged.insert(key.to_string(), value.clone());
+                validate(&merged)
+                    .map_err(|e| format!("invalid value for '{section}.{key}': {e}"))?;
+            }
+
+            if s.values.get(key) == Some(&value) {
+                return Ok(());
+            }
+            s.values.insert(key.to_string(), value.clone());
+            g.dirty = true;
+        }
+
+        log::info!("config: set {section}.{key}={value}");
+
+        let change = ConfigChange {
+            section: section.to_string(),
+            key: key.to_string(),
+            value,
+        };
+        let payload = serde_json::to_vec(&change).unwrap_or_default();
+        if let Err(e) =
+            crate::plugins::event_router::enqueue(&config_topic(section), &payload, true)
+        {
+            log::warn!("config: change notification dropped: {e}");
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    pub fn sections(&self) -> Vec<String> {
+        self.lock()
+            .map(|g| g.sections.keys().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Writes file/runtime values back if anything changed. Returns whether it wrote.
+    pub fn save(&self) -> Result<bool, String> {
+        let mut g = self.lock()?;
+        let Some(path) = g.path.clone() else {
+            return Ok(false);
+        };
+        if !g.dirty {
+            return Ok(false);
+        }
+
+        let root: Map<String, Value> = g
+            .sections
+            .iter()
+            .filter(|(_, s)| !s.values.is_empty())
+            .map(|(name, s)| (name.clone(), Value::Object(s.values.clone())))
+            .collect();
+
+        write_root(&path, root)?;
+        g.dirty = false;
+
+        log::info!("config: saved path='{}'", path.display());
+        Ok(true)
+    }
+
+    #[inline]
+    fn lock(&self) -> Result<std::sync::MutexGuard<'_, ConfigState>, String> {
+        self.0
+            .lock()
+            .map_err(|_| "config mutex poisoned".to_string())
+    }
+}
+
+fn validate_as<T: DeserializeOwned>(m: &Map<String, Value>) -> Result<(), String> {
+    serde_json::from_value::<T>(Value::Object(m.clone()))
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+#[inline]
+fn is_toml(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("toml"))
+}
+
+fn read_root(path: &Path) -> Result<Map<String, Value>, String> {
+    let text = match std::fs::read_to_string(path) {
+        Ok(t) => t,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Map::new()),
+        Err(e) => return Err(format!("config read failed path='{}': {e}", path.display())),
+    };
+
+    let value: Value = if is_toml(path) {
+        toml::from_str(&text)
+            .map_err(|e| format!("config parse failed path='{}': {e}", path.display()))?
+    } else {
+        serde_json::from_str(&text)
+            .map_err(|e| format!("config parse failed path='{}': {e}", path.display()))?
+    };
+
+    match value {
+        Value::Object(m) => Ok(m),
+        _ => Err(format!("config '{}' is not a table", path.display())),
+    }
+}
+
+fn write_root(path: &Path, root: Map<String, Value>) -> Result<(), String> {
+    let value = Value::Object(root);
+    let text = if is_toml(path) {
+        toml::to_string_pretty(&value).map_err(|e| format!("config encode failed: {e}"))?
+    } else {
+        serde_json::to_string_pretty(&value).map_err(|e| format!("config encode failed: {e}"))?
+    };
+
+    std::fs::write(path, text)
+        .map_err(|e| format!("config write failed path='{}': {e}", path.display()))
+}
+
+static CONFIG_API: OnceLock<ConfigApi> = OnceLock::new();
+
+/// Process-wide configuration handle.
+#[inline]
+pub fn config_api() -> ConfigApi {
+    CONFIG_API.get_or_init(ConfigApi::new).clone()
+}
@@ -0,0 +1,177 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use crate::config::config_api;
+use crate::plugins::host_api;
+use abi_stable::std_types::{RResult, RString};
+use newengine_plugin_api::{
+    guarded_service, Blob, CapabilityId, MethodName, ServiceV1, ServiceV1Dyn,
+};
+use serde::Serialize;
+use serde_json::{json, Value};
+
+pub const CONFIG_SERVICE_ID: &str = "engine.config";
+
+pub mod method {
+    pub const GET_JSON: &str = "config.get_json";
+    pub const SET: &str = "config.set";
+    pub const SAVE: &str = "config.save";
+}
+
+#[derive(Debug, Serialize)]
+struct ConfigSetResp {
+    ok: bool,
+    section: String,
+    key: String,
+    value: Value,
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct ConfigSaveResp {
+    ok: bool,
+    saved: bool,
+    error: Option<String>,
+}
+
+struct ConfigService;
+
+impl ConfigService {
+    /// `section.key` -> (`section`, `key`); sections may themselves contain dots.
+    #[inline]
+    fn split_path(path: &str) -> Option<(&str, &str)> {
+        path.rsplit_once('.')
+            .filter(|(s, k)| !s.is_empty() && !k.is_empty())
+    }
+
+    /// Payload: empty (all sections), `<section>` or `<section>.<key>`.
+    fn get(arg: &str) -> Result<Value, String> {
+        let api = config_api();
+        let path = arg.trim();
+
+        if path.is_empty() {
+            let all = api
+                .sections()
+                .into_iter()
+                .filter_map(|s| api.section_json(&s).map(|m| (s, Value::Object(m))))
+                .collect::<serde_json::Map<_, _>>();
+            return Ok(Value::Object(all));
+        }
+
+        if let Some(m) = api.section_json(path) {
+            return Ok(Value::Object(m));
+        }
+
+        let (section, key) =
+            Self::split_path(path).ok_or_else(|| format!("unknown config section: '{path}'"))?;
+        api.get_value(section, key)
+            .ok_or_else(|| format!("unknown config key: '{path}'"))
+    }
+
+    /// Payload: `<section>.<key> <value>`; the value is JSON, or a plain string otherwise.
+    fn set(arg: &str) -> ConfigSetResp {
+        let arg = arg.trim();
+        let (path, raw) = arg.split_once(char::is_whitespace).unwrap_or((arg, ""));
+        let raw = raw.trim();
+        let value =
+            serde_json::from_str::<Value>(raw).unwrap_or_else(|_| Value::String(raw.to_string()));
+
+        let res = match Self::split_path(path) {
+            None => Err("usage: config.set <section>.<key> <value>".to_string()),
+            Some(_) if raw.is_empty() => Err("missing value".to_string()),
+            Some((section, key)) => config_api().set_value(section, key, value.clone()),
+        };
+
+        let (section, key) = Self::split_path(path).unwrap_or((path, ""));
+        ConfigSetResp {
+            ok: res.is_ok(),
+            section: section.to_string(),
+            key: key.to_string(),
+            value,
+            error: res.err(),
+        }
+    }
+}
+
+impl ServiceV1 for ConfigService {
+    fn id(&self) -> CapabilityId {
+        RString::from(CONFIG_SERVICE_ID)
+    }
+
+    fn describe(&self) -> RString {
+        let d = json!({
+          "id": CONFIG_SERVICE_ID,
+          "version": 1,
+          "methods": [
+            { "name": method::GET_JSON, "payload": "utf8 '[section[.key]]'", "returns": "json value" },
+            { "name": method::SET, "payload": "utf8 '<section>.<key> <json value>'", "returns": "json ConfigSetResp" },
+            { "name": method::SAVE, "payload": "empty", "returns": "json ConfigSaveResp" }
+          ],
+          "console": {
+            "commands": [
+              {
+                "name": "config.get",
+                "help": "Show configuration: config.get [section[.key]]",
+                "usage": "config.get [section[.key]]",
+                "kind": "service_call",
+                "service_id": CONFIG_SERVICE_ID,
+                "method": method::GET_JSON,
+                "payload": "raw"
+              },
+              {
+                "name": "config.set",
+                "help": "Change a setting live: config.set <section>.<key> <value>",
+                "usage": "config.set <section>.<key> <value>",
+                "kind": "service_call",
+                "service_id": CONFIG_SERVICE_ID,
+                "method": method::SET,
+                "payload": "raw"
+              },
+              {
+                "name": "config.save",
+                "help": "Write changed settings to the config file now",
+                "kind": "service_call",
+                "service_id": CONFIG_SERVICE_ID,
+                "method": method::SAVE,
+                "payload": "empty"
+              }
+            ]
+          }
+        });
+
+        RString::from(d.to_string())
+    }
+
+    fn call(&self, method: MethodName, payload: Blob) -> RResult<Blob, RString> {
+        let m = method.to_string();
+        let arg = String::from_utf8_lossy(payload.as_slice());
+
+        let resp = match m.as_str() {
+            method::GET_JSON => match Self::get(&arg) {
+                Ok(v) => serde_json::to_vec(&v),
+                Err(e) => return RResult::RErr(RString::from(e)),
+            },
+            method::SET => serde_json::to_vec(&Self::set(&arg)),
+            method::SAVE => serde_json::to_vec(&match config_api().save() {
+                Ok(saved) => ConfigSaveResp {
+                    ok: true,
+                    saved,
+                    error: None,
+                },
+                Err(e) => ConfigSaveResp {
+                    ok: false,
+                    saved: false,
+                    error: Some(e),
+                },
+            }),
+            _ => return RResult::RErr(RString::from(format!("unknown method: {m}"))),
+        };
+
+        RResult::ROk(Blob::from(resp.unwrap_or_default()))
+    }
+}
+
+pub fn register_config_service() {
+    let dyn_svc: ServiceV1Dyn<'static> = guarded_service(ConfigService);
+
+    let _ = host_api::host_register_service_impl(dyn_svc, false);
+}
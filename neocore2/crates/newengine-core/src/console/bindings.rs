@@ -0,0 +1,187 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Key of the bindings object inside the user config file.
+const BINDINGS_KEY: &str = "bindings";
+
+/// Bound unless the user config rebinds the key or stores it as `null` (see [`KeyBindings`]).
+const DEFAULT_BINDINGS: &[(&str, &str)] = &[
+    ("f3", "stats.toggle"),
+    ("ctrl+z", "edit.undo"),
+    ("ctrl+y", "edit.redo"),
+];
+
+/// Modifier prefixes of a key name, in the order normalized names carry them.
+const MODIFIERS: [&str; 3] = ["ctrl", "alt", "shift"];
+
+/// Hotkey -> console line map, persisted under `"bindings"` in the user config.
+///
+/// Keys are stored normalized (see [`normalize_key`]), so `F5`, `f5` and winit's `F5` match.
+/// [`DEFAULT_BINDINGS`] are part of the map like any other binding; an unbound default is
+/// saved as `"key": null` so it stays unbound on the next load.
+#[derive(Debug)]
+pub(crate) struct KeyBindings {
+    map: BTreeMap<String, String>,
+    path: Option<PathBuf>,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        let mut b = Self {
+            map: BTreeMap::new(),
+            path: None,
+        };
+        b.reset_defaults();
+        b
+    }
+}
+
+impl KeyBindings {
+    /// Attaches the user config file and loads its bindings. A missing file is not an error.
+    pub(crate) fn load(&mut self, path: PathBuf) -> Result<usize, String> {
+        let root = read_root(&path)?;
+        self.path = Some(path);
+
+        self.reset_defaults();
+        if let Some(Value::Object(b)) = root.get(BINDINGS_KEY) {
+            for (k, v) in b {
+                let Some(key) = normalize_key(k) else {
+                    log::warn!("console.bind skipped invalid entry key='{k}'");
+                    continue;
+                };
+                match v {
+                    Value::String(line) => {
+                        self.map.insert(key, line.trim().to_string());
+                    }
+                    Value::Null => {
+                        self.map.remove(&key);
+                    }
+                    _ => log::warn!("console.bind skipped invalid entry key='{k}'"),
+                }
+            }
+        }
+
+        Ok(self.map.len())
+    }
+
+    #[inline]
+    pub(crate) fn get(&self, key: &str) -> Option<&str> {
+        self.map.get(&normalize_key(key)?).map(String::as_str)
+    }
+
+    #[inline]
+    pub(crate) fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.map.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    pub(crate) fn bind(&mut self, key: &str, line: &str) -> Result<String, String> {
+        let key = normalize_key(key).ok_or_else(|| format!("invalid key: '{key}'"))?;
+        self.map.insert(key.clone(), line.trim().to_string());
+        self.save()?;
+        Ok(key)
+    }
+
+    pub(crate) fn unbind(&mut self, key: &str) -> Result<Option<String>, String> {
+        let key = normalize_key(key).ok_or_else(|| format!("invalid key: '{key}'"))?;
+        let old = self.map.remove(&key);
+        if old.is_some() {
+            self.save()?;
+        }
+        Ok(old)
+    }
+
+    fn reset_defaults(&mut self) {
+        self.map = DEFAULT_BINDINGS
+            .iter()
+            .map(|(k, line)| (k.to_string(), line.to_string()))
+            .collect();
+    }
+
+    /// Rewrites only the `"bindings"` entry; other user settings in the file are kept.
+    fn save(&self) -> Result<(), String> {
+        let Some(path) = self.path.as_deref() else {
+            return Ok(());
+        };
+
+        let mut root = read_root(path)?;
+        let unbound = DEFAULT_BINDINGS
+            .iter()
+            .filter(|(k, _)| !self.map.contains_key(*k))
+            .map(|(k, _)| (k.to_string(), Value::Null));
+        let bindings = self
+            .map
+            .iter()
+            .map(|(k, v)| (k.clone(), Value::String(v.clone())))
+            .chain(unbound)
+            .collect::<Map<_, _>>();
+        root.insert(BINDINGS_KEY.to_string(), Value::Object(bindings));
+
+        let text = serde_json::to_string_pretty(&Value::Object(root))
+            .map_err(|e| format!("user config encode failed: {e}"))?;
+        std::fs::write(path, text)
+            .map_err(|e| format!("user config write failed path='{}': {e}", path.display()))
+    }
+}
+
+/// Canonical key name: lowercase, winit prefixes dropped (`KeyA` -> `a`, `Digit1` -> `1`),
+/// modifiers first in a fixed order (`Shift+Ctrl+KeyZ` -> `ctrl+shift+z`).
+pub(crate) fn normalize_key(name: &str) -> Option<String> {
+    let k = name.trim().to_ascii_lowercase();
+    if k.is_empty() || k.chars().any(char::is_whitespace) {
+        return None;
+    }
+
+    let mut parts: Vec<&str> = k.split('+').collect();
+    let key = parts.pop().filter(|key| !key.is_empty())?;
+    let mut held = [false; MODIFIERS.len()];
+    for m in parts {
+        let m = if m == "control" { "ctrl" } else { m };
+        held[MODIFIERS.iter().position(|x| *x == m)?] = true;
+    }
+
+    let mut out = String::new();
+    for (m, _) in MODIFIERS.iter().zip(held).filter(|(_, h)| *h) {
+        out.push_str(m);
+        out.push('+');
+    }
+
+    for prefix in ["key", "digit"] {
+        if let Some(rest) = key.strip_prefix(prefix) {
+            if rest.len() == 1 && rest.chars().all(|c| c.is_ascii_alphanumeric()) {
+                out.push_str(rest);
+                return Some(out);
+            }
+        }
+    }
+
+    out.push_str(key);
+    Some(out)
+}
+
+fn read_root(path: &Path) -> Result<Map<String, Value>, String> {
+    let text = match std::fs::read_to_string(path) {
+        Ok(t) => t,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Map::new()),
+        Err(e) => {
+            return Err(format!(
+                "user config read failed path='{}': {e}",
+                path.display()
+            ))
+        }
+    };
+
+    match serde_json::from_str::<Value>(&text) {
+        Ok(Value::Object(m)) => Ok(m),
+        Ok(_) => Err(format!(
+            "user config '{}' is not a JSON object",
+            path.display()
+        )),
+        Err(e) => Err(format!(
+            "user config parse failed path='{}': {e}",
+            path.display()
+        )),
+    }
+}
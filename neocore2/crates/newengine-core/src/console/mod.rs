@@ -1,9 +1,15 @@
 #![forbid(unsafe_op_in_unsafe_fn)]
 
+mod bindings;
 mod method;
 mod runtime;
+mod schema;
 mod service;
 mod types;
 
 pub use method::COMMAND_SERVICE_ID;
-pub use service::{init_console_service, take_exit_requested};
+pub use runtime::WATCH_TOPIC;
+pub use service::{
+    init_console_service, load_key_bindings, run_autoexec, run_key_binding, take_exit_requested,
+    tick_watches,
+};
@@ -1,15 +1,47 @@
 #![forbid(unsafe_op_in_unsafe_fn)]
 
-use crate::plugins::host_context;
+use crate::cvar;
+use crate::plugins::{host_api, host_context, limits};
+use crate::undo::{CvarSet, UndoStack};
 
+use super::bindings::KeyBindings;
+use super::schema;
 use super::types::{ConsoleCmdEntry, DynCommand, DynPayload, SuggestItem, SuggestResponse};
 
+use newengine_plugin_api::{split_page_args, PAGE_CURSOR_ARG, PAGE_LIMIT_ARG, PAGE_NEXT_FIELD};
+use std::cell::Cell;
 use std::collections::BTreeMap;
-use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
 
 type CmdFn = fn(&ConsoleRuntime, &str) -> Result<String, String>;
 
+/// Commands whose first argument is a cvar or variable name.
+const VAR_COMMANDS: [&str; 3] = ["set", "get", "cvar.reset"];
+
+/// Limit for aliases and `exec` files running each other.
+const MAX_EXEC_DEPTH: u32 = 16;
+
+thread_local! {
+    /// Nesting of `exec` calls (aliases, `.cfg` files) on this thread. Per thread so that
+    /// lines run concurrently (console UI, services, watches) do not count toward each other.
+    static EXEC_DEPTH: Cell<u32> = const { Cell::new(0) };
+}
+
+/// Shortest `watch` interval, in seconds.
+const MIN_WATCH_INTERVAL: f32 = 0.05;
+const MAX_WATCHES: usize = 16;
+
+/// Each `watch` run is published here as JSON `{id, command, ok, output?, error?}`.
+pub const WATCH_TOPIC: &str = "console.watch";
+
+/// `cmd > file` / `cmd >> file` write under this directory.
+const OUTPUT_DIR: &str = "logs";
+
+/// Run once at startup by [`ConsoleRuntime::run_autoexec`] when present.
+const AUTOEXEC_FILE: &str = "autoexec.cfg";
+
 struct Cmd {
     help: &'static str,
     usage: &'static str,
@@ -21,12 +53,55 @@ pub struct ConsoleRuntime {
 
     dyn_cmds: Mutex<BTreeMap<String, DynCommand>>,
     method_cache: Mutex<BTreeMap<String, Vec<String>>>,
+    /// `<service_id>::<method>` -> payload schema published in the method's describe entry.
+    schemas: Mutex<BTreeMap<String, serde_json::Value>>,
 
     cached_services_gen: AtomicU64,
 
+    bindings: Mutex<KeyBindings>,
+
+    /// Alias name -> command line run in its place.
+    aliases: Mutex<BTreeMap<String, String>>,
+    /// Untyped console variables, expanded as `$name` in command lines like registered cvars.
+    vars: Mutex<BTreeMap<String, String>>,
+
+    /// Continuation of the last paged service call, run by `more`.
+    page: Mutex<Option<PendingPage>>,
+    /// Output of the last command, filtered by a standalone `grep`.
+    last_output: Mutex<String>,
+
+    watches: Mutex<Vec<Watch>>,
+    next_watch_id: AtomicU32,
+
     exit_requested: AtomicBool,
 }
 
+/// A command line `watch` re-runs every `interval` seconds of wall-clock time.
+struct Watch {
+    id: u32,
+    interval: f32,
+    remaining: f32,
+    line: String,
+}
+
+/// A paged call whose response had a `next_cursor`.
+struct PendingPage {
+    service_id: String,
+    method: String,
+    /// Payload without its `cursor=` token.
+    args: String,
+    cursor: String,
+}
+
+/// Decrements the exec depth when a nested `exec` returns.
+struct DepthGuard;
+
+impl Drop for DepthGuard {
+    fn drop(&mut self) {
+        EXEC_DEPTH.with(|d| d.set(d.get() - 1));
+    }
+}
+
 impl ConsoleRuntime {
     pub fn new() -> Self {
         let mut cmds = BTreeMap::<&'static str, Cmd>::new();
@@ -86,6 +161,121 @@ impl ConsoleRuntime {
             },
         );
 
+        cmds.insert(
+            "bind",
+            Cmd {
+                help: "Bind a key to a console line (no args: list bindings)",
+                usage: "bind [<key> [<command line>]]",
+                f: |rt, line| rt.bind_cmd(line),
+            },
+        );
+
+        cmds.insert(
+            "unbind",
+            Cmd {
+                help: "Remove a key binding",
+                usage: "unbind <key>",
+                f: |rt, line| rt.unbind_cmd(line),
+            },
+        );
+
+        cmds.insert(
+            "alias",
+            Cmd {
+                help: "Define a command alias; quote lines with ';' (no args: list aliases)",
+                usage: "alias [<name> [<command line>]]",
+                f: |rt, line| rt.alias_cmd(line),
+            },
+        );
+
+        cmds.insert(
+            "unalias",
+            Cmd {
+                help: "Remove a command alias",
+                usage: "unalias <name>",
+                f: |rt, line| rt.unalias_cmd(line),
+            },
+        );
+
+        cmds.insert(
+            "set",
+            Cmd {
+                help: "Set a cvar or console variable, used as $name (no args: list all)",
+                usage: "set [<name> [<value>]]",
+                f: |rt, line| rt.set_cmd(line),
+            },
+        );
+
+        cmds.insert(
+            "get",
+            Cmd {
+                help: "Print a cvar or console variable",
+                usage: "get <name>",
+                f: |rt, line| rt.get_cmd(line),
+            },
+        );
+
+        cmds.insert(
+            "echo",
+            Cmd {
+                help: "Print text (with $variables expanded)",
+                usage: "echo <text>",
+                f: |_, line| Ok(args_of(line, "echo").to_string()),
+            },
+        );
+
+        cmds.insert(
+            "exec",
+            Cmd {
+                help: "Run a .cfg file line by line (file system path or asset)",
+                usage: "exec <file>",
+                f: |rt, line| rt.exec_cmd(line),
+            },
+        );
+
+        cmds.insert(
+            "grep",
+            Cmd {
+                help: "Filter the previous output by text (-i ignore case, -v invert) or after |",
+                usage: "grep [-i] [-v] <text>",
+                f: |rt, line| {
+                    let prev = rt
+                        .last_output
+                        .lock()
+                        .map_err(|_| "output mutex poisoned".to_string())?
+                        .clone();
+                    grep_lines(&prev, args_of(line, "grep"))
+                },
+            },
+        );
+
+        cmds.insert(
+            "watch",
+            Cmd {
+                help: "Re-run a command every interval (1, 0.5s, 250ms); no args: list watches",
+                usage: "watch [<interval> <command line>]",
+                f: |rt, line| rt.watch_cmd(line),
+            },
+        );
+
+        cmds.insert(
+            "unwatch",
+            Cmd {
+                help: "Stop a watch by id, or all of them",
+                usage: "unwatch <id|all>",
+                f: |rt, line| rt.unwatch_cmd(line),
+            },
+        );
+
+        cmds.insert(
+            "more",
+            Cmd {
+                help: "Fetch the next page of the last paged result (asset.list, log.tail)",
+                usage: "more",
+                f: |rt, _| rt.more_cmd(),
+            },
+        );
+
         cmds.insert(
             "quit",
             Cmd {
@@ -102,7 +292,15 @@ impl ConsoleRuntime {
             cmds,
             dyn_cmds: Mutex::new(BTreeMap::new()),
             method_cache: Mutex::new(BTreeMap::new()),
+            schemas: Mutex::new(BTreeMap::new()),
             cached_services_gen: AtomicU64::new(0),
+            bindings: Mutex::new(KeyBindings::default()),
+            aliases: Mutex::new(BTreeMap::new()),
+            vars: Mutex::new(BTreeMap::new()),
+            page: Mutex::new(None),
+            last_output: Mutex::new(String::new()),
+            watches: Mutex::new(Vec::new()),
+            next_watch_id: AtomicU32::new(1),
             exit_requested: AtomicBool::new(false),
         }
     }
@@ -111,7 +309,60 @@ impl ConsoleRuntime {
         self.exit_requested.swap(false, Ordering::AcqRel)
     }
 
+    /// Runs a console line: `;`-separated commands (outside double quotes), each with
+    /// `$name` variables expanded, stopping at the first error.
+    ///
+    /// A command may be followed by `| grep <text>` filters and a final `> file` or
+    /// `>> file` (append), written under `logs/`; the `>` must stand between spaces.
+    /// Alias and watch bodies are kept as written and expanded each time they run.
     pub fn exec(&self, line: &str) -> Result<String, String> {
+        let depth = EXEC_DEPTH.with(|d| d.replace(d.get() + 1));
+        let _guard = DepthGuard;
+        if depth >= MAX_EXEC_DEPTH {
+            return Err(format!("nesting deeper than {MAX_EXEC_DEPTH} (recursive alias?)"));
+        }
+
+        let mut out = Vec::new();
+        for cmd in split_commands(line) {
+            // Alias and watch bodies keep their variables, pipes and redirects until they run.
+            let res = if defines_body(cmd) {
+                self.exec_one(cmd)?
+            } else {
+                self.exec_pipeline(&self.expand_vars(cmd))?
+            };
+            if !res.is_empty() {
+                out.push(res);
+            }
+        }
+        Ok(out.join("\n"))
+    }
+
+    fn exec_pipeline(&self, cmd: &str) -> Result<String, String> {
+        let mut stages = split_unquoted(cmd, '|');
+        let (last, redirect) = split_redirect(stages.pop().unwrap_or(""));
+        stages.push(last);
+
+        let mut stages = stages.into_iter();
+        let mut res = self.exec_one(stages.next().unwrap_or(""))?;
+        for stage in stages {
+            let filter = stage
+                .strip_prefix("grep")
+                .filter(|a| a.is_empty() || a.starts_with(char::is_whitespace))
+                .ok_or_else(|| format!("only grep can follow '|', got '{stage}'"))?;
+            res = grep_lines(&res, filter.trim())?;
+        }
+
+        if let Ok(mut g) = self.last_output.lock() {
+            g.clone_from(&res);
+        }
+
+        match redirect {
+            Some(target) => write_output(target, &res),
+            None => Ok(res),
+        }
+    }
+
+    fn exec_one(&self, line: &str) -> Result<String, String> {
         let line = line.trim();
         if line.is_empty() {
             return Ok(String::new());
@@ -132,7 +383,7 @@ impl ConsoleRuntime {
             let args = it.collect::<Vec<_>>().join(" ");
             let payload = match d.payload {
                 DynPayload::Empty => Vec::new(),
-                DynPayload::Raw => args.into_bytes(),
+                DynPayload::Raw => self.prepare_payload(&d.service_id, &d.method, &args)?,
             };
             return self.call_service_raw(&d.service_id, &d.method, &payload);
         }
@@ -141,9 +392,64 @@ impl ConsoleRuntime {
             return (c.f)(self, line);
         }
 
+        let alias = self
+            .aliases
+            .lock()
+            .map_err(|_| "aliases mutex poisoned".to_string())?
+            .get(head)
+            .cloned();
+        if let Some(body) = alias {
+            // Arguments after the alias name are appended to its last command.
+            let args = args_of(line, head);
+            if args.is_empty() {
+                return self.exec(&body);
+            }
+            return self.exec(&format!("{body} {args}"));
+        }
+
         Err(format!("unknown command: {head}"))
     }
 
+    /// Replaces `$name` with the variable's value; unknown names stay as written, `$$` is `$`.
+    fn expand_vars(&self, line: &str) -> String {
+        if !line.contains('$') {
+            return line.to_string();
+        }
+        let Ok(vars) = self.vars.lock() else {
+            return line.to_string();
+        };
+
+        let mut out = String::with_capacity(line.len());
+        let mut rest = line;
+        while let Some(i) = rest.find('$') {
+            out.push_str(&rest[..i]);
+            let after = &rest[i + 1..];
+            if let Some(tail) = after.strip_prefix('$') {
+                out.push('$');
+                rest = tail;
+                continue;
+            }
+
+            let len = after
+                .find(|c: char| !is_name_char(c))
+                .unwrap_or(after.len());
+            let name = &after[..len];
+            let value = cvar::cvar_get(name)
+                .map(|v| v.to_string())
+                .or_else(|| vars.get(name).cloned());
+            match value {
+                Some(v) if !name.is_empty() => out.push_str(&v),
+                _ => {
+                    out.push('$');
+                    out.push_str(name);
+                }
+            }
+            rest = &after[len..];
+        }
+        out.push_str(rest);
+        out
+    }
+
     pub fn complete(&self, input: &str) -> Vec<String> {
         self.refresh_if_services_changed();
 
@@ -153,6 +459,15 @@ impl ConsoleRuntime {
             return self.complete_service_id(rest.trim());
         }
 
+        for head in VAR_COMMANDS {
+            if let Some(rest) = s.strip_prefix(head).and_then(|r| r.strip_prefix(' ')) {
+                let prefix = rest.trim_start();
+                if !prefix.contains(char::is_whitespace) {
+                    return self.var_names(prefix).into_iter().map(|(n, _)| n).collect();
+                }
+            }
+        }
+
         if let Some(rest) = s.strip_prefix("call ") {
             let mut parts = rest.split_whitespace();
             let sid = parts.next().unwrap_or("");
@@ -183,6 +498,10 @@ impl ConsoleRuntime {
             }
         }
 
+        if let Ok(g) = self.aliases.lock() {
+            out.extend(g.keys().filter(|k| k.starts_with(head)).cloned());
+        }
+
         out.sort();
         out.dedup();
         out
@@ -248,6 +567,17 @@ impl ConsoleRuntime {
 
             let sid = if tokens.len() >= 2 { tokens[1] } else { "" };
             let want_methods = tokens.len() >= 3 || (ends_with_space && tokens.len() == 2);
+            let want_payload = tokens.len() >= 4 || (ends_with_space && tokens.len() == 3);
+
+            if want_payload {
+                let method = tokens[2];
+                let args = &tokens[3..];
+                let signature = self
+                    .suggest_payload(sid, method, raw, args, ends_with_space, &mut items)
+                    .map(|usage| format!("call {sid} {method} {usage}"))
+                    .unwrap_or(signature);
+                return SuggestResponse { signature, items };
+            }
 
             if sid.is_empty() || !want_methods {
                 let prefix = sid;
@@ -277,18 +607,48 @@ impl ConsoleRuntime {
             return SuggestResponse { signature, items };
         }
 
+        let naming_var = tokens.len() == 1 || (tokens.len() == 2 && !ends_with_space);
+        if VAR_COMMANDS.contains(&head) && naming_var {
+            let prefix = tokens.get(1).copied().unwrap_or("");
+            for (name, help) in self.var_names(prefix) {
+                items.push(SuggestItem {
+                    kind: "cvar".into(),
+                    insert: format!("{head} {name} "),
+                    display: name,
+                    help,
+                    usage: String::new(),
+                });
+            }
+        }
+
         if let Some(c) = self.cmds.get(head) {
             let signature = c.usage.to_string();
+            for it in items.iter_mut() {
+                it.usage = signature.clone();
+            }
             return SuggestResponse { signature, items };
         }
 
-        if let Ok(g) = self.dyn_cmds.lock() {
-            if let Some(d) = g.get(head) {
-                return SuggestResponse {
-                    signature: d.usage.clone(),
-                    items,
-                };
+        let dyn_cmd = self.dyn_cmds.lock().ok().and_then(|g| g.get(head).cloned());
+        if let Some(d) = dyn_cmd {
+            if matches!(d.payload, DynPayload::Raw) {
+                let args = &tokens[1..];
+                let _ = self.suggest_payload(
+                    &d.service_id,
+                    &d.method,
+                    raw,
+                    args,
+                    ends_with_space,
+                    &mut items,
+                );
             }
+            for it in items.iter_mut() {
+                it.usage = d.usage.clone();
+            }
+            return SuggestResponse {
+                signature: d.usage,
+                items,
+            };
         }
 
         SuggestResponse {
@@ -333,6 +693,42 @@ impl ConsoleRuntime {
                 }
             }
         }
+
+        if let Ok(g) = self.aliases.lock() {
+            for (name, body) in g.iter().filter(|(n, _)| n.starts_with(prefix)) {
+                out.push(SuggestItem {
+                    kind: "alias".into(),
+                    display: name.clone(),
+                    insert: name.clone(),
+                    help: body.clone(),
+                    usage: name.clone(),
+                });
+            }
+        }
+    }
+
+    /// Registered cvars and console variables starting with `prefix`, with a help line each.
+    fn var_names(&self, prefix: &str) -> Vec<(String, String)> {
+        let mut out: Vec<(String, String)> = cvar::cvar_list(prefix)
+            .into_iter()
+            .map(|c| {
+                let help = match c.desc.help.as_str() {
+                    "" => format!("{:?} = {}", c.desc.kind, c.value),
+                    h => format!("{h} ({:?} = {})", c.desc.kind, c.value),
+                };
+                (c.desc.name, help)
+            })
+            .collect();
+
+        if let Ok(g) = self.vars.lock() {
+            for (name, value) in g.iter().filter(|(n, _)| n.starts_with(prefix)) {
+                out.push((name.clone(), format!("variable = {value}")));
+            }
+        }
+
+        out.sort();
+        out.dedup_by(|a, b| a.0 == b.0);
+        out
     }
 
     fn complete_service_id(&self, prefix: &str) -> Vec<String> {
@@ -408,18 +804,11 @@ impl ConsoleRuntime {
         let mut methods = Vec::new();
 
         if let Ok(val) = serde_json::from_str::<serde_json::Value>(&json) {
-            if let Some(arr) = val.get("methods").and_then(|v| v.as_array()) {
-                for m in arr {
-                    if let Some(name) = m.get("name").and_then(|v| v.as_str()) {
-                        methods.push(name.to_string());
-                    }
-                }
+            if let Ok(mut g) = self.schemas.lock() {
+                methods = read_methods(service_id, &val, &mut g);
             }
         }
 
-        methods.sort();
-        methods.dedup();
-
         if let Ok(mut g) = self.method_cache.lock() {
             let _ = g.insert(service_id.to_string(), methods);
         }
@@ -428,6 +817,7 @@ impl ConsoleRuntime {
     pub fn refresh_dyn_commands(&self) {
         let mut out: BTreeMap<String, DynCommand> = BTreeMap::new();
         let mut methods: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        let mut schemas: BTreeMap<String, serde_json::Value> = BTreeMap::new();
 
         let c = host_context::ctx();
         let services = match c.services.lock() {
@@ -439,6 +829,9 @@ impl ConsoleRuntime {
                 if let Ok(mut g) = self.method_cache.lock() {
                     g.clear();
                 }
+                if let Ok(mut g) = self.schemas.lock() {
+                    g.clear();
+                }
                 self.cached_services_gen
                     .store(host_context::services_generation(), Ordering::Release);
                 return;
@@ -452,16 +845,8 @@ impl ConsoleRuntime {
                 continue;
             };
 
-            if let Some(arr) = v.get("methods").and_then(|x| x.as_array()) {
-                let mut mm = Vec::new();
-                for m in arr {
-                    if let Some(name) = m.get("name").and_then(|x| x.as_str()) {
-                        mm.push(name.to_string());
-                    }
-                }
-                mm.sort();
-                mm.dedup();
-                methods.insert(id.clone(), mm);
+            if v.get("methods").is_some_and(|x| x.is_array()) {
+                methods.insert(id.clone(), read_methods(id, &v, &mut schemas));
             }
 
             let commands = v
@@ -524,6 +909,10 @@ impl ConsoleRuntime {
             *g = methods;
         }
 
+        if let Ok(mut g) = self.schemas.lock() {
+            *g = schemas;
+        }
+
         self.cached_services_gen
             .store(host_context::services_generation(), Ordering::Release);
     }
@@ -570,7 +959,59 @@ impl ConsoleRuntime {
             return Err("usage: call <service_id> <method> [payload]".into());
         }
 
-        self.call_service_raw(sid, method, payload.as_bytes())
+        let payload = self.prepare_payload(sid, method, &payload)?;
+        self.call_service_raw(sid, method, &payload)
+    }
+
+    fn method_schema(&self, service_id: &str, method: &str) -> Option<serde_json::Value> {
+        self.ensure_method_cache(service_id);
+        let g = self.schemas.lock().ok()?;
+        g.get(&format!("{service_id}::{method}")).cloned()
+    }
+
+    /// Checks console text against the method's payload schema, if it has one, and encodes
+    /// `key=value` arguments as the JSON object the method expects.
+    fn prepare_payload(
+        &self,
+        service_id: &str,
+        method: &str,
+        text: &str,
+    ) -> Result<Vec<u8>, String> {
+        match self.method_schema(service_id, method) {
+            Some(s) => schema::payload_from_text(&s, text),
+            None => Ok(text.as_bytes().to_vec()),
+        }
+    }
+
+    /// Argument suggestions from a payload schema; `args` are the tokens after the method.
+    fn suggest_payload(
+        &self,
+        service_id: &str,
+        method: &str,
+        raw: &str,
+        args: &[&str],
+        ends_with_space: bool,
+        items: &mut Vec<SuggestItem>,
+    ) -> Option<String> {
+        let s = self.method_schema(service_id, method)?;
+        let (prev, partial) = match args.split_last() {
+            Some((last, prev)) if !ends_with_space => (prev, *last),
+            _ => (args, ""),
+        };
+        let base = &raw[..raw.len() - partial.len()];
+        let usage = schema::usage(&s);
+
+        for (token, help) in schema::suggest_args(&s, prev, partial) {
+            let sep = if token.ends_with('=') { "" } else { " " };
+            items.push(SuggestItem {
+                kind: "arg".into(),
+                insert: format!("{base}{token}{sep}"),
+                display: token,
+                help,
+                usage: usage.clone(),
+            });
+        }
+        Some(usage)
     }
 
     fn call_service_raw(
@@ -579,28 +1020,25 @@ impl ConsoleRuntime {
         method: &str,
         payload: &[u8],
     ) -> Result<String, String> {
-        let c = host_context::ctx();
-        let g = c
-            .services
-            .lock()
-            .map_err(|_| "services mutex poisoned".to_string())?;
-
-        let entry = g
-            .get(service_id)
-            .ok_or_else(|| format!("unknown service: {service_id}"))?;
-
-        let res = entry.service.call(
-            abi_stable::std_types::RString::from(method),
-            newengine_plugin_api::Blob::from(payload.to_vec()),
-        );
+        // Through regular dispatch, so console input is subject to the service limits.
+        let res = limits::with_console_caller(|| {
+            host_api::call_service_v1(
+                abi_stable::std_types::RString::from(service_id),
+                abi_stable::std_types::RString::from(method),
+                newengine_plugin_api::Blob::from(payload.to_vec()),
+            )
+        });
 
         match res.into_result() {
             Ok(b) => {
                 let bytes = b.into_vec();
                 if let Ok(v) = serde_json::from_slice::<serde_json::Value>(&bytes) {
-                    return Ok(serde_json::to_string_pretty(&v).unwrap_or_else(|_| {
-                        String::from_utf8_lossy(&bytes).to_string()
-                    }));
+                    let mut out = serde_json::to_string_pretty(&v)
+                        .unwrap_or_else(|_| String::from_utf8_lossy(&bytes).to_string());
+                    if self.track_page(service_id, method, payload, &v) {
+                        out.push_str("\n[more results: 'more' fetches the next page]");
+                    }
+                    return Ok(out);
                 }
                 Ok(String::from_utf8_lossy(&bytes).to_string())
             }
@@ -608,6 +1046,138 @@ impl ConsoleRuntime {
         }
     }
 
+    /// Remembers where a paged response left off. Returns whether there is a next page.
+    fn track_page(
+        &self,
+        service_id: &str,
+        method: &str,
+        payload: &[u8],
+        resp: &serde_json::Value,
+    ) -> bool {
+        let Some(next) = resp.get(PAGE_NEXT_FIELD) else {
+            return false;
+        };
+        let Ok(mut g) = self.page.lock() else {
+            return false;
+        };
+        // A paged response without a cursor was the last page.
+        let Some(cursor) = next.as_str() else {
+            *g = None;
+            return false;
+        };
+
+        let text = String::from_utf8_lossy(payload);
+        let (_, limit, rest) = split_page_args(&text);
+        let mut args = rest.join(" ");
+        if let Some(n) = limit {
+            args = format!("{args} {PAGE_LIMIT_ARG}={n}");
+        }
+        *g = Some(PendingPage {
+            service_id: service_id.to_string(),
+            method: method.to_string(),
+            args,
+            cursor: cursor.to_string(),
+        });
+        true
+    }
+
+    /// Whether `more` has a page to fetch.
+    pub fn has_more(&self) -> bool {
+        self.page.lock().is_ok_and(|g| g.is_some())
+    }
+
+    fn more_cmd(&self) -> Result<String, String> {
+        let page = self
+            .page
+            .lock()
+            .map_err(|_| "page mutex poisoned".to_string())?
+            .take()
+            .ok_or_else(|| "no more results".to_string())?;
+
+        let payload = format!("{} {PAGE_CURSOR_ARG}={}", page.args, page.cursor);
+        self.call_service_raw(&page.service_id, &page.method, payload.trim().as_bytes())
+    }
+
+    /// Loads hotkeys from the user config; later `bind`/`unbind` calls are written back to it.
+    pub fn load_key_bindings(&self, path: PathBuf) -> Result<usize, String> {
+        let display = path.display().to_string();
+        let n = self
+            .bindings
+            .lock()
+            .map_err(|_| "bindings mutex poisoned".to_string())?
+            .load(path)?;
+        log::info!("console.bind loaded count={n} path='{display}'");
+        Ok(n)
+    }
+
+    /// Executes the line bound to `key`, if any. `None` means the key is unbound.
+    pub fn run_key_binding(&self, key: &str) -> Option<Result<String, String>> {
+        // Lock is released before exec: a bound line may itself be `bind`/`unbind`.
+        let line = self.bindings.lock().ok()?.get(key)?.to_string();
+        Some(self.exec(&line))
+    }
+
+    fn bind_cmd(&self, line: &str) -> Result<String, String> {
+        let rest = line.trim_start().strip_prefix("bind").unwrap_or("").trim();
+        let mut g = self
+            .bindings
+            .lock()
+            .map_err(|_| "bindings mutex poisoned".to_string())?;
+
+        if rest.is_empty() {
+            let out = g
+                .iter()
+                .map(|(k, v)| format!("{k} -> {v}"))
+                .collect::<Vec<_>>()
+                .join("\n");
+            if out.is_empty() {
+                return Ok("no bindings".into());
+            }
+            return Ok(out);
+        }
+
+        let (key, cmd) = match rest.split_once(char::is_whitespace) {
+            Some((k, c)) => (k, c.trim()),
+            None => (rest, ""),
+        };
+
+        if cmd.is_empty() {
+            return Ok(match g.get(key) {
+                Some(v) => format!("{key} -> {v}"),
+                None => format!("{key} is not bound"),
+            });
+        }
+
+        let key = g.bind(key, cmd)?;
+        log::info!("console.bind key='{key}' line='{cmd}'");
+        Ok(format!("{key} -> {cmd}"))
+    }
+
+    fn unbind_cmd(&self, line: &str) -> Result<String, String> {
+        let key = line
+            .trim_start()
+            .strip_prefix("unbind")
+            .unwrap_or("")
+            .trim();
+        if key.is_empty() {
+            return Err("usage: unbind <key>".into());
+        }
+
+        let old = self
+            .bindings
+            .lock()
+            .map_err(|_| "bindings mutex poisoned".to_string())?
+            .unbind(key)?;
+
+        match old {
+            Some(_) => {
+                log::info!("console.unbind key='{key}'");
+                Ok(format!("unbound {key}"))
+            }
+            None => Err(format!("{key} is not bound")),
+        }
+    }
+
     pub fn help_text(&self) -> Result<String, String> {
         self.refresh_if_services_changed();
 
@@ -635,12 +1205,537 @@ impl ConsoleRuntime {
             }
         }
 
+        if let Ok(aliases) = self.aliases.lock() {
+            if !aliases.is_empty() {
+                out.push('\n');
+                out.push_str("Aliases:\n");
+                for (name, body) in aliases.iter() {
+                    out.push_str("  ");
+                    out.push_str(name);
+                    out.push_str("  = ");
+                    out.push_str(body);
+                    out.push('\n');
+                }
+            }
+        }
+
         Ok(out.trim_end().to_string())
     }
+
+    fn is_command(&self, name: &str) -> bool {
+        self.cmds.contains_key(name)
+            || self
+                .dyn_cmds
+                .lock()
+                .map(|g| g.contains_key(name))
+                .unwrap_or(false)
+    }
+
+    fn alias_cmd(&self, line: &str) -> Result<String, String> {
+        let rest = args_of(line, "alias");
+        let mut g = self
+            .aliases
+            .lock()
+            .map_err(|_| "aliases mutex poisoned".to_string())?;
+
+        if rest.is_empty() {
+            let out = g
+                .iter()
+                .map(|(k, v)| format!("{k} = {v}"))
+                .collect::<Vec<_>>()
+                .join("\n");
+            if out.is_empty() {
+                return Ok("no aliases".into());
+            }
+            return Ok(out);
+        }
+
+        let (name, body) = match rest.split_once(char::is_whitespace) {
+            Some((n, b)) => (n, unquote(b.trim())),
+            None => (rest, ""),
+        };
+
+        if body.is_empty() {
+            return match g.get(name) {
+                Some(v) => Ok(format!("{name} = {v}")),
+                None => Err(format!("{name} is not an alias")),
+            };
+        }
+
+        if !name.chars().all(is_name_char) {
+            return Err(format!("invalid alias name: '{name}'"));
+        }
+        if self.is_command(name) {
+            return Err(format!("'{name}' is a command"));
+        }
+
+        g.insert(name.to_string(), body.to_string());
+        Ok(format!("{name} = {body}"))
+    }
+
+    fn unalias_cmd(&self, line: &str) -> Result<String, String> {
+        let name = args_of(line, "unalias");
+        if name.is_empty() {
+            return Err("usage: unalias <name>".into());
+        }
+
+        let old = self
+            .aliases
+            .lock()
+            .map_err(|_| "aliases mutex poisoned".to_string())?
+            .remove(name);
+        match old {
+            Some(_) => Ok(format!("removed alias {name}")),
+            None => Err(format!("{name} is not an alias")),
+        }
+    }
+
+    fn set_cmd(&self, line: &str) -> Result<String, String> {
+        let rest = args_of(line, "set");
+
+        if rest.is_empty() {
+            let out = self
+                .var_names("")
+                .into_iter()
+                .map(|(k, _)| format!("{k} = {}", self.var_value(&k).unwrap_or_default()))
+                .collect::<Vec<_>>()
+                .join("\n");
+            if out.is_empty() {
+                return Ok("no variables".into());
+            }
+            return Ok(out);
+        }
+
+        let (name, value) = match rest.split_once(char::is_whitespace) {
+            Some((n, v)) => (n, unquote(v.trim())),
+            None => (rest, ""),
+        };
+
+        if !name.chars().all(is_name_char) {
+            return Err(format!("invalid variable name: '{name}'"));
+        }
+        if cvar::cvar_exists(name) {
+            if value.is_empty() {
+                return Ok(format!("{name} = {}", self.var_value(name).unwrap_or_default()));
+            }
+            let before = cvar::cvar_get(name);
+            let v = cvar::cvar_set(name, value)?;
+            // Cvar sets are edits `edit.undo` reverts; console variables are not.
+            if let Some(before) = before.filter(|b| *b != v) {
+                UndoStack::global().record(CvarSet::applied(name, before, v.clone()));
+            }
+            return Ok(format!("{name} = {v}"));
+        }
+
+        let mut g = self
+            .vars
+            .lock()
+            .map_err(|_| "vars mutex poisoned".to_string())?;
+        if value.is_empty() {
+            return match g.get(name) {
+                Some(v) => Ok(format!("{name} = {v}")),
+                None => Err(format!("{name} is not set")),
+            };
+        }
+
+        g.insert(name.to_string(), value.to_string());
+        Ok(format!("{name} = {value}"))
+    }
+
+    fn get_cmd(&self, line: &str) -> Result<String, String> {
+        let name = args_of(line, "get");
+        if name.is_empty() {
+            return Err("usage: get <name>".into());
+        }
+        self.var_value(name)
+            .ok_or_else(|| format!("{name} is not set"))
+    }
+
+    /// A registered cvar wins over a console variable of the same name.
+    fn var_value(&self, name: &str) -> Option<String> {
+        if let Some(v) = cvar::cvar_get(name) {
+            return Some(v.to_string());
+        }
+        self.vars.lock().ok()?.get(name).cloned()
+    }
+
+    fn exec_cmd(&self, line: &str) -> Result<String, String> {
+        let path = unquote(args_of(line, "exec"));
+        if path.is_empty() {
+            return Err("usage: exec <file>".into());
+        }
+        let text = read_cfg(path)?.ok_or_else(|| format!("{path}: not found"))?;
+        self.exec_script(path, &text)
+    }
+
+    fn watch_cmd(&self, line: &str) -> Result<String, String> {
+        let rest = args_of(line, "watch");
+        let mut g = self
+            .watches
+            .lock()
+            .map_err(|_| "watches mutex poisoned".to_string())?;
+
+        if rest.is_empty() {
+            if g.is_empty() {
+                return Ok("no watches".into());
+            }
+            let out = g
+                .iter()
+                .map(|w| format!("#{} every {}s: {}", w.id, w.interval, w.line))
+                .collect::<Vec<_>>()
+                .join("\n");
+            return Ok(out);
+        }
+
+        let (interval, cmd) = rest
+            .split_once(char::is_whitespace)
+            .map(|(i, c)| (i, c.trim()))
+            .ok_or_else(|| "usage: watch <interval> <command line>".to_string())?;
+        let interval = parse_interval(interval)?;
+        if g.len() >= MAX_WATCHES {
+            return Err(format!("at most {MAX_WATCHES} watches"));
+        }
+
+        let id = self.next_watch_id.fetch_add(1, Ordering::Relaxed);
+        g.push(Watch {
+            id,
+            interval,
+            remaining: 0.0,
+            line: unquote(cmd).to_string(),
+        });
+        Ok(format!("watch #{id} every {interval}s: {cmd}"))
+    }
+
+    fn unwatch_cmd(&self, line: &str) -> Result<String, String> {
+        let arg = args_of(line, "unwatch").trim_start_matches('#');
+        let mut g = self
+            .watches
+            .lock()
+            .map_err(|_| "watches mutex poisoned".to_string())?;
+
+        if arg == "all" {
+            let n = g.len();
+            g.clear();
+            return Ok(format!("removed {n} watches"));
+        }
+        let id = arg
+            .parse::<u32>()
+            .map_err(|_| "usage: unwatch <id|all>".to_string())?;
+        let before = g.len();
+        g.retain(|w| w.id != id);
+        if g.len() == before {
+            return Err(format!("no watch #{id}"));
+        }
+        Ok(format!("removed watch #{id}"))
+    }
+
+    /// Advances watch timers by `dt` seconds and runs the due commands. Results go to the
+    /// log and to [`WATCH_TOPIC`].
+    pub fn tick_watches(&self, dt: f32) {
+        let due: Vec<(u32, String)> = {
+            let Ok(mut g) = self.watches.lock() else {
+                return;
+            };
+            g.iter_mut()
+                .filter_map(|w| {
+                    w.remaining -= dt;
+                    if w.remaining > 0.0 {
+                        return None;
+                    }
+                    // No catch-up after a long frame: at most one run per tick.
+                    w.remaining = w.interval;
+                    Some((w.id, w.line.clone()))
+                })
+                .collect()
+        };
+
+        if due.is_empty() {
+            return;
+        }
+        // Watches run in the background: keep what a later `grep` filters.
+        let last = self.last_output.lock().map(|g| g.clone()).unwrap_or_default();
+
+        // Lock released: a watched line may itself be `watch`/`unwatch`.
+        for (id, line) in due {
+            let res = self.exec(&line);
+            match &res {
+                Ok(out) => log::info!(target: "console.watch", "#{id} {line}: {out}"),
+                Err(e) => log::warn!(target: "console.watch", "#{id} {line}: {e}"),
+            }
+
+            let mut payload = serde_json::json!({ "id": id, "command": line, "ok": res.is_ok() });
+            match res {
+                Ok(out) => payload["output"] = out.into(),
+                Err(e) => payload["error"] = e.into(),
+            }
+            let bytes = serde_json::to_vec(&payload).unwrap_or_default();
+            if let Err(e) = crate::plugins::event_router::enqueue(WATCH_TOPIC, &bytes, true) {
+                log::warn!("console.watch: event dropped: {e}");
+            }
+        }
+
+        if let Ok(mut g) = self.last_output.lock() {
+            *g = last;
+        }
+    }
+
+    /// Runs `autoexec.cfg` if the working directory or the assets have one.
+    pub fn run_autoexec(&self) {
+        match read_cfg(AUTOEXEC_FILE) {
+            Ok(Some(text)) => match self.exec_script(AUTOEXEC_FILE, &text) {
+                Ok(out) => log::info!("console.exec {out}"),
+                Err(e) => log::warn!("console.exec {e}"),
+            },
+            Ok(None) => {}
+            Err(e) => log::warn!("console.exec file='{AUTOEXEC_FILE}' err='{e}'"),
+        }
+    }
+
+    /// Runs each line of a `.cfg` script; `//` and `#` start comment lines. A failing line is
+    /// logged and the rest still runs.
+    fn exec_script(&self, name: &str, text: &str) -> Result<String, String> {
+        let (mut ran, mut failed) = (0usize, 0usize);
+        for (i, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with("//") || line.starts_with('#') {
+                continue;
+            }
+            ran += 1;
+            if let Err(e) = self.exec(line) {
+                failed += 1;
+                log::warn!("console.exec file='{name}' line={} err='{e}'", i + 1);
+            }
+        }
+
+        if failed > 0 {
+            return Err(format!("{name}: {failed} of {ran} commands failed"));
+        }
+        Ok(format!("{name}: ran {ran} commands"))
+    }
+}
+
+/// Everything after the command name `head`, trimmed.
+#[inline]
+/// Method names of a describe document; `"schema"` entries are collected into `schemas`.
+fn read_methods(
+    service_id: &str,
+    describe: &serde_json::Value,
+    schemas: &mut BTreeMap<String, serde_json::Value>,
+) -> Vec<String> {
+    let mut methods = Vec::new();
+    if let Some(arr) = describe.get("methods").and_then(|v| v.as_array()) {
+        for m in arr {
+            let Some(name) = m.get("name").and_then(|v| v.as_str()) else {
+                continue;
+            };
+            if let Some(s) = m.get("schema").filter(|s| s.is_object()) {
+                schemas.insert(format!("{service_id}::{name}"), s.clone());
+            }
+            methods.push(name.to_string());
+        }
+    }
+    methods.sort();
+    methods.dedup();
+    methods
+}
+
+fn args_of<'a>(line: &'a str, head: &str) -> &'a str {
+    line.trim_start().strip_prefix(head).unwrap_or("").trim()
+}
+
+#[inline]
+fn is_name_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-')
+}
+
+/// Strips one pair of surrounding double quotes.
+#[inline]
+fn unquote(s: &str) -> &str {
+    s.strip_prefix('"')
+        .and_then(|s| s.strip_suffix('"'))
+        .unwrap_or(s)
+}
+
+/// Splits at `;` outside double-quoted strings (where `\` escapes the next character).
+#[inline]
+fn split_commands(line: &str) -> Vec<&str> {
+    let mut out = split_unquoted(line, ';');
+    out.retain(|c| !c.is_empty());
+    out
+}
+
+/// Trimmed pieces of `line` between `sep`s outside double-quoted strings.
+fn split_unquoted(line: &str, sep: char) -> Vec<&str> {
+    let mut out = Vec::new();
+    let mut rest = line;
+    while let Some(i) = find_unquoted(rest, sep) {
+        out.push(rest[..i].trim());
+        rest = &rest[i + sep.len_utf8()..];
+    }
+    out.push(rest.trim());
+    out
+}
+
+/// `alias <name> <body>` and `watch <interval> <body>`: the body runs later, as written.
+fn defines_body(cmd: &str) -> bool {
+    let mut it = cmd.split_whitespace();
+    match it.next() {
+        Some("alias") => true,
+        Some("watch") => it.next().is_some(),
+        _ => false,
+    }
+}
+
+/// Splits a trailing `> file` or `>> file` off the last stage of a pipeline. Only a `>`
+/// with whitespace on both sides counts, so `set x a>b` keeps its argument.
+fn split_redirect(stage: &str) -> (&str, Option<&str>) {
+    let mut from = 0;
+    while let Some(i) = find_unquoted(&stage[from..], '>').map(|i| i + from) {
+        let op = if stage[i..].starts_with(">>") { 2 } else { 1 };
+        let before = stage[..i].ends_with(char::is_whitespace);
+        let after = stage[i + op..].starts_with(char::is_whitespace);
+        if before && after {
+            return (stage[..i].trim_end(), Some(&stage[i + 1..]));
+        }
+        from = i + op;
+    }
+    (stage, None)
+}
+
+/// Byte index of the first `c` outside double-quoted strings.
+fn find_unquoted(line: &str, c: char) -> Option<usize> {
+    let (mut quoted, mut escaped) = (false, false);
+    for (i, ch) in line.char_indices() {
+        match ch {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            _ if ch == c && !quoted => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Seconds from `2`, `0.5s` or `250ms`.
+fn parse_interval(s: &str) -> Result<f32, String> {
+    let secs = match s.strip_suffix("ms") {
+        Some(ms) => ms.parse::<f32>().map(|v| v / 1000.0),
+        None => s.strip_suffix('s').unwrap_or(s).parse::<f32>(),
+    }
+    .map_err(|_| format!("bad interval: '{s}'"))?;
+
+    if !secs.is_finite() || secs < MIN_WATCH_INTERVAL {
+        return Err(format!("interval must be at least {MIN_WATCH_INTERVAL}s"));
+    }
+    Ok(secs)
+}
+
+/// Lines of `text` containing the pattern; args are `[-i] [-v] <text>`.
+fn grep_lines(text: &str, args: &str) -> Result<String, String> {
+    let (mut ignore_case, mut invert) = (false, false);
+    let mut rest = args.trim();
+    loop {
+        if let Some(r) = rest.strip_prefix("-i ") {
+            ignore_case = true;
+            rest = r.trim_start();
+        } else if let Some(r) = rest.strip_prefix("-v ") {
+            invert = true;
+            rest = r.trim_start();
+        } else {
+            break;
+        }
+    }
+
+    let pattern = unquote(rest);
+    if pattern.is_empty() {
+        return Err("usage: grep [-i] [-v] <text>".into());
+    }
+    let needle = if ignore_case {
+        pattern.to_lowercase()
+    } else {
+        pattern.to_string()
+    };
+
+    let out = text
+        .lines()
+        .filter(|l| {
+            let hit = if ignore_case {
+                l.to_lowercase().contains(&needle)
+            } else {
+                l.contains(&needle)
+            };
+            hit != invert
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    Ok(out)
+}
+
+/// Writes command output for `> file` (`>> file` appends) under [`OUTPUT_DIR`].
+fn write_output(target: &str, text: &str) -> Result<String, String> {
+    use std::io::Write;
+
+    let (append, name) = match target.strip_prefix('>') {
+        Some(rest) => (true, unquote(rest.trim())),
+        None => (false, unquote(target.trim())),
+    };
+    if name.is_empty() {
+        return Err("redirect needs a file name".into());
+    }
+    let rel = Path::new(name);
+    let inside = rel
+        .components()
+        .all(|c| matches!(c, std::path::Component::Normal(_)));
+    if !inside {
+        return Err(format!("'{name}': must be a relative path inside {OUTPUT_DIR}/"));
+    }
+
+    let path = Path::new(OUTPUT_DIR).join(rel);
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("{}: {e}", dir.display()))?;
+    }
+    let mut f = std::fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(append)
+        .truncate(!append)
+        .open(&path)
+        .map_err(|e| format!("{}: {e}", path.display()))?;
+    writeln!(f, "{text}").map_err(|e| format!("{}: {e}", path.display()))?;
+
+    Ok(format!(
+        "{} {} lines to {}",
+        if append { "appended" } else { "wrote" },
+        text.lines().count(),
+        path.display()
+    ))
+}
+
+/// A `.cfg` file from the file system, else from the assets. `None` when neither has it.
+fn read_cfg(path: &str) -> Result<Option<String>, String> {
+    let p = Path::new(path);
+    if p.is_file() {
+        return std::fs::read_to_string(p)
+            .map(Some)
+            .map_err(|e| format!("{path}: {e}"));
+    }
+
+    #[cfg(feature = "runtime")]
+    {
+        let store = host_context::ctx().asset_store.clone();
+        if let Ok(bytes) = store.read_source(path) {
+            return String::from_utf8(bytes)
+                .map(Some)
+                .map_err(|_| format!("{path}: not utf8"));
+        }
+    }
+
+    Ok(None)
 }
 
 impl ConsoleRuntime {
     pub fn shared() -> Arc<Self> {
         Arc::new(Self::new())
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,265 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use serde_json::{Map, Value};
+
+/// Payload schemas are the subset of JSON Schema a method may publish as `"schema"` in its
+/// describe entry: `type`, `enum`, `minimum`, `maximum`, `properties`, `required`, `items`
+/// and `description`.
+///
+/// Object payloads can be typed in the console as `key=value` pairs (arrays as `a,b,c`);
+/// scalar payloads stay utf8 text and are only checked. An empty payload is passed through
+/// so services keep their defaults.
+pub(crate) fn payload_from_text(schema: &Value, text: &str) -> Result<Vec<u8>, String> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    match type_of(schema) {
+        Some("object") | Some("array") => {
+            let value = if text.starts_with('{') || text.starts_with('[') {
+                serde_json::from_str(text).map_err(|e| format!("payload: bad json: {e}"))?
+            } else if type_of(schema) == Some("object") {
+                object_from_pairs(schema, text)?
+            } else {
+                coerce(schema, text)
+            };
+            validate(schema, &value, "payload")?;
+            serde_json::to_vec(&value).map_err(|e| e.to_string())
+        }
+        Some(_) => {
+            validate(schema, &coerce(schema, text), "payload")?;
+            Ok(text.as_bytes().to_vec())
+        }
+        None => Ok(text.as_bytes().to_vec()),
+    }
+}
+
+fn object_from_pairs(schema: &Value, text: &str) -> Result<Value, String> {
+    let mut out = Map::new();
+    for pair in text.split_whitespace() {
+        let (key, raw) = pair
+            .split_once('=')
+            .ok_or_else(|| format!("payload: expected key=value, got '{pair}'"))?;
+        let value = match property(schema, key) {
+            Some(p) => coerce(p, raw),
+            None => coerce(&Value::Null, raw),
+        };
+        out.insert(key.to_string(), value);
+    }
+    Ok(Value::Object(out))
+}
+
+/// Console text to the JSON value `schema` expects; text that does not parse stays a string
+/// and is reported by [`validate`].
+fn coerce(schema: &Value, raw: &str) -> Value {
+    let raw = raw.trim_matches('"');
+    match type_of(schema) {
+        Some("string") => Value::String(raw.to_string()),
+        Some("array") => {
+            let items = schema.get("items").unwrap_or(&Value::Null);
+            let v = raw
+                .split(',')
+                .filter(|s| !s.is_empty())
+                .map(|s| coerce(items, s))
+                .collect();
+            Value::Array(v)
+        }
+        Some("boolean") => match raw.to_ascii_lowercase().as_str() {
+            "1" | "on" | "true" | "yes" => Value::Bool(true),
+            "0" | "off" | "false" | "no" => Value::Bool(false),
+            _ => Value::String(raw.to_string()),
+        },
+        _ => serde_json::from_str(raw).unwrap_or_else(|_| Value::String(raw.to_string())),
+    }
+}
+
+pub(crate) fn validate(schema: &Value, v: &Value, path: &str) -> Result<(), String> {
+    if let Some(allowed) = schema.get("enum").and_then(Value::as_array) {
+        if !allowed.contains(v) {
+            let list = allowed
+                .iter()
+                .map(|a| a.to_string())
+                .collect::<Vec<_>>()
+                .join(", ");
+            return Err(format!("{path}: expected one of [{list}], got {v}"));
+        }
+    }
+
+    let Some(ty) = type_of(schema) else {
+        return Ok(());
+    };
+    let ok = match ty {
+        "object" => v.is_object(),
+        "array" => v.is_array(),
+        "string" => v.is_string(),
+        "number" => v.is_number(),
+        "integer" => v.is_i64() || v.is_u64(),
+        "boolean" => v.is_boolean(),
+        _ => true,
+    };
+    if !ok {
+        return Err(format!("{path}: expected {ty}, got {v}"));
+    }
+
+    if let Some(n) = v.as_f64() {
+        if let Some(min) = schema.get("minimum").and_then(Value::as_f64) {
+            if n < min {
+                return Err(format!("{path}: {n} is below the minimum {min}"));
+            }
+        }
+        if let Some(max) = schema.get("maximum").and_then(Value::as_f64) {
+            if n > max {
+                return Err(format!("{path}: {n} is above the maximum {max}"));
+            }
+        }
+    }
+
+    if let (Some(items), Some(arr)) = (schema.get("items"), v.as_array()) {
+        for (i, item) in arr.iter().enumerate() {
+            validate(items, item, &format!("{path}[{i}]"))?;
+        }
+    }
+
+    if let Some(obj) = v.as_object() {
+        for key in required(schema) {
+            if !obj.contains_key(key) {
+                return Err(format!("{path}: missing '{key}'"));
+            }
+        }
+        let props = schema.get("properties").and_then(Value::as_object);
+        for (key, value) in obj {
+            match props.and_then(|p| p.get(key)) {
+                Some(p) => validate(p, value, &format!("{path}.{key}"))?,
+                None if props.is_some() => return Err(format!("{path}: unknown key '{key}'")),
+                None => {}
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Argument hints for the console: `(token, help)` pairs completing `partial`, given the
+/// arguments already typed in `prev`.
+pub(crate) fn suggest_args(schema: &Value, prev: &[&str], partial: &str) -> Vec<(String, String)> {
+    if type_of(schema) != Some("object") {
+        if !prev.is_empty() {
+            return Vec::new();
+        }
+        return values_of(schema)
+            .into_iter()
+            .filter(|v| v.starts_with(partial))
+            .map(|v| (v, describe_type(schema)))
+            .collect();
+    }
+
+    if let Some((key, value_prefix)) = partial.split_once('=') {
+        let Some(p) = property(schema, key) else {
+            return Vec::new();
+        };
+        // Array values complete the item after the last comma.
+        let (done, last) = match value_prefix.rsplit_once(',') {
+            Some((done, last)) if type_of(p) == Some("array") => (format!("{done},"), last),
+            _ => (String::new(), value_prefix),
+        };
+        return values_of(p)
+            .into_iter()
+            .filter(|v| v.starts_with(last))
+            .map(|v| (format!("{key}={done}{v}"), describe_type(p)))
+            .collect();
+    }
+
+    let used: Vec<&str> = prev
+        .iter()
+        .filter_map(|a| a.split_once('=').map(|(k, _)| k))
+        .collect();
+    let Some(props) = schema.get("properties").and_then(Value::as_object) else {
+        return Vec::new();
+    };
+    let req = required(schema);
+    props
+        .iter()
+        .filter(|(k, _)| k.starts_with(partial) && !used.contains(&k.as_str()))
+        .map(|(k, p)| {
+            let mut help = describe_type(p);
+            if req.contains(&k.as_str()) {
+                help.push_str(" (required)");
+            }
+            (format!("{k}="), help)
+        })
+        .collect()
+}
+
+/// One-line usage built from the schema, e.g. `name=<string> [help=<string>]`.
+pub(crate) fn usage(schema: &Value) -> String {
+    let Some(props) = schema.get("properties").and_then(Value::as_object) else {
+        return match type_of(schema) {
+            Some(ty) => format!("[<{ty}>]"),
+            None => String::new(),
+        };
+    };
+    let req = required(schema);
+    props
+        .iter()
+        .map(|(k, p)| {
+            let ty = p
+                .get("enum")
+                .map(|_| values_of(p).join("|"))
+                .unwrap_or_else(|| type_of(p).unwrap_or("value").to_string());
+            if req.contains(&k.as_str()) {
+                format!("{k}=<{ty}>")
+            } else {
+                format!("[{k}=<{ty}>]")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[inline]
+fn type_of(schema: &Value) -> Option<&str> {
+    schema.get("type").and_then(Value::as_str)
+}
+
+#[inline]
+fn property<'a>(schema: &'a Value, key: &str) -> Option<&'a Value> {
+    schema.get("properties")?.get(key)
+}
+
+fn required(schema: &Value) -> Vec<&str> {
+    schema
+        .get("required")
+        .and_then(Value::as_array)
+        .map(|r| r.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default()
+}
+
+/// Completable values: the enum (array items' enum for arrays) or `true`/`false`.
+fn values_of(schema: &Value) -> Vec<String> {
+    let source = match type_of(schema) {
+        Some("array") => schema.get("items").unwrap_or(&Value::Null),
+        _ => schema,
+    };
+    if let Some(e) = source.get("enum").and_then(Value::as_array) {
+        return e
+            .iter()
+            .map(|v| match v {
+                Value::String(s) => s.clone(),
+                other => other.to_string(),
+            })
+            .collect();
+    }
+    if type_of(source) == Some("boolean") {
+        return vec!["true".to_string(), "false".to_string()];
+    }
+    Vec::new()
+}
+
+fn describe_type(schema: &Value) -> String {
+    let ty = type_of(schema).unwrap_or("value");
+    match schema.get("description").and_then(Value::as_str) {
+        Some(d) => format!("{d} ({ty})"),
+        None => ty.to_string(),
+    }
+}
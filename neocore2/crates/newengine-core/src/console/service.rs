@@ -7,8 +7,9 @@ use super::types::SuggestResponse;
 use crate::plugins::host_api;
 
 use abi_stable::std_types::{RResult, RString};
-use newengine_plugin_api::{Blob, CapabilityId, MethodName, ServiceV1, ServiceV1Dyn};
+use newengine_plugin_api::{guarded_service, Blob, CapabilityId, MethodName, ServiceV1};
 use serde_json::json;
+use std::path::PathBuf;
 use std::sync::{Arc, OnceLock};
 
 struct CommandService {
@@ -26,7 +27,7 @@ impl ServiceV1 for CommandService {
                 "id": COMMAND_SERVICE_ID,
                 "version": 2,
                 "methods": [
-                    { "name": method::EXEC, "payload": "utf8 line", "returns": "json {ok, output?, error?}" },
+                    { "name": method::EXEC, "payload": "utf8 line", "returns": "json {ok, output?, error?, more}" },
                     { "name": method::COMPLETE, "payload": "utf8 prefix", "returns": "json {items:[string]}" },
                     { "name": method::SUGGEST, "payload": "utf8 input", "returns": "json SuggestResponse" },
                     { "name": method::REFRESH, "payload": "empty", "returns": "json {ok:true}" }
@@ -38,6 +39,18 @@ impl ServiceV1 for CommandService {
                         { "name": "refresh", "help": "Refresh console commands", "usage": "refresh" },
                         { "name": "describe", "help": "Describe a service", "usage": "describe <service_id>" },
                         { "name": "call", "help": "Call a service method", "usage": "call <service_id> <method> [payload]" },
+                        { "name": "bind", "help": "Bind a key to a console line", "usage": "bind [<key> [<command line>]]" },
+                        { "name": "unbind", "help": "Remove a key binding", "usage": "unbind <key>" },
+                        { "name": "alias", "help": "Define a command alias", "usage": "alias [<name> [<command line>]]" },
+                        { "name": "unalias", "help": "Remove a command alias", "usage": "unalias <name>" },
+                        { "name": "set", "help": "Set a console variable", "usage": "set [<name> [<value>]]" },
+                        { "name": "get", "help": "Print a console variable", "usage": "get <name>" },
+                        { "name": "echo", "help": "Print text", "usage": "echo <text>" },
+                        { "name": "exec", "help": "Run a .cfg file", "usage": "exec <file>" },
+                        { "name": "grep", "help": "Filter the previous output by text", "usage": "grep [-i] [-v] <text>" },
+                        { "name": "watch", "help": "Re-run a command every interval", "usage": "watch [<interval> <command line>]" },
+                        { "name": "unwatch", "help": "Stop a watch", "usage": "unwatch <id|all>" },
+                        { "name": "more", "help": "Fetch the next page of the last paged result", "usage": "more" },
                         { "name": "quit", "help": "Exit engine", "usage": "quit" }
                     ]
                 }
@@ -52,9 +65,10 @@ impl ServiceV1 for CommandService {
                 let line = String::from_utf8_lossy(payload.as_slice());
                 let out = self.rt.exec(&line);
 
+                let more = self.rt.has_more();
                 let resp = match out {
-                    Ok(v) => json!({ "ok": true, "output": v }),
-                    Err(e) => json!({ "ok": false, "error": e }),
+                    Ok(v) => json!({ "ok": true, "output": v, "more": more }),
+                    Err(e) => json!({ "ok": false, "error": e, "more": more }),
                 };
 
                 RResult::ROk(Blob::from(resp.to_string().into_bytes()))
@@ -92,11 +106,45 @@ pub fn init_console_service() {
     rt.refresh_dyn_commands();
 
     let svc = CommandService { rt };
-    let dyn_svc = ServiceV1Dyn::from_value(svc, abi_stable::sabi_trait::TD_Opaque);
+    let dyn_svc = guarded_service(svc);
 
     let _ = host_api::host_register_service_impl(dyn_svc, false);
 }
 
+/// Runs `autoexec.cfg` once services are up; a missing file is not an error.
+pub fn run_autoexec() {
+    if let Some(rt) = RT.get() {
+        rt.run_autoexec();
+    }
+}
+
+/// Runs due `watch` commands; called once per frame with wall-clock `dt` seconds.
+pub fn tick_watches(dt: f32) {
+    if let Some(rt) = RT.get() {
+        rt.tick_watches(dt);
+    }
+}
+
 pub fn take_exit_requested() -> bool {
     RT.get().map(|r| r.take_exit_requested()).unwrap_or(false)
-}
\ No newline at end of file
+}
+
+/// Loads console hotkeys from the user config file (see `bind`).
+pub fn load_key_bindings(path: impl Into<PathBuf>) -> Result<usize, String> {
+    RT.get_or_init(|| Arc::new(ConsoleRuntime::new()))
+        .load_key_bindings(path.into())
+}
+
+/// Runs the console line bound to `key`. Returns `false` if the key is unbound.
+pub fn run_key_binding(key: &str) -> bool {
+    let Some(res) = RT.get().and_then(|r| r.run_key_binding(key)) else {
+        return false;
+    };
+
+    match res {
+        Ok(out) if out.is_empty() => log::info!("console.hotkey key='{key}'"),
+        Ok(out) => log::info!("console.hotkey key='{key}' output='{out}'"),
+        Err(e) => log::warn!("console.hotkey key='{key}' err='{e}'"),
+    }
+    true
+}
@@ -0,0 +1,367 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use std::backtrace::Backtrace;
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Version of `newengine-core`, recorded in every crash report.
+pub const ENGINE_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+const REPORT_PREFIX: &str = "crash-";
+const REPORT_EXT: &str = "txt";
+/// Holds the file name of the last report the user has seen (see [`mark_crash_report_seen`]).
+const SEEN_MARKER: &str = ".last_seen";
+
+#[derive(Debug, Clone)]
+pub struct CrashConfig {
+    /// Directory for `crash-<unix_ms>.txt` reports; created on first crash.
+    pub dir: PathBuf,
+    pub app_name: String,
+    /// Recent log lines kept for the report. 0 disables the tail.
+    pub log_tail: usize,
+    /// Also catch fatal signals (SIGSEGV, SIGBUS, SIGILL, SIGFPE) on unix.
+    /// Requires the `crash-signals` feature; ignored otherwise.
+    pub os_handlers: bool,
+}
+
+impl CrashConfig {
+    #[inline]
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            app_name: "newengine".to_string(),
+            log_tail: 200,
+            os_handlers: false,
+        }
+    }
+
+    #[inline]
+    pub fn with_app_name(mut self, name: impl Into<String>) -> Self {
+        self.app_name = name.into();
+        self
+    }
+
+    #[inline]
+    pub fn with_log_tail(mut self, lines: usize) -> Self {
+        self.log_tail = lines;
+        self
+    }
+
+    #[inline]
+    pub fn with_os_handlers(mut self, enabled: bool) -> Self {
+        self.os_handlers = enabled;
+        self
+    }
+}
+
+#[derive(Default)]
+struct CrashState {
+    log_tail: VecDeque<String>,
+    log_tail_cap: usize,
+    plugins: Vec<String>,
+}
+
+static CONFIG: OnceLock<CrashConfig> = OnceLock::new();
+static STATE: OnceLock<Mutex<CrashState>> = OnceLock::new();
+/// Set while a report is being written; a panic inside the hook must not recurse.
+static IN_HOOK: AtomicBool = AtomicBool::new(false);
+
+#[inline]
+fn state() -> &'static Mutex<CrashState> {
+    STATE.get_or_init(|| Mutex::new(CrashState::default()))
+}
+
+/// Installs the panic hook (once per process). The previous hook still runs afterwards,
+/// so the usual panic message is printed as well.
+pub fn install_crash_handler(config: CrashConfig) {
+    if CONFIG.set(config.clone()).is_err() {
+        log::warn!("crash: handler already installed");
+        return;
+    }
+
+    if let Ok(mut g) = state().lock() {
+        g.log_tail_cap = config.log_tail;
+    }
+
+    let prev = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        if !IN_HOOK.swap(true, Ordering::SeqCst) {
+            let thread = std::thread::current();
+            let reason = format!("thread '{}' {info}", thread.name().unwrap_or("<unnamed>"));
+            let backtrace = Backtrace::force_capture().to_string();
+
+            match write_crash_report(&reason, &backtrace) {
+                Ok(path) => eprintln!("crash: report written to {}", path.display()),
+                Err(e) => eprintln!("crash: report failed: {e}"),
+            }
+            IN_HOOK.store(false, Ordering::SeqCst);
+        }
+        prev(info);
+    }));
+
+    #[cfg(all(unix, feature = "crash-signals"))]
+    if config.os_handlers {
+        signals::install(&config);
+    }
+    #[cfg(not(all(unix, feature = "crash-signals")))]
+    if config.os_handlers {
+        log::warn!("crash: os handlers requested but not available in this build");
+    }
+
+    log::info!(
+        "crash: handler installed dir='{}' log_tail={}",
+        config.dir.display(),
+        config.log_tail
+    );
+}
+
+/// Appends a formatted log line to the tail kept for crash reports. No-op until
+/// [`install_crash_handler`] runs.
+pub fn record_log_line(line: impl Into<String>) {
+    let Ok(mut g) = state().lock() else {
+        return;
+    };
+    if g.log_tail_cap == 0 {
+        return;
+    }
+    while g.log_tail.len() >= g.log_tail_cap {
+        g.log_tail.pop_front();
+    }
+    g.log_tail.push_back(line.into());
+}
+
+/// Replaces the plugin list written into crash reports (`"<id> <version>"` entries).
+pub(crate) fn set_loaded_plugins(plugins: Vec<String>) {
+    if let Ok(mut g) = state().lock() {
+        g.plugins = plugins;
+    }
+}
+
+/// Writes a report to the configured directory and returns its path.
+pub fn write_crash_report(reason: &str, backtrace: &str) -> Result<PathBuf, String> {
+    let config = CONFIG
+        .get()
+        .ok_or_else(|| "crash handler is not installed".to_string())?;
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+
+    let mut s = String::new();
+    let _ = writeln!(s, "NewEngine crash report");
+    let _ = writeln!(s, "app: {}", config.app_name);
+    let _ = writeln!(s, "version: {ENGINE_VERSION}");
+    let _ = writeln!(s, "time_unix: {}", now.as_secs());
+    let _ = writeln!(s, "os: {} {}", std::env::consts::OS, std::env::consts::ARCH);
+    let _ = writeln!(s, "reason: {reason}");
+
+    // The panic may have happened while the state lock was held (e.g. inside `record_log_line`).
+    match state().try_lock() {
+        Ok(g) => {
+            let _ = writeln!(s, "\nplugins ({}):", g.plugins.len());
+            for p in g.plugins.iter() {
+                let _ = writeln!(s, "  {p}");
+            }
+            let _ = writeln!(s, "\nlog (last {} lines):", g.log_tail.len());
+            for line in g.log_tail.iter() {
+                let _ = writeln!(s, "  {line}");
+            }
+        }
+        Err(_) => {
+            let _ = writeln!(s, "\nplugins/log: unavailable (state locked)");
+        }
+    }
+
+    let _ = writeln!(s, "\nbacktrace:\n{backtrace}");
+
+    std::fs::create_dir_all(&config.dir)
+        .map_err(|e| format!("create dir '{}' failed: {e}", config.dir.display()))?;
+
+    let path = config
+        .dir
+        .join(format!("{REPORT_PREFIX}{}.{REPORT_EXT}", now.as_millis()));
+    std::fs::write(&path, s).map_err(|e| format!("write '{}' failed: {e}", path.display()))?;
+    Ok(path)
+}
+
+fn is_report(path: &Path) -> bool {
+    let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+        return false;
+    };
+    name.starts_with(REPORT_PREFIX) && path.extension().is_some_and(|e| e == REPORT_EXT)
+}
+
+/// Newest report in `dir`, if any.
+pub fn last_crash_report(dir: &Path) -> Option<PathBuf> {
+    std::fs::read_dir(dir)
+        .ok()?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| is_report(p))
+        .filter_map(|p| {
+            let modified = std::fs::metadata(&p).and_then(|m| m.modified()).ok()?;
+            Some((modified, p))
+        })
+        .max_by(|a, b| a.0.cmp(&b.0))
+        .map(|(_, p)| p)
+}
+
+/// Newest report in `dir` that has not been acknowledged with [`mark_crash_report_seen`].
+pub fn unseen_crash_report(dir: &Path) -> Option<PathBuf> {
+    let last = last_crash_report(dir)?;
+    let seen = std::fs::read_to_string(dir.join(SEEN_MARKER)).unwrap_or_default();
+    let name = last.file_name()?.to_str()?;
+    (seen.trim() != name).then_some(last)
+}
+
+pub fn mark_crash_report_seen(path: &Path) -> Result<(), String> {
+    let (Some(dir), Some(name)) = (path.parent(), path.file_name().and_then(|n| n.to_str())) else {
+        return Err(format!("not a report path: '{}'", path.display()));
+    };
+    std::fs::write(dir.join(SEEN_MARKER), name)
+        .map_err(|e| format!("write crash marker failed: {e}"))
+}
+
+#[cfg(all(unix, feature = "crash-signals"))]
+mod signals {
+    use super::{CrashConfig, ENGINE_VERSION, REPORT_EXT, REPORT_PREFIX};
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+    use std::sync::OnceLock;
+
+    /// SIGABRT is left alone: Rust panics that end in an abort (e.g. one unwinding into an
+    /// `extern "C"` frame) already got a report from the panic hook.
+    const SIGNALS: &[libc::c_int] = &[libc::SIGSEGV, libc::SIGBUS, libc::SIGILL, libc::SIGFPE];
+
+    static REPORT_PATH: OnceLock<CString> = OnceLock::new();
+    static HEADER: OnceLock<Vec<u8>> = OnceLock::new();
+
+    pub(super) fn install(config: &CrashConfig) {
+        // Everything the handler writes is prepared here; the handler itself only does
+        // async-signal-safe syscalls.
+        let _ = std::fs::create_dir_all(&config.dir);
+        let path = config.dir.join(format!(
+            "{REPORT_PREFIX}signal-{}.{REPORT_EXT}",
+            std::process::id()
+        ));
+        let Ok(path) = CString::new(path.as_os_str().as_bytes()) else {
+            return;
+        };
+
+        let header = format!(
+            "NewEngine crash report\napp: {}\nversion: {ENGINE_VERSION}\nos: {} {}\n",
+            config.app_name,
+            std::env::consts::OS,
+            std::env::consts::ARCH
+        );
+
+        let _ = REPORT_PATH.set(path);
+        let _ = HEADER.set(header.into_bytes());
+
+        for &sig in SIGNALS {
+            // SAFETY: `on_signal` only performs async-signal-safe calls.
+            unsafe {
+                libc::signal(
+                    sig,
+                    on_signal as extern "C" fn(libc::c_int) as libc::sighandler_t,
+                );
+            }
+        }
+    }
+
+    fn write_all(fd: libc::c_int, mut buf: &[u8]) {
+        while !buf.is_empty() {
+            // SAFETY: `buf` is a valid slice for its length.
+            let n = unsafe { libc::write(fd, buf.as_ptr().cast(), buf.len()) };
+            if n <= 0 {
+                return;
+            }
+            buf = &buf[n as usize..];
+        }
+    }
+
+    extern "C" fn on_signal(sig: libc::c_int) {
+        if let (Some(path), Some(header)) = (REPORT_PATH.get(), HEADER.get()) {
+            // SAFETY: open/write/close are async-signal-safe; `path` is NUL-terminated.
+            let fd = unsafe {
+                libc::open(
+                    path.as_ptr(),
+                    libc::O_WRONLY | libc::O_CREAT | libc::O_TRUNC,
+                    0o644 as libc::c_uint,
+                )
+            };
+            if fd >= 0 {
+                write_all(fd, header);
+                write_all(fd, b"reason: fatal signal ");
+
+                // No allocation in a signal handler: format the number by hand.
+                let mut digits = [0u8; 12];
+                let mut i = digits.len();
+                let mut v = sig.unsigned_abs();
+                loop {
+                    i -= 1;
+                    digits[i] = b'0' + (v % 10) as u8;
+                    v /= 10;
+                    if v == 0 {
+                        break;
+                    }
+                }
+                write_all(fd, &digits[i..]);
+                write_all(fd, b"\n");
+
+                // SAFETY: `fd` was returned by `open` above.
+                unsafe {
+                    libc::close(fd);
+                }
+            }
+        }
+
+        // SAFETY: restoring the default action and re-raising lets the OS terminate the
+        // process (and produce a core dump) as it would have without the handler.
+        unsafe {
+            libc::signal(sig, libc::SIG_DFL);
+            libc::raise(sig);
+        }
+    }
+}
+
+/// `log::Log` wrapper that copies every emitted record into the crash report tail.
+pub struct TailLogger<L> {
+    inner: L,
+}
+
+impl<L: log::Log> TailLogger<L> {
+    #[inline]
+    pub fn new(inner: L) -> Self {
+        Self { inner }
+    }
+}
+
+impl<L: log::Log> log::Log for TailLogger<L> {
+    #[inline]
+    fn enabled(&self, metadata: &log::Metadata<'_>) -> bool {
+        self.inner.enabled(metadata)
+    }
+
+    fn log(&self, record: &log::Record<'_>) {
+        if !self.inner.enabled(record.metadata()) {
+            return;
+        }
+        record_log_line(format!(
+            "[{} {}] {}",
+            record.level(),
+            record.target(),
+            record.args()
+        ));
+        self.inner.log(record);
+    }
+
+    #[inline]
+    fn flush(&self) {
+        self.inner.flush();
+    }
+}
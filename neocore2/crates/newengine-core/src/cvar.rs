@@ -0,0 +1,470 @@
+use crate::config::config_api;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::{Mutex, MutexGuard};
+
+/// Archived cvars are stored as keys of this config section.
+pub const CVAR_CONFIG_SECTION: &str = "cvars";
+
+/// Changes are published on `cvar.<name>` with a JSON [`CvarChange`] payload.
+pub const CVAR_TOPIC_PREFIX: &str = "cvar.";
+
+/// Built-in cvar that unlocks [`CvarFlag::Cheat`] cvars.
+pub const CHEATS_CVAR: &str = "cheats";
+
+/// Topic carrying changes of cvar `name`.
+#[inline]
+pub fn cvar_topic(name: &str) -> String {
+    format!("{CVAR_TOPIC_PREFIX}{name}")
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CvarKind {
+    Bool,
+    Int,
+    Float,
+    String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CvarFlag {
+    /// Only settable while the `cheats` cvar is on; reset to the default when it goes off.
+    Cheat,
+    /// Persisted in the `cvars` config section.
+    Archive,
+    /// Set at registration only.
+    ReadOnly,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum CvarValue {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    String(String),
+}
+
+impl CvarValue {
+    pub fn kind(&self) -> CvarKind {
+        match self {
+            CvarValue::Bool(_) => CvarKind::Bool,
+            CvarValue::Int(_) => CvarKind::Int,
+            CvarValue::Float(_) => CvarKind::Float,
+            CvarValue::String(_) => CvarKind::String,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            CvarValue::Bool(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            CvarValue::Int(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    /// Ints widen to floats.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            CvarValue::Int(v) => Some(*v as f64),
+            CvarValue::Float(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            CvarValue::String(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        serde_json::to_value(self).unwrap_or(Value::Null)
+    }
+}
+
+impl fmt::Display for CvarValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CvarValue::Bool(v) => write!(f, "{}", if *v { 1 } else { 0 }),
+            CvarValue::Int(v) => write!(f, "{v}"),
+            CvarValue::Float(v) => write!(f, "{v}"),
+            CvarValue::String(v) => f.write_str(v),
+        }
+    }
+}
+
+impl CvarKind {
+    /// Parses console input (`1`/`0`/`on`/`off`/`true`/`false` for bools).
+    pub fn parse(self, text: &str) -> Result<CvarValue, String> {
+        let t = text.trim();
+        match self {
+            CvarKind::Bool => match t.to_ascii_lowercase().as_str() {
+                "1" | "on" | "true" | "yes" => Ok(CvarValue::Bool(true)),
+                "0" | "off" | "false" | "no" => Ok(CvarValue::Bool(false)),
+                _ => Err(format!("expected 0|1, got '{t}'")),
+            },
+            CvarKind::Int => t
+                .parse::<i64>()
+                .map(CvarValue::Int)
+                .map_err(|_| format!("expected an integer, got '{t}'")),
+            CvarKind::Float => t
+                .parse::<f64>()
+                .ok()
+                .filter(|v| v.is_finite())
+                .map(CvarValue::Float)
+                .ok_or_else(|| format!("expected a number, got '{t}'")),
+            CvarKind::String => Ok(CvarValue::String(t.to_string())),
+        }
+    }
+
+    /// Converts a JSON value (config file, plugin payload) to this kind.
+    pub fn from_json(self, v: &Value) -> Result<CvarValue, String> {
+        match (self, v) {
+            (CvarKind::Bool, Value::Bool(b)) => Ok(CvarValue::Bool(*b)),
+            (CvarKind::Int, Value::Number(n)) => n
+                .as_i64()
+                .map(CvarValue::Int)
+                .ok_or_else(|| format!("expected an integer, got {n}")),
+            (CvarKind::Float, Value::Number(n)) => n
+                .as_f64()
+                .map(CvarValue::Float)
+                .ok_or_else(|| format!("expected a number, got {n}")),
+            (CvarKind::String, Value::String(s)) => Ok(CvarValue::String(s.clone())),
+            (_, Value::String(s)) => self.parse(s),
+            (kind, other) => Err(format!("expected {kind:?}, got {other}")),
+        }
+    }
+}
+
+/// Declaration of a console variable.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CvarDesc {
+    pub name: String,
+    pub kind: CvarKind,
+    pub default: Value,
+    #[serde(default)]
+    pub min: Option<f64>,
+    #[serde(default)]
+    pub max: Option<f64>,
+    #[serde(default)]
+    pub flags: Vec<CvarFlag>,
+    #[serde(default)]
+    pub help: String,
+}
+
+impl CvarDesc {
+    fn new(name: &str, kind: CvarKind, default: Value) -> Self {
+        Self {
+            name: name.to_string(),
+            kind,
+            default,
+            min: None,
+            max: None,
+            flags: Vec::new(),
+            help: String::new(),
+        }
+    }
+
+    pub fn bool(name: &str, default: bool) -> Self {
+        Self::new(name, CvarKind::Bool, Value::from(default))
+    }
+
+    pub fn int(name: &str, default: i64) -> Self {
+        Self::new(name, CvarKind::Int, Value::from(default))
+    }
+
+    pub fn float(name: &str, default: f64) -> Self {
+        Self::new(name, CvarKind::Float, Value::from(default))
+    }
+
+    pub fn string(name: &str, default: &str) -> Self {
+        Self::new(name, CvarKind::String, Value::from(default))
+    }
+
+    /// Inclusive bounds for int and float cvars.
+    pub fn range(mut self, min: f64, max: f64) -> Self {
+        self.min = Some(min);
+        self.max = Some(max);
+        self
+    }
+
+    pub fn flag(mut self, flag: CvarFlag) -> Self {
+        if !self.flags.contains(&flag) {
+            self.flags.push(flag);
+        }
+        self
+    }
+
+    pub fn help(mut self, help: &str) -> Self {
+        self.help = help.to_string();
+        self
+    }
+
+    #[inline]
+    pub fn has(&self, flag: CvarFlag) -> bool {
+        self.flags.contains(&flag)
+    }
+
+    fn check(&self, v: &CvarValue) -> Result<(), String> {
+        let Some(n) = v.as_f64() else {
+            return Ok(());
+        };
+        let below = self.min.is_some_and(|min| n < min);
+        let above = self.max.is_some_and(|max| n > max);
+        if below || above {
+            return Err(format!(
+                "{} out of range [{}, {}]",
+                self.name,
+                self.min.map_or("-inf".to_string(), |v| v.to_string()),
+                self.max.map_or("inf".to_string(), |v| v.to_string()),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// A registered cvar as reported by [`cvar_list`].
+#[derive(Debug, Clone, Serialize)]
+pub struct CvarInfo {
+    #[serde(flatten)]
+    pub desc: CvarDesc,
+    pub value: CvarValue,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CvarChange {
+    pub name: String,
+    pub value: CvarValue,
+}
+
+struct Cvar {
+    desc: CvarDesc,
+    default: CvarValue,
+    value: CvarValue,
+}
+
+/// Process-wide so console commands and plugin services reach cvars without an `Engine`.
+static CVARS: Mutex<BTreeMap<String, Cvar>> = Mutex::new(BTreeMap::new());
+
+fn lock() -> Result<MutexGuard<'static, BTreeMap<String, Cvar>>, String> {
+    let mut g = CVARS
+        .lock()
+        .map_err(|_| "cvar mutex poisoned".to_string())?;
+    if !g.contains_key(CHEATS_CVAR) {
+        let desc = CvarDesc::bool(CHEATS_CVAR, false).help("Allow setting cheat cvars");
+        g.insert(
+            CHEATS_CVAR.to_string(),
+            Cvar {
+                desc,
+                default: CvarValue::Bool(false),
+                value: CvarValue::Bool(false),
+            },
+        );
+    }
+    Ok(g)
+}
+
+/// Registers a cvar and returns its effective value.
+///
+/// Archived cvars start from the value saved in the `cvars` config section. Registering an
+/// existing name again (e.g. after a plugin reload) keeps the current value; a different
+/// kind is an error.
+pub fn register_cvar(desc: CvarDesc) -> Result<CvarValue, String> {
+    if desc.name.is_empty() || !desc.name.chars().all(is_cvar_name_char) {
+        return Err(format!("invalid cvar name: '{}'", desc.name));
+    }
+    let default = desc
+        .kind
+        .from_json(&desc.default)
+        .map_err(|e| format!("cvar '{}' default: {e}", desc.name))?;
+    desc.check(&default)?;
+
+    let mut g = lock()?;
+    if let Some(existing) = g.get_mut(&desc.name) {
+        if existing.desc.kind != desc.kind {
+            return Err(format!(
+                "cvar '{}' already registered as {:?}",
+                desc.name, existing.desc.kind
+            ));
+        }
+        existing.desc = desc;
+        existing.default = default;
+        return Ok(existing.value.clone());
+    }
+
+    let mut value = default.clone();
+    if desc.has(CvarFlag::Archive) {
+        if let Some(saved) = config_api().get_value(CVAR_CONFIG_SECTION, &desc.name) {
+            match desc
+                .kind
+                .from_json(&saved)
+                .and_then(|v| desc.check(&v).map(|_| v))
+            {
+                Ok(v) => value = v,
+                Err(e) => log::warn!("cvar: archived '{}' ignored: {e}", desc.name),
+            }
+        }
+    }
+
+    log::debug!("cvar: registered {}={value}", desc.name);
+    g.insert(
+        desc.name.clone(),
+        Cvar {
+            desc,
+            default,
+            value: value.clone(),
+        },
+    );
+    Ok(value)
+}
+
+pub fn unregister_cvar(name: &str) -> bool {
+    name != CHEATS_CVAR && lock().is_ok_and(|mut g| g.remove(name).is_some())
+}
+
+#[inline]
+pub fn cvar_exists(name: &str) -> bool {
+    lock().is_ok_and(|g| g.contains_key(name))
+}
+
+pub fn cvar_get(name: &str) -> Option<CvarValue> {
+    lock().ok()?.get(name).map(|c| c.value.clone())
+}
+
+#[inline]
+pub fn cvar_bool(name: &str) -> Option<bool> {
+    cvar_get(name)?.as_bool()
+}
+
+#[inline]
+pub fn cvar_int(name: &str) -> Option<i64> {
+    cvar_get(name)?.as_i64()
+}
+
+#[inline]
+pub fn cvar_float(name: &str) -> Option<f64> {
+    cvar_get(name)?.as_f64()
+}
+
+#[inline]
+pub fn cvar_string(name: &str) -> Option<String> {
+    cvar_get(name)?.as_str().map(str::to_string)
+}
+
+/// Sets a cvar from console text; see [`cvar_set_value`].
+pub fn cvar_set(name: &str, text: &str) -> Result<CvarValue, String> {
+    let kind = lock()?
+        .get(name)
+        .map(|c| c.desc.kind)
+        .ok_or_else(|| format!("unknown cvar: {name}"))?;
+    cvar_set_value(name, kind.parse(text)?)
+}
+
+/// Range-checks, applies and publishes a new value; archived cvars are written to config.
+pub fn cvar_set_value(name: &str, value: CvarValue) -> Result<CvarValue, String> {
+    let mut changes = Vec::new();
+    let applied = {
+        let mut g = lock()?;
+        let cheats = g
+            .get(CHEATS_CVAR)
+            .and_then(|c| c.value.as_bool())
+            .unwrap_or(false);
+        let c = g
+            .get_mut(name)
+            .ok_or_else(|| format!("unknown cvar: {name}"))?;
+
+        if c.desc.has(CvarFlag::ReadOnly) {
+            return Err(format!("{name} is read-only"));
+        }
+        if c.desc.has(CvarFlag::Cheat) && !cheats {
+            return Err(format!("{name} is cheat protected (set {CHEATS_CVAR} 1)"));
+        }
+        let value = c.desc.kind.from_json(&value.to_json())?;
+        c.desc.check(&value)?;
+
+        if c.value == value {
+            return Ok(value);
+        }
+        c.value = value.clone();
+        changes.push((
+            name.to_string(),
+            value.clone(),
+            c.desc.has(CvarFlag::Archive),
+        ));
+
+        if name == CHEATS_CVAR && c.value.as_bool() == Some(false) {
+            for (n, c) in g.iter_mut().filter(|(_, c)| c.desc.has(CvarFlag::Cheat)) {
+                if c.value != c.default {
+                    c.value = c.default.clone();
+                    changes.push((n.clone(), c.value.clone(), c.desc.has(CvarFlag::Archive)));
+                }
+            }
+        }
+        value
+    };
+
+    for (name, value, archive) in changes {
+        publish(&name, &value, archive);
+    }
+    Ok(applied)
+}
+
+/// Puts a cvar back to its registered default.
+pub fn cvar_reset(name: &str) -> Result<CvarValue, String> {
+    let default = lock()?
+        .get(name)
+        .map(|c| c.default.clone())
+        .ok_or_else(|| format!("unknown cvar: {name}"))?;
+    cvar_set_value(name, default)
+}
+
+fn publish(name: &str, value: &CvarValue, archive: bool) {
+    log::info!("cvar: set {name}={value}");
+
+    if archive {
+        if let Err(e) = config_api().set_value(CVAR_CONFIG_SECTION, name, value.to_json()) {
+            log::warn!("cvar: archive '{name}' failed: {e}");
+        }
+    }
+
+    let change = CvarChange {
+        name: name.to_string(),
+        value: value.clone(),
+    };
+    let payload = serde_json::to_vec(&change).unwrap_or_default();
+    if let Err(e) = crate::plugins::event_router::enqueue(&cvar_topic(name), &payload, true) {
+        log::warn!("cvar: change notification dropped: {e}");
+    }
+}
+
+/// Registered cvars whose name starts with `prefix`, sorted by name.
+pub fn cvar_list(prefix: &str) -> Vec<CvarInfo> {
+    let Ok(g) = lock() else {
+        return Vec::new();
+    };
+    g.iter()
+        .filter(|(n, _)| n.starts_with(prefix))
+        .map(|(_, c)| CvarInfo {
+            desc: c.desc.clone(),
+            value: c.value.clone(),
+        })
+        .collect()
+}
+
+/// Names usable as cvars; the same set the console accepts for `$name`.
+#[inline]
+pub fn is_cvar_name_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-')
+}
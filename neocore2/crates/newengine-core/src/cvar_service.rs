@@ -0,0 +1,175 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use crate::cvar::{
+    cvar_exists, cvar_list, cvar_reset, cvar_set, register_cvar, CvarDesc, CvarInfo,
+};
+use crate::plugins::host_api;
+use abi_stable::std_types::{RResult, RString};
+use newengine_plugin_api::{
+    guarded_service, Blob, CapabilityId, MethodName, ServiceV1, ServiceV1Dyn,
+};
+use serde::Serialize;
+use serde_json::json;
+
+pub const CVAR_SERVICE_ID: &str = "engine.cvar";
+
+pub mod method {
+    pub const REGISTER: &str = "cvar.register";
+    pub const GET_JSON: &str = "cvar.get_json";
+    pub const SET: &str = "cvar.set";
+    pub const RESET: &str = "cvar.reset";
+    pub const LIST_JSON: &str = "cvar.list_json";
+}
+
+#[derive(Debug, Serialize)]
+struct CvarResp {
+    ok: bool,
+    cvar: Option<CvarInfo>,
+    error: Option<String>,
+}
+
+impl CvarResp {
+    fn from_result(name: &str, res: Result<(), String>) -> Self {
+        Self {
+            ok: res.is_ok(),
+            cvar: cvar_list(name).into_iter().find(|c| c.desc.name == name),
+            error: res.err(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct CvarListResp {
+    cvars: Vec<CvarInfo>,
+}
+
+struct CvarService;
+
+impl CvarService {
+    /// Payload: json [`CvarDesc`].
+    fn register(payload: &[u8]) -> CvarResp {
+        match serde_json::from_slice::<CvarDesc>(payload) {
+            Ok(desc) => {
+                let name = desc.name.clone();
+                CvarResp::from_result(&name, register_cvar(desc).map(|_| ()))
+            }
+            Err(e) => CvarResp {
+                ok: false,
+                cvar: None,
+                error: Some(format!("bad cvar desc: {e}")),
+            },
+        }
+    }
+
+    /// Payload: `<name> <value>`.
+    fn set(arg: &str) -> CvarResp {
+        let (name, value) = arg
+            .trim()
+            .split_once(char::is_whitespace)
+            .unwrap_or((arg.trim(), ""));
+        let res = match value.trim() {
+            "" => Err(format!("usage: {} <name> <value>", method::SET)),
+            v => cvar_set(name, v).map(|_| ()),
+        };
+        CvarResp::from_result(name, res)
+    }
+}
+
+impl ServiceV1 for CvarService {
+    fn id(&self) -> CapabilityId {
+        RString::from(CVAR_SERVICE_ID)
+    }
+
+    fn describe(&self) -> RString {
+        let d = json!({
+          "id": CVAR_SERVICE_ID,
+          "version": 1,
+          "methods": [
+            {
+              "name": method::REGISTER,
+              "payload": "json CvarDesc",
+              "returns": "json CvarResp",
+              "schema": {
+                "type": "object",
+                "required": ["name", "kind", "default"],
+                "properties": {
+                  "name": { "type": "string" },
+                  "kind": { "type": "string", "enum": ["bool", "int", "float", "string"] },
+                  "default": { "description": "value of the declared kind" },
+                  "min": { "type": "number" },
+                  "max": { "type": "number" },
+                  "flags": {
+                    "type": "array",
+                    "items": { "type": "string", "enum": ["cheat", "archive", "read_only"] }
+                  },
+                  "help": { "type": "string" }
+                }
+              }
+            },
+            { "name": method::GET_JSON, "payload": "utf8 name", "returns": "json CvarResp" },
+            { "name": method::SET, "payload": "utf8 '<name> <value>'", "returns": "json CvarResp" },
+            { "name": method::RESET, "payload": "utf8 name", "returns": "json CvarResp" },
+            { "name": method::LIST_JSON, "payload": "utf8 '[prefix]'", "returns": "json CvarListResp" }
+          ],
+          "console": {
+            "commands": [
+              {
+                "name": "cvar.list",
+                "help": "List console variables with type, range, flags and value",
+                "usage": "cvar.list [prefix]",
+                "kind": "service_call",
+                "service_id": CVAR_SERVICE_ID,
+                "method": method::LIST_JSON,
+                "payload": "raw"
+              },
+              {
+                "name": "cvar.reset",
+                "help": "Put a console variable back to its default",
+                "usage": "cvar.reset <name>",
+                "kind": "service_call",
+                "service_id": CVAR_SERVICE_ID,
+                "method": method::RESET,
+                "payload": "raw"
+              }
+            ]
+          }
+        });
+
+        RString::from(d.to_string())
+    }
+
+    fn call(&self, method: MethodName, payload: Blob) -> RResult<Blob, RString> {
+        let m = method.to_string();
+        let arg = String::from_utf8_lossy(payload.as_slice());
+        let name = arg.trim();
+
+        let resp = match m.as_str() {
+            method::REGISTER => serde_json::to_vec(&Self::register(payload.as_slice())),
+            method::GET_JSON => {
+                let res = if cvar_exists(name) {
+                    Ok(())
+                } else {
+                    Err(format!("unknown cvar: {name}"))
+                };
+                serde_json::to_vec(&CvarResp::from_result(name, res))
+            }
+            method::SET => serde_json::to_vec(&Self::set(&arg)),
+            method::RESET => {
+                let res = cvar_reset(name).map(|_| ());
+                serde_json::to_vec(&CvarResp::from_result(name, res))
+            }
+            method::LIST_JSON => serde_json::to_vec(&CvarListResp {
+                cvars: cvar_list(name),
+            }),
+            _ => return RResult::RErr(RString::from(format!("unknown method: {m}"))),
+        };
+
+        RResult::ROk(Blob::from(resp.unwrap_or_default()))
+    }
+}
+
+pub fn register_cvar_service() {
+    let dyn_svc: ServiceV1Dyn<'static> = guarded_service(CvarService);
+
+    let _ = host_api::host_register_service_impl(dyn_svc, false);
+}
@@ -0,0 +1,155 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use crate::plugins::host_api;
+use crate::render::{Color4, DebugDraw, DebugShape};
+use abi_stable::std_types::{RResult, RString};
+use newengine_plugin_api::{
+    guarded_service, Blob, CapabilityId, MethodName, ServiceV1, ServiceV1Dyn,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+pub const DEBUG_DRAW_SERVICE_ID: &str = "engine.debug_draw";
+
+pub mod method {
+    pub const DRAW_JSON: &str = "debug.draw_json";
+    pub const CLEAR: &str = "debug.clear";
+    pub const STATS_JSON: &str = "debug.stats_json";
+}
+
+const WHITE: Color4 = [1.0, 1.0, 1.0, 1.0];
+
+/// `{"kind":"sphere","center":[0,1,0],"radius":0.5,"color":[1,0,0,1],"duration":2}`
+#[derive(Debug, Deserialize)]
+struct DebugDrawItem {
+    #[serde(flatten)]
+    shape: DebugShape,
+    #[serde(default = "white")]
+    color: Color4,
+    /// Seconds; `0` draws for one frame.
+    #[serde(default)]
+    duration: f32,
+}
+
+#[inline]
+fn white() -> Color4 {
+    WHITE
+}
+
+#[derive(Debug, Serialize)]
+struct DebugDrawResp {
+    ok: bool,
+    queued: usize,
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct DebugDrawStatsResp {
+    queued: usize,
+    dropped: usize,
+}
+
+struct DebugDrawService;
+
+impl DebugDrawService {
+    /// Payload: a single item or an array of items.
+    fn draw(payload: &[u8]) -> DebugDrawResp {
+        let parsed = serde_json::from_slice::<serde_json::Value>(payload).and_then(|v| {
+            if v.is_array() {
+                serde_json::from_value::<Vec<DebugDrawItem>>(v)
+            } else {
+                serde_json::from_value::<DebugDrawItem>(v).map(|i| vec![i])
+            }
+        });
+
+        let items = match parsed {
+            Ok(items) => items,
+            Err(e) => {
+                return DebugDrawResp {
+                    ok: false,
+                    queued: 0,
+                    error: Some(format!("bad debug draw payload: {e}")),
+                }
+            }
+        };
+
+        let dd = DebugDraw::global();
+        let queued = items.len();
+        for i in items {
+            dd.submit(i.shape, i.color, i.duration);
+        }
+
+        DebugDrawResp {
+            ok: true,
+            queued,
+            error: None,
+        }
+    }
+}
+
+impl ServiceV1 for DebugDrawService {
+    fn id(&self) -> CapabilityId {
+        RString::from(DEBUG_DRAW_SERVICE_ID)
+    }
+
+    fn describe(&self) -> RString {
+        let d = json!({
+          "id": DEBUG_DRAW_SERVICE_ID,
+          "version": 1,
+          "methods": [
+            { "name": method::DRAW_JSON, "payload": "json DebugDrawItem | [DebugDrawItem]", "returns": "json DebugDrawResp" },
+            { "name": method::CLEAR, "payload": "empty", "returns": "empty" },
+            { "name": method::STATS_JSON, "payload": "empty", "returns": "json DebugDrawStatsResp" }
+          ],
+          "shapes": ["line", "aabb", "box", "sphere", "cross", "text", "billboard"],
+          "console": {
+            "commands": [
+              {
+                "name": "debug.draw",
+                "help": "Queue debug geometry from JSON, e.g. debug.draw {\"kind\":\"sphere\",\"center\":[0,0,0],\"radius\":1,\"duration\":5}",
+                "usage": "debug.draw <json>",
+                "kind": "service_call",
+                "service_id": DEBUG_DRAW_SERVICE_ID,
+                "method": method::DRAW_JSON,
+                "payload": "raw"
+              },
+              {
+                "name": "debug.clear",
+                "help": "Remove all queued debug geometry",
+                "kind": "service_call",
+                "service_id": DEBUG_DRAW_SERVICE_ID,
+                "method": method::CLEAR,
+                "payload": "empty"
+              }
+            ]
+          }
+        });
+
+        RString::from(d.to_string())
+    }
+
+    fn call(&self, method: MethodName, payload: Blob) -> RResult<Blob, RString> {
+        let m = method.to_string();
+
+        let resp = match m.as_str() {
+            method::DRAW_JSON => serde_json::to_vec(&Self::draw(payload.as_slice())),
+            method::CLEAR => {
+                DebugDraw::global().clear();
+                Ok(Vec::new())
+            }
+            method::STATS_JSON => {
+                let (queued, dropped) = DebugDraw::global().stats();
+                serde_json::to_vec(&DebugDrawStatsResp { queued, dropped })
+            }
+            _ => return RResult::RErr(RString::from(format!("unknown method: {m}"))),
+        };
+
+        RResult::ROk(Blob::from(resp.unwrap_or_default()))
+    }
+}
+
+pub fn register_debug_draw_service() {
+    let dyn_svc: ServiceV1Dyn<'static> = guarded_service(DebugDrawService);
+
+    let _ = host_api::host_register_service_impl(dyn_svc, false);
+}
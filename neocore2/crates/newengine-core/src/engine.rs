@@ -1,28 +1,73 @@
+use crate::config::config_api;
 use crate::error::{EngineError, EngineResult, ModuleStage};
 use crate::events::EventHub;
 use crate::frame::Frame;
+use crate::jobs::JobSystem;
+use crate::module::order::resolve_init_order;
 use crate::module::{ApiVersion, Bus, Module, ModuleCtx, Resources, Services};
 #[cfg(feature = "runtime")]
 use crate::plugins::importers_host_api;
-use crate::plugins::{default_host_api, init_host_context, PluginManager};
+use crate::plugins::{
+    default_host_api, init_host_context, set_event_limits, set_service_limits, EventLimits,
+    PluginManager, ServiceLimits,
+};
 use crate::sched::Scheduler;
+use crate::snapshot::{
+    set_last_snapshot_report, take_snapshot_requests, SnapshotFile, SnapshotOp, SnapshotReport,
+    SnapshotSection,
+};
 use crate::sync::ShutdownToken;
+use crate::telemetry;
 use crate::system_info::SystemInfo;
 #[cfg(feature = "runtime")]
+use crate::topics::TopicSub;
+#[cfg(feature = "runtime")]
 use crate::AssetManagerConfig;
+use newengine_plugin_api::FrameInfoAbi;
 
 use std::any::Any;
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::{HashMap, HashSet};
 use std::fmt;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::{Duration, Instant};
 
+/// Which stages `begin_frame` runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RunProfile {
+    /// Fixed update, update and render (default).
+    Client,
+    /// Dedicated server: fixed update only; plugin/module `update` and `render` are skipped.
+    Server,
+}
+
+impl Default for RunProfile {
+    #[inline]
+    fn default() -> Self {
+        Self::Client
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct EngineConfig {
-    pub fixed_dt_ms: u32,
+    /// Fixed timestep; kept as a `Duration` so tick rates that do not divide 1000 stay exact.
+    pub fixed_dt: Duration,
     #[cfg(feature = "runtime")]
     pub assets: AssetManagerConfig,
     pub plugins_dir: Option<PathBuf>,
+    /// Plugin ids never initialised, on top of the `disabled` list in `plugins.json`.
+    pub disabled_plugins: Vec<String>,
+    pub service_limits: ServiceLimits,
+    pub event_limits: EventLimits,
+    /// Per-user settings file (console key bindings). `None` keeps bindings in memory only.
+    pub user_config_path: Option<PathBuf>,
+    /// Module settings file for `engine.config` (`.toml` or JSON). `None` keeps changes in memory.
+    pub config_path: Option<PathBuf>,
+    /// Run without a window: frames advance by exactly `fixed_dt` instead of wall-clock time,
+    /// so a driver like [`crate::HeadlessRunner`] gets reproducible runs (tests, CI, cooking).
+    pub headless: bool,
+    pub profile: RunProfile,
+    /// Worker threads of the job system; 0 picks one per logical CPU minus the engine thread.
+    pub job_threads: usize,
 }
 
 impl EngineConfig {
@@ -30,9 +75,17 @@ impl EngineConfig {
     #[inline]
     pub fn new(fixed_dt_ms: u32, assets: AssetManagerConfig) -> Self {
         Self {
-            fixed_dt_ms,
+            fixed_dt: Duration::from_millis(fixed_dt_ms.into()),
             assets,
             plugins_dir: None,
+            disabled_plugins: Vec::new(),
+            service_limits: ServiceLimits::default(),
+            event_limits: EventLimits::default(),
+            user_config_path: None,
+            config_path: None,
+            headless: false,
+            profile: RunProfile::Client,
+            job_threads: 0,
         }
     }
 
@@ -40,8 +93,16 @@ impl EngineConfig {
     #[inline]
     pub fn new(fixed_dt_ms: u32) -> Self {
         Self {
-            fixed_dt_ms,
+            fixed_dt: Duration::from_millis(fixed_dt_ms.into()),
             plugins_dir: None,
+            disabled_plugins: Vec::new(),
+            service_limits: ServiceLimits::default(),
+            event_limits: EventLimits::default(),
+            user_config_path: None,
+            config_path: None,
+            headless: false,
+            profile: RunProfile::Client,
+            job_threads: 0,
         }
     }
 
@@ -50,13 +111,81 @@ impl EngineConfig {
         self.plugins_dir = dir;
         self
     }
+
+    #[inline]
+    pub fn with_disabled_plugins(mut self, ids: Vec<String>) -> Self {
+        self.disabled_plugins = ids;
+        self
+    }
+
+    #[inline]
+    pub fn with_service_limits(mut self, limits: ServiceLimits) -> Self {
+        self.service_limits = limits;
+        self
+    }
+
+    #[inline]
+    pub fn with_event_limits(mut self, limits: EventLimits) -> Self {
+        self.event_limits = limits;
+        self
+    }
+
+    #[inline]
+    pub fn with_user_config_path(mut self, path: Option<PathBuf>) -> Self {
+        self.user_config_path = path;
+        self
+    }
+
+    #[inline]
+    pub fn with_config_path(mut self, path: Option<PathBuf>) -> Self {
+        self.config_path = path;
+        self
+    }
+
+    #[inline]
+    pub fn with_headless(mut self, headless: bool) -> Self {
+        self.headless = headless;
+        self
+    }
+
+    /// Selects the stage profile. `RunProfile::Server` implies `headless`.
+    #[inline]
+    pub fn with_profile(mut self, profile: RunProfile) -> Self {
+        self.profile = profile;
+        if profile == RunProfile::Server {
+            self.headless = true;
+        }
+        self
+    }
+
+    #[inline]
+    pub fn with_job_threads(mut self, threads: usize) -> Self {
+        self.job_threads = threads;
+        self
+    }
+
+    /// Sets `fixed_dt` to `1 / hz` (clamped to 1..=1000 Hz), exact to the nanosecond.
+    #[inline]
+    pub fn with_tick_rate(mut self, hz: u32) -> Self {
+        let hz = hz.clamp(1, 1000);
+        self.fixed_dt = Duration::from_nanos(1_000_000_000 / u64::from(hz));
+        self
+    }
 }
 
 pub struct Engine<E: Send + 'static> {
     fixed_dt: f32,
+    fixed_step: Duration,
     services: Box<dyn Services>,
     modules: Vec<Box<dyn Module<E>>>,
     module_ids: HashSet<&'static str>,
+    host_phases: HashSet<&'static str>,
+    /// `config.assets` changes, applied to the `AssetManager` budget between frames.
+    #[cfg(feature = "runtime")]
+    asset_config: TopicSub,
+    /// Store events forwarded to `asset.*` topics for host and plugin subscribers.
+    #[cfg(feature = "runtime")]
+    asset_events: newengine_assets::AssetEventReceiver,
 
     pub resources: Resources,
     bus: Bus<E>,
@@ -66,14 +195,20 @@ pub struct Engine<E: Send + 'static> {
 
     plugins: PluginManager,
     plugins_loaded: bool,
+    jobs: JobSystem,
     plugins_dir: Option<PathBuf>,
 
     shutdown: ShutdownToken,
     exit_requested: bool,
+    headless: bool,
+    profile: RunProfile,
+    /// Reported to plugins in `FrameInfoAbi`.
+    vsync: bool,
 
     frame_index: u64,
     fixed_tick: u64,
     started: bool,
+    epoch: Instant,
     last: Instant,
     acc: f32,
 }
@@ -123,6 +258,43 @@ impl<E: Send + 'static> Engine<E> {
         self.try_load_plugins_once()
     }
 
+    /// True when the engine runs without a window (see [`EngineConfig::headless`]).
+    #[inline]
+    pub fn is_headless(&self) -> bool {
+        self.headless
+    }
+
+    #[inline]
+    pub fn profile(&self) -> RunProfile {
+        self.profile
+    }
+
+    /// Whether presentation waits for the display refresh, as reported to plugins.
+    ///
+    /// Defaults to true for windowed runs (the Vulkan backend presents with FIFO or MAILBOX);
+    /// hosts with a different present mode should set it.
+    #[inline]
+    pub fn vsync(&self) -> bool {
+        self.vsync
+    }
+
+    #[inline]
+    pub fn set_vsync(&mut self, vsync: bool) {
+        self.vsync = vsync;
+    }
+
+    /// Fixed timestep in seconds.
+    #[inline]
+    pub fn fixed_dt(&self) -> f32 {
+        self.fixed_dt
+    }
+
+    /// Fixed timestep as configured, for pacing against wall-clock time.
+    #[inline]
+    pub fn fixed_step(&self) -> Duration {
+        self.fixed_step
+    }
+
     #[inline]
     pub fn shutdown_token(&self) -> ShutdownToken {
         self.shutdown.clone()
@@ -166,13 +338,29 @@ impl<E: Send + 'static> Engine<E> {
         bus: Bus<E>,
         shutdown: ShutdownToken,
     ) -> EngineResult<Self> {
-        let fixed_dt = (config.fixed_dt_ms as f32 / 1000.0).max(0.001);
+        let fixed_step = config.fixed_dt.max(Duration::from_millis(1));
+        let fixed_dt = fixed_step.as_secs_f32();
 
         let mut resources = Resources::default();
 
+        if let Some(path) = config.config_path.clone() {
+            match config_api().load(path) {
+                Ok(n) => log::info!("config: loaded sections={n}"),
+                Err(e) => log::warn!("config: load failed: {e}"),
+            }
+        }
+
         #[cfg(feature = "runtime")]
         {
-            let asset_manager = crate::assets::AssetManager::new_with_config(config.assets);
+            let tunables = config_api().declare_with(
+                crate::assets::ASSETS_CONFIG_SECTION,
+                crate::assets::AssetTunables {
+                    pump_steps: config.assets.pump_steps,
+                },
+            );
+            let asset_manager = crate::assets::AssetManager::new_with_config(
+                config.assets.with_pump_steps(tunables.pump_steps),
+            );
             resources.insert(asset_manager);
 
             // Host context must exist before any plugin can register services/importers.
@@ -184,7 +372,15 @@ impl<E: Send + 'static> Engine<E> {
 
             init_host_context(asset_store.clone());
             crate::assets_service::register_asset_manager_service(asset_store.clone());
+            crate::project_service::register_project_service(asset_store.clone());
             crate::console::init_console_service();
+            crate::window_service::register_window_service();
+
+            if let Some(path) = config.user_config_path.as_ref() {
+                if let Err(e) = crate::console::load_key_bindings(path.clone()) {
+                    log::warn!("console.bind load failed: {e}");
+                }
+            }
         }
 
         #[cfg(not(feature = "runtime"))]
@@ -192,27 +388,76 @@ impl<E: Send + 'static> Engine<E> {
             init_host_context();
         }
 
+        set_service_limits(config.service_limits);
+        set_event_limits(config.event_limits);
+        crate::events_service::register_events_service();
+        crate::modules_service::register_modules_service();
+        crate::plugins_service::register_plugins_service();
+        crate::config_service::register_config_service();
+        crate::telemetry_service::register_telemetry_service();
+        crate::telemetry::init();
+        crate::debug_draw_service::register_debug_draw_service();
+        crate::render_service::register_render_service();
+        crate::time_service::register_time_service();
+        crate::snapshot_service::register_snapshot_service();
+        crate::save_service::register_save_service();
+        crate::cvar_service::register_cvar_service();
+        crate::undo_service::register_undo_service();
+        crate::entity_service::register_entity_service();
+        resources.insert(crate::render::DebugDraw::global());
+        resources.insert(crate::undo::UndoStack::global());
+        let jobs = JobSystem::global_with_threads(config.job_threads).clone();
+        resources.insert(jobs.clone());
+
+        // Plugin-emitted events reach host topic subscribers through this hub.
+        let events = EventHub::new();
+        crate::plugins::event_router::attach_host_topics(events.topic_router());
+
+        #[cfg(feature = "runtime")]
+        let asset_config = events.subscribe_topic(
+            crate::config::config_topic(crate::assets::ASSETS_CONFIG_SECTION).as_str(),
+        );
+        #[cfg(feature = "runtime")]
+        let asset_events = resources
+            .get::<crate::assets::AssetManager>()
+            .expect("AssetManager missing")
+            .subscribe_events();
+
+        let mut plugins = PluginManager::new();
+        plugins.set_disabled_plugins(config.disabled_plugins);
+
         Ok(Self {
             fixed_dt,
+            fixed_step,
             services,
             modules: Vec::new(),
             module_ids: HashSet::new(),
+            host_phases: HashSet::new(),
+            #[cfg(feature = "runtime")]
+            asset_config,
+            #[cfg(feature = "runtime")]
+            asset_events,
 
             resources,
             bus,
-            events: EventHub::new(),
+            events,
             scheduler: Scheduler::new(),
 
-            plugins: PluginManager::new(),
+            plugins,
             plugins_loaded: false,
+            jobs,
             plugins_dir: config.plugins_dir,
 
             shutdown,
             exit_requested: false,
+            headless: config.headless,
+            profile: config.profile,
+            vsync: !config.headless,
 
             frame_index: 0,
             fixed_tick: 0,
             started: false,
+            epoch: Instant::now(),
             last: Instant::now(),
             acc: 0.0,
         })
@@ -238,11 +483,25 @@ impl<E: Send + 'static> Engine<E> {
             )));
         }
 
+        crate::module::toggles::track_module(id, module.dependencies());
         self.modules.push(module);
         self.module_ids.insert(id);
         Ok(())
     }
 
+    /// Enables or disables a registered module at runtime. Disabled modules stay initialized
+    /// but skip `fixed_update`, `update` and `render` until re-enabled.
+    pub fn set_module_enabled(&mut self, id: &str, enabled: bool) -> EngineResult<()> {
+        crate::module::set_module_enabled(id, enabled)
+            .map(|_| ())
+            .map_err(EngineError::Other)
+    }
+
+    #[inline]
+    pub fn is_module_enabled(&self, id: &str) -> bool {
+        crate::module::module_enabled(id)
+    }
+
     #[inline]
     fn elapsed_since(t0: Instant) -> Elapsed {
         Elapsed::from_duration(t0.elapsed())
@@ -406,6 +665,18 @@ impl<E: Send + 'static> Engine<E> {
         }
     }
 
+    /// Announces plugins disabled since the last frame on `PLUGIN_DISABLED_TOPIC`.
+    fn publish_plugin_faults(&self) {
+        for report in crate::plugins::take_disabled_reports() {
+            if let Err(e) = self
+                .events
+                .publish_topic_json(crate::plugins::PLUGIN_DISABLED_TOPIC, &report)
+            {
+                log::warn!("plugins: failed to publish disable report: {e}");
+            }
+        }
+    }
+
     fn log_plugins_diagnostics(&self, tag: &'static str) {
         let mut list: Vec<(String, String)> = Vec::new();
         for p in self.plugins.iter() {
@@ -415,6 +686,9 @@ impl<E: Send + 'static> Engine<E> {
         list.sort_by(|a, b| a.0.cmp(&b.0));
 
         log::info!("plugins: diagnostics tag='{}' loaded={}", tag, list.len());
+        crate::crash::set_loaded_plugins(
+            list.iter().map(|(id, ver)| format!("{id} {ver}")).collect(),
+        );
 
         for (i, (id, ver)) in list.iter().enumerate() {
             log::info!(
@@ -439,6 +713,17 @@ impl<E: Send + 'static> Engine<E> {
         }
     }
 
+    /// Records that the host reached `phase` (e.g. `HOST_PHASE_WINDOW`), satisfying
+    /// `"after:<phase>"` module dependencies. Call before `start()`.
+    pub fn mark_host_phase(&mut self, phase: &'static str) {
+        self.host_phases.insert(phase);
+    }
+
+    #[inline]
+    pub fn has_host_phase(&self, phase: &str) -> bool {
+        self.host_phases.contains(phase)
+    }
+
     pub fn start(&mut self) -> EngineResult<()> {
         self.started = true;
         self.last = Instant::now();
@@ -450,63 +735,23 @@ impl<E: Send + 'static> Engine<E> {
 
         self.validate_api_contracts()?;
 
-        let n = self.modules.len();
-
-        let mut id_to_index: HashMap<&'static str, usize> = HashMap::with_capacity(n);
-        for (i, m) in self.modules.iter().enumerate() {
-            let id = m.id();
-            if id_to_index.insert(id, i).is_some() {
-                return Err(EngineError::Other(format!("duplicate module id: {id}")));
-            }
-        }
-
-        let mut indegree = vec![0usize; n];
-        let mut rev_edges: Vec<Vec<usize>> = vec![Vec::new(); n];
-
-        for (i, m) in self.modules.iter().enumerate() {
-            for &dep in m.dependencies() {
-                let Some(&dep_i) = id_to_index.get(dep) else {
-                    return Err(EngineError::Other(format!(
-                        "module dependency missing: {} -> {dep}",
-                        m.id()
-                    )));
-                };
-                indegree[i] += 1;
-                rev_edges[dep_i].push(i);
-            }
-        }
+        let graph: Vec<(&'static str, &'static [&'static str])> = self
+            .modules
+            .iter()
+            .map(|m| (m.id(), m.dependencies()))
+            .collect();
+        let order = resolve_init_order(&graph, &self.host_phases).map_err(EngineError::Other)?;
 
-        let mut q: VecDeque<usize> = VecDeque::new();
-        for i in 0..n {
-            if indegree[i] == 0 {
-                q.push_back(i);
-            }
-        }
-
-        let mut order: Vec<usize> = Vec::with_capacity(n);
-        while let Some(i) = q.pop_front() {
-            order.push(i);
-            for &to in rev_edges[i].iter() {
-                indegree[to] = indegree[to].saturating_sub(1);
-                if indegree[to] == 0 {
-                    q.push_back(to);
-                }
-            }
-        }
-
-        if order.len() != n {
-            let mut cyclic = Vec::new();
-            for (i, deg) in indegree.iter().enumerate() {
-                if *deg != 0 {
-                    cyclic.push(self.modules[i].id());
-                }
-            }
-            return Err(EngineError::Other(format!(
-                "module dependency cycle detected among: {:?}",
-                cyclic
-            )));
-        }
+        log::info!(
+            "modules: init order [{}]",
+            order
+                .iter()
+                .map(|&i| graph[i].0)
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
 
+        let n = self.modules.len();
         let mut sorted: Vec<Box<dyn Module<E>>> = Vec::with_capacity(n);
         let mut old = std::mem::take(&mut self.modules);
         let mut slots: Vec<Option<Box<dyn Module<E>>>> = old.drain(..).map(Some).collect();
@@ -538,6 +783,7 @@ impl<E: Send + 'static> Engine<E> {
 
             let init_result = {
                 let m = &mut sorted[i];
+                self.resources.set_owner(Some(m.id()));
                 let mut ctx = ModuleCtx::new(
                     self.services.as_ref(),
                     &mut self.resources,
@@ -546,7 +792,9 @@ impl<E: Send + 'static> Engine<E> {
                     &mut self.scheduler,
                     &mut self.exit_requested,
                 );
-                m.init(&mut ctx)
+                let res = m.init(&mut ctx);
+                self.resources.set_owner(None);
+                res
             };
 
             if let Err(err) = init_result {
@@ -576,6 +824,10 @@ impl<E: Send + 'static> Engine<E> {
             return Err(EngineError::Other(format!("plugins: start failed: {e}")));
         }
 
+        // Plugin services are registered now, so autoexec can use their commands.
+        #[cfg(feature = "runtime")]
+        crate::console::run_autoexec();
+
         Ok(())
     }
 
@@ -592,14 +844,42 @@ impl<E: Send + 'static> Engine<E> {
         }
 
         let now = Instant::now();
-        let mut dt = (now - self.last).as_secs_f32();
+        let real_dt = if self.headless {
+            // Deterministic: one fixed step per frame, independent of how fast the host loops.
+            self.fixed_dt
+        } else {
+            (now - self.last).as_secs_f32().clamp(0.0, 0.2)
+        };
         self.last = now;
 
-        dt = dt.clamp(0.0, 0.2);
-
+        // Game clock: scaled, paused or single-stepped (see `crate::time`).
+        let dt = crate::time::advance(real_dt, self.fixed_dt);
         self.acc = (self.acc + dt).min(1.0);
 
-        self.scheduler.begin_frame(Duration::from_secs_f32(dt));
+        self.scheduler.begin_frame(Duration::from_secs_f32(real_dt));
+
+        let frame_info = FrameInfoAbi {
+            frame_index: self.frame_index,
+            dt,
+            wall_time: (now - self.epoch).as_secs_f64(),
+            vsync: self.vsync,
+            fixed_alpha: 0.0,
+        };
+        let res = {
+            let _scope = telemetry::scope("plugins", "begin_frame_all");
+            self.plugins.begin_frame_all(frame_info)
+        };
+        if let Err(e) = res {
+            return Err(EngineError::Other(format!("plugins: begin_frame failed: {e}")));
+        }
+
+        // Events phase: plugin/topic events queued since the last frame reach their sinks
+        // before anyone simulates on them.
+        {
+            let _scope = telemetry::scope("plugins", "dispatch_events");
+            crate::plugins::dispatch_events();
+        }
+        self.publish_plugin_faults();
 
         let mut steps_to_run = (self.acc / self.fixed_dt).floor() as u32;
         steps_to_run = steps_to_run.min(8);
@@ -616,6 +896,7 @@ impl<E: Send + 'static> Engine<E> {
             let fixed_frame = Frame {
                 frame_index: self.frame_index,
                 dt: self.fixed_dt,
+                real_dt,
                 fixed_dt: self.fixed_dt,
                 fixed_alpha: 0.0,
                 fixed_step_count: steps_to_run,
@@ -623,7 +904,11 @@ impl<E: Send + 'static> Engine<E> {
                 fixed_tick: self.fixed_tick,
             };
 
-            if let Err(e) = self.plugins.fixed_update_all(self.fixed_dt) {
+            let res = {
+                let _scope = telemetry::scope("plugins", "fixed_update_all");
+                self.plugins.fixed_update_all(self.fixed_dt)
+            };
+            if let Err(e) = res {
                 return Err(EngineError::Other(format!("plugins: fixed_update failed: {e}")));
             }
 
@@ -633,36 +918,74 @@ impl<E: Send + 'static> Engine<E> {
         let frame = Frame {
             frame_index: self.frame_index,
             dt,
+            real_dt,
             fixed_dt: self.fixed_dt,
             fixed_alpha: (self.acc / self.fixed_dt).clamp(0.0, 0.999_999),
             fixed_step_count: steps_to_run,
             fixed_step_index: 0,
             fixed_tick: self.fixed_tick,
         };
+        crate::plugins::host_api::set_current_fixed_alpha(frame.fixed_alpha);
+
+        if self.profile == RunProfile::Client {
+            let res = {
+                let _scope = telemetry::scope("plugins", "update_all");
+                self.plugins.update_all(dt)
+            };
+            if let Err(e) = res {
+                return Err(EngineError::Other(format!("plugins: update failed: {e}")));
+            }
+            self.run_stage(&frame, ModuleStage::Update, |m, ctx| m.update(ctx))?;
 
-        if let Err(e) = self.plugins.update_all(dt) {
-            return Err(EngineError::Other(format!("plugins: update failed: {e}")));
+            crate::render_service::begin_plugin_frame(
+                self.resources
+                    .api::<crate::render::RenderApiRef>(crate::render::RENDER_API_ID)
+                    .cloned(),
+            );
+            let res = {
+                let _scope = telemetry::scope("plugins", "render_all");
+                self.plugins.render_all(dt)
+            };
+            if let Err(e) = res {
+                return Err(EngineError::Other(format!("plugins: render failed: {e}")));
+            }
+            self.run_stage(&frame, ModuleStage::Render, |m, ctx| m.render(ctx))?;
+            crate::render_service::pump_captures();
+        }
+
+        {
+            let _scope = telemetry::scope("jobs", "wait_frame_jobs");
+            self.jobs.wait_frame_jobs();
         }
-        self.run_stage(&frame, ModuleStage::Update, |m, ctx| m.update(ctx))?;
 
-        if let Err(e) = self.plugins.render_all(dt) {
-            return Err(EngineError::Other(format!("plugins: render failed: {e}")));
+        let res = {
+            let _scope = telemetry::scope("plugins", "end_frame_all");
+            self.plugins.end_frame_all()
+        };
+        if let Err(e) = res {
+            return Err(EngineError::Other(format!("plugins: end_frame failed: {e}")));
         }
-        self.run_stage(&frame, ModuleStage::Render, |m, ctx| m.render(ctx))?;
 
-        self.scheduler.end_frame(Duration::from_secs_f32(dt));
+        self.scheduler.end_frame(Duration::from_secs_f32(real_dt));
         self.frame_index = self.frame_index.wrapping_add(1);
+        self.run_snapshot_requests();
 
         #[cfg(feature = "runtime")]
         {
+            self.apply_asset_config();
             if let Some(am) = self.resources.get::<crate::assets::AssetManager>() {
+                let _scope = telemetry::scope("assets", "pump");
                 am.pump();
             }
+            self.publish_asset_events();
+            crate::console::tick_watches(real_dt);
             if crate::console::take_exit_requested() {
                 self.exit_requested = true;
             }
         }
 
+        telemetry::frame_mark();
+
         Ok(frame)
     }
 
@@ -731,6 +1054,10 @@ impl<E: Send + 'static> Engine<E> {
     pub fn shutdown(&mut self) -> EngineResult<()> {
         self.sync_shutdown_state();
 
+        if let Err(e) = config_api().save() {
+            log::warn!("config: save failed: {e}");
+        }
+
         self.plugins.shutdown();
 
         for m in self.modules.iter_mut().rev() {
@@ -753,6 +1080,117 @@ impl<E: Send + 'static> Engine<E> {
         Ok(())
     }
 
+    /// Writes the state of every module implementing [`Snapshot`](crate::snapshot::Snapshot),
+    /// in init order. Call between frames.
+    pub fn save_snapshot(&mut self, path: impl AsRef<Path>) -> EngineResult<SnapshotReport> {
+        let path = path.as_ref();
+        let mut report = SnapshotReport::new(SnapshotOp::Save, path);
+        let mut file = SnapshotFile {
+            fixed_tick: self.fixed_tick,
+            frame_index: self.frame_index,
+            sections: Vec::new(),
+        };
+
+        for m in self.modules.iter_mut() {
+            let module_id = m.id();
+            let Some(snap) = m.snapshot() else {
+                continue;
+            };
+            let mut data = Vec::new();
+            snap.save(&mut data)
+                .map_err(|e| EngineError::Other(format!("snapshot: save '{module_id}': {e}")))?;
+            file.sections.push(SnapshotSection {
+                module_id: module_id.to_string(),
+                version: snap.snapshot_version(),
+                data,
+            });
+            report.modules.push(module_id.to_string());
+        }
+
+        file.save(path).map_err(|e| EngineError::Other(format!("snapshot: {e}")))?;
+
+        report.ok = true;
+        report.fixed_tick = file.fixed_tick;
+        log::info!(
+            "snapshot: saved path='{}' modules={} fixed_tick={}",
+            path.display(),
+            report.modules.len(),
+            report.fixed_tick
+        );
+        Ok(report)
+    }
+
+    /// Restores module state from a snapshot, in init order, and rewinds the fixed tick to the
+    /// saved one. Call between frames. Modules missing from the file keep their state.
+    pub fn load_snapshot(&mut self, path: impl AsRef<Path>) -> EngineResult<SnapshotReport> {
+        let path = path.as_ref();
+        let mut report = SnapshotReport::new(SnapshotOp::Load, path);
+        let file =
+            SnapshotFile::load(path).map_err(|e| EngineError::Other(format!("snapshot: {e}")))?;
+
+        let mut claimed = HashSet::new();
+        for m in self.modules.iter_mut() {
+            let module_id = m.id();
+            let Some(snap) = m.snapshot() else {
+                continue;
+            };
+            let Some(section) = file.section(module_id) else {
+                report.missing.push(module_id.to_string());
+                continue;
+            };
+            claimed.insert(module_id);
+            snap.load(section.version, &mut section.data.as_slice())
+                .map_err(|e| EngineError::Other(format!("snapshot: load '{module_id}': {e}")))?;
+            report.modules.push(module_id.to_string());
+        }
+
+        report.unknown = file
+            .sections
+            .iter()
+            .filter(|s| !claimed.contains(s.module_id.as_str()))
+            .map(|s| s.module_id.clone())
+            .collect();
+        if !report.missing.is_empty() || !report.unknown.is_empty() {
+            log::warn!(
+                "snapshot: path='{}' missing={:?} unknown={:?}",
+                path.display(),
+                report.missing,
+                report.unknown
+            );
+        }
+
+        // Resume exactly on the saved tick, with no carried-over accumulator time.
+        self.fixed_tick = file.fixed_tick;
+        self.acc = 0.0;
+
+        report.ok = true;
+        report.fixed_tick = file.fixed_tick;
+        log::info!(
+            "snapshot: loaded path='{}' modules={} fixed_tick={}",
+            path.display(),
+            report.modules.len(),
+            report.fixed_tick
+        );
+        Ok(report)
+    }
+
+    /// Runs saves and loads queued by `engine.snapshot.*` commands, after the frame finished.
+    fn run_snapshot_requests(&mut self) {
+        for (op, path) in take_snapshot_requests() {
+            let res = match op {
+                SnapshotOp::Save => self.save_snapshot(&path),
+                SnapshotOp::Load => self.load_snapshot(&path),
+            };
+            let report = res.unwrap_or_else(|e| {
+                log::warn!("snapshot: {op:?} failed path='{}': {e}", path.display());
+                let mut r = SnapshotReport::new(op, &path);
+                r.error = Some(e.to_string());
+                r
+            });
+            set_last_snapshot_report(report);
+        }
+    }
+
     #[inline]
     fn run_stage<F>(&mut self, frame: &Frame, stage: ModuleStage, mut call: F) -> EngineResult<()>
     where
@@ -782,11 +1220,21 @@ impl<E: Send + 'static> Engine<E> {
             }
 
             let module_id = m.id();
+            if !crate::module::module_enabled(module_id) {
+                continue;
+            }
 
-            let mut ctx = ModuleCtx::new(services, resources, bus, events, scheduler, exit_requested);
-            ctx.set_frame(frame);
+            // Entries inserted during the call are attributed to this module (Resources inspector).
+            resources.set_owner(Some(module_id));
+            let res = {
+                let _scope = telemetry::scope(stage.as_str(), module_id);
+                let mut ctx = ModuleCtx::new(services, resources, bus, events, scheduler, exit_requested);
+                ctx.set_frame(frame);
+                call(m.as_mut(), &mut ctx)
+            };
+            resources.set_owner(None);
 
-            call(m.as_mut(), &mut ctx).map_err(|e| EngineError::with_module_stage(module_id, stage, e))?;
+            res.map_err(|e| EngineError::with_module_stage(module_id, stage, e))?;
 
             if *exit_requested {
                 shutdown.request();
@@ -797,6 +1245,36 @@ impl<E: Send + 'static> Engine<E> {
         Ok(())
     }
 
+    #[cfg(feature = "runtime")]
+    fn publish_asset_events(&self) {
+        for ev in self.asset_events.drain() {
+            let Some((topic, payload)) = crate::plugins::asset_event_topic(&ev) else {
+                continue;
+            };
+            if let Err(e) = self.events.publish_topic_json(&topic, &payload) {
+                log::warn!("assets: failed to publish '{topic}': {e}");
+            }
+        }
+    }
+
+    #[cfg(feature = "runtime")]
+    fn apply_asset_config(&mut self) {
+        let mut changed = false;
+        self.asset_config.drain(|_| changed = true);
+        if !changed {
+            return;
+        }
+
+        let Some(t) =
+            config_api().get::<crate::assets::AssetTunables>(crate::assets::ASSETS_CONFIG_SECTION)
+        else {
+            return;
+        };
+        if let Some(am) = self.resources.get_mut::<crate::assets::AssetManager>() {
+            am.set_budget(t.pump_steps);
+        }
+    }
+
     #[inline]
     fn is_exit_requested(&self) -> bool {
         self.exit_requested || self.shutdown.is_requested()
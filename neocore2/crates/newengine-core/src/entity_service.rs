@@ -0,0 +1,152 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use crate::plugins::host_api;
+use crate::reflect::{component_list, set_entity_field, FieldKind, FieldValue};
+use abi_stable::std_types::{RResult, RString};
+use newengine_plugin_api::{
+    guarded_service, Blob, CapabilityId, MethodName, ServiceV1, ServiceV1Dyn,
+};
+use serde::Serialize;
+use serde_json::json;
+
+pub const ENTITY_SERVICE_ID: &str = "engine.entity";
+
+pub mod method {
+    pub const SET: &str = "entity.set";
+    pub const COMPONENTS_JSON: &str = "entity.components_json";
+}
+
+#[derive(Debug, Serialize)]
+struct EntitySetResp {
+    ok: bool,
+    /// Value applied, after normalization (rotations).
+    value: Option<FieldValue>,
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct FieldDesc {
+    name: String,
+    kind: FieldKind,
+    min: Option<f32>,
+    max: Option<f32>,
+}
+
+#[derive(Debug, Serialize)]
+struct ComponentDescResp {
+    name: String,
+    #[serde(rename = "type")]
+    type_name: &'static str,
+    fields: Vec<FieldDesc>,
+}
+
+struct EntityService;
+
+impl EntityService {
+    /// Payload: `<id> <component.field> <value>`.
+    fn set(arg: &str) -> EntitySetResp {
+        let mut parts = arg.trim().splitn(3, char::is_whitespace);
+        let (id, path, value) = (parts.next(), parts.next(), parts.next());
+        let res = match (id.map(str::parse::<u32>), path, value) {
+            (Some(Ok(id)), Some(path), Some(value)) if !value.trim().is_empty() => {
+                set_entity_field(id, path, value)
+            }
+            (Some(Err(_)), _, _) => Err(format!("bad entity id: '{}'", id.unwrap_or_default())),
+            _ => Err(format!(
+                "usage: {} <id> <component.field> <value>",
+                method::SET
+            )),
+        };
+        match res {
+            Ok(value) => EntitySetResp {
+                ok: true,
+                value: Some(value),
+                error: None,
+            },
+            Err(e) => EntitySetResp {
+                ok: false,
+                value: None,
+                error: Some(e),
+            },
+        }
+    }
+
+    fn components() -> Vec<ComponentDescResp> {
+        component_list()
+            .iter()
+            .map(|c| ComponentDescResp {
+                name: c.name.clone(),
+                type_name: c.type_name,
+                fields: c
+                    .fields
+                    .iter()
+                    .map(|f| FieldDesc {
+                        name: f.name.clone(),
+                        kind: f.kind,
+                        min: f.range.map(|r| r.0),
+                        max: f.range.map(|r| r.1),
+                    })
+                    .collect(),
+            })
+            .collect()
+    }
+}
+
+impl ServiceV1 for EntityService {
+    fn id(&self) -> CapabilityId {
+        RString::from(ENTITY_SERVICE_ID)
+    }
+
+    fn describe(&self) -> RString {
+        let d = json!({
+          "id": ENTITY_SERVICE_ID,
+          "version": 1,
+          "methods": [
+            { "name": method::SET, "payload": "utf8 '<id> <component.field> <value>'", "returns": "json EntitySetResp" },
+            { "name": method::COMPONENTS_JSON, "payload": "empty", "returns": "json [ComponentDesc]" }
+          ],
+          "console": {
+            "commands": [
+              {
+                "name": "entity.set",
+                "help": "Set a component field of an entity (undoable); rotations take Euler degrees",
+                "usage": "entity.set <id> <component.field> <value>",
+                "kind": "service_call",
+                "service_id": ENTITY_SERVICE_ID,
+                "method": method::SET,
+                "payload": "raw"
+              },
+              {
+                "name": "entity.components",
+                "help": "List registered components and their fields",
+                "kind": "service_call",
+                "service_id": ENTITY_SERVICE_ID,
+                "method": method::COMPONENTS_JSON,
+                "payload": "empty"
+              }
+            ]
+          }
+        });
+
+        RString::from(d.to_string())
+    }
+
+    fn call(&self, method: MethodName, payload: Blob) -> RResult<Blob, RString> {
+        let m = method.to_string();
+        let arg = String::from_utf8_lossy(payload.as_slice());
+
+        let resp = match m.as_str() {
+            method::SET => serde_json::to_vec(&Self::set(&arg)),
+            method::COMPONENTS_JSON => serde_json::to_vec(&Self::components()),
+            _ => return RResult::RErr(RString::from(format!("unknown method: {m}"))),
+        };
+
+        RResult::ROk(Blob::from(resp.unwrap_or_default()))
+    }
+}
+
+pub fn register_entity_service() {
+    let dyn_svc: ServiceV1Dyn<'static> = guarded_service(EntityService);
+
+    let _ = host_api::host_register_service_impl(dyn_svc, false);
+}
@@ -32,6 +32,21 @@ pub enum ModuleStage {
     Shutdown,
 }
 
+impl ModuleStage {
+    #[inline]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ModuleStage::Init => "init",
+            ModuleStage::Start => "start",
+            ModuleStage::FixedUpdate => "fixed_update",
+            ModuleStage::Update => "update",
+            ModuleStage::Render => "render",
+            ModuleStage::ExternalEvent => "external_event",
+            ModuleStage::Shutdown => "shutdown",
+        }
+    }
+}
+
 impl EngineError {
     #[inline]
     pub fn other(msg: impl Into<String>) -> Self {
@@ -1,4 +1,5 @@
 use crate::error::EngineResult;
+use crate::topics::{TopicEvent, TopicPattern, TopicRouter, TopicSub};
 
 use crossbeam_channel::{Receiver, Sender, TrySendError};
 use std::any::{Any, TypeId};
@@ -13,6 +14,10 @@ use std::sync::{
 pub enum OverflowPolicy {
     /// If the queue is full, silently drop the newly published event.
     DropNewest,
+    /// If the queue is full, evict the oldest queued event to make room.
+    ///
+    /// Suits "latest state wins" streams where stale entries are worthless.
+    DropOldest,
     /// Block the publisher until the subscriber makes room.
     ///
     /// Use with care: it can deadlock if you publish from the same thread
@@ -29,6 +34,11 @@ impl Default for OverflowPolicy {
 
 /// Multicast event hub with typed subscriptions and optional filters.
 ///
+/// Two addressing schemes share the hub:
+/// - typed channels keyed by `TypeId` (`publish` / `subscribe`)
+/// - named topics carrying byte payloads (`publish_topic` / `subscribe_topic`); these are
+///   bridged with DLL plugin events (`emit_event_v1` / `subscribe_events_v1`)
+///
 /// Backpressure is supported via bounded subscriptions with explicit overflow policies.
 ///
 /// Optimized for cheap publish:
@@ -52,6 +62,7 @@ impl EventHub {
             inner: Arc::new(Inner {
                 next_id: AtomicU64::new(1),
                 chans: RwLock::new(HashMap::new()),
+                topics: Arc::new(TopicRouter::new()),
             }),
         }
     }
@@ -132,6 +143,7 @@ impl EventHub {
                 id,
                 tx,
                 overflow,
+                rx: rx.clone(),
                 dropped: dropped.clone(),
                 filter: Some(filter_arc),
             },
@@ -148,6 +160,92 @@ impl EventHub {
             _phantom: std::marker::PhantomData,
         }
     }
+
+    /// Publish a payload on a named topic.
+    ///
+    /// Host topic subscribers and plugin event sinks both receive it.
+    pub fn publish_topic(&self, topic: &str, payload: impl Into<Arc<[u8]>>) -> EngineResult<()> {
+        let ev = TopicEvent::new(topic, payload);
+        self.inner.topics.deliver(&ev);
+        crate::plugins::host_context::emit_to_plugin_sinks(ev.topic(), ev.payload());
+        Ok(())
+    }
+
+    /// Publish a JSON-encoded payload on a named topic.
+    #[inline]
+    pub fn publish_topic_json<T>(&self, topic: &str, value: &T) -> EngineResult<()>
+    where
+        T: serde::Serialize + ?Sized,
+    {
+        let bytes = serde_json::to_vec(value).map_err(|e| {
+            crate::error::EngineError::other(format!("topic '{topic}': encode failed: {e}"))
+        })?;
+        self.publish_topic(topic, bytes)
+    }
+
+    /// Subscribe to named topics (`"ui.changed"`, `"winit.*"`, `"*"`).
+    ///
+    /// Bounded with `OverflowPolicy::DropNewest`, like `subscribe`.
+    #[inline]
+    pub fn subscribe_topic(&self, pattern: impl Into<TopicPattern>) -> TopicSub {
+        self.subscribe_topic_bounded(pattern, 1024, OverflowPolicy::DropNewest)
+    }
+
+    #[inline]
+    pub fn subscribe_topic_bounded(
+        &self,
+        pattern: impl Into<TopicPattern>,
+        capacity: usize,
+        overflow: OverflowPolicy,
+    ) -> TopicSub {
+        self.inner
+            .topics
+            .subscribe(pattern.into(), capacity, overflow)
+    }
+
+    /// Weak handle used by the plugin host to deliver plugin-emitted events.
+    #[inline]
+    pub(crate) fn topic_router(&self) -> Weak<TopicRouter> {
+        Arc::downgrade(&self.inner.topics)
+    }
+}
+
+/// Queues `msg` according to `overflow`.
+///
+/// Returns `false` if the receiving side is gone.
+pub(crate) fn offer<M>(
+    tx: &Sender<M>,
+    rx: &Receiver<M>,
+    overflow: OverflowPolicy,
+    dropped: &AtomicU64,
+    msg: M,
+) -> bool {
+    match overflow {
+        OverflowPolicy::Block => tx.send(msg).is_ok(),
+        OverflowPolicy::DropNewest => match tx.try_send(msg) {
+            Ok(_) => true,
+            Err(TrySendError::Full(_)) => {
+                dropped.fetch_add(1, Ordering::Relaxed);
+                true
+            }
+            Err(TrySendError::Disconnected(_)) => false,
+        },
+        OverflowPolicy::DropOldest => {
+            let mut msg = msg;
+            loop {
+                match tx.try_send(msg) {
+                    Ok(_) => return true,
+                    Err(TrySendError::Full(m)) => {
+                        if rx.try_recv().is_ok() {
+                            dropped.fetch_add(1, Ordering::Relaxed);
+                        }
+                        msg = m;
+                    }
+                    Err(TrySendError::Disconnected(_)) => return false,
+                }
+            }
+        }
+    }
 }
 
 /// Typed subscription handle.
@@ -224,6 +322,7 @@ struct SubInner {
 struct Inner {
     next_id: AtomicU64,
     chans: RwLock<HashMap<TypeId, Arc<Vec<Subscriber>>>>,
+    topics: Arc<TopicRouter>,
 }
 
 impl Inner {
@@ -275,21 +374,8 @@ impl Inner {
                 }
             }
 
-            match s.overflow {
-                OverflowPolicy::Block => {
-                    if s.tx.send(ev.clone()).is_err() {
-                        failed.insert(s.id);
-                    }
-                }
-                OverflowPolicy::DropNewest => match s.tx.try_send(ev.clone()) {
-                    Ok(_) => {}
-                    Err(TrySendError::Full(_)) => {
-                        s.dropped.fetch_add(1, Ordering::Relaxed);
-                    }
-                    Err(TrySendError::Disconnected(_)) => {
-                        failed.insert(s.id);
-                    }
-                },
+            if !offer(&s.tx, &s.rx, s.overflow, &s.dropped, ev.clone()) {
+                failed.insert(s.id);
             }
         }
 
@@ -323,6 +409,8 @@ impl Inner {
 struct Subscriber {
     id: u64,
     tx: Sender<Arc<dyn Any + Send + Sync>>,
+    /// Kept for `DropOldest` eviction.
+    rx: Receiver<Arc<dyn Any + Send + Sync>>,
     overflow: OverflowPolicy,
     dropped: Arc<AtomicU64>,
     filter: Option<Arc<dyn Fn(&Arc<dyn Any + Send + Sync>) -> bool + Send + Sync>>,
@@ -0,0 +1,95 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use crate::plugins::{event_router_stats, host_api};
+use abi_stable::std_types::{RResult, RString};
+use newengine_plugin_api::{
+    guarded_service, Blob, CapabilityId, MethodName, ServiceV1, ServiceV1Dyn,
+};
+use serde_json::json;
+
+pub const EVENTS_SERVICE_ID: &str = "engine.events";
+
+pub mod method {
+    pub const STATS_JSON: &str = "events.stats_json";
+    pub const EMIT: &str = "events.emit";
+}
+
+struct EventsService;
+
+impl ServiceV1 for EventsService {
+    fn id(&self) -> CapabilityId {
+        RString::from(EVENTS_SERVICE_ID)
+    }
+
+    fn describe(&self) -> RString {
+        let d = json!({
+          "id": EVENTS_SERVICE_ID,
+          "version": 1,
+          "methods": [
+            { "name": method::STATS_JSON, "payload": "empty", "returns": "json EventRouterStats" },
+            { "name": method::EMIT, "payload": "utf8 '<topic> [payload]'", "returns": "json {ok, error?}" }
+          ],
+          "console": {
+            "commands": [
+              {
+                "name": "events.stats",
+                "help": "Plugin event router stats (queue, sinks, per-topic counters)",
+                "kind": "service_call",
+                "service_id": EVENTS_SERVICE_ID,
+                "method": method::STATS_JSON,
+                "payload": "empty"
+              },
+              {
+                "name": "events.emit",
+                "help": "Queue an event: events.emit <topic> [payload]",
+                "usage": "events.emit <topic> [payload]",
+                "kind": "service_call",
+                "service_id": EVENTS_SERVICE_ID,
+                "method": method::EMIT,
+                "payload": "raw"
+              }
+            ]
+          }
+        });
+
+        RString::from(d.to_string())
+    }
+
+    fn call(&self, method: MethodName, payload: Blob) -> RResult<Blob, RString> {
+        let m = method.to_string();
+
+        let resp = match m.as_str() {
+            method::STATS_JSON => match event_router_stats() {
+                Some(stats) => serde_json::to_vec(&stats),
+                None => return RResult::RErr(RString::from("event router mutex poisoned")),
+            },
+            method::EMIT => {
+                let line = String::from_utf8_lossy(payload.as_slice());
+                let line = line.trim();
+                let (topic, body) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+
+                let r = if topic.is_empty() {
+                    Err("empty topic".to_string())
+                } else {
+                    crate::plugins::host_context::emit_plugin_event(
+                        RString::from(topic),
+                        Blob::from(body.trim().as_bytes().to_vec()),
+                    )
+                };
+                serde_json::to_vec(&match r {
+                    Ok(()) => json!({ "ok": true }),
+                    Err(e) => json!({ "ok": false, "error": e }),
+                })
+            }
+            _ => return RResult::RErr(RString::from(format!("unknown method: {m}"))),
+        };
+
+        RResult::ROk(Blob::from(resp.unwrap_or_default()))
+    }
+}
+
+pub fn register_events_service() {
+    let dyn_svc: ServiceV1Dyn<'static> = guarded_service(EventsService);
+
+    let _ = host_api::host_register_service_impl(dyn_svc, false);
+}
@@ -0,0 +1,539 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use crate::plugins::paths::{default_plugins_dir, is_dynamic_lib};
+use crate::plugins::PLUGINS_MANIFEST_FILE;
+use crate::project::{BuildProfile, Project};
+use crate::startup::{setting_text, StartupLoader};
+use newengine_assets::{
+    AssetKey, AssetSource, AssetState, AssetStore, BlobImporterDispatch, DerivedDataCache,
+    FileSystemSource, LoadPriority, PackWriter, PumpBudget, ASSET_META_EXT, ENGINE_MOUNT,
+    GAME_MOUNT, PACK_EXT,
+};
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+/// Written last into every export folder. Only folders holding one are replaced by the next
+/// export, so a mistyped `output` never deletes unrelated files.
+pub const EXPORT_MANIFEST_FILE: &str = "export.json";
+
+/// File stem of the game runtime, looked up next to the running executable.
+pub const RUNTIME_EXE_NAME: &str = "newengine-runtime";
+
+/// Layout of an export folder, relative to the game executable.
+const PACKS_DIR: &str = "packs";
+const PLUGINS_DIR: &str = "plugins";
+const IMPORTERS_DIR: &str = "importers";
+const CACHE_DIR: &str = "cache/assets";
+const CONFIG_FILE: &str = "config.json";
+
+/// Imports per pump while cooking; the cook runs on its own thread, so no frame budget.
+const COOK_PUMP_STEPS: u32 = 64;
+
+/// Failed imports listed in the error before it is cut short.
+const COOK_ERRORS_SHOWN: usize = 5;
+
+/// Result of a finished export, also written to [`EXPORT_MANIFEST_FILE`].
+#[derive(Debug, Clone, Serialize)]
+pub struct ExportReport {
+    pub project: String,
+    pub profile: String,
+    pub output: PathBuf,
+    pub executable: String,
+    /// Assets imported into the shipped cache.
+    pub cooked: usize,
+    /// Pack file names and entry counts.
+    pub packs: Vec<(String, usize)>,
+    pub pack_bytes: u64,
+    pub plugins: Vec<String>,
+    pub importers: Vec<String>,
+    pub duration_ms: u64,
+}
+
+/// Progress of the background export, for `project.export_status` and the editor.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum ExportStatus {
+    #[default]
+    Idle,
+    Running {
+        profile: String,
+        stage: String,
+    },
+    Done(ExportReport),
+    Failed {
+        profile: String,
+        error: String,
+    },
+}
+
+impl ExportStatus {
+    #[inline]
+    pub fn is_running(&self) -> bool {
+        matches!(self, Self::Running { .. })
+    }
+}
+
+static STATUS: Mutex<ExportStatus> = Mutex::new(ExportStatus::Idle);
+
+#[inline]
+pub fn export_status() -> ExportStatus {
+    STATUS.lock().map(|s| s.clone()).unwrap_or_default()
+}
+
+fn set_status(status: ExportStatus) {
+    if let Ok(mut g) = STATUS.lock() {
+        *g = status;
+    }
+}
+
+/// Starts exporting `profile` (the project default for `None`) on a background thread and
+/// returns the profile name. `importers` cook the assets; normally the live store's set.
+///
+/// One export runs at a time; poll [`export_status`] for the outcome.
+pub fn start_export(
+    project: Arc<Project>,
+    profile: Option<&str>,
+    importers: Vec<Arc<dyn BlobImporterDispatch>>,
+) -> Result<String, String> {
+    let name = profile_name(&project, profile)?;
+    {
+        let mut g = STATUS
+            .lock()
+            .map_err(|_| "export status poisoned".to_string())?;
+        if g.is_running() {
+            return Err("an export is already running".to_string());
+        }
+        *g = ExportStatus::Running {
+            profile: name.clone(),
+            stage: "starting".to_string(),
+        };
+    }
+
+    let profile = name.clone();
+    std::thread::Builder::new()
+        .name("project-export".to_string())
+        .spawn(move || {
+            let stage = |s: &str| {
+                set_status(ExportStatus::Running {
+                    profile: profile.clone(),
+                    stage: s.to_string(),
+                })
+            };
+            match export_project(&project, Some(&profile), &importers, &stage) {
+                Ok(report) => set_status(ExportStatus::Done(report)),
+                Err(error) => {
+                    log::warn!("export: failed profile='{profile}' err='{error}'");
+                    set_status(ExportStatus::Failed { profile, error });
+                }
+            }
+        })
+        .map_err(|e| {
+            set_status(ExportStatus::Idle);
+            format!("export thread failed to start: {e}")
+        })?;
+    Ok(name)
+}
+
+/// Name of `profile`, or of the project default; exporting needs one.
+fn profile_name(project: &Project, profile: Option<&str>) -> Result<String, String> {
+    match project.profile(profile)? {
+        Some((name, _)) => Ok(name.to_string()),
+        None => Err(format!(
+            "{}: no profile given and no default_profile set",
+            project.name()
+        )),
+    }
+}
+
+/// Builds a distributable folder for `profile`, blocking. `stage` is told what runs next.
+///
+/// Everything is written to a staging folder next to the output first, so a failed export
+/// leaves the previous one in place.
+pub fn export_project(
+    project: &Project,
+    profile: Option<&str>,
+    importers: &[Arc<dyn BlobImporterDispatch>],
+    stage: &dyn Fn(&str),
+) -> Result<ExportReport, String> {
+    let t0 = Instant::now();
+    let name = profile_name(project, profile)?;
+    let build = project
+        .profile(Some(&name))?
+        .map(|(_, p)| p.clone())
+        .unwrap_or_default();
+
+    let output = project.resolve(
+        build
+            .output
+            .clone()
+            .unwrap_or_else(|| format!("build/{name}")),
+    );
+    if output.exists() && !output.join(EXPORT_MANIFEST_FILE).is_file() {
+        return Err(format!(
+            "'{}' exists and is not an export ({EXPORT_MANIFEST_FILE} missing); not replacing it",
+            output.display()
+        ));
+    }
+    let staging = staging_dir(&output)?;
+    log::info!(
+        "export: start project='{}' profile='{name}' output='{}'",
+        project.name(),
+        output.display()
+    );
+
+    if staging.exists() {
+        std::fs::remove_dir_all(&staging).map_err(|e| io_err("clear", &staging, e))?;
+    }
+    create_dir(&staging)?;
+
+    let res = export_into(project, &name, &build, importers, &staging, stage);
+    let mut report = match res {
+        Ok(r) => r,
+        Err(e) => {
+            let _ = std::fs::remove_dir_all(&staging);
+            return Err(e);
+        }
+    };
+
+    stage("finishing");
+    report.output = output.clone();
+    report.duration_ms = t0.elapsed().as_millis() as u64;
+    let manifest = serde_json::to_string_pretty(&report).map_err(|e| e.to_string())?;
+    write_file(&staging.join(EXPORT_MANIFEST_FILE), manifest.as_bytes())?;
+    replace_dir(&staging, &output)?;
+
+    log::info!(
+        "export: done profile='{name}' output='{}' cooked={} pack_bytes={} ms={}",
+        output.display(),
+        report.cooked,
+        report.pack_bytes,
+        report.duration_ms
+    );
+    Ok(report)
+}
+
+fn export_into(
+    project: &Project,
+    name: &str,
+    build: &BuildProfile,
+    importers: &[Arc<dyn BlobImporterDispatch>],
+    staging: &Path,
+    stage: &dyn Fn(&str),
+) -> Result<ExportReport, String> {
+    let assets = &project.file().assets;
+    let root = |p: &Option<String>| {
+        p.as_deref()
+            .map(str::trim)
+            .filter(|p| !p.is_empty())
+            .map(|p| project.resolve(p))
+    };
+    // Mods stay loose: they are installed by players next to the game, not shipped in it.
+    let mut roots = vec![(
+        GAME_MOUNT,
+        root(&assets.game).unwrap_or_else(|| project.resolve("assets")),
+    )];
+    if let Some(engine) = root(&assets.engine) {
+        roots.push((ENGINE_MOUNT, engine));
+    }
+
+    stage("cooking assets");
+    let cooked = cook(&roots, importers, &staging.join(CACHE_DIR))?;
+
+    // After cooking: the first import writes missing `.meta` sidecars, which must ship so the
+    // runtime computes the same cache keys.
+    stage("writing packs");
+    let packs_dir = staging.join(PACKS_DIR);
+    create_dir(&packs_dir)?;
+    let mut packs = Vec::new();
+    let mut pack_bytes = 0u64;
+    for (mount, dir) in &roots {
+        let file = format!("{mount}.{PACK_EXT}");
+        let mut writer = PackWriter::create(packs_dir.join(&file)).map_err(|e| e.to_string())?;
+        let source = FileSystemSource::new(dir);
+        let mut files = source.list();
+        files.sort();
+        for path in &files {
+            let bytes = source.read(path).map_err(|e| e.to_string())?;
+            writer.add(path, &bytes).map_err(|e| e.to_string())?;
+        }
+        let stats = writer.finish().map_err(|e| e.to_string())?;
+        pack_bytes += stats.bytes;
+        packs.push((file, stats.entries));
+    }
+
+    stage("copying runtime");
+    let exe_dir = default_plugins_dir().map_err(|e| e.to_string())?;
+    let runtime = exe_dir.join(format!(
+        "{RUNTIME_EXE_NAME}{}",
+        std::env::consts::EXE_SUFFIX
+    ));
+    if !runtime.is_file() {
+        return Err(format!(
+            "runtime executable not found: '{}' (build the {RUNTIME_EXE_NAME} app)",
+            runtime.display()
+        ));
+    }
+    let executable = format!(
+        "{}{}",
+        executable_name(build.executable.as_deref().unwrap_or(project.name())),
+        std::env::consts::EXE_SUFFIX
+    );
+    copy_file(&runtime, &staging.join(&executable))?;
+
+    stage("copying plugins");
+    let set = project.plugin_set(Some(build));
+    let plugins_src = match set.and_then(|s| s.dir.as_deref()) {
+        Some(dir) => project.resolve(dir),
+        None => exe_dir.clone(),
+    };
+    let disabled: Vec<String> = set.map(|s| s.disabled.clone()).unwrap_or_default();
+    let plugins = copy_plugins(&plugins_src, &disabled, &staging.join(PLUGINS_DIR))?;
+    let importers_copied = copy_libs(&exe_dir.join(IMPORTERS_DIR), &staging.join(IMPORTERS_DIR))?;
+
+    stage("writing config");
+    let config = startup_config(project, build, &packs, &disabled)?;
+    let text = serde_json::to_string_pretty(&config).map_err(|e| e.to_string())?;
+    write_file(&staging.join(CONFIG_FILE), text.as_bytes())?;
+
+    Ok(ExportReport {
+        project: project.name().to_string(),
+        profile: name.to_string(),
+        output: PathBuf::new(),
+        executable,
+        cooked,
+        packs,
+        pack_bytes,
+        plugins,
+        importers: importers_copied,
+        duration_ms: 0,
+    })
+}
+
+/// Imports every asset that has an importer into a fresh cache at `cache_dir`, through a store
+/// of its own so the running editor's state is untouched. Any failed import fails the export.
+fn cook(
+    roots: &[(&str, PathBuf)],
+    importers: &[Arc<dyn BlobImporterDispatch>],
+    cache_dir: &Path,
+) -> Result<usize, String> {
+    let store = AssetStore::new();
+    for (mount, dir) in roots {
+        store
+            .add_source_to(mount, Arc::new(FileSystemSource::new(dir)))
+            .map_err(|e| e.to_string())?;
+    }
+    store.set_cache(Some(Arc::new(DerivedDataCache::new(cache_dir))));
+
+    let mut exts = HashSet::new();
+    for imp in importers {
+        exts.extend(imp.extensions().iter().map(|e| normalize_ext(e)));
+        store.add_importer(imp.clone());
+    }
+
+    let mut keys = Vec::new();
+    for (mount, dir) in roots {
+        let mut files = FileSystemSource::new(dir).list();
+        files.sort();
+        for path in files {
+            let Some(ext) = path.extension().and_then(|e| e.to_str()).map(normalize_ext) else {
+                continue;
+            };
+            if ext == ASSET_META_EXT || !exts.contains(&ext) {
+                continue;
+            }
+            let logical = path
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            keys.push(AssetKey::new(format!("{mount}://{logical}"), 0));
+        }
+    }
+
+    let group = store.load_group("export", keys, LoadPriority::Normal);
+    while !group.is_done() {
+        store.pump(PumpBudget::steps(COOK_PUMP_STEPS));
+    }
+
+    let (ready, failed, _) = group.outcome();
+    if failed > 0 {
+        let errors: Vec<String> = group
+            .handles()
+            .iter()
+            .filter_map(|h| match store.state(h.id()) {
+                AssetState::Failed(e) => Some(e.to_string()),
+                _ => None,
+            })
+            .take(COOK_ERRORS_SHOWN)
+            .collect();
+        return Err(format!(
+            "{failed} asset(s) failed to import: {}",
+            errors.join("; ")
+        ));
+    }
+    Ok(ready)
+}
+
+/// The exported game's `config.json`: packs instead of loose files, the shipped cache and
+/// plugins, then the profile settings on top.
+fn startup_config(
+    project: &Project,
+    build: &BuildProfile,
+    packs: &[(String, usize)],
+    disabled: &[String],
+) -> Result<Value, String> {
+    let packs: Vec<String> = packs
+        .iter()
+        .map(|(file, _)| format!("{PACKS_DIR}/{file}"))
+        .collect();
+    let mut config = json!({
+        "window": { "title": project.name() },
+        "engine": {
+            "modules_dir": PLUGINS_DIR,
+            "disabled_plugins": disabled,
+            "asset_filesystem_source": false,
+            "asset_packs": packs,
+            "asset_cache_dir": CACHE_DIR,
+            "startup_scene": project.startup_scene(Some(build)),
+        }
+    });
+
+    for (key, value) in &build.settings {
+        let value = StartupLoader::override_value(key, &setting_text(value))
+            .map_err(|e| format!("{}: setting '{key}': {e}", project.name()))?;
+        let (section, field) = key.split_once('.').unwrap_or((key, ""));
+        let Some(root) = config.as_object_mut() else {
+            continue;
+        };
+        let table = root
+            .entry(section)
+            .or_insert_with(|| Value::Object(Map::new()));
+        if let Some(table) = table.as_object_mut() {
+            table.insert(field.to_string(), value);
+        }
+    }
+    Ok(config)
+}
+
+/// Copies the plugin libraries of `src` except `disabled` ones, plus its `plugins.json`.
+///
+/// Plugin ids are only known once a library is opened, so they are matched against file names
+/// the way cargo names them (`newengine-modules-x` builds `libnewengine_modules_x.so`).
+fn copy_plugins(src: &Path, disabled: &[String], dst: &Path) -> Result<Vec<String>, String> {
+    let disabled: HashSet<String> = disabled.iter().map(|id| lib_stem(id)).collect();
+    create_dir(dst)?;
+
+    let mut copied = Vec::new();
+    for path in lib_files(src)? {
+        let stem = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or_default();
+        if disabled.contains(&lib_stem(stem)) {
+            log::info!("export: skipped disabled plugin '{}'", path.display());
+            continue;
+        }
+        let file = path.file_name().unwrap_or_default();
+        copy_file(&path, &dst.join(file))?;
+        copied.push(file.to_string_lossy().into_owned());
+    }
+
+    let manifest = src.join(PLUGINS_MANIFEST_FILE);
+    if manifest.is_file() {
+        copy_file(&manifest, &dst.join(PLUGINS_MANIFEST_FILE))?;
+    }
+    Ok(copied)
+}
+
+/// Copies every library in `src` (if it exists) to `dst`.
+fn copy_libs(src: &Path, dst: &Path) -> Result<Vec<String>, String> {
+    if !src.is_dir() {
+        return Ok(Vec::new());
+    }
+    create_dir(dst)?;
+    let mut copied = Vec::new();
+    for path in lib_files(src)? {
+        let file = path.file_name().unwrap_or_default();
+        copy_file(&path, &dst.join(file))?;
+        copied.push(file.to_string_lossy().into_owned());
+    }
+    Ok(copied)
+}
+
+fn lib_files(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let rd = std::fs::read_dir(dir).map_err(|e| io_err("read", dir, e))?;
+    let mut out: Vec<PathBuf> = rd
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.is_file() && is_dynamic_lib(p))
+        .collect();
+    out.sort();
+    Ok(out)
+}
+
+/// `libnewengine_modules_x` and `newengine-modules-x` both become `newengine_modules_x`.
+fn lib_stem(name: &str) -> String {
+    let name = name.strip_prefix("lib").unwrap_or(name);
+    name.replace('-', "_").to_ascii_lowercase()
+}
+
+/// Project or profile name as a file name: spaces become `-`, other unsafe characters go.
+fn executable_name(name: &str) -> String {
+    let out: String = name
+        .trim()
+        .chars()
+        .filter_map(|c| match c {
+            ' ' => Some('-'),
+            c if c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.' => Some(c),
+            _ => None,
+        })
+        .collect();
+    match out.trim_matches('.') {
+        "" => "game".to_string(),
+        s => s.to_string(),
+    }
+}
+
+#[inline]
+fn normalize_ext(ext: &str) -> String {
+    ext.trim().trim_start_matches('.').to_ascii_lowercase()
+}
+
+/// `<output>.tmp` beside the output folder.
+fn staging_dir(output: &Path) -> Result<PathBuf, String> {
+    let name = output
+        .file_name()
+        .ok_or_else(|| format!("invalid export output '{}'", output.display()))?;
+    Ok(output.with_file_name(format!("{}.tmp", name.to_string_lossy())))
+}
+
+/// Moves `staging` to `output`, replacing an earlier export there.
+fn replace_dir(staging: &Path, output: &Path) -> Result<(), String> {
+    if output.exists() {
+        std::fs::remove_dir_all(output).map_err(|e| io_err("remove", output, e))?;
+    }
+    std::fs::rename(staging, output).map_err(|e| io_err("move", staging, e))
+}
+
+fn create_dir(dir: &Path) -> Result<(), String> {
+    std::fs::create_dir_all(dir).map_err(|e| io_err("create", dir, e))
+}
+
+fn copy_file(src: &Path, dst: &Path) -> Result<(), String> {
+    std::fs::copy(src, dst)
+        .map(|_| ())
+        .map_err(|e| io_err("copy", src, e))
+}
+
+fn write_file(path: &Path, bytes: &[u8]) -> Result<(), String> {
+    std::fs::write(path, bytes).map_err(|e| io_err("write", path, e))
+}
+
+#[inline]
+fn io_err(op: &str, path: &Path, e: std::io::Error) -> String {
+    format!("failed to {op} '{}': {e}", path.display())
+}
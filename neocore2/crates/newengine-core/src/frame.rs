@@ -3,7 +3,8 @@
 /// The engine emits two kinds of frames:
 ///
 /// - **Variable frame**: used for `update()` and `render()`.
-///   `dt` is the real (clamped) wall-clock delta.
+///   `dt` is the game-clock delta: the clamped wall-clock delta after time scale and pause
+///   (see [`crate::time`]); `real_dt` is the unscaled one, for UI and tools.
 ///
 /// - **Fixed subframe**: emitted for each `fixed_update()` step.
 ///   `dt == fixed_dt`, `fixed_alpha == 0.0`, and `fixed_step_index` indicates
@@ -19,6 +20,9 @@ pub struct Frame {
     /// Delta time for this frame. For fixed subframes this equals `fixed_dt`.
     pub dt: f32,
 
+    /// Clamped wall-clock delta of the variable frame, ignoring time scale and pause.
+    pub real_dt: f32,
+
     /// Fixed timestep size.
     pub fixed_dt: f32,
 
@@ -0,0 +1,373 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use crate::render::CapturedFrame;
+
+use crossbeam_channel::{Receiver, Sender};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Recordings are written below this directory (relative to the working directory).
+pub const RECORD_DIR: &str = "captures";
+/// Frames waiting for the writer; captures beyond this are dropped instead of stalling.
+pub const RECORD_QUEUE_DEFAULT: usize = 8;
+pub const RECORD_QUEUE_MAX: usize = 256;
+
+/// `render.record_start` request.
+#[derive(Debug, Clone, Deserialize)]
+pub struct RecordConfig {
+    /// Directory (image sequence) or file stem (ffmpeg) inside [`RECORD_DIR`]; timestamped
+    /// when empty.
+    #[serde(default)]
+    pub name: String,
+    /// Capture every Nth frame.
+    #[serde(default = "one")]
+    pub every: u32,
+    /// Pipe raw RGBA frames to an `ffmpeg` process instead of writing PNGs.
+    #[serde(default)]
+    pub ffmpeg: bool,
+    /// Frame rate written into the ffmpeg stream.
+    #[serde(default = "default_fps")]
+    pub fps: f32,
+    #[serde(default = "default_queue")]
+    pub queue: usize,
+}
+
+#[inline]
+fn one() -> u32 {
+    1
+}
+
+#[inline]
+fn default_fps() -> f32 {
+    30.0
+}
+
+#[inline]
+fn default_queue() -> usize {
+    RECORD_QUEUE_DEFAULT
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RecordStatus {
+    pub active: bool,
+    pub output: Option<String>,
+    pub every: u32,
+    /// Frames seen since the recording started.
+    pub frames: u64,
+    /// Frames the backend delivered.
+    pub captured: u64,
+    /// Frames the writer finished.
+    pub written: u64,
+    /// Frames dropped because the writer queue was full.
+    pub dropped: u64,
+    pub error: Option<String>,
+}
+
+/// Written by the writer thread, read by status queries.
+#[derive(Default)]
+struct Shared {
+    written: AtomicU64,
+    error: Mutex<Option<String>>,
+}
+
+impl Shared {
+    fn fail(&self, e: String) {
+        log::warn!("render.record: {e}");
+        if let Ok(mut g) = self.error.lock() {
+            g.get_or_insert(e);
+        }
+    }
+
+    fn error(&self) -> Option<String> {
+        self.error.lock().ok()?.clone()
+    }
+}
+
+struct Recorder {
+    every: u32,
+    output: PathBuf,
+    tx: Sender<CapturedFrame>,
+    shared: Arc<Shared>,
+    frames: u64,
+    captured: u64,
+    dropped: u64,
+    /// A capture was requested and has not arrived yet.
+    waiting: bool,
+}
+
+impl Recorder {
+    fn status(&self) -> RecordStatus {
+        RecordStatus {
+            active: true,
+            output: Some(self.output.display().to_string()),
+            every: self.every,
+            frames: self.frames,
+            captured: self.captured,
+            written: self.shared.written.load(Ordering::Relaxed),
+            dropped: self.dropped,
+            error: self.shared.error(),
+        }
+    }
+}
+
+/// Process-wide so the render service can drive it without an Engine handle.
+static RECORDER: Mutex<Option<Recorder>> = Mutex::new(None);
+
+#[inline]
+pub fn is_recording() -> bool {
+    RECORDER.lock().map(|g| g.is_some()).unwrap_or(false)
+}
+
+pub fn record_status() -> RecordStatus {
+    match RECORDER.lock() {
+        Ok(g) => g.as_ref().map(Recorder::status).unwrap_or_default(),
+        Err(_) => RecordStatus::default(),
+    }
+}
+
+/// Starts a recording and returns where it is written. Frames are requested from the render
+/// backend by [`crate::render_service::pump_captures`].
+pub fn start_recording(cfg: RecordConfig) -> Result<String, String> {
+    let mut g = RECORDER
+        .lock()
+        .map_err(|_| "recorder lock poisoned".to_string())?;
+    if g.is_some() {
+        return Err("already recording; stop it with render.record_stop".to_string());
+    }
+    if cfg.every == 0 {
+        return Err("every must be at least 1".to_string());
+    }
+    if cfg.fps.is_nan() || cfg.fps <= 0.0 {
+        return Err("fps must be positive".to_string());
+    }
+
+    let name = if cfg.name.is_empty() {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        format!("rec-{}", now.as_secs())
+    } else {
+        cfg.name.clone()
+    };
+    let name_ok = name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.')
+        && !name.starts_with('.');
+    if !name_ok {
+        return Err(format!(
+            "'{name}': name may only use letters, digits, '_', '-', '.'"
+        ));
+    }
+
+    let sink = if cfg.ffmpeg {
+        Sink::Ffmpeg {
+            path: Path::new(RECORD_DIR).join(format!("{name}.mp4")),
+            fps: cfg.fps,
+            child: None,
+        }
+    } else {
+        Sink::Images {
+            dir: Path::new(RECORD_DIR).join(&name),
+            next: 0,
+        }
+    };
+    let output = sink.path().to_path_buf();
+    let dir = match &sink {
+        Sink::Images { dir, .. } => dir.as_path(),
+        Sink::Ffmpeg { .. } => Path::new(RECORD_DIR),
+    };
+    std::fs::create_dir_all(dir).map_err(|e| format!("{}: {e}", dir.display()))?;
+
+    let (tx, rx) = crossbeam_channel::bounded(cfg.queue.clamp(1, RECORD_QUEUE_MAX));
+    let shared = Arc::new(Shared::default());
+    let thread_shared = shared.clone();
+    std::thread::Builder::new()
+        .name("frame-record".to_string())
+        .spawn(move || writer(rx, sink, &thread_shared))
+        .map_err(|e| format!("cannot start writer thread: {e}"))?;
+
+    log::info!(
+        "render.record: recording every {} frame(s) to {}",
+        cfg.every,
+        output.display()
+    );
+    *g = Some(Recorder {
+        every: cfg.every,
+        output: output.clone(),
+        tx,
+        shared,
+        frames: 0,
+        captured: 0,
+        dropped: 0,
+        waiting: false,
+    });
+    Ok(output.display().to_string())
+}
+
+/// Stops requesting frames. Queued frames are still written; the writer logs when done.
+pub fn stop_recording() -> Result<RecordStatus, String> {
+    let rec = RECORDER
+        .lock()
+        .map_err(|_| "recorder lock poisoned".to_string())?
+        .take()
+        .ok_or_else(|| "not recording".to_string())?;
+
+    let mut status = rec.status();
+    status.active = false;
+    Ok(status)
+}
+
+/// Feeds one frame tick: `frame` is what the backend captured since the last call, if any.
+/// Returns whether a capture should be requested now. Never blocks: frames that do not fit
+/// into the writer queue are dropped.
+pub(crate) fn record_frame(frame: Option<&CapturedFrame>) -> bool {
+    let Ok(mut g) = RECORDER.lock() else {
+        return false;
+    };
+    let Some(rec) = g.as_mut() else {
+        return false;
+    };
+
+    if let Some(frame) = frame {
+        rec.waiting = false;
+        rec.captured += 1;
+        // Full queue or a writer that gave up (its error is in the status): drop the frame.
+        if rec.tx.try_send(frame.clone()).is_err() {
+            rec.dropped += 1;
+        }
+    }
+
+    let due = rec.frames % rec.every as u64 == 0;
+    rec.frames += 1;
+    let writer_ok = rec.shared.error().is_none();
+    if due && writer_ok && !rec.waiting && !rec.tx.is_full() {
+        rec.waiting = true;
+        return true;
+    }
+    false
+}
+
+/// Drops the recording after the backend refused to capture.
+pub(crate) fn abort_recording(reason: &str) {
+    if let Ok(mut g) = RECORDER.lock() {
+        if g.take().is_some() {
+            log::warn!("render.record: stopped: {reason}");
+        }
+    }
+}
+
+enum Sink {
+    Images {
+        dir: PathBuf,
+        next: u64,
+    },
+    Ffmpeg {
+        path: PathBuf,
+        fps: f32,
+        /// Started with the first frame, once the size is known.
+        child: Option<(Child, [u32; 2])>,
+    },
+}
+
+impl Sink {
+    fn path(&self) -> &Path {
+        match self {
+            Sink::Images { dir, .. } => dir,
+            Sink::Ffmpeg { path, .. } => path,
+        }
+    }
+
+    fn write(&mut self, frame: &CapturedFrame) -> Result<(), String> {
+        match self {
+            Sink::Images { dir, next } => {
+                *next += 1;
+                let path = dir.join(format!("frame_{next:06}.png"));
+                crate::render_service::write_png(&path, frame)
+            }
+            Sink::Ffmpeg { path, fps, child } => {
+                let size = [frame.width, frame.height];
+                if child.is_none() {
+                    *child = Some((spawn_ffmpeg(path, size, *fps)?, size));
+                }
+                let Some((proc, stream_size)) = child.as_mut() else {
+                    return Ok(());
+                };
+                if *stream_size != size {
+                    // Raw video has a fixed size; frames after a resize are skipped.
+                    return Ok(());
+                }
+                let stdin = proc
+                    .stdin
+                    .as_mut()
+                    .ok_or_else(|| "ffmpeg: stdin closed".to_string())?;
+                stdin
+                    .write_all(&frame.rgba8)
+                    .map_err(|e| format!("ffmpeg: {e}"))
+            }
+        }
+    }
+
+    fn finish(self) -> Result<(), String> {
+        if let Sink::Ffmpeg {
+            child: Some((mut proc, _)),
+            ..
+        } = self
+        {
+            drop(proc.stdin.take());
+            let status = proc.wait().map_err(|e| format!("ffmpeg: {e}"))?;
+            if !status.success() {
+                return Err(format!("ffmpeg exited with {status}"));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn spawn_ffmpeg(path: &Path, size: [u32; 2], fps: f32) -> Result<Child, String> {
+    Command::new("ffmpeg")
+        .args(["-hide_banner", "-loglevel", "error", "-y"])
+        .args(["-f", "rawvideo", "-pix_fmt", "rgba"])
+        .arg("-s")
+        .arg(format!("{}x{}", size[0], size[1]))
+        .arg("-r")
+        .arg(fps.to_string())
+        .args(["-i", "-", "-pix_fmt", "yuv420p"])
+        .arg(path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("cannot run ffmpeg: {e}"))
+}
+
+fn writer(rx: Receiver<CapturedFrame>, mut sink: Sink, shared: &Shared) {
+    let mut failed = false;
+    // Ends when the recording is stopped and the queue is drained.
+    for frame in rx {
+        if failed {
+            continue;
+        }
+        match sink.write(&frame) {
+            Ok(()) => {
+                shared.written.fetch_add(1, Ordering::Relaxed);
+            }
+            Err(e) => {
+                shared.fail(e);
+                failed = true;
+            }
+        }
+    }
+
+    let path = sink.path().display().to_string();
+    if let Err(e) = sink.finish() {
+        shared.fail(e);
+    }
+    log::info!(
+        "render.record: finished {} frame(s) -> {path}",
+        shared.written.load(Ordering::Relaxed)
+    );
+}
@@ -0,0 +1,172 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use crate::engine::Engine;
+use crate::error::{EngineError, EngineResult};
+use crate::frame::Frame;
+use crate::render::NullRenderModule;
+
+use std::time::{Duration, Instant};
+
+/// Why a headless run stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HeadlessExit {
+    /// A module, the console (`quit`) or the shutdown token requested exit.
+    Requested,
+    /// The configured frame budget was reached.
+    FrameLimit,
+}
+
+/// Summary returned by [`HeadlessRunner::run`].
+#[derive(Debug, Clone, Copy)]
+pub struct HeadlessReport {
+    pub exit: HeadlessExit,
+    pub frames: u64,
+    pub fixed_ticks: u64,
+    pub elapsed: Duration,
+}
+
+/// Manual tick driver for engines without a window (tests, CI tools, asset cooking).
+///
+/// Counterpart of `run_winit_app`: registers modules through `setup`, starts the engine, then
+/// steps it until exit or the frame limit. With [`EngineConfig::headless`](crate::EngineConfig)
+/// every frame advances by exactly one fixed step, so runs are reproducible.
+pub struct HeadlessRunner<E: Send + 'static> {
+    engine: Engine<E>,
+    max_frames: Option<u64>,
+    null_render: Option<(u32, u32)>,
+    started: bool,
+    frames: u64,
+    fixed_ticks: u64,
+}
+
+impl<E: Send + 'static> HeadlessRunner<E> {
+    pub fn new(engine: Engine<E>) -> Self {
+        if !engine.is_headless() {
+            log::warn!("headless: engine config is not headless; frame dt follows wall-clock time");
+        }
+
+        Self {
+            engine,
+            max_frames: None,
+            null_render: None,
+            started: false,
+            frames: 0,
+            fixed_ticks: 0,
+        }
+    }
+
+    /// Stops after `frames` frames. `None` runs until exit is requested.
+    #[inline]
+    pub fn with_max_frames(mut self, frames: Option<u64>) -> Self {
+        self.max_frames = frames;
+        self
+    }
+
+    /// Registers [`NullRenderModule`] on start so modules requiring the render API still run.
+    #[inline]
+    pub fn with_null_render(mut self, width: u32, height: u32) -> Self {
+        self.null_render = Some((width, height));
+        self
+    }
+
+    #[inline]
+    pub fn engine(&self) -> &Engine<E> {
+        &self.engine
+    }
+
+    #[inline]
+    pub fn engine_mut(&mut self) -> &mut Engine<E> {
+        &mut self.engine
+    }
+
+    #[inline]
+    pub fn frames(&self) -> u64 {
+        self.frames
+    }
+
+    /// Initializes modules and plugins. Called implicitly by [`Self::tick`] and [`Self::run`].
+    pub fn start(&mut self) -> EngineResult<()> {
+        if self.started {
+            return Ok(());
+        }
+
+        if let Some((w, h)) = self.null_render.take() {
+            self.engine
+                .register_module(Box::new(NullRenderModule::new(w, h)))?;
+        }
+
+        self.engine.start()?;
+        self.started = true;
+        log::info!("headless: started max_frames={:?}", self.max_frames);
+        Ok(())
+    }
+
+    /// Runs a single frame. Returns `EngineError::ExitRequested` once exit was requested.
+    pub fn tick(&mut self) -> EngineResult<Frame> {
+        self.start()?;
+
+        let frame = self.engine.step_frame()?;
+        self.frames += 1;
+        self.fixed_ticks = frame.fixed_tick;
+        Ok(frame)
+    }
+
+    /// Steps until exit or the frame limit, then shuts the engine down.
+    pub fn run(mut self) -> EngineResult<HeadlessReport> {
+        self.run_with(|_, _| Ok(()))
+    }
+
+    /// Like [`Self::run`], calling `after_frame` after every frame (assertions, cooking progress).
+    pub fn run_with<F>(&mut self, mut after_frame: F) -> EngineResult<HeadlessReport>
+    where
+        F: FnMut(&mut Engine<E>, &Frame) -> EngineResult<()>,
+    {
+        let t0 = Instant::now();
+
+        let result = self.run_loop(&mut after_frame);
+
+        if let Err(e) = self.engine.shutdown() {
+            log::error!("engine.shutdown failed: {e}");
+        }
+
+        let exit = result?;
+        let report = HeadlessReport {
+            exit,
+            frames: self.frames,
+            fixed_ticks: self.fixed_ticks,
+            elapsed: t0.elapsed(),
+        };
+
+        log::info!(
+            "headless: finished exit={:?} frames={} fixed_ticks={} elapsed_ms={}",
+            report.exit,
+            report.frames,
+            report.fixed_ticks,
+            report.elapsed.as_millis()
+        );
+        Ok(report)
+    }
+
+    fn run_loop<F>(&mut self, after_frame: &mut F) -> EngineResult<HeadlessExit>
+    where
+        F: FnMut(&mut Engine<E>, &Frame) -> EngineResult<()>,
+    {
+        loop {
+            if self.max_frames.is_some_and(|max| self.frames >= max) {
+                return Ok(HeadlessExit::FrameLimit);
+            }
+
+            let frame = match self.tick() {
+                Ok(f) => f,
+                Err(EngineError::ExitRequested) => return Ok(HeadlessExit::Requested),
+                Err(e) => return Err(e),
+            };
+
+            match after_frame(&mut self.engine, &frame) {
+                Ok(()) => {}
+                Err(EngineError::ExitRequested) => return Ok(HeadlessExit::Requested),
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use raw_window_handle::{RawDisplayHandle, RawWindowHandle};
+use std::path::PathBuf;
 
 #[derive(Debug, Clone)]
 pub enum HostEvent {
@@ -7,7 +8,7 @@ pub enum HostEvent {
     Text(TextHostEvent),
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub enum WindowHostEvent {
     /// Window became available (handles are provided via Resources, not events).
     Ready {
@@ -19,7 +20,18 @@ pub enum WindowHostEvent {
         height: u32,
     },
     Focused(bool),
+    /// DPI scale changed (window moved to another monitor or OS setting changed).
+    /// A `Resized` event with the new physical size follows.
+    ScaleFactorChanged {
+        scale_factor: f64,
+    },
     CloseRequested,
+    /// A file is dragged over the window (sent once per file).
+    FileHovered(PathBuf),
+    /// The drag left the window or was cancelled without dropping.
+    FileHoverCancelled,
+    /// A file was dropped onto the window (sent once per file).
+    FileDropped(PathBuf),
 }
 
 #[derive(Debug, Clone, Copy)]
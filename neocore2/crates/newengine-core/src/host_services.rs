@@ -1,7 +1,7 @@
 #![forbid(unsafe_op_in_unsafe_fn)]
 
 use abi_stable::std_types::{RResult, RString};
-use newengine_plugin_api::{Blob, CapabilityId, MethodName};
+use newengine_plugin_api::{Blob, CapabilityId, MethodName, ServiceV1Dyn};
 
 use crate::plugins::host_api;
 use crate::plugins::host_context;
@@ -18,6 +18,31 @@ pub fn call_service_v1(capability_id: &str, method: &str, payload: &[u8]) -> Res
     }
 }
 
+/// Registers an in-process (host side) service, e.g. from an engine module crate.
+///
+/// The service is visible to plugins and the console exactly like plugin-provided services.
+#[inline]
+pub fn register_service_v1(svc: ServiceV1Dyn<'static>) -> Result<(), String> {
+    match host_api::host_register_service_impl(svc, false) {
+        RResult::ROk(()) => Ok(()),
+        RResult::RErr(e) => Err(e.to_string()),
+    }
+}
+
+/// Removes a service registered via [`register_service_v1`]. Returns false if it was absent.
+#[inline]
+pub fn unregister_service_v1(service_id: &str) -> bool {
+    let c = host_context::ctx();
+    let Ok(mut g) = c.services.lock() else {
+        return false;
+    };
+    let removed = g.remove(service_id).is_some();
+    if removed {
+        host_context::bump_services_generation();
+    }
+    removed
+}
+
 #[inline]
 pub fn list_service_ids() -> Vec<String> {
     let c = host_context::ctx();
@@ -35,6 +60,6 @@ pub fn list_service_ids() -> Vec<String> {
 pub fn describe_service(service_id: &str) -> Option<String> {
     let c = host_context::ctx();
     let g = c.services.lock().ok()?;
-    let svc = g.get(service_id)?.clone();
+    let (_, svc) = host_context::resolve_service(&g, service_id).ok()?;
     Some(svc.describe_json.to_string())
 }
\ No newline at end of file
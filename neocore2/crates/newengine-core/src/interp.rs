@@ -0,0 +1,165 @@
+use serde::{Deserialize, Serialize};
+
+/// Values that can be blended between two fixed ticks.
+pub trait Lerp: Sized {
+    /// `self` at `t == 0`, `other` at `t == 1`.
+    fn lerp(&self, other: &Self, t: f32) -> Self;
+}
+
+impl Lerp for f32 {
+    #[inline]
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        self + (other - self) * t
+    }
+}
+
+impl<const N: usize> Lerp for [f32; N] {
+    #[inline]
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        std::array::from_fn(|i| self[i].lerp(&other[i], t))
+    }
+}
+
+/// Translation, rotation (unit quaternion `[x, y, z, w]`) and scale.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Transform {
+    pub translation: [f32; 3],
+    pub rotation: [f32; 4],
+    pub scale: [f32; 3],
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Transform {
+    pub const IDENTITY: Self = Self {
+        translation: [0.0; 3],
+        rotation: [0.0, 0.0, 0.0, 1.0],
+        scale: [1.0; 3],
+    };
+
+    #[inline]
+    pub fn from_translation(translation: [f32; 3]) -> Self {
+        Self {
+            translation,
+            ..Self::IDENTITY
+        }
+    }
+
+    /// Column-major `T * R * S`.
+    pub fn to_matrix(&self) -> [f32; 16] {
+        let [x, y, z, w] = self.rotation;
+        let [sx, sy, sz] = self.scale;
+        let (xx, yy, zz) = (x * x, y * y, z * z);
+        let (xy, xz, yz) = (x * y, x * z, y * z);
+        let (wx, wy, wz) = (w * x, w * y, w * z);
+        [
+            (1.0 - 2.0 * (yy + zz)) * sx,
+            2.0 * (xy + wz) * sx,
+            2.0 * (xz - wy) * sx,
+            0.0,
+            2.0 * (xy - wz) * sy,
+            (1.0 - 2.0 * (xx + zz)) * sy,
+            2.0 * (yz + wx) * sy,
+            0.0,
+            2.0 * (xz + wy) * sz,
+            2.0 * (yz - wx) * sz,
+            (1.0 - 2.0 * (xx + yy)) * sz,
+            0.0,
+            self.translation[0],
+            self.translation[1],
+            self.translation[2],
+            1.0,
+        ]
+    }
+}
+
+impl Lerp for Transform {
+    fn lerp(&self, other: &Self, t: f32) -> Self {
+        Self {
+            translation: self.translation.lerp(&other.translation, t),
+            rotation: slerp(self.rotation, other.rotation, t),
+            scale: self.scale.lerp(&other.scale, t),
+        }
+    }
+}
+
+/// Shortest-arc spherical interpolation of unit quaternions `[x, y, z, w]`.
+pub fn slerp(a: [f32; 4], mut b: [f32; 4], f: f32) -> [f32; 4] {
+    let mut cos = a[0] * b[0] + a[1] * b[1] + a[2] * b[2] + a[3] * b[3];
+    // Take the short way round.
+    if cos < 0.0 {
+        b = b.map(|v| -v);
+        cos = -cos;
+    }
+    let (wa, wb) = if cos > 0.9995 {
+        (1.0 - f, f)
+    } else {
+        let theta = cos.acos();
+        let sin = theta.sin();
+        (((1.0 - f) * theta).sin() / sin, (f * theta).sin() / sin)
+    };
+    let q = [0, 1, 2, 3].map(|k| a[k] * wa + b[k] * wb);
+    let len = (q[0] * q[0] + q[1] * q[1] + q[2] * q[2] + q[3] * q[3]).sqrt();
+    if len > f32::EPSILON {
+        q.map(|v| v / len)
+    } else {
+        [0.0, 0.0, 0.0, 1.0]
+    }
+}
+
+/// State of the last two fixed ticks, for drawing fixed-step motion at any refresh rate.
+///
+/// [`push`](Self::push) the simulated value once per `fixed_update`, then draw
+/// [`get`](Self::get) with the frame's `fixed_alpha` (see [`Frame::fixed_alpha`]). The result
+/// trails the simulation by less than one tick and only depends on the tick states and the
+/// accumulator, so replays interpolate identically.
+///
+/// [`Frame::fixed_alpha`]: crate::frame::Frame::fixed_alpha
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Interpolated<T> {
+    prev: T,
+    curr: T,
+}
+
+impl<T: Lerp + Clone> Interpolated<T> {
+    #[inline]
+    pub fn new(value: T) -> Self {
+        Self {
+            prev: value.clone(),
+            curr: value,
+        }
+    }
+
+    /// Records the state after a fixed tick.
+    #[inline]
+    pub fn push(&mut self, value: T) {
+        self.prev = std::mem::replace(&mut self.curr, value);
+    }
+
+    /// Jumps to `value` without blending from the previous tick (spawns, teleports).
+    #[inline]
+    pub fn teleport(&mut self, value: T) {
+        self.prev = value.clone();
+        self.curr = value;
+    }
+
+    #[inline]
+    pub fn previous(&self) -> &T {
+        &self.prev
+    }
+
+    #[inline]
+    pub fn current(&self) -> &T {
+        &self.curr
+    }
+
+    /// The state `alpha` of the way from the previous tick to the current one.
+    #[inline]
+    pub fn get(&self, alpha: f32) -> T {
+        self.prev.lerp(&self.curr, alpha.clamp(0.0, 1.0))
+    }
+}
@@ -0,0 +1,491 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use parking_lot::{Condvar, Mutex};
+use serde::Serialize;
+use std::cell::Cell;
+use std::collections::VecDeque;
+use std::marker::PhantomData;
+use std::ops::Range;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+type Job = Box<dyn FnOnce() + Send + 'static>;
+
+/// Idle workers re-check the queues at least this often.
+const IDLE_WAIT: Duration = Duration::from_millis(10);
+
+thread_local! {
+    /// `(pool address, worker index + 1)` of the pool worker running on this thread.
+    static WORKER: Cell<(usize, usize)> = const { Cell::new((0, 0)) };
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct JobStats {
+    pub threads: usize,
+    /// Jobs waiting in a queue.
+    pub queued: usize,
+    /// Jobs being run right now.
+    pub running: usize,
+    /// Frame-scoped jobs not finished yet.
+    pub frame_pending: usize,
+    pub executed: u64,
+    /// Jobs a worker took from another worker's queue.
+    pub stolen: u64,
+    pub panicked: u64,
+}
+
+struct Shared {
+    injector: Mutex<VecDeque<Job>>,
+    locals: Vec<Mutex<VecDeque<Job>>>,
+
+    queued: AtomicUsize,
+    running: AtomicUsize,
+    frame_pending: AtomicUsize,
+
+    executed: AtomicU64,
+    stolen: AtomicU64,
+    panicked: AtomicU64,
+
+    sleep: Mutex<()>,
+    wake: Condvar,
+    shutdown: AtomicBool,
+}
+
+impl Shared {
+    #[inline]
+    fn key(&self) -> usize {
+        self as *const Shared as usize
+    }
+
+    /// Index of the calling thread when it is one of this pool's workers.
+    #[inline]
+    fn current_worker(&self) -> Option<usize> {
+        let (pool, idx) = WORKER.with(Cell::get);
+        (pool == self.key() && idx > 0).then(|| idx - 1)
+    }
+
+    fn push(&self, job: Job) {
+        self.queued.fetch_add(1, Ordering::AcqRel);
+        match self.current_worker() {
+            // Jobs spawned by a job stay on its worker; idle workers steal them.
+            Some(w) => self.locals[w].lock().push_back(job),
+            None => self.injector.lock().push_back(job),
+        }
+
+        // Taking the lock orders this with a worker's "queue empty" check before it sleeps.
+        drop(self.sleep.lock());
+        self.wake.notify_one();
+    }
+
+    /// Own queue first (newest job), then the injector, then the oldest job of another worker.
+    fn find_job(&self, me: Option<usize>) -> Option<Job> {
+        if let Some(w) = me {
+            if let Some(job) = self.locals[w].lock().pop_back() {
+                return Some(self.take(job));
+            }
+        }
+        if let Some(job) = self.injector.lock().pop_front() {
+            return Some(self.take(job));
+        }
+
+        let n = self.locals.len();
+        let start = me.map_or(0, |w| w + 1);
+        for i in 0..n {
+            let victim = (start + i) % n;
+            if Some(victim) == me {
+                continue;
+            }
+            if let Some(job) = self.locals[victim].lock().pop_front() {
+                self.stolen.fetch_add(1, Ordering::Relaxed);
+                return Some(self.take(job));
+            }
+        }
+        None
+    }
+
+    #[inline]
+    fn take(&self, job: Job) -> Job {
+        self.running.fetch_add(1, Ordering::AcqRel);
+        self.queued.fetch_sub(1, Ordering::AcqRel);
+        job
+    }
+
+    #[inline]
+    fn note_panic(&self) {
+        self.panicked.fetch_add(1, Ordering::Relaxed);
+        log::error!("jobs: job panicked");
+    }
+
+    fn run(&self, job: Job) {
+        if catch_unwind(AssertUnwindSafe(job)).is_err() {
+            self.note_panic();
+        }
+        self.executed.fetch_add(1, Ordering::Relaxed);
+        self.running.fetch_sub(1, Ordering::AcqRel);
+    }
+
+    /// Runs queued jobs on the calling thread until `done`, so waiting never deadlocks the
+    /// pool, even when called from a job.
+    fn help_until(&self, done: impl Fn() -> bool) {
+        let me = self.current_worker();
+        let mut spin: u32 = 0;
+        while !done() {
+            if let Some(job) = self.find_job(me) {
+                self.run(job);
+                spin = 0;
+                continue;
+            }
+
+            spin = spin.saturating_add(1);
+            if spin < 64 {
+                std::thread::yield_now();
+            } else {
+                std::thread::sleep(Duration::from_micros(50));
+            }
+        }
+    }
+
+    fn worker_loop(self: Arc<Self>, idx: usize) {
+        WORKER.with(|w| w.set((self.key(), idx + 1)));
+
+        while !self.shutdown.load(Ordering::Acquire) {
+            if let Some(job) = self.find_job(Some(idx)) {
+                self.run(job);
+                continue;
+            }
+
+            let mut g = self.sleep.lock();
+            if self.queued.load(Ordering::Acquire) == 0 && !self.shutdown.load(Ordering::Acquire) {
+                self.wake.wait_for(&mut g, IDLE_WAIT);
+            }
+        }
+    }
+}
+
+/// Stops and joins the workers once the last [`JobSystem`] handle is dropped.
+struct Owner {
+    shared: Arc<Shared>,
+    threads: Vec<JoinHandle<()>>,
+}
+
+impl Drop for Owner {
+    fn drop(&mut self) {
+        self.shared.shutdown.store(true, Ordering::Release);
+        drop(self.shared.sleep.lock());
+        self.shared.wake.notify_all();
+
+        if self.shared.current_worker().is_some() {
+            // Dropped from inside a job: the workers exit on their own.
+            return;
+        }
+        for t in self.threads.drain(..) {
+            let _ = t.join();
+        }
+    }
+}
+
+/// Work-stealing thread pool for modules and plugins.
+///
+/// - [`spawn`](Self::spawn): fire-and-forget or awaited background work.
+/// - [`spawn_frame`](Self::spawn_frame): work that must be done before the frame ends; the
+///   engine waits for it before plugin `end_frame` and the end-of-frame scheduler queue.
+/// - [`scope`](Self::scope) / [`parallel_for`](Self::parallel_for): fork-join over borrowed
+///   data; the call returns once every job in it finished.
+///
+/// Handles are cheap clones of one pool. The engine inserts the process-wide pool
+/// ([`JobSystem::global`]) as a resource; plugins reach it through `HostApiV1::submit_job`.
+#[derive(Clone)]
+pub struct JobSystem {
+    owner: Arc<Owner>,
+}
+
+static GLOBAL: OnceLock<JobSystem> = OnceLock::new();
+
+impl JobSystem {
+    /// Starts `threads` workers; 0 means one per logical CPU, minus the engine thread.
+    pub fn new(threads: usize) -> Self {
+        let threads = match threads {
+            0 => std::thread::available_parallelism()
+                .map(|n| n.get().saturating_sub(1))
+                .unwrap_or(1)
+                .max(1),
+            n => n,
+        };
+
+        let shared = Arc::new(Shared {
+            injector: Mutex::new(VecDeque::new()),
+            locals: (0..threads).map(|_| Mutex::new(VecDeque::new())).collect(),
+            queued: AtomicUsize::new(0),
+            running: AtomicUsize::new(0),
+            frame_pending: AtomicUsize::new(0),
+            executed: AtomicU64::new(0),
+            stolen: AtomicU64::new(0),
+            panicked: AtomicU64::new(0),
+            sleep: Mutex::new(()),
+            wake: Condvar::new(),
+            shutdown: AtomicBool::new(false),
+        });
+
+        let mut handles = Vec::with_capacity(threads);
+        for idx in 0..threads {
+            let s = shared.clone();
+            match std::thread::Builder::new()
+                .name(format!("ne-job-{idx}"))
+                .spawn(move || s.worker_loop(idx))
+            {
+                Ok(h) => handles.push(h),
+                Err(e) => log::error!("jobs: failed to start worker {idx}: {e}"),
+            }
+        }
+        log::info!("jobs: started threads={}", handles.len());
+
+        Self {
+            owner: Arc::new(Owner {
+                shared,
+                threads: handles,
+            }),
+        }
+    }
+
+    /// The process-wide pool. `threads` only applies when this call creates it (see
+    /// [`JobSystem::new`]).
+    pub fn global_with_threads(threads: usize) -> &'static JobSystem {
+        GLOBAL.get_or_init(|| JobSystem::new(threads))
+    }
+
+    #[inline]
+    pub fn global() -> &'static JobSystem {
+        Self::global_with_threads(0)
+    }
+
+    /// The process-wide pool, if something created it already.
+    #[inline]
+    pub fn try_global() -> Option<&'static JobSystem> {
+        GLOBAL.get()
+    }
+
+    #[inline]
+    fn shared(&self) -> &Shared {
+        &self.owner.shared
+    }
+
+    #[inline]
+    pub fn threads(&self) -> usize {
+        self.shared().locals.len()
+    }
+
+    pub fn spawn<F>(&self, f: F) -> JobHandle
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        let shared = self.owner.shared.clone();
+        let state = Arc::new(JobState::default());
+        let s = state.clone();
+        self.shared().push(Box::new(move || s.finish(&shared, f)));
+        JobHandle {
+            pool: self.clone(),
+            state,
+        }
+    }
+
+    /// Like [`spawn`](Self::spawn), but the job is guaranteed to have run when
+    /// [`wait_frame_jobs`](Self::wait_frame_jobs) returns.
+    pub fn spawn_frame<F>(&self, f: F) -> JobHandle
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        let shared = self.owner.shared.clone();
+        shared.frame_pending.fetch_add(1, Ordering::AcqRel);
+
+        let state = Arc::new(JobState::default());
+        let s = state.clone();
+        let owner = shared.clone();
+        shared.push(Box::new(move || {
+            s.finish(&owner, f);
+            owner.frame_pending.fetch_sub(1, Ordering::AcqRel);
+        }));
+
+        JobHandle {
+            pool: self.clone(),
+            state,
+        }
+    }
+
+    /// Blocks until every frame-scoped job finished, running queued jobs meanwhile.
+    /// Called by the engine once per frame.
+    pub fn wait_frame_jobs(&self) {
+        let s = self.shared();
+        s.help_until(|| s.frame_pending.load(Ordering::Acquire) == 0);
+    }
+
+    /// Blocks until the queues are empty and no job is running.
+    pub fn wait_idle(&self) {
+        let s = self.shared();
+        s.help_until(|| {
+            s.queued.load(Ordering::Acquire) == 0 && s.running.load(Ordering::Acquire) == 0
+        });
+    }
+
+    /// Runs `f` with a [`JobScope`] whose jobs may borrow anything that outlives this call;
+    /// returns after all of them finished.
+    ///
+    /// Panics if a scoped job panicked.
+    pub fn scope<'env, F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(&JobScope<'env>) -> R,
+    {
+        let scope = JobScope {
+            pool: self.clone(),
+            pending: Arc::new(AtomicUsize::new(0)),
+            panicked: Arc::new(AtomicBool::new(false)),
+            _env: PhantomData,
+        };
+
+        let out = {
+            // Waits on unwind too: jobs may still reference the caller's stack.
+            let _wait = ScopeWait(&scope);
+            f(&scope)
+        };
+
+        if scope.panicked.load(Ordering::Acquire) {
+            panic!("jobs: a scoped job panicked");
+        }
+        out
+    }
+
+    /// Calls `f` on chunks of `0..len` in parallel, at least `min_chunk` indices each;
+    /// returns when all chunks are done.
+    pub fn parallel_for<F>(&self, len: usize, min_chunk: usize, f: F)
+    where
+        F: Fn(Range<usize>) + Sync,
+    {
+        if len == 0 {
+            return;
+        }
+        let chunk = min_chunk.max(1).max(len.div_ceil((self.threads() + 1) * 4));
+        if chunk >= len {
+            f(0..len);
+            return;
+        }
+
+        let f = &f;
+        self.scope(|s| {
+            let mut start = 0;
+            while start < len {
+                let end = (start + chunk).min(len);
+                s.spawn(move || f(start..end));
+                start = end;
+            }
+        });
+    }
+
+    pub fn stats(&self) -> JobStats {
+        let s = self.shared();
+        JobStats {
+            threads: s.locals.len(),
+            queued: s.queued.load(Ordering::Relaxed),
+            running: s.running.load(Ordering::Relaxed),
+            frame_pending: s.frame_pending.load(Ordering::Relaxed),
+            executed: s.executed.load(Ordering::Relaxed),
+            stolen: s.stolen.load(Ordering::Relaxed),
+            panicked: s.panicked.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl std::fmt::Debug for JobSystem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("JobSystem")
+            .field("threads", &self.threads())
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Default)]
+struct JobState {
+    done: AtomicBool,
+    panicked: AtomicBool,
+}
+
+impl JobState {
+    /// Runs `f` and marks the job done, also when it panics.
+    #[inline]
+    fn finish(&self, shared: &Shared, f: impl FnOnce()) {
+        if catch_unwind(AssertUnwindSafe(f)).is_err() {
+            self.panicked.store(true, Ordering::Release);
+            shared.note_panic();
+        }
+        self.done.store(true, Ordering::Release);
+    }
+}
+
+/// Completion of a spawned job. Dropping the handle does not cancel the job.
+pub struct JobHandle {
+    pool: JobSystem,
+    state: Arc<JobState>,
+}
+
+impl JobHandle {
+    #[inline]
+    pub fn is_done(&self) -> bool {
+        self.state.done.load(Ordering::Acquire)
+    }
+
+    /// Blocks until the job ran, running queued jobs meanwhile. Errors if the job panicked.
+    pub fn wait(&self) -> Result<(), String> {
+        self.pool.shared().help_until(|| self.is_done());
+        if self.state.panicked.load(Ordering::Acquire) {
+            return Err("job panicked".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Spawns jobs that may borrow from the enclosing [`JobSystem::scope`] call.
+pub struct JobScope<'env> {
+    pool: JobSystem,
+    pending: Arc<AtomicUsize>,
+    panicked: Arc<AtomicBool>,
+    /// Invariant in `'env`, so borrows cannot be shortened below the `scope` call.
+    _env: PhantomData<&'env mut &'env ()>,
+}
+
+impl<'env> JobScope<'env> {
+    pub fn spawn<F>(&self, f: F)
+    where
+        F: FnOnce() + Send + 'env,
+    {
+        self.pending.fetch_add(1, Ordering::AcqRel);
+        let shared = self.pool.owner.shared.clone();
+        let pending = self.pending.clone();
+        let panicked = self.panicked.clone();
+
+        let job: Box<dyn FnOnce() + Send + 'env> = Box::new(move || {
+            if catch_unwind(AssertUnwindSafe(f)).is_err() {
+                panicked.store(true, Ordering::Release);
+                shared.note_panic();
+            }
+            pending.fetch_sub(1, Ordering::AcqRel);
+        });
+
+        // SAFETY: `JobSystem::scope` does not return (or unwind) before `pending` drops to
+        // zero, so everything the job borrows for `'env` outlives its execution.
+        let job: Job = unsafe { std::mem::transmute::<Box<dyn FnOnce() + Send + 'env>, Job>(job) };
+        self.pool.shared().push(job);
+    }
+}
+
+struct ScopeWait<'a, 'env>(&'a JobScope<'env>);
+
+impl Drop for ScopeWait<'_, '_> {
+    fn drop(&mut self) {
+        let pending = &self.0.pending;
+        self.0
+            .pool
+            .shared()
+            .help_until(|| pending.load(Ordering::Acquire) == 0);
+    }
+}
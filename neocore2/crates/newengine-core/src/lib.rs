@@ -1,14 +1,37 @@
+#[cfg(feature = "runtime")]
+pub mod animation;
 pub mod bus;
+pub mod clipboard;
+pub mod config;
+pub mod config_service;
 pub mod core_invariants;
+pub mod crash;
+pub mod cvar;
 pub mod engine;
 pub mod error;
 pub mod events;
+#[cfg(feature = "runtime")]
+pub mod export;
 pub mod frame;
+pub mod frame_record;
+pub mod headless;
 pub mod host_events;
+pub mod interp;
+pub mod jobs;
 pub mod module;
 pub mod plugins;
+pub mod project;
+pub mod reflect;
+pub mod save;
 pub mod sched;
+pub mod server;
+pub mod snapshot;
 pub mod sync;
+pub mod telemetry;
+pub mod time;
+pub mod topics;
+pub mod undo;
+pub mod window;
 mod system_info;
 pub mod render;
 pub mod startup;
@@ -16,23 +39,112 @@ pub mod assets;
 pub mod assets_service;
 pub mod console;
 pub mod host_services;
+pub mod window_service;
+pub mod events_service;
+pub mod modules_service;
+pub mod plugins_service;
+pub mod telemetry_service;
+pub mod debug_draw_service;
+pub mod render_service;
+pub mod snapshot_service;
+pub mod save_service;
+pub mod time_service;
+pub mod cvar_service;
+pub mod undo_service;
+pub mod entity_service;
+#[cfg(feature = "runtime")]
+pub mod project_service;
+#[cfg(feature = "media")]
+pub mod media;
+#[cfg(feature = "media")]
+pub mod media_service;
+#[cfg(feature = "runtime")]
+pub mod stats_overlay;
+#[cfg(feature = "runtime")]
+pub mod stats_service;
 
-pub use host_services::{call_service_v1, describe_service, list_service_ids};
+pub use host_services::{
+    call_service_v1, describe_service, list_service_ids, register_service_v1,
+    unregister_service_v1,
+};
 
-pub use assets::{AssetManager, AssetManagerConfig};
+pub use assets::{AssetManager, AssetManagerConfig, AssetTunables, ASSETS_CONFIG_SECTION};
 
+#[cfg(feature = "runtime")]
+pub use animation::AnimationPlayer;
 pub use bus::Bus;
-pub use engine::{Engine, EngineConfig};
+pub use clipboard::{clipboard_get, clipboard_set, install_clipboard_backend, ClipboardBackend};
+pub use config::{config_api, config_topic, ConfigApi, ConfigChange, CONFIG_TOPIC_PREFIX};
+pub use crash::{install_crash_handler, CrashConfig, TailLogger, ENGINE_VERSION};
+pub use cvar::{
+    cvar_bool, cvar_exists, cvar_float, cvar_get, cvar_int, cvar_list, cvar_reset, cvar_set,
+    cvar_set_value, cvar_string, cvar_topic, register_cvar, unregister_cvar, CvarChange, CvarDesc,
+    CvarFlag, CvarInfo, CvarKind, CvarValue, CHEATS_CVAR, CVAR_CONFIG_SECTION, CVAR_TOPIC_PREFIX,
+};
+pub use engine::{Engine, EngineConfig, RunProfile};
 pub use error::{EngineError, EngineResult, ModuleStage};
-pub use events::{EventHub, EventSub};
+pub use events::{EventHub, EventSub, OverflowPolicy};
+#[cfg(feature = "runtime")]
+pub use export::{
+    export_project, export_status, start_export, ExportReport, ExportStatus,
+    EXPORT_MANIFEST_FILE, RUNTIME_EXE_NAME,
+};
 pub use frame::Frame;
+pub use frame_record::{
+    is_recording, record_status, start_recording, stop_recording, RecordConfig, RecordStatus,
+    RECORD_DIR,
+};
+pub use headless::{HeadlessExit, HeadlessReport, HeadlessRunner};
 pub use host_events::WindowHostEvent;
-pub use module::{ApiProvide, ApiRequire, ApiVersion, Module, ModuleCtx, Resources, Services};
+pub use interp::{Interpolated, Lerp, Transform};
+pub use jobs::{JobHandle, JobScope, JobStats, JobSystem};
+#[cfg(feature = "media")]
+pub use media::{
+    media_command, media_status, open_video, MediaCommand, MediaModule, MediaOpen, MediaStatus,
+    MjpegDecoder, VideoDecoder, VideoFrame, MEDIA_DEFAULT_FPS,
+};
+pub use module::{
+    ApiProvide, ApiRequire, ApiVersion, Dependency, Module, ModuleCtx, ModuleState, ResourceInfo,
+    Resources, Services, HOST_PHASE_WINDOW,
+};
+pub use project::{
+    active_project, project_path, set_active_project, BuildProfile, PluginSet, Project,
+    ProjectAssets, ProjectFile, PROJECT_EXTENSION,
+};
+pub use reflect::{
+    component_for_type, component_info, component_list, component_store, register_component,
+    set_component_store, set_entity_field, unregister_component, ComponentDesc, ComponentInfo,
+    ComponentSet, ComponentStore, FieldInfo, FieldKind, FieldValue, TRANSFORM_COMPONENT,
+};
+pub use save::{
+    list_saves, read_save, register_save_migration, save_dir, save_schema, set_save_dir,
+    set_save_schema, user_data_dir, write_save, SaveData, SaveEncoding, SaveMigration,
+    SaveSlotInfo, SAVE_EXT, SAVE_FORMAT_VERSION, SAVE_MAGIC,
+};
 pub use sched::Scheduler;
+#[cfg(feature = "runtime")]
+pub use stats_overlay::{
+    set_stats_overlay_visible, stats_overlay_visible, toggle_stats_overlay, StatsOverlayModule,
+    StatsSnapshot,
+};
+pub use server::{ServerRunner, ServerTickStats};
+pub use snapshot::{last_snapshot_report, request_snapshot, Snapshot, SnapshotOp, SnapshotReport};
 pub use sync::ShutdownToken;
+pub use time::{
+    set_time_paused, set_time_scale, step_time, time_paused, time_scale, time_state,
+    toggle_time_paused, TimeState,
+};
+pub use topics::{TopicEvent, TopicPattern, TopicSub};
+pub use undo::{
+    undoable_cvar_set, Command, CommandBatch, CvarSet, UndoEntry, UndoStack, UNDO_LIMIT,
+};
+pub use window::{
+    window_api, CursorGrab, CursorIcon, CursorState, MonitorInfo, WindowApi, WindowMode,
+};
 
 pub use render::{
-    BeginFrameDesc, Color4, RenderApi, RenderApiRef, RENDER_API_ID, RENDER_API_PROVIDE,
+    BeginFrameDesc, CapturedFrame, Color4, DebugDraw, GpuPassTiming, NullRenderModule,
+    NullRenderProbe, NullRenderStats, RenderApi, RenderApiRef, RENDER_API_ID, RENDER_API_PROVIDE,
     RENDER_API_VERSION,
 };
 
@@ -43,6 +155,7 @@ pub use startup::{
     StartupLoadReport,
     StartupLoader,
     StartupOverride,
+    StartupOverrideOrigin,
     StartupResolvedFrom,
     WindowPlacement,
 };
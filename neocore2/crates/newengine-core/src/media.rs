@@ -0,0 +1,502 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use crate::error::{EngineError, EngineResult};
+use crate::media_service::MediaService;
+use crate::module::{Module, ModuleCtx};
+
+use newengine_plugin_api::{guarded_service, ServiceV1Dyn};
+use newengine_ui::draw::{UiDrawList, UiTexId, UiTexture};
+use newengine_ui::texture::reserved;
+use newengine_ui::{ui_color, UiPainter};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::ops::Range;
+use std::sync::Mutex;
+
+/// Frame rate of streams that do not carry one.
+pub const MEDIA_DEFAULT_FPS: f32 = 30.0;
+
+/// One decoded picture.
+#[derive(Debug, Clone)]
+pub struct VideoFrame {
+    pub size: [u32; 2],
+    pub rgba8: Vec<u8>,
+}
+
+/// A video stream decoded on demand, frame by frame.
+pub trait VideoDecoder: Send {
+    fn frame_count(&self) -> usize;
+
+    fn fps(&self) -> f32;
+
+    fn decode(&mut self, index: usize) -> Result<VideoFrame, String>;
+}
+
+/// Motion-JPEG elementary stream: JPEG pictures back to back (`.mjpeg`), as written by
+/// `ffmpeg -i in.mp4 -c:v mjpeg -f mjpeg out.mjpeg`.
+pub struct MjpegDecoder {
+    data: Vec<u8>,
+    frames: Vec<Range<usize>>,
+    fps: f32,
+}
+
+impl MjpegDecoder {
+    pub fn new(data: Vec<u8>, fps: f32) -> Result<Self, String> {
+        let mut frames = Vec::new();
+        let mut pos = 0;
+        while pos + 1 < data.len() {
+            if data[pos] != 0xFF || data[pos + 1] != 0xD8 {
+                pos += 1;
+                continue;
+            }
+            let end = jpeg_end(&data, pos)
+                .ok_or_else(|| format!("mjpeg: frame {} is truncated", frames.len()))?;
+            frames.push(pos..end);
+            pos = end;
+        }
+
+        if frames.is_empty() {
+            return Err("mjpeg: no frames".to_string());
+        }
+        Ok(Self {
+            data,
+            frames,
+            fps: if fps > 0.0 { fps } else { MEDIA_DEFAULT_FPS },
+        })
+    }
+}
+
+impl VideoDecoder for MjpegDecoder {
+    #[inline]
+    fn frame_count(&self) -> usize {
+        self.frames.len()
+    }
+
+    #[inline]
+    fn fps(&self) -> f32 {
+        self.fps
+    }
+
+    fn decode(&mut self, index: usize) -> Result<VideoFrame, String> {
+        let range = self
+            .frames
+            .get(index)
+            .cloned()
+            .ok_or_else(|| format!("mjpeg: no frame {index}"))?;
+        let img = image::load_from_memory_with_format(&self.data[range], image::ImageFormat::Jpeg)
+            .map_err(|e| format!("mjpeg: frame {index}: {e}"))?
+            .to_rgba8();
+        Ok(VideoFrame {
+            size: [img.width(), img.height()],
+            rgba8: img.into_raw(),
+        })
+    }
+}
+
+/// End (exclusive) of the JPEG starting at `start`: walks marker segments up to the scan,
+/// then the entropy-coded data (where `FF` is stuffed) up to EOI.
+fn jpeg_end(data: &[u8], start: usize) -> Option<usize> {
+    let mut pos = start + 2;
+    loop {
+        while *data.get(pos)? == 0xFF && *data.get(pos + 1)? == 0xFF {
+            pos += 1;
+        }
+        if *data.get(pos)? != 0xFF {
+            return None;
+        }
+        let marker = *data.get(pos + 1)?;
+        match marker {
+            0xD9 => return Some(pos + 2),
+            0x01 | 0xD0..=0xD7 => pos += 2,
+            _ => {
+                let len = u16::from_be_bytes([*data.get(pos + 2)?, *data.get(pos + 3)?]) as usize;
+                pos += 2 + len;
+                if marker == 0xDA {
+                    // Scan data: only `FF 00` and restart markers may appear inside.
+                    loop {
+                        if *data.get(pos)? == 0xFF {
+                            let next = *data.get(pos + 1)?;
+                            if next != 0x00 && !(0xD0..=0xD7).contains(&next) {
+                                break;
+                            }
+                            pos += 2;
+                        } else {
+                            pos += 1;
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Picks a decoder from the file contents. Motion-JPEG is the only supported format;
+/// WebM/Matroska (VP8/VP9) is recognized and rejected until a decoder is added.
+pub fn open_video(
+    path: &str,
+    data: Vec<u8>,
+    fps: Option<f32>,
+) -> Result<Box<dyn VideoDecoder>, String> {
+    if data.starts_with(&[0x1A, 0x45, 0xDF, 0xA3]) {
+        return Err(format!(
+            "{path}: WebM/Matroska needs a VP8/VP9/AV1 decoder this build does not have; \
+             convert it to Motion-JPEG (.mjpeg)"
+        ));
+    }
+    if data.starts_with(&[0xFF, 0xD8]) {
+        let dec = MjpegDecoder::new(data, fps.unwrap_or(MEDIA_DEFAULT_FPS))?;
+        return Ok(Box::new(dec));
+    }
+    Err(format!("{path}: unsupported video format"))
+}
+
+/// `media.open` request.
+#[derive(Debug, Clone, Deserialize)]
+pub struct MediaOpen {
+    /// Handle used by the other media commands; the path when empty.
+    #[serde(default)]
+    pub name: String,
+    /// Logical asset path.
+    pub path: String,
+    #[serde(default)]
+    pub fps: Option<f32>,
+    #[serde(default)]
+    pub looping: bool,
+    #[serde(default = "default_true")]
+    pub autoplay: bool,
+    /// Pixel rect `[x0, y0, x1, y1]` the module draws the video into; `None` with
+    /// `fullscreen == false` only streams the texture for UI code to show.
+    #[serde(default)]
+    pub rect: Option<[f32; 4]>,
+    #[serde(default)]
+    pub fullscreen: bool,
+}
+
+#[inline]
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Clone)]
+pub enum MediaCommand {
+    Open(MediaOpen),
+    Play(String),
+    Pause(String),
+    Seek(String, f32),
+    Close(String),
+}
+
+/// State of one open video, as reported by `media.list_json`.
+#[derive(Debug, Clone, Serialize)]
+pub struct MediaStatus {
+    pub name: String,
+    pub path: String,
+    /// UI texture the frames are streamed into.
+    pub texture: u32,
+    pub size: [u32; 2],
+    pub fps: f32,
+    pub frames: usize,
+    pub time: f32,
+    pub duration: f32,
+    pub playing: bool,
+    pub looping: bool,
+    pub error: Option<String>,
+}
+
+/// Process-wide so the media service can queue commands without a handle to the module.
+static PENDING: Mutex<VecDeque<MediaCommand>> = Mutex::new(VecDeque::new());
+static STATUS: Mutex<Vec<MediaStatus>> = Mutex::new(Vec::new());
+
+/// Queues a command; the media module applies it on its next update.
+pub fn media_command(cmd: MediaCommand) {
+    if let Ok(mut g) = PENDING.lock() {
+        g.push_back(cmd);
+    }
+}
+
+/// Open videos as of the media module's last update.
+pub fn media_status() -> Vec<MediaStatus> {
+    STATUS.lock().map(|g| g.clone()).unwrap_or_default()
+}
+
+struct Player {
+    open: MediaOpen,
+    decoder: Box<dyn VideoDecoder>,
+    texture: UiTexId,
+    size: [u32; 2],
+    time: f32,
+    playing: bool,
+    /// Frame currently in the texture.
+    shown: Option<usize>,
+    error: Option<String>,
+}
+
+impl Player {
+    #[inline]
+    fn duration(&self) -> f32 {
+        self.decoder.frame_count() as f32 / self.decoder.fps()
+    }
+
+    fn advance(&mut self, dt: f32) {
+        if !self.playing {
+            return;
+        }
+        self.time += dt;
+        let duration = self.duration();
+        if self.time >= duration {
+            if self.open.looping && duration > 0.0 {
+                self.time %= duration;
+            } else {
+                self.time = duration;
+                self.playing = false;
+            }
+        }
+    }
+
+    #[inline]
+    fn frame_index(&self) -> usize {
+        let last = self.decoder.frame_count().saturating_sub(1);
+        ((self.time * self.decoder.fps()) as usize).min(last)
+    }
+
+    fn status(&self) -> MediaStatus {
+        MediaStatus {
+            name: self.open.name.clone(),
+            path: self.open.path.clone(),
+            texture: self.texture.0,
+            size: self.size,
+            fps: self.decoder.fps(),
+            frames: self.decoder.frame_count(),
+            time: self.time,
+            duration: self.duration(),
+            playing: self.playing,
+            looping: self.open.looping,
+            error: self.error.clone(),
+        }
+    }
+}
+
+/// Plays Motion-JPEG videos from the asset store into UI textures (`media.*` commands).
+///
+/// Each open video streams its current frame into its own [`UiTexId`] through the frame's
+/// [`UiDrawList`] texture delta, so any UI can show it; with `rect`/`fullscreen` the module
+/// also draws it, e.g. as a menu background. Time follows the wall clock, so videos keep
+/// playing while the game clock is paused.
+pub struct MediaModule {
+    players: BTreeMap<String, Player>,
+    next_tex: u32,
+    freed: Vec<UiTexId>,
+    service_registered: bool,
+}
+
+impl MediaModule {
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            players: BTreeMap::new(),
+            next_tex: reserved::MEDIA_BEGIN,
+            freed: Vec::new(),
+            service_registered: false,
+        }
+    }
+
+    fn open<E: Send + 'static>(&mut self, ctx: &ModuleCtx<'_, E>, mut req: MediaOpen) {
+        if req.name.is_empty() {
+            req.name = req.path.clone();
+        }
+
+        let data = match ctx.resources().get::<crate::assets::AssetManager>() {
+            Some(am) => am
+                .store()
+                .read_source(&req.path)
+                .map_err(|e| format!("{}: {e}", req.path)),
+            None => Err("media: no asset manager".to_string()),
+        };
+        let decoder = match data.and_then(|d| open_video(&req.path, d, req.fps)) {
+            Ok(d) => d,
+            Err(e) => {
+                log::warn!("media: open '{}' failed: {e}", req.name);
+                return;
+            }
+        };
+
+        let texture = match self.players.remove(&req.name) {
+            Some(old) => old.texture,
+            None => {
+                let id = UiTexId::new(self.next_tex);
+                self.next_tex = self.next_tex.saturating_add(1);
+                id
+            }
+        };
+        log::info!(
+            "media: opened '{}' path='{}' frames={} fps={}",
+            req.name,
+            req.path,
+            decoder.frame_count(),
+            decoder.fps()
+        );
+
+        let player = Player {
+            playing: req.autoplay,
+            open: req,
+            decoder,
+            texture,
+            size: [0, 0],
+            time: 0.0,
+            shown: None,
+            error: None,
+        };
+        self.players.insert(player.open.name.clone(), player);
+    }
+
+    fn apply<E: Send + 'static>(&mut self, ctx: &ModuleCtx<'_, E>, cmd: MediaCommand) {
+        let name = match &cmd {
+            MediaCommand::Open(_) => String::new(),
+            MediaCommand::Play(n)
+            | MediaCommand::Pause(n)
+            | MediaCommand::Seek(n, _)
+            | MediaCommand::Close(n) => n.clone(),
+        };
+        if !name.is_empty() && !self.players.contains_key(&name) {
+            log::warn!("media: no video '{name}'");
+            return;
+        }
+
+        match cmd {
+            MediaCommand::Open(req) => self.open(ctx, req),
+            MediaCommand::Play(_) => {
+                if let Some(p) = self.players.get_mut(&name) {
+                    if p.time >= p.duration() {
+                        p.time = 0.0;
+                    }
+                    p.playing = true;
+                }
+            }
+            MediaCommand::Pause(_) => {
+                if let Some(p) = self.players.get_mut(&name) {
+                    p.playing = false;
+                }
+            }
+            MediaCommand::Seek(_, t) => {
+                if let Some(p) = self.players.get_mut(&name) {
+                    p.time = t.clamp(0.0, p.duration());
+                }
+            }
+            MediaCommand::Close(_) => {
+                if let Some(p) = self.players.remove(&name) {
+                    self.freed.push(p.texture);
+                }
+            }
+        }
+    }
+
+    fn present(&mut self, list: &mut UiDrawList) {
+        for id in self.freed.drain(..) {
+            list.texture_delta.free.push(id);
+        }
+
+        for p in self.players.values_mut() {
+            let index = p.frame_index();
+            if p.shown != Some(index) {
+                match p.decoder.decode(index) {
+                    Ok(frame) => {
+                        p.size = frame.size;
+                        p.error = None;
+                        list.texture_delta.set.insert(
+                            p.texture,
+                            UiTexture {
+                                size: frame.size,
+                                rgba8: frame.rgba8,
+                            },
+                        );
+                    }
+                    Err(e) => {
+                        if p.error.is_none() {
+                            log::warn!("media: '{}': {e}", p.open.name);
+                        }
+                        p.error = Some(e);
+                    }
+                }
+                p.shown = Some(index);
+            }
+
+            let rect = match (p.open.rect, p.open.fullscreen) {
+                (Some(r), _) => Some(r),
+                (None, true) if list.screen_size_px != [0, 0] => {
+                    let [w, h] = list.screen_size_px;
+                    Some([0.0, 0.0, w as f32, h as f32])
+                }
+                _ => None,
+            };
+            if let Some([x0, y0, x1, y1]) = rect {
+                UiPainter::new(list).image(
+                    [x0, y0],
+                    [x1, y1],
+                    p.texture,
+                    ui_color(255, 255, 255, 255),
+                );
+            }
+        }
+    }
+}
+
+impl Default for MediaModule {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E: Send + 'static> Module<E> for MediaModule {
+    fn id(&self) -> &'static str {
+        "media"
+    }
+
+    fn init(&mut self, _ctx: &mut ModuleCtx<'_, E>) -> EngineResult<()> {
+        let dyn_svc: ServiceV1Dyn<'static> = guarded_service(MediaService);
+        crate::register_service_v1(dyn_svc).map_err(EngineError::other)?;
+        self.service_registered = true;
+        Ok(())
+    }
+
+    fn update(&mut self, ctx: &mut ModuleCtx<'_, E>) -> EngineResult<()> {
+        let cmds: Vec<MediaCommand> = PENDING
+            .lock()
+            .map(|mut g| g.drain(..).collect())
+            .unwrap_or_default();
+        for cmd in cmds {
+            self.apply(ctx, cmd);
+        }
+
+        let dt = ctx.frame().map_or(0.0, |f| f.real_dt);
+        for p in self.players.values_mut() {
+            p.advance(dt);
+        }
+
+        if !self.players.is_empty() || !self.freed.is_empty() {
+            if ctx.resources().get::<UiDrawList>().is_none() {
+                ctx.resources_mut().insert(UiDrawList::new());
+            }
+            if let Some(list) = ctx.resources_mut().get_mut::<UiDrawList>() {
+                self.present(list);
+            }
+        }
+
+        if let Ok(mut g) = STATUS.lock() {
+            *g = self.players.values().map(Player::status).collect();
+        }
+        Ok(())
+    }
+
+    fn shutdown(&mut self, _ctx: &mut ModuleCtx<'_, E>) -> EngineResult<()> {
+        self.players.clear();
+        if let Ok(mut g) = STATUS.lock() {
+            g.clear();
+        }
+        if self.service_registered {
+            crate::unregister_service_v1(crate::media_service::MEDIA_SERVICE_ID);
+            self.service_registered = false;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,199 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use crate::media::{media_command, media_status, MediaCommand, MediaOpen, MediaStatus};
+use abi_stable::std_types::{RResult, RString};
+use newengine_plugin_api::{Blob, CapabilityId, MethodName, ServiceV1};
+use serde::Serialize;
+use serde_json::json;
+
+pub const MEDIA_SERVICE_ID: &str = "engine.media";
+
+pub mod method {
+    pub const OPEN: &str = "media.open";
+    pub const PLAY: &str = "media.play";
+    pub const PAUSE: &str = "media.pause";
+    pub const SEEK: &str = "media.seek";
+    pub const CLOSE: &str = "media.close";
+    pub const LIST_JSON: &str = "media.list_json";
+}
+
+#[derive(Debug, Serialize)]
+struct MediaResp {
+    ok: bool,
+    error: Option<String>,
+}
+
+impl MediaResp {
+    fn from_result(res: Result<(), String>) -> Self {
+        Self {
+            ok: res.is_ok(),
+            error: res.err(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct MediaListResp {
+    videos: Vec<MediaStatus>,
+}
+
+/// Registered by [`crate::media::MediaModule`]; commands are applied on its next update.
+pub(crate) struct MediaService;
+
+impl MediaService {
+    /// Payload: json [`MediaOpen`], or utf8 `<path> [name]` to play it fullscreen.
+    fn open(payload: &[u8]) -> Result<(), String> {
+        let text = String::from_utf8_lossy(payload);
+        let text = text.trim();
+        let req = if text.starts_with('{') {
+            serde_json::from_str::<MediaOpen>(text).map_err(|e| format!("bad media.open: {e}"))?
+        } else {
+            let mut it = text.split_whitespace();
+            let path = it
+                .next()
+                .ok_or_else(|| format!("usage: {} <path> [name]", method::OPEN))?;
+            MediaOpen {
+                name: it.next().unwrap_or_default().to_string(),
+                path: path.to_string(),
+                fps: None,
+                looping: false,
+                autoplay: true,
+                rect: None,
+                fullscreen: true,
+            }
+        };
+        if req.path.is_empty() {
+            return Err("media.open: empty path".to_string());
+        }
+
+        media_command(MediaCommand::Open(req));
+        Ok(())
+    }
+
+    /// Payload: `<name> <seconds>`.
+    fn seek(arg: &str) -> Result<(), String> {
+        let (name, t) = arg
+            .trim()
+            .split_once(char::is_whitespace)
+            .ok_or_else(|| format!("usage: {} <name> <seconds>", method::SEEK))?;
+        let t: f32 = t
+            .trim()
+            .parse()
+            .map_err(|_| format!("bad time: '{}'", t.trim()))?;
+
+        media_command(MediaCommand::Seek(name.to_string(), t));
+        Ok(())
+    }
+}
+
+impl ServiceV1 for MediaService {
+    fn id(&self) -> CapabilityId {
+        RString::from(MEDIA_SERVICE_ID)
+    }
+
+    fn describe(&self) -> RString {
+        let d = json!({
+          "id": MEDIA_SERVICE_ID,
+          "version": 1,
+          "methods": [
+            { "name": method::OPEN, "payload": "json MediaOpen | utf8 '<path> [name]'", "returns": "json MediaResp" },
+            { "name": method::PLAY, "payload": "utf8 name", "returns": "json MediaResp" },
+            { "name": method::PAUSE, "payload": "utf8 name", "returns": "json MediaResp" },
+            { "name": method::SEEK, "payload": "utf8 '<name> <seconds>'", "returns": "json MediaResp" },
+            { "name": method::CLOSE, "payload": "utf8 name", "returns": "json MediaResp" },
+            { "name": method::LIST_JSON, "payload": "empty", "returns": "json MediaListResp" }
+          ],
+          "console": {
+            "commands": [
+              {
+                "name": "media.open",
+                "help": "Play a video from the asset store (Motion-JPEG) fullscreen",
+                "usage": "media.open <path> [name]",
+                "kind": "service_call",
+                "service_id": MEDIA_SERVICE_ID,
+                "method": method::OPEN,
+                "payload": "raw"
+              },
+              {
+                "name": "media.play",
+                "help": "Resume a video, restarting it if it ended",
+                "usage": "media.play <name>",
+                "kind": "service_call",
+                "service_id": MEDIA_SERVICE_ID,
+                "method": method::PLAY,
+                "payload": "raw"
+              },
+              {
+                "name": "media.pause",
+                "help": "Pause a video",
+                "usage": "media.pause <name>",
+                "kind": "service_call",
+                "service_id": MEDIA_SERVICE_ID,
+                "method": method::PAUSE,
+                "payload": "raw"
+              },
+              {
+                "name": "media.seek",
+                "help": "Jump to a time in seconds",
+                "usage": "media.seek <name> <seconds>",
+                "kind": "service_call",
+                "service_id": MEDIA_SERVICE_ID,
+                "method": method::SEEK,
+                "payload": "raw"
+              },
+              {
+                "name": "media.close",
+                "help": "Stop a video and free its texture",
+                "usage": "media.close <name>",
+                "kind": "service_call",
+                "service_id": MEDIA_SERVICE_ID,
+                "method": method::CLOSE,
+                "payload": "raw"
+              },
+              {
+                "name": "media.list",
+                "help": "List open videos with texture, time and state",
+                "usage": "media.list",
+                "kind": "service_call",
+                "service_id": MEDIA_SERVICE_ID,
+                "method": method::LIST_JSON,
+                "payload": "empty"
+              }
+            ]
+          }
+        });
+
+        RString::from(d.to_string())
+    }
+
+    fn call(&self, method: MethodName, payload: Blob) -> RResult<Blob, RString> {
+        let m = method.to_string();
+        let arg = String::from_utf8_lossy(payload.as_slice());
+        let name = arg.trim().to_string();
+        let need_name = || {
+            if name.is_empty() {
+                Err(format!("usage: {m} <name>"))
+            } else {
+                Ok(())
+            }
+        };
+
+        let res = match m.as_str() {
+            method::OPEN => Self::open(payload.as_slice()),
+            method::PLAY => need_name().map(|_| media_command(MediaCommand::Play(name.clone()))),
+            method::PAUSE => need_name().map(|_| media_command(MediaCommand::Pause(name.clone()))),
+            method::SEEK => Self::seek(&arg),
+            method::CLOSE => need_name().map(|_| media_command(MediaCommand::Close(name.clone()))),
+            method::LIST_JSON => {
+                let resp = serde_json::to_vec(&MediaListResp {
+                    videos: media_status(),
+                });
+                return RResult::ROk(Blob::from(resp.unwrap_or_default()));
+            }
+            _ => return RResult::RErr(RString::from(format!("unknown method: {m}"))),
+        };
+
+        let resp = serde_json::to_vec(&MediaResp::from_result(res));
+        RResult::ROk(Blob::from(resp.unwrap_or_default()))
+    }
+}
@@ -1,5 +1,6 @@
 use crate::events::EventHub;
 use crate::frame::Frame;
+use crate::interp::{Interpolated, Lerp};
 use crate::module::{Bus, Resources, Services};
 use crate::sched::Scheduler;
 
@@ -47,6 +48,19 @@ impl<'a, E: Send + 'static> ModuleCtx<'a, E> {
         self.frame.as_ref()
     }
 
+    /// How far the render frame is between the last two fixed ticks, in `[0..1)`.
+    /// `0.0` in fixed subframes and outside a frame.
+    #[inline]
+    pub fn fixed_alpha(&self) -> f32 {
+        self.frame.map_or(0.0, |f| f.fixed_alpha)
+    }
+
+    /// `value` blended to this frame's [`fixed_alpha`](Self::fixed_alpha).
+    #[inline]
+    pub fn interpolate<T: Lerp + Clone>(&self, value: &Interpolated<T>) -> T {
+        value.get(self.fixed_alpha())
+    }
+
     #[inline]
     pub fn services(&self) -> &dyn Services {
         self.services
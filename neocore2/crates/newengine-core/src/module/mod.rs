@@ -1,12 +1,16 @@
 pub mod ctx;
 pub mod module;
+pub mod order;
 pub mod resources;
 pub mod services;
+pub mod toggles;
 
 pub use ctx::ModuleCtx;
 pub use module::{ApiProvide, ApiRequire, ApiVersion, Module};
-pub use resources::Resources;
+pub use order::{Dependency, HOST_PHASE_WINDOW};
+pub use resources::{ResourceInfo, Resources};
 pub use services::Services;
+pub use toggles::{module_enabled, module_states, set_module_enabled, ModuleState};
 
 /// Re-export the engine bus as a part of `crate::module` facade.
 pub use crate::bus::Bus;
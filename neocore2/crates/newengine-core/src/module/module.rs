@@ -1,5 +1,6 @@
 use crate::error::EngineResult;
 use crate::module::ModuleCtx;
+use crate::snapshot::Snapshot;
 
 use std::any::Any;
 
@@ -52,6 +53,8 @@ pub trait Module<E: Send + 'static>: Send {
         "module"
     }
 
+    /// Init ordering constraints: `"<module id>"` for a hard dependency, `"after:<name>"` to
+    /// start after a module or host phase (see `Dependency`). Shutdown runs in reverse.
     fn dependencies(&self) -> &'static [&'static str] {
         &[]
     }
@@ -96,4 +99,9 @@ pub trait Module<E: Send + 'static>: Send {
     fn shutdown(&mut self, _ctx: &mut ModuleCtx<'_, E>) -> EngineResult<()> {
         Ok(())
     }
+
+    /// State saved to and restored from engine snapshots; `None` leaves the module out.
+    fn snapshot(&mut self) -> Option<&mut dyn Snapshot> {
+        None
+    }
 }
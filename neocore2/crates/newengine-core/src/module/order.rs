@@ -0,0 +1,145 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+/// Host phase reached once the platform window exists and `WinitWindowHandles` is installed.
+pub const HOST_PHASE_WINDOW: &str = "window";
+
+/// Parsed entry of `Module::dependencies()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Dependency<'a> {
+    /// `"<module id>"`: the module must be registered and is initialized first.
+    Module(&'a str),
+    /// `"after:<name>"`: initialized after module `<name>` if registered, otherwise `<name>`
+    /// must be a host phase the platform has already reached (e.g. `"after:window"`).
+    After(&'a str),
+}
+
+impl<'a> Dependency<'a> {
+    pub fn parse(s: &'a str) -> Self {
+        let s = s.trim();
+        match s.strip_prefix("after:") {
+            Some(name) => Dependency::After(name.trim()),
+            None => Dependency::Module(s),
+        }
+    }
+}
+
+/// Computes module init order from `(id, dependencies)` in registration order.
+///
+/// Modules without constraints between them keep their registration order.
+pub(crate) fn resolve_init_order(
+    modules: &[(&'static str, &'static [&'static str])],
+    host_phases: &HashSet<&'static str>,
+) -> Result<Vec<usize>, String> {
+    let n = modules.len();
+
+    let mut id_to_index: HashMap<&'static str, usize> = HashMap::with_capacity(n);
+    for (i, (id, _)) in modules.iter().enumerate() {
+        if id_to_index.insert(id, i).is_some() {
+            return Err(format!("duplicate module id: {id}"));
+        }
+    }
+
+    // deps[i]: modules that must be initialized before module i.
+    let mut deps: Vec<Vec<usize>> = vec![Vec::new(); n];
+
+    for (i, (id, list)) in modules.iter().enumerate() {
+        for raw in list.iter() {
+            match Dependency::parse(raw) {
+                Dependency::Module(dep) => {
+                    let Some(&dep_i) = id_to_index.get(dep) else {
+                        return Err(format!(
+                            "module '{id}' depends on '{dep}', which is not registered"
+                        ));
+                    };
+                    deps[i].push(dep_i);
+                }
+                Dependency::After(name) => {
+                    if let Some(&dep_i) = id_to_index.get(name) {
+                        deps[i].push(dep_i);
+                    } else if !host_phases.contains(name) {
+                        return Err(format!(
+                            "module '{id}' must start after '{name}', which is neither a registered \
+                             module nor a reached host phase (reached: {:?})",
+                            sorted_phases(host_phases)
+                        ));
+                    }
+                }
+            }
+        }
+
+        if deps[i].contains(&i) {
+            return Err(format!("module '{id}' depends on itself"));
+        }
+        deps[i].sort_unstable();
+        deps[i].dedup();
+    }
+
+    let mut indegree: Vec<usize> = deps.iter().map(|d| d.len()).collect();
+    let mut dependents: Vec<Vec<usize>> = vec![Vec::new(); n];
+    for (i, d) in deps.iter().enumerate() {
+        for &dep_i in d.iter() {
+            dependents[dep_i].push(i);
+        }
+    }
+
+    // Lowest registration index first keeps the order deterministic.
+    let mut ready: BTreeSet<usize> = (0..n).filter(|&i| indegree[i] == 0).collect();
+    let mut order: Vec<usize> = Vec::with_capacity(n);
+
+    while let Some(i) = ready.pop_first() {
+        order.push(i);
+        for &to in dependents[i].iter() {
+            indegree[to] -= 1;
+            if indegree[to] == 0 {
+                ready.insert(to);
+            }
+        }
+    }
+
+    if order.len() != n {
+        let cycle = find_cycle(&deps, &indegree)
+            .into_iter()
+            .map(|i| modules[i].0)
+            .collect::<Vec<_>>()
+            .join(" -> ");
+        return Err(format!("module dependency cycle: {cycle}"));
+    }
+
+    Ok(order)
+}
+
+/// Walks unresolved dependencies until a module repeats.
+///
+/// Every module left with a non-zero indegree has at least one unresolved dependency, so
+/// the walk cannot dead-end.
+fn find_cycle(deps: &[Vec<usize>], indegree: &[usize]) -> Vec<usize> {
+    let Some(start) = indegree.iter().position(|&d| d != 0) else {
+        return Vec::new();
+    };
+
+    let mut path: Vec<usize> = vec![start];
+    let mut cur = start;
+
+    loop {
+        let Some(&next) = deps[cur].iter().find(|&&d| indegree[d] != 0) else {
+            return path;
+        };
+
+        if let Some(pos) = path.iter().position(|&p| p == next) {
+            let mut cycle = path.split_off(pos);
+            cycle.push(next);
+            // `path` follows "depends on" edges; report in init order.
+            cycle.reverse();
+            return cycle;
+        }
+
+        path.push(next);
+        cur = next;
+    }
+}
+
+fn sorted_phases(phases: &HashSet<&'static str>) -> Vec<&'static str> {
+    let mut v: Vec<&'static str> = phases.iter().copied().collect();
+    v.sort_unstable();
+    v
+}
@@ -1,8 +1,42 @@
 use crate::error::{EngineError, EngineResult};
-
+use crate::reflect::component_for_type;
+use serde_json::Value;
 use std::any::{Any, TypeId};
 use std::collections::HashMap;
 
+/// Stored value plus diagnostics metadata.
+struct Entry {
+    value: Box<dyn Any>,
+    type_id: TypeId,
+    type_name: &'static str,
+    owner: Option<&'static str>,
+}
+
+impl Entry {
+    #[inline]
+    fn new<T: Any + 'static>(value: T, owner: Option<&'static str>) -> Self {
+        Self {
+            value: Box::new(value),
+            type_id: TypeId::of::<T>(),
+            type_name: std::any::type_name::<T>(),
+            owner,
+        }
+    }
+}
+
+/// One row of [`Resources::snapshot`].
+#[derive(Debug, Clone)]
+pub struct ResourceInfo {
+    /// `Some(id)` for named APIs, `None` for typed resources.
+    pub api_id: Option<&'static str>,
+    pub type_name: &'static str,
+    /// Module that inserted the entry; `None` when inserted by the host (outside module stages).
+    pub owner: Option<&'static str>,
+    /// Serialized value when the type is registered in the reflection registry
+    /// ([`crate::reflect::register_component`]).
+    pub value: Option<Value>,
+}
+
 /// Type-safe storage for engine-local resources and module APIs.
 ///
 /// This container is **engine-thread local** by design.
@@ -13,8 +47,10 @@ use std::collections::HashMap;
 /// Use explicit thread-safe APIs (Arc/Mutex/etc.) for cross-thread communication.
 #[derive(Default)]
 pub struct Resources {
-    typed: HashMap<TypeId, Box<dyn Any>>,
-    apis: HashMap<&'static str, Box<dyn Any>>,
+    typed: HashMap<TypeId, Entry>,
+    apis: HashMap<&'static str, Entry>,
+    /// Module currently running a stage; recorded as the owner of inserted entries.
+    owner: Option<&'static str>,
 }
 
 impl Resources {
@@ -27,7 +63,8 @@ impl Resources {
     where
         T: Any + 'static,
     {
-        self.typed.insert(TypeId::of::<T>(), Box::new(value));
+        self.typed
+            .insert(TypeId::of::<T>(), Entry::new(value, self.owner));
     }
 
     #[inline]
@@ -39,7 +76,7 @@ impl Resources {
         if self.typed.contains_key(&k) {
             return Err(EngineError::Other("resource already exists".to_string()));
         }
-        self.typed.insert(k, Box::new(value));
+        self.typed.insert(k, Entry::new(value, self.owner));
         Ok(())
     }
 
@@ -50,7 +87,7 @@ impl Resources {
     {
         self.typed
             .get(&TypeId::of::<T>())
-            .and_then(|v| v.downcast_ref::<T>())
+            .and_then(|e| e.value.downcast_ref::<T>())
     }
 
     #[inline]
@@ -60,7 +97,7 @@ impl Resources {
     {
         self.typed
             .get_mut(&TypeId::of::<T>())
-            .and_then(|v| v.downcast_mut::<T>())
+            .and_then(|e| e.value.downcast_mut::<T>())
     }
 
     #[inline]
@@ -79,7 +116,7 @@ impl Resources {
     {
         self.typed
             .remove(&TypeId::of::<T>())
-            .and_then(|v| v.downcast::<T>().ok())
+            .and_then(|e| e.value.downcast::<T>().ok())
             .map(|b| *b)
     }
 
@@ -104,7 +141,7 @@ impl Resources {
         if self.apis.contains_key(id) {
             return Err(EngineError::Other(format!("api already registered: {id}")));
         }
-        self.apis.insert(id, Box::new(api));
+        self.apis.insert(id, Entry::new(api, self.owner));
         Ok(())
     }
 
@@ -113,7 +150,7 @@ impl Resources {
     where
         T: Any + 'static,
     {
-        self.apis.get(id).and_then(|v| v.downcast_ref::<T>())
+        self.apis.get(id).and_then(|e| e.value.downcast_ref::<T>())
     }
 
     #[inline]
@@ -121,7 +158,9 @@ impl Resources {
     where
         T: Any + 'static,
     {
-        self.apis.get_mut(id).and_then(|v| v.downcast_mut::<T>())
+        self.apis
+            .get_mut(id)
+            .and_then(|e| e.value.downcast_mut::<T>())
     }
 
     #[inline]
@@ -145,7 +184,43 @@ impl Resources {
     {
         self.apis
             .remove(id)
-            .and_then(|v| v.downcast::<T>().ok())
+            .and_then(|e| e.value.downcast::<T>().ok())
             .map(|b| *b)
     }
+
+    /* ============================
+    Diagnostics
+    ============================ */
+
+    #[inline]
+    pub(crate) fn set_owner(&mut self, owner: Option<&'static str>) {
+        self.owner = owner;
+    }
+
+    /// Lists every typed resource and named API, sorted (APIs first, then by type name).
+    pub fn snapshot(&self) -> Vec<ResourceInfo> {
+        let render = |e: &Entry| {
+            component_for_type(e.type_id).and_then(|c| c.serialize(e.value.as_ref()).ok())
+        };
+
+        let mut out: Vec<ResourceInfo> = self
+            .apis
+            .iter()
+            .map(|(id, e)| ResourceInfo {
+                api_id: Some(*id),
+                type_name: e.type_name,
+                owner: e.owner,
+                value: render(e),
+            })
+            .chain(self.typed.values().map(|e| ResourceInfo {
+                api_id: None,
+                type_name: e.type_name,
+                owner: e.owner,
+                value: render(e),
+            }))
+            .collect();
+
+        out.sort_by_key(|r| (r.api_id.is_none(), r.api_id, r.type_name));
+        out
+    }
 }
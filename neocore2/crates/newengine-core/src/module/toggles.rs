@@ -0,0 +1,92 @@
+use crate::module::Dependency;
+
+use serde::Serialize;
+use std::sync::{Mutex, OnceLock};
+
+/// Registered module as seen by `module.list`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ModuleState {
+    pub id: &'static str,
+    pub enabled: bool,
+    pub dependencies: &'static [&'static str],
+}
+
+/// Process-wide so console services can flip modules without a handle to the `Engine`.
+static MODULES: OnceLock<Mutex<Vec<ModuleState>>> = OnceLock::new();
+
+#[inline]
+fn state() -> &'static Mutex<Vec<ModuleState>> {
+    MODULES.get_or_init(|| Mutex::new(Vec::new()))
+}
+
+/// Called by `Engine::register_module`. Re-registering an id keeps its enabled flag.
+pub(crate) fn track_module(id: &'static str, dependencies: &'static [&'static str]) {
+    let Ok(mut g) = state().lock() else {
+        return;
+    };
+
+    match g.iter_mut().find(|m| m.id == id) {
+        Some(m) => m.dependencies = dependencies,
+        None => g.push(ModuleState {
+            id,
+            enabled: true,
+            dependencies,
+        }),
+    }
+}
+
+/// Disabled modules keep their resources but skip `fixed_update`, `update` and `render`.
+#[inline]
+pub fn module_enabled(id: &str) -> bool {
+    state()
+        .lock()
+        .map(|g| g.iter().find(|m| m.id == id).is_none_or(|m| m.enabled))
+        .unwrap_or(true)
+}
+
+/// Enables or disables a registered module; takes effect on the next stage.
+///
+/// Returns enabled modules that declare a dependency on `id` so callers can warn.
+pub fn set_module_enabled(id: &str, enabled: bool) -> Result<Vec<&'static str>, String> {
+    let mut g = state()
+        .lock()
+        .map_err(|_| "module toggles mutex poisoned".to_string())?;
+
+    let Some(m) = g.iter_mut().find(|m| m.id == id) else {
+        return Err(format!("unknown module: '{id}'"));
+    };
+
+    if m.enabled != enabled {
+        m.enabled = enabled;
+        log::info!(
+            "modules: '{id}' {}",
+            if enabled { "enabled" } else { "disabled" }
+        );
+    }
+
+    if enabled {
+        return Ok(Vec::new());
+    }
+
+    let dependents: Vec<&'static str> = g
+        .iter()
+        .filter(|m| m.enabled)
+        .filter(|m| {
+            m.dependencies.iter().any(|d| match Dependency::parse(d) {
+                Dependency::Module(dep) | Dependency::After(dep) => dep == id,
+            })
+        })
+        .map(|m| m.id)
+        .collect();
+
+    if !dependents.is_empty() {
+        log::warn!("modules: '{id}' disabled while {dependents:?} depend on it");
+    }
+
+    Ok(dependents)
+}
+
+/// Registered modules in registration order.
+pub fn module_states() -> Vec<ModuleState> {
+    state().lock().map(|g| g.clone()).unwrap_or_default()
+}
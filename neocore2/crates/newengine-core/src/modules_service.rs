@@ -0,0 +1,136 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use crate::module::{module_enabled, module_states, set_module_enabled, ModuleState};
+use crate::plugins::host_api;
+use abi_stable::std_types::{RResult, RString};
+use newengine_plugin_api::{
+    guarded_service, Blob, CapabilityId, MethodName, ServiceV1, ServiceV1Dyn,
+};
+use serde::Serialize;
+use serde_json::json;
+
+pub const MODULES_SERVICE_ID: &str = "engine.modules";
+
+pub mod method {
+    pub const LIST_JSON: &str = "module.list_json";
+    pub const TOGGLE: &str = "module.toggle";
+}
+
+#[derive(Debug, Serialize)]
+struct ModuleListResp {
+    modules: Vec<ModuleState>,
+}
+
+#[derive(Debug, Serialize)]
+struct ModuleToggleResp {
+    ok: bool,
+    id: String,
+    enabled: bool,
+    /// Enabled modules that declare a dependency on the one just disabled.
+    dependents: Vec<&'static str>,
+    error: Option<String>,
+}
+
+struct ModulesService;
+
+impl ModulesService {
+    /// Payload: `<id> [on|off|toggle]`; the state flips when omitted.
+    fn toggle(arg: &str) -> ModuleToggleResp {
+        let mut it = arg.split_whitespace();
+        let id = it.next().unwrap_or_default().to_string();
+        let current = module_enabled(&id);
+
+        let enabled = match it.next().map(|s| s.to_ascii_lowercase()).as_deref() {
+            None | Some("toggle") => Ok(!current),
+            Some("on" | "enable" | "true" | "1") => Ok(true),
+            Some("off" | "disable" | "false" | "0") => Ok(false),
+            Some(other) => Err(format!("expected on|off|toggle, got '{other}'")),
+        };
+
+        let res = if id.is_empty() {
+            Err("usage: module.toggle <id> [on|off]".to_string())
+        } else {
+            enabled.and_then(|e| set_module_enabled(&id, e).map(|deps| (e, deps)))
+        };
+
+        match res {
+            Ok((enabled, dependents)) => ModuleToggleResp {
+                ok: true,
+                id,
+                enabled,
+                dependents,
+                error: None,
+            },
+            Err(e) => ModuleToggleResp {
+                ok: false,
+                id,
+                enabled: current,
+                dependents: Vec::new(),
+                error: Some(e),
+            },
+        }
+    }
+}
+
+impl ServiceV1 for ModulesService {
+    fn id(&self) -> CapabilityId {
+        RString::from(MODULES_SERVICE_ID)
+    }
+
+    fn describe(&self) -> RString {
+        let d = json!({
+          "id": MODULES_SERVICE_ID,
+          "version": 1,
+          "methods": [
+            { "name": method::LIST_JSON, "payload": "empty", "returns": "json ModuleListResp" },
+            { "name": method::TOGGLE, "payload": "utf8 '<id> [on|off|toggle]'", "returns": "json ModuleToggleResp" }
+          ],
+          "console": {
+            "commands": [
+              {
+                "name": "module.list",
+                "help": "List engine modules and whether they are enabled",
+                "kind": "service_call",
+                "service_id": MODULES_SERVICE_ID,
+                "method": method::LIST_JSON,
+                "payload": "empty"
+              },
+              {
+                "name": "module.toggle",
+                "help": "Enable/disable a module's update and render: module.toggle <id> [on|off]",
+                "usage": "module.toggle <id> [on|off]",
+                "kind": "service_call",
+                "service_id": MODULES_SERVICE_ID,
+                "method": method::TOGGLE,
+                "payload": "raw"
+              }
+            ]
+          }
+        });
+
+        RString::from(d.to_string())
+    }
+
+    fn call(&self, method: MethodName, payload: Blob) -> RResult<Blob, RString> {
+        let m = method.to_string();
+
+        let resp = match m.as_str() {
+            method::LIST_JSON => serde_json::to_vec(&ModuleListResp {
+                modules: module_states(),
+            }),
+            method::TOGGLE => {
+                let arg = String::from_utf8_lossy(payload.as_slice());
+                serde_json::to_vec(&Self::toggle(&arg))
+            }
+            _ => return RResult::RErr(RString::from(format!("unknown method: {m}"))),
+        };
+
+        RResult::ROk(Blob::from(resp.unwrap_or_default()))
+    }
+}
+
+pub fn register_modules_service() {
+    let dyn_svc: ServiceV1Dyn<'static> = guarded_service(ModulesService);
+
+    let _ = host_api::host_register_service_impl(dyn_svc, false);
+}
@@ -0,0 +1,98 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use crate::plugins::event_router;
+use crate::plugins::host_context::{ctx, current_plugin_id};
+use crate::topics::TopicPattern;
+use abi_stable::std_types::{ROption, RResult, RString};
+use newengine_assets::{AssetEvent, AssetId, AssetState};
+use newengine_plugin_api::{
+    AssetApiV1, AssetApiV1Dyn, AssetBlobV1, AssetIdV1, AssetStateV1, Blob, EventSinkV1Dyn,
+};
+use serde_json::{json, Value};
+
+/// Asset store events are published as `asset.ready`, `asset.reloaded`, `asset.failed` and
+/// `asset.cancelled`.
+pub const ASSET_EVENT_TOPIC_PREFIX: &str = "asset.";
+
+/// `AssetApiV1` over the host context's asset store.
+struct HostAssetApi;
+
+impl AssetApiV1 for HostAssetApi {
+    fn load(&self, logical_path: RString) -> RResult<AssetIdV1, RString> {
+        let path = logical_path.trim();
+        if path.is_empty() {
+            return RResult::RErr(RString::from("empty path"));
+        }
+        match ctx().asset_store.load_path(path) {
+            Ok(id) => RResult::ROk(AssetIdV1::from_u128(id.to_u128())),
+            Err(e) => RResult::RErr(RString::from(e.to_string())),
+        }
+    }
+
+    fn state(&self, id: AssetIdV1) -> AssetStateV1 {
+        match ctx().asset_store.state(AssetId::from_u128(id.to_u128())) {
+            AssetState::Unloaded => AssetStateV1::Unloaded,
+            AssetState::Loading => AssetStateV1::Loading,
+            AssetState::Ready => AssetStateV1::Ready,
+            AssetState::Failed(e) => AssetStateV1::Failed(RString::from(&*e)),
+        }
+    }
+
+    fn get_blob(&self, id: AssetIdV1) -> ROption<AssetBlobV1> {
+        ctx()
+            .asset_store
+            .get_blob(AssetId::from_u128(id.to_u128()))
+            .map(|b| AssetBlobV1 {
+                type_id: RString::from(&*b.type_id),
+                format: RString::from(&*b.format),
+                meta_json: RString::from(&*b.meta_json),
+                payload: Blob::from(b.payload.clone()),
+            })
+            .into()
+    }
+
+    fn subscribe(&self, sink: EventSinkV1Dyn<'static>) -> RResult<(), RString> {
+        let pattern = TopicPattern::parse(&format!("{ASSET_EVENT_TOPIC_PREFIX}*"));
+        event_router::subscribe(current_plugin_id(), pattern, sink)
+            .map_err(RString::from)
+            .into()
+    }
+}
+
+pub(crate) extern "C" fn host_asset_api_v1() -> ROption<AssetApiV1Dyn<'static>> {
+    ROption::RSome(AssetApiV1Dyn::from_value(
+        HostAssetApi,
+        abi_stable::sabi_trait::TD_Opaque,
+    ))
+}
+
+/// Topic and JSON payload `ev` is published as; `None` for progress and group events, which
+/// stay host-side.
+pub fn asset_event_topic(ev: &AssetEvent) -> Option<(String, Value)> {
+    let hex = |id: AssetId| format!("{:032x}", id.to_u128());
+    let (kind, payload) = match ev {
+        AssetEvent::Ready {
+            id,
+            type_id,
+            format,
+        } => (
+            "ready",
+            json!({ "id": hex(*id), "type_id": &**type_id, "format": &**format }),
+        ),
+        AssetEvent::Reloaded {
+            id,
+            type_id,
+            format,
+        } => (
+            "reloaded",
+            json!({ "id": hex(*id), "type_id": &**type_id, "format": &**format }),
+        ),
+        AssetEvent::Failed { id, type_id, error } => (
+            "failed",
+            json!({ "id": hex(*id), "type_id": &**type_id, "error": &**error }),
+        ),
+        AssetEvent::Cancelled { id } => ("cancelled", json!({ "id": hex(*id) })),
+        AssetEvent::Progress { .. } | AssetEvent::GroupCompleted { .. } => return None,
+    };
+    Some((format!("{ASSET_EVENT_TOPIC_PREFIX}{kind}"), payload))
+}
@@ -4,6 +4,9 @@ use serde::Deserialize;
 
 #[derive(Debug, Deserialize)]
 pub(crate) struct ServiceDescribe {
+    /// Interface version; 1 when absent.
+    #[serde(default)]
+    pub version: Option<u32>,
     #[serde(default)]
     pub kind: Option<String>,
     #[serde(default)]
@@ -20,6 +23,12 @@ pub(crate) struct AssetImporterDesc {
     pub priority: Option<i32>,
     #[serde(default)]
     pub wire: Option<String>,
+    /// Method taking `[u32 settings_len_le][settings_json][bytes]` for per-asset settings.
+    #[serde(default)]
+    pub settings_method: Option<String>,
+    /// Settings written to a new `.meta` sidecar; must be a JSON object.
+    #[serde(default)]
+    pub default_settings: Option<serde_json::Value>,
 }
 
 #[inline]
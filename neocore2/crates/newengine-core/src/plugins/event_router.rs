@@ -0,0 +1,309 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use crate::topics::{TopicEvent, TopicPattern, TopicRouter};
+
+use abi_stable::std_types::RString;
+use newengine_plugin_api::{Blob, EventSinkV1Dyn};
+use serde::Serialize;
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::{Arc, Mutex, OnceLock, Weak};
+
+/// Limits enforced by the plugin event router (`emit_event_v1`).
+#[derive(Debug, Clone)]
+pub struct EventLimits {
+    /// Max events queued between two dispatches; further emits are rejected. 0 disables the cap.
+    pub max_pending: usize,
+    /// Max payload per event in bytes. 0 disables the cap.
+    pub max_payload_bytes: usize,
+}
+
+impl EventLimits {
+    #[inline]
+    pub fn unlimited() -> Self {
+        Self {
+            max_pending: 0,
+            max_payload_bytes: 0,
+        }
+    }
+
+    #[inline]
+    pub fn with_max_pending(mut self, events: usize) -> Self {
+        self.max_pending = events;
+        self
+    }
+
+    #[inline]
+    pub fn with_max_payload_bytes(mut self, bytes: usize) -> Self {
+        self.max_payload_bytes = bytes;
+        self
+    }
+}
+
+impl Default for EventLimits {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            // Input alone emits a few hundred events per frame under heavy mouse movement.
+            max_pending: 8192,
+            max_payload_bytes: 1024 * 1024,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TopicCounters {
+    pub emitted: u64,
+    pub delivered: u64,
+    pub dropped: u64,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EventSinkInfo {
+    pub owner: Option<String>,
+    pub pattern: String,
+    pub delivered: u64,
+}
+
+/// Snapshot for diagnostics (`events.stats`).
+#[derive(Debug, Clone, Serialize)]
+pub struct EventRouterStats {
+    pub dispatches: u64,
+    pub pending: usize,
+    pub pending_peak: usize,
+    pub max_pending: usize,
+    pub max_payload_bytes: usize,
+    pub sinks: Vec<EventSinkInfo>,
+    pub topics: BTreeMap<String, TopicCounters>,
+}
+
+struct Pending {
+    ev: TopicEvent,
+    /// Also deliver to host `EventHub` topic subscribers (plugin-originated events).
+    to_host: bool,
+}
+
+#[derive(Clone)]
+struct Sink {
+    id: u64,
+    owner_plugin_id: Option<String>,
+    pattern: TopicPattern,
+    sink: Arc<Mutex<EventSinkV1Dyn<'static>>>,
+}
+
+#[derive(Default)]
+struct RouterState {
+    limits: EventLimits,
+    next_sink_id: u64,
+    sinks: Vec<Sink>,
+    sink_delivered: BTreeMap<u64, u64>,
+    queue: VecDeque<Pending>,
+    pending_peak: usize,
+    dispatches: u64,
+    topics: BTreeMap<String, TopicCounters>,
+    host_topics: Weak<TopicRouter>,
+}
+
+static ROUTER: OnceLock<Mutex<RouterState>> = OnceLock::new();
+
+#[inline]
+fn state() -> &'static Mutex<RouterState> {
+    ROUTER.get_or_init(|| Mutex::new(RouterState::default()))
+}
+
+/// Replaces event limits (process-wide). Already queued events are kept.
+pub fn set_event_limits(limits: EventLimits) {
+    log::info!(
+        "events.limits max_pending={} max_payload_bytes={}",
+        limits.max_pending,
+        limits.max_payload_bytes
+    );
+
+    if let Ok(mut g) = state().lock() {
+        g.limits = limits;
+    }
+}
+
+#[inline]
+pub fn event_limits() -> EventLimits {
+    state().lock().map(|g| g.limits.clone()).unwrap_or_default()
+}
+
+/// Routes plugin-originated events to the given host topic router as well.
+pub(crate) fn attach_host_topics(router: Weak<TopicRouter>) {
+    if let Ok(mut g) = state().lock() {
+        g.host_topics = router;
+    }
+}
+
+pub(crate) fn subscribe(
+    owner_plugin_id: Option<String>,
+    pattern: TopicPattern,
+    sink: EventSinkV1Dyn<'static>,
+) -> Result<(), String> {
+    let mut g = state()
+        .lock()
+        .map_err(|_| "event router mutex poisoned".to_string())?;
+
+    g.next_sink_id += 1;
+    let id = g.next_sink_id;
+
+    log::info!(
+        "events.subscribe owner='{}' pattern='{}'",
+        owner_plugin_id.as_deref().unwrap_or(super::HOST_CALLER_ID),
+        pattern
+    );
+
+    g.sinks.push(Sink {
+        id,
+        owner_plugin_id,
+        pattern,
+        sink: Arc::new(Mutex::new(sink)),
+    });
+    Ok(())
+}
+
+/// Queues an event for the next dispatch.
+///
+/// `to_host` is false for events published through `EventHub::publish_topic`, whose host
+/// subscribers were already served synchronously.
+pub(crate) fn enqueue(topic: &str, payload: &[u8], to_host: bool) -> Result<(), String> {
+    let mut g = state()
+        .lock()
+        .map_err(|_| "event router mutex poisoned".to_string())?;
+
+    let max_payload = g.limits.max_payload_bytes;
+    let max_pending = g.limits.max_pending;
+    let pending = g.queue.len();
+
+    let reject = if max_payload != 0 && payload.len() > max_payload {
+        Some(format!(
+            "event payload too large for topic '{topic}': {} bytes (limit {max_payload})",
+            payload.len()
+        ))
+    } else if max_pending != 0 && pending >= max_pending {
+        Some(format!(
+            "event queue full: {pending} pending (limit {max_pending}), topic '{topic}' dropped"
+        ))
+    } else {
+        None
+    };
+
+    let counters = g.topics.entry(topic.to_string()).or_default();
+    if let Some(e) = reject {
+        counters.dropped += 1;
+        // Log the first drop per topic and then every 1024th; a stuck queue drops every emit.
+        if counters.dropped % 1024 == 1 {
+            log::warn!("events.reject {e} dropped_total={}", counters.dropped);
+        }
+        return Err(e);
+    }
+
+    counters.emitted += 1;
+    counters.bytes += payload.len() as u64;
+
+    g.queue.push_back(Pending {
+        ev: TopicEvent::new(topic, payload),
+        to_host,
+    });
+    g.pending_peak = g.pending_peak.max(g.queue.len());
+    Ok(())
+}
+
+/// Delivers everything queued so far; returns the number of events dispatched.
+///
+/// Sinks run without the router lock held, so they may emit; those events are queued
+/// for the next dispatch rather than delivered recursively.
+pub fn dispatch() -> usize {
+    let (batch, sinks, host_topics) = {
+        let Ok(mut g) = state().lock() else {
+            return 0;
+        };
+        if g.queue.is_empty() {
+            return 0;
+        }
+        g.dispatches += 1;
+        let batch: Vec<Pending> = g.queue.drain(..).collect();
+        (batch, g.sinks.clone(), g.host_topics.upgrade())
+    };
+
+    let mut per_topic: BTreeMap<String, u64> = BTreeMap::new();
+    let mut per_sink: BTreeMap<u64, u64> = BTreeMap::new();
+
+    for p in batch.iter() {
+        let topic = p.ev.topic();
+        let mut n = 0u64;
+
+        for s in sinks.iter().filter(|s| s.pattern.matches(topic)) {
+            let Ok(mut guard) = s.sink.lock() else {
+                continue;
+            };
+            guard.on_event(RString::from(topic), Blob::from(p.ev.payload().to_vec()));
+            *per_sink.entry(s.id).or_default() += 1;
+            n += 1;
+        }
+
+        if p.to_host {
+            if let Some(router) = host_topics.as_ref() {
+                router.deliver(&p.ev);
+            }
+        }
+
+        if n != 0 {
+            *per_topic.entry(topic.to_string()).or_default() += n;
+        }
+    }
+
+    if let Ok(mut g) = state().lock() {
+        for (topic, n) in per_topic {
+            g.topics.entry(topic).or_default().delivered += n;
+        }
+        for (id, n) in per_sink {
+            *g.sink_delivered.entry(id).or_default() += n;
+        }
+    }
+
+    batch.len()
+}
+
+/// Drops sinks registered by an unloaded plugin.
+pub(crate) fn remove_owner(plugin_id: &str) {
+    if let Ok(mut g) = state().lock() {
+        let RouterState {
+            sinks,
+            sink_delivered,
+            ..
+        } = &mut *g;
+        sinks.retain(|s| {
+            let keep = s.owner_plugin_id.as_deref() != Some(plugin_id);
+            if !keep {
+                sink_delivered.remove(&s.id);
+            }
+            keep
+        });
+    }
+}
+
+pub fn event_router_stats() -> Option<EventRouterStats> {
+    let g = state().lock().ok()?;
+
+    let sinks = g
+        .sinks
+        .iter()
+        .map(|s| EventSinkInfo {
+            owner: s.owner_plugin_id.clone(),
+            pattern: s.pattern.to_string(),
+            delivered: g.sink_delivered.get(&s.id).copied().unwrap_or_default(),
+        })
+        .collect();
+
+    Some(EventRouterStats {
+        dispatches: g.dispatches,
+        pending: g.queue.len(),
+        pending_peak: g.pending_peak,
+        max_pending: g.limits.max_pending,
+        max_payload_bytes: g.limits.max_payload_bytes,
+        sinks,
+        topics: g.topics.clone(),
+    })
+}
@@ -1,15 +1,81 @@
 #![forbid(unsafe_op_in_unsafe_fn)]
 
-use crate::plugins::describe::is_asset_importer;
-use crate::plugins::host_context::{ctx, ServiceEntry};
 #[cfg(feature = "runtime")]
-use crate::plugins::importer::try_auto_register_importer;
+use crate::plugins::asset_api::host_asset_api_v1;
+use crate::plugins::describe::{is_asset_importer, parse_describe};
+use crate::jobs::JobSystem;
+use crate::plugins::host_context::{ctx, resolve_service, with_current_plugin_id, ServiceEntry};
+use crate::plugins::watchdog;
+#[cfg(feature = "runtime")]
+use crate::plugins::importer::{import_cancelled, try_auto_register_importer};
+#[cfg(not(feature = "runtime"))]
+use abi_stable::std_types::ROption;
 use abi_stable::std_types::{RResult, RString};
+#[cfg(not(feature = "runtime"))]
+use newengine_plugin_api::AssetApiV1Dyn;
 use newengine_plugin_api::{
-    Blob, CapabilityId, EventSinkV1Dyn, HostApiV1, MethodName, ServiceV1Dyn,
+    Blob, CallOutcomeV1, CapabilityId, EventSinkV1Dyn, FrameInfoAbi, HostApiV1, JobV1Dyn,
+    MethodName, ServiceRef, ServiceV1Dyn, VersionReq,
 };
 use std::cell::Cell;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// `HostApiV1` capabilities plugins can list in `PluginInfo::requires`, with their versions.
+/// `host.services` 2 added versioned lookups (`id@>=N`).
+pub const HOST_CAPABILITIES: &[(&str, u32)] = &[
+    ("host.log", 1),
+    ("host.services", 2),
+    ("host.events", 1),
+    ("host.clipboard", 1),
+    ("host.cursor", 1),
+    ("host.assets", 1),
+    ("host.frame", 1),
+    ("host.jobs", 1),
+    ("host.import", 1),
+];
+
+/// Prefix of `PluginInfo::requires` entries naming host capabilities rather than services.
+pub(crate) const HOST_CAPABILITY_PREFIX: &str = "host.";
+
+/// Checks one `PluginInfo::requires` entry against the host capabilities or, for anything
+/// outside `host.*`, the services registered right now.
+pub(crate) fn check_requirement(requirement: &str) -> Result<(), String> {
+    let r = ServiceRef::parse(requirement)?;
+
+    if r.id.starts_with(HOST_CAPABILITY_PREFIX) {
+        let Some(&(_, version)) = HOST_CAPABILITIES.iter().find(|(id, _)| *id == r.id) else {
+            return Err(format!("host capability not provided: {}", r.id));
+        };
+        if !r.req.matches(version) {
+            return Err(format!(
+                "host capability '{}' is version {version}, required '{r}'",
+                r.id
+            ));
+        }
+        return Ok(());
+    }
+
+    let c = ctx();
+    let g = c
+        .services
+        .lock()
+        .map_err(|_| "services mutex poisoned".to_string())?;
+    resolve_service(&g, requirement).map(|_| ())
+}
+
+/// Interface version of a service being registered: from an `id@N` id, else from the
+/// describe `"version"` field, else 1.
+fn service_version(service_id: &str, describe_json: &str) -> Result<u32, String> {
+    match ServiceRef::parse(service_id)?.req {
+        VersionReq::Exact(v) => Ok(v),
+        VersionReq::AtLeast(_) => Err(format!(
+            "service id cannot carry a version range: {service_id}"
+        )),
+        VersionReq::Any => Ok(parse_describe(describe_json)
+            .and_then(|d| d.version)
+            .unwrap_or(1)),
+    }
+}
 
 pub(crate) struct ImporterLoadState {
     pub saw_importer: bool,
@@ -52,6 +118,10 @@ pub(crate) fn host_register_service_impl(
     let service_id = svc.id().to_string();
     let describe_json = svc.describe().to_string();
     let owner = crate::plugins::host_context::current_plugin_id();
+    let version = match service_version(&service_id, &describe_json) {
+        Ok(v) => v,
+        Err(e) => return RResult::RErr(RString::from(e)),
+    };
 
     let c = ctx();
 
@@ -74,6 +144,7 @@ pub(crate) fn host_register_service_impl(
                 owner_plugin_id: owner,
                 service: Arc::from(svc),
                 describe_json: describe_json.clone(),
+                version,
             },
         );
         crate::plugins::host_context::bump_services_generation();
@@ -119,22 +190,50 @@ pub(crate) extern "C" fn call_service_v1(
     method: MethodName,
     payload: Blob,
 ) -> RResult<Blob, RString> {
-    let id = cap_id.to_string();
+    let reference = cap_id.to_string();
+
     let c = ctx();
 
-    let svc = {
+    let (id, svc, owner) = {
         let g = match c.services.lock() {
             Ok(v) => v,
             Err(_) => return RResult::RErr(RString::from("services mutex poisoned")),
         };
 
-        match g.get(&id) {
-            Some(v) => v.service.clone(),
-            None => return RResult::RErr(RString::from(format!("service not found: {id}"))),
+        match resolve_service(&g, &reference) {
+            Ok((id, v)) => (id.to_string(), v.service.clone(), v.owner_plugin_id.clone()),
+            Err(e) => return RResult::RErr(RString::from(e)),
         }
     };
 
-    svc.call(method, payload)
+    if let Some(owner) = owner.as_deref().filter(|o| watchdog::is_disabled(o)) {
+        return RResult::RErr(RString::from(format!(
+            "service '{id}' unavailable: plugin '{owner}' is disabled"
+        )));
+    }
+
+    let caller = crate::plugins::host_context::current_plugin_id();
+    if let Err(e) = crate::plugins::limits::check_call(&id, caller.as_deref(), payload.len()) {
+        return RResult::RErr(RString::from(e));
+    }
+
+    let _scope = crate::telemetry::scope_with("service", || format!("{id}::{method}"));
+    let method_name = method.to_string();
+    // Services built with `guarded_service` flag a caught panic; an unguarded one aborts in
+    // the ABI shim before it could be caught here.
+    let CallOutcomeV1 { result, panicked } = svc.call_checked(method, payload);
+    if panicked {
+        log::error!("services: panic in '{}::{}'", id, method_name);
+    }
+    if let Some(owner) = owner {
+        if panicked {
+            let what = format!("service {id}::{method_name}");
+            watchdog::record_fault(&owner, &what, "panic");
+        } else {
+            watchdog::record_ok(&owner);
+        }
+    }
+    result
 }
 
 extern "C" fn host_emit_event_v1(topic: RString, payload: Blob) -> RResult<(), RString> {
@@ -151,6 +250,128 @@ extern "C" fn host_subscribe_events_v1(sink: EventSinkV1Dyn<'static>) -> RResult
     }
 }
 
+extern "C" fn host_subscribe_topic_v1(
+    pattern: RString,
+    sink: EventSinkV1Dyn<'static>,
+) -> RResult<(), RString> {
+    match crate::plugins::host_context::subscribe_event_sink_topic(pattern.as_str(), sink) {
+        Ok(()) => RResult::ROk(()),
+        Err(e) => RResult::RErr(RString::from(e)),
+    }
+}
+
+extern "C" fn host_clipboard_get() -> RResult<RString, RString> {
+    match crate::clipboard::clipboard_get() {
+        Ok(text) => RResult::ROk(RString::from(text)),
+        Err(e) => RResult::RErr(RString::from(e)),
+    }
+}
+
+extern "C" fn host_clipboard_set(text: RString) -> RResult<(), RString> {
+    match crate::clipboard::clipboard_set(text.as_str()) {
+        Ok(()) => RResult::ROk(()),
+        Err(e) => RResult::RErr(RString::from(e)),
+    }
+}
+
+extern "C" fn host_set_cursor_icon(name: RString) -> RResult<(), RString> {
+    match crate::window::CursorIcon::parse(name.as_str()) {
+        Some(icon) => {
+            crate::window::window_api().set_cursor_icon(icon);
+            RResult::ROk(())
+        }
+        None => RResult::RErr(RString::from(format!("unknown cursor icon: {name}"))),
+    }
+}
+
+extern "C" fn host_set_cursor_visible(visible: bool) {
+    crate::window::window_api().set_cursor_visible(visible);
+}
+
+extern "C" fn host_set_cursor_grab(mode: RString) -> RResult<(), RString> {
+    match crate::window::CursorGrab::parse(mode.as_str()) {
+        Some(grab) => {
+            crate::window::window_api().set_cursor_grab(grab);
+            RResult::ROk(())
+        }
+        None => RResult::RErr(RString::from(format!("unknown cursor grab mode: {mode}"))),
+    }
+}
+
+/// Hosts without an asset store hand plugins no `AssetApiV1`.
+#[cfg(not(feature = "runtime"))]
+extern "C" fn host_asset_api_v1() -> ROption<AssetApiV1Dyn<'static>> {
+    ROption::RNone
+}
+
+static CURRENT_FRAME: OnceLock<Mutex<FrameInfoAbi>> = OnceLock::new();
+
+#[inline]
+fn current_frame() -> &'static Mutex<FrameInfoAbi> {
+    CURRENT_FRAME.get_or_init(|| Mutex::new(FrameInfoAbi::default()))
+}
+
+/// Makes `frame` what `frame_info_v1` returns until the next frame begins.
+pub(crate) fn set_current_frame(frame: FrameInfoAbi) {
+    if let Ok(mut g) = current_frame().lock() {
+        *g = frame;
+    }
+}
+
+/// Publishes the frame's interpolation factor once its fixed steps ran.
+pub(crate) fn set_current_fixed_alpha(alpha: f32) {
+    if let Ok(mut g) = current_frame().lock() {
+        g.fixed_alpha = alpha;
+    }
+}
+
+extern "C" fn host_frame_info_v1() -> FrameInfoAbi {
+    current_frame().lock().map(|g| *g).unwrap_or_default()
+}
+
+/// Runs the job under its plugin's id, so service calls are attributed and a failed job counts
+/// as a plugin fault. Panics only arrive as errors from jobs built with `guarded_job`; one
+/// unwinding out of an unguarded job aborts in the ABI shim.
+extern "C" fn host_submit_job(
+    mut job: JobV1Dyn<'static>,
+    frame_scoped: bool,
+) -> RResult<(), RString> {
+    let owner = crate::plugins::host_context::current_plugin_id();
+    let run = move || {
+        let Some(id) = owner else {
+            if let RResult::RErr(e) = job.run_checked().result {
+                log::error!("plugins: job failed err='{e}'");
+            }
+            return;
+        };
+        let CallOutcomeV1 { result, panicked } = with_current_plugin_id(&id, || job.run_checked());
+        if let RResult::RErr(e) = result {
+            log::error!("plugins: job failed for id='{id}' panicked={panicked} err='{e}'");
+            let reason = if panicked { "panic" } else { e.as_str() };
+            watchdog::record_fault(&id, "job", reason);
+        }
+    };
+
+    let jobs = JobSystem::global();
+    if frame_scoped {
+        jobs.spawn_frame(run);
+    } else {
+        jobs.spawn(run);
+    }
+    RResult::ROk(())
+}
+
+extern "C" fn host_import_cancelled() -> bool {
+    #[cfg(feature = "runtime")]
+    {
+        import_cancelled()
+    }
+    #[cfg(not(feature = "runtime"))]
+    {
+        false
+    }
+}
+
 pub fn default_host_api() -> HostApiV1 {
     HostApiV1 {
         log_info: host_log_info,
@@ -162,6 +383,21 @@ pub fn default_host_api() -> HostApiV1 {
 
         emit_event_v1: host_emit_event_v1,
         subscribe_events_v1: host_subscribe_events_v1,
+        subscribe_topic_v1: host_subscribe_topic_v1,
+
+        clipboard_get: host_clipboard_get,
+        clipboard_set: host_clipboard_set,
+
+        set_cursor_icon: host_set_cursor_icon,
+        set_cursor_visible: host_set_cursor_visible,
+        set_cursor_grab: host_set_cursor_grab,
+
+        asset_api_v1: host_asset_api_v1,
+
+        frame_info_v1: host_frame_info_v1,
+        submit_job: host_submit_job,
+
+        import_cancelled: host_import_cancelled,
     }
 }
 
@@ -176,5 +412,20 @@ pub fn importers_host_api() -> HostApiV1 {
 
         emit_event_v1: host_emit_event_v1,
         subscribe_events_v1: host_subscribe_events_v1,
+        subscribe_topic_v1: host_subscribe_topic_v1,
+
+        clipboard_get: host_clipboard_get,
+        clipboard_set: host_clipboard_set,
+
+        set_cursor_icon: host_set_cursor_icon,
+        set_cursor_visible: host_set_cursor_visible,
+        set_cursor_grab: host_set_cursor_grab,
+
+        asset_api_v1: host_asset_api_v1,
+
+        frame_info_v1: host_frame_info_v1,
+        submit_job: host_submit_job,
+
+        import_cancelled: host_import_cancelled,
     }
 }
\ No newline at end of file
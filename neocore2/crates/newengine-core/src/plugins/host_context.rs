@@ -3,24 +3,65 @@
 use abi_stable::std_types::RString;
 #[cfg(feature = "runtime")]
 use newengine_assets::AssetStore;
-use newengine_plugin_api::{Blob, EventSinkV1Dyn, ServiceV1Dyn};
+use newengine_plugin_api::{Blob, EventSinkV1Dyn, ServiceRef, ServiceV1Dyn};
 
 use std::cell::RefCell;
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex, OnceLock};
 
+use crate::plugins::event_router;
+use crate::topics::TopicPattern;
+
 #[derive(Clone)]
 pub struct ServiceEntry {
     pub owner_plugin_id: Option<String>,
     pub service: Arc<ServiceV1Dyn<'static>>,
     pub describe_json: String,
+    /// Interface version, from an `id@N` registration or the describe `"version"` field.
+    pub version: u32,
 }
 
-#[derive(Clone)]
-pub struct EventSinkEntry {
-    pub owner_plugin_id: Option<String>,
-    pub sink: Arc<Mutex<EventSinkV1Dyn<'static>>>,
+/// Provider for `reference`: a plain id, or one with a requirement (`id@2`, `id@>=2`).
+///
+/// An id registered exactly as given wins. Otherwise the highest matching version among
+/// services registered as `id` or `id@N` is used.
+pub fn resolve_service<'a>(
+    services: &'a HashMap<String, ServiceEntry>,
+    reference: &str,
+) -> Result<(&'a str, &'a ServiceEntry), String> {
+    if let Some((k, e)) = services.get_key_value(reference) {
+        return Ok((k.as_str(), e));
+    }
+
+    let r = ServiceRef::parse(reference)?;
+    let mut available = Vec::new();
+    let mut best: Option<(&'a str, &'a ServiceEntry)> = None;
+    for (k, e) in services {
+        let base = k.split_once('@').map_or(k.as_str(), |(b, _)| b);
+        if base != r.id {
+            continue;
+        }
+        available.push(e.version);
+        if !r.req.matches(e.version) {
+            continue;
+        }
+        match best {
+            Some((_, b)) if b.version >= e.version => {}
+            _ => best = Some((k.as_str(), e)),
+        }
+    }
+
+    if let Some(found) = best {
+        return Ok(found);
+    }
+    if available.is_empty() {
+        return Err(format!("service not found: {}", r.id));
+    }
+    available.sort_unstable();
+    Err(format!(
+        "no compatible provider for '{reference}' (registered versions: {available:?})"
+    ))
 }
 
 thread_local! {
@@ -46,8 +87,6 @@ pub struct HostContext {
     #[cfg(feature = "runtime")]
     pub(crate) asset_store: Arc<AssetStore>,
     services_generation: AtomicU64,
-
-    pub(crate) event_sinks: Mutex<Vec<EventSinkEntry>>,
 }
 
 static HOST_CTX: OnceLock<Arc<HostContext>> = OnceLock::new();
@@ -58,7 +97,6 @@ pub fn init_host_context(asset_store: Arc<AssetStore>) {
         services: Mutex::new(HashMap::new()),
         asset_store,
         services_generation: AtomicU64::new(1),
-        event_sinks: Mutex::new(Vec::new()),
     });
     let _ = HOST_CTX.set(ctx);
 }
@@ -68,7 +106,6 @@ pub fn init_host_context() {
     let ctx = Arc::new(HostContext {
         services: Mutex::new(HashMap::new()),
         services_generation: AtomicU64::new(1),
-        event_sinks: Mutex::new(Vec::new()),
     });
     let _ = HOST_CTX.set(ctx);
 }
@@ -88,38 +125,33 @@ pub fn bump_services_generation() {
     ctx().services_generation.fetch_add(1, Ordering::AcqRel);
 }
 
+/// Subscribes a plugin sink to every topic.
+#[inline]
 pub fn subscribe_event_sink(sink: EventSinkV1Dyn<'static>) -> Result<(), String> {
-    let c = ctx();
-    let mut g = c
-        .event_sinks
-        .lock()
-        .map_err(|_| "event_sinks mutex poisoned".to_string())?;
-    g.push(EventSinkEntry {
-        owner_plugin_id: current_plugin_id(),
-        sink: Arc::new(Mutex::new(sink)),
-    });
-    Ok(())
+    event_router::subscribe(current_plugin_id(), TopicPattern::Any, sink)
 }
 
-pub fn emit_plugin_event(topic: RString, payload: Blob) -> Result<(), String> {
-    let c = ctx();
-    let sinks = {
-        let g = c
-            .event_sinks
-            .lock()
-            .map_err(|_| "event_sinks mutex poisoned".to_string())?;
-        g.clone()
-    };
-
-    for s in sinks {
-        let mut guard = s
-            .sink
-            .lock()
-            .map_err(|_| "event sink mutex poisoned".to_string())?;
-        guard.on_event(topic.clone(), payload.clone());
+/// Subscribes a plugin sink to topics matching `pattern` (`winit.key`, `winit.*`, `*`).
+pub fn subscribe_event_sink_topic(
+    pattern: &str,
+    sink: EventSinkV1Dyn<'static>,
+) -> Result<(), String> {
+    if pattern.trim().is_empty() {
+        return Err("empty topic pattern".to_string());
     }
+    event_router::subscribe(current_plugin_id(), TopicPattern::parse(pattern), sink)
+}
 
-    Ok(())
+/// Queues a plugin-side event for the next dispatch (plugin sinks and host topic subscribers).
+#[inline]
+pub fn emit_plugin_event(topic: RString, payload: Blob) -> Result<(), String> {
+    event_router::enqueue(topic.as_str(), payload.as_slice(), true)
+}
+
+/// Queues a host-published topic event for plugin sinks only.
+#[inline]
+pub(crate) fn emit_to_plugin_sinks(topic: &str, payload: &[u8]) {
+    let _ = event_router::enqueue(topic, payload, false);
 }
 
 pub fn unregister_by_owner(plugin_id: &str) {
@@ -137,11 +169,7 @@ pub fn unregister_by_owner(plugin_id: &str) {
         }
     }
 
-    {
-        let mut g = match c.event_sinks.lock() {
-            Ok(v) => v,
-            Err(_) => return,
-        };
-        g.retain(|e| e.owner_plugin_id.as_deref() != Some(plugin_id));
-    }
-}
\ No newline at end of file
+    event_router::remove_owner(plugin_id);
+    crate::plugins::limits::forget_caller(plugin_id);
+    crate::render_service::release_owner(plugin_id);
+}
@@ -1,31 +1,54 @@
 #![forbid(unsafe_op_in_unsafe_fn)]
 
 use abi_stable::std_types::{RResult, RString};
-use newengine_assets::{AssetBlob, AssetError, AssetKey, BlobImporterDispatch, ImporterPriority};
+use newengine_assets::{
+    AssetBlob, AssetError, AssetKey, BlobImporterDispatch, CancelToken, ImporterPriority,
+};
 use newengine_plugin_api::{Blob, CapabilityId, MethodName};
+use std::cell::RefCell;
 use std::sync::Arc;
 
 use crate::plugins::describe::parse_describe;
 use crate::plugins::host_api::call_service_v1;
 use crate::plugins::host_context::ctx;
 
+thread_local! {
+    /// Token of the import this thread is running; the importer service runs synchronously on
+    /// the same thread and polls it through `HostApiV1::import_cancelled`.
+    static CURRENT_IMPORT: RefCell<Option<CancelToken>> = const { RefCell::new(None) };
+}
+
+/// See `HostApiV1::import_cancelled`.
+#[inline]
+pub(crate) fn import_cancelled() -> bool {
+    CURRENT_IMPORT.with(|c| c.borrow().as_ref().is_some_and(CancelToken::is_cancelled))
+}
+
 pub(crate) struct ServiceBlobImporter {
     stable_id: Arc<str>,
     exts: Vec<String>,
     output_type_id: Arc<str>,
     format: Arc<str>,
     method: Arc<str>,
+    settings_method: Option<Arc<str>>,
+    default_settings: Option<Arc<str>>,
     service_id: Arc<str>,
     priority: ImporterPriority,
 }
 
 impl ServiceBlobImporter {
+    /// Uses the settings method when the key carries `.meta` settings and the service has one.
     #[inline]
-    fn call_import(&self, bytes: &[u8]) -> Result<Vec<u8>, AssetError> {
+    fn call_import(&self, bytes: &[u8], key: &AssetKey) -> Result<Vec<u8>, AssetError> {
+        let (method, input) = match (&self.settings_method, &key.import_settings) {
+            (Some(m), Some(settings)) => (m.as_ref(), Self::pack_settings(settings, bytes)),
+            _ => (self.method.as_ref(), bytes.to_vec()),
+        };
+
         let out: RResult<Blob, RString> = call_service_v1(
             CapabilityId::from(self.service_id.as_ref()),
-            MethodName::from(self.method.as_ref()),
-            Blob::from(bytes.to_vec()),
+            MethodName::from(method),
+            Blob::from(input),
         );
 
         out.into_result()
@@ -33,6 +56,16 @@ impl ServiceBlobImporter {
             .map_err(|e| AssetError::new(e.to_string()))
     }
 
+    #[inline]
+    fn pack_settings(settings: &str, bytes: &[u8]) -> Vec<u8> {
+        let s = settings.as_bytes();
+        let mut out = Vec::with_capacity(4 + s.len() + bytes.len());
+        out.extend_from_slice(&(s.len() as u32).to_le_bytes());
+        out.extend_from_slice(s);
+        out.extend_from_slice(bytes);
+        out
+    }
+
     #[inline]
     fn unpack_wire_v1(frame: &[u8]) -> Result<(Arc<str>, Vec<u8>), AssetError> {
         if frame.len() < 4 {
@@ -58,8 +91,8 @@ impl ServiceBlobImporter {
 }
 
 impl BlobImporterDispatch for ServiceBlobImporter {
-    fn import_blob(&self, bytes: &[u8], _key: &AssetKey) -> Result<AssetBlob, AssetError> {
-        let frame = self.call_import(bytes)?;
+    fn import_blob(&self, bytes: &[u8], key: &AssetKey) -> Result<AssetBlob, AssetError> {
+        let frame = self.call_import(bytes, key)?;
         let (meta_json, payload) = Self::unpack_wire_v1(&frame)?;
 
         Ok(AssetBlob {
@@ -71,6 +104,18 @@ impl BlobImporterDispatch for ServiceBlobImporter {
         })
     }
 
+    fn import_blob_cancellable(
+        &self,
+        bytes: &[u8],
+        key: &AssetKey,
+        cancel: &CancelToken,
+    ) -> Result<AssetBlob, AssetError> {
+        let prev = CURRENT_IMPORT.with(|c| c.replace(Some(cancel.clone())));
+        let res = self.import_blob(bytes, key);
+        CURRENT_IMPORT.with(|c| *c.borrow_mut() = prev);
+        res
+    }
+
     fn output_type_id(&self) -> Arc<str> {
         self.output_type_id.clone()
     }
@@ -86,6 +131,10 @@ impl BlobImporterDispatch for ServiceBlobImporter {
     fn stable_id(&self) -> Arc<str> {
         self.stable_id.clone()
     }
+
+    fn default_settings(&self) -> Option<Arc<str>> {
+        self.default_settings.clone()
+    }
 }
 
 pub(crate) fn try_auto_register_importer(service_id: &str, describe_json: &str) {
@@ -108,6 +157,11 @@ pub(crate) fn try_auto_register_importer(service_id: &str, describe_json: &str)
         output_type_id: Arc::from(imp.output_type_id),
         format: Arc::from(imp.format),
         method: Arc::from(imp.method),
+        settings_method: imp.settings_method.map(Arc::from),
+        default_settings: imp
+            .default_settings
+            .filter(|v| v.is_object())
+            .map(|v| Arc::from(v.to_string())),
         service_id: Arc::from(service_id.to_string()),
         priority: ImporterPriority::new(imp.priority.unwrap_or(0)),
     };
@@ -0,0 +1,212 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// Caller key used for engine-internal calls (asset imports, UI), i.e. outside any plugin.
+pub const HOST_CALLER_ID: &str = "host";
+/// Caller key used for console commands (local or remote).
+pub const CONSOLE_CALLER_ID: &str = "console";
+
+/// Default for [`ServiceLimits::max_payload_bytes`].
+pub const DEFAULT_MAX_PAYLOAD_BYTES: usize = 4 * 1024 * 1024;
+
+const RATE_WINDOW: Duration = Duration::from_secs(1);
+
+thread_local! {
+    static CONSOLE_CALL: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Limits enforced by service dispatch (`call_service_v1`).
+///
+/// Plugin and console calls are always checked. Engine-internal calls skip the payload cap
+/// (asset imports hand whole source files to importer services) and are only rate limited
+/// with `limit_host`.
+#[derive(Debug, Clone)]
+pub struct ServiceLimits {
+    /// Default max payload per call in bytes. 0 disables the cap.
+    pub max_payload_bytes: usize,
+    /// Per-service payload caps (service id -> bytes), overriding the default.
+    pub payload_overrides: HashMap<String, usize>,
+    /// Max calls per caller per second. 0 disables rate limiting.
+    pub max_calls_per_sec: u32,
+    /// Rate limit engine-internal callers as well. Off by default: the engine polls services
+    /// every frame.
+    pub limit_host: bool,
+}
+
+impl ServiceLimits {
+    #[inline]
+    pub fn unlimited() -> Self {
+        Self {
+            max_payload_bytes: 0,
+            payload_overrides: HashMap::new(),
+            max_calls_per_sec: 0,
+            limit_host: false,
+        }
+    }
+
+    #[inline]
+    pub fn with_max_payload_bytes(mut self, bytes: usize) -> Self {
+        self.max_payload_bytes = bytes;
+        self
+    }
+
+    #[inline]
+    pub fn with_service_payload_limit(
+        mut self,
+        service_id: impl Into<String>,
+        bytes: usize,
+    ) -> Self {
+        self.payload_overrides.insert(service_id.into(), bytes);
+        self
+    }
+
+    #[inline]
+    pub fn with_max_calls_per_sec(mut self, calls: u32) -> Self {
+        self.max_calls_per_sec = calls;
+        self
+    }
+
+    #[inline]
+    pub fn with_limit_host(mut self, enabled: bool) -> Self {
+        self.limit_host = enabled;
+        self
+    }
+
+    #[inline]
+    fn payload_limit(&self, service_id: &str) -> usize {
+        self.payload_overrides
+            .get(service_id)
+            .copied()
+            .unwrap_or(self.max_payload_bytes)
+    }
+}
+
+impl Default for ServiceLimits {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            max_payload_bytes: DEFAULT_MAX_PAYLOAD_BYTES,
+            payload_overrides: HashMap::new(),
+            max_calls_per_sec: 10_000,
+            limit_host: false,
+        }
+    }
+}
+
+struct RateWindow {
+    start: Instant,
+    calls: u32,
+}
+
+#[derive(Default)]
+struct LimitsState {
+    limits: ServiceLimits,
+    windows: HashMap<String, RateWindow>,
+}
+
+static LIMITS: OnceLock<Mutex<LimitsState>> = OnceLock::new();
+
+#[inline]
+fn state() -> &'static Mutex<LimitsState> {
+    LIMITS.get_or_init(|| Mutex::new(LimitsState::default()))
+}
+
+/// Replaces dispatch limits (process-wide) and resets rate windows.
+pub fn set_service_limits(limits: ServiceLimits) {
+    log::info!(
+        "services.limits max_payload_bytes={} overrides={} max_calls_per_sec={} limit_host={}",
+        limits.max_payload_bytes,
+        limits.payload_overrides.len(),
+        limits.max_calls_per_sec,
+        limits.limit_host
+    );
+
+    if let Ok(mut g) = state().lock() {
+        g.limits = limits;
+        g.windows.clear();
+    }
+}
+
+#[inline]
+pub fn service_limits() -> ServiceLimits {
+    state().lock().map(|g| g.limits.clone()).unwrap_or_default()
+}
+
+/// Attributes service calls made inside `f` to the console, so they are capped and rate
+/// limited like plugin calls.
+pub(crate) fn with_console_caller<R>(f: impl FnOnce() -> R) -> R {
+    let prev = CONSOLE_CALL.with(|c| c.replace(true));
+    let out = f();
+    CONSOLE_CALL.with(|c| c.set(prev));
+    out
+}
+
+/// Validates one dispatch. `caller` is the calling plugin id (`None` for host-side calls).
+pub(crate) fn check_call(
+    service_id: &str,
+    caller: Option<&str>,
+    payload_len: usize,
+) -> Result<(), String> {
+    let mut g = state()
+        .lock()
+        .map_err(|_| "service limits mutex poisoned".to_string())?;
+
+    let internal = caller.is_none() && !CONSOLE_CALL.with(Cell::get);
+    let caller = match caller {
+        Some(id) => id,
+        None if internal => HOST_CALLER_ID,
+        None => CONSOLE_CALLER_ID,
+    };
+
+    let cap = g.limits.payload_limit(service_id);
+    if !internal && cap != 0 && payload_len > cap {
+        log::warn!(
+            "services.reject payload_too_large service='{service_id}' caller='{caller}' size={payload_len} limit={cap}"
+        );
+        return Err(format!(
+            "payload too large for service '{service_id}': {payload_len} bytes (limit {cap})"
+        ));
+    }
+
+    let max_calls = g.limits.max_calls_per_sec;
+    if max_calls == 0 || (internal && !g.limits.limit_host) {
+        return Ok(());
+    }
+
+    let now = Instant::now();
+    let w = g.windows.entry(caller.to_string()).or_insert(RateWindow {
+        start: now,
+        calls: 0,
+    });
+
+    if now.duration_since(w.start) >= RATE_WINDOW {
+        w.start = now;
+        w.calls = 0;
+    }
+
+    w.calls = w.calls.saturating_add(1);
+    if w.calls > max_calls {
+        // Log once per window; a spamming caller would otherwise flood the log as well.
+        if w.calls == max_calls + 1 {
+            log::warn!(
+                "services.reject rate_limited caller='{caller}' service='{service_id}' limit={max_calls}/s"
+            );
+        }
+        return Err(format!(
+            "rate limited: caller '{caller}' exceeded {max_calls} service calls/s"
+        ));
+    }
+
+    Ok(())
+}
+
+/// Drops rate state for an unloaded plugin.
+pub(crate) fn forget_caller(caller: &str) {
+    if let Ok(mut g) = state().lock() {
+        g.windows.remove(caller);
+    }
+}
@@ -1,15 +1,20 @@
 #![forbid(unsafe_op_in_unsafe_fn)]
 
 use libloading::Library;
-use newengine_plugin_api::{HostApiV1, PluginInfo, PluginModuleDyn, PluginRootV1Ref, ServiceV1Dyn};
+use newengine_plugin_api::{
+    FrameInfoAbi, HostApiV1, PluginInfo, PluginModuleDyn, PluginRootV1Ref, ServiceV1Dyn,
+};
 use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 
 use crate::plugins::host_api::{
-    host_register_service_impl, with_importer_load_state, ImporterLoadState,
+    check_requirement, host_register_service_impl, set_current_frame, with_importer_load_state,
+    ImporterLoadState, HOST_CAPABILITY_PREFIX,
 };
 use crate::plugins::host_context::{unregister_by_owner, with_current_plugin_id};
+use crate::plugins::manifest::{PluginManifest, PLUGINS_MANIFEST_FILE};
 use crate::plugins::paths::{default_plugins_dir, is_dynamic_lib, resolve_plugins_dir};
+use crate::plugins::{timings, watchdog};
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 enum PluginState {
@@ -41,9 +46,19 @@ struct LoadedPlugin {
     disabled_reason: Option<String>,
 }
 
+/// Library opened and its info read, not yet initialised.
+struct PluginCandidate {
+    path: PathBuf,
+    lib: Library,
+    module: PluginModuleDyn<'static>,
+    info: PluginInfo,
+}
+
 pub struct PluginManager {
     loaded: Vec<LoadedPlugin>,
     loaded_ids: HashSet<String>,
+    /// Ids skipped at load time regardless of `plugins.json`.
+    disabled_ids: HashSet<String>,
 }
 
 impl PluginManager {
@@ -52,9 +67,19 @@ impl PluginManager {
         Self {
             loaded: Vec::new(),
             loaded_ids: HashSet::new(),
+            disabled_ids: HashSet::new(),
         }
     }
 
+    /// Plugin ids never to initialise, e.g. from the startup config. Applies to later loads.
+    pub fn set_disabled_plugins<I, S>(&mut self, ids: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.disabled_ids = ids.into_iter().map(Into::into).collect();
+    }
+
     #[inline]
     pub fn iter(&self) -> impl Iterator<Item = &PluginModuleDyn<'static>> {
         self.loaded.iter().map(|p| &p.module)
@@ -178,15 +203,70 @@ impl PluginManager {
             dir.display()
         );
 
+        let manifest = match PluginManifest::load(&dir) {
+            Ok(Some(m)) => {
+                log::info!(
+                    "plugins: using {} in '{}'",
+                    PLUGINS_MANIFEST_FILE,
+                    dir.display()
+                );
+                m
+            }
+            Ok(None) => PluginManifest::default(),
+            Err(e) => {
+                log::warn!("plugins: ignoring invalid manifest: {}", e);
+                PluginManifest::default()
+            }
+        };
+
+        let mut opened = Vec::with_capacity(candidates.len());
         for path in candidates {
-            match self.load_one(&path, host.clone()) {
-                Ok(()) => {}
+            match self.open_one(&path) {
+                Ok(c) => opened.push(Some(c)),
                 Err(e) => {
                     log::warn!("plugins: failed to load '{}': {}", path.display(), e);
                 }
             }
         }
 
+        let ids: Vec<String> = opened
+            .iter()
+            .flatten()
+            .map(|c| c.info.id.to_string())
+            .collect();
+        for id in manifest.order.iter().filter(|id| !ids.contains(id)) {
+            log::warn!(
+                "plugins: {} orders unknown plugin '{}'",
+                PLUGINS_MANIFEST_FILE,
+                id
+            );
+        }
+
+        let plan = manifest.plan(&ids, &self.loaded_ids, &self.disabled_ids);
+
+        for (i, reason) in plan.skipped {
+            let Some(mut c) = opened[i].take() else {
+                continue;
+            };
+            log::warn!(
+                "plugins: skipped id='{}' from '{}': {}",
+                ids[i],
+                c.path.display(),
+                reason
+            );
+            let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| c.module.shutdown()));
+        }
+
+        for i in plan.order {
+            let Some(c) = opened[i].take() else {
+                continue;
+            };
+            let path = c.path.clone();
+            if let Err(e) = self.init_one(c, host.clone()) {
+                log::warn!("plugins: failed to load '{}': {}", path.display(), e);
+            }
+        }
+
         Ok(())
     }
 
@@ -198,6 +278,8 @@ impl PluginManager {
     }
 
     pub fn start_all(&mut self) -> Result<(), String> {
+        self.disable_unmet_requirements();
+
         for i in 0..self.loaded.len() {
             if self.loaded[i].state != PluginState::Registered {
                 continue;
@@ -207,6 +289,21 @@ impl PluginManager {
         Ok(())
     }
 
+    /// Publishes `frame` through `HostApiV1::frame_info_v1` and calls every plugin's
+    /// `begin_frame`.
+    pub fn begin_frame_all(&mut self, frame: FrameInfoAbi) -> Result<(), String> {
+        set_current_frame(frame);
+        for i in 0..self.loaded.len() {
+            if self.loaded[i].state != PluginState::Running {
+                continue;
+            }
+            self.call_plugin(i, "begin_frame", |m| {
+                Self::rresult_to_string(m.begin_frame(frame))
+            });
+        }
+        Ok(())
+    }
+
     pub fn fixed_update_all(&mut self, dt: f32) -> Result<(), String> {
         for i in 0..self.loaded.len() {
             if self.loaded[i].state != PluginState::Running {
@@ -239,6 +336,18 @@ impl PluginManager {
         Ok(())
     }
 
+    /// Calls every plugin's `end_frame`, in reverse load order like `shutdown`, so plugins
+    /// clean up before the plugins they depend on.
+    pub fn end_frame_all(&mut self) -> Result<(), String> {
+        for i in (0..self.loaded.len()).rev() {
+            if self.loaded[i].state != PluginState::Running {
+                continue;
+            }
+            self.call_plugin(i, "end_frame", |m| Self::rresult_to_string(m.end_frame()));
+        }
+        Ok(())
+    }
+
     pub fn shutdown(&mut self) {
         for i in (0..self.loaded.len()).rev() {
             let id = self.loaded[i].info.id.to_string();
@@ -246,6 +355,11 @@ impl PluginManager {
             self.loaded[i].state = PluginState::Stopped;
             unregister_by_owner(&id);
         }
+
+        // Queued plugin jobs run code from the libraries unloaded below.
+        if let Some(jobs) = crate::jobs::JobSystem::try_global() {
+            jobs.wait_idle();
+        }
         self.loaded.clear();
         self.loaded_ids.clear();
     }
@@ -253,7 +367,7 @@ impl PluginManager {
     fn call_plugin(
         &mut self,
         idx: usize,
-        op: &str,
+        op: &'static str,
         f: impl FnOnce(&mut PluginModuleDyn<'static>) -> Result<(), String>,
     ) {
         if idx >= self.loaded.len() {
@@ -266,23 +380,47 @@ impl PluginManager {
 
         let id = self.loaded[idx].info.id.to_string();
 
+        // Tripped by faults in its services since the last frame.
+        if watchdog::is_disabled(&id) {
+            self.disable_plugin(idx, &id, "disabled by watchdog".to_string());
+            return;
+        }
+
+        let _scope = crate::telemetry::scope_with("plugin", || format!("{id}::{op}"));
+
+        let t0 = std::time::Instant::now();
         let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
             with_current_plugin_id(&id, || f(&mut self.loaded[idx].module))
         }));
+        timings::record(&id, op, t0.elapsed());
 
-        match result {
-            Ok(Ok(())) => {}
+        let fault = match result {
+            Ok(Ok(())) => None,
             Ok(Err(e)) => {
                 log::error!("plugins: op '{}' failed for id='{}': {}", op, id, e);
-                self.disable_plugin(idx, &id, format!("op '{op}' failed: {e}"));
+                Some(format!("failed: {e}"))
             }
             Err(_) => {
-                log::error!(
-                    "plugins: panic during op '{}' for id='{}' (plugin disabled)",
-                    op,
-                    id
-                );
-                self.disable_plugin(idx, &id, format!("panic during op '{op}'"));
+                log::error!("plugins: panic during op '{}' for id='{}'", op, id);
+                Some("panic".to_string())
+            }
+        };
+
+        match fault {
+            None => watchdog::record_ok(&id),
+            // A plugin that cannot start never runs; no point in retrying it.
+            Some(reason) if op == "start" => {
+                self.disable_plugin(idx, &id, format!("op '{op}' {reason}"));
+            }
+            Some(reason) => {
+                if watchdog::record_fault(&id, op, &reason) {
+                    log::error!(
+                        "plugins: id='{}' disabled after {} consecutive faults",
+                        id,
+                        watchdog::fault_limit()
+                    );
+                    self.disable_plugin(idx, &id, format!("op '{op}' {reason}"));
+                }
             }
         }
 
@@ -293,12 +431,42 @@ impl PluginManager {
         }
     }
 
+    /// Disables plugins whose required services are not registered. Runs to a fixed point,
+    /// since a disabled plugin takes its own services with it.
+    fn disable_unmet_requirements(&mut self) {
+        loop {
+            let mut changed = false;
+            for i in 0..self.loaded.len() {
+                if self.loaded[i].state != PluginState::Registered {
+                    continue;
+                }
+                let unmet = self.loaded[i]
+                    .info
+                    .requires
+                    .iter()
+                    .find_map(|r| check_requirement(r.as_str()).err());
+                let Some(e) = unmet else {
+                    continue;
+                };
+
+                let id = self.loaded[i].info.id.to_string();
+                log::error!("plugins: id='{}' not started: {}", id, e);
+                self.disable_plugin(i, &id, format!("unmet requirement: {e}"));
+                changed = true;
+            }
+            if !changed {
+                break;
+            }
+        }
+    }
+
     fn disable_plugin(&mut self, idx: usize, id: &str, reason: String) {
         if idx >= self.loaded.len() || self.loaded[idx].state == PluginState::Disabled {
             return;
         }
 
         self.loaded[idx].state = PluginState::Disabled;
+        watchdog::mark_disabled(id, &reason);
         self.loaded[idx].disabled_reason = Some(reason);
 
         self.safe_shutdown_one(idx);
@@ -318,7 +486,8 @@ impl PluginManager {
         }));
     }
 
-    fn load_one(&mut self, path: &Path, host: HostApiV1) -> Result<(), PluginLoadError> {
+    /// Opens `path` and reads the plugin's info without initialising it.
+    fn open_one(&self, path: &Path) -> Result<PluginCandidate, PluginLoadError> {
         log::info!("plugins: loading '{}'", path.display());
 
         let lib = unsafe { Library::new(path) }.map_err(|e| PluginLoadError {
@@ -336,9 +505,8 @@ impl PluginManager {
         let mut module = root.create()();
 
         let info = module.info();
-        let id_str = info.id.to_string();
 
-        if id_str.trim().is_empty() {
+        if info.id.to_string().trim().is_empty() {
             let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| module.shutdown()));
             return Err(PluginLoadError {
                 path: path.to_path_buf(),
@@ -362,6 +530,28 @@ impl PluginManager {
             });
         }
 
+        Ok(PluginCandidate {
+            path: path.to_path_buf(),
+            lib,
+            module,
+            info,
+        })
+    }
+
+    fn init_one(
+        &mut self,
+        candidate: PluginCandidate,
+        host: HostApiV1,
+    ) -> Result<(), PluginLoadError> {
+        let PluginCandidate {
+            path,
+            lib,
+            mut module,
+            info,
+        } = candidate;
+        let path = path.as_path();
+        let id_str = info.id.to_string();
+
         if self.loaded_ids.contains(&id_str) {
             log::warn!(
                 "plugins: duplicate id='{}' from '{}' ignored (already loaded)",
@@ -372,6 +562,14 @@ impl PluginManager {
             return Ok(());
         }
 
+        if let Err(e) = check_host_requirements(&info) {
+            let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| module.shutdown()));
+            return Err(PluginLoadError {
+                path: path.to_path_buf(),
+                message: e,
+            });
+        }
+
         let init_res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
             with_current_plugin_id(&id_str, || module.init(host).into_result())
         }));
@@ -407,6 +605,8 @@ impl PluginManager {
             path.display()
         );
 
+        watchdog::reset(&id_str);
+        timings::forget(&id_str);
         self.loaded_ids.insert(id_str);
         self.loaded.push(LoadedPlugin {
             _lib: lib,
@@ -443,6 +643,14 @@ impl PluginManager {
         let info_pre = module.info();
         let id_pre = info_pre.id.to_string();
 
+        if let Err(e) = check_host_requirements(&info_pre) {
+            let _ = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| module.shutdown()));
+            return Err(PluginLoadError {
+                path: path.to_path_buf(),
+                message: e,
+            });
+        }
+
         let mut state = ImporterLoadState {
             saw_importer: false,
             staged: Vec::<ServiceV1Dyn<'static>>::new(),
@@ -530,6 +738,8 @@ impl PluginManager {
             return Ok(ImporterLoadOutcome::SkippedNotImporter);
         }
 
+        watchdog::reset(&id_str);
+        timings::forget(&id_str);
         self.loaded_ids.insert(id_str);
 
         self.loaded.push(LoadedPlugin {
@@ -544,6 +754,17 @@ impl PluginManager {
     }
 }
 
+/// Host capability part of `PluginInfo::requires`, checked before `init`. Services are checked
+/// in `start_all`, once every plugin has had the chance to register its own.
+fn check_host_requirements(info: &PluginInfo) -> Result<(), String> {
+    for r in info.requires.iter() {
+        if r.trim().starts_with(HOST_CAPABILITY_PREFIX) {
+            check_requirement(r.as_str())?;
+        }
+    }
+    Ok(())
+}
+
 enum ImporterLoadOutcome {
     Loaded(PluginInfo),
     SkippedNotImporter,
@@ -0,0 +1,160 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// Optional manifest in the plugins directory.
+pub const PLUGINS_MANIFEST_FILE: &str = "plugins.json";
+
+/// Load order, dependencies and disabled plugins, read from [`PLUGINS_MANIFEST_FILE`]:
+///
+/// ```json
+/// {
+///   "order": ["newengine-modules-logging"],
+///   "dependencies": { "newengine-modules-scripting": ["newengine-modules-input"] },
+///   "disabled": ["newengine-modules-physics"]
+/// }
+/// ```
+///
+/// All keys are optional. Without a manifest, plugins load in file name order.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PluginManifest {
+    /// Plugin ids loaded first, in this order; unlisted plugins follow in file name order.
+    pub order: Vec<String>,
+    /// Plugin id -> ids that must be loaded before it.
+    pub dependencies: HashMap<String, Vec<String>>,
+    /// Plugin ids that are never initialised.
+    pub disabled: Vec<String>,
+}
+
+/// Result of [`PluginManifest::plan`]; indices refer to the candidate list.
+#[derive(Debug, Default)]
+pub(crate) struct LoadPlan {
+    /// Candidates to initialise, dependencies first.
+    pub order: Vec<usize>,
+    /// Candidates left out, with the reason.
+    pub skipped: Vec<(usize, String)>,
+}
+
+impl PluginManifest {
+    /// Reads `<dir>/plugins.json`; `Ok(None)` when the file does not exist.
+    pub fn load(dir: &Path) -> Result<Option<Self>, String> {
+        let path = dir.join(PLUGINS_MANIFEST_FILE);
+        let data = match std::fs::read_to_string(&path) {
+            Ok(s) => s,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(format!("{}: {e}", path.display())),
+        };
+        serde_json::from_str(&data)
+            .map(Some)
+            .map_err(|e| format!("{}: {e}", path.display()))
+    }
+
+    #[inline]
+    fn deps_of<'a>(&'a self, id: &str) -> impl Iterator<Item = &'a str> {
+        self.dependencies
+            .get(id)
+            .into_iter()
+            .flatten()
+            .map(String::as_str)
+    }
+
+    /// Orders candidate plugin `ids` (in file name order) so that every plugin comes after its
+    /// dependencies. Ids in `loaded` are already running and satisfy dependencies as they are.
+    ///
+    /// Plugins disabled here or in `config_disabled`, plugins with a missing (or itself
+    /// skipped) dependency and plugins on a dependency cycle are skipped.
+    pub(crate) fn plan(
+        &self,
+        ids: &[String],
+        loaded: &HashSet<String>,
+        config_disabled: &HashSet<String>,
+    ) -> LoadPlan {
+        let mut skipped: Vec<Option<String>> = ids
+            .iter()
+            .map(|id| {
+                if config_disabled.contains(id) {
+                    Some("disabled by config".to_string())
+                } else if self.disabled.contains(id) {
+                    Some(format!("disabled in {PLUGINS_MANIFEST_FILE}"))
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        // A skipped plugin takes its dependents with it, so run to a fixed point.
+        loop {
+            let mut changed = false;
+            for i in 0..ids.len() {
+                if skipped[i].is_some() {
+                    continue;
+                }
+                let unmet = self.deps_of(&ids[i]).find_map(|dep| {
+                    if loaded.contains(dep) {
+                        return None;
+                    }
+                    let mut found = ids.iter().enumerate().filter(|(_, id)| *id == dep);
+                    match found.clone().next() {
+                        None => Some(format!("missing dependency '{dep}'")),
+                        Some(_) if found.all(|(j, _)| skipped[j].is_some()) => {
+                            Some(format!("dependency '{dep}' is not loaded"))
+                        }
+                        Some(_) => None,
+                    }
+                });
+                if let Some(reason) = unmet {
+                    skipped[i] = Some(reason);
+                    changed = true;
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+
+        let rank = |i: usize| {
+            let pos = self.order.iter().position(|o| *o == ids[i]);
+            (pos.unwrap_or(usize::MAX), i)
+        };
+
+        let mut remaining: Vec<usize> = (0..ids.len()).filter(|&i| skipped[i].is_none()).collect();
+        let mut done: HashSet<&str> = HashSet::new();
+        let mut order = Vec::with_capacity(remaining.len());
+
+        while !remaining.is_empty() {
+            let ready = remaining
+                .iter()
+                .copied()
+                .filter(|&i| {
+                    self.deps_of(&ids[i])
+                        .all(|d| loaded.contains(d) || done.contains(d))
+                })
+                .min_by_key(|&i| rank(i));
+
+            let Some(i) = ready else {
+                let cycle: Vec<&str> = remaining.iter().map(|&i| ids[i].as_str()).collect();
+                let reason = format!("dependency cycle among [{}]", cycle.join(", "));
+                for &i in &remaining {
+                    skipped[i] = Some(reason.clone());
+                }
+                break;
+            };
+
+            order.push(i);
+            done.insert(ids[i].as_str());
+            remaining.retain(|&j| j != i);
+        }
+
+        LoadPlan {
+            order,
+            skipped: skipped
+                .into_iter()
+                .enumerate()
+                .filter_map(|(i, r)| r.map(|r| (i, r)))
+                .collect(),
+        }
+    }
+}
@@ -1,13 +1,39 @@
 #![forbid(unsafe_op_in_unsafe_fn)]
 
+#[cfg(feature = "runtime")]
+mod asset_api;
 mod describe;
+pub(crate) mod event_router;
 pub(crate) mod host_api;
 pub mod host_context;
 #[cfg(feature = "runtime")]
 mod importer;
+pub(crate) mod limits;
 mod manager;
-mod paths;
+mod manifest;
+pub(crate) mod paths;
+mod timings;
+mod watchdog;
 
-pub use host_api::{default_host_api, importers_host_api};
+#[cfg(feature = "runtime")]
+pub use asset_api::{asset_event_topic, ASSET_EVENT_TOPIC_PREFIX};
+pub use event_router::{
+    dispatch as dispatch_events, event_limits, event_router_stats, set_event_limits, EventLimits,
+    EventRouterStats, EventSinkInfo, TopicCounters,
+};
+pub use host_api::{default_host_api, importers_host_api, HOST_CAPABILITIES};
 pub use host_context::init_host_context;
+pub use limits::{
+    service_limits, set_service_limits, ServiceLimits, CONSOLE_CALLER_ID,
+    DEFAULT_MAX_PAYLOAD_BYTES, HOST_CALLER_ID,
+};
 pub use manager::PluginManager;
+pub use manifest::{PluginManifest, PLUGINS_MANIFEST_FILE};
+pub use timings::{
+    plugin_budget, plugin_timings, set_op_budget, set_plugin_budget, OpTiming, PluginBudget,
+    PluginTiming,
+};
+pub use watchdog::{
+    fault_limit, plugin_health, set_fault_limit, take_disabled_reports, PluginDisabled,
+    PluginHealth, DEFAULT_FAULT_LIMIT, PLUGIN_DISABLED_TOPIC,
+};
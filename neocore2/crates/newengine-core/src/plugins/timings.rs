@@ -0,0 +1,209 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use serde::Serialize;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+/// Weight of the newest sample in the running average.
+const AVG_ALPHA: f32 = 0.1;
+
+/// Soft per-call budgets for plugin frame ops. Exceeding one is only reported, never enforced.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct PluginBudget {
+    pub fixed_update_ms: f32,
+    pub update_ms: f32,
+    pub render_ms: f32,
+    /// Consecutive over-budget calls before a warning is logged.
+    pub warn_after_frames: u32,
+}
+
+impl Default for PluginBudget {
+    fn default() -> Self {
+        Self {
+            fixed_update_ms: 1.0,
+            update_ms: 2.0,
+            render_ms: 2.0,
+            warn_after_frames: 30,
+        }
+    }
+}
+
+impl PluginBudget {
+    /// Budget for `op`; `None` for ops that are not timed (`start`).
+    #[inline]
+    pub fn for_op(&self, op: &str) -> Option<f32> {
+        match op {
+            "fixed_update" => Some(self.fixed_update_ms),
+            "update" => Some(self.update_ms),
+            "render" => Some(self.render_ms),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    fn slot(&mut self, op: &str) -> Option<&mut f32> {
+        match op {
+            "fixed_update" => Some(&mut self.fixed_update_ms),
+            "update" => Some(&mut self.update_ms),
+            "render" => Some(&mut self.render_ms),
+            _ => None,
+        }
+    }
+}
+
+/// Timing of one plugin op, as returned by `plugins.timings`.
+#[derive(Debug, Clone, Serialize)]
+pub struct OpTiming {
+    pub op: &'static str,
+    pub calls: u64,
+    pub last_ms: f32,
+    pub avg_ms: f32,
+    pub max_ms: f32,
+    pub budget_ms: f32,
+    /// Current run of consecutive over-budget calls.
+    pub over_budget_streak: u32,
+    pub over_budget_total: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PluginTiming {
+    pub id: String,
+    /// Sum of `avg_ms` over all ops: the plugin's typical cost per frame.
+    pub frame_avg_ms: f32,
+    pub ops: Vec<OpTiming>,
+}
+
+struct Timings {
+    budget: PluginBudget,
+    plugins: Vec<PluginTiming>,
+}
+
+static TIMINGS: OnceLock<Mutex<Timings>> = OnceLock::new();
+
+#[inline]
+fn state() -> &'static Mutex<Timings> {
+    TIMINGS.get_or_init(|| {
+        Mutex::new(Timings {
+            budget: PluginBudget::default(),
+            plugins: Vec::new(),
+        })
+    })
+}
+
+pub fn set_plugin_budget(budget: PluginBudget) {
+    if let Ok(mut g) = state().lock() {
+        g.budget = budget;
+    }
+}
+
+/// Sets the budget of a single op (`fixed_update`, `update`, `render`).
+pub fn set_op_budget(op: &str, ms: f32) -> Result<(), String> {
+    if !ms.is_finite() || ms <= 0.0 {
+        return Err(format!("budget must be a positive number of ms, got {ms}"));
+    }
+    let mut g = state()
+        .lock()
+        .map_err(|_| "plugin timings mutex poisoned".to_string())?;
+    let slot = g
+        .budget
+        .slot(op)
+        .ok_or_else(|| format!("unknown op '{op}' (expected fixed_update|update|render)"))?;
+    *slot = ms;
+    Ok(())
+}
+
+#[inline]
+pub fn plugin_budget() -> PluginBudget {
+    state().lock().map(|g| g.budget).unwrap_or_default()
+}
+
+/// Records one call of `op` for plugin `id`; untimed ops are ignored.
+pub(crate) fn record(id: &str, op: &'static str, dt: Duration) {
+    let Ok(mut g) = state().lock() else {
+        return;
+    };
+    let budget = g.budget;
+    let Some(budget_ms) = budget.for_op(op) else {
+        return;
+    };
+    let ms = dt.as_secs_f32() * 1000.0;
+
+    let idx = match g.plugins.iter().position(|p| p.id == id) {
+        Some(i) => i,
+        None => {
+            g.plugins.push(PluginTiming {
+                id: id.to_string(),
+                frame_avg_ms: 0.0,
+                ops: Vec::new(),
+            });
+            g.plugins.len() - 1
+        }
+    };
+    let plugin = &mut g.plugins[idx];
+
+    let t = match plugin.ops.iter().position(|o| o.op == op) {
+        Some(i) => &mut plugin.ops[i],
+        None => {
+            plugin.ops.push(OpTiming {
+                op,
+                calls: 0,
+                last_ms: 0.0,
+                avg_ms: ms,
+                max_ms: 0.0,
+                budget_ms,
+                over_budget_streak: 0,
+                over_budget_total: 0,
+            });
+            plugin.ops.last_mut().expect("just pushed")
+        }
+    };
+
+    t.calls += 1;
+    t.last_ms = ms;
+    t.avg_ms += (ms - t.avg_ms) * AVG_ALPHA;
+    t.max_ms = t.max_ms.max(ms);
+    t.budget_ms = budget_ms;
+
+    if ms > budget_ms {
+        t.over_budget_streak = t.over_budget_streak.saturating_add(1);
+        t.over_budget_total += 1;
+        // Once per streak; a plugin that stays slow does not flood the log.
+        if t.over_budget_streak == budget.warn_after_frames.max(1) {
+            log::warn!(
+                "plugins.budget slow id='{}' op='{}' frames={} avg_ms={:.3} budget_ms={:.3}",
+                id,
+                op,
+                t.over_budget_streak,
+                t.avg_ms,
+                budget_ms
+            );
+        }
+    } else {
+        t.over_budget_streak = 0;
+    }
+
+    plugin.frame_avg_ms = plugin.ops.iter().map(|o| o.avg_ms).sum();
+}
+
+/// Drops a plugin's history, e.g. when it is loaded again.
+pub(crate) fn forget(id: &str) {
+    if let Ok(mut g) = state().lock() {
+        g.plugins.retain(|p| p.id != id);
+    }
+}
+
+/// Per-plugin timings, most expensive first.
+pub fn plugin_timings() -> Vec<PluginTiming> {
+    let Ok(g) = state().lock() else {
+        return Vec::new();
+    };
+    let mut out = g.plugins.clone();
+    let budget = g.budget;
+    drop(g);
+
+    for t in out.iter_mut().flat_map(|p| p.ops.iter_mut()) {
+        t.budget_ms = budget.for_op(t.op).unwrap_or(t.budget_ms);
+    }
+    out.sort_by(|a, b| b.frame_avg_ms.total_cmp(&a.frame_avg_ms));
+    out
+}
@@ -0,0 +1,191 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use serde::Serialize;
+use std::sync::{Mutex, OnceLock};
+
+/// Consecutive faults (errors or panics) after which a plugin is disabled.
+pub const DEFAULT_FAULT_LIMIT: u32 = 3;
+
+/// Topic published on the engine `EventHub` when a plugin gets disabled; payload is a JSON
+/// [`PluginDisabled`].
+pub const PLUGIN_DISABLED_TOPIC: &str = "plugins.disabled";
+
+/// Fault history of one plugin, as returned by `plugins.health`.
+#[derive(Debug, Clone, Serialize)]
+pub struct PluginHealth {
+    pub id: String,
+    pub consecutive_faults: u32,
+    pub total_faults: u64,
+    pub last_fault: Option<String>,
+    pub disabled: bool,
+    pub disabled_reason: Option<String>,
+}
+
+impl PluginHealth {
+    fn new(id: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            consecutive_faults: 0,
+            total_faults: 0,
+            last_fault: None,
+            disabled: false,
+            disabled_reason: None,
+        }
+    }
+}
+
+/// Report queued for [`PLUGIN_DISABLED_TOPIC`].
+#[derive(Debug, Clone, Serialize)]
+pub struct PluginDisabled {
+    pub id: String,
+    pub reason: String,
+    pub total_faults: u64,
+}
+
+struct Watchdog {
+    fault_limit: u32,
+    plugins: Vec<PluginHealth>,
+    reports: Vec<PluginDisabled>,
+}
+
+impl Watchdog {
+    fn entry(&mut self, id: &str) -> &mut PluginHealth {
+        let idx = match self.plugins.iter().position(|p| p.id == id) {
+            Some(i) => i,
+            None => {
+                self.plugins.push(PluginHealth::new(id));
+                self.plugins.len() - 1
+            }
+        };
+        &mut self.plugins[idx]
+    }
+
+    fn disable(&mut self, id: &str, reason: &str) {
+        let p = self.entry(id);
+        if p.disabled {
+            return;
+        }
+        p.disabled = true;
+        p.disabled_reason = Some(reason.to_string());
+        let report = PluginDisabled {
+            id: id.to_string(),
+            reason: reason.to_string(),
+            total_faults: p.total_faults,
+        };
+        self.reports.push(report);
+    }
+}
+
+/// Process-wide: service calls are dispatched through `extern "C"` host functions that have
+/// no handle to the `PluginManager`, yet their faults count against the owning plugin.
+static WATCHDOG: OnceLock<Mutex<Watchdog>> = OnceLock::new();
+
+#[inline]
+fn state() -> &'static Mutex<Watchdog> {
+    WATCHDOG.get_or_init(|| {
+        Mutex::new(Watchdog {
+            fault_limit: DEFAULT_FAULT_LIMIT,
+            plugins: Vec::new(),
+            reports: Vec::new(),
+        })
+    })
+}
+
+/// Sets how many consecutive faults disable a plugin (at least 1).
+pub fn set_fault_limit(limit: u32) {
+    if let Ok(mut g) = state().lock() {
+        g.fault_limit = limit.max(1);
+    }
+}
+
+#[inline]
+pub fn fault_limit() -> u32 {
+    state()
+        .lock()
+        .map(|g| g.fault_limit)
+        .unwrap_or(DEFAULT_FAULT_LIMIT)
+}
+
+/// A successful call resets the consecutive fault count.
+pub(crate) fn record_ok(id: &str) {
+    let Ok(mut g) = state().lock() else {
+        return;
+    };
+    if let Some(p) = g.plugins.iter_mut().find(|p| p.id == id) {
+        p.consecutive_faults = 0;
+    }
+}
+
+/// Counts a fault in `what` (`update`, `service asset.x::load`, ...). Returns true when this
+/// fault reaches the limit and the plugin is now marked disabled.
+pub(crate) fn record_fault(id: &str, what: &str, reason: &str) -> bool {
+    let Ok(mut g) = state().lock() else {
+        return false;
+    };
+    let limit = g.fault_limit;
+
+    let p = g.entry(id);
+    if p.disabled {
+        return false;
+    }
+    p.consecutive_faults = p.consecutive_faults.saturating_add(1);
+    p.total_faults = p.total_faults.saturating_add(1);
+    p.last_fault = Some(format!("{what}: {reason}"));
+
+    let faults = p.consecutive_faults;
+    log::warn!(
+        "plugins.watchdog fault id='{}' op='{}' consecutive={}/{} reason='{}'",
+        id,
+        what,
+        faults,
+        limit,
+        reason
+    );
+
+    if faults < limit {
+        return false;
+    }
+    g.disable(
+        id,
+        &format!("{faults} consecutive faults, last in {what}: {reason}"),
+    );
+    true
+}
+
+/// Records a plugin disabled for another reason (failed start, unmet requirement).
+pub(crate) fn mark_disabled(id: &str, reason: &str) {
+    if let Ok(mut g) = state().lock() {
+        g.disable(id, reason);
+    }
+}
+
+/// Clears the disabled flag and counters, e.g. when a plugin is loaded again.
+pub(crate) fn reset(id: &str) {
+    if let Ok(mut g) = state().lock() {
+        g.plugins.retain(|p| p.id != id);
+    }
+}
+
+#[inline]
+pub fn is_disabled(id: &str) -> bool {
+    state()
+        .lock()
+        .map(|g| g.plugins.iter().any(|p| p.id == id && p.disabled))
+        .unwrap_or(false)
+}
+
+/// Every plugin that has faulted or been disabled since it was loaded.
+pub fn plugin_health() -> Vec<PluginHealth> {
+    state()
+        .lock()
+        .map(|g| g.plugins.clone())
+        .unwrap_or_default()
+}
+
+/// Disable reports not yet published on [`PLUGIN_DISABLED_TOPIC`].
+pub fn take_disabled_reports() -> Vec<PluginDisabled> {
+    state()
+        .lock()
+        .map(|mut g| std::mem::take(&mut g.reports))
+        .unwrap_or_default()
+}
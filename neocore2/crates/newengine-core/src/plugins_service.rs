@@ -0,0 +1,190 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use crate::plugins::host_api;
+use crate::plugins::{
+    fault_limit, plugin_budget, plugin_health, plugin_timings, set_fault_limit, set_op_budget,
+    PluginBudget, PluginHealth, PluginTiming,
+};
+use abi_stable::std_types::{RResult, RString};
+use newengine_plugin_api::{
+    guarded_service, Blob, CapabilityId, MethodName, ServiceV1, ServiceV1Dyn,
+};
+use serde::Serialize;
+use serde_json::json;
+
+pub const PLUGINS_SERVICE_ID: &str = "engine.plugins";
+
+pub mod method {
+    pub const HEALTH_JSON: &str = "plugins.health_json";
+    pub const FAULT_LIMIT: &str = "plugins.fault_limit";
+    pub const TIMINGS_JSON: &str = "plugins.timings_json";
+    pub const BUDGET: &str = "plugins.budget";
+}
+
+#[derive(Debug, Serialize)]
+struct PluginHealthResp {
+    fault_limit: u32,
+    plugins: Vec<PluginHealth>,
+}
+
+#[derive(Debug, Serialize)]
+struct FaultLimitResp {
+    ok: bool,
+    fault_limit: u32,
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct PluginTimingsResp {
+    budget: PluginBudget,
+    plugins: Vec<PluginTiming>,
+}
+
+#[derive(Debug, Serialize)]
+struct BudgetResp {
+    ok: bool,
+    budget: PluginBudget,
+    error: Option<String>,
+}
+
+struct PluginsService;
+
+impl PluginsService {
+    /// Payload: empty to read the limit, or a number to set it.
+    fn fault_limit(arg: &str) -> FaultLimitResp {
+        let arg = arg.trim();
+        if arg.is_empty() {
+            return FaultLimitResp {
+                ok: true,
+                fault_limit: fault_limit(),
+                error: None,
+            };
+        }
+
+        match arg.parse::<u32>() {
+            Ok(n) if n > 0 => {
+                set_fault_limit(n);
+                FaultLimitResp {
+                    ok: true,
+                    fault_limit: fault_limit(),
+                    error: None,
+                }
+            }
+            _ => FaultLimitResp {
+                ok: false,
+                fault_limit: fault_limit(),
+                error: Some(format!("expected a positive number, got '{arg}'")),
+            },
+        }
+    }
+
+    /// Payload: empty to read the budgets, or `<fixed_update|update|render> <ms>` to set one.
+    fn budget(arg: &str) -> BudgetResp {
+        let mut it = arg.split_whitespace();
+        let res = match (it.next(), it.next()) {
+            (None, _) => Ok(()),
+            (Some(op), Some(ms)) => ms
+                .parse::<f32>()
+                .map_err(|_| format!("invalid ms value '{ms}'"))
+                .and_then(|ms| set_op_budget(op, ms)),
+            (Some(_), None) => Err("usage: plugins.budget [<op> <ms>]".to_string()),
+        };
+
+        BudgetResp {
+            ok: res.is_ok(),
+            budget: plugin_budget(),
+            error: res.err(),
+        }
+    }
+}
+
+impl ServiceV1 for PluginsService {
+    fn id(&self) -> CapabilityId {
+        RString::from(PLUGINS_SERVICE_ID)
+    }
+
+    fn describe(&self) -> RString {
+        let d = json!({
+          "id": PLUGINS_SERVICE_ID,
+          "version": 1,
+          "methods": [
+            { "name": method::HEALTH_JSON, "payload": "empty", "returns": "json PluginHealthResp" },
+            { "name": method::FAULT_LIMIT, "payload": "utf8 '[n]'", "returns": "json FaultLimitResp" },
+            { "name": method::TIMINGS_JSON, "payload": "empty", "returns": "json PluginTimingsResp" },
+            { "name": method::BUDGET, "payload": "utf8 '[<op> <ms>]'", "returns": "json BudgetResp" }
+          ],
+          "console": {
+            "commands": [
+              {
+                "name": "plugins.health",
+                "help": "Plugin fault counts and which plugins the watchdog disabled",
+                "kind": "service_call",
+                "service_id": PLUGINS_SERVICE_ID,
+                "method": method::HEALTH_JSON,
+                "payload": "empty"
+              },
+              {
+                "name": "plugins.fault_limit",
+                "help": "Show or set how many consecutive faults disable a plugin",
+                "usage": "plugins.fault_limit [n]",
+                "kind": "service_call",
+                "service_id": PLUGINS_SERVICE_ID,
+                "method": method::FAULT_LIMIT,
+                "payload": "raw"
+              },
+              {
+                "name": "plugins.timings",
+                "help": "Per-plugin update/render cost against the soft budgets, slowest first",
+                "kind": "service_call",
+                "service_id": PLUGINS_SERVICE_ID,
+                "method": method::TIMINGS_JSON,
+                "payload": "empty"
+              },
+              {
+                "name": "plugins.budget",
+                "help": "Show or set a soft per-call budget: plugins.budget <fixed_update|update|render> <ms>",
+                "usage": "plugins.budget [<op> <ms>]",
+                "kind": "service_call",
+                "service_id": PLUGINS_SERVICE_ID,
+                "method": method::BUDGET,
+                "payload": "raw"
+              }
+            ]
+          }
+        });
+
+        RString::from(d.to_string())
+    }
+
+    fn call(&self, method: MethodName, payload: Blob) -> RResult<Blob, RString> {
+        let m = method.to_string();
+
+        let resp = match m.as_str() {
+            method::HEALTH_JSON => serde_json::to_vec(&PluginHealthResp {
+                fault_limit: fault_limit(),
+                plugins: plugin_health(),
+            }),
+            method::FAULT_LIMIT => {
+                let arg = String::from_utf8_lossy(payload.as_slice());
+                serde_json::to_vec(&Self::fault_limit(&arg))
+            }
+            method::TIMINGS_JSON => serde_json::to_vec(&PluginTimingsResp {
+                budget: plugin_budget(),
+                plugins: plugin_timings(),
+            }),
+            method::BUDGET => {
+                let arg = String::from_utf8_lossy(payload.as_slice());
+                serde_json::to_vec(&Self::budget(&arg))
+            }
+            _ => return RResult::RErr(RString::from(format!("unknown method: {m}"))),
+        };
+
+        RResult::ROk(Blob::from(resp.unwrap_or_default()))
+    }
+}
+
+pub fn register_plugins_service() {
+    let dyn_svc: ServiceV1Dyn<'static> = guarded_service(PluginsService);
+
+    let _ = host_api::host_register_service_impl(dyn_svc, false);
+}
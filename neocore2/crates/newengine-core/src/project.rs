@@ -0,0 +1,224 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// Extension of project files.
+pub const PROJECT_EXTENSION: &str = "neproject";
+
+/// A `.neproject` file. Paths are relative to the directory holding it:
+///
+/// ```json
+/// {
+///   "name": "Demo",
+///   "assets": { "game": "assets", "cache": "cache/assets" },
+///   "plugin_sets": {
+///     "full": {},
+///     "server": { "disabled": ["newengine-modules-render-vulkan-ash"] }
+///   },
+///   "startup_scene": "scenes/main.scene.json",
+///   "default_profile": "dev",
+///   "profiles": {
+///     "dev": { "plugins": "full", "settings": { "logging.level": "debug" } },
+///     "release": { "plugins": "full", "output": "build/release" }
+///   }
+/// }
+/// ```
+///
+/// All keys are optional.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProjectFile {
+    /// Display name; the file stem when empty.
+    pub name: String,
+    pub assets: ProjectAssets,
+    /// Named plugin directories and disabled ids, picked by a profile.
+    pub plugin_sets: BTreeMap<String, PluginSet>,
+    /// Scene opened first, a logical path under the game assets.
+    pub startup_scene: Option<String>,
+    /// Profile used when none is asked for.
+    pub default_profile: Option<String>,
+    pub profiles: BTreeMap<String, BuildProfile>,
+}
+
+/// Asset roots of a project; unset roots keep the startup config value. An empty string
+/// unmounts `engine`/`mods` and disables the cache.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProjectAssets {
+    /// Mounted at `game://`.
+    pub game: Option<String>,
+    pub engine: Option<String>,
+    pub mods: Option<String>,
+    pub cache: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PluginSet {
+    /// Directory plugins are loaded from; unset keeps the startup config one (next to the
+    /// executable by default).
+    pub dir: Option<String>,
+    /// Plugin ids never initialised, on top of `plugins.json`.
+    pub disabled: Vec<String>,
+}
+
+/// A way to run and package the project.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BuildProfile {
+    /// Name in `plugin_sets`.
+    pub plugins: Option<String>,
+    /// Replaces the project's startup scene.
+    pub startup_scene: Option<String>,
+    /// Export folder; `build/<profile>` when unset.
+    pub output: Option<String>,
+    /// File name of the exported game executable, without extension; the project name when
+    /// unset.
+    pub executable: Option<String>,
+    /// Startup config overrides, `<section>.<key>` like `--window.size=1280x720`.
+    pub settings: BTreeMap<String, Value>,
+}
+
+/// A loaded project file and the directory its paths are relative to.
+#[derive(Debug, Clone)]
+pub struct Project {
+    path: PathBuf,
+    root: PathBuf,
+    file: ProjectFile,
+}
+
+impl Project {
+    /// Reads and checks a `.neproject` file: profiles must name existing plugin sets.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let path = std::fs::canonicalize(path).map_err(|e| format!("{}: {e}", path.display()))?;
+        let data =
+            std::fs::read_to_string(&path).map_err(|e| format!("{}: {e}", path.display()))?;
+        let file: ProjectFile =
+            serde_json::from_str(&data).map_err(|e| format!("{}: {e}", path.display()))?;
+        let root = path.parent().map(Path::to_path_buf).unwrap_or_default();
+
+        let project = Self { path, root, file };
+        for (name, profile) in project.file.profiles.iter() {
+            if let Some(set) = &profile.plugins {
+                if !project.file.plugin_sets.contains_key(set) {
+                    return Err(format!(
+                        "{}: profile '{name}' uses unknown plugin set '{set}'",
+                        project.path.display()
+                    ));
+                }
+            }
+        }
+        if let Some(name) = &project.file.default_profile {
+            project.profile(Some(name))?;
+        }
+        Ok(project)
+    }
+
+    pub fn name(&self) -> &str {
+        match self.file.name.trim() {
+            "" => self
+                .path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or_default(),
+            name => name,
+        }
+    }
+
+    /// The project file, absolute.
+    #[inline]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Directory of the project file.
+    #[inline]
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    #[inline]
+    pub fn file(&self) -> &ProjectFile {
+        &self.file
+    }
+
+    /// `path` under the project root; absolute paths are kept.
+    #[inline]
+    pub fn resolve(&self, path: impl AsRef<Path>) -> PathBuf {
+        self.root.join(path)
+    }
+
+    /// Profile `name`, or the default profile for `None`. `Ok(None)` when no name is given and
+    /// the project has no default.
+    pub fn profile<'a>(
+        &'a self,
+        name: Option<&'a str>,
+    ) -> Result<Option<(&'a str, &'a BuildProfile)>, String> {
+        let Some(name) = name.or(self.file.default_profile.as_deref()) else {
+            return Ok(None);
+        };
+        match self.file.profiles.get(name) {
+            Some(p) => Ok(Some((name, p))),
+            None => Err(format!(
+                "{}: unknown profile '{name}' (known: {})",
+                self.name(),
+                self.profile_names().collect::<Vec<_>>().join(", ")
+            )),
+        }
+    }
+
+    pub fn profile_names(&self) -> impl Iterator<Item = &str> {
+        self.file.profiles.keys().map(String::as_str)
+    }
+
+    /// Plugin set of a profile; `None` keeps the startup config plugins.
+    pub fn plugin_set(&self, profile: Option<&BuildProfile>) -> Option<&PluginSet> {
+        let name = profile?.plugins.as_ref()?;
+        self.file.plugin_sets.get(name)
+    }
+
+    /// Logical path of the first scene, the profile's before the project's.
+    pub fn startup_scene<'a>(&'a self, profile: Option<&'a BuildProfile>) -> Option<&'a str> {
+        profile
+            .and_then(|p| p.startup_scene.as_deref())
+            .or(self.file.startup_scene.as_deref())
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+    }
+}
+
+/// Process-wide so path resolution works without an Engine handle (importers, services).
+static ACTIVE: Mutex<Option<Arc<Project>>> = Mutex::new(None);
+
+/// Makes `project` the one [`project_path`] resolves against; `None` goes back to the working
+/// directory.
+pub fn set_active_project(project: Option<Project>) {
+    if let Some(p) = &project {
+        log::info!(
+            "project: active name='{}' root='{}'",
+            p.name(),
+            p.root().display()
+        );
+    }
+    if let Ok(mut g) = ACTIVE.lock() {
+        *g = project.map(Arc::new);
+    }
+}
+
+#[inline]
+pub fn active_project() -> Option<Arc<Project>> {
+    ACTIVE.lock().ok()?.clone()
+}
+
+/// `path` under the active project's root, or as given without a project (relative to the
+/// working directory). Absolute paths are kept.
+pub fn project_path(path: impl AsRef<Path>) -> PathBuf {
+    match active_project() {
+        Some(p) => p.resolve(path),
+        None => path.as_ref().to_path_buf(),
+    }
+}
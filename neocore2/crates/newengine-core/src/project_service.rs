@@ -0,0 +1,154 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use crate::export::{export_status, start_export, ExportStatus};
+use crate::plugins::host_api;
+use crate::project::active_project;
+use abi_stable::std_types::{RResult, RString};
+use newengine_assets::AssetStore;
+use newengine_plugin_api::{
+    guarded_service, Blob, CapabilityId, MethodName, ServiceV1, ServiceV1Dyn,
+};
+use serde::Serialize;
+use serde_json::json;
+use std::sync::Arc;
+
+pub const PROJECT_SERVICE_ID: &str = "engine.project";
+
+pub mod method {
+    pub const INFO_JSON: &str = "project.info_json";
+    pub const EXPORT: &str = "project.export";
+    pub const EXPORT_STATUS_JSON: &str = "project.export_status_json";
+}
+
+#[derive(Debug, Serialize)]
+struct ProjectInfoResp {
+    name: String,
+    path: String,
+    root: String,
+    default_profile: Option<String>,
+    profiles: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct ExportStartResp {
+    started: bool,
+    profile: Option<String>,
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct ExportStatusResp {
+    status: ExportStatus,
+}
+
+struct ProjectService {
+    /// Source of the importers an export cooks with.
+    store: Arc<AssetStore>,
+}
+
+impl ProjectService {
+    fn info() -> Result<ProjectInfoResp, String> {
+        let project = active_project().ok_or_else(|| "no project loaded".to_string())?;
+        Ok(ProjectInfoResp {
+            name: project.name().to_string(),
+            path: project.path().display().to_string(),
+            root: project.root().display().to_string(),
+            default_profile: project.file().default_profile.clone(),
+            profiles: project.profile_names().map(str::to_owned).collect(),
+        })
+    }
+
+    /// Payload: profile name; empty exports the project's default profile.
+    fn export(&self, arg: &str) -> ExportStartResp {
+        let profile = Some(arg.trim()).filter(|p| !p.is_empty());
+        let res = active_project()
+            .ok_or_else(|| "no project loaded".to_string())
+            .and_then(|p| start_export(p, profile, self.store.importers()));
+        match res {
+            Ok(profile) => ExportStartResp {
+                started: true,
+                profile: Some(profile),
+                error: None,
+            },
+            Err(e) => ExportStartResp {
+                started: false,
+                profile: profile.map(str::to_owned),
+                error: Some(e),
+            },
+        }
+    }
+}
+
+impl ServiceV1 for ProjectService {
+    fn id(&self) -> CapabilityId {
+        RString::from(PROJECT_SERVICE_ID)
+    }
+
+    fn describe(&self) -> RString {
+        let d = json!({
+          "id": PROJECT_SERVICE_ID,
+          "version": 1,
+          "methods": [
+            { "name": method::INFO_JSON, "payload": "empty", "returns": "json ProjectInfoResp" },
+            { "name": method::EXPORT, "payload": "utf8 '[profile]'", "returns": "json ExportStartResp" },
+            { "name": method::EXPORT_STATUS_JSON, "payload": "empty", "returns": "json ExportStatusResp" }
+          ],
+          "console": {
+            "commands": [
+              {
+                "name": "project.info",
+                "help": "Print the loaded project and its build profiles",
+                "kind": "service_call",
+                "service_id": PROJECT_SERVICE_ID,
+                "method": method::INFO_JSON,
+                "payload": "empty"
+              },
+              {
+                "name": "project.export",
+                "help": "Build a distributable game folder for a profile (default profile when omitted)",
+                "usage": "project.export [profile]",
+                "kind": "service_call",
+                "service_id": PROJECT_SERVICE_ID,
+                "method": method::EXPORT,
+                "payload": "raw"
+              },
+              {
+                "name": "project.export_status",
+                "help": "Print the progress or result of the last export",
+                "kind": "service_call",
+                "service_id": PROJECT_SERVICE_ID,
+                "method": method::EXPORT_STATUS_JSON,
+                "payload": "empty"
+              }
+            ]
+          }
+        });
+
+        RString::from(d.to_string())
+    }
+
+    fn call(&self, method: MethodName, payload: Blob) -> RResult<Blob, RString> {
+        let m = method.to_string();
+        let arg = String::from_utf8_lossy(payload.as_slice());
+
+        let resp = match m.as_str() {
+            method::INFO_JSON => match Self::info() {
+                Ok(info) => serde_json::to_vec(&info),
+                Err(e) => return RResult::RErr(RString::from(e)),
+            },
+            method::EXPORT => serde_json::to_vec(&self.export(&arg)),
+            method::EXPORT_STATUS_JSON => serde_json::to_vec(&ExportStatusResp {
+                status: export_status(),
+            }),
+            _ => return RResult::RErr(RString::from(format!("unknown method: {m}"))),
+        };
+
+        RResult::ROk(Blob::from(resp.unwrap_or_default()))
+    }
+}
+
+pub fn register_project_service(store: Arc<AssetStore>) {
+    let dyn_svc: ServiceV1Dyn<'static> = guarded_service(ProjectService { store });
+
+    let _ = host_api::host_register_service_impl(dyn_svc, false);
+}
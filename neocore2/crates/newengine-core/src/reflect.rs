@@ -0,0 +1,606 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use crate::interp::Transform;
+use crate::undo::{Command, UndoStack};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::any::{Any, TypeId};
+use std::fmt;
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// Built-in component of every scene entity, stored as a [`Transform`].
+pub const TRANSFORM_COMPONENT: &str = "transform";
+
+/// Type of a component field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FieldKind {
+    Bool,
+    Float,
+    Vec3,
+    /// Four numbers, e.g. an RGBA color.
+    Vec4,
+    /// Unit quaternion `[x, y, z, w]`; typed as XYZ Euler angles in degrees.
+    Rotation,
+    String,
+}
+
+/// Value of a component field. Rotations are [`FieldValue::Vec4`] quaternions.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum FieldValue {
+    Bool(bool),
+    Float(f32),
+    Vec3([f32; 3]),
+    Vec4([f32; 4]),
+    String(String),
+}
+
+impl FieldValue {
+    /// Numbers the value is made of; empty for bools and strings.
+    pub fn numbers(&self) -> &[f32] {
+        match self {
+            FieldValue::Float(v) => std::slice::from_ref(v),
+            FieldValue::Vec3(v) => v,
+            FieldValue::Vec4(v) => v,
+            FieldValue::Bool(_) | FieldValue::String(_) => &[],
+        }
+    }
+}
+
+impl fmt::Display for FieldValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FieldValue::Bool(v) => write!(f, "{}", if *v { 1 } else { 0 }),
+            FieldValue::String(v) => f.write_str(v),
+            numeric => {
+                let text: Vec<String> = numeric.numbers().iter().map(f32::to_string).collect();
+                f.write_str(&text.join(" "))
+            }
+        }
+    }
+}
+
+impl FieldKind {
+    /// Parses console input. Numbers are separated by spaces or commas; rotations take three
+    /// Euler angles in degrees or a quaternion.
+    pub fn parse(self, text: &str) -> Result<FieldValue, String> {
+        let t = text.trim();
+        let numbers = || -> Result<Vec<f32>, String> {
+            t.split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|s| !s.is_empty())
+                .map(|s| {
+                    s.parse::<f32>()
+                        .ok()
+                        .filter(|v| v.is_finite())
+                        .ok_or_else(|| format!("expected a number, got '{s}'"))
+                })
+                .collect()
+        };
+
+        match self {
+            FieldKind::Bool => match t.to_ascii_lowercase().as_str() {
+                "1" | "on" | "true" | "yes" => Ok(FieldValue::Bool(true)),
+                "0" | "off" | "false" | "no" => Ok(FieldValue::Bool(false)),
+                _ => Err(format!("expected 0|1, got '{t}'")),
+            },
+            FieldKind::Float => match numbers()?[..] {
+                [v] => Ok(FieldValue::Float(v)),
+                _ => Err(format!("expected a number, got '{t}'")),
+            },
+            FieldKind::Vec3 => match numbers()?[..] {
+                [x, y, z] => Ok(FieldValue::Vec3([x, y, z])),
+                _ => Err(format!("expected 3 numbers, got '{t}'")),
+            },
+            FieldKind::Vec4 => match numbers()?[..] {
+                [x, y, z, w] => Ok(FieldValue::Vec4([x, y, z, w])),
+                _ => Err(format!("expected 4 numbers, got '{t}'")),
+            },
+            FieldKind::Rotation => match numbers()?[..] {
+                [x, y, z] => Ok(FieldValue::Vec4(quat_from_euler_degrees([x, y, z]))),
+                [x, y, z, w] => normalize_quat([x, y, z, w])
+                    .map(FieldValue::Vec4)
+                    .ok_or_else(|| "a zero quaternion is no rotation".to_string()),
+                _ => Err(format!(
+                    "expected 3 angles in degrees or a quaternion, got '{t}'"
+                )),
+            },
+            FieldKind::String => Ok(FieldValue::String(t.to_string())),
+        }
+    }
+
+    #[inline]
+    fn accepts(self, value: &FieldValue) -> bool {
+        matches!(
+            (self, value),
+            (FieldKind::Bool, FieldValue::Bool(_))
+                | (FieldKind::Float, FieldValue::Float(_))
+                | (FieldKind::Vec3, FieldValue::Vec3(_))
+                | (FieldKind::Vec4 | FieldKind::Rotation, FieldValue::Vec4(_))
+                | (FieldKind::String, FieldValue::String(_))
+        )
+    }
+}
+
+/// Quaternion `[x, y, z, w]` of XYZ Euler angles in degrees: X applied last.
+pub fn quat_from_euler_degrees(degrees: [f32; 3]) -> [f32; 4] {
+    let [x, y, z] = degrees.map(|d| d.to_radians() * 0.5);
+    let (sx, cx) = x.sin_cos();
+    let (sy, cy) = y.sin_cos();
+    let (sz, cz) = z.sin_cos();
+    [
+        sx * cy * cz + cx * sy * sz,
+        cx * sy * cz - sx * cy * sz,
+        cx * cy * sz + sx * sy * cz,
+        cx * cy * cz - sx * sy * sz,
+    ]
+}
+
+#[inline]
+fn normalize_quat(q: [f32; 4]) -> Option<[f32; 4]> {
+    let len = q.iter().map(|v| v * v).sum::<f32>().sqrt();
+    (len > f32::EPSILON).then(|| q.map(|v| v / len))
+}
+
+type FieldGet = Box<dyn Fn(&dyn Any) -> Option<FieldValue> + Send + Sync>;
+/// `false` when the component is not of the registered type.
+type FieldSet = Box<dyn Fn(&mut dyn Any, FieldValue) -> bool + Send + Sync>;
+type ComponentSerialize = Box<dyn Fn(&dyn Any) -> Option<Result<Value, String>> + Send + Sync>;
+type ComponentDeserialize =
+    Box<dyn Fn(&Value) -> Result<Box<dyn Any + Send>, String> + Send + Sync>;
+
+/// A field of a registered component.
+pub struct FieldInfo {
+    pub name: String,
+    pub kind: FieldKind,
+    /// Inclusive bounds of each number of the field.
+    pub range: Option<(f32, f32)>,
+    get: FieldGet,
+    set: FieldSet,
+}
+
+impl FieldInfo {
+    /// `None` when `component` is not of the registered type.
+    #[inline]
+    pub fn get(&self, component: &dyn Any) -> Option<FieldValue> {
+        (self.get)(component)
+    }
+
+    /// Checks `value` against the kind and range and writes it into `component`.
+    pub fn set(&self, component: &mut dyn Any, value: FieldValue) -> Result<(), String> {
+        self.check(&value)?;
+        match (self.set)(component, value) {
+            true => Ok(()),
+            false => Err(format!("{}: wrong component type", self.name)),
+        }
+    }
+
+    pub fn check(&self, value: &FieldValue) -> Result<(), String> {
+        if !self.kind.accepts(value) {
+            return Err(format!(
+                "{} expects {:?}, got '{value}'",
+                self.name, self.kind
+            ));
+        }
+        if let Some((min, max)) = self.range {
+            if value.numbers().iter().any(|n| *n < min || *n > max) {
+                return Err(format!("{} out of range [{min}, {max}]", self.name));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for FieldInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FieldInfo")
+            .field("name", &self.name)
+            .field("kind", &self.kind)
+            .field("range", &self.range)
+            .finish()
+    }
+}
+
+/// A registered component: its fields and how it is (de)serialized, over any value of its
+/// Rust type.
+pub struct ComponentInfo {
+    pub name: String,
+    /// Rust type the component is stored as.
+    pub type_name: &'static str,
+    type_id: TypeId,
+    pub fields: Vec<FieldInfo>,
+    serialize: ComponentSerialize,
+    deserialize: ComponentDeserialize,
+}
+
+impl ComponentInfo {
+    pub fn field(&self, name: &str) -> Option<&FieldInfo> {
+        self.fields.iter().find(|f| f.name == name)
+    }
+
+    /// Whether the component is stored as a `T`.
+    #[inline]
+    pub fn is<T: Any>(&self) -> bool {
+        self.type_id == TypeId::of::<T>()
+    }
+
+    pub fn serialize(&self, component: &dyn Any) -> Result<Value, String> {
+        (self.serialize)(component)
+            .unwrap_or_else(|| Err(format!("{}: wrong component type", self.name)))
+    }
+
+    pub fn deserialize(&self, value: &Value) -> Result<Box<dyn Any + Send>, String> {
+        (self.deserialize)(value).map_err(|e| format!("{}: {e}", self.name))
+    }
+}
+
+impl fmt::Debug for ComponentInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ComponentInfo")
+            .field("name", &self.name)
+            .field("type_name", &self.type_name)
+            .field("fields", &self.fields)
+            .finish()
+    }
+}
+
+/// Declaration of a component stored as a `T`, for [`register_component`].
+pub struct ComponentDesc<T> {
+    info: ComponentInfo,
+    _type: PhantomData<fn() -> T>,
+}
+
+impl<T: Any + Send + Serialize + DeserializeOwned> ComponentDesc<T> {
+    /// Serialized through serde.
+    pub fn new(name: &str) -> Self {
+        Self::with_codec(
+            name,
+            |c| serde_json::to_value(c).map_err(|e| e.to_string()),
+            |v| T::deserialize(v).map_err(|e| e.to_string()),
+        )
+    }
+}
+
+impl<T: Any + Send> ComponentDesc<T> {
+    /// Serialized through the given functions.
+    pub fn with_codec(
+        name: &str,
+        serialize: fn(&T) -> Result<Value, String>,
+        deserialize: fn(&Value) -> Result<T, String>,
+    ) -> Self {
+        Self {
+            info: ComponentInfo {
+                name: name.to_string(),
+                type_name: std::any::type_name::<T>(),
+                type_id: TypeId::of::<T>(),
+                fields: Vec::new(),
+                serialize: Box::new(move |c| c.downcast_ref::<T>().map(serialize)),
+                deserialize: Box::new(move |v| {
+                    deserialize(v).map(|c| Box::new(c) as Box<dyn Any + Send>)
+                }),
+            },
+            _type: PhantomData,
+        }
+    }
+
+    pub fn bool(self, name: &str, get: fn(&T) -> bool, set: fn(&mut T, bool)) -> Self {
+        self.field(
+            name,
+            FieldKind::Bool,
+            move |c| FieldValue::Bool(get(c)),
+            move |c, v| match v {
+                FieldValue::Bool(v) => set(c, v),
+                _ => unreachable!("checked by FieldInfo::set"),
+            },
+        )
+    }
+
+    pub fn float(self, name: &str, get: fn(&T) -> f32, set: fn(&mut T, f32)) -> Self {
+        self.field(
+            name,
+            FieldKind::Float,
+            move |c| FieldValue::Float(get(c)),
+            move |c, v| match v {
+                FieldValue::Float(v) => set(c, v),
+                _ => unreachable!("checked by FieldInfo::set"),
+            },
+        )
+    }
+
+    pub fn vec3(self, name: &str, get: fn(&T) -> [f32; 3], set: fn(&mut T, [f32; 3])) -> Self {
+        self.field(
+            name,
+            FieldKind::Vec3,
+            move |c| FieldValue::Vec3(get(c)),
+            move |c, v| match v {
+                FieldValue::Vec3(v) => set(c, v),
+                _ => unreachable!("checked by FieldInfo::set"),
+            },
+        )
+    }
+
+    pub fn vec4(self, name: &str, get: fn(&T) -> [f32; 4], set: fn(&mut T, [f32; 4])) -> Self {
+        self.field(
+            name,
+            FieldKind::Vec4,
+            move |c| FieldValue::Vec4(get(c)),
+            move |c, v| match v {
+                FieldValue::Vec4(v) => set(c, v),
+                _ => unreachable!("checked by FieldInfo::set"),
+            },
+        )
+    }
+
+    /// Quaternion field; values are normalized before `set`.
+    pub fn rotation(self, name: &str, get: fn(&T) -> [f32; 4], set: fn(&mut T, [f32; 4])) -> Self {
+        self.field(
+            name,
+            FieldKind::Rotation,
+            move |c| FieldValue::Vec4(get(c)),
+            move |c, v| match v {
+                FieldValue::Vec4(v) => set(c, normalize_quat(v).unwrap_or([0.0, 0.0, 0.0, 1.0])),
+                _ => unreachable!("checked by FieldInfo::set"),
+            },
+        )
+    }
+
+    pub fn string(self, name: &str, get: fn(&T) -> String, set: fn(&mut T, String)) -> Self {
+        self.field(
+            name,
+            FieldKind::String,
+            move |c| FieldValue::String(get(c)),
+            move |c, v| match v {
+                FieldValue::String(v) => set(c, v),
+                _ => unreachable!("checked by FieldInfo::set"),
+            },
+        )
+    }
+
+    /// Inclusive bounds of each number of the field added last.
+    pub fn range(mut self, min: f32, max: f32) -> Self {
+        if let Some(f) = self.info.fields.last_mut() {
+            f.range = Some((min, max));
+        }
+        self
+    }
+
+    fn field(
+        mut self,
+        name: &str,
+        kind: FieldKind,
+        get: impl Fn(&T) -> FieldValue + Send + Sync + 'static,
+        set: impl Fn(&mut T, FieldValue) + Send + Sync + 'static,
+    ) -> Self {
+        self.info.fields.push(FieldInfo {
+            name: name.to_string(),
+            kind,
+            range: None,
+            get: Box::new(move |c| c.downcast_ref::<T>().map(&get)),
+            set: Box::new(move |c, v| match c.downcast_mut::<T>() {
+                Some(c) => {
+                    set(c, v);
+                    true
+                }
+                None => false,
+            }),
+        });
+        self
+    }
+}
+
+fn transform_component() -> ComponentDesc<Transform> {
+    ComponentDesc::<Transform>::new(TRANSFORM_COMPONENT)
+        .vec3("translation", |t| t.translation, |t, v| t.translation = v)
+        .rotation("rotation", |t| t.rotation, |t, v| t.rotation = v)
+        .vec3("scale", |t| t.scale, |t, v| t.scale = v)
+        .range(0.001, 1000.0)
+}
+
+/// Process-wide so the inspector, scene serializers and console commands share one registry.
+static COMPONENTS: Mutex<Vec<Arc<ComponentInfo>>> = Mutex::new(Vec::new());
+
+fn lock() -> Result<MutexGuard<'static, Vec<Arc<ComponentInfo>>>, String> {
+    let mut g = COMPONENTS
+        .lock()
+        .map_err(|_| "component registry mutex poisoned".to_string())?;
+    if g.is_empty() {
+        g.push(Arc::new(transform_component().info));
+    }
+    Ok(g)
+}
+
+/// Registers a component. Registering a name again with the same type (e.g. after a plugin
+/// reload) replaces its description; a different type is an error.
+pub fn register_component<T: Any + Send>(desc: ComponentDesc<T>) -> Result<(), String> {
+    let info = desc.info;
+    let valid = |c: char| c.is_ascii_alphanumeric() || c == '_';
+    if info.name.is_empty() || !info.name.chars().all(valid) {
+        return Err(format!("invalid component name: '{}'", info.name));
+    }
+
+    let mut g = lock()?;
+    match g.iter_mut().find(|c| c.name == info.name) {
+        Some(existing) if existing.type_id != info.type_id => Err(format!(
+            "component '{}' already registered as {}",
+            info.name, existing.type_name
+        )),
+        Some(existing) => {
+            *existing = Arc::new(info);
+            Ok(())
+        }
+        None => {
+            log::debug!("reflect: registered component {}", info.name);
+            g.push(Arc::new(info));
+            Ok(())
+        }
+    }
+}
+
+pub fn unregister_component(name: &str) -> bool {
+    if name == TRANSFORM_COMPONENT {
+        return false;
+    }
+    let Ok(mut g) = lock() else {
+        return false;
+    };
+    let before = g.len();
+    g.retain(|c| c.name != name);
+    g.len() != before
+}
+
+pub fn component_info(name: &str) -> Option<Arc<ComponentInfo>> {
+    lock().ok()?.iter().find(|c| c.name == name).cloned()
+}
+
+/// Component stored as the Rust type `type_id`, e.g. to render an arbitrary value.
+pub fn component_for_type(type_id: TypeId) -> Option<Arc<ComponentInfo>> {
+    lock().ok()?.iter().find(|c| c.type_id == type_id).cloned()
+}
+
+/// Registered components in registration order, [`TRANSFORM_COMPONENT`] first.
+pub fn component_list() -> Vec<Arc<ComponentInfo>> {
+    lock().map(|g| g.clone()).unwrap_or_default()
+}
+
+/// Entities whose components are reached by name: the editor scene, a game world.
+pub trait ComponentStore: Send + Sync {
+    /// Components entity `id` has; `None` when there is no such entity.
+    fn components(&self, id: u32) -> Option<Vec<String>>;
+
+    /// Copy of component `name` of entity `id`.
+    fn get_component(&self, id: u32, name: &str) -> Option<Box<dyn Any + Send>>;
+
+    /// Replaces component `name` of entity `id`.
+    fn set_component(&self, id: u32, name: &str, value: Box<dyn Any + Send>) -> Result<(), String>;
+}
+
+static STORE: Mutex<Option<Arc<dyn ComponentStore>>> = Mutex::new(None);
+
+/// Entities `entity.set` edits; `None` detaches them.
+pub fn set_component_store(store: Option<Arc<dyn ComponentStore>>) {
+    if let Ok(mut g) = STORE.lock() {
+        *g = store;
+    }
+}
+
+pub fn component_store() -> Option<Arc<dyn ComponentStore>> {
+    STORE.lock().ok()?.clone()
+}
+
+/// Undoable replacement of one component of an entity, kept serialized.
+///
+/// Like other scene edits it only applies over the value it started from, so it fails instead
+/// of overwriting a component changed by something else since. Consecutive edits of the same
+/// field merge.
+pub struct ComponentSet {
+    store: Arc<dyn ComponentStore>,
+    id: u32,
+    component: String,
+    /// What changed, for the history: `transform.scale`, or just the component.
+    path: String,
+    before: Value,
+    after: Value,
+}
+
+impl ComponentSet {
+    /// Setting component `component` of entity `id` to `value`.
+    pub fn new(
+        store: Arc<dyn ComponentStore>,
+        id: u32,
+        component: &str,
+        value: &dyn Any,
+        path: &str,
+    ) -> Result<Self, String> {
+        let info =
+            component_info(component).ok_or_else(|| format!("unknown component: {component}"))?;
+        let current = store
+            .get_component(id, component)
+            .ok_or_else(|| format!("entity #{id} has no {component}"))?;
+        Ok(Self {
+            before: info.serialize(&*current)?,
+            after: info.serialize(value)?,
+            store,
+            id,
+            component: component.to_string(),
+            path: path.to_string(),
+        })
+    }
+
+    /// Whether applying changes anything.
+    #[inline]
+    pub fn is_noop(&self) -> bool {
+        self.before == self.after
+    }
+
+    fn replace(&self, from: &Value, to: &Value) -> Result<(), String> {
+        let info = component_info(&self.component)
+            .ok_or_else(|| format!("unknown component: {}", self.component))?;
+        let current = self
+            .store
+            .get_component(self.id, &self.component)
+            .ok_or_else(|| format!("entity #{} has no {}", self.id, self.component))?;
+        if info.serialize(&*current)? != *from {
+            return Err(format!(
+                "{} of entity #{} has changed since",
+                self.component, self.id
+            ));
+        }
+        self.store
+            .set_component(self.id, &self.component, info.deserialize(to)?)
+    }
+}
+
+impl Command for ComponentSet {
+    fn label(&self) -> String {
+        format!("set #{} {}", self.id, self.path)
+    }
+
+    fn apply(&mut self) -> Result<(), String> {
+        self.replace(&self.before, &self.after)
+    }
+
+    fn revert(&mut self) -> Result<(), String> {
+        self.replace(&self.after, &self.before)
+    }
+
+    fn merge(&mut self, next: &dyn Any) -> bool {
+        let Some(next) = next.downcast_ref::<Self>() else {
+            return false;
+        };
+        if next.id != self.id || next.path != self.path || next.before != self.after {
+            return false;
+        }
+        self.after = next.after.clone();
+        true
+    }
+}
+
+/// Sets field `path` (`component.field`) of entity `id` from console text through the global
+/// [`UndoStack`]; returns the value applied.
+pub fn set_entity_field(id: u32, path: &str, text: &str) -> Result<FieldValue, String> {
+    let store = component_store().ok_or_else(|| "no entities to edit".to_string())?;
+    let (component, field) = path
+        .split_once('.')
+        .ok_or_else(|| format!("expected <component.field>, got '{path}'"))?;
+    let info =
+        component_info(component).ok_or_else(|| format!("unknown component: {component}"))?;
+    let field = info
+        .field(field)
+        .ok_or_else(|| format!("{component} has no field '{field}'"))?;
+
+    let mut value = store
+        .get_component(id, component)
+        .ok_or_else(|| format!("entity #{id} has no {component}"))?;
+    field.set(&mut *value, field.kind.parse(text)?)?;
+    let applied = field
+        .get(&*value)
+        .ok_or_else(|| format!("{}: wrong component type", info.name))?;
+
+    let cmd = ComponentSet::new(store, id, component, &*value, path)?;
+    if !cmd.is_noop() {
+        UndoStack::global().execute(cmd)?;
+    }
+    Ok(applied)
+}
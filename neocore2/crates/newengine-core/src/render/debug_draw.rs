@@ -0,0 +1,415 @@
+use super::Color4;
+
+use parking_lot::Mutex;
+use serde::Deserialize;
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+
+/// Queued primitives above this are dropped until the next flush.
+pub const MAX_DEBUG_PRIMITIVES: usize = 65_536;
+
+const SPHERE_SEGMENTS: usize = 24;
+
+/// One end of a debug line. `color` is straight (not premultiplied) RGBA8, little endian.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(C)]
+pub struct DebugVertex {
+    pub pos: [f32; 3],
+    pub color: u32,
+}
+
+/// Camera-facing label anchored (bottom center) at `pos`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DebugText {
+    pub pos: [f32; 3],
+    pub text: String,
+    pub color: u32,
+    /// Height in world units, so the label shrinks with distance; `0` keeps it
+    /// [`DebugText::SCREEN_PX`] tall on screen.
+    pub size: f32,
+    /// Hide the label behind scene geometry. Only honored by backends with a scene depth
+    /// buffer; others draw every label over the scene.
+    pub depth_test: bool,
+}
+
+impl DebugText {
+    pub const SCREEN_PX: f32 = 16.0;
+}
+
+/// Camera-facing solid quad, e.g. a waypoint marker.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DebugBillboard {
+    pub center: [f32; 3],
+    /// Width and height in world units.
+    pub size: [f32; 2],
+    pub color: u32,
+    /// As [`DebugText::depth_test`].
+    pub depth_test: bool,
+}
+
+/// World-space shapes accepted by [`DebugDraw::submit`] and the `engine.debug_draw` service.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DebugShape {
+    Line {
+        a: [f32; 3],
+        b: [f32; 3],
+    },
+    Aabb {
+        min: [f32; 3],
+        max: [f32; 3],
+    },
+    Box {
+        center: [f32; 3],
+        half_extents: [f32; 3],
+    },
+    Sphere {
+        center: [f32; 3],
+        radius: f32,
+    },
+    Cross {
+        center: [f32; 3],
+        size: f32,
+    },
+    Text {
+        pos: [f32; 3],
+        text: String,
+        /// See [`DebugText::size`].
+        #[serde(default)]
+        size: f32,
+        #[serde(default)]
+        depth_test: bool,
+    },
+    Billboard {
+        center: [f32; 3],
+        size: [f32; 2],
+        #[serde(default)]
+        depth_test: bool,
+    },
+}
+
+/// Everything to draw this frame, produced by [`DebugDraw::flush`].
+#[derive(Debug, Clone, Default)]
+pub struct DebugDrawBatch {
+    /// Column-major, as set by [`DebugDraw::set_view_proj`].
+    pub view_proj: [f32; 16],
+    /// Line list: every two vertices form one segment.
+    pub vertices: Vec<DebugVertex>,
+    pub texts: Vec<DebugText>,
+    pub billboards: Vec<DebugBillboard>,
+}
+
+impl DebugDrawBatch {
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.vertices.is_empty() && self.texts.is_empty() && self.billboards.is_empty()
+    }
+
+    /// Clip-space `w` of `pos`: the distance along the view direction for perspective
+    /// cameras. Camera-facing items are drawn in decreasing order of it.
+    pub fn view_depth(&self, pos: [f32; 3]) -> f32 {
+        let m = &self.view_proj;
+        m[3] * pos[0] + m[7] * pos[1] + m[11] * pos[2] + m[15]
+    }
+
+    /// Pixels per world unit on a camera-facing plane through `pos`, or `None` behind the
+    /// camera. Scales world-sized labels and billboards.
+    pub fn pixels_per_unit(&self, pos: [f32; 3], size_px: [u32; 2]) -> Option<f32> {
+        // The first row of view-projection points along the camera's right axis.
+        let m = &self.view_proj;
+        let right = [m[0], m[4], m[8]];
+        let len = (right[0] * right[0] + right[1] * right[1] + right[2] * right[2]).sqrt();
+        if len <= f32::EPSILON {
+            return None;
+        }
+        let step = [
+            pos[0] + right[0] / len,
+            pos[1] + right[1] / len,
+            pos[2] + right[2] / len,
+        ];
+        let a = self.project(pos, size_px)?;
+        let b = self.project(step, size_px)?;
+        Some((b[0] - a[0]).hypot(b[1] - a[1]))
+    }
+
+    /// Pixel position of `pos` on a `size_px` target (y down), or `None` behind the camera.
+    pub fn project(&self, pos: [f32; 3], size_px: [u32; 2]) -> Option<[f32; 2]> {
+        let m = &self.view_proj;
+        let clip = |r: usize| m[r] * pos[0] + m[4 + r] * pos[1] + m[8 + r] * pos[2] + m[12 + r];
+        let w = clip(3);
+        if w <= f32::EPSILON {
+            return None;
+        }
+        let (x, y) = (clip(0) / w, clip(1) / w);
+        Some([
+            (x * 0.5 + 0.5) * size_px[0] as f32,
+            (y * 0.5 + 0.5) * size_px[1] as f32,
+        ])
+    }
+}
+
+struct Queued {
+    shape: DebugShape,
+    color: u32,
+    /// `None`: drawn by the next flush only.
+    until: Option<Instant>,
+}
+
+struct DebugDrawState {
+    queue: Vec<Queued>,
+    view_proj: [f32; 16],
+    dropped: usize,
+}
+
+/// Immediate-mode debug geometry: lines, boxes, spheres and world-space text.
+///
+/// Anything may queue primitives (modules through the `DebugDraw` resource, plugins through
+/// the `engine.debug_draw` service); the render backend flushes the queue once per frame.
+/// A `duration` of zero draws for one frame, otherwise the primitive stays for that many
+/// seconds. Whoever owns the camera sets the view-projection with [`DebugDraw::set_view_proj`].
+#[derive(Clone)]
+pub struct DebugDraw(Arc<Mutex<DebugDrawState>>);
+
+impl DebugDraw {
+    /// Process-wide queue; the engine also inserts it into `Resources`.
+    pub fn global() -> Self {
+        static GLOBAL: OnceLock<DebugDraw> = OnceLock::new();
+        GLOBAL
+            .get_or_init(|| {
+                DebugDraw(Arc::new(Mutex::new(DebugDrawState {
+                    queue: Vec::new(),
+                    view_proj: IDENTITY,
+                    dropped: 0,
+                })))
+            })
+            .clone()
+    }
+
+    pub fn submit(&self, shape: DebugShape, color: Color4, duration: f32) {
+        let mut g = self.0.lock();
+        if g.queue.len() >= MAX_DEBUG_PRIMITIVES {
+            g.dropped += 1;
+            return;
+        }
+        let until = (duration > 0.0).then(|| Instant::now() + Duration::from_secs_f32(duration));
+        g.queue.push(Queued {
+            shape,
+            color: pack_rgba8(color),
+            until,
+        });
+    }
+
+    #[inline]
+    pub fn line(&self, a: [f32; 3], b: [f32; 3], color: Color4, duration: f32) {
+        self.submit(DebugShape::Line { a, b }, color, duration);
+    }
+
+    #[inline]
+    pub fn aabb(&self, min: [f32; 3], max: [f32; 3], color: Color4, duration: f32) {
+        self.submit(DebugShape::Aabb { min, max }, color, duration);
+    }
+
+    #[inline]
+    pub fn cuboid(&self, center: [f32; 3], half_extents: [f32; 3], color: Color4, duration: f32) {
+        self.submit(
+            DebugShape::Box {
+                center,
+                half_extents,
+            },
+            color,
+            duration,
+        );
+    }
+
+    #[inline]
+    pub fn sphere(&self, center: [f32; 3], radius: f32, color: Color4, duration: f32) {
+        self.submit(DebugShape::Sphere { center, radius }, color, duration);
+    }
+
+    #[inline]
+    pub fn cross(&self, center: [f32; 3], size: f32, color: Color4, duration: f32) {
+        self.submit(DebugShape::Cross { center, size }, color, duration);
+    }
+
+    /// Label of a fixed on-screen size; see [`DebugDraw::label`] for world-sized ones.
+    #[inline]
+    pub fn text3d(&self, pos: [f32; 3], text: impl Into<String>, color: Color4, duration: f32) {
+        self.label(pos, text, 0.0, false, color, duration);
+    }
+
+    /// Camera-facing label `size` world units tall (`0`: fixed on-screen size), e.g. an entity
+    /// name or a profiling readout.
+    #[inline]
+    pub fn label(
+        &self,
+        pos: [f32; 3],
+        text: impl Into<String>,
+        size: f32,
+        depth_test: bool,
+        color: Color4,
+        duration: f32,
+    ) {
+        let text = text.into();
+        self.submit(
+            DebugShape::Text {
+                pos,
+                text,
+                size,
+                depth_test,
+            },
+            color,
+            duration,
+        );
+    }
+
+    /// Camera-facing quad of `size` world units, e.g. a waypoint marker.
+    #[inline]
+    pub fn billboard(&self, center: [f32; 3], size: [f32; 2], color: Color4, duration: f32) {
+        let shape = DebugShape::Billboard {
+            center,
+            size,
+            depth_test: false,
+        };
+        self.submit(shape, color, duration);
+    }
+
+    /// Column-major view-projection used to place the geometry.
+    #[inline]
+    pub fn set_view_proj(&self, view_proj: [f32; 16]) {
+        self.0.lock().view_proj = view_proj;
+    }
+
+    /// Drops everything, including primitives with time left.
+    #[inline]
+    pub fn clear(&self) {
+        self.0.lock().queue.clear();
+    }
+
+    /// `(queued, dropped since the last flush)`.
+    #[inline]
+    pub fn stats(&self) -> (usize, usize) {
+        let g = self.0.lock();
+        (g.queue.len(), g.dropped)
+    }
+
+    /// Tessellates the queue into a batch and removes expired and one-frame primitives.
+    pub fn flush(&self) -> DebugDrawBatch {
+        let now = Instant::now();
+        let mut g = self.0.lock();
+
+        if g.dropped > 0 {
+            log::warn!(
+                "debug_draw: dropped {} primitives (limit {MAX_DEBUG_PRIMITIVES})",
+                g.dropped
+            );
+            g.dropped = 0;
+        }
+
+        let mut batch = DebugDrawBatch {
+            view_proj: g.view_proj,
+            ..DebugDrawBatch::default()
+        };
+        for q in &g.queue {
+            tessellate(&q.shape, q.color, &mut batch);
+        }
+        g.queue.retain(|q| q.until.is_some_and(|t| t > now));
+        batch
+    }
+}
+
+const IDENTITY: [f32; 16] = [
+    1.0, 0.0, 0.0, 0.0, //
+    0.0, 1.0, 0.0, 0.0, //
+    0.0, 0.0, 1.0, 0.0, //
+    0.0, 0.0, 0.0, 1.0,
+];
+
+#[inline]
+fn pack_rgba8(c: Color4) -> u32 {
+    let b = |v: f32| (v.clamp(0.0, 1.0) * 255.0 + 0.5) as u8;
+    u32::from_le_bytes([b(c[0]), b(c[1]), b(c[2]), b(c[3])])
+}
+
+fn tessellate(shape: &DebugShape, color: u32, out: &mut DebugDrawBatch) {
+    let mut seg = |a: [f32; 3], b: [f32; 3]| {
+        out.vertices.push(DebugVertex { pos: a, color });
+        out.vertices.push(DebugVertex { pos: b, color });
+    };
+
+    match shape {
+        DebugShape::Line { a, b } => seg(*a, *b),
+        DebugShape::Aabb { min, max } => aabb_edges(*min, *max, &mut seg),
+        DebugShape::Box {
+            center: c,
+            half_extents: h,
+        } => aabb_edges(
+            [c[0] - h[0], c[1] - h[1], c[2] - h[2]],
+            [c[0] + h[0], c[1] + h[1], c[2] + h[2]],
+            &mut seg,
+        ),
+        DebugShape::Sphere { center: c, radius } => {
+            // One great circle per axis plane.
+            for (u, v) in [(0, 1), (1, 2), (0, 2)] {
+                let point = |i: usize| {
+                    let a = i as f32 / SPHERE_SEGMENTS as f32 * std::f32::consts::TAU;
+                    let mut p = *c;
+                    p[u] += a.cos() * radius;
+                    p[v] += a.sin() * radius;
+                    p
+                };
+                for i in 0..SPHERE_SEGMENTS {
+                    seg(point(i), point(i + 1));
+                }
+            }
+        }
+        DebugShape::Cross { center: c, size } => {
+            let h = size * 0.5;
+            for axis in 0..3 {
+                let (mut a, mut b) = (*c, *c);
+                a[axis] -= h;
+                b[axis] += h;
+                seg(a, b);
+            }
+        }
+        DebugShape::Text {
+            pos,
+            text,
+            size,
+            depth_test,
+        } => out.texts.push(DebugText {
+            pos: *pos,
+            text: text.clone(),
+            color,
+            size: size.max(0.0),
+            depth_test: *depth_test,
+        }),
+        DebugShape::Billboard {
+            center,
+            size,
+            depth_test,
+        } => out.billboards.push(DebugBillboard {
+            center: *center,
+            size: *size,
+            color,
+            depth_test: *depth_test,
+        }),
+    }
+}
+
+fn aabb_edges(min: [f32; 3], max: [f32; 3], seg: &mut impl FnMut([f32; 3], [f32; 3])) {
+    let corner = |i: usize| {
+        [
+            if i & 1 == 0 { min[0] } else { max[0] },
+            if i & 2 == 0 { min[1] } else { max[1] },
+            if i & 4 == 0 { min[2] } else { max[2] },
+        ]
+    };
+    // Corners differing in exactly one bit share an edge.
+    for i in 0..8 {
+        for bit in [1, 2, 4] {
+            if i & bit == 0 {
+                seg(corner(i), corner(i | bit));
+            }
+        }
+    }
+}
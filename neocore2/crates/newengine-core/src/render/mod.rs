@@ -3,9 +3,22 @@ use crate::module::{ApiProvide, ApiVersion};
 
 use newengine_ui::draw::UiDrawList;
 use parking_lot::{Mutex, MutexGuard};
+use raw_window_handle::{RawDisplayHandle, RawWindowHandle};
+use serde::Deserialize;
 use std::num::NonZeroU32;
 use std::sync::Arc;
 
+pub mod debug_draw;
+pub mod null;
+pub mod post;
+
+pub use debug_draw::{
+    DebugBillboard, DebugDraw, DebugDrawBatch, DebugShape, DebugText, DebugVertex,
+    MAX_DEBUG_PRIMITIVES,
+};
+pub use null::{NullRenderApi, NullRenderModule, NullRenderProbe, NullRenderStats};
+pub use post::{post_cvar, PostProcessSettings};
+
 pub const RENDER_API_ID: &str = "render.api";
 pub const RENDER_API_VERSION: ApiVersion = ApiVersion::new(0, 2, 0);
 pub const RENDER_API_PROVIDE: ApiProvide = ApiProvide::new(RENDER_API_ID, RENDER_API_VERSION);
@@ -37,16 +50,21 @@ impl Extent2D {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum BufferUsage {
     Vertex,
     Index,
     Uniform,
     Storage,
     Staging,
+    /// Draw commands for [`RenderApi::draw_indirect`] / [`RenderApi::draw_indexed_indirect`];
+    /// also writable as a storage buffer so a compute pass can fill it.
+    Indirect,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum MemoryHint {
     GpuOnly,
     CpuToGpu,
@@ -79,15 +97,34 @@ impl BufferDesc {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum TextureFormat {
     Rgba8Unorm,
     Bgra8Unorm,
     Rgba16Float,
+    /// One unsigned integer per texel; the color format of pipelines that draw into the
+    /// object-ID pass (see [`RenderApi::begin_id_pass`]).
+    R32Uint,
     Depth24Stencil8,
     Depth32Float,
 }
 
+impl TextureFormat {
+    /// Bytes per texel.
+    #[inline]
+    pub const fn texel_size(self) -> u32 {
+        match self {
+            Self::Rgba16Float => 8,
+            Self::Rgba8Unorm
+            | Self::Bgra8Unorm
+            | Self::R32Uint
+            | Self::Depth24Stencil8
+            | Self::Depth32Float => 4,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TextureUsage {
     Sampled,
@@ -103,6 +140,8 @@ pub struct TextureDesc {
     pub format: TextureFormat,
     pub usage: TextureUsage,
     pub mip_levels: NonZeroU32,
+    /// Cubemap of six square `extent` faces, bound as [`BindingKind::TextureCube`].
+    pub cube: bool,
 }
 
 impl TextureDesc {
@@ -114,6 +153,7 @@ impl TextureDesc {
             format,
             usage,
             mip_levels: NonZeroU32::new(1).unwrap(),
+            cube: false,
         }
     }
 
@@ -128,6 +168,23 @@ impl TextureDesc {
         self.mip_levels = mip_levels;
         self
     }
+
+    /// Makes this a cubemap; `extent` is the size of one face.
+    #[inline]
+    pub fn with_cube(mut self) -> Self {
+        self.cube = true;
+        self
+    }
+
+    /// Array layers: 6 for cubemaps, 1 otherwise.
+    #[inline]
+    pub const fn layers(&self) -> u32 {
+        if self.cube {
+            6
+        } else {
+            1
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -177,7 +234,8 @@ impl SamplerDesc {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum ShaderStage {
     Vertex,
     Fragment,
@@ -210,7 +268,8 @@ impl ShaderDesc {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum PrimitiveTopology {
     TriangleList,
     TriangleStrip,
@@ -218,21 +277,26 @@ pub enum PrimitiveTopology {
     LineStrip,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum IndexFormat {
     U16,
     U32,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum VertexFormat {
     Float32x2,
     Float32x3,
     Float32x4,
     Unorm8x4,
+    /// Integer joint indices (skinning).
+    Uint8x4,
+    Uint16x4,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Deserialize)]
 pub struct VertexAttribute {
     pub location: u32,
     pub offset: u32,
@@ -250,17 +314,222 @@ impl VertexAttribute {
     }
 }
 
+/// What a vertex buffer slot carries. Lets pipelines declare deformation inputs explicitly
+/// instead of relying on attribute locations alone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VertexStream {
+    /// Position/normal/uv and other per-vertex surface data.
+    Base,
+    /// Joint indices + weights for GPU skinning.
+    Skin,
+}
+
+impl Default for VertexStream {
+    #[inline]
+    fn default() -> Self {
+        Self::Base
+    }
+}
+
+/// How often a vertex buffer slot advances.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VertexStepMode {
+    /// Once per vertex.
+    #[default]
+    Vertex,
+    /// Once per instance: per-instance transforms, colors, etc.
+    Instance,
+}
+
+/// Attribute locations used by the default material set.
+pub mod vertex_location {
+    pub const POSITION: u32 = 0;
+    pub const NORMAL: u32 = 1;
+    pub const UV: u32 = 2;
+    pub const JOINTS: u32 = 3;
+    pub const WEIGHTS: u32 = 4;
+}
+
 #[derive(Debug, Clone)]
 pub struct VertexLayout {
     pub stride: u32,
     pub attributes: Vec<VertexAttribute>,
+    pub stream: VertexStream,
+    pub step_mode: VertexStepMode,
 }
 
 impl VertexLayout {
     #[inline]
     pub fn new(stride: u32, attributes: Vec<VertexAttribute>) -> Self {
-        Self { stride, attributes }
+        Self {
+            stride,
+            attributes,
+            stream: VertexStream::Base,
+            step_mode: VertexStepMode::Vertex,
+        }
+    }
+
+    #[inline]
+    pub fn with_stream(mut self, stream: VertexStream) -> Self {
+        self.stream = stream;
+        self
+    }
+
+    /// Instance buffer layout: the slot advances once per instance.
+    #[inline]
+    pub fn per_instance(mut self) -> Self {
+        self.step_mode = VertexStepMode::Instance;
+        self
+    }
+
+    /// Base stream of the default material set: `position: f32x3, normal: f32x3, uv: f32x2`.
+    pub fn default_mesh() -> Self {
+        Self::new(
+            32,
+            vec![
+                VertexAttribute::new(vertex_location::POSITION, 0, VertexFormat::Float32x3),
+                VertexAttribute::new(vertex_location::NORMAL, 12, VertexFormat::Float32x3),
+                VertexAttribute::new(vertex_location::UV, 24, VertexFormat::Float32x2),
+            ],
+        )
     }
+
+    /// Skin stream of the default material set: `joints: u16x4, weights: f32x4`.
+    pub fn default_skin() -> Self {
+        Self::new(
+            24,
+            vec![
+                VertexAttribute::new(vertex_location::JOINTS, 0, VertexFormat::Uint16x4),
+                VertexAttribute::new(vertex_location::WEIGHTS, 8, VertexFormat::Float32x4),
+            ],
+        )
+        .with_stream(VertexStream::Skin)
+    }
+}
+
+/// GPU vertex deformation enabled for a pipeline.
+///
+/// Skinning reads joint matrices from a [`BindingKind::BoneMatrices`] buffer and needs a
+/// [`VertexStream::Skin`] layout. Morph targets read per-target weights and per-vertex deltas
+/// from [`BindingKind::MorphWeights`] / [`BindingKind::MorphDeltas`]; no extra vertex stream.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct VertexDeformation {
+    pub skinning: bool,
+    pub morph_targets: bool,
+}
+
+impl VertexDeformation {
+    pub const NONE: Self = Self {
+        skinning: false,
+        morph_targets: false,
+    };
+
+    #[inline]
+    pub const fn is_none(self) -> bool {
+        !self.skinning && !self.morph_targets
+    }
+}
+
+/// Materials every backend with `default_material_shaders` support provides.
+///
+/// The lit ones shade with the forward light list bound at [`LIGHTS_BIND_GROUP`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DefaultMaterial {
+    /// Single-color, lambert-lit surface. Set 0: object uniform; set 1: deformation buffers;
+    /// set 3: lights.
+    Lit,
+    /// Terrain blending up to four layers by a splat map; no deformation.
+    ///
+    /// Set 0: object uniform extended with `vec4 tiling` (xy: layer repeats across the
+    /// terrain) and `vec4 layer_tint[4]`, [`TERRAIN_OBJECT_UBO_SIZE`] bytes in all. Set 1: the
+    /// splat map (texture + sampler), whose RGBA channels weight layers 0..3 over the
+    /// vertex uv. Set 2: the layer textures packed as a 2x2 atlas (texture + sampler), layer
+    /// `i` in cell `(i % 2, i / 2)`. Set 3: lights.
+    TerrainSplat,
+    /// Depth only, for [`PipelineDesc::depth_only`] pipelines (shadow maps). Same vertex path
+    /// and sets 0 and 1 as [`DefaultMaterial::Lit`]; `view_proj` is the light's matrix.
+    Depth,
+    /// Sky seen through every pixel; draw it first, with no vertex buffers and 3 vertices (one
+    /// triangle over the target). No deformation.
+    ///
+    /// Set 0: `mat4 inv_view_proj` (clip space to a world direction; leave out the camera
+    /// translation) and `vec4 tint` (rgb multiplies the sky), [`SKYBOX_UBO_SIZE`] bytes. Set 1:
+    /// the cubemap ([`BindingKind::TextureCube`] + sampler).
+    Skybox,
+    /// Object id of the draw, for [`TextureFormat::R32Uint`] pipelines in the object-ID pass
+    /// ([`RenderApi::begin_id_pass`]). Same vertex path and sets 0 and 1 as
+    /// [`DefaultMaterial::Lit`]; set 2 ([`OBJECT_ID_BIND_GROUP`]) holds a `uvec4` uniform
+    /// with the id in `x`, [`OBJECT_ID_UBO_SIZE`] bytes. Id 0 reads back as no object.
+    ObjectId,
+}
+
+/// Bind group index of deformation buffers in the default material set.
+pub const DEFORMATION_BIND_GROUP: u32 = 1;
+/// Bind group index of the splat map of [`DefaultMaterial::TerrainSplat`].
+pub const TERRAIN_SPLAT_BIND_GROUP: u32 = 1;
+/// Bind group index of the layer atlas of [`DefaultMaterial::TerrainSplat`].
+pub const TERRAIN_LAYERS_BIND_GROUP: u32 = 2;
+/// Size of the [`DefaultMaterial::TerrainSplat`] object uniform: `view_proj`, `model`,
+/// `color`, `tiling`, `layer_tint[4]`.
+pub const TERRAIN_OBJECT_UBO_SIZE: u64 = 64 + 64 + 16 + 16 + 4 * 16;
+/// Size of the [`DefaultMaterial::Skybox`] uniform: `inv_view_proj`, `tint`.
+pub const SKYBOX_UBO_SIZE: u64 = 64 + 16;
+/// Bind group index of the id uniform of [`DefaultMaterial::ObjectId`].
+pub const OBJECT_ID_BIND_GROUP: u32 = 2;
+/// Size of the [`DefaultMaterial::ObjectId`] id uniform: `uvec4 id`.
+pub const OBJECT_ID_UBO_SIZE: u64 = 16;
+/// Bind group index of the light list in the default material set. Pipelines that leave the
+/// groups below it unused still give them a layout; an empty one will do.
+pub const LIGHTS_BIND_GROUP: u32 = 3;
+/// Bytes before the first light of the light list: `u32 count`, 3 pad words, `vec4 ambient`.
+pub const LIGHT_LIST_HEADER_SIZE: u64 = 32;
+/// Bytes per light of the light list: `vec4 position_range`, `vec4 color_intensity`.
+pub const GPU_LIGHT_SIZE: u64 = 32;
+/// Shadow cascades the light list's shadow uniform holds.
+pub const MAX_SHADOW_CASCADES: usize = 4;
+/// Size of the shadow uniform of the light list: `mat4 cascade_view_proj[4]`, `vec4 params`.
+pub const SHADOW_UNIFORM_SIZE: u64 = MAX_SHADOW_CASCADES as u64 * 64 + 16;
+
+/// Blending of the color target with what is already there.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BlendState {
+    /// No blending; the output replaces the target.
+    #[default]
+    Opaque,
+    /// `src * src.a + dst * (1 - src.a)`.
+    Alpha,
+    /// `src * src.a + dst`; glows, particles.
+    Additive,
+    /// `src + dst * (1 - src.a)`, for colors already multiplied by alpha.
+    Premultiplied,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CullMode {
+    None,
+    Front,
+    #[default]
+    Back,
+}
+
+/// Winding of front-facing triangles in framebuffer space.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FrontFace {
+    #[default]
+    CounterClockwise,
+    Clockwise,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PolygonMode {
+    #[default]
+    Fill,
+    /// Triangle edges only (wireframe). Backends may not support it.
+    Line,
 }
 
 #[derive(Debug, Clone)]
@@ -271,8 +540,16 @@ pub struct PipelineDesc {
     pub topology: PrimitiveTopology,
     pub vertex_layouts: Vec<VertexLayout>,
     pub bind_group_layouts: Vec<BindGroupLayoutId>,
+    pub deformation: VertexDeformation,
     pub color_format: TextureFormat,
     pub depth_format: Option<TextureFormat>,
+    pub blend: BlendState,
+    pub cull_mode: CullMode,
+    pub front_face: FrontFace,
+    pub polygon_mode: PolygonMode,
+    /// Draws inside [`RenderApi::begin_depth_pass`] passes: no color output, depth test and
+    /// write into `depth_format`.
+    pub depth_only: bool,
 }
 
 impl PipelineDesc {
@@ -285,11 +562,26 @@ impl PipelineDesc {
             topology: PrimitiveTopology::TriangleList,
             vertex_layouts: Vec::new(),
             bind_group_layouts: Vec::new(),
+            deformation: VertexDeformation::NONE,
             color_format,
             depth_format: None,
+            blend: BlendState::Opaque,
+            cull_mode: CullMode::Back,
+            front_face: FrontFace::CounterClockwise,
+            polygon_mode: PolygonMode::Fill,
+            depth_only: false,
         }
     }
 
+    /// Pipeline for depth passes. There is no color target: `color_format` is ignored and any
+    /// color `fs` writes is dropped.
+    #[inline]
+    pub fn depth_only(vs: ShaderId, fs: ShaderId, depth_format: TextureFormat) -> Self {
+        let mut desc = Self::new(vs, fs, depth_format).with_depth(depth_format);
+        desc.depth_only = true;
+        desc
+    }
+
     #[inline]
     pub fn with_label(mut self, label: &'static str) -> Self {
         self.label = Some(label);
@@ -325,6 +617,56 @@ impl PipelineDesc {
         self.depth_format = Some(depth_format);
         self
     }
+
+    #[inline]
+    pub fn with_deformation(mut self, deformation: VertexDeformation) -> Self {
+        self.deformation = deformation;
+        self
+    }
+
+    #[inline]
+    pub fn with_blend(mut self, blend: BlendState) -> Self {
+        self.blend = blend;
+        self
+    }
+
+    #[inline]
+    pub fn with_cull_mode(mut self, cull_mode: CullMode) -> Self {
+        self.cull_mode = cull_mode;
+        self
+    }
+
+    #[inline]
+    pub fn with_front_face(mut self, front_face: FrontFace) -> Self {
+        self.front_face = front_face;
+        self
+    }
+
+    #[inline]
+    pub fn with_polygon_mode(mut self, polygon_mode: PolygonMode) -> Self {
+        self.polygon_mode = polygon_mode;
+        self
+    }
+
+    /// Checks deformation declarations against vertex streams (backend-agnostic part).
+    pub fn validate_deformation(&self) -> EngineResult<()> {
+        let has_skin_stream = self
+            .vertex_layouts
+            .iter()
+            .any(|l| l.stream == VertexStream::Skin);
+
+        if self.deformation.skinning && !has_skin_stream {
+            return Err(EngineError::other(
+                "PipelineDesc: skinning enabled but no VertexStream::Skin layout declared",
+            ));
+        }
+        if !self.deformation.skinning && has_skin_stream {
+            return Err(EngineError::other(
+                "PipelineDesc: VertexStream::Skin layout declared but skinning is disabled",
+            ));
+        }
+        Ok(())
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -387,6 +729,15 @@ pub struct BindGroupLayoutId(NonZeroU32);
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct BindGroupId(NonZeroU32);
 
+/// An OS window the backend presents to. [`ViewportId::MAIN`] is the window the backend was
+/// created for; more are added with [`RenderApi::create_viewport`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ViewportId(NonZeroU32);
+
+/// A TrueType/OpenType font loaded with [`RenderApi::load_font`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FontId(NonZeroU32);
+
 #[allow(dead_code)]
 impl BufferId {
     #[inline]
@@ -398,15 +749,20 @@ impl BufferId {
 #[allow(dead_code)]
 impl TextureId {
     #[inline]
-    pub(crate) fn new(v: u32) -> Self {
+    pub fn new(v: u32) -> Self {
         Self(NonZeroU32::new(v).expect("TextureId must be non-zero"))
     }
+
+    #[inline]
+    pub const fn get(self) -> u32 {
+        self.0.get()
+    }
 }
 
 #[allow(dead_code)]
 impl SamplerId {
     #[inline]
-    pub(crate) fn new(v: u32) -> Self {
+    pub fn new(v: u32) -> Self {
         Self(NonZeroU32::new(v).expect("SamplerId must be non-zero"))
     }
 }
@@ -443,6 +799,32 @@ impl BindGroupId {
     }
 }
 
+impl ViewportId {
+    pub const MAIN: Self = Self(NonZeroU32::MIN);
+
+    #[inline]
+    pub fn new(v: u32) -> Self {
+        Self(NonZeroU32::new(v).expect("ViewportId must be non-zero"))
+    }
+
+    #[inline]
+    pub const fn get(self) -> u32 {
+        self.0.get()
+    }
+}
+
+impl FontId {
+    #[inline]
+    pub fn new(v: u32) -> Self {
+        Self(NonZeroU32::new(v).expect("FontId must be non-zero"))
+    }
+
+    #[inline]
+    pub const fn get(self) -> u32 {
+        self.0.get()
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct BufferSlice {
     pub buffer: BufferId,
@@ -498,12 +880,65 @@ impl DrawIndexedArgs {
     }
 }
 
+/// One draw of [`RenderApi::draw_indirect`]; same layout as `VkDrawIndirectCommand`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DrawIndirectCommand {
+    pub vertex_count: u32,
+    pub instance_count: u32,
+    pub first_vertex: u32,
+    pub first_instance: u32,
+}
+
+impl DrawIndirectCommand {
+    pub const SIZE: u64 = std::mem::size_of::<Self>() as u64;
+}
+
+/// One draw of [`RenderApi::draw_indexed_indirect`]; same layout as
+/// `VkDrawIndexedIndirectCommand`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DrawIndexedIndirectCommand {
+    pub index_count: u32,
+    pub instance_count: u32,
+    pub first_index: u32,
+    pub vertex_offset: i32,
+    pub first_instance: u32,
+}
+
+impl DrawIndexedIndirectCommand {
+    pub const SIZE: u64 = std::mem::size_of::<Self>() as u64;
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum BindingKind {
     Texture2D,
+    /// `texture0` as a `textureCube`; the texture must be a cubemap.
+    TextureCube,
     Sampler,
     UniformBuffer,
+    /// Uniform buffer whose offset is supplied per draw by
+    /// [`RenderApi::set_bind_group_with_offsets`]; `uniform0` gives the buffer and the size of
+    /// one element. Lets per-object constants share one buffer and one bind group.
+    UniformBufferDynamic,
     StorageBuffer,
+    /// Storage buffer of `mat4` joint matrices (skinning).
+    BoneMatrices,
+    /// Storage buffer: `u32 target_count, u32 vertex_count, u32 pad[2]` then `f32` weights.
+    MorphWeights,
+    /// Storage buffer of per-target, per-vertex `{ vec4 position; vec4 normal; }` deltas,
+    /// indexed `target * vertex_count + vertex`.
+    MorphDeltas,
+}
+
+impl BindingKind {
+    #[inline]
+    pub const fn is_storage_buffer(self) -> bool {
+        matches!(
+            self,
+            Self::StorageBuffer | Self::BoneMatrices | Self::MorphWeights | Self::MorphDeltas
+        )
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -540,6 +975,39 @@ impl BindGroupLayoutDesc {
         self.label = Some(label);
         self
     }
+
+    /// Deformation group of the default material set (bindings 0..=2).
+    pub fn deformation() -> Self {
+        Self::new(vec![
+            BindingKind::BoneMatrices,
+            BindingKind::MorphWeights,
+            BindingKind::MorphDeltas,
+        ])
+        .with_label("deformation_bgl")
+    }
+
+    /// Light list of the default material set.
+    ///
+    /// Binding 0 is a std430 storage buffer holding a [`LIGHT_LIST_HEADER_SIZE`] header
+    /// (`count`, `ambient.rgb`) followed by `count` lights of [`GPU_LIGHT_SIZE`] bytes. A
+    /// light's `position_range.w` of 0 marks a directional light whose xyz is the direction it
+    /// shines in; otherwise xyz is the world position of a point light reaching `w` units.
+    /// `color_intensity` is linear RGB times intensity.
+    ///
+    /// Bindings 1..=3 shadow light 0: a [`SHADOW_UNIFORM_SIZE`] uniform, the shadow map and
+    /// its sampler. The map is a depth texture holding the cascades side by side, cascade `i`
+    /// in the `i`-th equal-width cell. The uniform holds each cascade's world-to-clip matrix
+    /// and `params` (x: cascade count, 0 when light 0 casts no shadow; y: depth bias; zw: one
+    /// texel of the map in uv units).
+    pub fn lights() -> Self {
+        Self::new(vec![
+            BindingKind::StorageBuffer,
+            BindingKind::UniformBuffer,
+            BindingKind::Texture2D,
+            BindingKind::Sampler,
+        ])
+        .with_label("lights_bgl")
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -551,6 +1019,10 @@ pub struct BindGroupDesc {
     pub sampler0: Option<SamplerId>,
     pub uniform0: Option<BufferBinding>,
     pub storage0: Option<BufferBinding>,
+
+    pub bone_matrices: Option<BufferBinding>,
+    pub morph_weights: Option<BufferBinding>,
+    pub morph_deltas: Option<BufferBinding>,
 }
 
 impl BindGroupDesc {
@@ -563,6 +1035,9 @@ impl BindGroupDesc {
             sampler0: None,
             uniform0: None,
             storage0: None,
+            bone_matrices: None,
+            morph_weights: None,
+            morph_deltas: None,
         }
     }
 
@@ -595,6 +1070,115 @@ impl BindGroupDesc {
         self.storage0 = Some(b);
         self
     }
+
+    #[inline]
+    pub fn with_bone_matrices(mut self, b: BufferBinding) -> Self {
+        self.bone_matrices = Some(b);
+        self
+    }
+
+    #[inline]
+    pub fn with_morph_weights(mut self, b: BufferBinding) -> Self {
+        self.morph_weights = Some(b);
+        self
+    }
+
+    #[inline]
+    pub fn with_morph_deltas(mut self, b: BufferBinding) -> Self {
+        self.morph_deltas = Some(b);
+        self
+    }
+
+    /// Buffer bound for a buffer-typed binding kind.
+    #[inline]
+    pub fn buffer_for(&self, kind: BindingKind) -> Option<BufferBinding> {
+        match kind {
+            BindingKind::UniformBuffer | BindingKind::UniformBufferDynamic => self.uniform0,
+            BindingKind::StorageBuffer => self.storage0,
+            BindingKind::BoneMatrices => self.bone_matrices,
+            BindingKind::MorphWeights => self.morph_weights,
+            BindingKind::MorphDeltas => self.morph_deltas,
+            BindingKind::Texture2D | BindingKind::TextureCube | BindingKind::Sampler => None,
+        }
+    }
+}
+
+/// GPU time of one backend pass (scene, UI, ...).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GpuPassTiming {
+    pub name: &'static str,
+    pub ms: f32,
+}
+
+/// Object id under a pixel of the object-ID pass, answering [`RenderApi::pick`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PickResult {
+    pub x: u32,
+    pub y: u32,
+    /// 0 where no draw wrote an id.
+    pub id: u32,
+}
+
+impl PickResult {
+    /// The id, unless the pixel shows no object.
+    #[inline]
+    pub const fn object(&self) -> Option<u32> {
+        match self.id {
+            0 => None,
+            id => Some(id),
+        }
+    }
+}
+
+/// Pixels of a presented frame: tightly packed RGBA8, top row first.
+#[derive(Debug, Clone)]
+pub struct CapturedFrame {
+    pub width: u32,
+    pub height: u32,
+    pub rgba8: Vec<u8>,
+}
+
+/// A run of screen-space text. `pos` is the top-left of the first line in pixels (y down);
+/// `\n` starts a new line.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextDraw {
+    pub text: String,
+    pub pos: [f32; 2],
+    /// Em size in pixels.
+    pub size_px: f32,
+    pub color: Color4,
+    /// `None`: the backend's built-in font.
+    pub font: Option<FontId>,
+}
+
+impl TextDraw {
+    pub fn new(text: impl Into<String>, pos: [f32; 2]) -> Self {
+        Self {
+            text: text.into(),
+            pos,
+            size_px: 16.0,
+            color: [1.0, 1.0, 1.0, 1.0],
+            font: None,
+        }
+    }
+
+    #[inline]
+    pub fn with_size(mut self, size_px: f32) -> Self {
+        self.size_px = size_px;
+        self
+    }
+
+    #[inline]
+    pub fn with_color(mut self, color: Color4) -> Self {
+        self.color = color;
+        self
+    }
+
+    #[inline]
+    pub fn with_font(mut self, font: FontId) -> Self {
+        self.font = Some(font);
+        self
+    }
 }
 
 pub trait RenderApi: Send {
@@ -610,6 +1194,15 @@ pub trait RenderApi: Send {
     fn create_texture(&mut self, desc: TextureDesc) -> EngineResult<TextureId>;
     fn destroy_texture(&mut self, id: TextureId);
 
+    /// Replaces mip 0 of a `Sampled` texture. `data` holds every texel in the texture's
+    /// format, rows tightly packed, top row first; cubemaps take their faces one after
+    /// another, +X, -X, +Y, -Y, +Z, -Z. Write a texture before drawing with it.
+    fn write_texture(&mut self, _id: TextureId, _data: &[u8]) -> EngineResult<()> {
+        Err(EngineError::other(
+            "texture uploads are not supported by this render backend",
+        ))
+    }
+
     fn create_sampler(&mut self, desc: SamplerDesc) -> EngineResult<SamplerId>;
     fn destroy_sampler(&mut self, id: SamplerId);
 
@@ -632,11 +1225,209 @@ pub trait RenderApi: Send {
     fn set_pipeline(&mut self, pipeline: PipelineId) -> EngineResult<()>;
     fn set_bind_group(&mut self, index: u32, group: BindGroupId) -> EngineResult<()>;
 
+    /// Binds `group` with one offset per [`BindingKind::UniformBufferDynamic`] binding, in
+    /// binding order. Offsets must be multiples of
+    /// [`RenderApi::uniform_offset_alignment`]. [`RenderApi::set_bind_group`] binds with zero
+    /// offsets.
+    fn set_bind_group_with_offsets(
+        &mut self,
+        index: u32,
+        group: BindGroupId,
+        offsets: &[u32],
+    ) -> EngineResult<()> {
+        if offsets.is_empty() {
+            return self.set_bind_group(index, group);
+        }
+        Err(EngineError::other(
+            "dynamic uniform offsets are not supported by this render backend",
+        ))
+    }
+
+    /// Alignment of dynamic uniform offsets, in bytes.
+    fn uniform_offset_alignment(&self) -> u64 {
+        256
+    }
+
     fn set_vertex_buffer(&mut self, slot: u32, slice: BufferSlice) -> EngineResult<()>;
     fn set_index_buffer(&mut self, slice: BufferSlice, format: IndexFormat) -> EngineResult<()>;
 
     fn draw(&mut self, args: DrawArgs) -> EngineResult<()>;
     fn draw_indexed(&mut self, args: DrawIndexedArgs) -> EngineResult<()>;
+
+    /// `draw_count` draws read from tightly packed [`DrawIndirectCommand`]s at `args`, e.g.
+    /// written by a culling compute pass. The buffer needs [`BufferUsage::Indirect`].
+    fn draw_indirect(&mut self, _args: BufferSlice, _draw_count: u32) -> EngineResult<()> {
+        Err(EngineError::other(
+            "indirect draws are not supported by this render backend",
+        ))
+    }
+
+    /// Indexed variant of [`RenderApi::draw_indirect`], reading
+    /// [`DrawIndexedIndirectCommand`]s.
+    fn draw_indexed_indirect(&mut self, _args: BufferSlice, _draw_count: u32) -> EngineResult<()> {
+        Err(EngineError::other(
+            "indirect draws are not supported by this render backend",
+        ))
+    }
+
+    /// Starts a depth-only pass into `target`, a [`TextureUsage::DepthStencil`] texture, cleared
+    /// to 1.0. Draws until [`RenderApi::end_depth_pass`] go into it with
+    /// [`PipelineDesc::depth_only`] pipelines; afterwards shaders sample it as a texture.
+    ///
+    /// The backend runs every depth pass of the frame before the frame's main pass, wherever it
+    /// is recorded, so draws of the main pass can depend on it. Pipeline, bind groups and
+    /// buffers are unset when the pass begins and again when it ends; viewport and scissor
+    /// start out covering `target`.
+    fn begin_depth_pass(&mut self, _target: TextureId) -> EngineResult<()> {
+        Err(EngineError::other(
+            "depth passes are not supported by this render backend",
+        ))
+    }
+
+    fn end_depth_pass(&mut self) -> EngineResult<()> {
+        Err(EngineError::other(
+            "depth passes are not supported by this render backend",
+        ))
+    }
+
+    /// Starts the frame's object-ID pass. Draws until [`RenderApi::end_id_pass`] go into a
+    /// backend-owned target the size of the frame holding one `u32` per pixel, cleared to 0
+    /// (no object), with a depth buffer of its own so the nearest draw wins. Use
+    /// [`TextureFormat::R32Uint`] pipelines with a `Depth32Float` depth format, e.g. with
+    /// [`DefaultMaterial::ObjectId`].
+    ///
+    /// At most one per frame; it runs with the depth passes, before the main pass. Bindings
+    /// are unset when it begins and ends, and viewport and scissor start out covering the
+    /// frame.
+    fn begin_id_pass(&mut self) -> EngineResult<()> {
+        Err(EngineError::other(
+            "object-ID passes are not supported by this render backend",
+        ))
+    }
+
+    fn end_id_pass(&mut self) -> EngineResult<()> {
+        Err(EngineError::other(
+            "object-ID passes are not supported by this render backend",
+        ))
+    }
+
+    /// Asks for the id at pixel `(x, y)` (top-left origin) of the next frame with an
+    /// object-ID pass. Fetch it with [`RenderApi::take_picked`] once the GPU is done with
+    /// that frame, normally one frame later. A newer request replaces one not yet served.
+    fn pick(&mut self, _x: u32, _y: u32) -> EngineResult<()> {
+        Err(EngineError::other(
+            "picking is not supported by this render backend",
+        ))
+    }
+
+    /// The answer to the last [`RenderApi::pick`], once read back.
+    fn take_picked(&mut self) -> Option<PickResult> {
+        None
+    }
+
+    /// Post-process chain applied to the main pass from the next [`RenderApi::end_frame`] on.
+    /// Backends that support it render the main pass into an HDR target and run the chain
+    /// on its way to the window; the settings stay until changed.
+    fn set_post_process(&mut self, _settings: PostProcessSettings) -> EngineResult<()> {
+        Err(EngineError::other(
+            "post-processing is not supported by this render backend",
+        ))
+    }
+
+    /// Built-in (vertex, fragment) shaders of the default material set for the requested
+    /// deformation path. Ids are owned by the backend and cached; do not destroy them.
+    fn default_material_shaders(
+        &mut self,
+        _material: DefaultMaterial,
+        _deformation: VertexDeformation,
+    ) -> EngineResult<(ShaderId, ShaderId)> {
+        Err(EngineError::other(
+            "default material set is not supported by this render backend",
+        ))
+    }
+
+    /// Pass timings of the newest frame the GPU has finished; usually a frame or two behind.
+    /// Empty when the backend does not measure them.
+    fn gpu_pass_timings(&self) -> Vec<GpuPassTiming> {
+        Vec::new()
+    }
+
+    /// Backend diagnostic counters, e.g. validation messages by severity.
+    fn debug_counters(&self) -> Vec<(&'static str, u64)> {
+        Vec::new()
+    }
+
+    /// Asks for a copy of the next presented frame; fetch it with
+    /// [`RenderApi::take_captured_frame`] once the GPU is done with it.
+    fn capture_frame(&mut self) -> EngineResult<()> {
+        Err(EngineError::other(
+            "frame capture is not supported by this render backend",
+        ))
+    }
+
+    /// The frame requested by [`RenderApi::capture_frame`], once its pixels reached host
+    /// memory (usually a frame or two later).
+    fn take_captured_frame(&mut self) -> Option<CapturedFrame> {
+        None
+    }
+
+    /// `size` bytes of per-frame scratch memory (vertex, index, uniform or storage data), filled
+    /// with [`RenderApi::write_buffer`] at the returned offset. Valid until the end of the
+    /// current frame; the backend reuses it once the GPU is done with that frame. Offsets are
+    /// aligned for uniform/storage binding; never destroy the buffer.
+    fn alloc_transient(&mut self, _size: u64) -> EngineResult<BufferSlice> {
+        Err(EngineError::other(
+            "transient allocations are not supported by this render backend",
+        ))
+    }
+
+    /// Loads a TrueType/OpenType font (e.g. the payload of a font asset) for [`TextDraw`]s.
+    /// Fonts stay loaded for the lifetime of the backend.
+    fn load_font(&mut self, _data: Vec<u8>) -> EngineResult<FontId> {
+        Err(EngineError::other(
+            "font loading is not supported by this render backend",
+        ))
+    }
+
+    /// Queues `text` for the next presented frame, drawn over the scene below the UI.
+    fn draw_text(&mut self, _text: TextDraw) -> EngineResult<()> {
+        Err(EngineError::other(
+            "text drawing is not supported by this render backend",
+        ))
+    }
+
+    /// Adds `window` as a presentation target, e.g. an editor panel in its own OS window. Each
+    /// frame it shows the list set with [`RenderApi::set_viewport_ui_draw_list`] over the
+    /// frame's clear color, and is presented by [`RenderApi::end_frame`].
+    fn create_viewport(
+        &mut self,
+        _display: RawDisplayHandle,
+        _window: RawWindowHandle,
+        _size: Extent2D,
+    ) -> EngineResult<ViewportId> {
+        Err(EngineError::other(
+            "multiple viewports are not supported by this render backend",
+        ))
+    }
+
+    /// Removes a viewport created by [`RenderApi::create_viewport`]; call before its window is
+    /// destroyed. The main viewport cannot be removed.
+    fn destroy_viewport(&mut self, _id: ViewportId) {}
+
+    fn resize_viewport(&mut self, id: ViewportId, width: u32, height: u32) -> EngineResult<()> {
+        if id == ViewportId::MAIN {
+            return self.resize(width, height);
+        }
+        Err(EngineError::other("unknown viewport"))
+    }
+
+    /// UI for the next frame of viewport `id`; for [`ViewportId::MAIN`] this is
+    /// [`RenderApi::set_ui_draw_list`].
+    fn set_viewport_ui_draw_list(&mut self, id: ViewportId, ui: UiDrawList) {
+        if id == ViewportId::MAIN {
+            self.set_ui_draw_list(ui);
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -652,6 +1443,12 @@ impl RenderApiRef {
     pub fn lock(&self) -> MutexGuard<'_, Box<dyn RenderApi + Send + 'static>> {
         self.0.lock()
     }
+
+    /// Whether both refer to the same backend instance.
+    #[inline]
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
 }
 
 #[inline]
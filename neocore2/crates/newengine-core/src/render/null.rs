@@ -0,0 +1,357 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use super::*;
+use crate::module::{Module, ModuleCtx};
+
+use parking_lot::Mutex;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+/// Counters collected by [`NullRenderApi`]; useful to assert render-side behavior in CI.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NullRenderStats {
+    pub frames: u64,
+    pub draws: u64,
+    pub draws_indexed: u64,
+    pub ui_lists: u64,
+    pub live_buffers: usize,
+    pub live_objects: usize,
+    pub bytes_written: u64,
+}
+
+/// Shared view of [`NullRenderStats`]; inserted into `Resources` by [`NullRenderModule`].
+#[derive(Debug, Clone, Default)]
+pub struct NullRenderProbe(Arc<Mutex<NullRenderStats>>);
+
+impl NullRenderProbe {
+    #[inline]
+    pub fn stats(&self) -> NullRenderStats {
+        *self.0.lock()
+    }
+}
+
+/// Render backend that records calls without touching a GPU.
+///
+/// Hands out unique ids and validates buffer writes, so controllers that drive the real
+/// backend run unchanged in headless builds.
+#[derive(Debug)]
+pub struct NullRenderApi {
+    next_id: u32,
+    extent: Extent2D,
+    in_frame: bool,
+    buffers: HashMap<BufferId, u64>,
+    /// Ids of live textures, samplers, shaders, pipelines and bind groups (layouts).
+    objects: HashSet<u32>,
+    stats: NullRenderProbe,
+    /// Nothing is drawn, so every pick reads back as no object.
+    picked: Option<PickResult>,
+}
+
+impl NullRenderApi {
+    #[inline]
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            next_id: 0,
+            extent: Extent2D::new(width, height),
+            in_frame: false,
+            buffers: HashMap::new(),
+            objects: HashSet::new(),
+            stats: NullRenderProbe::default(),
+            picked: None,
+        }
+    }
+
+    #[inline]
+    pub fn extent(&self) -> Extent2D {
+        self.extent
+    }
+
+    #[inline]
+    pub fn probe(&self) -> NullRenderProbe {
+        self.stats.clone()
+    }
+
+    #[inline]
+    fn record(&self, f: impl FnOnce(&mut NullRenderStats)) {
+        f(&mut self.stats.0.lock());
+    }
+
+    #[inline]
+    fn alloc(&mut self) -> u32 {
+        self.next_id = self.next_id.wrapping_add(1).max(1);
+        self.next_id
+    }
+
+    #[inline]
+    fn alloc_object(&mut self) -> u32 {
+        let id = self.alloc();
+        self.objects.insert(id);
+        self.record(|s| s.live_objects += 1);
+        id
+    }
+
+    /// Unknown or already destroyed ids are ignored so double destroys do not skew the count.
+    #[inline]
+    fn release_object(&mut self, id: u32) {
+        if self.objects.remove(&id) {
+            self.record(|s| s.live_objects -= 1);
+        }
+    }
+
+    #[inline]
+    fn require_frame(&self, op: &str) -> EngineResult<()> {
+        if self.in_frame {
+            Ok(())
+        } else {
+            Err(EngineError::other(format!(
+                "render.null: {op} called outside begin_frame/end_frame"
+            )))
+        }
+    }
+}
+
+impl RenderApi for NullRenderApi {
+    fn begin_frame(&mut self, _desc: BeginFrameDesc) -> EngineResult<()> {
+        self.in_frame = true;
+        Ok(())
+    }
+
+    fn set_ui_draw_list(&mut self, _ui: UiDrawList) {
+        self.record(|s| s.ui_lists += 1);
+    }
+
+    fn end_frame(&mut self) -> EngineResult<()> {
+        self.require_frame("end_frame")?;
+        self.in_frame = false;
+        self.record(|s| s.frames += 1);
+        Ok(())
+    }
+
+    fn resize(&mut self, width: u32, height: u32) -> EngineResult<()> {
+        self.extent = Extent2D::new(width, height);
+        Ok(())
+    }
+
+    fn create_buffer(&mut self, desc: BufferDesc) -> EngineResult<BufferId> {
+        let id = BufferId::new(self.alloc());
+        self.buffers.insert(id, desc.size);
+        self.record(|s| s.live_buffers += 1);
+        Ok(id)
+    }
+
+    fn destroy_buffer(&mut self, id: BufferId) {
+        if self.buffers.remove(&id).is_some() {
+            self.record(|s| s.live_buffers -= 1);
+        }
+    }
+
+    fn write_buffer(&mut self, id: BufferId, offset: u64, data: &[u8]) -> EngineResult<()> {
+        let Some(&size) = self.buffers.get(&id) else {
+            return Err(EngineError::other(format!(
+                "render.null: unknown buffer {id:?}"
+            )));
+        };
+
+        let end = offset.saturating_add(data.len() as u64);
+        if end > size {
+            return Err(EngineError::other(format!(
+                "render.null: write out of bounds buffer={id:?} end={end} size={size}"
+            )));
+        }
+
+        self.record(|s| s.bytes_written += data.len() as u64);
+        Ok(())
+    }
+
+    fn create_texture(&mut self, _desc: TextureDesc) -> EngineResult<TextureId> {
+        Ok(TextureId::new(self.alloc_object()))
+    }
+
+    fn destroy_texture(&mut self, id: TextureId) {
+        self.release_object(id.0.get());
+    }
+
+    fn write_texture(&mut self, _id: TextureId, data: &[u8]) -> EngineResult<()> {
+        self.record(|s| s.bytes_written += data.len() as u64);
+        Ok(())
+    }
+
+    fn create_sampler(&mut self, _desc: SamplerDesc) -> EngineResult<SamplerId> {
+        Ok(SamplerId::new(self.alloc_object()))
+    }
+
+    fn destroy_sampler(&mut self, id: SamplerId) {
+        self.release_object(id.0.get());
+    }
+
+    fn create_shader(&mut self, _desc: ShaderDesc) -> EngineResult<ShaderId> {
+        Ok(ShaderId::new(self.alloc_object()))
+    }
+
+    fn destroy_shader(&mut self, id: ShaderId) {
+        self.release_object(id.0.get());
+    }
+
+    fn create_pipeline(&mut self, _desc: PipelineDesc) -> EngineResult<PipelineId> {
+        Ok(PipelineId::new(self.alloc_object()))
+    }
+
+    fn destroy_pipeline(&mut self, id: PipelineId) {
+        self.release_object(id.0.get());
+    }
+
+    fn create_bind_group_layout(
+        &mut self,
+        _desc: BindGroupLayoutDesc,
+    ) -> EngineResult<BindGroupLayoutId> {
+        Ok(BindGroupLayoutId::new(self.alloc_object()))
+    }
+
+    fn destroy_bind_group_layout(&mut self, id: BindGroupLayoutId) {
+        self.release_object(id.0.get());
+    }
+
+    fn create_bind_group(&mut self, _desc: BindGroupDesc) -> EngineResult<BindGroupId> {
+        Ok(BindGroupId::new(self.alloc_object()))
+    }
+
+    fn destroy_bind_group(&mut self, id: BindGroupId) {
+        self.release_object(id.0.get());
+    }
+
+    fn set_viewport(&mut self, _vp: Viewport) -> EngineResult<()> {
+        self.require_frame("set_viewport")
+    }
+
+    fn set_scissor(&mut self, _rect: RectI32) -> EngineResult<()> {
+        self.require_frame("set_scissor")
+    }
+
+    fn set_pipeline(&mut self, _pipeline: PipelineId) -> EngineResult<()> {
+        self.require_frame("set_pipeline")
+    }
+
+    fn set_bind_group(&mut self, _index: u32, _group: BindGroupId) -> EngineResult<()> {
+        self.require_frame("set_bind_group")
+    }
+
+    fn set_vertex_buffer(&mut self, _slot: u32, _slice: BufferSlice) -> EngineResult<()> {
+        self.require_frame("set_vertex_buffer")
+    }
+
+    fn set_index_buffer(&mut self, _slice: BufferSlice, _format: IndexFormat) -> EngineResult<()> {
+        self.require_frame("set_index_buffer")
+    }
+
+    fn draw(&mut self, _args: DrawArgs) -> EngineResult<()> {
+        self.require_frame("draw")?;
+        self.record(|s| s.draws += 1);
+        Ok(())
+    }
+
+    fn draw_indexed(&mut self, _args: DrawIndexedArgs) -> EngineResult<()> {
+        self.require_frame("draw_indexed")?;
+        self.record(|s| s.draws_indexed += 1);
+        Ok(())
+    }
+
+    fn begin_depth_pass(&mut self, _target: TextureId) -> EngineResult<()> {
+        self.require_frame("begin_depth_pass")
+    }
+
+    fn end_depth_pass(&mut self) -> EngineResult<()> {
+        self.require_frame("end_depth_pass")
+    }
+
+    fn begin_id_pass(&mut self) -> EngineResult<()> {
+        self.require_frame("begin_id_pass")
+    }
+
+    fn end_id_pass(&mut self) -> EngineResult<()> {
+        self.require_frame("end_id_pass")
+    }
+
+    fn pick(&mut self, x: u32, y: u32) -> EngineResult<()> {
+        self.picked = Some(PickResult { x, y, id: 0 });
+        Ok(())
+    }
+
+    #[inline]
+    fn take_picked(&mut self) -> Option<PickResult> {
+        self.picked.take()
+    }
+
+    #[inline]
+    fn set_post_process(&mut self, _settings: PostProcessSettings) -> EngineResult<()> {
+        Ok(())
+    }
+
+    fn default_material_shaders(
+        &mut self,
+        _material: DefaultMaterial,
+        _deformation: VertexDeformation,
+    ) -> EngineResult<(ShaderId, ShaderId)> {
+        Ok((
+            ShaderId::new(self.alloc_object()),
+            ShaderId::new(self.alloc_object()),
+        ))
+    }
+}
+
+/// Provides [`RENDER_API_ID`] backed by [`NullRenderApi`] (headless runs, CI, asset cooking).
+pub struct NullRenderModule {
+    width: u32,
+    height: u32,
+    api: Option<RenderApiRef>,
+}
+
+impl Default for NullRenderModule {
+    fn default() -> Self {
+        Self::new(1280, 720)
+    }
+}
+
+impl NullRenderModule {
+    #[inline]
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            api: None,
+        }
+    }
+}
+
+impl<E: Send + 'static> Module<E> for NullRenderModule {
+    fn id(&self) -> &'static str {
+        "render.null"
+    }
+
+    fn provides(&self) -> &'static [ApiProvide] {
+        &[RENDER_API_PROVIDE]
+    }
+
+    fn init(&mut self, ctx: &mut ModuleCtx<'_, E>) -> EngineResult<()> {
+        let null = NullRenderApi::new(self.width, self.height);
+        ctx.resources_mut().insert(null.probe());
+
+        let api = RenderApiRef::new(null);
+        ctx.resources_mut()
+            .register_api(RENDER_API_ID, api.clone())?;
+        self.api = Some(api);
+        log::info!(
+            "render.null: initialized extent={}x{}",
+            self.width,
+            self.height
+        );
+        Ok(())
+    }
+
+    fn shutdown(&mut self, ctx: &mut ModuleCtx<'_, E>) -> EngineResult<()> {
+        let _ = ctx
+            .resources_mut()
+            .unregister_api::<RenderApiRef>(RENDER_API_ID);
+        self.api = None;
+        Ok(())
+    }
+}
@@ -0,0 +1,155 @@
+use crate::cvar::{cvar_bool, cvar_float, register_cvar, CvarDesc, CvarFlag};
+
+/// Cvars behind [`PostProcessSettings::from_cvars`].
+pub mod post_cvar {
+    /// Common prefix, e.g. for listing them in a panel.
+    pub const PREFIX: &str = "post.";
+
+    pub const BLOOM: &str = "post.bloom";
+    pub const BLOOM_THRESHOLD: &str = "post.bloom_threshold";
+    pub const BLOOM_INTENSITY: &str = "post.bloom_intensity";
+    pub const FXAA: &str = "post.fxaa";
+    pub const VIGNETTE: &str = "post.vignette";
+    pub const VIGNETTE_INTENSITY: &str = "post.vignette_intensity";
+    pub const VIGNETTE_RADIUS: &str = "post.vignette_radius";
+}
+
+/// Post-process chain run on the HDR scene before it reaches the window: bloom, vignette,
+/// then FXAA. Debug draws, text and UI are drawn afterwards and are not affected.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PostProcessSettings {
+    pub bloom: bool,
+    /// Scene brightness where bloom starts; it fades in over half the threshold below it.
+    pub bloom_threshold: f32,
+    /// Weight of the blurred highlights added back to the scene.
+    pub bloom_intensity: f32,
+    pub fxaa: bool,
+    pub vignette: bool,
+    /// Darkening at the corners, 0..1.
+    pub vignette_intensity: f32,
+    /// Distance from the center, in half screen heights, where darkening starts.
+    pub vignette_radius: f32,
+}
+
+impl PostProcessSettings {
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            bloom: true,
+            bloom_threshold: 1.0,
+            bloom_intensity: 0.25,
+            fxaa: true,
+            vignette: true,
+            vignette_intensity: 0.25,
+            vignette_radius: 0.75,
+        }
+    }
+
+    /// Every pass off; the scene reaches the window unchanged.
+    #[inline]
+    pub fn disabled() -> Self {
+        Self {
+            bloom: false,
+            fxaa: false,
+            vignette: false,
+            ..Self::new()
+        }
+    }
+
+    #[inline]
+    pub fn with_bloom(mut self, threshold: f32, intensity: f32) -> Self {
+        self.bloom = true;
+        self.bloom_threshold = threshold;
+        self.bloom_intensity = intensity;
+        self
+    }
+
+    #[inline]
+    pub fn with_fxaa(mut self, fxaa: bool) -> Self {
+        self.fxaa = fxaa;
+        self
+    }
+
+    #[inline]
+    pub fn with_vignette(mut self, intensity: f32, radius: f32) -> Self {
+        self.vignette = true;
+        self.vignette_intensity = intensity;
+        self.vignette_radius = radius;
+        self
+    }
+
+    /// Registers the `post.*` cvars, archived, with these settings as defaults.
+    pub fn register_cvars(&self) -> Result<(), String> {
+        use post_cvar::*;
+
+        let float = |name: &str, v: f32, max: f64, help: &str| {
+            CvarDesc::float(name, v as f64)
+                .range(0.0, max)
+                .flag(CvarFlag::Archive)
+                .help(help)
+        };
+        let descs = [
+            CvarDesc::bool(BLOOM, self.bloom)
+                .flag(CvarFlag::Archive)
+                .help("Bloom around bright parts of the scene"),
+            float(
+                BLOOM_THRESHOLD,
+                self.bloom_threshold,
+                16.0,
+                "Brightness where bloom starts",
+            ),
+            float(
+                BLOOM_INTENSITY,
+                self.bloom_intensity,
+                4.0,
+                "Strength of the bloom",
+            ),
+            CvarDesc::bool(FXAA, self.fxaa)
+                .flag(CvarFlag::Archive)
+                .help("Fast approximate anti-aliasing"),
+            CvarDesc::bool(VIGNETTE, self.vignette)
+                .flag(CvarFlag::Archive)
+                .help("Darken the screen corners"),
+            float(
+                VIGNETTE_INTENSITY,
+                self.vignette_intensity,
+                1.0,
+                "Darkening at the corners",
+            ),
+            float(
+                VIGNETTE_RADIUS,
+                self.vignette_radius,
+                2.0,
+                "Where the darkening starts",
+            ),
+        ];
+        for desc in descs {
+            register_cvar(desc)?;
+        }
+        Ok(())
+    }
+
+    /// Current values of the `post.*` cvars; unregistered ones keep their [`Self::new`] value.
+    pub fn from_cvars() -> Self {
+        use post_cvar::*;
+
+        let d = Self::new();
+        let float = |name: &str, v: f32| cvar_float(name).map_or(v, |f| f as f32);
+        Self {
+            bloom: cvar_bool(BLOOM).unwrap_or(d.bloom),
+            bloom_threshold: float(BLOOM_THRESHOLD, d.bloom_threshold),
+            bloom_intensity: float(BLOOM_INTENSITY, d.bloom_intensity),
+            fxaa: cvar_bool(FXAA).unwrap_or(d.fxaa),
+            vignette: cvar_bool(VIGNETTE).unwrap_or(d.vignette),
+            vignette_intensity: float(VIGNETTE_INTENSITY, d.vignette_intensity),
+            vignette_radius: float(VIGNETTE_RADIUS, d.vignette_radius),
+        }
+    }
+}
+
+impl Default for PostProcessSettings {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
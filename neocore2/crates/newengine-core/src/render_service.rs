@@ -0,0 +1,924 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use crate::frame_record::{
+    record_status, start_recording, stop_recording, RecordConfig, RecordStatus, RECORD_QUEUE_MAX,
+};
+use crate::plugins::host_api;
+use crate::plugins::host_context::current_plugin_id;
+use crate::plugins::HOST_CALLER_ID;
+use crate::render::{
+    BindGroupDesc, BindGroupId, BindGroupLayoutDesc, BindGroupLayoutId, BindingKind, BlendState,
+    BufferBinding, BufferDesc, BufferId, BufferSlice, BufferUsage, CapturedFrame, CullMode,
+    DrawArgs, DrawIndexedArgs, IndexFormat, MemoryHint, PipelineDesc, PipelineId, PolygonMode,
+    PrimitiveTopology, RenderApi, RenderApiRef, ShaderDesc, ShaderId, ShaderStage, TextureFormat,
+    VertexAttribute, VertexLayout,
+};
+use abi_stable::std_types::{RResult, RString};
+use newengine_plugin_api::{
+    guarded_service, Blob, CapabilityId, MethodName, ServiceV1, ServiceV1Dyn,
+};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Render backend subset for plugins: buffers, SPIR-V shaders, pipelines and draws.
+pub const RENDER_SERVICE_ID: &str = "engine.render";
+
+pub mod method {
+    pub const CREATE_BUFFER: &str = "render.create_buffer";
+    pub const WRITE_BUFFER: &str = "render.write_buffer";
+    pub const CREATE_SHADER: &str = "render.create_shader";
+    pub const CREATE_PIPELINE: &str = "render.create_pipeline";
+    pub const CREATE_UNIFORM_GROUP: &str = "render.create_uniform_group";
+    pub const DESTROY: &str = "render.destroy";
+    pub const DRAW: &str = "render.draw";
+    pub const STATS_JSON: &str = "render.stats_json";
+    pub const GPU_STATS_JSON: &str = "render.gpu_stats_json";
+    pub const SCREENSHOT: &str = "render.screenshot";
+    pub const RECORD_START: &str = "render.record_start";
+    pub const RECORD_STOP: &str = "render.record_stop";
+    pub const RECORD_STATUS_JSON: &str = "render.record_status_json";
+}
+
+/// Screenshots are written below this directory (relative to the working directory).
+pub const SCREENSHOT_DIR: &str = "screenshots";
+/// Published with `{"path":..,"ok":..,"error":..}` once a screenshot is written or failed.
+pub const SCREENSHOT_TOPIC: &str = "render.screenshot";
+
+/// Draws queued per frame across all plugins; further draws are dropped.
+pub const MAX_PLUGIN_DRAWS_PER_FRAME: usize = 4096;
+
+/// Ends the JSON header of `write_buffer` and `create_shader` payloads; raw bytes follow.
+const HEADER_END: u8 = b'\n';
+
+const SPIRV_MAGIC: u32 = 0x0723_0203;
+
+#[derive(Debug, Clone, Copy)]
+enum GpuResource {
+    Buffer(BufferId),
+    Shader(ShaderId),
+    Pipeline(PipelineId),
+    UniformGroup(BindGroupId),
+}
+
+struct Owned {
+    owner: String,
+    res: GpuResource,
+}
+
+/// `{"pipeline":3,"vertex_buffers":[4],"index_buffer":5,"uniform_group":6,"count":36}`.
+/// `count` is an index count when `index_buffer` is set, else a vertex count.
+#[derive(Debug, Clone, Deserialize)]
+struct DrawReq {
+    pipeline: u32,
+    #[serde(default)]
+    vertex_buffers: Vec<u32>,
+    #[serde(default)]
+    index_buffer: Option<u32>,
+    #[serde(default = "index_u32")]
+    index_format: IndexFormat,
+    #[serde(default)]
+    uniform_group: Option<u32>,
+    count: u32,
+    #[serde(default = "one")]
+    instances: u32,
+}
+
+#[inline]
+fn index_u32() -> IndexFormat {
+    IndexFormat::U32
+}
+
+#[inline]
+fn one() -> u32 {
+    1
+}
+
+struct QueuedDraw {
+    owner: String,
+    req: DrawReq,
+}
+
+#[derive(Debug, Deserialize)]
+struct BufferReq {
+    size: u64,
+    usage: BufferUsage,
+    #[serde(default = "cpu_to_gpu")]
+    memory: MemoryHint,
+}
+
+#[inline]
+fn cpu_to_gpu() -> MemoryHint {
+    MemoryHint::CpuToGpu
+}
+
+#[derive(Debug, Deserialize)]
+struct WriteReq {
+    buffer: u32,
+    #[serde(default)]
+    offset: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct ShaderReq {
+    stage: ShaderStage,
+    #[serde(default)]
+    entry: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct VertexLayoutReq {
+    stride: u32,
+    attributes: Vec<VertexAttribute>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PipelineReq {
+    vs: u32,
+    fs: u32,
+    #[serde(default = "triangle_list")]
+    topology: PrimitiveTopology,
+    #[serde(default)]
+    vertex_layouts: Vec<VertexLayoutReq>,
+    #[serde(default = "bgra8")]
+    color_format: TextureFormat,
+    #[serde(default)]
+    depth_format: Option<TextureFormat>,
+    #[serde(default)]
+    blend: BlendState,
+    #[serde(default)]
+    cull_mode: CullMode,
+    #[serde(default)]
+    polygon_mode: PolygonMode,
+    /// Adds the uniform group layout at bind group 0 (see `create_uniform_group`).
+    #[serde(default)]
+    uniform_group: bool,
+}
+
+#[inline]
+fn triangle_list() -> PrimitiveTopology {
+    PrimitiveTopology::TriangleList
+}
+
+#[inline]
+fn bgra8() -> TextureFormat {
+    TextureFormat::Bgra8Unorm
+}
+
+#[derive(Debug, Deserialize)]
+struct UniformGroupReq {
+    buffer: u32,
+    #[serde(default)]
+    offset: u64,
+    size: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct DestroyReq {
+    handle: u32,
+}
+
+#[derive(Debug, Serialize)]
+struct RenderResp {
+    ok: bool,
+    handle: Option<u32>,
+    error: Option<String>,
+}
+
+impl RenderResp {
+    fn from_result(r: Result<Option<u32>, String>) -> Self {
+        match r {
+            Ok(handle) => Self {
+                ok: true,
+                handle,
+                error: None,
+            },
+            Err(e) => Self {
+                ok: false,
+                handle: None,
+                error: Some(e),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct ScreenshotResp {
+    ok: bool,
+    path: Option<String>,
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct RecordResp {
+    ok: bool,
+    output: Option<String>,
+    status: RecordStatus,
+    error: Option<String>,
+}
+
+impl RecordResp {
+    fn failed(e: String) -> Self {
+        Self {
+            ok: false,
+            output: None,
+            status: record_status(),
+            error: Some(e),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PluginRenderStats {
+    pub backend: bool,
+    pub resources: usize,
+    pub queued_draws: usize,
+    pub submitted_draws: u64,
+    pub dropped_draws: u64,
+}
+
+#[derive(Default)]
+struct RenderServiceState {
+    api: Option<RenderApiRef>,
+    next_handle: u32,
+    resources: HashMap<u32, Owned>,
+    /// Shared by every uniform group and `uniform_group` pipeline, so they stay compatible.
+    uniform_layout: Option<BindGroupLayoutId>,
+    draws: Vec<QueuedDraw>,
+    submitted_draws: u64,
+    dropped_draws: u64,
+    /// Files waiting for the frame the backend was asked to capture.
+    screenshots: Vec<PathBuf>,
+}
+
+impl RenderServiceState {
+    fn insert(&mut self, owner: String, res: GpuResource) -> u32 {
+        self.next_handle = self.next_handle.wrapping_add(1).max(1);
+        while self.resources.contains_key(&self.next_handle) {
+            self.next_handle = self.next_handle.wrapping_add(1).max(1);
+        }
+        self.resources
+            .insert(self.next_handle, Owned { owner, res });
+        self.next_handle
+    }
+
+    /// Resources are private to the caller that created them.
+    fn get(&self, owner: &str, handle: u32) -> Result<GpuResource, String> {
+        match self.resources.get(&handle) {
+            Some(o) if o.owner == owner => Ok(o.res),
+            _ => Err(format!("unknown render handle {handle}")),
+        }
+    }
+
+    fn buffer(&self, owner: &str, handle: u32) -> Result<BufferId, String> {
+        match self.get(owner, handle)? {
+            GpuResource::Buffer(b) => Ok(b),
+            _ => Err(format!("render handle {handle} is not a buffer")),
+        }
+    }
+
+    fn shader(&self, owner: &str, handle: u32) -> Result<ShaderId, String> {
+        match self.get(owner, handle)? {
+            GpuResource::Shader(s) => Ok(s),
+            _ => Err(format!("render handle {handle} is not a shader")),
+        }
+    }
+
+    fn pipeline(&self, owner: &str, handle: u32) -> Result<PipelineId, String> {
+        match self.get(owner, handle)? {
+            GpuResource::Pipeline(p) => Ok(p),
+            _ => Err(format!("render handle {handle} is not a pipeline")),
+        }
+    }
+
+    fn uniform_group(&self, owner: &str, handle: u32) -> Result<BindGroupId, String> {
+        match self.get(owner, handle)? {
+            GpuResource::UniformGroup(g) => Ok(g),
+            _ => Err(format!("render handle {handle} is not a uniform group")),
+        }
+    }
+}
+
+/// Process-wide: plugins reach it through `call_service_v1`, the host through
+/// [`begin_plugin_frame`] and [`replay_plugin_draws`].
+static STATE: OnceLock<Mutex<RenderServiceState>> = OnceLock::new();
+
+#[inline]
+fn state() -> &'static Mutex<RenderServiceState> {
+    STATE.get_or_init(|| Mutex::new(RenderServiceState::default()))
+}
+
+#[inline]
+fn caller() -> String {
+    current_plugin_id().unwrap_or_else(|| HOST_CALLER_ID.to_string())
+}
+
+fn destroy_resource(r: &mut dyn RenderApi, res: GpuResource) {
+    match res {
+        GpuResource::Buffer(b) => r.destroy_buffer(b),
+        GpuResource::Shader(s) => r.destroy_shader(s),
+        GpuResource::Pipeline(p) => r.destroy_pipeline(p),
+        GpuResource::UniformGroup(g) => r.destroy_bind_group(g),
+    }
+}
+
+/// Splits `<json header>\n<raw bytes>`.
+fn split_header<'a, T: Deserialize<'a>>(payload: &'a [u8]) -> Result<(T, &'a [u8]), String> {
+    let end = payload
+        .iter()
+        .position(|&b| b == HEADER_END)
+        .ok_or_else(|| "expected '<json header>\\n<bytes>'".to_string())?;
+    let header = serde_json::from_slice(&payload[..end]).map_err(|e| e.to_string())?;
+    Ok((header, &payload[end + 1..]))
+}
+
+fn spirv_words(bytes: &[u8]) -> Result<Vec<u32>, String> {
+    if bytes.len() % 4 != 0 || bytes.len() < 20 {
+        return Err(format!("invalid SPIR-V: {} bytes", bytes.len()));
+    }
+    let words: Vec<u32> = bytes
+        .chunks_exact(4)
+        .map(|c| u32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+        .collect();
+    if words[0] != SPIRV_MAGIC {
+        return Err("invalid SPIR-V: bad magic number".to_string());
+    }
+    Ok(words)
+}
+
+/// Sets the backend plugin requests go to and drops draws nobody replayed last frame.
+/// The engine calls this before plugin `render`.
+pub fn begin_plugin_frame(api: Option<RenderApiRef>) {
+    let mut g = state().lock();
+
+    let stale = g.draws.len() as u64;
+    g.dropped_draws += stale;
+    g.draws.clear();
+
+    let same = match (&g.api, &api) {
+        (Some(a), Some(b)) => a.ptr_eq(b),
+        (None, None) => true,
+        _ => false,
+    };
+    if !same {
+        // Handles of the previous backend are meaningless to the new one.
+        if !g.resources.is_empty() {
+            log::warn!(
+                "render.service backend changed; dropping {} plugin resource(s)",
+                g.resources.len()
+            );
+        }
+        g.resources.clear();
+        g.uniform_layout = None;
+        g.api = api;
+    }
+}
+
+/// Records the draws plugins queued this frame into `r`, which must be inside
+/// `begin_frame`/`end_frame`. Draws naming destroyed resources are skipped.
+pub fn replay_plugin_draws(r: &mut dyn RenderApi) -> usize {
+    let mut g = state().lock();
+    let draws = std::mem::take(&mut g.draws);
+
+    let mut done = 0usize;
+    for d in draws.iter() {
+        let res = replay_one(&g, r, d);
+        match res {
+            Ok(()) => done += 1,
+            Err(e) => log::warn!("render.service draw skipped owner='{}': {}", d.owner, e),
+        }
+    }
+    g.submitted_draws += done as u64;
+    g.dropped_draws += (draws.len() - done) as u64;
+    done
+}
+
+fn replay_one(g: &RenderServiceState, r: &mut dyn RenderApi, d: &QueuedDraw) -> Result<(), String> {
+    let owner = d.owner.as_str();
+    let req = &d.req;
+    let err = |e: crate::error::EngineError| e.to_string();
+
+    r.set_pipeline(g.pipeline(owner, req.pipeline)?)
+        .map_err(err)?;
+    if let Some(h) = req.uniform_group {
+        r.set_bind_group(0, g.uniform_group(owner, h)?)
+            .map_err(err)?;
+    }
+    for (slot, &h) in req.vertex_buffers.iter().enumerate() {
+        let slice = BufferSlice::new(g.buffer(owner, h)?, 0);
+        r.set_vertex_buffer(slot as u32, slice).map_err(err)?;
+    }
+
+    match req.index_buffer {
+        Some(h) => {
+            let slice = BufferSlice::new(g.buffer(owner, h)?, 0);
+            r.set_index_buffer(slice, req.index_format).map_err(err)?;
+            let mut args = DrawIndexedArgs::new(req.count);
+            args.instance_count = req.instances;
+            r.draw_indexed(args).map_err(err)
+        }
+        None => {
+            let mut args = DrawArgs::new(req.count);
+            args.instance_count = req.instances;
+            r.draw(args).map_err(err)
+        }
+    }
+}
+
+/// Destroys everything `owner` created and drops its queued draws, e.g. on plugin unload.
+pub(crate) fn release_owner(owner: &str) {
+    let mut g = state().lock();
+    g.draws.retain(|d| d.owner != owner);
+
+    let handles: Vec<u32> = g
+        .resources
+        .iter()
+        .filter(|(_, o)| o.owner == owner)
+        .map(|(h, _)| *h)
+        .collect();
+    if handles.is_empty() {
+        return;
+    }
+
+    let owned: Vec<GpuResource> = handles
+        .iter()
+        .filter_map(|h| g.resources.remove(h))
+        .map(|o| o.res)
+        .collect();
+    let api = g.api.clone();
+    drop(g);
+
+    if let Some(api) = api {
+        let mut r = api.lock();
+        for res in owned {
+            destroy_resource(&mut **r, res);
+        }
+    }
+}
+
+pub fn plugin_render_stats() -> PluginRenderStats {
+    let g = state().lock();
+    PluginRenderStats {
+        backend: g.api.is_some(),
+        resources: g.resources.len(),
+        queued_draws: g.draws.len(),
+        submitted_draws: g.submitted_draws,
+        dropped_draws: g.dropped_draws,
+    }
+}
+
+fn gpu_stats(r: &dyn RenderApi) -> serde_json::Value {
+    let passes: Vec<_> = r
+        .gpu_pass_timings()
+        .iter()
+        .map(|t| json!({ "name": t.name, "ms": t.ms }))
+        .collect();
+    let counters: serde_json::Map<_, _> = r
+        .debug_counters()
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), json!(v)))
+        .collect();
+    json!({ "passes": passes, "counters": counters })
+}
+
+/// Hands frames the backend captured to `render.screenshot` requests (a thread encodes and
+/// writes the PNGs) and to the frame recorder, and asks for the recorder's next frame. The
+/// engine calls this after the render stage.
+pub fn pump_captures() {
+    let recording = crate::frame_record::is_recording();
+    let api = {
+        let g = state().lock();
+        if g.screenshots.is_empty() && !recording {
+            return;
+        }
+        g.api.clone()
+    };
+    let Some(api) = api else {
+        return;
+    };
+
+    let frame = {
+        let mut r = api.lock();
+        let frame = r.take_captured_frame();
+        if recording && crate::frame_record::record_frame(frame.as_ref()) {
+            if let Err(e) = r.capture_frame() {
+                crate::frame_record::abort_recording(&e.to_string());
+            }
+        }
+        frame
+    };
+    let Some(frame) = frame else {
+        return;
+    };
+    let paths = std::mem::take(&mut state().lock().screenshots);
+    if paths.is_empty() {
+        return;
+    }
+
+    let spawned = std::thread::Builder::new()
+        .name("screenshot".to_string())
+        .spawn(move || {
+            for path in paths {
+                let res = write_png(&path, &frame);
+                match &res {
+                    Ok(()) => log::info!("render.screenshot: saved {}", path.display()),
+                    Err(e) => log::warn!("render.screenshot: {e}"),
+                }
+                let payload = json!({
+                    "path": path.display().to_string(),
+                    "ok": res.is_ok(),
+                    "error": res.err(),
+                });
+                let _ = crate::plugins::event_router::enqueue(
+                    SCREENSHOT_TOPIC,
+                    payload.to_string().as_bytes(),
+                    true,
+                );
+            }
+        });
+    if let Err(e) = spawned {
+        log::warn!("render.screenshot: cannot start encoder thread: {e}");
+    }
+}
+
+pub(crate) fn write_png(path: &Path, frame: &CapturedFrame) -> Result<(), String> {
+    let err = |e: &dyn std::fmt::Display| format!("{}: {e}", path.display());
+
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| err(&e))?;
+    }
+    let file = std::fs::File::create(path).map_err(|e| err(&e))?;
+
+    let mut enc = png::Encoder::new(std::io::BufWriter::new(file), frame.width, frame.height);
+    enc.set_color(png::ColorType::Rgba);
+    enc.set_depth(png::BitDepth::Eight);
+    let mut w = enc.write_header().map_err(|e| err(&e))?;
+    w.write_image_data(&frame.rgba8).map_err(|e| err(&e))?;
+    w.finish().map_err(|e| err(&e))
+}
+
+/// `name` (relative, inside [`SCREENSHOT_DIR`]; `.png` added when missing), or a timestamped
+/// name when empty.
+fn screenshot_path(name: &str) -> Result<PathBuf, String> {
+    let name = name.trim().trim_matches('"');
+    if name.is_empty() {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        let file = format!("screenshot-{}-{:03}.png", now.as_secs(), now.subsec_millis());
+        return Ok(Path::new(SCREENSHOT_DIR).join(file));
+    }
+
+    let rel = Path::new(name);
+    if !rel.components().all(|c| matches!(c, Component::Normal(_))) {
+        return Err(format!("'{name}': must be a relative path inside {SCREENSHOT_DIR}/"));
+    }
+    let mut path = Path::new(SCREENSHOT_DIR).join(rel);
+    if path.extension().is_none() {
+        path.set_extension("png");
+    }
+    Ok(path)
+}
+
+struct RenderService;
+
+impl RenderService {
+    /// Runs `f` against the backend without holding the service lock, so a host module that
+    /// holds the backend lock and replays draws cannot deadlock with a plugin call.
+    fn with_api<R>(f: impl FnOnce(&mut dyn RenderApi) -> Result<R, String>) -> Result<R, String> {
+        let api = state()
+            .lock()
+            .api
+            .clone()
+            .ok_or_else(|| "no render backend".to_string())?;
+        let mut r = api.lock();
+        f(&mut **r)
+    }
+
+    /// Payload: utf8 `[path]`. The file is written a few frames later, off the frame thread.
+    fn screenshot(payload: &[u8]) -> Result<PathBuf, String> {
+        let path = screenshot_path(&String::from_utf8_lossy(payload))?;
+        Self::with_api(|r| r.capture_frame().map_err(|e| e.to_string()))?;
+        state().lock().screenshots.push(path.clone());
+        Ok(path)
+    }
+
+    fn create_buffer(payload: &[u8]) -> Result<Option<u32>, String> {
+        let req: BufferReq = serde_json::from_slice(payload).map_err(|e| e.to_string())?;
+        let desc = BufferDesc::new(req.size, req.usage, req.memory).with_label("plugin_buffer");
+        let id = Self::with_api(|r| r.create_buffer(desc).map_err(|e| e.to_string()))?;
+        Ok(Some(
+            state().lock().insert(caller(), GpuResource::Buffer(id)),
+        ))
+    }
+
+    fn write_buffer(payload: &[u8]) -> Result<Option<u32>, String> {
+        let (req, bytes): (WriteReq, &[u8]) = split_header(payload)?;
+        let id = state().lock().buffer(&caller(), req.buffer)?;
+        Self::with_api(|r| {
+            r.write_buffer(id, req.offset, bytes)
+                .map_err(|e| e.to_string())
+        })?;
+        Ok(None)
+    }
+
+    fn create_shader(payload: &[u8]) -> Result<Option<u32>, String> {
+        let (req, bytes): (ShaderReq, &[u8]) = split_header(payload)?;
+        // `ShaderDesc::entry` is `&'static str`; plugins get the conventional entry point.
+        if let Some(entry) = req.entry.as_deref().filter(|e| *e != "main") {
+            return Err(format!("unsupported entry point '{entry}' (only 'main')"));
+        }
+        let desc =
+            ShaderDesc::new(req.stage, "main", spirv_words(bytes)?).with_label("plugin_shader");
+        let id = Self::with_api(|r| r.create_shader(desc).map_err(|e| e.to_string()))?;
+        Ok(Some(
+            state().lock().insert(caller(), GpuResource::Shader(id)),
+        ))
+    }
+
+    fn uniform_layout(r: &mut dyn RenderApi) -> Result<BindGroupLayoutId, String> {
+        if let Some(l) = state().lock().uniform_layout {
+            return Ok(l);
+        }
+        let desc = BindGroupLayoutDesc::new(vec![BindingKind::UniformBuffer])
+            .with_label("plugin_uniform_bgl");
+        let l = r
+            .create_bind_group_layout(desc)
+            .map_err(|e| e.to_string())?;
+        state().lock().uniform_layout = Some(l);
+        Ok(l)
+    }
+
+    fn create_pipeline(payload: &[u8]) -> Result<Option<u32>, String> {
+        let req: PipelineReq = serde_json::from_slice(payload).map_err(|e| e.to_string())?;
+        let owner = caller();
+        let (vs, fs) = {
+            let g = state().lock();
+            (g.shader(&owner, req.vs)?, g.shader(&owner, req.fs)?)
+        };
+
+        let layouts = req
+            .vertex_layouts
+            .into_iter()
+            .map(|l| VertexLayout::new(l.stride, l.attributes))
+            .collect();
+
+        let id = Self::with_api(|r| {
+            let mut desc = PipelineDesc::new(vs, fs, req.color_format)
+                .with_label("plugin_pipeline")
+                .with_topology(req.topology)
+                .with_vertex_layouts(layouts)
+                .with_blend(req.blend)
+                .with_cull_mode(req.cull_mode)
+                .with_polygon_mode(req.polygon_mode);
+            if let Some(depth) = req.depth_format {
+                desc = desc.with_depth(depth);
+            }
+            if req.uniform_group {
+                desc = desc.with_bind_group_layouts(vec![Self::uniform_layout(r)?]);
+            }
+            r.create_pipeline(desc).map_err(|e| e.to_string())
+        })?;
+        Ok(Some(
+            state().lock().insert(owner, GpuResource::Pipeline(id)),
+        ))
+    }
+
+    fn create_uniform_group(payload: &[u8]) -> Result<Option<u32>, String> {
+        let req: UniformGroupReq = serde_json::from_slice(payload).map_err(|e| e.to_string())?;
+        let owner = caller();
+        let buffer = state().lock().buffer(&owner, req.buffer)?;
+
+        let id = Self::with_api(|r| {
+            let desc = BindGroupDesc::new(Self::uniform_layout(r)?)
+                .with_label("plugin_uniform_group")
+                .with_uniform0(BufferBinding::new(buffer, req.offset, req.size));
+            r.create_bind_group(desc).map_err(|e| e.to_string())
+        })?;
+        Ok(Some(
+            state().lock().insert(owner, GpuResource::UniformGroup(id)),
+        ))
+    }
+
+    fn destroy(payload: &[u8]) -> Result<Option<u32>, String> {
+        let req: DestroyReq = serde_json::from_slice(payload).map_err(|e| e.to_string())?;
+        let res = {
+            let mut g = state().lock();
+            let res = g.get(&caller(), req.handle)?;
+            g.resources.remove(&req.handle);
+            res
+        };
+        Self::with_api(|r| {
+            destroy_resource(r, res);
+            Ok(())
+        })?;
+        Ok(None)
+    }
+
+    fn draw(payload: &[u8]) -> Result<Option<u32>, String> {
+        let req: DrawReq = serde_json::from_slice(payload).map_err(|e| e.to_string())?;
+        let owner = caller();
+        let mut g = state().lock();
+
+        // Validate now so the plugin sees the error; replay checks again.
+        g.pipeline(&owner, req.pipeline)?;
+        for &h in req.vertex_buffers.iter().chain(req.index_buffer.iter()) {
+            g.buffer(&owner, h)?;
+        }
+        if let Some(h) = req.uniform_group {
+            g.uniform_group(&owner, h)?;
+        }
+
+        if g.draws.len() >= MAX_PLUGIN_DRAWS_PER_FRAME {
+            g.dropped_draws += 1;
+            return Err(format!(
+                "draw queue full ({MAX_PLUGIN_DRAWS_PER_FRAME} per frame)"
+            ));
+        }
+        g.draws.push(QueuedDraw { owner, req });
+        Ok(None)
+    }
+}
+
+impl ServiceV1 for RenderService {
+    fn id(&self) -> CapabilityId {
+        RString::from(RENDER_SERVICE_ID)
+    }
+
+    fn describe(&self) -> RString {
+        let d = json!({
+          "id": RENDER_SERVICE_ID,
+          "version": 1,
+          "methods": [
+            { "name": method::CREATE_BUFFER, "payload": "json {size, usage, memory?}", "returns": "json RenderResp" },
+            { "name": method::WRITE_BUFFER, "payload": "json {buffer, offset?} '\\n' bytes", "returns": "json RenderResp" },
+            { "name": method::CREATE_SHADER, "payload": "json {stage, entry?} '\\n' spirv", "returns": "json RenderResp" },
+            { "name": method::CREATE_PIPELINE, "payload": "json {vs, fs, topology?, vertex_layouts?, color_format?, depth_format?, blend?, cull_mode?, polygon_mode?, uniform_group?}", "returns": "json RenderResp" },
+            { "name": method::CREATE_UNIFORM_GROUP, "payload": "json {buffer, offset?, size}", "returns": "json RenderResp" },
+            { "name": method::DESTROY, "payload": "json {handle}", "returns": "json RenderResp" },
+            { "name": method::DRAW, "payload": "json {pipeline, vertex_buffers?, index_buffer?, index_format?, uniform_group?, count, instances?}", "returns": "json RenderResp" },
+            { "name": method::STATS_JSON, "payload": "empty", "returns": "json PluginRenderStats" },
+            { "name": method::GPU_STATS_JSON, "payload": "empty", "returns": "json {passes, counters}" },
+            { "name": method::SCREENSHOT, "payload": "utf8 '[path]'", "returns": "json ScreenshotResp" },
+            {
+              "name": method::RECORD_START,
+              "payload": "json RecordConfig",
+              "returns": "json RecordResp",
+              "schema": {
+                "type": "object",
+                "properties": {
+                  "name": { "type": "string", "description": "output name inside captures/" },
+                  "every": { "type": "integer", "minimum": 1, "description": "capture every Nth frame" },
+                  "ffmpeg": { "type": "boolean", "description": "pipe raw frames to ffmpeg (.mp4)" },
+                  "fps": { "type": "number", "minimum": 1, "description": "ffmpeg stream rate" },
+                  "queue": { "type": "integer", "minimum": 1, "maximum": RECORD_QUEUE_MAX, "description": "frames buffered before dropping" }
+                }
+              }
+            },
+            { "name": method::RECORD_STOP, "payload": "empty", "returns": "json RecordResp" },
+            { "name": method::RECORD_STATUS_JSON, "payload": "empty", "returns": "json RecordStatus" }
+          ],
+          "console": {
+            "commands": [
+              {
+                "name": "render.plugins",
+                "help": "Plugin render resources and queued/submitted/dropped draws",
+                "kind": "service_call",
+                "service_id": RENDER_SERVICE_ID,
+                "method": method::STATS_JSON,
+                "payload": "empty"
+              },
+              {
+                "name": "render.gpu_stats",
+                "help": "GPU pass timings and backend counters (validation messages, ...)",
+                "kind": "service_call",
+                "service_id": RENDER_SERVICE_ID,
+                "method": method::GPU_STATS_JSON,
+                "payload": "empty"
+              },
+              {
+                "name": "render.screenshot",
+                "help": "Save the next presented frame as PNG (under screenshots/)",
+                "usage": "render.screenshot [path]",
+                "kind": "service_call",
+                "service_id": RENDER_SERVICE_ID,
+                "method": method::SCREENSHOT,
+                "payload": "raw"
+              },
+              {
+                "name": "render.record",
+                "help": "Record every Nth frame as a PNG sequence (or to ffmpeg) under captures/",
+                "usage": "render.record [name=<name>] [every=<n>] [ffmpeg=true] [fps=<n>] [queue=<n>]",
+                "kind": "service_call",
+                "service_id": RENDER_SERVICE_ID,
+                "method": method::RECORD_START,
+                "payload": "raw"
+              },
+              {
+                "name": "render.record_stop",
+                "help": "Stop recording; queued frames are still written",
+                "kind": "service_call",
+                "service_id": RENDER_SERVICE_ID,
+                "method": method::RECORD_STOP,
+                "payload": "empty"
+              },
+              {
+                "name": "render.record_status",
+                "help": "Recording output and captured/written/dropped frame counts",
+                "kind": "service_call",
+                "service_id": RENDER_SERVICE_ID,
+                "method": method::RECORD_STATUS_JSON,
+                "payload": "empty"
+              }
+            ]
+          }
+        });
+
+        RString::from(d.to_string())
+    }
+
+    fn call(&self, method: MethodName, payload: Blob) -> RResult<Blob, RString> {
+        let m = method.to_string();
+        let p = payload.as_slice();
+
+        let res = match m.as_str() {
+            method::CREATE_BUFFER => Self::create_buffer(p),
+            method::WRITE_BUFFER => Self::write_buffer(p),
+            method::CREATE_SHADER => Self::create_shader(p),
+            method::CREATE_PIPELINE => Self::create_pipeline(p),
+            method::CREATE_UNIFORM_GROUP => Self::create_uniform_group(p),
+            method::DESTROY => Self::destroy(p),
+            method::DRAW => Self::draw(p),
+            method::STATS_JSON => {
+                let bytes = serde_json::to_vec(&plugin_render_stats()).unwrap_or_default();
+                return RResult::ROk(Blob::from(bytes));
+            }
+            method::GPU_STATS_JSON => {
+                let bytes = Self::with_api(|r| Ok(gpu_stats(r)))
+                    .map(|v| v.to_string().into_bytes())
+                    .unwrap_or_else(|e| json!({ "error": e }).to_string().into_bytes());
+                return RResult::ROk(Blob::from(bytes));
+            }
+            method::SCREENSHOT => {
+                let res = Self::screenshot(p);
+                let resp = ScreenshotResp {
+                    ok: res.is_ok(),
+                    path: res.as_ref().ok().map(|path| path.display().to_string()),
+                    error: res.err(),
+                };
+                let bytes = serde_json::to_vec(&resp).unwrap_or_default();
+                return RResult::ROk(Blob::from(bytes));
+            }
+            method::RECORD_START => {
+                let res = if p.iter().all(u8::is_ascii_whitespace) {
+                    serde_json::from_str::<RecordConfig>("{}").map_err(|e| e.to_string())
+                } else {
+                    serde_json::from_slice::<RecordConfig>(p)
+                        .map_err(|e| format!("bad record config: {e}"))
+                };
+                let resp = match res.and_then(start_recording) {
+                    Ok(output) => RecordResp {
+                        ok: true,
+                        status: record_status(),
+                        error: None,
+                        output: Some(output),
+                    },
+                    Err(e) => RecordResp::failed(e),
+                };
+                let bytes = serde_json::to_vec(&resp).unwrap_or_default();
+                return RResult::ROk(Blob::from(bytes));
+            }
+            method::RECORD_STOP => {
+                let resp = match stop_recording() {
+                    Ok(status) => RecordResp {
+                        ok: true,
+                        output: status.output.clone(),
+                        status,
+                        error: None,
+                    },
+                    Err(e) => RecordResp::failed(e),
+                };
+                let bytes = serde_json::to_vec(&resp).unwrap_or_default();
+                return RResult::ROk(Blob::from(bytes));
+            }
+            method::RECORD_STATUS_JSON => {
+                let bytes = serde_json::to_vec(&record_status()).unwrap_or_default();
+                return RResult::ROk(Blob::from(bytes));
+            }
+            _ => return RResult::RErr(RString::from(format!("unknown method: {m}"))),
+        };
+
+        let bytes = serde_json::to_vec(&RenderResp::from_result(res)).unwrap_or_default();
+        RResult::ROk(Blob::from(bytes))
+    }
+}
+
+pub fn register_render_service() {
+    let dyn_svc: ServiceV1Dyn<'static> = guarded_service(RenderService);
+
+    let _ = host_api::host_register_service_impl(dyn_svc, false);
+}
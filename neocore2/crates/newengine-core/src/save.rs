@@ -0,0 +1,442 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use crate::project::active_project;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// File magic of save games.
+pub const SAVE_MAGIC: &[u8; 8] = b"NESAVE\0\0";
+/// Container layout version; the game's own data carries [`SaveData::schema`].
+pub const SAVE_FORMAT_VERSION: u32 = 1;
+/// Extension of slot files.
+pub const SAVE_EXT: &str = "nesave";
+
+/// Magic, format, schema, encoding, flags, reserved, saved-at, raw and stored lengths, hash.
+const HEADER_LEN: usize = 8 + 4 + 4 + 1 + 1 + 2 + 8 + 8 + 8 + 32;
+/// Offset of the hash, which covers the header before it and the raw payload.
+const HASH_OFFSET: usize = HEADER_LEN - 32;
+const FLAG_DEFLATE: u8 = 1;
+/// Smaller payloads are stored as they are; deflate would not pay for itself.
+const COMPRESS_MIN_BYTES: usize = 256;
+/// Refused on read, so a damaged length cannot ask for an absurd allocation.
+const MAX_PAYLOAD_BYTES: u64 = 256 * 1024 * 1024;
+const SLOT_NAME_MAX: usize = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SaveEncoding {
+    /// UTF-8 JSON, readable by `save.load`.
+    Json,
+    /// The game's own binary layout.
+    Binary,
+}
+
+impl SaveEncoding {
+    fn to_u8(self) -> u8 {
+        match self {
+            Self::Json => 0,
+            Self::Binary => 1,
+        }
+    }
+
+    fn from_u8(v: u8) -> Option<Self> {
+        match v {
+            0 => Some(Self::Json),
+            1 => Some(Self::Binary),
+            _ => None,
+        }
+    }
+}
+
+/// Game data of one slot and the schema version it was written with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SaveData {
+    pub schema: u32,
+    pub encoding: SaveEncoding,
+    pub bytes: Vec<u8>,
+}
+
+impl SaveData {
+    pub fn json(schema: u32, value: &Value) -> Self {
+        Self {
+            schema,
+            encoding: SaveEncoding::Json,
+            bytes: serde_json::to_vec(value).unwrap_or_default(),
+        }
+    }
+
+    #[inline]
+    pub fn binary(schema: u32, bytes: Vec<u8>) -> Self {
+        Self {
+            schema,
+            encoding: SaveEncoding::Binary,
+            bytes,
+        }
+    }
+
+    pub fn to_json(&self) -> Result<Value, String> {
+        if self.encoding != SaveEncoding::Json {
+            return Err("save data is binary".to_string());
+        }
+        serde_json::from_slice(&self.bytes).map_err(|e| format!("save data: {e}"))
+    }
+}
+
+/// A slot as listed by [`list_saves`].
+#[derive(Debug, Clone, Serialize)]
+pub struct SaveSlotInfo {
+    pub slot: String,
+    pub path: String,
+    pub schema: u32,
+    pub encoding: SaveEncoding,
+    /// Unix time in milliseconds.
+    pub saved_at_ms: u64,
+    /// Payload bytes before compression.
+    pub size: u64,
+    pub compressed: bool,
+    /// Why the slot cannot be loaded; `None` when it verified.
+    pub error: Option<String>,
+}
+
+/// Upgrades data written with schema `from` to `from + 1`; the caller bumps the schema.
+pub type SaveMigration = Arc<dyn Fn(&mut SaveData) -> Result<(), String> + Send + Sync>;
+
+struct SaveState {
+    dir: Option<PathBuf>,
+    schema: u32,
+    migrations: BTreeMap<u32, SaveMigration>,
+}
+
+/// Process-wide so the service and gameplay code share one slot directory and one set of
+/// migrations.
+static STATE: Mutex<SaveState> = Mutex::new(SaveState {
+    dir: None,
+    schema: 1,
+    migrations: BTreeMap::new(),
+});
+
+/// Schema version of the data the game writes now; older slots are migrated up to it on load.
+pub fn set_save_schema(schema: u32) {
+    if let Ok(mut g) = STATE.lock() {
+        g.schema = schema;
+    }
+}
+
+#[inline]
+pub fn save_schema() -> u32 {
+    STATE.lock().map(|g| g.schema).unwrap_or(1)
+}
+
+/// Registers the step from schema `from` to `from + 1`, replacing an earlier one.
+pub fn register_save_migration<F>(from: u32, migrate: F)
+where
+    F: Fn(&mut SaveData) -> Result<(), String> + Send + Sync + 'static,
+{
+    if let Ok(mut g) = STATE.lock() {
+        g.migrations.insert(from, Arc::new(migrate));
+    }
+}
+
+/// Directory slots are kept in; `None` goes back to the per-project default.
+pub fn set_save_dir(dir: Option<PathBuf>) {
+    if let Ok(mut g) = STATE.lock() {
+        g.dir = dir;
+    }
+}
+
+/// `saves` under [`user_data_dir`] of the active project (or of the executable without one),
+/// unless [`set_save_dir`] picked another.
+pub fn save_dir() -> PathBuf {
+    if let Some(dir) = STATE.lock().ok().and_then(|g| g.dir.clone()) {
+        return dir;
+    }
+    let app = match active_project() {
+        Some(p) => p.name().to_string(),
+        None => std::env::current_exe()
+            .ok()
+            .and_then(|exe| exe.file_stem().map(|s| s.to_string_lossy().into_owned()))
+            .unwrap_or_else(|| "game".to_string()),
+    };
+    user_data_dir(&app)
+        .unwrap_or_else(|| PathBuf::from("user_data"))
+        .join("saves")
+}
+
+/// Per-user data directory of `app`: `%APPDATA%\NewEngine\<app>` on Windows,
+/// `~/Library/Application Support/NewEngine/<app>` on macOS and
+/// `$XDG_DATA_HOME/newengine/<app>` (`~/.local/share` by default) elsewhere.
+pub fn user_data_dir(app: &str) -> Option<PathBuf> {
+    let env_dir = |key: &str| {
+        std::env::var_os(key)
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from)
+    };
+    let base = if cfg!(windows) {
+        env_dir("APPDATA")?.join("NewEngine")
+    } else if cfg!(target_os = "macos") {
+        env_dir("HOME")?.join("Library/Application Support/NewEngine")
+    } else {
+        env_dir("XDG_DATA_HOME")
+            .or_else(|| env_dir("HOME").map(|h| h.join(".local/share")))?
+            .join("newengine")
+    };
+    Some(base.join(dir_name(app)))
+}
+
+/// `app` with characters file systems disagree on replaced.
+fn dir_name(app: &str) -> String {
+    let name: String = app
+        .trim()
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    match name.trim_matches('.') {
+        "" => "game".to_string(),
+        n => n.to_string(),
+    }
+}
+
+fn slot_path(dir: &Path, slot: &str) -> Result<PathBuf, String> {
+    let valid = !slot.is_empty()
+        && slot.len() <= SLOT_NAME_MAX
+        && slot
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+    if !valid {
+        return Err(format!(
+            "invalid slot name '{slot}' (1-{SLOT_NAME_MAX} of a-z, A-Z, 0-9, '_', '-')"
+        ));
+    }
+    Ok(dir.join(format!("{slot}.{SAVE_EXT}")))
+}
+
+/// Writes `data` to `slot`. The previous file of the slot is kept as `<slot>.bak`, which
+/// [`read_save`] falls back to when the slot itself is damaged.
+pub fn write_save(slot: &str, data: &SaveData) -> Result<SaveSlotInfo, String> {
+    let dir = save_dir();
+    let path = slot_path(&dir, slot)?;
+    std::fs::create_dir_all(&dir).map_err(|e| format!("{}: {e}", dir.display()))?;
+
+    let saved_at_ms = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default();
+    let (bytes, compressed) = encode(data, saved_at_ms);
+
+    // Write next to the target first so a crash mid-save keeps the previous file.
+    let tmp = path.with_extension("tmp");
+    let write = || -> std::io::Result<()> {
+        use std::io::Write;
+        let mut f = std::fs::File::create(&tmp)?;
+        f.write_all(&bytes)?;
+        f.sync_all()
+    };
+    write().map_err(|e| format!("{}: {e}", tmp.display()))?;
+    if path.exists() {
+        let bak = path.with_extension("bak");
+        let _ = std::fs::remove_file(&bak);
+        std::fs::rename(&path, &bak).map_err(|e| format!("{}: {e}", bak.display()))?;
+    }
+    std::fs::rename(&tmp, &path).map_err(|e| format!("{}: {e}", path.display()))?;
+
+    log::info!(
+        "save: write slot='{slot}' schema={} bytes={} path='{}'",
+        data.schema,
+        bytes.len(),
+        path.display()
+    );
+    Ok(SaveSlotInfo {
+        slot: slot.to_string(),
+        path: path.display().to_string(),
+        schema: data.schema,
+        encoding: data.encoding,
+        saved_at_ms,
+        size: data.bytes.len() as u64,
+        compressed,
+        error: None,
+    })
+}
+
+/// Reads `slot` and migrates it to [`save_schema`]. A damaged slot falls back to its backup.
+pub fn read_save(slot: &str) -> Result<SaveData, String> {
+    let path = slot_path(&save_dir(), slot)?;
+    let data = match read_file(&path) {
+        Ok((data, _)) => data,
+        Err(e) if path.exists() => {
+            let bak = path.with_extension("bak");
+            let (data, _) = read_file(&bak).map_err(|_| e.clone())?;
+            log::warn!("save: slot '{slot}' unreadable, loaded backup err='{e}'");
+            data
+        }
+        Err(e) => return Err(e),
+    };
+    migrate(data)
+}
+
+/// Slots in [`save_dir`], by name. Each is verified, so damaged ones show with an error.
+pub fn list_saves() -> Vec<SaveSlotInfo> {
+    let dir = save_dir();
+    let Ok(entries) = std::fs::read_dir(&dir) else {
+        return Vec::new();
+    };
+
+    let mut out: Vec<SaveSlotInfo> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|e| e == SAVE_EXT))
+        .map(|path| {
+            let slot = path
+                .file_stem()
+                .map(|s| s.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            let mut info = SaveSlotInfo {
+                slot,
+                path: path.display().to_string(),
+                schema: 0,
+                encoding: SaveEncoding::Binary,
+                saved_at_ms: 0,
+                size: 0,
+                compressed: false,
+                error: None,
+            };
+            match read_file(&path) {
+                Ok((data, header)) => {
+                    info.schema = data.schema;
+                    info.encoding = data.encoding;
+                    info.saved_at_ms = header.saved_at_ms;
+                    info.size = data.bytes.len() as u64;
+                    info.compressed = header.compressed;
+                }
+                Err(e) => info.error = Some(e),
+            }
+            info
+        })
+        .collect();
+    out.sort_by(|a, b| a.slot.cmp(&b.slot));
+    out
+}
+
+fn migrate(mut data: SaveData) -> Result<SaveData, String> {
+    let (target, migrations) = match STATE.lock() {
+        Ok(g) => (g.schema, g.migrations.clone()),
+        Err(_) => return Err("save state poisoned".to_string()),
+    };
+    if data.schema > target {
+        return Err(format!(
+            "save schema {} is newer than this game's {target}",
+            data.schema
+        ));
+    }
+    while data.schema < target {
+        let from = data.schema;
+        let step = migrations
+            .get(&from)
+            .ok_or_else(|| format!("no save migration from schema {from}"))?;
+        step(&mut data).map_err(|e| format!("save migration {from} -> {}: {e}", from + 1))?;
+        data.schema = from + 1;
+        log::info!("save: migrated schema {from} -> {}", data.schema);
+    }
+    Ok(data)
+}
+
+struct SaveHeader {
+    saved_at_ms: u64,
+    compressed: bool,
+}
+
+/// The file bytes and whether the payload was deflated.
+fn encode(data: &SaveData, saved_at_ms: u64) -> (Vec<u8>, bool) {
+    let deflated = (data.bytes.len() >= COMPRESS_MIN_BYTES)
+        .then(|| miniz_oxide::deflate::compress_to_vec(&data.bytes, 6))
+        .filter(|d| d.len() < data.bytes.len());
+    let flags = if deflated.is_some() { FLAG_DEFLATE } else { 0 };
+    let stored = deflated.as_deref().unwrap_or(&data.bytes);
+
+    let mut out = Vec::with_capacity(HEADER_LEN + stored.len());
+    out.extend_from_slice(SAVE_MAGIC);
+    out.extend_from_slice(&SAVE_FORMAT_VERSION.to_le_bytes());
+    out.extend_from_slice(&data.schema.to_le_bytes());
+    out.push(data.encoding.to_u8());
+    out.push(flags);
+    out.extend_from_slice(&[0u8; 2]);
+    out.extend_from_slice(&saved_at_ms.to_le_bytes());
+    out.extend_from_slice(&(data.bytes.len() as u64).to_le_bytes());
+    out.extend_from_slice(&(stored.len() as u64).to_le_bytes());
+    let hash = content_hash(&out, &data.bytes);
+    out.extend_from_slice(&hash);
+    out.extend_from_slice(stored);
+    (out, flags != 0)
+}
+
+/// Covers the header fields as well as the data, so a flipped schema or length is caught too.
+fn content_hash(header: &[u8], raw: &[u8]) -> [u8; 32] {
+    let mut h = blake3::Hasher::new();
+    h.update(&header[..HASH_OFFSET]);
+    h.update(raw);
+    *h.finalize().as_bytes()
+}
+
+fn read_file(path: &Path) -> Result<(SaveData, SaveHeader), String> {
+    let bytes = std::fs::read(path).map_err(|e| format!("{}: {e}", path.display()))?;
+    decode(&bytes).map_err(|e| format!("{}: {e}", path.display()))
+}
+
+fn decode(bytes: &[u8]) -> Result<(SaveData, SaveHeader), String> {
+    if bytes.len() < HEADER_LEN {
+        return Err("save truncated".to_string());
+    }
+    if &bytes[..8] != SAVE_MAGIC {
+        return Err("not a save game".to_string());
+    }
+    let u32_at = |o: usize| u32::from_le_bytes(bytes[o..o + 4].try_into().unwrap_or_default());
+    let u64_at = |o: usize| u64::from_le_bytes(bytes[o..o + 8].try_into().unwrap_or_default());
+
+    let format = u32_at(8);
+    if format != SAVE_FORMAT_VERSION {
+        return Err(format!(
+            "unsupported save format {format} (expected {SAVE_FORMAT_VERSION})"
+        ));
+    }
+    let schema = u32_at(12);
+    let encoding =
+        SaveEncoding::from_u8(bytes[16]).ok_or_else(|| "save: unknown encoding".to_string())?;
+    let compressed = bytes[17] & FLAG_DEFLATE != 0;
+    let saved_at_ms = u64_at(20);
+    let raw_len = u64_at(28);
+    let stored_len = u64_at(36);
+    if raw_len > MAX_PAYLOAD_BYTES || stored_len != (bytes.len() - HEADER_LEN) as u64 {
+        return Err("save corrupt (bad length)".to_string());
+    }
+
+    let stored = &bytes[HEADER_LEN..];
+    let raw = if compressed {
+        miniz_oxide::inflate::decompress_to_vec_with_limit(stored, raw_len as usize)
+            .map_err(|e| format!("save corrupt (inflate: {e:?})"))?
+    } else {
+        stored.to_vec()
+    };
+    if raw.len() as u64 != raw_len || content_hash(bytes, &raw) != bytes[HASH_OFFSET..HEADER_LEN] {
+        return Err("save corrupt (hash mismatch)".to_string());
+    }
+
+    let data = SaveData {
+        schema,
+        encoding,
+        bytes: raw,
+    };
+    Ok((
+        data,
+        SaveHeader {
+            saved_at_ms,
+            compressed,
+        },
+    ))
+}
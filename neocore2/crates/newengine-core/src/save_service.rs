@@ -0,0 +1,142 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use crate::plugins::host_api;
+use crate::save::{
+    list_saves, read_save, save_dir, save_schema, write_save, SaveData, SaveEncoding, SaveSlotInfo,
+};
+use abi_stable::std_types::{RResult, RString};
+use newengine_plugin_api::{
+    guarded_service, Blob, CapabilityId, MethodName, ServiceV1, ServiceV1Dyn,
+};
+use serde::Serialize;
+use serde_json::{json, Value};
+
+pub const SAVE_SERVICE_ID: &str = "engine.save";
+
+pub mod method {
+    pub const WRITE: &str = "save.write";
+    pub const LOAD_JSON: &str = "save.load_json";
+    pub const LIST_JSON: &str = "save.list_json";
+}
+
+#[derive(Debug, Serialize)]
+struct SaveLoadResp {
+    slot: String,
+    schema: u32,
+    encoding: SaveEncoding,
+    bytes: usize,
+    /// Decoded payload of a JSON slot; binary slots only report their size.
+    data: Option<Value>,
+}
+
+#[derive(Debug, Serialize)]
+struct SaveListResp {
+    dir: String,
+    slots: Vec<SaveSlotInfo>,
+}
+
+struct SaveService;
+
+impl SaveService {
+    /// Payload: `<slot> [json]`; the data defaults to `{}` and gets the current schema.
+    fn write(arg: &str) -> Result<SaveSlotInfo, String> {
+        let arg = arg.trim();
+        let (slot, rest) = arg.split_once(char::is_whitespace).unwrap_or((arg, ""));
+        if slot.is_empty() {
+            return Err("usage: save.write <slot> [json]".to_string());
+        }
+        let value = match rest.trim() {
+            "" => json!({}),
+            s => serde_json::from_str(s).map_err(|e| format!("save.write: bad json: {e}"))?,
+        };
+        write_save(slot, &SaveData::json(save_schema(), &value))
+    }
+
+    fn load(arg: &str) -> Result<SaveLoadResp, String> {
+        let slot = arg.trim();
+        let data = read_save(slot)?;
+        Ok(SaveLoadResp {
+            slot: slot.to_string(),
+            schema: data.schema,
+            encoding: data.encoding,
+            bytes: data.bytes.len(),
+            data: data.to_json().ok(),
+        })
+    }
+}
+
+impl ServiceV1 for SaveService {
+    fn id(&self) -> CapabilityId {
+        RString::from(SAVE_SERVICE_ID)
+    }
+
+    fn describe(&self) -> RString {
+        let d = json!({
+          "id": SAVE_SERVICE_ID,
+          "version": 1,
+          "methods": [
+            { "name": method::WRITE, "payload": "utf8 '<slot> [json]'", "returns": "json SaveSlotInfo" },
+            { "name": method::LOAD_JSON, "payload": "utf8 '<slot>'", "returns": "json SaveLoadResp" },
+            { "name": method::LIST_JSON, "payload": "empty", "returns": "json SaveListResp" }
+          ],
+          "console": {
+            "commands": [
+              {
+                "name": "save.write",
+                "help": "Write JSON data (default {}) to a save slot",
+                "usage": "save.write <slot> [json]",
+                "kind": "service_call",
+                "service_id": SAVE_SERVICE_ID,
+                "method": method::WRITE,
+                "payload": "raw"
+              },
+              {
+                "name": "save.load",
+                "help": "Load and verify a save slot, migrating it to the current schema",
+                "usage": "save.load <slot>",
+                "kind": "service_call",
+                "service_id": SAVE_SERVICE_ID,
+                "method": method::LOAD_JSON,
+                "payload": "raw"
+              },
+              {
+                "name": "save.list",
+                "help": "List save slots with their schema and integrity",
+                "kind": "service_call",
+                "service_id": SAVE_SERVICE_ID,
+                "method": method::LIST_JSON,
+                "payload": "empty"
+              }
+            ]
+          }
+        });
+
+        RString::from(d.to_string())
+    }
+
+    fn call(&self, method: MethodName, payload: Blob) -> RResult<Blob, RString> {
+        let m = method.to_string();
+        let arg = String::from_utf8_lossy(payload.as_slice());
+
+        let resp = match m.as_str() {
+            method::WRITE => Self::write(&arg).map(|r| serde_json::to_vec(&r)),
+            method::LOAD_JSON => Self::load(&arg).map(|r| serde_json::to_vec(&r)),
+            method::LIST_JSON => Ok(serde_json::to_vec(&SaveListResp {
+                dir: save_dir().to_string_lossy().to_string(),
+                slots: list_saves(),
+            })),
+            _ => return RResult::RErr(RString::from(format!("unknown method: {m}"))),
+        };
+
+        match resp {
+            Ok(bytes) => RResult::ROk(Blob::from(bytes.unwrap_or_default())),
+            Err(e) => RResult::RErr(RString::from(e)),
+        }
+    }
+}
+
+pub fn register_save_service() {
+    let dyn_svc: ServiceV1Dyn<'static> = guarded_service(SaveService);
+
+    let _ = host_api::host_register_service_impl(dyn_svc, false);
+}
@@ -0,0 +1,206 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use crate::engine::{Engine, RunProfile};
+use crate::error::{EngineError, EngineResult};
+use crate::headless::{HeadlessExit, HeadlessReport, HeadlessRunner};
+
+use std::time::{Duration, Instant};
+
+const DEFAULT_TELEMETRY_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Tick-time telemetry for the dedicated server loop.
+///
+/// Updated every tick and stored in `Resources`, so modules and services can report it.
+/// The window fields cover the current telemetry interval and reset after each log line.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ServerTickStats {
+    pub tick_rate_hz: f32,
+    pub ticks: u64,
+    pub last_tick_ms: f32,
+    pub window_avg_ms: f32,
+    pub window_max_ms: f32,
+    /// Ticks that took longer than the tick budget since start.
+    pub overruns: u64,
+    window_ticks: u32,
+    window_sum_ms: f64,
+}
+
+impl ServerTickStats {
+    fn record(&mut self, tick_ms: f32, budget_ms: f32) {
+        self.ticks += 1;
+        self.last_tick_ms = tick_ms;
+        if tick_ms > budget_ms {
+            self.overruns += 1;
+        }
+
+        self.window_ticks += 1;
+        self.window_sum_ms += tick_ms as f64;
+        self.window_avg_ms = (self.window_sum_ms / self.window_ticks as f64) as f32;
+        self.window_max_ms = self.window_max_ms.max(tick_ms);
+    }
+
+    fn reset_window(&mut self) {
+        self.window_ticks = 0;
+        self.window_sum_ms = 0.0;
+        self.window_max_ms = 0.0;
+    }
+}
+
+/// Fixed-rate loop for dedicated servers.
+///
+/// Expects an engine built with `RunProfile::Server` (fixed update only, no render phase) and
+/// paces ticks at `1 / fixed_dt`. SIGINT/SIGTERM request shutdown through the engine's
+/// `ShutdownToken`, so modules get a regular `shutdown` pass.
+pub struct ServerRunner<E: Send + 'static> {
+    runner: HeadlessRunner<E>,
+    max_ticks: Option<u64>,
+    telemetry_interval: Duration,
+    signals: bool,
+}
+
+impl<E: Send + 'static> ServerRunner<E> {
+    pub fn new(engine: Engine<E>) -> Self {
+        if engine.profile() != RunProfile::Server {
+            log::warn!(
+                "server: engine profile is {:?}; update/render stages still run",
+                engine.profile()
+            );
+        }
+
+        Self {
+            runner: HeadlessRunner::new(engine),
+            max_ticks: None,
+            telemetry_interval: DEFAULT_TELEMETRY_INTERVAL,
+            signals: true,
+        }
+    }
+
+    /// Stops after `ticks` ticks. `None` runs until shutdown is requested.
+    #[inline]
+    pub fn with_max_ticks(mut self, ticks: Option<u64>) -> Self {
+        self.max_ticks = ticks;
+        self
+    }
+
+    /// How often tick telemetry is logged. `Duration::ZERO` disables logging.
+    #[inline]
+    pub fn with_telemetry_interval(mut self, interval: Duration) -> Self {
+        self.telemetry_interval = interval;
+        self
+    }
+
+    /// Installs a SIGINT/SIGTERM handler on run (default). Disable when the host owns signals.
+    #[inline]
+    pub fn with_signal_handler(mut self, enabled: bool) -> Self {
+        self.signals = enabled;
+        self
+    }
+
+    #[inline]
+    pub fn engine_mut(&mut self) -> &mut Engine<E> {
+        self.runner.engine_mut()
+    }
+
+    /// Runs the fixed-rate loop until shutdown, then shuts the engine down.
+    pub fn run(mut self) -> EngineResult<HeadlessReport> {
+        if self.signals {
+            self.install_signal_handler();
+        }
+
+        let t0 = Instant::now();
+        let result = self.run_loop();
+
+        if let Err(e) = self.runner.engine_mut().shutdown() {
+            log::error!("engine.shutdown failed: {e}");
+        }
+
+        let (exit, stats) = result?;
+        let report = HeadlessReport {
+            exit,
+            frames: self.runner.frames(),
+            fixed_ticks: stats.ticks,
+            elapsed: t0.elapsed(),
+        };
+
+        log::info!(
+            "server: stopped exit={:?} ticks={} overruns={} elapsed_ms={}",
+            report.exit,
+            stats.ticks,
+            stats.overruns,
+            report.elapsed.as_millis()
+        );
+        Ok(report)
+    }
+
+    fn install_signal_handler(&self) {
+        let token = self.runner.engine().shutdown_token();
+        let res = ctrlc::set_handler(move || {
+            log::info!("server: termination signal received; shutting down");
+            token.request();
+        });
+
+        if let Err(e) = res {
+            log::warn!("server: signal handler not installed err='{e}'");
+        }
+    }
+
+    fn run_loop(&mut self) -> EngineResult<(HeadlessExit, ServerTickStats)> {
+        let tick = self.runner.engine().fixed_step();
+        let budget_ms = tick.as_secs_f32() * 1000.0;
+
+        let mut stats = ServerTickStats {
+            tick_rate_hz: 1.0 / tick.as_secs_f32(),
+            ..ServerTickStats::default()
+        };
+
+        self.runner.start()?;
+        log::info!(
+            "server: running tick_rate_hz={:.1} max_ticks={:?}",
+            stats.tick_rate_hz,
+            self.max_ticks
+        );
+
+        let mut next = Instant::now();
+        let mut last_report = Instant::now();
+
+        loop {
+            if self.max_ticks.is_some_and(|max| stats.ticks >= max) {
+                return Ok((HeadlessExit::FrameLimit, stats));
+            }
+
+            let started = Instant::now();
+            match self.runner.tick() {
+                Ok(_) => {}
+                Err(EngineError::ExitRequested) => return Ok((HeadlessExit::Requested, stats)),
+                Err(e) => return Err(e),
+            }
+
+            stats.record(started.elapsed().as_secs_f32() * 1000.0, budget_ms);
+            self.runner.engine_mut().resources_mut().insert(stats);
+
+            if !self.telemetry_interval.is_zero()
+                && last_report.elapsed() >= self.telemetry_interval
+            {
+                log::info!(
+                    "server.tick ticks={} avg_ms={:.3} max_ms={:.3} budget_ms={:.3} overruns={}",
+                    stats.ticks,
+                    stats.window_avg_ms,
+                    stats.window_max_ms,
+                    budget_ms,
+                    stats.overruns
+                );
+                stats.reset_window();
+                last_report = Instant::now();
+            }
+
+            next += tick;
+            let now = Instant::now();
+            if next > now {
+                std::thread::sleep(next - now);
+            } else if now - next > tick * 4 {
+                // Far behind (debugger, suspended VM): resync instead of bursting to catch up.
+                next = now;
+            }
+        }
+    }
+}
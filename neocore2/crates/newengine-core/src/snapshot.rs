@@ -0,0 +1,226 @@
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// File magic of engine snapshots.
+pub const SNAPSHOT_MAGIC: &[u8; 8] = b"NESNAP\0\0";
+/// Container layout version; module payloads carry their own [`Snapshot::snapshot_version`].
+pub const SNAPSHOT_FORMAT_VERSION: u32 = 1;
+/// Used by `engine.snapshot.save` / `engine.snapshot.load` without a path.
+pub const QUICK_SNAPSHOT_FILE: &str = "quicksave.nesnap";
+
+/// Module state that can be written to and restored from an engine snapshot.
+///
+/// Modules opt in through [`Module::snapshot`](crate::module::Module::snapshot). The engine
+/// saves and loads between frames, never inside a stage, and visits modules in init order,
+/// so a module is restored after the modules it depends on.
+pub trait Snapshot {
+    /// Version of this module's payload layout, handed back to [`Snapshot::load`].
+    fn snapshot_version(&self) -> u32 {
+        1
+    }
+
+    fn save(&self, w: &mut dyn Write) -> Result<(), String>;
+
+    /// `version` is the [`Snapshot::snapshot_version`] the payload was written with.
+    fn load(&mut self, version: u32, r: &mut dyn Read) -> Result<(), String>;
+}
+
+/// One module's payload inside a snapshot file.
+#[derive(Debug, Clone)]
+pub struct SnapshotSection {
+    pub module_id: String,
+    pub version: u32,
+    pub data: Vec<u8>,
+}
+
+/// Decoded snapshot file.
+#[derive(Debug, Clone, Default)]
+pub struct SnapshotFile {
+    /// Fixed tick the snapshot was taken at; restored on load.
+    pub fixed_tick: u64,
+    pub frame_index: u64,
+    pub sections: Vec<SnapshotSection>,
+}
+
+impl SnapshotFile {
+    pub fn section(&self, module_id: &str) -> Option<&SnapshotSection> {
+        self.sections.iter().find(|s| s.module_id == module_id)
+    }
+
+    pub fn write_to(&self, w: &mut dyn Write) -> Result<(), String> {
+        let mut out = Vec::new();
+        out.extend_from_slice(SNAPSHOT_MAGIC);
+        out.extend_from_slice(&SNAPSHOT_FORMAT_VERSION.to_le_bytes());
+        out.extend_from_slice(&self.fixed_tick.to_le_bytes());
+        out.extend_from_slice(&self.frame_index.to_le_bytes());
+        out.extend_from_slice(&(self.sections.len() as u32).to_le_bytes());
+
+        for s in self.sections.iter() {
+            let id = s.module_id.as_bytes();
+            let id_len = u16::try_from(id.len())
+                .map_err(|_| format!("module id too long: '{}'", s.module_id))?;
+            out.extend_from_slice(&id_len.to_le_bytes());
+            out.extend_from_slice(id);
+            out.extend_from_slice(&s.version.to_le_bytes());
+            out.extend_from_slice(&(s.data.len() as u64).to_le_bytes());
+            out.extend_from_slice(&s.data);
+        }
+
+        w.write_all(&out).map_err(|e| e.to_string())
+    }
+
+    pub fn read_from(r: &mut dyn Read) -> Result<Self, String> {
+        let mut magic = [0u8; 8];
+        r.read_exact(&mut magic)
+            .map_err(|e| format!("snapshot header: {e}"))?;
+        if &magic != SNAPSHOT_MAGIC {
+            return Err("not an engine snapshot".to_string());
+        }
+
+        let format = read_u32(r)?;
+        if format != SNAPSHOT_FORMAT_VERSION {
+            return Err(format!(
+                "unsupported snapshot format {format} (expected {SNAPSHOT_FORMAT_VERSION})"
+            ));
+        }
+
+        let fixed_tick = read_u64(r)?;
+        let frame_index = read_u64(r)?;
+        let count = read_u32(r)?;
+
+        let mut sections = Vec::new();
+        for _ in 0..count {
+            let mut id_len = [0u8; 2];
+            r.read_exact(&mut id_len).map_err(truncated)?;
+            let mut id = vec![0u8; u16::from_le_bytes(id_len) as usize];
+            r.read_exact(&mut id).map_err(truncated)?;
+            let module_id =
+                String::from_utf8(id).map_err(|_| "snapshot: module id is not utf8".to_string())?;
+
+            let version = read_u32(r)?;
+            let len = read_u64(r)?;
+            let mut data = Vec::new();
+            r.take(len).read_to_end(&mut data).map_err(truncated)?;
+            if data.len() as u64 != len {
+                return Err(format!("snapshot: section '{module_id}' truncated"));
+            }
+
+            sections.push(SnapshotSection {
+                module_id,
+                version,
+                data,
+            });
+        }
+
+        Ok(Self {
+            fixed_tick,
+            frame_index,
+            sections,
+        })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir).map_err(|e| format!("{}: {e}", dir.display()))?;
+        }
+        // Write next to the target first so a crash mid-save keeps the previous snapshot.
+        let tmp = path.with_extension("tmp");
+        let mut f = std::fs::File::create(&tmp).map_err(|e| format!("{}: {e}", tmp.display()))?;
+        self.write_to(&mut f)?;
+        f.sync_all().map_err(|e| e.to_string())?;
+        drop(f);
+        std::fs::rename(&tmp, path).map_err(|e| format!("{}: {e}", path.display()))
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let f = std::fs::File::open(path).map_err(|e| format!("{}: {e}", path.display()))?;
+        Self::read_from(&mut std::io::BufReader::new(f))
+    }
+}
+
+#[inline]
+fn truncated(e: std::io::Error) -> String {
+    format!("snapshot truncated: {e}")
+}
+
+fn read_u32(r: &mut dyn Read) -> Result<u32, String> {
+    let mut b = [0u8; 4];
+    r.read_exact(&mut b).map_err(truncated)?;
+    Ok(u32::from_le_bytes(b))
+}
+
+fn read_u64(r: &mut dyn Read) -> Result<u64, String> {
+    let mut b = [0u8; 8];
+    r.read_exact(&mut b).map_err(truncated)?;
+    Ok(u64::from_le_bytes(b))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SnapshotOp {
+    Save,
+    Load,
+}
+
+/// Outcome of a snapshot save or load.
+#[derive(Debug, Clone, Serialize)]
+pub struct SnapshotReport {
+    pub op: SnapshotOp,
+    pub path: String,
+    pub ok: bool,
+    pub fixed_tick: u64,
+    /// Modules written or restored, in order.
+    pub modules: Vec<String>,
+    /// Modules that implement [`Snapshot`] but had no section in the loaded file.
+    pub missing: Vec<String>,
+    /// Sections in the loaded file no registered module claimed.
+    pub unknown: Vec<String>,
+    pub error: Option<String>,
+}
+
+impl SnapshotReport {
+    pub(crate) fn new(op: SnapshotOp, path: &Path) -> Self {
+        Self {
+            op,
+            path: path.display().to_string(),
+            ok: false,
+            fixed_tick: 0,
+            modules: Vec::new(),
+            missing: Vec::new(),
+            unknown: Vec::new(),
+            error: None,
+        }
+    }
+}
+
+/// Process-wide so console commands can queue requests without a handle to the `Engine`.
+static PENDING: Mutex<VecDeque<(SnapshotOp, PathBuf)>> = Mutex::new(VecDeque::new());
+static LAST: Mutex<Option<SnapshotReport>> = Mutex::new(None);
+
+/// Queues a save or load; the engine runs it at the end of the current frame.
+pub fn request_snapshot(op: SnapshotOp, path: impl Into<PathBuf>) {
+    if let Ok(mut g) = PENDING.lock() {
+        g.push_back((op, path.into()));
+    }
+}
+
+pub(crate) fn take_snapshot_requests() -> Vec<(SnapshotOp, PathBuf)> {
+    PENDING
+        .lock()
+        .map(|mut g| g.drain(..).collect())
+        .unwrap_or_default()
+}
+
+/// Report of the most recent save or load, queued or direct.
+pub fn last_snapshot_report() -> Option<SnapshotReport> {
+    LAST.lock().ok()?.clone()
+}
+
+pub(crate) fn set_last_snapshot_report(report: SnapshotReport) {
+    if let Ok(mut g) = LAST.lock() {
+        *g = Some(report);
+    }
+}
@@ -0,0 +1,122 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use crate::plugins::host_api;
+use crate::snapshot::{
+    last_snapshot_report, request_snapshot, SnapshotOp, SnapshotReport, QUICK_SNAPSHOT_FILE,
+};
+use abi_stable::std_types::{RResult, RString};
+use newengine_plugin_api::{
+    guarded_service, Blob, CapabilityId, MethodName, ServiceV1, ServiceV1Dyn,
+};
+use serde::Serialize;
+use serde_json::json;
+
+pub const SNAPSHOT_SERVICE_ID: &str = "engine.snapshot";
+
+pub mod method {
+    pub const SAVE: &str = "snapshot.save";
+    pub const LOAD: &str = "snapshot.load";
+    pub const STATUS_JSON: &str = "snapshot.status_json";
+}
+
+#[derive(Debug, Serialize)]
+struct SnapshotQueuedResp {
+    queued: bool,
+    op: SnapshotOp,
+    path: String,
+}
+
+#[derive(Debug, Serialize)]
+struct SnapshotStatusResp {
+    last: Option<SnapshotReport>,
+}
+
+struct SnapshotService;
+
+impl SnapshotService {
+    /// Payload: file path; empty uses [`QUICK_SNAPSHOT_FILE`]. Runs at the end of the frame.
+    fn queue(op: SnapshotOp, arg: &str) -> SnapshotQueuedResp {
+        let path = match arg.trim() {
+            "" => QUICK_SNAPSHOT_FILE,
+            p => p,
+        };
+        request_snapshot(op, path);
+        SnapshotQueuedResp {
+            queued: true,
+            op,
+            path: path.to_string(),
+        }
+    }
+}
+
+impl ServiceV1 for SnapshotService {
+    fn id(&self) -> CapabilityId {
+        RString::from(SNAPSHOT_SERVICE_ID)
+    }
+
+    fn describe(&self) -> RString {
+        let d = json!({
+          "id": SNAPSHOT_SERVICE_ID,
+          "version": 1,
+          "methods": [
+            { "name": method::SAVE, "payload": "utf8 '[path]'", "returns": "json SnapshotQueuedResp" },
+            { "name": method::LOAD, "payload": "utf8 '[path]'", "returns": "json SnapshotQueuedResp" },
+            { "name": method::STATUS_JSON, "payload": "empty", "returns": "json SnapshotStatusResp" }
+          ],
+          "console": {
+            "commands": [
+              {
+                "name": "engine.snapshot.save",
+                "help": format!("Save module state at the end of the frame (default {QUICK_SNAPSHOT_FILE})"),
+                "usage": "engine.snapshot.save [path]",
+                "kind": "service_call",
+                "service_id": SNAPSHOT_SERVICE_ID,
+                "method": method::SAVE,
+                "payload": "raw"
+              },
+              {
+                "name": "engine.snapshot.load",
+                "help": format!("Restore module state at the end of the frame (default {QUICK_SNAPSHOT_FILE})"),
+                "usage": "engine.snapshot.load [path]",
+                "kind": "service_call",
+                "service_id": SNAPSHOT_SERVICE_ID,
+                "method": method::LOAD,
+                "payload": "raw"
+              },
+              {
+                "name": "engine.snapshot.status",
+                "help": "Print the result of the last snapshot save or load",
+                "kind": "service_call",
+                "service_id": SNAPSHOT_SERVICE_ID,
+                "method": method::STATUS_JSON,
+                "payload": "empty"
+              }
+            ]
+          }
+        });
+
+        RString::from(d.to_string())
+    }
+
+    fn call(&self, method: MethodName, payload: Blob) -> RResult<Blob, RString> {
+        let m = method.to_string();
+        let arg = String::from_utf8_lossy(payload.as_slice());
+
+        let resp = match m.as_str() {
+            method::SAVE => serde_json::to_vec(&Self::queue(SnapshotOp::Save, &arg)),
+            method::LOAD => serde_json::to_vec(&Self::queue(SnapshotOp::Load, &arg)),
+            method::STATUS_JSON => serde_json::to_vec(&SnapshotStatusResp {
+                last: last_snapshot_report(),
+            }),
+            _ => return RResult::RErr(RString::from(format!("unknown method: {m}"))),
+        };
+
+        RResult::ROk(Blob::from(resp.unwrap_or_default()))
+    }
+}
+
+pub fn register_snapshot_service() {
+    let dyn_svc: ServiceV1Dyn<'static> = guarded_service(SnapshotService);
+
+    let _ = host_api::host_register_service_impl(dyn_svc, false);
+}
@@ -1,6 +1,7 @@
 #![forbid(unsafe_op_in_unsafe_fn)]
 
-use std::collections::HashMap;
+use newengine_assets::PathCaseMode;
+use std::collections::{BTreeMap, HashMap};
 use std::path::PathBuf;
 
 #[derive(Debug, Clone)]
@@ -51,22 +52,68 @@ pub struct StartupConfig {
     pub window_title: String,
     pub window_size: (u32, u32),
     pub window_placement: WindowPlacement,
+    /// Frame rate cap while the window is unfocused; 0 keeps running at full rate.
+    pub window_unfocused_fps: u32,
 
     /// Path inside assets root, resolved via AssetManager + existing importers.
     /// Example: "ui/icon.png".
     pub window_icon_path: Option<String>,
 
     pub modules_dir: PathBuf,
+    /// Plugin ids not to load, on top of the `disabled` list in `<modules_dir>/plugins.json`.
+    pub disabled_plugins: Vec<String>,
 
     pub assets_root: PathBuf,
     pub asset_pump_steps: u32,
     pub asset_filesystem_source: bool,
+    /// Whether letter case distinguishes asset paths (`insensitive` by default).
+    pub asset_path_case: PathCaseMode,
+    /// Derived-data cache for imported blobs. `None` always re-imports.
+    pub asset_cache_dir: Option<PathBuf>,
+    /// Extra filesystem roots mounted at `engine://` and `mods://`; `assets_root` is `game://`.
+    pub asset_engine_root: Option<PathBuf>,
+    pub asset_mods_root: Option<PathBuf>,
+    /// Asset packs (`.nepak`) mounted under the filesystem roots; see `AssetManagerConfig`.
+    pub asset_packs: Vec<PathBuf>,
+    /// Logical path of the first scene; `None` leaves the choice to the app.
+    pub startup_scene: Option<String>,
+
+    /// Service dispatch caps (see `plugins::ServiceLimits`). 0 disables the respective limit.
+    pub service_max_payload_bytes: u32,
+    /// Service id -> payload cap in bytes, overriding `service_max_payload_bytes`.
+    pub service_payload_limits: BTreeMap<String, u32>,
+    pub service_max_calls_per_sec: u32,
+    /// Rate limit engine-internal calls too; plugin and console calls always are.
+    pub service_limit_host: bool,
 
     pub render_backend: String,
     pub render_clear_color: [f32; 4],
     pub render_debug_text: String,
+    /// Persistent GPU pipeline cache (one file per device and driver). `None` keeps it in memory.
+    pub render_pipeline_cache_dir: Option<PathBuf>,
+    /// Cascaded shadow maps of the sun: cascade count (0 disables shadows), texels per cascade
+    /// side, view distance covered, and the split blend from even (0) to logarithmic (1).
+    pub render_shadow_cascades: u32,
+    pub render_shadow_map_size: u32,
+    pub render_shadow_distance: f32,
+    pub render_shadow_split_lambda: f32,
+    /// Logical path of the sky (`.cubemap`, equirectangular `.png` or cubemap `.dds`). Empty
+    /// draws no sky.
+    pub render_skybox: String,
 
     pub ui_backend: UiBackend,
+    /// Initial locale for string tables (e.g. "en"). Switchable at runtime via `locale.set`.
+    pub ui_locale: String,
+
+    /// Logical path of the image shown while the game loads; `None` shows only the progress bar.
+    pub splash_image: Option<String>,
+    /// Logical paths loaded behind the splash before the game is shown.
+    pub splash_preload: Vec<String>,
+    /// Shortest time the splash stays up, in milliseconds.
+    pub splash_min_ms: u32,
+
+    /// Fixed tick rate (Hz) for the dedicated server profile.
+    pub server_tick_rate: u32,
 
     pub extra: HashMap<String, String>,
 
@@ -84,20 +131,46 @@ impl Default for StartupConfig {
             window_title: "NewEngine".to_owned(),
             window_size: (1600, 900),
             window_placement: WindowPlacement::Default,
+            window_unfocused_fps: 0,
 
             window_icon_path: None,
 
             modules_dir: PathBuf::from("./"),
+            disabled_plugins: Vec::new(),
 
             assets_root: PathBuf::from("assets"),
             asset_pump_steps: 8,
             asset_filesystem_source: true,
+            asset_path_case: PathCaseMode::Insensitive,
+            asset_cache_dir: Some(PathBuf::from("cache/assets")),
+            asset_engine_root: None,
+            asset_mods_root: None,
+            asset_packs: Vec::new(),
+            startup_scene: None,
+
+            service_max_payload_bytes: 4 * 1024 * 1024,
+            service_payload_limits: BTreeMap::new(),
+            service_max_calls_per_sec: 10_000,
+            service_limit_host: false,
 
             render_backend: "vulkan".to_owned(),
             render_clear_color: [0.02, 0.02, 0.03, 1.0],
             render_debug_text: "NewEngine".to_owned(),
+            render_pipeline_cache_dir: Some(PathBuf::from("cache/pipelines")),
+            render_shadow_cascades: 4,
+            render_shadow_map_size: 1024,
+            render_shadow_distance: 100.0,
+            render_shadow_split_lambda: 0.6,
+            render_skybox: String::new(),
 
             ui_backend: UiBackend::default(),
+            ui_locale: "en".to_owned(),
+
+            splash_image: None,
+            splash_preload: Vec::new(),
+            splash_min_ms: 0,
+
+            server_tick_rate: 30,
 
             extra: HashMap::new(),
 
@@ -106,11 +179,43 @@ impl Default for StartupConfig {
     }
 }
 
+/// Where a startup value came from; later sources win (file, then project, then env, then CLI).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StartupOverrideOrigin {
+    File,
+    /// Environment variable name, e.g. `NEWENGINE_WINDOW_SIZE`.
+    Env(String),
+    /// Command line argument, e.g. `--window.size=1920x1080`.
+    Arg(String),
+    /// Project file and profile, e.g. `demo.neproject profile=release`.
+    Project(String),
+}
+
+impl Default for StartupOverrideOrigin {
+    #[inline]
+    fn default() -> Self {
+        Self::File
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct StartupOverride {
     pub key: &'static str,
     pub from: String,
     pub to: String,
+    pub origin: StartupOverrideOrigin,
+}
+
+impl StartupOverride {
+    #[inline]
+    pub fn new(key: &'static str, from: String, to: String) -> Self {
+        Self {
+            key,
+            from,
+            to,
+            origin: StartupOverrideOrigin::File,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -136,6 +241,8 @@ pub struct StartupLoadReport {
     pub file: Option<PathBuf>,
     pub resolved_from: StartupResolvedFrom,
     pub overrides: Vec<StartupOverride>,
+    /// Skipped environment overrides; logging is usually not up yet while loading.
+    pub warnings: Vec<String>,
 }
 
 impl StartupLoadReport {
@@ -146,6 +253,7 @@ impl StartupLoadReport {
             file: None,
             resolved_from: StartupResolvedFrom::NotProvided,
             overrides: Vec::new(),
+            warnings: Vec::new(),
         }
     }
 }
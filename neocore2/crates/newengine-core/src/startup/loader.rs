@@ -1,19 +1,109 @@
 #![forbid(unsafe_op_in_unsafe_fn)]
 
 use crate::error::{EngineError, EngineResult};
+use crate::project::Project;
 use crate::startup::config::UiBackend;
 use crate::startup::{
     ConfigPaths, StartupConfig, StartupConfigSource, StartupLoadReport, StartupOverride,
-    StartupResolvedFrom, WindowPlacement,
+    StartupOverrideOrigin, StartupResolvedFrom, WindowPlacement,
 };
+use newengine_assets::PathCaseMode;
 use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 
 pub struct StartupLoader;
 
+/// Prefix of environment overrides: `NEWENGINE_<SECTION>_<KEY>`, e.g. `NEWENGINE_WINDOW_SIZE`.
+pub const STARTUP_ENV_PREFIX: &str = "NEWENGINE_";
+
+/// `<section>.<key>` names accepted by env/CLI overrides (same shape as the config file).
+const OVERRIDE_KEYS: &[&str] = &[
+    "logging.level",
+    "window.title",
+    "window.size",
+    "window.width",
+    "window.height",
+    "window.icon",
+    "window.unfocused_fps",
+    "engine.assets_root",
+    "engine.asset_pump_steps",
+    "engine.asset_filesystem_source",
+    "engine.asset_path_case",
+    "engine.asset_cache_dir",
+    "engine.asset_engine_root",
+    "engine.asset_mods_root",
+    "engine.asset_packs",
+    "engine.startup_scene",
+    "engine.modules_dir",
+    "engine.disabled_plugins",
+    "render.backend",
+    "render.clear_color",
+    "render.debug_text",
+    "render.pipeline_cache_dir",
+    "render.shadow_cascades",
+    "render.shadow_map_size",
+    "render.shadow_distance",
+    "render.shadow_split_lambda",
+    "render.skybox",
+    "ui.backend",
+    "ui.locale",
+    "splash.image",
+    "splash.preload",
+    "splash.min_ms",
+    "services.max_payload_bytes",
+    "services.max_calls_per_sec",
+    "services.payload_limits",
+    "services.limit_host",
+    "server.tick_rate",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StartupFormat {
+    Json,
+    Toml,
+    Yaml,
+}
+
+impl StartupFormat {
+    fn from_path(path: &Path) -> Self {
+        let ext = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+
+        match ext.as_str() {
+            "toml" => Self::Toml,
+            "yaml" | "yml" => Self::Yaml,
+            _ => Self::Json,
+        }
+    }
+
+    #[inline]
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Toml => "toml",
+            Self::Yaml => "yaml",
+        }
+    }
+
+    fn parse(self, data: &str) -> Result<RootJson, String> {
+        match self {
+            Self::Json => serde_json::from_str(data).map_err(|e| e.to_string()),
+            Self::Toml => toml::from_str(data).map_err(|e| e.to_string()),
+            Self::Yaml => serde_yaml::from_str(data).map_err(|e| e.to_string()),
+        }
+    }
+}
+
 impl StartupLoader {
-    pub fn load_json(paths: &ConfigPaths) -> EngineResult<(StartupConfig, StartupLoadReport)> {
+    /// Loads the startup file; the format follows the extension (`.toml`, `.yaml`/`.yml`,
+    /// anything else is JSON). A missing file yields defaults.
+    pub fn load(paths: &ConfigPaths) -> EngineResult<(StartupConfig, StartupLoadReport)> {
         let mut cfg = StartupConfig::default();
         let mut report = StartupLoadReport::new();
 
@@ -31,10 +121,13 @@ impl StartupLoader {
                     ))
                 })?;
 
-                let parsed: RootJson = serde_json::from_str(&data).map_err(|e| {
+                let format = StartupFormat::from_path(&resolved);
+                let parsed = format.parse(&data).map_err(|e| {
                     EngineError::Other(format!(
-                        "startup config parse failed (json): path={:?} err={}",
-                        resolved, e
+                        "startup config parse failed ({}): path={:?} err={}",
+                        format.as_str(),
+                        resolved,
+                        e
                     ))
                 })?;
 
@@ -55,6 +148,263 @@ impl StartupLoader {
 
         Ok((cfg, report))
     }
+
+    /// Kept for existing callers; detects the format like [`StartupLoader::load`].
+    #[inline]
+    pub fn load_json(paths: &ConfigPaths) -> EngineResult<(StartupConfig, StartupLoadReport)> {
+        Self::load(paths)
+    }
+
+    /// File, then `NEWENGINE_*` environment variables, then `--<section>.<key>=<value>` args.
+    pub fn load_with_overrides<I>(
+        paths: &ConfigPaths,
+        args: I,
+    ) -> EngineResult<(StartupConfig, StartupLoadReport)>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let (mut cfg, mut report) = Self::load(paths)?;
+        Self::apply_env_overrides(&mut cfg, &mut report, std::env::vars());
+        Self::apply_arg_overrides(&mut cfg, &mut report, args)?;
+        Ok((cfg, report))
+    }
+
+    /// Like [`StartupLoader::load_with_overrides`], with `project` applied between the file and
+    /// the environment. `profile` falls back to the project's default profile.
+    pub fn load_with_project<I>(
+        paths: &ConfigPaths,
+        project: Option<&Project>,
+        profile: Option<&str>,
+        args: I,
+    ) -> EngineResult<(StartupConfig, StartupLoadReport)>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let (mut cfg, mut report) = Self::load(paths)?;
+        if let Some(project) = project {
+            Self::apply_project(&mut cfg, &mut report, project, profile)?;
+        }
+        Self::apply_env_overrides(&mut cfg, &mut report, std::env::vars());
+        Self::apply_arg_overrides(&mut cfg, &mut report, args)?;
+        Ok((cfg, report))
+    }
+
+    /// Applies a project: its asset roots and the profile's plugin set, resolved against the
+    /// project root, then the profile's `settings`.
+    pub fn apply_project(
+        cfg: &mut StartupConfig,
+        report: &mut StartupLoadReport,
+        project: &Project,
+        profile: Option<&str>,
+    ) -> EngineResult<()> {
+        let file_name = project
+            .path()
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let profile = project
+            .profile(profile)
+            .map_err(|e| EngineError::Other(format!("startup: {e}")))?;
+        let origin = StartupOverrideOrigin::Project(match profile {
+            Some((name, _)) => format!("{file_name} profile={name}"),
+            None => file_name,
+        });
+        let profile = profile.map(|(_, p)| p);
+
+        // Empty values keep their meaning (unmount, no cache) instead of becoming the root.
+        let path = |p: &str| match p.trim() {
+            "" => String::new(),
+            p => project.resolve(p).to_string_lossy().into_owned(),
+        };
+        let assets = &project.file().assets;
+        let mut values: Vec<(&str, String)> = [
+            ("engine.assets_root", &assets.game),
+            ("engine.asset_engine_root", &assets.engine),
+            ("engine.asset_mods_root", &assets.mods),
+            ("engine.asset_cache_dir", &assets.cache),
+        ]
+        .into_iter()
+        .filter_map(|(key, p)| Some((key, path(p.as_deref()?))))
+        .collect();
+
+        if let Some(set) = project.plugin_set(profile) {
+            if let Some(dir) = &set.dir {
+                values.push(("engine.modules_dir", path(dir)));
+            }
+            values.push(("engine.disabled_plugins", set.disabled.join(",")));
+        }
+        if let Some(scene) = project.startup_scene(profile) {
+            values.push(("engine.startup_scene", scene.to_owned()));
+        }
+
+        for (key, raw) in values {
+            apply_string_override(cfg, report, key, raw, origin.clone())
+                .map_err(|e| EngineError::Other(format!("startup: {}: {e}", project.name())))?;
+        }
+
+        for (key, value) in profile.map(|p| &p.settings).into_iter().flatten() {
+            let raw = setting_text(value);
+            apply_override(cfg, report, key, &raw, origin.clone()).map_err(|e| {
+                EngineError::Other(format!("startup: {}: setting '{key}': {e}", project.name()))
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Config file value of one `<section>.<key>` override (`1280x720` -> `[1280, 720]`), read
+    /// the way env/CLI overrides are; for writing a generated config file.
+    pub fn override_value(path: &str, raw: &str) -> Result<Value, String> {
+        if !OVERRIDE_KEYS.contains(&path) {
+            return Err(format!("unknown startup key '{path}'"));
+        }
+        let raw = raw.trim();
+        [parse_override_value(raw), Value::String(raw.to_owned())]
+            .into_iter()
+            .find(|v| override_root(path, v.clone()).is_some())
+            .ok_or_else(|| format!("invalid value '{raw}' for '{path}'"))
+    }
+
+    /// Applies `NEWENGINE_<SECTION>_<KEY>=<value>` pairs, e.g. `NEWENGINE_WINDOW_SIZE=1920x1080`.
+    ///
+    /// Unknown or invalid variables are skipped and recorded in `report.warnings`: the
+    /// environment is shared with other tools.
+    pub fn apply_env_overrides<I>(
+        cfg: &mut StartupConfig,
+        report: &mut StartupLoadReport,
+        vars: I,
+    ) where
+        I: IntoIterator<Item = (String, String)>,
+    {
+        for (var, raw) in vars {
+            let Some(rest) = var.strip_prefix(STARTUP_ENV_PREFIX) else {
+                continue;
+            };
+            let Some((section, key)) = rest.split_once('_') else {
+                report.warnings.push(format!(
+                    "ignored env override '{var}' (expected {STARTUP_ENV_PREFIX}<SECTION>_<KEY>)"
+                ));
+                continue;
+            };
+
+            let path = format!("{}.{}", section.to_ascii_lowercase(), key.to_ascii_lowercase());
+            let origin = StartupOverrideOrigin::Env(var.clone());
+            if let Err(e) = apply_override(cfg, report, &path, &raw, origin) {
+                report
+                    .warnings
+                    .push(format!("ignored env override '{var}': {e}"));
+            }
+        }
+    }
+
+    /// Applies `--<section>.<key>=<value>` arguments, e.g. `--window.size=1920x1080`.
+    ///
+    /// Other arguments are left to the caller; a malformed override is an error.
+    pub fn apply_arg_overrides<I>(
+        cfg: &mut StartupConfig,
+        report: &mut StartupLoadReport,
+        args: I,
+    ) -> EngineResult<()>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        for arg in args {
+            let Some((path, raw)) = arg.strip_prefix("--").and_then(|a| a.split_once('=')) else {
+                continue;
+            };
+            if !path.contains('.') {
+                continue;
+            }
+
+            let origin = StartupOverrideOrigin::Arg(arg.clone());
+            apply_override(cfg, report, path, raw, origin)
+                .map_err(|e| EngineError::Other(format!("startup: argument '{arg}': {e}")))?;
+        }
+        Ok(())
+    }
+}
+
+/// Applies one `<section>.<key>` override through the same path as the config file.
+///
+/// The value is tried as structured first (`1920x1080`, `1,0,0,1`, numbers, bools), then as
+/// a plain string, so `window.title=1984` still works.
+fn apply_override(
+    cfg: &mut StartupConfig,
+    report: &mut StartupLoadReport,
+    path: &str,
+    raw: &str,
+    origin: StartupOverrideOrigin,
+) -> Result<(), String> {
+    let value = StartupLoader::override_value(path, raw)?;
+    let root = override_root(path, value).ok_or_else(|| format!("invalid value for '{path}'"))?;
+    apply_root_from(cfg, report, root, origin);
+    Ok(())
+}
+
+/// A profile setting as override text, like on the command line: `[1280, 720]` -> `1280,720`.
+pub(crate) fn setting_text(value: &Value) -> String {
+    let text = |v: &Value| v.as_str().map_or_else(|| v.to_string(), str::to_owned);
+    match value {
+        Value::Array(items) => items.iter().map(text).collect::<Vec<_>>().join(","),
+        v => text(v),
+    }
+}
+
+/// Path and list values of a project; applied as strings so a path is never read as a size or
+/// a number.
+fn apply_string_override(
+    cfg: &mut StartupConfig,
+    report: &mut StartupLoadReport,
+    path: &str,
+    raw: String,
+    origin: StartupOverrideOrigin,
+) -> Result<(), String> {
+    let root = override_root(path, Value::String(raw))
+        .ok_or_else(|| format!("invalid value for '{path}'"))?;
+    apply_root_from(cfg, report, root, origin);
+    Ok(())
+}
+
+/// The config file shape holding just `<section>.<key>`.
+fn override_root(path: &str, value: Value) -> Option<RootJson> {
+    let (section, key) = path.split_once('.').unwrap_or((path, ""));
+    let mut table = Map::new();
+    table.insert(key.to_owned(), value);
+    let mut root = Map::new();
+    root.insert(section.to_owned(), Value::Object(table));
+    serde_json::from_value(Value::Object(root)).ok()
+}
+
+fn apply_root_from(
+    cfg: &mut StartupConfig,
+    report: &mut StartupLoadReport,
+    root: RootJson,
+    origin: StartupOverrideOrigin,
+) {
+    let first = report.overrides.len();
+    apply_root(cfg, report, root);
+    for ov in report.overrides[first..].iter_mut() {
+        ov.origin = origin.clone();
+    }
+}
+
+fn parse_override_value(raw: &str) -> Value {
+    if let Some((w, h)) = raw.split_once(['x', 'X']) {
+        if let (Ok(w), Ok(h)) = (w.trim().parse::<u32>(), h.trim().parse::<u32>()) {
+            return Value::Array(vec![w.into(), h.into()]);
+        }
+    }
+
+    if raw.contains(',') {
+        let parts: Option<Vec<Value>> = raw
+            .split(',')
+            .map(|p| serde_json::from_str::<Value>(p.trim()).ok().filter(Value::is_number))
+            .collect();
+        if let Some(parts) = parts {
+            return Value::Array(parts);
+        }
+    }
+
+    serde_json::from_str::<Value>(raw).unwrap_or_else(|_| Value::String(raw.to_owned()))
 }
 
 #[derive(Deserialize)]
@@ -64,6 +414,9 @@ struct RootJson {
     engine: Option<EngineJson>,
     render: Option<RenderJson>,
     ui: Option<UiJson>,
+    splash: Option<SplashJson>,
+    services: Option<ServicesJson>,
+    server: Option<ServerJson>,
 }
 
 #[derive(Deserialize)]
@@ -87,6 +440,8 @@ struct WindowJson {
 
     /// Logical path inside assets, e.g. "ui/icon.png"
     icon: Option<String>,
+
+    unfocused_fps: Option<u32>,
 }
 
 #[derive(Deserialize)]
@@ -101,7 +456,39 @@ struct EngineJson {
     assets_root: Option<String>,
     asset_pump_steps: Option<u32>,
     asset_filesystem_source: Option<bool>,
+    /// "insensitive" or "sensitive".
+    asset_path_case: Option<String>,
+    /// Empty string disables the cache.
+    asset_cache_dir: Option<String>,
+    /// Empty string unmounts the root.
+    asset_engine_root: Option<String>,
+    asset_mods_root: Option<String>,
+    asset_packs: Option<StringListJson>,
+    startup_scene: Option<String>,
     modules_dir: Option<String>,
+    disabled_plugins: Option<StringListJson>,
+}
+
+/// A list, or one comma-separated string (the shape of env/CLI overrides).
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StringListJson {
+    List(Vec<String>),
+    Csv(String),
+}
+
+impl StringListJson {
+    fn into_vec(self) -> Vec<String> {
+        let items = match self {
+            Self::List(v) => v,
+            Self::Csv(s) => s.split(',').map(str::to_owned).collect(),
+        };
+        items
+            .into_iter()
+            .map(|s| s.trim().to_owned())
+            .filter(|s| !s.is_empty())
+            .collect()
+    }
 }
 
 #[derive(Deserialize)]
@@ -109,11 +496,40 @@ struct RenderJson {
     backend: Option<String>,
     clear_color: Option<[f32; 4]>,
     debug_text: Option<String>,
+    /// Empty string disables the persistent cache.
+    pipeline_cache_dir: Option<String>,
+    shadow_cascades: Option<u32>,
+    shadow_map_size: Option<u32>,
+    shadow_distance: Option<f32>,
+    shadow_split_lambda: Option<f32>,
+    skybox: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct SplashJson {
+    image: Option<String>,
+    preload: Option<StringListJson>,
+    min_ms: Option<u32>,
+}
+
+#[derive(Deserialize)]
+struct ServicesJson {
+    max_payload_bytes: Option<u32>,
+    /// Service id -> bytes; merged into the current caps; 0 lifts the cap for that service.
+    payload_limits: Option<BTreeMap<String, u32>>,
+    max_calls_per_sec: Option<u32>,
+    limit_host: Option<bool>,
+}
+
+#[derive(Deserialize)]
+struct ServerJson {
+    tick_rate: Option<u32>,
 }
 
 #[derive(Deserialize)]
 struct UiJson {
     backend: Option<String>,
+    locale: Option<String>,
 }
 
 fn apply_root(cfg: &mut StartupConfig, report: &mut StartupLoadReport, src: RootJson) {
@@ -135,11 +551,11 @@ fn apply_root(cfg: &mut StartupConfig, report: &mut StartupLoadReport, src: Root
                 (Some(ww), Some(hh)) => {
                     apply_size(report, "window_size", &mut cfg.window_size, (ww, hh));
                 }
-                (Some(_), None) | (None, Some(_)) => report.overrides.push(StartupOverride {
-                    key: "window_size",
-                    from: format_size(cfg.window_size),
-                    to: "ignored (width/height must both be present)".to_owned(),
-                }),
+                (Some(_), None) | (None, Some(_)) => report.overrides.push(StartupOverride::new(
+                    "window_size",
+                    format_size(cfg.window_size),
+                    "ignored (width/height must both be present)".to_owned(),
+                )),
                 (None, None) => {}
             }
         }
@@ -153,6 +569,10 @@ fn apply_root(cfg: &mut StartupConfig, report: &mut StartupLoadReport, src: Root
         if let Some(icon) = w.icon {
             apply_opt_string(report, "window_icon", &mut cfg.window_icon_path, icon);
         }
+
+        if let Some(fps) = w.unfocused_fps {
+            apply_u32(report, "window_unfocused_fps", &mut cfg.window_unfocused_fps, fps);
+        }
     }
 
     if let Some(engine) = src.engine {
@@ -170,9 +590,35 @@ fn apply_root(cfg: &mut StartupConfig, report: &mut StartupLoadReport, src: Root
                 enabled,
             );
         }
+        if let Some(mode) = engine.asset_path_case {
+            if let Some(mode) = parse_path_case(&mode) {
+                apply_path_case(report, "asset_path_case", &mut cfg.asset_path_case, mode);
+            }
+        }
+        if let Some(dir) = engine.asset_cache_dir {
+            apply_opt_path(report, "asset_cache_dir", &mut cfg.asset_cache_dir, dir);
+        }
+        if let Some(dir) = engine.asset_engine_root {
+            apply_opt_path(report, "asset_engine_root", &mut cfg.asset_engine_root, dir);
+        }
+        if let Some(dir) = engine.asset_mods_root {
+            apply_opt_path(report, "asset_mods_root", &mut cfg.asset_mods_root, dir);
+        }
+        if let Some(packs) = engine.asset_packs {
+            let mut cur: Vec<String> =
+                cfg.asset_packs.iter().map(|p| p.display().to_string()).collect();
+            apply_list(report, "asset_packs", &mut cur, packs.into_vec());
+            cfg.asset_packs = cur.into_iter().map(PathBuf::from).collect();
+        }
+        if let Some(scene) = engine.startup_scene {
+            apply_opt_string(report, "startup_scene", &mut cfg.startup_scene, scene);
+        }
         if let Some(dir) = engine.modules_dir {
             apply_path(report, "modules_dir", &mut cfg.modules_dir, dir);
         }
+        if let Some(ids) = engine.disabled_plugins {
+            apply_list(report, "disabled_plugins", &mut cfg.disabled_plugins, ids.into_vec());
+        }
     }
 
     if let Some(render) = src.render {
@@ -185,6 +631,34 @@ fn apply_root(cfg: &mut StartupConfig, report: &mut StartupLoadReport, src: Root
         if let Some(text) = render.debug_text {
             apply_string(report, "render_debug_text", &mut cfg.render_debug_text, text);
         }
+        if let Some(dir) = render.pipeline_cache_dir {
+            apply_opt_path(
+                report,
+                "render_pipeline_cache_dir",
+                &mut cfg.render_pipeline_cache_dir,
+                dir,
+            );
+        }
+        if let Some(n) = render.shadow_cascades {
+            apply_u32(report, "render_shadow_cascades", &mut cfg.render_shadow_cascades, n);
+        }
+        if let Some(size) = render.shadow_map_size {
+            apply_u32(report, "render_shadow_map_size", &mut cfg.render_shadow_map_size, size);
+        }
+        if let Some(d) = render.shadow_distance {
+            apply_f32(report, "render_shadow_distance", &mut cfg.render_shadow_distance, d);
+        }
+        if let Some(l) = render.shadow_split_lambda {
+            apply_f32(
+                report,
+                "render_shadow_split_lambda",
+                &mut cfg.render_shadow_split_lambda,
+                l,
+            );
+        }
+        if let Some(path) = render.skybox {
+            apply_string(report, "render_skybox", &mut cfg.render_skybox, path);
+        }
     }
 
     if let Some(ui) = src.ui {
@@ -192,6 +666,57 @@ fn apply_root(cfg: &mut StartupConfig, report: &mut StartupLoadReport, src: Root
             let parsed = parse_ui_backend(&backend);
             apply_ui_backend(report, "ui_backend", &mut cfg.ui_backend, parsed);
         }
+        if let Some(locale) = ui.locale {
+            apply_string(report, "ui_locale", &mut cfg.ui_locale, locale);
+        }
+    }
+
+    if let Some(splash) = src.splash {
+        if let Some(image) = splash.image {
+            apply_opt_string(report, "splash_image", &mut cfg.splash_image, image);
+        }
+        if let Some(paths) = splash.preload {
+            apply_list(report, "splash_preload", &mut cfg.splash_preload, paths.into_vec());
+        }
+        if let Some(ms) = splash.min_ms {
+            apply_u32(report, "splash_min_ms", &mut cfg.splash_min_ms, ms);
+        }
+    }
+
+    if let Some(services) = src.services {
+        if let Some(bytes) = services.max_payload_bytes {
+            apply_u32(
+                report,
+                "service_max_payload_bytes",
+                &mut cfg.service_max_payload_bytes,
+                bytes,
+            );
+        }
+        if let Some(calls) = services.max_calls_per_sec {
+            apply_u32(
+                report,
+                "service_max_calls_per_sec",
+                &mut cfg.service_max_calls_per_sec,
+                calls,
+            );
+        }
+        if let Some(caps) = services.payload_limits {
+            let from = format!("{:?}", cfg.service_payload_limits);
+            cfg.service_payload_limits.extend(caps);
+            let to = format!("{:?}", cfg.service_payload_limits);
+            if from != to {
+                report.overrides.push(StartupOverride::new("service_payload_limits", from, to));
+            }
+        }
+        if let Some(enabled) = services.limit_host {
+            apply_bool(report, "service_limit_host", &mut cfg.service_limit_host, enabled);
+        }
+    }
+
+    if let Some(server) = src.server {
+        if let Some(hz) = server.tick_rate {
+            apply_u32(report, "server_tick_rate", &mut cfg.server_tick_rate, hz.clamp(1, 1000));
+        }
     }
 }
 
@@ -213,6 +738,14 @@ fn parse_placement(p: WindowPlacementJson) -> Option<WindowPlacement> {
     }
 }
 
+fn parse_path_case(s: &str) -> Option<PathCaseMode> {
+    match s.trim().to_ascii_lowercase().as_str() {
+        "insensitive" => Some(PathCaseMode::Insensitive),
+        "sensitive" => Some(PathCaseMode::Sensitive),
+        _ => None,
+    }
+}
+
 fn parse_ui_backend(s: &str) -> UiBackend {
     let v = s.trim().to_ascii_lowercase();
     match v.as_str() {
@@ -227,11 +760,7 @@ fn apply_string(report: &mut StartupLoadReport, key: &'static str, dst: &mut Str
     let from = dst.clone();
     if from != v {
         *dst = v.clone();
-        report.overrides.push(StartupOverride {
-            key,
-            from,
-            to: v,
-        });
+        report.overrides.push(StartupOverride::new(key, from, v));
     }
 }
 
@@ -252,7 +781,7 @@ fn apply_opt_string(
 
     if changed {
         *dst = Some(v);
-        report.overrides.push(StartupOverride { key, from, to });
+        report.overrides.push(StartupOverride::new(key, from, to));
     }
 }
 
@@ -262,7 +791,17 @@ fn apply_u32(report: &mut StartupLoadReport, key: &'static str, dst: &mut u32, v
     let to = v.to_string();
     if *dst != v {
         *dst = v;
-        report.overrides.push(StartupOverride { key, from, to });
+        report.overrides.push(StartupOverride::new(key, from, to));
+    }
+}
+
+#[inline]
+fn apply_f32(report: &mut StartupLoadReport, key: &'static str, dst: &mut f32, v: f32) {
+    let from = format!("{:.3}", *dst);
+    let to = format!("{v:.3}");
+    if *dst != v {
+        *dst = v;
+        report.overrides.push(StartupOverride::new(key, from, to));
     }
 }
 
@@ -272,7 +811,7 @@ fn apply_bool(report: &mut StartupLoadReport, key: &'static str, dst: &mut bool,
     let to = v.to_string();
     if *dst != v {
         *dst = v;
-        report.overrides.push(StartupOverride { key, from, to });
+        report.overrides.push(StartupOverride::new(key, from, to));
     }
 }
 
@@ -287,7 +826,7 @@ fn apply_size(
     let to = format_size(v);
     if *dst != v {
         *dst = v;
-        report.overrides.push(StartupOverride { key, from, to });
+        report.overrides.push(StartupOverride::new(key, from, to));
     }
 }
 
@@ -302,7 +841,22 @@ fn apply_placement(
     let to = format!("{:?}", v);
     if *dst != v {
         *dst = v;
-        report.overrides.push(StartupOverride { key, from, to });
+        report.overrides.push(StartupOverride::new(key, from, to));
+    }
+}
+
+#[inline]
+fn apply_path_case(
+    report: &mut StartupLoadReport,
+    key: &'static str,
+    dst: &mut PathCaseMode,
+    v: PathCaseMode,
+) {
+    let from = format!("{:?}", dst);
+    let to = format!("{:?}", v);
+    if *dst != v {
+        *dst = v;
+        report.overrides.push(StartupOverride::new(key, from, to));
     }
 }
 
@@ -312,7 +866,7 @@ fn apply_ui_backend(report: &mut StartupLoadReport, key: &'static str, dst: &mut
     let to = format!("{:?}", v);
     if *dst != v {
         *dst = v;
-        report.overrides.push(StartupOverride { key, from, to });
+        report.overrides.push(StartupOverride::new(key, from, to));
     }
 }
 
@@ -323,7 +877,44 @@ fn apply_path(report: &mut StartupLoadReport, key: &'static str, dst: &mut PathB
     let to = pb.display().to_string();
     if *dst != pb {
         *dst = pb;
-        report.overrides.push(StartupOverride { key, from, to });
+        report.overrides.push(StartupOverride::new(key, from, to));
+    }
+}
+
+/// Like [`apply_path`]; an empty value clears the option.
+#[inline]
+fn apply_opt_path(
+    report: &mut StartupLoadReport,
+    key: &'static str,
+    dst: &mut Option<PathBuf>,
+    v: String,
+) {
+    let fmt = |p: &Option<PathBuf>| {
+        p.as_ref()
+            .map(|p| p.display().to_string())
+            .unwrap_or_else(|| "null".to_owned())
+    };
+    let pb = (!v.trim().is_empty()).then(|| PathBuf::from(v));
+    if *dst != pb {
+        let from = fmt(dst);
+        let to = fmt(&pb);
+        *dst = pb;
+        report.overrides.push(StartupOverride::new(key, from, to));
+    }
+}
+
+#[inline]
+fn apply_list(
+    report: &mut StartupLoadReport,
+    key: &'static str,
+    dst: &mut Vec<String>,
+    v: Vec<String>,
+) {
+    if *dst != v {
+        let from = dst.join(",");
+        let to = v.join(",");
+        *dst = v;
+        report.overrides.push(StartupOverride::new(key, from, to));
     }
 }
 
@@ -338,7 +929,7 @@ fn apply_color(
     let to = format!("{:.3},{:.3},{:.3},{:.3}", v[0], v[1], v[2], v[3]);
     if *dst != v {
         *dst = v;
-        report.overrides.push(StartupOverride { key, from, to });
+        report.overrides.push(StartupOverride::new(key, from, to));
     }
 }
 
@@ -3,7 +3,8 @@ mod loader;
 
 pub use config::{
     ConfigPaths, StartupConfig, StartupConfigSource, StartupLoadReport, StartupOverride,
-    StartupResolvedFrom, UiBackend, WindowPlacement,
+    StartupOverrideOrigin, StartupResolvedFrom, UiBackend, WindowPlacement,
 };
 
-pub use loader::StartupLoader;
+pub use loader::{StartupLoader, STARTUP_ENV_PREFIX};
+pub(crate) use loader::setting_text;
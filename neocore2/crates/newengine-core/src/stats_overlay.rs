@@ -0,0 +1,272 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use crate::error::{EngineError, EngineResult};
+use crate::module::{Module, ModuleCtx};
+use crate::render::{RenderApiRef, RENDER_API_ID};
+use crate::stats_service::{StatsService, STATS_SERVICE_ID};
+
+use newengine_plugin_api::{guarded_service, ServiceV1Dyn};
+use newengine_ui::draw::UiDrawList;
+use newengine_ui::{ui_color, UiPainter};
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Instant;
+
+/// Frame times kept for the averages and the graph (one graph column each).
+const HISTORY: usize = 240;
+/// Frame time mapped to the top of the graph.
+const GRAPH_MAX_MS: f32 = 50.0;
+const GRAPH_HEIGHT: f32 = 64.0;
+const PAD: f32 = 6.0;
+
+/// Process-wide so the `stats.toggle` command and hotkeys work without a module handle.
+static VISIBLE: AtomicBool = AtomicBool::new(false);
+static LAST: Mutex<Option<StatsSnapshot>> = Mutex::new(None);
+
+/// Numbers shown by the overlay; also returned by `stats.snapshot_json`.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct StatsSnapshot {
+    pub fps: f32,
+    pub frame_ms: f32,
+    pub frame_ms_max: f32,
+    pub asset_pump_ms: f32,
+    pub asset_pump_steps: u32,
+    pub asset_queue: usize,
+    pub assets_ready: usize,
+    pub asset_bytes: u64,
+    /// `(pass, ms)` as reported by the render backend.
+    pub gpu_ms: Vec<(&'static str, f32)>,
+}
+
+#[inline]
+pub fn stats_overlay_visible() -> bool {
+    VISIBLE.load(Ordering::Relaxed)
+}
+
+#[inline]
+pub fn set_stats_overlay_visible(visible: bool) {
+    VISIBLE.store(visible, Ordering::Relaxed);
+}
+
+/// Flips visibility and returns the new state.
+#[inline]
+pub fn toggle_stats_overlay() -> bool {
+    !VISIBLE.fetch_xor(true, Ordering::Relaxed)
+}
+
+/// Most recent snapshot taken by a running [`StatsOverlayModule`].
+pub fn last_stats_snapshot() -> Option<StatsSnapshot> {
+    LAST.lock().ok()?.clone()
+}
+
+/// FPS, frame time graph, asset pump and GPU pass timings drawn over the frame.
+///
+/// Paints into the frame's [`UiDrawList`] with [`UiPainter`], so it shows up with any UI
+/// provider and render backend; without a UI the module adds a list of its own. Runs in
+/// `update`, before the render stage hands the list to the backend. Hidden by default; toggled
+/// with `stats.toggle` (bound to F3 unless the user config rebinds it).
+pub struct StatsOverlayModule {
+    frame_ms: VecDeque<f32>,
+    last_update: Option<Instant>,
+    font_uploaded: bool,
+    service_registered: bool,
+}
+
+impl StatsOverlayModule {
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            frame_ms: VecDeque::with_capacity(HISTORY),
+            last_update: None,
+            font_uploaded: false,
+            service_registered: false,
+        }
+    }
+
+    #[inline]
+    pub fn with_visible(self, visible: bool) -> Self {
+        set_stats_overlay_visible(visible);
+        self
+    }
+
+    fn sample(&mut self) {
+        let now = Instant::now();
+        if let Some(prev) = self.last_update.replace(now) {
+            if self.frame_ms.len() == HISTORY {
+                self.frame_ms.pop_front();
+            }
+            self.frame_ms.push_back((now - prev).as_secs_f32() * 1000.0);
+        }
+    }
+
+    fn snapshot<E: Send + 'static>(&self, ctx: &ModuleCtx<'_, E>) -> StatsSnapshot {
+        let n = self.frame_ms.len().max(1) as f32;
+        let avg = self.frame_ms.iter().sum::<f32>() / n;
+
+        let mut s = StatsSnapshot {
+            fps: if avg > 0.0 { 1000.0 / avg } else { 0.0 },
+            frame_ms: avg,
+            frame_ms_max: self.frame_ms.iter().copied().fold(0.0, f32::max),
+            ..StatsSnapshot::default()
+        };
+
+        if let Some(am) = ctx.resources().get::<crate::assets::AssetManager>() {
+            let st = am.store().stats_snapshot();
+            s.asset_pump_ms = am.last_pump_time().as_secs_f32() * 1000.0;
+            s.asset_pump_steps = am.budget_steps();
+            s.asset_queue = st.queue_len;
+            s.assets_ready = st.blobs_ready;
+            s.asset_bytes = st.blobs_bytes;
+        }
+
+        if let Some(api) = ctx.api::<RenderApiRef>(RENDER_API_ID) {
+            s.gpu_ms = api
+                .lock()
+                .gpu_pass_timings()
+                .into_iter()
+                .map(|t| (t.name, t.ms))
+                .collect();
+        }
+
+        s
+    }
+
+    fn paint(&self, list: &mut UiDrawList, s: &StatsSnapshot) {
+        let scale = list.pixels_per_point.round().max(1.0);
+        let pad = PAD * scale;
+        let line_h = 10.0 * scale;
+
+        let mut lines = vec![
+            format!(
+                "FPS {:>5.1}  {:>6.2} ms  max {:>6.2} ms",
+                s.fps, s.frame_ms, s.frame_ms_max
+            ),
+            format!(
+                "assets pump {:.2} ms/{} steps  queue {}  ready {} ({:.1} MiB)",
+                s.asset_pump_ms,
+                s.asset_pump_steps,
+                s.asset_queue,
+                s.assets_ready,
+                s.asset_bytes as f64 / (1024.0 * 1024.0)
+            ),
+        ];
+        if s.gpu_ms.is_empty() {
+            lines.push("gpu   n/a".to_string());
+        } else {
+            let total: f32 = s.gpu_ms.iter().map(|(_, ms)| ms).sum();
+            let passes = s
+                .gpu_ms
+                .iter()
+                .map(|(name, ms)| format!("{name} {ms:.2}"))
+                .collect::<Vec<_>>()
+                .join("  ");
+            lines.push(format!("gpu   {total:.2} ms  ({passes})"));
+        }
+
+        let text_w = lines
+            .iter()
+            .map(|l| UiPainter::text_size(l, scale)[0])
+            .fold(0.0, f32::max);
+        let graph_w = HISTORY as f32 * scale;
+        let graph_h = GRAPH_HEIGHT * scale;
+
+        let x0 = pad;
+        let y0 = pad;
+        let w = text_w.max(graph_w) + pad * 2.0;
+        let h = lines.len() as f32 * line_h + graph_h + pad * 3.0;
+
+        let mut p = UiPainter::new(list);
+        p.rect_filled([x0, y0], [x0 + w, y0 + h], ui_color(12, 14, 18, 200));
+
+        let white = ui_color(235, 235, 235, 255);
+        for (i, l) in lines.iter().enumerate() {
+            p.text([x0 + pad, y0 + pad + i as f32 * line_h], l, scale, white);
+        }
+
+        let gx = x0 + pad;
+        let gy = y0 + pad * 2.0 + lines.len() as f32 * line_h;
+        p.rect_filled(
+            [gx, gy],
+            [gx + graph_w, gy + graph_h],
+            ui_color(0, 0, 0, 140),
+        );
+
+        let y_of = |ms: f32| gy + graph_h - (ms / GRAPH_MAX_MS).clamp(0.0, 1.0) * graph_h;
+        for (ms, color) in [
+            (1000.0 / 60.0, ui_color(90, 200, 120, 160)),
+            (1000.0 / 30.0, ui_color(230, 170, 60, 160)),
+        ] {
+            let y = y_of(ms);
+            p.line([gx, y], [gx + graph_w, y], scale, color);
+        }
+
+        let start = HISTORY - self.frame_ms.len();
+        let points: Vec<[f32; 2]> = self
+            .frame_ms
+            .iter()
+            .enumerate()
+            .map(|(i, &ms)| [gx + (start + i) as f32 * scale, y_of(ms)])
+            .collect();
+        p.polyline(&points, scale, ui_color(120, 190, 255, 255));
+    }
+}
+
+impl Default for StatsOverlayModule {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E: Send + 'static> Module<E> for StatsOverlayModule {
+    fn id(&self) -> &'static str {
+        "stats-overlay"
+    }
+
+    fn init(&mut self, _ctx: &mut ModuleCtx<'_, E>) -> EngineResult<()> {
+        let dyn_svc: ServiceV1Dyn<'static> = guarded_service(StatsService);
+        crate::register_service_v1(dyn_svc).map_err(EngineError::other)?;
+        self.service_registered = true;
+        Ok(())
+    }
+
+    fn update(&mut self, ctx: &mut ModuleCtx<'_, E>) -> EngineResult<()> {
+        self.sample();
+        let snap = self.snapshot(ctx);
+
+        if stats_overlay_visible() {
+            if ctx.resources().get::<UiDrawList>().is_none() {
+                // Zero size: the backend maps it onto the whole target.
+                ctx.resources_mut().insert(UiDrawList::new());
+            }
+            if let Some(list) = ctx.resources_mut().get_mut::<UiDrawList>() {
+                if !self.font_uploaded {
+                    UiPainter::upload_font(list);
+                    self.font_uploaded = true;
+                }
+                self.paint(list, &snap);
+            }
+        } else {
+            // Uploaded again when shown; the backend may have been recreated meanwhile.
+            self.font_uploaded = false;
+        }
+
+        if let Ok(mut g) = LAST.lock() {
+            *g = Some(snap);
+        }
+        Ok(())
+    }
+
+    fn shutdown(&mut self, _ctx: &mut ModuleCtx<'_, E>) -> EngineResult<()> {
+        if self.service_registered {
+            crate::unregister_service_v1(STATS_SERVICE_ID);
+            self.service_registered = false;
+        }
+        if let Ok(mut g) = LAST.lock() {
+            *g = None;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,116 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use crate::stats_overlay::{
+    last_stats_snapshot, set_stats_overlay_visible, stats_overlay_visible, toggle_stats_overlay,
+    StatsSnapshot,
+};
+use abi_stable::std_types::{RResult, RString};
+use newengine_plugin_api::{Blob, CapabilityId, MethodName, ServiceV1};
+use serde::Serialize;
+use serde_json::json;
+
+pub const STATS_SERVICE_ID: &str = "engine.stats";
+
+pub mod method {
+    pub const TOGGLE: &str = "stats.toggle";
+    pub const SNAPSHOT_JSON: &str = "stats.snapshot_json";
+}
+
+#[derive(Debug, Serialize)]
+struct StatsToggleResp {
+    ok: bool,
+    visible: bool,
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct StatsSnapshotResp {
+    visible: bool,
+    stats: Option<StatsSnapshot>,
+}
+
+pub(crate) struct StatsService;
+
+impl StatsService {
+    /// Payload: empty flips the overlay, `on` / `off` sets it.
+    fn toggle(arg: &str) -> StatsToggleResp {
+        let visible = match arg.trim().to_ascii_lowercase().as_str() {
+            "" => Ok(toggle_stats_overlay()),
+            "on" | "1" | "true" => Ok(true),
+            "off" | "0" | "false" => Ok(false),
+            other => Err(format!("expected on|off, got '{other}'")),
+        };
+
+        match visible {
+            Ok(v) => {
+                set_stats_overlay_visible(v);
+                StatsToggleResp {
+                    ok: true,
+                    visible: v,
+                    error: None,
+                }
+            }
+            Err(e) => StatsToggleResp {
+                ok: false,
+                visible: stats_overlay_visible(),
+                error: Some(e),
+            },
+        }
+    }
+}
+
+impl ServiceV1 for StatsService {
+    fn id(&self) -> CapabilityId {
+        RString::from(STATS_SERVICE_ID)
+    }
+
+    fn describe(&self) -> RString {
+        let d = json!({
+          "id": STATS_SERVICE_ID,
+          "version": 1,
+          "methods": [
+            { "name": method::TOGGLE, "payload": "utf8 '[on|off]'", "returns": "json StatsToggleResp" },
+            { "name": method::SNAPSHOT_JSON, "payload": "empty", "returns": "json StatsSnapshotResp" }
+          ],
+          "console": {
+            "commands": [
+              {
+                "name": "stats.toggle",
+                "help": "Show or hide the frame stats overlay: stats.toggle [on|off]",
+                "usage": "stats.toggle [on|off]",
+                "kind": "service_call",
+                "service_id": STATS_SERVICE_ID,
+                "method": method::TOGGLE,
+                "payload": "raw"
+              },
+              {
+                "name": "stats",
+                "help": "Print FPS, frame time, asset pump and GPU pass timings",
+                "kind": "service_call",
+                "service_id": STATS_SERVICE_ID,
+                "method": method::SNAPSHOT_JSON,
+                "payload": "empty"
+              }
+            ]
+          }
+        });
+
+        RString::from(d.to_string())
+    }
+
+    fn call(&self, method: MethodName, payload: Blob) -> RResult<Blob, RString> {
+        let m = method.to_string();
+        let arg = String::from_utf8_lossy(payload.as_slice());
+
+        let resp = match m.as_str() {
+            method::TOGGLE => serde_json::to_vec(&Self::toggle(&arg)),
+            method::SNAPSHOT_JSON => serde_json::to_vec(&StatsSnapshotResp {
+                visible: stats_overlay_visible(),
+                stats: last_stats_snapshot(),
+            }),
+            _ => return RResult::RErr(RString::from(format!("unknown method: {m}"))),
+        };
+
+        RResult::ROk(Blob::from(resp.unwrap_or_default()))
+    }
+}
@@ -0,0 +1,280 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use serde::Serialize;
+use std::borrow::Cow;
+use std::cell::Cell;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::Instant;
+
+pub const DEFAULT_CAPTURE_FRAMES: u32 = 120;
+
+/// Upper bound on recorded scopes per capture; later scopes are dropped.
+const MAX_CAPTURE_EVENTS: usize = 1_000_000;
+
+#[derive(Debug, Clone, Serialize)]
+struct TraceEvent {
+    name: Cow<'static, str>,
+    cat: &'static str,
+    ph: &'static str,
+    /// Microseconds since process start.
+    ts: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dur: Option<f64>,
+    pid: u32,
+    tid: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    s: Option<&'static str>,
+}
+
+struct Capture {
+    path: PathBuf,
+    frames_left: u32,
+    frames: u32,
+    events: Vec<TraceEvent>,
+    dropped: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CaptureStatus {
+    pub path: String,
+    pub frames: u32,
+    pub frames_left: u32,
+    pub events: usize,
+}
+
+static CAPTURING: AtomicBool = AtomicBool::new(false);
+static CAPTURE: Mutex<Option<Capture>> = Mutex::new(None);
+static NEXT_TID: AtomicU32 = AtomicU32::new(1);
+
+thread_local! {
+    static TID: Cell<u32> = const { Cell::new(0) };
+}
+
+#[inline]
+fn epoch() -> Instant {
+    static EPOCH: OnceLock<Instant> = OnceLock::new();
+    *EPOCH.get_or_init(Instant::now)
+}
+
+#[inline]
+fn now_us() -> f64 {
+    epoch().elapsed().as_secs_f64() * 1_000_000.0
+}
+
+#[inline]
+fn thread_id() -> u32 {
+    TID.with(|t| {
+        if t.get() == 0 {
+            t.set(NEXT_TID.fetch_add(1, Ordering::Relaxed));
+        }
+        t.get()
+    })
+}
+
+fn record(ev: TraceEvent) {
+    let Ok(mut g) = CAPTURE.lock() else {
+        return;
+    };
+    let Some(c) = g.as_mut() else {
+        return;
+    };
+    if c.events.len() >= MAX_CAPTURE_EVENTS {
+        c.dropped += 1;
+        return;
+    }
+    c.events.push(ev);
+}
+
+/// Named timing scope (module stages, asset pump, plugin calls); ends on drop.
+///
+/// Costs one relaxed atomic load unless a capture is running or the `tracy` feature is on.
+/// A capture records every scope for N frames into a Chrome trace file (`chrome://tracing`,
+/// Perfetto).
+#[must_use = "the scope ends when this guard is dropped"]
+pub struct Scope {
+    active: Option<(Cow<'static, str>, &'static str, f64)>,
+    #[cfg(feature = "tracy")]
+    _span: Option<tracy_client::Span>,
+}
+
+impl Scope {
+    #[inline]
+    fn begin(cat: &'static str, name: impl FnOnce() -> Cow<'static, str>) -> Self {
+        let capturing = CAPTURING.load(Ordering::Relaxed);
+
+        #[cfg(feature = "tracy")]
+        {
+            let name = name();
+            let span = tracy_client::Client::running()
+                .map(|c| c.span_alloc(Some(&name), cat, file!(), line!(), 0));
+            Self {
+                active: capturing.then(|| (name, cat, now_us())),
+                _span: span,
+            }
+        }
+
+        #[cfg(not(feature = "tracy"))]
+        {
+            Self {
+                active: capturing.then(|| (name(), cat, now_us())),
+            }
+        }
+    }
+}
+
+impl Drop for Scope {
+    fn drop(&mut self) {
+        let Some((name, cat, start)) = self.active.take() else {
+            return;
+        };
+        record(TraceEvent {
+            name,
+            cat,
+            ph: "X",
+            ts: start,
+            dur: Some(now_us() - start),
+            pid: std::process::id(),
+            tid: thread_id(),
+            s: None,
+        });
+    }
+}
+
+/// Scope with a static name, e.g. `scope("assets", "pump")`.
+#[inline]
+pub fn scope(cat: &'static str, name: &'static str) -> Scope {
+    Scope::begin(cat, || Cow::Borrowed(name))
+}
+
+/// Scope with a computed name; `name` only runs while something is recording.
+#[inline]
+pub fn scope_with(cat: &'static str, name: impl FnOnce() -> String) -> Scope {
+    Scope::begin(cat, || Cow::Owned(name()))
+}
+
+/// Starts the Tracy client when the `tracy` feature is enabled; no-op otherwise.
+#[inline]
+pub fn init() {
+    let _ = epoch();
+    #[cfg(feature = "tracy")]
+    {
+        let _ = tracy_client::Client::start();
+    }
+}
+
+/// Called once per engine frame. Finishes a running capture after its last frame.
+pub fn frame_mark() {
+    #[cfg(feature = "tracy")]
+    if let Some(c) = tracy_client::Client::running() {
+        c.frame_mark();
+    }
+
+    if !CAPTURING.load(Ordering::Relaxed) {
+        return;
+    }
+
+    let done = {
+        let Ok(mut g) = CAPTURE.lock() else {
+            return;
+        };
+        let Some(c) = g.as_mut() else {
+            return;
+        };
+
+        c.events.push(TraceEvent {
+            name: Cow::Borrowed("frame"),
+            cat: "frame",
+            ph: "i",
+            ts: now_us(),
+            dur: None,
+            pid: std::process::id(),
+            tid: thread_id(),
+            s: Some("g"),
+        });
+
+        c.frames_left = c.frames_left.saturating_sub(1);
+        if c.frames_left > 0 {
+            return;
+        }
+        CAPTURING.store(false, Ordering::Relaxed);
+        g.take()
+    };
+
+    if let Some(c) = done {
+        match write_chrome_trace(&c) {
+            Ok(()) => log::info!(
+                "telemetry: wrote trace path='{}' frames={} events={} dropped={}",
+                c.path.display(),
+                c.frames,
+                c.events.len(),
+                c.dropped
+            ),
+            Err(e) => log::warn!("telemetry: {e}"),
+        }
+    }
+}
+
+/// Records the next `frames` frames into a Chrome trace file at `path`.
+pub fn start_capture(frames: u32, path: PathBuf) -> Result<(), String> {
+    if frames == 0 {
+        return Err("frame count must be > 0".to_string());
+    }
+
+    let mut g = CAPTURE
+        .lock()
+        .map_err(|_| "telemetry mutex poisoned".to_string())?;
+    if let Some(c) = g.as_ref() {
+        return Err(format!(
+            "capture already running ({} frames left, path='{}')",
+            c.frames_left,
+            c.path.display()
+        ));
+    }
+
+    *g = Some(Capture {
+        path,
+        frames_left: frames,
+        frames,
+        events: Vec::new(),
+        dropped: 0,
+    });
+    CAPTURING.store(true, Ordering::Relaxed);
+    Ok(())
+}
+
+pub fn capture_status() -> Option<CaptureStatus> {
+    let g = CAPTURE.lock().ok()?;
+    g.as_ref().map(|c| CaptureStatus {
+        path: c.path.display().to_string(),
+        frames: c.frames,
+        frames_left: c.frames_left,
+        events: c.events.len(),
+    })
+}
+
+fn write_chrome_trace(c: &Capture) -> Result<(), String> {
+    #[derive(Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct ChromeTrace<'a> {
+        trace_events: &'a [TraceEvent],
+        display_time_unit: &'static str,
+    }
+
+    if let Some(parent) = c.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("trace dir create failed '{}': {e}", parent.display()))?;
+    }
+
+    let file = std::fs::File::create(&c.path)
+        .map_err(|e| format!("trace write failed path='{}': {e}", c.path.display()))?;
+    serde_json::to_writer(
+        std::io::BufWriter::new(file),
+        &ChromeTrace {
+            trace_events: &c.events,
+            display_time_unit: "ms",
+        },
+    )
+    .map_err(|e| format!("trace write failed path='{}': {e}", c.path.display()))
+}
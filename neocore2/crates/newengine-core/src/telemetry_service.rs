@@ -0,0 +1,145 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use crate::plugins::host_api;
+use crate::telemetry::{self, CaptureStatus, DEFAULT_CAPTURE_FRAMES};
+use abi_stable::std_types::{RResult, RString};
+use newengine_plugin_api::{
+    guarded_service, Blob, CapabilityId, MethodName, ServiceV1, ServiceV1Dyn,
+};
+use serde::Serialize;
+use serde_json::json;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const TELEMETRY_SERVICE_ID: &str = "engine.telemetry";
+
+pub mod method {
+    pub const CAPTURE: &str = "trace.capture";
+    pub const STATUS_JSON: &str = "trace.status_json";
+}
+
+#[derive(Debug, Serialize)]
+struct TraceCaptureResp {
+    ok: bool,
+    frames: u32,
+    path: String,
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct TraceStatusResp {
+    running: bool,
+    tracy: bool,
+    capture: Option<CaptureStatus>,
+}
+
+struct TelemetryService;
+
+impl TelemetryService {
+    /// Payload: `[frames] [path]`; the path defaults to `trace-<unix ms>.json`.
+    fn capture(arg: &str) -> TraceCaptureResp {
+        let mut it = arg.split_whitespace();
+        let frames = it.next().map(str::parse::<u32>);
+        let path = it.next().map(PathBuf::from).unwrap_or_else(|| {
+            let ms = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_millis())
+                .unwrap_or(0);
+            PathBuf::from(format!("trace-{ms}.json"))
+        });
+
+        let res = match frames {
+            Some(Err(_)) => Err("usage: trace.capture [frames] [path]".to_string()),
+            Some(Ok(n)) => telemetry::start_capture(n, path.clone()).map(|_| n),
+            None => telemetry::start_capture(DEFAULT_CAPTURE_FRAMES, path.clone())
+                .map(|_| DEFAULT_CAPTURE_FRAMES),
+        };
+
+        match res {
+            Ok(frames) => {
+                log::info!(
+                    "telemetry: capturing frames={frames} path='{}'",
+                    path.display()
+                );
+                TraceCaptureResp {
+                    ok: true,
+                    frames,
+                    path: path.display().to_string(),
+                    error: None,
+                }
+            }
+            Err(e) => TraceCaptureResp {
+                ok: false,
+                frames: 0,
+                path: path.display().to_string(),
+                error: Some(e),
+            },
+        }
+    }
+}
+
+impl ServiceV1 for TelemetryService {
+    fn id(&self) -> CapabilityId {
+        RString::from(TELEMETRY_SERVICE_ID)
+    }
+
+    fn describe(&self) -> RString {
+        let d = json!({
+          "id": TELEMETRY_SERVICE_ID,
+          "version": 1,
+          "methods": [
+            { "name": method::CAPTURE, "payload": "utf8 '[frames] [path]'", "returns": "json TraceCaptureResp" },
+            { "name": method::STATUS_JSON, "payload": "empty", "returns": "json TraceStatusResp" }
+          ],
+          "console": {
+            "commands": [
+              {
+                "name": "trace.capture",
+                "help": "Record N frames into a chrome://tracing JSON file: trace.capture [frames] [path]",
+                "usage": "trace.capture [frames] [path]",
+                "kind": "service_call",
+                "service_id": TELEMETRY_SERVICE_ID,
+                "method": method::CAPTURE,
+                "payload": "raw"
+              },
+              {
+                "name": "trace.status",
+                "help": "Show the running trace capture, if any",
+                "kind": "service_call",
+                "service_id": TELEMETRY_SERVICE_ID,
+                "method": method::STATUS_JSON,
+                "payload": "empty"
+              }
+            ]
+          }
+        });
+
+        RString::from(d.to_string())
+    }
+
+    fn call(&self, method: MethodName, payload: Blob) -> RResult<Blob, RString> {
+        let m = method.to_string();
+        let arg = String::from_utf8_lossy(payload.as_slice());
+
+        let resp = match m.as_str() {
+            method::CAPTURE => serde_json::to_vec(&Self::capture(&arg)),
+            method::STATUS_JSON => {
+                let capture = telemetry::capture_status();
+                serde_json::to_vec(&TraceStatusResp {
+                    running: capture.is_some(),
+                    tracy: cfg!(feature = "tracy"),
+                    capture,
+                })
+            }
+            _ => return RResult::RErr(RString::from(format!("unknown method: {m}"))),
+        };
+
+        RResult::ROk(Blob::from(resp.unwrap_or_default()))
+    }
+}
+
+pub fn register_telemetry_service() {
+    let dyn_svc: ServiceV1Dyn<'static> = guarded_service(TelemetryService);
+
+    let _ = host_api::host_register_service_impl(dyn_svc, false);
+}
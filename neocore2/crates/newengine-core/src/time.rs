@@ -0,0 +1,105 @@
+use serde::Serialize;
+use std::sync::Mutex;
+
+/// Largest accepted [`set_time_scale`] value.
+pub const MAX_TIME_SCALE: f32 = 100.0;
+
+/// Process-wide so console commands and the editor reach the clock without an engine handle.
+static CLOCK: Mutex<TimeState> = Mutex::new(TimeState::REAL_TIME);
+
+/// Game clock controls applied to `update` dt and the fixed-step accumulator.
+///
+/// UI, tools, the scheduler and `Frame::real_dt` keep running on wall-clock time.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct TimeState {
+    pub scale: f32,
+    pub paused: bool,
+    /// Fixed ticks still to run while paused, one per frame.
+    pub pending_steps: u32,
+}
+
+impl TimeState {
+    pub const REAL_TIME: Self = Self {
+        scale: 1.0,
+        paused: false,
+        pending_steps: 0,
+    };
+}
+
+impl Default for TimeState {
+    fn default() -> Self {
+        Self::REAL_TIME
+    }
+}
+
+#[inline]
+fn with_clock<R>(f: impl FnOnce(&mut TimeState) -> R) -> R {
+    let mut g = CLOCK.lock().unwrap_or_else(|p| p.into_inner());
+    f(&mut g)
+}
+
+#[inline]
+pub fn time_state() -> TimeState {
+    with_clock(|c| *c)
+}
+
+#[inline]
+pub fn time_scale() -> f32 {
+    with_clock(|c| c.scale)
+}
+
+/// `1.0` is real time, `0.5` half speed. Errors on negative, non-finite or too large values.
+pub fn set_time_scale(scale: f32) -> Result<(), String> {
+    if !scale.is_finite() || !(0.0..=MAX_TIME_SCALE).contains(&scale) {
+        return Err(format!(
+            "time scale must be in [0..{MAX_TIME_SCALE}], got {scale}"
+        ));
+    }
+    with_clock(|c| c.scale = scale);
+    Ok(())
+}
+
+#[inline]
+pub fn time_paused() -> bool {
+    with_clock(|c| c.paused)
+}
+
+/// Resuming drops steps not run yet.
+pub fn set_time_paused(paused: bool) {
+    with_clock(|c| {
+        c.paused = paused;
+        if !paused {
+            c.pending_steps = 0;
+        }
+    });
+}
+
+/// Flips pause and returns the new state.
+pub fn toggle_time_paused() -> bool {
+    let paused = !time_paused();
+    set_time_paused(paused);
+    paused
+}
+
+/// Pauses the clock and runs `steps` fixed ticks, one per frame.
+pub fn step_time(steps: u32) {
+    with_clock(|c| {
+        c.paused = true;
+        c.pending_steps = c.pending_steps.saturating_add(steps);
+    });
+}
+
+/// Game-clock delta for a frame that took `real_dt`: scaled, zero while paused, or exactly
+/// one `fixed_dt` for a pending step.
+pub(crate) fn advance(real_dt: f32, fixed_dt: f32) -> f32 {
+    with_clock(|c| {
+        if !c.paused {
+            return real_dt * c.scale;
+        }
+        if c.pending_steps > 0 {
+            c.pending_steps -= 1;
+            return fixed_dt;
+        }
+        0.0
+    })
+}
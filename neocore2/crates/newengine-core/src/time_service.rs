@@ -0,0 +1,170 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use crate::plugins::host_api;
+use crate::time::{
+    set_time_paused, set_time_scale, step_time, time_state, toggle_time_paused, TimeState,
+    MAX_TIME_SCALE,
+};
+use abi_stable::std_types::{RResult, RString};
+use newengine_plugin_api::{
+    guarded_service, Blob, CapabilityId, MethodName, ServiceV1, ServiceV1Dyn,
+};
+use serde::Serialize;
+use serde_json::json;
+
+pub const TIME_SERVICE_ID: &str = "engine.time";
+
+pub mod method {
+    pub const SCALE: &str = "time.scale";
+    pub const PAUSE: &str = "time.pause";
+    pub const STEP: &str = "time.step";
+    pub const STATE_JSON: &str = "time.state_json";
+}
+
+#[derive(Debug, Serialize)]
+struct TimeResp {
+    ok: bool,
+    state: TimeState,
+    error: Option<String>,
+}
+
+impl TimeResp {
+    fn from_result(res: Result<(), String>) -> Self {
+        Self {
+            ok: res.is_ok(),
+            state: time_state(),
+            error: res.err(),
+        }
+    }
+}
+
+struct TimeService;
+
+impl TimeService {
+    /// Payload: empty reports the state, a number sets the scale.
+    fn scale(arg: &str) -> Result<(), String> {
+        match arg.trim() {
+            "" => Ok(()),
+            v => {
+                let scale = v
+                    .parse::<f32>()
+                    .map_err(|_| format!("expected a number, got '{v}'"))?;
+                set_time_scale(scale)
+            }
+        }
+    }
+
+    /// Payload: empty flips pause, `on` / `off` sets it.
+    fn pause(arg: &str) -> Result<(), String> {
+        match arg.trim().to_ascii_lowercase().as_str() {
+            "" => {
+                toggle_time_paused();
+            }
+            "on" | "1" | "true" => set_time_paused(true),
+            "off" | "0" | "false" => set_time_paused(false),
+            other => return Err(format!("expected on|off, got '{other}'")),
+        }
+        Ok(())
+    }
+
+    /// Payload: empty runs one fixed tick, a number runs that many.
+    fn step(arg: &str) -> Result<(), String> {
+        let steps = match arg.trim() {
+            "" => 1,
+            v => v
+                .parse::<u32>()
+                .map_err(|_| format!("expected a step count, got '{v}'"))?,
+        };
+        step_time(steps);
+        Ok(())
+    }
+}
+
+impl ServiceV1 for TimeService {
+    fn id(&self) -> CapabilityId {
+        RString::from(TIME_SERVICE_ID)
+    }
+
+    fn describe(&self) -> RString {
+        let d = json!({
+          "id": TIME_SERVICE_ID,
+          "version": 1,
+          "methods": [
+            {
+              "name": method::SCALE,
+              "payload": "utf8 '[scale]'",
+              "returns": "json TimeResp",
+              "schema": { "type": "number", "minimum": 0.0, "maximum": MAX_TIME_SCALE }
+            },
+            {
+              "name": method::PAUSE,
+              "payload": "utf8 '[on|off]'",
+              "returns": "json TimeResp",
+              "schema": { "type": "string", "enum": ["on", "off", "1", "0", "true", "false"] }
+            },
+            {
+              "name": method::STEP,
+              "payload": "utf8 '[count]'",
+              "returns": "json TimeResp",
+              "schema": { "type": "integer", "minimum": 1 }
+            },
+            { "name": method::STATE_JSON, "payload": "empty", "returns": "json TimeResp" }
+          ],
+          "console": {
+            "commands": [
+              {
+                "name": "time.scale",
+                "help": "Show or set the game clock speed, e.g. time.scale 0.25",
+                "usage": "time.scale [scale]",
+                "kind": "service_call",
+                "service_id": TIME_SERVICE_ID,
+                "method": method::SCALE,
+                "payload": "raw"
+              },
+              {
+                "name": "time.pause",
+                "help": "Pause or resume the game clock: time.pause [on|off]",
+                "usage": "time.pause [on|off]",
+                "kind": "service_call",
+                "service_id": TIME_SERVICE_ID,
+                "method": method::PAUSE,
+                "payload": "raw"
+              },
+              {
+                "name": "time.step",
+                "help": "Pause and advance the game clock by fixed ticks: time.step [count]",
+                "usage": "time.step [count]",
+                "kind": "service_call",
+                "service_id": TIME_SERVICE_ID,
+                "method": method::STEP,
+                "payload": "raw"
+              }
+            ]
+          }
+        });
+
+        RString::from(d.to_string())
+    }
+
+    fn call(&self, method: MethodName, payload: Blob) -> RResult<Blob, RString> {
+        let m = method.to_string();
+        let arg = String::from_utf8_lossy(payload.as_slice());
+
+        let res = match m.as_str() {
+            method::SCALE => Self::scale(&arg),
+            method::PAUSE => Self::pause(&arg),
+            method::STEP => Self::step(&arg),
+            method::STATE_JSON => Ok(()),
+            _ => return RResult::RErr(RString::from(format!("unknown method: {m}"))),
+        };
+
+        let resp = serde_json::to_vec(&TimeResp::from_result(res));
+        RResult::ROk(Blob::from(resp.unwrap_or_default()))
+    }
+}
+
+pub fn register_time_service() {
+    let dyn_svc: ServiceV1Dyn<'static> = guarded_service(TimeService);
+
+    let _ = host_api::host_register_service_impl(dyn_svc, false);
+}
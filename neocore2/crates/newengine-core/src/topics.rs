@@ -0,0 +1,218 @@
+use crate::events::{offer, OverflowPolicy};
+
+use crossbeam_channel::{Receiver, Sender};
+use serde::de::DeserializeOwned;
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc, RwLock, Weak,
+};
+
+/// Event published on a named topic.
+///
+/// This is the host-side shape of plugin events (`emit_event_v1(topic, payload)`):
+/// the payload is opaque bytes, JSON by convention.
+#[derive(Debug, Clone)]
+pub struct TopicEvent {
+    topic: Arc<str>,
+    payload: Arc<[u8]>,
+}
+
+impl TopicEvent {
+    #[inline]
+    pub fn new(topic: impl Into<Arc<str>>, payload: impl Into<Arc<[u8]>>) -> Self {
+        Self {
+            topic: topic.into(),
+            payload: payload.into(),
+        }
+    }
+
+    #[inline]
+    pub fn topic(&self) -> &str {
+        &self.topic
+    }
+
+    #[inline]
+    pub fn payload(&self) -> &[u8] {
+        &self.payload
+    }
+
+    /// Payload as UTF-8, if it is.
+    #[inline]
+    pub fn as_str(&self) -> Option<&str> {
+        std::str::from_utf8(&self.payload).ok()
+    }
+
+    #[inline]
+    pub fn json<T: DeserializeOwned>(&self) -> Result<T, serde_json::Error> {
+        serde_json::from_slice(&self.payload)
+    }
+}
+
+/// Topic selector for subscriptions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TopicPattern {
+    /// Exactly this topic.
+    Exact(Arc<str>),
+    /// Every topic starting with this prefix (`"winit."` matches `"winit.key"`).
+    Prefix(Arc<str>),
+    /// Every topic.
+    Any,
+}
+
+impl TopicPattern {
+    /// `"*"` is `Any`, a trailing `*` makes a prefix (`"winit.*"`), anything else is exact.
+    pub fn parse(s: &str) -> Self {
+        let s = s.trim();
+        if s == "*" {
+            return TopicPattern::Any;
+        }
+        match s.strip_suffix('*') {
+            Some(prefix) => TopicPattern::Prefix(Arc::from(prefix)),
+            None => TopicPattern::Exact(Arc::from(s)),
+        }
+    }
+
+    #[inline]
+    pub fn matches(&self, topic: &str) -> bool {
+        match self {
+            TopicPattern::Exact(t) => &**t == topic,
+            TopicPattern::Prefix(p) => topic.starts_with(&**p),
+            TopicPattern::Any => true,
+        }
+    }
+}
+
+impl From<&str> for TopicPattern {
+    #[inline]
+    fn from(s: &str) -> Self {
+        TopicPattern::parse(s)
+    }
+}
+
+impl std::fmt::Display for TopicPattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TopicPattern::Exact(t) => write!(f, "{t}"),
+            TopicPattern::Prefix(p) => write!(f, "{p}*"),
+            TopicPattern::Any => write!(f, "*"),
+        }
+    }
+}
+
+/// Topic subscription handle.
+/// On drop, automatically unregisters.
+pub struct TopicSub {
+    router: Weak<TopicRouter>,
+    sub_id: u64,
+    rx: Receiver<TopicEvent>,
+    dropped: Arc<AtomicU64>,
+}
+
+impl TopicSub {
+    /// Number of events dropped due to overflow on this subscription.
+    #[inline]
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    #[inline]
+    pub fn try_recv(&self) -> Option<TopicEvent> {
+        self.rx.try_recv().ok()
+    }
+
+    #[inline]
+    pub fn drain<F: FnMut(TopicEvent)>(&self, mut f: F) {
+        while let Ok(ev) = self.rx.try_recv() {
+            f(ev);
+        }
+    }
+}
+
+impl Drop for TopicSub {
+    fn drop(&mut self) {
+        if let Some(router) = self.router.upgrade() {
+            router.remove(self.sub_id);
+        }
+    }
+}
+
+#[derive(Clone)]
+struct TopicSubscriber {
+    id: u64,
+    pattern: TopicPattern,
+    tx: Sender<TopicEvent>,
+    rx: Receiver<TopicEvent>,
+    overflow: OverflowPolicy,
+    dropped: Arc<AtomicU64>,
+}
+
+/// Named-topic fan-out owned by an `EventHub`.
+///
+/// Same copy-on-write subscriber list as typed channels; publish only takes a read lock.
+pub(crate) struct TopicRouter {
+    next_id: AtomicU64,
+    subs: RwLock<Arc<Vec<TopicSubscriber>>>,
+}
+
+impl TopicRouter {
+    #[inline]
+    pub(crate) fn new() -> Self {
+        Self {
+            next_id: AtomicU64::new(1),
+            subs: RwLock::new(Arc::new(Vec::new())),
+        }
+    }
+
+    pub(crate) fn subscribe(
+        self: &Arc<Self>,
+        pattern: TopicPattern,
+        capacity: usize,
+        overflow: OverflowPolicy,
+    ) -> TopicSub {
+        let (tx, rx) = crossbeam_channel::bounded::<TopicEvent>(capacity.max(1));
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let dropped = Arc::new(AtomicU64::new(0));
+
+        {
+            let mut g = self.subs.write().expect("TopicRouter subscribers poisoned");
+            let mut v: Vec<TopicSubscriber> = (**g).clone();
+            v.push(TopicSubscriber {
+                id,
+                pattern,
+                tx,
+                rx: rx.clone(),
+                overflow,
+                dropped: dropped.clone(),
+            });
+            *g = Arc::new(v);
+        }
+
+        TopicSub {
+            router: Arc::downgrade(self),
+            sub_id: id,
+            rx,
+            dropped,
+        }
+    }
+
+    fn remove(&self, sub_id: u64) {
+        let mut g = self.subs.write().expect("TopicRouter subscribers poisoned");
+        let v: Vec<TopicSubscriber> = g.iter().filter(|s| s.id != sub_id).cloned().collect();
+        *g = Arc::new(v);
+    }
+
+    /// Delivers to matching host subscribers only.
+    pub(crate) fn deliver(&self, ev: &TopicEvent) {
+        let subs = {
+            let g = self.subs.read().expect("TopicRouter subscribers poisoned");
+            g.clone()
+        };
+
+        for s in subs.iter() {
+            if s.pattern.matches(ev.topic()) {
+                // Disconnected receivers unregister themselves on drop.
+                let _ = offer(&s.tx, &s.rx, s.overflow, &s.dropped, ev.clone());
+            }
+        }
+    }
+}
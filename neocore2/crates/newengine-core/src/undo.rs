@@ -0,0 +1,309 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use crate::cvar::{cvar_get, cvar_set_value, CvarValue};
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::any::Any;
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+
+/// Commands kept for undo; the oldest are dropped past this.
+pub const UNDO_LIMIT: usize = 256;
+
+/// A command recorded within this long of the previous one may merge into it (slider drags,
+/// repeated `set`s of one cvar).
+const MERGE_WINDOW: Duration = Duration::from_millis(500);
+
+/// An undoable edit.
+pub trait Command: Any + Send {
+    /// One line for the history, e.g. `set post.bloom 1`.
+    fn label(&self) -> String;
+
+    fn apply(&mut self) -> Result<(), String>;
+
+    fn revert(&mut self) -> Result<(), String>;
+
+    /// Folds `next`, recorded right after this command, into it so one undo reverts both.
+    /// `next` is the concrete command; `false` keeps them apart.
+    fn merge(&mut self, next: &dyn Any) -> bool {
+        let _ = next;
+        false
+    }
+}
+
+/// One line of [`UndoStack::history`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct UndoEntry {
+    pub label: String,
+    /// Reverted and waiting for redo.
+    pub undone: bool,
+}
+
+struct Recorded {
+    cmd: Box<dyn Command>,
+    /// When it was last recorded or merged into; `None` once it must not merge any more.
+    at: Option<Instant>,
+}
+
+struct UndoState {
+    done: Vec<Recorded>,
+    undone: Vec<Recorded>,
+}
+
+/// Undo/redo history of editor edits.
+///
+/// Edits are [`Command`]s: [`UndoStack::execute`] applies and records one,
+/// [`UndoStack::record`] records one its caller already applied (a finished gizmo drag).
+/// Recording drops whatever was undone. The engine inserts the stack into `Resources`; the
+/// `engine.edit` service and the `edit.undo` / `edit.redo` / `edit.history` console commands
+/// drive it, and Ctrl+Z / Ctrl+Y are bound to them by default.
+#[derive(Clone)]
+pub struct UndoStack(Arc<Mutex<UndoState>>);
+
+impl UndoStack {
+    /// Process-wide history; the engine also inserts it into `Resources`.
+    pub fn global() -> Self {
+        static GLOBAL: OnceLock<UndoStack> = OnceLock::new();
+        GLOBAL
+            .get_or_init(|| {
+                UndoStack(Arc::new(Mutex::new(UndoState {
+                    done: Vec::new(),
+                    undone: Vec::new(),
+                })))
+            })
+            .clone()
+    }
+
+    /// Applies `cmd` and records it; nothing is recorded when it fails.
+    pub fn execute<C: Command>(&self, mut cmd: C) -> Result<(), String> {
+        cmd.apply()?;
+        self.record(cmd);
+        Ok(())
+    }
+
+    /// Records `cmd`, already applied by the caller.
+    pub fn record<C: Command>(&self, cmd: C) {
+        let mut g = self.0.lock();
+        g.undone.clear();
+
+        let now = Instant::now();
+        if let Some(top) = g.done.last_mut() {
+            let recent = top
+                .at
+                .is_some_and(|at| now.duration_since(at) <= MERGE_WINDOW);
+            if recent && top.cmd.merge(&cmd) {
+                top.at = Some(now);
+                return;
+            }
+        }
+
+        g.done.push(Recorded {
+            cmd: Box::new(cmd),
+            at: Some(now),
+        });
+        if g.done.len() > UNDO_LIMIT {
+            let excess = g.done.len() - UNDO_LIMIT;
+            g.done.drain(..excess);
+        }
+    }
+
+    /// Reverts the last command; `Ok(None)` when there is nothing to undo. A command that
+    /// fails to revert no longer matches what it edited and is dropped, so the ones before it
+    /// stay reachable.
+    pub fn undo(&self) -> Result<Option<String>, String> {
+        let Some(mut r) = self.0.lock().done.pop() else {
+            return Ok(None);
+        };
+        // Unlocked: reverting may run code that reads the history (a UI, a topic listener).
+        let res = r.cmd.revert();
+        let label = r.cmd.label();
+        let mut g = self.0.lock();
+        match res {
+            Ok(()) => {
+                // What is now on top was done before the undo; later edits stay separate.
+                if let Some(top) = g.done.last_mut() {
+                    top.at = None;
+                }
+                r.at = None;
+                g.undone.push(r);
+                Ok(Some(label))
+            }
+            Err(e) => Err(format!("undo '{label}' failed: {e}")),
+        }
+    }
+
+    /// Applies the last undone command again; `Ok(None)` when there is nothing to redo. A
+    /// command that fails is dropped like in [`UndoStack::undo`].
+    pub fn redo(&self) -> Result<Option<String>, String> {
+        let Some(mut r) = self.0.lock().undone.pop() else {
+            return Ok(None);
+        };
+        let res = r.cmd.apply();
+        let label = r.cmd.label();
+        let mut g = self.0.lock();
+        match res {
+            Ok(()) => {
+                g.done.push(r);
+                Ok(Some(label))
+            }
+            Err(e) => Err(format!("redo '{label}' failed: {e}")),
+        }
+    }
+
+    /// Label of the command [`UndoStack::undo`] would revert.
+    pub fn undo_label(&self) -> Option<String> {
+        self.0.lock().done.last().map(|r| r.cmd.label())
+    }
+
+    /// Label of the command [`UndoStack::redo`] would apply.
+    pub fn redo_label(&self) -> Option<String> {
+        self.0.lock().undone.last().map(|r| r.cmd.label())
+    }
+
+    /// Up to `limit` commands, newest first: undone ones (next redo last), then done ones
+    /// (next undo first).
+    pub fn history(&self, limit: usize) -> Vec<UndoEntry> {
+        let g = self.0.lock();
+        let undone = g.undone.iter().map(|r| (r, true));
+        let done = g.done.iter().rev().map(|r| (r, false));
+        undone
+            .chain(done)
+            .take(limit)
+            .map(|(r, undone)| UndoEntry {
+                label: r.cmd.label(),
+                undone,
+            })
+            .collect()
+    }
+
+    /// `(undo, redo)` counts.
+    pub fn counts(&self) -> (usize, usize) {
+        let g = self.0.lock();
+        (g.done.len(), g.undone.len())
+    }
+
+    pub fn clear(&self) {
+        let mut g = self.0.lock();
+        g.done.clear();
+        g.undone.clear();
+    }
+}
+
+/// Sets a cvar; repeated sets of the same cvar merge.
+#[derive(Debug, Clone)]
+pub struct CvarSet {
+    name: String,
+    before: CvarValue,
+    after: CvarValue,
+}
+
+impl CvarSet {
+    /// Setting `name` from its current value to `value`.
+    pub fn new(name: &str, value: CvarValue) -> Result<Self, String> {
+        let before = cvar_get(name).ok_or_else(|| format!("unknown cvar: {name}"))?;
+        Ok(Self {
+            name: name.to_string(),
+            before,
+            after: value,
+        })
+    }
+
+    /// A set that already happened.
+    #[inline]
+    pub fn applied(name: &str, before: CvarValue, after: CvarValue) -> Self {
+        Self {
+            name: name.to_string(),
+            before,
+            after,
+        }
+    }
+}
+
+impl Command for CvarSet {
+    fn label(&self) -> String {
+        format!("set {} {}", self.name, self.after)
+    }
+
+    fn apply(&mut self) -> Result<(), String> {
+        // The cvar may normalize the value (kind, range).
+        self.after = cvar_set_value(&self.name, self.after.clone())?;
+        Ok(())
+    }
+
+    fn revert(&mut self) -> Result<(), String> {
+        cvar_set_value(&self.name, self.before.clone()).map(|_| ())
+    }
+
+    fn merge(&mut self, next: &dyn Any) -> bool {
+        match next.downcast_ref::<Self>() {
+            Some(next) if next.name == self.name => {
+                self.after = next.after.clone();
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Commands done and undone as one, in order and in reverse.
+pub struct CommandBatch {
+    label: String,
+    cmds: Vec<Box<dyn Command>>,
+}
+
+impl CommandBatch {
+    #[inline]
+    pub fn new(label: impl Into<String>) -> Self {
+        Self {
+            label: label.into(),
+            cmds: Vec::new(),
+        }
+    }
+
+    #[inline]
+    pub fn push<C: Command>(&mut self, cmd: C) {
+        self.cmds.push(Box::new(cmd));
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.cmds.is_empty()
+    }
+}
+
+impl Command for CommandBatch {
+    fn label(&self) -> String {
+        self.label.clone()
+    }
+
+    /// Stops at the first failure, reverting what it already applied.
+    fn apply(&mut self) -> Result<(), String> {
+        for i in 0..self.cmds.len() {
+            if let Err(e) = self.cmds[i].apply() {
+                for done in self.cmds[..i].iter_mut().rev() {
+                    let _ = done.revert();
+                }
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+
+    fn revert(&mut self) -> Result<(), String> {
+        for cmd in self.cmds.iter_mut().rev() {
+            cmd.revert()?;
+        }
+        Ok(())
+    }
+}
+
+/// Sets cvar `name` to `value` through the global [`UndoStack`]; returns the value applied.
+pub fn undoable_cvar_set(name: &str, value: CvarValue) -> Result<CvarValue, String> {
+    let mut cmd = CvarSet::new(name, value)?;
+    cmd.apply()?;
+    let applied = cmd.after.clone();
+    if cmd.before != applied {
+        UndoStack::global().record(cmd);
+    }
+    Ok(applied)
+}
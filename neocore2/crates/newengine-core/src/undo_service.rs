@@ -0,0 +1,147 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use crate::plugins::host_api;
+use crate::undo::{UndoEntry, UndoStack};
+use abi_stable::std_types::{RResult, RString};
+use newengine_plugin_api::{
+    guarded_service, Blob, CapabilityId, MethodName, ServiceV1, ServiceV1Dyn,
+};
+use serde::Serialize;
+use serde_json::json;
+
+pub const UNDO_SERVICE_ID: &str = "engine.edit";
+
+pub mod method {
+    pub const UNDO: &str = "edit.undo";
+    pub const REDO: &str = "edit.redo";
+    pub const HISTORY_JSON: &str = "edit.history_json";
+}
+
+/// Commands `edit.history` lists without a count.
+const DEFAULT_HISTORY: usize = 20;
+
+#[derive(Debug, Serialize)]
+struct UndoResp {
+    ok: bool,
+    /// Label of the command undone or redone; `None` when there was none.
+    command: Option<String>,
+    error: Option<String>,
+}
+
+impl UndoResp {
+    fn from_result(res: Result<Option<String>, String>) -> Self {
+        match res {
+            Ok(command) => Self {
+                ok: true,
+                command,
+                error: None,
+            },
+            Err(e) => Self {
+                ok: false,
+                command: None,
+                error: Some(e),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct UndoHistoryResp {
+    undo: usize,
+    redo: usize,
+    /// Newest first.
+    entries: Vec<UndoEntry>,
+}
+
+struct UndoService;
+
+impl UndoService {
+    /// Payload: optional count, default [`DEFAULT_HISTORY`].
+    fn history(arg: &str) -> Result<UndoHistoryResp, String> {
+        let limit = match arg.trim() {
+            "" => DEFAULT_HISTORY,
+            n => n
+                .parse::<usize>()
+                .map_err(|_| format!("bad count: '{n}'"))?,
+        };
+        let stack = UndoStack::global();
+        let (undo, redo) = stack.counts();
+        Ok(UndoHistoryResp {
+            undo,
+            redo,
+            entries: stack.history(limit),
+        })
+    }
+}
+
+impl ServiceV1 for UndoService {
+    fn id(&self) -> CapabilityId {
+        RString::from(UNDO_SERVICE_ID)
+    }
+
+    fn describe(&self) -> RString {
+        let d = json!({
+          "id": UNDO_SERVICE_ID,
+          "version": 1,
+          "methods": [
+            { "name": method::UNDO, "payload": "empty", "returns": "json UndoResp" },
+            { "name": method::REDO, "payload": "empty", "returns": "json UndoResp" },
+            { "name": method::HISTORY_JSON, "payload": "utf8 '[count]'", "returns": "json UndoHistoryResp" }
+          ],
+          "console": {
+            "commands": [
+              {
+                "name": "edit.undo",
+                "help": "Revert the last edit (Ctrl+Z)",
+                "kind": "service_call",
+                "service_id": UNDO_SERVICE_ID,
+                "method": method::UNDO,
+                "payload": "empty"
+              },
+              {
+                "name": "edit.redo",
+                "help": "Apply the last undone edit again (Ctrl+Y)",
+                "kind": "service_call",
+                "service_id": UNDO_SERVICE_ID,
+                "method": method::REDO,
+                "payload": "empty"
+              },
+              {
+                "name": "edit.history",
+                "help": format!("List recent edits, newest first (default {DEFAULT_HISTORY})"),
+                "usage": "edit.history [count]",
+                "kind": "service_call",
+                "service_id": UNDO_SERVICE_ID,
+                "method": method::HISTORY_JSON,
+                "payload": "raw"
+              }
+            ]
+          }
+        });
+
+        RString::from(d.to_string())
+    }
+
+    fn call(&self, method: MethodName, payload: Blob) -> RResult<Blob, RString> {
+        let m = method.to_string();
+        let arg = String::from_utf8_lossy(payload.as_slice());
+
+        let resp = match m.as_str() {
+            method::UNDO => serde_json::to_vec(&UndoResp::from_result(UndoStack::global().undo())),
+            method::REDO => serde_json::to_vec(&UndoResp::from_result(UndoStack::global().redo())),
+            method::HISTORY_JSON => match Self::history(&arg) {
+                Ok(h) => serde_json::to_vec(&h),
+                Err(e) => return RResult::RErr(RString::from(e)),
+            },
+            _ => return RResult::RErr(RString::from(format!("unknown method: {m}"))),
+        };
+
+        RResult::ROk(Blob::from(resp.unwrap_or_default()))
+    }
+}
+
+pub fn register_undo_service() {
+    let dyn_svc: ServiceV1Dyn<'static> = guarded_service(UndoService);
+
+    let _ = host_api::host_register_service_impl(dyn_svc, false);
+}
@@ -0,0 +1,327 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use serde::Serialize;
+use std::sync::{Arc, Mutex, OnceLock};
+
+/// Mouse cursor shape. Names follow the CSS cursor keywords (see [`CursorIcon::parse`]).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CursorIcon {
+    Default,
+    Pointer,
+    Text,
+    Crosshair,
+    Move,
+    Grab,
+    Grabbing,
+    NotAllowed,
+    Wait,
+    Progress,
+    Help,
+    EwResize,
+    NsResize,
+    NeswResize,
+    NwseResize,
+    ColResize,
+    RowResize,
+    AllScroll,
+    ZoomIn,
+    ZoomOut,
+}
+
+impl Default for CursorIcon {
+    #[inline]
+    fn default() -> Self {
+        Self::Default
+    }
+}
+
+impl CursorIcon {
+    pub fn parse(name: &str) -> Option<Self> {
+        let icon = match name.trim().to_ascii_lowercase().replace('_', "-").as_str() {
+            "default" => Self::Default,
+            "pointer" => Self::Pointer,
+            "text" => Self::Text,
+            "crosshair" => Self::Crosshair,
+            "move" => Self::Move,
+            "grab" => Self::Grab,
+            "grabbing" => Self::Grabbing,
+            "not-allowed" => Self::NotAllowed,
+            "wait" => Self::Wait,
+            "progress" => Self::Progress,
+            "help" => Self::Help,
+            "ew-resize" => Self::EwResize,
+            "ns-resize" => Self::NsResize,
+            "nesw-resize" => Self::NeswResize,
+            "nwse-resize" => Self::NwseResize,
+            "col-resize" => Self::ColResize,
+            "row-resize" => Self::RowResize,
+            "all-scroll" => Self::AllScroll,
+            "zoom-in" => Self::ZoomIn,
+            "zoom-out" => Self::ZoomOut,
+            _ => return None,
+        };
+        Some(icon)
+    }
+}
+
+/// Cursor confinement mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CursorGrab {
+    None,
+    /// Cursor stays inside the window but still moves.
+    Confined,
+    /// Cursor is pinned in place; only relative motion is reported (FPS-style look).
+    Locked,
+}
+
+impl Default for CursorGrab {
+    #[inline]
+    fn default() -> Self {
+        Self::None
+    }
+}
+
+impl CursorGrab {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "none" | "off" => Some(Self::None),
+            "confined" | "confine" => Some(Self::Confined),
+            "locked" | "lock" => Some(Self::Locked),
+            _ => None,
+        }
+    }
+}
+
+/// Requested cursor state. The platform layer applies it to the window once per frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CursorState {
+    pub icon: CursorIcon,
+    pub visible: bool,
+    pub grab: CursorGrab,
+}
+
+impl Default for CursorState {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            icon: CursorIcon::Default,
+            visible: true,
+            grab: CursorGrab::None,
+        }
+    }
+}
+
+/// Window presentation mode. `monitor` indexes [`WindowApi::monitors`]; `None` means the
+/// monitor the window is currently on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WindowMode {
+    Windowed,
+    /// Borderless window covering the monitor; no video mode switch.
+    Borderless {
+        monitor: Option<usize>,
+    },
+    /// Exclusive fullscreen at the monitor's largest video mode.
+    Exclusive {
+        monitor: Option<usize>,
+    },
+}
+
+impl Default for WindowMode {
+    #[inline]
+    fn default() -> Self {
+        Self::Windowed
+    }
+}
+
+impl WindowMode {
+    #[inline]
+    pub fn is_fullscreen(self) -> bool {
+        !matches!(self, Self::Windowed)
+    }
+
+    #[inline]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Windowed => "windowed",
+            Self::Borderless { .. } => "borderless",
+            Self::Exclusive { .. } => "exclusive",
+        }
+    }
+
+    #[inline]
+    pub fn monitor(self) -> Option<usize> {
+        match self {
+            Self::Windowed => None,
+            Self::Borderless { monitor } | Self::Exclusive { monitor } => monitor,
+        }
+    }
+}
+
+/// Connected monitor as reported by the platform layer.
+#[derive(Debug, Clone, Serialize)]
+pub struct MonitorInfo {
+    pub index: usize,
+    pub name: String,
+    pub width: u32,
+    pub height: u32,
+    pub x: i32,
+    pub y: i32,
+    pub scale_factor: f64,
+    pub refresh_mhz: Option<u32>,
+    pub primary: bool,
+}
+
+#[derive(Debug)]
+struct WindowApiState {
+    cursor: CursorState,
+    dirty: bool,
+    mode: WindowMode,
+    mode_dirty: bool,
+    monitors: Vec<MonitorInfo>,
+    scale_factor: f64,
+}
+
+/// Window controls available to modules and plugins (cursor, fullscreen, monitors, DPI scale).
+///
+/// Stored in `Resources` by the platform layer and shared with the `HostApiV1` cursor
+/// functions. Setters only record the request: window handles are not `Send` on every
+/// target, so the platform applies changes on its own thread (see [`WindowApi::take_cursor`]).
+#[derive(Debug, Clone)]
+pub struct WindowApi(Arc<Mutex<WindowApiState>>);
+
+impl WindowApi {
+    fn new() -> Self {
+        Self(Arc::new(Mutex::new(WindowApiState {
+            cursor: CursorState::default(),
+            dirty: false,
+            mode: WindowMode::Windowed,
+            mode_dirty: false,
+            monitors: Vec::new(),
+            scale_factor: 1.0,
+        })))
+    }
+
+    #[inline]
+    pub fn set_cursor_icon(&self, icon: CursorIcon) {
+        self.update(|c| c.icon = icon);
+    }
+
+    #[inline]
+    pub fn set_cursor_visible(&self, visible: bool) {
+        self.update(|c| c.visible = visible);
+    }
+
+    #[inline]
+    pub fn set_cursor_grab(&self, grab: CursorGrab) {
+        self.update(|c| c.grab = grab);
+    }
+
+    /// Last requested cursor state.
+    #[inline]
+    pub fn cursor(&self) -> CursorState {
+        self.0.lock().map(|g| g.cursor).unwrap_or_default()
+    }
+
+    /// Returns the cursor state if it changed since the last call (platform side).
+    pub fn take_cursor(&self) -> Option<CursorState> {
+        let mut g = self.0.lock().ok()?;
+        if !g.dirty {
+            return None;
+        }
+        g.dirty = false;
+        Some(g.cursor)
+    }
+
+    /// Requests a window mode change (applied on the event loop thread).
+    pub fn set_mode(&self, mode: WindowMode) {
+        let Ok(mut g) = self.0.lock() else {
+            log::warn!("window.mode update failed: mutex poisoned");
+            return;
+        };
+        if g.mode != mode {
+            g.mode = mode;
+            g.mode_dirty = true;
+        }
+    }
+
+    /// Switches between windowed and borderless fullscreen.
+    pub fn toggle_fullscreen(&self) -> WindowMode {
+        let next = match self.mode() {
+            WindowMode::Windowed => WindowMode::Borderless { monitor: None },
+            _ => WindowMode::Windowed,
+        };
+        self.set_mode(next);
+        next
+    }
+
+    /// Last requested (or platform-confirmed) window mode.
+    #[inline]
+    pub fn mode(&self) -> WindowMode {
+        self.0.lock().map(|g| g.mode).unwrap_or_default()
+    }
+
+    /// Returns the window mode if it changed since the last call (platform side).
+    pub fn take_mode(&self) -> Option<WindowMode> {
+        let mut g = self.0.lock().ok()?;
+        if !g.mode_dirty {
+            return None;
+        }
+        g.mode_dirty = false;
+        Some(g.mode)
+    }
+
+    /// Records the mode actually applied, e.g. after a fallback or an OS-driven change.
+    pub fn confirm_mode(&self, mode: WindowMode) {
+        if let Ok(mut g) = self.0.lock() {
+            g.mode = mode;
+        }
+    }
+
+    #[inline]
+    pub fn monitors(&self) -> Vec<MonitorInfo> {
+        self.0
+            .lock()
+            .map(|g| g.monitors.clone())
+            .unwrap_or_default()
+    }
+
+    /// Replaces the monitor list (platform side).
+    pub fn set_monitors(&self, monitors: Vec<MonitorInfo>) {
+        if let Ok(mut g) = self.0.lock() {
+            g.monitors = monitors;
+        }
+    }
+
+    /// Physical pixels per logical pixel of the window's current monitor.
+    #[inline]
+    pub fn scale_factor(&self) -> f64 {
+        self.0.lock().map(|g| g.scale_factor).unwrap_or(1.0)
+    }
+
+    /// Records the window scale factor (platform side).
+    pub fn set_scale_factor(&self, scale_factor: f64) {
+        if let Ok(mut g) = self.0.lock() {
+            g.scale_factor = scale_factor;
+        }
+    }
+
+    fn update(&self, f: impl FnOnce(&mut CursorState)) {
+        let Ok(mut g) = self.0.lock() else {
+            log::warn!("window.cursor update failed: mutex poisoned");
+            return;
+        };
+        let before = g.cursor;
+        f(&mut g.cursor);
+        if g.cursor != before {
+            g.dirty = true;
+        }
+    }
+}
+
+static WINDOW_API: OnceLock<WindowApi> = OnceLock::new();
+
+/// Process-wide window API handle (one window per process).
+#[inline]
+pub fn window_api() -> WindowApi {
+    WINDOW_API.get_or_init(WindowApi::new).clone()
+}
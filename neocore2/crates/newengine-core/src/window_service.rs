@@ -0,0 +1,157 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use crate::plugins::host_api;
+use crate::window::{window_api, MonitorInfo, WindowMode};
+use abi_stable::std_types::{RResult, RString};
+use newengine_plugin_api::{
+    guarded_service, Blob, CapabilityId, MethodName, ServiceV1, ServiceV1Dyn,
+};
+use serde::Serialize;
+use serde_json::json;
+
+pub const WINDOW_SERVICE_ID: &str = "engine.window";
+
+pub mod method {
+    pub const MODE_JSON: &str = "window.mode_json";
+    pub const SET_MODE: &str = "window.set_mode";
+    pub const MONITORS_JSON: &str = "window.monitors_json";
+}
+
+#[derive(Debug, Serialize)]
+struct WindowModeResp {
+    ok: bool,
+    mode: &'static str,
+    monitor: Option<usize>,
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct MonitorsResp {
+    monitors: Vec<MonitorInfo>,
+}
+
+struct WindowService;
+
+impl WindowService {
+    fn mode_resp(mode: WindowMode, error: Option<String>) -> WindowModeResp {
+        WindowModeResp {
+            ok: error.is_none(),
+            mode: mode.as_str(),
+            monitor: mode.monitor(),
+            error,
+        }
+    }
+
+    /// Payload: `[toggle|off|windowed|borderless|exclusive] [monitor]`; empty toggles.
+    fn parse_mode(arg: &str) -> Result<Option<WindowMode>, String> {
+        let mut it = arg.split_whitespace();
+        let kind = it.next().unwrap_or("toggle").to_ascii_lowercase();
+
+        let monitor = match it.next() {
+            Some(m) => Some(
+                m.parse::<usize>()
+                    .map_err(|_| format!("invalid monitor index: '{m}'"))?,
+            ),
+            None => None,
+        };
+
+        let mode = match kind.as_str() {
+            "toggle" => return Ok(None),
+            "off" | "windowed" => WindowMode::Windowed,
+            "on" | "borderless" => WindowMode::Borderless { monitor },
+            "exclusive" => WindowMode::Exclusive { monitor },
+            other => return Err(format!("unknown window mode: '{other}'")),
+        };
+
+        if let Some(i) = monitor {
+            let count = window_api().monitors().len();
+            if i >= count {
+                return Err(format!("monitor {i} out of range (count={count})"));
+            }
+        }
+
+        Ok(Some(mode))
+    }
+}
+
+impl ServiceV1 for WindowService {
+    fn id(&self) -> CapabilityId {
+        RString::from(WINDOW_SERVICE_ID)
+    }
+
+    fn describe(&self) -> RString {
+        let d = json!({
+          "id": WINDOW_SERVICE_ID,
+          "version": 1,
+          "methods": [
+            { "name": method::MODE_JSON, "payload": "empty", "returns": "json WindowModeResp" },
+            { "name": method::SET_MODE, "payload": "utf8 '[toggle|off|borderless|exclusive] [monitor]'", "returns": "json WindowModeResp" },
+            { "name": method::MONITORS_JSON, "payload": "empty", "returns": "json MonitorsResp" }
+          ],
+          "console": {
+            "commands": [
+              {
+                "name": "window.fullscreen",
+                "help": "Switch window mode: window.fullscreen [toggle|off|borderless|exclusive] [monitor]",
+                "usage": "window.fullscreen [toggle|off|borderless|exclusive] [monitor]",
+                "kind": "service_call",
+                "service_id": WINDOW_SERVICE_ID,
+                "method": method::SET_MODE,
+                "payload": "raw"
+              },
+              {
+                "name": "window.mode",
+                "help": "Show current window mode",
+                "kind": "service_call",
+                "service_id": WINDOW_SERVICE_ID,
+                "method": method::MODE_JSON,
+                "payload": "empty"
+              },
+              {
+                "name": "window.monitors",
+                "help": "List connected monitors",
+                "kind": "service_call",
+                "service_id": WINDOW_SERVICE_ID,
+                "method": method::MONITORS_JSON,
+                "payload": "empty"
+              }
+            ]
+          }
+        });
+
+        RString::from(d.to_string())
+    }
+
+    fn call(&self, method: MethodName, payload: Blob) -> RResult<Blob, RString> {
+        let m = method.to_string();
+        let api = window_api();
+
+        let resp = match m.as_str() {
+            method::MODE_JSON => serde_json::to_vec(&Self::mode_resp(api.mode(), None)),
+            method::SET_MODE => {
+                let arg = String::from_utf8_lossy(payload.as_slice());
+                let resp = match Self::parse_mode(&arg) {
+                    Ok(Some(mode)) => {
+                        api.set_mode(mode);
+                        Self::mode_resp(mode, None)
+                    }
+                    Ok(None) => Self::mode_resp(api.toggle_fullscreen(), None),
+                    Err(e) => Self::mode_resp(api.mode(), Some(e)),
+                };
+                serde_json::to_vec(&resp)
+            }
+            method::MONITORS_JSON => serde_json::to_vec(&MonitorsResp {
+                monitors: api.monitors(),
+            }),
+            _ => return RResult::RErr(RString::from(format!("unknown method: {m}"))),
+        };
+
+        RResult::ROk(Blob::from(resp.unwrap_or_default()))
+    }
+}
+
+pub fn register_window_service() {
+    let dyn_svc: ServiceV1Dyn<'static> = guarded_service(WindowService);
+
+    let _ = host_api::host_register_service_impl(dyn_svc, false);
+}
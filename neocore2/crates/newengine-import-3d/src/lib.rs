@@ -3,3 +3,4 @@
 pub mod module;
 pub mod plugin;
 pub mod providers;
+pub mod settings;
@@ -1,16 +1,16 @@
 #![forbid(unsafe_op_in_unsafe_fn)]
 
-use abi_stable::sabi_trait::TD_Opaque;
 use abi_stable::std_types::{RResult, RString, RVec};
 use abi_stable::StableAbi;
 
 use newengine_plugin_api::{
-    Blob, HostApiV1, MethodName, PluginInfo, PluginModule, ServiceV1, ServiceV1Dyn, ServiceV1_TO,
+    guarded_service, Blob, HostApiV1, MethodName, PluginInfo, PluginModule, ServiceV1, ServiceV1Dyn,
 };
 
 use std::sync::OnceLock;
 
 use crate::providers;
+use crate::settings::ImportSettings;
 
 /* =============================================================================================
 Wire: [u32 meta_len_le][meta_json utf8][payload bytes]
@@ -28,21 +28,50 @@ pub(crate) fn pack_wire(meta_json: &str, payload: &[u8]) -> Vec<u8> {
     out
 }
 
+/// Host probe for a cancelled import (`HostApiV1::import_cancelled`), set in `init`.
+static IMPORT_CANCELLED: OnceLock<extern "C" fn() -> bool> = OnceLock::new();
+
+/// Fails once the host cancelled the import in progress; checked between conversion stages so
+/// a large file stops without running the rest of the pipeline.
+#[inline]
+pub(crate) fn check_cancelled() -> Result<(), String> {
+    match IMPORT_CANCELLED.get() {
+        Some(cancelled) if cancelled() => Err("3d: import cancelled".to_owned()),
+        _ => Ok(()),
+    }
+}
+
 #[inline]
 fn err(msg: impl Into<String>) -> RResult<RVec<u8>, RString> {
     RResult::RErr(RString::from(msg.into()))
 }
 
-fn import_auto(bytes: &[u8]) -> RResult<RVec<u8>, RString> {
+/// Inverse of the wire for `import_3d_v2` input: `[u32 settings_len_le][settings_json][bytes]`.
+#[inline]
+fn unpack_settings(frame: &[u8]) -> Result<(ImportSettings, &[u8]), String> {
+    if frame.len() < 4 {
+        return Err("3d: settings frame too small".to_owned());
+    }
+    let len = u32::from_le_bytes([frame[0], frame[1], frame[2], frame[3]]) as usize;
+    let end = 4usize.saturating_add(len);
+    if frame.len() < end {
+        return Err("3d: truncated import settings".to_owned());
+    }
+    let json = std::str::from_utf8(&frame[4..end])
+        .map_err(|_| "3d: import settings are not utf8".to_owned())?;
+    Ok((ImportSettings::from_json(json)?, &frame[end..]))
+}
+
+fn import_auto(bytes: &[u8], settings: &ImportSettings) -> RResult<RVec<u8>, RString> {
     for p in providers::iter_providers() {
         if p.sniff(bytes) {
-            return p.import(bytes);
+            return p.import(bytes, settings);
         }
     }
 
     // Fallback: try parsers even if sniffing failed (helps with edge cases).
     for p in providers::iter_providers() {
-        let r = p.import(bytes);
+        let r = p.import(bytes, settings);
         if r.is_ok() {
             return r;
         }
@@ -109,15 +138,19 @@ impl ThreeDImporterService {
     "format":"3d",
     "method":"import_3d_v1",
     "wire":"u32_meta_len_le + meta_utf8 + payload",
+    "settings_method":"import_3d_v2",
+    "default_settings":{settings_json},
     "formats":{formats_json}
   }},
   "methods":{{
-    "import_3d_v1":{{"in":"3d bytes (auto sniff)","out":"[u32 meta_len_le][meta_json][payload]"}}
+    "import_3d_v1":{{"in":"3d bytes (auto sniff), default settings","out":"[u32 meta_len_le][meta_json][payload]"}},
+    "import_3d_v2":{{"in":"[u32 settings_len_le][settings_json][3d bytes]","out":"[u32 meta_len_le][meta_json][payload]"}}
   }},
   "meta_schema":"kalitech.model3d.meta.v1"
 }}"#,
                     exts_json = exts_json,
                     formats_json = formats_json,
+                    settings_json = ImportSettings::default().to_json(),
                 )
             })
             .as_str()
@@ -136,7 +169,11 @@ impl ServiceV1 for ThreeDImporterService {
     fn call(&self, method: MethodName, payload: Blob) -> RResult<Blob, RString> {
         let bytes: Vec<u8> = payload.into_vec();
         match method.as_str() {
-            "import_3d_v1" => import_auto(&bytes).map(|v| v),
+            "import_3d_v1" => import_auto(&bytes, &ImportSettings::default()).map(|v| v),
+            "import_3d_v2" => match unpack_settings(&bytes) {
+                Ok((settings, input)) => import_auto(input, &settings),
+                Err(e) => err(e),
+            },
             _ => RResult::RErr(RString::from(format!(
                 "3d-importer: unknown method '{}'",
                 method
@@ -154,11 +191,14 @@ impl PluginModule for ThreeDImporterPlugin {
             id: RString::from("import.3d"),
             name: RString::from("3D Importer (.obj/.fbx/.glb/.gltf)"),
             version: RString::from(env!("CARGO_PKG_VERSION")),
+            requires: RVec::new(),
         }
     }
 
     fn init(&mut self, host: HostApiV1) -> RResult<(), RString> {
-        let svc: ServiceV1Dyn<'static> = ServiceV1_TO::from_value(ThreeDImporterService, TD_Opaque);
+        let _ = IMPORT_CANCELLED.set(host.import_cancelled);
+
+        let svc: ServiceV1Dyn<'static> = guarded_service(ThreeDImporterService);
 
         let r = (host.register_service_v1)(svc);
         if let Err(e) = r.clone().into_result() {
@@ -0,0 +1,87 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use abi_stable::std_types::{RResult, RString, RVec};
+
+use super::optimize::optimize;
+use super::Provider;
+use crate::module::check_cancelled;
+use crate::settings::ImportSettings;
+
+mod reader;
+mod scene;
+
+pub(crate) struct FbxProvider;
+
+impl FbxProvider {
+    #[inline]
+    fn is_binary(bytes: &[u8]) -> bool {
+        bytes.starts_with(reader::BINARY_MAGIC)
+    }
+
+    /// ASCII FBX commonly starts with ';' comments like "; FBX 7.4.0 project file".
+    fn is_ascii(bytes: &[u8]) -> bool {
+        let prefix = &bytes[..bytes.len().min(256)];
+        let Ok(s) = std::str::from_utf8(prefix) else { return false; };
+        let t = s.trim_start();
+        t.starts_with(';') && t.contains("FBX")
+    }
+
+    fn convert(bytes: &[u8], settings: &ImportSettings) -> Result<(String, Vec<u8>), String> {
+        if !Self::is_binary(bytes) {
+            if Self::is_ascii(bytes) {
+                return Err("fbx: ASCII FBX is not supported; re-export as binary".to_owned());
+            }
+            return Err("fbx: not an fbx container".to_owned());
+        }
+
+        let doc = reader::parse(bytes)?;
+        check_cancelled()?;
+        let (mut mesh, info) = scene::extract(&doc)?;
+        check_cancelled()?;
+        let report = optimize(&mut mesh, settings);
+
+        let meta = format!(
+            "{{\"schema\":\"kalitech.model3d.meta.v1\",\"container\":\"fbx\",\"format\":\"ne3d_mesh\",\"mesh\":{},\"fbx\":{{\"version\":{},\"models\":{},\"geometries\":{},\"materials\":{},\"unit_scale\":{:.6},\"up_axis\":{},\"skipped_polygons\":{}}},\"optimize\":{}}}",
+            mesh.meta_json(),
+            doc.version,
+            info.models,
+            info.geometries,
+            info.materials,
+            info.unit_scale,
+            info.up_axis,
+            info.skipped_polygons,
+            report.to_json()
+        );
+
+        Ok((meta, mesh.write()))
+    }
+}
+
+impl Provider for FbxProvider {
+    fn name(&self) -> &'static str {
+        "fbx"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["fbx"]
+    }
+
+    fn sniff(&self, bytes: &[u8]) -> bool {
+        // ASCII files are claimed too so the import reports why they are rejected.
+        Self::is_binary(bytes) || Self::is_ascii(bytes)
+    }
+
+    fn import(&self, bytes: &[u8], settings: &ImportSettings) -> RResult<RVec<u8>, RString> {
+        match Self::convert(bytes, settings) {
+            Ok((meta, payload)) => {
+                let packed = super::super::module::pack_wire(&meta, &payload);
+                RResult::ROk(RVec::from(packed))
+            }
+            Err(e) => RResult::RErr(RString::from(e)),
+        }
+    }
+
+    fn describe_json(&self) -> &'static str {
+        r#"{"name":"fbx","container":"fbx","notes":"Binary FBX 7.x converted to NE3D mesh (little-endian): mesh geometry with world transforms baked in (meters, Y up), Lambert/Phong materials mapped to metallic-roughness, one submesh per material. ASCII FBX, skins and animation are not imported.","ne3d":{"version":4}}"#
+    }
+}
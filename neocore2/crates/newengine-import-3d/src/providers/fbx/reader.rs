@@ -0,0 +1,306 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+pub(super) const BINARY_MAGIC: &[u8; 18] = b"Kaydara FBX Binary";
+
+/// Header: 21 bytes magic (with padding and NUL), 0x1A 0x00, u32 version.
+const HEADER_LEN: usize = 27;
+
+/// Files from 7.5 on use 64-bit node record offsets.
+const WIDE_OFFSETS_VERSION: u32 = 7500;
+
+/// Upper bound for one decompressed array property (256 MiB).
+const MAX_ARRAY_BYTES: usize = 256 << 20;
+
+const MAX_DEPTH: usize = 64;
+
+#[derive(Debug, Clone)]
+pub(super) enum Property {
+    Bool(bool),
+    I16(i16),
+    I32(i32),
+    I64(i64),
+    F32(f32),
+    F64(f64),
+    Str(String),
+    /// Raw blobs and bool arrays are skipped; nothing imported reads them yet.
+    Raw,
+    BoolArray,
+    I32Array(Vec<i32>),
+    I64Array(Vec<i64>),
+    F32Array(Vec<f32>),
+    F64Array(Vec<f64>),
+}
+
+impl Property {
+    pub fn as_i64(&self) -> Option<i64> {
+        match *self {
+            Self::I16(v) => Some(v as i64),
+            Self::I32(v) => Some(v as i64),
+            Self::I64(v) => Some(v),
+            Self::Bool(v) => Some(v as i64),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match *self {
+            Self::F32(v) => Some(v as f64),
+            Self::F64(v) => Some(v),
+            _ => self.as_i64().map(|v| v as f64),
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Self::Str(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn to_f64_vec(&self) -> Option<Vec<f64>> {
+        match self {
+            Self::F64Array(v) => Some(v.clone()),
+            Self::F32Array(v) => Some(v.iter().map(|&x| x as f64).collect()),
+            Self::I32Array(v) => Some(v.iter().map(|&x| x as f64).collect()),
+            Self::I64Array(v) => Some(v.iter().map(|&x| x as f64).collect()),
+            _ => None,
+        }
+    }
+
+    pub fn to_i32_vec(&self) -> Option<Vec<i32>> {
+        match self {
+            Self::I32Array(v) => Some(v.clone()),
+            Self::I64Array(v) => Some(v.iter().map(|&x| x as i32).collect()),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub(super) struct Node {
+    pub name: String,
+    pub props: Vec<Property>,
+    pub children: Vec<Node>,
+}
+
+impl Node {
+    pub fn child(&self, name: &str) -> Option<&Node> {
+        self.children.iter().find(|c| c.name == name)
+    }
+
+    pub fn children_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Node> + 'a {
+        self.children.iter().filter(move |c| c.name == name)
+    }
+
+    #[inline]
+    pub fn prop(&self, i: usize) -> Option<&Property> {
+        self.props.get(i)
+    }
+
+    /// First property of the child `name`, e.g. `MappingInformationType`.
+    pub fn child_prop(&self, name: &str) -> Option<&Property> {
+        self.child(name).and_then(|c| c.prop(0))
+    }
+}
+
+/// Parsed binary FBX: version and the top-level nodes (`FBXHeaderExtension`, `Objects`, ...).
+pub(super) struct Document {
+    pub version: u32,
+    pub nodes: Vec<Node>,
+}
+
+impl Document {
+    pub fn node(&self, name: &str) -> Option<&Node> {
+        self.nodes.iter().find(|n| n.name == name)
+    }
+}
+
+pub(super) fn parse(bytes: &[u8]) -> Result<Document, String> {
+    if bytes.len() < HEADER_LEN || &bytes[..BINARY_MAGIC.len()] != BINARY_MAGIC {
+        return Err("fbx: missing binary header".to_owned());
+    }
+    let version = u32::from_le_bytes([bytes[23], bytes[24], bytes[25], bytes[26]]);
+
+    let mut r = Reader {
+        bytes,
+        at: HEADER_LEN,
+        wide: version >= WIDE_OFFSETS_VERSION,
+    };
+
+    let mut nodes = Vec::new();
+    while let Some(n) = r.node(0)? {
+        nodes.push(n);
+    }
+
+    Ok(Document { version, nodes })
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    at: usize,
+    wide: bool,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize, what: &str) -> Result<&'a [u8], String> {
+        let end = self
+            .at
+            .checked_add(len)
+            .filter(|&e| e <= self.bytes.len())
+            .ok_or_else(|| format!("fbx: truncated {what} at byte {}", self.at))?;
+        let s = &self.bytes[self.at..end];
+        self.at = end;
+        Ok(s)
+    }
+
+    fn u8(&mut self, what: &str) -> Result<u8, String> {
+        Ok(self.take(1, what)?[0])
+    }
+
+    fn u32(&mut self, what: &str) -> Result<u32, String> {
+        let b = self.take(4, what)?;
+        Ok(u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    fn offset(&mut self, what: &str) -> Result<u64, String> {
+        if self.wide {
+            let b = self.take(8, what)?;
+            Ok(u64::from_le_bytes([
+                b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7],
+            ]))
+        } else {
+            self.u32(what).map(u64::from)
+        }
+    }
+
+    /// `None` at the null record that closes a node list (or at the end of the file).
+    fn node(&mut self, depth: usize) -> Result<Option<Node>, String> {
+        if depth > MAX_DEPTH {
+            return Err("fbx: node nesting too deep".to_owned());
+        }
+        if self.at >= self.bytes.len() {
+            return Ok(None);
+        }
+
+        let start = self.at;
+        let end = self.offset("node end offset")?;
+        let prop_count = self.offset("node property count")?;
+        let _prop_bytes = self.offset("node property length")?;
+        let name_len = self.u8("node name length")? as usize;
+
+        if end == 0 {
+            // Null record; the top level may also end with the footer here.
+            return Ok(None);
+        }
+        let end = usize::try_from(end).map_err(|_| "fbx: bad node offset".to_owned())?;
+        if end <= start || end > self.bytes.len() {
+            return Err(format!(
+                "fbx: node at byte {start} ends out of bounds ({end})"
+            ));
+        }
+
+        let name = String::from_utf8_lossy(self.take(name_len, "node name")?).into_owned();
+
+        let mut props = Vec::new();
+        for _ in 0..prop_count {
+            if self.at >= end {
+                return Err(format!("fbx: properties of '{name}' overrun the node"));
+            }
+            props.push(self.property()?);
+        }
+
+        let mut children = Vec::new();
+        while self.at < end {
+            match self.node(depth + 1)? {
+                Some(c) => children.push(c),
+                None => break,
+            }
+        }
+        self.at = end;
+
+        Ok(Some(Node {
+            name,
+            props,
+            children,
+        }))
+    }
+
+    fn property(&mut self) -> Result<Property, String> {
+        let ty = self.u8("property type")?;
+        let p = match ty {
+            b'C' => Property::Bool(self.u8("bool")? != 0),
+            b'Y' => {
+                let b = self.take(2, "i16")?;
+                Property::I16(i16::from_le_bytes([b[0], b[1]]))
+            }
+            b'I' => Property::I32(self.u32("i32")? as i32),
+            b'F' => Property::F32(f32::from_bits(self.u32("f32")?)),
+            b'L' => Property::I64(i64::from_le_bytes(self.take(8, "i64")?.try_into().unwrap())),
+            b'D' => Property::F64(f64::from_le_bytes(self.take(8, "f64")?.try_into().unwrap())),
+            b'S' => {
+                let len = self.u32("string length")? as usize;
+                Property::Str(String::from_utf8_lossy(self.take(len, "string")?).into_owned())
+            }
+            b'R' => {
+                let len = self.u32("raw length")? as usize;
+                self.take(len, "raw")?;
+                Property::Raw
+            }
+            b'b' => {
+                self.array(1, |_| ())?;
+                Property::BoolArray
+            }
+            b'i' => {
+                Property::I32Array(self.array(4, |b| i32::from_le_bytes(b.try_into().unwrap()))?)
+            }
+            b'l' => {
+                Property::I64Array(self.array(8, |b| i64::from_le_bytes(b.try_into().unwrap()))?)
+            }
+            b'f' => {
+                Property::F32Array(self.array(4, |b| f32::from_le_bytes(b.try_into().unwrap()))?)
+            }
+            b'd' => {
+                Property::F64Array(self.array(8, |b| f64::from_le_bytes(b.try_into().unwrap()))?)
+            }
+            other => {
+                return Err(format!(
+                    "fbx: unknown property type 0x{other:02x} at byte {}",
+                    self.at - 1
+                ))
+            }
+        };
+        Ok(p)
+    }
+
+    /// `u32 count, u32 encoding (0 raw, 1 zlib), u32 byte_len, data`.
+    fn array<T>(&mut self, elem: usize, read: impl Fn(&[u8]) -> T) -> Result<Vec<T>, String> {
+        let count = self.u32("array length")? as usize;
+        let encoding = self.u32("array encoding")?;
+        let byte_len = self.u32("array byte length")? as usize;
+        let data = self.take(byte_len, "array data")?;
+
+        let raw_len = count
+            .checked_mul(elem)
+            .filter(|&n| n <= MAX_ARRAY_BYTES)
+            .ok_or_else(|| format!("fbx: array of {count} elements is too large"))?;
+
+        let inflated;
+        let raw = match encoding {
+            0 => data,
+            1 => {
+                inflated = miniz_oxide::inflate::decompress_to_vec_zlib_with_limit(data, raw_len)
+                    .map_err(|e| format!("fbx: array inflate failed: {e:?}"))?;
+                &inflated[..]
+            }
+            e => return Err(format!("fbx: unknown array encoding {e}")),
+        };
+        if raw.len() < raw_len {
+            return Err(format!(
+                "fbx: array holds {} bytes, expected {raw_len}",
+                raw.len()
+            ));
+        }
+
+        Ok(raw[..raw_len].chunks_exact(elem).map(read).collect())
+    }
+}
@@ -0,0 +1,565 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use std::collections::HashMap;
+
+use super::reader::{Document, Node, Property};
+use crate::module::check_cancelled;
+use crate::providers::ne3d::{AlphaMode, Material, Ne3dMesh, Submesh};
+
+/// `Properties70` and the object layout this module relies on arrived with FBX 7.0.
+pub(super) const MIN_VERSION: u32 = 7000;
+
+/// Scene-wide settings reported in the importer meta.
+pub(super) struct SceneInfo {
+    pub models: usize,
+    pub geometries: usize,
+    pub materials: usize,
+    /// Centimeters per file unit (FBX default 1.0); positions are exported in meters.
+    pub unit_scale: f64,
+    pub up_axis: i64,
+    pub skipped_polygons: usize,
+}
+
+struct Model<'a> {
+    parent: Option<i64>,
+    props: Option<&'a Node>,
+    geometries: Vec<i64>,
+    materials: Vec<i64>,
+}
+
+/// Triangles of one (model, material slot) pair, already baked into scene space.
+#[derive(Default)]
+struct Part {
+    positions: Vec<[f32; 3]>,
+    normals: Vec<[f32; 3]>,
+    uvs: Vec<[f32; 2]>,
+    indices: Vec<u32>,
+    material: Option<u32>,
+}
+
+/// How a layer element (normals, uvs, material ids) maps onto the polygon mesh.
+struct Layer<'a> {
+    mapping: &'a str,
+    indexed: bool,
+    index: Vec<i32>,
+}
+
+impl Layer<'_> {
+    fn read<'n>(element: &'n Node, index_name: &str) -> Layer<'n> {
+        let mapping = element
+            .child_prop("MappingInformationType")
+            .and_then(Property::as_str);
+        let reference = element
+            .child_prop("ReferenceInformationType")
+            .and_then(Property::as_str);
+        Layer {
+            mapping: mapping.unwrap_or("ByPolygonVertex"),
+            indexed: matches!(reference, Some("IndexToDirect" | "Index")),
+            index: element
+                .child_prop(index_name)
+                .and_then(Property::to_i32_vec)
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Element for polygon-vertex `pv` (control point `cp`, polygon `poly`).
+    fn element(&self, pv: usize, cp: usize, poly: usize) -> Option<usize> {
+        let i = match self.mapping {
+            "ByPolygonVertex" => pv,
+            "ByVertex" | "ByVertice" | "ByControlPoint" => cp,
+            "ByPolygon" => poly,
+            "AllSame" => 0,
+            _ => return None,
+        };
+        if self.indexed {
+            usize::try_from(*self.index.get(i)?).ok()
+        } else {
+            Some(i)
+        }
+    }
+}
+
+pub(super) fn extract(doc: &Document) -> Result<(Ne3dMesh, SceneInfo), String> {
+    if doc.version < MIN_VERSION {
+        return Err(format!(
+            "fbx: version {}.{} is too old (7.0 or newer required)",
+            doc.version / 1000,
+            doc.version % 1000 / 100
+        ));
+    }
+
+    let objects = doc
+        .node("Objects")
+        .ok_or_else(|| "fbx: missing Objects section".to_owned())?;
+
+    let mut geometries: HashMap<i64, &Node> = HashMap::new();
+    let mut models: HashMap<i64, Model> = HashMap::new();
+    let mut materials: HashMap<i64, &Node> = HashMap::new();
+    for n in &objects.children {
+        let Some(id) = n.prop(0).and_then(Property::as_i64) else {
+            continue;
+        };
+        match n.name.as_str() {
+            "Geometry" if n.prop(2).and_then(Property::as_str) == Some("Mesh") => {
+                geometries.insert(id, n);
+            }
+            "Model" => {
+                models.insert(
+                    id,
+                    Model {
+                        parent: None,
+                        props: n.child("Properties70"),
+                        geometries: Vec::new(),
+                        materials: Vec::new(),
+                    },
+                );
+            }
+            "Material" => {
+                materials.insert(id, n);
+            }
+            _ => {}
+        }
+    }
+
+    if let Some(conns) = doc.node("Connections") {
+        for c in conns.children_named("C") {
+            if c.prop(0).and_then(Property::as_str) != Some("OO") {
+                continue;
+            }
+            let (Some(child), Some(parent)) = (
+                c.prop(1).and_then(Property::as_i64),
+                c.prop(2).and_then(Property::as_i64),
+            ) else {
+                continue;
+            };
+            if !models.contains_key(&parent) {
+                continue;
+            }
+            if geometries.contains_key(&child) {
+                models.get_mut(&parent).unwrap().geometries.push(child);
+            } else if materials.contains_key(&child) {
+                models.get_mut(&parent).unwrap().materials.push(child);
+            } else if let Some(m) = models.get_mut(&child) {
+                m.parent = Some(parent);
+            }
+        }
+    }
+
+    let settings = doc
+        .node("GlobalSettings")
+        .and_then(|g| g.child("Properties70"));
+    let unit_scale = p70_f64(settings, "UnitScaleFactor").unwrap_or(1.0);
+    let up_axis = p70_f64(settings, "UpAxis").map_or(1, |v| v as i64);
+    let up_sign = p70_f64(settings, "UpAxisSign").unwrap_or(1.0);
+
+    // Centimeters to meters, then the file's up axis onto +Y.
+    let s = unit_scale / 100.0;
+    let mut root = scale(s);
+    if up_axis == 2 {
+        root = mat_mul(&rotation_deg([-90.0 * up_sign, 0.0, 0.0]), &root);
+    } else if up_axis == 0 {
+        root = mat_mul(&rotation_deg([0.0, 0.0, 90.0 * up_sign]), &root);
+    } else if up_sign < 0.0 {
+        root = mat_mul(&rotation_deg([180.0, 0.0, 0.0]), &root);
+    }
+
+    let mut material_index: HashMap<i64, u32> = HashMap::new();
+    let mut out_materials: Vec<Material> = Vec::new();
+    let mut parts: Vec<Part> = Vec::new();
+    let mut skipped_polygons = 0usize;
+
+    // Sorted so the output does not depend on hash order.
+    let mut ids: Vec<i64> = models.keys().copied().collect();
+    ids.sort_unstable();
+
+    for id in ids {
+        let model = &models[&id];
+        if model.geometries.is_empty() {
+            continue;
+        }
+
+        let world = mat_mul(&root, &world_matrix(&models, id));
+        let geometric = model.props.map_or(IDENTITY, geometric_matrix);
+        let m = mat_mul(&world, &geometric);
+
+        let slots: Vec<Option<u32>> = model
+            .materials
+            .iter()
+            .map(|mid| {
+                let next = out_materials.len() as u32;
+                let idx = *material_index.entry(*mid).or_insert(next);
+                if idx == next {
+                    out_materials.push(read_material(materials[mid]));
+                }
+                Some(idx)
+            })
+            .collect();
+
+        for gid in &model.geometries {
+            check_cancelled()?;
+            skipped_polygons += read_geometry(geometries[gid], &m, &slots, &mut parts)?;
+        }
+    }
+
+    if parts.iter().all(|p| p.indices.is_empty()) {
+        return Err("fbx: no mesh geometry".to_owned());
+    }
+
+    let mut mesh = Ne3dMesh::default();
+    let has_uvs = parts.iter().any(|p| !p.uvs.is_empty());
+    for p in parts.into_iter().filter(|p| !p.indices.is_empty()) {
+        let base = mesh.positions.len() as u32;
+        let n = p.positions.len();
+        mesh.normals.extend(p.normals);
+        if has_uvs {
+            if p.uvs.is_empty() {
+                mesh.uvs.extend(std::iter::repeat_n([0.0; 2], n));
+            } else {
+                mesh.uvs.extend(p.uvs);
+            }
+        }
+        mesh.positions.extend(p.positions);
+
+        let first_index = mesh.indices.len() as u32;
+        mesh.indices.extend(p.indices.iter().map(|&i| base + i));
+        mesh.submeshes.push(Submesh {
+            first_index,
+            index_count: p.indices.len() as u32,
+            material: p.material,
+        });
+    }
+    mesh.materials = out_materials;
+
+    let info = SceneInfo {
+        models: models.len(),
+        geometries: geometries.len(),
+        materials: mesh.materials.len(),
+        unit_scale,
+        up_axis,
+        skipped_polygons,
+    };
+    Ok((mesh, info))
+}
+
+/// Appends one part per material slot. Returns the number of degenerate polygons skipped.
+fn read_geometry(
+    geo: &Node,
+    m: &[f64; 16],
+    slots: &[Option<u32>],
+    parts: &mut Vec<Part>,
+) -> Result<usize, String> {
+    let vertices = geo
+        .child_prop("Vertices")
+        .and_then(Property::to_f64_vec)
+        .ok_or_else(|| "fbx: geometry without Vertices".to_owned())?;
+    let polygon_index = geo
+        .child_prop("PolygonVertexIndex")
+        .and_then(Property::to_i32_vec)
+        .ok_or_else(|| "fbx: geometry without PolygonVertexIndex".to_owned())?;
+    let cp_count = vertices.len() / 3;
+
+    let normal_el = geo.child("LayerElementNormal");
+    let normals = normal_el
+        .and_then(|e| e.child_prop("Normals"))
+        .and_then(Property::to_f64_vec)
+        .unwrap_or_default();
+    let normal_layer = normal_el.map(|e| Layer::read(e, "NormalsIndex"));
+
+    let uv_el = geo.child("LayerElementUV");
+    let uvs = uv_el
+        .and_then(|e| e.child_prop("UV"))
+        .and_then(Property::to_f64_vec)
+        .unwrap_or_default();
+    let uv_layer = uv_el.map(|e| Layer::read(e, "UVIndex"));
+
+    let mat_el = geo.child("LayerElementMaterial");
+    let mat_ids = mat_el
+        .and_then(|e| e.child_prop("Materials"))
+        .and_then(Property::to_i32_vec)
+        .unwrap_or_default();
+    let mat_mapping = mat_el
+        .and_then(|e| e.child_prop("MappingInformationType"))
+        .and_then(Property::as_str)
+        .unwrap_or("AllSame");
+
+    let first_part = parts.len();
+    let part_count = slots.len().max(1);
+    parts.extend((0..part_count).map(|i| Part {
+        material: slots.get(i).copied().flatten(),
+        ..Part::default()
+    }));
+
+    let flip = det3(m) < 0.0;
+    let mut skipped = 0usize;
+    let mut corners: Vec<(usize, usize)> = Vec::new();
+    let mut poly = 0usize;
+
+    for (pv, &raw) in polygon_index.iter().enumerate() {
+        // The last corner of each polygon is stored as `!index`.
+        let (cp, last) = if raw < 0 {
+            (!raw as usize, true)
+        } else {
+            (raw as usize, false)
+        };
+        if cp >= cp_count {
+            return Err(format!(
+                "fbx: polygon vertex {cp} out of range ({cp_count} vertices)"
+            ));
+        }
+        corners.push((pv, cp));
+        if !last {
+            continue;
+        }
+
+        if corners.len() < 3 {
+            skipped += 1;
+        } else {
+            let slot = match mat_mapping {
+                "ByPolygon" => mat_ids.get(poly).copied().unwrap_or(0),
+                _ => mat_ids.first().copied().unwrap_or(0),
+            };
+            let part = &mut parts[first_part + (slot.max(0) as usize).min(part_count - 1)];
+
+            let base = part.positions.len() as u32;
+            for &(pv, cp) in &corners {
+                let p = [vertices[cp * 3], vertices[cp * 3 + 1], vertices[cp * 3 + 2]];
+                part.positions.push(transform_point(m, p));
+
+                let n = normal_layer
+                    .as_ref()
+                    .and_then(|l| l.element(pv, cp, poly))
+                    .and_then(|i| normals.get(i * 3..i * 3 + 3))
+                    .map_or([0.0, 1.0, 0.0], |n| {
+                        normalize(transform_dir(m, [n[0], n[1], n[2]]))
+                    });
+                part.normals.push(n);
+
+                if let Some(l) = &uv_layer {
+                    let uv = l
+                        .element(pv, cp, poly)
+                        .and_then(|i| uvs.get(i * 2..i * 2 + 2))
+                        .map_or([0.0, 0.0], |t| [t[0] as f32, t[1] as f32]);
+                    part.uvs.push(uv);
+                }
+            }
+
+            // Fan triangulation; fine for the convex polygons DCC tools export.
+            for k in 1..corners.len() as u32 - 1 {
+                let (b, c) = if flip { (k + 1, k) } else { (k, k + 1) };
+                part.indices.extend_from_slice(&[base, base + b, base + c]);
+            }
+        }
+
+        corners.clear();
+        poly += 1;
+    }
+
+    Ok(skipped)
+}
+
+fn read_material(node: &Node) -> Material {
+    let props = node.child("Properties70");
+    let color = |name: &str| p70_vec3(props, name);
+
+    let diffuse = color("DiffuseColor")
+        .or_else(|| color("Diffuse"))
+        .unwrap_or([0.8; 3]);
+    let diffuse_factor = p70_f64(props, "DiffuseFactor").unwrap_or(1.0);
+    let emissive = color("EmissiveColor").unwrap_or([0.0; 3]);
+    let emissive_factor = p70_f64(props, "EmissiveFactor").unwrap_or(1.0);
+    let opacity = p70_f64(props, "Opacity").unwrap_or(1.0).clamp(0.0, 1.0);
+    // Phong exponent to a perceptual roughness.
+    let roughness = p70_f64(props, "ShininessExponent")
+        .or_else(|| p70_f64(props, "Shininess"))
+        .map_or(1.0, |s| (2.0 / (s.max(0.0) + 2.0)).sqrt());
+
+    Material {
+        name: object_name(node),
+        base_color: [
+            (diffuse[0] * diffuse_factor) as f32,
+            (diffuse[1] * diffuse_factor) as f32,
+            (diffuse[2] * diffuse_factor) as f32,
+            opacity as f32,
+        ],
+        metallic: 0.0,
+        roughness: roughness as f32,
+        emissive: emissive.map(|c| (c * emissive_factor) as f32),
+        alpha_mode: if opacity < 1.0 {
+            AlphaMode::Blend
+        } else {
+            AlphaMode::Opaque
+        },
+        alpha_cutoff: 0.5,
+        double_sided: false,
+        base_color_texture: None,
+        metallic_roughness_texture: None,
+        normal_texture: None,
+        normal_scale: 1.0,
+        occlusion_texture: None,
+        occlusion_strength: 1.0,
+        emissive_texture: None,
+    }
+}
+
+/// Binary FBX names are `Name\x00\x01Class`.
+fn object_name(node: &Node) -> String {
+    let raw = node.prop(1).and_then(Property::as_str).unwrap_or("");
+    raw.split("\u{0}\u{1}").next().unwrap_or("").to_owned()
+}
+
+/// `P: "Name", "Type", "Label", "Flags", values...`
+fn p70<'a>(props: Option<&'a Node>, name: &str) -> Option<&'a Node> {
+    props?
+        .children_named("P")
+        .find(|p| p.prop(0).and_then(Property::as_str) == Some(name))
+}
+
+fn p70_f64(props: Option<&Node>, name: &str) -> Option<f64> {
+    p70(props, name)?.prop(4)?.as_f64()
+}
+
+fn p70_vec3(props: Option<&Node>, name: &str) -> Option<[f64; 3]> {
+    let p = p70(props, name)?;
+    Some([
+        p.prop(4)?.as_f64()?,
+        p.prop(5)?.as_f64()?,
+        p.prop(6)?.as_f64()?,
+    ])
+}
+
+/// Parent chain of `Lcl Translation * PreRotation * Lcl Rotation * Lcl Scaling`.
+/// Pivots and rotation offsets are ignored.
+fn world_matrix(models: &HashMap<i64, Model>, id: i64) -> [f64; 16] {
+    let mut m = IDENTITY;
+    let mut at = Some(id);
+    let mut guard = 0;
+    while let Some(cur) = at {
+        let Some(model) = models.get(&cur) else { break };
+        m = mat_mul(&local_matrix(model.props), &m);
+        at = model.parent;
+        guard += 1;
+        if guard > 1024 {
+            break;
+        }
+    }
+    m
+}
+
+fn local_matrix(props: Option<&Node>) -> [f64; 16] {
+    let t = p70_vec3(props, "Lcl Translation").unwrap_or([0.0; 3]);
+    let r = p70_vec3(props, "Lcl Rotation").unwrap_or([0.0; 3]);
+    let s = p70_vec3(props, "Lcl Scaling").unwrap_or([1.0; 3]);
+    let pre = p70_vec3(props, "PreRotation").unwrap_or([0.0; 3]);
+    trs(t, &mat_mul(&rotation_deg(pre), &rotation_deg(r)), s)
+}
+
+/// Geometric transforms apply to the model's own geometry only.
+fn geometric_matrix(props: &Node) -> [f64; 16] {
+    let props = Some(props);
+    let t = p70_vec3(props, "GeometricTranslation").unwrap_or([0.0; 3]);
+    let r = p70_vec3(props, "GeometricRotation").unwrap_or([0.0; 3]);
+    let s = p70_vec3(props, "GeometricScaling").unwrap_or([1.0; 3]);
+    trs(t, &rotation_deg(r), s)
+}
+
+const IDENTITY: [f64; 16] = [
+    1.0, 0.0, 0.0, 0.0, //
+    0.0, 1.0, 0.0, 0.0, //
+    0.0, 0.0, 1.0, 0.0, //
+    0.0, 0.0, 0.0, 1.0,
+];
+
+/// Column-major `T * R * S`.
+fn trs(t: [f64; 3], r: &[f64; 16], s: [f64; 3]) -> [f64; 16] {
+    let mut m = *r;
+    for c in 0..3 {
+        for k in 0..3 {
+            m[c * 4 + k] *= s[c];
+        }
+    }
+    m[12] = t[0];
+    m[13] = t[1];
+    m[14] = t[2];
+    m
+}
+
+#[inline]
+fn scale(s: f64) -> [f64; 16] {
+    trs([0.0; 3], &IDENTITY, [s; 3])
+}
+
+/// FBX default `eEulerXYZ` order: X first, then Y, then Z (`Rz * Ry * Rx`), in degrees.
+fn rotation_deg(r: [f64; 3]) -> [f64; 16] {
+    let (sx, cx) = r[0].to_radians().sin_cos();
+    let (sy, cy) = r[1].to_radians().sin_cos();
+    let (sz, cz) = r[2].to_radians().sin_cos();
+    let rx = [
+        1.0, 0.0, 0.0, 0.0, //
+        0.0, cx, sx, 0.0, //
+        0.0, -sx, cx, 0.0, //
+        0.0, 0.0, 0.0, 1.0,
+    ];
+    let ry = [
+        cy, 0.0, -sy, 0.0, //
+        0.0, 1.0, 0.0, 0.0, //
+        sy, 0.0, cy, 0.0, //
+        0.0, 0.0, 0.0, 1.0,
+    ];
+    let rz = [
+        cz, sz, 0.0, 0.0, //
+        -sz, cz, 0.0, 0.0, //
+        0.0, 0.0, 1.0, 0.0, //
+        0.0, 0.0, 0.0, 1.0,
+    ];
+    mat_mul(&rz, &mat_mul(&ry, &rx))
+}
+
+/// Column-major `a * b`.
+fn mat_mul(a: &[f64; 16], b: &[f64; 16]) -> [f64; 16] {
+    let mut out = [0.0; 16];
+    for c in 0..4 {
+        for r in 0..4 {
+            out[c * 4 + r] = (0..4).map(|k| a[k * 4 + r] * b[c * 4 + k]).sum();
+        }
+    }
+    out
+}
+
+#[inline]
+fn transform_point(m: &[f64; 16], p: [f64; 3]) -> [f32; 3] {
+    let d = transform_dir(m, p);
+    [
+        (d[0] + m[12]) as f32,
+        (d[1] + m[13]) as f32,
+        (d[2] + m[14]) as f32,
+    ]
+}
+
+#[inline]
+fn transform_dir(m: &[f64; 16], v: [f64; 3]) -> [f64; 3] {
+    [
+        m[0] * v[0] + m[4] * v[1] + m[8] * v[2],
+        m[1] * v[0] + m[5] * v[1] + m[9] * v[2],
+        m[2] * v[0] + m[6] * v[1] + m[10] * v[2],
+    ]
+}
+
+#[inline]
+fn normalize(v: [f64; 3]) -> [f32; 3] {
+    let len = (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt();
+    if len > f64::EPSILON {
+        [
+            (v[0] / len) as f32,
+            (v[1] / len) as f32,
+            (v[2] / len) as f32,
+        ]
+    } else {
+        [0.0, 1.0, 0.0]
+    }
+}
+
+#[inline]
+fn det3(m: &[f64; 16]) -> f64 {
+    m[0] * (m[5] * m[10] - m[9] * m[6]) - m[4] * (m[1] * m[10] - m[9] * m[2])
+        + m[8] * (m[1] * m[6] - m[5] * m[2])
+}
@@ -1,11 +1,58 @@
 #![forbid(unsafe_op_in_unsafe_fn)]
 
 use abi_stable::std_types::{RResult, RString, RVec};
+use base64::Engine as _;
+use gltf::animation::util::ReadOutputs;
+use gltf::animation::Interpolation;
+use gltf::mesh::Mode;
 
+use super::ne3d::{
+    AlphaMode, Channel, ChannelPath, Clip, Joint, Material, Ne3dMesh, Submesh, Texture,
+};
+use super::optimize::optimize;
 use super::Provider;
+use crate::module::check_cancelled;
+use crate::settings::ImportSettings;
 
 pub(crate) struct GltfProvider;
 
+const IDENTITY: [f32; 16] = [
+    1.0, 0.0, 0.0, 0.0, //
+    0.0, 1.0, 0.0, 0.0, //
+    0.0, 0.0, 1.0, 0.0, //
+    0.0, 0.0, 0.0, 1.0,
+];
+
+/// Joints of the first skin, reordered so that parents come before their children.
+struct Skeleton {
+    skin_index: usize,
+    /// glTF node index -> NE3D joint index.
+    node_to_joint: Vec<Option<u16>>,
+    /// Skin joint slot (as referenced by `JOINTS_0`) -> NE3D joint index.
+    slot_to_joint: Vec<u16>,
+    joints: Vec<Joint>,
+}
+
+/// One triangle primitive, already in the space it is exported in.
+struct Part {
+    positions: Vec<[f32; 3]>,
+    normals: Option<Vec<[f32; 3]>>,
+    uvs: Option<Vec<[f32; 2]>>,
+    joints: Vec<[u16; 4]>,
+    weights: Vec<[f32; 4]>,
+    indices: Vec<u32>,
+    material: Option<u32>,
+}
+
+/// glTF images become NE3D textures on first use by a material.
+#[derive(Default)]
+struct TextureTable {
+    /// glTF image index -> NE3D texture index; `Some(None)` once an image failed to load.
+    by_image: Vec<Option<Option<u32>>>,
+    textures: Vec<Texture>,
+    skipped: usize,
+}
+
 impl GltfProvider {
     fn detect_container(bytes: &[u8]) -> Option<&'static str> {
         if bytes.len() >= 4 && &bytes[0..4] == b"glTF" {
@@ -22,63 +69,566 @@ impl GltfProvider {
         None
     }
 
-    fn validate(bytes: &[u8]) -> Result<(String, Vec<u8>), String> {
-        let container = Self::detect_container(bytes).ok_or_else(|| "gltf: not a gltf/glb".to_owned())?;
+    // NOTE: This importer operates on a single blob.
+    // For .gltf we only support data: URIs (embedded buffers/images). External references are rejected.
+    fn reject_external_uris(bytes: &[u8]) -> Result<(), String> {
+        let v: serde_json::Value = serde_json::from_slice(bytes)
+            .map_err(|e| format!("gltf: json parse failed: {e}"))?;
 
-        let gltf = gltf::Gltf::from_slice(bytes).map_err(|e| format!("gltf: parse failed: {e}"))?;
+        fn uri_is_external(uri: &str) -> bool {
+            let u = uri.trim();
+            !u.is_empty() && !u.starts_with("data:")
+        }
 
-        // NOTE: This importer operates on a single blob.
-        // For .gltf we only support data: URIs (embedded buffers/images). External references are rejected.
-        if container == "gltf" {
-            let v: serde_json::Value = serde_json::from_slice(bytes)
-                .map_err(|e| format!("gltf: json parse failed: {e}"))?;
-
-            fn uri_is_external(uri: &str) -> bool {
-                let u = uri.trim();
-                !u.is_empty() && !u.starts_with("data:")
-            }
-
-            if let Some(buffers) = v.get("buffers").and_then(|x| x.as_array()) {
-                for b in buffers {
-                    if let Some(uri) = b.get("uri").and_then(|x| x.as_str()) {
-                        if uri_is_external(uri) {
-                            return Err(
-                                "gltf: external buffer URIs are not supported (use .glb or embed data: URIs)".to_owned(),
-                            );
-                        }
+        if let Some(buffers) = v.get("buffers").and_then(|x| x.as_array()) {
+            for b in buffers {
+                if let Some(uri) = b.get("uri").and_then(|x| x.as_str()) {
+                    if uri_is_external(uri) {
+                        return Err(
+                            "gltf: external buffer URIs are not supported (use .glb or embed data: URIs)".to_owned(),
+                        );
                     }
                 }
             }
-            if let Some(images) = v.get("images").and_then(|x| x.as_array()) {
-                for img in images {
-                    if let Some(uri) = img.get("uri").and_then(|x| x.as_str()) {
-                        if uri_is_external(uri) {
-                            return Err(
-                                "gltf: external image URIs are not supported (use .glb or embed data: URIs)".to_owned(),
-                            );
-                        }
+        }
+        if let Some(images) = v.get("images").and_then(|x| x.as_array()) {
+            for img in images {
+                if let Some(uri) = img.get("uri").and_then(|x| x.as_str()) {
+                    if uri_is_external(uri) {
+                        return Err(
+                            "gltf: external image URIs are not supported (use .glb or embed data: URIs)".to_owned(),
+                        );
                     }
                 }
             }
         }
 
-        let doc = &gltf.document;
-        let scenes = doc.scenes().len();
-        let nodes = doc.nodes().len();
-        let meshes = doc.meshes().len();
-        let materials = doc.materials().len();
-        let textures = doc.textures().len();
-        let images = doc.images().len();
+        Ok(())
+    }
+
+    /// Flattens the default scene into one NE3D mesh.
+    ///
+    /// Static primitives are baked into world space. When the file has a skin, only the
+    /// primitives bound to the first skin are exported (in mesh space, as glTF skinning
+    /// expects) together with its joints and every animation channel that targets them.
+    fn convert(bytes: &[u8], settings: &ImportSettings) -> Result<(String, Vec<u8>), String> {
+        let container = Self::detect_container(bytes).ok_or_else(|| "gltf: not a gltf/glb".to_owned())?;
+
+        let gltf = gltf::Gltf::from_slice(bytes).map_err(|e| format!("gltf: parse failed: {e}"))?;
+        if container == "gltf" {
+            Self::reject_external_uris(bytes)?;
+        }
+
+        let gltf::Gltf { document: doc, blob } = gltf;
+        let buffers = gltf::import_buffers(&doc, None, blob)
+            .map_err(|e| format!("gltf: buffer load failed: {e}"))?;
+        check_cancelled()?;
+        let get = |b: gltf::Buffer| buffers.get(b.index()).map(|d| d.0.as_slice());
+
+        let skeleton = doc.skins().next().map(|skin| Self::read_skeleton(&doc, &skin, get));
+
+        let scene = doc
+            .default_scene()
+            .or_else(|| doc.scenes().next())
+            .ok_or_else(|| "gltf: no scene".to_owned())?;
+
+        let mut parts = Vec::new();
+        let mut skipped = 0usize;
+        for node in scene.nodes() {
+            Self::visit(&node, IDENTITY, skeleton.as_ref(), get, &mut parts, &mut skipped);
+        }
+
+        let mut mesh = Self::assemble(parts);
+        if mesh.positions.is_empty() || mesh.indices.is_empty() {
+            return Err("gltf: no triangle geometry in the default scene".to_owned());
+        }
+        check_cancelled()?;
+
+        let mut table = TextureTable {
+            by_image: vec![None; doc.images().len()],
+            ..TextureTable::default()
+        };
+        mesh.materials = doc
+            .materials()
+            .map(|m| Self::read_material(&m, &buffers, &mut table))
+            .collect();
+        mesh.textures = table.textures;
+
+        if let Some(sk) = skeleton {
+            mesh.clips = doc
+                .animations()
+                .enumerate()
+                .filter_map(|(i, a)| Self::read_clip(i, &a, &sk, get))
+                .collect();
+            mesh.skeleton = sk.joints;
+        }
+        // Embedded textures are decoded above; optimizing is the last heavy step.
+        check_cancelled()?;
+
+        let report = optimize(&mut mesh, settings);
 
         let meta = format!(
-            "{{\"schema\":\"kalitech.model3d.meta.v1\",\"container\":\"{}\",\"format\":\"gltf\",\"gltf\":{{\"scenes\":{},\"nodes\":{},\"meshes\":{},\"materials\":{},\"textures\":{},\"images\":{}}}}}",
-            container, scenes, nodes, meshes, materials, textures, images
+            "{{\"schema\":\"kalitech.model3d.meta.v1\",\"container\":\"{}\",\"format\":\"ne3d_mesh\",\"mesh\":{},\"gltf\":{{\"scenes\":{},\"nodes\":{},\"meshes\":{},\"materials\":{},\"skins\":{},\"animations\":{},\"images\":{},\"skipped_primitives\":{},\"skipped_images\":{}}},\"optimize\":{}}}",
+            container,
+            mesh.meta_json(),
+            doc.scenes().len(),
+            doc.nodes().len(),
+            doc.meshes().len(),
+            doc.materials().len(),
+            doc.skins().len(),
+            doc.animations().len(),
+            doc.images().len(),
+            skipped,
+            table.skipped,
+            report.to_json()
         );
 
-        Ok((meta, bytes.to_vec()))
+        Ok((meta, mesh.write()))
+    }
+
+    fn read_skeleton<'s, F>(doc: &gltf::Document, skin: &gltf::Skin, get: F) -> Skeleton
+    where
+        F: Clone + for<'b> Fn(gltf::Buffer<'b>) -> Option<&'s [u8]>,
+    {
+        let mut node_parent = vec![None; doc.nodes().len()];
+        for n in doc.nodes() {
+            for c in n.children() {
+                node_parent[c.index()] = Some(n.index());
+            }
+        }
+
+        let nodes: Vec<gltf::Node> = skin.joints().collect();
+        let mut slot_of_node = vec![None; doc.nodes().len()];
+        for (slot, n) in nodes.iter().enumerate() {
+            slot_of_node[n.index()] = Some(slot);
+        }
+
+        // Nearest ancestor that is itself a joint of this skin.
+        let parent_slot = |slot: usize| {
+            let mut at = node_parent[nodes[slot].index()];
+            while let Some(n) = at {
+                if let Some(s) = slot_of_node[n] {
+                    return Some(s);
+                }
+                at = node_parent[n];
+            }
+            None
+        };
+        let depth = |slot: usize| {
+            let mut d = 0usize;
+            let mut at = parent_slot(slot);
+            while let Some(s) = at {
+                d += 1;
+                at = parent_slot(s);
+            }
+            d
+        };
+
+        let mut order: Vec<usize> = (0..nodes.len()).collect();
+        order.sort_by_key(|&s| depth(s));
+
+        let mut slot_to_joint = vec![0u16; nodes.len()];
+        for (j, &slot) in order.iter().enumerate() {
+            slot_to_joint[slot] = j as u16;
+        }
+
+        let inverse_binds: Vec<[f32; 16]> = skin
+            .reader(get)
+            .read_inverse_bind_matrices()
+            .map(|it| it.map(flatten).collect())
+            .unwrap_or_default();
+
+        let joints = order
+            .iter()
+            .map(|&slot| {
+                let node = &nodes[slot];
+                let (translation, rotation, scale) = node.transform().decomposed();
+                Joint {
+                    name: node.name().map_or_else(|| format!("joint{slot}"), str::to_owned),
+                    parent: parent_slot(slot).map(|p| slot_to_joint[p] as u32),
+                    inverse_bind: inverse_binds.get(slot).copied().unwrap_or(IDENTITY),
+                    translation,
+                    rotation,
+                    scale,
+                }
+            })
+            .collect();
+
+        let mut node_to_joint = vec![None; doc.nodes().len()];
+        for (slot, n) in nodes.iter().enumerate() {
+            node_to_joint[n.index()] = Some(slot_to_joint[slot]);
+        }
+
+        Skeleton {
+            skin_index: skin.index(),
+            node_to_joint,
+            slot_to_joint,
+            joints,
+        }
+    }
+
+    fn visit<'s, F>(
+        node: &gltf::Node,
+        parent_world: [f32; 16],
+        skeleton: Option<&Skeleton>,
+        get: F,
+        parts: &mut Vec<Part>,
+        skipped: &mut usize,
+    ) where
+        F: Clone + for<'b> Fn(gltf::Buffer<'b>) -> Option<&'s [u8]>,
+    {
+        let world = mat4_mul(&parent_world, &flatten(node.transform().matrix()));
+
+        if let Some(mesh) = node.mesh() {
+            let skinned = node.skin().map(|s| s.index());
+            for prim in mesh.primitives() {
+                let part = match (skeleton, skinned) {
+                    (Some(sk), Some(s)) if s == sk.skin_index => {
+                        Self::read_part(&prim, None, Some(sk), get.clone())
+                    }
+                    (None, _) => Self::read_part(&prim, Some(&world), None, get.clone()),
+                    _ => None,
+                };
+                match part {
+                    Some(p) => parts.push(p),
+                    None => *skipped += 1,
+                }
+            }
+        }
+
+        for child in node.children() {
+            Self::visit(&child, world, skeleton, get.clone(), parts, skipped);
+        }
+    }
+
+    /// `world` bakes a static primitive; `skeleton` reads and remaps its skin attributes.
+    fn read_part<'s, F>(
+        prim: &gltf::Primitive,
+        world: Option<&[f32; 16]>,
+        skeleton: Option<&Skeleton>,
+        get: F,
+    ) -> Option<Part>
+    where
+        F: Clone + for<'b> Fn(gltf::Buffer<'b>) -> Option<&'s [u8]>,
+    {
+        if prim.mode() != Mode::Triangles {
+            return None;
+        }
+
+        let reader = prim.reader(get);
+        let mut positions: Vec<[f32; 3]> = reader.read_positions()?.collect();
+        let mut normals: Option<Vec<[f32; 3]>> = reader.read_normals().map(|it| it.collect());
+        let uvs = reader.read_tex_coords(0).map(|it| it.into_f32().collect::<Vec<_>>());
+        let mut indices: Vec<u32> = match reader.read_indices() {
+            Some(it) => it.into_u32().collect(),
+            None => (0..positions.len() as u32).collect(),
+        };
+
+        let n = positions.len();
+        if n == 0
+            || indices.len() < 3
+            || indices.iter().any(|&i| i as usize >= n)
+            || normals.as_ref().is_some_and(|v| v.len() != n)
+            || uvs.as_ref().is_some_and(|v| v.len() != n)
+        {
+            return None;
+        }
+        indices.truncate(indices.len() / 3 * 3);
+
+        let (mut joints, mut weights) = (Vec::new(), Vec::new());
+        if let Some(sk) = skeleton {
+            joints = reader
+                .read_joints(0)?
+                .into_u16()
+                .map(|j| j.map(|s| sk.slot_to_joint.get(s as usize).copied().unwrap_or(0)))
+                .collect();
+            weights = reader
+                .read_weights(0)?
+                .into_f32()
+                .map(|w| {
+                    let sum = w[0] + w[1] + w[2] + w[3];
+                    if sum > f32::EPSILON {
+                        w.map(|v| v / sum)
+                    } else {
+                        [1.0, 0.0, 0.0, 0.0]
+                    }
+                })
+                .collect();
+            if joints.len() != n || weights.len() != n {
+                return None;
+            }
+        }
+
+        if let Some(m) = world {
+            for p in &mut positions {
+                *p = transform_point(m, *p);
+            }
+            if let Some(ns) = &mut normals {
+                for v in ns {
+                    *v = normalize(transform_dir(m, *v));
+                }
+            }
+            // A mirroring transform flips the winding.
+            if det3(m) < 0.0 {
+                for tri in indices.chunks_exact_mut(3) {
+                    tri.swap(1, 2);
+                }
+            }
+        }
+
+        Some(Part {
+            positions,
+            normals,
+            uvs,
+            joints,
+            weights,
+            indices,
+            material: prim.material().index().map(|i| i as u32),
+        })
+    }
+
+    /// Concatenates parts; a stream present in any part is zero-filled in the others.
+    /// Neighbouring parts with the same material share a submesh.
+    fn assemble(parts: Vec<Part>) -> Ne3dMesh {
+        let has_normals = parts.iter().any(|p| p.normals.is_some());
+        let has_uvs = parts.iter().any(|p| p.uvs.is_some());
+
+        let mut mesh = Ne3dMesh::default();
+        for p in parts {
+            let base = mesh.positions.len() as u32;
+            let n = p.positions.len();
+            if has_normals {
+                mesh.normals.extend(p.normals.unwrap_or_else(|| vec![[0.0; 3]; n]));
+            }
+            if has_uvs {
+                mesh.uvs.extend(p.uvs.unwrap_or_else(|| vec![[0.0; 2]; n]));
+            }
+            mesh.positions.extend(p.positions);
+            mesh.joints.extend(p.joints);
+            mesh.weights.extend(p.weights);
+
+            let first_index = mesh.indices.len() as u32;
+            mesh.indices.extend(p.indices.iter().map(|&i| base + i));
+            let index_count = p.indices.len() as u32;
+            match mesh.submeshes.last_mut() {
+                Some(s) if s.material == p.material => s.index_count += index_count,
+                _ => mesh.submeshes.push(Submesh {
+                    first_index,
+                    index_count,
+                    material: p.material,
+                }),
+            }
+        }
+        mesh
+    }
+
+    fn read_material(
+        m: &gltf::Material,
+        buffers: &[gltf::buffer::Data],
+        table: &mut TextureTable,
+    ) -> Material {
+        let pbr = m.pbr_metallic_roughness();
+        let mut tex = |t: Option<gltf::texture::Texture>| {
+            t.and_then(|t| Self::texture_index(&t.source(), buffers, table))
+        };
+
+        Material {
+            name: m
+                .name()
+                .map_or_else(|| format!("material{}", m.index().unwrap_or(0)), str::to_owned),
+            base_color: pbr.base_color_factor(),
+            metallic: pbr.metallic_factor(),
+            roughness: pbr.roughness_factor(),
+            emissive: m.emissive_factor(),
+            alpha_mode: match m.alpha_mode() {
+                gltf::material::AlphaMode::Opaque => AlphaMode::Opaque,
+                gltf::material::AlphaMode::Mask => AlphaMode::Mask,
+                gltf::material::AlphaMode::Blend => AlphaMode::Blend,
+            },
+            alpha_cutoff: m.alpha_cutoff().unwrap_or(0.5),
+            double_sided: m.double_sided(),
+            base_color_texture: tex(pbr.base_color_texture().map(|i| i.texture())),
+            metallic_roughness_texture: tex(pbr.metallic_roughness_texture().map(|i| i.texture())),
+            normal_texture: tex(m.normal_texture().map(|n| n.texture())),
+            normal_scale: m.normal_texture().map_or(1.0, |n| n.scale()),
+            occlusion_texture: tex(m.occlusion_texture().map(|o| o.texture())),
+            occlusion_strength: m.occlusion_texture().map_or(1.0, |o| o.strength()),
+            emissive_texture: tex(m.emissive_texture().map(|i| i.texture())),
+        }
+    }
+
+    /// Embeds `image` on first use. Images that cannot be read leave the slot empty instead
+    /// of failing the import.
+    fn texture_index(
+        image: &gltf::Image,
+        buffers: &[gltf::buffer::Data],
+        table: &mut TextureTable,
+    ) -> Option<u32> {
+        if let Some(known) = table.by_image.get(image.index()).copied().flatten() {
+            return known;
+        }
+
+        let read = match image.source() {
+            gltf::image::Source::View { view, mime_type } => buffers
+                .get(view.buffer().index())
+                .and_then(|b| b.0.get(view.offset()..view.offset() + view.length()))
+                .map(|b| (mime_type.to_owned(), b.to_vec())),
+            gltf::image::Source::Uri { uri, mime_type } => Self::decode_data_uri(uri)
+                .map(|(mime, b)| (mime_type.map_or(mime, str::to_owned), b)),
+        };
+
+        let index = match read {
+            Some((mime, bytes)) => {
+                table.textures.push(Texture {
+                    name: image
+                        .name()
+                        .map_or_else(|| format!("image{}", image.index()), str::to_owned),
+                    mime,
+                    bytes,
+                });
+                Some(table.textures.len() as u32 - 1)
+            }
+            None => {
+                table.skipped += 1;
+                None
+            }
+        };
+        if let Some(slot) = table.by_image.get_mut(image.index()) {
+            *slot = Some(index);
+        }
+        index
+    }
+
+    /// `data:<mime>;base64,<payload>` -> `(mime, bytes)`.
+    fn decode_data_uri(uri: &str) -> Option<(String, Vec<u8>)> {
+        let (header, data) = uri.trim().strip_prefix("data:")?.split_once(',')?;
+        let mime = header.strip_suffix(";base64")?;
+        let bytes = base64::engine::general_purpose::STANDARD.decode(data).ok()?;
+        Some((mime.to_owned(), bytes))
+    }
+
+    fn read_clip<'s, F>(
+        index: usize,
+        anim: &gltf::Animation,
+        skeleton: &Skeleton,
+        get: F,
+    ) -> Option<Clip>
+    where
+        F: Clone + for<'b> Fn(gltf::Buffer<'b>) -> Option<&'s [u8]>,
+    {
+        let mut channels = Vec::new();
+        let mut duration = 0.0f32;
+
+        for ch in anim.channels() {
+            let target = ch.target();
+            let Some(joint) = skeleton.node_to_joint.get(target.node().index()).copied().flatten()
+            else {
+                continue;
+            };
+
+            let reader = ch.reader(get.clone());
+            let Some(times) = reader.read_inputs().map(|it| it.collect::<Vec<f32>>()) else {
+                continue;
+            };
+            let (path, flat): (ChannelPath, Vec<f32>) = match reader.read_outputs() {
+                Some(ReadOutputs::Translations(it)) => {
+                    (ChannelPath::Translation, it.flatten().collect())
+                }
+                Some(ReadOutputs::Rotations(it)) => {
+                    (ChannelPath::Rotation, it.into_f32().flatten().collect())
+                }
+                Some(ReadOutputs::Scales(it)) => (ChannelPath::Scale, it.flatten().collect()),
+                // Morph weights are not part of NE3D.
+                Some(ReadOutputs::MorphTargetWeights(_)) | None => continue,
+            };
+
+            let w = path.width();
+            let interpolation = ch.sampler().interpolation();
+            let values = if interpolation == Interpolation::CubicSpline {
+                // (in-tangent, value, out-tangent) per key: keep the values, play them linearly.
+                flat.chunks_exact(w * 3)
+                    .flat_map(|k| k[w..w * 2].iter().copied())
+                    .collect()
+            } else {
+                flat
+            };
+            if times.is_empty() || values.len() != times.len() * w {
+                continue;
+            }
+
+            duration = duration.max(times.last().copied().unwrap_or(0.0));
+            channels.push(Channel {
+                joint: joint as u32,
+                path,
+                step: interpolation == Interpolation::Step,
+                times,
+                values,
+            });
+        }
+
+        if channels.is_empty() {
+            return None;
+        }
+
+        Some(Clip {
+            name: anim.name().map_or_else(|| format!("clip{index}"), str::to_owned),
+            duration,
+            channels,
+        })
     }
 }
 
+#[inline]
+fn flatten(m: [[f32; 4]; 4]) -> [f32; 16] {
+    let mut out = [0.0; 16];
+    for (c, col) in m.iter().enumerate() {
+        out[c * 4..c * 4 + 4].copy_from_slice(col);
+    }
+    out
+}
+
+/// Column-major `a * b`.
+fn mat4_mul(a: &[f32; 16], b: &[f32; 16]) -> [f32; 16] {
+    let mut out = [0.0; 16];
+    for c in 0..4 {
+        for r in 0..4 {
+            out[c * 4 + r] = (0..4).map(|k| a[k * 4 + r] * b[c * 4 + k]).sum();
+        }
+    }
+    out
+}
+
+#[inline]
+fn transform_point(m: &[f32; 16], p: [f32; 3]) -> [f32; 3] {
+    let d = transform_dir(m, p);
+    [d[0] + m[12], d[1] + m[13], d[2] + m[14]]
+}
+
+#[inline]
+fn transform_dir(m: &[f32; 16], v: [f32; 3]) -> [f32; 3] {
+    [
+        m[0] * v[0] + m[4] * v[1] + m[8] * v[2],
+        m[1] * v[0] + m[5] * v[1] + m[9] * v[2],
+        m[2] * v[0] + m[6] * v[1] + m[10] * v[2],
+    ]
+}
+
+#[inline]
+fn normalize(v: [f32; 3]) -> [f32; 3] {
+    let len = (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt();
+    if len > f32::EPSILON {
+        [v[0] / len, v[1] / len, v[2] / len]
+    } else {
+        v
+    }
+}
+
+#[inline]
+fn det3(m: &[f32; 16]) -> f32 {
+    m[0] * (m[5] * m[10] - m[9] * m[6]) - m[4] * (m[1] * m[10] - m[9] * m[2])
+        + m[8] * (m[1] * m[6] - m[5] * m[2])
+}
+
 impl Provider for GltfProvider {
     fn name(&self) -> &'static str {
         "gltf"
@@ -92,8 +642,8 @@ impl Provider for GltfProvider {
         Self::detect_container(bytes).is_some()
     }
 
-    fn import(&self, bytes: &[u8]) -> RResult<RVec<u8>, RString> {
-        match Self::validate(bytes) {
+    fn import(&self, bytes: &[u8], settings: &ImportSettings) -> RResult<RVec<u8>, RString> {
+        match Self::convert(bytes, settings) {
             Ok((meta, payload)) => {
                 let packed = super::super::module::pack_wire(&meta, &payload);
                 RResult::ROk(RVec::from(packed))
@@ -103,6 +653,6 @@ impl Provider for GltfProvider {
     }
 
     fn describe_json(&self) -> &'static str {
-        r#"{"name":"gltf","container":"glb|gltf","notes":"Validates and packs source bytes. .gltf requires embedded data URIs."}"#
+        r#"{"name":"gltf","container":"glb|gltf","notes":"Converted to NE3D mesh (little-endian) with the first skin's joints and animation clips. .gltf requires embedded data URIs.","ne3d":{"version":4,"materials":{"name":"str","base_color":"f32x4 linear rgba","metallic":"f32","roughness":"f32","emissive":"f32x3","alpha_mode":"u8 0 opaque|1 mask|2 blend","alpha_cutoff":"f32","double_sided":"u8","base_color_texture":"i32 texture or -1","metallic_roughness_texture":"i32 texture or -1 (G roughness, B metallic)","normal_texture":"i32 texture or -1","normal_scale":"f32","occlusion_texture":"i32 texture or -1","occlusion_strength":"f32","emissive_texture":"i32 texture or -1"},"textures":{"name":"str","mime":"str image/png|image/jpeg","bytes":"u32 length + encoded image"},"submeshes":{"first_index":"u32","index_count":"u32","material":"i32 material or -1"},"lods":{"first_index":"u32","index_count":"u32","error":"f32 relative to mesh extent"}}}"#
     }
 }
@@ -2,6 +2,10 @@
 
 use abi_stable::std_types::{RResult, RString, RVec};
 
+use crate::settings::ImportSettings;
+
+mod ne3d;
+mod optimize;
 mod obj;
 mod gltf;
 mod fbx;
@@ -11,7 +15,7 @@ pub(crate) trait Provider: Sync {
     fn extensions(&self) -> &'static [&'static str];
     fn sniff(&self, bytes: &[u8]) -> bool;
 
-    fn import(&self, bytes: &[u8]) -> RResult<RVec<u8>, RString>;
+    fn import(&self, bytes: &[u8], settings: &ImportSettings) -> RResult<RVec<u8>, RString>;
 
     /// Returns a JSON object string that describes the format.
     fn describe_json(&self) -> &'static str;
@@ -0,0 +1,292 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+/// Payload version written by every provider. The layout is documented on
+/// `newengine_assets::Ne3dMesh`, which decodes it.
+pub(crate) const NE3D_VERSION: u32 = 4;
+
+const FLAG_NORMALS: u32 = 1 << 0;
+const FLAG_UVS: u32 = 1 << 1;
+const FLAG_SKIN: u32 = 1 << 2;
+
+pub(crate) struct Joint {
+    pub name: String,
+    /// Must be lower than the joint's own index.
+    pub parent: Option<u32>,
+    pub inverse_bind: [f32; 16],
+    pub translation: [f32; 3],
+    pub rotation: [f32; 4],
+    pub scale: [f32; 3],
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ChannelPath {
+    Translation = 0,
+    Rotation = 1,
+    Scale = 2,
+}
+
+impl ChannelPath {
+    #[inline]
+    pub const fn width(self) -> usize {
+        match self {
+            Self::Rotation => 4,
+            Self::Translation | Self::Scale => 3,
+        }
+    }
+}
+
+pub(crate) struct Channel {
+    pub joint: u32,
+    pub path: ChannelPath,
+    pub step: bool,
+    pub times: Vec<f32>,
+    pub values: Vec<f32>,
+}
+
+pub(crate) struct Clip {
+    pub name: String,
+    pub duration: f32,
+    pub channels: Vec<Channel>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum AlphaMode {
+    Opaque = 0,
+    Mask = 1,
+    Blend = 2,
+}
+
+/// Metallic-roughness material; texture slots index `Ne3dMesh::textures`.
+pub(crate) struct Material {
+    pub name: String,
+    pub base_color: [f32; 4],
+    pub metallic: f32,
+    pub roughness: f32,
+    pub emissive: [f32; 3],
+    pub alpha_mode: AlphaMode,
+    pub alpha_cutoff: f32,
+    pub double_sided: bool,
+    pub base_color_texture: Option<u32>,
+    pub metallic_roughness_texture: Option<u32>,
+    pub normal_texture: Option<u32>,
+    pub normal_scale: f32,
+    pub occlusion_texture: Option<u32>,
+    pub occlusion_strength: f32,
+    pub emissive_texture: Option<u32>,
+}
+
+/// Encoded image bytes (png/jpeg) embedded as-is.
+pub(crate) struct Texture {
+    pub name: String,
+    pub mime: String,
+    pub bytes: Vec<u8>,
+}
+
+pub(crate) struct Submesh {
+    pub first_index: u32,
+    pub index_count: u32,
+    pub material: Option<u32>,
+}
+
+/// One level of detail: a range of `Ne3dMesh::indices` over the shared vertex streams.
+#[derive(Clone, Copy)]
+pub(crate) struct Lod {
+    pub first_index: u32,
+    pub index_count: u32,
+    /// Simplification error relative to the mesh extent (0 for LOD 0).
+    pub error: f32,
+}
+
+/// CPU-side mesh shared by the providers. `normals`/`uvs` are either empty or vertex-aligned;
+/// `joints`/`weights` are only written together with a non-empty `skeleton`.
+#[derive(Default)]
+pub(crate) struct Ne3dMesh {
+    pub positions: Vec<[f32; 3]>,
+    pub normals: Vec<[f32; 3]>,
+    pub uvs: Vec<[f32; 2]>,
+    pub joints: Vec<[u16; 4]>,
+    pub weights: Vec<[f32; 4]>,
+    pub indices: Vec<u32>,
+    pub skeleton: Vec<Joint>,
+    pub clips: Vec<Clip>,
+    pub materials: Vec<Material>,
+    pub textures: Vec<Texture>,
+    /// Empty: the whole index range uses the default material.
+    pub submeshes: Vec<Submesh>,
+    /// Empty: the whole index range is LOD 0. Submeshes of each level lie inside its range.
+    pub lods: Vec<Lod>,
+}
+
+impl Ne3dMesh {
+    #[inline]
+    pub fn has_normals(&self) -> bool {
+        !self.normals.is_empty()
+    }
+
+    #[inline]
+    pub fn has_uvs(&self) -> bool {
+        !self.uvs.is_empty()
+    }
+
+    #[inline]
+    pub fn is_skinned(&self) -> bool {
+        !self.skeleton.is_empty() && !self.joints.is_empty()
+    }
+
+    pub fn bounds(&self) -> ([f32; 3], [f32; 3]) {
+        let mut min = [f32::INFINITY; 3];
+        let mut max = [f32::NEG_INFINITY; 3];
+        for p in &self.positions {
+            for k in 0..3 {
+                min[k] = min[k].min(p[k]);
+                max[k] = max[k].max(p[k]);
+            }
+        }
+        (min, max)
+    }
+
+    pub fn write(&self) -> Vec<u8> {
+        let skinned = self.is_skinned();
+        let flags = (self.has_normals() as u32 * FLAG_NORMALS)
+            | (self.has_uvs() as u32 * FLAG_UVS)
+            | (skinned as u32 * FLAG_SKIN);
+
+        let mut out = Vec::with_capacity(20 + self.positions.len() * 32 + self.indices.len() * 4);
+        out.extend_from_slice(b"NE3D");
+        put_u32(&mut out, NE3D_VERSION);
+        put_u32(&mut out, self.positions.len() as u32);
+        put_u32(&mut out, self.indices.len() as u32);
+        put_u32(&mut out, flags);
+        put_u32(&mut out, self.lods.len() as u32);
+        for l in &self.lods {
+            put_u32(&mut out, l.first_index);
+            put_u32(&mut out, l.index_count);
+            put_f32s(&mut out, &[l.error]);
+        }
+
+        self.positions.iter().for_each(|p| put_f32s(&mut out, p));
+        self.normals.iter().for_each(|n| put_f32s(&mut out, n));
+        self.uvs.iter().for_each(|t| put_f32s(&mut out, t));
+        if skinned {
+            for j in &self.joints {
+                j.iter().for_each(|v| out.extend_from_slice(&v.to_le_bytes()));
+            }
+            self.weights.iter().for_each(|w| put_f32s(&mut out, w));
+        }
+        self.indices.iter().for_each(|&i| put_u32(&mut out, i));
+
+        put_u32(&mut out, self.skeleton.len() as u32);
+        for j in &self.skeleton {
+            put_str(&mut out, &j.name);
+            put_index(&mut out, j.parent);
+            put_f32s(&mut out, &j.inverse_bind);
+            put_f32s(&mut out, &j.translation);
+            put_f32s(&mut out, &j.rotation);
+            put_f32s(&mut out, &j.scale);
+        }
+
+        put_u32(&mut out, self.clips.len() as u32);
+        for c in &self.clips {
+            put_str(&mut out, &c.name);
+            put_f32s(&mut out, &[c.duration]);
+            put_u32(&mut out, c.channels.len() as u32);
+            for ch in &c.channels {
+                put_u32(&mut out, ch.joint);
+                out.push(ch.path as u8);
+                out.push(ch.step as u8);
+                put_u32(&mut out, ch.times.len() as u32);
+                put_f32s(&mut out, &ch.times);
+                put_f32s(&mut out, &ch.values);
+            }
+        }
+
+        put_u32(&mut out, self.materials.len() as u32);
+        for m in &self.materials {
+            put_str(&mut out, &m.name);
+            put_f32s(&mut out, &m.base_color);
+            put_f32s(&mut out, &[m.metallic, m.roughness]);
+            put_f32s(&mut out, &m.emissive);
+            out.push(m.alpha_mode as u8);
+            put_f32s(&mut out, &[m.alpha_cutoff]);
+            out.push(m.double_sided as u8);
+            put_index(&mut out, m.base_color_texture);
+            put_index(&mut out, m.metallic_roughness_texture);
+            put_index(&mut out, m.normal_texture);
+            put_f32s(&mut out, &[m.normal_scale]);
+            put_index(&mut out, m.occlusion_texture);
+            put_f32s(&mut out, &[m.occlusion_strength]);
+            put_index(&mut out, m.emissive_texture);
+        }
+
+        put_u32(&mut out, self.textures.len() as u32);
+        for t in &self.textures {
+            put_str(&mut out, &t.name);
+            put_str(&mut out, &t.mime);
+            put_u32(&mut out, t.bytes.len() as u32);
+            out.extend_from_slice(&t.bytes);
+        }
+
+        put_u32(&mut out, self.submeshes.len() as u32);
+        for s in &self.submeshes {
+            put_u32(&mut out, s.first_index);
+            put_u32(&mut out, s.index_count);
+            put_index(&mut out, s.material);
+        }
+
+        out
+    }
+
+    /// The `"mesh"` object of the importer meta.
+    pub fn meta_json(&self) -> String {
+        let (min, max) = self.bounds();
+        format!(
+            "{{\"vertex_count\":{},\"index_count\":{},\"has_normals\":{},\"has_uvs\":{},\"bbox_min\":[{:.6},{:.6},{:.6}],\"bbox_max\":[{:.6},{:.6},{:.6}],\"skinned\":{},\"joint_count\":{},\"clip_count\":{},\"material_count\":{},\"texture_count\":{},\"submesh_count\":{},\"lod_count\":{}}}",
+            self.positions.len(),
+            self.indices.len(),
+            self.has_normals(),
+            self.has_uvs(),
+            min[0],
+            min[1],
+            min[2],
+            max[0],
+            max[1],
+            max[2],
+            self.is_skinned(),
+            self.skeleton.len(),
+            self.clips.len(),
+            self.materials.len(),
+            self.textures.len(),
+            self.submeshes.len(),
+            self.lods.len()
+        )
+    }
+}
+
+#[inline]
+fn put_u32(out: &mut Vec<u8>, v: u32) {
+    out.extend_from_slice(&v.to_le_bytes());
+}
+
+/// `-1` for none.
+#[inline]
+fn put_index(out: &mut Vec<u8>, v: Option<u32>) {
+    out.extend_from_slice(&v.map_or(-1, |i| i as i32).to_le_bytes());
+}
+
+#[inline]
+fn put_f32s(out: &mut Vec<u8>, v: &[f32]) {
+    for f in v {
+        out.extend_from_slice(&f.to_le_bytes());
+    }
+}
+
+#[inline]
+fn put_str(out: &mut Vec<u8>, s: &str) {
+    let mut end = s.len().min(u16::MAX as usize);
+    while !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    let b = &s.as_bytes()[..end];
+    out.extend_from_slice(&(b.len() as u16).to_le_bytes());
+    out.extend_from_slice(b);
+}
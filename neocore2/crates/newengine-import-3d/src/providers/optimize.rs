@@ -0,0 +1,470 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use super::ne3d::{Lod, Ne3dMesh, Submesh};
+use crate::settings::ImportSettings;
+
+/// Post-transform cache size the triangle order is tuned for and ACMR is measured with.
+const CACHE_SIZE: usize = 32;
+
+/// The LOD chain stops before a level would have fewer triangles than this.
+const MIN_LOD_TRIANGLES: usize = 16;
+
+/// Grid resolutions tried when clustering a level (cells along the longest axis).
+const MAX_GRID: u32 = 1024;
+
+/// What [`optimize`] did, reported in the importer meta.
+pub(crate) struct OptimizeReport {
+    pub settings: ImportSettings,
+    pub vertices_in: usize,
+    pub vertices_out: usize,
+    /// Average cache misses per triangle of LOD 0, before and after.
+    pub acmr_in: f32,
+    pub acmr_out: f32,
+}
+
+impl OptimizeReport {
+    pub fn to_json(&self) -> String {
+        format!(
+            "{{\"settings\":{},\"vertices_in\":{},\"vertices_out\":{},\"acmr_in\":{:.3},\"acmr_out\":{:.3}}}",
+            self.settings.to_json(),
+            self.vertices_in,
+            self.vertices_out,
+            self.acmr_in,
+            self.acmr_out
+        )
+    }
+}
+
+/// Triangles of one material within one level.
+struct Group {
+    material: Option<u32>,
+    indices: Vec<u32>,
+}
+
+/// Post-import pass run by every provider before writing NE3D.
+///
+/// Order: vertex dedup, LOD chain (vertex clustering over the shared vertex streams), triangle
+/// order per submesh, then vertex order by first use.
+pub(crate) fn optimize(mesh: &mut Ne3dMesh, settings: &ImportSettings) -> OptimizeReport {
+    let vertices_in = mesh.positions.len();
+    let acmr_in = acmr(&mesh.indices);
+
+    if settings.dedupe_vertices {
+        dedupe(mesh);
+    }
+
+    if settings.optimize_vertex_cache || settings.lod_count > 0 {
+        // Submeshes that do not tile the index buffer are left untouched.
+        if let Some(groups) = split_groups(mesh) {
+            let mut levels = vec![groups];
+            let mut errors = vec![0.0];
+            if settings.lod_count > 0 {
+                build_lods(mesh, settings, &mut levels, &mut errors);
+            }
+            if settings.optimize_vertex_cache {
+                for g in levels.iter_mut().flatten() {
+                    reorder_triangles(&mut g.indices);
+                }
+            }
+            assemble(mesh, levels, &errors);
+        }
+        if settings.optimize_vertex_cache {
+            reorder_vertices(mesh);
+        }
+    }
+
+    let lod0 = mesh
+        .lods
+        .first()
+        .map_or(mesh.indices.len(), |l| l.index_count as usize);
+    OptimizeReport {
+        settings: *settings,
+        vertices_in,
+        vertices_out: mesh.positions.len(),
+        acmr_in,
+        acmr_out: acmr(&mesh.indices[..lod0]),
+    }
+}
+
+fn acmr(indices: &[u32]) -> f32 {
+    let tris = indices.len() / 3;
+    if tris == 0 {
+        return 0.0;
+    }
+    let mut cache: VecDeque<u32> = VecDeque::with_capacity(CACHE_SIZE + 1);
+    let mut misses = 0usize;
+    for &v in indices {
+        if !cache.contains(&v) {
+            misses += 1;
+            cache.push_back(v);
+            if cache.len() > CACHE_SIZE {
+                cache.pop_front();
+            }
+        }
+    }
+    misses as f32 / tris as f32
+}
+
+/// Bit patterns of every stream of vertex `i`; absent streams stay zero.
+fn vertex_key(mesh: &Ne3dMesh, i: usize) -> [u32; 14] {
+    let mut k = [0u32; 14];
+    for (d, v) in k[0..3].iter_mut().zip(mesh.positions[i]) {
+        *d = v.to_bits();
+    }
+    if let Some(n) = mesh.normals.get(i) {
+        for (d, v) in k[3..6].iter_mut().zip(n) {
+            *d = v.to_bits();
+        }
+    }
+    if let Some(t) = mesh.uvs.get(i) {
+        for (d, v) in k[6..8].iter_mut().zip(t) {
+            *d = v.to_bits();
+        }
+    }
+    if let Some(j) = mesh.joints.get(i) {
+        k[8] = j[0] as u32 | (j[1] as u32) << 16;
+        k[9] = j[2] as u32 | (j[3] as u32) << 16;
+    }
+    if let Some(w) = mesh.weights.get(i) {
+        for (d, v) in k[10..14].iter_mut().zip(w) {
+            *d = v.to_bits();
+        }
+    }
+    k
+}
+
+/// Merges bit-identical vertices, e.g. the per-corner copies of OBJ `single_index` and FBX.
+fn dedupe(mesh: &mut Ne3dMesh) {
+    let n = mesh.positions.len();
+    let mut first: HashMap<[u32; 14], u32> = HashMap::with_capacity(n);
+    let mut remap = Vec::with_capacity(n);
+    let mut keep = Vec::new();
+    for i in 0..n {
+        let next = keep.len() as u32;
+        let id = *first.entry(vertex_key(mesh, i)).or_insert(next);
+        if id == next {
+            keep.push(i);
+        }
+        remap.push(id);
+    }
+    if keep.len() == n {
+        return;
+    }
+    gather_vertices(mesh, &keep);
+    for i in &mut mesh.indices {
+        *i = remap[*i as usize];
+    }
+}
+
+fn gather<T: Copy>(v: &mut Vec<T>, order: &[usize]) {
+    if !v.is_empty() {
+        *v = order.iter().map(|&i| v[i]).collect();
+    }
+}
+
+/// Keeps vertices `order[..]` in that order. Indices are the caller's to remap.
+fn gather_vertices(mesh: &mut Ne3dMesh, order: &[usize]) {
+    gather(&mut mesh.positions, order);
+    gather(&mut mesh.normals, order);
+    gather(&mut mesh.uvs, order);
+    gather(&mut mesh.joints, order);
+    gather(&mut mesh.weights, order);
+}
+
+/// Renumbers vertices in order of first use and drops unreferenced ones.
+fn reorder_vertices(mesh: &mut Ne3dMesh) {
+    let mut remap = vec![u32::MAX; mesh.positions.len()];
+    let mut order = Vec::with_capacity(mesh.positions.len());
+    for i in &mut mesh.indices {
+        let v = *i as usize;
+        if remap[v] == u32::MAX {
+            remap[v] = order.len() as u32;
+            order.push(v);
+        }
+        *i = remap[v];
+    }
+    gather_vertices(mesh, &order);
+}
+
+fn split_groups(mesh: &Ne3dMesh) -> Option<Vec<Group>> {
+    if !mesh.lods.is_empty() || !mesh.indices.len().is_multiple_of(3) {
+        return None;
+    }
+    if mesh.submeshes.is_empty() {
+        return Some(vec![Group {
+            material: None,
+            indices: mesh.indices.clone(),
+        }]);
+    }
+
+    let mut at = 0u32;
+    for s in &mesh.submeshes {
+        if s.first_index != at || !s.index_count.is_multiple_of(3) {
+            return None;
+        }
+        at += s.index_count;
+    }
+    if at as usize != mesh.indices.len() {
+        return None;
+    }
+
+    Some(
+        mesh.submeshes
+            .iter()
+            .map(|s| Group {
+                material: s.material,
+                indices: mesh.indices
+                    [s.first_index as usize..(s.first_index + s.index_count) as usize]
+                    .to_vec(),
+            })
+            .collect(),
+    )
+}
+
+/// Writes the levels back as one index buffer: LOD 0 first, each level's submeshes in order.
+fn assemble(mesh: &mut Ne3dMesh, levels: Vec<Vec<Group>>, errors: &[f32]) {
+    let had_submeshes = !mesh.submeshes.is_empty();
+    let with_lods = levels.len() > 1;
+    mesh.indices.clear();
+    mesh.submeshes.clear();
+    mesh.lods.clear();
+
+    for (level, &error) in levels.into_iter().zip(errors) {
+        let first = mesh.indices.len() as u32;
+        for g in level.into_iter().filter(|g| !g.indices.is_empty()) {
+            if had_submeshes {
+                mesh.submeshes.push(Submesh {
+                    first_index: mesh.indices.len() as u32,
+                    index_count: g.indices.len() as u32,
+                    material: g.material,
+                });
+            }
+            mesh.indices.extend(g.indices);
+        }
+        if with_lods {
+            mesh.lods.push(Lod {
+                first_index: first,
+                index_count: mesh.indices.len() as u32 - first,
+                error,
+            });
+        }
+    }
+}
+
+#[inline]
+fn triangle_count(level: &[Group]) -> usize {
+    level.iter().map(|g| g.indices.len() / 3).sum()
+}
+
+/// Each level clusters LOD 0 to `lod_ratio^k` of its triangles, per submesh.
+fn build_lods(
+    mesh: &Ne3dMesh,
+    settings: &ImportSettings,
+    levels: &mut Vec<Vec<Group>>,
+    errors: &mut Vec<f32>,
+) {
+    let (min, max) = mesh.bounds();
+    let size = (0..3).map(|k| max[k] - min[k]).fold(0.0f32, f32::max);
+    if !(size > 0.0 && size.is_finite()) {
+        return;
+    }
+
+    let base = triangle_count(&levels[0]);
+    let mut prev = base;
+    let mut ratio = 1.0f32;
+    for _ in 0..settings.lod_count {
+        ratio *= settings.lod_ratio;
+        if ((base as f32 * ratio) as usize) < MIN_LOD_TRIANGLES {
+            break;
+        }
+
+        let mut error = 0.0f32;
+        let level: Vec<Group> = levels[0]
+            .iter()
+            .map(|g| {
+                let target = ((g.indices.len() / 3) as f32 * ratio).ceil() as usize;
+                let (indices, cell) = simplify(&mesh.positions, &g.indices, target, min, size);
+                error = error.max(cell / size);
+                Group {
+                    material: g.material,
+                    indices,
+                }
+            })
+            .collect();
+
+        let tris = triangle_count(&level);
+        if tris == 0 || tris >= prev {
+            break;
+        }
+        prev = tris;
+        levels.push(level);
+        errors.push(error);
+    }
+}
+
+/// Vertex clustering on the finest grid that meets `target` triangles. Every cell collapses
+/// onto its first referenced vertex, so the level reuses the existing vertex streams.
+/// Returns the indices and the cell size.
+fn simplify(
+    positions: &[[f32; 3]],
+    indices: &[u32],
+    target: usize,
+    min: [f32; 3],
+    size: f32,
+) -> (Vec<u32>, f32) {
+    let (mut lo, mut hi) = (1u32, MAX_GRID);
+    let mut best = (Vec::new(), size);
+    while lo <= hi {
+        let res = lo + (hi - lo) / 2;
+        let cell = size / res as f32;
+        let out = cluster(positions, indices, min, cell);
+        if out.len() / 3 <= target {
+            best = (out, cell);
+            lo = res + 1;
+        } else {
+            hi = res - 1;
+        }
+    }
+    best
+}
+
+fn cluster(positions: &[[f32; 3]], indices: &[u32], min: [f32; 3], cell: f32) -> Vec<u32> {
+    let key = |v: u32| {
+        let p = positions[v as usize];
+        [0, 1, 2].map(|k| ((p[k] - min[k]) / cell) as i32)
+    };
+
+    let mut rep: HashMap<[i32; 3], u32> = HashMap::new();
+    let mut seen: HashSet<[u32; 3]> = HashSet::new();
+    let mut out = Vec::new();
+    for tri in indices.chunks_exact(3) {
+        let t = [0, 1, 2].map(|k| *rep.entry(key(tri[k])).or_insert(tri[k]));
+        if t[0] == t[1] || t[1] == t[2] || t[0] == t[2] {
+            continue;
+        }
+        // Rotate the smallest index first so duplicates compare equal with winding kept.
+        let r = (0..3).min_by_key(|&k| t[k]).unwrap_or(0);
+        let canon = [t[r], t[(r + 1) % 3], t[(r + 2) % 3]];
+        if seen.insert(canon) {
+            out.extend_from_slice(&t);
+        }
+    }
+    out
+}
+
+#[inline]
+fn vertex_score(cache_pos: usize, live: u32) -> f32 {
+    if live == 0 {
+        return -1.0;
+    }
+    let cache = if cache_pos < 3 {
+        0.75
+    } else if cache_pos < CACHE_SIZE {
+        (1.0 - (cache_pos - 3) as f32 / (CACHE_SIZE - 3) as f32).powf(1.5)
+    } else {
+        0.0
+    };
+    cache + 2.0 / (live as f32).sqrt()
+}
+
+/// Forsyth's linear-speed vertex cache optimization over one index range.
+fn reorder_triangles(indices: &mut [u32]) {
+    let tri_count = indices.len() / 3;
+    if tri_count < 2 {
+        return;
+    }
+
+    // Local vertex ids keep the work proportional to the range, not the whole mesh.
+    let mut local: HashMap<u32, u32> = HashMap::new();
+    let mut global = Vec::new();
+    let tris: Vec<[usize; 3]> = indices
+        .chunks_exact(3)
+        .map(|t| {
+            [0, 1, 2].map(|k| {
+                *local.entry(t[k]).or_insert_with(|| {
+                    global.push(t[k]);
+                    global.len() as u32 - 1
+                }) as usize
+            })
+        })
+        .collect();
+    let n = global.len();
+
+    let mut live = vec![0u32; n];
+    for t in &tris {
+        t.iter().for_each(|&v| live[v] += 1);
+    }
+    let mut offset = vec![0usize; n + 1];
+    for v in 0..n {
+        offset[v + 1] = offset[v] + live[v] as usize;
+    }
+    let mut adj = vec![0usize; offset[n]];
+    let mut fill = offset.clone();
+    for (i, t) in tris.iter().enumerate() {
+        for &v in t {
+            adj[fill[v]] = i;
+            fill[v] += 1;
+        }
+    }
+
+    let mut cache_pos = vec![usize::MAX; n];
+    let mut score: Vec<f32> = (0..n).map(|v| vertex_score(usize::MAX, live[v])).collect();
+    let mut emitted = vec![false; tri_count];
+    let mut cache: Vec<usize> = Vec::with_capacity(CACHE_SIZE + 3);
+    let mut next: Vec<usize> = Vec::with_capacity(CACHE_SIZE + 3);
+    let mut out = Vec::with_capacity(indices.len());
+    let mut best: Option<usize> = None;
+    let mut cursor = 0usize;
+
+    for _ in 0..tri_count {
+        let t = match best {
+            Some(t) => t,
+            None => {
+                // Dead end: restart at the first triangle not emitted yet.
+                while emitted[cursor] {
+                    cursor += 1;
+                }
+                cursor
+            }
+        };
+        emitted[t] = true;
+        let tv = tris[t];
+        out.extend(tv.iter().map(|&v| global[v]));
+
+        for &v in &tv {
+            let list = &mut adj[offset[v]..offset[v] + live[v] as usize];
+            if let Some(p) = list.iter().position(|&u| u == t) {
+                let last = list.len() - 1;
+                list.swap(p, last);
+                live[v] -= 1;
+            }
+        }
+
+        next.clear();
+        next.extend_from_slice(&tv);
+        next.extend(cache.iter().filter(|v| !tv.contains(v)));
+        for (i, &v) in next.iter().enumerate() {
+            cache_pos[v] = if i < CACHE_SIZE { i } else { usize::MAX };
+            score[v] = vertex_score(cache_pos[v], live[v]);
+        }
+
+        best = None;
+        let mut best_score = f32::NEG_INFINITY;
+        for &v in &next {
+            for &u in &adj[offset[v]..offset[v] + live[v] as usize] {
+                let s: f32 = tris[u].iter().map(|&w| score[w]).sum();
+                if s > best_score {
+                    best_score = s;
+                    best = Some(u);
+                }
+            }
+        }
+
+        next.truncate(CACHE_SIZE);
+        std::mem::swap(&mut cache, &mut next);
+    }
+
+    indices.copy_from_slice(&out);
+}
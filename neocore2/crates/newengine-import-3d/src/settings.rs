@@ -0,0 +1,70 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use serde_json::Value;
+
+/// Per-import options, sent as JSON with `import_3d_v2`.
+///
+/// ```json
+/// {"dedupe_vertices":true,"optimize_vertex_cache":true,"lod_count":3,"lod_ratio":0.5}
+/// ```
+///
+/// Missing or mistyped keys keep their defaults.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct ImportSettings {
+    /// Merge vertices whose streams are bit-identical.
+    pub dedupe_vertices: bool,
+    /// Reorder triangles for the post-transform cache and vertices for fetch locality.
+    pub optimize_vertex_cache: bool,
+    /// Extra simplified levels after LOD 0.
+    pub lod_count: u32,
+    /// Triangle ratio of each level relative to the previous one.
+    pub lod_ratio: f32,
+}
+
+impl Default for ImportSettings {
+    fn default() -> Self {
+        Self {
+            dedupe_vertices: true,
+            optimize_vertex_cache: true,
+            lod_count: 0,
+            lod_ratio: 0.5,
+        }
+    }
+}
+
+impl ImportSettings {
+    pub const MAX_LODS: u32 = 8;
+
+    pub fn from_json(s: &str) -> Result<Self, String> {
+        if s.trim().is_empty() {
+            return Ok(Self::default());
+        }
+        let v: Value =
+            serde_json::from_str(s).map_err(|e| format!("3d: bad import settings: {e}"))?;
+        let Some(obj) = v.as_object() else {
+            return Err("3d: import settings must be a JSON object".to_owned());
+        };
+
+        let mut out = Self::default();
+        if let Some(b) = obj.get("dedupe_vertices").and_then(Value::as_bool) {
+            out.dedupe_vertices = b;
+        }
+        if let Some(b) = obj.get("optimize_vertex_cache").and_then(Value::as_bool) {
+            out.optimize_vertex_cache = b;
+        }
+        if let Some(n) = obj.get("lod_count").and_then(Value::as_u64) {
+            out.lod_count = (n as u32).min(Self::MAX_LODS);
+        }
+        if let Some(r) = obj.get("lod_ratio").and_then(Value::as_f64) {
+            out.lod_ratio = (r as f32).clamp(0.05, 0.95);
+        }
+        Ok(out)
+    }
+
+    pub fn to_json(self) -> String {
+        format!(
+            "{{\"dedupe_vertices\":{},\"optimize_vertex_cache\":{},\"lod_count\":{},\"lod_ratio\":{:.3}}}",
+            self.dedupe_vertices, self.optimize_vertex_cache, self.lod_count, self.lod_ratio
+        )
+    }
+}
@@ -1,11 +1,10 @@
 #![forbid(unsafe_op_in_unsafe_fn)]
 
-use abi_stable::sabi_trait::TD_Opaque;
 use abi_stable::std_types::{RResult, RString, RVec};
 use abi_stable::StableAbi;
 
 use newengine_plugin_api::{
-    Blob, HostApiV1, MethodName, PluginInfo, PluginModule, ServiceV1, ServiceV1Dyn, ServiceV1_TO,
+    guarded_service, Blob, HostApiV1, MethodName, PluginInfo, PluginModule, ServiceV1, ServiceV1Dyn,
 };
 
 use std::sync::OnceLock;
@@ -215,11 +214,12 @@ impl PluginModule for AudioImporterPlugin {
             id: RString::from("import.audio"),
             name: RString::from("Audio Importer (Provider-based)"),
             version: RString::from(env!("CARGO_PKG_VERSION")),
+            requires: RVec::new(),
         }
     }
 
     fn init(&mut self, host: HostApiV1) -> RResult<(), RString> {
-        let svc: ServiceV1Dyn<'static> = ServiceV1_TO::from_value(AudioImporterService, TD_Opaque);
+        let svc: ServiceV1Dyn<'static> = guarded_service(AudioImporterService);
 
         let r = (host.register_service_v1)(svc);
         if let Err(e) = r.clone().into_result() {
@@ -0,0 +1,69 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use serde_json::{json, Map, Value};
+
+/// Face keys of a six-image manifest, in cubemap layer order (+X, -X, +Y, -Y, +Z, -Z).
+const FACE_KEYS: [&str; 6] = ["px", "nx", "py", "ny", "pz", "nz"];
+
+/// Upper bound on `face_size`.
+const MAX_FACE_SIZE: u64 = 4096;
+
+/// Imports a `.cubemap` manifest into `kalitech.cubemap.meta.v1` meta JSON.
+///
+/// A manifest names either six face images or one equirectangular panorama:
+///
+/// ```json
+/// {"faces":{"px":"sky_px.png","nx":"sky_nx.png","py":"sky_py.png",
+///           "ny":"sky_ny.png","pz":"sky_pz.png","nz":"sky_nz.png"}}
+/// {"equirect":"sky.png","face_size":512}
+/// ```
+///
+/// Paths stay relative to the manifest; the images are loaded by whoever uses the cubemap.
+/// `face_size` is optional.
+pub fn import_manifest(bytes: &[u8]) -> Result<String, String> {
+    let text = std::str::from_utf8(bytes).map_err(|e| format!("cubemap: not utf-8: {e}"))?;
+    let v: Value = serde_json::from_str(text).map_err(|e| format!("cubemap: {e}"))?;
+    let Some(obj) = v.as_object() else {
+        return Err("cubemap: manifest must be a JSON object".to_owned());
+    };
+
+    let meta = match (obj.get("faces"), obj.get("equirect")) {
+        (Some(faces), None) => {
+            let faces = faces
+                .as_object()
+                .ok_or_else(|| "cubemap: 'faces' must be an object".to_owned())?;
+            let paths = FACE_KEYS
+                .iter()
+                .map(|key| path(faces, key))
+                .collect::<Result<Vec<_>, _>>()?;
+            json!({ "kind": "faces", "faces": paths })
+        }
+        (None, Some(_)) => {
+            let image = path(obj, "equirect")?;
+            let face_size = match obj.get("face_size") {
+                None | Some(Value::Null) => Value::Null,
+                Some(n) => match n.as_u64() {
+                    Some(n @ 1..=MAX_FACE_SIZE) => json!(n),
+                    _ => {
+                        return Err(format!(
+                            "cubemap: 'face_size' must be an integer in 1..={MAX_FACE_SIZE}"
+                        ))
+                    }
+                },
+            };
+            json!({ "kind": "equirect", "image": image, "face_size": face_size })
+        }
+        (Some(_), Some(_)) => {
+            return Err("cubemap: give either 'faces' or 'equirect', not both".to_owned())
+        }
+        (None, None) => return Err("cubemap: manifest needs 'faces' or 'equirect'".to_owned()),
+    };
+    Ok(meta.to_string())
+}
+
+fn path(obj: &Map<String, Value>, key: &str) -> Result<String, String> {
+    match obj.get(key).and_then(Value::as_str).map(str::trim) {
+        Some(p) if !p.is_empty() => Ok(p.to_owned()),
+        _ => Err(format!("cubemap: missing image path '{key}'")),
+    }
+}
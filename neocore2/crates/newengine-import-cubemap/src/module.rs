@@ -0,0 +1,124 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use abi_stable::std_types::{RResult, RString, RVec};
+use abi_stable::StableAbi;
+
+use newengine_plugin_api::{
+    guarded_service, Blob, HostApiV1, MethodName, PluginInfo, PluginModule, ServiceV1, ServiceV1Dyn,
+};
+
+use crate::manifest;
+
+/* =============================================================================================
+Wire helpers: [u32 meta_len_le][meta_json utf8][payload bytes]
+============================================================================================= */
+
+#[inline]
+fn pack(meta_json: &str, payload: &[u8]) -> RVec<u8> {
+    let meta = meta_json.as_bytes();
+    let meta_len: u32 = meta.len().min(u32::MAX as usize) as u32;
+
+    let mut out = Vec::with_capacity(4 + meta.len() + payload.len());
+    out.extend_from_slice(&meta_len.to_le_bytes());
+    out.extend_from_slice(meta);
+    out.extend_from_slice(payload);
+    RVec::from(out)
+}
+
+fn import_cubemap(bytes: &[u8]) -> RResult<RVec<u8>, RString> {
+    match manifest::import_manifest(bytes) {
+        Ok(meta_json) => RResult::ROk(pack(&meta_json, &[])),
+        Err(e) => RResult::RErr(RString::from(e)),
+    }
+}
+
+#[derive(StableAbi)]
+#[repr(C)]
+struct CubemapImporterService;
+
+impl CubemapImporterService {
+    const DESCRIBE: &'static str = r#"{
+  "id":"kalitech.import.cubemap.v1",
+  "kind":"asset_importer",
+  "asset_importer":{
+    "priority":100,
+    "extensions":["cubemap"],
+    "output_type_id":"kalitech.asset.cubemap",
+    "format":"cubemap",
+    "method":"import_cubemap_v1",
+    "wire":"u32_meta_len_le + meta_utf8 + payload"
+  },
+  "methods":{
+    "import_cubemap_v1":{"in":"cubemap manifest JSON (six faces or an equirectangular image)","out":"[u32 meta_len_le][meta_json utf8], empty payload"}
+  },
+  "meta_schema":"kalitech.cubemap.meta.v1"
+}"#;
+}
+
+impl ServiceV1 for CubemapImporterService {
+    fn id(&self) -> RString {
+        RString::from("kalitech.import.cubemap.v1")
+    }
+
+    fn describe(&self) -> RString {
+        RString::from(Self::DESCRIBE)
+    }
+
+    fn call(&self, method: MethodName, payload: Blob) -> RResult<Blob, RString> {
+        let bytes: Vec<u8> = payload.into_vec();
+        match method.as_str() {
+            "import_cubemap_v1" => import_cubemap(&bytes),
+            _ => RResult::RErr(RString::from(format!(
+                "cubemap-importer: unknown method '{}'",
+                method
+            ))),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct CubemapImporterPlugin;
+
+impl PluginModule for CubemapImporterPlugin {
+    fn info(&self) -> PluginInfo {
+        PluginInfo {
+            id: RString::from("import.cubemap"),
+            name: RString::from("Cubemap Importer"),
+            version: RString::from(env!("CARGO_PKG_VERSION")),
+            requires: RVec::new(),
+        }
+    }
+
+    fn init(&mut self, host: HostApiV1) -> RResult<(), RString> {
+        let svc: ServiceV1Dyn<'static> = guarded_service(CubemapImporterService);
+
+        let r = (host.register_service_v1)(svc);
+        if let Err(e) = r.clone().into_result() {
+            (host.log_warn)(RString::from(format!(
+                "cubemap-importer: register_service_v1 failed: {}",
+                e
+            )));
+            return r;
+        }
+
+        RResult::ROk(())
+    }
+
+    fn start(&mut self) -> RResult<(), RString> {
+        RResult::ROk(())
+    }
+
+    fn fixed_update(&mut self, _dt: f32) -> RResult<(), RString> {
+        RResult::ROk(())
+    }
+
+    fn update(&mut self, _dt: f32) -> RResult<(), RString> {
+        RResult::ROk(())
+    }
+
+    fn render(&mut self, _dt: f32) -> RResult<(), RString> {
+        RResult::ROk(())
+    }
+
+    fn shutdown(&mut self) {}
+}
@@ -0,0 +1,5 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+pub mod module;
+pub mod plugin;
+pub mod providers;
@@ -0,0 +1,28 @@
+use ttf_parser::{name_id, Face};
+
+use super::FontMetaV1;
+
+/// Reads the metrics both containers share; the first face of a collection is used.
+#[inline]
+pub fn probe_ttf_parser(bytes: &[u8], container: &'static str) -> Result<FontMetaV1, String> {
+    let face = Face::parse(bytes, 0).map_err(|e| format!("font: {container}: {e}"))?;
+
+    let family = face
+        .names()
+        .into_iter()
+        .filter(|n| n.name_id == name_id::TYPOGRAPHIC_FAMILY || n.name_id == name_id::FAMILY)
+        .filter_map(|n| n.to_string().map(|s| (n.name_id, s)))
+        .min_by_key(|(id, _)| *id != name_id::TYPOGRAPHIC_FAMILY)
+        .map(|(_, s)| s)
+        .unwrap_or_default();
+
+    Ok(FontMetaV1 {
+        container,
+        family,
+        units_per_em: face.units_per_em(),
+        ascender: face.ascender(),
+        descender: face.descender(),
+        line_gap: face.line_gap(),
+        glyph_count: face.number_of_glyphs(),
+    })
+}
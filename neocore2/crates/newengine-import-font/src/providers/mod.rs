@@ -0,0 +1,35 @@
+pub struct FontMetaV1 {
+    pub container: &'static str,
+    pub family: String,
+    pub units_per_em: u16,
+    pub ascender: i16,
+    pub descender: i16,
+    pub line_gap: i16,
+    pub glyph_count: u16,
+}
+
+pub trait FontProviderV1: Sync + Send + 'static {
+    fn container(&self) -> &'static str;
+    fn extensions(&self) -> &'static [&'static str];
+    fn sniff(&self, bytes: &[u8]) -> bool;
+    fn probe_meta(&self, bytes: &[u8]) -> Result<FontMetaV1, String>;
+
+    fn describe_json(&self) -> &'static str;
+}
+
+pub struct ProviderEntry {
+    pub provider: &'static dyn FontProviderV1,
+}
+
+inventory::collect!(ProviderEntry);
+
+#[inline]
+pub fn iter_providers() -> impl Iterator<Item = &'static dyn FontProviderV1> {
+    inventory::iter::<ProviderEntry>
+        .into_iter()
+        .map(|e| e.provider)
+}
+
+pub mod common;
+pub mod otf;
+pub mod ttf;
@@ -0,0 +1,29 @@
+use crate::providers::{common, FontMetaV1, FontProviderV1, ProviderEntry};
+
+pub struct OtfProvider;
+
+impl FontProviderV1 for OtfProvider {
+    fn container(&self) -> &'static str {
+        "otf"
+    }
+    fn extensions(&self) -> &'static [&'static str] {
+        &["otf"]
+    }
+
+    fn sniff(&self, bytes: &[u8]) -> bool {
+        bytes.len() >= 4 && &bytes[0..4] == b"OTTO"
+    }
+
+    fn probe_meta(&self, bytes: &[u8]) -> Result<FontMetaV1, String> {
+        common::probe_ttf_parser(bytes, "otf")
+    }
+
+    fn describe_json(&self) -> &'static str {
+        r#"{"container":"otf","extensions":["otf"],"sniff":"OTTO","method":"import_font_v1"}"#
+    }
+}
+
+static PROVIDER: OtfProvider = OtfProvider;
+inventory::submit!(ProviderEntry {
+    provider: &PROVIDER
+});
@@ -0,0 +1,29 @@
+use crate::providers::{common, FontMetaV1, FontProviderV1, ProviderEntry};
+
+pub struct TtfProvider;
+
+impl FontProviderV1 for TtfProvider {
+    fn container(&self) -> &'static str {
+        "ttf"
+    }
+    fn extensions(&self) -> &'static [&'static str] {
+        &["ttf"]
+    }
+
+    fn sniff(&self, bytes: &[u8]) -> bool {
+        bytes.len() >= 4 && (bytes[0..4] == [0x00, 0x01, 0x00, 0x00] || &bytes[0..4] == b"true")
+    }
+
+    fn probe_meta(&self, bytes: &[u8]) -> Result<FontMetaV1, String> {
+        common::probe_ttf_parser(bytes, "ttf")
+    }
+
+    fn describe_json(&self) -> &'static str {
+        r#"{"container":"ttf","extensions":["ttf"],"sniff":"00010000|true","method":"import_font_v1"}"#
+    }
+}
+
+static PROVIDER: TtfProvider = TtfProvider;
+inventory::submit!(ProviderEntry {
+    provider: &PROVIDER
+});
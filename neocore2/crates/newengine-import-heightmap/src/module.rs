@@ -0,0 +1,159 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use abi_stable::std_types::{RResult, RString, RVec};
+use abi_stable::StableAbi;
+
+use newengine_plugin_api::{
+    guarded_service, Blob, HostApiV1, MethodName, PluginInfo, PluginModule, ServiceV1, ServiceV1Dyn,
+};
+
+use std::sync::OnceLock;
+
+use crate::raw::{self, RawSettings};
+
+/* =============================================================================================
+Wire helpers: [u32 meta_len_le][meta_json utf8][payload bytes]
+============================================================================================= */
+
+#[inline]
+fn pack(meta_json: &str, payload: &[u8]) -> RVec<u8> {
+    let meta = meta_json.as_bytes();
+    let meta_len: u32 = meta.len().min(u32::MAX as usize) as u32;
+
+    let mut out = Vec::with_capacity(4 + meta.len() + payload.len());
+    out.extend_from_slice(&meta_len.to_le_bytes());
+    out.extend_from_slice(meta);
+    out.extend_from_slice(payload);
+    RVec::from(out)
+}
+
+/// Splits `import_heightmap_v2` input: `[u32 settings_len_le][settings_json][bytes]`.
+#[inline]
+fn unpack_settings(frame: &[u8]) -> Result<(RawSettings, &[u8]), String> {
+    if frame.len() < 4 {
+        return Err("heightmap: settings frame too small".to_owned());
+    }
+    let len = u32::from_le_bytes([frame[0], frame[1], frame[2], frame[3]]) as usize;
+    let end = 4usize.saturating_add(len);
+    if frame.len() < end {
+        return Err("heightmap: truncated import settings".to_owned());
+    }
+    let json = std::str::from_utf8(&frame[4..end])
+        .map_err(|_| "heightmap: import settings are not utf8".to_owned())?;
+    Ok((RawSettings::from_json(json)?, &frame[end..]))
+}
+
+fn import_heightmap(bytes: &[u8], settings: RawSettings) -> RResult<RVec<u8>, RString> {
+    match raw::import_r16(bytes, settings) {
+        Ok(h) => RResult::ROk(pack(&h.meta_json, &h.payload)),
+        Err(e) => RResult::RErr(RString::from(e)),
+    }
+}
+
+#[derive(StableAbi)]
+#[repr(C)]
+struct HeightmapImporterService;
+
+impl HeightmapImporterService {
+    fn describe_cached() -> &'static str {
+        static DESCRIBE: OnceLock<String> = OnceLock::new();
+        DESCRIBE
+            .get_or_init(|| {
+                format!(
+                    r#"{{
+  "id":"kalitech.import.heightmap.v1",
+  "kind":"asset_importer",
+  "asset_importer":{{
+    "priority":100,
+    "extensions":["r16","raw"],
+    "output_type_id":"kalitech.asset.heightmap",
+    "format":"r16",
+    "method":"import_heightmap_v1",
+    "wire":"u32_meta_len_le + meta_utf8 + payload",
+    "settings_method":"import_heightmap_v2",
+    "default_settings":{settings_json}
+  }},
+  "methods":{{
+    "import_heightmap_v1":{{"in":"headerless u16 samples, square, little-endian","out":"[u32 meta_len_le][meta_json][u16 le samples]"}},
+    "import_heightmap_v2":{{"in":"[u32 settings_len_le][settings_json][u16 samples]","out":"[u32 meta_len_le][meta_json][u16 le samples]"}}
+  }},
+  "meta_schema":"kalitech.heightmap.meta.v1"
+}}"#,
+                    settings_json = RawSettings::default().to_json(),
+                )
+            })
+            .as_str()
+    }
+}
+
+impl ServiceV1 for HeightmapImporterService {
+    fn id(&self) -> RString {
+        RString::from("kalitech.import.heightmap.v1")
+    }
+
+    fn describe(&self) -> RString {
+        RString::from(Self::describe_cached())
+    }
+
+    fn call(&self, method: MethodName, payload: Blob) -> RResult<Blob, RString> {
+        let bytes: Vec<u8> = payload.into_vec();
+        match method.as_str() {
+            "import_heightmap_v1" => import_heightmap(&bytes, RawSettings::default()),
+            "import_heightmap_v2" => match unpack_settings(&bytes) {
+                Ok((settings, input)) => import_heightmap(input, settings),
+                Err(e) => RResult::RErr(RString::from(e)),
+            },
+            _ => RResult::RErr(RString::from(format!(
+                "heightmap-importer: unknown method '{}'",
+                method
+            ))),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct HeightmapImporterPlugin;
+
+impl PluginModule for HeightmapImporterPlugin {
+    fn info(&self) -> PluginInfo {
+        PluginInfo {
+            id: RString::from("import.heightmap"),
+            name: RString::from("Heightmap Importer"),
+            version: RString::from(env!("CARGO_PKG_VERSION")),
+            requires: RVec::new(),
+        }
+    }
+
+    fn init(&mut self, host: HostApiV1) -> RResult<(), RString> {
+        let svc: ServiceV1Dyn<'static> = guarded_service(HeightmapImporterService);
+
+        let r = (host.register_service_v1)(svc);
+        if let Err(e) = r.clone().into_result() {
+            (host.log_warn)(RString::from(format!(
+                "heightmap-importer: register_service_v1 failed: {}",
+                e
+            )));
+            return r;
+        }
+
+        RResult::ROk(())
+    }
+
+    fn start(&mut self) -> RResult<(), RString> {
+        RResult::ROk(())
+    }
+
+    fn fixed_update(&mut self, _dt: f32) -> RResult<(), RString> {
+        RResult::ROk(())
+    }
+
+    fn update(&mut self, _dt: f32) -> RResult<(), RString> {
+        RResult::ROk(())
+    }
+
+    fn render(&mut self, _dt: f32) -> RResult<(), RString> {
+        RResult::ROk(())
+    }
+
+    fn shutdown(&mut self) {}
+}
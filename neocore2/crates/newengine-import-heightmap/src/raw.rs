@@ -0,0 +1,128 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use serde_json::{json, Value};
+
+/// Upper bound on samples, so a wrong size setting cannot allocate gigabytes.
+const MAX_SAMPLES: u64 = 8193 * 8193;
+
+/// Per-import options, sent as JSON with `import_heightmap_v2`.
+///
+/// ```json
+/// {"width":1025,"height":1025,"big_endian":false}
+/// ```
+///
+/// RAW files carry no header: with `width` and `height` both 0 the map is taken to be square,
+/// with one of them 0 it is derived from the other. Missing or mistyped keys keep their
+/// defaults.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) struct RawSettings {
+    pub width: u32,
+    pub height: u32,
+    /// Byte order of the samples; most tools write little-endian `.r16`.
+    pub big_endian: bool,
+}
+
+impl RawSettings {
+    pub fn from_json(s: &str) -> Result<Self, String> {
+        if s.trim().is_empty() {
+            return Ok(Self::default());
+        }
+        let v: Value =
+            serde_json::from_str(s).map_err(|e| format!("heightmap: bad import settings: {e}"))?;
+        let Some(obj) = v.as_object() else {
+            return Err("heightmap: import settings must be a JSON object".to_owned());
+        };
+
+        let mut out = Self::default();
+        if let Some(n) = obj.get("width").and_then(Value::as_u64) {
+            out.width = n.min(u32::MAX as u64) as u32;
+        }
+        if let Some(n) = obj.get("height").and_then(Value::as_u64) {
+            out.height = n.min(u32::MAX as u64) as u32;
+        }
+        if let Some(b) = obj.get("big_endian").and_then(Value::as_bool) {
+            out.big_endian = b;
+        }
+        Ok(out)
+    }
+
+    pub fn to_json(self) -> String {
+        format!(
+            "{{\"width\":{},\"height\":{},\"big_endian\":{}}}",
+            self.width, self.height, self.big_endian
+        )
+    }
+
+    /// Map size for `samples` samples.
+    fn dimensions(self, samples: u64) -> Result<(u32, u32), String> {
+        let (w, h) = match (self.width as u64, self.height as u64) {
+            (0, 0) => {
+                let side = (samples as f64).sqrt().round() as u64;
+                if side * side != samples {
+                    return Err(format!(
+                        "heightmap: {samples} samples do not form a square; set width and height"
+                    ));
+                }
+                (side, side)
+            }
+            (0, h) => (samples / h, h),
+            (w, 0) => (w, samples / w),
+            (w, h) => (w, h),
+        };
+        if w < 2 || h < 2 || w * h != samples {
+            return Err(format!(
+                "heightmap: {w}x{h} does not match {samples} samples"
+            ));
+        }
+        Ok((w as u32, h as u32))
+    }
+}
+
+pub(crate) struct Imported {
+    pub meta_json: String,
+    pub payload: Vec<u8>,
+}
+
+/// Imports headerless 16-bit samples (`.r16` / `.raw`), rows first.
+///
+/// Output payload: `width * height` little-endian u16 samples, row 0 first; meta schema
+/// `kalitech.heightmap.meta.v1` with the size and the sample range.
+pub(crate) fn import_r16(bytes: &[u8], settings: RawSettings) -> Result<Imported, String> {
+    if !bytes.len().is_multiple_of(2) {
+        return Err(format!(
+            "heightmap: {} bytes is not a whole number of 16-bit samples",
+            bytes.len()
+        ));
+    }
+    let samples = (bytes.len() / 2) as u64;
+    if samples > MAX_SAMPLES {
+        return Err(format!("heightmap: {samples} samples exceed {MAX_SAMPLES}"));
+    }
+    let (width, height) = settings.dimensions(samples)?;
+
+    let mut payload = Vec::with_capacity(bytes.len());
+    let (mut min, mut max) = (u16::MAX, u16::MIN);
+    for b in bytes.chunks_exact(2) {
+        let v = if settings.big_endian {
+            u16::from_be_bytes([b[0], b[1]])
+        } else {
+            u16::from_le_bytes([b[0], b[1]])
+        };
+        min = min.min(v);
+        max = max.max(v);
+        payload.extend_from_slice(&v.to_le_bytes());
+    }
+
+    let meta = json!({
+        "schema": "kalitech.heightmap.meta.v1",
+        "format": "r16",
+        "width": width,
+        "height": height,
+        "min": min,
+        "max": max,
+    });
+    Ok(Imported {
+        meta_json: meta.to_string(),
+        payload,
+    })
+}
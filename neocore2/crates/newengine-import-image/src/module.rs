@@ -1,17 +1,19 @@
 #![forbid(unsafe_op_in_unsafe_fn)]
 
-use abi_stable::sabi_trait::TD_Opaque;
 use abi_stable::std_types::{RResult, RString, RVec};
 use abi_stable::StableAbi;
 
 use newengine_plugin_api::{
-    Blob, HostApiV1, MethodName, PluginInfo, PluginModule, ServiceV1, ServiceV1Dyn, ServiceV1_TO,
+    guarded_service, Blob, HostApiV1, MethodName, PluginInfo, PluginModule, ServiceV1, ServiceV1Dyn,
 };
 
 use std::sync::OnceLock;
 
 use crate::providers;
 
+/// Host probe for a cancelled import (`HostApiV1::import_cancelled`), set in `init`.
+static IMPORT_CANCELLED: OnceLock<extern "C" fn() -> bool> = OnceLock::new();
+
 #[inline]
 fn err(msg: impl Into<String>) -> RResult<RVec<u8>, RString> {
     RResult::RErr(RString::from(msg.into()))
@@ -24,6 +26,10 @@ struct ImageImporterService;
 impl ImageImporterService {
     #[inline]
     fn import_auto(bytes: &[u8]) -> RResult<RVec<u8>, RString> {
+        // Providers read headers and pass the bytes through, so one check up front is enough.
+        if IMPORT_CANCELLED.get().is_some_and(|cancelled| cancelled()) {
+            return err("image: import cancelled");
+        }
         for p in providers::iter_providers() {
             if p.sniff(bytes) {
                 return p.import(bytes);
@@ -122,11 +128,14 @@ impl PluginModule for ImageImporterPlugin {
             id: RString::from("import.image"),
             name: RString::from("Image Importer (Provider-based)"),
             version: RString::from(env!("CARGO_PKG_VERSION")),
+            requires: RVec::new(),
         }
     }
 
     fn init(&mut self, host: HostApiV1) -> RResult<(), RString> {
-        let svc: ServiceV1Dyn<'static> = ServiceV1_TO::from_value(ImageImporterService, TD_Opaque);
+        let _ = IMPORT_CANCELLED.set(host.import_cancelled);
+
+        let svc: ServiceV1Dyn<'static> = guarded_service(ImageImporterService);
 
         let r = (host.register_service_v1)(svc);
         if let Err(e) = r.clone().into_result() {
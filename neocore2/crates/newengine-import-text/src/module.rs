@@ -1,10 +1,9 @@
 #![forbid(unsafe_op_in_unsafe_fn)]
 
-use abi_stable::sabi_trait::TD_Opaque;
 use abi_stable::std_types::{RResult, RString, RVec};
 
 use newengine_plugin_api::{
-    Blob, HostApiV1, MethodName, PluginInfo, PluginModule, ServiceV1, ServiceV1Dyn, ServiceV1_TO,
+    guarded_service, Blob, HostApiV1, MethodName, PluginInfo, PluginModule, ServiceV1, ServiceV1Dyn,
 };
 
 use crate::providers::{self, TextMetaV1};
@@ -148,6 +147,7 @@ impl PluginModule for TextImporterPlugin {
             id: RString::from("import.text"),
             name: RString::from("Text Importer (Provider-based)"),
             version: RString::from(env!("CARGO_PKG_VERSION")),
+            requires: RVec::new(),
         }
     }
 
@@ -160,7 +160,7 @@ impl PluginModule for TextImporterPlugin {
                 provider: p,
             };
 
-            let dyn_svc: ServiceV1Dyn<'static> = ServiceV1_TO::from_value(svc, TD_Opaque);
+            let dyn_svc: ServiceV1Dyn<'static> = guarded_service(svc);
 
             let r = (host.register_service_v1)(dyn_svc);
             if let Err(e) = r.clone().into_result() {
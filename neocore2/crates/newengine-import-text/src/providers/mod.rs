@@ -55,6 +55,8 @@ pub mod ron;
 pub mod toml;
 pub mod yaml;
 
+pub mod rhai;
+
 pub mod glsl;
 pub mod hlsl;
 pub mod shader_stage;
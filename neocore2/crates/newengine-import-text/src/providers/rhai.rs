@@ -0,0 +1,30 @@
+use crate::providers::{ProviderEntry, TextProviderV1};
+
+pub struct RhaiProvider;
+
+impl TextProviderV1 for RhaiProvider {
+    fn service_id(&self) -> &'static str {
+        "kalitech.import.rhai.v1"
+    }
+
+    fn container(&self) -> &'static str {
+        "rhai"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["rhai"]
+    }
+
+    fn mime(&self) -> &'static str {
+        "text/plain"
+    }
+
+    fn describe_json(&self) -> &'static str {
+        r#"{"service_id":"kalitech.import.rhai.v1","container":"rhai","extensions":["rhai"],"mime":"text/plain","method":"import_text_v1"}"#
+    }
+}
+
+static PROVIDER: RhaiProvider = RhaiProvider;
+inventory::submit!(ProviderEntry {
+    provider: &PROVIDER
+});
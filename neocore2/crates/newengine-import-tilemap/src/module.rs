@@ -0,0 +1,150 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use abi_stable::std_types::{RResult, RString, RVec};
+use abi_stable::StableAbi;
+
+use newengine_plugin_api::{
+    guarded_service, Blob, HostApiV1, MethodName, PluginInfo, PluginModule, ServiceV1, ServiceV1Dyn,
+};
+
+use crate::tiled::{self, Imported};
+
+/* =============================================================================================
+Wire helpers: [u32 meta_len_le][meta_json utf8][payload bytes]
+============================================================================================= */
+
+#[inline]
+fn pack(meta_json: &str, payload: &[u8]) -> RVec<u8> {
+    let meta = meta_json.as_bytes();
+    let meta_len: u32 = meta.len().min(u32::MAX as usize) as u32;
+
+    let mut out = Vec::with_capacity(4 + meta.len() + payload.len());
+    out.extend_from_slice(&meta_len.to_le_bytes());
+    out.extend_from_slice(meta);
+    out.extend_from_slice(payload);
+    RVec::from(out)
+}
+
+fn import_tilemap(bytes: &[u8], ext_hint: Option<&str>) -> RResult<RVec<u8>, RString> {
+    let ext = ext_hint
+        .map(|e| e.trim().trim_start_matches('.').to_ascii_lowercase())
+        .unwrap_or_default();
+
+    let imported: Result<Imported, String> = match ext.as_str() {
+        "tmx" => tiled::import_tmx(bytes),
+        "tsx" => tiled::import_tsx(bytes),
+        // No hint: the root element tells maps from tilesets.
+        _ => tiled::import_tmx(bytes)
+            .or_else(|map_err| tiled::import_tsx(bytes).map_err(|_| map_err)),
+    };
+
+    match imported {
+        Ok(t) => RResult::ROk(pack(&t.meta_json, &t.payload)),
+        Err(e) => RResult::RErr(RString::from(e)),
+    }
+}
+
+#[derive(StableAbi)]
+#[repr(C)]
+struct TilemapImporterService;
+
+impl TilemapImporterService {
+    const DESCRIBE: &'static str = r#"{
+  "id":"kalitech.import.tilemap.v1",
+  "kind":"asset_importer",
+  "asset_importer":{
+    "priority":100,
+    "extensions":["tmx","tsx"],
+    "output_type_id":"kalitech.asset.tilemap",
+    "format":"tiled",
+    "method":"import_tilemap_v1",
+    "wire":"u32_meta_len_le + meta_utf8 + payload",
+    "formats":[
+      {"container":"tmx","extensions":["tmx"],"sniff":"xml root <map>","method":"import_tilemap_v1"},
+      {"container":"tsx","extensions":["tsx"],"sniff":"xml root <tileset>","method":"import_tilemap_v1"}
+    ]
+  },
+  "methods":{
+    "import_tilemap_v1":{"in":"Tiled XML bytes","out":"[u32 meta_len_le][meta_json utf8][u32 le gids of every chunk]"}
+  },
+  "meta_schema":"kalitech.tilemap.meta.v1"
+}"#;
+}
+
+impl ServiceV1 for TilemapImporterService {
+    fn id(&self) -> RString {
+        RString::from("kalitech.import.tilemap.v1")
+    }
+
+    fn describe(&self) -> RString {
+        RString::from(Self::DESCRIBE)
+    }
+
+    fn call(&self, method: MethodName, payload: Blob) -> RResult<Blob, RString> {
+        let bytes: Vec<u8> = payload.into_vec();
+
+        match method.as_str() {
+            "import_tilemap_v1" => import_tilemap(&bytes, None),
+
+            _ => {
+                if let Some((base, ext)) = method.as_str().split_once(':') {
+                    if base == "import_tilemap_v1" {
+                        return import_tilemap(&bytes, Some(ext));
+                    }
+                }
+
+                RResult::RErr(RString::from(format!(
+                    "tilemap-importer: unknown method '{}'",
+                    method
+                )))
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct TilemapImporterPlugin;
+
+impl PluginModule for TilemapImporterPlugin {
+    fn info(&self) -> PluginInfo {
+        PluginInfo {
+            id: RString::from("import.tilemap"),
+            name: RString::from("Tiled Map Importer"),
+            version: RString::from(env!("CARGO_PKG_VERSION")),
+            requires: RVec::new(),
+        }
+    }
+
+    fn init(&mut self, host: HostApiV1) -> RResult<(), RString> {
+        let svc: ServiceV1Dyn<'static> = guarded_service(TilemapImporterService);
+
+        let r = (host.register_service_v1)(svc);
+        if let Err(e) = r.clone().into_result() {
+            (host.log_warn)(RString::from(format!(
+                "tilemap-importer: register_service_v1 failed: {}",
+                e
+            )));
+            return r;
+        }
+
+        RResult::ROk(())
+    }
+
+    fn start(&mut self) -> RResult<(), RString> {
+        RResult::ROk(())
+    }
+
+    fn fixed_update(&mut self, _dt: f32) -> RResult<(), RString> {
+        RResult::ROk(())
+    }
+
+    fn update(&mut self, _dt: f32) -> RResult<(), RString> {
+        RResult::ROk(())
+    }
+
+    fn render(&mut self, _dt: f32) -> RResult<(), RString> {
+        RResult::ROk(())
+    }
+
+    fn shutdown(&mut self) {}
+}
@@ -0,0 +1,506 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use base64::Engine as _;
+use roxmltree::{Document, Node};
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+
+/// Tiles per chunk edge; every tile layer is re-chunked to this size, finite or not.
+pub const CHUNK_SIZE: u32 = 16;
+
+/// Upper bound on tiles per layer, so a corrupt header cannot allocate gigabytes.
+const MAX_LAYER_TILES: u64 = 1 << 26;
+
+/// Imported map or tileset: meta JSON and the packed tile payload.
+pub struct Imported {
+    pub meta_json: String,
+    pub payload: Vec<u8>,
+}
+
+/// Imports a `.tmx` map.
+///
+/// Tile layers become chunks of `CHUNK_SIZE`² gids in the payload (u32 little-endian, row by
+/// row, Tiled flip flags kept in the top bits); chunks without any tile are left out. Groups
+/// are flattened into their layers with `/`-joined names and combined offsets, opacity and
+/// visibility. Image layers are skipped.
+pub fn import_tmx(bytes: &[u8]) -> Result<Imported, String> {
+    let text = std::str::from_utf8(bytes).map_err(|e| format!("tmx: not utf-8: {e}"))?;
+    let doc = Document::parse(text).map_err(|e| format!("tmx: {e}"))?;
+    let map = doc.root_element();
+    if !map.has_tag_name("map") {
+        return Err("tmx: root element is not <map>".to_string());
+    }
+
+    let orientation = map.attribute("orientation").unwrap_or("orthogonal");
+    let infinite = attr_u32(map, "infinite", 0) != 0;
+
+    let mut tilesets = Vec::new();
+    for ts in map.children().filter(|n| n.has_tag_name("tileset")) {
+        let first_gid = attr_u32(ts, "firstgid", 1);
+        match ts.attribute("source") {
+            Some(source) => tilesets.push(json!({ "first_gid": first_gid, "source": source })),
+            None => tilesets.push(json!({ "first_gid": first_gid, "tileset": tileset_json(ts)? })),
+        }
+    }
+
+    let mut out = LayerOut::default();
+    collect_layers(map, &LayerCtx::ROOT, &mut out)?;
+
+    let meta = json!({
+        "schema": "kalitech.tilemap.meta.v1",
+        "kind": "map",
+        "orientation": orientation,
+        "render_order": map.attribute("renderorder").unwrap_or("right-down"),
+        "width": attr_u32(map, "width", 0),
+        "height": attr_u32(map, "height", 0),
+        "tile_width": attr_u32(map, "tilewidth", 0),
+        "tile_height": attr_u32(map, "tileheight", 0),
+        "infinite": infinite,
+        "chunk_size": CHUNK_SIZE,
+        "background": map.attribute("backgroundcolor"),
+        "properties": properties_json(map),
+        "tilesets": tilesets,
+        "layers": out.layers,
+    });
+
+    let mut payload = Vec::with_capacity(out.gids.len() * 4);
+    for gid in out.gids {
+        payload.extend_from_slice(&gid.to_le_bytes());
+    }
+    Ok(Imported {
+        meta_json: meta.to_string(),
+        payload,
+    })
+}
+
+/// Imports an external `.tsx` tileset; the payload is empty.
+pub fn import_tsx(bytes: &[u8]) -> Result<Imported, String> {
+    let text = std::str::from_utf8(bytes).map_err(|e| format!("tsx: not utf-8: {e}"))?;
+    let doc = Document::parse(text).map_err(|e| format!("tsx: {e}"))?;
+    let ts = doc.root_element();
+    if !ts.has_tag_name("tileset") {
+        return Err("tsx: root element is not <tileset>".to_string());
+    }
+
+    let meta = json!({
+        "schema": "kalitech.tilemap.meta.v1",
+        "kind": "tileset",
+        "tileset": tileset_json(ts)?,
+    });
+    Ok(Imported {
+        meta_json: meta.to_string(),
+        payload: Vec::new(),
+    })
+}
+
+fn tileset_json(ts: Node<'_, '_>) -> Result<Value, String> {
+    let name = ts.attribute("name").unwrap_or_default();
+    let Some(image) = child(ts, "image") else {
+        return Err(format!(
+            "tileset '{name}': image collection tilesets are not supported"
+        ));
+    };
+
+    let offset = child(ts, "tileoffset")
+        .map(|o| [attr_f32(o, "x", 0.0), attr_f32(o, "y", 0.0)])
+        .unwrap_or([0.0, 0.0]);
+
+    let mut tiles = Vec::new();
+    for tile in ts.children().filter(|n| n.has_tag_name("tile")) {
+        let mut entry = Map::new();
+        entry.insert("id".into(), json!(attr_u32(tile, "id", 0)));
+        if let Some(class) = tile.attribute("type").or_else(|| tile.attribute("class")) {
+            entry.insert("class".into(), json!(class));
+        }
+        let props = properties_json(tile);
+        if !props.as_object().is_some_and(Map::is_empty) {
+            entry.insert("properties".into(), props);
+        }
+        if let Some(group) = child(tile, "objectgroup") {
+            let shapes = objects_json(group)?;
+            if !shapes.is_empty() {
+                entry.insert("collision".into(), Value::Array(shapes));
+            }
+        }
+        if entry.len() > 1 {
+            tiles.push(Value::Object(entry));
+        }
+    }
+
+    Ok(json!({
+        "name": name,
+        "tile_width": attr_u32(ts, "tilewidth", 0),
+        "tile_height": attr_u32(ts, "tileheight", 0),
+        "tile_count": attr_u32(ts, "tilecount", 0),
+        "columns": attr_u32(ts, "columns", 0),
+        "spacing": attr_u32(ts, "spacing", 0),
+        "margin": attr_u32(ts, "margin", 0),
+        "tile_offset": offset,
+        "image": image.attribute("source").unwrap_or_default(),
+        "image_width": attr_u32(image, "width", 0),
+        "image_height": attr_u32(image, "height", 0),
+        "properties": properties_json(ts),
+        "tiles": tiles,
+    }))
+}
+
+/// Inherited state of the groups around a layer.
+struct LayerCtx {
+    prefix: String,
+    offset: [f32; 2],
+    opacity: f32,
+    visible: bool,
+}
+
+impl LayerCtx {
+    const ROOT: Self = Self {
+        prefix: String::new(),
+        offset: [0.0, 0.0],
+        opacity: 1.0,
+        visible: true,
+    };
+
+    fn child(&self, node: Node<'_, '_>) -> Self {
+        let name = node.attribute("name").unwrap_or_default();
+        Self {
+            prefix: if self.prefix.is_empty() {
+                name.to_string()
+            } else {
+                format!("{}/{name}", self.prefix)
+            },
+            offset: [
+                self.offset[0] + attr_f32(node, "offsetx", 0.0),
+                self.offset[1] + attr_f32(node, "offsety", 0.0),
+            ],
+            opacity: self.opacity * attr_f32(node, "opacity", 1.0),
+            visible: self.visible && attr_u32(node, "visible", 1) != 0,
+        }
+    }
+}
+
+#[derive(Default)]
+struct LayerOut {
+    layers: Vec<Value>,
+    gids: Vec<u32>,
+}
+
+fn collect_layers(parent: Node<'_, '_>, ctx: &LayerCtx, out: &mut LayerOut) -> Result<(), String> {
+    for node in parent.children().filter(Node::is_element) {
+        match node.tag_name().name() {
+            "layer" => {
+                let lc = ctx.child(node);
+                let chunks = tile_layer_chunks(node, &lc.prefix, &mut out.gids)?;
+                out.layers.push(json!({
+                    "kind": "tiles",
+                    "id": attr_u32(node, "id", 0),
+                    "name": lc.prefix,
+                    "visible": lc.visible,
+                    "opacity": lc.opacity,
+                    "offset": lc.offset,
+                    "properties": properties_json(node),
+                    "chunks": chunks,
+                }));
+            }
+            "objectgroup" => {
+                let lc = ctx.child(node);
+                out.layers.push(json!({
+                    "kind": "objects",
+                    "id": attr_u32(node, "id", 0),
+                    "name": lc.prefix,
+                    "visible": lc.visible,
+                    "opacity": lc.opacity,
+                    "offset": lc.offset,
+                    "properties": properties_json(node),
+                    "objects": objects_json(node)?,
+                }));
+            }
+            "group" => collect_layers(node, &ctx.child(node), out)?,
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Appends the layer's non-empty chunks to `gids` and describes them.
+fn tile_layer_chunks(
+    layer: Node<'_, '_>,
+    name: &str,
+    gids: &mut Vec<u32>,
+) -> Result<Vec<Value>, String> {
+    let Some(data) = child(layer, "data") else {
+        return Ok(Vec::new());
+    };
+
+    // Source regions: the whole layer for finite maps, `<chunk>`s for infinite ones.
+    let mut regions = Vec::new();
+    let chunk_nodes: Vec<_> = data
+        .children()
+        .filter(|n| n.has_tag_name("chunk"))
+        .collect();
+    if chunk_nodes.is_empty() {
+        let (w, h) = (attr_u32(layer, "width", 0), attr_u32(layer, "height", 0));
+        regions.push((0, 0, w, h, decode_data(data, data, w, h, name)?));
+    } else {
+        for c in chunk_nodes {
+            let (w, h) = (attr_u32(c, "width", 0), attr_u32(c, "height", 0));
+            let (x, y) = (attr_i32(c, "x", 0), attr_i32(c, "y", 0));
+            regions.push((x, y, w, h, decode_data(data, c, w, h, name)?));
+        }
+    }
+
+    // Re-chunk into CHUNK_SIZE tiles; keyed (row, column) so output is row-major.
+    let size = CHUNK_SIZE as i32;
+    let mut chunks: BTreeMap<(i32, i32), Vec<u32>> = BTreeMap::new();
+    for (x0, y0, w, _, tiles) in regions {
+        for (i, &gid) in tiles.iter().enumerate() {
+            if gid == 0 {
+                continue;
+            }
+            let x = x0 + (i as u32 % w) as i32;
+            let y = y0 + (i as u32 / w) as i32;
+            let (cx, cy) = (x.div_euclid(size), y.div_euclid(size));
+            let local = (y.rem_euclid(size) * size + x.rem_euclid(size)) as usize;
+            chunks
+                .entry((cy, cx))
+                .or_insert_with(|| vec![0; (CHUNK_SIZE * CHUNK_SIZE) as usize])[local] = gid;
+        }
+    }
+
+    let mut out = Vec::with_capacity(chunks.len());
+    for ((cy, cx), tiles) in chunks {
+        out.push(json!({ "x": cx * size, "y": cy * size, "offset": gids.len() }));
+        gids.extend_from_slice(&tiles);
+    }
+    Ok(out)
+}
+
+/// Decodes `w * h` gids from `node`, with the encoding and compression declared on `data`.
+fn decode_data(
+    data: Node<'_, '_>,
+    node: Node<'_, '_>,
+    w: u32,
+    h: u32,
+    layer: &str,
+) -> Result<Vec<u32>, String> {
+    let count = w as u64 * h as u64;
+    if count > MAX_LAYER_TILES {
+        return Err(format!("layer '{layer}': {w}x{h} tiles is too large"));
+    }
+    let count = count as usize;
+
+    let text: String = node
+        .children()
+        .filter(Node::is_text)
+        .filter_map(|n| n.text())
+        .collect();
+
+    let gids = match data.attribute("encoding") {
+        Some("csv") => text
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(|s| {
+                s.parse::<u32>()
+                    .map_err(|e| format!("layer '{layer}': bad csv gid '{s}': {e}"))
+            })
+            .collect::<Result<Vec<_>, _>>()?,
+        Some("base64") => {
+            let compact: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+            let raw = base64::engine::general_purpose::STANDARD
+                .decode(compact)
+                .map_err(|e| format!("layer '{layer}': base64: {e}"))?;
+            let bytes = decompress(&raw, data.attribute("compression"), count * 4, layer)?;
+            bytes
+                .chunks_exact(4)
+                .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                .collect()
+        }
+        None => node
+            .children()
+            .filter(|n| n.has_tag_name("tile"))
+            .map(|t| attr_u32(t, "gid", 0))
+            .collect(),
+        Some(other) => return Err(format!("layer '{layer}': unsupported encoding '{other}'")),
+    };
+
+    if gids.len() != count {
+        return Err(format!(
+            "layer '{layer}': expected {count} tiles, got {}",
+            gids.len()
+        ));
+    }
+    Ok(gids)
+}
+
+fn decompress(
+    raw: &[u8],
+    compression: Option<&str>,
+    expected: usize,
+    layer: &str,
+) -> Result<Vec<u8>, String> {
+    let inflate_err = |e| format!("layer '{layer}': inflate: {e:?}");
+    match compression {
+        None | Some("") => Ok(raw.to_vec()),
+        Some("zlib") => miniz_oxide::inflate::decompress_to_vec_zlib_with_limit(raw, expected)
+            .map_err(inflate_err),
+        Some("gzip") => {
+            let body = gzip_body(raw).ok_or_else(|| format!("layer '{layer}': bad gzip header"))?;
+            miniz_oxide::inflate::decompress_to_vec_with_limit(body, expected).map_err(inflate_err)
+        }
+        Some(other) => Err(format!(
+            "layer '{layer}': unsupported compression '{other}'; save the map with zlib or gzip"
+        )),
+    }
+}
+
+/// Raw deflate stream of a gzip member (RFC 1952), past the optional header fields.
+fn gzip_body(raw: &[u8]) -> Option<&[u8]> {
+    const FEXTRA: u8 = 0x04;
+    const FNAME: u8 = 0x08;
+    const FCOMMENT: u8 = 0x10;
+    const FHCRC: u8 = 0x02;
+
+    if raw.len() < 18 || raw[0] != 0x1f || raw[1] != 0x8b || raw[2] != 8 {
+        return None;
+    }
+    let flags = raw[3];
+    let mut at = 10usize;
+    if flags & FEXTRA != 0 {
+        let len = u16::from_le_bytes([*raw.get(at)?, *raw.get(at + 1)?]) as usize;
+        at += 2 + len;
+    }
+    for flag in [FNAME, FCOMMENT] {
+        if flags & flag != 0 {
+            at += raw.get(at..)?.iter().position(|&b| b == 0)? + 1;
+        }
+    }
+    if flags & FHCRC != 0 {
+        at += 2;
+    }
+    // The member ends with CRC32 and ISIZE.
+    raw.get(at..raw.len().checked_sub(8)?)
+}
+
+fn objects_json(group: Node<'_, '_>) -> Result<Vec<Value>, String> {
+    let mut out = Vec::new();
+    for obj in group.children().filter(|n| n.has_tag_name("object")) {
+        let mut entry = Map::new();
+        entry.insert("id".into(), json!(attr_u32(obj, "id", 0)));
+        entry.insert(
+            "name".into(),
+            json!(obj.attribute("name").unwrap_or_default()),
+        );
+        entry.insert(
+            "class".into(),
+            json!(obj
+                .attribute("type")
+                .or_else(|| obj.attribute("class"))
+                .unwrap_or_default()),
+        );
+        entry.insert("x".into(), json!(attr_f32(obj, "x", 0.0)));
+        entry.insert("y".into(), json!(attr_f32(obj, "y", 0.0)));
+        entry.insert("width".into(), json!(attr_f32(obj, "width", 0.0)));
+        entry.insert("height".into(), json!(attr_f32(obj, "height", 0.0)));
+        entry.insert("rotation".into(), json!(attr_f32(obj, "rotation", 0.0)));
+        entry.insert("visible".into(), json!(attr_u32(obj, "visible", 1) != 0));
+        if let Some(gid) = obj.attribute("gid") {
+            let gid = gid
+                .parse::<u32>()
+                .map_err(|e| format!("object {}: bad gid: {e}", attr_u32(obj, "id", 0)))?;
+            entry.insert("gid".into(), json!(gid));
+        }
+
+        let shape = if child(obj, "ellipse").is_some() {
+            json!("ellipse")
+        } else if child(obj, "point").is_some() {
+            json!("point")
+        } else if let Some(poly) = child(obj, "polygon") {
+            entry.insert("points".into(), points_json(poly)?);
+            json!("polygon")
+        } else if let Some(line) = child(obj, "polyline") {
+            entry.insert("points".into(), points_json(line)?);
+            json!("polyline")
+        } else {
+            json!("rect")
+        };
+        entry.insert("shape".into(), shape);
+
+        let props = properties_json(obj);
+        if !props.as_object().is_some_and(Map::is_empty) {
+            entry.insert("properties".into(), props);
+        }
+        out.push(Value::Object(entry));
+    }
+    Ok(out)
+}
+
+fn points_json(node: Node<'_, '_>) -> Result<Value, String> {
+    let mut points = Vec::new();
+    for pair in node
+        .attribute("points")
+        .unwrap_or_default()
+        .split_whitespace()
+    {
+        let parsed = pair
+            .split_once(',')
+            .and_then(|(x, y)| Some([x.parse::<f32>().ok()?, y.parse::<f32>().ok()?]));
+        match parsed {
+            Some(p) => points.push(json!(p)),
+            None => return Err(format!("bad point '{pair}'")),
+        }
+    }
+    Ok(Value::Array(points))
+}
+
+/// `<properties>` of `node` as a JSON object; int/float/bool keep their type, everything
+/// else (strings, colors, files, objects) is a string.
+fn properties_json(node: Node<'_, '_>) -> Value {
+    let mut out = Map::new();
+    let Some(props) = child(node, "properties") else {
+        return Value::Object(out);
+    };
+    for p in props.children().filter(|n| n.has_tag_name("property")) {
+        let Some(name) = p.attribute("name") else {
+            continue;
+        };
+        let raw = p
+            .attribute("value")
+            .map(str::to_string)
+            .or_else(|| p.text().map(str::to_string))
+            .unwrap_or_default();
+        let value = match p.attribute("type") {
+            Some("int") => raw.parse::<i64>().map(Value::from).unwrap_or(Value::Null),
+            Some("float") => raw.parse::<f64>().map(Value::from).unwrap_or(Value::Null),
+            Some("bool") => Value::Bool(raw == "true"),
+            _ => Value::String(raw),
+        };
+        out.insert(name.to_string(), value);
+    }
+    Value::Object(out)
+}
+
+#[inline]
+fn child<'a, 'i>(node: Node<'a, 'i>, tag: &str) -> Option<Node<'a, 'i>> {
+    node.children().find(|n| n.has_tag_name(tag))
+}
+
+#[inline]
+fn attr_u32(node: Node<'_, '_>, name: &str, default: u32) -> u32 {
+    node.attribute(name)
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(default)
+}
+
+#[inline]
+fn attr_i32(node: Node<'_, '_>, name: &str, default: i32) -> i32 {
+    node.attribute(name)
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(default)
+}
+
+#[inline]
+fn attr_f32(node: Node<'_, '_>, name: &str, default: f32) -> f32 {
+    node.attribute(name)
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(default)
+}
@@ -0,0 +1,125 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use parking_lot::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::table::StringTable;
+
+/// Loaded string tables plus the active and fallback locales.
+#[derive(Debug, Default)]
+pub struct Localization {
+    current: String,
+    fallback: String,
+    tables: HashMap<String, StringTable>,
+    generation: u64,
+}
+
+impl Localization {
+    #[inline]
+    pub fn new(current: impl Into<String>, fallback: impl Into<String>) -> Self {
+        Self {
+            current: current.into(),
+            fallback: fallback.into(),
+            tables: HashMap::new(),
+            generation: 0,
+        }
+    }
+
+    #[inline]
+    pub fn locale(&self) -> &str {
+        &self.current
+    }
+
+    #[inline]
+    pub fn fallback_locale(&self) -> &str {
+        &self.fallback
+    }
+
+    /// Bumped on every table or locale change; consumers cache against it.
+    #[inline]
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Sorted list of locales that have a loaded table.
+    pub fn locales(&self) -> Vec<String> {
+        let mut out: Vec<String> = self.tables.keys().cloned().collect();
+        out.sort();
+        out
+    }
+
+    pub fn insert_table(&mut self, table: StringTable) {
+        log::info!(
+            target: "localization",
+            "table.insert locale='{}' keys={}",
+            table.locale,
+            table.len()
+        );
+        self.tables.insert(table.locale.clone(), table);
+        self.generation = self.generation.wrapping_add(1);
+    }
+
+    /// Switches the active locale. Fails if no table is loaded for it.
+    pub fn set_locale(&mut self, locale: &str) -> Result<(), String> {
+        let locale = locale.trim();
+        if !self.tables.contains_key(locale) {
+            return Err(format!("locale '{locale}' is not loaded"));
+        }
+        if self.current != locale {
+            log::info!(target: "localization", "locale.set from='{}' to='{}'", self.current, locale);
+            self.current = locale.to_string();
+            self.generation = self.generation.wrapping_add(1);
+        }
+        Ok(())
+    }
+
+    /// Looks up `key` in the active locale, then in the fallback locale.
+    pub fn lookup(&self, key: &str) -> Option<&str> {
+        self.tables
+            .get(&self.current)
+            .and_then(|t| t.get(key))
+            .or_else(|| self.tables.get(&self.fallback).and_then(|t| t.get(key)))
+    }
+
+    /// Like [`Self::lookup`] but returns the key itself when missing, so gaps stay visible.
+    #[inline]
+    pub fn tr<'a>(&'a self, key: &'a str) -> &'a str {
+        self.lookup(key).unwrap_or(key)
+    }
+
+    /// Merged view (fallback overlaid by current) for consumers that resolve many keys per frame.
+    pub fn resolved_strings(&self) -> HashMap<String, String> {
+        let mut out = HashMap::new();
+        if let Some(t) = self.tables.get(&self.fallback) {
+            out.extend(t.strings.iter().map(|(k, v)| (k.clone(), v.clone())));
+        }
+        if self.current != self.fallback {
+            if let Some(t) = self.tables.get(&self.current) {
+                out.extend(t.strings.iter().map(|(k, v)| (k.clone(), v.clone())));
+            }
+        }
+        out
+    }
+}
+
+/// Shared handle registered in `Resources` under [`crate::LOCALIZATION_API_ID`].
+#[derive(Debug, Clone, Default)]
+pub struct LocalizationApiRef(Arc<RwLock<Localization>>);
+
+impl LocalizationApiRef {
+    #[inline]
+    pub fn new(loc: Localization) -> Self {
+        Self(Arc::new(RwLock::new(loc)))
+    }
+
+    #[inline]
+    pub fn read(&self) -> RwLockReadGuard<'_, Localization> {
+        self.0.read()
+    }
+
+    #[inline]
+    pub fn write(&self) -> RwLockWriteGuard<'_, Localization> {
+        self.0.write()
+    }
+}
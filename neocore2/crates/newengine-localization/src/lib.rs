@@ -0,0 +1,18 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+mod api;
+mod module;
+mod service;
+mod table;
+
+pub use api::{Localization, LocalizationApiRef};
+pub use module::{LocalizationConfig, LocalizationModule};
+pub use service::{method, LOCALIZATION_SERVICE_ID};
+pub use table::{StringTable, StringTableError};
+
+use newengine_core::{ApiProvide, ApiVersion};
+
+pub const LOCALIZATION_API_ID: &str = "localization.api";
+pub const LOCALIZATION_API_VERSION: ApiVersion = ApiVersion::new(0, 1, 0);
+pub const LOCALIZATION_API_PROVIDE: ApiProvide =
+    ApiProvide::new(LOCALIZATION_API_ID, LOCALIZATION_API_VERSION);
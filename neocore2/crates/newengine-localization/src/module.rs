@@ -0,0 +1,198 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use newengine_assets::{AssetId, AssetState, TextReader};
+use newengine_core::assets::AssetManager;
+use newengine_core::{ApiProvide, EngineError, EngineResult, Module, ModuleCtx};
+use newengine_plugin_api::{guarded_service, ServiceV1Dyn};
+
+use crate::api::{Localization, LocalizationApiRef};
+use crate::service::{LocalizationService, LOCALIZATION_SERVICE_ID};
+use crate::table::StringTable;
+use crate::{LOCALIZATION_API_ID, LOCALIZATION_API_PROVIDE};
+
+#[derive(Debug, Clone)]
+pub struct LocalizationConfig {
+    /// Asset directory holding `<locale>.json` string tables.
+    pub dir: String,
+    pub locale: String,
+    pub fallback_locale: String,
+    /// Locales to load at start. The active and fallback locales are always included.
+    pub locales: Vec<String>,
+}
+
+impl LocalizationConfig {
+    #[inline]
+    pub fn new(locale: impl Into<String>) -> Self {
+        Self {
+            dir: "locale".to_string(),
+            locale: locale.into(),
+            fallback_locale: "en".to_string(),
+            locales: Vec::new(),
+        }
+    }
+
+    #[inline]
+    pub fn with_dir(mut self, dir: impl Into<String>) -> Self {
+        self.dir = dir.into();
+        self
+    }
+
+    #[inline]
+    pub fn with_fallback(mut self, locale: impl Into<String>) -> Self {
+        self.fallback_locale = locale.into();
+        self
+    }
+
+    #[inline]
+    pub fn with_locales(mut self, locales: Vec<String>) -> Self {
+        self.locales = locales;
+        self
+    }
+
+    fn all_locales(&self) -> Vec<String> {
+        let mut out = vec![self.locale.clone(), self.fallback_locale.clone()];
+        out.extend(self.locales.iter().cloned());
+        out.retain(|l| !l.trim().is_empty());
+        out.sort();
+        out.dedup();
+        out
+    }
+}
+
+impl Default for LocalizationConfig {
+    #[inline]
+    fn default() -> Self {
+        Self::new("en")
+    }
+}
+
+/// Loads string tables through the AssetManager and exposes them as `localization.api`.
+///
+/// Tables are requested in `start` and picked up in `update` as they become ready, so the
+/// module never blocks a frame on IO.
+pub struct LocalizationModule {
+    config: LocalizationConfig,
+    api: LocalizationApiRef,
+    pending: Vec<(String, AssetId)>,
+    service_registered: bool,
+}
+
+impl LocalizationModule {
+    #[inline]
+    pub fn new(config: LocalizationConfig) -> Self {
+        let api = LocalizationApiRef::new(Localization::new(
+            config.locale.clone(),
+            config.fallback_locale.clone(),
+        ));
+        Self {
+            config,
+            api,
+            pending: Vec::new(),
+            service_registered: false,
+        }
+    }
+
+    /// Handle for consumers living outside the engine (e.g. the UI build callback).
+    #[inline]
+    pub fn api(&self) -> LocalizationApiRef {
+        self.api.clone()
+    }
+
+    fn poll_pending(&mut self, am: &AssetManager) {
+        let api = &self.api;
+        self.pending.retain(|(locale, id)| match am.state(*id) {
+            AssetState::Ready => {
+                let Some(blob) = am.get_blob(*id) else {
+                    return false;
+                };
+                let parsed = TextReader::from_blob_parts(&blob.meta_json, &blob.payload)
+                    .map_err(|e| e.to_string())
+                    .and_then(|doc| {
+                        StringTable::from_json(locale, &doc.text).map_err(|e| e.to_string())
+                    });
+                match parsed {
+                    Ok(table) => api.write().insert_table(table),
+                    Err(e) => log::warn!(
+                        target: "localization",
+                        "table.parse failed locale='{locale}' err='{e}'"
+                    ),
+                }
+                false
+            }
+            AssetState::Failed(e) => {
+                log::warn!(
+                    target: "localization",
+                    "table.load failed locale='{locale}' err='{e}'"
+                );
+                false
+            }
+            AssetState::Loading | AssetState::Unloaded => true,
+        });
+    }
+}
+
+impl<E: Send + 'static> Module<E> for LocalizationModule {
+    fn id(&self) -> &'static str {
+        "localization"
+    }
+
+    fn provides(&self) -> &'static [ApiProvide] {
+        &[LOCALIZATION_API_PROVIDE]
+    }
+
+    fn init(&mut self, ctx: &mut ModuleCtx<'_, E>) -> EngineResult<()> {
+        ctx.resources_mut()
+            .register_api(LOCALIZATION_API_ID, self.api.clone())?;
+
+        let svc = LocalizationService::new(self.api.clone());
+        let dyn_svc: ServiceV1Dyn<'static> = guarded_service(svc);
+        newengine_core::register_service_v1(dyn_svc).map_err(EngineError::other)?;
+        self.service_registered = true;
+
+        Ok(())
+    }
+
+    fn start(&mut self, ctx: &mut ModuleCtx<'_, E>) -> EngineResult<()> {
+        let Some(am) = ctx.resources().get::<AssetManager>() else {
+            log::warn!(target: "localization", "AssetManager missing; no string tables loaded");
+            return Ok(());
+        };
+
+        let dir = self.config.dir.trim_end_matches('/');
+        for locale in self.config.all_locales() {
+            let path = format!("{dir}/{locale}.json");
+            match am.store().load_path(&path) {
+                Ok(id) => self.pending.push((locale, id)),
+                Err(e) => log::warn!(
+                    target: "localization",
+                    "table.load rejected locale='{locale}' path='{path}' err='{e}'"
+                ),
+            }
+        }
+
+        self.poll_pending(am);
+        Ok(())
+    }
+
+    fn update(&mut self, ctx: &mut ModuleCtx<'_, E>) -> EngineResult<()> {
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+        if let Some(am) = ctx.resources().get::<AssetManager>() {
+            self.poll_pending(am);
+        }
+        Ok(())
+    }
+
+    fn shutdown(&mut self, ctx: &mut ModuleCtx<'_, E>) -> EngineResult<()> {
+        let _ = ctx
+            .resources_mut()
+            .unregister_api::<LocalizationApiRef>(LOCALIZATION_API_ID);
+        if self.service_registered {
+            newengine_core::unregister_service_v1(LOCALIZATION_SERVICE_ID);
+            self.service_registered = false;
+        }
+        self.pending.clear();
+        Ok(())
+    }
+}
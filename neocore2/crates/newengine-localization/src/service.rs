@@ -0,0 +1,131 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use abi_stable::std_types::{RResult, RString};
+use newengine_plugin_api::{Blob, CapabilityId, MethodName, ServiceV1};
+use serde::Serialize;
+use serde_json::json;
+
+use crate::api::LocalizationApiRef;
+
+pub const LOCALIZATION_SERVICE_ID: &str = "localization";
+
+pub mod method {
+    pub const GET_JSON: &str = "locale.get_json";
+    pub const SET: &str = "locale.set";
+    pub const TR: &str = "locale.tr";
+}
+
+#[derive(Debug, Serialize)]
+struct LocaleResp {
+    ok: bool,
+    locale: String,
+    fallback: String,
+    locales: Vec<String>,
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct TrResp {
+    key: String,
+    text: Option<String>,
+}
+
+pub(crate) struct LocalizationService {
+    api: LocalizationApiRef,
+}
+
+impl LocalizationService {
+    #[inline]
+    pub(crate) fn new(api: LocalizationApiRef) -> Self {
+        Self { api }
+    }
+
+    fn locale_resp(&self, error: Option<String>) -> LocaleResp {
+        let g = self.api.read();
+        LocaleResp {
+            ok: error.is_none(),
+            locale: g.locale().to_string(),
+            fallback: g.fallback_locale().to_string(),
+            locales: g.locales(),
+            error,
+        }
+    }
+}
+
+impl ServiceV1 for LocalizationService {
+    fn id(&self) -> CapabilityId {
+        RString::from(LOCALIZATION_SERVICE_ID)
+    }
+
+    fn describe(&self) -> RString {
+        let d = json!({
+          "id": LOCALIZATION_SERVICE_ID,
+          "version": 1,
+          "methods": [
+            { "name": method::GET_JSON, "payload": "empty", "returns": "json LocaleResp" },
+            { "name": method::SET, "payload": "utf8 locale", "returns": "json LocaleResp" },
+            { "name": method::TR, "payload": "utf8 key", "returns": "json TrResp" }
+          ],
+          "console": {
+            "commands": [
+              {
+                "name": "locale",
+                "help": "Show current/fallback locale and loaded tables",
+                "kind": "service_call",
+                "service_id": LOCALIZATION_SERVICE_ID,
+                "method": method::GET_JSON,
+                "payload": "empty"
+              },
+              {
+                "name": "locale.set",
+                "help": "Switch locale at runtime: locale.set <locale>",
+                "usage": "locale.set <locale>",
+                "kind": "service_call",
+                "service_id": LOCALIZATION_SERVICE_ID,
+                "method": method::SET,
+                "payload": "raw"
+              },
+              {
+                "name": "locale.tr",
+                "help": "Look up a string key: locale.tr <key>",
+                "usage": "locale.tr <key>",
+                "kind": "service_call",
+                "service_id": LOCALIZATION_SERVICE_ID,
+                "method": method::TR,
+                "payload": "raw"
+              }
+            ]
+          }
+        });
+
+        RString::from(d.to_string())
+    }
+
+    fn call(&self, method: MethodName, payload: Blob) -> RResult<Blob, RString> {
+        let m = method.to_string();
+
+        match m.as_str() {
+            method::GET_JSON => {
+                let bytes = serde_json::to_vec(&self.locale_resp(None)).unwrap_or_default();
+                RResult::ROk(Blob::from(bytes))
+            }
+            method::SET => {
+                let locale = String::from_utf8_lossy(payload.as_slice())
+                    .trim()
+                    .to_string();
+                let err = self.api.write().set_locale(&locale).err();
+                let bytes = serde_json::to_vec(&self.locale_resp(err)).unwrap_or_default();
+                RResult::ROk(Blob::from(bytes))
+            }
+            method::TR => {
+                let key = String::from_utf8_lossy(payload.as_slice())
+                    .trim()
+                    .to_string();
+                let text = self.api.read().lookup(&key).map(str::to_string);
+                let bytes = serde_json::to_vec(&TrResp { key, text }).unwrap_or_default();
+                RResult::ROk(Blob::from(bytes))
+            }
+            _ => RResult::RErr(RString::from(format!("unknown method: {m}"))),
+        }
+    }
+}
@@ -0,0 +1,101 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+
+#[derive(Debug)]
+pub enum StringTableError {
+    Json(String),
+    NotAnObject,
+}
+
+impl std::fmt::Display for StringTableError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StringTableError::Json(e) => write!(f, "string table: json parse failed: {e}"),
+            StringTableError::NotAnObject => write!(f, "string table: root must be an object"),
+        }
+    }
+}
+
+impl std::error::Error for StringTableError {}
+
+/// Flat `key -> text` table for one locale.
+///
+/// JSON layout:
+/// - `{ "locale": "en", "strings": { ... } }`, or
+/// - a bare object of strings.
+///
+/// Nested objects are flattened with '.', so `{"menu":{"quit":"Quit"}}` yields `menu.quit`.
+#[derive(Debug, Clone, Default)]
+pub struct StringTable {
+    pub locale: String,
+    pub strings: HashMap<String, String>,
+}
+
+impl StringTable {
+    /// Parses a table; `locale` is used when the document does not declare one.
+    pub fn from_json(locale: &str, text: &str) -> Result<Self, StringTableError> {
+        let v: JsonValue =
+            serde_json::from_str(text).map_err(|e| StringTableError::Json(e.to_string()))?;
+
+        let JsonValue::Object(root) = &v else {
+            return Err(StringTableError::NotAnObject);
+        };
+
+        let locale = root
+            .get("locale")
+            .and_then(|x| x.as_str())
+            .unwrap_or(locale)
+            .to_string();
+
+        let mut strings = HashMap::new();
+        match root.get("strings") {
+            Some(JsonValue::Object(_)) => flatten_into("", &root["strings"], &mut strings),
+            _ => flatten_into("", &v, &mut strings),
+        }
+        strings.remove("locale");
+
+        Ok(Self { locale, strings })
+    }
+
+    #[inline]
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.strings.get(key).map(String::as_str)
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.strings.is_empty()
+    }
+}
+
+fn flatten_into(prefix: &str, v: &JsonValue, out: &mut HashMap<String, String>) {
+    match v {
+        JsonValue::Object(map) => {
+            for (k, child) in map.iter() {
+                let key = if prefix.is_empty() {
+                    k.clone()
+                } else {
+                    format!("{prefix}.{k}")
+                };
+                flatten_into(&key, child, out);
+            }
+        }
+        JsonValue::String(s) => {
+            out.insert(prefix.to_string(), s.clone());
+        }
+        JsonValue::Number(n) => {
+            out.insert(prefix.to_string(), n.to_string());
+        }
+        JsonValue::Bool(b) => {
+            out.insert(prefix.to_string(), b.to_string());
+        }
+        JsonValue::Null | JsonValue::Array(_) => {}
+    }
+}
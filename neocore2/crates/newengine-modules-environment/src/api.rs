@@ -0,0 +1,360 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use glam::{Mat4, Vec3};
+use newengine_assets::TextureAsset;
+use newengine_core::render::{
+    AddressMode, BindGroupDesc, BindGroupId, BindGroupLayoutDesc, BindGroupLayoutId, BindingKind,
+    BufferBinding, BufferDesc, BufferId, BufferUsage, CullMode, DefaultMaterial, DrawArgs,
+    Extent2D, FilterMode, MemoryHint, PipelineDesc, PipelineId, RectI32, RenderApi, SamplerDesc,
+    SamplerId, TextureDesc, TextureFormat, TextureId, TextureUsage, VertexDeformation, Viewport,
+    SKYBOX_UBO_SIZE,
+};
+use newengine_core::EngineResult;
+use parking_lot::Mutex;
+use std::sync::Arc;
+
+use crate::component::Skybox;
+use crate::ibl::IblMaps;
+
+/// Backend objects of the loaded sky cube.
+struct CubeGpu {
+    texture: TextureId,
+    bg: BindGroupId,
+}
+
+impl CubeGpu {
+    fn destroy(self, r: &mut dyn RenderApi) {
+        r.destroy_bind_group(self.bg);
+        r.destroy_texture(self.texture);
+    }
+}
+
+/// Backend objects that outlive sky changes, created on the first render.
+struct SharedGpu {
+    sky_layout: BindGroupLayoutId,
+    cube_layout: BindGroupLayoutId,
+    pipeline: PipelineId,
+    sampler: SamplerId,
+    ubo: BufferId,
+    sky_bg: BindGroupId,
+}
+
+impl SharedGpu {
+    fn create(r: &mut dyn RenderApi) -> EngineResult<Self> {
+        // Owned and cached by the backend.
+        let (vs, fs) =
+            r.default_material_shaders(DefaultMaterial::Skybox, VertexDeformation::NONE)?;
+        let sky_layout = r.create_bind_group_layout(
+            BindGroupLayoutDesc::new(vec![BindingKind::UniformBuffer]).with_label("skybox_bgl"),
+        )?;
+        let cube_layout = r.create_bind_group_layout(
+            BindGroupLayoutDesc::new(vec![BindingKind::TextureCube, BindingKind::Sampler])
+                .with_label("skybox_cube_bgl"),
+        )?;
+        // No depth: the sky is drawn first and everything else lands on top.
+        let pipeline = r.create_pipeline(
+            PipelineDesc::new(vs, fs, TextureFormat::Bgra8Unorm)
+                .with_label("skybox_pipeline")
+                .with_bind_group_layouts(vec![sky_layout, cube_layout])
+                .with_cull_mode(CullMode::None),
+        )?;
+        let sampler = r.create_sampler(SamplerDesc {
+            min_filter: FilterMode::Linear,
+            mag_filter: FilterMode::Linear,
+            mip_filter: FilterMode::Linear,
+            address_u: AddressMode::ClampToEdge,
+            address_v: AddressMode::ClampToEdge,
+            address_w: AddressMode::ClampToEdge,
+            label: Some("skybox_sampler"),
+        })?;
+        let ubo = r.create_buffer(
+            BufferDesc::new(SKYBOX_UBO_SIZE, BufferUsage::Uniform, MemoryHint::CpuToGpu)
+                .with_label("skybox_ubo"),
+        )?;
+        let sky_bg = r.create_bind_group(
+            BindGroupDesc::new(sky_layout)
+                .with_label("skybox_bg")
+                .with_uniform0(BufferBinding::new(ubo, 0, SKYBOX_UBO_SIZE)),
+        )?;
+        Ok(Self {
+            sky_layout,
+            cube_layout,
+            pipeline,
+            sampler,
+            ubo,
+            sky_bg,
+        })
+    }
+
+    fn destroy(self, r: &mut dyn RenderApi) {
+        r.destroy_bind_group(self.sky_bg);
+        r.destroy_buffer(self.ubo);
+        r.destroy_sampler(self.sampler);
+        r.destroy_pipeline(self.pipeline);
+        r.destroy_bind_group_layout(self.cube_layout);
+        r.destroy_bind_group_layout(self.sky_layout);
+    }
+
+    fn upload(&self, r: &mut dyn RenderApi, cube: &TextureAsset) -> EngineResult<CubeGpu> {
+        let side = cube.desc.width;
+        let mut texels = Vec::with_capacity(side as usize * side as usize * 4 * 6);
+        for face in 0..6 {
+            texels.extend_from_slice(cube.cube_face(face).unwrap_or_default());
+        }
+
+        let texture = r.create_texture(
+            TextureDesc::new(
+                Extent2D::new(side, side),
+                TextureFormat::Rgba8Unorm,
+                TextureUsage::Sampled,
+            )
+            .with_cube()
+            .with_label("skybox_cube"),
+        )?;
+        let bg = match r.write_texture(texture, &texels) {
+            Ok(()) => r.create_bind_group(
+                BindGroupDesc::new(self.cube_layout)
+                    .with_label("skybox_cube_bg")
+                    .with_texture0(texture)
+                    .with_sampler0(self.sampler),
+            ),
+            Err(e) => Err(e),
+        };
+        match bg {
+            Ok(bg) => Ok(CubeGpu { texture, bg }),
+            Err(e) => {
+                r.destroy_texture(texture);
+                Err(e)
+            }
+        }
+    }
+}
+
+struct EnvironmentWorld {
+    skybox: Option<Skybox>,
+    /// Source the module has not been asked to load yet.
+    request: Option<String>,
+    /// Cube of `skybox.source` once loaded; `None` while loading or after a failed load.
+    cube: Option<Arc<TextureAsset>>,
+    ibl: Option<Arc<IblMaps>>,
+    shared: Option<SharedGpu>,
+    gpu: Option<CubeGpu>,
+    /// GPU objects of replaced skies, destroyed on the next render.
+    stale: Vec<CubeGpu>,
+}
+
+impl EnvironmentWorld {
+    #[inline]
+    fn is_current(&self, source: &str) -> bool {
+        self.skybox.as_ref().is_some_and(|s| s.source == source)
+    }
+
+    fn drop_cube(&mut self) {
+        self.cube = None;
+        self.ibl = None;
+        if let Some(gpu) = self.gpu.take() {
+            self.stale.push(gpu);
+        }
+    }
+}
+
+/// Shared handle to the environment, registered as `environment.api`.
+///
+/// Holds the one [`Skybox`] of the world. The module loads its cubemap on first use and, when
+/// configured to, generates [`IblMaps`] from it; whoever owns the frame (the host render
+/// controller) calls [`EnvironmentApiRef::render`] right after `begin_frame`, before any
+/// scene geometry, since the sky covers every pixel.
+#[derive(Clone)]
+pub struct EnvironmentApiRef(Arc<Mutex<EnvironmentWorld>>);
+
+impl EnvironmentApiRef {
+    pub(crate) fn new() -> Self {
+        Self(Arc::new(Mutex::new(EnvironmentWorld {
+            skybox: None,
+            request: None,
+            cube: None,
+            ibl: None,
+            shared: None,
+            gpu: None,
+            stale: Vec::new(),
+        })))
+    }
+
+    /// Sets or (with `None`) removes the skybox. Keeps the loaded cube when the source stays.
+    pub fn set_skybox(&self, skybox: Option<Skybox>) {
+        let mut w = self.0.lock();
+        let source = skybox.as_ref().map(|s| s.source.clone());
+        let same = match (&source, &w.skybox) {
+            (Some(new), Some(old)) => *new == old.source,
+            _ => false,
+        };
+        if !same {
+            w.drop_cube();
+            w.request = source;
+        }
+        w.skybox = skybox;
+    }
+
+    pub fn skybox(&self) -> Option<Skybox> {
+        self.0.lock().skybox.clone()
+    }
+
+    /// Whether the skybox's cubemap is in.
+    pub fn is_loaded(&self) -> bool {
+        self.0.lock().cube.is_some()
+    }
+
+    pub fn set_visible(&self, visible: bool) -> bool {
+        self.with_skybox(|s| s.visible = visible)
+    }
+
+    pub fn set_rotation(&self, radians: f32) -> bool {
+        self.with_skybox(|s| s.rotation = radians)
+    }
+
+    pub fn set_intensity(&self, intensity: f32) -> bool {
+        self.with_skybox(|s| s.intensity = intensity)
+    }
+
+    /// CPU copy of the sky cube (single mip, `Rgba8Unorm`).
+    pub fn cubemap(&self) -> Option<Arc<TextureAsset>> {
+        self.0.lock().cube.clone()
+    }
+
+    /// Image-based lighting maps of the sky; `None` until generated, or when the module's
+    /// config leaves them off.
+    pub fn ibl(&self) -> Option<Arc<IblMaps>> {
+        self.0.lock().ibl.clone()
+    }
+
+    /// Draws the sky over the whole frame. `view_proj` is the column-major camera matrix and
+    /// `eye` the camera position; only the camera's orientation matters. Returns whether the
+    /// sky was drawn. Leaves the viewport and scissor covering `extent`.
+    pub fn render(
+        &self,
+        r: &mut dyn RenderApi,
+        extent: Extent2D,
+        view_proj: [f32; 16],
+        eye: [f32; 3],
+    ) -> EngineResult<bool> {
+        let mut guard = self.0.lock();
+        let w = &mut *guard;
+        for gpu in w.stale.drain(..) {
+            gpu.destroy(r);
+        }
+
+        let Some(sky) = w.skybox.as_ref().filter(|s| s.visible) else {
+            return Ok(false);
+        };
+        let Some(cube) = w.cube.as_ref() else {
+            return Ok(false);
+        };
+        if extent.width == 0 || extent.height == 0 {
+            return Ok(false);
+        }
+        if w.shared.is_none() {
+            w.shared = Some(SharedGpu::create(r)?);
+        }
+        let Some(shared) = w.shared.as_ref() else {
+            return Ok(false);
+        };
+        if w.gpu.is_none() {
+            w.gpu = Some(shared.upload(r, cube)?);
+        }
+        let Some(gpu) = w.gpu.as_ref() else {
+            return Ok(false);
+        };
+
+        r.write_buffer(shared.ubo, 0, &sky_uniform(sky, view_proj, eye))?;
+        r.set_viewport(Viewport::full(extent))?;
+        r.set_scissor(RectI32::new(
+            0,
+            0,
+            extent.width as i32,
+            extent.height as i32,
+        ))?;
+        r.set_pipeline(shared.pipeline)?;
+        r.set_bind_group(0, shared.sky_bg)?;
+        r.set_bind_group(1, gpu.bg)?;
+        r.draw(DrawArgs::new(3))?;
+        Ok(true)
+    }
+
+    /// Destroys the backend objects; the next render recreates them.
+    pub fn release(&self, r: &mut dyn RenderApi) {
+        let mut w = self.0.lock();
+        let mut gpus: Vec<CubeGpu> = w.stale.drain(..).collect();
+        gpus.extend(w.gpu.take());
+        for gpu in gpus {
+            gpu.destroy(r);
+        }
+        if let Some(shared) = w.shared.take() {
+            shared.destroy(r);
+        }
+    }
+
+    /// Removes the skybox and forgets backend objects without destroying them (the backend is
+    /// gone).
+    pub fn clear(&self) {
+        let mut w = self.0.lock();
+        w.skybox = None;
+        w.request = None;
+        w.cube = None;
+        w.ibl = None;
+        w.shared = None;
+        w.gpu = None;
+        w.stale.clear();
+    }
+
+    /// Skybox source that nobody has loaded yet.
+    pub(crate) fn take_request(&self) -> Option<String> {
+        self.0.lock().request.take()
+    }
+
+    /// Whether `source` is still the skybox's; loads of replaced sources are dropped.
+    pub(crate) fn wants(&self, source: &str) -> bool {
+        self.0.lock().is_current(source)
+    }
+
+    pub(crate) fn set_cube(&self, source: &str, cube: Arc<TextureAsset>) {
+        let mut w = self.0.lock();
+        if w.is_current(source) {
+            w.drop_cube();
+            w.cube = Some(cube);
+        }
+    }
+
+    pub(crate) fn set_ibl(&self, maps: Arc<IblMaps>) {
+        let mut w = self.0.lock();
+        if w.is_current(&maps.source) {
+            w.ibl = Some(maps);
+        }
+    }
+
+    fn with_skybox(&self, f: impl FnOnce(&mut Skybox)) -> bool {
+        match self.0.lock().skybox.as_mut() {
+            Some(s) => {
+                f(s);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// [`DefaultMaterial::Skybox`] uniform bytes: clip space to sky direction, then the tint.
+fn sky_uniform(sky: &Skybox, view_proj: [f32; 16], eye: [f32; 3]) -> Vec<u8> {
+    // Moving the camera to the origin leaves only its orientation.
+    let at_origin = Mat4::from_cols_array(&view_proj) * Mat4::from_translation(Vec3::from(eye));
+    let inv = Mat4::from_rotation_y(-sky.rotation) * at_origin.inverse();
+    let tint = Vec3::from(sky.tint) * sky.intensity;
+
+    let mut out = Vec::with_capacity(SKYBOX_UBO_SIZE as usize);
+    for f in inv.to_cols_array() {
+        out.extend_from_slice(&f.to_ne_bytes());
+    }
+    for f in [tint.x, tint.y, tint.z, 1.0] {
+        out.extend_from_slice(&f.to_ne_bytes());
+    }
+    out
+}
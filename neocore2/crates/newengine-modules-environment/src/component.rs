@@ -0,0 +1,46 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+/// Sky drawn behind everything else, from a cubemap.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Skybox {
+    /// Logical path of the sky: a `.cubemap` manifest (six faces or an equirectangular
+    /// panorama), an RGBA8 `.dds` cubemap, or a `.png` equirectangular panorama.
+    pub source: String,
+    /// Multiplies the sky (linear RGB).
+    pub tint: [f32; 3],
+    pub intensity: f32,
+    /// Turn of the sky around +Y, in radians.
+    pub rotation: f32,
+    pub visible: bool,
+}
+
+impl Skybox {
+    #[inline]
+    pub fn new(source: impl Into<String>) -> Self {
+        Self {
+            source: source.into(),
+            tint: [1.0; 3],
+            intensity: 1.0,
+            rotation: 0.0,
+            visible: true,
+        }
+    }
+
+    #[inline]
+    pub fn with_tint(mut self, tint: [f32; 3]) -> Self {
+        self.tint = tint;
+        self
+    }
+
+    #[inline]
+    pub fn with_intensity(mut self, intensity: f32) -> Self {
+        self.intensity = intensity;
+        self
+    }
+
+    #[inline]
+    pub fn with_rotation(mut self, radians: f32) -> Self {
+        self.rotation = radians;
+        self
+    }
+}
@@ -0,0 +1,339 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use glam::Vec3;
+use newengine_assets::{
+    cube_direction_face, cube_face_direction, TextureAsset, TextureDesc, TextureFormat,
+    TextureKind, TextureMip, TextureSubresource,
+};
+
+/// Sizes and quality of the image-based lighting maps generated from the sky.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IblConfig {
+    /// Side of each face of the irradiance cube.
+    pub irradiance_size: u32,
+    /// Side of each face of the first prefiltered mip; every further mip halves it.
+    pub prefilter_size: u32,
+    /// Prefiltered mips, roughness 0 at mip 0 up to roughness 1 at the last.
+    pub prefilter_levels: u32,
+    /// GGX samples per prefiltered texel.
+    pub prefilter_samples: u32,
+}
+
+impl IblConfig {
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            irradiance_size: 32,
+            prefilter_size: 128,
+            prefilter_levels: 5,
+            prefilter_samples: 64,
+        }
+    }
+
+    #[inline]
+    pub fn with_irradiance_size(mut self, size: u32) -> Self {
+        self.irradiance_size = size;
+        self
+    }
+
+    #[inline]
+    pub fn with_prefilter(mut self, size: u32, levels: u32, samples: u32) -> Self {
+        self.prefilter_size = size;
+        self.prefilter_levels = levels;
+        self.prefilter_samples = samples;
+        self
+    }
+}
+
+impl Default for IblConfig {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Image-based lighting inputs for a physically based material, generated from the sky on the
+/// CPU. Nothing samples them yet: they are for the PBR material path to upload once the
+/// backend takes mip chains.
+#[derive(Debug, Clone)]
+pub struct IblMaps {
+    /// Skybox source the maps come from.
+    pub source: String,
+    /// Diffuse light as 9 spherical harmonics (bands 0..=2, real basis, `y` order
+    /// `1, y, z, x, xy, yz, 3z²-1, xz, x²-y²`), already convolved with the cosine lobe and
+    /// divided by pi: their sum at a normal is the light a white lambert surface reflects.
+    pub irradiance_sh: [[f32; 3]; 9],
+    /// The same diffuse light as an `Rgba8Unorm` cube, one texel per normal.
+    pub irradiance: TextureAsset,
+    /// GGX-prefiltered sky, `Rgba8Unorm`: mip `i` of `n` holds roughness `i / (n - 1)` and is
+    /// looked up along the reflection vector.
+    pub prefiltered: TextureAsset,
+}
+
+/// Builds the IBL maps of a single-mip `Rgba8Unorm` cubemap; `None` for anything else.
+pub(crate) fn generate(source: &str, sky: &TextureAsset, config: &IblConfig) -> Option<IblMaps> {
+    let base = CubeF::from_texture(sky, config.prefilter_size.clamp(1, 1024))?;
+
+    let sh = irradiance_sh(&base);
+    let irradiance = CubeF::from_fn(config.irradiance_size.clamp(1, 256), |n| {
+        eval_sh(&sh, n.normalize())
+    });
+
+    let levels = config.prefilter_levels.clamp(1, 10) as usize;
+    let samples = config.prefilter_samples.clamp(1, 1024);
+    let mut chain = vec![base];
+    while chain.len() < levels {
+        let Some(next) = chain.last().and_then(CubeF::downsample) else {
+            break;
+        };
+        chain.push(next);
+    }
+    let last = (chain.len() - 1).max(1) as f32;
+    let mips: Vec<CubeF> = chain
+        .iter()
+        .enumerate()
+        .map(|(i, level)| match i {
+            0 => level.clone(),
+            _ => prefilter(level, i as f32 / last, samples),
+        })
+        .collect();
+
+    Some(IblMaps {
+        source: source.to_string(),
+        irradiance_sh: sh.map(|c| c.to_array()),
+        irradiance: to_texture(std::slice::from_ref(&irradiance)),
+        prefiltered: to_texture(&mips),
+    })
+}
+
+/// Float RGB cubemap, faces in layer order, rows top to bottom.
+#[derive(Clone)]
+struct CubeF {
+    size: u32,
+    faces: [Vec<Vec3>; 6],
+}
+
+impl CubeF {
+    fn from_fn(size: u32, mut f: impl FnMut(Vec3) -> Vec3) -> Self {
+        let faces = std::array::from_fn(|face| {
+            let mut texels = Vec::with_capacity((size * size) as usize);
+            for y in 0..size {
+                let t = (y as f32 + 0.5) / size as f32 * 2.0 - 1.0;
+                for x in 0..size {
+                    let s = (x as f32 + 0.5) / size as f32 * 2.0 - 1.0;
+                    texels.push(f(Vec3::from_array(cube_face_direction(face, s, t))));
+                }
+            }
+            texels
+        });
+        Self { size, faces }
+    }
+
+    /// `texture` resampled to `size` faces.
+    fn from_texture(texture: &TextureAsset, size: u32) -> Option<Self> {
+        let side = texture.desc.width;
+        if texture.desc.format != TextureFormat::Rgba8Unorm || side == 0 {
+            return None;
+        }
+        let expected = side as usize * side as usize * 4;
+        let faces: [&[u8]; 6] = std::array::from_fn(|i| texture.cube_face(i).unwrap_or(&[]));
+        if faces.iter().any(|f| f.len() != expected) {
+            return None;
+        }
+        let src = Self {
+            size: side,
+            faces: faces.map(|f| {
+                f.chunks_exact(4)
+                    .map(|p| Vec3::new(p[0] as f32, p[1] as f32, p[2] as f32) / 255.0)
+                    .collect()
+            }),
+        };
+
+        let mut out = src;
+        while out.size >= size * 2 {
+            let Some(half) = out.downsample() else {
+                break;
+            };
+            out = half;
+        }
+        Some(match out.size == size {
+            true => out,
+            false => Self::from_fn(size, |dir| out.sample(dir)),
+        })
+    }
+
+    /// Half-size copy, 2x2 texels averaged; `None` once faces are a single texel.
+    fn downsample(&self) -> Option<Self> {
+        if self.size < 2 {
+            return None;
+        }
+        let (size, src) = (self.size / 2, self.size as usize);
+        let faces = std::array::from_fn(|face| {
+            let f = &self.faces[face];
+            let mut texels = Vec::with_capacity((size * size) as usize);
+            for y in 0..size as usize {
+                for x in 0..size as usize {
+                    let (x, y) = (x * 2, y * 2);
+                    let sum = f[y * src + x]
+                        + f[y * src + x + 1]
+                        + f[(y + 1) * src + x]
+                        + f[(y + 1) * src + x + 1];
+                    texels.push(sum * 0.25);
+                }
+            }
+            texels
+        });
+        Some(Self { size, faces })
+    }
+
+    /// Bilinear lookup along `dir`, clamped at face edges.
+    fn sample(&self, dir: Vec3) -> Vec3 {
+        let (face, s, t) = cube_direction_face(dir.to_array());
+        let max = (self.size - 1) as f32;
+        let fx = ((s + 1.0) * 0.5 * self.size as f32 - 0.5).clamp(0.0, max);
+        let fy = ((t + 1.0) * 0.5 * self.size as f32 - 0.5).clamp(0.0, max);
+        let (x0, y0) = (fx as usize, fy as usize);
+        let (x1, y1) = ((x0 + 1).min(max as usize), (y0 + 1).min(max as usize));
+        let (tx, ty) = (fx - x0 as f32, fy - y0 as f32);
+
+        let f = &self.faces[face];
+        let row = self.size as usize;
+        let top = f[y0 * row + x0].lerp(f[y0 * row + x1], tx);
+        let bottom = f[y1 * row + x0].lerp(f[y1 * row + x1], tx);
+        top.lerp(bottom, ty)
+    }
+}
+
+/// Real spherical harmonics basis, bands 0..=2.
+fn sh_basis(n: Vec3) -> [f32; 9] {
+    let Vec3 { x, y, z } = n;
+    [
+        0.282_095,
+        0.488_603 * y,
+        0.488_603 * z,
+        0.488_603 * x,
+        1.092_548 * x * y,
+        1.092_548 * y * z,
+        0.315_392 * (3.0 * z * z - 1.0),
+        1.092_548 * x * z,
+        0.546_274 * (x * x - y * y),
+    ]
+}
+
+/// Projects the cube onto the basis, weighting texels by their solid angle, and convolves
+/// with the cosine lobe (divided by pi).
+fn irradiance_sh(cube: &CubeF) -> [Vec3; 9] {
+    let mut sh = [Vec3::ZERO; 9];
+    let size = cube.size as f32;
+    for (face, texels) in cube.faces.iter().enumerate() {
+        for (i, &color) in texels.iter().enumerate() {
+            let (x, y) = (i as u32 % cube.size, i as u32 / cube.size);
+            let s = (x as f32 + 0.5) / size * 2.0 - 1.0;
+            let t = (y as f32 + 0.5) / size * 2.0 - 1.0;
+            let d = 1.0 + s * s + t * t;
+            let solid_angle = 4.0 / (size * size * d * d.sqrt());
+            let dir = Vec3::from_array(cube_face_direction(face, s, t)).normalize();
+            for (c, b) in sh.iter_mut().zip(sh_basis(dir)) {
+                *c += color * (b * solid_angle);
+            }
+        }
+    }
+    // Cosine lobe per band (pi, 2pi/3, pi/4), divided by pi.
+    for (i, c) in sh.iter_mut().enumerate() {
+        *c *= match i {
+            0 => 1.0,
+            1..=3 => 2.0 / 3.0,
+            _ => 0.25,
+        };
+    }
+    sh
+}
+
+#[inline]
+fn eval_sh(sh: &[Vec3; 9], n: Vec3) -> Vec3 {
+    sh.iter()
+        .zip(sh_basis(n))
+        .fold(Vec3::ZERO, |acc, (c, b)| acc + *c * b)
+        .max(Vec3::ZERO)
+}
+
+/// Sky as seen in a GGX lobe of `roughness` around every texel direction (view = normal).
+fn prefilter(src: &CubeF, roughness: f32, samples: u32) -> CubeF {
+    let a = roughness * roughness;
+    CubeF::from_fn(src.size, |n| {
+        let n = n.normalize();
+        let up = if n.z.abs() < 0.999 { Vec3::Z } else { Vec3::X };
+        let tangent = up.cross(n).normalize();
+        let bitangent = n.cross(tangent);
+
+        let (mut sum, mut weight) = (Vec3::ZERO, 0.0);
+        for k in 0..samples {
+            let (u, v) = hammersley(k, samples);
+            let phi = std::f32::consts::TAU * u;
+            let cos_theta = ((1.0 - v) / (1.0 + (a * a - 1.0) * v)).sqrt();
+            let sin_theta = (1.0 - cos_theta * cos_theta).max(0.0).sqrt();
+            let h = tangent * (sin_theta * phi.cos())
+                + bitangent * (sin_theta * phi.sin())
+                + n * cos_theta;
+            let l = h * (2.0 * n.dot(h)) - n;
+            let n_dot_l = n.dot(l);
+            if n_dot_l > 0.0 {
+                sum += src.sample(l) * n_dot_l;
+                weight += n_dot_l;
+            }
+        }
+        match weight > 0.0 {
+            true => sum / weight,
+            false => src.sample(n),
+        }
+    })
+}
+
+/// Point `i` of `n` of the Hammersley set.
+#[inline]
+fn hammersley(i: u32, n: u32) -> (f32, f32) {
+    (
+        i as f32 / n as f32,
+        i.reverse_bits() as f32 / 4_294_967_296.0,
+    )
+}
+
+/// `Rgba8Unorm` cubemap with one mip per entry of `mips`.
+fn to_texture(mips: &[CubeF]) -> TextureAsset {
+    let size = mips.first().map_or(1, |m| m.size);
+    TextureAsset {
+        desc: TextureDesc {
+            width: size,
+            height: size,
+            depth: 1,
+            layers: 6,
+            mip_count: mips.len() as u32,
+            format: TextureFormat::Rgba8Unorm,
+            kind: TextureKind::Cube,
+        },
+        mips: mips
+            .iter()
+            .map(|m| TextureMip {
+                width: m.size,
+                height: m.size,
+                depth: 1,
+                subresources: m
+                    .faces
+                    .iter()
+                    .zip(0..)
+                    .map(|(texels, layer)| TextureSubresource {
+                        layer,
+                        data: texels
+                            .iter()
+                            .flat_map(|c| {
+                                let [r, g, b] =
+                                    (c.clamp(Vec3::ZERO, Vec3::ONE) * 255.0).round().to_array();
+                                [r as u8, g as u8, b as u8, 255]
+                            })
+                            .collect(),
+                    })
+                    .collect(),
+            })
+            .collect(),
+    }
+}
@@ -0,0 +1,19 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+mod api;
+mod component;
+mod ibl;
+mod image;
+mod module;
+
+pub use api::EnvironmentApiRef;
+pub use component::Skybox;
+pub use ibl::{IblConfig, IblMaps};
+pub use module::{EnvironmentConfig, EnvironmentModule};
+
+use newengine_core::{ApiProvide, ApiVersion};
+
+pub const ENVIRONMENT_API_ID: &str = "environment.api";
+pub const ENVIRONMENT_API_VERSION: ApiVersion = ApiVersion::new(0, 1, 0);
+pub const ENVIRONMENT_API_PROVIDE: ApiProvide =
+    ApiProvide::new(ENVIRONMENT_API_ID, ENVIRONMENT_API_VERSION);
@@ -0,0 +1,307 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use newengine_assets::{
+    AssetId, AssetState, CubemapAsset, TextureAsset, TextureFormat, TextureKind,
+};
+use newengine_core::assets::AssetManager;
+use newengine_core::render::{RenderApiRef, RENDER_API_ID};
+use newengine_core::{ApiProvide, EngineResult, Module, ModuleCtx};
+use std::sync::Arc;
+
+use crate::api::EnvironmentApiRef;
+use crate::ibl::{self, IblConfig};
+use crate::image::{decode_png_rgba8, RgbaImage};
+use crate::{ENVIRONMENT_API_ID, ENVIRONMENT_API_PROVIDE};
+
+#[derive(Debug, Clone)]
+pub struct EnvironmentConfig {
+    /// Generates [`crate::IblMaps`] for every sky that loads; `None` skips the work.
+    pub ibl: Option<IblConfig>,
+}
+
+impl EnvironmentConfig {
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            ibl: Some(IblConfig::new()),
+        }
+    }
+
+    #[inline]
+    pub fn with_ibl(mut self, ibl: Option<IblConfig>) -> Self {
+        self.ibl = ibl;
+        self
+    }
+}
+
+impl Default for EnvironmentConfig {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Where a loading sky is at.
+enum Stage {
+    /// `.cubemap` manifest.
+    Manifest(AssetId),
+    /// Images named by the manifest, in [`CubemapAsset::images`] order.
+    Images(Arc<CubemapAsset>, Vec<AssetId>),
+    /// Equirectangular PNG panorama.
+    Panorama(AssetId),
+    /// Cubemap texture (`.dds`) through the texture importer.
+    Texture(AssetId),
+}
+
+struct PendingSky {
+    source: String,
+    stage: Stage,
+}
+
+/// Outcome of one `update` of a loading sky.
+enum Step {
+    Wait(Stage),
+    Done(TextureAsset),
+}
+
+/// Draws a cubemap skybox and exposes it as `environment.api`.
+///
+/// A sky's source is a `.cubemap` manifest (six faces or an equirectangular panorama, through
+/// the cubemap importer), an equirectangular PNG, or a cubemap `.dds`; it loads through the
+/// AssetManager when the skybox first names it. Once in, the module generates the sky's
+/// [`crate::IblMaps`] when configured to. The module does not own the frame: the host calls
+/// [`EnvironmentApiRef::render`] from its render controller before drawing the scene.
+pub struct EnvironmentModule {
+    config: EnvironmentConfig,
+    api: EnvironmentApiRef,
+    pending: Option<PendingSky>,
+}
+
+impl EnvironmentModule {
+    #[inline]
+    pub fn new(config: EnvironmentConfig) -> Self {
+        Self {
+            config,
+            api: EnvironmentApiRef::new(),
+            pending: None,
+        }
+    }
+
+    /// Handle for consumers living outside the engine.
+    #[inline]
+    pub fn api(&self) -> EnvironmentApiRef {
+        self.api.clone()
+    }
+
+    fn load_requested(&mut self, am: &AssetManager) {
+        let Some(source) = self.api.take_request() else {
+            return;
+        };
+        // A newer source replaces whatever was loading.
+        self.pending = None;
+        match am.store().load_path(&source) {
+            Ok(id) => {
+                let stage = match extension(&source).as_str() {
+                    "cubemap" => Stage::Manifest(id),
+                    "png" => Stage::Panorama(id),
+                    _ => Stage::Texture(id),
+                };
+                self.pending = Some(PendingSky { source, stage });
+            }
+            Err(e) => {
+                log::warn!(target: "environment", "asset.load rejected path='{source}' err='{e}'");
+            }
+        }
+    }
+
+    fn advance(&mut self, am: &AssetManager) {
+        let Some(PendingSky { source, stage }) = self.pending.take() else {
+            return;
+        };
+        if !self.api.wants(&source) {
+            return;
+        }
+        match step(am, &source, stage) {
+            Ok(Step::Wait(stage)) => self.pending = Some(PendingSky { source, stage }),
+            Ok(Step::Done(cube)) => self.finish(&source, cube),
+            Err(e) => {
+                log::warn!(target: "environment", "skybox load failed path='{source}' err='{e}'");
+            }
+        }
+    }
+
+    fn finish(&self, source: &str, cube: TextureAsset) {
+        let cube = Arc::new(cube);
+        self.api.set_cube(source, cube.clone());
+        if let Some(config) = &self.config.ibl {
+            match ibl::generate(source, &cube, config) {
+                Some(maps) => self.api.set_ibl(Arc::new(maps)),
+                None => log::warn!(target: "environment", "ibl skipped path='{source}'"),
+            }
+        }
+    }
+}
+
+fn step(am: &AssetManager, source: &str, stage: Stage) -> Result<Step, String> {
+    match stage {
+        Stage::Manifest(id) => {
+            if !ready(am, id)? {
+                return Ok(Step::Wait(Stage::Manifest(id)));
+            }
+            let manifest = am
+                .get_typed::<CubemapAsset>(id)
+                .map_err(|e| e.to_string())?;
+            let ids = manifest
+                .images()
+                .map(|image| {
+                    let path = resolve_path(source, image);
+                    am.store()
+                        .load_path(&path)
+                        .map_err(|e| format!("'{path}': {e}"))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(Step::Wait(Stage::Images(manifest, ids)))
+        }
+        Stage::Images(manifest, ids) => {
+            for &id in &ids {
+                if !ready(am, id)? {
+                    return Ok(Step::Wait(Stage::Images(manifest, ids)));
+                }
+            }
+            let images = ids
+                .iter()
+                .map(|&id| {
+                    let blob = am.get_blob(id).ok_or_else(|| "blob missing".to_string())?;
+                    decode_png_rgba8(&blob.payload)
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            let cube = match manifest.as_ref() {
+                CubemapAsset::Faces { .. } => {
+                    let faces: [RgbaImage; 6] = images
+                        .try_into()
+                        .map_err(|_| "expected six faces".to_string())?;
+                    let size = faces[0].width;
+                    if faces.iter().any(|i| i.width != size || i.height != size) {
+                        return Err("faces must be square and of one size".to_string());
+                    }
+                    TextureAsset::cube_from_faces(size, faces.map(|i| i.rgba))
+                }
+                CubemapAsset::Equirect { face_size, .. } => {
+                    let image = &images[0];
+                    let size = face_size.unwrap_or(image.width / 4);
+                    TextureAsset::cube_from_equirect(image.width, image.height, &image.rgba, size)
+                }
+            };
+            cube.map(Step::Done).map_err(|e| e.to_string())
+        }
+        Stage::Panorama(id) => {
+            if !ready(am, id)? {
+                return Ok(Step::Wait(Stage::Panorama(id)));
+            }
+            let blob = am.get_blob(id).ok_or_else(|| "blob missing".to_string())?;
+            let image = decode_png_rgba8(&blob.payload)?;
+            TextureAsset::cube_from_equirect(
+                image.width,
+                image.height,
+                &image.rgba,
+                image.width / 4,
+            )
+            .map(Step::Done)
+            .map_err(|e| e.to_string())
+        }
+        Stage::Texture(id) => {
+            if !ready(am, id)? {
+                return Ok(Step::Wait(Stage::Texture(id)));
+            }
+            let texture = am
+                .get_typed::<TextureAsset>(id)
+                .map_err(|e| e.to_string())?;
+            if texture.desc.kind != TextureKind::Cube {
+                return Err("texture is not a cubemap".to_string());
+            }
+            if texture.desc.format != TextureFormat::Rgba8Unorm {
+                return Err(format!(
+                    "{:?} cubemap (expected rgba8)",
+                    texture.desc.format
+                ));
+            }
+            // Only the top mip is drawn.
+            Ok(Step::Done(TextureAsset::clone(&texture)))
+        }
+    }
+}
+
+/// Whether asset `id` is in; errors once it failed.
+fn ready(am: &AssetManager, id: AssetId) -> Result<bool, String> {
+    match am.state(id) {
+        AssetState::Ready => Ok(true),
+        AssetState::Failed(e) => Err(e.to_string()),
+        AssetState::Unloaded | AssetState::Loading => Ok(false),
+    }
+}
+
+#[inline]
+fn extension(path: &str) -> String {
+    path.rsplit_once('.')
+        .map(|(_, ext)| ext.to_ascii_lowercase())
+        .unwrap_or_default()
+}
+
+/// Resolves `rel`, as written in the asset file `base`, into a logical path.
+fn resolve_path(base: &str, rel: &str) -> String {
+    let rel = rel.replace('\\', "/");
+    let mut parts: Vec<&str> = if rel.starts_with('/') {
+        Vec::new()
+    } else {
+        base.rsplit_once('/')
+            .map(|(dir, _)| dir.split('/').collect())
+            .unwrap_or_default()
+    };
+    for part in rel.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            p => parts.push(p),
+        }
+    }
+    parts.retain(|p| !p.is_empty());
+    parts.join("/")
+}
+
+impl<E: Send + 'static> Module<E> for EnvironmentModule {
+    fn id(&self) -> &'static str {
+        "environment"
+    }
+
+    fn provides(&self) -> &'static [ApiProvide] {
+        &[ENVIRONMENT_API_PROVIDE]
+    }
+
+    fn init(&mut self, ctx: &mut ModuleCtx<'_, E>) -> EngineResult<()> {
+        ctx.resources_mut()
+            .register_api(ENVIRONMENT_API_ID, self.api.clone())
+    }
+
+    fn update(&mut self, ctx: &mut ModuleCtx<'_, E>) -> EngineResult<()> {
+        if let Some(am) = ctx.resources().get::<AssetManager>() {
+            self.load_requested(am);
+            self.advance(am);
+        }
+        Ok(())
+    }
+
+    fn shutdown(&mut self, ctx: &mut ModuleCtx<'_, E>) -> EngineResult<()> {
+        match ctx.api::<RenderApiRef>(RENDER_API_ID) {
+            Some(render) => self.api.release(&mut **render.lock()),
+            None => self.api.clear(),
+        }
+        let _ = ctx
+            .resources_mut()
+            .unregister_api::<EnvironmentApiRef>(ENVIRONMENT_API_ID);
+        self.pending = None;
+        self.api.clear();
+        Ok(())
+    }
+}
@@ -1,12 +1,11 @@
 #![forbid(unsafe_op_in_unsafe_fn)]
 
-use abi_stable::sabi_trait::TD_Opaque;
 use abi_stable::std_types::{RResult, RString, RVec};
 use abi_stable::StableAbi;
 
 use newengine_plugin_api::{
-    Blob, EventSinkV1, EventSinkV1Dyn, EventSinkV1_TO, HostApiV1, MethodName, PluginInfo,
-    PluginModule, ServiceV1, ServiceV1Dyn, ServiceV1_TO,
+    guarded_service, guarded_sink, Blob, EventSinkV1, EventSinkV1Dyn, HostApiV1, MethodName,
+    PluginInfo, PluginModule, ServiceV1, ServiceV1Dyn,
 };
 
 use gilrs::{EventType, Gilrs};
@@ -44,7 +43,9 @@ struct MouseState {
 struct TextState {
     text: String,
     ime_preedit: String,
+    ime_preedit_cursor: Option<(usize, usize)>,
     ime_commit: String,
+    ime_enabled: bool,
 }
 
 #[derive(Default)]
@@ -263,10 +264,31 @@ impl EventSinkV1 for InputEventSink {
 
             "winit.ime_preedit" => {
                 if let Some(s) = v.get("text").and_then(|x| x.as_str()) {
+                    let cursor = v.get("cursor").and_then(|c| c.as_array()).and_then(|arr| {
+                        let a = arr.first()?.as_u64()? as usize;
+                        let b = arr.get(1)?.as_u64()? as usize;
+                        Some((a, b))
+                    });
+
                     let mut g = state().lock();
-                    if g.text.ime_preedit != s {
+                    if g.text.ime_preedit != s || g.text.ime_preedit_cursor != cursor {
                         g.text.ime_preedit.clear();
                         g.text.ime_preedit.push_str(s);
+                        g.text.ime_preedit_cursor = cursor;
+                        g.bump_epoch();
+                    }
+                }
+            }
+
+            "winit.ime_enabled" => {
+                if let Some(enabled) = v.get("enabled").and_then(|x| x.as_bool()) {
+                    let mut g = state().lock();
+                    if g.text.ime_enabled != enabled {
+                        g.text.ime_enabled = enabled;
+                        if !enabled {
+                            g.text.ime_preedit.clear();
+                            g.text.ime_preedit_cursor = None;
+                        }
                         g.bump_epoch();
                     }
                 }
@@ -277,6 +299,9 @@ impl EventSinkV1 for InputEventSink {
                     let mut g = state().lock();
                     g.text.ime_commit.clear();
                     g.text.ime_commit.push_str(s);
+                    // Commit ends the composition.
+                    g.text.ime_preedit.clear();
+                    g.text.ime_preedit_cursor = None;
                     g.bump_epoch();
                 }
             }
@@ -345,7 +370,9 @@ impl InputService {
             "text": {
                 "buffer": g.text.text,
                 "ime_preedit": g.text.ime_preedit,
-                "ime_commit": g.text.ime_commit
+                "ime_preedit_cursor": g.text.ime_preedit_cursor.map(|(a, b)| [a, b]),
+                "ime_commit": g.text.ime_commit,
+                "ime_enabled": g.text.ime_enabled
             },
             "gamepads": pads
         })
@@ -514,19 +541,23 @@ impl PluginModule for InputPlugin {
             id: RString::from(env!("CARGO_PKG_NAME")),
             name: RString::from("NewEngine Input"),
             version: RString::from(env!("CARGO_PKG_VERSION")),
+            requires: RVec::from(vec![
+                RString::from("host.events"),
+                RString::from("host.services"),
+            ]),
         }
     }
 
     fn init(&mut self, host: HostApiV1) -> RResult<(), RString> {
-        let sink: EventSinkV1Dyn<'static> = EventSinkV1_TO::from_value(InputEventSink, TD_Opaque);
-        if let Err(e) = (host.subscribe_events_v1)(sink).into_result() {
+        let sink: EventSinkV1Dyn<'static> = guarded_sink(InputEventSink);
+        if let Err(e) = (host.subscribe_topic_v1)(RString::from("winit.*"), sink).into_result() {
             return RResult::RErr(RString::from(format!(
-                "input: subscribe_events_v1 failed: {}",
+                "input: subscribe_topic_v1 failed: {}",
                 e
             )));
         }
 
-        let svc: ServiceV1Dyn<'static> = ServiceV1_TO::from_value(InputService, TD_Opaque);
+        let svc: ServiceV1Dyn<'static> = guarded_service(InputService);
         if let Err(e) = (host.register_service_v1)(svc).into_result() {
             return RResult::RErr(RString::from(format!(
                 "input: register_service_v1 failed: {}",
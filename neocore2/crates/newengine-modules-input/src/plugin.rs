@@ -1,9 +1,8 @@
 #![forbid(unsafe_op_in_unsafe_fn)]
 
 use abi_stable::derive_macro_reexports::PrefixTypeTrait;
-use abi_stable::sabi_trait::TD_Opaque;
 
-use newengine_plugin_api::{PluginModuleDyn, PluginModule_TO, PluginRootV1, PluginRootV1Ref};
+use newengine_plugin_api::{guarded_module, PluginModuleDyn, PluginRootV1, PluginRootV1Ref};
 
 use crate::module::InputPlugin;
 
@@ -13,5 +12,5 @@ pub extern "C" fn export_plugin_root() -> PluginRootV1Ref {
 }
 
 extern "C" fn create_module() -> PluginModuleDyn<'static> {
-    PluginModule_TO::from_value(InputPlugin::default(), TD_Opaque)
+    guarded_module(InputPlugin::default())
 }
\ No newline at end of file
@@ -0,0 +1,491 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use newengine_core::render::{
+    AddressMode, BindGroupDesc, BindGroupId, BindGroupLayoutDesc, BindGroupLayoutId, BufferBinding,
+    BufferDesc, BufferId, BufferUsage, Color4, DebugDraw, Extent2D, FilterMode, MemoryHint,
+    RectI32, RenderApi, SamplerDesc, SamplerId, TextureDesc, TextureFormat, TextureId,
+    TextureUsage, Viewport, GPU_LIGHT_SIZE, LIGHT_LIST_HEADER_SIZE, MAX_SHADOW_CASCADES,
+    SHADOW_UNIFORM_SIZE,
+};
+use newengine_core::EngineResult;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::component::{Light, LightKind};
+use crate::shadow::{cascade_matrices, ShadowCamera, ShadowCascade, ShadowConfig};
+use crate::LightId;
+
+/// Length of the line drawn along a directional light.
+const DIRECTION_LINE: f32 = 2.0;
+
+/// Counters of the last [`LightingApiRef::prepare`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LightingStats {
+    pub lights: usize,
+    /// Lights written to the light list.
+    pub uploaded: usize,
+    /// Contributing lights left out because the list was full.
+    pub dropped: usize,
+    /// Shadow cascades the light list samples; 0 without a shadow caster.
+    pub shadow_cascades: usize,
+}
+
+/// Shadow half of the light list: cascade uniform, shadow map and its sampler.
+struct ShadowGpu {
+    ubo: BufferId,
+    map: TextureId,
+    sampler: SamplerId,
+}
+
+impl ShadowGpu {
+    fn create(r: &mut dyn RenderApi, config: &ShadowConfig) -> EngineResult<Self> {
+        let ubo = r.create_buffer(
+            BufferDesc::new(
+                SHADOW_UNIFORM_SIZE,
+                BufferUsage::Uniform,
+                MemoryHint::CpuToGpu,
+            )
+            .with_label("shadow_cascades"),
+        )?;
+        let (width, height) = config.map_extent();
+        let map = match r.create_texture(
+            TextureDesc::new(
+                Extent2D::new(width, height),
+                TextureFormat::Depth32Float,
+                TextureUsage::DepthStencil,
+            )
+            .with_label("shadow_map"),
+        ) {
+            Ok(map) => map,
+            Err(e) => {
+                r.destroy_buffer(ubo);
+                return Err(e);
+            }
+        };
+        // Depth formats need not support linear filtering; PCF filters in the shader.
+        let sampler = match r.create_sampler(SamplerDesc {
+            min_filter: FilterMode::Nearest,
+            mag_filter: FilterMode::Nearest,
+            mip_filter: FilterMode::Nearest,
+            address_u: AddressMode::ClampToEdge,
+            address_v: AddressMode::ClampToEdge,
+            address_w: AddressMode::ClampToEdge,
+            label: Some("shadow_sampler"),
+        }) {
+            Ok(sampler) => sampler,
+            Err(e) => {
+                r.destroy_texture(map);
+                r.destroy_buffer(ubo);
+                return Err(e);
+            }
+        };
+        Ok(Self { ubo, map, sampler })
+    }
+
+    fn destroy(self, r: &mut dyn RenderApi) {
+        r.destroy_sampler(self.sampler);
+        r.destroy_texture(self.map);
+        r.destroy_buffer(self.ubo);
+    }
+}
+
+/// Backend objects of the light list, created on the first prepare.
+struct LightGpu {
+    layout: BindGroupLayoutId,
+    buffer: BufferId,
+    shadow: ShadowGpu,
+    bind_group: BindGroupId,
+}
+
+impl LightGpu {
+    fn create(
+        r: &mut dyn RenderApi,
+        max_lights: usize,
+        shadows: &ShadowConfig,
+    ) -> EngineResult<Self> {
+        let size = LIGHT_LIST_HEADER_SIZE + max_lights as u64 * GPU_LIGHT_SIZE;
+        let layout = r.create_bind_group_layout(BindGroupLayoutDesc::lights())?;
+        let buffer = match r.create_buffer(
+            BufferDesc::new(size, BufferUsage::Storage, MemoryHint::CpuToGpu)
+                .with_label("light_list"),
+        ) {
+            Ok(buffer) => buffer,
+            Err(e) => {
+                r.destroy_bind_group_layout(layout);
+                return Err(e);
+            }
+        };
+        let shadow = match ShadowGpu::create(r, shadows) {
+            Ok(shadow) => shadow,
+            Err(e) => {
+                r.destroy_buffer(buffer);
+                r.destroy_bind_group_layout(layout);
+                return Err(e);
+            }
+        };
+        let bind_group = match r.create_bind_group(
+            BindGroupDesc::new(layout)
+                .with_label("light_list_bg")
+                .with_storage0(BufferBinding::new(buffer, 0, size))
+                .with_uniform0(BufferBinding::new(shadow.ubo, 0, SHADOW_UNIFORM_SIZE))
+                .with_texture0(shadow.map)
+                .with_sampler0(shadow.sampler),
+        ) {
+            Ok(bg) => bg,
+            Err(e) => {
+                shadow.destroy(r);
+                r.destroy_buffer(buffer);
+                r.destroy_bind_group_layout(layout);
+                return Err(e);
+            }
+        };
+        Ok(Self {
+            layout,
+            buffer,
+            shadow,
+            bind_group,
+        })
+    }
+
+    fn destroy(self, r: &mut dyn RenderApi) {
+        r.destroy_bind_group(self.bind_group);
+        self.shadow.destroy(r);
+        r.destroy_buffer(self.buffer);
+        r.destroy_bind_group_layout(self.layout);
+    }
+}
+
+struct LightWorld {
+    lights: HashMap<LightId, Light>,
+    /// Linear RGB added to every lit surface.
+    ambient: [f32; 3],
+    max_lights: usize,
+    debug_volumes: bool,
+    shadows: ShadowConfig,
+    /// Light matrices of the cascades drawn since the last prepare.
+    cascades: Vec<[f32; 16]>,
+    gpu: Option<LightGpu>,
+    /// Light list bytes, reused between frames.
+    bytes: Vec<u8>,
+    shadow_bytes: Vec<u8>,
+    stats: LightingStats,
+}
+
+impl LightWorld {
+    fn gpu(&mut self, r: &mut dyn RenderApi) -> EngineResult<&LightGpu> {
+        let max_lights = self.max_lights;
+        match &mut self.gpu {
+            Some(gpu) => Ok(gpu),
+            slot @ None => Ok(slot.insert(LightGpu::create(r, max_lights, &self.shadows)?)),
+        }
+    }
+
+    /// The shadow caster and its direction: the shadow-casting directional light of lowest id.
+    fn caster(&self) -> Option<(LightId, [f32; 3])> {
+        self.lights
+            .iter()
+            .filter_map(|(&id, l)| Some((id, l.shadow_direction()?)))
+            .min_by_key(|(id, _)| *id)
+    }
+
+    /// Packs the light list: the shadow caster first, then the other directional lights, then
+    /// point lights nearest to `eye`, at most `max_lights` in all. Also packs the shadow
+    /// uniform, which shadows light 0 through the cascades drawn since the last pack.
+    fn pack(&mut self, eye: [f32; 3]) {
+        let caster = self.caster().map(|(id, _)| id);
+        let mut directional: Vec<&Light> = Vec::new();
+        let mut point: Vec<(f32, &Light)> = Vec::new();
+        for (&id, light) in self.lights.iter().filter(|(_, l)| l.contributes()) {
+            match light.kind {
+                LightKind::Directional { .. } if Some(id) == caster => directional.insert(0, light),
+                LightKind::Directional { .. } => directional.push(light),
+                LightKind::Point { position, range } => {
+                    // Distance to the light volume, so big lights nearby win over small ones.
+                    let d = distance(position, eye) - range;
+                    point.push((d, light));
+                }
+            }
+        }
+        point.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let contributing = directional.len() + point.len();
+        let uploaded: Vec<&Light> = directional
+            .into_iter()
+            .chain(point.into_iter().map(|(_, l)| l))
+            .take(self.max_lights)
+            .collect();
+
+        let bytes = &mut self.bytes;
+        bytes.clear();
+        bytes.extend_from_slice(&(uploaded.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&[0u8; 12]);
+        for f in [self.ambient[0], self.ambient[1], self.ambient[2], 0.0] {
+            bytes.extend_from_slice(&f.to_le_bytes());
+        }
+        for light in &uploaded {
+            let [r, g, b] = light.color.map(|c| c * light.intensity);
+            let position_range = match light.kind {
+                LightKind::Directional {
+                    direction: [x, y, z],
+                } => [x, y, z, 0.0],
+                LightKind::Point {
+                    position: [x, y, z],
+                    range,
+                } => [x, y, z, range],
+            };
+            for f in position_range.into_iter().chain([r, g, b, 1.0]) {
+                bytes.extend_from_slice(&f.to_le_bytes());
+            }
+        }
+
+        let cascades = std::mem::take(&mut self.cascades);
+        let shadowed = if caster.is_some() { cascades.len() } else { 0 };
+        self.shadow_bytes.clear();
+        for i in 0..MAX_SHADOW_CASCADES {
+            let matrix = cascades.get(i).copied().unwrap_or([0.0; 16]);
+            for f in matrix {
+                self.shadow_bytes.extend_from_slice(&f.to_le_bytes());
+            }
+        }
+        let (width, height) = self.shadows.map_extent();
+        let params = [
+            shadowed as f32,
+            self.shadows.bias,
+            1.0 / width as f32,
+            1.0 / height as f32,
+        ];
+        for f in params {
+            self.shadow_bytes.extend_from_slice(&f.to_le_bytes());
+        }
+
+        self.stats = LightingStats {
+            lights: self.lights.len(),
+            uploaded: uploaded.len(),
+            dropped: contributing - uploaded.len(),
+            shadow_cascades: shadowed,
+        };
+    }
+
+    /// Queues one-frame light volumes: a sphere per point light, a line from the world origin
+    /// along each directional light.
+    fn draw_volumes(&self, dd: &DebugDraw) {
+        for light in self.lights.values().filter(|l| l.enabled) {
+            let [r, g, b] = light.color;
+            let color: Color4 = [r, g, b, 1.0];
+            match light.kind {
+                LightKind::Point { position, range } => {
+                    dd.sphere(position, range, color, 0.0);
+                    dd.cross(position, (range * 0.1).min(0.25), color, 0.0);
+                }
+                LightKind::Directional { direction } => {
+                    let len = distance(direction, [0.0; 3]);
+                    if len <= 0.0 {
+                        continue;
+                    }
+                    let end = direction.map(|c| c / len * DIRECTION_LINE);
+                    dd.line([0.0; 3], end, color, 0.0);
+                    dd.cross(end, 0.1, color, 0.0);
+                }
+            }
+        }
+    }
+}
+
+#[inline]
+fn distance(a: [f32; 3], b: [f32; 3]) -> f32 {
+    let d = [a[0] - b[0], a[1] - b[1], a[2] - b[2]];
+    (d[0] * d[0] + d[1] * d[1] + d[2] * d[2]).sqrt()
+}
+
+/// Shared handle to the lights, registered as `lighting.api`.
+///
+/// Lights are components keyed by caller-picked [`LightId`]s. Shading is forward: every
+/// frame the owner of the frame (the host render controller) calls
+/// [`LightingApiRef::prepare`], which uploads the light list and returns the bind group to
+/// set at `LIGHTS_BIND_GROUP` for every draw with a default material. The list is capped at
+/// `max_lights`; past the cap, point lights farthest from the camera are left out.
+///
+/// One directional light can cast cascaded shadows (see [`Light::with_shadows`]). Its depth
+/// is drawn by [`LightingApiRef::render_shadows`], called before `prepare` in the same frame;
+/// the light list then samples those cascades. Frames without `render_shadows` draw no
+/// shadows.
+#[derive(Clone)]
+pub struct LightingApiRef(Arc<Mutex<LightWorld>>);
+
+impl LightingApiRef {
+    pub(crate) fn new(max_lights: usize, debug_volumes: bool, shadows: ShadowConfig) -> Self {
+        Self(Arc::new(Mutex::new(LightWorld {
+            lights: HashMap::new(),
+            ambient: [0.15; 3],
+            max_lights: max_lights.max(1),
+            debug_volumes,
+            shadows,
+            cascades: Vec::new(),
+            gpu: None,
+            bytes: Vec::new(),
+            shadow_bytes: Vec::new(),
+            stats: LightingStats::default(),
+        })))
+    }
+
+    /// Adds or replaces the light of `id`.
+    pub fn insert(&self, id: LightId, light: Light) {
+        self.0.lock().lights.insert(id, light);
+    }
+
+    /// Removes the light. Returns false if there was none.
+    pub fn remove(&self, id: LightId) -> bool {
+        self.0.lock().lights.remove(&id).is_some()
+    }
+
+    #[inline]
+    pub fn contains(&self, id: LightId) -> bool {
+        self.0.lock().lights.contains_key(&id)
+    }
+
+    pub fn light(&self, id: LightId) -> Option<Light> {
+        self.0.lock().lights.get(&id).copied()
+    }
+
+    pub fn set_enabled(&self, id: LightId, enabled: bool) -> bool {
+        match self.0.lock().lights.get_mut(&id) {
+            Some(light) => {
+                light.enabled = enabled;
+                true
+            }
+            None => false,
+        }
+    }
+
+    #[inline]
+    pub fn ambient(&self) -> [f32; 3] {
+        self.0.lock().ambient
+    }
+
+    #[inline]
+    pub fn set_ambient(&self, ambient: [f32; 3]) {
+        self.0.lock().ambient = ambient;
+    }
+
+    #[inline]
+    pub fn max_lights(&self) -> usize {
+        self.0.lock().max_lights
+    }
+
+    #[inline]
+    pub fn debug_volumes_enabled(&self) -> bool {
+        self.0.lock().debug_volumes
+    }
+
+    /// Draws light volumes through `DebugDraw` while enabled.
+    #[inline]
+    pub fn set_debug_volumes(&self, enabled: bool) {
+        self.0.lock().debug_volumes = enabled;
+    }
+
+    #[inline]
+    pub fn shadows(&self) -> ShadowConfig {
+        self.0.lock().shadows.clone()
+    }
+
+    #[inline]
+    pub fn stats(&self) -> LightingStats {
+        self.0.lock().stats
+    }
+
+    /// Layout of the bind group [`LightingApiRef::prepare`] returns, for pipelines that
+    /// shade with the light list. Created on first use; owned by the API.
+    pub fn layout(&self, r: &mut dyn RenderApi) -> EngineResult<BindGroupLayoutId> {
+        Ok(self.0.lock().gpu(r)?.layout)
+    }
+
+    /// Draws the shadow caster's cascades for `camera` in a depth pass of the current frame.
+    /// `draw` is called once per cascade with the viewport set to its cell of the shadow map;
+    /// it draws the casters with `PipelineDesc::depth_only` pipelines and the cascade's
+    /// matrix. Returns the cascades drawn: 0 without a caster or with shadows off.
+    pub fn render_shadows(
+        &self,
+        r: &mut dyn RenderApi,
+        camera: &ShadowCamera,
+        mut draw: impl FnMut(&mut dyn RenderApi, &ShadowCascade) -> EngineResult<()>,
+    ) -> EngineResult<usize> {
+        let (map, cascades, size) = {
+            let mut w = self.0.lock();
+            w.cascades.clear();
+            let Some((_, direction)) = w.caster() else {
+                return Ok(0);
+            };
+            let cascades = cascade_matrices(&w.shadows, camera, direction);
+            if cascades.is_empty() {
+                return Ok(0);
+            }
+            let map = w.gpu(r)?.shadow.map;
+            (map, cascades, w.shadows.map_size)
+        };
+
+        // The lock is released while the host draws.
+        let mut draw_cascades = |r: &mut dyn RenderApi| -> EngineResult<()> {
+            for (index, &view_proj) in cascades.iter().enumerate() {
+                let x = index as u32 * size;
+                r.set_viewport(Viewport {
+                    x: x as f32,
+                    y: 0.0,
+                    w: size as f32,
+                    h: size as f32,
+                    min_depth: 0.0,
+                    max_depth: 1.0,
+                })?;
+                r.set_scissor(RectI32::new(x as i32, 0, size as i32, size as i32))?;
+                draw(r, &ShadowCascade { index, view_proj })?;
+            }
+            Ok(())
+        };
+        r.begin_depth_pass(map)?;
+        let drawn = draw_cascades(r);
+        r.end_depth_pass()?;
+        drawn?;
+
+        let count = cascades.len();
+        self.0.lock().cascades = cascades;
+        Ok(count)
+    }
+
+    /// Uploads this frame's light list, picking point lights by distance to `eye`, and
+    /// returns its bind group.
+    pub fn prepare(&self, r: &mut dyn RenderApi, eye: [f32; 3]) -> EngineResult<BindGroupId> {
+        let mut w = self.0.lock();
+        let (buffer, shadow_ubo, bind_group) = {
+            let gpu = w.gpu(r)?;
+            (gpu.buffer, gpu.shadow.ubo, gpu.bind_group)
+        };
+        w.pack(eye);
+        r.write_buffer(buffer, 0, &w.bytes)?;
+        r.write_buffer(shadow_ubo, 0, &w.shadow_bytes)?;
+        Ok(bind_group)
+    }
+
+    /// Destroys the backend objects; the next prepare recreates them.
+    pub fn release(&self, r: &mut dyn RenderApi) {
+        if let Some(gpu) = self.0.lock().gpu.take() {
+            gpu.destroy(r);
+        }
+    }
+
+    /// Removes every light and forgets backend objects without destroying them (the backend
+    /// is gone).
+    pub fn clear(&self) {
+        let mut w = self.0.lock();
+        w.lights.clear();
+        w.cascades.clear();
+        w.gpu = None;
+        w.stats = LightingStats::default();
+    }
+
+    pub(crate) fn draw_debug(&self, dd: &DebugDraw) {
+        let w = self.0.lock();
+        if w.debug_volumes {
+            w.draw_volumes(dd);
+        }
+    }
+}
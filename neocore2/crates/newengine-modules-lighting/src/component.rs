@@ -0,0 +1,93 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+/// Shape of a light.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LightKind {
+    /// Infinitely far light (sun); `direction` is where it shines, need not be normalized.
+    Directional { direction: [f32; 3] },
+    /// Light at `position` fading to nothing at `range` world units.
+    Point { position: [f32; 3], range: f32 },
+}
+
+/// Light component.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Light {
+    pub kind: LightKind,
+    /// Linear RGB.
+    pub color: [f32; 3],
+    /// Multiplies `color`.
+    pub intensity: f32,
+    pub enabled: bool,
+    /// Directional lights only: casts the cascaded shadows. Only one light does; with several,
+    /// the one with the lowest id.
+    pub cast_shadows: bool,
+}
+
+impl Light {
+    #[inline]
+    pub fn directional(direction: [f32; 3]) -> Self {
+        Self::new(LightKind::Directional { direction })
+    }
+
+    #[inline]
+    pub fn point(position: [f32; 3], range: f32) -> Self {
+        Self::new(LightKind::Point { position, range })
+    }
+
+    #[inline]
+    fn new(kind: LightKind) -> Self {
+        Self {
+            kind,
+            color: [1.0; 3],
+            intensity: 1.0,
+            enabled: true,
+            cast_shadows: false,
+        }
+    }
+
+    #[inline]
+    pub fn with_color(mut self, color: [f32; 3]) -> Self {
+        self.color = color;
+        self
+    }
+
+    #[inline]
+    pub fn with_intensity(mut self, intensity: f32) -> Self {
+        self.intensity = intensity;
+        self
+    }
+
+    #[inline]
+    pub fn with_enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
+    #[inline]
+    pub fn with_shadows(mut self, cast_shadows: bool) -> Self {
+        self.cast_shadows = cast_shadows;
+        self
+    }
+
+    /// Whether the light adds anything: enabled, with some intensity and, for point lights,
+    /// some range.
+    #[inline]
+    pub(crate) fn contributes(&self) -> bool {
+        let reaches = match self.kind {
+            LightKind::Directional { direction } => direction.iter().any(|c| *c != 0.0),
+            LightKind::Point { range, .. } => range > 0.0,
+        };
+        self.enabled && self.intensity > 0.0 && reaches
+    }
+
+    /// Direction of a contributing, shadow-casting directional light.
+    #[inline]
+    pub(crate) fn shadow_direction(&self) -> Option<[f32; 3]> {
+        match self.kind {
+            LightKind::Directional { direction } if self.cast_shadows && self.contributes() => {
+                Some(direction)
+            }
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,27 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+mod api;
+mod component;
+mod module;
+mod shadow;
+
+pub use api::{LightingApiRef, LightingStats};
+pub use component::{Light, LightKind};
+pub use module::{LightingConfig, LightingModule};
+pub use shadow::{ShadowCamera, ShadowCascade, ShadowConfig};
+
+use newengine_core::{ApiProvide, ApiVersion};
+
+pub const LIGHTING_API_ID: &str = "lighting.api";
+pub const LIGHTING_API_VERSION: ApiVersion = ApiVersion::new(0, 1, 0);
+pub const LIGHTING_API_PROVIDE: ApiProvide = ApiProvide::new(LIGHTING_API_ID, LIGHTING_API_VERSION);
+
+/// Key of a light component; callers pick the ids, as with physics entities.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct LightId(pub u64);
+
+impl std::fmt::Display for LightId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
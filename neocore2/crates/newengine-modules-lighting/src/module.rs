@@ -0,0 +1,111 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use newengine_core::render::{DebugDraw, RenderApiRef, RENDER_API_ID};
+use newengine_core::{ApiProvide, EngineResult, Module, ModuleCtx};
+
+use crate::api::LightingApiRef;
+use crate::shadow::ShadowConfig;
+use crate::{LIGHTING_API_ID, LIGHTING_API_PROVIDE};
+
+#[derive(Debug, Clone)]
+pub struct LightingConfig {
+    /// Lights the light list holds; sizes the storage buffer.
+    pub max_lights: usize,
+    /// Draw point light ranges and directional light directions through `DebugDraw`.
+    pub debug_volumes: bool,
+    pub shadows: ShadowConfig,
+}
+
+impl LightingConfig {
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            max_lights: 64,
+            debug_volumes: false,
+            shadows: ShadowConfig::new(),
+        }
+    }
+
+    #[inline]
+    pub fn with_max_lights(mut self, max_lights: usize) -> Self {
+        self.max_lights = max_lights;
+        self
+    }
+
+    #[inline]
+    pub fn with_debug_volumes(mut self, enabled: bool) -> Self {
+        self.debug_volumes = enabled;
+        self
+    }
+
+    #[inline]
+    pub fn with_shadows(mut self, shadows: ShadowConfig) -> Self {
+        self.shadows = shadows;
+        self
+    }
+}
+
+impl Default for LightingConfig {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Keeps the scene lights and exposes them as `lighting.api`.
+///
+/// The module does not own the frame: the host calls [`LightingApiRef::render_shadows`] and
+/// then [`LightingApiRef::prepare`] from its render controller, and binds the returned light
+/// list for its default-material draws.
+pub struct LightingModule {
+    api: LightingApiRef,
+}
+
+impl LightingModule {
+    #[inline]
+    pub fn new(config: LightingConfig) -> Self {
+        Self {
+            api: LightingApiRef::new(config.max_lights, config.debug_volumes, config.shadows),
+        }
+    }
+
+    /// Handle for consumers living outside the engine.
+    #[inline]
+    pub fn api(&self) -> LightingApiRef {
+        self.api.clone()
+    }
+}
+
+impl<E: Send + 'static> Module<E> for LightingModule {
+    fn id(&self) -> &'static str {
+        "lighting"
+    }
+
+    fn provides(&self) -> &'static [ApiProvide] {
+        &[LIGHTING_API_PROVIDE]
+    }
+
+    fn init(&mut self, ctx: &mut ModuleCtx<'_, E>) -> EngineResult<()> {
+        ctx.resources_mut()
+            .register_api(LIGHTING_API_ID, self.api.clone())
+    }
+
+    fn update(&mut self, ctx: &mut ModuleCtx<'_, E>) -> EngineResult<()> {
+        if let Some(dd) = ctx.resources().get::<DebugDraw>() {
+            self.api.draw_debug(dd);
+        }
+        Ok(())
+    }
+
+    fn shutdown(&mut self, ctx: &mut ModuleCtx<'_, E>) -> EngineResult<()> {
+        match ctx.api::<RenderApiRef>(RENDER_API_ID) {
+            Some(render) => self.api.release(&mut **render.lock()),
+            None => self.api.clear(),
+        }
+        let _ = ctx
+            .resources_mut()
+            .unregister_api::<LightingApiRef>(LIGHTING_API_ID);
+        self.api.clear();
+        Ok(())
+    }
+}
@@ -0,0 +1,189 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use glam::{Mat4, Vec3};
+use newengine_core::render::MAX_SHADOW_CASCADES;
+
+/// Casters this many cascade radii behind a cascade's slice of the view still land in its map.
+const CASTER_REACH: f32 = 2.0;
+
+/// Cascaded shadow maps of the sun: the first shadow-casting directional light.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShadowConfig {
+    /// Slices of the view distance, each with its own map; 0 turns shadows off. At most
+    /// `MAX_SHADOW_CASCADES`.
+    pub cascades: usize,
+    /// Texels along each side of a cascade's map.
+    pub map_size: u32,
+    /// View distance the cascades cover, clamped to the camera's far plane.
+    pub distance: f32,
+    /// Split placement: 0 splits the distance evenly, 1 logarithmically (finer up close).
+    pub split_lambda: f32,
+    /// Subtracted from the receiver's depth before the comparison, in the depth units of a
+    /// cascade (0..1 across its depth range); hides self-shadowing acne.
+    pub bias: f32,
+}
+
+impl ShadowConfig {
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            cascades: 4,
+            map_size: 1024,
+            distance: 100.0,
+            split_lambda: 0.6,
+            bias: 0.0015,
+        }
+    }
+
+    /// Shadows off.
+    #[inline]
+    pub fn disabled() -> Self {
+        Self {
+            cascades: 0,
+            ..Self::new()
+        }
+    }
+
+    #[inline]
+    pub fn with_cascades(mut self, cascades: usize) -> Self {
+        self.cascades = cascades;
+        self
+    }
+
+    #[inline]
+    pub fn with_map_size(mut self, map_size: u32) -> Self {
+        self.map_size = map_size;
+        self
+    }
+
+    #[inline]
+    pub fn with_distance(mut self, distance: f32) -> Self {
+        self.distance = distance;
+        self
+    }
+
+    #[inline]
+    pub fn with_split_lambda(mut self, lambda: f32) -> Self {
+        self.split_lambda = lambda;
+        self
+    }
+
+    #[inline]
+    pub fn with_bias(mut self, bias: f32) -> Self {
+        self.bias = bias;
+        self
+    }
+
+    /// `cascades` as actually used.
+    #[inline]
+    pub(crate) fn cascade_count(&self) -> usize {
+        if self.map_size == 0 {
+            return 0;
+        }
+        self.cascades.min(MAX_SHADOW_CASCADES)
+    }
+
+    /// Size of the shadow map: the cascades side by side along x.
+    #[inline]
+    pub(crate) fn map_extent(&self) -> (u32, u32) {
+        match self.cascade_count() {
+            0 => (1, 1),
+            n => (self.map_size * n as u32, self.map_size),
+        }
+    }
+}
+
+impl Default for ShadowConfig {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Camera the cascades split, as passed to [`crate::LightingApiRef::render_shadows`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ShadowCamera {
+    /// Column-major world-to-clip matrix, clip depth 0..1.
+    pub view_proj: [f32; 16],
+    /// View distances of the near and far planes.
+    pub near: f32,
+    pub far: f32,
+}
+
+/// One cascade to draw casters into.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ShadowCascade {
+    pub index: usize,
+    /// Column-major world-to-clip matrix of the light for this cascade.
+    pub view_proj: [f32; 16],
+}
+
+/// World-to-clip matrices of the light, one per cascade, nearest slice first.
+///
+/// Each slice of the view frustum is wrapped in a sphere, so the cascade keeps its size as the
+/// camera turns, and its center is snapped to whole texels of the light's view, so shadow
+/// edges do not crawl as the camera moves.
+pub(crate) fn cascade_matrices(
+    config: &ShadowConfig,
+    camera: &ShadowCamera,
+    direction: [f32; 3],
+) -> Vec<[f32; 16]> {
+    let count = config.cascade_count();
+    let dir = Vec3::from_array(direction).normalize_or_zero();
+    let (near, far) = (camera.near, camera.far);
+    if count == 0 || dir == Vec3::ZERO || near <= 0.0 || far <= near {
+        return Vec::new();
+    }
+    let inv = Mat4::from_cols_array(&camera.view_proj).inverse();
+    let corner = |x: f32, y: f32, z: f32| inv.project_point3(Vec3::new(x, y, z));
+    let edges: [(Vec3, Vec3); 4] = [(-1.0, -1.0), (1.0, -1.0), (-1.0, 1.0), (1.0, 1.0)]
+        .map(|(x, y)| (corner(x, y, 0.0), corner(x, y, 1.0)));
+
+    let reach = config.distance.clamp(near, far);
+    let split = |i: usize| {
+        let t = i as f32 / count as f32;
+        let log = near * (reach / near).powf(t);
+        let linear = near + (reach - near) * t;
+        let lambda = config.split_lambda.clamp(0.0, 1.0);
+        log * lambda + linear * (1.0 - lambda)
+    };
+
+    let up = if dir.y.abs() > 0.99 { Vec3::Z } else { Vec3::Y };
+    let rotation = Mat4::look_at_rh(Vec3::ZERO, dir, up);
+    let texels = config.map_size as f32;
+
+    (0..count)
+        .map(|i| {
+            // Points along a frustum edge are linear in view depth, from near to far.
+            let (t0, t1) = (
+                (split(i) - near) / (far - near),
+                (split(i + 1) - near) / (far - near),
+            );
+            let mut points = [Vec3::ZERO; 8];
+            for (k, (a, b)) in edges.iter().enumerate() {
+                points[k * 2] = a.lerp(*b, t0);
+                points[k * 2 + 1] = a.lerp(*b, t1);
+            }
+            let center = points.iter().copied().sum::<Vec3>() / 8.0;
+            let radius = points
+                .iter()
+                .map(|p| p.distance(center))
+                .fold(0.0f32, f32::max);
+            let radius = ((radius * 16.0).ceil() / 16.0).max(1.0 / 16.0);
+
+            let texel = 2.0 * radius / texels;
+            let light_space = rotation.transform_point3(center);
+            let snapped = Vec3::new(
+                (light_space.x / texel).floor() * texel,
+                (light_space.y / texel).floor() * texel,
+                light_space.z,
+            );
+            let center = rotation.inverse().transform_point3(snapped);
+
+            let back = radius * (1.0 + CASTER_REACH);
+            let view = Mat4::look_at_rh(center - dir * back, center, up);
+            let proj = Mat4::orthographic_rh(-radius, radius, -radius, radius, 0.0, back + radius);
+            (proj * view).to_cols_array()
+        })
+        .collect()
+}
@@ -1,10 +1,23 @@
 use env_logger::fmt::{Target, TimestampPrecision, WriteStyle};
 use env_logger::Builder;
 use log::LevelFilter;
-use newengine_core::{EngineResult, Module, ModuleCtx};
+use newengine_core::{EngineError, EngineResult, Module, ModuleCtx, TailLogger};
+use newengine_plugin_api::{guarded_service, ServiceV1Dyn};
 
 use std::env;
 
+mod service;
+mod sink;
+
+pub use service::{method, LOG_SERVICE_ID};
+pub use sink::{
+    log_tail, set_target_level, target_levels, JsonLogFile, LogEntry, LogQuery,
+    DEFAULT_RING_CAPACITY,
+};
+
+use service::LogService;
+use sink::LogSink;
+
 /// Logger output destination that is trivially cloneable.
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub enum LogOutput {
@@ -36,6 +49,10 @@ pub struct ConsoleLoggerConfig {
     pub indent: Option<usize>,
     /// Destination for log output. When `None` defaults to `stderr`.
     pub output: Option<LogOutput>,
+    /// Records kept in memory for the `engine.log` service.
+    pub ring_capacity: usize,
+    /// Optional JSON-lines copy of every record, with size-based rotation.
+    pub json_file: Option<JsonLogFile>,
 }
 
 impl ConsoleLoggerConfig {
@@ -125,6 +142,31 @@ impl ConsoleLoggerConfig {
             _ => None,
         };
 
+        let ring_capacity = env::var("NEWENGINE_LOG_RING")
+            .ok()
+            .and_then(|v| v.parse::<usize>().ok())
+            .unwrap_or(DEFAULT_RING_CAPACITY);
+
+        let json_file = env::var("NEWENGINE_LOG_JSON")
+            .ok()
+            .filter(|s| !s.is_empty())
+            .map(|path| {
+                let mut f = JsonLogFile::new(path);
+                if let Some(n) = env::var("NEWENGINE_LOG_JSON_MAX_BYTES")
+                    .ok()
+                    .and_then(|v| v.parse::<u64>().ok())
+                {
+                    f = f.with_max_bytes(n);
+                }
+                if let Some(n) = env::var("NEWENGINE_LOG_JSON_FILES")
+                    .ok()
+                    .and_then(|v| v.parse::<usize>().ok())
+                {
+                    f = f.with_max_files(n);
+                }
+                f
+            });
+
         ConsoleLoggerConfig {
             filter,
             level,
@@ -137,13 +179,28 @@ impl ConsoleLoggerConfig {
             timestamp,
             indent,
             output,
+            ring_capacity,
+            json_file,
         }
     }
+
+    #[inline]
+    pub fn with_ring_capacity(mut self, capacity: usize) -> Self {
+        self.ring_capacity = capacity;
+        self
+    }
+
+    #[inline]
+    pub fn with_json_file(mut self, json_file: Option<JsonLogFile>) -> Self {
+        self.json_file = json_file;
+        self
+    }
 }
 
 pub struct ConsoleLoggerModule {
     config: ConsoleLoggerConfig,
     initialized: bool,
+    service_registered: bool,
 }
 
 impl ConsoleLoggerModule {
@@ -152,6 +209,7 @@ impl ConsoleLoggerModule {
         Self {
             config,
             initialized: false,
+            service_registered: false,
         }
     }
 }
@@ -172,55 +230,92 @@ impl<E: Send + 'static> Module<E> for ConsoleLoggerModule {
             return Ok(());
         }
 
-        let mut builder = Builder::new();
+        install_logger(&self.config);
+        self.initialized = true;
 
-        if let Some(ref filters) = self.config.filter {
-            builder.parse_filters(filters);
-        } else {
-            builder.filter_level(self.config.level);
+        if !self.service_registered {
+            let dyn_svc: ServiceV1Dyn<'static> = guarded_service(LogService);
+            newengine_core::register_service_v1(dyn_svc).map_err(EngineError::other)?;
+            self.service_registered = true;
         }
 
-        if let Some(out) = self.config.output {
-            builder.target(out.to_env_target());
-        }
+        Ok(())
+    }
 
-        if let Some(style) = self.config.write_style {
-            builder.write_style(style);
-        } else if !self.config.colors {
-            builder.write_style(WriteStyle::Never);
-        } else {
-            builder.write_style(WriteStyle::Auto);
+    fn shutdown(&mut self, _ctx: &mut ModuleCtx<'_, E>) -> EngineResult<()> {
+        if self.service_registered {
+            newengine_core::unregister_service_v1(LOG_SERVICE_ID);
+            self.service_registered = false;
         }
+        log::logger().flush();
+        Ok(())
+    }
+}
 
-        builder
-            .format_module_path(self.config.include_module_path)
-            .format_target(self.config.include_target);
+/// Installs the global logger: console output plus the `engine.log` ring buffer and the
+/// optional JSON file. Returns false if a logger was already installed.
+///
+/// Apps call this before `Engine::start()` to see early logs; the module's `init` then no-ops.
+pub fn install_logger(config: &ConsoleLoggerConfig) -> bool {
+    let mut builder = Builder::new();
 
-        if self.config.include_file && self.config.include_line_number {
-            builder.format_source_path(true);
-        } else {
-            builder.format_file(self.config.include_file);
-            builder.format_line_number(self.config.include_line_number);
-        }
+    // Filtering happens in the sink so runtime `log.level` overrides can also raise levels.
+    builder.filter_level(LevelFilter::Trace);
 
-        builder.format_indent(self.config.indent);
+    let mut filter = env_filter::Builder::new();
+    if let Some(ref filters) = config.filter {
+        filter.parse(filters);
+    } else {
+        filter.filter_level(config.level);
+    }
 
-        match self.config.timestamp {
-            Some(TimestampPrecision::Seconds) => builder.format_timestamp_secs(),
-            Some(TimestampPrecision::Millis) => builder.format_timestamp_millis(),
-            Some(TimestampPrecision::Micros) => builder.format_timestamp_micros(),
-            Some(TimestampPrecision::Nanos) => builder.format_timestamp_nanos(),
-            None => builder.format_timestamp(None::<TimestampPrecision>),
-        };
+    if let Some(out) = config.output {
+        builder.target(out.to_env_target());
+    }
 
-        match builder.try_init() {
-            Ok(()) => {}
-            Err(_e) => {
-                // Most likely "logger already initialized". Treat as non-fatal.
-            }
-        }
+    if let Some(style) = config.write_style {
+        builder.write_style(style);
+    } else if !config.colors {
+        builder.write_style(WriteStyle::Never);
+    } else {
+        builder.write_style(WriteStyle::Auto);
+    }
 
-        self.initialized = true;
-        Ok(())
+    builder
+        .format_module_path(config.include_module_path)
+        .format_target(config.include_target);
+
+    if config.include_file && config.include_line_number {
+        builder.format_source_path(true);
+    } else {
+        builder.format_file(config.include_file);
+        builder.format_line_number(config.include_line_number);
+    }
+
+    builder.format_indent(config.indent);
+
+    match config.timestamp {
+        Some(TimestampPrecision::Seconds) => builder.format_timestamp_secs(),
+        Some(TimestampPrecision::Millis) => builder.format_timestamp_millis(),
+        Some(TimestampPrecision::Micros) => builder.format_timestamp_micros(),
+        Some(TimestampPrecision::Nanos) => builder.format_timestamp_nanos(),
+        None => builder.format_timestamp(None::<TimestampPrecision>),
+    };
+
+    // Records also feed the crash report tail.
+    let sink = LogSink::new(
+        builder.build(),
+        filter.build(),
+        config.ring_capacity,
+        config.json_file.clone(),
+    );
+    let max_level = sink.max_level();
+    match log::set_boxed_logger(Box::new(TailLogger::new(sink))) {
+        Ok(()) => {
+            log::set_max_level(max_level);
+            true
+        }
+        // Most likely "logger already initialized". Treat as non-fatal.
+        Err(_e) => false,
     }
 }
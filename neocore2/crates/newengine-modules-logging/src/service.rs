@@ -0,0 +1,178 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use abi_stable::std_types::{RResult, RString};
+use log::LevelFilter;
+use newengine_plugin_api::{split_page_args, Blob, CapabilityId, MethodName, ServiceV1};
+use serde::Serialize;
+use serde_json::json;
+
+use std::collections::BTreeMap;
+
+use crate::sink::{log_tail, set_target_level, target_levels, LogEntry, LogQuery};
+
+pub const LOG_SERVICE_ID: &str = "engine.log";
+
+pub mod method {
+    pub const TAIL_JSON: &str = "log.tail_json";
+    pub const LEVEL: &str = "log.level";
+}
+
+const DEFAULT_TAIL: usize = 50;
+
+#[derive(Debug, Serialize)]
+struct LogTailResp {
+    next_seq: u64,
+    entries: Vec<LogEntry>,
+    /// `cursor=` for the page of older records, if there are any.
+    next_cursor: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct LogLevelResp {
+    ok: bool,
+    levels: BTreeMap<String, String>,
+    error: Option<String>,
+}
+
+pub(crate) struct LogService;
+
+impl LogService {
+    /// Payload: `[n] [level=<level>] [target=<prefix>] [since=<seq>]`, in any order, plus
+    /// the paging `cursor=<seq>` / `limit=<n>` (`limit` is the same as `n`).
+    fn parse_query(arg: &str) -> Result<LogQuery, String> {
+        let (cursor, limit, rest) = split_page_args(arg);
+        let mut q = LogQuery {
+            limit: Some(limit.unwrap_or(DEFAULT_TAIL)),
+            before: cursor
+                .map(|c| c.parse().map_err(|_| format!("bad cursor: '{c}'")))
+                .transpose()?,
+            ..LogQuery::default()
+        };
+
+        for tok in rest {
+            match tok.split_once('=') {
+                Some(("level", v)) => {
+                    q.level = Some(v.parse().map_err(|_| format!("bad level: '{v}'"))?)
+                }
+                Some(("target", v)) => q.target = Some(v.to_string()),
+                Some(("since", v)) => {
+                    q.since = Some(v.parse().map_err(|_| format!("bad seq: '{v}'"))?)
+                }
+                Some((k, _)) => return Err(format!("unknown filter: '{k}'")),
+                None => {
+                    let n = tok
+                        .parse::<usize>()
+                        .map_err(|_| format!("bad count: '{tok}'"))?;
+                    q.limit = (n > 0).then_some(n);
+                }
+            }
+        }
+
+        Ok(q)
+    }
+
+    /// Payload: empty (list overrides) or `<target|*> <level|reset>`.
+    fn level(arg: &str) -> LogLevelResp {
+        let mut it = arg.split_whitespace();
+        let res = match (it.next(), it.next(), it.next()) {
+            (None, _, _) => Ok(()),
+            (Some(target), Some(level), None) => {
+                let level = match level.to_ascii_lowercase().as_str() {
+                    "reset" | "default" => Ok(None),
+                    l => l
+                        .parse::<LevelFilter>()
+                        .map(Some)
+                        .map_err(|_| format!("bad level: '{level}'")),
+                };
+                level.and_then(|l| set_target_level(target, l))
+            }
+            _ => Err(
+                "usage: log.level <target|*> <off|error|warn|info|debug|trace|reset>".to_string(),
+            ),
+        };
+
+        LogLevelResp {
+            ok: res.is_ok(),
+            levels: target_levels()
+                .into_iter()
+                .map(|(t, l)| (t, l.as_str().to_ascii_lowercase()))
+                .collect(),
+            error: res.err(),
+        }
+    }
+}
+
+impl ServiceV1 for LogService {
+    fn id(&self) -> CapabilityId {
+        RString::from(LOG_SERVICE_ID)
+    }
+
+    fn describe(&self) -> RString {
+        let d = json!({
+          "id": LOG_SERVICE_ID,
+          "version": 1,
+          "methods": [
+            { "name": method::TAIL_JSON, "payload": "utf8 '[n] [level=..] [target=..] [since=seq] [cursor=seq]'", "returns": "json LogTailResp", "paged": true },
+            { "name": method::LEVEL, "payload": "utf8 '[<target|*> <level|reset>]'", "returns": "json LogLevelResp" }
+          ],
+          "console": {
+            "commands": [
+              {
+                "name": "log.tail",
+                "help": "Show recent log records: log.tail [n] [level=warn] [target=prefix]",
+                "usage": "log.tail [n] [level=<level>] [target=<prefix>]",
+                "kind": "service_call",
+                "service_id": LOG_SERVICE_ID,
+                "method": method::TAIL_JSON,
+                "payload": "raw"
+              },
+              {
+                "name": "log.level",
+                "help": "Change a log level at runtime: log.level <target|*> <level|reset>",
+                "usage": "log.level <target|*> <level|reset>",
+                "kind": "service_call",
+                "service_id": LOG_SERVICE_ID,
+                "method": method::LEVEL,
+                "payload": "raw"
+              }
+            ]
+          }
+        });
+
+        RString::from(d.to_string())
+    }
+
+    fn call(&self, method: MethodName, payload: Blob) -> RResult<Blob, RString> {
+        let m = method.to_string();
+        let arg = String::from_utf8_lossy(payload.as_slice());
+
+        let resp = match m.as_str() {
+            method::TAIL_JSON => match Self::parse_query(&arg) {
+                Ok(mut q) => {
+                    // One extra record tells whether an older page exists.
+                    let limit = q.limit;
+                    q.limit = limit.map(|n| n + 1);
+                    let (mut entries, next_seq) = log_tail(&q);
+                    let more = limit.is_some_and(|n| entries.len() > n);
+                    if more {
+                        entries.remove(0);
+                    }
+                    let next_cursor = entries
+                        .first()
+                        .filter(|_| more)
+                        .map(|e| e.seq.to_string());
+                    serde_json::to_vec(&LogTailResp {
+                        next_seq,
+                        entries,
+                        next_cursor,
+                    })
+                }
+                Err(e) => return RResult::RErr(RString::from(e)),
+            },
+            method::LEVEL => serde_json::to_vec(&Self::level(&arg)),
+            _ => return RResult::RErr(RString::from(format!("unknown method: {m}"))),
+        };
+
+        RResult::ROk(Blob::from(resp.unwrap_or_default()))
+    }
+}
@@ -0,0 +1,387 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use env_logger::Logger;
+use log::{LevelFilter, Log, Metadata, Record};
+use serde::Serialize;
+
+use std::collections::VecDeque;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock, RwLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const DEFAULT_RING_CAPACITY: usize = 4096;
+
+/// One record kept in the ring buffer and written to the JSON file.
+#[derive(Debug, Clone, Serialize)]
+pub struct LogEntry {
+    /// Monotonic per process; lets pollers ask for records newer than the last one seen.
+    pub seq: u64,
+    pub ts_ms: u64,
+    pub level: &'static str,
+    pub target: String,
+    pub message: String,
+}
+
+/// JSON-lines file output, rotated as `<path>.1` .. `<path>.<max_files>`.
+#[derive(Debug, Clone)]
+pub struct JsonLogFile {
+    pub path: PathBuf,
+    pub max_bytes: u64,
+    pub max_files: usize,
+}
+
+impl JsonLogFile {
+    #[inline]
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            max_bytes: 8 * 1024 * 1024,
+            max_files: 3,
+        }
+    }
+
+    #[inline]
+    pub fn with_max_bytes(mut self, max_bytes: u64) -> Self {
+        self.max_bytes = max_bytes.max(1024);
+        self
+    }
+
+    #[inline]
+    pub fn with_max_files(mut self, max_files: usize) -> Self {
+        self.max_files = max_files;
+        self
+    }
+}
+
+/// Filter for [`log_tail`]; all fields are optional.
+#[derive(Debug, Clone, Default)]
+pub struct LogQuery {
+    /// At most this many of the newest matching records.
+    pub limit: Option<usize>,
+    /// Records at this level or more severe.
+    pub level: Option<LevelFilter>,
+    /// Target prefix (`a::b` also matches `a::b::c`).
+    pub target: Option<String>,
+    /// Only records with `seq > since`.
+    pub since: Option<u64>,
+    /// Only records with `seq < before`; pages backwards through older records.
+    pub before: Option<u64>,
+}
+
+struct JsonFileWriter {
+    cfg: JsonLogFile,
+    file: File,
+    written: u64,
+}
+
+impl JsonFileWriter {
+    fn open(cfg: JsonLogFile) -> std::io::Result<Self> {
+        if let Some(parent) = cfg.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&cfg.path)?;
+        let written = file.metadata().map(|m| m.len()).unwrap_or(0);
+        Ok(Self { cfg, file, written })
+    }
+
+    fn write(&mut self, entry: &LogEntry) -> std::io::Result<()> {
+        let mut line = serde_json::to_vec(entry).map_err(std::io::Error::other)?;
+        line.push(b'\n');
+
+        if self.written > 0 && self.written + line.len() as u64 > self.cfg.max_bytes {
+            self.rotate()?;
+        }
+
+        self.file.write_all(&line)?;
+        self.written += line.len() as u64;
+        Ok(())
+    }
+
+    fn rotate(&mut self) -> std::io::Result<()> {
+        let path = &self.cfg.path;
+        if self.cfg.max_files == 0 {
+            self.file = File::create(path)?;
+            self.written = 0;
+            return Ok(());
+        }
+
+        let _ = std::fs::remove_file(rotated(path, self.cfg.max_files));
+        for i in (1..self.cfg.max_files).rev() {
+            let from = rotated(path, i);
+            if from.exists() {
+                std::fs::rename(&from, rotated(path, i + 1))?;
+            }
+        }
+        std::fs::rename(path, rotated(path, 1))?;
+
+        self.file = OpenOptions::new().create(true).append(true).open(path)?;
+        self.written = 0;
+        Ok(())
+    }
+}
+
+#[inline]
+fn rotated(path: &Path, n: usize) -> PathBuf {
+    let mut s = path.as_os_str().to_os_string();
+    s.push(format!(".{n}"));
+    PathBuf::from(s)
+}
+
+struct Ring {
+    entries: VecDeque<LogEntry>,
+    capacity: usize,
+    next_seq: u64,
+    file: Option<JsonFileWriter>,
+}
+
+struct Levels {
+    /// Replaces the startup filter when set (`log.level * <level>`).
+    default: Option<LevelFilter>,
+    /// Per-target overrides; the longest matching prefix wins.
+    targets: Vec<(String, LevelFilter)>,
+    /// Max level of the startup filter, used to recompute `log::max_level`.
+    base_max: LevelFilter,
+}
+
+impl Levels {
+    fn lookup(&self, target: &str) -> Option<LevelFilter> {
+        self.targets
+            .iter()
+            .filter(|(t, _)| target_matches(target, t))
+            .max_by_key(|(t, _)| t.len())
+            .map(|(_, l)| *l)
+            .or(self.default)
+    }
+
+    fn max_level(&self) -> LevelFilter {
+        self.targets
+            .iter()
+            .map(|(_, l)| *l)
+            .fold(self.default.unwrap_or(self.base_max), Ord::max)
+    }
+}
+
+#[inline]
+fn target_matches(target: &str, prefix: &str) -> bool {
+    target
+        .strip_prefix(prefix)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+}
+
+struct SinkShared {
+    ring: Mutex<Ring>,
+    levels: RwLock<Levels>,
+}
+
+/// Process-wide: there is a single global logger, and the `engine.log` service reads it
+/// without a handle to the module.
+static SINK: OnceLock<SinkShared> = OnceLock::new();
+
+#[inline]
+fn shared() -> &'static SinkShared {
+    SINK.get_or_init(|| SinkShared {
+        ring: Mutex::new(Ring {
+            entries: VecDeque::new(),
+            capacity: DEFAULT_RING_CAPACITY,
+            next_seq: 1,
+            file: None,
+        }),
+        levels: RwLock::new(Levels {
+            default: None,
+            targets: Vec::new(),
+            base_max: LevelFilter::Info,
+        }),
+    })
+}
+
+/// Global logger: startup filter plus runtime overrides, feeding the console, the ring
+/// buffer and the optional JSON file.
+pub(crate) struct LogSink {
+    console: Logger,
+    filter: env_filter::Filter,
+}
+
+impl LogSink {
+    /// `console` must not filter on its own; all filtering happens in [`LogSink::enabled`].
+    pub(crate) fn new(
+        console: Logger,
+        filter: env_filter::Filter,
+        capacity: usize,
+        json_file: Option<JsonLogFile>,
+    ) -> Self {
+        let s = shared();
+
+        if let Ok(mut r) = s.ring.lock() {
+            r.capacity = capacity.max(1);
+            r.file = json_file.and_then(|cfg| {
+                let path = cfg.path.display().to_string();
+                match JsonFileWriter::open(cfg) {
+                    Ok(w) => Some(w),
+                    Err(e) => {
+                        // The logger is not installed yet.
+                        eprintln!("logging: json file '{path}' disabled: {e}");
+                        None
+                    }
+                }
+            });
+        }
+        if let Ok(mut l) = s.levels.write() {
+            l.base_max = filter.filter();
+        }
+
+        Self { console, filter }
+    }
+
+    #[inline]
+    pub(crate) fn max_level(&self) -> LevelFilter {
+        shared()
+            .levels
+            .read()
+            .map(|l| l.max_level())
+            .unwrap_or_else(|_| self.filter.filter())
+    }
+
+    fn push(&self, record: &Record<'_>) {
+        let Ok(mut r) = shared().ring.lock() else {
+            return;
+        };
+
+        let entry = LogEntry {
+            seq: r.next_seq,
+            ts_ms: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or(0),
+            level: record.level().as_str(),
+            target: record.target().to_string(),
+            message: record.args().to_string(),
+        };
+        r.next_seq += 1;
+
+        if let Some(w) = r.file.as_mut() {
+            if let Err(e) = w.write(&entry) {
+                // Logging from here would re-enter the sink.
+                eprintln!("logging: json file disabled: {e}");
+                r.file = None;
+            }
+        }
+
+        while r.entries.len() >= r.capacity {
+            r.entries.pop_front();
+        }
+        r.entries.push_back(entry);
+    }
+}
+
+impl Log for LogSink {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        let level = shared()
+            .levels
+            .read()
+            .ok()
+            .and_then(|l| l.lookup(metadata.target()));
+
+        match level {
+            Some(l) => metadata.level() <= l,
+            None => self.filter.enabled(metadata),
+        }
+    }
+
+    fn log(&self, record: &Record<'_>) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        self.push(record);
+        self.console.log(record);
+    }
+
+    fn flush(&self) {
+        self.console.flush();
+        if let Ok(mut r) = shared().ring.lock() {
+            if let Some(w) = r.file.as_mut() {
+                let _ = w.file.flush();
+            }
+        }
+    }
+}
+
+/// Newest records matching `query`, oldest first, and the `seq` the next record will get.
+pub fn log_tail(query: &LogQuery) -> (Vec<LogEntry>, u64) {
+    let Ok(r) = shared().ring.lock() else {
+        return (Vec::new(), 0);
+    };
+
+    let mut out: Vec<LogEntry> = r
+        .entries
+        .iter()
+        .rev()
+        .filter(|e| query.since.is_none_or(|s| e.seq > s))
+        .filter(|e| query.before.is_none_or(|b| e.seq < b))
+        .filter(|e| {
+            query
+                .level
+                .is_none_or(|l| e.level.parse::<log::Level>().is_ok_and(|lv| lv <= l))
+        })
+        .filter(|e| {
+            query
+                .target
+                .as_deref()
+                .is_none_or(|t| target_matches(&e.target, t))
+        })
+        .take(query.limit.unwrap_or(usize::MAX))
+        .cloned()
+        .collect();
+    out.reverse();
+
+    (out, r.next_seq)
+}
+
+/// Overrides the level of `target` (and its `::` children) at runtime.
+///
+/// `*` replaces the startup filter for all other targets; `None` removes the override.
+pub fn set_target_level(target: &str, level: Option<LevelFilter>) -> Result<(), String> {
+    let target = target.trim();
+    if target.is_empty() {
+        return Err("empty log target".to_string());
+    }
+
+    let max = {
+        let mut l = shared()
+            .levels
+            .write()
+            .map_err(|_| "log levels lock poisoned".to_string())?;
+
+        if target == "*" {
+            l.default = level;
+        } else {
+            l.targets.retain(|(t, _)| t != target);
+            if let Some(level) = level {
+                l.targets.push((target.to_string(), level));
+            }
+        }
+        l.max_level()
+    };
+
+    log::set_max_level(max);
+    Ok(())
+}
+
+/// Active runtime overrides; `*` is the default override.
+pub fn target_levels() -> Vec<(String, LevelFilter)> {
+    let Ok(l) = shared().levels.read() else {
+        return Vec::new();
+    };
+
+    let mut out: Vec<(String, LevelFilter)> = l
+        .default
+        .map(|d| ("*".to_string(), d))
+        .into_iter()
+        .collect();
+    out.extend(l.targets.iter().cloned());
+    out
+}
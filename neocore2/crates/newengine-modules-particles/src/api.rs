@@ -0,0 +1,189 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use newengine_core::render::TextureId;
+use newengine_modules_sprite2d::{Sprite, Sprite2dApiRef};
+use parking_lot::Mutex;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use crate::desc::{EmitterDesc, EmitterDescError};
+use crate::emitter::{EmitterState, ParticleEmitter};
+use crate::EmitterId;
+
+/// Counters of the last fixed step.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ParticlesStats {
+    pub emitters: usize,
+    /// Emitters whose asset has not loaded (or failed to).
+    pub waiting: usize,
+    pub particles: usize,
+    /// Pooled slots across all emitters.
+    pub capacity: usize,
+    /// Spawns skipped because an emitter's pool was full.
+    pub dropped: usize,
+}
+
+struct ParticleWorld {
+    descs: HashMap<String, Arc<EmitterDesc>>,
+    emitters: HashMap<EmitterId, EmitterState>,
+    /// Assets referenced by emitters that the module has not been asked to load yet.
+    requests: Vec<String>,
+    requested: HashSet<String>,
+    sprites: Vec<Sprite>,
+    stats: ParticlesStats,
+}
+
+impl ParticleWorld {
+    fn request(&mut self, asset: &str) {
+        if !self.descs.contains_key(asset) && self.requested.insert(asset.to_string()) {
+            self.requests.push(asset.to_string());
+        }
+    }
+
+    fn step(&mut self, dt: f32) {
+        let mut stats = ParticlesStats {
+            emitters: self.emitters.len(),
+            ..Default::default()
+        };
+        for state in self.emitters.values_mut() {
+            match self.descs.get(&state.component.asset) {
+                Some(desc) => stats.dropped += state.step(desc, dt),
+                None => stats.waiting += 1,
+            }
+            stats.particles += state.alive();
+            stats.capacity += state.capacity();
+        }
+        self.stats = stats;
+    }
+}
+
+/// Shared handle to the particle emitters, registered as `particles.api`.
+///
+/// Emitters are components keyed by caller-picked [`EmitterId`]s. Their assets are loaded by
+/// the module on first use; until then an emitter simply does not spawn.
+#[derive(Clone)]
+pub struct ParticlesApiRef(Arc<Mutex<ParticleWorld>>);
+
+impl ParticlesApiRef {
+    pub(crate) fn new() -> Self {
+        Self(Arc::new(Mutex::new(ParticleWorld {
+            descs: HashMap::new(),
+            emitters: HashMap::new(),
+            requests: Vec::new(),
+            requested: HashSet::new(),
+            sprites: Vec::new(),
+            stats: ParticlesStats::default(),
+        })))
+    }
+
+    /// Adds or replaces the emitter of `id`; a replaced emitter loses its live particles.
+    pub fn insert(&self, id: EmitterId, emitter: ParticleEmitter) {
+        let mut w = self.0.lock();
+        w.request(&emitter.asset);
+        w.emitters.insert(id, EmitterState::new(emitter, id.0));
+    }
+
+    /// Removes the emitter and its particles. Returns false if there was none.
+    #[inline]
+    pub fn remove(&self, id: EmitterId) -> bool {
+        self.0.lock().emitters.remove(&id).is_some()
+    }
+
+    #[inline]
+    pub fn contains(&self, id: EmitterId) -> bool {
+        self.0.lock().emitters.contains_key(&id)
+    }
+
+    pub fn emitter(&self, id: EmitterId) -> Option<ParticleEmitter> {
+        self.0.lock().emitters.get(&id).map(|s| s.component.clone())
+    }
+
+    /// Moves where new particles spawn; live ones keep their paths.
+    pub fn set_position(&self, id: EmitterId, position: [f32; 2]) -> bool {
+        self.with_emitter(id, |s| s.component.position = position)
+    }
+
+    pub fn set_emitting(&self, id: EmitterId, emitting: bool) -> bool {
+        self.with_emitter(id, |s| s.component.emitting = emitting)
+    }
+
+    pub fn set_texture(&self, id: EmitterId, texture: Option<TextureId>) -> bool {
+        self.with_emitter(id, |s| s.component.texture = texture)
+    }
+
+    /// Rewinds the emitter's duration and fires its burst again.
+    pub fn restart(&self, id: EmitterId) -> bool {
+        self.with_emitter(id, EmitterState::restart)
+    }
+
+    /// Registers an emitter asset built in code under `name`, usable as
+    /// [`ParticleEmitter::asset`]. Replaces a loaded asset of the same name.
+    pub fn insert_desc(
+        &self,
+        name: impl Into<String>,
+        mut desc: EmitterDesc,
+    ) -> Result<(), EmitterDescError> {
+        desc.validate()?;
+        self.set_desc(name.into(), desc);
+        Ok(())
+    }
+
+    /// The emitter asset known as `name`, if loaded.
+    pub fn desc(&self, name: &str) -> Option<Arc<EmitterDesc>> {
+        self.0.lock().descs.get(name).cloned()
+    }
+
+    #[inline]
+    pub fn stats(&self) -> ParticlesStats {
+        self.0.lock().stats
+    }
+
+    /// Advances every emitter by `dt` seconds.
+    #[inline]
+    pub fn step(&self, dt: f32) {
+        self.0.lock().step(dt);
+    }
+
+    /// Queues every live particle as a sprite.
+    pub fn draw(&self, sprites: &Sprite2dApiRef) {
+        let mut guard = self.0.lock();
+        let w = &mut *guard;
+        for state in w.emitters.values() {
+            if let Some(desc) = w.descs.get(&state.component.asset) {
+                state.sprites(desc, &mut w.sprites);
+            }
+        }
+        sprites.draw_all(w.sprites.drain(..));
+    }
+
+    /// Removes every emitter and asset.
+    pub fn clear(&self) {
+        let mut w = self.0.lock();
+        w.emitters.clear();
+        w.descs.clear();
+        w.requests.clear();
+        w.requested.clear();
+        w.stats = ParticlesStats::default();
+    }
+
+    pub(crate) fn set_desc(&self, name: String, desc: EmitterDesc) {
+        let mut w = self.0.lock();
+        w.requested.remove(&name);
+        w.descs.insert(name, Arc::new(desc));
+    }
+
+    /// Asset names emitters refer to that nobody has loaded yet.
+    pub(crate) fn take_requests(&self) -> Vec<String> {
+        std::mem::take(&mut self.0.lock().requests)
+    }
+
+    fn with_emitter(&self, id: EmitterId, f: impl FnOnce(&mut EmitterState)) -> bool {
+        match self.0.lock().emitters.get_mut(&id) {
+            Some(state) => {
+                f(state);
+                true
+            }
+            None => false,
+        }
+    }
+}
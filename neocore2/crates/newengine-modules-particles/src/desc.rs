@@ -0,0 +1,248 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use newengine_core::render::Color4;
+use serde::Deserialize;
+
+#[derive(Debug)]
+pub enum EmitterDescError {
+    Json(String),
+    Invalid(String),
+}
+
+impl std::fmt::Display for EmitterDescError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EmitterDescError::Json(e) => write!(f, "emitter: json parse failed: {e}"),
+            EmitterDescError::Invalid(e) => write!(f, "emitter: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for EmitterDescError {}
+
+/// Value of a curve at normalized particle age `t`.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct CurveKey {
+    pub t: f32,
+    pub value: f32,
+}
+
+/// Piecewise-linear function of normalized age (0 at birth, 1 at death).
+///
+/// No keys means a constant 1; before the first and after the last key the end values hold.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(transparent)]
+pub struct Curve {
+    pub keys: Vec<CurveKey>,
+}
+
+impl Curve {
+    #[inline]
+    pub fn constant(value: f32) -> Self {
+        Self {
+            keys: vec![CurveKey { t: 0.0, value }],
+        }
+    }
+
+    pub fn sample(&self, t: f32) -> f32 {
+        sample_keys(
+            &self.keys,
+            t,
+            |k| k.t,
+            |k| k.value,
+            1.0,
+            |a, b, f| a + (b - a) * f,
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct ColorKey {
+    pub t: f32,
+    /// Straight-alpha RGBA.
+    pub color: Color4,
+}
+
+/// Color over normalized age; no keys means opaque white.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(transparent)]
+pub struct Gradient {
+    pub keys: Vec<ColorKey>,
+}
+
+impl Gradient {
+    pub fn sample(&self, t: f32) -> Color4 {
+        sample_keys(
+            &self.keys,
+            t,
+            |k| k.t,
+            |k| k.color,
+            [1.0; 4],
+            |a, b, f| std::array::from_fn(|i| a[i] + (b[i] - a[i]) * f),
+        )
+    }
+}
+
+fn sample_keys<K, V: Copy>(
+    keys: &[K],
+    t: f32,
+    key_t: impl Fn(&K) -> f32,
+    key_v: impl Fn(&K) -> V,
+    empty: V,
+    lerp: impl Fn(V, V, f32) -> V,
+) -> V {
+    let (Some(first), Some(last)) = (keys.first(), keys.last()) else {
+        return empty;
+    };
+    if t <= key_t(first) {
+        return key_v(first);
+    }
+    for w in keys.windows(2) {
+        let (t0, t1) = (key_t(&w[0]), key_t(&w[1]));
+        if t <= t1 {
+            let f = if t1 > t0 { (t - t0) / (t1 - t0) } else { 1.0 };
+            return lerp(key_v(&w[0]), key_v(&w[1]), f);
+        }
+    }
+    key_v(last)
+}
+
+/// Emitter asset (`*.particles.json` by convention), shared by every emitter using it.
+///
+/// Every field is optional in JSON:
+///
+/// ```json
+/// {
+///   "rate": 40, "max_particles": 256,
+///   "lifetime": [0.6, 1.2], "speed": [2, 4], "direction": 90, "spread": 25,
+///   "gravity": [0, -3],
+///   "velocity_over_life": [{ "t": 0, "value": 1 }, { "t": 1, "value": 0.2 }],
+///   "size": [0.2, 0.3],
+///   "size_over_life": [{ "t": 0, "value": 0.5 }, { "t": 1, "value": 1.5 }],
+///   "color_over_life": [
+///     { "t": 0, "color": [1, 0.8, 0.3, 1] },
+///     { "t": 1, "color": [0.6, 0.1, 0, 0] }
+///   ]
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct EmitterDesc {
+    /// Particles spawned per second while emitting.
+    pub rate: f32,
+    /// Particles spawned at once when the emitter starts.
+    pub burst: u32,
+    /// Pool size; spawns beyond it are dropped.
+    pub max_particles: u32,
+    /// Seconds the emitter runs before stopping; 0 emits until removed or disabled.
+    pub duration: f32,
+    /// Restart after `duration`, burst included.
+    pub looping: bool,
+    /// Seconds, picked uniformly per particle from `[min, max]`.
+    pub lifetime: [f32; 2],
+    /// World units per second at birth, `[min, max]`.
+    pub speed: [f32; 2],
+    /// Launch angle in degrees, counter-clockwise from +x.
+    pub direction: f32,
+    /// Full cone angle in degrees around `direction`.
+    pub spread: f32,
+    /// Acceleration in world units per second squared.
+    pub gravity: [f32; 2],
+    /// Multiplies the particle velocity when integrating (drag-like slowdowns).
+    pub velocity_over_life: Curve,
+    /// Quad edge in world units at birth, `[min, max]`.
+    pub size: [f32; 2],
+    pub size_over_life: Curve,
+    pub color_over_life: Gradient,
+    /// Spin in degrees per second, `[min, max]`.
+    pub angular_velocity: [f32; 2],
+    /// Sprite layer the particles are drawn on.
+    pub layer: i32,
+}
+
+impl Default for EmitterDesc {
+    fn default() -> Self {
+        Self {
+            rate: 10.0,
+            burst: 0,
+            max_particles: 256,
+            duration: 0.0,
+            looping: false,
+            lifetime: [1.0, 1.0],
+            speed: [1.0, 1.0],
+            direction: 90.0,
+            spread: 0.0,
+            gravity: [0.0, 0.0],
+            velocity_over_life: Curve::default(),
+            size: [0.1, 0.1],
+            size_over_life: Curve::default(),
+            color_over_life: Gradient::default(),
+            angular_velocity: [0.0, 0.0],
+            layer: 0,
+        }
+    }
+}
+
+impl EmitterDesc {
+    /// Upper bound for `max_particles`, so a typo cannot reserve gigabytes.
+    pub const MAX_PARTICLES: u32 = 65_536;
+
+    pub fn from_json(text: &str) -> Result<Self, EmitterDescError> {
+        let mut desc: Self =
+            serde_json::from_str(text).map_err(|e| EmitterDescError::Json(e.to_string()))?;
+        desc.validate()?;
+        Ok(desc)
+    }
+
+    /// Rejects values the simulation cannot use and orders `[min, max]` ranges and curve keys.
+    pub fn validate(&mut self) -> Result<(), EmitterDescError> {
+        let finite = [self.rate, self.duration, self.direction, self.spread]
+            .into_iter()
+            .chain(self.lifetime)
+            .chain(self.speed)
+            .chain(self.gravity)
+            .chain(self.size)
+            .chain(self.angular_velocity)
+            .all(f32::is_finite);
+        if !finite {
+            return Err(EmitterDescError::Invalid("non-finite number".to_string()));
+        }
+        if self.rate < 0.0 || self.duration < 0.0 {
+            return Err(EmitterDescError::Invalid(
+                "rate and duration must not be negative".to_string(),
+            ));
+        }
+        if self.max_particles > Self::MAX_PARTICLES {
+            return Err(EmitterDescError::Invalid(format!(
+                "max_particles {} exceeds {}",
+                self.max_particles,
+                Self::MAX_PARTICLES
+            )));
+        }
+
+        for range in [
+            &mut self.lifetime,
+            &mut self.speed,
+            &mut self.size,
+            &mut self.angular_velocity,
+        ] {
+            if range[0] > range[1] {
+                range.swap(0, 1);
+            }
+        }
+        if self.lifetime[0] <= 0.0 {
+            return Err(EmitterDescError::Invalid(
+                "lifetime must be positive".to_string(),
+            ));
+        }
+
+        self.velocity_over_life
+            .keys
+            .sort_by(|a, b| a.t.total_cmp(&b.t));
+        self.size_over_life.keys.sort_by(|a, b| a.t.total_cmp(&b.t));
+        self.color_over_life
+            .keys
+            .sort_by(|a, b| a.t.total_cmp(&b.t));
+        Ok(())
+    }
+}
@@ -0,0 +1,214 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use newengine_core::render::TextureId;
+use newengine_modules_sprite2d::Sprite;
+
+use crate::desc::EmitterDesc;
+
+/// Emitter component: where an emitter asset spawns particles.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParticleEmitter {
+    /// Logical path of the emitter asset, or a name given to `ParticlesApiRef::insert_desc`.
+    pub asset: String,
+    pub position: [f32; 2],
+    /// Spawning enabled; live particles finish their life either way.
+    pub emitting: bool,
+    /// `None`: solid quads tinted by the color gradient.
+    pub texture: Option<TextureId>,
+}
+
+impl ParticleEmitter {
+    #[inline]
+    pub fn new(asset: impl Into<String>) -> Self {
+        Self {
+            asset: asset.into(),
+            position: [0.0, 0.0],
+            emitting: true,
+            texture: None,
+        }
+    }
+
+    #[inline]
+    pub fn with_position(mut self, position: [f32; 2]) -> Self {
+        self.position = position;
+        self
+    }
+
+    #[inline]
+    pub fn with_emitting(mut self, emitting: bool) -> Self {
+        self.emitting = emitting;
+        self
+    }
+
+    #[inline]
+    pub fn with_texture(mut self, texture: TextureId) -> Self {
+        self.texture = Some(texture);
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Particle {
+    position: [f32; 2],
+    velocity: [f32; 2],
+    age: f32,
+    lifetime: f32,
+    size: f32,
+    rotation: f32,
+    /// Radians per second.
+    spin: f32,
+}
+
+/// Live state of one emitter: its particle pool and spawn timing.
+///
+/// The pool is a `Vec` sized to the asset's `max_particles` once; dead particles are
+/// swap-removed, so steady-state simulation does not allocate.
+pub(crate) struct EmitterState {
+    pub(crate) component: ParticleEmitter,
+    particles: Vec<Particle>,
+    spawn_debt: f32,
+    elapsed: f32,
+    started: bool,
+    finished: bool,
+    rng: u32,
+}
+
+impl EmitterState {
+    pub(crate) fn new(component: ParticleEmitter, seed: u64) -> Self {
+        Self {
+            component,
+            particles: Vec::new(),
+            spawn_debt: 0.0,
+            elapsed: 0.0,
+            started: false,
+            finished: false,
+            rng: (seed ^ (seed >> 32)) as u32 | 1,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn alive(&self) -> usize {
+        self.particles.len()
+    }
+
+    #[inline]
+    pub(crate) fn capacity(&self) -> usize {
+        self.particles.capacity()
+    }
+
+    /// Starts over as if just inserted: fires the burst again on the next step.
+    pub(crate) fn restart(&mut self) {
+        self.spawn_debt = 0.0;
+        self.elapsed = 0.0;
+        self.started = false;
+        self.finished = false;
+    }
+
+    /// Advances the particles by `dt` seconds and spawns new ones. Returns how many spawns
+    /// were dropped because the pool was full.
+    pub(crate) fn step(&mut self, desc: &EmitterDesc, dt: f32) -> usize {
+        let max = desc.max_particles as usize;
+        if self.particles.len() > max {
+            self.particles.truncate(max);
+        }
+        if self.particles.capacity() < max {
+            self.particles.reserve_exact(max - self.particles.len());
+        }
+
+        let mut i = 0;
+        while i < self.particles.len() {
+            let p = &mut self.particles[i];
+            p.age += dt;
+            if p.age >= p.lifetime {
+                self.particles.swap_remove(i);
+                continue;
+            }
+
+            let scale = desc.velocity_over_life.sample(p.age / p.lifetime);
+            p.velocity[0] += desc.gravity[0] * dt;
+            p.velocity[1] += desc.gravity[1] * dt;
+            p.position[0] += p.velocity[0] * scale * dt;
+            p.position[1] += p.velocity[1] * scale * dt;
+            p.rotation += p.spin * dt;
+            i += 1;
+        }
+
+        if !self.component.emitting || self.finished {
+            return 0;
+        }
+
+        let mut spawn = 0u32;
+        if !self.started {
+            self.started = true;
+            spawn += desc.burst;
+        }
+
+        self.elapsed += dt;
+        if desc.duration > 0.0 && self.elapsed >= desc.duration {
+            if desc.looping {
+                self.elapsed %= desc.duration;
+                spawn += desc.burst;
+            } else {
+                self.finished = true;
+            }
+        }
+
+        if !self.finished {
+            self.spawn_debt += desc.rate * dt;
+            let due = self.spawn_debt.floor();
+            self.spawn_debt -= due;
+            spawn = spawn.saturating_add(due as u32);
+        }
+
+        let free = max - self.particles.len();
+        let count = (spawn as usize).min(free);
+        for _ in 0..count {
+            let particle = self.spawn(desc);
+            self.particles.push(particle);
+        }
+        spawn as usize - count
+    }
+
+    fn spawn(&mut self, desc: &EmitterDesc) -> Particle {
+        let half_spread = desc.spread * 0.5;
+        let angle = (desc.direction + self.range([-half_spread, half_spread])).to_radians();
+        let speed = self.range(desc.speed);
+        let (sin, cos) = angle.sin_cos();
+        Particle {
+            position: self.component.position,
+            velocity: [cos * speed, sin * speed],
+            age: 0.0,
+            lifetime: self.range(desc.lifetime),
+            size: self.range(desc.size),
+            rotation: 0.0,
+            spin: self.range(desc.angular_velocity).to_radians(),
+        }
+    }
+
+    /// Uniform in `[min, max]` (xorshift32; particles do not need a real RNG).
+    fn range(&mut self, [min, max]: [f32; 2]) -> f32 {
+        let mut x = self.rng;
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        self.rng = x;
+        min + (max - min) * ((x >> 8) as f32 / (1u32 << 24) as f32)
+    }
+
+    /// Appends one sprite per live particle, sized and tinted for its age.
+    pub(crate) fn sprites(&self, desc: &EmitterDesc, out: &mut Vec<Sprite>) {
+        out.extend(self.particles.iter().map(|p| {
+            let t = p.age / p.lifetime;
+            let size = p.size * desc.size_over_life.sample(t);
+            let sprite = Sprite::new([size, size])
+                .with_position(p.position)
+                .with_rotation(p.rotation)
+                .with_color(desc.color_over_life.sample(t))
+                .with_layer(desc.layer);
+            match self.component.texture {
+                Some(tex) => sprite.with_texture(tex),
+                None => sprite,
+            }
+        }));
+    }
+}
@@ -0,0 +1,28 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+mod api;
+mod desc;
+mod emitter;
+mod module;
+
+pub use api::{ParticlesApiRef, ParticlesStats};
+pub use desc::{ColorKey, Curve, CurveKey, EmitterDesc, EmitterDescError, Gradient};
+pub use emitter::ParticleEmitter;
+pub use module::{ParticlesConfig, ParticlesModule};
+
+use newengine_core::{ApiProvide, ApiVersion};
+
+pub const PARTICLES_API_ID: &str = "particles.api";
+pub const PARTICLES_API_VERSION: ApiVersion = ApiVersion::new(0, 1, 0);
+pub const PARTICLES_API_PROVIDE: ApiProvide =
+    ApiProvide::new(PARTICLES_API_ID, PARTICLES_API_VERSION);
+
+/// Key of a particle emitter component; callers pick the ids, as with physics entities.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct EmitterId(pub u64);
+
+impl std::fmt::Display for EmitterId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
@@ -0,0 +1,227 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use newengine_assets::{AssetEvent, AssetEventReceiver, AssetId, AssetState, TextReader};
+use newengine_core::assets::AssetManager;
+use newengine_core::{ApiProvide, EngineResult, Module, ModuleCtx};
+use newengine_modules_sprite2d::{Sprite2dApiRef, SPRITE2D_API_ID};
+use std::collections::HashMap;
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::api::ParticlesApiRef;
+use crate::desc::EmitterDesc;
+use crate::{PARTICLES_API_ID, PARTICLES_API_PROVIDE};
+
+#[derive(Debug, Clone)]
+pub struct ParticlesConfig {
+    /// Re-import emitter assets when their source files change.
+    pub hot_reload: bool,
+    pub poll_interval: Duration,
+}
+
+impl ParticlesConfig {
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            hot_reload: true,
+            poll_interval: Duration::from_millis(500),
+        }
+    }
+
+    #[inline]
+    pub fn with_hot_reload(mut self, enabled: bool) -> Self {
+        self.hot_reload = enabled;
+        self
+    }
+
+    #[inline]
+    pub fn with_poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+}
+
+impl Default for ParticlesConfig {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+struct TrackedAsset {
+    path: String,
+    modified: Option<SystemTime>,
+}
+
+/// Simulates particle emitters and exposes them as `particles.api`.
+///
+/// Particles advance once per `fixed_update` and are queued on the sprite layer in `update`.
+/// Emitter assets load through the AssetManager when an emitter first names them; with hot
+/// reload on, a changed file is re-imported and swapped in on `AssetEvent::Reloaded`, and a
+/// broken edit keeps the previous version.
+pub struct ParticlesModule {
+    config: ParticlesConfig,
+    api: ParticlesApiRef,
+    assets: HashMap<AssetId, TrackedAsset>,
+    events: Option<AssetEventReceiver>,
+    last_poll: Instant,
+}
+
+impl ParticlesModule {
+    #[inline]
+    pub fn new(config: ParticlesConfig) -> Self {
+        Self {
+            config,
+            api: ParticlesApiRef::new(),
+            assets: HashMap::new(),
+            events: None,
+            last_poll: Instant::now(),
+        }
+    }
+
+    /// Handle for consumers living outside the engine.
+    #[inline]
+    pub fn api(&self) -> ParticlesApiRef {
+        self.api.clone()
+    }
+
+    fn load_requested(&mut self, am: &AssetManager) {
+        for path in self.api.take_requests() {
+            match am.store().load_path(&path) {
+                Ok(id) => {
+                    let modified = am.store().source_modified(&path);
+                    self.assets.insert(id, TrackedAsset { path, modified });
+                    // Already imported by someone else: no Ready event will follow.
+                    if matches!(am.state(id), AssetState::Ready) {
+                        self.apply(am, id);
+                    }
+                }
+                Err(e) => log::warn!(
+                    target: "particles",
+                    "emitter.load rejected path='{path}' err='{e}'"
+                ),
+            }
+        }
+    }
+
+    fn apply(&self, am: &AssetManager, id: AssetId) {
+        let Some(asset) = self.assets.get(&id) else {
+            return;
+        };
+        let Some(blob) = am.get_blob(id) else {
+            return;
+        };
+        let parsed = TextReader::from_blob_parts(&blob.meta_json, &blob.payload)
+            .map_err(|e| e.to_string())
+            .and_then(|doc| EmitterDesc::from_json(&doc.text).map_err(|e| e.to_string()));
+        match parsed {
+            Ok(desc) => self.api.set_desc(asset.path.clone(), desc),
+            Err(e) => log::warn!(
+                target: "particles",
+                "emitter.parse failed path='{}' err='{e}'",
+                asset.path
+            ),
+        }
+    }
+
+    fn poll_events(&self, am: &AssetManager) {
+        let Some(events) = self.events.as_ref() else {
+            return;
+        };
+        for ev in events.drain() {
+            match ev {
+                AssetEvent::Ready { id, .. } | AssetEvent::Reloaded { id, .. } => {
+                    self.apply(am, id)
+                }
+                AssetEvent::Failed { id, error, .. } => {
+                    if let Some(asset) = self.assets.get(&id) {
+                        log::warn!(
+                            target: "particles",
+                            "emitter.load failed path='{}' err='{error}'",
+                            asset.path
+                        );
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn poll_files(&mut self, am: &AssetManager) {
+        if self.last_poll.elapsed() < self.config.poll_interval {
+            return;
+        }
+        self.last_poll = Instant::now();
+
+        for asset in self.assets.values_mut() {
+            let modified = am.store().source_modified(&asset.path);
+            if modified.is_none() || modified == asset.modified {
+                continue;
+            }
+            asset.modified = modified;
+            if let Err(e) = am.store().reload_path(&asset.path) {
+                log::warn!(
+                    target: "particles",
+                    "emitter.reload rejected path='{}' err='{e}'",
+                    asset.path
+                );
+            }
+        }
+    }
+}
+
+impl<E: Send + 'static> Module<E> for ParticlesModule {
+    fn id(&self) -> &'static str {
+        "particles"
+    }
+
+    fn provides(&self) -> &'static [ApiProvide] {
+        &[PARTICLES_API_PROVIDE]
+    }
+
+    fn init(&mut self, ctx: &mut ModuleCtx<'_, E>) -> EngineResult<()> {
+        ctx.resources_mut()
+            .register_api(PARTICLES_API_ID, self.api.clone())
+    }
+
+    fn start(&mut self, ctx: &mut ModuleCtx<'_, E>) -> EngineResult<()> {
+        match ctx.resources().get::<AssetManager>() {
+            Some(am) => self.events = Some(am.subscribe_events()),
+            None => {
+                log::warn!(target: "particles", "AssetManager missing; emitter assets disabled")
+            }
+        }
+        Ok(())
+    }
+
+    fn fixed_update(&mut self, ctx: &mut ModuleCtx<'_, E>) -> EngineResult<()> {
+        if let Some(dt) = ctx.frame().map(|f| f.fixed_dt) {
+            self.api.step(dt);
+        }
+        Ok(())
+    }
+
+    fn update(&mut self, ctx: &mut ModuleCtx<'_, E>) -> EngineResult<()> {
+        if let Some(am) = ctx.resources().get::<AssetManager>() {
+            self.load_requested(am);
+            self.poll_events(am);
+            if self.config.hot_reload {
+                self.poll_files(am);
+            }
+        }
+
+        if let Some(sprites) = ctx.api::<Sprite2dApiRef>(SPRITE2D_API_ID) {
+            self.api.draw(sprites);
+        }
+        Ok(())
+    }
+
+    fn shutdown(&mut self, ctx: &mut ModuleCtx<'_, E>) -> EngineResult<()> {
+        let _ = ctx
+            .resources_mut()
+            .unregister_api::<ParticlesApiRef>(PARTICLES_API_ID);
+        self.events = None;
+        self.assets.clear();
+        self.api.clear();
+        Ok(())
+    }
+}
@@ -0,0 +1,385 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use newengine_core::render::{Color4, DebugDraw};
+use parking_lot::Mutex;
+use rapier3d::crossbeam::channel::{unbounded, Receiver};
+use rapier3d::na::{Isometry3, Quaternion, Translation3, UnitQuaternion};
+use rapier3d::prelude::*;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Instant;
+
+use crate::components::{BodyKind, Collider, ColliderShape, PhysicsTransform, RigidBody};
+use crate::events::PhysicsEvent;
+use crate::EntityId;
+
+const DYNAMIC_COLOR: Color4 = [0.35, 0.9, 0.45, 1.0];
+const SLEEPING_COLOR: Color4 = [0.45, 0.5, 0.5, 1.0];
+const FIXED_COLOR: Color4 = [0.35, 0.6, 1.0, 1.0];
+const KINEMATIC_COLOR: Color4 = [0.9, 0.5, 1.0, 1.0];
+const SENSOR_COLOR: Color4 = [1.0, 0.85, 0.2, 1.0];
+
+/// Counters for `physics.stats`.
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct PhysicsStats {
+    pub bodies: usize,
+    pub colliders: usize,
+    /// Dynamic bodies that are awake.
+    pub active_bodies: usize,
+    /// Collider pairs currently touching.
+    pub contact_pairs: usize,
+    pub steps: u64,
+    pub last_step_ms: f32,
+    pub collision_events: u64,
+}
+
+pub(crate) struct PhysicsWorld {
+    gravity: Vector<Real>,
+    params: IntegrationParameters,
+    pipeline: PhysicsPipeline,
+    islands: IslandManager,
+    broad_phase: DefaultBroadPhase,
+    narrow_phase: NarrowPhase,
+    bodies: RigidBodySet,
+    colliders: ColliderSet,
+    impulse_joints: ImpulseJointSet,
+    multibody_joints: MultibodyJointSet,
+    ccd: CCDSolver,
+    query: QueryPipeline,
+
+    events: ChannelEventCollector,
+    collision_rx: Receiver<CollisionEvent>,
+    /// Drained and dropped every step; contact force events are not enabled on colliders.
+    force_rx: Receiver<ContactForceEvent>,
+
+    handles: HashMap<EntityId, RigidBodyHandle>,
+    debug_draw: bool,
+    stats: PhysicsStats,
+}
+
+impl PhysicsWorld {
+    fn new(gravity: [f32; 3], debug_draw: bool) -> Self {
+        let (collision_tx, collision_rx) = unbounded();
+        let (force_tx, force_rx) = unbounded();
+
+        Self {
+            gravity: vector![gravity[0], gravity[1], gravity[2]],
+            params: IntegrationParameters::default(),
+            pipeline: PhysicsPipeline::new(),
+            islands: IslandManager::new(),
+            broad_phase: DefaultBroadPhase::new(),
+            narrow_phase: NarrowPhase::new(),
+            bodies: RigidBodySet::new(),
+            colliders: ColliderSet::new(),
+            impulse_joints: ImpulseJointSet::new(),
+            multibody_joints: MultibodyJointSet::new(),
+            ccd: CCDSolver::new(),
+            query: QueryPipeline::new(),
+
+            events: ChannelEventCollector::new(collision_tx, force_tx),
+            collision_rx,
+            force_rx,
+
+            handles: HashMap::new(),
+            debug_draw,
+            stats: PhysicsStats::default(),
+        }
+    }
+
+    fn insert(&mut self, entity: EntityId, body: &RigidBody, colliders: &[Collider]) {
+        if self.handles.contains_key(&entity) {
+            self.remove(entity);
+        }
+
+        let kind = match body.kind {
+            BodyKind::Dynamic => RigidBodyType::Dynamic,
+            BodyKind::Fixed => RigidBodyType::Fixed,
+            BodyKind::Kinematic => RigidBodyType::KinematicPositionBased,
+        };
+        let rb = RigidBodyBuilder::new(kind)
+            .position(isometry(body.position, body.rotation))
+            .linvel(vector![body.linvel[0], body.linvel[1], body.linvel[2]])
+            .gravity_scale(body.gravity_scale)
+            .ccd_enabled(body.ccd)
+            .user_data(entity.0 as u128)
+            .build();
+        let handle = self.bodies.insert(rb);
+
+        for c in colliders {
+            let builder = match c.shape {
+                ColliderShape::Ball { radius } => ColliderBuilder::ball(radius),
+                ColliderShape::Cuboid { half_extents: h } => {
+                    ColliderBuilder::cuboid(h[0], h[1], h[2])
+                }
+                ColliderShape::Capsule {
+                    half_height,
+                    radius,
+                } => ColliderBuilder::capsule_y(half_height, radius),
+            };
+            let collider = builder
+                .translation(vector![c.offset[0], c.offset[1], c.offset[2]])
+                .friction(c.friction)
+                .restitution(c.restitution)
+                .density(c.density)
+                .sensor(c.sensor)
+                .active_events(ActiveEvents::COLLISION_EVENTS)
+                .user_data(entity.0 as u128)
+                .build();
+            self.colliders
+                .insert_with_parent(collider, handle, &mut self.bodies);
+        }
+
+        self.handles.insert(entity, handle);
+    }
+
+    fn remove(&mut self, entity: EntityId) -> bool {
+        let Some(handle) = self.handles.remove(&entity) else {
+            return false;
+        };
+        self.bodies.remove(
+            handle,
+            &mut self.islands,
+            &mut self.colliders,
+            &mut self.impulse_joints,
+            &mut self.multibody_joints,
+            true,
+        );
+        true
+    }
+
+    #[inline]
+    fn body_mut(&mut self, entity: EntityId) -> Option<&mut rapier3d::dynamics::RigidBody> {
+        let handle = *self.handles.get(&entity)?;
+        self.bodies.get_mut(handle)
+    }
+
+    fn step(&mut self, dt: f32) -> Vec<PhysicsEvent> {
+        let t0 = Instant::now();
+        self.params.dt = dt;
+
+        self.pipeline.step(
+            &self.gravity,
+            &self.params,
+            &mut self.islands,
+            &mut self.broad_phase,
+            &mut self.narrow_phase,
+            &mut self.bodies,
+            &mut self.colliders,
+            &mut self.impulse_joints,
+            &mut self.multibody_joints,
+            &mut self.ccd,
+            Some(&mut self.query),
+            &(),
+            &self.events,
+        );
+
+        while self.force_rx.try_recv().is_ok() {}
+
+        let mut out = Vec::new();
+        while let Ok(ev) = self.collision_rx.try_recv() {
+            let entity_of =
+                |h: ColliderHandle| self.colliders.get(h).map(|c| EntityId(c.user_data as u64));
+            let (Some(a), Some(b)) = (entity_of(ev.collider1()), entity_of(ev.collider2())) else {
+                // `Stopped` for a collider that was removed since; its entity is gone too.
+                continue;
+            };
+            let sensor = ev.sensor();
+            out.push(if ev.started() {
+                PhysicsEvent::CollisionStarted { a, b, sensor }
+            } else {
+                PhysicsEvent::CollisionStopped { a, b, sensor }
+            });
+        }
+
+        self.stats.steps += 1;
+        self.stats.collision_events += out.len() as u64;
+        self.stats.last_step_ms = t0.elapsed().as_secs_f32() * 1000.0;
+        out
+    }
+
+    fn stats(&self) -> PhysicsStats {
+        PhysicsStats {
+            bodies: self.bodies.len(),
+            colliders: self.colliders.len(),
+            active_bodies: self.islands.active_dynamic_bodies().len(),
+            contact_pairs: self
+                .narrow_phase
+                .contact_pairs()
+                .filter(|p| p.has_any_active_contact)
+                .count(),
+            ..self.stats
+        }
+    }
+
+    /// Queues one-frame collider bounds, colored by body state.
+    fn draw_colliders(&self, dd: &DebugDraw) {
+        for (_, c) in self.colliders.iter() {
+            let body = c.parent().and_then(|h| self.bodies.get(h));
+            let color = match body {
+                _ if c.is_sensor() => SENSOR_COLOR,
+                Some(b) if b.is_fixed() => FIXED_COLOR,
+                Some(b) if b.is_kinematic() => KINEMATIC_COLOR,
+                Some(b) if b.is_sleeping() => SLEEPING_COLOR,
+                _ => DYNAMIC_COLOR,
+            };
+
+            if let Some(ball) = c.shape().as_ball() {
+                let p = c.translation();
+                dd.sphere([p.x, p.y, p.z], ball.radius, color, 0.0);
+            } else {
+                let aabb = c.compute_aabb();
+                dd.aabb(
+                    [aabb.mins.x, aabb.mins.y, aabb.mins.z],
+                    [aabb.maxs.x, aabb.maxs.y, aabb.maxs.z],
+                    color,
+                    0.0,
+                );
+            }
+        }
+    }
+}
+
+#[inline]
+fn isometry(position: [f32; 3], rotation: [f32; 4]) -> Isometry3<f32> {
+    let [x, y, z, w] = rotation;
+    Isometry3::from_parts(
+        Translation3::new(position[0], position[1], position[2]),
+        UnitQuaternion::from_quaternion(Quaternion::new(w, x, y, z)),
+    )
+}
+
+/// Shared handle registered in `Resources` under [`crate::PHYSICS_API_ID`].
+///
+/// Bodies are keyed by [`EntityId`]; inserting an entity that already has a body replaces
+/// it. Changes take effect on the next fixed step.
+#[derive(Clone)]
+pub struct PhysicsApiRef(Arc<Mutex<PhysicsWorld>>);
+
+impl PhysicsApiRef {
+    pub(crate) fn new(gravity: [f32; 3], debug_draw: bool) -> Self {
+        Self(Arc::new(Mutex::new(PhysicsWorld::new(gravity, debug_draw))))
+    }
+
+    /// Adds the entity's rigid body with its colliders.
+    #[inline]
+    pub fn insert(&self, entity: EntityId, body: &RigidBody, colliders: &[Collider]) {
+        self.0.lock().insert(entity, body, colliders);
+    }
+
+    /// Removes the body and its colliders. Returns false if the entity had none.
+    #[inline]
+    pub fn remove(&self, entity: EntityId) -> bool {
+        self.0.lock().remove(entity)
+    }
+
+    #[inline]
+    pub fn contains(&self, entity: EntityId) -> bool {
+        self.0.lock().handles.contains_key(&entity)
+    }
+
+    pub fn transform(&self, entity: EntityId) -> Option<PhysicsTransform> {
+        let g = self.0.lock();
+        let body = g.bodies.get(*g.handles.get(&entity)?)?;
+        let iso = body.position();
+        let t = iso.translation.vector;
+        let r = iso.rotation.coords;
+        Some(PhysicsTransform {
+            position: [t.x, t.y, t.z],
+            rotation: [r.x, r.y, r.z, r.w],
+        })
+    }
+
+    pub fn linvel(&self, entity: EntityId) -> Option<[f32; 3]> {
+        let g = self.0.lock();
+        let v = g.bodies.get(*g.handles.get(&entity)?)?.linvel();
+        Some([v.x, v.y, v.z])
+    }
+
+    /// Returns false if the entity has no body.
+    pub fn set_linvel(&self, entity: EntityId, linvel: [f32; 3]) -> bool {
+        let mut g = self.0.lock();
+        let Some(body) = g.body_mut(entity) else {
+            return false;
+        };
+        body.set_linvel(vector![linvel[0], linvel[1], linvel[2]], true);
+        true
+    }
+
+    /// Returns false if the entity has no body.
+    pub fn apply_impulse(&self, entity: EntityId, impulse: [f32; 3]) -> bool {
+        let mut g = self.0.lock();
+        let Some(body) = g.body_mut(entity) else {
+            return false;
+        };
+        body.apply_impulse(vector![impulse[0], impulse[1], impulse[2]], true);
+        true
+    }
+
+    /// Pose a kinematic body moves to during the next step; teleports other kinds.
+    pub fn set_kinematic_target(
+        &self,
+        entity: EntityId,
+        position: [f32; 3],
+        rotation: [f32; 4],
+    ) -> bool {
+        let mut g = self.0.lock();
+        let Some(body) = g.body_mut(entity) else {
+            return false;
+        };
+        let iso = isometry(position, rotation);
+        if body.is_kinematic() {
+            body.set_next_kinematic_position(iso);
+        } else {
+            body.set_position(iso, true);
+        }
+        true
+    }
+
+    #[inline]
+    pub fn gravity(&self) -> [f32; 3] {
+        let g = self.0.lock().gravity;
+        [g.x, g.y, g.z]
+    }
+
+    #[inline]
+    pub fn set_gravity(&self, gravity: [f32; 3]) {
+        self.0.lock().gravity = vector![gravity[0], gravity[1], gravity[2]];
+    }
+
+    #[inline]
+    pub fn debug_draw_enabled(&self) -> bool {
+        self.0.lock().debug_draw
+    }
+
+    #[inline]
+    pub fn set_debug_draw(&self, enabled: bool) {
+        self.0.lock().debug_draw = enabled;
+    }
+
+    #[inline]
+    pub fn stats(&self) -> PhysicsStats {
+        self.0.lock().stats()
+    }
+
+    #[inline]
+    pub(crate) fn step(&self, dt: f32) -> Vec<PhysicsEvent> {
+        self.0.lock().step(dt)
+    }
+
+    pub(crate) fn draw_debug(&self, dd: &DebugDraw) {
+        let g = self.0.lock();
+        if g.debug_draw {
+            g.draw_colliders(dd);
+        }
+    }
+
+    /// Drops every body; the world stays usable.
+    pub(crate) fn clear(&self) {
+        let mut g = self.0.lock();
+        let entities: Vec<EntityId> = g.handles.keys().copied().collect();
+        for e in entities {
+            g.remove(e);
+        }
+    }
+}
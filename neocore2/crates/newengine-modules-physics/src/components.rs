@@ -0,0 +1,181 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BodyKind {
+    /// Moved by forces, gravity and contacts.
+    Dynamic,
+    /// Never moves.
+    Fixed,
+    /// Moved by the game through `PhysicsApiRef::set_kinematic_target`; pushes dynamic bodies.
+    Kinematic,
+}
+
+/// Rigid body component.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RigidBody {
+    pub kind: BodyKind,
+    pub position: [f32; 3],
+    /// Quaternion `[x, y, z, w]`.
+    pub rotation: [f32; 4],
+    pub linvel: [f32; 3],
+    pub gravity_scale: f32,
+    /// Continuous collision detection for fast, small bodies.
+    pub ccd: bool,
+}
+
+impl RigidBody {
+    #[inline]
+    pub fn new(kind: BodyKind) -> Self {
+        Self {
+            kind,
+            position: [0.0; 3],
+            rotation: [0.0, 0.0, 0.0, 1.0],
+            linvel: [0.0; 3],
+            gravity_scale: 1.0,
+            ccd: false,
+        }
+    }
+
+    #[inline]
+    pub fn dynamic() -> Self {
+        Self::new(BodyKind::Dynamic)
+    }
+
+    #[inline]
+    pub fn fixed() -> Self {
+        Self::new(BodyKind::Fixed)
+    }
+
+    #[inline]
+    pub fn kinematic() -> Self {
+        Self::new(BodyKind::Kinematic)
+    }
+
+    #[inline]
+    pub fn with_position(mut self, position: [f32; 3]) -> Self {
+        self.position = position;
+        self
+    }
+
+    #[inline]
+    pub fn with_rotation(mut self, rotation: [f32; 4]) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    #[inline]
+    pub fn with_linvel(mut self, linvel: [f32; 3]) -> Self {
+        self.linvel = linvel;
+        self
+    }
+
+    #[inline]
+    pub fn with_gravity_scale(mut self, scale: f32) -> Self {
+        self.gravity_scale = scale;
+        self
+    }
+
+    #[inline]
+    pub fn with_ccd(mut self, ccd: bool) -> Self {
+        self.ccd = ccd;
+        self
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColliderShape {
+    Ball {
+        radius: f32,
+    },
+    Cuboid {
+        half_extents: [f32; 3],
+    },
+    /// Along the local Y axis; `half_height` excludes the caps.
+    Capsule {
+        half_height: f32,
+        radius: f32,
+    },
+}
+
+/// Collider component, attached to the entity's rigid body.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Collider {
+    pub shape: ColliderShape,
+    /// Offset from the body origin.
+    pub offset: [f32; 3],
+    pub friction: f32,
+    pub restitution: f32,
+    pub density: f32,
+    /// Reports overlaps but produces no contact forces.
+    pub sensor: bool,
+}
+
+impl Collider {
+    #[inline]
+    pub fn new(shape: ColliderShape) -> Self {
+        Self {
+            shape,
+            offset: [0.0; 3],
+            friction: 0.5,
+            restitution: 0.0,
+            density: 1.0,
+            sensor: false,
+        }
+    }
+
+    #[inline]
+    pub fn ball(radius: f32) -> Self {
+        Self::new(ColliderShape::Ball { radius })
+    }
+
+    #[inline]
+    pub fn cuboid(half_extents: [f32; 3]) -> Self {
+        Self::new(ColliderShape::Cuboid { half_extents })
+    }
+
+    #[inline]
+    pub fn capsule(half_height: f32, radius: f32) -> Self {
+        Self::new(ColliderShape::Capsule {
+            half_height,
+            radius,
+        })
+    }
+
+    #[inline]
+    pub fn with_offset(mut self, offset: [f32; 3]) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    #[inline]
+    pub fn with_friction(mut self, friction: f32) -> Self {
+        self.friction = friction;
+        self
+    }
+
+    #[inline]
+    pub fn with_restitution(mut self, restitution: f32) -> Self {
+        self.restitution = restitution;
+        self
+    }
+
+    #[inline]
+    pub fn with_density(mut self, density: f32) -> Self {
+        self.density = density;
+        self
+    }
+
+    #[inline]
+    pub fn with_sensor(mut self, sensor: bool) -> Self {
+        self.sensor = sensor;
+        self
+    }
+}
+
+/// World transform of a body after the latest step.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PhysicsTransform {
+    pub position: [f32; 3],
+    /// Quaternion `[x, y, z, w]`.
+    pub rotation: [f32; 4],
+}
@@ -0,0 +1,25 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use serde::Serialize;
+
+use crate::EntityId;
+
+/// Host topic carrying every [`PhysicsEvent`] as JSON, for plugins and scripts.
+pub const PHYSICS_COLLISION_TOPIC: &str = "physics.collision";
+
+/// Published on the engine `EventHub` after each fixed step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum PhysicsEvent {
+    CollisionStarted {
+        a: EntityId,
+        b: EntityId,
+        /// One of the colliders is a sensor: an overlap, not a contact.
+        sensor: bool,
+    },
+    CollisionStopped {
+        a: EntityId,
+        b: EntityId,
+        sensor: bool,
+    },
+}
@@ -0,0 +1,32 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+mod api;
+mod components;
+mod events;
+mod module;
+mod service;
+
+pub use api::{PhysicsApiRef, PhysicsStats};
+pub use components::{BodyKind, Collider, ColliderShape, PhysicsTransform, RigidBody};
+pub use events::{PhysicsEvent, PHYSICS_COLLISION_TOPIC};
+pub use module::{PhysicsConfig, PhysicsModule};
+pub use service::PHYSICS_SERVICE_ID;
+
+use newengine_core::{ApiProvide, ApiVersion};
+
+pub const PHYSICS_API_ID: &str = "physics.api";
+pub const PHYSICS_API_VERSION: ApiVersion = ApiVersion::new(0, 1, 0);
+pub const PHYSICS_API_PROVIDE: ApiProvide = ApiProvide::new(PHYSICS_API_ID, PHYSICS_API_VERSION);
+
+/// Key that ties a rigid body and its colliders to a game object.
+///
+/// The engine has no entity storage of its own yet, so the physics world keeps the
+/// `RigidBody`/`Collider` components per entity and callers pick the ids.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, serde::Serialize)]
+pub struct EntityId(pub u64);
+
+impl std::fmt::Display for EntityId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
@@ -0,0 +1,125 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use newengine_core::render::DebugDraw;
+use newengine_core::{ApiProvide, EngineError, EngineResult, Module, ModuleCtx};
+use newengine_plugin_api::{guarded_service, ServiceV1Dyn};
+
+use crate::api::PhysicsApiRef;
+use crate::events::PHYSICS_COLLISION_TOPIC;
+use crate::service::{PhysicsService, PHYSICS_SERVICE_ID};
+use crate::{PHYSICS_API_ID, PHYSICS_API_PROVIDE};
+
+#[derive(Debug, Clone)]
+pub struct PhysicsConfig {
+    pub gravity: [f32; 3],
+    /// Draw collider bounds through `DebugDraw`; toggled at runtime with `physics.debug`.
+    pub debug_draw: bool,
+}
+
+impl PhysicsConfig {
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            gravity: [0.0, -9.81, 0.0],
+            debug_draw: false,
+        }
+    }
+
+    #[inline]
+    pub fn with_gravity(mut self, gravity: [f32; 3]) -> Self {
+        self.gravity = gravity;
+        self
+    }
+
+    #[inline]
+    pub fn with_debug_draw(mut self, enabled: bool) -> Self {
+        self.debug_draw = enabled;
+        self
+    }
+}
+
+impl Default for PhysicsConfig {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Owns the rapier world and exposes it as `physics.api`.
+///
+/// The world advances once per `fixed_update` by the engine's fixed dt, so the simulation is
+/// frame-rate independent and also runs under the dedicated server profile. Collisions are
+/// published as [`crate::PhysicsEvent`] on the engine `EventHub` and as JSON on
+/// [`PHYSICS_COLLISION_TOPIC`].
+pub struct PhysicsModule {
+    api: PhysicsApiRef,
+    service_registered: bool,
+}
+
+impl PhysicsModule {
+    #[inline]
+    pub fn new(config: PhysicsConfig) -> Self {
+        Self {
+            api: PhysicsApiRef::new(config.gravity, config.debug_draw),
+            service_registered: false,
+        }
+    }
+
+    /// Handle for consumers living outside the engine.
+    #[inline]
+    pub fn api(&self) -> PhysicsApiRef {
+        self.api.clone()
+    }
+}
+
+impl<E: Send + 'static> Module<E> for PhysicsModule {
+    fn id(&self) -> &'static str {
+        "physics"
+    }
+
+    fn provides(&self) -> &'static [ApiProvide] {
+        &[PHYSICS_API_PROVIDE]
+    }
+
+    fn init(&mut self, ctx: &mut ModuleCtx<'_, E>) -> EngineResult<()> {
+        ctx.resources_mut()
+            .register_api(PHYSICS_API_ID, self.api.clone())?;
+
+        let dyn_svc: ServiceV1Dyn<'static> = guarded_service(PhysicsService::new(self.api.clone()));
+        newengine_core::register_service_v1(dyn_svc).map_err(EngineError::other)?;
+        self.service_registered = true;
+        Ok(())
+    }
+
+    fn fixed_update(&mut self, ctx: &mut ModuleCtx<'_, E>) -> EngineResult<()> {
+        let Some(dt) = ctx.frame().map(|f| f.fixed_dt) else {
+            return Ok(());
+        };
+
+        for ev in self.api.step(dt) {
+            ctx.events()
+                .publish_topic_json(PHYSICS_COLLISION_TOPIC, &ev)?;
+            ctx.events().publish(ev)?;
+        }
+        Ok(())
+    }
+
+    fn update(&mut self, ctx: &mut ModuleCtx<'_, E>) -> EngineResult<()> {
+        if let Some(dd) = ctx.resources().get::<DebugDraw>() {
+            self.api.draw_debug(dd);
+        }
+        Ok(())
+    }
+
+    fn shutdown(&mut self, ctx: &mut ModuleCtx<'_, E>) -> EngineResult<()> {
+        if self.service_registered {
+            newengine_core::unregister_service_v1(PHYSICS_SERVICE_ID);
+            self.service_registered = false;
+        }
+        let _ = ctx
+            .resources_mut()
+            .unregister_api::<PhysicsApiRef>(PHYSICS_API_ID);
+        self.api.clear();
+        Ok(())
+    }
+}
@@ -0,0 +1,123 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use abi_stable::std_types::{RResult, RString};
+use newengine_plugin_api::{Blob, CapabilityId, MethodName, ServiceV1};
+use serde::Serialize;
+use serde_json::json;
+
+use crate::api::{PhysicsApiRef, PhysicsStats};
+
+pub const PHYSICS_SERVICE_ID: &str = "physics";
+
+pub mod method {
+    pub const STATS_JSON: &str = "physics.stats_json";
+    pub const DEBUG: &str = "physics.debug";
+}
+
+#[derive(Debug, Serialize)]
+struct PhysicsStatsResp {
+    gravity: [f32; 3],
+    debug_draw: bool,
+    stats: PhysicsStats,
+}
+
+#[derive(Debug, Serialize)]
+struct PhysicsDebugResp {
+    ok: bool,
+    debug_draw: bool,
+    error: Option<String>,
+}
+
+pub(crate) struct PhysicsService {
+    api: PhysicsApiRef,
+}
+
+impl PhysicsService {
+    #[inline]
+    pub(crate) fn new(api: PhysicsApiRef) -> Self {
+        Self { api }
+    }
+
+    /// Payload: empty flips collider drawing, `on` / `off` sets it.
+    fn debug(&self, arg: &str) -> PhysicsDebugResp {
+        let enabled = match arg.trim().to_ascii_lowercase().as_str() {
+            "" => Ok(!self.api.debug_draw_enabled()),
+            "on" | "1" | "true" => Ok(true),
+            "off" | "0" | "false" => Ok(false),
+            other => Err(format!("expected on|off, got '{other}'")),
+        };
+
+        match enabled {
+            Ok(v) => {
+                self.api.set_debug_draw(v);
+                PhysicsDebugResp {
+                    ok: true,
+                    debug_draw: v,
+                    error: None,
+                }
+            }
+            Err(e) => PhysicsDebugResp {
+                ok: false,
+                debug_draw: self.api.debug_draw_enabled(),
+                error: Some(e),
+            },
+        }
+    }
+}
+
+impl ServiceV1 for PhysicsService {
+    fn id(&self) -> CapabilityId {
+        RString::from(PHYSICS_SERVICE_ID)
+    }
+
+    fn describe(&self) -> RString {
+        let d = json!({
+          "id": PHYSICS_SERVICE_ID,
+          "version": 1,
+          "methods": [
+            { "name": method::STATS_JSON, "payload": "empty", "returns": "json PhysicsStatsResp" },
+            { "name": method::DEBUG, "payload": "utf8 '[on|off]'", "returns": "json PhysicsDebugResp" }
+          ],
+          "console": {
+            "commands": [
+              {
+                "name": "physics.stats",
+                "help": "Print body/collider counts, contacts and last step time",
+                "kind": "service_call",
+                "service_id": PHYSICS_SERVICE_ID,
+                "method": method::STATS_JSON,
+                "payload": "empty"
+              },
+              {
+                "name": "physics.debug",
+                "help": "Draw collider bounds: physics.debug [on|off]",
+                "usage": "physics.debug [on|off]",
+                "kind": "service_call",
+                "service_id": PHYSICS_SERVICE_ID,
+                "method": method::DEBUG,
+                "payload": "raw"
+              }
+            ]
+          }
+        });
+
+        RString::from(d.to_string())
+    }
+
+    fn call(&self, method: MethodName, payload: Blob) -> RResult<Blob, RString> {
+        let m = method.to_string();
+        let arg = String::from_utf8_lossy(payload.as_slice());
+
+        let resp = match m.as_str() {
+            method::STATS_JSON => serde_json::to_vec(&PhysicsStatsResp {
+                gravity: self.api.gravity(),
+                debug_draw: self.api.debug_draw_enabled(),
+                stats: self.api.stats(),
+            }),
+            method::DEBUG => serde_json::to_vec(&self.debug(&arg)),
+            _ => return RResult::RErr(RString::from(format!("unknown method: {m}"))),
+        };
+
+        RResult::ROk(Blob::from(resp.unwrap_or_default()))
+    }
+}
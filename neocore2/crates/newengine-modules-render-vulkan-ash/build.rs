@@ -9,6 +9,23 @@ fn main() {
     println!("cargo:rerun-if-changed=shaders/text.frag");
     println!("cargo:rerun-if-changed=shaders/ui.vert");
     println!("cargo:rerun-if-changed=shaders/ui.frag");
+    println!("cargo:rerun-if-changed=shaders/mesh.vert");
+    println!("cargo:rerun-if-changed=shaders/mesh.frag");
+    println!("cargo:rerun-if-changed=shaders/terrain_splat.frag");
+    println!("cargo:rerun-if-changed=shaders/depth.frag");
+    println!("cargo:rerun-if-changed=shaders/skybox.vert");
+    println!("cargo:rerun-if-changed=shaders/skybox.frag");
+    println!("cargo:rerun-if-changed=shaders/object_id.frag");
+    println!("cargo:rerun-if-changed=shaders/lights.glsl");
+    println!("cargo:rerun-if-changed=shaders/debug_line.vert");
+    println!("cargo:rerun-if-changed=shaders/debug_line.frag");
+    println!("cargo:rerun-if-changed=shaders/post.vert");
+    println!("cargo:rerun-if-changed=shaders/post_blit.frag");
+    println!("cargo:rerun-if-changed=shaders/bloom_down.frag");
+    println!("cargo:rerun-if-changed=shaders/bloom_up.frag");
+    println!("cargo:rerun-if-changed=shaders/bloom_combine.frag");
+    println!("cargo:rerun-if-changed=shaders/vignette.frag");
+    println!("cargo:rerun-if-changed=shaders/fxaa.frag");
 
     let out_dir = PathBuf::from(env::var("OUT_DIR").expect("OUT_DIR"));
     let compiler = shaderc::Compiler::new().expect("shaderc compiler");
@@ -58,6 +75,106 @@ fn main() {
         &out_dir,
         "ui.frag.spv",
     );
+
+    // Debug draw line list
+    compile(
+        &compiler,
+        "shaders/debug_line.vert",
+        shaderc::ShaderKind::Vertex,
+        &out_dir,
+        "debug_line.vert.spv",
+    );
+    compile(
+        &compiler,
+        "shaders/debug_line.frag",
+        shaderc::ShaderKind::Fragment,
+        &out_dir,
+        "debug_line.frag.spv",
+    );
+
+    // Default material set: one vertex variant per deformation path.
+    for (defines, out_name) in [
+        (&[][..], "mesh.vert.spv"),
+        (&["SKINNING"][..], "mesh_skin.vert.spv"),
+        (&["MORPH"][..], "mesh_morph.vert.spv"),
+        (&["SKINNING", "MORPH"][..], "mesh_skin_morph.vert.spv"),
+    ] {
+        compile_with_defines(
+            &compiler,
+            "shaders/mesh.vert",
+            shaderc::ShaderKind::Vertex,
+            &out_dir,
+            out_name,
+            defines,
+        );
+    }
+    compile(
+        &compiler,
+        "shaders/mesh.frag",
+        shaderc::ShaderKind::Fragment,
+        &out_dir,
+        "mesh.frag.spv",
+    );
+    compile(
+        &compiler,
+        "shaders/terrain_splat.frag",
+        shaderc::ShaderKind::Fragment,
+        &out_dir,
+        "terrain_splat.frag.spv",
+    );
+    compile(
+        &compiler,
+        "shaders/depth.frag",
+        shaderc::ShaderKind::Fragment,
+        &out_dir,
+        "depth.frag.spv",
+    );
+    compile(
+        &compiler,
+        "shaders/skybox.vert",
+        shaderc::ShaderKind::Vertex,
+        &out_dir,
+        "skybox.vert.spv",
+    );
+    compile(
+        &compiler,
+        "shaders/skybox.frag",
+        shaderc::ShaderKind::Fragment,
+        &out_dir,
+        "skybox.frag.spv",
+    );
+    compile(
+        &compiler,
+        "shaders/object_id.frag",
+        shaderc::ShaderKind::Fragment,
+        &out_dir,
+        "object_id.frag.spv",
+    );
+
+    // Post-process chain: one fullscreen vertex shader, a fragment shader per pass.
+    compile(
+        &compiler,
+        "shaders/post.vert",
+        shaderc::ShaderKind::Vertex,
+        &out_dir,
+        "post.vert.spv",
+    );
+    for name in [
+        "post_blit",
+        "bloom_down",
+        "bloom_up",
+        "bloom_combine",
+        "vignette",
+        "fxaa",
+    ] {
+        compile(
+            &compiler,
+            &format!("shaders/{name}.frag"),
+            shaderc::ShaderKind::Fragment,
+            &out_dir,
+            &format!("{name}.frag.spv"),
+        );
+    }
 }
 
 fn compile(
@@ -66,12 +183,39 @@ fn compile(
     kind: shaderc::ShaderKind,
     out_dir: &Path,
     out_name: &str,
+) {
+    compile_with_defines(compiler, path, kind, out_dir, out_name, &[]);
+}
+
+fn compile_with_defines(
+    compiler: &shaderc::Compiler,
+    path: &str,
+    kind: shaderc::ShaderKind,
+    out_dir: &Path,
+    out_name: &str,
+    defines: &[&str],
 ) {
     let src = fs::read_to_string(path)
         .unwrap_or_else(|e| panic!("failed to read shader '{path}': {e}"));
 
     let mut opts = shaderc::CompileOptions::new().expect("shaderc options");
     opts.set_optimization_level(shaderc::OptimizationLevel::Performance);
+    for d in defines {
+        opts.add_macro_definition(d, None);
+    }
+    // `#include "x.glsl"` resolves next to the including shader.
+    opts.set_include_callback(|name, _kind, source, _depth| {
+        let resolved = Path::new(source)
+            .parent()
+            .unwrap_or_else(|| Path::new("."))
+            .join(name);
+        let content = fs::read_to_string(&resolved)
+            .map_err(|e| format!("failed to read include '{}': {e}", resolved.display()))?;
+        Ok(shaderc::ResolvedInclude {
+            resolved_name: resolved.to_string_lossy().into_owned(),
+            content,
+        })
+    });
 
     let compiled = compiler
         .compile_into_spirv(&src, kind, path, "main", Some(&opts))
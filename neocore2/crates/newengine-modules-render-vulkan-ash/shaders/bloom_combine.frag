@@ -0,0 +1,29 @@
+#version 450
+
+// Bloom: adds the upsampled first mip of the chain to the scene.
+
+layout(set = 0, binding = 0) uniform sampler2D u_scene;
+layout(set = 1, binding = 0) uniform sampler2D u_bloom;
+
+layout(push_constant) uniform Pc {
+    vec2 texel;
+    float intensity;
+    float _pad;
+} pc;
+
+layout(location = 0) in vec2 v_uv;
+
+layout(location = 0) out vec4 o_color;
+
+vec3 tap(float x, float y) {
+    return texture(u_bloom, v_uv + vec2(x, y) * pc.texel).rgb;
+}
+
+void main() {
+    vec3 corners = tap(-1.0, -1.0) + tap(1.0, -1.0) + tap(-1.0, 1.0) + tap(1.0, 1.0);
+    vec3 edges = tap(0.0, -1.0) + tap(-1.0, 0.0) + tap(1.0, 0.0) + tap(0.0, 1.0);
+    vec3 bloom = (tap(0.0, 0.0) * 4.0 + edges * 2.0 + corners) / 16.0;
+
+    vec4 scene = texture(u_scene, v_uv);
+    o_color = vec4(scene.rgb + bloom * pc.intensity, scene.a);
+}
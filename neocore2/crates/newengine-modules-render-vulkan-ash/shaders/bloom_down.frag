@@ -0,0 +1,42 @@
+#version 450
+
+// Bloom: 13-tap downsample to the next mip. The first one, from the scene, also keeps only
+// what is brighter than the threshold, with a soft knee half the threshold wide.
+
+layout(set = 0, binding = 0) uniform sampler2D u_src;
+
+layout(push_constant) uniform Pc {
+    vec2 texel;
+    float threshold;
+    float prefilter;
+} pc;
+
+layout(location = 0) in vec2 v_uv;
+
+layout(location = 0) out vec4 o_color;
+
+vec3 tap(float x, float y) {
+    return texture(u_src, v_uv + vec2(x, y) * pc.texel).rgb;
+}
+
+vec3 bright(vec3 c) {
+    float br = max(c.r, max(c.g, c.b));
+    float knee = pc.threshold * 0.5;
+    float soft = clamp(br - pc.threshold + knee, 0.0, 2.0 * knee);
+    soft = soft * soft / (4.0 * knee + 1e-4);
+    return c * (max(soft, br - pc.threshold) / max(br, 1e-4));
+}
+
+void main() {
+    vec3 outer = tap(-2.0, -2.0) + tap(2.0, -2.0) + tap(-2.0, 2.0) + tap(2.0, 2.0);
+    vec3 axis = tap(0.0, -2.0) + tap(-2.0, 0.0) + tap(2.0, 0.0) + tap(0.0, 2.0);
+    vec3 inner = tap(-1.0, -1.0) + tap(1.0, -1.0) + tap(-1.0, 1.0) + tap(1.0, 1.0);
+    vec3 c = tap(0.0, 0.0) * 0.125 + outer * 0.03125 + axis * 0.0625 + inner * 0.125;
+
+    // Half-float overflow and negative values from the scene would spread over the whole mip.
+    c = clamp(c, 0.0, 65000.0);
+    if (pc.prefilter > 0.5) {
+        c = bright(c);
+    }
+    o_color = vec4(c, 1.0);
+}
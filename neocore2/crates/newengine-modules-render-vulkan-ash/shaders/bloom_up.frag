@@ -0,0 +1,25 @@
+#version 450
+
+// Bloom: 3x3 tent upsample of a mip, blended additively onto the next larger one.
+
+layout(set = 0, binding = 0) uniform sampler2D u_src;
+
+layout(push_constant) uniform Pc {
+    vec2 texel;
+    vec2 _pad;
+} pc;
+
+layout(location = 0) in vec2 v_uv;
+
+layout(location = 0) out vec4 o_color;
+
+vec3 tap(float x, float y) {
+    return texture(u_src, v_uv + vec2(x, y) * pc.texel).rgb;
+}
+
+void main() {
+    vec3 corners = tap(-1.0, -1.0) + tap(1.0, -1.0) + tap(-1.0, 1.0) + tap(1.0, 1.0);
+    vec3 edges = tap(0.0, -1.0) + tap(-1.0, 0.0) + tap(1.0, 0.0) + tap(0.0, 1.0);
+    vec3 c = (tap(0.0, 0.0) * 4.0 + edges * 2.0 + corners) / 16.0;
+    o_color = vec4(c, 1.0);
+}
@@ -0,0 +1,15 @@
+#version 450
+
+layout(location = 0) in vec3 a_pos;
+layout(location = 1) in vec4 a_color;
+
+layout(push_constant) uniform Pc {
+    mat4 view_proj;
+} pc;
+
+layout(location = 0) out vec4 v_color;
+
+void main() {
+    gl_Position = pc.view_proj * vec4(a_pos, 1.0);
+    v_color = a_color;
+}
\ No newline at end of file
@@ -0,0 +1,7 @@
+#version 450
+
+// Default material set: depth-only fragment path (shadow maps). Depth comes from the
+// rasterizer; there is no color output.
+
+void main() {
+}
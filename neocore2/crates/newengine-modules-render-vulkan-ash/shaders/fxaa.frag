@@ -0,0 +1,50 @@
+#version 450
+
+// FXAA: blurs along the local edge direction where the luma contrast of the neighbourhood
+// says there is an aliased edge.
+
+layout(set = 0, binding = 0) uniform sampler2D u_src;
+
+layout(push_constant) uniform Pc {
+    vec2 texel;
+    vec2 _pad;
+} pc;
+
+layout(location = 0) in vec2 v_uv;
+
+layout(location = 0) out vec4 o_color;
+
+const float REDUCE_MIN = 1.0 / 128.0;
+const float REDUCE_MUL = 1.0 / 8.0;
+const float SPAN_MAX = 8.0;
+
+float luma(vec3 c) {
+    return dot(min(c, vec3(1.0)), vec3(0.299, 0.587, 0.114));
+}
+
+vec3 tap(vec2 offset) {
+    return texture(u_src, v_uv + offset).rgb;
+}
+
+void main() {
+    vec4 center = texture(u_src, v_uv);
+    float l_nw = luma(tap(vec2(-1.0, -1.0) * pc.texel));
+    float l_ne = luma(tap(vec2(1.0, -1.0) * pc.texel));
+    float l_sw = luma(tap(vec2(-1.0, 1.0) * pc.texel));
+    float l_se = luma(tap(vec2(1.0, 1.0) * pc.texel));
+    float l_m = luma(center.rgb);
+
+    float l_min = min(l_m, min(min(l_nw, l_ne), min(l_sw, l_se)));
+    float l_max = max(l_m, max(max(l_nw, l_ne), max(l_sw, l_se)));
+
+    vec2 dir = vec2(-((l_nw + l_ne) - (l_sw + l_se)), (l_nw + l_sw) - (l_ne + l_se));
+    float reduce = max((l_nw + l_ne + l_sw + l_se) * 0.25 * REDUCE_MUL, REDUCE_MIN);
+    float rcp_min = 1.0 / (min(abs(dir.x), abs(dir.y)) + reduce);
+    dir = clamp(dir * rcp_min, vec2(-SPAN_MAX), vec2(SPAN_MAX)) * pc.texel;
+
+    vec3 a = 0.5 * (tap(dir * (1.0 / 3.0 - 0.5)) + tap(dir * (2.0 / 3.0 - 0.5)));
+    vec3 b = a * 0.5 + 0.25 * (tap(dir * -0.5) + tap(dir * 0.5));
+    float l_b = luma(b);
+
+    o_color = vec4((l_b < l_min || l_b > l_max) ? a : b, center.a);
+}
@@ -0,0 +1,84 @@
+// Forward light list of the default material set (BindGroupLayoutDesc::lights), set 3, and
+// the cascaded shadow map of light 0. Included by the material fragment shaders (see build.rs).
+
+struct Light {
+    // xyz: direction (directional, w == 0) or world position (point, w = range).
+    vec4 position_range;
+    // rgb: linear color times intensity.
+    vec4 color_intensity;
+};
+
+layout(std430, set = 3, binding = 0) readonly buffer Lights {
+    uint light_count;
+    uint _pad0;
+    uint _pad1;
+    uint _pad2;
+    vec4 ambient;
+    Light lights[];
+} uLights;
+
+layout(set = 3, binding = 1) uniform Shadows {
+    mat4 cascade_view_proj[4];
+    // x: cascade count (0: light 0 casts no shadow), y: depth bias, zw: one map texel in uv.
+    vec4 params;
+} uShadows;
+
+// Cascade i fills the i-th of `count` equal cells along x.
+layout(set = 3, binding = 2) uniform texture2D uShadowMap;
+layout(set = 3, binding = 3) uniform sampler uShadowSampler;
+
+// Share of light 0 reaching world_pos: 3x3 PCF in the first cascade that covers it.
+float shadow_factor(vec3 world_pos) {
+    int count = int(uShadows.params.x);
+    for (int i = 0; i < count; ++i) {
+        vec4 clip = uShadows.cascade_view_proj[i] * vec4(world_pos, 1.0);
+        vec3 p = clip.xyz / clip.w;
+        vec2 uv = p.xy * 0.5 + 0.5;
+        if (any(lessThan(uv, vec2(0.0))) || any(greaterThan(uv, vec2(1.0))) || p.z > 1.0) {
+            continue;
+        }
+        vec2 texel = uShadows.params.zw;
+        // Taps stay inside the cell so the filter never reads a neighbouring cascade.
+        float lo = float(i) / float(count) + texel.x * 0.5;
+        float hi = float(i + 1) / float(count) - texel.x * 0.5;
+        vec2 center = vec2((float(i) + uv.x) / float(count), uv.y);
+        float lit = 0.0;
+        for (int y = -1; y <= 1; ++y) {
+            for (int x = -1; x <= 1; ++x) {
+                vec2 tap = center + vec2(x, y) * texel;
+                tap.x = clamp(tap.x, lo, hi);
+                float depth = texture(sampler2D(uShadowMap, uShadowSampler), tap).r;
+                lit += p.z - uShadows.params.y <= depth ? 1.0 : 0.0;
+            }
+        }
+        return lit / 9.0;
+    }
+    return 1.0;
+}
+
+vec3 shade_lights(vec3 n, vec3 world_pos) {
+    vec3 sum = uLights.ambient.rgb;
+    for (uint i = 0u; i < uLights.light_count; ++i) {
+        Light li = uLights.lights[i];
+        float range = li.position_range.w;
+        if (range <= 0.0) {
+            vec3 l = -normalize(li.position_range.xyz);
+            float lit = max(dot(n, l), 0.0);
+            if (i == 0u && lit > 0.0) {
+                lit *= shadow_factor(world_pos);
+            }
+            sum += li.color_intensity.rgb * lit;
+            continue;
+        }
+        vec3 to_light = li.position_range.xyz - world_pos;
+        float d = length(to_light);
+        if (d >= range) {
+            continue;
+        }
+        // Smooth falloff reaching zero at the range.
+        float falloff = 1.0 - d / range;
+        vec3 l = to_light / max(d, 1e-4);
+        sum += li.color_intensity.rgb * max(dot(n, l), 0.0) * falloff * falloff;
+    }
+    return sum;
+}
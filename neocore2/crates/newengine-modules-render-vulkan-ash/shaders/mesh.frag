@@ -0,0 +1,22 @@
+#version 450
+
+// Default material set: lit fragment path.
+
+layout(location = 0) in vec3 vNormal;
+layout(location = 1) in vec2 vUv;
+layout(location = 2) in vec3 vWorldPos;
+
+layout(set = 0, binding = 0) uniform Object {
+    mat4 view_proj;
+    mat4 model;
+    vec4 color;
+} uObj;
+
+layout(location = 0) out vec4 oColor;
+
+#include "lights.glsl"
+
+void main() {
+    vec3 n = normalize(vNormal);
+    oColor = vec4(uObj.color.rgb * shade_lights(n, vWorldPos), uObj.color.a);
+}
@@ -0,0 +1,83 @@
+#version 450
+
+// Default material set: vertex path.
+// Variants are compiled with SKINNING and/or MORPH defined (see build.rs).
+
+layout(location = 0) in vec3 aPos;
+layout(location = 1) in vec3 aNormal;
+layout(location = 2) in vec2 aUv;
+
+#ifdef SKINNING
+layout(location = 3) in uvec4 aJoints;
+layout(location = 4) in vec4 aWeights;
+#endif
+
+layout(set = 0, binding = 0) uniform Object {
+    mat4 view_proj;
+    mat4 model;
+    vec4 color;
+} uObj;
+
+#ifdef SKINNING
+layout(std430, set = 1, binding = 0) readonly buffer Bones {
+    mat4 bones[];
+};
+#endif
+
+#ifdef MORPH
+layout(std430, set = 1, binding = 1) readonly buffer MorphWeights {
+    uint target_count;
+    uint vertex_count;
+    uint _pad0;
+    uint _pad1;
+    float weights[];
+} uMorph;
+
+struct MorphDelta {
+    vec4 position;
+    vec4 normal;
+};
+
+layout(std430, set = 1, binding = 2) readonly buffer MorphDeltas {
+    MorphDelta deltas[];
+};
+#endif
+
+layout(location = 0) out vec3 vNormal;
+layout(location = 1) out vec2 vUv;
+layout(location = 2) out vec3 vWorldPos;
+
+void main() {
+    vec3 pos = aPos;
+    vec3 nrm = aNormal;
+
+#ifdef MORPH
+    // Morph first (bind pose space), then skin.
+    uint v = uint(gl_VertexIndex);
+    for (uint t = 0u; t < uMorph.target_count; ++t) {
+        float w = uMorph.weights[t];
+        if (w == 0.0) {
+            continue;
+        }
+        MorphDelta d = deltas[t * uMorph.vertex_count + v];
+        pos += w * d.position.xyz;
+        nrm += w * d.normal.xyz;
+    }
+#endif
+
+#ifdef SKINNING
+    mat4 skin =
+        aWeights.x * bones[aJoints.x] +
+        aWeights.y * bones[aJoints.y] +
+        aWeights.z * bones[aJoints.z] +
+        aWeights.w * bones[aJoints.w];
+    pos = (skin * vec4(pos, 1.0)).xyz;
+    nrm = mat3(skin) * nrm;
+#endif
+
+    vec4 world = uObj.model * vec4(pos, 1.0);
+    gl_Position = uObj.view_proj * world;
+    vWorldPos = world.xyz;
+    vNormal = mat3(uObj.model) * nrm;
+    vUv = aUv;
+}
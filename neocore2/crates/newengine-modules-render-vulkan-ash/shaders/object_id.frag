@@ -0,0 +1,14 @@
+#version 450
+
+// Default material set: object-id fragment path (editor picking). Writes the draw's id; the
+// ID pass depth test keeps the nearest.
+
+layout(set = 2, binding = 0) uniform ObjectId {
+    uvec4 id;
+} uId;
+
+layout(location = 0) out uint oId;
+
+void main() {
+    oId = uId.id.x;
+}
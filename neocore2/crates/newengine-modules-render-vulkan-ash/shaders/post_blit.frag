@@ -0,0 +1,13 @@
+#version 450
+
+// Post-process chain: copies its result into the swapchain pass.
+
+layout(set = 0, binding = 0) uniform sampler2D u_src;
+
+layout(location = 0) in vec2 v_uv;
+
+layout(location = 0) out vec4 o_color;
+
+void main() {
+    o_color = vec4(clamp(texture(u_src, v_uv).rgb, 0.0, 1.0), 1.0);
+}
@@ -0,0 +1,20 @@
+#version 450
+
+// Default material set: skybox fragment path.
+
+layout(set = 0, binding = 0) uniform Sky {
+    mat4 inv_view_proj;
+    vec4 tint;
+} uSky;
+
+layout(set = 1, binding = 0) uniform textureCube uCube;
+layout(set = 1, binding = 1) uniform sampler uCubeSampler;
+
+layout(location = 0) in vec3 vDir;
+
+layout(location = 0) out vec4 oColor;
+
+void main() {
+    vec3 sky = texture(samplerCube(uCube, uCubeSampler), normalize(vDir)).rgb;
+    oColor = vec4(sky * uSky.tint.rgb, 1.0);
+}
@@ -0,0 +1,19 @@
+#version 450
+
+// Default material set: skybox vertex path. One triangle covering the target; each corner
+// carries the world direction seen through it.
+
+layout(set = 0, binding = 0) uniform Sky {
+    mat4 inv_view_proj;
+    vec4 tint;
+} uSky;
+
+layout(location = 0) out vec3 vDir;
+
+void main() {
+    vec2 ndc = vec2((gl_VertexIndex << 1) & 2, gl_VertexIndex & 2) * 2.0 - 1.0;
+    // A point on the near plane; the camera sits at the origin of the matrix.
+    vec4 p = uSky.inv_view_proj * vec4(ndc, 0.0, 1.0);
+    vDir = p.xyz / p.w;
+    gl_Position = vec4(ndc, 1.0, 1.0);
+}
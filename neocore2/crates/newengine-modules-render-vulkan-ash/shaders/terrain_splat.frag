@@ -0,0 +1,53 @@
+#version 450
+
+// Default material set: terrain splat fragment path.
+// Blends four layers of a 2x2 atlas by the RGBA weights of the splat map.
+
+layout(location = 0) in vec3 vNormal;
+layout(location = 1) in vec2 vUv;
+layout(location = 2) in vec3 vWorldPos;
+
+layout(set = 0, binding = 0) uniform Object {
+    mat4 view_proj;
+    mat4 model;
+    vec4 color;
+    vec4 tiling;
+    vec4 layer_tint[4];
+} uObj;
+
+layout(set = 1, binding = 0) uniform texture2D uSplat;
+layout(set = 1, binding = 1) uniform sampler uSplatSampler;
+
+layout(set = 2, binding = 0) uniform texture2D uLayers;
+layout(set = 2, binding = 1) uniform sampler uLayersSampler;
+
+layout(location = 0) out vec4 oColor;
+
+#include "lights.glsl"
+
+vec3 layer(int i, vec2 uv, vec2 dx, vec2 dy) {
+    // Repeat inside the atlas cell by hand; the gradients come from the unwrapped uv so the
+    // wrap seam does not pick a blurry mip.
+    vec2 cell = vec2(float(i % 2), float(i / 2));
+    vec2 atlas_uv = (cell + fract(uv)) * 0.5;
+    vec3 texel = textureGrad(sampler2D(uLayers, uLayersSampler), atlas_uv, dx, dy).rgb;
+    return texel * uObj.layer_tint[i].rgb;
+}
+
+void main() {
+    vec4 w = texture(sampler2D(uSplat, uSplatSampler), vUv);
+    float sum = w.r + w.g + w.b + w.a;
+    w = sum > 0.0001 ? w / sum : vec4(1.0, 0.0, 0.0, 0.0);
+
+    vec2 uv = vUv * uObj.tiling.xy;
+    vec2 dx = dFdx(uv) * 0.5;
+    vec2 dy = dFdy(uv) * 0.5;
+    vec3 albedo =
+        w.r * layer(0, uv, dx, dy) +
+        w.g * layer(1, uv, dx, dy) +
+        w.b * layer(2, uv, dx, dy) +
+        w.a * layer(3, uv, dx, dy);
+
+    vec3 n = normalize(vNormal);
+    oColor = vec4(albedo * uObj.color.rgb * shade_lights(n, vWorldPos), uObj.color.a);
+}
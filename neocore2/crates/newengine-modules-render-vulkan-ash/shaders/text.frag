@@ -8,6 +8,9 @@ layout(location = 1) in vec4 v_color;
 layout(location = 0) out vec4 out_color;
 
 void main() {
-    float a = texture(u_font, v_uv).r;
+    // Signed distance field: 0.5 on the outline. Antialias over about one screen pixel.
+    float d = texture(u_font, v_uv).r;
+    float w = max(fwidth(d) * 0.5, 1e-4);
+    float a = smoothstep(0.5 - w, 0.5 + w, d);
     out_color = vec4(v_color.rgb, v_color.a * a);
 }
\ No newline at end of file
@@ -0,0 +1,24 @@
+#version 450
+
+// Vignette: darkens towards the corners, starting `radius` half screen heights from the
+// center and reaching full strength one half height further out.
+
+layout(set = 0, binding = 0) uniform sampler2D u_src;
+
+layout(push_constant) uniform Pc {
+    float intensity;
+    float radius;
+    float aspect;
+    float _pad;
+} pc;
+
+layout(location = 0) in vec2 v_uv;
+
+layout(location = 0) out vec4 o_color;
+
+void main() {
+    vec4 c = texture(u_src, v_uv);
+    float d = length((v_uv - 0.5) * vec2(pc.aspect, 1.0) * 2.0);
+    float v = smoothstep(pc.radius, pc.radius + 1.0, d);
+    o_color = vec4(c.rgb * (1.0 - pc.intensity * v), c.a);
+}
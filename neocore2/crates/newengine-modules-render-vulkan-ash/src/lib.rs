@@ -5,12 +5,14 @@ mod vulkan;
 use newengine_core::render::{RenderApiRef, RENDER_API_ID, RENDER_API_PROVIDE};
 use newengine_core::{EngineError, EngineResult, Module, ModuleCtx};
 use newengine_platform_winit::{WinitWindowHandles, WinitWindowInitSize};
+use std::path::PathBuf;
 
 use crate::error::VkRenderError;
 use crate::render_api::VulkanRenderApi;
 
 pub struct VulkanAshRenderModule {
     api: Option<RenderApiRef>,
+    pipeline_cache_dir: Option<PathBuf>,
 }
 
 impl Default for VulkanAshRenderModule {
@@ -24,6 +26,11 @@ impl<E: Send + 'static> Module<E> for VulkanAshRenderModule {
         "render.vulkan.ash"
     }
 
+    fn dependencies(&self) -> &'static [&'static str] {
+        // Needs `WinitWindowHandles`, installed by the platform once the window exists.
+        &["after:window"]
+    }
+
     fn provides(&self) -> &'static [newengine_core::ApiProvide] {
         &[RENDER_API_PROVIDE]
     }
@@ -42,7 +49,8 @@ impl<E: Send + 'static> Module<E> for VulkanAshRenderModule {
             (handles.display, handles.window, size.width, size.height)
         };
 
-        let renderer = unsafe { vulkan::VulkanRenderer::new(display, window, w, h) }
+        let cache_dir = self.pipeline_cache_dir.as_deref();
+        let renderer = unsafe { vulkan::VulkanRenderer::new(display, window, w, h, cache_dir) }
             .map_err(|e| EngineError::other(e.to_string()))?;
 
         let api = RenderApiRef::new(VulkanRenderApi::new(renderer, w, h));
@@ -71,6 +79,17 @@ impl<E: Send + 'static> Module<E> for VulkanAshRenderModule {
 impl VulkanAshRenderModule {
     #[inline]
     pub fn new() -> Self {
-        Self { api: None }
+        Self {
+            api: None,
+            pipeline_cache_dir: None,
+        }
+    }
+
+    /// Persists the Vulkan pipeline cache in `dir` (one file per GPU and driver), so pipelines
+    /// compiled in earlier runs are not rebuilt at startup.
+    #[inline]
+    pub fn with_pipeline_cache_dir(mut self, dir: Option<PathBuf>) -> Self {
+        self.pipeline_cache_dir = dir;
+        self
     }
 }
\ No newline at end of file
@@ -1,5 +1,8 @@
+use crate::vulkan::descriptors::{DescriptorAllocator, ShapeId};
+use crate::vulkan::materials::default_material_spirv;
+use crate::vulkan::memory::{Allocation, MemoryAllocator};
 use crate::vulkan::pipeline::create_shader_module;
-use crate::vulkan::util::immediate_submit;
+use crate::vulkan::textures::GpuTexture;
 use crate::vulkan::VulkanRenderer;
 
 use ash::vk;
@@ -7,6 +10,7 @@ use ash::vk;
 use newengine_core::render::*;
 use newengine_core::{EngineError, EngineResult};
 use newengine_ui::draw::UiDrawList;
+use raw_window_handle::{RawDisplayHandle, RawWindowHandle};
 
 use std::collections::HashMap;
 use std::ffi::CString;
@@ -14,7 +18,8 @@ use std::ffi::CString;
 #[derive(Clone, Copy)]
 struct VkBuffer {
     buffer: vk::Buffer,
-    memory: vk::DeviceMemory,
+    /// `None` for transient ring pages, which the renderer owns.
+    memory: Option<Allocation>,
     size: vk::DeviceSize,
     usage: vk::BufferUsageFlags,
     host_visible: bool,
@@ -31,19 +36,34 @@ struct VkShader {
 struct VkBgLayout {
     layout: vk::DescriptorSetLayout,
     bindings: Vec<BindingKind>,
+    shape: ShapeId,
 }
 
+/// Dynamic offsets a bind group holds at most; the Vulkan minimum of
+/// `maxDescriptorSetUniformBuffersDynamic` is 8 per pipeline layout.
+const MAX_DYNAMIC_OFFSETS: usize = 8;
+
 #[derive(Clone, Copy)]
 struct VkBindGroup {
     set: vk::DescriptorSet,
-    pool: vk::DescriptorPool,
-    layout: vk::DescriptorSetLayout,
+    shape: ShapeId,
+    /// `UniformBufferDynamic` bindings of the layout.
+    dynamic_count: u32,
+}
+
+/// Offsets for the dynamic bindings of a bound group.
+#[derive(Clone, Copy, Default)]
+struct DynamicOffsets {
+    values: [u32; MAX_DYNAMIC_OFFSETS],
+    count: u32,
 }
 
 #[derive(Clone, Copy)]
 struct VkPipeline {
     pipeline: vk::Pipeline,
     layout: vk::PipelineLayout,
+    /// Bind group layouts of `layout`; groups bound past them are left for later pipelines.
+    set_count: u32,
 }
 
 enum RecordedCmd {
@@ -55,6 +75,8 @@ enum RecordedCmd {
         first_set: u32,
         sets: [vk::DescriptorSet; 4],
         set_count: u32,
+        offsets: [u32; MAX_DYNAMIC_OFFSETS],
+        offset_count: u32,
     },
     BindVertexBuffer {
         first_binding: u32,
@@ -69,6 +91,23 @@ enum RecordedCmd {
     },
     Draw(DrawArgs),
     DrawIndexed(DrawIndexedArgs),
+    DrawIndirect {
+        buffer: vk::Buffer,
+        offset: vk::DeviceSize,
+        draw_count: u32,
+    },
+    DrawIndexedIndirect {
+        buffer: vk::Buffer,
+        offset: vk::DeviceSize,
+        draw_count: u32,
+    },
+}
+
+/// Commands between `begin_depth_pass` and `end_depth_pass`.
+struct DepthPass {
+    framebuffer: vk::Framebuffer,
+    extent: vk::Extent2D,
+    cmds: Vec<RecordedCmd>,
 }
 
 pub struct VulkanRenderApi {
@@ -78,36 +117,74 @@ pub struct VulkanRenderApi {
     next_id: u32,
 
     buffers: HashMap<BufferId, VkBuffer>,
+    memory: MemoryAllocator,
+    /// Ids handed out for transient ring pages; pages live as long as the renderer.
+    transient_ids: HashMap<vk::Buffer, BufferId>,
+    textures: HashMap<TextureId, GpuTexture>,
+    samplers: HashMap<SamplerId, vk::Sampler>,
     shaders: HashMap<ShaderId, VkShader>,
     bg_layouts: HashMap<BindGroupLayoutId, VkBgLayout>,
     bind_groups: HashMap<BindGroupId, VkBindGroup>,
+    descriptors: DescriptorAllocator,
     pipelines: HashMap<PipelineId, VkPipeline>,
 
+    default_shaders: HashMap<(DefaultMaterial, VertexDeformation), (ShaderId, ShaderId)>,
+
     current_pipeline: Option<PipelineId>,
     current_vertex: [Option<BufferSlice>; 4],
     current_index: Option<(BufferSlice, IndexFormat)>,
     current_bind_groups: [Option<BindGroupId>; 4],
+    current_dynamic_offsets: [DynamicOffsets; 4],
+    /// `minUniformBufferOffsetAlignment`.
+    uniform_align: u64,
 
     recorded: Vec<RecordedCmd>,
+    /// Replayed ahead of `recorded`, before the main render pass begins.
+    depth_passes: Vec<DepthPass>,
+    /// Commands go to the last of `depth_passes`.
+    in_depth_pass: bool,
+    /// Commands of the object-ID pass, replayed after the depth passes.
+    id_pass: Option<Vec<RecordedCmd>>,
+    /// Commands go to `id_pass`.
+    in_id_pass: bool,
 }
 
 impl VulkanRenderApi {
     #[inline]
     pub fn new(renderer: VulkanRenderer, width: u32, height: u32) -> Self {
+        let limits = unsafe {
+            renderer
+                .core
+                .instance
+                .get_physical_device_properties(renderer.core.physical_device)
+                .limits
+        };
         Self {
             renderer,
             target: Extent2D::new(width, height),
             next_id: 1,
             buffers: HashMap::new(),
+            memory: MemoryAllocator::default(),
+            transient_ids: HashMap::new(),
+            textures: HashMap::new(),
+            samplers: HashMap::new(),
             shaders: HashMap::new(),
             bg_layouts: HashMap::new(),
             bind_groups: HashMap::new(),
+            descriptors: DescriptorAllocator::default(),
             pipelines: HashMap::new(),
+            default_shaders: HashMap::new(),
             current_pipeline: None,
             current_vertex: [None, None, None, None],
             current_index: None,
             current_bind_groups: [None, None, None, None],
+            current_dynamic_offsets: [DynamicOffsets::default(); 4],
+            uniform_align: limits.min_uniform_buffer_offset_alignment.max(1),
             recorded: Vec::new(),
+            depth_passes: Vec::new(),
+            in_depth_pass: false,
+            id_pass: None,
+            in_id_pass: false,
         }
     }
 
@@ -137,6 +214,34 @@ impl VulkanRenderApi {
         }
     }
 
+    #[inline]
+    fn map_texture_format(f: TextureFormat) -> Option<vk::Format> {
+        match f {
+            TextureFormat::Rgba8Unorm => Some(vk::Format::R8G8B8A8_UNORM),
+            TextureFormat::Bgra8Unorm => Some(vk::Format::B8G8R8A8_UNORM),
+            TextureFormat::Rgba16Float => Some(vk::Format::R16G16B16A16_SFLOAT),
+            TextureFormat::R32Uint => Some(vk::Format::R32_UINT),
+            TextureFormat::Depth24Stencil8 | TextureFormat::Depth32Float => None,
+        }
+    }
+
+    #[inline]
+    fn map_filter(f: FilterMode) -> vk::Filter {
+        match f {
+            FilterMode::Nearest => vk::Filter::NEAREST,
+            FilterMode::Linear => vk::Filter::LINEAR,
+        }
+    }
+
+    #[inline]
+    fn map_address_mode(m: AddressMode) -> vk::SamplerAddressMode {
+        match m {
+            AddressMode::ClampToEdge => vk::SamplerAddressMode::CLAMP_TO_EDGE,
+            AddressMode::Repeat => vk::SamplerAddressMode::REPEAT,
+            AddressMode::MirroredRepeat => vk::SamplerAddressMode::MIRRORED_REPEAT,
+        }
+    }
+
     #[inline]
     fn map_topology(t: PrimitiveTopology) -> vk::PrimitiveTopology {
         match t {
@@ -162,9 +267,55 @@ impl VulkanRenderApi {
             VertexFormat::Float32x3 => vk::Format::R32G32B32_SFLOAT,
             VertexFormat::Float32x4 => vk::Format::R32G32B32A32_SFLOAT,
             VertexFormat::Unorm8x4 => vk::Format::R8G8B8A8_UNORM,
+            VertexFormat::Uint8x4 => vk::Format::R8G8B8A8_UINT,
+            VertexFormat::Uint16x4 => vk::Format::R16G16B16A16_UINT,
+        }
+    }
+
+    #[inline]
+    fn map_cull_mode(c: CullMode) -> vk::CullModeFlags {
+        match c {
+            CullMode::None => vk::CullModeFlags::NONE,
+            CullMode::Front => vk::CullModeFlags::FRONT,
+            CullMode::Back => vk::CullModeFlags::BACK,
         }
     }
 
+    #[inline]
+    fn map_front_face(f: FrontFace) -> vk::FrontFace {
+        match f {
+            FrontFace::CounterClockwise => vk::FrontFace::COUNTER_CLOCKWISE,
+            FrontFace::Clockwise => vk::FrontFace::CLOCKWISE,
+        }
+    }
+
+    #[inline]
+    fn map_polygon_mode(m: PolygonMode) -> vk::PolygonMode {
+        match m {
+            PolygonMode::Fill => vk::PolygonMode::FILL,
+            PolygonMode::Line => vk::PolygonMode::LINE,
+        }
+    }
+
+    /// Blend factors for `b`; the color write mask is left to the caller.
+    fn map_blend(b: BlendState) -> vk::PipelineColorBlendAttachmentState {
+        use vk::BlendFactor as F;
+        let (src_color, dst_color, src_alpha, dst_alpha) = match b {
+            BlendState::Opaque => return vk::PipelineColorBlendAttachmentState::default().blend_enable(false),
+            BlendState::Alpha => (F::SRC_ALPHA, F::ONE_MINUS_SRC_ALPHA, F::ONE, F::ONE_MINUS_SRC_ALPHA),
+            BlendState::Additive => (F::SRC_ALPHA, F::ONE, F::ONE, F::ONE),
+            BlendState::Premultiplied => (F::ONE, F::ONE_MINUS_SRC_ALPHA, F::ONE, F::ONE_MINUS_SRC_ALPHA),
+        };
+        vk::PipelineColorBlendAttachmentState::default()
+            .blend_enable(true)
+            .src_color_blend_factor(src_color)
+            .dst_color_blend_factor(dst_color)
+            .color_blend_op(vk::BlendOp::ADD)
+            .src_alpha_blend_factor(src_alpha)
+            .dst_alpha_blend_factor(dst_alpha)
+            .alpha_blend_op(vk::BlendOp::ADD)
+    }
+
     fn buffer_usage_flags(u: BufferUsage) -> vk::BufferUsageFlags {
         match u {
             BufferUsage::Vertex => vk::BufferUsageFlags::VERTEX_BUFFER | vk::BufferUsageFlags::TRANSFER_DST,
@@ -172,6 +323,11 @@ impl VulkanRenderApi {
             BufferUsage::Uniform => vk::BufferUsageFlags::UNIFORM_BUFFER | vk::BufferUsageFlags::TRANSFER_DST,
             BufferUsage::Storage => vk::BufferUsageFlags::STORAGE_BUFFER | vk::BufferUsageFlags::TRANSFER_DST,
             BufferUsage::Staging => vk::BufferUsageFlags::TRANSFER_SRC,
+            BufferUsage::Indirect => {
+                vk::BufferUsageFlags::INDIRECT_BUFFER
+                    | vk::BufferUsageFlags::STORAGE_BUFFER
+                    | vk::BufferUsageFlags::TRANSFER_DST
+            }
         }
     }
 
@@ -200,17 +356,25 @@ impl VulkanRenderApi {
     }
 
     unsafe fn create_vk_buffer(
-        &self,
+        &mut self,
         size: vk::DeviceSize,
         usage: vk::BufferUsageFlags,
         props: vk::MemoryPropertyFlags,
     ) -> EngineResult<VkBuffer> {
         let device = &self.renderer.core.device;
+        let core = &self.renderer.core;
 
-        let info = vk::BufferCreateInfo::default()
+        // Device-local buffers are filled by the transfer queue and read by the graphics queue.
+        let families = [core.queue_family_index, core.transfer_queue_family_index];
+        let mut info = vk::BufferCreateInfo::default()
             .size(size)
             .usage(usage)
             .sharing_mode(vk::SharingMode::EXCLUSIVE);
+        if core.has_transfer_queue() && !props.contains(vk::MemoryPropertyFlags::HOST_VISIBLE) {
+            info = info
+                .sharing_mode(vk::SharingMode::CONCURRENT)
+                .queue_family_indices(&families);
+        }
 
         let buffer = device
             .create_buffer(&info, None)
@@ -226,27 +390,40 @@ impl VulkanRenderApi {
         )
             .ok_or_else(|| EngineError::other("No compatible Vulkan memory type"))?;
 
-        let alloc = vk::MemoryAllocateInfo::default()
-            .allocation_size(req.size)
-            .memory_type_index(mem_type);
-
-        let memory = device
-            .allocate_memory(&alloc, None)
-            .map_err(|e| EngineError::other(e.to_string()))?;
+        let memory = match self.memory.allocate(device, mem_type, req) {
+            Ok(m) => m,
+            Err(e) => {
+                device.destroy_buffer(buffer, None);
+                return Err(EngineError::other(e.to_string()));
+            }
+        };
 
-        device
-            .bind_buffer_memory(buffer, memory, 0)
-            .map_err(|e| EngineError::other(e.to_string()))?;
+        if let Err(e) = device.bind_buffer_memory(buffer, memory.memory, memory.offset) {
+            device.destroy_buffer(buffer, None);
+            self.memory.free(device, memory);
+            return Err(EngineError::other(e.to_string()));
+        }
 
         Ok(VkBuffer {
             buffer,
-            memory,
+            memory: Some(memory),
             size,
             usage,
             host_visible: props.contains(vk::MemoryPropertyFlags::HOST_VISIBLE),
         })
     }
 
+    unsafe fn destroy_vk_buffer(&mut self, b: VkBuffer) {
+        let Some(memory) = b.memory else {
+            return;
+        };
+        let device = &self.renderer.core.device;
+        if b.buffer != vk::Buffer::null() {
+            device.destroy_buffer(b.buffer, None);
+        }
+        self.memory.free(device, memory);
+    }
+
     unsafe fn current_cmd(&self) -> Option<vk::CommandBuffer> {
         if !self.renderer.debug.in_frame {
             return None;
@@ -255,23 +432,197 @@ impl VulkanRenderApi {
         Some(self.renderer.frames.command_buffers[idx])
     }
 
+    /// Appends to the open depth or object-ID pass, or to the main pass.
+    fn record(&mut self, cmd: RecordedCmd) {
+        match (self.depth_passes.last_mut(), self.id_pass.as_mut()) {
+            (Some(pass), _) if self.in_depth_pass => pass.cmds.push(cmd),
+            (_, Some(cmds)) if self.in_id_pass => cmds.push(cmd),
+            _ => self.recorded.push(cmd),
+        }
+    }
+
+    /// Unbinds pipeline, bind groups and buffers at depth and object-ID pass boundaries.
+    fn reset_bindings(&mut self) {
+        self.current_pipeline = None;
+        self.current_vertex = [None, None, None, None];
+        self.current_index = None;
+        self.current_bind_groups = [None, None, None, None];
+        self.current_dynamic_offsets = [DynamicOffsets::default(); 4];
+    }
+
+    /// Records the bind groups and vertex buffers bound for the next draw.
+    fn bind_draw_state(&mut self, op: &str) -> EngineResult<()> {
+        let Some(pipeline_id) = self.current_pipeline else {
+            return self.err(format!("{op}: no pipeline bound"));
+        };
+        let p = *self
+            .pipelines
+            .get(&pipeline_id)
+            .ok_or_else(|| EngineError::other(format!("{op}: invalid current pipeline")))?;
+
+        let mut sets = [vk::DescriptorSet::null(); 4];
+        let mut set_count = 0u32;
+        let mut offsets = [0u32; MAX_DYNAMIC_OFFSETS];
+        let mut offset_count = 0usize;
+        for (i, bg_id) in self.current_bind_groups.iter().enumerate().take(p.set_count as usize) {
+            if let Some(bg_id) = bg_id {
+                let bg = *self
+                    .bind_groups
+                    .get(bg_id)
+                    .ok_or_else(|| EngineError::other(format!("{op}: invalid bind group")))?;
+                sets[i] = bg.set;
+                set_count = (i as u32) + 1;
+
+                // Offsets of all bound sets, in set order.
+                let dyn_offsets = &self.current_dynamic_offsets[i];
+                let n = dyn_offsets.count as usize;
+                if offset_count + n > MAX_DYNAMIC_OFFSETS {
+                    return self.err(format!("{op}: too many dynamic offsets (max {MAX_DYNAMIC_OFFSETS})"));
+                }
+                offsets[offset_count..offset_count + n].copy_from_slice(&dyn_offsets.values[..n]);
+                offset_count += n;
+            }
+        }
+        if set_count > 0 {
+            self.record(RecordedCmd::BindDescriptorSets {
+                layout: p.layout,
+                first_set: 0,
+                sets,
+                set_count,
+                offsets,
+                offset_count: offset_count as u32,
+            });
+        }
+
+        let mut bufs = [vk::Buffer::null(); 4];
+        let mut offs = [0u64; 4];
+        let mut count = 0u32;
+        for (i, s) in self.current_vertex.iter().enumerate() {
+            if let Some(s) = s {
+                let b = *self
+                    .buffers
+                    .get(&s.buffer)
+                    .ok_or_else(|| EngineError::other(format!("{op}: invalid vertex buffer")))?;
+                bufs[i] = b.buffer;
+                offs[i] = s.offset;
+                count = (i as u32) + 1;
+            }
+        }
+        if count > 0 {
+            self.record(RecordedCmd::BindVertexBuffer { first_binding: 0, buffers: bufs, offsets: offs, count });
+        }
+        Ok(())
+    }
+
+    fn bind_index_state(&mut self, op: &str) -> EngineResult<()> {
+        let Some((idx_slice, fmt)) = self.current_index else {
+            return self.err(format!("{op}: no index buffer bound"));
+        };
+        let ib = *self
+            .buffers
+            .get(&idx_slice.buffer)
+            .ok_or_else(|| EngineError::other(format!("{op}: invalid index buffer")))?;
+
+        self.record(RecordedCmd::BindIndexBuffer {
+            buffer: ib.buffer,
+            offset: idx_slice.offset as vk::DeviceSize,
+            index_type: Self::map_index_format(fmt),
+        });
+        Ok(())
+    }
+
+    fn indirect_buffer(&self, op: &str, args: BufferSlice) -> EngineResult<(vk::Buffer, vk::DeviceSize)> {
+        let b = self
+            .buffers
+            .get(&args.buffer)
+            .ok_or_else(|| EngineError::other(format!("{op}: invalid buffer")))?;
+        if !b.usage.contains(vk::BufferUsageFlags::INDIRECT_BUFFER) {
+            return self.err(format!("{op}: buffer was not created with BufferUsage::Indirect"));
+        }
+        // Vulkan requires 4-byte aligned command offsets.
+        if args.offset % 4 != 0 {
+            return self.err(format!("{op}: offset must be a multiple of 4"));
+        }
+        Ok((b.buffer, args.offset))
+    }
+
+    /// Replays the depth passes and the object-ID pass, then begins the scene render pass and
+    /// replays its commands.
     unsafe fn flush_recorded(&mut self) -> EngineResult<()> {
         let Some(cmd) = self.current_cmd() else { return Ok(()); };
         let device = &self.renderer.core.device;
+        // Without the feature, a multi-draw is split into single draws.
+        let multi_draw = self.renderer.core.multi_draw_indirect;
+
+        for pass in self.depth_passes.drain(..) {
+            let area = vk::Rect2D { offset: vk::Offset2D { x: 0, y: 0 }, extent: pass.extent };
+            let clear = vk::ClearValue {
+                depth_stencil: vk::ClearDepthStencilValue { depth: 1.0, stencil: 0 },
+            };
+            let rp_begin = vk::RenderPassBeginInfo::default()
+                .render_pass(self.renderer.pipelines.depth_render_pass)
+                .framebuffer(pass.framebuffer)
+                .render_area(area)
+                .clear_values(std::slice::from_ref(&clear));
+            device.cmd_begin_render_pass(cmd, &rp_begin, vk::SubpassContents::INLINE);
+
+            let viewport = vk::Viewport {
+                x: 0.0,
+                y: 0.0,
+                width: pass.extent.width as f32,
+                height: pass.extent.height as f32,
+                min_depth: 0.0,
+                max_depth: 1.0,
+            };
+            device.cmd_set_viewport(cmd, 0, std::slice::from_ref(&viewport));
+            device.cmd_set_scissor(cmd, 0, std::slice::from_ref(&area));
+            Self::replay(device, cmd, multi_draw, pass.cmds);
+            device.cmd_end_render_pass(cmd);
+        }
+
+        if let Some(cmds) = self.id_pass.take() {
+            let extent = self.renderer.id_pass_begin(cmd).map_err(|e| EngineError::other(e.to_string()))?;
+            let area = vk::Rect2D { offset: vk::Offset2D { x: 0, y: 0 }, extent };
+            let viewport = vk::Viewport {
+                x: 0.0,
+                y: 0.0,
+                width: extent.width as f32,
+                height: extent.height as f32,
+                min_depth: 0.0,
+                max_depth: 1.0,
+            };
+            let device = &self.renderer.core.device;
+            device.cmd_set_viewport(cmd, 0, std::slice::from_ref(&viewport));
+            device.cmd_set_scissor(cmd, 0, std::slice::from_ref(&area));
+            Self::replay(device, cmd, multi_draw, cmds);
+            self.renderer.id_pass_end(cmd).map_err(|e| EngineError::other(e.to_string()))?;
+        }
+
+        self.renderer.begin_main_pass();
+        let device = &self.renderer.core.device;
+        Self::replay(device, cmd, multi_draw, self.recorded.drain(..));
+        Ok(())
+    }
 
-        for c in self.recorded.drain(..) {
+    unsafe fn replay(
+        device: &ash::Device,
+        cmd: vk::CommandBuffer,
+        multi_draw: bool,
+        cmds: impl IntoIterator<Item = RecordedCmd>,
+    ) {
+        for c in cmds {
             match c {
                 RecordedCmd::SetViewport(vp) => device.cmd_set_viewport(cmd, 0, std::slice::from_ref(&vp)),
                 RecordedCmd::SetScissor(sc) => device.cmd_set_scissor(cmd, 0, std::slice::from_ref(&sc)),
                 RecordedCmd::BindPipeline(p) => device.cmd_bind_pipeline(cmd, vk::PipelineBindPoint::GRAPHICS, p),
-                RecordedCmd::BindDescriptorSets { layout, first_set, sets, set_count } => {
+                RecordedCmd::BindDescriptorSets { layout, first_set, sets, set_count, offsets, offset_count } => {
                     device.cmd_bind_descriptor_sets(
                         cmd,
                         vk::PipelineBindPoint::GRAPHICS,
                         layout,
                         first_set,
                         &sets[..set_count as usize],
-                        &[],
+                        &offsets[..offset_count as usize],
                     );
                 }
                 RecordedCmd::BindVertexBuffer { first_binding, buffers, offsets, count } => {
@@ -294,10 +645,28 @@ impl VulkanRenderApi {
                     a.vertex_offset,
                     a.first_instance,
                 ),
+                RecordedCmd::DrawIndirect { buffer, offset, draw_count } => {
+                    let stride = DrawIndirectCommand::SIZE;
+                    if multi_draw {
+                        device.cmd_draw_indirect(cmd, buffer, offset, draw_count, stride as u32);
+                    } else {
+                        for i in 0..draw_count as u64 {
+                            device.cmd_draw_indirect(cmd, buffer, offset + i * stride, 1, stride as u32);
+                        }
+                    }
+                }
+                RecordedCmd::DrawIndexedIndirect { buffer, offset, draw_count } => {
+                    let stride = DrawIndexedIndirectCommand::SIZE;
+                    if multi_draw {
+                        device.cmd_draw_indexed_indirect(cmd, buffer, offset, draw_count, stride as u32);
+                    } else {
+                        for i in 0..draw_count as u64 {
+                            device.cmd_draw_indexed_indirect(cmd, buffer, offset + i * stride, 1, stride as u32);
+                        }
+                    }
+                }
             }
         }
-
-        Ok(())
     }
 }
 
@@ -315,12 +684,9 @@ impl Drop for VulkanRenderApi {
                 }
             }
 
-            for (_, bg) in self.bind_groups.drain() {
-                if bg.pool != vk::DescriptorPool::null() {
-                    device.destroy_descriptor_pool(bg.pool, None);
-                }
-                let _ = bg.layout;
-            }
+            // Sets go away with their pools.
+            self.bind_groups.clear();
+            self.descriptors.destroy(device);
 
             for (_, l) in self.bg_layouts.drain() {
                 if l.layout != vk::DescriptorSetLayout::null() {
@@ -328,6 +694,19 @@ impl Drop for VulkanRenderApi {
                 }
             }
 
+            for (_, s) in self.samplers.drain() {
+                device.destroy_sampler(s, None);
+            }
+
+            for (_, t) in self.textures.drain() {
+                if t.is_depth() {
+                    device.destroy_framebuffer(t.framebuffer, None);
+                }
+                device.destroy_image_view(t.view, None);
+                device.destroy_image(t.image, None);
+                device.free_memory(t.memory, None);
+            }
+
             for (_, s) in self.shaders.drain() {
                 if s.module != vk::ShaderModule::null() {
                     device.destroy_shader_module(s.module, None);
@@ -335,14 +714,12 @@ impl Drop for VulkanRenderApi {
             }
 
             for (_, b) in self.buffers.drain() {
-                if b.buffer != vk::Buffer::null() {
+                if b.buffer != vk::Buffer::null() && b.memory.is_some() {
                     device.destroy_buffer(b.buffer, None);
                 }
-                if b.memory != vk::DeviceMemory::null() {
-                    device.free_memory(b.memory, None);
-                }
                 let _ = b.size;
             }
+            self.memory.destroy(device);
         }
     }
 }
@@ -350,10 +727,12 @@ impl Drop for VulkanRenderApi {
 impl RenderApi for VulkanRenderApi {
     fn begin_frame(&mut self, desc: BeginFrameDesc) -> EngineResult<()> {
         self.recorded.clear();
-        self.current_pipeline = None;
-        self.current_vertex = [None, None, None, None];
-        self.current_index = None;
-        self.current_bind_groups = [None, None, None, None];
+        self.depth_passes.clear();
+        self.in_depth_pass = false;
+        self.id_pass = None;
+        self.in_id_pass = false;
+        self.reset_bindings();
+        self.descriptors.advance_frame();
 
         self.renderer.begin_frame(desc.clear_color).map_err(|e| EngineError::other(e.to_string()))
     }
@@ -364,7 +743,18 @@ impl RenderApi for VulkanRenderApi {
     }
 
     fn end_frame(&mut self) -> EngineResult<()> {
+        if std::mem::take(&mut self.in_depth_pass) {
+            log::warn!("end_frame: depth pass was not ended; closing it");
+        }
+        if std::mem::take(&mut self.in_id_pass) {
+            log::warn!("end_frame: object-ID pass was not ended; closing it");
+        }
         unsafe { self.flush_recorded()?; }
+
+        let batch = DebugDraw::global().flush();
+        if !batch.is_empty() {
+            self.renderer.set_debug_draw(batch);
+        }
         self.renderer.end_frame().map_err(|e| EngineError::other(e.to_string()))
     }
 
@@ -379,22 +769,21 @@ impl RenderApi for VulkanRenderApi {
             let usage = Self::buffer_usage_flags(desc.usage);
             let props = Self::memory_props(desc.memory);
             let b = self.create_vk_buffer(desc.size as vk::DeviceSize, usage, props)?;
+            if let Some(label) = desc.label {
+                self.renderer.set_object_name(b.buffer, label);
+            }
             self.buffers.insert(id, b);
         }
         Ok(id)
     }
 
     fn destroy_buffer(&mut self, id: BufferId) {
+        if self.buffers.get(&id).is_some_and(|b| b.memory.is_none()) {
+            log::warn!("destroy_buffer: {id:?} is a transient ring page; ignored");
+            return;
+        }
         if let Some(b) = self.buffers.remove(&id) {
-            unsafe {
-                let device = &self.renderer.core.device;
-                if b.buffer != vk::Buffer::null() {
-                    device.destroy_buffer(b.buffer, None);
-                }
-                if b.memory != vk::DeviceMemory::null() {
-                    device.free_memory(b.memory, None);
-                }
-            }
+            unsafe { self.destroy_vk_buffer(b) };
         }
     }
 
@@ -408,118 +797,173 @@ impl RenderApi for VulkanRenderApi {
             return Err(EngineError::other("write_buffer: out of bounds"));
         }
 
-        unsafe {
-            let device = &self.renderer.core.device;
+        let Some(memory) = b.memory else {
+            return self
+                .renderer
+                .write_transient(b.buffer, offset as vk::DeviceSize, data)
+                .map_err(|e| EngineError::other(format!("write_buffer: {e}")));
+        };
 
+        unsafe {
             if b.host_visible {
+                let device = &self.renderer.core.device;
                 let ptr = device
                     .map_memory(
-                        b.memory,
-                        offset as vk::DeviceSize,
+                        memory.memory,
+                        memory.offset + offset as vk::DeviceSize,
                         data.len() as vk::DeviceSize,
                         vk::MemoryMapFlags::empty(),
                     )
                     .map_err(|e| EngineError::other(e.to_string()))? as *mut u8;
 
                 std::ptr::copy_nonoverlapping(data.as_ptr(), ptr, data.len());
-                device.unmap_memory(b.memory);
+                device.unmap_memory(memory.memory);
                 return Ok(());
             }
 
-            let staging = self.create_vk_buffer(
-                data.len() as vk::DeviceSize,
-                vk::BufferUsageFlags::TRANSFER_SRC,
-                vk::MemoryPropertyFlags::HOST_VISIBLE | vk::MemoryPropertyFlags::HOST_COHERENT,
-            )?;
-
-            let ptr = device
-                .map_memory(
-                    staging.memory,
-                    0,
-                    data.len() as vk::DeviceSize,
-                    vk::MemoryMapFlags::empty(),
+            let (dst_stage, dst_access) = if b.usage.intersects(
+                vk::BufferUsageFlags::VERTEX_BUFFER | vk::BufferUsageFlags::INDEX_BUFFER,
+            ) {
+                (
+                    vk::PipelineStageFlags::VERTEX_INPUT,
+                    vk::AccessFlags::VERTEX_ATTRIBUTE_READ | vk::AccessFlags::INDEX_READ,
                 )
-                .map_err(|e| EngineError::other(e.to_string()))? as *mut u8;
-
-            std::ptr::copy_nonoverlapping(data.as_ptr(), ptr, data.len());
-            device.unmap_memory(staging.memory);
-
-            immediate_submit(
-                device,
-                self.renderer.frames.upload_command_pool,
-                self.renderer.core.queue,
-                |cmd| {
-                    let region = vk::BufferCopy::default()
-                        .src_offset(0)
-                        .dst_offset(offset as vk::DeviceSize)
-                        .size(data.len() as vk::DeviceSize);
-
-                    device.cmd_copy_buffer(cmd, staging.buffer, b.buffer, std::slice::from_ref(&region));
-
-                    let (dst_stage, dst_access) = if b.usage.intersects(
-                        vk::BufferUsageFlags::VERTEX_BUFFER | vk::BufferUsageFlags::INDEX_BUFFER,
-                    ) {
-                        (
-                            vk::PipelineStageFlags::VERTEX_INPUT,
-                            vk::AccessFlags::VERTEX_ATTRIBUTE_READ | vk::AccessFlags::INDEX_READ,
-                        )
-                    } else if b.usage.contains(vk::BufferUsageFlags::UNIFORM_BUFFER) {
-                        (
-                            vk::PipelineStageFlags::VERTEX_SHADER | vk::PipelineStageFlags::FRAGMENT_SHADER,
-                            vk::AccessFlags::UNIFORM_READ,
-                        )
-                    } else if b.usage.contains(vk::BufferUsageFlags::STORAGE_BUFFER) {
-                        (
-                            vk::PipelineStageFlags::VERTEX_SHADER | vk::PipelineStageFlags::FRAGMENT_SHADER,
-                            vk::AccessFlags::SHADER_READ | vk::AccessFlags::SHADER_WRITE,
-                        )
-                    } else {
-                        (
-                            vk::PipelineStageFlags::ALL_COMMANDS,
-                            vk::AccessFlags::MEMORY_READ | vk::AccessFlags::MEMORY_WRITE,
-                        )
-                    };
-
-                    let barrier = vk::BufferMemoryBarrier::default()
-                        .src_access_mask(vk::AccessFlags::TRANSFER_WRITE)
-                        .dst_access_mask(dst_access)
-                        .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
-                        .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
-                        .buffer(b.buffer)
-                        .offset(offset as vk::DeviceSize)
-                        .size(data.len() as vk::DeviceSize);
-
-                    device.cmd_pipeline_barrier(
-                        cmd,
-                        vk::PipelineStageFlags::TRANSFER,
-                        dst_stage,
-                        vk::DependencyFlags::empty(),
-                        &[],
-                        std::slice::from_ref(&barrier),
-                        &[],
-                    );
-                },
-            )
-                .map_err(|e| EngineError::other(e.to_string()))?;
+            } else if b.usage.contains(vk::BufferUsageFlags::UNIFORM_BUFFER) {
+                (
+                    vk::PipelineStageFlags::VERTEX_SHADER | vk::PipelineStageFlags::FRAGMENT_SHADER,
+                    vk::AccessFlags::UNIFORM_READ,
+                )
+            } else if b.usage.contains(vk::BufferUsageFlags::STORAGE_BUFFER) {
+                (
+                    vk::PipelineStageFlags::VERTEX_SHADER | vk::PipelineStageFlags::FRAGMENT_SHADER,
+                    vk::AccessFlags::SHADER_READ | vk::AccessFlags::SHADER_WRITE,
+                )
+            } else {
+                (
+                    vk::PipelineStageFlags::ALL_COMMANDS,
+                    vk::AccessFlags::MEMORY_READ | vk::AccessFlags::MEMORY_WRITE,
+                )
+            };
 
-            device.destroy_buffer(staging.buffer, None);
-            device.free_memory(staging.memory, None);
+            // Batched on the transfer queue and submitted ahead of the next frame.
+            self.renderer
+                .upload_buffer(b.buffer, offset as vk::DeviceSize, data, dst_stage, dst_access)
+                .map_err(|e| EngineError::other(format!("write_buffer: {e}")))?;
         }
 
         Ok(())
     }
 
-    fn create_texture(&mut self, _desc: TextureDesc) -> EngineResult<TextureId> {
-        self.err("VulkanRenderApi: create_texture not implemented (world textures pending)")
+    fn create_texture(&mut self, desc: TextureDesc) -> EngineResult<TextureId> {
+        let depth = match desc.usage {
+            TextureUsage::Sampled => false,
+            TextureUsage::DepthStencil if desc.format == TextureFormat::Depth32Float => true,
+            TextureUsage::DepthStencil => {
+                return self.err("create_texture: DepthStencil textures must be Depth32Float");
+            }
+            usage => {
+                return self.err(format!(
+                    "create_texture: {usage:?} textures not implemented (only Sampled, DepthStencil)"
+                ));
+            }
+        };
+        if desc.mip_levels.get() != 1 {
+            return self.err("create_texture: mip chains not implemented");
+        }
+        if desc.extent.width == 0 || desc.extent.height == 0 {
+            return self.err("create_texture: empty extent");
+        }
+        if desc.cube && (depth || desc.extent.width != desc.extent.height) {
+            return self.err("create_texture: cubemaps must be Sampled with square faces");
+        }
+
+        let extent = vk::Extent2D {
+            width: desc.extent.width,
+            height: desc.extent.height,
+        };
+        let tex = if depth {
+            unsafe { self.renderer.create_depth_texture(extent, desc.label) }
+        } else {
+            let Some(format) = Self::map_texture_format(desc.format) else {
+                return self.err(format!(
+                    "create_texture: {:?} cannot be sampled",
+                    desc.format
+                ));
+            };
+            unsafe {
+                self.renderer.create_texture(
+                    format,
+                    extent,
+                    desc.cube,
+                    desc.format.texel_size(),
+                    desc.label,
+                )
+            }
+        };
+        let tex = tex.map_err(|e| EngineError::other(format!("create_texture: {e}")))?;
+
+        let id = TextureId::new(self.alloc_u32());
+        self.textures.insert(id, tex);
+        Ok(id)
+    }
+
+    fn destroy_texture(&mut self, id: TextureId) {
+        if let Some(tex) = self.textures.remove(&id) {
+            unsafe { self.renderer.destroy_texture(tex) };
+        }
+    }
+
+    fn write_texture(&mut self, id: TextureId, data: &[u8]) -> EngineResult<()> {
+        let Some(tex) = self.textures.get_mut(&id) else {
+            return self.err("write_texture: invalid TextureId");
+        };
+        if tex.is_depth() {
+            return Err(EngineError::other(
+                "write_texture: depth textures are written by depth passes",
+            ));
+        }
+        unsafe {
+            self.renderer
+                .write_texture(tex, data)
+                .map_err(|e| EngineError::other(e.to_string()))
+        }
     }
 
-    fn destroy_texture(&mut self, _id: TextureId) {}
+    fn create_sampler(&mut self, desc: SamplerDesc) -> EngineResult<SamplerId> {
+        let mipmap_mode = match desc.mip_filter {
+            FilterMode::Nearest => vk::SamplerMipmapMode::NEAREST,
+            FilterMode::Linear => vk::SamplerMipmapMode::LINEAR,
+        };
+        let info = vk::SamplerCreateInfo::default()
+            .mag_filter(Self::map_filter(desc.mag_filter))
+            .min_filter(Self::map_filter(desc.min_filter))
+            .mipmap_mode(mipmap_mode)
+            .address_mode_u(Self::map_address_mode(desc.address_u))
+            .address_mode_v(Self::map_address_mode(desc.address_v))
+            .address_mode_w(Self::map_address_mode(desc.address_w))
+            .max_lod(vk::LOD_CLAMP_NONE);
+
+        let sampler = unsafe {
+            self.renderer
+                .core
+                .device
+                .create_sampler(&info, None)
+                .map_err(|e| EngineError::other(e.to_string()))?
+        };
+        if let Some(label) = desc.label {
+            self.renderer.set_object_name(sampler, label);
+        }
 
-    fn create_sampler(&mut self, _desc: SamplerDesc) -> EngineResult<SamplerId> {
-        self.err("VulkanRenderApi: create_sampler not implemented (world samplers pending)")
+        let id = SamplerId::new(self.alloc_u32());
+        self.samplers.insert(id, sampler);
+        Ok(id)
     }
 
-    fn destroy_sampler(&mut self, _id: SamplerId) {}
+    fn destroy_sampler(&mut self, id: SamplerId) {
+        if let Some(s) = self.samplers.remove(&id) {
+            unsafe { self.renderer.core.device.destroy_sampler(s, None); }
+        }
+    }
 
     fn create_shader(&mut self, desc: ShaderDesc) -> EngineResult<ShaderId> {
         let id = ShaderId::new(self.alloc_u32());
@@ -535,6 +979,9 @@ impl RenderApi for VulkanRenderApi {
             let entry = CString::new(desc.entry)
                 .map_err(|_| EngineError::other("ShaderDesc.entry must be a valid C string"))?;
 
+            if let Some(label) = desc.label {
+                self.renderer.set_object_name(module, label);
+            }
             self.shaders.insert(id, VkShader { module, stage, entry });
         }
 
@@ -553,6 +1000,21 @@ impl RenderApi for VulkanRenderApi {
         let vs = self.shaders.get(&desc.vs).ok_or_else(|| EngineError::other("create_pipeline: invalid vs"))?.clone();
         let fs = self.shaders.get(&desc.fs).ok_or_else(|| EngineError::other("create_pipeline: invalid fs"))?.clone();
 
+        desc.validate_deformation()?;
+        if desc.depth_only && desc.depth_format != Some(TextureFormat::Depth32Float) {
+            return self.err("create_pipeline: depth-only pipelines need a Depth32Float depth format");
+        }
+        let id_pass = desc.color_format == TextureFormat::R32Uint;
+        if id_pass && (desc.depth_only || desc.depth_format != Some(TextureFormat::Depth32Float)) {
+            return self.err("create_pipeline: R32Uint (object-ID) pipelines need a Depth32Float depth format");
+        }
+        if id_pass && desc.blend != BlendState::Opaque {
+            return self.err("create_pipeline: R32Uint (object-ID) pipelines cannot blend");
+        }
+        if desc.polygon_mode == PolygonMode::Line && !self.renderer.core.fill_mode_non_solid {
+            return self.err("create_pipeline: PolygonMode::Line needs the fillModeNonSolid feature");
+        }
+
         let mut set_layouts: Vec<vk::DescriptorSetLayout> = Vec::with_capacity(desc.bind_group_layouts.len());
         for l_id in &desc.bind_group_layouts {
             let l = self.bg_layouts.get(l_id).ok_or_else(|| EngineError::other("create_pipeline: invalid bind group layout"))?;
@@ -578,7 +1040,10 @@ impl RenderApi for VulkanRenderApi {
                     vk::VertexInputBindingDescription::default()
                         .binding(i as u32)
                         .stride(l.stride)
-                        .input_rate(vk::VertexInputRate::VERTEX),
+                        .input_rate(match l.step_mode {
+                            VertexStepMode::Vertex => vk::VertexInputRate::VERTEX,
+                            VertexStepMode::Instance => vk::VertexInputRate::INSTANCE,
+                        }),
                 );
 
                 for a in &l.attributes {
@@ -600,28 +1065,39 @@ impl RenderApi for VulkanRenderApi {
             let vp = vk::PipelineViewportStateCreateInfo::default().viewport_count(1).scissor_count(1);
 
             let rs = vk::PipelineRasterizationStateCreateInfo::default()
-                .polygon_mode(vk::PolygonMode::FILL)
-                .cull_mode(vk::CullModeFlags::BACK)
-                .front_face(vk::FrontFace::COUNTER_CLOCKWISE)
+                .polygon_mode(Self::map_polygon_mode(desc.polygon_mode))
+                .cull_mode(Self::map_cull_mode(desc.cull_mode))
+                .front_face(Self::map_front_face(desc.front_face))
                 .line_width(1.0);
 
             let ms = vk::PipelineMultisampleStateCreateInfo::default().rasterization_samples(vk::SampleCountFlags::TYPE_1);
 
-            let ca = vk::PipelineColorBlendAttachmentState::default()
-                .blend_enable(false)
-                .color_write_mask(
-                    vk::ColorComponentFlags::R
-                        | vk::ColorComponentFlags::G
-                        | vk::ColorComponentFlags::B
-                        | vk::ColorComponentFlags::A,
-                );
+            let ca = Self::map_blend(desc.blend).color_write_mask(
+                vk::ColorComponentFlags::R
+                    | vk::ColorComponentFlags::G
+                    | vk::ColorComponentFlags::B
+                    | vk::ColorComponentFlags::A,
+            );
 
-            let cb = vk::PipelineColorBlendStateCreateInfo::default().attachments(std::slice::from_ref(&ca));
+            // Depth-only pipelines run in the depth render pass, which has no color attachment.
+            let color_attachments: &[_] = if desc.depth_only { &[] } else { std::slice::from_ref(&ca) };
+            let cb = vk::PipelineColorBlendStateCreateInfo::default().attachments(color_attachments);
+            let dss = vk::PipelineDepthStencilStateCreateInfo::default()
+                .depth_test_enable(true)
+                .depth_write_enable(true)
+                .depth_compare_op(vk::CompareOp::LESS_OR_EQUAL);
+            let render_pass = if desc.depth_only {
+                self.renderer.pipelines.depth_render_pass
+            } else if id_pass {
+                self.renderer.pipelines.id_render_pass
+            } else {
+                self.renderer.post.scene_pass
+            };
 
             let dyn_states = [vk::DynamicState::VIEWPORT, vk::DynamicState::SCISSOR];
             let ds = vk::PipelineDynamicStateCreateInfo::default().dynamic_states(&dyn_states);
 
-            let gp = vk::GraphicsPipelineCreateInfo::default()
+            let mut gp = vk::GraphicsPipelineCreateInfo::default()
                 .stages(&stages)
                 .vertex_input_state(&vi)
                 .input_assembly_state(&ia)
@@ -631,16 +1107,24 @@ impl RenderApi for VulkanRenderApi {
                 .color_blend_state(&cb)
                 .dynamic_state(&ds)
                 .layout(layout)
-                .render_pass(self.renderer.pipelines.render_pass)
+                .render_pass(render_pass)
                 .subpass(0);
+            if desc.depth_only || id_pass {
+                gp = gp.depth_stencil_state(&dss);
+            }
 
-            let pipelines = device.create_graphics_pipelines(vk::PipelineCache::null(), &[gp], None);
+            let pipelines = device.create_graphics_pipelines(self.renderer.pipelines.cache, &[gp], None);
             let pipeline = match pipelines {
                 Ok(v) => v[0],
                 Err((_, e)) => return Err(EngineError::other(e.to_string())),
             };
 
-            self.pipelines.insert(id, VkPipeline { pipeline, layout });
+            if let Some(label) = desc.label {
+                self.renderer.set_object_name(pipeline, label);
+                self.renderer.set_object_name(layout, label);
+            }
+            let set_count = set_layouts.len() as u32;
+            self.pipelines.insert(id, VkPipeline { pipeline, layout, set_count });
         }
 
         Ok(id)
@@ -663,17 +1147,30 @@ impl RenderApi for VulkanRenderApi {
     fn create_bind_group_layout(&mut self, desc: BindGroupLayoutDesc) -> EngineResult<BindGroupLayoutId> {
         let id = BindGroupLayoutId::new(self.alloc_u32());
 
+        let dynamic = desc.bindings.iter().filter(|&&k| k == BindingKind::UniformBufferDynamic).count();
+        if dynamic > MAX_DYNAMIC_OFFSETS {
+            return self.err(format!(
+                "create_bind_group_layout: too many UniformBufferDynamic bindings (max {MAX_DYNAMIC_OFFSETS})"
+            ));
+        }
+
         unsafe {
             let device = &self.renderer.core.device;
 
             let mut vk_bindings: Vec<vk::DescriptorSetLayoutBinding> = Vec::with_capacity(desc.bindings.len());
+            let mut types: Vec<vk::DescriptorType> = Vec::with_capacity(desc.bindings.len());
             for (i, k) in desc.bindings.iter().enumerate() {
                 let ty = match k {
-                    BindingKind::Texture2D => vk::DescriptorType::SAMPLED_IMAGE,
+                    BindingKind::Texture2D | BindingKind::TextureCube => vk::DescriptorType::SAMPLED_IMAGE,
                     BindingKind::Sampler => vk::DescriptorType::SAMPLER,
                     BindingKind::UniformBuffer => vk::DescriptorType::UNIFORM_BUFFER,
-                    BindingKind::StorageBuffer => vk::DescriptorType::STORAGE_BUFFER,
+                    BindingKind::UniformBufferDynamic => vk::DescriptorType::UNIFORM_BUFFER_DYNAMIC,
+                    BindingKind::StorageBuffer
+                    | BindingKind::BoneMatrices
+                    | BindingKind::MorphWeights
+                    | BindingKind::MorphDeltas => vk::DescriptorType::STORAGE_BUFFER,
                 };
+                types.push(ty);
 
                 vk_bindings.push(
                     vk::DescriptorSetLayoutBinding::default()
@@ -689,7 +1186,8 @@ impl RenderApi for VulkanRenderApi {
                 .create_descriptor_set_layout(&ci, None)
                 .map_err(|e| EngineError::other(e.to_string()))?;
 
-            self.bg_layouts.insert(id, VkBgLayout { layout, bindings: desc.bindings });
+            let shape = self.descriptors.shape(&types);
+            self.bg_layouts.insert(id, VkBgLayout { layout, bindings: desc.bindings, shape });
         }
 
         Ok(id)
@@ -712,69 +1210,9 @@ impl RenderApi for VulkanRenderApi {
         unsafe {
             let device = &self.renderer.core.device;
 
-            let mut need_img = 0u32;
-            let mut need_samp = 0u32;
-            let mut need_ubo = 0u32;
-            let mut need_ssbo = 0u32;
-
-            for k in &l.bindings {
-                match k {
-                    BindingKind::Texture2D => need_img += 1,
-                    BindingKind::Sampler => need_samp += 1,
-                    BindingKind::UniformBuffer => need_ubo += 1,
-                    BindingKind::StorageBuffer => need_ssbo += 1,
-                }
-            }
-
-            let mut pool_sizes: Vec<vk::DescriptorPoolSize> = Vec::new();
-            if need_img > 0 {
-                pool_sizes.push(
-                    vk::DescriptorPoolSize::default()
-                        .ty(vk::DescriptorType::SAMPLED_IMAGE)
-                        .descriptor_count(need_img),
-                );
-            }
-            if need_samp > 0 {
-                pool_sizes.push(
-                    vk::DescriptorPoolSize::default()
-                        .ty(vk::DescriptorType::SAMPLER)
-                        .descriptor_count(need_samp),
-                );
-            }
-            if need_ubo > 0 {
-                pool_sizes.push(
-                    vk::DescriptorPoolSize::default()
-                        .ty(vk::DescriptorType::UNIFORM_BUFFER)
-                        .descriptor_count(need_ubo),
-                );
-            }
-            if need_ssbo > 0 {
-                pool_sizes.push(
-                    vk::DescriptorPoolSize::default()
-                        .ty(vk::DescriptorType::STORAGE_BUFFER)
-                        .descriptor_count(need_ssbo),
-                );
-            }
-
-            let pool_ci = vk::DescriptorPoolCreateInfo::default()
-                .max_sets(1)
-                .pool_sizes(&pool_sizes);
-
-            let pool = device
-                .create_descriptor_pool(&pool_ci, None)
-                .map_err(|e| EngineError::other(e.to_string()))?;
-
-            let set_layouts = [l.layout];
-            let alloc = vk::DescriptorSetAllocateInfo::default()
-                .descriptor_pool(pool)
-                .set_layouts(&set_layouts);
-
-            let set = device
-                .allocate_descriptor_sets(&alloc)
-                .map_err(|e| EngineError::other(e.to_string()))?[0];
-
             let mut writes: Vec<vk::WriteDescriptorSet> = Vec::new();
             let mut buf_infos: Vec<vk::DescriptorBufferInfo> = Vec::new();
+            let mut img_infos: Vec<vk::DescriptorImageInfo> = Vec::new();
 
             #[derive(Clone, Copy)]
             struct PendingBufWrite {
@@ -783,14 +1221,23 @@ impl RenderApi for VulkanRenderApi {
                 buf_info_index: usize,
             }
 
+            #[derive(Clone, Copy)]
+            struct PendingImgWrite {
+                binding: u32,
+                ty: vk::DescriptorType,
+                img_info_index: usize,
+            }
+
             let mut pending: Vec<PendingBufWrite> = Vec::new();
+            let mut pending_img: Vec<PendingImgWrite> = Vec::new();
 
-            buf_infos.reserve_exact((need_ubo + need_ssbo) as usize);
-            pending.reserve_exact((need_ubo + need_ssbo) as usize);
+            buf_infos.reserve_exact(l.bindings.len());
+            img_infos.reserve_exact(l.bindings.len());
+            pending.reserve_exact(l.bindings.len());
 
             for (binding, k) in l.bindings.iter().enumerate() {
                 match k {
-                    BindingKind::UniformBuffer => {
+                    BindingKind::UniformBuffer | BindingKind::UniformBufferDynamic => {
                         let Some(bb) = desc.uniform0 else { continue; };
                         let b = *self
                             .buffers
@@ -804,18 +1251,26 @@ impl RenderApi for VulkanRenderApi {
                                 .range(bb.size),
                         );
 
+                        let ty = if *k == BindingKind::UniformBufferDynamic {
+                            vk::DescriptorType::UNIFORM_BUFFER_DYNAMIC
+                        } else {
+                            vk::DescriptorType::UNIFORM_BUFFER
+                        };
                         pending.push(PendingBufWrite {
                             binding: binding as u32,
-                            ty: vk::DescriptorType::UNIFORM_BUFFER,
+                            ty,
                             buf_info_index: buf_infos.len() - 1,
                         });
                     }
-                    BindingKind::StorageBuffer => {
-                        let Some(bb) = desc.storage0 else { continue; };
+                    BindingKind::StorageBuffer
+                    | BindingKind::BoneMatrices
+                    | BindingKind::MorphWeights
+                    | BindingKind::MorphDeltas => {
+                        let Some(bb) = desc.buffer_for(*k) else { continue; };
                         let b = *self
                             .buffers
                             .get(&bb.buffer)
-                            .ok_or_else(|| EngineError::other("create_bind_group: invalid storage0 buffer"))?;
+                            .ok_or_else(|| EngineError::other(format!("create_bind_group: invalid {k:?} buffer")))?;
 
                         buf_infos.push(
                             vk::DescriptorBufferInfo::default()
@@ -830,20 +1285,54 @@ impl RenderApi for VulkanRenderApi {
                             buf_info_index: buf_infos.len() - 1,
                         });
                     }
-                    BindingKind::Texture2D => {
-                        return Err(EngineError::other(
-                            "create_bind_group: Texture2D not implemented (world textures pending)",
-                        ));
+                    BindingKind::Texture2D | BindingKind::TextureCube => {
+                        let Some(t) = desc.texture0 else { continue; };
+                        let tex = self
+                            .textures
+                            .get(&t)
+                            .ok_or_else(|| EngineError::other("create_bind_group: invalid texture0"))?;
+                        // The view type has to match the shader's texture type.
+                        if (*k == BindingKind::TextureCube) != (tex.layers == 6) {
+                            return Err(EngineError::other(format!(
+                                "create_bind_group: texture0 does not fit a {k:?} binding"
+                            )));
+                        }
+
+                        img_infos.push(
+                            vk::DescriptorImageInfo::default()
+                                .image_view(tex.view)
+                                .image_layout(vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL),
+                        );
+                        pending_img.push(PendingImgWrite {
+                            binding: binding as u32,
+                            ty: vk::DescriptorType::SAMPLED_IMAGE,
+                            img_info_index: img_infos.len() - 1,
+                        });
                     }
                     BindingKind::Sampler => {
-                        return Err(EngineError::other(
-                            "create_bind_group: Sampler not implemented (world samplers pending)",
-                        ));
+                        let Some(s) = desc.sampler0 else { continue; };
+                        let sampler = *self
+                            .samplers
+                            .get(&s)
+                            .ok_or_else(|| EngineError::other("create_bind_group: invalid sampler0"))?;
+
+                        img_infos.push(vk::DescriptorImageInfo::default().sampler(sampler));
+                        pending_img.push(PendingImgWrite {
+                            binding: binding as u32,
+                            ty: vk::DescriptorType::SAMPLER,
+                            img_info_index: img_infos.len() - 1,
+                        });
                     }
                 }
             }
 
-            writes.reserve_exact(pending.len());
+            // Allocated once every binding is known to be valid, so errors above leak nothing.
+            let set = self
+                .descriptors
+                .allocate(device, l.shape, l.layout)
+                .map_err(|e| EngineError::other(e.to_string()))?;
+
+            writes.reserve_exact(pending.len() + pending_img.len());
             for p in pending {
                 let bi_ref = std::slice::from_ref(&buf_infos[p.buf_info_index]);
                 writes.push(
@@ -854,6 +1343,16 @@ impl RenderApi for VulkanRenderApi {
                         .buffer_info(bi_ref),
                 );
             }
+            for p in pending_img {
+                let ii_ref = std::slice::from_ref(&img_infos[p.img_info_index]);
+                writes.push(
+                    vk::WriteDescriptorSet::default()
+                        .dst_set(set)
+                        .dst_binding(p.binding)
+                        .descriptor_type(p.ty)
+                        .image_info(ii_ref),
+                );
+            }
 
             if !writes.is_empty() {
                 device.update_descriptor_sets(&writes, &[]);
@@ -863,8 +1362,9 @@ impl RenderApi for VulkanRenderApi {
                 id,
                 VkBindGroup {
                     set,
-                    pool,
-                    layout: l.layout,
+                    shape: l.shape,
+                    dynamic_count: l.bindings.iter().filter(|&&k| k == BindingKind::UniformBufferDynamic).count()
+                        as u32,
                 },
             );
         }
@@ -874,11 +1374,7 @@ impl RenderApi for VulkanRenderApi {
 
     fn destroy_bind_group(&mut self, id: BindGroupId) {
         if let Some(bg) = self.bind_groups.remove(&id) {
-            unsafe {
-                if bg.pool != vk::DescriptorPool::null() {
-                    self.renderer.core.device.destroy_descriptor_pool(bg.pool, None);
-                }
-            }
+            self.descriptors.release(bg.shape, bg.set);
         }
     }
 
@@ -891,7 +1387,7 @@ impl RenderApi for VulkanRenderApi {
             min_depth: vp.min_depth,
             max_depth: vp.max_depth,
         };
-        self.recorded.push(RecordedCmd::SetViewport(vk_vp));
+        self.record(RecordedCmd::SetViewport(vk_vp));
         Ok(())
     }
 
@@ -900,25 +1396,52 @@ impl RenderApi for VulkanRenderApi {
             offset: vk::Offset2D { x: rect.x, y: rect.y },
             extent: vk::Extent2D { width: rect.w.max(0) as u32, height: rect.h.max(0) as u32 },
         };
-        self.recorded.push(RecordedCmd::SetScissor(sc));
+        self.record(RecordedCmd::SetScissor(sc));
         Ok(())
     }
 
     fn set_pipeline(&mut self, pipeline: PipelineId) -> EngineResult<()> {
         let p = *self.pipelines.get(&pipeline).ok_or_else(|| EngineError::other("set_pipeline: invalid PipelineId"))?;
         self.current_pipeline = Some(pipeline);
-        self.recorded.push(RecordedCmd::BindPipeline(p.pipeline));
+        self.record(RecordedCmd::BindPipeline(p.pipeline));
         Ok(())
     }
 
     fn set_bind_group(&mut self, index: u32, group: BindGroupId) -> EngineResult<()> {
+        let dynamic_count = self.bind_groups.get(&group).map_or(0, |bg| bg.dynamic_count);
+        let zeros = [0u32; MAX_DYNAMIC_OFFSETS];
+        self.set_bind_group_with_offsets(index, group, &zeros[..dynamic_count as usize])
+    }
+
+    fn set_bind_group_with_offsets(&mut self, index: u32, group: BindGroupId, offsets: &[u32]) -> EngineResult<()> {
         if index as usize >= self.current_bind_groups.len() {
             return self.err("set_bind_group: index out of range (max 4)");
         }
+        let dynamic_count = self.bind_groups.get(&group).map_or(0, |bg| bg.dynamic_count);
+        if offsets.len() != dynamic_count as usize {
+            return self.err(format!(
+                "set_bind_group: {} dynamic offsets given, the group has {dynamic_count}",
+                offsets.len()
+            ));
+        }
+        if let Some(&o) = offsets.iter().find(|&&o| o as u64 % self.uniform_align != 0) {
+            return self.err(format!("set_bind_group: offset {o} is not a multiple of {}", self.uniform_align));
+        }
+
+        let mut dyn_offsets = DynamicOffsets::default();
+        dyn_offsets.values[..offsets.len()].copy_from_slice(offsets);
+        dyn_offsets.count = offsets.len() as u32;
+
         self.current_bind_groups[index as usize] = Some(group);
+        self.current_dynamic_offsets[index as usize] = dyn_offsets;
         Ok(())
     }
 
+    #[inline]
+    fn uniform_offset_alignment(&self) -> u64 {
+        self.uniform_align
+    }
+
     fn set_vertex_buffer(&mut self, slot: u32, slice: BufferSlice) -> EngineResult<()> {
         if slot as usize >= self.current_vertex.len() {
             return self.err("set_vertex_buffer: slot out of range (max 4)");
@@ -933,83 +1456,276 @@ impl RenderApi for VulkanRenderApi {
     }
 
     fn draw(&mut self, args: DrawArgs) -> EngineResult<()> {
-        let Some(pipeline_id) = self.current_pipeline else { return self.err("draw: no pipeline bound"); };
-        let p = *self.pipelines.get(&pipeline_id).ok_or_else(|| EngineError::other("draw: invalid current pipeline"))?;
+        self.bind_draw_state("draw")?;
+        self.record(RecordedCmd::Draw(args));
+        Ok(())
+    }
 
-        let mut sets = [vk::DescriptorSet::null(); 4];
-        let mut set_count = 0u32;
-        for (i, bg_id) in self.current_bind_groups.iter().enumerate() {
-            if let Some(bg_id) = bg_id {
-                let bg = *self.bind_groups.get(bg_id).ok_or_else(|| EngineError::other("draw: invalid bind group"))?;
-                sets[i] = bg.set;
-                set_count = (i as u32) + 1;
-            }
+    fn draw_indexed(&mut self, args: DrawIndexedArgs) -> EngineResult<()> {
+        self.bind_draw_state("draw_indexed")?;
+        self.bind_index_state("draw_indexed")?;
+        self.record(RecordedCmd::DrawIndexed(args));
+        Ok(())
+    }
+
+    fn draw_indirect(&mut self, args: BufferSlice, draw_count: u32) -> EngineResult<()> {
+        let (buffer, offset) = self.indirect_buffer("draw_indirect", args)?;
+        self.bind_draw_state("draw_indirect")?;
+        self.record(RecordedCmd::DrawIndirect {
+            buffer,
+            offset,
+            draw_count,
+        });
+        Ok(())
+    }
+
+    fn draw_indexed_indirect(&mut self, args: BufferSlice, draw_count: u32) -> EngineResult<()> {
+        let (buffer, offset) = self.indirect_buffer("draw_indexed_indirect", args)?;
+        self.bind_draw_state("draw_indexed_indirect")?;
+        self.bind_index_state("draw_indexed_indirect")?;
+        self.record(RecordedCmd::DrawIndexedIndirect {
+            buffer,
+            offset,
+            draw_count,
+        });
+        Ok(())
+    }
+
+    fn begin_depth_pass(&mut self, target: TextureId) -> EngineResult<()> {
+        if self.in_depth_pass {
+            return self.err("begin_depth_pass: a depth pass is already open");
         }
-        if set_count > 0 {
-            self.recorded.push(RecordedCmd::BindDescriptorSets { layout: p.layout, first_set: 0, sets, set_count });
+        if self.in_id_pass {
+            return self.err("begin_depth_pass: the object-ID pass is open");
         }
+        let Some(tex) = self.textures.get(&target) else {
+            return self.err("begin_depth_pass: invalid TextureId");
+        };
+        if !tex.is_depth() {
+            return self.err("begin_depth_pass: target is not a DepthStencil texture");
+        }
+        self.depth_passes.push(DepthPass { framebuffer: tex.framebuffer, extent: tex.extent, cmds: Vec::new() });
+        self.in_depth_pass = true;
+        self.reset_bindings();
+        Ok(())
+    }
 
-        let mut bufs = [vk::Buffer::null(); 4];
-        let mut offs = [0u64; 4];
-        let mut count = 0u32;
-        for (i, s) in self.current_vertex.iter().enumerate() {
-            if let Some(s) = s {
-                let b = *self.buffers.get(&s.buffer).ok_or_else(|| EngineError::other("draw: invalid vertex buffer"))?;
-                bufs[i] = b.buffer;
-                offs[i] = s.offset as u64;
-                count = (i as u32) + 1;
-            }
+    fn end_depth_pass(&mut self) -> EngineResult<()> {
+        if !self.in_depth_pass {
+            return self.err("end_depth_pass: no depth pass is open");
         }
-        if count > 0 {
-            self.recorded.push(RecordedCmd::BindVertexBuffer { first_binding: 0, buffers: bufs, offsets: offs, count });
+        self.in_depth_pass = false;
+        self.reset_bindings();
+        Ok(())
+    }
+
+    fn begin_id_pass(&mut self) -> EngineResult<()> {
+        if self.in_depth_pass {
+            return self.err("begin_id_pass: a depth pass is open");
+        }
+        if self.in_id_pass || self.id_pass.is_some() {
+            return self.err("begin_id_pass: the frame already has an object-ID pass");
         }
+        self.id_pass = Some(Vec::new());
+        self.in_id_pass = true;
+        self.reset_bindings();
+        Ok(())
+    }
 
-        self.recorded.push(RecordedCmd::Draw(args));
+    fn end_id_pass(&mut self) -> EngineResult<()> {
+        if !self.in_id_pass {
+            return self.err("end_id_pass: no object-ID pass is open");
+        }
+        self.in_id_pass = false;
+        self.reset_bindings();
         Ok(())
     }
 
-    fn draw_indexed(&mut self, args: DrawIndexedArgs) -> EngineResult<()> {
-        let Some(pipeline_id) = self.current_pipeline else { return self.err("draw_indexed: no pipeline bound"); };
-        let p = *self.pipelines.get(&pipeline_id).ok_or_else(|| EngineError::other("draw_indexed: invalid current pipeline"))?;
+    fn pick(&mut self, x: u32, y: u32) -> EngineResult<()> {
+        self.renderer.request_pick(x, y);
+        Ok(())
+    }
 
-        let mut sets = [vk::DescriptorSet::null(); 4];
-        let mut set_count = 0u32;
-        for (i, bg_id) in self.current_bind_groups.iter().enumerate() {
-            if let Some(bg_id) = bg_id {
-                let bg = *self.bind_groups.get(bg_id).ok_or_else(|| EngineError::other("draw_indexed: invalid bind group"))?;
-                sets[i] = bg.set;
-                set_count = (i as u32) + 1;
+    #[inline]
+    fn take_picked(&mut self) -> Option<PickResult> {
+        self.renderer.take_pick()
+    }
+
+    fn set_post_process(&mut self, settings: PostProcessSettings) -> EngineResult<()> {
+        self.renderer.set_post_process(settings);
+        Ok(())
+    }
+
+    fn default_material_shaders(
+        &mut self,
+        material: DefaultMaterial,
+        deformation: VertexDeformation,
+    ) -> EngineResult<(ShaderId, ShaderId)> {
+        let key = (material, deformation);
+        if let Some(&(vs, fs)) = self.default_shaders.get(&key) {
+            if self.shaders.contains_key(&vs) && self.shaders.contains_key(&fs) {
+                return Ok((vs, fs));
             }
         }
-        if set_count > 0 {
-            self.recorded.push(RecordedCmd::BindDescriptorSets { layout: p.layout, first_set: 0, sets, set_count });
-        }
 
-        let mut bufs = [vk::Buffer::null(); 4];
-        let mut offs = [0u64; 4];
-        let mut count = 0u32;
-        for (i, s) in self.current_vertex.iter().enumerate() {
-            if let Some(s) = s {
-                let b = *self.buffers.get(&s.buffer).ok_or_else(|| EngineError::other("draw_indexed: invalid vertex buffer"))?;
-                bufs[i] = b.buffer;
-                offs[i] = s.offset as u64;
-                count = (i as u32) + 1;
+        let (vs_bytes, fs_bytes) = default_material_spirv(material, deformation);
+        let read = |bytes: &[u8]| {
+            ash::util::read_spv(&mut std::io::Cursor::new(bytes))
+                .map_err(|e| EngineError::other(format!("default material: invalid SPIR-V: {e}")))
+        };
+
+        let vs = self.create_shader(
+            ShaderDesc::new(ShaderStage::Vertex, "main", read(vs_bytes)?).with_label("default_material_vs"),
+        )?;
+        let fs = self.create_shader(
+            ShaderDesc::new(ShaderStage::Fragment, "main", read(fs_bytes)?).with_label("default_material_fs"),
+        )?;
+
+        log::debug!(
+            "render.default_material {:?} skinning={} morph_targets={}",
+            material,
+            deformation.skinning,
+            deformation.morph_targets
+        );
+
+        self.default_shaders.insert(key, (vs, fs));
+        Ok((vs, fs))
+    }
+
+    fn gpu_pass_timings(&self) -> Vec<GpuPassTiming> {
+        self.renderer
+            .gpu_pass_timings()
+            .iter()
+            .map(|&(name, ms)| GpuPassTiming { name, ms })
+            .collect()
+    }
+
+    #[inline]
+    fn debug_counters(&self) -> Vec<(&'static str, u64)> {
+        let d = self.descriptors.stats();
+        let m = self.memory.stats();
+        let (t_capacity, t_used, t_peak) = self.renderer.transient_stats();
+        let mut out = crate::vulkan::debug_utils::debug_message_counts();
+        out.extend([
+            ("transient_capacity", t_capacity),
+            ("transient_used", t_used),
+            ("transient_peak", t_peak),
+            ("descriptor_pools", d.pools),
+            ("descriptor_capacity", d.capacity),
+            ("descriptor_sets_live", d.live),
+            ("descriptor_sets_free", d.free),
+            ("descriptor_allocations", d.allocations),
+            ("descriptor_recycled", d.recycled),
+            ("memory_blocks", m.blocks),
+            ("memory_block_bytes", m.block_bytes),
+            ("memory_used_bytes", m.used_bytes),
+            ("memory_suballocations", m.suballocations),
+            ("memory_dedicated", m.dedicated),
+            ("memory_dedicated_bytes", m.dedicated_bytes),
+            ("memory_free_ranges", m.free_ranges),
+            ("memory_largest_free", m.largest_free),
+            ("memory_fragmentation_pct", m.fragmentation_pct),
+        ]);
+        out
+    }
+
+    fn capture_frame(&mut self) -> EngineResult<()> {
+        self.renderer
+            .request_capture()
+            .map_err(|e| EngineError::other(e.to_string()))
+    }
+
+    #[inline]
+    fn take_captured_frame(&mut self) -> Option<CapturedFrame> {
+        self.renderer.take_capture()
+    }
+
+    fn alloc_transient(&mut self, size: u64) -> EngineResult<BufferSlice> {
+        let a = self
+            .renderer
+            .alloc_transient(size as vk::DeviceSize)
+            .map_err(|e| EngineError::other(e.to_string()))?;
+
+        let id = match self.transient_ids.get(&a.buffer) {
+            Some(&id) => id,
+            None => {
+                let id = BufferId::new(self.alloc_u32());
+                self.buffers.insert(
+                    id,
+                    VkBuffer {
+                        buffer: a.buffer,
+                        memory: None,
+                        size: vk::WHOLE_SIZE,
+                        usage: vk::BufferUsageFlags::VERTEX_BUFFER
+                            | vk::BufferUsageFlags::INDEX_BUFFER
+                            | vk::BufferUsageFlags::UNIFORM_BUFFER
+                            | vk::BufferUsageFlags::STORAGE_BUFFER
+                            | vk::BufferUsageFlags::INDIRECT_BUFFER,
+                        host_visible: true,
+                    },
+                );
+                self.transient_ids.insert(a.buffer, id);
+                id
+            }
+        };
+        Ok(BufferSlice::new(id, a.offset))
+    }
+
+    fn load_font(&mut self, data: Vec<u8>) -> EngineResult<FontId> {
+        let id = self
+            .renderer
+            .load_font(data)
+            .map_err(|e| EngineError::other(format!("load_font: {e}")))?;
+        Ok(FontId::new(id))
+    }
+
+    fn draw_text(&mut self, text: TextDraw) -> EngineResult<()> {
+        if let Some(font) = text.font {
+            if !self.renderer.has_font(font.get()) {
+                return self.err("draw_text: unknown font");
             }
         }
-        if count > 0 {
-            self.recorded.push(RecordedCmd::BindVertexBuffer { first_binding: 0, buffers: bufs, offsets: offs, count });
+        if !text.size_px.is_finite() || text.size_px <= 0.0 {
+            return self.err("draw_text: size_px must be positive");
         }
+        self.renderer.queue_text(text);
+        Ok(())
+    }
+
+    fn create_viewport(
+        &mut self,
+        display: RawDisplayHandle,
+        window: RawWindowHandle,
+        size: Extent2D,
+    ) -> EngineResult<ViewportId> {
+        let id = unsafe {
+            self.renderer
+                .create_viewport(display, window, size.width, size.height)
+                .map_err(|e| EngineError::other(format!("create_viewport: {e}")))?
+        };
+        Ok(ViewportId::new(id))
+    }
 
-        let Some((idx_slice, fmt)) = self.current_index else { return self.err("draw_indexed: no index buffer bound"); };
-        let ib = *self.buffers.get(&idx_slice.buffer).ok_or_else(|| EngineError::other("draw_indexed: invalid index buffer"))?;
+    fn destroy_viewport(&mut self, id: ViewportId) {
+        if id != ViewportId::MAIN {
+            unsafe { self.renderer.destroy_viewport(id.get()) };
+        }
+    }
 
-        self.recorded.push(RecordedCmd::BindIndexBuffer {
-            buffer: ib.buffer,
-            offset: idx_slice.offset as vk::DeviceSize,
-            index_type: Self::map_index_format(fmt),
-        });
+    fn resize_viewport(&mut self, id: ViewportId, width: u32, height: u32) -> EngineResult<()> {
+        if id == ViewportId::MAIN {
+            return self.resize(width, height);
+        }
+        self.renderer
+            .resize_viewport(id.get(), width, height)
+            .map_err(|e| EngineError::other(e.to_string()))
+    }
 
-        self.recorded.push(RecordedCmd::DrawIndexed(args));
-        Ok(())
+    fn set_viewport_ui_draw_list(&mut self, id: ViewportId, ui: UiDrawList) {
+        if id == ViewportId::MAIN {
+            self.renderer.set_ui_draw_list(ui);
+        } else {
+            self.renderer.set_viewport_ui_draw_list(id.get(), ui);
+        }
     }
 }
@@ -0,0 +1,98 @@
+use newengine_core::render::{DebugDrawBatch, DebugText, TextDraw};
+
+use super::super::font::GlyphQuad;
+use super::super::text::{push_glyph_quad, TextVertex};
+use super::super::VulkanRenderer;
+
+enum WorldItem<'a> {
+    /// Label, its anchor on screen and its size in pixels.
+    Label(&'a DebugText, [f32; 2], f32),
+    Quad(GlyphQuad, u32),
+}
+
+/// Straight RGBA8 (little endian) as used by debug primitives.
+#[inline]
+fn unpack_rgba8(c: u32) -> [f32; 4] {
+    c.to_le_bytes().map(|v| v as f32 / 255.0)
+}
+
+impl VulkanRenderer {
+    /// Appends the batch's labels and billboards to the text pass. They are laid out in
+    /// screen space, so they always face the camera; the farthest go first so nearer ones
+    /// overlap them. The main pass has no depth buffer, so `depth_test` is not honored.
+    pub(crate) fn debug_world_text(&mut self, batch: &DebugDrawBatch, out: &mut Vec<TextVertex>) {
+        let extent = [self.swapchain.extent.width, self.swapchain.extent.height];
+        let (w, h) = (extent[0] as f32, extent[1] as f32);
+
+        let mut items = Vec::with_capacity(batch.texts.len() + batch.billboards.len());
+        for t in &batch.texts {
+            let Some(anchor) = batch.project(t.pos, extent) else {
+                continue;
+            };
+            let size_px = if t.size > 0.0 {
+                match batch.pixels_per_unit(t.pos, extent) {
+                    Some(ppu) => t.size * ppu,
+                    None => continue,
+                }
+            } else {
+                DebugText::SCREEN_PX
+            };
+            // Too small to read.
+            if size_px < 1.0 {
+                continue;
+            }
+            let depth = batch.view_depth(t.pos);
+            items.push((depth, WorldItem::Label(t, anchor, size_px)));
+        }
+
+        let uv = self.text.atlas.solid_uv();
+        for b in &batch.billboards {
+            let (Some(c), Some(ppu)) = (
+                batch.project(b.center, extent),
+                batch.pixels_per_unit(b.center, extent),
+            ) else {
+                continue;
+            };
+            let half = [b.size[0] * ppu * 0.5, b.size[1] * ppu * 0.5];
+            let quad = GlyphQuad {
+                min: [c[0] - half[0], c[1] - half[1]],
+                max: [c[0] + half[0], c[1] + half[1]],
+                uv_min: uv,
+                uv_max: uv,
+            };
+            let depth = batch.view_depth(b.center);
+            items.push((depth, WorldItem::Quad(quad, b.color)));
+        }
+
+        items.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+        let mut quads = Vec::new();
+        for (_, item) in &items {
+            match item {
+                WorldItem::Quad(q, color) => push_glyph_quad(out, q, unpack_rgba8(*color), w, h),
+                WorldItem::Label(t, anchor, size_px) => {
+                    quads.clear();
+                    let run = TextDraw::new(t.text.as_str(), [0.0, 0.0]).with_size(*size_px);
+                    self.text.atlas.layout(&run, &mut quads);
+
+                    // Anchored at the bottom center of the laid out glyphs.
+                    let (mut x0, mut x1, mut y1) = (f32::MAX, f32::MIN, f32::MIN);
+                    for q in &quads {
+                        x0 = x0.min(q.min[0]);
+                        x1 = x1.max(q.max[0]);
+                        y1 = y1.max(q.max[1]);
+                    }
+                    let dx = anchor[0] - (x0 + x1) * 0.5;
+                    let dy = anchor[1] - y1;
+
+                    let color = unpack_rgba8(t.color);
+                    for q in &mut quads {
+                        q.min = [q.min[0] + dx, q.min[1] + dy];
+                        q.max = [q.max[0] + dx, q.max[1] + dy];
+                        push_glyph_quad(out, q, color, w, h);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use crate::error::VkResult;
+
+use ash::vk;
+use newengine_core::render::{DebugDrawBatch, DebugVertex};
+use std::mem;
+
+use super::super::VulkanRenderer;
+
+use super::pipeline::{create_debug_line_pipeline, debug_line_pc_bytes};
+
+impl VulkanRenderer {
+    pub(crate) fn init_debug_lines(&mut self) -> VkResult<()> {
+        unsafe {
+            let (pl, p) = create_debug_line_pipeline(
+                &self.core.device,
+                self.pipelines.render_pass,
+                self.pipelines.cache,
+            )?;
+            self.lines.pipeline_layout = pl;
+            self.lines.pipeline = p;
+        }
+        Ok(())
+    }
+
+    pub(crate) unsafe fn destroy_debug_lines(&mut self) {
+        if self.lines.pipeline != vk::Pipeline::null() {
+            self.core.device.destroy_pipeline(self.lines.pipeline, None);
+            self.lines.pipeline = vk::Pipeline::null();
+        }
+        if self.lines.pipeline_layout != vk::PipelineLayout::null() {
+            self.core
+                .device
+                .destroy_pipeline_layout(self.lines.pipeline_layout, None);
+            self.lines.pipeline_layout = vk::PipelineLayout::null();
+        }
+    }
+
+    /// Records the batch's line list into the open render pass over the whole target.
+    pub(crate) unsafe fn debug_lines_draw(
+        &mut self,
+        cmd: vk::CommandBuffer,
+        batch: &DebugDrawBatch,
+    ) -> VkResult<()> {
+        if batch.vertices.is_empty() || self.lines.pipeline == vk::Pipeline::null() {
+            return Ok(());
+        }
+
+        let vb_bytes = mem::size_of::<DebugVertex>() * batch.vertices.len();
+        // `DebugVertex` is `repr(C)` without padding.
+        let bytes = std::slice::from_raw_parts(batch.vertices.as_ptr() as *const u8, vb_bytes);
+        let vb = self.alloc_transient(vb_bytes as vk::DeviceSize)?;
+        vb.write(0, bytes)?;
+
+        // Scene draws may have narrowed the viewport; debug geometry spans the full target.
+        let extent = self.swapchain.extent;
+        let viewport = vk::Viewport {
+            x: 0.0,
+            y: 0.0,
+            width: extent.width as f32,
+            height: extent.height as f32,
+            min_depth: 0.0,
+            max_depth: 1.0,
+        };
+        let scissor = vk::Rect2D {
+            offset: vk::Offset2D { x: 0, y: 0 },
+            extent,
+        };
+        self.core
+            .device
+            .cmd_set_viewport(cmd, 0, std::slice::from_ref(&viewport));
+        self.core
+            .device
+            .cmd_set_scissor(cmd, 0, std::slice::from_ref(&scissor));
+
+        self.core.device.cmd_bind_pipeline(
+            cmd,
+            vk::PipelineBindPoint::GRAPHICS,
+            self.lines.pipeline,
+        );
+
+        let pc = debug_line_pc_bytes(batch.view_proj);
+        self.core.device.cmd_push_constants(
+            cmd,
+            self.lines.pipeline_layout,
+            vk::ShaderStageFlags::VERTEX,
+            0,
+            &pc,
+        );
+
+        let vbs = [vb.buffer];
+        let offsets = [vb.offset];
+        self.core
+            .device
+            .cmd_bind_vertex_buffers(cmd, 0, &vbs, &offsets);
+        self.core
+            .device
+            .cmd_draw(cmd, batch.vertices.len() as u32, 1, 0, 0);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,5 @@
+mod labels;
+mod lines;
+mod pipeline;
+
+pub(super) use pipeline::create_debug_line_pipeline;
@@ -0,0 +1,98 @@
+use ash::vk;
+use std::ffi::{c_void, CStr};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Log target of validation / driver messages.
+const LOG_TARGET: &str = "vulkan.validation";
+
+// Process-wide: the messenger callback has no renderer to count into.
+static ERRORS: AtomicU64 = AtomicU64::new(0);
+static WARNINGS: AtomicU64 = AtomicU64::new(0);
+static INFOS: AtomicU64 = AtomicU64::new(0);
+
+/// `(severity, messages)` received since startup.
+pub(crate) fn debug_message_counts() -> Vec<(&'static str, u64)> {
+    vec![
+        ("validation_errors", ERRORS.load(Ordering::Relaxed)),
+        ("validation_warnings", WARNINGS.load(Ordering::Relaxed)),
+        ("validation_infos", INFOS.load(Ordering::Relaxed)),
+    ]
+}
+
+/// Messenger routing warnings and errors (and info at debug level) into the log. Also chained
+/// into `vkCreateInstance` to cover instance creation and destruction.
+pub(super) fn messenger_create_info<'a>() -> vk::DebugUtilsMessengerCreateInfoEXT<'a> {
+    vk::DebugUtilsMessengerCreateInfoEXT::default()
+        .message_severity(
+            vk::DebugUtilsMessageSeverityFlagsEXT::ERROR
+                | vk::DebugUtilsMessageSeverityFlagsEXT::WARNING
+                | vk::DebugUtilsMessageSeverityFlagsEXT::INFO,
+        )
+        .message_type(
+            vk::DebugUtilsMessageTypeFlagsEXT::GENERAL
+                | vk::DebugUtilsMessageTypeFlagsEXT::VALIDATION
+                | vk::DebugUtilsMessageTypeFlagsEXT::PERFORMANCE,
+        )
+        .pfn_user_callback(Some(on_message))
+}
+
+#[inline]
+unsafe fn c_str<'a>(p: *const std::ffi::c_char) -> Option<&'a str> {
+    if p.is_null() {
+        None
+    } else {
+        CStr::from_ptr(p).to_str().ok()
+    }
+}
+
+unsafe extern "system" fn on_message(
+    severity: vk::DebugUtilsMessageSeverityFlagsEXT,
+    types: vk::DebugUtilsMessageTypeFlagsEXT,
+    data: *const vk::DebugUtilsMessengerCallbackDataEXT<'_>,
+    _user: *mut c_void,
+) -> vk::Bool32 {
+    let (level, counter) = if severity.contains(vk::DebugUtilsMessageSeverityFlagsEXT::ERROR) {
+        (log::Level::Error, &ERRORS)
+    } else if severity.contains(vk::DebugUtilsMessageSeverityFlagsEXT::WARNING) {
+        (log::Level::Warn, &WARNINGS)
+    } else {
+        (log::Level::Debug, &INFOS)
+    };
+    counter.fetch_add(1, Ordering::Relaxed);
+
+    let Some(data) = data.as_ref() else {
+        return vk::FALSE;
+    };
+    let id = c_str(data.p_message_id_name).unwrap_or("-");
+    let msg = c_str(data.p_message).unwrap_or("");
+
+    // Objects named through `VulkanRenderer::set_object_name` show up by name.
+    let mut objects = String::new();
+    if !data.p_objects.is_null() {
+        let objs = std::slice::from_raw_parts(data.p_objects, data.object_count as usize);
+        for o in objs {
+            if let Some(name) = c_str(o.p_object_name) {
+                if !objects.is_empty() {
+                    objects.push_str(", ");
+                }
+                objects.push_str(&format!("{:?} '{name}'", o.object_type));
+            }
+        }
+    }
+
+    let kind = if types.contains(vk::DebugUtilsMessageTypeFlagsEXT::PERFORMANCE) {
+        "perf"
+    } else if types.contains(vk::DebugUtilsMessageTypeFlagsEXT::VALIDATION) {
+        "validation"
+    } else {
+        "general"
+    };
+    if objects.is_empty() {
+        log::log!(target: LOG_TARGET, level, "[{kind}] {id}: {msg}");
+    } else {
+        log::log!(target: LOG_TARGET, level, "[{kind}] {id}: {msg} (objects: {objects})");
+    }
+
+    // Never abort the call that triggered the message.
+    vk::FALSE
+}
@@ -0,0 +1,190 @@
+use ash::vk;
+use ash::Device;
+
+use super::renderer::FRAMES_IN_FLIGHT;
+
+/// Sets in the first pool of a shape; each further pool doubles, up to `CHUNK_MAX_SETS`.
+const CHUNK_MIN_SETS: u32 = 16;
+const CHUNK_MAX_SETS: u32 = 1024;
+
+/// A released set may still be bound by a frame in flight; it is handed out again only after
+/// this many frames.
+const RECYCLE_DELAY: u64 = FRAMES_IN_FLIGHT as u64 + 1;
+
+/// Index of a descriptor shape inside [`DescriptorAllocator`].
+pub(crate) type ShapeId = usize;
+
+struct Chunk {
+    pool: vk::DescriptorPool,
+    capacity: u32,
+    allocated: u32,
+}
+
+/// Pools for layouts with identical bindings. Such layouts are compatible, so a set released by
+/// one can be reused for any other.
+struct Shape {
+    types: Vec<vk::DescriptorType>,
+    chunks: Vec<Chunk>,
+    /// Released sets and the frame they were released in.
+    free: Vec<(vk::DescriptorSet, u64)>,
+    live: u32,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct DescriptorStats {
+    pub pools: u64,
+    /// Sets all pools can hold.
+    pub capacity: u64,
+    pub live: u64,
+    /// Released sets waiting for reuse.
+    pub free: u64,
+    pub allocations: u64,
+    /// Allocations served from the free list.
+    pub recycled: u64,
+}
+
+/// Descriptor sets for bind groups, carved out of growable pools shared per binding shape.
+/// Sets are never returned to their pool; destroyed bind groups feed a free list instead.
+#[derive(Default)]
+pub(crate) struct DescriptorAllocator {
+    shapes: Vec<Shape>,
+    frame: u64,
+    allocations: u64,
+    recycled: u64,
+}
+
+impl DescriptorAllocator {
+    /// Call once per frame; ages released sets towards reuse.
+    #[inline]
+    pub(crate) fn advance_frame(&mut self) {
+        self.frame += 1;
+    }
+
+    /// The shape for a layout with these binding types (in binding order).
+    pub(crate) fn shape(&mut self, types: &[vk::DescriptorType]) -> ShapeId {
+        if let Some(i) = self.shapes.iter().position(|s| s.types == types) {
+            return i;
+        }
+        self.shapes.push(Shape {
+            types: types.to_vec(),
+            chunks: Vec::new(),
+            free: Vec::new(),
+            live: 0,
+        });
+        self.shapes.len() - 1
+    }
+
+    pub(crate) unsafe fn allocate(
+        &mut self,
+        device: &Device,
+        shape: ShapeId,
+        layout: vk::DescriptorSetLayout,
+    ) -> Result<vk::DescriptorSet, vk::Result> {
+        let frame = self.frame;
+        let s = &mut self.shapes[shape];
+
+        if let Some(i) = s
+            .free
+            .iter()
+            .position(|&(_, at)| at + RECYCLE_DELAY <= frame)
+        {
+            let (set, _) = s.free.swap_remove(i);
+            s.live += 1;
+            self.allocations += 1;
+            self.recycled += 1;
+            return Ok(set);
+        }
+
+        let has_room = s.chunks.last().is_some_and(|c| c.allocated < c.capacity);
+        if !has_room {
+            let capacity = s
+                .chunks
+                .last()
+                .map_or(CHUNK_MIN_SETS, |c| (c.capacity * 2).min(CHUNK_MAX_SETS));
+            let pool = create_pool(device, &s.types, capacity)?;
+            s.chunks.push(Chunk {
+                pool,
+                capacity,
+                allocated: 0,
+            });
+        }
+
+        let Some(chunk) = s.chunks.last_mut() else {
+            return Err(vk::Result::ERROR_OUT_OF_POOL_MEMORY);
+        };
+        let set_layouts = [layout];
+        let alloc = vk::DescriptorSetAllocateInfo::default()
+            .descriptor_pool(chunk.pool)
+            .set_layouts(&set_layouts);
+        let set = device.allocate_descriptor_sets(&alloc)?[0];
+
+        chunk.allocated += 1;
+        s.live += 1;
+        self.allocations += 1;
+        Ok(set)
+    }
+
+    /// Returns `set` to the free list of `shape`.
+    pub(crate) fn release(&mut self, shape: ShapeId, set: vk::DescriptorSet) {
+        let frame = self.frame;
+        if let Some(s) = self.shapes.get_mut(shape) {
+            s.live = s.live.saturating_sub(1);
+            s.free.push((set, frame));
+        }
+    }
+
+    pub(crate) fn stats(&self) -> DescriptorStats {
+        let mut st = DescriptorStats {
+            allocations: self.allocations,
+            recycled: self.recycled,
+            ..Default::default()
+        };
+        for s in &self.shapes {
+            st.pools += s.chunks.len() as u64;
+            st.capacity += s.chunks.iter().map(|c| c.capacity as u64).sum::<u64>();
+            st.live += s.live as u64;
+            st.free += s.free.len() as u64;
+        }
+        st
+    }
+
+    /// Destroys every pool, which frees all sets allocated from them.
+    pub(crate) unsafe fn destroy(&mut self, device: &Device) {
+        for s in self.shapes.drain(..) {
+            for c in s.chunks {
+                device.destroy_descriptor_pool(c.pool, None);
+            }
+        }
+    }
+}
+
+unsafe fn create_pool(
+    device: &Device,
+    types: &[vk::DescriptorType],
+    sets: u32,
+) -> Result<vk::DescriptorPool, vk::Result> {
+    let mut sizes: Vec<vk::DescriptorPoolSize> = Vec::new();
+    for &ty in types {
+        match sizes.iter_mut().find(|p| p.ty == ty) {
+            Some(p) => p.descriptor_count += sets,
+            None => sizes.push(
+                vk::DescriptorPoolSize::default()
+                    .ty(ty)
+                    .descriptor_count(sets),
+            ),
+        }
+    }
+    // Layouts without bindings still need a non-empty pool.
+    if sizes.is_empty() {
+        sizes.push(
+            vk::DescriptorPoolSize::default()
+                .ty(vk::DescriptorType::UNIFORM_BUFFER)
+                .descriptor_count(1),
+        );
+    }
+
+    let ci = vk::DescriptorPoolCreateInfo::default()
+        .max_sets(sets)
+        .pool_sizes(&sizes);
+    device.create_descriptor_pool(&ci, None)
+}
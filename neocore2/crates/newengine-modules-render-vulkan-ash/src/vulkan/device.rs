@@ -78,28 +78,79 @@ pub(super) fn pick_physical_device(
     ))
 }
 
+/// A transfer-only queue family (usually a DMA engine) that can upload while the graphics queue
+/// renders. `None` when the device exposes no such family.
+pub(super) fn find_transfer_queue_family(
+    instance: &Instance,
+    physical_device: vk::PhysicalDevice,
+) -> Option<u32> {
+    let qprops = unsafe { instance.get_physical_device_queue_family_properties(physical_device) };
+    let transfer_only = |q: &vk::QueueFamilyProperties, no_compute: bool| {
+        q.queue_count > 0
+            && q.queue_flags.contains(vk::QueueFlags::TRANSFER)
+            && !q.queue_flags.contains(vk::QueueFlags::GRAPHICS)
+            && (!no_compute || !q.queue_flags.contains(vk::QueueFlags::COMPUTE))
+    };
+
+    // Prefer a pure copy family over an async compute one.
+    qprops
+        .iter()
+        .position(|q| transfer_only(q, true))
+        .or_else(|| qprops.iter().position(|q| transfer_only(q, false)))
+        .map(|i| i as u32)
+}
+
+/// Optional features enabled when the device has them; callers check the returned flags.
+pub(super) fn optional_features(
+    instance: &Instance,
+    physical_device: vk::PhysicalDevice,
+) -> vk::PhysicalDeviceFeatures {
+    let supported = unsafe { instance.get_physical_device_features(physical_device) };
+    vk::PhysicalDeviceFeatures::default()
+        .multi_draw_indirect(supported.multi_draw_indirect == vk::TRUE)
+        .draw_indirect_first_instance(supported.draw_indirect_first_instance == vk::TRUE)
+        .fill_mode_non_solid(supported.fill_mode_non_solid == vk::TRUE)
+}
+
+/// Creates the device with a graphics queue and, when `transfer_family_index` is set, a queue
+/// from that family. Returns `(device, graphics queue, transfer queue)`; without a transfer
+/// family both queues are the same.
 pub(super) fn create_device(
     instance: &Instance,
     physical_device: vk::PhysicalDevice,
     queue_family_index: u32,
-) -> VkResult<(Device, vk::Queue)> {
+    transfer_family_index: Option<u32>,
+    features: &vk::PhysicalDeviceFeatures,
+) -> VkResult<(Device, vk::Queue, vk::Queue)> {
     let queue_priorities = [1.0f32];
 
-    let queue_info = vk::DeviceQueueCreateInfo::default()
+    let mut queue_infos = vec![vk::DeviceQueueCreateInfo::default()
         .queue_family_index(queue_family_index)
-        .queue_priorities(&queue_priorities);
+        .queue_priorities(&queue_priorities)];
+    if let Some(family) = transfer_family_index {
+        queue_infos.push(
+            vk::DeviceQueueCreateInfo::default()
+                .queue_family_index(family)
+                .queue_priorities(&queue_priorities),
+        );
+    }
 
     // Enable required device extensions.
     let device_extensions = [ash::khr::swapchain::NAME.as_ptr()];
 
     let device_info = vk::DeviceCreateInfo::default()
-        .queue_create_infos(std::slice::from_ref(&queue_info))
-        .enabled_extension_names(&device_extensions);
+        .queue_create_infos(&queue_infos)
+        .enabled_extension_names(&device_extensions)
+        .enabled_features(features);
 
     let device = unsafe { instance.create_device(physical_device, &device_info, None)? };
     let queue = unsafe { device.get_device_queue(queue_family_index, 0) };
+    let transfer_queue = match transfer_family_index {
+        Some(family) => unsafe { device.get_device_queue(family, 0) },
+        None => queue,
+    };
 
-    Ok((device, queue))
+    Ok((device, queue, transfer_queue))
 }
 
 pub(super) fn find_memory_type(
@@ -0,0 +1,356 @@
+use std::collections::HashMap;
+
+use ab_glyph::{point, Font, FontArc, GlyphId, PxScale, ScaleFont};
+use newengine_core::render::TextDraw;
+
+use super::text::glyph8x8;
+
+/// Side of the square R8 glyph atlas.
+pub(crate) const ATLAS_SIZE: u32 = 1024;
+
+/// Pixel size glyphs are baked at; draws at other sizes scale the quads.
+const BAKE_PX: f32 = 32.0;
+/// Distance in baked pixels the field covers on each side of an outline.
+const SPREAD: i32 = 4;
+/// Built-in 8x8 glyphs are upscaled by this before the distance transform.
+const BUILTIN_SCALE: usize = 4;
+/// Empty texels between packed glyphs.
+const GUTTER: u32 = 1;
+/// Side of the fully inside block at the atlas origin, sampled by untextured quads.
+const SOLID: u32 = 4;
+
+enum FontFace {
+    /// The 8x8 ASCII bitmap font, always at index 0.
+    Builtin,
+    Outline(FontArc),
+}
+
+impl FontFace {
+    /// (ascent, line height) in baked pixels.
+    fn metrics(&self) -> (f32, f32) {
+        match self {
+            // Matches the old bitmap overlay: 8 px cells on a 10 px line at size 8.
+            FontFace::Builtin => (BAKE_PX, BAKE_PX * 1.25),
+            FontFace::Outline(font) => {
+                let f = font.as_scaled(PxScale::from(BAKE_PX));
+                (f.ascent(), f.ascent() - f.descent() + f.line_gap())
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+struct GlyphSlot {
+    /// x, y, width, height in atlas texels.
+    rect: [u32; 4],
+    /// Top-left of `rect` relative to the pen on the baseline, in baked pixels.
+    offset: [f32; 2],
+}
+
+/// One glyph of a laid out [`TextDraw`]: pixel rect (y down) and atlas uv rect.
+pub(crate) struct GlyphQuad {
+    pub(crate) min: [f32; 2],
+    pub(crate) max: [f32; 2],
+    pub(crate) uv_min: [f32; 2],
+    pub(crate) uv_max: [f32; 2],
+}
+
+/// Signed distance field glyphs of every loaded font, baked on first use into one R8 atlas.
+///
+/// Texels hold 0.5 on the outline, rising inside the glyph, so one bake stays sharp at any
+/// draw size. Glyphs are packed in shelves; once the atlas is full it is cleared and refilled
+/// from the glyphs the next frame uses.
+pub(crate) struct GlyphAtlas {
+    pixels: Vec<u8>,
+    faces: Vec<FontFace>,
+    /// `None`: the glyph has no pixels (e.g. a space) or did not fit.
+    glyphs: HashMap<(u32, char), Option<GlyphSlot>>,
+    cursor: [u32; 2],
+    row_height: u32,
+    full: bool,
+    /// Texels changed since the last upload: x0, y0, x1, y1.
+    dirty: Option<[u32; 4]>,
+}
+
+impl GlyphAtlas {
+    pub(crate) fn new() -> Self {
+        let mut atlas = Self {
+            pixels: vec![0; (ATLAS_SIZE * ATLAS_SIZE) as usize],
+            faces: vec![FontFace::Builtin],
+            glyphs: HashMap::new(),
+            cursor: [0, 0],
+            row_height: 0,
+            full: false,
+            dirty: None,
+        };
+        atlas.clear();
+        atlas
+    }
+
+    /// Empties the atlas down to the solid block.
+    fn clear(&mut self) {
+        self.glyphs.clear();
+        self.pixels.fill(0);
+        for row in 0..SOLID {
+            let start = (row * ATLAS_SIZE) as usize;
+            self.pixels[start..start + SOLID as usize].fill(255);
+        }
+        self.cursor = [SOLID + GUTTER, 0];
+        self.row_height = SOLID + GUTTER;
+        self.dirty = Some([0, 0, ATLAS_SIZE, ATLAS_SIZE]);
+    }
+
+    /// Uv of a texel inside every outline: quads sampling it come out solid.
+    #[inline]
+    pub(crate) fn solid_uv(&self) -> [f32; 2] {
+        let c = SOLID as f32 * 0.5 / ATLAS_SIZE as f32;
+        [c, c]
+    }
+
+    /// Parses a TrueType/OpenType font; returns its index (a `FontId` value).
+    pub(crate) fn add_font(&mut self, data: Vec<u8>) -> Result<u32, String> {
+        let font = FontArc::try_from_vec(data).map_err(|e| e.to_string())?;
+        self.faces.push(FontFace::Outline(font));
+        Ok((self.faces.len() - 1) as u32)
+    }
+
+    #[inline]
+    pub(crate) fn has_font(&self, index: u32) -> bool {
+        (index as usize) < self.faces.len()
+    }
+
+    /// Atlas texels; upload the rect returned by [`GlyphAtlas::take_dirty`].
+    #[inline]
+    pub(crate) fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    #[inline]
+    pub(crate) fn take_dirty(&mut self) -> Option<[u32; 4]> {
+        self.dirty.take()
+    }
+
+    /// Call before laying out a frame's text. Clears the atlas if it filled up last frame.
+    pub(crate) fn begin_frame(&mut self) {
+        if !std::mem::take(&mut self.full) {
+            return;
+        }
+        log::debug!(
+            "text: glyph atlas full, evicting {} glyphs",
+            self.glyphs.len()
+        );
+        self.clear();
+    }
+
+    /// Appends the quads of `draw`, baking glyphs it uses for the first time.
+    pub(crate) fn layout(&mut self, draw: &TextDraw, out: &mut Vec<GlyphQuad>) {
+        let font = draw
+            .font
+            .map(|f| f.get())
+            .filter(|&i| self.has_font(i))
+            .unwrap_or(0);
+        let scale = draw.size_px / BAKE_PX;
+        let (ascent, line_height) = self.faces[font as usize].metrics();
+
+        let mut x = draw.pos[0];
+        let mut baseline = draw.pos[1] + ascent * scale;
+        let mut prev: Option<GlyphId> = None;
+
+        for ch in draw.text.chars() {
+            if ch == '\n' {
+                x = draw.pos[0];
+                baseline += line_height * scale;
+                prev = None;
+                continue;
+            }
+
+            let advance = match &self.faces[font as usize] {
+                FontFace::Builtin => BAKE_PX,
+                FontFace::Outline(f) => {
+                    let f = f.as_scaled(PxScale::from(BAKE_PX));
+                    let id = f.glyph_id(ch);
+                    if let Some(p) = prev {
+                        x += f.kern(p, id) * scale;
+                    }
+                    prev = Some(id);
+                    f.h_advance(id)
+                }
+            };
+
+            if let Some(slot) = self.glyph(font, ch) {
+                let [rx, ry, rw, rh] = slot.rect;
+                let min = [
+                    x + slot.offset[0] * scale,
+                    baseline + slot.offset[1] * scale,
+                ];
+                let inv = 1.0 / ATLAS_SIZE as f32;
+                out.push(GlyphQuad {
+                    min,
+                    max: [min[0] + rw as f32 * scale, min[1] + rh as f32 * scale],
+                    uv_min: [rx as f32 * inv, ry as f32 * inv],
+                    uv_max: [(rx + rw) as f32 * inv, (ry + rh) as f32 * inv],
+                });
+            }
+            x += advance * scale;
+        }
+    }
+
+    fn glyph(&mut self, font: u32, ch: char) -> Option<GlyphSlot> {
+        if let Some(slot) = self.glyphs.get(&(font, ch)) {
+            return *slot;
+        }
+        if self.full {
+            return None;
+        }
+
+        let slot = match bake(&self.faces[font as usize], ch) {
+            Some(baked) => {
+                let slot = self.insert(&baked);
+                if slot.is_none() {
+                    // Not cached: the glyph is retried after the atlas is cleared.
+                    self.full = true;
+                    return None;
+                }
+                slot
+            }
+            None => None,
+        };
+        self.glyphs.insert((font, ch), slot);
+        slot
+    }
+
+    fn insert(&mut self, baked: &BakedGlyph) -> Option<GlyphSlot> {
+        let (w, h) = (baked.width as u32, baked.height as u32);
+        if w + GUTTER > ATLAS_SIZE || h + GUTTER > ATLAS_SIZE {
+            return None;
+        }
+        if self.cursor[0] + w + GUTTER > ATLAS_SIZE {
+            self.cursor = [0, self.cursor[1] + self.row_height];
+            self.row_height = 0;
+        }
+        if self.cursor[1] + h + GUTTER > ATLAS_SIZE {
+            return None;
+        }
+
+        let [x, y] = self.cursor;
+        self.cursor[0] += w + GUTTER;
+        self.row_height = self.row_height.max(h + GUTTER);
+
+        for row in 0..h {
+            let src = (row * w) as usize;
+            let dst = ((y + row) * ATLAS_SIZE + x) as usize;
+            self.pixels[dst..dst + w as usize].copy_from_slice(&baked.field[src..src + w as usize]);
+        }
+
+        let d = self.dirty.get_or_insert([x, y, x + w, y + h]);
+        *d = [d[0].min(x), d[1].min(y), d[2].max(x + w), d[3].max(y + h)];
+
+        Some(GlyphSlot {
+            rect: [x, y, w, h],
+            offset: baked.offset,
+        })
+    }
+}
+
+struct BakedGlyph {
+    field: Vec<u8>,
+    width: usize,
+    height: usize,
+    offset: [f32; 2],
+}
+
+/// Rasterizes `ch` at [`BAKE_PX`] and turns its coverage into a distance field; `None` for
+/// glyphs without pixels.
+fn bake(face: &FontFace, ch: char) -> Option<BakedGlyph> {
+    let (coverage, w, h, offset) = match face {
+        FontFace::Builtin => {
+            let ch = if ch.is_ascii_graphic() {
+                ch as u8
+            } else {
+                b'?'
+            };
+            let bits = glyph8x8(ch);
+            if bits.iter().all(|&b| b == 0) {
+                return None;
+            }
+            let n = 8 * BUILTIN_SCALE;
+            let mut cov = vec![0.0f32; n * n];
+            for (y, c) in cov.chunks_mut(n).enumerate() {
+                let row = bits[y / BUILTIN_SCALE];
+                for (x, v) in c.iter_mut().enumerate() {
+                    if row & (1u8 << (x / BUILTIN_SCALE)) != 0 {
+                        *v = 1.0;
+                    }
+                }
+            }
+            (cov, n, n, [0.0, -BAKE_PX])
+        }
+        FontFace::Outline(font) => {
+            let glyph = font
+                .glyph_id(ch)
+                .with_scale_and_position(PxScale::from(BAKE_PX), point(0.0, 0.0));
+            let outlined = font.outline_glyph(glyph)?;
+            let b = outlined.px_bounds();
+            let (w, h) = (b.width() as usize, b.height() as usize);
+            if w == 0 || h == 0 {
+                return None;
+            }
+            let mut cov = vec![0.0f32; w * h];
+            outlined.draw(|x, y, c| {
+                if let Some(v) = cov.get_mut(y as usize * w + x as usize) {
+                    *v = c;
+                }
+            });
+            (cov, w, h, [b.min.x, b.min.y])
+        }
+    };
+
+    let pad = SPREAD as usize;
+    let (pw, ph) = (w + 2 * pad, h + 2 * pad);
+    let at = |x: i32, y: i32| -> f32 {
+        let (x, y) = (x - SPREAD, y - SPREAD);
+        if x < 0 || y < 0 || x >= w as i32 || y >= h as i32 {
+            0.0
+        } else {
+            coverage[y as usize * w + x as usize]
+        }
+    };
+
+    let mut field = vec![0u8; pw * ph];
+    for y in 0..ph as i32 {
+        for x in 0..pw as i32 {
+            let c = at(x, y);
+            let inside = c >= 0.5;
+
+            // Partially covered texels sit on the outline; their coverage is the best estimate.
+            let d = if c > 0.0 && c < 1.0 {
+                c - 0.5
+            } else {
+                let mut best = (SPREAD * SPREAD) as f32;
+                for dy in -SPREAD..=SPREAD {
+                    for dx in -SPREAD..=SPREAD {
+                        if (at(x + dx, y + dy) >= 0.5) != inside {
+                            best = best.min((dx * dx + dy * dy) as f32);
+                        }
+                    }
+                }
+                let d = (best.sqrt() - 0.5).min(SPREAD as f32);
+                if inside {
+                    d
+                } else {
+                    -d
+                }
+            };
+
+            let v = 0.5 + d / (2.0 * SPREAD as f32);
+            field[y as usize * pw + x as usize] = (v.clamp(0.0, 1.0) * 255.0).round() as u8;
+        }
+    }
+
+    Some(BakedGlyph {
+        field,
+        width: pw,
+        height: ph,
+        offset: [offset[0] - SPREAD as f32, offset[1] - SPREAD as f32],
+    })
+}
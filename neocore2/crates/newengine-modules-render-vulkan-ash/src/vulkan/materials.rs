@@ -0,0 +1,34 @@
+use newengine_core::render::{DefaultMaterial, VertexDeformation};
+
+/// Precompiled SPIR-V of the default material set (see build.rs), as (vertex, fragment).
+pub(crate) fn default_material_spirv(
+    material: DefaultMaterial,
+    deformation: VertexDeformation,
+) -> (&'static [u8], &'static [u8]) {
+    let fs: &'static [u8] = match material {
+        DefaultMaterial::Lit => include_bytes!(concat!(env!("OUT_DIR"), "/mesh.frag.spv")),
+        DefaultMaterial::TerrainSplat => {
+            include_bytes!(concat!(env!("OUT_DIR"), "/terrain_splat.frag.spv"))
+        }
+        DefaultMaterial::Depth => include_bytes!(concat!(env!("OUT_DIR"), "/depth.frag.spv")),
+        DefaultMaterial::ObjectId => {
+            include_bytes!(concat!(env!("OUT_DIR"), "/object_id.frag.spv"))
+        }
+        // No mesh vertex path; the skybox makes its own triangle.
+        DefaultMaterial::Skybox => {
+            return (
+                include_bytes!(concat!(env!("OUT_DIR"), "/skybox.vert.spv")),
+                include_bytes!(concat!(env!("OUT_DIR"), "/skybox.frag.spv")),
+            );
+        }
+    };
+
+    let vs: &'static [u8] = match (deformation.skinning, deformation.morph_targets) {
+        (false, false) => include_bytes!(concat!(env!("OUT_DIR"), "/mesh.vert.spv")),
+        (true, false) => include_bytes!(concat!(env!("OUT_DIR"), "/mesh_skin.vert.spv")),
+        (false, true) => include_bytes!(concat!(env!("OUT_DIR"), "/mesh_morph.vert.spv")),
+        (true, true) => include_bytes!(concat!(env!("OUT_DIR"), "/mesh_skin_morph.vert.spv")),
+    };
+
+    (vs, fs)
+}
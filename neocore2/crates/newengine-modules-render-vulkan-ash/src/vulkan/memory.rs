@@ -0,0 +1,230 @@
+use ash::vk;
+use ash::Device;
+
+/// Size of each shared `vkAllocateMemory` block.
+const BLOCK_SIZE: vk::DeviceSize = 64 << 20;
+/// Requests at least this large get their own allocation instead of a block range.
+const DEDICATED_MIN: vk::DeviceSize = BLOCK_SIZE / 2;
+
+/// A range of device memory backing one buffer.
+#[derive(Clone, Copy)]
+pub(crate) struct Allocation {
+    pub memory: vk::DeviceMemory,
+    pub offset: vk::DeviceSize,
+    pub size: vk::DeviceSize,
+    dedicated: bool,
+}
+
+struct Block {
+    memory: vk::DeviceMemory,
+    memory_type: u32,
+    size: vk::DeviceSize,
+    /// Free ranges `(offset, size)`, sorted by offset; neighbours are always merged.
+    free: Vec<(vk::DeviceSize, vk::DeviceSize)>,
+    allocations: u32,
+}
+
+impl Block {
+    /// First fit; the alignment padding stays in the free list.
+    fn take(&mut self, size: vk::DeviceSize, align: vk::DeviceSize) -> Option<vk::DeviceSize> {
+        let align = align.max(1);
+        for i in 0..self.free.len() {
+            let (off, len) = self.free[i];
+            let start = off.next_multiple_of(align);
+            let pad = start - off;
+            if pad + size > len {
+                continue;
+            }
+
+            let end = start + size;
+            let tail = off + len - end;
+            match (pad > 0, tail > 0) {
+                (false, false) => {
+                    self.free.remove(i);
+                }
+                (true, false) => self.free[i] = (off, pad),
+                (false, true) => self.free[i] = (end, tail),
+                (true, true) => {
+                    self.free[i] = (off, pad);
+                    self.free.insert(i + 1, (end, tail));
+                }
+            }
+            self.allocations += 1;
+            return Some(start);
+        }
+        None
+    }
+
+    fn give(&mut self, offset: vk::DeviceSize, size: vk::DeviceSize) {
+        let i = self.free.partition_point(|&(o, _)| o < offset);
+        self.free.insert(i, (offset, size));
+
+        // Merge with the following range, then with the preceding one.
+        if i + 1 < self.free.len() && offset + size == self.free[i + 1].0 {
+            self.free[i].1 += self.free[i + 1].1;
+            self.free.remove(i + 1);
+        }
+        if i > 0 && self.free[i - 1].0 + self.free[i - 1].1 == offset {
+            self.free[i - 1].1 += self.free[i].1;
+            self.free.remove(i);
+        }
+        self.allocations = self.allocations.saturating_sub(1);
+    }
+
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.allocations == 0
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct MemoryStats {
+    pub blocks: u64,
+    pub block_bytes: u64,
+    /// Bytes handed out from blocks, alignment padding excluded.
+    pub used_bytes: u64,
+    pub suballocations: u64,
+    pub dedicated: u64,
+    pub dedicated_bytes: u64,
+    pub free_ranges: u64,
+    pub largest_free: u64,
+    /// Share of free block memory outside the largest free range, in percent.
+    pub fragmentation_pct: u64,
+}
+
+/// Buffer memory sub-allocated from large blocks per memory type, so each buffer does not cost
+/// a `vkAllocateMemory` (drivers cap the number of live allocations).
+#[derive(Default)]
+pub(crate) struct MemoryAllocator {
+    blocks: Vec<Block>,
+    used_bytes: u64,
+    dedicated: u64,
+    dedicated_bytes: u64,
+}
+
+impl MemoryAllocator {
+    pub(crate) unsafe fn allocate(
+        &mut self,
+        device: &Device,
+        memory_type: u32,
+        req: vk::MemoryRequirements,
+    ) -> Result<Allocation, vk::Result> {
+        if req.size >= DEDICATED_MIN {
+            let memory = allocate_memory(device, memory_type, req.size)?;
+            self.dedicated += 1;
+            self.dedicated_bytes += req.size;
+            return Ok(Allocation {
+                memory,
+                offset: 0,
+                size: req.size,
+                dedicated: true,
+            });
+        }
+
+        for b in self
+            .blocks
+            .iter_mut()
+            .filter(|b| b.memory_type == memory_type)
+        {
+            if let Some(offset) = b.take(req.size, req.alignment) {
+                self.used_bytes += req.size;
+                return Ok(Allocation {
+                    memory: b.memory,
+                    offset,
+                    size: req.size,
+                    dedicated: false,
+                });
+            }
+        }
+
+        let memory = allocate_memory(device, memory_type, BLOCK_SIZE)?;
+        let mut block = Block {
+            memory,
+            memory_type,
+            size: BLOCK_SIZE,
+            free: vec![(0, BLOCK_SIZE)],
+            allocations: 0,
+        };
+        let offset = block.take(req.size, req.alignment).unwrap_or_default();
+        self.blocks.push(block);
+        self.used_bytes += req.size;
+        Ok(Allocation {
+            memory,
+            offset,
+            size: req.size,
+            dedicated: false,
+        })
+    }
+
+    /// Returns `a` to its block. Empty blocks are released, except the last one of a memory
+    /// type, which is kept to absorb create/destroy churn.
+    pub(crate) unsafe fn free(&mut self, device: &Device, a: Allocation) {
+        if a.dedicated {
+            device.free_memory(a.memory, None);
+            self.dedicated = self.dedicated.saturating_sub(1);
+            self.dedicated_bytes = self.dedicated_bytes.saturating_sub(a.size);
+            return;
+        }
+
+        let Some(i) = self.blocks.iter().position(|b| b.memory == a.memory) else {
+            log::warn!("vulkan: freeing memory that belongs to no block");
+            return;
+        };
+        self.blocks[i].give(a.offset, a.size);
+        self.used_bytes = self.used_bytes.saturating_sub(a.size);
+
+        let memory_type = self.blocks[i].memory_type;
+        let same_type = self
+            .blocks
+            .iter()
+            .filter(|b| b.memory_type == memory_type)
+            .count();
+        if self.blocks[i].is_empty() && same_type > 1 {
+            let b = self.blocks.swap_remove(i);
+            device.free_memory(b.memory, None);
+        }
+    }
+
+    pub(crate) fn stats(&self) -> MemoryStats {
+        let mut st = MemoryStats {
+            blocks: self.blocks.len() as u64,
+            used_bytes: self.used_bytes,
+            dedicated: self.dedicated,
+            dedicated_bytes: self.dedicated_bytes,
+            ..Default::default()
+        };
+
+        let mut free_bytes = 0u64;
+        for b in &self.blocks {
+            st.block_bytes += b.size;
+            st.suballocations += b.allocations as u64;
+            st.free_ranges += b.free.len() as u64;
+            for &(_, len) in &b.free {
+                free_bytes += len;
+                st.largest_free = st.largest_free.max(len);
+            }
+        }
+        if free_bytes > 0 {
+            st.fragmentation_pct = (free_bytes - st.largest_free) * 100 / free_bytes;
+        }
+        st
+    }
+
+    /// Frees every block. Buffers bound to them must be destroyed first.
+    pub(crate) unsafe fn destroy(&mut self, device: &Device) {
+        for b in self.blocks.drain(..) {
+            device.free_memory(b.memory, None);
+        }
+    }
+}
+
+unsafe fn allocate_memory(
+    device: &Device,
+    memory_type: u32,
+    size: vk::DeviceSize,
+) -> Result<vk::DeviceMemory, vk::Result> {
+    let info = vk::MemoryAllocateInfo::default()
+        .allocation_size(size)
+        .memory_type_index(memory_type);
+    device.allocate_memory(&info, None)
+}
@@ -1,10 +1,21 @@
+mod debug_draw;
+pub(crate) mod debug_utils;
+pub(crate) mod descriptors;
 mod device;
+mod font;
 mod instance;
+pub(crate) mod materials;
+pub(crate) mod memory;
 pub(crate) mod pipeline;
+mod pipeline_cache;
+mod post;
 mod resources;
 mod swapchain;
 mod text;
+pub(crate) mod textures;
+pub(crate) mod transient;
 mod ui;
+mod upload;
 pub(crate) mod util;
 
 pub mod renderer;
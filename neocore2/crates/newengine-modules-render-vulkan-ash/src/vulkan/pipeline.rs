@@ -36,6 +36,123 @@ pub(super) unsafe fn create_render_pass(device: &Device, format: vk::Format) ->
     Ok(device.create_render_pass(&rp, None)?)
 }
 
+/// Depth-only pass of shadow maps: clears, and leaves the image ready for sampling.
+pub(super) unsafe fn create_depth_render_pass(device: &Device, format: vk::Format) -> VkResult<vk::RenderPass> {
+    let depth = vk::AttachmentDescription::default()
+        .format(format)
+        .samples(vk::SampleCountFlags::TYPE_1)
+        .load_op(vk::AttachmentLoadOp::CLEAR)
+        .store_op(vk::AttachmentStoreOp::STORE)
+        .stencil_load_op(vk::AttachmentLoadOp::DONT_CARE)
+        .stencil_store_op(vk::AttachmentStoreOp::DONT_CARE)
+        .initial_layout(vk::ImageLayout::UNDEFINED)
+        .final_layout(vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL);
+
+    let depth_ref = vk::AttachmentReference::default()
+        .attachment(0)
+        .layout(vk::ImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL);
+
+    let subpass = vk::SubpassDescription::default()
+        .pipeline_bind_point(vk::PipelineBindPoint::GRAPHICS)
+        .depth_stencil_attachment(&depth_ref);
+
+    let tests = vk::PipelineStageFlags::EARLY_FRAGMENT_TESTS | vk::PipelineStageFlags::LATE_FRAGMENT_TESTS;
+    let deps = [
+        // Reads of the previous frame finish before the clear.
+        vk::SubpassDependency::default()
+            .src_subpass(vk::SUBPASS_EXTERNAL)
+            .dst_subpass(0)
+            .src_stage_mask(vk::PipelineStageFlags::FRAGMENT_SHADER)
+            .src_access_mask(vk::AccessFlags::SHADER_READ)
+            .dst_stage_mask(tests)
+            .dst_access_mask(vk::AccessFlags::DEPTH_STENCIL_ATTACHMENT_WRITE),
+        // Depth is written before the main pass samples it.
+        vk::SubpassDependency::default()
+            .src_subpass(0)
+            .dst_subpass(vk::SUBPASS_EXTERNAL)
+            .src_stage_mask(tests)
+            .src_access_mask(vk::AccessFlags::DEPTH_STENCIL_ATTACHMENT_WRITE)
+            .dst_stage_mask(vk::PipelineStageFlags::FRAGMENT_SHADER)
+            .dst_access_mask(vk::AccessFlags::SHADER_READ),
+    ];
+
+    let rp = vk::RenderPassCreateInfo::default()
+        .attachments(std::slice::from_ref(&depth))
+        .subpasses(std::slice::from_ref(&subpass))
+        .dependencies(&deps);
+
+    Ok(device.create_render_pass(&rp, None)?)
+}
+
+/// Object-ID pass: a `color` id target left ready to be copied from, and a depth buffer of its
+/// own that is thrown away.
+pub(super) unsafe fn create_id_render_pass(
+    device: &Device,
+    color: vk::Format,
+    depth: vk::Format,
+) -> VkResult<vk::RenderPass> {
+    let attachments = [
+        vk::AttachmentDescription::default()
+            .format(color)
+            .samples(vk::SampleCountFlags::TYPE_1)
+            .load_op(vk::AttachmentLoadOp::CLEAR)
+            .store_op(vk::AttachmentStoreOp::STORE)
+            .initial_layout(vk::ImageLayout::UNDEFINED)
+            .final_layout(vk::ImageLayout::TRANSFER_SRC_OPTIMAL),
+        vk::AttachmentDescription::default()
+            .format(depth)
+            .samples(vk::SampleCountFlags::TYPE_1)
+            .load_op(vk::AttachmentLoadOp::CLEAR)
+            .store_op(vk::AttachmentStoreOp::DONT_CARE)
+            .stencil_load_op(vk::AttachmentLoadOp::DONT_CARE)
+            .stencil_store_op(vk::AttachmentStoreOp::DONT_CARE)
+            .initial_layout(vk::ImageLayout::UNDEFINED)
+            .final_layout(vk::ImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL),
+    ];
+
+    let color_ref = vk::AttachmentReference::default()
+        .attachment(0)
+        .layout(vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL);
+    let depth_ref = vk::AttachmentReference::default()
+        .attachment(1)
+        .layout(vk::ImageLayout::DEPTH_STENCIL_ATTACHMENT_OPTIMAL);
+
+    let subpass = vk::SubpassDescription::default()
+        .pipeline_bind_point(vk::PipelineBindPoint::GRAPHICS)
+        .color_attachments(std::slice::from_ref(&color_ref))
+        .depth_stencil_attachment(&depth_ref);
+
+    let stages = vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT
+        | vk::PipelineStageFlags::EARLY_FRAGMENT_TESTS
+        | vk::PipelineStageFlags::LATE_FRAGMENT_TESTS;
+    let writes = vk::AccessFlags::COLOR_ATTACHMENT_WRITE | vk::AccessFlags::DEPTH_STENCIL_ATTACHMENT_WRITE;
+    let deps = [
+        // The previous frame's pass and pick copy finish before the clears.
+        vk::SubpassDependency::default()
+            .src_subpass(vk::SUBPASS_EXTERNAL)
+            .dst_subpass(0)
+            .src_stage_mask(stages | vk::PipelineStageFlags::TRANSFER)
+            .src_access_mask(writes)
+            .dst_stage_mask(stages)
+            .dst_access_mask(writes),
+        // Ids are written before the pick copy reads them.
+        vk::SubpassDependency::default()
+            .src_subpass(0)
+            .dst_subpass(vk::SUBPASS_EXTERNAL)
+            .src_stage_mask(vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT)
+            .src_access_mask(vk::AccessFlags::COLOR_ATTACHMENT_WRITE)
+            .dst_stage_mask(vk::PipelineStageFlags::TRANSFER)
+            .dst_access_mask(vk::AccessFlags::TRANSFER_READ),
+    ];
+
+    let rp = vk::RenderPassCreateInfo::default()
+        .attachments(&attachments)
+        .subpasses(std::slice::from_ref(&subpass))
+        .dependencies(&deps);
+
+    Ok(device.create_render_pass(&rp, None)?)
+}
+
 pub(super) unsafe fn create_framebuffers(
     device: &Device,
     render_pass: vk::RenderPass,
@@ -67,6 +184,7 @@ pub(crate) unsafe fn create_shader_module(device: &Device, bytes: &[u8]) -> VkRe
 pub(super) unsafe fn create_pipeline(
     device: &Device,
     render_pass: vk::RenderPass,
+    cache: vk::PipelineCache,
 ) -> VkResult<(vk::PipelineLayout, vk::Pipeline)> {
     let vert = create_shader_module(
         device,
@@ -149,7 +267,7 @@ pub(super) unsafe fn create_pipeline(
         .render_pass(render_pass)
         .subpass(0);
 
-    let pipelines = device.create_graphics_pipelines(vk::PipelineCache::null(), &[gp], None);
+    let pipelines = device.create_graphics_pipelines(cache, &[gp], None);
     let pipeline = match pipelines {
         Ok(v) => v[0],
         Err((_, e)) => return Err(e.into()),
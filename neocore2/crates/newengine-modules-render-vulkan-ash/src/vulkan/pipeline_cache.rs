@@ -0,0 +1,101 @@
+use ash::vk;
+use ash::{Device, Instance};
+use std::path::{Path, PathBuf};
+
+/// `VkPipelineCacheHeaderVersionOne`: length, version, vendor id, device id, uuid.
+const HEADER_LEN: usize = 16 + vk::UUID_SIZE;
+
+/// Cache file for this GPU and driver. Data from another device or driver would be rejected by
+/// the driver anyway, so each combination gets its own file.
+pub(crate) fn cache_file(dir: &Path, props: &vk::PhysicalDeviceProperties) -> PathBuf {
+    let uuid: String = props
+        .pipeline_cache_uuid
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect();
+    dir.join(format!(
+        "pipelines-{:04x}-{:04x}-{:08x}-{uuid}.bin",
+        props.vendor_id, props.device_id, props.driver_version
+    ))
+}
+
+/// Checks the header of a saved cache against the device before handing it to the driver.
+fn header_matches(data: &[u8], props: &vk::PhysicalDeviceProperties) -> bool {
+    if data.len() < HEADER_LEN {
+        return false;
+    }
+    let u32_at = |i: usize| u32::from_le_bytes([data[i], data[i + 1], data[i + 2], data[i + 3]]);
+
+    u32_at(0) as usize >= HEADER_LEN
+        && u32_at(4) == vk::PipelineCacheHeaderVersion::ONE.as_raw() as u32
+        && u32_at(8) == props.vendor_id
+        && u32_at(12) == props.device_id
+        && data[16..HEADER_LEN] == props.pipeline_cache_uuid
+}
+
+/// Creates the pipeline cache, seeded from `dir` when a matching file exists. Returns the cache
+/// and the file to save it to (`None` when persistence is off).
+pub(crate) unsafe fn create_pipeline_cache(
+    instance: &Instance,
+    physical_device: vk::PhysicalDevice,
+    device: &Device,
+    dir: Option<&Path>,
+) -> (vk::PipelineCache, Option<PathBuf>) {
+    let props = instance.get_physical_device_properties(physical_device);
+    let path = dir.map(|d| cache_file(d, &props));
+
+    let initial = match path.as_deref().map(std::fs::read) {
+        Some(Ok(data)) if header_matches(&data, &props) => data,
+        Some(Ok(_)) => {
+            log::info!("vulkan: pipeline cache is from another device or driver; rebuilding");
+            Vec::new()
+        }
+        // Missing on first run.
+        Some(Err(_)) | None => Vec::new(),
+    };
+
+    let info = vk::PipelineCacheCreateInfo::default().initial_data(&initial);
+    match device.create_pipeline_cache(&info, None) {
+        Ok(cache) => {
+            if !initial.is_empty() {
+                log::info!("vulkan: pipeline cache loaded ({} bytes)", initial.len());
+            }
+            return (cache, path);
+        }
+        Err(e) if !initial.is_empty() => {
+            log::warn!("vulkan: pipeline cache rejected ({e:?}); starting empty");
+        }
+        Err(e) => {
+            log::warn!("vulkan: pipeline cache unavailable: {e:?}");
+            return (vk::PipelineCache::null(), None);
+        }
+    }
+
+    match device.create_pipeline_cache(&vk::PipelineCacheCreateInfo::default(), None) {
+        Ok(cache) => (cache, path),
+        Err(e) => {
+            log::warn!("vulkan: pipeline cache unavailable: {e:?}");
+            (vk::PipelineCache::null(), None)
+        }
+    }
+}
+
+/// Writes the cache contents to `path` through a temporary file, so a crash mid-write never
+/// leaves a truncated cache behind.
+pub(crate) unsafe fn save_pipeline_cache(
+    device: &Device,
+    cache: vk::PipelineCache,
+    path: &Path,
+) -> Result<usize, String> {
+    let data = device
+        .get_pipeline_cache_data(cache)
+        .map_err(|e| format!("{e:?}"))?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("{}: {e}", dir.display()))?;
+    }
+
+    let tmp = path.with_extension("bin.tmp");
+    std::fs::write(&tmp, &data).map_err(|e| format!("{}: {e}", tmp.display()))?;
+    std::fs::rename(&tmp, path).map_err(|e| format!("{}: {e}", path.display()))?;
+    Ok(data.len())
+}
@@ -0,0 +1,159 @@
+use crate::error::VkResult;
+
+use ash::vk;
+use newengine_core::render::PostProcessSettings;
+
+use super::{create_post_pipeline, PostImage, PostNode};
+use crate::vulkan::VulkanRenderer;
+
+/// Halvings of the scene size in the mip chain; fewer when the scene is smaller.
+const BLOOM_LEVELS: usize = 6;
+
+/// Bright parts of the scene, blurred over a chain of half-size mips and added back.
+///
+/// The scene is downsampled mip by mip (thresholded on the way into the first), then every
+/// mip is upsampled and blended onto the next larger one, and the first mip is added to the
+/// scene.
+pub(crate) struct BloomNode {
+    down: vk::Pipeline,
+    up: vk::Pipeline,
+    combine: vk::Pipeline,
+    levels: Vec<PostImage>,
+}
+
+impl BloomNode {
+    pub(crate) unsafe fn new(r: &VulkanRenderer) -> VkResult<Self> {
+        let device = &r.core.device;
+        let post = &r.post;
+        let cache = r.pipelines.cache;
+        let mut node = Self {
+            down: vk::Pipeline::null(),
+            up: vk::Pipeline::null(),
+            combine: vk::Pipeline::null(),
+            levels: Vec::new(),
+        };
+
+        let res = (|| -> VkResult<()> {
+            node.down = create_post_pipeline(
+                device,
+                post.target_pass,
+                post.layouts[0],
+                include_bytes!(concat!(env!("OUT_DIR"), "/bloom_down.frag.spv")),
+                false,
+                cache,
+            )?;
+            node.up = create_post_pipeline(
+                device,
+                post.blend_pass,
+                post.layouts[0],
+                include_bytes!(concat!(env!("OUT_DIR"), "/bloom_up.frag.spv")),
+                true,
+                cache,
+            )?;
+            node.combine = create_post_pipeline(
+                device,
+                post.target_pass,
+                post.layouts[1],
+                include_bytes!(concat!(env!("OUT_DIR"), "/bloom_combine.frag.spv")),
+                false,
+                cache,
+            )?;
+            Ok(())
+        })();
+        if let Err(e) = res {
+            node.destroy(device);
+            return Err(e);
+        }
+        Ok(node)
+    }
+}
+
+impl PostNode for BloomNode {
+    fn enabled(&self, settings: &PostProcessSettings) -> bool {
+        settings.bloom && settings.bloom_intensity > 0.0
+    }
+
+    unsafe fn resize(&mut self, r: &VulkanRenderer, extent: vk::Extent2D) -> VkResult<()> {
+        for level in &mut self.levels {
+            level.destroy(&r.core.device);
+        }
+        self.levels.clear();
+
+        let mut size = extent;
+        for i in 0..BLOOM_LEVELS {
+            size = vk::Extent2D {
+                width: size.width / 2,
+                height: size.height / 2,
+            };
+            if size.width == 0 || size.height == 0 {
+                break;
+            }
+            let level = r.create_post_image(size, &format!("post_bloom{i}"))?;
+            self.levels.push(level);
+        }
+        Ok(())
+    }
+
+    unsafe fn record(
+        &self,
+        r: &VulkanRenderer,
+        cmd: vk::CommandBuffer,
+        input: &PostImage,
+        output: &PostImage,
+        settings: &PostProcessSettings,
+    ) {
+        let target_pass = r.post.target_pass;
+        let Some(first) = self.levels.first() else {
+            // Scene too small for a single mip: plain copy.
+            r.post_pass(
+                cmd,
+                target_pass,
+                output,
+                self.combine,
+                &[input.set, input.set],
+                [0.0; 4],
+            );
+            return;
+        };
+
+        let mut src = input;
+        for (i, level) in self.levels.iter().enumerate() {
+            let [tx, ty] = src.texel();
+            let prefilter = if i == 0 { 1.0 } else { 0.0 };
+            let push = [tx, ty, settings.bloom_threshold.max(0.0), prefilter];
+            r.post_pass(cmd, target_pass, level, self.down, &[src.set], push);
+            src = level;
+        }
+
+        for pair in self.levels.windows(2).rev() {
+            let (dst, src) = (&pair[0], &pair[1]);
+            let [tx, ty] = src.texel();
+            let push = [tx, ty, 0.0, 0.0];
+            r.post_pass(cmd, r.post.blend_pass, dst, self.up, &[src.set], push);
+        }
+
+        let [tx, ty] = first.texel();
+        let push = [tx, ty, settings.bloom_intensity, 0.0];
+        r.post_pass(
+            cmd,
+            target_pass,
+            output,
+            self.combine,
+            &[input.set, first.set],
+            push,
+        );
+    }
+
+    unsafe fn destroy(&mut self, device: &ash::Device) {
+        for level in &mut self.levels {
+            level.destroy(device);
+        }
+        self.levels.clear();
+        for p in [&mut self.down, &mut self.up, &mut self.combine] {
+            if *p != vk::Pipeline::null() {
+                device.destroy_pipeline(*p, None);
+                *p = vk::Pipeline::null();
+            }
+        }
+    }
+}
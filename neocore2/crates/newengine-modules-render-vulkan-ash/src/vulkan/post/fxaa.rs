@@ -0,0 +1,58 @@
+use crate::error::VkResult;
+
+use ash::vk;
+use newengine_core::render::PostProcessSettings;
+
+use super::{create_post_pipeline, PostImage, PostNode};
+use crate::vulkan::VulkanRenderer;
+
+/// Fast approximate anti-aliasing: blurs along the edges found in the image's luma.
+pub(crate) struct FxaaNode {
+    pipeline: vk::Pipeline,
+}
+
+impl FxaaNode {
+    pub(crate) unsafe fn new(r: &VulkanRenderer) -> VkResult<Self> {
+        let pipeline = create_post_pipeline(
+            &r.core.device,
+            r.post.target_pass,
+            r.post.layouts[0],
+            include_bytes!(concat!(env!("OUT_DIR"), "/fxaa.frag.spv")),
+            false,
+            r.pipelines.cache,
+        )?;
+        Ok(Self { pipeline })
+    }
+}
+
+impl PostNode for FxaaNode {
+    fn enabled(&self, settings: &PostProcessSettings) -> bool {
+        settings.fxaa
+    }
+
+    unsafe fn record(
+        &self,
+        r: &VulkanRenderer,
+        cmd: vk::CommandBuffer,
+        input: &PostImage,
+        output: &PostImage,
+        _settings: &PostProcessSettings,
+    ) {
+        let [tx, ty] = input.texel();
+        r.post_pass(
+            cmd,
+            r.post.target_pass,
+            output,
+            self.pipeline,
+            &[input.set],
+            [tx, ty, 0.0, 0.0],
+        );
+    }
+
+    unsafe fn destroy(&mut self, device: &ash::Device) {
+        if self.pipeline != vk::Pipeline::null() {
+            device.destroy_pipeline(self.pipeline, None);
+            self.pipeline = vk::Pipeline::null();
+        }
+    }
+}
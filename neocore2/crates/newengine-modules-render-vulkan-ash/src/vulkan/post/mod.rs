@@ -0,0 +1,627 @@
+use crate::error::VkResult;
+
+use ash::vk;
+use newengine_core::render::PostProcessSettings;
+use std::ffi::CString;
+
+use super::pipeline::create_shader_module;
+use super::VulkanRenderer;
+
+mod bloom;
+mod fxaa;
+mod vignette;
+
+use bloom::BloomNode;
+use fxaa::FxaaNode;
+use vignette::VignetteNode;
+
+/// Format of the scene target and of every intermediate target of the chain.
+pub(crate) const HDR_FORMAT: vk::Format = vk::Format::R16G16B16A16_SFLOAT;
+
+/// Targets a post pass samples or renders into: the scene, two ping-pong targets and the
+/// bloom mips.
+const MAX_POST_IMAGES: u32 = 16;
+
+/// Fragment push constants of every post pipeline: four floats.
+const PUSH_SIZE: u32 = 16;
+
+/// Color target of the chain; rendered by one pass, then sampled by the next.
+#[derive(Clone, Copy, Default)]
+pub(crate) struct PostImage {
+    pub(crate) image: vk::Image,
+    pub(crate) memory: vk::DeviceMemory,
+    pub(crate) view: vk::ImageView,
+    pub(crate) framebuffer: vk::Framebuffer,
+    pub(crate) extent: vk::Extent2D,
+    /// Samples the image. Sets live in `PostResources::pool`, which is reset on resize.
+    pub(crate) set: vk::DescriptorSet,
+}
+
+impl PostImage {
+    #[inline]
+    pub(crate) fn texel(&self) -> [f32; 2] {
+        [
+            1.0 / self.extent.width.max(1) as f32,
+            1.0 / self.extent.height.max(1) as f32,
+        ]
+    }
+
+    unsafe fn destroy(&mut self, device: &ash::Device) {
+        if self.framebuffer != vk::Framebuffer::null() {
+            device.destroy_framebuffer(self.framebuffer, None);
+        }
+        if self.view != vk::ImageView::null() {
+            device.destroy_image_view(self.view, None);
+        }
+        if self.image != vk::Image::null() {
+            device.destroy_image(self.image, None);
+        }
+        if self.memory != vk::DeviceMemory::null() {
+            device.free_memory(self.memory, None);
+        }
+        *self = Self::default();
+    }
+}
+
+/// One pass of the post chain: samples `input` and covers all of `output`, both sized like
+/// the scene. Nodes are independent of each other and of their position in the chain; a node
+/// needing targets of its own (e.g. a mip chain) creates them in `resize`.
+pub(crate) trait PostNode: Send {
+    fn enabled(&self, settings: &PostProcessSettings) -> bool;
+
+    /// Called once the scene target exists and again whenever it changes size, with the
+    /// device idle.
+    unsafe fn resize(&mut self, _r: &VulkanRenderer, _extent: vk::Extent2D) -> VkResult<()> {
+        Ok(())
+    }
+
+    /// Records the node outside of any render pass.
+    unsafe fn record(
+        &self,
+        r: &VulkanRenderer,
+        cmd: vk::CommandBuffer,
+        input: &PostImage,
+        output: &PostImage,
+        settings: &PostProcessSettings,
+    );
+
+    unsafe fn destroy(&mut self, device: &ash::Device);
+}
+
+/// HDR scene target and the post-process chain that takes it to the swapchain.
+///
+/// The main pass renders into `scene`; `end_frame` runs the enabled nodes, ping-ponging
+/// between the two `ping` targets, and copies the last result into the swapchain pass, where
+/// debug draws, text and UI follow.
+#[derive(Default)]
+pub struct PostResources {
+    pub(crate) settings: PostProcessSettings,
+
+    /// Main pass into `scene`: clears, and leaves it ready for sampling. Pipelines created
+    /// through the render API are built against it.
+    pub(crate) scene_pass: vk::RenderPass,
+    /// Passes overwriting their whole target.
+    pub(crate) target_pass: vk::RenderPass,
+    /// Passes blending onto what their target holds.
+    pub(crate) blend_pass: vk::RenderPass,
+
+    pub(crate) sampler: vk::Sampler,
+    pub(crate) set_layout: vk::DescriptorSetLayout,
+    pub(crate) pool: vk::DescriptorPool,
+    /// Pipeline layouts sampling one and two targets.
+    pub(crate) layouts: [vk::PipelineLayout; 2],
+
+    pub(crate) scene: PostImage,
+    pub(crate) ping: [PostImage; 2],
+    pub(crate) nodes: Vec<Box<dyn PostNode>>,
+
+    /// Copies the chain's result into the swapchain pass; rebuilt with that pass.
+    pub(crate) blit: vk::Pipeline,
+}
+
+/// Color pass over a single [`HDR_FORMAT`] target that is sampled afterwards. `LOAD` passes
+/// expect the target in `SHADER_READ_ONLY_OPTIMAL`; all of them leave it there.
+unsafe fn create_post_render_pass(
+    device: &ash::Device,
+    load_op: vk::AttachmentLoadOp,
+) -> VkResult<vk::RenderPass> {
+    let initial_layout = if load_op == vk::AttachmentLoadOp::LOAD {
+        vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL
+    } else {
+        vk::ImageLayout::UNDEFINED
+    };
+    let color = vk::AttachmentDescription::default()
+        .format(HDR_FORMAT)
+        .samples(vk::SampleCountFlags::TYPE_1)
+        .load_op(load_op)
+        .store_op(vk::AttachmentStoreOp::STORE)
+        .initial_layout(initial_layout)
+        .final_layout(vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL);
+
+    let color_ref = vk::AttachmentReference::default()
+        .attachment(0)
+        .layout(vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL);
+
+    let subpass = vk::SubpassDescription::default()
+        .pipeline_bind_point(vk::PipelineBindPoint::GRAPHICS)
+        .color_attachments(std::slice::from_ref(&color_ref));
+
+    let output = vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT;
+    let deps = [
+        // Earlier reads (this frame or the previous one) and writes finish before rendering.
+        vk::SubpassDependency::default()
+            .src_subpass(vk::SUBPASS_EXTERNAL)
+            .dst_subpass(0)
+            .src_stage_mask(vk::PipelineStageFlags::FRAGMENT_SHADER | output)
+            .src_access_mask(vk::AccessFlags::COLOR_ATTACHMENT_WRITE)
+            .dst_stage_mask(output)
+            .dst_access_mask(
+                vk::AccessFlags::COLOR_ATTACHMENT_READ | vk::AccessFlags::COLOR_ATTACHMENT_WRITE,
+            ),
+        // The next pass samples the result.
+        vk::SubpassDependency::default()
+            .src_subpass(0)
+            .dst_subpass(vk::SUBPASS_EXTERNAL)
+            .src_stage_mask(output)
+            .src_access_mask(vk::AccessFlags::COLOR_ATTACHMENT_WRITE)
+            .dst_stage_mask(vk::PipelineStageFlags::FRAGMENT_SHADER)
+            .dst_access_mask(vk::AccessFlags::SHADER_READ),
+    ];
+
+    let rp = vk::RenderPassCreateInfo::default()
+        .attachments(std::slice::from_ref(&color))
+        .subpasses(std::slice::from_ref(&subpass))
+        .dependencies(&deps);
+
+    Ok(device.create_render_pass(&rp, None)?)
+}
+
+/// Fullscreen-triangle pipeline running `frag`; `additive` blends its output onto the target.
+pub(crate) unsafe fn create_post_pipeline(
+    device: &ash::Device,
+    render_pass: vk::RenderPass,
+    layout: vk::PipelineLayout,
+    frag: &[u8],
+    additive: bool,
+    cache: vk::PipelineCache,
+) -> VkResult<vk::Pipeline> {
+    let vert = create_shader_module(
+        device,
+        include_bytes!(concat!(env!("OUT_DIR"), "/post.vert.spv")),
+    )?;
+    let frag = match create_shader_module(device, frag) {
+        Ok(m) => m,
+        Err(e) => {
+            device.destroy_shader_module(vert, None);
+            return Err(e);
+        }
+    };
+
+    let entry = CString::new("main").unwrap();
+
+    let stages = [
+        vk::PipelineShaderStageCreateInfo::default()
+            .stage(vk::ShaderStageFlags::VERTEX)
+            .module(vert)
+            .name(&entry),
+        vk::PipelineShaderStageCreateInfo::default()
+            .stage(vk::ShaderStageFlags::FRAGMENT)
+            .module(frag)
+            .name(&entry),
+    ];
+
+    let vi = vk::PipelineVertexInputStateCreateInfo::default();
+
+    let ia = vk::PipelineInputAssemblyStateCreateInfo::default()
+        .topology(vk::PrimitiveTopology::TRIANGLE_LIST);
+
+    let vp = vk::PipelineViewportStateCreateInfo::default()
+        .viewport_count(1)
+        .scissor_count(1);
+
+    let rs = vk::PipelineRasterizationStateCreateInfo::default()
+        .polygon_mode(vk::PolygonMode::FILL)
+        .cull_mode(vk::CullModeFlags::NONE)
+        .front_face(vk::FrontFace::COUNTER_CLOCKWISE)
+        .line_width(1.0);
+
+    let ms = vk::PipelineMultisampleStateCreateInfo::default()
+        .rasterization_samples(vk::SampleCountFlags::TYPE_1);
+
+    let ca = vk::PipelineColorBlendAttachmentState::default()
+        .blend_enable(additive)
+        .src_color_blend_factor(vk::BlendFactor::ONE)
+        .dst_color_blend_factor(vk::BlendFactor::ONE)
+        .color_blend_op(vk::BlendOp::ADD)
+        .src_alpha_blend_factor(vk::BlendFactor::ZERO)
+        .dst_alpha_blend_factor(vk::BlendFactor::ONE)
+        .alpha_blend_op(vk::BlendOp::ADD)
+        .color_write_mask(vk::ColorComponentFlags::RGBA);
+
+    let cb =
+        vk::PipelineColorBlendStateCreateInfo::default().attachments(std::slice::from_ref(&ca));
+
+    let dyn_states = [vk::DynamicState::VIEWPORT, vk::DynamicState::SCISSOR];
+    let ds = vk::PipelineDynamicStateCreateInfo::default().dynamic_states(&dyn_states);
+
+    let gp = vk::GraphicsPipelineCreateInfo::default()
+        .stages(&stages)
+        .vertex_input_state(&vi)
+        .input_assembly_state(&ia)
+        .viewport_state(&vp)
+        .rasterization_state(&rs)
+        .multisample_state(&ms)
+        .color_blend_state(&cb)
+        .dynamic_state(&ds)
+        .layout(layout)
+        .render_pass(render_pass)
+        .subpass(0);
+
+    let pipelines = device.create_graphics_pipelines(cache, &[gp], None);
+
+    device.destroy_shader_module(vert, None);
+    device.destroy_shader_module(frag, None);
+
+    match pipelines {
+        Ok(v) => Ok(v[0]),
+        Err((_, e)) => Err(e.into()),
+    }
+}
+
+impl VulkanRenderer {
+    /// Creates the scene target, the chain's shared objects and its nodes: bloom, vignette,
+    /// then FXAA. Needs the swapchain render pass.
+    pub(crate) unsafe fn init_post(&mut self) -> VkResult<()> {
+        let device = &self.core.device;
+
+        self.post.scene_pass = create_post_render_pass(device, vk::AttachmentLoadOp::CLEAR)?;
+        self.post.target_pass = create_post_render_pass(device, vk::AttachmentLoadOp::DONT_CARE)?;
+        self.post.blend_pass = create_post_render_pass(device, vk::AttachmentLoadOp::LOAD)?;
+
+        let sampler_info = vk::SamplerCreateInfo::default()
+            .mag_filter(vk::Filter::LINEAR)
+            .min_filter(vk::Filter::LINEAR)
+            .mipmap_mode(vk::SamplerMipmapMode::NEAREST)
+            .address_mode_u(vk::SamplerAddressMode::CLAMP_TO_EDGE)
+            .address_mode_v(vk::SamplerAddressMode::CLAMP_TO_EDGE)
+            .address_mode_w(vk::SamplerAddressMode::CLAMP_TO_EDGE);
+        self.post.sampler = device.create_sampler(&sampler_info, None)?;
+
+        let binding = vk::DescriptorSetLayoutBinding::default()
+            .binding(0)
+            .descriptor_type(vk::DescriptorType::COMBINED_IMAGE_SAMPLER)
+            .descriptor_count(1)
+            .stage_flags(vk::ShaderStageFlags::FRAGMENT);
+        self.post.set_layout = device.create_descriptor_set_layout(
+            &vk::DescriptorSetLayoutCreateInfo::default().bindings(std::slice::from_ref(&binding)),
+            None,
+        )?;
+
+        let pool_size = vk::DescriptorPoolSize::default()
+            .ty(vk::DescriptorType::COMBINED_IMAGE_SAMPLER)
+            .descriptor_count(MAX_POST_IMAGES);
+        self.post.pool = device.create_descriptor_pool(
+            &vk::DescriptorPoolCreateInfo::default()
+                .max_sets(MAX_POST_IMAGES)
+                .pool_sizes(std::slice::from_ref(&pool_size)),
+            None,
+        )?;
+
+        let push = vk::PushConstantRange::default()
+            .stage_flags(vk::ShaderStageFlags::FRAGMENT)
+            .offset(0)
+            .size(PUSH_SIZE);
+        for (i, layout) in self.post.layouts.iter_mut().enumerate() {
+            let set_layouts = [self.post.set_layout; 2];
+            *layout = device.create_pipeline_layout(
+                &vk::PipelineLayoutCreateInfo::default()
+                    .set_layouts(&set_layouts[..=i])
+                    .push_constant_ranges(std::slice::from_ref(&push)),
+                None,
+            )?;
+        }
+
+        self.create_post_blit()?;
+
+        // Pushed one by one, so a failure still leaves the earlier ones to `destroy_post`.
+        let bloom = BloomNode::new(self)?;
+        self.post.nodes.push(Box::new(bloom));
+        let vignette = VignetteNode::new(self)?;
+        self.post.nodes.push(Box::new(vignette));
+        let fxaa = FxaaNode::new(self)?;
+        self.post.nodes.push(Box::new(fxaa));
+
+        self.resize_post(self.swapchain.extent)
+    }
+
+    /// (Re)builds the blit pipeline against the current swapchain render pass.
+    pub(crate) unsafe fn create_post_blit(&mut self) -> VkResult<()> {
+        if self.post.blit != vk::Pipeline::null() {
+            self.core.device.destroy_pipeline(self.post.blit, None);
+            self.post.blit = vk::Pipeline::null();
+        }
+        self.post.blit = create_post_pipeline(
+            &self.core.device,
+            self.pipelines.render_pass,
+            self.post.layouts[0],
+            include_bytes!(concat!(env!("OUT_DIR"), "/post_blit.frag.spv")),
+            false,
+            self.pipelines.cache,
+        )?;
+        Ok(())
+    }
+
+    /// Recreates every target of the chain at `extent`. The device must be idle.
+    pub(crate) unsafe fn resize_post(&mut self, extent: vk::Extent2D) -> VkResult<()> {
+        if self.post.pool == vk::DescriptorPool::null() || extent.width == 0 || extent.height == 0 {
+            return Ok(());
+        }
+        let device = &self.core.device;
+        self.post.scene.destroy(device);
+        for p in &mut self.post.ping {
+            p.destroy(device);
+        }
+        device.reset_descriptor_pool(self.post.pool, vk::DescriptorPoolResetFlags::empty())?;
+
+        self.post.scene = self.create_post_image(extent, "post_scene")?;
+        self.post.ping[0] = self.create_post_image(extent, "post_ping0")?;
+        self.post.ping[1] = self.create_post_image(extent, "post_ping1")?;
+
+        let mut nodes = std::mem::take(&mut self.post.nodes);
+        let mut res = Ok(());
+        for node in &mut nodes {
+            res = node.resize(self, extent);
+            if res.is_err() {
+                break;
+            }
+        }
+        self.post.nodes = nodes;
+        res
+    }
+
+    /// [`HDR_FORMAT`] target with its framebuffer and sampling set.
+    pub(crate) unsafe fn create_post_image(
+        &self,
+        extent: vk::Extent2D,
+        label: &str,
+    ) -> VkResult<PostImage> {
+        let device = &self.core.device;
+        let usage = vk::ImageUsageFlags::COLOR_ATTACHMENT | vk::ImageUsageFlags::SAMPLED;
+        let (image, memory, view) = self.create_image(
+            HDR_FORMAT,
+            extent,
+            false,
+            usage,
+            vk::ImageAspectFlags::COLOR,
+            Some(label),
+        )?;
+        let mut out = PostImage {
+            image,
+            memory,
+            view,
+            extent,
+            ..PostImage::default()
+        };
+
+        let attachments = [view];
+        let fb_info = vk::FramebufferCreateInfo::default()
+            .render_pass(self.post.target_pass)
+            .attachments(&attachments)
+            .width(extent.width)
+            .height(extent.height)
+            .layers(1);
+        out.framebuffer = match device.create_framebuffer(&fb_info, None) {
+            Ok(fb) => fb,
+            Err(e) => {
+                out.destroy(device);
+                return Err(e.into());
+            }
+        };
+
+        let set_layouts = [self.post.set_layout];
+        let alloc = vk::DescriptorSetAllocateInfo::default()
+            .descriptor_pool(self.post.pool)
+            .set_layouts(&set_layouts);
+        out.set = match device.allocate_descriptor_sets(&alloc) {
+            Ok(sets) => sets[0],
+            Err(e) => {
+                out.destroy(device);
+                return Err(e.into());
+            }
+        };
+
+        let info = vk::DescriptorImageInfo::default()
+            .sampler(self.post.sampler)
+            .image_view(view)
+            .image_layout(vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL);
+        let write = vk::WriteDescriptorSet::default()
+            .dst_set(out.set)
+            .dst_binding(0)
+            .descriptor_type(vk::DescriptorType::COMBINED_IMAGE_SAMPLER)
+            .image_info(std::slice::from_ref(&info));
+        device.update_descriptor_sets(std::slice::from_ref(&write), &[]);
+
+        Ok(out)
+    }
+
+    pub(crate) unsafe fn destroy_post(&mut self) {
+        let device = &self.core.device;
+
+        for mut node in self.post.nodes.drain(..) {
+            node.destroy(device);
+        }
+        self.post.scene.destroy(device);
+        for p in &mut self.post.ping {
+            p.destroy(device);
+        }
+
+        if self.post.blit != vk::Pipeline::null() {
+            device.destroy_pipeline(self.post.blit, None);
+            self.post.blit = vk::Pipeline::null();
+        }
+        for layout in &mut self.post.layouts {
+            if *layout != vk::PipelineLayout::null() {
+                device.destroy_pipeline_layout(*layout, None);
+                *layout = vk::PipelineLayout::null();
+            }
+        }
+        if self.post.pool != vk::DescriptorPool::null() {
+            device.destroy_descriptor_pool(self.post.pool, None);
+            self.post.pool = vk::DescriptorPool::null();
+        }
+        if self.post.set_layout != vk::DescriptorSetLayout::null() {
+            device.destroy_descriptor_set_layout(self.post.set_layout, None);
+            self.post.set_layout = vk::DescriptorSetLayout::null();
+        }
+        if self.post.sampler != vk::Sampler::null() {
+            device.destroy_sampler(self.post.sampler, None);
+            self.post.sampler = vk::Sampler::null();
+        }
+        for pass in [
+            &mut self.post.scene_pass,
+            &mut self.post.target_pass,
+            &mut self.post.blend_pass,
+        ] {
+            if *pass != vk::RenderPass::null() {
+                device.destroy_render_pass(*pass, None);
+                *pass = vk::RenderPass::null();
+            }
+        }
+    }
+
+    #[inline]
+    pub fn set_post_process(&mut self, settings: PostProcessSettings) {
+        self.post.settings = settings;
+    }
+
+    /// Begins `pass` over all of `target`, with viewport and scissor covering it.
+    pub(crate) unsafe fn post_begin(
+        &self,
+        cmd: vk::CommandBuffer,
+        pass: vk::RenderPass,
+        target: &PostImage,
+    ) {
+        let area = vk::Rect2D {
+            offset: vk::Offset2D { x: 0, y: 0 },
+            extent: target.extent,
+        };
+        let clear = vk::ClearValue {
+            color: vk::ClearColorValue {
+                float32: self.debug.clear_color,
+            },
+        };
+        let rp_begin = vk::RenderPassBeginInfo::default()
+            .render_pass(pass)
+            .framebuffer(target.framebuffer)
+            .render_area(area)
+            .clear_values(std::slice::from_ref(&clear));
+        let device = &self.core.device;
+        device.cmd_begin_render_pass(cmd, &rp_begin, vk::SubpassContents::INLINE);
+
+        let viewport = vk::Viewport {
+            x: 0.0,
+            y: 0.0,
+            width: target.extent.width as f32,
+            height: target.extent.height as f32,
+            min_depth: 0.0,
+            max_depth: 1.0,
+        };
+        device.cmd_set_viewport(cmd, 0, std::slice::from_ref(&viewport));
+        device.cmd_set_scissor(cmd, 0, std::slice::from_ref(&area));
+    }
+
+    /// Draws the fullscreen triangle with `pipeline`, sampling `inputs` as sets 0.., with
+    /// `push` as its push constants.
+    pub(crate) unsafe fn post_draw(
+        &self,
+        cmd: vk::CommandBuffer,
+        pipeline: vk::Pipeline,
+        inputs: &[vk::DescriptorSet],
+        push: [f32; 4],
+    ) {
+        let layout = self.post.layouts[inputs.len().clamp(1, 2) - 1];
+        let mut bytes = [0u8; PUSH_SIZE as usize];
+        for (dst, v) in bytes.chunks_exact_mut(4).zip(push) {
+            dst.copy_from_slice(&v.to_ne_bytes());
+        }
+
+        let device = &self.core.device;
+        device.cmd_bind_pipeline(cmd, vk::PipelineBindPoint::GRAPHICS, pipeline);
+        device.cmd_bind_descriptor_sets(
+            cmd,
+            vk::PipelineBindPoint::GRAPHICS,
+            layout,
+            0,
+            inputs,
+            &[],
+        );
+        device.cmd_push_constants(cmd, layout, vk::ShaderStageFlags::FRAGMENT, 0, &bytes);
+        device.cmd_draw(cmd, 3, 1, 0, 0);
+    }
+
+    /// One pass of `pipeline` over all of `target`.
+    pub(crate) unsafe fn post_pass(
+        &self,
+        cmd: vk::CommandBuffer,
+        pass: vk::RenderPass,
+        target: &PostImage,
+        pipeline: vk::Pipeline,
+        inputs: &[vk::DescriptorSet],
+        push: [f32; 4],
+    ) {
+        self.post_begin(cmd, pass, target);
+        self.post_draw(cmd, pipeline, inputs, push);
+        self.core.device.cmd_end_render_pass(cmd);
+    }
+
+    /// Runs the enabled nodes over the scene target, which the main pass has finished, and
+    /// returns the target holding the result.
+    pub(crate) unsafe fn record_post(&self, cmd: vk::CommandBuffer) -> PostImage {
+        let settings = &self.post.settings;
+        let mut input = self.post.scene;
+        let mut next = 0;
+        for node in self.post.nodes.iter().filter(|n| n.enabled(settings)) {
+            let output = self.post.ping[next];
+            node.record(self, cmd, &input, &output, settings);
+            input = output;
+            next ^= 1;
+        }
+        input
+    }
+
+    /// Begins the swapchain render pass and copies `result` of the chain into it.
+    pub(crate) unsafe fn begin_present_pass(&self, cmd: vk::CommandBuffer, result: &PostImage) {
+        let idx = self.debug.current_swapchain_idx;
+        let area = vk::Rect2D {
+            offset: vk::Offset2D { x: 0, y: 0 },
+            extent: self.swapchain.extent,
+        };
+        let clear = vk::ClearValue {
+            color: vk::ClearColorValue {
+                float32: self.debug.clear_color,
+            },
+        };
+        let rp_begin = vk::RenderPassBeginInfo::default()
+            .render_pass(self.pipelines.render_pass)
+            .framebuffer(self.swapchain.framebuffers[idx])
+            .render_area(area)
+            .clear_values(std::slice::from_ref(&clear));
+        let device = &self.core.device;
+        device.cmd_begin_render_pass(cmd, &rp_begin, vk::SubpassContents::INLINE);
+
+        let viewport = vk::Viewport {
+            x: 0.0,
+            y: 0.0,
+            width: area.extent.width as f32,
+            height: area.extent.height as f32,
+            min_depth: 0.0,
+            max_depth: 1.0,
+        };
+        device.cmd_set_viewport(cmd, 0, std::slice::from_ref(&viewport));
+        device.cmd_set_scissor(cmd, 0, std::slice::from_ref(&area));
+
+        if self.post.blit != vk::Pipeline::null() {
+            self.post_draw(cmd, self.post.blit, &[result.set], [0.0; 4]);
+        }
+    }
+}
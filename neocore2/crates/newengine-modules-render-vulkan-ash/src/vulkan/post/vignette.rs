@@ -0,0 +1,64 @@
+use crate::error::VkResult;
+
+use ash::vk;
+use newengine_core::render::PostProcessSettings;
+
+use super::{create_post_pipeline, PostImage, PostNode};
+use crate::vulkan::VulkanRenderer;
+
+/// Darkens the image towards its corners.
+pub(crate) struct VignetteNode {
+    pipeline: vk::Pipeline,
+}
+
+impl VignetteNode {
+    pub(crate) unsafe fn new(r: &VulkanRenderer) -> VkResult<Self> {
+        let pipeline = create_post_pipeline(
+            &r.core.device,
+            r.post.target_pass,
+            r.post.layouts[0],
+            include_bytes!(concat!(env!("OUT_DIR"), "/vignette.frag.spv")),
+            false,
+            r.pipelines.cache,
+        )?;
+        Ok(Self { pipeline })
+    }
+}
+
+impl PostNode for VignetteNode {
+    fn enabled(&self, settings: &PostProcessSettings) -> bool {
+        settings.vignette && settings.vignette_intensity > 0.0
+    }
+
+    unsafe fn record(
+        &self,
+        r: &VulkanRenderer,
+        cmd: vk::CommandBuffer,
+        input: &PostImage,
+        output: &PostImage,
+        settings: &PostProcessSettings,
+    ) {
+        let aspect = output.extent.width as f32 / output.extent.height.max(1) as f32;
+        let push = [
+            settings.vignette_intensity.clamp(0.0, 1.0),
+            settings.vignette_radius.max(0.0),
+            aspect,
+            0.0,
+        ];
+        r.post_pass(
+            cmd,
+            r.post.target_pass,
+            output,
+            self.pipeline,
+            &[input.set],
+            push,
+        );
+    }
+
+    unsafe fn destroy(&mut self, device: &ash::Device) {
+        if self.pipeline != vk::Pipeline::null() {
+            device.destroy_pipeline(self.pipeline, None);
+            self.pipeline = vk::Pipeline::null();
+        }
+    }
+}
@@ -1,6 +1,8 @@
 use crate::error::VkResult;
 use ash::vk;
+use newengine_core::render::DebugDrawBatch;
 use newengine_ui::draw::UiDrawList;
+use std::ffi::CString;
 
 use super::state::VulkanRenderer;
 
@@ -36,17 +38,38 @@ impl VulkanRenderer {
         self.debug.pending_ui = Some(ui);
     }
 
-    /// Submits a short-lived upload command buffer using a persistent `UploadCtx`.
+    /// Stores debug lines and labels for the next presented frame.
+    #[inline]
+    pub fn set_debug_draw(&mut self, batch: DebugDrawBatch) {
+        self.debug.pending_debug_draw = Some(batch);
+    }
+
+    /// Labels `handle` for validation messages and GPU debuggers. No-op without debug utils.
+    pub fn set_object_name<H: vk::Handle>(&self, handle: H, name: &str) {
+        let Some(du) = self.debug_utils.device.as_ref() else {
+            return;
+        };
+        let Ok(name) = CString::new(name) else {
+            return;
+        };
+        let info = vk::DebugUtilsObjectNameInfoEXT::default()
+            .object_handle(handle)
+            .object_name(&name);
+        if let Err(e) = unsafe { du.set_debug_utils_object_name(&info) } {
+            log::debug!("vulkan: naming object '{}' failed: {e:?}", name.to_string_lossy());
+        }
+    }
+
+    /// Records upload commands into the current batch on the transfer queue. The batch is
+    /// submitted ahead of the next frame (see `end_frame`).
     ///
-    /// This method does NOT call `queue_wait_idle`.
-    /// It returns a fence that will be signaled once the upload work is complete.
+    /// This method does NOT block (unless every upload context is still in flight).
+    /// It returns a fence that will be signaled once the batch has executed.
     #[inline]
     pub unsafe fn submit_upload<F: FnOnce(vk::CommandBuffer)>(&mut self, f: F) -> VkResult<vk::Fence> {
-        let idx = self.frames.upload_cursor;
-        self.frames.upload_cursor = (self.frames.upload_cursor + 1) % super::state::UPLOAD_CONTEXTS;
-
-        let ctx = self.frames.upload_ctxs[idx];
-        ctx.submit_async(&self.core.device, self.core.queue, f)
+        let cmd = self.upload_transfer_cmd()?;
+        f(cmd);
+        self.upload_fence()
     }
 
     /// Schedules a staging buffer for destruction after `fence` is signaled.
@@ -0,0 +1,180 @@
+use crate::error::{VkRenderError, VkResult};
+use crate::vulkan::util::transition_image;
+
+use ash::vk;
+use newengine_core::render::CapturedFrame;
+
+use super::super::device::create_buffer;
+use super::state::{PendingCapture, VulkanRenderer};
+
+impl VulkanRenderer {
+    /// Marks the next presented frame for readback. Fails when the surface cannot be a
+    /// transfer source.
+    pub fn request_capture(&mut self) -> VkResult<()> {
+        let caps = unsafe {
+            self.core
+                .surface_loader
+                .get_physical_device_surface_capabilities(
+                    self.core.physical_device,
+                    self.core.surface,
+                )
+        }?;
+        if !caps
+            .supported_usage_flags
+            .contains(vk::ImageUsageFlags::TRANSFER_SRC)
+        {
+            return Err(VkRenderError::InvalidState(
+                "surface images cannot be copied (no TRANSFER_SRC usage)",
+            ));
+        }
+        self.capture.requested = true;
+        Ok(())
+    }
+
+    /// The frame copied for [`Self::request_capture`], once read back.
+    #[inline]
+    pub fn take_capture(&mut self) -> Option<CapturedFrame> {
+        self.capture.ready.take()
+    }
+
+    /// Records the copy of swapchain image `image` into a host-visible buffer when a capture
+    /// was requested. Call after the render pass; returns the layout the image is left in.
+    pub(super) unsafe fn capture_record(
+        &mut self,
+        cmd: vk::CommandBuffer,
+        image: vk::Image,
+    ) -> VkResult<vk::ImageLayout> {
+        let layout = vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL;
+        if !self.capture.requested || self.capture.pending.is_some() {
+            return Ok(layout);
+        }
+        self.capture.requested = false;
+
+        let extent = self.swapchain.extent;
+        let size = extent.width as vk::DeviceSize * extent.height as vk::DeviceSize * 4;
+        let (buffer, memory) = create_buffer(
+            &self.core.instance,
+            self.core.physical_device,
+            &self.core.device,
+            size,
+            vk::BufferUsageFlags::TRANSFER_DST,
+            vk::MemoryPropertyFlags::HOST_VISIBLE | vk::MemoryPropertyFlags::HOST_COHERENT,
+        )?;
+
+        let device = &self.core.device;
+        transition_image(
+            device,
+            cmd,
+            image,
+            layout,
+            vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+        );
+
+        let region = vk::BufferImageCopy::default()
+            .image_subresource(
+                vk::ImageSubresourceLayers::default()
+                    .aspect_mask(vk::ImageAspectFlags::COLOR)
+                    .layer_count(1),
+            )
+            .image_extent(vk::Extent3D {
+                width: extent.width,
+                height: extent.height,
+                depth: 1,
+            });
+        device.cmd_copy_image_to_buffer(
+            cmd,
+            image,
+            vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+            buffer,
+            std::slice::from_ref(&region),
+        );
+
+        let barrier = vk::BufferMemoryBarrier::default()
+            .src_access_mask(vk::AccessFlags::TRANSFER_WRITE)
+            .dst_access_mask(vk::AccessFlags::HOST_READ)
+            .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+            .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+            .buffer(buffer)
+            .size(vk::WHOLE_SIZE);
+        device.cmd_pipeline_barrier(
+            cmd,
+            vk::PipelineStageFlags::TRANSFER,
+            vk::PipelineStageFlags::HOST,
+            vk::DependencyFlags::empty(),
+            &[],
+            std::slice::from_ref(&barrier),
+            &[],
+        );
+
+        self.capture.pending = Some(PendingCapture {
+            buffer,
+            memory,
+            slot: self.frames.frame_index,
+            extent,
+            format: self.swapchain.format,
+        });
+        Ok(vk::ImageLayout::TRANSFER_SRC_OPTIMAL)
+    }
+
+    /// Reads back the capture recorded in frame slot `slot`. Call after waiting on its
+    /// in-flight fence.
+    pub(super) unsafe fn capture_collect(&mut self, slot: usize) {
+        if self.capture.pending.as_ref().map(|p| p.slot) != Some(slot) {
+            return;
+        }
+        let Some(p) = self.capture.pending.take() else {
+            return;
+        };
+
+        match self.capture_read(&p) {
+            Ok(frame) => self.capture.ready = Some(frame),
+            Err(e) => log::warn!("vulkan: frame capture failed: {e}"),
+        }
+        self.core.device.destroy_buffer(p.buffer, None);
+        self.core.device.free_memory(p.memory, None);
+    }
+
+    unsafe fn capture_read(&self, p: &PendingCapture) -> VkResult<CapturedFrame> {
+        let bgra = match p.format {
+            vk::Format::B8G8R8A8_UNORM | vk::Format::B8G8R8A8_SRGB => true,
+            vk::Format::R8G8B8A8_UNORM | vk::Format::R8G8B8A8_SRGB => false,
+            _ => {
+                return Err(VkRenderError::InvalidState(
+                    "frame capture: unsupported swapchain format",
+                ))
+            }
+        };
+
+        let len = p.extent.width as usize * p.extent.height as usize * 4;
+        let ptr = self.core.device.map_memory(
+            p.memory,
+            0,
+            len as vk::DeviceSize,
+            vk::MemoryMapFlags::empty(),
+        )?;
+        let mut rgba8 = std::slice::from_raw_parts(ptr as *const u8, len).to_vec();
+        self.core.device.unmap_memory(p.memory);
+
+        for px in rgba8.chunks_exact_mut(4) {
+            if bgra {
+                px.swap(0, 2);
+            }
+            // The surface is composited opaque; alpha is whatever the passes left behind.
+            px[3] = 255;
+        }
+
+        Ok(CapturedFrame {
+            width: p.extent.width,
+            height: p.extent.height,
+            rgba8,
+        })
+    }
+
+    pub(super) unsafe fn destroy_capture(&mut self) {
+        if let Some(p) = self.capture.pending.take() {
+            self.core.device.destroy_buffer(p.buffer, None);
+            self.core.device.free_memory(p.memory, None);
+        }
+        self.capture.ready = None;
+    }
+}
@@ -1,21 +1,42 @@
 use ash::vk;
 
+use super::super::pipeline_cache::save_pipeline_cache;
 use super::state::VulkanRenderer;
 
 impl Drop for VulkanRenderer {
     fn drop(&mut self) {
         unsafe {
+            // Staging buffers are only released once their batch has executed.
+            let _ = self.flush_uploads();
             let _ = self.core.device.device_wait_idle();
 
             self.destroy_ui_overlay();
+            self.destroy_debug_lines();
+            self.destroy_post();
             self.destroy_text_overlay();
+            self.destroy_gpu_timing();
+            self.destroy_capture();
+            self.destroy_pick();
+            self.destroy_transient();
+            self.destroy_viewports();
 
             // Flush deferred frees; device is idle already.
             let _ = self.frames.deferred_free.pump(&self.core.device);
 
-            for ctx in &mut self.frames.upload_ctxs {
+            for ctx in self
+                .frames
+                .upload_ctxs
+                .iter_mut()
+                .chain(self.frames.graphics_upload_ctxs.iter_mut())
+            {
                 ctx.destroy(&self.core.device);
             }
+            for s in &mut self.frames.upload_semaphores {
+                if *s != vk::Semaphore::null() {
+                    self.core.device.destroy_semaphore(*s, None);
+                    *s = vk::Semaphore::null();
+                }
+            }
 
             if self.frames.upload_command_pool != vk::CommandPool::null() {
                 self.core
@@ -74,6 +95,18 @@ impl Drop for VulkanRenderer {
                     .destroy_render_pass(self.pipelines.render_pass, None);
                 self.pipelines.render_pass = vk::RenderPass::null();
             }
+            if self.pipelines.depth_render_pass != vk::RenderPass::null() {
+                self.core
+                    .device
+                    .destroy_render_pass(self.pipelines.depth_render_pass, None);
+                self.pipelines.depth_render_pass = vk::RenderPass::null();
+            }
+            if self.pipelines.id_render_pass != vk::RenderPass::null() {
+                self.core
+                    .device
+                    .destroy_render_pass(self.pipelines.id_render_pass, None);
+                self.pipelines.id_render_pass = vk::RenderPass::null();
+            }
 
             for &iv in &self.swapchain.image_views {
                 if iv != vk::ImageView::null() {
@@ -96,7 +129,27 @@ impl Drop for VulkanRenderer {
                 self.core.surface = vk::SurfaceKHR::null();
             }
 
+            if self.pipelines.cache != vk::PipelineCache::null() {
+                if let Some(path) = self.pipelines.cache_file.as_deref() {
+                    match save_pipeline_cache(&self.core.device, self.pipelines.cache, path) {
+                        Ok(n) => log::info!("vulkan: pipeline cache saved ({n} bytes)"),
+                        Err(e) => log::warn!("vulkan: pipeline cache not saved: {e}"),
+                    }
+                }
+                self.core
+                    .device
+                    .destroy_pipeline_cache(self.pipelines.cache, None);
+                self.pipelines.cache = vk::PipelineCache::null();
+            }
+
             self.core.device.destroy_device(None);
+
+            if let Some(loader) = self.debug_utils.instance.take() {
+                if self.debug_utils.messenger != vk::DebugUtilsMessengerEXT::null() {
+                    loader.destroy_debug_utils_messenger(self.debug_utils.messenger, None);
+                    self.debug_utils.messenger = vk::DebugUtilsMessengerEXT::null();
+                }
+            }
             self.core.instance.destroy_instance(None);
         }
     }
@@ -4,6 +4,7 @@ use crate::vulkan::util::transition_image;
 use ash::vk;
 
 use super::state::VulkanRenderer;
+use super::timing::mark;
 use super::types::FRAMES_IN_FLIGHT;
 
 impl VulkanRenderer {
@@ -16,10 +17,13 @@ impl VulkanRenderer {
         if self.debug.in_frame {
             return Err(VkRenderError::InvalidState("begin_frame called while already in frame"));
         }
+        self.debug.frame_skipped = false;
+        self.debug.clear_color = clear_rgba;
 
         // If window is minimized or has no drawable area: keep state clean and do nothing.
         if self.debug.target_width == 0 || self.debug.target_height == 0 {
             self.debug.swapchain_dirty = true;
+            self.debug.frame_skipped = true;
             return Ok(());
         }
 
@@ -35,22 +39,43 @@ impl VulkanRenderer {
             self.core
                 .device
                 .wait_for_fences(&[frame.in_flight], true, u64::MAX)?;
+            self.gpu_timing_collect(self.frames.frame_index);
+            self.capture_collect(self.frames.frame_index);
+            self.pick_collect();
         }
-
-        let (image_index, _suboptimal) = match unsafe {
-            self.core.swapchain_loader.acquire_next_image(
-                self.swapchain.swapchain,
-                u64::MAX,
-                frame.image_available,
-                vk::Fence::null(),
-            )
-        } {
-            Ok(v) => v,
-            Err(vk::Result::ERROR_OUT_OF_DATE_KHR) | Err(vk::Result::SUBOPTIMAL_KHR) => {
-                self.debug.swapchain_dirty = true;
-                return Ok(());
+        self.transient_reset(self.frames.frame_index);
+
+        // An out-of-date swapchain (the window was resized since the last recreation) is
+        // recreated right away and acquired again, so frames keep up with a resize drag instead
+        // of being dropped. A suboptimal image is still rendered; the swapchain follows next
+        // frame.
+        let mut acquired = None;
+        for _ in 0..2 {
+            match unsafe {
+                self.core.swapchain_loader.acquire_next_image(
+                    self.swapchain.swapchain,
+                    u64::MAX,
+                    frame.image_available,
+                    vk::Fence::null(),
+                )
+            } {
+                Ok((index, suboptimal)) => {
+                    if suboptimal {
+                        self.debug.swapchain_dirty = true;
+                    }
+                    acquired = Some(index);
+                    break;
+                }
+                Err(vk::Result::ERROR_OUT_OF_DATE_KHR) | Err(vk::Result::SUBOPTIMAL_KHR) => unsafe {
+                    self.recreate_swapchain()?;
+                },
+                Err(e) => return Err(e.into()),
             }
-            Err(e) => return Err(e.into()),
+        }
+        let Some(image_index) = acquired else {
+            self.debug.swapchain_dirty = true;
+            self.debug.frame_skipped = true;
+            return Ok(());
         };
 
         let idx = image_index as usize;
@@ -79,6 +104,8 @@ impl VulkanRenderer {
                 &vk::CommandBufferBeginInfo::default()
                     .flags(vk::CommandBufferUsageFlags::ONE_TIME_SUBMIT),
             )?;
+            self.gpu_timing_reset(cmd);
+            self.gpu_timing_mark(cmd, mark::FRAME_BEGIN);
 
             let old_layout = self.swapchain.image_layouts[idx];
             transition_image(
@@ -88,54 +115,41 @@ impl VulkanRenderer {
                 old_layout,
                 vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL,
             );
-
-            let clear = vk::ClearValue {
-                color: vk::ClearColorValue { float32: clear_rgba },
-            };
-
-            let rp_begin = vk::RenderPassBeginInfo::default()
-                .render_pass(self.pipelines.render_pass)
-                .framebuffer(self.swapchain.framebuffers[idx])
-                .render_area(vk::Rect2D {
-                    offset: vk::Offset2D { x: 0, y: 0 },
-                    extent: self.swapchain.extent,
-                })
-                .clear_values(std::slice::from_ref(&clear));
-
-            self.core
-                .device
-                .cmd_begin_render_pass(cmd, &rp_begin, vk::SubpassContents::INLINE);
-
-            let viewport = vk::Viewport {
-                x: 0.0,
-                y: 0.0,
-                width: self.swapchain.extent.width as f32,
-                height: self.swapchain.extent.height as f32, // <- positive
-                min_depth: 0.0,
-                max_depth: 1.0,
-            };
-
-            let scissor = vk::Rect2D {
-                offset: vk::Offset2D { x: 0, y: 0 },
-                extent: self.swapchain.extent,
-            };
-
-            self.core
-                .device
-                .cmd_set_viewport(cmd, 0, std::slice::from_ref(&viewport));
-            self.core
-                .device
-                .cmd_set_scissor(cmd, 0, std::slice::from_ref(&scissor));
         }
 
         self.debug.in_frame = true;
+        self.debug.main_pass_open = false;
         self.debug.current_image_index = image_index;
         self.debug.current_swapchain_idx = idx;
         Ok(())
     }
 
+    /// Begins the scene render pass of the current frame unless it is open already. It
+    /// renders into the HDR scene target of the post chain; depth passes are recorded into the
+    /// frame's command buffer before this.
+    pub(crate) unsafe fn begin_main_pass(&mut self) {
+        if !self.debug.in_frame || self.debug.main_pass_open {
+            return;
+        }
+        let idx = self.debug.current_swapchain_idx;
+        let cmd = self.frames.command_buffers[idx];
+
+        self.post_begin(cmd, self.post.scene_pass, &self.post.scene);
+        self.debug.main_pass_open = true;
+    }
+
     pub fn end_frame(&mut self) -> VkResult<()> {
         if !self.debug.in_frame {
+            // Nothing was recorded; the UI and debug draws are dropped with the frame.
+            if std::mem::take(&mut self.debug.frame_skipped) {
+                self.debug.pending_ui = None;
+                self.debug.pending_debug_draw = None;
+                self.text.pending.clear();
+                for vp in self.viewports.targets.values_mut() {
+                    vp.pending_ui = None;
+                }
+                return Ok(());
+            }
             return Err(VkRenderError::InvalidState("end_frame called without begin_frame"));
         }
 
@@ -146,16 +160,29 @@ impl VulkanRenderer {
         let image_index = self.debug.current_image_index;
 
         unsafe {
+            // Frames without draws through the render API never opened it.
+            self.begin_main_pass();
+            self.core.device.cmd_end_render_pass(cmd);
+            self.debug.main_pass_open = false;
+
+            self.gpu_timing_mark(cmd, mark::POST_BEGIN);
+            let result = self.record_post(cmd);
+            self.begin_present_pass(cmd, &result);
+
+            let debug_batch = self.debug.pending_debug_draw.take();
+            if let Some(batch) = &debug_batch {
+                self.debug_lines_draw(cmd, batch)?;
+            }
+
             if self.pipelines.text_pipeline != vk::Pipeline::null()
                 && self.pipelines.text_pipeline_layout != vk::PipelineLayout::null()
-                && !self.debug.debug_text.is_empty()
             {
-                let debug_text = std::mem::take(&mut self.debug.debug_text);
-                let res = self.draw_text_overlay(cmd, &debug_text);
-                self.debug.debug_text = debug_text;
-                res?;
+                self.draw_texts(cmd, debug_batch.as_ref())?;
+            } else {
+                self.text.pending.clear();
             }
 
+            self.gpu_timing_mark(cmd, mark::UI_BEGIN);
             if let Some(list) = self.debug.pending_ui.take() {
                 let ui_ready = self.pipelines.ui_pipeline != vk::Pipeline::null()
                     && self.pipelines.ui_pipeline_layout != vk::PipelineLayout::null()
@@ -163,33 +190,49 @@ impl VulkanRenderer {
                     && self.ui.sampler != vk::Sampler::null();
 
                 if ui_ready {
-                    self.ui_upload_and_draw(cmd, &list)?;
+                    self.ui_upload_and_draw(cmd, &list, self.swapchain.extent)?;
                 }
             }
 
             self.core.device.cmd_end_render_pass(cmd);
+            self.gpu_timing_mark(cmd, mark::FRAME_END);
 
+            let layout = self.capture_record(cmd, image)?;
             transition_image(
                 &self.core.device,
                 cmd,
                 image,
-                vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL,
+                layout,
                 vk::ImageLayout::PRESENT_SRC_KHR,
             );
             self.swapchain.image_layouts[idx] = vk::ImageLayout::PRESENT_SRC_KHR;
 
             self.core.device.end_command_buffer(cmd)?;
 
+            let viewport_frames = self.record_viewports()?;
+
+            // Uploads recorded since the last frame (including this frame's UI textures) go
+            // ahead of it on the queue.
+            self.flush_uploads()?;
+
             let wait_stages = [vk::PipelineStageFlags::COLOR_ATTACHMENT_OUTPUT];
             let wait_sems = [frame.image_available];
             let signal_sems = [frame.render_finished];
             let cmd_bufs = [cmd];
 
-            let submit_infos = [vk::SubmitInfo::default()
+            // Secondary viewports go in the same submit, so the frame fence covers them.
+            let mut submit_infos = vec![vk::SubmitInfo::default()
                 .wait_semaphores(&wait_sems)
                 .wait_dst_stage_mask(&wait_stages)
                 .command_buffers(&cmd_bufs)
                 .signal_semaphores(&signal_sems)];
+            submit_infos.extend(viewport_frames.iter().map(|f| {
+                vk::SubmitInfo::default()
+                    .wait_semaphores(&f.image_available)
+                    .wait_dst_stage_mask(&wait_stages)
+                    .command_buffers(&f.cmd)
+                    .signal_semaphores(&f.render_finished)
+            }));
 
             self.core
                 .device
@@ -208,12 +251,18 @@ impl VulkanRenderer {
                 .swapchain_loader
                 .queue_present(self.core.queue, &present_info)
             {
-                Ok(_) => {}
+                Ok(suboptimal) => {
+                    if suboptimal {
+                        self.debug.swapchain_dirty = true;
+                    }
+                }
                 Err(vk::Result::ERROR_OUT_OF_DATE_KHR) | Err(vk::Result::SUBOPTIMAL_KHR) => {
                     self.debug.swapchain_dirty = true;
                 }
                 Err(e) => return Err(e.into()),
             }
+
+            self.present_viewports(&viewport_frames);
         }
 
         self.frames.frame_index = (self.frames.frame_index + 1) % FRAMES_IN_FLIGHT;
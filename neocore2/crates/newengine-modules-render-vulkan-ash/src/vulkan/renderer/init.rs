@@ -4,27 +4,40 @@ use ash::vk;
 use ash::{Device, Entry};
 use raw_window_handle::{RawDisplayHandle, RawWindowHandle};
 use std::ffi::CString;
+use std::path::Path;
 use std::time::Instant;
 
+use super::picking::ID_FORMAT;
 use super::state::UPLOAD_CONTEXTS;
 use super::state::{
-    CoreContext, DebugState, FrameManager, PipelinePack, SwapchainContext, TextOverlayResources,
-    UiOverlayResources, VulkanRenderer,
+    CaptureState, CoreContext, DebugLineResources, DebugUtilsContext, DebugState, FrameManager, GpuTimingState,
+    PickState, PipelinePack, SwapchainContext, TextOverlayResources, UiOverlayResources, ViewportSet,
+    VulkanRenderer,
 };
 use super::types::{FrameSync, FRAMES_IN_FLIGHT};
+use super::viewports::MAIN_VIEWPORT;
+use crate::vulkan::font::GlyphAtlas;
+use crate::vulkan::post::PostResources;
 use crate::vulkan::resources::{DeferredFree, UploadCtx};
+use crate::vulkan::textures::DEPTH_FORMAT;
+use crate::vulkan::transient::TransientRing;
 
+use super::super::debug_utils::messenger_create_info;
 use super::super::device::*;
 use super::super::instance::*;
 use super::super::pipeline::*;
+use super::super::pipeline_cache::create_pipeline_cache;
 use super::super::swapchain::*;
 
 impl VulkanRenderer {
+    /// `pipeline_cache_dir`: where the pipeline cache is loaded from and saved to; `None` keeps
+    /// it in memory only.
     pub unsafe fn new(
         display: RawDisplayHandle,
         window: RawWindowHandle,
         width: u32,
         height: u32,
+        pipeline_cache_dir: Option<&Path>,
     ) -> VkResult<Self> {
         let entry = Entry::load().map_err(|e| VkRenderError::AshWindow(e.to_string()))?;
 
@@ -42,7 +55,8 @@ impl VulkanRenderer {
             .map_err(|e| VkRenderError::AshWindow(e.to_string()))?
             .to_vec();
 
-        if cfg!(debug_assertions) {
+        let debug_utils_enabled = cfg!(debug_assertions);
+        if debug_utils_enabled {
             extension_names.push(ash::ext::debug_utils::NAME.as_ptr());
         }
 
@@ -65,8 +79,23 @@ impl VulkanRenderer {
             create_info = create_info.enabled_layer_names(&layer_ptrs);
         }
 
+        let mut instance_messenger = messenger_create_info();
+        if debug_utils_enabled {
+            create_info = create_info.push_next(&mut instance_messenger);
+        }
+
         let instance = entry.create_instance(&create_info, None)?;
 
+        let mut debug_utils = DebugUtilsContext::default();
+        if debug_utils_enabled {
+            let loader = ash::ext::debug_utils::Instance::new(&entry, &instance);
+            match loader.create_debug_utils_messenger(&messenger_create_info(), None) {
+                Ok(m) => debug_utils.messenger = m,
+                Err(e) => log::warn!("vulkan: debug messenger unavailable: {e:?}"),
+            }
+            debug_utils.instance = Some(loader);
+        }
+
         let surface = ash_window::create_surface(&entry, &instance, display, window, None)
             .map_err(|e| VkRenderError::AshWindow(e.to_string()))?;
 
@@ -75,8 +104,24 @@ impl VulkanRenderer {
         let (physical_device, queue_family_index) =
             pick_physical_device(&instance, &surface_loader, surface)?;
 
-        let (device, queue) = create_device(&instance, physical_device, queue_family_index)?;
+        let transfer_family = find_transfer_queue_family(&instance, physical_device);
+        let features = optional_features(&instance, physical_device);
+        let (device, queue, transfer_queue) = create_device(
+            &instance,
+            physical_device,
+            queue_family_index,
+            transfer_family,
+            &features,
+        )?;
+        let transfer_queue_family_index = transfer_family.unwrap_or(queue_family_index);
+        match transfer_family {
+            Some(family) => log::info!("vulkan: uploads use transfer queue family {family}"),
+            None => log::info!("vulkan: no transfer queue family; uploads use the graphics queue"),
+        }
         let swapchain_loader = ash::khr::swapchain::Device::new(&instance, &device);
+        if debug_utils_enabled {
+            debug_utils.device = Some(ash::ext::debug_utils::Device::new(&instance, &device));
+        }
 
         let (swapchain, images, format, extent) = create_swapchain(
             &swapchain_loader,
@@ -93,7 +138,12 @@ impl VulkanRenderer {
         let image_layouts = vec![vk::ImageLayout::UNDEFINED; images.len()];
 
         let render_pass = create_render_pass(&device, format)?;
-        let (tri_pipeline_layout, tri_pipeline) = create_pipeline(&device, render_pass)?;
+        let depth_render_pass = create_depth_render_pass(&device, DEPTH_FORMAT)?;
+        let id_render_pass = create_id_render_pass(&device, ID_FORMAT, DEPTH_FORMAT)?;
+        let (pipeline_cache, pipeline_cache_file) =
+            create_pipeline_cache(&instance, physical_device, &device, pipeline_cache_dir);
+        let (tri_pipeline_layout, tri_pipeline) =
+            create_pipeline(&device, render_pass, pipeline_cache)?;
         let framebuffers = create_framebuffers(&device, render_pass, &image_views, extent)?;
 
         let command_pool = device.create_command_pool(
@@ -117,11 +167,10 @@ impl VulkanRenderer {
             None,
         )?;
 
-        let mut upload_ctxs = [UploadCtx::default(); UPLOAD_CONTEXTS];
-        for ctx in &mut upload_ctxs {
+        let make_upload_ctx = |device: &Device, family: u32| -> VkResult<UploadCtx> {
             let pool = device.create_command_pool(
                 &vk::CommandPoolCreateInfo::default()
-                    .queue_family_index(queue_family_index)
+                    .queue_family_index(family)
                     .flags(vk::CommandPoolCreateFlags::RESET_COMMAND_BUFFER),
                 None,
             )?;
@@ -138,7 +187,22 @@ impl VulkanRenderer {
                 None,
             )?;
 
-            *ctx = UploadCtx { pool, cmd, fence };
+            Ok(UploadCtx { pool, cmd, fence })
+        };
+
+        let mut upload_ctxs = [UploadCtx::default(); UPLOAD_CONTEXTS];
+        let mut graphics_upload_ctxs = [UploadCtx::default(); UPLOAD_CONTEXTS];
+        let mut upload_semaphores = [vk::Semaphore::null(); UPLOAD_CONTEXTS];
+        for ((ctx, graphics_ctx), semaphore) in upload_ctxs
+            .iter_mut()
+            .zip(&mut graphics_upload_ctxs)
+            .zip(&mut upload_semaphores)
+        {
+            *ctx = make_upload_ctx(&device, transfer_queue_family_index)?;
+            if transfer_family.is_some() {
+                *graphics_ctx = make_upload_ctx(&device, queue_family_index)?;
+                *semaphore = device.create_semaphore(&vk::SemaphoreCreateInfo::default(), None)?;
+            }
         }
 
         let make_frame = |device: &Device| -> VkResult<FrameSync> {
@@ -161,6 +225,7 @@ impl VulkanRenderer {
         let images_in_flight = vec![vk::Fence::null(); images.len()];
 
         let core = CoreContext {
+            entry,
             instance,
             surface_loader,
             surface,
@@ -168,6 +233,10 @@ impl VulkanRenderer {
             device,
             queue_family_index,
             queue,
+            transfer_queue_family_index,
+            transfer_queue,
+            multi_draw_indirect: features.multi_draw_indirect == vk::TRUE,
+            fill_mode_non_solid: features.fill_mode_non_solid == vk::TRUE,
             swapchain_loader,
         };
 
@@ -183,6 +252,10 @@ impl VulkanRenderer {
 
         let pipelines = PipelinePack {
             render_pass,
+            depth_render_pass,
+            id_render_pass,
+            cache: pipeline_cache,
+            cache_file: pipeline_cache_file,
             tri_pipeline_layout,
             tri_pipeline,
             text_pipeline_layout: vk::PipelineLayout::null(),
@@ -201,9 +274,9 @@ impl VulkanRenderer {
             font_image_view: vk::ImageView::null(),
             font_sampler: vk::Sampler::null(),
 
-            vb: vk::Buffer::null(),
-            vb_mem: vk::DeviceMemory::null(),
-            vb_size: 0,
+            atlas: GlyphAtlas::new(),
+            atlas_resident: false,
+            pending: Vec::new(),
         };
 
         let ui = UiOverlayResources {
@@ -211,32 +284,29 @@ impl VulkanRenderer {
             desc_pool: vk::DescriptorPool::null(),
             sampler: vk::Sampler::null(),
             textures: std::collections::HashMap::new(),
+        };
 
-            vb: vk::Buffer::null(),
-            vb_mem: vk::DeviceMemory::null(),
-            vb_size: 0,
-
-            ib: vk::Buffer::null(),
-            ib_mem: vk::DeviceMemory::null(),
-            ib_size: 0,
-
-            staging_buf: vk::Buffer::null(),
-            staging_mem: vk::DeviceMemory::null(),
-            staging_size: 0,
+        let lines = DebugLineResources {
+            pipeline_layout: vk::PipelineLayout::null(),
+            pipeline: vk::Pipeline::null(),
         };
 
         let debug = DebugState {
             debug_text: String::new(),
             start_time: Instant::now(),
             pending_ui: None,
+            pending_debug_draw: None,
+            clear_color: [0.0; 4],
             target_width: width,
             target_height: height,
 
             swapchain_dirty: false,
 
             in_frame: false,
+            frame_skipped: false,
             current_image_index: 0,
             current_swapchain_idx: 0,
+            main_pass_open: false,
         };
 
         let mut me = Self {
@@ -252,16 +322,38 @@ impl VulkanRenderer {
                 upload_command_pool,
 
                 upload_ctxs,
+                graphics_upload_ctxs,
+                upload_semaphores,
                 upload_cursor: 0,
+                upload_batch: None,
                 deferred_free: DeferredFree::new(),
             },
             text,
             ui,
+            lines,
+            post: PostResources::default(),
             debug,
+            timing: GpuTimingState {
+                query_pool: vk::QueryPool::null(),
+                period_ns: 0.0,
+                written: [false; FRAMES_IN_FLIGHT],
+                last: Vec::new(),
+            },
+            capture: CaptureState::default(),
+            pick: PickState::default(),
+            transient: TransientRing::default(),
+            viewports: ViewportSet {
+                next_id: MAIN_VIEWPORT + 1,
+                targets: std::collections::HashMap::new(),
+            },
+            debug_utils,
         };
 
         me.init_text_overlay()?;
         me.init_ui_overlay()?;
+        me.init_debug_lines()?;
+        me.init_post()?;
+        me.init_gpu_timing();
 
         Ok(me)
     }
@@ -1,8 +1,14 @@
 mod api;
+mod capture;
 mod frame;
 mod drop_impl;
 mod init;
+mod picking;
 mod state;
+mod timing;
 mod types;
+mod viewports;
 
 pub use state::VulkanRenderer;
+pub(crate) use state::UPLOAD_CONTEXTS;
+pub(crate) use types::FRAMES_IN_FLIGHT;
@@ -0,0 +1,288 @@
+use crate::error::{VkRenderError, VkResult};
+
+use ash::vk;
+use ash::Device;
+use newengine_core::render::PickResult;
+
+use super::super::device::create_buffer;
+use super::state::{IdTarget, PendingPick, VulkanRenderer};
+use crate::vulkan::textures::DEPTH_FORMAT;
+
+/// Format of the object-ID target (`TextureFormat::R32Uint`).
+pub(crate) const ID_FORMAT: vk::Format = vk::Format::R32_UINT;
+
+impl VulkanRenderer {
+    /// Marks pixel `(x, y)` of the next object-ID pass for readback, replacing a request not
+    /// yet recorded.
+    #[inline]
+    pub fn request_pick(&mut self, x: u32, y: u32) {
+        self.pick.requested = Some((x, y));
+    }
+
+    /// The id read back for [`Self::request_pick`], once the GPU is done with its frame.
+    #[inline]
+    pub fn take_pick(&mut self) -> Option<PickResult> {
+        self.pick.ready.take()
+    }
+
+    /// Begins the object-ID render pass over a target the size of the swapchain, (re)creating
+    /// the target first when needed. Returns the target extent.
+    pub(crate) unsafe fn id_pass_begin(
+        &mut self,
+        cmd: vk::CommandBuffer,
+    ) -> VkResult<vk::Extent2D> {
+        let extent = self.swapchain.extent;
+        if self.pick.target.as_ref().map(|t| t.extent) != Some(extent) {
+            if let Some(old) = self.pick.target.take() {
+                self.destroy_id_target(old);
+            }
+            self.pick.target = Some(self.create_id_target(extent)?);
+        }
+        let Some(target) = self.pick.target.as_ref() else {
+            return Err(VkRenderError::InvalidState("object-ID target missing"));
+        };
+
+        let clears = [
+            vk::ClearValue {
+                color: vk::ClearColorValue { uint32: [0; 4] },
+            },
+            vk::ClearValue {
+                depth_stencil: vk::ClearDepthStencilValue {
+                    depth: 1.0,
+                    stencil: 0,
+                },
+            },
+        ];
+        let rp_begin = vk::RenderPassBeginInfo::default()
+            .render_pass(self.pipelines.id_render_pass)
+            .framebuffer(target.framebuffer)
+            .render_area(vk::Rect2D {
+                offset: vk::Offset2D { x: 0, y: 0 },
+                extent,
+            })
+            .clear_values(&clears);
+        self.core
+            .device
+            .cmd_begin_render_pass(cmd, &rp_begin, vk::SubpassContents::INLINE);
+        Ok(extent)
+    }
+
+    /// Ends the object-ID render pass and records the copy of the requested pixel, if any,
+    /// into a host-visible buffer.
+    pub(crate) unsafe fn id_pass_end(&mut self, cmd: vk::CommandBuffer) -> VkResult<()> {
+        self.core.device.cmd_end_render_pass(cmd);
+
+        if self.pick.pending.is_some() {
+            return Ok(());
+        }
+        let Some((x, y)) = self.pick.requested else {
+            return Ok(());
+        };
+        let Some(target) = self.pick.target.as_ref() else {
+            return Ok(());
+        };
+        self.pick.requested = None;
+        if x >= target.extent.width || y >= target.extent.height {
+            // Outside the frame: nothing is there.
+            self.pick.ready = Some(PickResult { x, y, id: 0 });
+            return Ok(());
+        }
+
+        let (buffer, memory) = create_buffer(
+            &self.core.instance,
+            self.core.physical_device,
+            &self.core.device,
+            4,
+            vk::BufferUsageFlags::TRANSFER_DST,
+            vk::MemoryPropertyFlags::HOST_VISIBLE | vk::MemoryPropertyFlags::HOST_COHERENT,
+        )?;
+
+        let device = &self.core.device;
+        let region = vk::BufferImageCopy::default()
+            .image_subresource(
+                vk::ImageSubresourceLayers::default()
+                    .aspect_mask(vk::ImageAspectFlags::COLOR)
+                    .layer_count(1),
+            )
+            .image_offset(vk::Offset3D {
+                x: x as i32,
+                y: y as i32,
+                z: 0,
+            })
+            .image_extent(vk::Extent3D {
+                width: 1,
+                height: 1,
+                depth: 1,
+            });
+        device.cmd_copy_image_to_buffer(
+            cmd,
+            target.color,
+            vk::ImageLayout::TRANSFER_SRC_OPTIMAL,
+            buffer,
+            std::slice::from_ref(&region),
+        );
+
+        let barrier = vk::BufferMemoryBarrier::default()
+            .src_access_mask(vk::AccessFlags::TRANSFER_WRITE)
+            .dst_access_mask(vk::AccessFlags::HOST_READ)
+            .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+            .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+            .buffer(buffer)
+            .size(vk::WHOLE_SIZE);
+        device.cmd_pipeline_barrier(
+            cmd,
+            vk::PipelineStageFlags::TRANSFER,
+            vk::PipelineStageFlags::HOST,
+            vk::DependencyFlags::empty(),
+            &[],
+            std::slice::from_ref(&barrier),
+            &[],
+        );
+
+        self.pick.pending = Some(PendingPick {
+            buffer,
+            memory,
+            slot: self.frames.frame_index,
+            x,
+            y,
+        });
+        Ok(())
+    }
+
+    /// Reads back the pending pick once its frame slot's fence has signaled; does not wait.
+    pub(super) unsafe fn pick_collect(&mut self) {
+        let Some(slot) = self.pick.pending.as_ref().map(|p| p.slot) else {
+            return;
+        };
+        let fence = self.frames.frames[slot].in_flight;
+        if !matches!(self.core.device.get_fence_status(fence), Ok(true)) {
+            return;
+        }
+        let Some(p) = self.pick.pending.take() else {
+            return;
+        };
+
+        let device = &self.core.device;
+        match device.map_memory(p.memory, 0, 4, vk::MemoryMapFlags::empty()) {
+            Ok(ptr) => {
+                let id = std::ptr::read_unaligned(ptr as *const u32);
+                device.unmap_memory(p.memory);
+                self.pick.ready = Some(PickResult { x: p.x, y: p.y, id });
+            }
+            Err(e) => log::warn!("vulkan: pick readback failed: {e}"),
+        }
+        device.destroy_buffer(p.buffer, None);
+        device.free_memory(p.memory, None);
+    }
+
+    unsafe fn create_id_target(&self, extent: vk::Extent2D) -> VkResult<IdTarget> {
+        let (color, color_memory, color_view) = self.create_image(
+            ID_FORMAT,
+            extent,
+            false,
+            vk::ImageUsageFlags::COLOR_ATTACHMENT | vk::ImageUsageFlags::TRANSFER_SRC,
+            vk::ImageAspectFlags::COLOR,
+            Some("object id"),
+        )?;
+        let mut target = IdTarget {
+            color,
+            color_memory,
+            color_view,
+            depth: vk::Image::null(),
+            depth_memory: vk::DeviceMemory::null(),
+            depth_view: vk::ImageView::null(),
+            framebuffer: vk::Framebuffer::null(),
+            extent,
+        };
+
+        let device = &self.core.device;
+        match self.create_image(
+            DEPTH_FORMAT,
+            extent,
+            false,
+            vk::ImageUsageFlags::DEPTH_STENCIL_ATTACHMENT,
+            vk::ImageAspectFlags::DEPTH,
+            Some("object id depth"),
+        ) {
+            Ok((image, memory, view)) => {
+                target.depth = image;
+                target.depth_memory = memory;
+                target.depth_view = view;
+            }
+            Err(e) => {
+                destroy_id_target_now(device, &target);
+                return Err(e);
+            }
+        }
+
+        let attachments = [target.color_view, target.depth_view];
+        let fb_info = vk::FramebufferCreateInfo::default()
+            .render_pass(self.pipelines.id_render_pass)
+            .attachments(&attachments)
+            .width(extent.width)
+            .height(extent.height)
+            .layers(1);
+        match device.create_framebuffer(&fb_info, None) {
+            Ok(fb) => target.framebuffer = fb,
+            Err(e) => {
+                destroy_id_target_now(device, &target);
+                return Err(e.into());
+            }
+        }
+        Ok(target)
+    }
+
+    /// Frees `target` once frames in flight are done with it.
+    unsafe fn destroy_id_target(&mut self, target: IdTarget) {
+        match self.upload_fence() {
+            Ok(fence) => {
+                let deferred = &mut self.frames.deferred_free;
+                deferred.push_framebuffer(fence, target.framebuffer);
+                for (image, view, memory) in target.images() {
+                    deferred.push_image(fence, image, view, memory, vk::Sampler::null());
+                }
+            }
+            Err(_) => destroy_id_target_now(&self.core.device, &target),
+        }
+    }
+
+    pub(super) unsafe fn destroy_pick(&mut self) {
+        let device = &self.core.device;
+        if let Some(p) = self.pick.pending.take() {
+            device.destroy_buffer(p.buffer, None);
+            device.free_memory(p.memory, None);
+        }
+        if let Some(target) = self.pick.target.take() {
+            destroy_id_target_now(device, &target);
+        }
+        self.pick.requested = None;
+        self.pick.ready = None;
+    }
+}
+
+impl IdTarget {
+    #[inline]
+    fn images(&self) -> [(vk::Image, vk::ImageView, vk::DeviceMemory); 2] {
+        [
+            (self.color, self.color_view, self.color_memory),
+            (self.depth, self.depth_view, self.depth_memory),
+        ]
+    }
+}
+
+unsafe fn destroy_id_target_now(device: &Device, target: &IdTarget) {
+    if target.framebuffer != vk::Framebuffer::null() {
+        device.destroy_framebuffer(target.framebuffer, None);
+    }
+    for (image, view, memory) in target.images() {
+        if view != vk::ImageView::null() {
+            device.destroy_image_view(view, None);
+        }
+        if image != vk::Image::null() {
+            device.destroy_image(image, None);
+        }
+        if memory != vk::DeviceMemory::null() {
+            device.free_memory(memory, None);
+        }
+    }
+}
@@ -1,15 +1,21 @@
 use ash::vk;
+use newengine_core::render::{CapturedFrame, DebugDrawBatch, PickResult, TextDraw};
 use newengine_ui::draw::UiDrawList;
 use std::collections::HashMap;
 use std::time::Instant;
 
 use super::types::{FrameSync, FRAMES_IN_FLIGHT};
+use crate::vulkan::font::GlyphAtlas;
+use crate::vulkan::post::PostResources;
 use crate::vulkan::resources::{DeferredFree, UploadCtx};
+use crate::vulkan::transient::TransientRing;
 use crate::vulkan::ui::GpuUiTexture;
 
 pub(crate) const UPLOAD_CONTEXTS: usize = 3;
 
 pub struct CoreContext {
+    /// Kept loaded for surfaces created after init (secondary viewports).
+    pub(crate) entry: ash::Entry,
     pub(crate) instance: ash::Instance,
 
     pub(crate) surface_loader: ash::khr::surface::Instance,
@@ -21,9 +27,26 @@ pub struct CoreContext {
     pub(crate) queue_family_index: u32,
     pub(crate) queue: vk::Queue,
 
+    /// Upload queue; same family and queue as graphics when there is no dedicated one.
+    pub(crate) transfer_queue_family_index: u32,
+    pub(crate) transfer_queue: vk::Queue,
+
+    /// Several draws per indirect command; without it they are issued one by one.
+    pub(crate) multi_draw_indirect: bool,
+    /// Line polygon mode (wireframe pipelines).
+    pub(crate) fill_mode_non_solid: bool,
+
     pub(crate) swapchain_loader: ash::khr::swapchain::Device,
 }
 
+impl CoreContext {
+    /// Uploads run on their own queue family and need ownership transfers.
+    #[inline]
+    pub(crate) fn has_transfer_queue(&self) -> bool {
+        self.transfer_queue_family_index != self.queue_family_index
+    }
+}
+
 pub struct SwapchainContext {
     pub(crate) swapchain: vk::SwapchainKHR,
     pub(crate) images: Vec<vk::Image>,
@@ -34,8 +57,41 @@ pub struct SwapchainContext {
     pub(crate) image_layouts: Vec<vk::ImageLayout>,
 }
 
+/// A secondary OS window (see `create_viewport`). It shares the render pass and UI pipeline
+/// with the main window, so its surface must use the same format. Its command buffer is
+/// submitted with the main frame and covered by the same in-flight fence.
+pub struct ViewportTarget {
+    pub(crate) surface: vk::SurfaceKHR,
+    /// Null until the window has a drawable area.
+    pub(crate) swapchain: SwapchainContext,
+    pub(crate) target_width: u32,
+    pub(crate) target_height: u32,
+    pub(crate) swapchain_dirty: bool,
+
+    pub(crate) command_pool: vk::CommandPool,
+    pub(crate) command_buffers: [vk::CommandBuffer; FRAMES_IN_FLIGHT],
+    pub(crate) image_available: [vk::Semaphore; FRAMES_IN_FLIGHT],
+    pub(crate) render_finished: [vk::Semaphore; FRAMES_IN_FLIGHT],
+
+    pub(crate) pending_ui: Option<UiDrawList>,
+}
+
+/// Secondary viewports by id; id 1 is the main window.
+pub struct ViewportSet {
+    pub(crate) next_id: u32,
+    pub(crate) targets: HashMap<u32, ViewportTarget>,
+}
+
 pub struct PipelinePack {
     pub(crate) render_pass: vk::RenderPass,
+    /// Depth passes into `TextureUsage::DepthStencil` textures (`textures::DEPTH_FORMAT`).
+    pub(crate) depth_render_pass: vk::RenderPass,
+    /// Object-ID pass (`picking::ID_FORMAT` ids, `textures::DEPTH_FORMAT` depth).
+    pub(crate) id_render_pass: vk::RenderPass,
+
+    /// Shared by every pipeline; persisted to `cache_file` on drop.
+    pub(crate) cache: vk::PipelineCache,
+    pub(crate) cache_file: Option<std::path::PathBuf>,
 
     pub(crate) tri_pipeline_layout: vk::PipelineLayout,
     pub(crate) tri_pipeline: vk::Pipeline,
@@ -47,6 +103,17 @@ pub struct PipelinePack {
     pub(crate) ui_pipeline: vk::Pipeline,
 }
 
+/// Frames in flight and their uploads.
+///
+/// Frame resource lifetime: the command buffer of frame slot `i` may read anything it references
+/// until `frames[i].in_flight` signals; `begin_frame` waits on that fence before reusing the slot.
+/// Therefore:
+/// - data written every frame (UI, text and debug geometry, per-draw constants) is allocated from
+///   the transient ring, which rewinds a slot only after that wait; a buffer rewritten in place
+///   each frame would race the previous frame on the GPU;
+/// - writes into long-lived resources go through the upload batch, submitted ahead of the frame;
+/// - resources a frame may still reference are destroyed through `deferred_free`, keyed by a
+///   fence that signals after their last use.
 pub struct FrameManager {
     pub(crate) frames: [FrameSync; FRAMES_IN_FLIGHT],
     pub(crate) frame_index: usize,
@@ -58,8 +125,16 @@ pub struct FrameManager {
     // New code should use `upload_ctxs`.
     pub(crate) upload_command_pool: vk::CommandPool,
 
+    /// Transfer-family contexts; one per batch of uploads.
     pub(crate) upload_ctxs: [UploadCtx; UPLOAD_CONTEXTS],
+    /// Graphics-family halves of the same batches (ownership acquires, patches of images in
+    /// use). Only used with a dedicated transfer queue.
+    pub(crate) graphics_upload_ctxs: [UploadCtx; UPLOAD_CONTEXTS],
+    /// Signaled by the transfer half of a batch, waited on by its graphics half.
+    pub(crate) upload_semaphores: [vk::Semaphore; UPLOAD_CONTEXTS],
     pub(crate) upload_cursor: usize,
+    /// Batch being recorded; submitted by `end_frame`.
+    pub(crate) upload_batch: Option<usize>,
     pub(crate) deferred_free: DeferredFree,
 }
 
@@ -73,9 +148,11 @@ pub struct TextOverlayResources {
     pub(crate) font_image_view: vk::ImageView,
     pub(crate) font_sampler: vk::Sampler,
 
-    pub(crate) vb: vk::Buffer,
-    pub(crate) vb_mem: vk::DeviceMemory,
-    pub(crate) vb_size: vk::DeviceSize,
+    pub(crate) atlas: GlyphAtlas,
+    /// False until the atlas image got its first full upload.
+    pub(crate) atlas_resident: bool,
+    /// Text runs queued for the next presented frame.
+    pub(crate) pending: Vec<TextDraw>,
 }
 
 pub struct UiOverlayResources {
@@ -84,18 +161,11 @@ pub struct UiOverlayResources {
     pub(crate) sampler: vk::Sampler,
 
     pub(crate) textures: HashMap<u32, GpuUiTexture>,
+}
 
-    pub(crate) vb: vk::Buffer,
-    pub(crate) vb_mem: vk::DeviceMemory,
-    pub(crate) vb_size: vk::DeviceSize,
-
-    pub(crate) ib: vk::Buffer,
-    pub(crate) ib_mem: vk::DeviceMemory,
-    pub(crate) ib_size: vk::DeviceSize,
-
-    pub(crate) staging_buf: vk::Buffer,
-    pub(crate) staging_mem: vk::DeviceMemory,
-    pub(crate) staging_size: vk::DeviceSize,
+pub struct DebugLineResources {
+    pub(crate) pipeline_layout: vk::PipelineLayout,
+    pub(crate) pipeline: vk::Pipeline,
 }
 
 pub struct DebugState {
@@ -103,6 +173,9 @@ pub struct DebugState {
     pub(crate) start_time: Instant,
 
     pub(crate) pending_ui: Option<UiDrawList>,
+    pub(crate) pending_debug_draw: Option<DebugDrawBatch>,
+    /// Clear color of the current frame; secondary viewports are cleared with it too.
+    pub(crate) clear_color: [f32; 4],
 
     pub(crate) target_width: u32,
     pub(crate) target_height: u32,
@@ -112,8 +185,78 @@ pub struct DebugState {
 
     // Per-frame recording state.
     pub(crate) in_frame: bool,
+    /// `begin_frame` returned without starting a frame (minimized, swapchain unavailable);
+    /// the matching `end_frame` is a no-op.
+    pub(crate) frame_skipped: bool,
     pub(crate) current_image_index: u32,
     pub(crate) current_swapchain_idx: usize,
+    /// The scene render pass was begun; depth passes of the frame are recorded before it.
+    pub(crate) main_pass_open: bool,
+}
+
+pub struct GpuTimingState {
+    /// `TIMESTAMPS_PER_FRAME` queries per frame in flight; null when timing is unsupported.
+    pub(crate) query_pool: vk::QueryPool,
+    pub(crate) period_ns: f32,
+    /// Slot has timestamps recorded that were not read back yet.
+    pub(crate) written: [bool; FRAMES_IN_FLIGHT],
+    pub(crate) last: Vec<(&'static str, f32)>,
+}
+
+/// Swapchain copy recorded into frame slot `slot`, waiting for that slot's fence.
+pub struct PendingCapture {
+    pub(crate) buffer: vk::Buffer,
+    pub(crate) memory: vk::DeviceMemory,
+    pub(crate) slot: usize,
+    pub(crate) extent: vk::Extent2D,
+    pub(crate) format: vk::Format,
+}
+
+#[derive(Default)]
+pub struct CaptureState {
+    /// Copy the next presented frame.
+    pub(crate) requested: bool,
+    pub(crate) pending: Option<PendingCapture>,
+    pub(crate) ready: Option<CapturedFrame>,
+}
+
+/// Frame-sized object-ID target and the depth buffer it is drawn with.
+pub struct IdTarget {
+    pub(crate) color: vk::Image,
+    pub(crate) color_memory: vk::DeviceMemory,
+    pub(crate) color_view: vk::ImageView,
+    pub(crate) depth: vk::Image,
+    pub(crate) depth_memory: vk::DeviceMemory,
+    pub(crate) depth_view: vk::ImageView,
+    pub(crate) framebuffer: vk::Framebuffer,
+    pub(crate) extent: vk::Extent2D,
+}
+
+/// Id texel copied in frame slot `slot`, waiting for that slot's fence.
+pub struct PendingPick {
+    pub(crate) buffer: vk::Buffer,
+    pub(crate) memory: vk::DeviceMemory,
+    pub(crate) slot: usize,
+    pub(crate) x: u32,
+    pub(crate) y: u32,
+}
+
+#[derive(Default)]
+pub struct PickState {
+    /// Pixel to copy out of the next object-ID pass.
+    pub(crate) requested: Option<(u32, u32)>,
+    pub(crate) pending: Option<PendingPick>,
+    pub(crate) ready: Option<PickResult>,
+    /// Created by the first object-ID pass, recreated when the frame size changes.
+    pub(crate) target: Option<IdTarget>,
+}
+
+/// `VK_EXT_debug_utils` loaders; `None` unless debug utils are enabled (debug builds).
+#[derive(Default)]
+pub struct DebugUtilsContext {
+    pub(crate) instance: Option<ash::ext::debug_utils::Instance>,
+    pub(crate) device: Option<ash::ext::debug_utils::Device>,
+    pub(crate) messenger: vk::DebugUtilsMessengerEXT,
 }
 
 pub struct VulkanRenderer {
@@ -123,5 +266,13 @@ pub struct VulkanRenderer {
     pub(crate) frames: FrameManager,
     pub(crate) text: TextOverlayResources,
     pub(crate) ui: UiOverlayResources,
+    pub(crate) lines: DebugLineResources,
+    pub(crate) post: PostResources,
     pub(crate) debug: DebugState,
+    pub(crate) timing: GpuTimingState,
+    pub(crate) capture: CaptureState,
+    pub(crate) pick: PickState,
+    pub(crate) transient: TransientRing,
+    pub(crate) viewports: ViewportSet,
+    pub(crate) debug_utils: DebugUtilsContext,
 }
\ No newline at end of file
@@ -0,0 +1,133 @@
+use ash::vk;
+
+use super::state::VulkanRenderer;
+use super::types::FRAMES_IN_FLIGHT;
+
+/// Timestamps written per frame: frame start, before the post chain, before UI, after the
+/// swapchain render pass.
+const TIMESTAMPS_PER_FRAME: u32 = 4;
+
+/// Pass names for consecutive timestamp pairs.
+const PASS_NAMES: [&str; (TIMESTAMPS_PER_FRAME - 1) as usize] = ["scene", "post", "ui"];
+
+pub(super) mod mark {
+    pub(crate) const FRAME_BEGIN: u32 = 0;
+    pub(crate) const POST_BEGIN: u32 = 1;
+    pub(crate) const UI_BEGIN: u32 = 2;
+    pub(crate) const FRAME_END: u32 = 3;
+}
+
+impl VulkanRenderer {
+    /// Creates the timestamp query pool. GPU timing stays off when the queue cannot time.
+    pub(super) unsafe fn init_gpu_timing(&mut self) {
+        let props = self
+            .core
+            .instance
+            .get_physical_device_properties(self.core.physical_device);
+        let families = self
+            .core
+            .instance
+            .get_physical_device_queue_family_properties(self.core.physical_device);
+        let valid_bits = families
+            .get(self.core.queue_family_index as usize)
+            .map(|f| f.timestamp_valid_bits)
+            .unwrap_or(0);
+
+        if valid_bits == 0 || props.limits.timestamp_period <= 0.0 {
+            log::info!("vulkan: gpu timing unavailable (no timestamp support on queue)");
+            return;
+        }
+
+        let info = vk::QueryPoolCreateInfo::default()
+            .query_type(vk::QueryType::TIMESTAMP)
+            .query_count(TIMESTAMPS_PER_FRAME * FRAMES_IN_FLIGHT as u32);
+
+        match self.core.device.create_query_pool(&info, None) {
+            Ok(pool) => {
+                self.timing.query_pool = pool;
+                self.timing.period_ns = props.limits.timestamp_period;
+            }
+            Err(e) => log::warn!("vulkan: gpu timing disabled (query pool: {e:?})"),
+        }
+    }
+
+    pub(super) unsafe fn destroy_gpu_timing(&mut self) {
+        if self.timing.query_pool != vk::QueryPool::null() {
+            self.core
+                .device
+                .destroy_query_pool(self.timing.query_pool, None);
+            self.timing.query_pool = vk::QueryPool::null();
+        }
+    }
+
+    /// Reads the results of frame slot `slot`. Call after waiting on its in-flight fence.
+    pub(super) unsafe fn gpu_timing_collect(&mut self, slot: usize) {
+        if self.timing.query_pool == vk::QueryPool::null() || !self.timing.written[slot] {
+            return;
+        }
+        self.timing.written[slot] = false;
+
+        let mut ticks = [0u64; TIMESTAMPS_PER_FRAME as usize];
+        if self
+            .core
+            .device
+            .get_query_pool_results(
+                self.timing.query_pool,
+                slot as u32 * TIMESTAMPS_PER_FRAME,
+                &mut ticks,
+                vk::QueryResultFlags::TYPE_64,
+            )
+            .is_err()
+        {
+            return;
+        }
+
+        let to_ms = self.timing.period_ns as f64 / 1_000_000.0;
+        self.timing.last = PASS_NAMES
+            .iter()
+            .zip(ticks.windows(2))
+            .map(|(name, w)| (*name, (w[1].saturating_sub(w[0]) as f64 * to_ms) as f32))
+            .collect();
+    }
+
+    /// Resets the slot's queries; must be recorded outside a render pass.
+    pub(super) unsafe fn gpu_timing_reset(&mut self, cmd: vk::CommandBuffer) {
+        if self.timing.query_pool == vk::QueryPool::null() {
+            return;
+        }
+        self.core.device.cmd_reset_query_pool(
+            cmd,
+            self.timing.query_pool,
+            self.frames.frame_index as u32 * TIMESTAMPS_PER_FRAME,
+            TIMESTAMPS_PER_FRAME,
+        );
+    }
+
+    /// Writes timestamp `which` (see [`mark`]) for the current frame slot.
+    pub(super) unsafe fn gpu_timing_mark(&mut self, cmd: vk::CommandBuffer, which: u32) {
+        if self.timing.query_pool == vk::QueryPool::null() {
+            return;
+        }
+        let stage = if which == mark::FRAME_BEGIN {
+            vk::PipelineStageFlags::TOP_OF_PIPE
+        } else {
+            vk::PipelineStageFlags::BOTTOM_OF_PIPE
+        };
+        let slot = self.frames.frame_index;
+        self.core.device.cmd_write_timestamp(
+            cmd,
+            stage,
+            self.timing.query_pool,
+            slot as u32 * TIMESTAMPS_PER_FRAME + which,
+        );
+        if which == mark::FRAME_END {
+            self.timing.written[slot] = true;
+        }
+    }
+
+    /// `(pass, ms)` of the newest frame whose timestamps have been read back.
+    #[inline]
+    pub fn gpu_pass_timings(&self) -> &[(&'static str, f32)] {
+        &self.timing.last
+    }
+}
@@ -1,6 +1,6 @@
 use ash::vk;
 
-pub(super) const FRAMES_IN_FLIGHT: usize = 2;
+pub(crate) const FRAMES_IN_FLIGHT: usize = 2;
 
 #[derive(Clone, Copy)]
 
@@ -0,0 +1,416 @@
+use crate::error::{VkRenderError, VkResult};
+use crate::vulkan::util::transition_image;
+
+use ash::vk;
+use newengine_ui::draw::UiDrawList;
+use raw_window_handle::{RawDisplayHandle, RawWindowHandle};
+
+use super::state::{CoreContext, SwapchainContext, ViewportTarget, VulkanRenderer};
+use super::types::FRAMES_IN_FLIGHT;
+
+use super::super::pipeline::create_framebuffers;
+use super::super::swapchain::{create_image_views, create_swapchain};
+
+/// Id of the window the renderer was created for.
+pub(crate) const MAIN_VIEWPORT: u32 = 1;
+
+/// A secondary viewport image recorded this frame, waiting to be submitted and presented.
+pub(crate) struct ViewportFrame {
+    id: u32,
+    image_index: u32,
+    pub(crate) cmd: [vk::CommandBuffer; 1],
+    pub(crate) image_available: [vk::Semaphore; 1],
+    pub(crate) render_finished: [vk::Semaphore; 1],
+}
+
+fn empty_swapchain() -> SwapchainContext {
+    SwapchainContext {
+        swapchain: vk::SwapchainKHR::null(),
+        images: Vec::new(),
+        image_views: Vec::new(),
+        format: vk::Format::UNDEFINED,
+        extent: vk::Extent2D::default(),
+        framebuffers: Vec::new(),
+        image_layouts: Vec::new(),
+    }
+}
+
+/// Destroys framebuffers and views; the swapchain handle is left to the caller.
+unsafe fn destroy_views(device: &ash::Device, sc: &mut SwapchainContext) {
+    for fb in sc.framebuffers.drain(..) {
+        device.destroy_framebuffer(fb, None);
+    }
+    for iv in sc.image_views.drain(..) {
+        device.destroy_image_view(iv, None);
+    }
+}
+
+/// (Re)creates the swapchain of `vp` for its target size. The GPU must not use the old one.
+unsafe fn rebuild_swapchain(
+    core: &CoreContext,
+    render_pass: vk::RenderPass,
+    format: vk::Format,
+    vp: &mut ViewportTarget,
+) -> VkResult<()> {
+    destroy_views(&core.device, &mut vp.swapchain);
+
+    let old = std::mem::replace(&mut vp.swapchain.swapchain, vk::SwapchainKHR::null());
+    let created = create_swapchain(
+        &core.swapchain_loader,
+        &core.surface_loader,
+        vp.surface,
+        core.physical_device,
+        vp.target_width,
+        vp.target_height,
+        core.queue_family_index,
+        old,
+    );
+    if old != vk::SwapchainKHR::null() {
+        core.swapchain_loader.destroy_swapchain(old, None);
+    }
+    let (swapchain, images, new_format, extent) = created?;
+    vp.swapchain.swapchain = swapchain;
+
+    // Framebuffers must be compatible with the shared render pass.
+    if new_format != format {
+        return Err(VkRenderError::InvalidState(
+            "viewport surface format differs from the main window",
+        ));
+    }
+
+    vp.swapchain.image_views = create_image_views(&core.device, &images, new_format)?;
+    vp.swapchain.framebuffers =
+        create_framebuffers(&core.device, render_pass, &vp.swapchain.image_views, extent)?;
+    vp.swapchain.image_layouts = vec![vk::ImageLayout::UNDEFINED; images.len()];
+    vp.swapchain.images = images;
+    vp.swapchain.format = new_format;
+    vp.swapchain.extent = extent;
+    Ok(())
+}
+
+impl VulkanRenderer {
+    /// Adds `window` as a presentation target and returns its id. It shows the UI list set with
+    /// `set_viewport_ui_draw_list`, cleared to the frame's clear color, and is presented by
+    /// `end_frame` together with the main window.
+    pub unsafe fn create_viewport(
+        &mut self,
+        display: RawDisplayHandle,
+        window: RawWindowHandle,
+        width: u32,
+        height: u32,
+    ) -> VkResult<u32> {
+        let surface = ash_window::create_surface(
+            &self.core.entry,
+            &self.core.instance,
+            display,
+            window,
+            None,
+        )
+        .map_err(|e| VkRenderError::AshWindow(e.to_string()))?;
+
+        let mut vp = ViewportTarget {
+            surface,
+            swapchain: empty_swapchain(),
+            target_width: width,
+            target_height: height,
+            swapchain_dirty: false,
+            command_pool: vk::CommandPool::null(),
+            command_buffers: [vk::CommandBuffer::null(); FRAMES_IN_FLIGHT],
+            image_available: [vk::Semaphore::null(); FRAMES_IN_FLIGHT],
+            render_finished: [vk::Semaphore::null(); FRAMES_IN_FLIGHT],
+            pending_ui: None,
+        };
+        if let Err(e) = self.init_viewport(&mut vp) {
+            self.destroy_viewport_target(&mut vp);
+            return Err(e);
+        }
+
+        let id = self.viewports.next_id;
+        self.viewports.next_id += 1;
+        self.viewports.targets.insert(id, vp);
+        log::info!("vulkan: viewport {id} created ({width}x{height})");
+        Ok(id)
+    }
+
+    unsafe fn init_viewport(&self, vp: &mut ViewportTarget) -> VkResult<()> {
+        let core = &self.core;
+        let supported = core.surface_loader.get_physical_device_surface_support(
+            core.physical_device,
+            core.queue_family_index,
+            vp.surface,
+        )?;
+        if !supported {
+            return Err(VkRenderError::InvalidState(
+                "graphics queue cannot present to the viewport window",
+            ));
+        }
+
+        vp.command_pool = core.device.create_command_pool(
+            &vk::CommandPoolCreateInfo::default()
+                .queue_family_index(core.queue_family_index)
+                .flags(vk::CommandPoolCreateFlags::RESET_COMMAND_BUFFER),
+            None,
+        )?;
+        let cmds = core.device.allocate_command_buffers(
+            &vk::CommandBufferAllocateInfo::default()
+                .command_pool(vp.command_pool)
+                .level(vk::CommandBufferLevel::PRIMARY)
+                .command_buffer_count(FRAMES_IN_FLIGHT as u32),
+        )?;
+        vp.command_buffers.copy_from_slice(&cmds);
+
+        for s in vp
+            .image_available
+            .iter_mut()
+            .chain(vp.render_finished.iter_mut())
+        {
+            *s = core
+                .device
+                .create_semaphore(&vk::SemaphoreCreateInfo::default(), None)?;
+        }
+
+        // A window without a drawable area gets its swapchain once it is resized.
+        if vp.target_width == 0 || vp.target_height == 0 {
+            vp.swapchain_dirty = true;
+            return Ok(());
+        }
+        rebuild_swapchain(core, self.pipelines.render_pass, self.swapchain.format, vp)
+    }
+
+    /// Removes a viewport; unknown ids are ignored. Waits for the GPU to finish with it.
+    pub unsafe fn destroy_viewport(&mut self, id: u32) {
+        let Some(mut vp) = self.viewports.targets.remove(&id) else {
+            return;
+        };
+        let _ = self.core.device.device_wait_idle();
+        self.destroy_viewport_target(&mut vp);
+    }
+
+    /// Records the new window size; the swapchain is recreated before the next frame draws it.
+    pub fn resize_viewport(&mut self, id: u32, width: u32, height: u32) -> VkResult<()> {
+        let Some(vp) = self.viewports.targets.get_mut(&id) else {
+            return Err(VkRenderError::InvalidState("unknown viewport"));
+        };
+        if vp.target_width != width || vp.target_height != height {
+            vp.target_width = width;
+            vp.target_height = height;
+            vp.swapchain_dirty = true;
+        }
+        Ok(())
+    }
+
+    /// Stores the UI for the next frame of viewport `id`; unknown ids drop the list.
+    pub fn set_viewport_ui_draw_list(&mut self, id: u32, ui: UiDrawList) {
+        if let Some(vp) = self.viewports.targets.get_mut(&id) {
+            vp.pending_ui = Some(ui);
+        }
+    }
+
+    /// Records every drawable viewport into the command buffer of the current frame slot.
+    /// A viewport that cannot be drawn this frame (minimized, swapchain out of date or
+    /// unusable) is skipped without failing the frame.
+    pub(super) unsafe fn record_viewports(&mut self) -> VkResult<Vec<ViewportFrame>> {
+        let mut ids: Vec<u32> = self.viewports.targets.keys().copied().collect();
+        ids.sort_unstable();
+
+        let mut out = Vec::with_capacity(ids.len());
+        for id in ids {
+            if let Some(f) = self.record_viewport(id)? {
+                out.push(f);
+            }
+        }
+        Ok(out)
+    }
+
+    unsafe fn record_viewport(&mut self, id: u32) -> VkResult<Option<ViewportFrame>> {
+        let slot = self.frames.frame_index;
+        let Some(vp) = self.viewports.targets.get_mut(&id) else {
+            return Ok(None);
+        };
+        let list = vp.pending_ui.take();
+
+        if vp.target_width == 0 || vp.target_height == 0 {
+            vp.swapchain_dirty = true;
+            return Ok(None);
+        }
+        if vp.swapchain_dirty {
+            let _ = self.core.device.device_wait_idle();
+            let rebuilt = rebuild_swapchain(
+                &self.core,
+                self.pipelines.render_pass,
+                self.swapchain.format,
+                vp,
+            );
+            // Retried on the next resize rather than every frame.
+            vp.swapchain_dirty = false;
+            if let Err(e) = rebuilt {
+                log::warn!("vulkan: viewport {id} swapchain unavailable: {e}");
+            }
+        }
+        if vp.swapchain.framebuffers.is_empty() {
+            return Ok(None);
+        }
+
+        let image_available = vp.image_available[slot];
+        let image_index = match self.core.swapchain_loader.acquire_next_image(
+            vp.swapchain.swapchain,
+            u64::MAX,
+            image_available,
+            vk::Fence::null(),
+        ) {
+            Ok((index, suboptimal)) => {
+                vp.swapchain_dirty |= suboptimal;
+                index
+            }
+            Err(vk::Result::ERROR_OUT_OF_DATE_KHR) => {
+                vp.swapchain_dirty = true;
+                return Ok(None);
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        let idx = image_index as usize;
+        let cmd = vp.command_buffers[slot];
+        let image = vp.swapchain.images[idx];
+        let old_layout = vp.swapchain.image_layouts[idx];
+        let framebuffer = vp.swapchain.framebuffers[idx];
+        let extent = vp.swapchain.extent;
+        let render_finished = vp.render_finished[slot];
+        vp.swapchain.image_layouts[idx] = vk::ImageLayout::PRESENT_SRC_KHR;
+
+        let device = &self.core.device;
+        device.reset_command_buffer(cmd, vk::CommandBufferResetFlags::empty())?;
+        device.begin_command_buffer(
+            cmd,
+            &vk::CommandBufferBeginInfo::default()
+                .flags(vk::CommandBufferUsageFlags::ONE_TIME_SUBMIT),
+        )?;
+        transition_image(
+            device,
+            cmd,
+            image,
+            old_layout,
+            vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL,
+        );
+
+        let clear = vk::ClearValue {
+            color: vk::ClearColorValue {
+                float32: self.debug.clear_color,
+            },
+        };
+        let area = vk::Rect2D {
+            offset: vk::Offset2D { x: 0, y: 0 },
+            extent,
+        };
+        let rp_begin = vk::RenderPassBeginInfo::default()
+            .render_pass(self.pipelines.render_pass)
+            .framebuffer(framebuffer)
+            .render_area(area)
+            .clear_values(std::slice::from_ref(&clear));
+        device.cmd_begin_render_pass(cmd, &rp_begin, vk::SubpassContents::INLINE);
+
+        let viewport = vk::Viewport {
+            x: 0.0,
+            y: 0.0,
+            width: extent.width as f32,
+            height: extent.height as f32,
+            min_depth: 0.0,
+            max_depth: 1.0,
+        };
+        device.cmd_set_viewport(cmd, 0, std::slice::from_ref(&viewport));
+        device.cmd_set_scissor(cmd, 0, std::slice::from_ref(&area));
+
+        let ui_ready = self.pipelines.ui_pipeline != vk::Pipeline::null()
+            && self.ui.desc_set_layout != vk::DescriptorSetLayout::null()
+            && self.ui.sampler != vk::Sampler::null();
+        if let Some(list) = list.filter(|_| ui_ready) {
+            self.ui_upload_and_draw(cmd, &list, extent)?;
+        }
+
+        let device = &self.core.device;
+        device.cmd_end_render_pass(cmd);
+        transition_image(
+            device,
+            cmd,
+            image,
+            vk::ImageLayout::COLOR_ATTACHMENT_OPTIMAL,
+            vk::ImageLayout::PRESENT_SRC_KHR,
+        );
+        device.end_command_buffer(cmd)?;
+
+        Ok(Some(ViewportFrame {
+            id,
+            image_index,
+            cmd: [cmd],
+            image_available: [image_available],
+            render_finished: [render_finished],
+        }))
+    }
+
+    /// Presents viewports submitted this frame. Failures only mark the viewport for
+    /// recreation; they never fail the main frame.
+    pub(super) unsafe fn present_viewports(&mut self, frames: &[ViewportFrame]) {
+        for f in frames {
+            let Some(vp) = self.viewports.targets.get_mut(&f.id) else {
+                continue;
+            };
+            let swapchains = [vp.swapchain.swapchain];
+            let indices = [f.image_index];
+            let present_info = vk::PresentInfoKHR::default()
+                .wait_semaphores(&f.render_finished)
+                .swapchains(&swapchains)
+                .image_indices(&indices);
+
+            match self
+                .core
+                .swapchain_loader
+                .queue_present(self.core.queue, &present_info)
+            {
+                Ok(suboptimal) => vp.swapchain_dirty |= suboptimal,
+                Err(vk::Result::ERROR_OUT_OF_DATE_KHR) => vp.swapchain_dirty = true,
+                Err(e) => {
+                    log::warn!("vulkan: viewport {} present failed: {e:?}", f.id);
+                    vp.swapchain_dirty = true;
+                }
+            }
+        }
+    }
+
+    unsafe fn destroy_viewport_target(&self, vp: &mut ViewportTarget) {
+        let device = &self.core.device;
+        destroy_views(device, &mut vp.swapchain);
+        if vp.swapchain.swapchain != vk::SwapchainKHR::null() {
+            self.core
+                .swapchain_loader
+                .destroy_swapchain(vp.swapchain.swapchain, None);
+            vp.swapchain.swapchain = vk::SwapchainKHR::null();
+        }
+        for s in vp
+            .image_available
+            .iter_mut()
+            .chain(vp.render_finished.iter_mut())
+        {
+            if *s != vk::Semaphore::null() {
+                device.destroy_semaphore(*s, None);
+                *s = vk::Semaphore::null();
+            }
+        }
+        // Frees the command buffers with it.
+        if vp.command_pool != vk::CommandPool::null() {
+            device.destroy_command_pool(vp.command_pool, None);
+            vp.command_pool = vk::CommandPool::null();
+        }
+        if vp.surface != vk::SurfaceKHR::null() {
+            self.core.surface_loader.destroy_surface(vp.surface, None);
+            vp.surface = vk::SurfaceKHR::null();
+        }
+    }
+
+    /// Destroys every secondary viewport. The device must be idle.
+    pub(super) unsafe fn destroy_viewports(&mut self) {
+        let targets: Vec<_> = self.viewports.targets.drain().collect();
+        for (_, mut vp) in targets {
+            self.destroy_viewport_target(&mut vp);
+        }
+    }
+}
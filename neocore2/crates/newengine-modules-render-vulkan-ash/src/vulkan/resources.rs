@@ -138,20 +138,8 @@ impl UploadCtx {
         }
     }
 
-    /// Records and submits an upload command buffer.
-    ///
-    /// Contract:
-    /// - This method does NOT block.
-    /// - The caller must ensure that the context is not in flight (or accept a wait).
-    ///
-    /// Returns the fence associated with this submission.
-    #[inline]
-    pub unsafe fn submit_async<F: FnOnce(vk::CommandBuffer)>(
-        &self,
-        device: &ash::Device,
-        queue: vk::Queue,
-        f: F,
-    ) -> VkResult<vk::Fence> {
+    /// Waits until the context is idle, then resets it and begins recording.
+    pub unsafe fn begin(&self, device: &ash::Device) -> VkResult<()> {
         debug_assert!(self.is_ready());
 
         // If the context is still in flight, we must wait; otherwise we'd reset in-use resources.
@@ -167,16 +155,50 @@ impl UploadCtx {
             &vk::CommandBufferBeginInfo::default()
                 .flags(vk::CommandBufferUsageFlags::ONE_TIME_SUBMIT),
         )?;
+        Ok(())
+    }
 
-        f(self.cmd);
-
+    /// Ends recording and submits; `wait` semaphores block at `ALL_COMMANDS`.
+    ///
+    /// Returns the fence associated with this submission.
+    pub unsafe fn submit(
+        &self,
+        device: &ash::Device,
+        queue: vk::Queue,
+        wait: &[vk::Semaphore],
+        signal: &[vk::Semaphore],
+    ) -> VkResult<vk::Fence> {
         device.end_command_buffer(self.cmd)?;
 
-        let submit = vk::SubmitInfo::default().command_buffers(std::slice::from_ref(&self.cmd));
+        let wait_stages = vec![vk::PipelineStageFlags::ALL_COMMANDS; wait.len()];
+        let submit = vk::SubmitInfo::default()
+            .wait_semaphores(wait)
+            .wait_dst_stage_mask(&wait_stages)
+            .command_buffers(std::slice::from_ref(&self.cmd))
+            .signal_semaphores(signal);
         device.queue_submit(queue, std::slice::from_ref(&submit), self.fence)?;
 
         Ok(self.fence)
     }
+
+    /// Records and submits an upload command buffer.
+    ///
+    /// Contract:
+    /// - This method does NOT block.
+    /// - The caller must ensure that the context is not in flight (or accept a wait).
+    ///
+    /// Returns the fence associated with this submission.
+    #[inline]
+    pub unsafe fn submit_async<F: FnOnce(vk::CommandBuffer)>(
+        &self,
+        device: &ash::Device,
+        queue: vk::Queue,
+        f: F,
+    ) -> VkResult<vk::Fence> {
+        self.begin(device)?;
+        f(self.cmd);
+        self.submit(device, queue, &[], &[])
+    }
 }
 
 /// Deferred destruction queue keyed by a fence.
@@ -214,6 +236,14 @@ impl DeferredFree {
         self.items.push(DeferredItem::DescriptorPool { fence, pool });
     }
 
+    #[inline]
+    pub fn push_framebuffer(&mut self, fence: vk::Fence, framebuffer: vk::Framebuffer) {
+        if framebuffer == vk::Framebuffer::null() {
+            return;
+        }
+        self.items.push(DeferredItem::Framebuffer { fence, framebuffer });
+    }
+
     #[inline]
     pub fn push_image(
         &mut self,
@@ -272,6 +302,10 @@ enum DeferredItem {
         fence: vk::Fence,
         pool: vk::DescriptorPool,
     },
+    Framebuffer {
+        fence: vk::Fence,
+        framebuffer: vk::Framebuffer,
+    },
     Image {
         fence: vk::Fence,
         image: vk::Image,
@@ -287,6 +321,7 @@ impl DeferredItem {
         match *self {
             DeferredItem::Buffer { fence, .. } => fence,
             DeferredItem::DescriptorPool { fence, .. } => fence,
+            DeferredItem::Framebuffer { fence, .. } => fence,
             DeferredItem::Image { fence, .. } => fence,
         }
     }
@@ -307,6 +342,11 @@ impl DeferredItem {
                     device.destroy_descriptor_pool(pool, None);
                 }
             }
+            DeferredItem::Framebuffer { framebuffer, .. } => {
+                if framebuffer != vk::Framebuffer::null() {
+                    device.destroy_framebuffer(framebuffer, None);
+                }
+            }
             DeferredItem::Image {
                 image,
                 view,
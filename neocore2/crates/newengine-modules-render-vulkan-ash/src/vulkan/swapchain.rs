@@ -59,6 +59,15 @@ pub(super) fn create_swapchain(
 
     let family_indices = [queue_family_index];
 
+    // Transfer source lets frames be copied out for screenshots.
+    let mut usage = vk::ImageUsageFlags::COLOR_ATTACHMENT;
+    if caps
+        .supported_usage_flags
+        .contains(vk::ImageUsageFlags::TRANSFER_SRC)
+    {
+        usage |= vk::ImageUsageFlags::TRANSFER_SRC;
+    }
+
     let create_info = vk::SwapchainCreateInfoKHR::default()
         .surface(surface)
         .min_image_count(image_count)
@@ -66,7 +75,7 @@ pub(super) fn create_swapchain(
         .image_color_space(surface_format.color_space)
         .image_extent(extent)
         .image_array_layers(1)
-        .image_usage(vk::ImageUsageFlags::COLOR_ATTACHMENT)
+        .image_usage(usage)
         .image_sharing_mode(vk::SharingMode::EXCLUSIVE)
         .queue_family_indices(&family_indices)
         .pre_transform(caps.current_transform)
@@ -155,6 +164,10 @@ impl VulkanRenderer {
         let format_changed = new_format != self.swapchain.format;
 
         if format_changed {
+            // Secondary viewports share the render pass; their framebuffers are rebuilt too.
+            for vp in self.viewports.targets.values_mut() {
+                vp.swapchain_dirty = true;
+            }
             if self.pipelines.tri_pipeline != vk::Pipeline::null() {
                 self.core.device.destroy_pipeline(self.pipelines.tri_pipeline, None);
                 self.pipelines.tri_pipeline = vk::Pipeline::null();
@@ -198,7 +211,11 @@ impl VulkanRenderer {
             self.swapchain.format = new_format;
             self.pipelines.render_pass = create_render_pass(&self.core.device, self.swapchain.format)?;
 
-            let (pl, p) = create_pipeline(&self.core.device, self.pipelines.render_pass)?;
+            let (pl, p) = create_pipeline(
+                &self.core.device,
+                self.pipelines.render_pass,
+                self.pipelines.cache,
+            )?;
             self.pipelines.tri_pipeline_layout = pl;
             self.pipelines.tri_pipeline = p;
 
@@ -207,6 +224,7 @@ impl VulkanRenderer {
                     &self.core.device,
                     self.pipelines.render_pass,
                     self.text.desc_set_layout,
+                    self.pipelines.cache,
                 )?;
                 self.pipelines.text_pipeline_layout = tpl;
                 self.pipelines.text_pipeline = tp;
@@ -217,10 +235,15 @@ impl VulkanRenderer {
                     &self.core.device,
                     self.pipelines.render_pass,
                     self.ui.desc_set_layout,
+                    self.pipelines.cache,
                 )?;
                 self.pipelines.ui_pipeline_layout = upl;
                 self.pipelines.ui_pipeline = up;
             }
+
+            if self.post.layouts[0] != vk::PipelineLayout::null() {
+                self.create_post_blit()?;
+            }
         } else {
             self.swapchain.format = new_format;
         }
@@ -254,6 +277,6 @@ impl VulkanRenderer {
         self.swapchain.image_layouts = vec![vk::ImageLayout::UNDEFINED; new_image_count];
         self.frames.images_in_flight = vec![vk::Fence::null(); new_image_count];
 
-        Ok(())
+        self.resize_post(new_extent)
     }
 }
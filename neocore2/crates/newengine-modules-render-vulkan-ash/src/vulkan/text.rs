@@ -1,12 +1,13 @@
 use crate::error::VkResult;
 
 use ash::vk;
+use newengine_core::render::{DebugDrawBatch, TextDraw};
 use std::mem;
 use std::ptr;
 
 use super::device::*;
+use super::font::{GlyphQuad, ATLAS_SIZE};
 use super::pipeline::create_shader_module;
-use super::util::*;
 use super::VulkanRenderer;
 
 mod font8x8 {
@@ -40,6 +41,7 @@ pub(super) unsafe fn create_text_pipeline(
     device: &ash::Device,
     render_pass: vk::RenderPass,
     set_layout: vk::DescriptorSetLayout,
+    cache: vk::PipelineCache,
 ) -> VkResult<(vk::PipelineLayout, vk::Pipeline)> {
     let vert = create_shader_module(
         device,
@@ -146,7 +148,7 @@ pub(super) unsafe fn create_text_pipeline(
         .render_pass(render_pass)
         .subpass(0);
 
-    let pipelines = device.create_graphics_pipelines(vk::PipelineCache::null(), &[gp], None);
+    let pipelines = device.create_graphics_pipelines(cache, &[gp], None);
     let pipeline = match pipelines {
         Ok(v) => v[0],
         Err((_, e)) => return Err(e.into()),
@@ -161,33 +163,38 @@ pub(super) unsafe fn create_text_pipeline(
 impl VulkanRenderer {
     pub(super) fn init_text_overlay(&mut self) -> VkResult<()> {
         unsafe {
-            let atlas = build_font_atlas_128_r8();
-            debug_assert_eq!(atlas.len(), 128 * 128);
-
-            self.create_font_resources(&atlas)?;
+            self.create_font_resources()?;
             self.create_text_descriptor()?;
 
             let (tpl, tp) = create_text_pipeline(
                 &self.core.device,
                 self.pipelines.render_pass,
                 self.text.desc_set_layout,
+                self.pipelines.cache,
             )?;
             self.pipelines.text_pipeline_layout = tpl;
             self.pipelines.text_pipeline = tp;
-
-            self.create_text_vertex_buffer(6 * 2048)?;
         }
         Ok(())
     }
 
-    pub(super) unsafe fn destroy_text_overlay(&mut self) {
-        if self.text.vb != vk::Buffer::null() {
-            self.core.device.destroy_buffer(self.text.vb, None);
-        }
-        if self.text.vb_mem != vk::DeviceMemory::null() {
-            self.core.device.free_memory(self.text.vb_mem, None);
-        }
+    /// Loads a TrueType/OpenType font for text runs; returns its `FontId` value.
+    pub fn load_font(&mut self, data: Vec<u8>) -> Result<u32, String> {
+        self.text.atlas.add_font(data)
+    }
+
+    #[inline]
+    pub fn has_font(&self, id: u32) -> bool {
+        self.text.atlas.has_font(id)
+    }
+
+    /// Queues a text run for the next presented frame.
+    #[inline]
+    pub fn queue_text(&mut self, text: TextDraw) {
+        self.text.pending.push(text);
+    }
 
+    pub(super) unsafe fn destroy_text_overlay(&mut self) {
         if self.pipelines.text_pipeline != vk::Pipeline::null() {
             self.core
                 .device
@@ -228,66 +235,14 @@ impl VulkanRenderer {
         }
     }
 
-    unsafe fn create_text_vertex_buffer(&mut self, max_vertices: usize) -> VkResult<()> {
-        self.text.vb_size = (mem::size_of::<TextVertex>() * max_vertices) as vk::DeviceSize;
-
-        let info = vk::BufferCreateInfo::default()
-            .size(self.text.vb_size)
-            .usage(vk::BufferUsageFlags::VERTEX_BUFFER)
-            .sharing_mode(vk::SharingMode::EXCLUSIVE);
-
-        self.text.vb = self.core.device.create_buffer(&info, None)?;
-        let req = self
-            .core
-            .device
-            .get_buffer_memory_requirements(self.text.vb);
-
-        let mem_type = find_memory_type(
-            &self.core.instance,
-            self.core.physical_device,
-            req.memory_type_bits,
-            vk::MemoryPropertyFlags::HOST_VISIBLE | vk::MemoryPropertyFlags::HOST_COHERENT,
-        )?;
-
-        let alloc = vk::MemoryAllocateInfo::default()
-            .allocation_size(req.size)
-            .memory_type_index(mem_type);
-
-        self.text.vb_mem = self.core.device.allocate_memory(&alloc, None)?;
-        self.core
-            .device
-            .bind_buffer_memory(self.text.vb, self.text.vb_mem, 0)?;
-        Ok(())
-    }
-
-    unsafe fn create_font_resources(&mut self, atlas_r8: &[u8]) -> VkResult<()> {
-        let staging_size = atlas_r8.len() as vk::DeviceSize;
-
-        let (staging_buf, staging_mem) = create_buffer(
-            &self.core.instance,
-            self.core.physical_device,
-            &self.core.device,
-            staging_size,
-            vk::BufferUsageFlags::TRANSFER_SRC,
-            vk::MemoryPropertyFlags::HOST_VISIBLE | vk::MemoryPropertyFlags::HOST_COHERENT,
-        )?;
-
-        let ptr_map = self.core.device.map_memory(
-            staging_mem,
-            0,
-            staging_size,
-            vk::MemoryMapFlags::empty(),
-        )? as *mut u8;
-
-        ptr::copy_nonoverlapping(atlas_r8.as_ptr(), ptr_map, atlas_r8.len());
-        self.core.device.unmap_memory(staging_mem);
-
+    /// Glyph atlas image; its texels arrive with the first frame that draws text.
+    unsafe fn create_font_resources(&mut self) -> VkResult<()> {
         let image_info = vk::ImageCreateInfo::default()
             .image_type(vk::ImageType::TYPE_2D)
             .format(vk::Format::R8_UNORM)
             .extent(vk::Extent3D {
-                width: 128,
-                height: 128,
+                width: ATLAS_SIZE,
+                height: ATLAS_SIZE,
                 depth: 1,
             })
             .mip_levels(1)
@@ -320,58 +275,6 @@ impl VulkanRenderer {
             .device
             .bind_image_memory(self.text.font_image, self.text.font_image_mem, 0)?;
 
-        immediate_submit(
-            &self.core.device,
-            self.frames.upload_command_pool,
-            self.core.queue,
-            |cmd| {
-                transition_image_layout(
-                    &self.core.device,
-                    cmd,
-                    self.text.font_image,
-                    vk::ImageLayout::UNDEFINED,
-                    vk::ImageLayout::TRANSFER_DST_OPTIMAL,
-                );
-
-                let region = vk::BufferImageCopy::default()
-                    .buffer_offset(0)
-                    .buffer_row_length(0)
-                    .buffer_image_height(0)
-                    .image_subresource(
-                        vk::ImageSubresourceLayers::default()
-                            .aspect_mask(vk::ImageAspectFlags::COLOR)
-                            .mip_level(0)
-                            .base_array_layer(0)
-                            .layer_count(1),
-                    )
-                    .image_offset(vk::Offset3D { x: 0, y: 0, z: 0 })
-                    .image_extent(vk::Extent3D {
-                        width: 128,
-                        height: 128,
-                        depth: 1,
-                    });
-
-                self.core.device.cmd_copy_buffer_to_image(
-                    cmd,
-                    staging_buf,
-                    self.text.font_image,
-                    vk::ImageLayout::TRANSFER_DST_OPTIMAL,
-                    std::slice::from_ref(&region),
-                );
-
-                transition_image_layout(
-                    &self.core.device,
-                    cmd,
-                    self.text.font_image,
-                    vk::ImageLayout::TRANSFER_DST_OPTIMAL,
-                    vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL,
-                );
-            },
-        )?;
-
-        self.core.device.destroy_buffer(staging_buf, None);
-        self.core.device.free_memory(staging_mem, None);
-
         self.text.font_image_view = self.core.device.create_image_view(
             &vk::ImageViewCreateInfo::default()
                 .image(self.text.font_image)
@@ -390,8 +293,8 @@ impl VulkanRenderer {
 
         self.text.font_sampler = self.core.device.create_sampler(
             &vk::SamplerCreateInfo::default()
-                .mag_filter(vk::Filter::NEAREST)
-                .min_filter(vk::Filter::NEAREST)
+                .mag_filter(vk::Filter::LINEAR)
+                .min_filter(vk::Filter::LINEAR)
                 .mipmap_mode(vk::SamplerMipmapMode::NEAREST)
                 .address_mode_u(vk::SamplerAddressMode::CLAMP_TO_EDGE)
                 .address_mode_v(vk::SamplerAddressMode::CLAMP_TO_EDGE)
@@ -448,33 +351,112 @@ impl VulkanRenderer {
         Ok(())
     }
 
-    pub(super) unsafe fn draw_text_overlay(
+    /// Records the atlas texels baked since the last upload into the frame's upload batch.
+    unsafe fn upload_glyph_atlas(&mut self) -> VkResult<()> {
+        let dirty = self.text.atlas.take_dirty();
+        let [x0, y0, x1, y1] = if self.text.atlas_resident {
+            match dirty {
+                Some(rect) => rect,
+                None => return Ok(()),
+            }
+        } else {
+            // The image starts out undefined, so the first upload covers all of it.
+            [0, 0, ATLAS_SIZE, ATLAS_SIZE]
+        };
+        let (w, h) = (x1 - x0, y1 - y0);
+        let size = (w * h) as vk::DeviceSize;
+
+        let fence = self.upload_fence()?;
+        let (staging, memory) = self.create_staging(size)?;
+        self.frames
+            .deferred_free
+            .push_buffer(fence, staging, memory);
+
+        let device = &self.core.device;
+        let mapped = device.map_memory(memory, 0, size, vk::MemoryMapFlags::empty())? as *mut u8;
+        let pixels = self.text.atlas.pixels();
+        for row in 0..h {
+            let src = ((y0 + row) * ATLAS_SIZE + x0) as usize;
+            ptr::copy_nonoverlapping(
+                pixels.as_ptr().add(src),
+                mapped.add((row * w) as usize),
+                w as usize,
+            );
+        }
+        device.unmap_memory(memory);
+
+        let region = vk::BufferImageCopy::default()
+            .buffer_offset(0)
+            .buffer_row_length(0)
+            .buffer_image_height(0)
+            .image_subresource(
+                vk::ImageSubresourceLayers::default()
+                    .aspect_mask(vk::ImageAspectFlags::COLOR)
+                    .mip_level(0)
+                    .base_array_layer(0)
+                    .layer_count(1),
+            )
+            .image_offset(vk::Offset3D {
+                x: x0 as i32,
+                y: y0 as i32,
+                z: 0,
+            })
+            .image_extent(vk::Extent3D {
+                width: w,
+                height: h,
+                depth: 1,
+            });
+
+        let fresh = !self.text.atlas_resident;
+        self.upload_image(staging, self.text.font_image, region, fresh)?;
+        self.text.atlas_resident = true;
+        Ok(())
+    }
+
+    /// Draws the labels and billboards of `world`, then the debug text and the queued text
+    /// runs, in one draw over the glyph atlas.
+    pub(super) unsafe fn draw_texts(
         &mut self,
         cmd: vk::CommandBuffer,
-        text: &str,
+        world: Option<&DebugDrawBatch>,
     ) -> VkResult<()> {
-        if text.is_empty() {
-            return Ok(());
+        let mut runs = mem::take(&mut self.text.pending);
+        if !self.debug.debug_text.is_empty() {
+            let debug = TextDraw::new(self.debug.debug_text.clone(), [8.0, 8.0]).with_size(8.0);
+            runs.insert(0, debug);
         }
 
-        let vertices = build_text_vertices(text, self.swapchain.extent);
-        if vertices.is_empty() {
-            return Ok(());
+        let extent = self.swapchain.extent;
+        let (w, h) = (extent.width as f32, extent.height as f32);
+
+        self.text.atlas.begin_frame();
+        let mut quads = Vec::new();
+        let mut vertices = Vec::new();
+        if let Some(batch) = world {
+            self.debug_world_text(batch, &mut vertices);
+        }
+        for run in &runs {
+            quads.clear();
+            self.text.atlas.layout(run, &mut quads);
+            for q in &quads {
+                push_glyph_quad(&mut vertices, q, run.color, w, h);
+            }
         }
 
-        let bytes = (vertices.len() * mem::size_of::<TextVertex>()) as vk::DeviceSize;
-        if bytes > self.text.vb_size {
+        // Hands the buffer back to keep its capacity for the next frame.
+        runs.clear();
+        self.text.pending = runs;
+
+        if vertices.is_empty() {
             return Ok(());
         }
+        self.upload_glyph_atlas()?;
 
-        let dst =
-            self.core
-                .device
-                .map_memory(self.text.vb_mem, 0, bytes, vk::MemoryMapFlags::empty())?
-                as *mut u8;
-
-        ptr::copy_nonoverlapping(vertices.as_ptr() as *const u8, dst, bytes as usize);
-        self.core.device.unmap_memory(self.text.vb_mem);
+        // A fresh ring allocation each frame: the previous frame may still be reading its own.
+        let bytes = vertices.len() * mem::size_of::<TextVertex>();
+        let data = std::slice::from_raw_parts(vertices.as_ptr() as *const u8, bytes);
+        let vb = self.alloc_transient(bytes as vk::DeviceSize)?;
+        vb.write(0, data)?;
 
         self.core.device.cmd_bind_pipeline(
             cmd,
@@ -491,11 +473,11 @@ impl VulkanRenderer {
             &[],
         );
 
-        let vb = [self.text.vb];
-        let offsets = [0u64];
+        let buffers = [vb.buffer];
+        let offsets = [vb.offset];
         self.core
             .device
-            .cmd_bind_vertex_buffers(cmd, 0, &vb, &offsets);
+            .cmd_bind_vertex_buffers(cmd, 0, &buffers, &offsets);
 
         self.core
             .device
@@ -504,48 +486,28 @@ impl VulkanRenderer {
     }
 }
 
-pub(super) fn build_text_vertices(text: &str, extent: vk::Extent2D) -> Vec<TextVertex> {
-    let mut out = Vec::new();
-    let mut x = 8.0f32;
-    let mut y = 8.0f32;
-
-    let w = extent.width as f32;
-    let h = extent.height as f32;
-
-    let color = [1.0, 1.0, 1.0, 1.0];
-
-    for &b in text.as_bytes() {
-        if b == b'\n' {
-            x = 8.0;
-            y += 10.0;
-            continue;
-        }
-
-        let gx = (b & 0x0F) as f32;
-        let gy = (b >> 4) as f32;
-
-        let u0 = gx / 16.0;
-        let v0 = gy / 16.0;
-        let u1 = (gx + 1.0) / 16.0;
-        let v1 = (gy + 1.0) / 16.0;
-
-        let p0 = px_to_ndc(x, y, w, h);
-        let p1 = px_to_ndc(x + 8.0, y, w, h);
-        let p2 = px_to_ndc(x + 8.0, y + 8.0, w, h);
-        let p3 = px_to_ndc(x, y + 8.0, w, h);
-
-        out.push(TextVertex::new(p0, [u0, v0], color));
-        out.push(TextVertex::new(p1, [u1, v0], color));
-        out.push(TextVertex::new(p2, [u1, v1], color));
-
-        out.push(TextVertex::new(p0, [u0, v0], color));
-        out.push(TextVertex::new(p2, [u1, v1], color));
-        out.push(TextVertex::new(p3, [u0, v1], color));
-
-        x += 8.0;
-    }
-
-    out
+pub(super) fn push_glyph_quad(
+    out: &mut Vec<TextVertex>,
+    q: &GlyphQuad,
+    color: [f32; 4],
+    w: f32,
+    h: f32,
+) {
+    let [u0, v0] = q.uv_min;
+    let [u1, v1] = q.uv_max;
+
+    let p0 = px_to_ndc(q.min[0], q.min[1], w, h);
+    let p1 = px_to_ndc(q.max[0], q.min[1], w, h);
+    let p2 = px_to_ndc(q.max[0], q.max[1], w, h);
+    let p3 = px_to_ndc(q.min[0], q.max[1], w, h);
+
+    out.push(TextVertex::new(p0, [u0, v0], color));
+    out.push(TextVertex::new(p1, [u1, v0], color));
+    out.push(TextVertex::new(p2, [u1, v1], color));
+
+    out.push(TextVertex::new(p0, [u0, v0], color));
+    out.push(TextVertex::new(p2, [u1, v1], color));
+    out.push(TextVertex::new(p3, [u0, v1], color));
 }
 
 pub(super) fn px_to_ndc(x_px: f32, y_px: f32, w: f32, h: f32) -> [f32; 2] {
@@ -554,29 +516,6 @@ pub(super) fn px_to_ndc(x_px: f32, y_px: f32, w: f32, h: f32) -> [f32; 2] {
     [x, y]
 }
 
-pub(super) fn build_font_atlas_128_r8() -> Vec<u8> {
-    let mut atlas = vec![0u8; 128 * 128];
-
-    for ch in 0u8..=127u8 {
-        let glyph = glyph8x8(ch);
-        let gx = (ch & 0x0F) as usize;
-        let gy = (ch >> 4) as usize;
-
-        let ox = gx * 8;
-        let oy = gy * 8;
-
-        for row in 0..8 {
-            let bits = glyph[row];
-            for col in 0..8 {
-                let on = (bits & (1u8 << col)) != 0;
-                atlas[(oy + row) * 128 + (ox + col)] = if on { 255 } else { 0 };
-            }
-        }
-    }
-
-    atlas
-}
-
 /// Extract glyph from table and fix only vertical orientation.
 /// Do NOT reverse bits: the table defines bit0 as leftmost pixel.
 pub(super) fn glyph8x8(ch: u8) -> [u8; 8] {
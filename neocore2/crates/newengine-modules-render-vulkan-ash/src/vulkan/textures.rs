@@ -0,0 +1,330 @@
+use crate::error::{VkRenderError, VkResult};
+
+use ash::vk;
+
+use super::device::find_memory_type;
+use super::VulkanRenderer;
+
+/// Format of `TextureUsage::DepthStencil` textures.
+pub(crate) const DEPTH_FORMAT: vk::Format = vk::Format::D32_SFLOAT;
+
+/// A sampled 2D image or cubemap created through `RenderApi::create_texture`.
+#[derive(Clone, Copy)]
+pub(crate) struct GpuTexture {
+    pub(crate) image: vk::Image,
+    pub(crate) memory: vk::DeviceMemory,
+    pub(crate) view: vk::ImageView,
+    pub(crate) extent: vk::Extent2D,
+    /// 6 for cubemaps (one per face), 1 otherwise.
+    pub(crate) layers: u32,
+    pub(crate) texel_size: u32,
+    /// Filled at least once; until then the image contents (and layout) are undefined.
+    pub(crate) resident: bool,
+    /// Depth textures only: framebuffer of the depth render pass; null otherwise.
+    pub(crate) framebuffer: vk::Framebuffer,
+}
+
+impl GpuTexture {
+    /// Written by depth passes rather than `write_texture`.
+    #[inline]
+    pub(crate) fn is_depth(&self) -> bool {
+        self.framebuffer != vk::Framebuffer::null()
+    }
+}
+
+impl VulkanRenderer {
+    /// Device-local, single-mip image the shaders sample; its texels arrive with
+    /// [`VulkanRenderer::write_texture`]. A `cube` has six `extent` faces.
+    pub(crate) unsafe fn create_texture(
+        &self,
+        format: vk::Format,
+        extent: vk::Extent2D,
+        cube: bool,
+        texel_size: u32,
+        label: Option<&str>,
+    ) -> VkResult<GpuTexture> {
+        let usage = vk::ImageUsageFlags::TRANSFER_DST | vk::ImageUsageFlags::SAMPLED;
+        let aspect = vk::ImageAspectFlags::COLOR;
+        let (image, memory, view) =
+            self.create_image(format, extent, cube, usage, aspect, label)?;
+        Ok(GpuTexture {
+            image,
+            memory,
+            view,
+            extent,
+            layers: if cube { 6 } else { 1 },
+            texel_size,
+            resident: false,
+            framebuffer: vk::Framebuffer::null(),
+        })
+    }
+
+    /// [`DEPTH_FORMAT`] image that depth passes render into and shaders then sample. It starts
+    /// out cleared to 1.0 in `SHADER_READ_ONLY_OPTIMAL`, so sampling it before its first pass
+    /// reads as "nothing in front".
+    pub(crate) unsafe fn create_depth_texture(
+        &mut self,
+        extent: vk::Extent2D,
+        label: Option<&str>,
+    ) -> VkResult<GpuTexture> {
+        let usage = vk::ImageUsageFlags::DEPTH_STENCIL_ATTACHMENT
+            | vk::ImageUsageFlags::SAMPLED
+            | vk::ImageUsageFlags::TRANSFER_DST;
+        let aspect = vk::ImageAspectFlags::DEPTH;
+        let (image, memory, view) =
+            self.create_image(DEPTH_FORMAT, extent, false, usage, aspect, label)?;
+
+        let device = &self.core.device;
+        let fb_info = vk::FramebufferCreateInfo::default()
+            .render_pass(self.pipelines.depth_render_pass)
+            .attachments(std::slice::from_ref(&view))
+            .width(extent.width)
+            .height(extent.height)
+            .layers(1);
+        let framebuffer = match device.create_framebuffer(&fb_info, None) {
+            Ok(fb) => fb,
+            Err(e) => {
+                device.destroy_image_view(view, None);
+                device.destroy_image(image, None);
+                device.free_memory(memory, None);
+                return Err(e.into());
+            }
+        };
+        let tex = GpuTexture {
+            image,
+            memory,
+            view,
+            extent,
+            layers: 1,
+            texel_size: 4,
+            resident: true,
+            framebuffer,
+        };
+
+        let cmd = match self.upload_graphics_cmd() {
+            Ok(cmd) => cmd,
+            Err(e) => {
+                self.destroy_texture(tex);
+                return Err(e);
+            }
+        };
+        let range = vk::ImageSubresourceRange::default()
+            .aspect_mask(vk::ImageAspectFlags::DEPTH)
+            .level_count(1)
+            .layer_count(1);
+        let barrier = |old, new, src, dst| {
+            vk::ImageMemoryBarrier::default()
+                .src_access_mask(src)
+                .dst_access_mask(dst)
+                .old_layout(old)
+                .new_layout(new)
+                .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                .image(image)
+                .subresource_range(range)
+        };
+        let device = &self.core.device;
+        device.cmd_pipeline_barrier(
+            cmd,
+            vk::PipelineStageFlags::TOP_OF_PIPE,
+            vk::PipelineStageFlags::TRANSFER,
+            vk::DependencyFlags::empty(),
+            &[],
+            &[],
+            &[barrier(
+                vk::ImageLayout::UNDEFINED,
+                vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+                vk::AccessFlags::empty(),
+                vk::AccessFlags::TRANSFER_WRITE,
+            )],
+        );
+        let clear = vk::ClearDepthStencilValue {
+            depth: 1.0,
+            stencil: 0,
+        };
+        device.cmd_clear_depth_stencil_image(
+            cmd,
+            image,
+            vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+            &clear,
+            std::slice::from_ref(&range),
+        );
+        device.cmd_pipeline_barrier(
+            cmd,
+            vk::PipelineStageFlags::TRANSFER,
+            vk::PipelineStageFlags::FRAGMENT_SHADER,
+            vk::DependencyFlags::empty(),
+            &[],
+            &[],
+            &[barrier(
+                vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+                vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+                vk::AccessFlags::TRANSFER_WRITE,
+                vk::AccessFlags::SHADER_READ,
+            )],
+        );
+        Ok(tex)
+    }
+
+    /// Single-mip image and a view of all of it; a `cube` has six layers, viewed as a cube.
+    pub(crate) unsafe fn create_image(
+        &self,
+        format: vk::Format,
+        extent: vk::Extent2D,
+        cube: bool,
+        usage: vk::ImageUsageFlags,
+        aspect: vk::ImageAspectFlags,
+        label: Option<&str>,
+    ) -> VkResult<(vk::Image, vk::DeviceMemory, vk::ImageView)> {
+        let device = &self.core.device;
+        let (flags, layers, view_type) = if cube {
+            (vk::ImageCreateFlags::CUBE_COMPATIBLE, 6, vk::ImageViewType::CUBE)
+        } else {
+            (vk::ImageCreateFlags::empty(), 1, vk::ImageViewType::TYPE_2D)
+        };
+        let image_info = vk::ImageCreateInfo::default()
+            .flags(flags)
+            .image_type(vk::ImageType::TYPE_2D)
+            .format(format)
+            .extent(vk::Extent3D {
+                width: extent.width,
+                height: extent.height,
+                depth: 1,
+            })
+            .mip_levels(1)
+            .array_layers(layers)
+            .samples(vk::SampleCountFlags::TYPE_1)
+            .tiling(vk::ImageTiling::OPTIMAL)
+            .usage(usage)
+            .sharing_mode(vk::SharingMode::EXCLUSIVE)
+            .initial_layout(vk::ImageLayout::UNDEFINED);
+
+        let image = device.create_image(&image_info, None)?;
+        let req = device.get_image_memory_requirements(image);
+
+        let memory = find_memory_type(
+            &self.core.instance,
+            self.core.physical_device,
+            req.memory_type_bits,
+            vk::MemoryPropertyFlags::DEVICE_LOCAL,
+        )
+        .and_then(|mem_type| {
+            let alloc = vk::MemoryAllocateInfo::default()
+                .allocation_size(req.size)
+                .memory_type_index(mem_type);
+            Ok(device.allocate_memory(&alloc, None)?)
+        });
+        let memory = match memory {
+            Ok(m) => m,
+            Err(e) => {
+                device.destroy_image(image, None);
+                return Err(e);
+            }
+        };
+
+        let view_info = vk::ImageViewCreateInfo::default()
+            .image(image)
+            .view_type(view_type)
+            .format(format)
+            .subresource_range(
+                vk::ImageSubresourceRange::default()
+                    .aspect_mask(aspect)
+                    .base_mip_level(0)
+                    .level_count(1)
+                    .base_array_layer(0)
+                    .layer_count(layers),
+            );
+        let view = match device
+            .bind_image_memory(image, memory, 0)
+            .and_then(|()| device.create_image_view(&view_info, None))
+        {
+            Ok(v) => v,
+            Err(e) => {
+                device.destroy_image(image, None);
+                device.free_memory(memory, None);
+                return Err(e.into());
+            }
+        };
+
+        if let Some(label) = label {
+            self.set_object_name(image, label);
+            self.set_object_name(view, label);
+        }
+
+        Ok((image, memory, view))
+    }
+
+    /// Records a copy of `data` (every texel of mip 0, layer after layer) into the frame's
+    /// upload batch.
+    pub(crate) unsafe fn write_texture(
+        &mut self,
+        tex: &mut GpuTexture,
+        data: &[u8],
+    ) -> VkResult<()> {
+        let expected = tex.extent.width as u64
+            * tex.extent.height as u64
+            * tex.layers as u64
+            * tex.texel_size as u64;
+        if data.len() as u64 != expected {
+            return Err(VkRenderError::AshWindow(format!(
+                "write_texture: expected {expected} bytes, got {}",
+                data.len()
+            )));
+        }
+        let size = expected as vk::DeviceSize;
+
+        let fence = self.upload_fence()?;
+        let (staging, memory) = self.create_staging(size)?;
+        self.frames
+            .deferred_free
+            .push_buffer(fence, staging, memory);
+
+        let device = &self.core.device;
+        let ptr = device.map_memory(memory, 0, size, vk::MemoryMapFlags::empty())? as *mut u8;
+        std::ptr::copy_nonoverlapping(data.as_ptr(), ptr, data.len());
+        device.unmap_memory(memory);
+
+        let region = vk::BufferImageCopy::default()
+            .buffer_offset(0)
+            .buffer_row_length(0)
+            .buffer_image_height(0)
+            .image_subresource(
+                vk::ImageSubresourceLayers::default()
+                    .aspect_mask(vk::ImageAspectFlags::COLOR)
+                    .mip_level(0)
+                    .base_array_layer(0)
+                    .layer_count(tex.layers),
+            )
+            .image_offset(vk::Offset3D { x: 0, y: 0, z: 0 })
+            .image_extent(vk::Extent3D {
+                width: tex.extent.width,
+                height: tex.extent.height,
+                depth: 1,
+            });
+
+        self.upload_image(staging, tex.image, region, !tex.resident)?;
+        tex.resident = true;
+        Ok(())
+    }
+
+    pub(crate) unsafe fn destroy_texture(&mut self, tex: GpuTexture) {
+        // Recorded uploads and frames in flight may still use the image; the upload batch
+        // fence signals after both.
+        match self.upload_fence() {
+            Ok(fence) => {
+                let deferred = &mut self.frames.deferred_free;
+                deferred.push_framebuffer(fence, tex.framebuffer);
+                deferred.push_image(fence, tex.image, tex.view, tex.memory, vk::Sampler::null());
+            }
+            Err(_) => {
+                let device = &self.core.device;
+                if tex.is_depth() {
+                    device.destroy_framebuffer(tex.framebuffer, None);
+                }
+                device.destroy_image_view(tex.view, None);
+                device.destroy_image(tex.image, None);
+                device.free_memory(tex.memory, None);
+            }
+        }
+    }
+}
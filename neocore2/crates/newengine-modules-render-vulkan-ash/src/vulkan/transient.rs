@@ -0,0 +1,198 @@
+use crate::error::{VkRenderError, VkResult};
+
+use ash::vk;
+
+use super::device::create_buffer;
+use super::renderer::FRAMES_IN_FLIGHT;
+use super::VulkanRenderer;
+
+/// Smallest ring page; a larger request gets a page of its own size.
+const PAGE_SIZE: vk::DeviceSize = 1 << 20;
+/// Offsets are aligned for any use, including uniform/storage binding offsets (at most 256 bytes
+/// on every supported GPU).
+const ALIGN: vk::DeviceSize = 256;
+
+const USAGE: vk::BufferUsageFlags = vk::BufferUsageFlags::from_raw(
+    vk::BufferUsageFlags::VERTEX_BUFFER.as_raw()
+        | vk::BufferUsageFlags::INDEX_BUFFER.as_raw()
+        | vk::BufferUsageFlags::UNIFORM_BUFFER.as_raw()
+        | vk::BufferUsageFlags::STORAGE_BUFFER.as_raw()
+        | vk::BufferUsageFlags::INDIRECT_BUFFER.as_raw(),
+);
+
+/// Host address of a persistently mapped page.
+#[derive(Clone, Copy)]
+struct Mapped(*mut u8);
+
+// Only dereferenced through `&mut VulkanRenderer`, which is never shared between threads.
+unsafe impl Send for Mapped {}
+
+struct Page {
+    buffer: vk::Buffer,
+    memory: vk::DeviceMemory,
+    size: vk::DeviceSize,
+    mapped: Mapped,
+}
+
+/// Per-frame-in-flight bump allocator for data that lives one frame (UI and debug geometry,
+/// per-object constants). A slot is rewound once its fence has signaled, so nothing handed out
+/// is overwritten while the GPU may still read it. Pages are kept at their peak size.
+#[derive(Default)]
+pub struct TransientRing {
+    slots: [Vec<Page>; FRAMES_IN_FLIGHT],
+    slot: usize,
+    page: usize,
+    head: vk::DeviceSize,
+    used: vk::DeviceSize,
+    peak: vk::DeviceSize,
+}
+
+/// Host-visible, coherent memory valid until the frame it was allocated in has finished.
+#[derive(Clone, Copy)]
+pub struct TransientAlloc {
+    pub buffer: vk::Buffer,
+    pub offset: vk::DeviceSize,
+    pub size: vk::DeviceSize,
+    mapped: Mapped,
+}
+
+impl TransientAlloc {
+    /// Copies `data` to `offset` bytes into the allocation; fails when it does not fit.
+    pub fn write(&self, offset: vk::DeviceSize, data: &[u8]) -> VkResult<()> {
+        if offset.saturating_add(data.len() as vk::DeviceSize) > self.size {
+            return Err(VkRenderError::InvalidState("transient write out of bounds"));
+        }
+        // In bounds of the mapped page, which outlives the allocation.
+        unsafe {
+            let dst = self.mapped.0.add((self.offset + offset) as usize);
+            std::ptr::copy_nonoverlapping(data.as_ptr(), dst, data.len());
+        }
+        Ok(())
+    }
+}
+
+impl VulkanRenderer {
+    /// Ring memory for the frame being recorded; only valid between `begin_frame` and
+    /// `end_frame`.
+    pub fn alloc_transient(&mut self, size: vk::DeviceSize) -> VkResult<TransientAlloc> {
+        if !self.debug.in_frame {
+            return Err(VkRenderError::InvalidState(
+                "alloc_transient called outside of a frame",
+            ));
+        }
+        let size = size.max(1);
+        let ring = &mut self.transient;
+        let pages = &ring.slots[ring.slot];
+
+        loop {
+            let Some(page) = pages.get(ring.page) else {
+                break;
+            };
+            let offset = ring.head.next_multiple_of(ALIGN);
+            if offset + size <= page.size {
+                ring.head = offset + size;
+                ring.used += size;
+                ring.peak = ring.peak.max(ring.used);
+                return Ok(TransientAlloc {
+                    buffer: page.buffer,
+                    offset,
+                    size,
+                    mapped: page.mapped,
+                });
+            }
+            ring.page += 1;
+            ring.head = 0;
+        }
+
+        let page_size = size.next_multiple_of(ALIGN).max(PAGE_SIZE);
+        let (buffer, memory) = create_buffer(
+            &self.core.instance,
+            self.core.physical_device,
+            &self.core.device,
+            page_size,
+            USAGE,
+            vk::MemoryPropertyFlags::HOST_VISIBLE | vk::MemoryPropertyFlags::HOST_COHERENT,
+        )?;
+        let mapped = unsafe {
+            self.core
+                .device
+                .map_memory(memory, 0, vk::WHOLE_SIZE, vk::MemoryMapFlags::empty())
+        };
+        let mapped = match mapped {
+            Ok(p) => Mapped(p as *mut u8),
+            Err(e) => {
+                unsafe {
+                    self.core.device.destroy_buffer(buffer, None);
+                    self.core.device.free_memory(memory, None);
+                }
+                return Err(e.into());
+            }
+        };
+        self.set_object_name(buffer, "transient ring page");
+
+        let ring = &mut self.transient;
+        let pages = &mut ring.slots[ring.slot];
+        pages.push(Page {
+            buffer,
+            memory,
+            size: page_size,
+            mapped,
+        });
+        ring.page = pages.len() - 1;
+        ring.head = size;
+        ring.used += size;
+        ring.peak = ring.peak.max(ring.used);
+        Ok(TransientAlloc {
+            buffer,
+            offset: 0,
+            size,
+            mapped,
+        })
+    }
+
+    /// Writes into a page handed out this frame; `offset` is relative to the page.
+    pub fn write_transient(
+        &self,
+        buffer: vk::Buffer,
+        offset: vk::DeviceSize,
+        data: &[u8],
+    ) -> VkResult<()> {
+        let ring = &self.transient;
+        let Some(page) = ring.slots[ring.slot].iter().find(|p| p.buffer == buffer) else {
+            return Err(VkRenderError::InvalidState(
+                "transient buffer does not belong to the current frame",
+            ));
+        };
+        TransientAlloc {
+            buffer,
+            offset: 0,
+            size: page.size,
+            mapped: page.mapped,
+        }
+        .write(offset, data)
+    }
+
+    /// `(capacity, used this frame, peak per frame)` in bytes.
+    pub fn transient_stats(&self) -> (u64, u64, u64) {
+        let ring = &self.transient;
+        let capacity = ring.slots.iter().flatten().map(|p| p.size).sum();
+        (capacity, ring.used, ring.peak)
+    }
+
+    /// Rewinds frame slot `slot`. Call after waiting on its in-flight fence.
+    pub(crate) fn transient_reset(&mut self, slot: usize) {
+        let ring = &mut self.transient;
+        ring.slot = slot;
+        ring.page = 0;
+        ring.head = 0;
+        ring.used = 0;
+    }
+
+    pub(crate) unsafe fn destroy_transient(&mut self) {
+        for page in self.transient.slots.iter_mut().flat_map(|s| s.drain(..)) {
+            self.core.device.unmap_memory(page.memory);
+            self.core.device.destroy_buffer(page.buffer, None);
+            self.core.device.free_memory(page.memory, None);
+        }
+    }
+}
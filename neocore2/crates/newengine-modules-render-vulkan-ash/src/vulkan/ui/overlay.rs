@@ -5,7 +5,6 @@ use std::mem;
 use std::ptr;
 
 use super::super::device::*;
-use super::super::util::*;
 use super::super::VulkanRenderer;
 
 use newengine_ui::draw::{UiDrawCmd, UiDrawList, UiTexId, UiTextureDelta};
@@ -28,6 +27,7 @@ impl VulkanRenderer {
                 &self.core.device,
                 self.pipelines.render_pass,
                 self.ui.desc_set_layout,
+                self.pipelines.cache,
             )?;
             self.pipelines.ui_pipeline_layout = pl;
             self.pipelines.ui_pipeline = p;
@@ -62,29 +62,6 @@ impl VulkanRenderer {
         if self.ui.sampler != vk::Sampler::null() {
             self.core.device.destroy_sampler(self.ui.sampler, None);
         }
-
-        if self.ui.vb != vk::Buffer::null() {
-            self.core.device.destroy_buffer(self.ui.vb, None);
-        }
-        if self.ui.vb_mem != vk::DeviceMemory::null() {
-            self.core.device.free_memory(self.ui.vb_mem, None);
-        }
-        if self.ui.ib != vk::Buffer::null() {
-            self.core.device.destroy_buffer(self.ui.ib, None);
-        }
-        if self.ui.ib_mem != vk::DeviceMemory::null() {
-            self.core.device.free_memory(self.ui.ib_mem, None);
-        }
-
-        if self.ui.staging_buf != vk::Buffer::null() {
-            self.core.device.destroy_buffer(self.ui.staging_buf, None);
-            self.ui.staging_buf = vk::Buffer::null();
-        }
-        if self.ui.staging_mem != vk::DeviceMemory::null() {
-            self.core.device.free_memory(self.ui.staging_mem, None);
-            self.ui.staging_mem = vk::DeviceMemory::null();
-        }
-        self.ui.staging_size = 0;
     }
 
     unsafe fn destroy_ui_resources(&mut self) {
@@ -156,7 +133,6 @@ impl VulkanRenderer {
         #[derive(Clone, Copy)]
         struct UploadOp {
             image: vk::Image,
-            old_layout: vk::ImageLayout,
             offset: vk::DeviceSize,
             extent: vk::Extent3D,
             kind: UploadKind,
@@ -173,10 +149,15 @@ impl VulkanRenderer {
         let mut ops: Vec<UploadOp> = Vec::with_capacity(delta.set.len() + delta.patches.len());
 
         if total_bytes != 0 {
-            self.ui_ensure_staging(total_bytes)?;
+            // Freed once the upload batch has executed.
+            let fence = self.upload_fence()?;
+            let (staging, staging_mem) = self.create_staging(total_bytes)?;
+            self.frames
+                .deferred_free
+                .push_buffer(fence, staging, staging_mem);
 
             let mapped = self.core.device.map_memory(
-                self.ui.staging_mem,
+                staging_mem,
                 0,
                 total_bytes,
                 vk::MemoryMapFlags::empty(),
@@ -199,7 +180,6 @@ impl VulkanRenderer {
 
                 ops.push(UploadOp {
                     image: gpu.image,
-                    old_layout: vk::ImageLayout::UNDEFINED,
                     offset,
                     extent: vk::Extent3D {
                         width: tex.size[0],
@@ -227,7 +207,6 @@ impl VulkanRenderer {
 
                 ops.push(UploadOp {
                     image: tex.image,
-                    old_layout: vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL,
                     offset,
                     extent: vk::Extent3D {
                         width: p.size[0],
@@ -240,68 +219,34 @@ impl VulkanRenderer {
 
             debug_assert!(cursor == total_bytes);
 
-            self.core.device.unmap_memory(self.ui.staging_mem);
+            self.core.device.unmap_memory(staging_mem);
 
-            // One submit for the whole delta.
-            immediate_submit(
-                &self.core.device,
-                self.frames.upload_command_pool,
-                self.core.queue,
-                |cmd| {
-                    for op in &ops {
-                        transition_image_layout(
-                            &self.core.device,
-                            cmd,
-                            op.image,
-                            op.old_layout,
-                            vk::ImageLayout::TRANSFER_DST_OPTIMAL,
-                        );
-
-                        let mut region = vk::BufferImageCopy::default()
-                            .buffer_offset(op.offset)
-                            .buffer_row_length(0)
-                            .buffer_image_height(0)
-                            .image_subresource(
-                                vk::ImageSubresourceLayers::default()
-                                    .aspect_mask(vk::ImageAspectFlags::COLOR)
-                                    .mip_level(0)
-                                    .base_array_layer(0)
-                                    .layer_count(1),
-                            )
-                            .image_extent(op.extent);
-
-                        if let UploadKind::Patch { origin } = op.kind {
-                            region = region.image_offset(vk::Offset3D {
-                                x: origin[0] as i32,
-                                y: origin[1] as i32,
-                                z: 0,
-                            });
-                        } else {
-                            region = region.image_offset(vk::Offset3D { x: 0, y: 0, z: 0 });
-                        }
-
-                        self.core.device.cmd_copy_buffer_to_image(
-                            cmd,
-                            self.ui.staging_buf,
-                            op.image,
-                            vk::ImageLayout::TRANSFER_DST_OPTIMAL,
-                            std::slice::from_ref(&region),
-                        );
-
-                        transition_image_layout(
-                            &self.core.device,
-                            cmd,
-                            op.image,
-                            vk::ImageLayout::TRANSFER_DST_OPTIMAL,
-                            vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL,
-                        );
-                    }
-                },
-            )?;
-        } else {
-            // No uploads, but we still may free textures.
-            for (id, tex) in &delta.set {
-                let _ = (id, tex);
+            // Recorded into the frame's upload batch; nothing waits here.
+            for op in &ops {
+                let origin = match op.kind {
+                    UploadKind::Patch { origin } => vk::Offset3D {
+                        x: origin[0] as i32,
+                        y: origin[1] as i32,
+                        z: 0,
+                    },
+                    UploadKind::New => vk::Offset3D { x: 0, y: 0, z: 0 },
+                };
+                let region = vk::BufferImageCopy::default()
+                    .buffer_offset(op.offset)
+                    .buffer_row_length(0)
+                    .buffer_image_height(0)
+                    .image_subresource(
+                        vk::ImageSubresourceLayers::default()
+                            .aspect_mask(vk::ImageAspectFlags::COLOR)
+                            .mip_level(0)
+                            .base_array_layer(0)
+                            .layer_count(1),
+                    )
+                    .image_offset(origin)
+                    .image_extent(op.extent);
+
+                let fresh = matches!(op.kind, UploadKind::New);
+                self.upload_image(staging, op.image, region, fresh)?;
             }
         }
 
@@ -323,39 +268,23 @@ impl VulkanRenderer {
                     .device
                     .free_descriptor_sets(self.ui.desc_pool, &[tex.desc_set]);
             }
-            self.core.device.destroy_image_view(tex.view, None);
-            self.core.device.destroy_image(tex.image, None);
-            self.core.device.free_memory(tex.mem, None);
-        }
-    }
-
-    unsafe fn ui_ensure_staging(&mut self, required: vk::DeviceSize) -> VkResult<()> {
-        if self.ui.staging_buf != vk::Buffer::null() && required <= self.ui.staging_size {
-            return Ok(());
-        }
-
-        if self.ui.staging_buf != vk::Buffer::null() {
-            self.core.device.destroy_buffer(self.ui.staging_buf, None);
-            self.ui.staging_buf = vk::Buffer::null();
-        }
-        if self.ui.staging_mem != vk::DeviceMemory::null() {
-            self.core.device.free_memory(self.ui.staging_mem, None);
-            self.ui.staging_mem = vk::DeviceMemory::null();
+            // Recorded uploads and frames in flight may still use the image; the upload batch
+            // fence signals after both.
+            match self.upload_fence() {
+                Ok(fence) => self.frames.deferred_free.push_image(
+                    fence,
+                    tex.image,
+                    tex.view,
+                    tex.mem,
+                    vk::Sampler::null(),
+                ),
+                Err(_) => {
+                    self.core.device.destroy_image_view(tex.view, None);
+                    self.core.device.destroy_image(tex.image, None);
+                    self.core.device.free_memory(tex.mem, None);
+                }
+            }
         }
-
-        self.ui.staging_size = required.max(64 * 1024);
-        let (buf, mem) = create_buffer(
-            &self.core.instance,
-            self.core.physical_device,
-            &self.core.device,
-            self.ui.staging_size,
-            vk::BufferUsageFlags::TRANSFER_SRC,
-            vk::MemoryPropertyFlags::HOST_VISIBLE | vk::MemoryPropertyFlags::HOST_COHERENT,
-        )?;
-
-        self.ui.staging_buf = buf;
-        self.ui.staging_mem = mem;
-        Ok(())
     }
 
     unsafe fn ui_create_texture_objects(
@@ -447,60 +376,11 @@ impl VulkanRenderer {
         Ok(gpu)
     }
 
-    pub(super) unsafe fn ui_ensure_buffers(
-        &mut self,
-        vb_bytes: vk::DeviceSize,
-        ib_bytes: vk::DeviceSize,
-    ) -> VkResult<()> {
-        if self.ui.vb == vk::Buffer::null() || vb_bytes > self.ui.vb_size {
-            if self.ui.vb != vk::Buffer::null() {
-                self.core.device.destroy_buffer(self.ui.vb, None);
-            }
-            if self.ui.vb_mem != vk::DeviceMemory::null() {
-                self.core.device.free_memory(self.ui.vb_mem, None);
-            }
-
-            self.ui.vb_size = vb_bytes.max(64 * 1024);
-            let (buf, mem) = create_buffer(
-                &self.core.instance,
-                self.core.physical_device,
-                &self.core.device,
-                self.ui.vb_size,
-                vk::BufferUsageFlags::VERTEX_BUFFER,
-                vk::MemoryPropertyFlags::HOST_VISIBLE | vk::MemoryPropertyFlags::HOST_COHERENT,
-            )?;
-            self.ui.vb = buf;
-            self.ui.vb_mem = mem;
-        }
-
-        if self.ui.ib == vk::Buffer::null() || ib_bytes > self.ui.ib_size {
-            if self.ui.ib != vk::Buffer::null() {
-                self.core.device.destroy_buffer(self.ui.ib, None);
-            }
-            if self.ui.ib_mem != vk::DeviceMemory::null() {
-                self.core.device.free_memory(self.ui.ib_mem, None);
-            }
-
-            self.ui.ib_size = ib_bytes.max(64 * 1024);
-            let (buf, mem) = create_buffer(
-                &self.core.instance,
-                self.core.physical_device,
-                &self.core.device,
-                self.ui.ib_size,
-                vk::BufferUsageFlags::INDEX_BUFFER,
-                vk::MemoryPropertyFlags::HOST_VISIBLE | vk::MemoryPropertyFlags::HOST_COHERENT,
-            )?;
-            self.ui.ib = buf;
-            self.ui.ib_mem = mem;
-        }
-
-        Ok(())
-    }
-
     pub(crate) unsafe fn ui_upload_and_draw(
         &mut self,
         cmd: vk::CommandBuffer,
         list: &UiDrawList,
+        target: vk::Extent2D,
     ) -> VkResult<()> {
         self.ui_apply_delta(&list.texture_delta)?;
 
@@ -508,38 +388,6 @@ impl VulkanRenderer {
             as vk::DeviceSize;
         let ib_bytes = (mem::size_of::<u32>() * list.mesh.indices.len()) as vk::DeviceSize;
 
-        self.ui_ensure_buffers(vb_bytes, ib_bytes)?;
-
-        if !list.mesh.vertices.is_empty() {
-            let mapped = self.core.device.map_memory(
-                self.ui.vb_mem,
-                0,
-                vb_bytes,
-                vk::MemoryMapFlags::empty(),
-            )? as *mut u8;
-            ptr::copy_nonoverlapping(
-                list.mesh.vertices.as_ptr() as *const u8,
-                mapped,
-                vb_bytes as usize,
-            );
-            self.core.device.unmap_memory(self.ui.vb_mem);
-        }
-
-        if !list.mesh.indices.is_empty() {
-            let mapped = self.core.device.map_memory(
-                self.ui.ib_mem,
-                0,
-                ib_bytes,
-                vk::MemoryMapFlags::empty(),
-            )? as *mut u8;
-            ptr::copy_nonoverlapping(
-                list.mesh.indices.as_ptr() as *const u8,
-                mapped,
-                ib_bytes as usize,
-            );
-            self.core.device.unmap_memory(self.ui.ib_mem);
-        }
-
         if list.mesh.indices.is_empty()
             || list.mesh.vertices.is_empty()
             || list.mesh.cmds.is_empty()
@@ -547,13 +395,25 @@ impl VulkanRenderer {
             return Ok(());
         }
 
+        // Ring memory: the previous frame's geometry may still be read by the GPU.
+        let vb = self.alloc_transient(vb_bytes)?;
+        vb.write(0, bytemuck::cast_slice(&list.mesh.vertices))?;
+        let ib = self.alloc_transient(ib_bytes)?;
+        ib.write(0, bytemuck::cast_slice(&list.mesh.indices))?;
+
         self.core.device.cmd_bind_pipeline(
             cmd,
             vk::PipelineBindPoint::GRAPHICS,
             self.pipelines.ui_pipeline,
         );
 
-        let pc = ui_pc_bytes(list.screen_size_px);
+        // UI vertices are in physical pixels; a list without a size maps onto the whole target.
+        let extent = [target.width, target.height];
+        let screen_size_px = match list.screen_size_px {
+            [0, _] | [_, 0] => extent,
+            s => s,
+        };
+        let pc = ui_pc_bytes(screen_size_px);
 
         self.core.device.cmd_push_constants(
             cmd,
@@ -563,23 +423,28 @@ impl VulkanRenderer {
             &pc,
         );
 
-        let vb = [self.ui.vb];
-        let offsets = [0u64];
+        let vbs = [vb.buffer];
+        let offsets = [vb.offset];
         self.core
             .device
-            .cmd_bind_vertex_buffers(cmd, 0, &vb, &offsets);
+            .cmd_bind_vertex_buffers(cmd, 0, &vbs, &offsets);
         self.core
             .device
-            .cmd_bind_index_buffer(cmd, self.ui.ib, 0, vk::IndexType::UINT32);
+            .cmd_bind_index_buffer(cmd, ib.buffer, ib.offset, vk::IndexType::UINT32);
 
         for c in &list.mesh.cmds {
-            self.ui_draw_cmd(cmd, c)?;
+            self.ui_draw_cmd(cmd, c, target)?;
         }
 
         Ok(())
     }
 
-    unsafe fn ui_draw_cmd(&mut self, cmd: vk::CommandBuffer, c: &UiDrawCmd) -> VkResult<()> {
+    unsafe fn ui_draw_cmd(
+        &mut self,
+        cmd: vk::CommandBuffer,
+        c: &UiDrawCmd,
+        target: vk::Extent2D,
+    ) -> VkResult<()> {
         let Some(tex) = self.ui.textures.get(&c.texture.0) else {
             return Ok(());
         };
@@ -589,10 +454,10 @@ impl VulkanRenderer {
         let mut x1 = c.clip_rect.max_x.ceil() as i32;
         let mut y1 = c.clip_rect.max_y.ceil() as i32;
 
-        x0 = x0.clamp(0, self.swapchain.extent.width as i32);
-        y0 = y0.clamp(0, self.swapchain.extent.height as i32);
-        x1 = x1.clamp(0, self.swapchain.extent.width as i32);
-        y1 = y1.clamp(0, self.swapchain.extent.height as i32);
+        x0 = x0.clamp(0, target.width as i32);
+        y0 = y0.clamp(0, target.height as i32);
+        x1 = x1.clamp(0, target.width as i32);
+        y1 = y1.clamp(0, target.height as i32);
 
         if x1 <= x0 || y1 <= y0 {
             return Ok(());
@@ -16,6 +16,7 @@ pub unsafe fn create_ui_pipeline(
     device: &ash::Device,
     render_pass: vk::RenderPass,
     set_layout: vk::DescriptorSetLayout,
+    cache: vk::PipelineCache,
 ) -> VkResult<(vk::PipelineLayout, vk::Pipeline)> {
     let vert = create_shader_module(
         device,
@@ -129,7 +130,7 @@ pub unsafe fn create_ui_pipeline(
         .render_pass(render_pass)
         .subpass(0);
 
-    let pipelines = device.create_graphics_pipelines(vk::PipelineCache::null(), &[gp], None);
+    let pipelines = device.create_graphics_pipelines(cache, &[gp], None);
     let pipeline = match pipelines {
         Ok(v) => v[0],
         Err((_, e)) => return Err(e.into()),
@@ -0,0 +1,271 @@
+use crate::error::VkResult;
+
+use ash::vk;
+
+use super::device::create_buffer;
+use super::renderer::UPLOAD_CONTEXTS;
+use super::util::transition_image_layout;
+use super::VulkanRenderer;
+
+const COLOR_RANGE: vk::ImageSubresourceRange = vk::ImageSubresourceRange {
+    aspect_mask: vk::ImageAspectFlags::COLOR,
+    base_mip_level: 0,
+    level_count: 1,
+    base_array_layer: 0,
+    layer_count: vk::REMAINING_ARRAY_LAYERS,
+};
+
+// Uploads are recorded into a batch that `end_frame` submits ahead of the frame, instead of
+// one blocking submit per upload. With a dedicated transfer queue the batch has two halves:
+// copies run on the transfer queue and signal a semaphore; the graphics half waits on it,
+// acquires ownership of freshly filled images and patches images the GPU already samples.
+// Staging buffers are freed through `DeferredFree` once the batch fence signals.
+impl VulkanRenderer {
+    /// Begins a batch unless one is being recorded; returns its context index.
+    unsafe fn upload_batch(&mut self) -> VkResult<usize> {
+        if let Some(i) = self.frames.upload_batch {
+            return Ok(i);
+        }
+        let i = self.frames.upload_cursor;
+        self.frames.upload_cursor = (i + 1) % UPLOAD_CONTEXTS;
+
+        self.frames.upload_ctxs[i].begin(&self.core.device)?;
+        if self.core.has_transfer_queue() {
+            if let Err(e) = self.frames.graphics_upload_ctxs[i].begin(&self.core.device) {
+                let _ = self
+                    .core
+                    .device
+                    .end_command_buffer(self.frames.upload_ctxs[i].cmd);
+                return Err(e);
+            }
+        }
+        self.frames.upload_batch = Some(i);
+        Ok(i)
+    }
+
+    /// Command buffer for copies, recorded on the transfer queue.
+    pub(crate) unsafe fn upload_transfer_cmd(&mut self) -> VkResult<vk::CommandBuffer> {
+        let i = self.upload_batch()?;
+        Ok(self.frames.upload_ctxs[i].cmd)
+    }
+
+    /// Command buffer on the graphics queue, executed after the transfer half of the batch.
+    pub(crate) unsafe fn upload_graphics_cmd(&mut self) -> VkResult<vk::CommandBuffer> {
+        let i = self.upload_batch()?;
+        if self.core.has_transfer_queue() {
+            Ok(self.frames.graphics_upload_ctxs[i].cmd)
+        } else {
+            Ok(self.frames.upload_ctxs[i].cmd)
+        }
+    }
+
+    /// Fence signaled once the whole current batch has executed.
+    pub(crate) unsafe fn upload_fence(&mut self) -> VkResult<vk::Fence> {
+        let i = self.upload_batch()?;
+        if self.core.has_transfer_queue() {
+            Ok(self.frames.graphics_upload_ctxs[i].fence)
+        } else {
+            Ok(self.frames.upload_ctxs[i].fence)
+        }
+    }
+
+    /// Host-visible buffer holding `size` bytes for the transfer queue to read from.
+    pub(crate) unsafe fn create_staging(
+        &self,
+        size: vk::DeviceSize,
+    ) -> VkResult<(vk::Buffer, vk::DeviceMemory)> {
+        create_buffer(
+            &self.core.instance,
+            self.core.physical_device,
+            &self.core.device,
+            size,
+            vk::BufferUsageFlags::TRANSFER_SRC,
+            vk::MemoryPropertyFlags::HOST_VISIBLE | vk::MemoryPropertyFlags::HOST_COHERENT,
+        )
+    }
+
+    /// Copies `data` into `dst` at `dst_offset` in the current batch. `dst_stage`/`dst_access`
+    /// describe how frames read the buffer. `dst` must be created with concurrent sharing
+    /// across the graphics and transfer families when they differ.
+    pub(crate) unsafe fn upload_buffer(
+        &mut self,
+        dst: vk::Buffer,
+        dst_offset: vk::DeviceSize,
+        data: &[u8],
+        dst_stage: vk::PipelineStageFlags,
+        dst_access: vk::AccessFlags,
+    ) -> VkResult<()> {
+        if data.is_empty() {
+            return Ok(());
+        }
+        let size = data.len() as vk::DeviceSize;
+
+        let fence = self.upload_fence()?;
+        let (staging, memory) = self.create_staging(size)?;
+        self.frames
+            .deferred_free
+            .push_buffer(fence, staging, memory);
+
+        let device = &self.core.device;
+        let ptr = device.map_memory(memory, 0, size, vk::MemoryMapFlags::empty())? as *mut u8;
+        std::ptr::copy_nonoverlapping(data.as_ptr(), ptr, data.len());
+        device.unmap_memory(memory);
+
+        let cmd = self.upload_transfer_cmd()?;
+        let device = &self.core.device;
+        let region = vk::BufferCopy::default()
+            .src_offset(0)
+            .dst_offset(dst_offset)
+            .size(size);
+        device.cmd_copy_buffer(cmd, staging, dst, std::slice::from_ref(&region));
+
+        // With a transfer queue, the semaphore and the hand-over barrier in `flush_uploads`
+        // order the copy before the frame.
+        if !self.core.has_transfer_queue() {
+            let barrier = vk::BufferMemoryBarrier::default()
+                .src_access_mask(vk::AccessFlags::TRANSFER_WRITE)
+                .dst_access_mask(dst_access)
+                .src_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                .dst_queue_family_index(vk::QUEUE_FAMILY_IGNORED)
+                .buffer(dst)
+                .offset(dst_offset)
+                .size(size);
+
+            device.cmd_pipeline_barrier(
+                cmd,
+                vk::PipelineStageFlags::TRANSFER,
+                dst_stage,
+                vk::DependencyFlags::empty(),
+                &[],
+                std::slice::from_ref(&barrier),
+                &[],
+            );
+        }
+        Ok(())
+    }
+
+    /// Copies `region` of `staging` into a sampled color image and leaves it in
+    /// `SHADER_READ_ONLY_OPTIMAL` on the graphics queue. `fresh` images (contents undefined) are
+    /// filled on the transfer queue and handed over with an ownership transfer; existing ones
+    /// are patched on the graphics queue so their contents never have to travel back.
+    /// `staging` must live until `upload_fence` signals.
+    pub(crate) unsafe fn upload_image(
+        &mut self,
+        staging: vk::Buffer,
+        image: vk::Image,
+        region: vk::BufferImageCopy,
+        fresh: bool,
+    ) -> VkResult<()> {
+        let old_layout = if fresh {
+            vk::ImageLayout::UNDEFINED
+        } else {
+            vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL
+        };
+        let cmd = if fresh {
+            self.upload_transfer_cmd()?
+        } else {
+            self.upload_graphics_cmd()?
+        };
+        let device = &self.core.device;
+
+        transition_image_layout(
+            device,
+            cmd,
+            image,
+            old_layout,
+            vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+        );
+        device.cmd_copy_buffer_to_image(
+            cmd,
+            staging,
+            image,
+            vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+            std::slice::from_ref(&region),
+        );
+
+        if !fresh || !self.core.has_transfer_queue() {
+            transition_image_layout(
+                device,
+                cmd,
+                image,
+                vk::ImageLayout::TRANSFER_DST_OPTIMAL,
+                vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+            );
+            return Ok(());
+        }
+
+        // Release on the transfer queue, acquire on the graphics queue. Both barriers carry
+        // the same layout change.
+        let handover = vk::ImageMemoryBarrier::default()
+            .old_layout(vk::ImageLayout::TRANSFER_DST_OPTIMAL)
+            .new_layout(vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL)
+            .src_queue_family_index(self.core.transfer_queue_family_index)
+            .dst_queue_family_index(self.core.queue_family_index)
+            .image(image)
+            .subresource_range(COLOR_RANGE);
+
+        let release = handover.src_access_mask(vk::AccessFlags::TRANSFER_WRITE);
+        device.cmd_pipeline_barrier(
+            cmd,
+            vk::PipelineStageFlags::TRANSFER,
+            vk::PipelineStageFlags::BOTTOM_OF_PIPE,
+            vk::DependencyFlags::empty(),
+            &[],
+            &[],
+            std::slice::from_ref(&release),
+        );
+
+        let cmd = self.upload_graphics_cmd()?;
+        let acquire = handover.dst_access_mask(vk::AccessFlags::SHADER_READ);
+        self.core.device.cmd_pipeline_barrier(
+            cmd,
+            vk::PipelineStageFlags::ALL_COMMANDS,
+            vk::PipelineStageFlags::FRAGMENT_SHADER,
+            vk::DependencyFlags::empty(),
+            &[],
+            &[],
+            std::slice::from_ref(&acquire),
+        );
+        Ok(())
+    }
+
+    /// Submits the batch being recorded, if any. Must run before the frame that uses the
+    /// uploaded data is submitted.
+    pub(crate) unsafe fn flush_uploads(&mut self) -> VkResult<()> {
+        let Some(i) = self.frames.upload_batch.take() else {
+            return Ok(());
+        };
+        let device = &self.core.device;
+        let transfer = self.frames.upload_ctxs[i];
+
+        if !self.core.has_transfer_queue() {
+            transfer.submit(device, self.core.queue, &[], &[])?;
+            return Ok(());
+        }
+
+        let graphics = self.frames.graphics_upload_ctxs[i];
+        let semaphore = [self.frames.upload_semaphores[i]];
+
+        // Chains the semaphore wait to everything submitted to the graphics queue afterwards,
+        // so frames see the copied buffers.
+        let handover = vk::MemoryBarrier::default()
+            .src_access_mask(vk::AccessFlags::MEMORY_WRITE)
+            .dst_access_mask(vk::AccessFlags::MEMORY_READ | vk::AccessFlags::MEMORY_WRITE);
+        device.cmd_pipeline_barrier(
+            graphics.cmd,
+            vk::PipelineStageFlags::ALL_COMMANDS,
+            vk::PipelineStageFlags::ALL_COMMANDS,
+            vk::DependencyFlags::empty(),
+            std::slice::from_ref(&handover),
+            &[],
+            &[],
+        );
+
+        if let Err(e) = transfer.submit(device, self.core.transfer_queue, &[], &semaphore) {
+            let _ = device.end_command_buffer(graphics.cmd);
+            return Err(e);
+        }
+        graphics.submit(device, self.core.queue, &semaphore, &[])?;
+        Ok(())
+    }
+}
@@ -1,5 +1,3 @@
-use crate::error::VkResult;
-
 use ash::vk;
 
 /// Picks conservative stage+access masks for a given layout.
@@ -44,6 +42,7 @@ fn stage_access_for_layout(layout: vk::ImageLayout) -> (vk::PipelineStageFlags,
 }
 
 /// Generic barrier helper. Critical: queue family indices MUST be IGNORED unless ownership transfer is intended.
+/// Covers mip 0 of every array layer.
 #[inline]
 pub unsafe fn transition_image_layout(
     device: &ash::Device,
@@ -73,7 +72,7 @@ pub unsafe fn transition_image_layout(
                 .base_mip_level(0)
                 .level_count(1)
                 .base_array_layer(0)
-                .layer_count(1),
+                .layer_count(vk::REMAINING_ARRAY_LAYERS),
         );
 
     device.cmd_pipeline_barrier(
@@ -98,42 +97,3 @@ pub unsafe fn transition_image(
 ) {
     transition_image_layout(device, cmd, image, old_layout, new_layout);
 }
-
-/// One-shot submit utility for short copy/transition work.
-///
-/// Note:
-/// - This does NOT call `queue_wait_idle`.
-/// - It uses a temporary fence and waits for that fence.
-/// - Prefer `VulkanRenderer::submit_upload` for high-frequency uploads.
-pub unsafe fn immediate_submit<F: FnOnce(vk::CommandBuffer)>(
-    device: &ash::Device,
-    pool: vk::CommandPool,
-    queue: vk::Queue,
-    f: F,
-) -> VkResult<()> {
-    let alloc = vk::CommandBufferAllocateInfo::default()
-        .command_pool(pool)
-        .level(vk::CommandBufferLevel::PRIMARY)
-        .command_buffer_count(1);
-
-    let cmd = device.allocate_command_buffers(&alloc)?[0];
-
-    device.begin_command_buffer(
-        cmd,
-        &vk::CommandBufferBeginInfo::default().flags(vk::CommandBufferUsageFlags::ONE_TIME_SUBMIT),
-    )?;
-
-    f(cmd);
-
-    device.end_command_buffer(cmd)?;
-
-    let fence = device.create_fence(&vk::FenceCreateInfo::default(), None)?;
-
-    let submit = vk::SubmitInfo::default().command_buffers(std::slice::from_ref(&cmd));
-    device.queue_submit(queue, std::slice::from_ref(&submit), fence)?;
-    device.wait_for_fences(&[fence], true, u64::MAX)?;
-    device.destroy_fence(fence, None);
-
-    device.free_command_buffers(pool, std::slice::from_ref(&cmd));
-    Ok(())
-}
\ No newline at end of file
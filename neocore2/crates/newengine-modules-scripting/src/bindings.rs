@@ -0,0 +1,227 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use abi_stable::std_types::{RString, RVec};
+
+use newengine_plugin_api::HostApiV1;
+
+use parking_lot::Mutex;
+use rhai::{Array, Dynamic, Engine, EvalAltResult, FLOAT, INT};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+
+const COMMAND_SERVICE_ID: &str = "engine.command";
+const COMMAND_EXEC: &str = "command.exec";
+
+const INPUT_SERVICE_ID: &str = "kalitech.input.v1";
+const INPUT_STATE_JSON: &str = "state_json";
+
+/// Per-call budget; a runaway loop aborts the script instead of hanging the frame.
+const MAX_OPERATIONS: u64 = 5_000_000;
+
+/* =============================================================================================
+   Host bridge
+   ============================================================================================= */
+
+static HOST: OnceLock<HostApiV1> = OnceLock::new();
+
+#[inline]
+pub(crate) fn set_host(host: HostApiV1) {
+    let _ = HOST.set(host);
+}
+
+#[inline]
+pub(crate) fn log_info(msg: impl Into<String>) {
+    if let Some(h) = HOST.get() {
+        (h.log_info)(RString::from(msg.into()));
+    }
+}
+
+#[inline]
+pub(crate) fn log_warn(msg: impl Into<String>) {
+    if let Some(h) = HOST.get() {
+        (h.log_warn)(RString::from(msg.into()));
+    }
+}
+
+#[inline]
+pub(crate) fn log_error(msg: impl Into<String>) {
+    if let Some(h) = HOST.get() {
+        (h.log_error)(RString::from(msg.into()));
+    }
+}
+
+pub(crate) fn call_service(id: &str, method: &str, payload: &[u8]) -> Result<Vec<u8>, String> {
+    let Some(h) = HOST.get() else {
+        return Err("scripting: host api is not initialized".to_string());
+    };
+
+    (h.call_service_v1)(
+        RString::from(id),
+        RString::from(method),
+        RVec::from(payload.to_vec()),
+    )
+    .into_result()
+    .map(|b| b.into_vec())
+    .map_err(|e| e.into_string())
+}
+
+/* =============================================================================================
+   Shared UI / input view
+   ============================================================================================= */
+
+#[derive(Default)]
+pub(crate) struct Bridge {
+    /// Last `UiState` vars pushed by the host through `script.ui_sync`.
+    pub ui_vars: BTreeMap<String, String>,
+    /// Script writes not yet picked up by the host.
+    pub ui_writes: BTreeMap<String, String>,
+    /// Input snapshot for the current frame; fetched on first use.
+    input: Option<Value>,
+}
+
+static BRIDGE: OnceLock<Mutex<Bridge>> = OnceLock::new();
+
+#[inline]
+pub(crate) fn bridge() -> &'static Mutex<Bridge> {
+    BRIDGE.get_or_init(|| Mutex::new(Bridge::default()))
+}
+
+/// Drops the cached input snapshot so the next query sees this frame's state.
+#[inline]
+pub(crate) fn begin_frame() {
+    bridge().lock().input = None;
+}
+
+fn input_snapshot() -> Value {
+    if let Some(v) = bridge().lock().input.as_ref() {
+        return v.clone();
+    }
+
+    // Fetched outside the lock: the input service may be slow to serialize.
+    let v = call_service(INPUT_SERVICE_ID, INPUT_STATE_JSON, &[])
+        .ok()
+        .and_then(|b| serde_json::from_slice::<Value>(&b).ok())
+        .unwrap_or(Value::Null);
+
+    bridge().lock().input = Some(v.clone());
+    v
+}
+
+#[inline]
+fn contains_code(list: &Value, code: INT) -> bool {
+    list.as_array()
+        .is_some_and(|a| a.iter().any(|x| x.as_i64() == Some(code)))
+}
+
+#[inline]
+fn script_err(msg: impl Into<String>) -> Box<EvalAltResult> {
+    msg.into().into()
+}
+
+/* =============================================================================================
+   Engine
+   ============================================================================================= */
+
+/// Script-facing API:
+///
+/// - `log(s)`, `warn(s)`, `print(s)`
+/// - `call_service(id, method[, payload]) -> string`
+/// - `console(line) -> string`
+/// - `input() -> map`, `key_down/key_pressed/key_released(code)`,
+///   `mouse_down/mouse_pressed(button)`, `mouse_pos() -> [x, y]`
+/// - `ui_get(name) -> string | ()`, `ui_set(name, value)`
+pub(crate) fn build_engine() -> Engine {
+    let mut engine = Engine::new();
+    engine.set_max_operations(MAX_OPERATIONS);
+
+    engine.on_print(|s| log_info(format!("script: {s}")));
+    engine.on_debug(|s, src, pos| {
+        log_info(format!(
+            "script: debug {}:{pos} {s}",
+            src.unwrap_or("<eval>")
+        ))
+    });
+
+    engine.register_fn("log", |s: &str| log_info(format!("script: {s}")));
+    engine.register_fn("warn", |s: &str| log_warn(format!("script: {s}")));
+
+    engine.register_fn(
+        "call_service",
+        |id: &str, method: &str, payload: &str| -> Result<String, Box<EvalAltResult>> {
+            call_service(id, method, payload.as_bytes())
+                .map(|b| String::from_utf8_lossy(&b).to_string())
+                .map_err(|e| script_err(format!("{id}/{method}: {e}")))
+        },
+    );
+    engine.register_fn(
+        "call_service",
+        |id: &str, method: &str| -> Result<String, Box<EvalAltResult>> {
+            call_service(id, method, &[])
+                .map(|b| String::from_utf8_lossy(&b).to_string())
+                .map_err(|e| script_err(format!("{id}/{method}: {e}")))
+        },
+    );
+
+    engine.register_fn(
+        "console",
+        |line: &str| -> Result<String, Box<EvalAltResult>> {
+            let bytes = call_service(COMMAND_SERVICE_ID, COMMAND_EXEC, line.as_bytes())
+                .map_err(script_err)?;
+            let v: Value = serde_json::from_slice(&bytes)
+                .map_err(|e| script_err(format!("console: bad response json: {e}")))?;
+
+            if v["ok"].as_bool() == Some(true) {
+                Ok(v["output"].as_str().unwrap_or_default().to_string())
+            } else {
+                Err(script_err(
+                    v["error"].as_str().unwrap_or("command failed").to_string(),
+                ))
+            }
+        },
+    );
+
+    engine.register_fn("input", || -> Result<Dynamic, Box<EvalAltResult>> {
+        rhai::serde::to_dynamic(input_snapshot())
+    });
+    engine.register_fn("key_down", |code: INT| {
+        contains_code(&input_snapshot()["keys"]["down"], code)
+    });
+    engine.register_fn("key_pressed", |code: INT| {
+        contains_code(&input_snapshot()["keys"]["pressed"], code)
+    });
+    engine.register_fn("key_released", |code: INT| {
+        contains_code(&input_snapshot()["keys"]["released"], code)
+    });
+    engine.register_fn("mouse_down", |button: INT| {
+        contains_code(&input_snapshot()["mouse"]["down"], button)
+    });
+    engine.register_fn("mouse_pressed", |button: INT| {
+        contains_code(&input_snapshot()["mouse"]["pressed"], button)
+    });
+    engine.register_fn("mouse_pos", || -> Array {
+        let v = input_snapshot();
+        let pos = &v["mouse"]["pos"];
+        vec![
+            Dynamic::from_float(pos["x"].as_f64().unwrap_or_default() as FLOAT),
+            Dynamic::from_float(pos["y"].as_f64().unwrap_or_default() as FLOAT),
+        ]
+    });
+
+    engine.register_fn("ui_get", |name: &str| -> Dynamic {
+        bridge()
+            .lock()
+            .ui_vars
+            .get(name)
+            .map(|v| Dynamic::from(v.clone()))
+            .unwrap_or(Dynamic::UNIT)
+    });
+    engine.register_fn("ui_set", |name: &str, value: Dynamic| {
+        let value = value.to_string();
+        let mut b = bridge().lock();
+        b.ui_vars.insert(name.to_string(), value.clone());
+        b.ui_writes.insert(name.to_string(), value);
+    });
+
+    engine
+}
@@ -0,0 +1,7 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+#![allow(non_local_definitions)]
+#![allow(non_camel_case_types)]
+
+mod bindings;
+mod module;
+mod plugin;
@@ -0,0 +1,496 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use abi_stable::std_types::{RResult, RString, RVec};
+use abi_stable::StableAbi;
+
+use newengine_plugin_api::{
+    guarded_service, Blob, HostApiV1, MethodName, PluginInfo, PluginModule, ServiceV1, ServiceV1Dyn,
+};
+
+use parking_lot::Mutex;
+use rhai::{CallFnOptions, Dynamic, Engine, Scope, AST, FLOAT};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::OnceLock;
+
+use crate::bindings::{self, bridge, call_service, log_error, log_info, log_warn};
+
+const SCRIPT_SERVICE_ID: &str = "kalitech.script.v1";
+
+mod method {
+    pub const RUN: &str = "script.run";
+    pub const STOP: &str = "script.stop";
+    pub const LIST_JSON: &str = "script.list_json";
+    pub const UI_SYNC: &str = "script.ui_sync";
+}
+
+const ASSET_SERVICE_ID: &str = "asset.manager";
+const ASSET_LOAD: &str = "asset.load";
+const ASSET_RELOAD: &str = "asset.reload";
+const ASSET_INFO_JSON: &str = "asset.info_json";
+const ASSET_READ: &str = "asset.read";
+
+/// Script function called every frame with `dt` (seconds) when defined.
+const UPDATE_FN: &str = "update";
+
+/// How often source modification times are polled for hot reload.
+const RELOAD_POLL_SECS: f32 = 0.5;
+
+/* =============================================================================================
+   Shared state (service <-> plugin)
+   ============================================================================================= */
+
+enum Request {
+    Run(String),
+    Stop(String),
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct ScriptStatus {
+    path: String,
+    state: &'static str,
+    error: Option<String>,
+    reloads: u32,
+}
+
+#[derive(Default)]
+struct Shared {
+    requests: Vec<Request>,
+    status: BTreeMap<String, ScriptStatus>,
+}
+
+static SHARED: OnceLock<Mutex<Shared>> = OnceLock::new();
+
+#[inline]
+fn shared() -> &'static Mutex<Shared> {
+    SHARED.get_or_init(|| Mutex::new(Shared::default()))
+}
+
+fn set_status(path: &str, state: &'static str, error: Option<String>) {
+    let mut g = shared().lock();
+    let st = g
+        .status
+        .entry(path.to_string())
+        .or_insert_with(|| ScriptStatus {
+            path: path.to_string(),
+            state,
+            error: None,
+            reloads: 0,
+        });
+    st.state = state;
+    st.error = error;
+}
+
+/* =============================================================================================
+   Asset service responses (subset)
+   ============================================================================================= */
+
+#[derive(Debug, Deserialize)]
+struct LoadResp {
+    ok: bool,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AssetInfo {
+    state: String,
+    #[serde(default)]
+    error: Option<String>,
+    #[serde(default)]
+    modified_unix_ms: Option<u64>,
+}
+
+fn asset_request(method: &str, path: &str) -> Result<(), String> {
+    let bytes = call_service(ASSET_SERVICE_ID, method, path.as_bytes())?;
+    let resp: LoadResp = serde_json::from_slice(&bytes).map_err(|e| e.to_string())?;
+    if resp.ok {
+        Ok(())
+    } else {
+        Err(resp.error.unwrap_or_else(|| format!("{method} failed")))
+    }
+}
+
+fn asset_info(path: &str) -> Result<AssetInfo, String> {
+    let bytes = call_service(ASSET_SERVICE_ID, ASSET_INFO_JSON, path.as_bytes())?;
+    serde_json::from_slice(&bytes).map_err(|e| e.to_string())
+}
+
+/* =============================================================================================
+   Service
+   ============================================================================================= */
+
+#[derive(StableAbi)]
+#[repr(C)]
+struct ScriptService;
+
+impl ServiceV1 for ScriptService {
+    fn id(&self) -> RString {
+        RString::from(SCRIPT_SERVICE_ID)
+    }
+
+    fn describe(&self) -> RString {
+        RString::from(
+            json!({
+                "id": SCRIPT_SERVICE_ID,
+                "version": 1,
+                "methods": [
+                    { "name": method::RUN, "payload": "utf8 logical_path", "returns": "json {ok, queued}" },
+                    { "name": method::STOP, "payload": "utf8 logical_path", "returns": "json {ok, stopped}" },
+                    { "name": method::LIST_JSON, "payload": "empty", "returns": "json [ScriptStatus]" },
+                    { "name": method::UI_SYNC, "payload": "json {var: value} (host UiState vars)", "returns": "json {var: value} written by scripts" }
+                ],
+                "console": {
+                    "commands": [
+                        {
+                            "name": "script.run",
+                            "help": "Run a script asset (hot reloaded): script.run <path>",
+                            "usage": "script.run <path>",
+                            "kind": "service_call",
+                            "service_id": SCRIPT_SERVICE_ID,
+                            "method": method::RUN,
+                            "payload": "raw"
+                        },
+                        {
+                            "name": "script.stop",
+                            "help": "Stop a running script: script.stop <path>",
+                            "usage": "script.stop <path>",
+                            "kind": "service_call",
+                            "service_id": SCRIPT_SERVICE_ID,
+                            "method": method::STOP,
+                            "payload": "raw"
+                        },
+                        {
+                            "name": "script.list",
+                            "help": "List scripts and their state",
+                            "kind": "service_call",
+                            "service_id": SCRIPT_SERVICE_ID,
+                            "method": method::LIST_JSON,
+                            "payload": "empty"
+                        }
+                    ]
+                }
+            })
+                .to_string(),
+        )
+    }
+
+    fn call(&self, method: MethodName, payload: Blob) -> RResult<Blob, RString> {
+        match method.as_str() {
+            method::RUN | method::STOP => {
+                let path = String::from_utf8_lossy(payload.as_slice())
+                    .trim()
+                    .to_string();
+                if path.is_empty() {
+                    return RResult::RErr(RString::from(format!("{method}: empty path")));
+                }
+
+                let resp = if method.as_str() == method::RUN {
+                    shared().lock().requests.push(Request::Run(path.clone()));
+                    json!({ "ok": true, "queued": path })
+                } else {
+                    shared().lock().requests.push(Request::Stop(path.clone()));
+                    json!({ "ok": true, "stopped": path })
+                };
+                RResult::ROk(RVec::from(resp.to_string().into_bytes()))
+            }
+            method::LIST_JSON => {
+                let list: Vec<ScriptStatus> = shared().lock().status.values().cloned().collect();
+                RResult::ROk(RVec::from(serde_json::to_vec(&list).unwrap_or_default()))
+            }
+            method::UI_SYNC => {
+                let vars: BTreeMap<String, String> =
+                    match serde_json::from_slice(payload.as_slice()) {
+                        Ok(v) => v,
+                        Err(e) => {
+                            return RResult::RErr(RString::from(format!(
+                                "{method}: bad payload: {e}"
+                            )))
+                        }
+                    };
+
+                let mut b = bridge().lock();
+                let writes = std::mem::take(&mut b.ui_writes);
+                b.ui_vars = vars;
+                b.ui_vars
+                    .extend(writes.iter().map(|(k, v)| (k.clone(), v.clone())));
+
+                RResult::ROk(RVec::from(serde_json::to_vec(&writes).unwrap_or_default()))
+            }
+            _ => RResult::RErr(RString::from(format!(
+                "scripting: unknown method '{}'",
+                method
+            ))),
+        }
+    }
+}
+
+/* =============================================================================================
+   Plugin module
+   ============================================================================================= */
+
+struct LoadedScript {
+    ast: AST,
+    scope: Scope<'static>,
+    has_update: bool,
+    failed: bool,
+}
+
+pub struct ScriptPlugin {
+    engine: Engine,
+    scripts: BTreeMap<String, LoadedScript>,
+    /// Paths waiting for the asset store to finish (first load or reload).
+    loading: BTreeSet<String>,
+    /// Source modification time seen at the last load, per path (running or failed).
+    watched: BTreeMap<String, Option<u64>>,
+    poll_acc: f32,
+}
+
+impl Default for ScriptPlugin {
+    fn default() -> Self {
+        Self {
+            engine: bindings::build_engine(),
+            scripts: BTreeMap::new(),
+            loading: BTreeSet::new(),
+            watched: BTreeMap::new(),
+            poll_acc: 0.0,
+        }
+    }
+}
+
+impl ScriptPlugin {
+    fn drain_requests(&mut self) {
+        let requests = std::mem::take(&mut shared().lock().requests);
+
+        for req in requests {
+            match req {
+                Request::Run(path) => match asset_request(ASSET_LOAD, &path) {
+                    Ok(()) => {
+                        self.loading.insert(path.clone());
+                        set_status(&path, "loading", None);
+                    }
+                    Err(e) => {
+                        log_error(format!("script: load failed path='{path}' err='{e}'"));
+                        set_status(&path, "failed", Some(e));
+                    }
+                },
+                Request::Stop(path) => {
+                    let known = self.scripts.remove(&path).is_some()
+                        | self.loading.remove(&path)
+                        | self.watched.remove(&path).is_some();
+                    shared().lock().status.remove(&path);
+                    if known {
+                        log_info(format!("script: stopped path='{path}'"));
+                    }
+                }
+            }
+        }
+    }
+
+    fn poll_loading(&mut self) {
+        let pending: Vec<String> = self.loading.iter().cloned().collect();
+
+        for path in pending {
+            let info = match asset_info(&path) {
+                Ok(v) => v,
+                Err(e) => {
+                    self.loading.remove(&path);
+                    self.finish_failed(&path, None, e);
+                    continue;
+                }
+            };
+
+            match info.state.as_str() {
+                "ready" => {
+                    self.loading.remove(&path);
+                    match call_service(ASSET_SERVICE_ID, ASSET_READ, path.as_bytes()) {
+                        Ok(bytes) => {
+                            let source = String::from_utf8_lossy(&bytes);
+                            self.start(&path, &source, info.modified_unix_ms);
+                        }
+                        Err(e) => self.finish_failed(&path, info.modified_unix_ms, e),
+                    }
+                }
+                "failed" => {
+                    self.loading.remove(&path);
+                    let e = info.error.unwrap_or_else(|| "import failed".to_string());
+                    self.finish_failed(&path, info.modified_unix_ms, e);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Compiles and runs the top level of a script, replacing the previous instance.
+    ///
+    /// A failed reload keeps the previous instance running.
+    fn start(&mut self, path: &str, source: &str, modified: Option<u64>) {
+        let ast = match self.engine.compile(source) {
+            Ok(ast) => ast,
+            Err(e) => return self.finish_failed(path, modified, e.to_string()),
+        };
+
+        let mut scope = Scope::new();
+        if let Err(e) = self.engine.run_ast_with_scope(&mut scope, &ast) {
+            return self.finish_failed(path, modified, e.to_string());
+        }
+
+        let has_update = ast
+            .iter_functions()
+            .any(|f| f.name == UPDATE_FN && f.params.len() == 1);
+
+        let reloaded = self
+            .scripts
+            .insert(
+                path.to_string(),
+                LoadedScript {
+                    ast,
+                    scope,
+                    has_update,
+                    failed: false,
+                },
+            )
+            .is_some();
+        self.watched.insert(path.to_string(), modified);
+
+        set_status(path, "running", None);
+        if reloaded {
+            if let Some(st) = shared().lock().status.get_mut(path) {
+                st.reloads += 1;
+            }
+        }
+
+        log_info(format!(
+            "script: {} path='{path}' update={has_update}",
+            if reloaded { "reloaded" } else { "started" }
+        ));
+    }
+
+    fn finish_failed(&mut self, path: &str, modified: Option<u64>, err: String) {
+        self.watched.insert(path.to_string(), modified);
+        log_error(format!("script: failed path='{path}' err='{err}'"));
+
+        let state = if self.scripts.contains_key(path) {
+            "running"
+        } else {
+            "failed"
+        };
+        set_status(path, state, Some(err));
+    }
+
+    fn poll_reload(&mut self, dt: f32) {
+        self.poll_acc += dt;
+        if self.poll_acc < RELOAD_POLL_SECS {
+            return;
+        }
+        self.poll_acc = 0.0;
+
+        let watched: Vec<(String, Option<u64>)> = self
+            .watched
+            .iter()
+            .filter(|(p, _)| !self.loading.contains(*p))
+            .map(|(p, m)| (p.clone(), *m))
+            .collect();
+
+        for (path, seen) in watched {
+            let Ok(info) = asset_info(&path) else {
+                continue;
+            };
+            if info.modified_unix_ms.is_none() || info.modified_unix_ms == seen {
+                continue;
+            }
+
+            match asset_request(ASSET_RELOAD, &path) {
+                Ok(()) => {
+                    self.loading.insert(path.clone());
+                    log_info(format!("script: source changed, reloading path='{path}'"));
+                }
+                Err(e) => {
+                    self.watched.insert(path.clone(), info.modified_unix_ms);
+                    log_warn(format!("script: reload failed path='{path}' err='{e}'"));
+                }
+            }
+        }
+    }
+
+    fn tick(&mut self, dt: f32) {
+        for (path, s) in self.scripts.iter_mut() {
+            if !s.has_update || s.failed {
+                continue;
+            }
+
+            let r = self.engine.call_fn_with_options::<Dynamic>(
+                CallFnOptions::new().eval_ast(false).rewind_scope(false),
+                &mut s.scope,
+                &s.ast,
+                UPDATE_FN,
+                (dt as FLOAT,),
+            );
+
+            if let Err(e) = r {
+                // Stays disabled until the source changes and the script is reloaded.
+                s.failed = true;
+                log_error(format!("script: update failed path='{path}' err='{e}'"));
+                set_status(path, "failed", Some(e.to_string()));
+            }
+        }
+    }
+}
+
+impl PluginModule for ScriptPlugin {
+    fn info(&self) -> PluginInfo {
+        PluginInfo {
+            id: RString::from(env!("CARGO_PKG_NAME")),
+            name: RString::from("NewEngine Scripting"),
+            version: RString::from(env!("CARGO_PKG_VERSION")),
+            requires: RVec::from(vec![
+                RString::from("host.services@>=2"),
+                RString::from(ASSET_SERVICE_ID),
+            ]),
+        }
+    }
+
+    fn init(&mut self, host: HostApiV1) -> RResult<(), RString> {
+        bindings::set_host(host.clone());
+
+        let svc: ServiceV1Dyn<'static> = guarded_service(ScriptService);
+        if let Err(e) = (host.register_service_v1)(svc).into_result() {
+            return RResult::RErr(RString::from(format!(
+                "scripting: register_service_v1 failed: {}",
+                e
+            )));
+        }
+
+        (host.log_info)(RString::from("scripting: initialized (rhai)"));
+        RResult::ROk(())
+    }
+
+    fn start(&mut self) -> RResult<(), RString> {
+        RResult::ROk(())
+    }
+
+    fn fixed_update(&mut self, _dt: f32) -> RResult<(), RString> {
+        RResult::ROk(())
+    }
+
+    fn update(&mut self, dt: f32) -> RResult<(), RString> {
+        bindings::begin_frame();
+
+        self.drain_requests();
+        self.poll_loading();
+        self.poll_reload(dt);
+        self.tick(dt);
+        RResult::ROk(())
+    }
+
+    fn render(&mut self, _dt: f32) -> RResult<(), RString> {
+        RResult::ROk(())
+    }
+
+    fn shutdown(&mut self) {
+        self.scripts.clear();
+        self.loading.clear();
+        self.watched.clear();
+    }
+}
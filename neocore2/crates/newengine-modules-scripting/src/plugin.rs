@@ -0,0 +1,16 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use abi_stable::derive_macro_reexports::PrefixTypeTrait;
+
+use newengine_plugin_api::{guarded_module, PluginModuleDyn, PluginRootV1, PluginRootV1Ref};
+
+use crate::module::ScriptPlugin;
+
+#[no_mangle]
+pub extern "C" fn export_plugin_root() -> PluginRootV1Ref {
+    PluginRootV1 { create: create_module }.leak_into_prefix()
+}
+
+extern "C" fn create_module() -> PluginModuleDyn<'static> {
+    guarded_module(ScriptPlugin::default())
+}
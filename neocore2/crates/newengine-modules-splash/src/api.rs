@@ -0,0 +1,77 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use newengine_assets::LoadGroup;
+use parking_lot::Mutex;
+use std::sync::Arc;
+
+struct SplashState {
+    active: bool,
+    groups: Vec<LoadGroup>,
+}
+
+/// Shared handle to the boot splash, registered as `splash.api`.
+///
+/// The splash stays up until every tracked group is done; the host's render controller checks
+/// [`SplashApiRef::is_active`] and keeps the scene hidden until then.
+#[derive(Clone)]
+pub struct SplashApiRef(Arc<Mutex<SplashState>>);
+
+impl SplashApiRef {
+    #[inline]
+    pub fn new() -> Self {
+        Self(Arc::new(Mutex::new(SplashState {
+            active: true,
+            groups: Vec::new(),
+        })))
+    }
+
+    /// Holds the splash until `group` is done as well. Ignored once the splash is gone.
+    pub fn track(&self, group: LoadGroup) {
+        let mut s = self.0.lock();
+        if s.active {
+            s.groups.push(group);
+        }
+    }
+
+    #[inline]
+    pub fn is_active(&self) -> bool {
+        self.0.lock().active
+    }
+
+    /// Loads done out of all tracked loads, in `0.0..=1.0`. Nothing tracked is complete.
+    pub fn progress(&self) -> f32 {
+        let (done, total) = self
+            .0
+            .lock()
+            .groups
+            .iter()
+            .map(LoadGroup::done_count)
+            .fold((0, 0), |(d, t), (gd, gt)| (d + gd, t + gt));
+        if total == 0 {
+            return 1.0;
+        }
+        done as f32 / total as f32
+    }
+
+    #[inline]
+    pub(crate) fn groups_done(&self) -> bool {
+        self.0.lock().groups.iter().all(LoadGroup::is_done)
+    }
+
+    /// Ends the splash and returns `(ready, failed)` over the tracked loads.
+    pub(crate) fn finish(&self) -> (usize, usize) {
+        let mut s = self.0.lock();
+        s.active = false;
+        std::mem::take(&mut s.groups)
+            .iter()
+            .map(LoadGroup::outcome)
+            .fold((0, 0), |(r, f), (gr, gf, gc)| (r + gr, f + gf + gc))
+    }
+}
+
+impl Default for SplashApiRef {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,14 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+mod api;
+mod image;
+mod module;
+
+pub use api::SplashApiRef;
+pub use module::{SplashConfig, SplashModule};
+
+use newengine_core::{ApiProvide, ApiVersion};
+
+pub const SPLASH_API_ID: &str = "splash.api";
+pub const SPLASH_API_VERSION: ApiVersion = ApiVersion::new(0, 1, 0);
+pub const SPLASH_API_PROVIDE: ApiProvide = ApiProvide::new(SPLASH_API_ID, SPLASH_API_VERSION);
@@ -0,0 +1,315 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use newengine_assets::{AssetId, AssetKey, AssetState, LoadPriority};
+use newengine_core::assets::AssetManager;
+use newengine_core::render::{Color4, RenderApi, RenderApiRef, TextureId, RENDER_API_ID};
+use newengine_core::{ApiProvide, EngineResult, Module, ModuleCtx};
+use newengine_modules_sprite2d::{Camera2D, Sprite, Sprite2dApiRef, SPRITE2D_API_ID};
+use std::time::{Duration, Instant};
+
+use crate::api::SplashApiRef;
+use crate::image::decode_png_rgba8;
+use crate::{SPLASH_API_ID, SPLASH_API_PROVIDE};
+
+/// Sprite layer of the backdrop; the image and the bar go above it, everything else below.
+const SPLASH_LAYER: i32 = 1 << 24;
+
+/// Share of the screen the image may cover, keeping its aspect.
+const IMAGE_FIT: [f32; 2] = [0.6, 0.5];
+/// Bar width as a share of the screen, its height in pixels and its centre above the bottom.
+const BAR_WIDTH: f32 = 0.4;
+const BAR_HEIGHT: f32 = 6.0;
+const BAR_BOTTOM: f32 = 0.15;
+
+#[derive(Debug, Clone)]
+pub struct SplashConfig {
+    /// Logical path of a PNG shown centred; `None` shows only the bar.
+    pub image: Option<String>,
+    /// Logical paths loaded before the splash lets go.
+    pub preload: Vec<String>,
+    /// Shortest time on screen, so a fast load does not flash the image.
+    pub min_duration: Duration,
+    pub background: Color4,
+    pub bar_color: Color4,
+    pub bar_track_color: Color4,
+}
+
+impl SplashConfig {
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            image: None,
+            preload: Vec::new(),
+            min_duration: Duration::ZERO,
+            background: [0.0, 0.0, 0.0, 1.0],
+            bar_color: [0.9, 0.9, 0.9, 1.0],
+            bar_track_color: [0.2, 0.2, 0.2, 1.0],
+        }
+    }
+
+    /// Empty or blank paths show no image.
+    #[inline]
+    pub fn with_image(mut self, image: Option<String>) -> Self {
+        self.image = image.filter(|p| !p.trim().is_empty());
+        self
+    }
+
+    #[inline]
+    pub fn with_preload(mut self, paths: Vec<String>) -> Self {
+        self.preload = paths;
+        self
+    }
+
+    #[inline]
+    pub fn with_min_duration(mut self, min_duration: Duration) -> Self {
+        self.min_duration = min_duration;
+        self
+    }
+
+    #[inline]
+    pub fn with_background(mut self, color: Color4) -> Self {
+        self.background = color;
+        self
+    }
+
+    #[inline]
+    pub fn with_bar_colors(mut self, bar: Color4, track: Color4) -> Self {
+        self.bar_color = bar;
+        self.bar_track_color = track;
+        self
+    }
+}
+
+impl Default for SplashConfig {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+enum SplashImage {
+    None,
+    Loading(AssetId),
+    Ready { texture: TextureId, size: [u32; 2] },
+}
+
+/// Boot screen: a backdrop, an optional image and a progress bar, drawn through the sprite
+/// layer while the preload group (and any group handed to `splash.api`) loads.
+///
+/// The first `update` starts the preload and swaps in a pixel camera; the splash ends once
+/// every tracked load is done, the image settled and `min_duration` passed, and the sprite
+/// camera goes back to what it was.
+pub struct SplashModule {
+    config: SplashConfig,
+    api: SplashApiRef,
+    image: SplashImage,
+    started: Option<Instant>,
+    /// Sprite camera of the game, restored when the splash ends.
+    camera: Option<Camera2D>,
+}
+
+impl SplashModule {
+    #[inline]
+    pub fn new(config: SplashConfig) -> Self {
+        Self {
+            config,
+            api: SplashApiRef::new(),
+            image: SplashImage::None,
+            started: None,
+            camera: None,
+        }
+    }
+
+    /// Handle for consumers living outside the engine.
+    #[inline]
+    pub fn api(&self) -> SplashApiRef {
+        self.api.clone()
+    }
+
+    fn start(&mut self, am: &AssetManager, sprites: Option<&Sprite2dApiRef>) {
+        self.started = Some(Instant::now());
+
+        let keys = self
+            .config
+            .preload
+            .iter()
+            .map(|p| AssetKey::new(p.as_str(), 0));
+        self.api
+            .track(am.load_group("splash", keys, LoadPriority::Ui));
+
+        if let Some(path) = &self.config.image {
+            match am.load_with_priority(AssetKey::new(path.as_str(), 0), LoadPriority::Ui) {
+                Ok(handle) => self.image = SplashImage::Loading(handle.id()),
+                Err(e) => {
+                    log::warn!(target: "splash", "image.load rejected path='{path}' err='{e}'")
+                }
+            }
+        }
+
+        if let Some(sprites) = sprites {
+            self.camera = Some(sprites.camera());
+            sprites.set_camera(Camera2D::new());
+        }
+        log::info!(
+            target: "splash",
+            "start preload={} image={:?}",
+            self.config.preload.len(),
+            self.config.image
+        );
+    }
+
+    /// Uploads the image once it loaded; a failed image leaves the bar alone on screen.
+    fn poll_image(
+        &mut self,
+        am: &AssetManager,
+        render: &mut dyn RenderApi,
+        sprites: &Sprite2dApiRef,
+    ) {
+        let SplashImage::Loading(id) = self.image else {
+            return;
+        };
+        let path = self.config.image.as_deref().unwrap_or_default();
+
+        let uploaded = match am.state(id) {
+            AssetState::Unloaded | AssetState::Loading => return,
+            AssetState::Failed(e) => Err(e.to_string()),
+            AssetState::Ready => am
+                .get_blob(id)
+                .ok_or_else(|| "blob missing".to_string())
+                .and_then(|blob| decode_png_rgba8(&blob.payload))
+                .and_then(|(w, h, rgba)| {
+                    sprites
+                        .create_texture(render, w, h, &rgba)
+                        .map(|texture| (texture, [w, h]))
+                        .map_err(|e| e.to_string())
+                }),
+        };
+        self.image = match uploaded {
+            Ok((texture, size)) => SplashImage::Ready { texture, size },
+            Err(e) => {
+                log::warn!(target: "splash", "image failed path='{path}' err='{e}'");
+                SplashImage::None
+            }
+        };
+    }
+
+    fn draw(&self, sprites: &Sprite2dApiRef) {
+        // Known after the first sprite render; the frame before shows the clear color.
+        let Some([min_x, min_y, max_x, max_y]) = sprites.view_bounds() else {
+            return;
+        };
+        let (w, h) = (max_x - min_x, max_y - min_y);
+        let center = [(min_x + max_x) * 0.5, (min_y + max_y) * 0.5];
+
+        let mut quads = vec![Sprite::new([w, h])
+            .with_position(center)
+            .with_color(self.config.background)
+            .with_layer(SPLASH_LAYER)];
+
+        if let SplashImage::Ready { texture, size } = self.image {
+            let scale = (w * IMAGE_FIT[0] / size[0] as f32).min(h * IMAGE_FIT[1] / size[1] as f32);
+            quads.push(
+                Sprite::new([size[0] as f32 * scale, size[1] as f32 * scale])
+                    .with_texture(texture)
+                    .with_position(center)
+                    .with_layer(SPLASH_LAYER + 1),
+            );
+        }
+
+        let bar_w = w * BAR_WIDTH;
+        let bar_y = min_y + h * BAR_BOTTOM;
+        let left = center[0] - bar_w * 0.5;
+        quads.push(
+            Sprite::new([bar_w, BAR_HEIGHT])
+                .with_position([center[0], bar_y])
+                .with_color(self.config.bar_track_color)
+                .with_layer(SPLASH_LAYER + 1),
+        );
+        quads.push(
+            Sprite::new([bar_w * self.api.progress(), BAR_HEIGHT])
+                .with_pivot([0.0, 0.5])
+                .with_position([left, bar_y])
+                .with_color(self.config.bar_color)
+                .with_layer(SPLASH_LAYER + 2),
+        );
+        sprites.draw_all(quads);
+    }
+
+    /// Drops the image and gives the sprite camera back.
+    fn release(&mut self, render: Option<&RenderApiRef>, sprites: Option<&Sprite2dApiRef>) {
+        if let SplashImage::Ready { texture, .. } = self.image {
+            if let (Some(render), Some(sprites)) = (render, sprites) {
+                sprites.destroy_texture(&mut **render.lock(), texture);
+            }
+        }
+        self.image = SplashImage::None;
+        if let (Some(camera), Some(sprites)) = (self.camera.take(), sprites) {
+            sprites.set_camera(camera);
+        }
+    }
+}
+
+impl<E: Send + 'static> Module<E> for SplashModule {
+    fn id(&self) -> &'static str {
+        "splash"
+    }
+
+    fn provides(&self) -> &'static [ApiProvide] {
+        &[SPLASH_API_PROVIDE]
+    }
+
+    fn init(&mut self, ctx: &mut ModuleCtx<'_, E>) -> EngineResult<()> {
+        ctx.resources_mut()
+            .register_api(SPLASH_API_ID, self.api.clone())
+    }
+
+    fn update(&mut self, ctx: &mut ModuleCtx<'_, E>) -> EngineResult<()> {
+        if !self.api.is_active() {
+            return Ok(());
+        }
+        let sprites = ctx.api::<Sprite2dApiRef>(SPRITE2D_API_ID).cloned();
+        let render = ctx.api::<RenderApiRef>(RENDER_API_ID).cloned();
+        let Some(am) = ctx.resources().get::<AssetManager>() else {
+            return Ok(());
+        };
+
+        let Some(started) = self.started else {
+            self.start(am, sprites.as_ref());
+            return Ok(());
+        };
+
+        if let (Some(render), Some(sprites)) = (&render, &sprites) {
+            self.poll_image(am, &mut **render.lock(), sprites);
+        }
+
+        let image_pending = matches!(self.image, SplashImage::Loading(_));
+        if self.api.groups_done() && !image_pending && started.elapsed() >= self.config.min_duration
+        {
+            let (ready, failed) = self.api.finish();
+            self.release(render.as_ref(), sprites.as_ref());
+            log::info!(
+                target: "splash",
+                "done ms={} ready={ready} failed={failed}",
+                started.elapsed().as_millis()
+            );
+            return Ok(());
+        }
+
+        if let Some(sprites) = &sprites {
+            self.draw(sprites);
+        }
+        Ok(())
+    }
+
+    fn shutdown(&mut self, ctx: &mut ModuleCtx<'_, E>) -> EngineResult<()> {
+        let sprites = ctx.api::<Sprite2dApiRef>(SPRITE2D_API_ID).cloned();
+        let render = ctx.api::<RenderApiRef>(RENDER_API_ID).cloned();
+        self.release(render.as_ref(), sprites.as_ref());
+        self.api.finish();
+
+        let _ = ctx
+            .resources_mut()
+            .unregister_api::<SplashApiRef>(SPLASH_API_ID);
+        Ok(())
+    }
+}
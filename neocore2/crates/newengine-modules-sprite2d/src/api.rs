@@ -0,0 +1,337 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use newengine_core::render::{
+    AddressMode, BindGroupDesc, BindGroupId, BindGroupLayoutDesc, BindGroupLayoutId, BindingKind,
+    BlendState, CullMode, DrawIndexedArgs, Extent2D, FilterMode, IndexFormat, PipelineDesc,
+    PipelineId, RectI32, RenderApi, SamplerDesc, SamplerId, ShaderDesc, ShaderId, ShaderStage,
+    TextureDesc, TextureFormat, TextureId, TextureUsage, VertexAttribute, VertexFormat,
+    VertexLayout, Viewport,
+};
+use newengine_core::{EngineError, EngineResult};
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::batch::{SpriteBatch, SpriteVertex};
+use crate::camera::Camera2D;
+use crate::sprite::Sprite;
+
+static SPRITE_VS: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/sprite.vert.spv"));
+static SPRITE_FS: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/sprite.frag.spv"));
+
+/// Counters of the last [`Sprite2dApiRef::render`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Sprite2dStats {
+    pub sprites: usize,
+    /// Sprites entirely outside the camera view, skipped.
+    pub culled: usize,
+    /// One per texture change in draw order.
+    pub draw_calls: usize,
+    /// Textures with a cached bind group.
+    pub textures: usize,
+}
+
+/// Backend objects, created on the first render.
+struct GpuState {
+    vs: ShaderId,
+    fs: ShaderId,
+    layout: BindGroupLayoutId,
+    pipeline: PipelineId,
+    sampler: SamplerId,
+    /// 1x1 white texel sampled by untextured sprites.
+    white: TextureId,
+    bind_groups: HashMap<TextureId, BindGroupId>,
+}
+
+impl GpuState {
+    fn create(r: &mut dyn RenderApi, filter: FilterMode) -> EngineResult<Self> {
+        let vs = r.create_shader(
+            ShaderDesc::new(ShaderStage::Vertex, "main", spirv_words(SPRITE_VS)?)
+                .with_label("sprite2d_vs"),
+        )?;
+        let fs = r.create_shader(
+            ShaderDesc::new(ShaderStage::Fragment, "main", spirv_words(SPRITE_FS)?)
+                .with_label("sprite2d_fs"),
+        )?;
+        let layout = r.create_bind_group_layout(
+            BindGroupLayoutDesc::new(vec![BindingKind::Texture2D, BindingKind::Sampler])
+                .with_label("sprite2d_bgl"),
+        )?;
+
+        let vertex_layout = VertexLayout::new(
+            SpriteVertex::SIZE,
+            vec![
+                VertexAttribute::new(0, 0, VertexFormat::Float32x2),
+                VertexAttribute::new(1, 8, VertexFormat::Float32x2),
+                VertexAttribute::new(2, 16, VertexFormat::Unorm8x4),
+            ],
+        );
+        let pipeline = r.create_pipeline(
+            PipelineDesc::new(vs, fs, TextureFormat::Bgra8Unorm)
+                .with_label("sprite2d_pipeline")
+                .with_vertex_layouts(vec![vertex_layout])
+                .with_bind_group_layouts(vec![layout])
+                .with_blend(BlendState::Alpha)
+                .with_cull_mode(CullMode::None),
+        )?;
+
+        let sampler = r.create_sampler(SamplerDesc {
+            min_filter: filter,
+            mag_filter: filter,
+            mip_filter: filter,
+            address_u: AddressMode::ClampToEdge,
+            address_v: AddressMode::ClampToEdge,
+            address_w: AddressMode::ClampToEdge,
+            label: Some("sprite2d_sampler"),
+        })?;
+
+        let white = r.create_texture(
+            TextureDesc::new(
+                Extent2D::new(1, 1),
+                TextureFormat::Rgba8Unorm,
+                TextureUsage::Sampled,
+            )
+            .with_label("sprite2d_white"),
+        )?;
+        r.write_texture(white, &[255; 4])?;
+
+        Ok(Self {
+            vs,
+            fs,
+            layout,
+            pipeline,
+            sampler,
+            white,
+            bind_groups: HashMap::new(),
+        })
+    }
+
+    fn bind_group(&mut self, r: &mut dyn RenderApi, tex: TextureId) -> EngineResult<BindGroupId> {
+        if let Some(&bg) = self.bind_groups.get(&tex) {
+            return Ok(bg);
+        }
+        let bg = r.create_bind_group(
+            BindGroupDesc::new(self.layout)
+                .with_label("sprite2d_bg")
+                .with_texture0(tex)
+                .with_sampler0(self.sampler),
+        )?;
+        self.bind_groups.insert(tex, bg);
+        Ok(bg)
+    }
+
+    fn destroy(self, r: &mut dyn RenderApi) {
+        for (_, bg) in self.bind_groups {
+            r.destroy_bind_group(bg);
+        }
+        r.destroy_texture(self.white);
+        r.destroy_sampler(self.sampler);
+        r.destroy_pipeline(self.pipeline);
+        r.destroy_bind_group_layout(self.layout);
+        r.destroy_shader(self.fs);
+        r.destroy_shader(self.vs);
+    }
+}
+
+struct SpriteRenderer {
+    queue: Vec<Sprite>,
+    camera: Camera2D,
+    /// Target size of the last render.
+    extent: Extent2D,
+    filter: FilterMode,
+    batch: SpriteBatch,
+    gpu: Option<GpuState>,
+    stats: Sprite2dStats,
+}
+
+/// Shared handle to the sprite layer, registered as `sprite2d.api`.
+///
+/// Gameplay code submits sprites every frame with [`Sprite2dApiRef::draw`]; whoever owns the
+/// frame (the host render controller) calls [`Sprite2dApiRef::render`] between
+/// `begin_frame` and `end_frame`, which draws and clears the queue.
+#[derive(Clone)]
+pub struct Sprite2dApiRef(Arc<Mutex<SpriteRenderer>>);
+
+impl Sprite2dApiRef {
+    #[inline]
+    pub fn new(filter: FilterMode) -> Self {
+        Self(Arc::new(Mutex::new(SpriteRenderer {
+            queue: Vec::new(),
+            camera: Camera2D::new(),
+            extent: Extent2D::new(0, 0),
+            filter,
+            batch: SpriteBatch::default(),
+            gpu: None,
+            stats: Sprite2dStats::default(),
+        })))
+    }
+
+    /// Queues `sprite` for the next [`Sprite2dApiRef::render`].
+    #[inline]
+    pub fn draw(&self, sprite: Sprite) {
+        self.0.lock().queue.push(sprite);
+    }
+
+    pub fn draw_all(&self, sprites: impl IntoIterator<Item = Sprite>) {
+        self.0.lock().queue.extend(sprites);
+    }
+
+    #[inline]
+    pub fn camera(&self) -> Camera2D {
+        self.0.lock().camera
+    }
+
+    #[inline]
+    pub fn set_camera(&self, camera: Camera2D) {
+        self.0.lock().camera = camera;
+    }
+
+    /// World box `[min_x, min_y, max_x, max_y]` the camera shows on the last rendered target;
+    /// `None` before the first render. For culling whole groups of sprites before submitting.
+    pub fn view_bounds(&self) -> Option<[f32; 4]> {
+        let s = self.0.lock();
+        (s.extent.width > 0 && s.extent.height > 0).then(|| s.camera.view_bounds(s.extent))
+    }
+
+    #[inline]
+    pub fn stats(&self) -> Sprite2dStats {
+        self.0.lock().stats
+    }
+
+    /// Creates a sampled RGBA8 texture holding `rgba8` (rows top to bottom) for sprites.
+    pub fn create_texture(
+        &self,
+        r: &mut dyn RenderApi,
+        width: u32,
+        height: u32,
+        rgba8: &[u8],
+    ) -> EngineResult<TextureId> {
+        let expected = width as usize * height as usize * 4;
+        if width == 0 || height == 0 || rgba8.len() != expected {
+            return Err(EngineError::other(format!(
+                "sprite2d: texture {width}x{height} needs {expected} bytes, got {}",
+                rgba8.len()
+            )));
+        }
+
+        let tex = r.create_texture(
+            TextureDesc::new(
+                Extent2D::new(width, height),
+                TextureFormat::Rgba8Unorm,
+                TextureUsage::Sampled,
+            )
+            .with_label("sprite2d_texture"),
+        )?;
+        if let Err(e) = r.write_texture(tex, rgba8) {
+            r.destroy_texture(tex);
+            return Err(e);
+        }
+        Ok(tex)
+    }
+
+    /// Drops the cached bind group of `texture` and destroys it.
+    pub fn destroy_texture(&self, r: &mut dyn RenderApi, texture: TextureId) {
+        if let Some(gpu) = self.0.lock().gpu.as_mut() {
+            if let Some(bg) = gpu.bind_groups.remove(&texture) {
+                r.destroy_bind_group(bg);
+            }
+        }
+        r.destroy_texture(texture);
+    }
+
+    /// Draws the queued sprites into the current frame with the 2D camera and empties the
+    /// queue. Leaves the viewport and scissor covering `extent`.
+    pub fn render(&self, r: &mut dyn RenderApi, extent: Extent2D) -> EngineResult<Sprite2dStats> {
+        let mut guard = self.0.lock();
+        let s = &mut *guard;
+
+        s.extent = extent;
+        let mut sprites = std::mem::take(&mut s.queue);
+        s.stats = Sprite2dStats {
+            sprites: sprites.len(),
+            ..Default::default()
+        };
+        if sprites.is_empty() || extent.width == 0 || extent.height == 0 {
+            s.queue = recycle(sprites);
+            return Ok(s.stats);
+        }
+
+        if s.gpu.is_none() {
+            s.gpu = Some(GpuState::create(r, s.filter)?);
+        }
+        let Some(gpu) = s.gpu.as_mut() else {
+            return Ok(s.stats);
+        };
+
+        s.batch.build(&mut sprites, &s.camera, extent);
+        s.queue = recycle(sprites);
+        s.stats.culled = s.batch.culled;
+        if s.batch.runs.is_empty() {
+            return Ok(s.stats);
+        }
+
+        let vb = r.alloc_transient(s.batch.vertices.len() as u64)?;
+        r.write_buffer(vb.buffer, vb.offset, &s.batch.vertices)?;
+        let index_bytes = s.batch.index_bytes();
+        let ib = r.alloc_transient(index_bytes.len() as u64)?;
+        r.write_buffer(ib.buffer, ib.offset, &index_bytes)?;
+
+        r.set_viewport(Viewport::full(extent))?;
+        r.set_scissor(RectI32::new(
+            0,
+            0,
+            extent.width as i32,
+            extent.height as i32,
+        ))?;
+        r.set_pipeline(gpu.pipeline)?;
+        r.set_vertex_buffer(0, vb)?;
+        r.set_index_buffer(ib, IndexFormat::U32)?;
+
+        for run in &s.batch.runs {
+            let bg = gpu.bind_group(r, run.texture.unwrap_or(gpu.white))?;
+            r.set_bind_group(0, bg)?;
+            r.draw_indexed(DrawIndexedArgs {
+                first_index: run.first_index,
+                ..DrawIndexedArgs::new(run.index_count)
+            })?;
+        }
+
+        s.stats.draw_calls = s.batch.runs.len();
+        s.stats.textures = gpu.bind_groups.len();
+        Ok(s.stats)
+    }
+
+    /// Destroys the backend objects; the next render recreates them.
+    pub fn release(&self, r: &mut dyn RenderApi) {
+        if let Some(gpu) = self.0.lock().gpu.take() {
+            gpu.destroy(r);
+        }
+    }
+
+    /// Drops queued sprites and forgets backend objects without destroying them (the backend
+    /// is gone).
+    pub fn clear(&self) {
+        let mut s = self.0.lock();
+        s.queue.clear();
+        s.gpu = None;
+    }
+}
+
+/// Keeps the queue's allocation for the next frame.
+#[inline]
+fn recycle(mut sprites: Vec<Sprite>) -> Vec<Sprite> {
+    sprites.clear();
+    sprites
+}
+
+fn spirv_words(bytes: &[u8]) -> EngineResult<Vec<u32>> {
+    if bytes.len() % 4 != 0 {
+        return Err(EngineError::other(
+            "sprite2d: SPIR-V size is not a multiple of 4",
+        ));
+    }
+    Ok(bytes
+        .chunks_exact(4)
+        .map(|w| u32::from_le_bytes([w[0], w[1], w[2], w[3]]))
+        .collect())
+}
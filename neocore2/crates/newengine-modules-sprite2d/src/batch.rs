@@ -0,0 +1,111 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use newengine_core::render::{Color4, Extent2D, TextureId};
+
+use crate::camera::Camera2D;
+use crate::sprite::Sprite;
+
+/// Corner of a sprite quad: clip position, uv and RGBA8 color (20 bytes).
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct SpriteVertex {
+    pub(crate) pos: [f32; 2],
+    pub(crate) uv: [f32; 2],
+    pub(crate) color: [u8; 4],
+}
+
+impl SpriteVertex {
+    pub(crate) const SIZE: u32 = std::mem::size_of::<Self>() as u32;
+
+    #[inline]
+    fn write(&self, out: &mut Vec<u8>) {
+        for f in self.pos.iter().chain(&self.uv) {
+            out.extend_from_slice(&f.to_ne_bytes());
+        }
+        out.extend_from_slice(&self.color);
+    }
+}
+
+/// Consecutive indices drawn with one texture.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct DrawRun {
+    pub(crate) texture: Option<TextureId>,
+    pub(crate) first_index: u32,
+    pub(crate) index_count: u32,
+}
+
+/// Geometry of one frame's sprites, ordered by layer, then by texture.
+#[derive(Debug, Default)]
+pub(crate) struct SpriteBatch {
+    pub(crate) vertices: Vec<u8>,
+    pub(crate) indices: Vec<u32>,
+    pub(crate) runs: Vec<DrawRun>,
+    pub(crate) culled: usize,
+}
+
+impl SpriteBatch {
+    /// Rebuilds the batch from `sprites`, which are sorted in place. The sort is stable, so
+    /// sprites sharing a layer and texture keep their submission order.
+    pub(crate) fn build(&mut self, sprites: &mut [Sprite], camera: &Camera2D, extent: Extent2D) {
+        self.vertices.clear();
+        self.indices.clear();
+        self.runs.clear();
+        self.culled = 0;
+
+        sprites.sort_by_key(|s| (s.layer, s.texture.map_or(0, |t| t.get())));
+        let m = camera.view_proj(extent);
+
+        let mut vertex_count = 0u32;
+        for s in sprites.iter() {
+            let clip = s.corners().map(|[x, y]| {
+                [
+                    m[0][0] * x + m[0][1] * y + m[0][2],
+                    m[1][0] * x + m[1][1] * y + m[1][2],
+                ]
+            });
+            if outside_view(&clip) {
+                self.culled += 1;
+                continue;
+            }
+
+            let [u0, v0, u1, v1] = s.uv_rect;
+            // Corners run bottom-left, bottom-right, top-right, top-left; v grows downwards.
+            let uvs = [[u0, v1], [u1, v1], [u1, v0], [u0, v0]];
+            let color = pack_color(s.color);
+            for (pos, uv) in clip.into_iter().zip(uvs) {
+                SpriteVertex { pos, uv, color }.write(&mut self.vertices);
+            }
+
+            let first_index = self.indices.len() as u32;
+            let b = vertex_count;
+            self.indices
+                .extend_from_slice(&[b, b + 1, b + 2, b, b + 2, b + 3]);
+            vertex_count += 4;
+
+            match self.runs.last_mut() {
+                Some(run) if run.texture == s.texture => run.index_count += 6,
+                _ => self.runs.push(DrawRun {
+                    texture: s.texture,
+                    first_index,
+                    index_count: 6,
+                }),
+            }
+        }
+    }
+
+    #[inline]
+    pub(crate) fn index_bytes(&self) -> Vec<u8> {
+        self.indices.iter().flat_map(|i| i.to_ne_bytes()).collect()
+    }
+}
+
+/// True when every corner is past the same edge of clip space.
+#[inline]
+fn outside_view(clip: &[[f32; 2]; 4]) -> bool {
+    (0..2).any(|axis| clip.iter().all(|c| c[axis] < -1.0) || clip.iter().all(|c| c[axis] > 1.0))
+}
+
+#[inline]
+fn pack_color(c: Color4) -> [u8; 4] {
+    c.map(|v| (v.clamp(0.0, 1.0) * 255.0).round() as u8)
+}
@@ -0,0 +1,104 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use newengine_core::render::Extent2D;
+
+/// Orthographic 2D view: world y up, one world unit covers `zoom` pixels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Camera2D {
+    /// World point shown at the center of the target.
+    pub position: [f32; 2],
+    /// Radians, counter-clockwise.
+    pub rotation: f32,
+    /// Target pixels per world unit.
+    pub zoom: f32,
+}
+
+impl Camera2D {
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            position: [0.0, 0.0],
+            rotation: 0.0,
+            zoom: 1.0,
+        }
+    }
+
+    #[inline]
+    pub fn with_position(mut self, position: [f32; 2]) -> Self {
+        self.position = position;
+        self
+    }
+
+    #[inline]
+    pub fn with_rotation(mut self, rotation: f32) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    #[inline]
+    pub fn with_zoom(mut self, zoom: f32) -> Self {
+        self.zoom = zoom;
+        self
+    }
+
+    /// World to Vulkan clip space (y down) for a target of `extent`, as rows of a 2x3 affine
+    /// matrix: `clip = m * [x, y, 1]`.
+    pub fn view_proj(&self, extent: Extent2D) -> [[f32; 3]; 2] {
+        let (sin, cos) = self.rotation.sin_cos();
+        let sx = 2.0 * self.zoom / extent.width.max(1) as f32;
+        let sy = -2.0 * self.zoom / extent.height.max(1) as f32;
+        let [px, py] = self.position;
+
+        // Undo the camera rotation, then scale to clip space.
+        let (a, b) = (cos * sx, sin * sx);
+        let (c, d) = (-sin * sy, cos * sy);
+        [[a, b, -(a * px + b * py)], [c, d, -(c * px + d * py)]]
+    }
+
+    /// World point under a target pixel (y down from the top-left), e.g. the mouse cursor.
+    pub fn screen_to_world(&self, screen: [f32; 2], extent: Extent2D) -> [f32; 2] {
+        let zoom = if self.zoom.abs() > f32::EPSILON {
+            self.zoom
+        } else {
+            1.0
+        };
+        let x = (screen[0] - extent.width as f32 * 0.5) / zoom;
+        let y = (extent.height as f32 * 0.5 - screen[1]) / zoom;
+        let (sin, cos) = self.rotation.sin_cos();
+        [
+            self.position[0] + x * cos - y * sin,
+            self.position[1] + x * sin + y * cos,
+        ]
+    }
+
+    /// World-space box `[min_x, min_y, max_x, max_y]` around everything visible on a target of
+    /// `extent`.
+    pub fn view_bounds(&self, extent: Extent2D) -> [f32; 4] {
+        let (w, h) = (extent.width as f32, extent.height as f32);
+        let corners =
+            [[0.0, 0.0], [w, 0.0], [w, h], [0.0, h]].map(|p| self.screen_to_world(p, extent));
+        let mut b = [f32::MAX, f32::MAX, f32::MIN, f32::MIN];
+        for [x, y] in corners {
+            b = [b[0].min(x), b[1].min(y), b[2].max(x), b[3].max(y)];
+        }
+        b
+    }
+
+    /// Target pixel (y down from the top-left) a world point lands on.
+    pub fn world_to_screen(&self, world: [f32; 2], extent: Extent2D) -> [f32; 2] {
+        let m = self.view_proj(extent);
+        let cx = m[0][0] * world[0] + m[0][1] * world[1] + m[0][2];
+        let cy = m[1][0] * world[0] + m[1][1] * world[1] + m[1][2];
+        [
+            (cx + 1.0) * 0.5 * extent.width as f32,
+            (cy + 1.0) * 0.5 * extent.height as f32,
+        ]
+    }
+}
+
+impl Default for Camera2D {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,18 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+mod api;
+mod batch;
+mod camera;
+mod module;
+mod sprite;
+
+pub use api::{Sprite2dApiRef, Sprite2dStats};
+pub use camera::Camera2D;
+pub use module::{Sprite2dConfig, Sprite2dModule};
+pub use sprite::{Sprite, Transform2D};
+
+use newengine_core::{ApiProvide, ApiVersion};
+
+pub const SPRITE2D_API_ID: &str = "sprite2d.api";
+pub const SPRITE2D_API_VERSION: ApiVersion = ApiVersion::new(0, 1, 0);
+pub const SPRITE2D_API_PROVIDE: ApiProvide = ApiProvide::new(SPRITE2D_API_ID, SPRITE2D_API_VERSION);
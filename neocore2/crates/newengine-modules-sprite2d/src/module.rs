@@ -0,0 +1,84 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use newengine_core::render::{FilterMode, RenderApiRef, RENDER_API_ID};
+use newengine_core::{ApiProvide, EngineResult, Module, ModuleCtx};
+
+use crate::api::Sprite2dApiRef;
+use crate::{SPRITE2D_API_ID, SPRITE2D_API_PROVIDE};
+
+#[derive(Debug, Clone)]
+pub struct Sprite2dConfig {
+    /// Texture filtering; `Nearest` keeps pixel art crisp.
+    pub filter: FilterMode,
+}
+
+impl Sprite2dConfig {
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            filter: FilterMode::Linear,
+        }
+    }
+
+    #[inline]
+    pub fn with_filter(mut self, filter: FilterMode) -> Self {
+        self.filter = filter;
+        self
+    }
+}
+
+impl Default for Sprite2dConfig {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Exposes the batched sprite layer as `sprite2d.api`.
+///
+/// The module does not own the frame: the host calls [`Sprite2dApiRef::render`] from its render
+/// controller, after the scene and before `end_frame`, so sprites land on top of 3D content.
+pub struct Sprite2dModule {
+    api: Sprite2dApiRef,
+}
+
+impl Sprite2dModule {
+    #[inline]
+    pub fn new(config: Sprite2dConfig) -> Self {
+        Self {
+            api: Sprite2dApiRef::new(config.filter),
+        }
+    }
+
+    /// Handle for consumers living outside the engine.
+    #[inline]
+    pub fn api(&self) -> Sprite2dApiRef {
+        self.api.clone()
+    }
+}
+
+impl<E: Send + 'static> Module<E> for Sprite2dModule {
+    fn id(&self) -> &'static str {
+        "sprite2d"
+    }
+
+    fn provides(&self) -> &'static [ApiProvide] {
+        &[SPRITE2D_API_PROVIDE]
+    }
+
+    fn init(&mut self, ctx: &mut ModuleCtx<'_, E>) -> EngineResult<()> {
+        ctx.resources_mut()
+            .register_api(SPRITE2D_API_ID, self.api.clone())
+    }
+
+    fn shutdown(&mut self, ctx: &mut ModuleCtx<'_, E>) -> EngineResult<()> {
+        match ctx.api::<RenderApiRef>(RENDER_API_ID) {
+            Some(render) => self.api.release(&mut **render.lock()),
+            None => self.api.clear(),
+        }
+        let _ = ctx
+            .resources_mut()
+            .unregister_api::<Sprite2dApiRef>(SPRITE2D_API_ID);
+        Ok(())
+    }
+}
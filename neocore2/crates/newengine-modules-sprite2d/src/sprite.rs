@@ -0,0 +1,161 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use newengine_core::render::{Color4, TextureId};
+
+/// Placement of a sprite in the 2D world (y up).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform2D {
+    pub position: [f32; 2],
+    /// Radians, counter-clockwise.
+    pub rotation: f32,
+    pub scale: [f32; 2],
+}
+
+impl Transform2D {
+    pub const IDENTITY: Self = Self {
+        position: [0.0, 0.0],
+        rotation: 0.0,
+        scale: [1.0, 1.0],
+    };
+
+    #[inline]
+    pub const fn from_position(position: [f32; 2]) -> Self {
+        Self {
+            position,
+            ..Self::IDENTITY
+        }
+    }
+
+    /// Maps a point of the sprite's local space into the world.
+    #[inline]
+    pub fn apply(&self, local: [f32; 2]) -> [f32; 2] {
+        let (sin, cos) = self.rotation.sin_cos();
+        let x = local[0] * self.scale[0];
+        let y = local[1] * self.scale[1];
+        [
+            self.position[0] + x * cos - y * sin,
+            self.position[1] + x * sin + y * cos,
+        ]
+    }
+}
+
+impl Default for Transform2D {
+    #[inline]
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+/// One textured quad, drawn for the frame it is submitted in.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sprite {
+    pub transform: Transform2D,
+    /// Quad size in world units, before `transform.scale`.
+    pub size: [f32; 2],
+    /// Point of the quad placed at `transform.position` and rotated around: (0, 0) is the
+    /// bottom-left corner, (1, 1) the top-right.
+    pub pivot: [f32; 2],
+    /// Multiplies the texture (straight alpha).
+    pub color: Color4,
+    /// Texture region as `[u0, v0, u1, v1]`, v down: (0, 0) is the top-left of the image.
+    /// Swap the ends to flip the sprite.
+    pub uv_rect: [f32; 4],
+    /// `None`: a solid `color` quad.
+    pub texture: Option<TextureId>,
+    /// Lower layers are drawn first. Within a layer sprites are grouped by texture, so only
+    /// layers order overlapping sprites of different textures.
+    pub layer: i32,
+}
+
+impl Sprite {
+    pub const FULL_UV: [f32; 4] = [0.0, 0.0, 1.0, 1.0];
+
+    #[inline]
+    pub fn new(size: [f32; 2]) -> Self {
+        Self {
+            transform: Transform2D::IDENTITY,
+            size,
+            pivot: [0.5, 0.5],
+            color: [1.0, 1.0, 1.0, 1.0],
+            uv_rect: Self::FULL_UV,
+            texture: None,
+            layer: 0,
+        }
+    }
+
+    #[inline]
+    pub fn with_texture(mut self, texture: TextureId) -> Self {
+        self.texture = Some(texture);
+        self
+    }
+
+    #[inline]
+    pub fn with_uv_rect(mut self, uv_rect: [f32; 4]) -> Self {
+        self.uv_rect = uv_rect;
+        self
+    }
+
+    /// Picks cell `index` (row-major) of a sheet of `columns` x `rows` equal frames.
+    #[inline]
+    pub fn with_sheet_cell(self, columns: u32, rows: u32, index: u32) -> Self {
+        let (columns, rows) = (columns.max(1), rows.max(1));
+        let (cx, cy) = ((index % columns) as f32, ((index / columns) % rows) as f32);
+        let (w, h) = (1.0 / columns as f32, 1.0 / rows as f32);
+        self.with_uv_rect([cx * w, cy * h, (cx + 1.0) * w, (cy + 1.0) * h])
+    }
+
+    #[inline]
+    pub fn with_color(mut self, color: Color4) -> Self {
+        self.color = color;
+        self
+    }
+
+    #[inline]
+    pub fn with_layer(mut self, layer: i32) -> Self {
+        self.layer = layer;
+        self
+    }
+
+    #[inline]
+    pub fn with_pivot(mut self, pivot: [f32; 2]) -> Self {
+        self.pivot = pivot;
+        self
+    }
+
+    #[inline]
+    pub fn with_transform(mut self, transform: Transform2D) -> Self {
+        self.transform = transform;
+        self
+    }
+
+    #[inline]
+    pub fn with_position(mut self, position: [f32; 2]) -> Self {
+        self.transform.position = position;
+        self
+    }
+
+    #[inline]
+    pub fn with_rotation(mut self, rotation: f32) -> Self {
+        self.transform.rotation = rotation;
+        self
+    }
+
+    #[inline]
+    pub fn with_scale(mut self, scale: [f32; 2]) -> Self {
+        self.transform.scale = scale;
+        self
+    }
+
+    /// World positions of the corners: bottom-left, bottom-right, top-right, top-left.
+    pub fn corners(&self) -> [[f32; 2]; 4] {
+        let x0 = -self.pivot[0] * self.size[0];
+        let y0 = -self.pivot[1] * self.size[1];
+        let (x1, y1) = (x0 + self.size[0], y0 + self.size[1]);
+        [
+            self.transform.apply([x0, y0]),
+            self.transform.apply([x1, y0]),
+            self.transform.apply([x1, y1]),
+            self.transform.apply([x0, y1]),
+        ]
+    }
+}
@@ -0,0 +1,627 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use glam::{Mat4, Vec3};
+use newengine_assets::HeightmapAsset;
+use newengine_core::render::{
+    AddressMode, BindGroupDesc, BindGroupId, BindGroupLayoutDesc, BindGroupLayoutId, BindingKind,
+    BufferBinding, BufferDesc, BufferId, BufferSlice, BufferUsage, CullMode, DefaultMaterial,
+    DrawIndexedArgs, Extent2D, FilterMode, IndexFormat, MemoryHint, PipelineDesc, PipelineId,
+    RectI32, RenderApi, SamplerDesc, SamplerId, TextureDesc, TextureFormat, TextureId,
+    TextureUsage, VertexDeformation, VertexLayout, Viewport, LIGHTS_BIND_GROUP,
+    TERRAIN_LAYERS_BIND_GROUP, TERRAIN_OBJECT_UBO_SIZE, TERRAIN_SPLAT_BIND_GROUP,
+};
+use newengine_core::EngineResult;
+use parking_lot::Mutex;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use crate::component::{Terrain, MAX_TERRAIN_LAYERS};
+use crate::image::RgbaImage;
+use crate::mesh::{ChunkDraw, TerrainMesh};
+use crate::TerrainId;
+
+/// Side of one layer cell in the 2x2 layer atlas, in texels.
+const ATLAS_CELL: u32 = 512;
+
+/// Counters of the last [`TerrainApiRef::render`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TerrainStats {
+    pub terrains: usize,
+    /// Terrains whose heightmap or textures have not loaded (or failed to).
+    pub loading: usize,
+    /// Chunks drawn.
+    pub chunks: usize,
+    /// Chunks skipped as outside the camera frustum.
+    pub culled: usize,
+    pub triangles: usize,
+}
+
+/// What an asset path is loaded as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum AssetKind {
+    Heightmap,
+    Image,
+}
+
+/// Backend objects of one terrain.
+struct TerrainGpu {
+    vb: BufferId,
+    ib: BufferId,
+    ubo: BufferId,
+    object_bg: BindGroupId,
+    splat: TextureId,
+    splat_bg: BindGroupId,
+    layers: TextureId,
+    layers_bg: BindGroupId,
+}
+
+impl TerrainGpu {
+    fn destroy(self, r: &mut dyn RenderApi) {
+        r.destroy_bind_group(self.layers_bg);
+        r.destroy_bind_group(self.splat_bg);
+        r.destroy_bind_group(self.object_bg);
+        r.destroy_texture(self.layers);
+        r.destroy_texture(self.splat);
+        r.destroy_buffer(self.ubo);
+        r.destroy_buffer(self.ib);
+        r.destroy_buffer(self.vb);
+    }
+}
+
+/// Backend objects shared by every terrain, created on the first render.
+struct SharedGpu {
+    object_layout: BindGroupLayoutId,
+    texture_layout: BindGroupLayoutId,
+    lights_layout: BindGroupLayoutId,
+    pipeline: PipelineId,
+    sampler: SamplerId,
+}
+
+impl SharedGpu {
+    fn create(r: &mut dyn RenderApi) -> EngineResult<Self> {
+        // Owned and cached by the backend.
+        let (vs, fs) =
+            r.default_material_shaders(DefaultMaterial::TerrainSplat, VertexDeformation::NONE)?;
+        let object_layout = r.create_bind_group_layout(
+            BindGroupLayoutDesc::new(vec![BindingKind::UniformBuffer])
+                .with_label("terrain_object_bgl"),
+        )?;
+        let texture_layout = r.create_bind_group_layout(
+            BindGroupLayoutDesc::new(vec![BindingKind::Texture2D, BindingKind::Sampler])
+                .with_label("terrain_texture_bgl"),
+        )?;
+        let lights_layout = r.create_bind_group_layout(BindGroupLayoutDesc::lights())?;
+        let pipeline = r.create_pipeline(
+            PipelineDesc::new(vs, fs, TextureFormat::Bgra8Unorm)
+                .with_depth(TextureFormat::Depth32Float)
+                .with_label("terrain_pipeline")
+                .with_vertex_layouts(vec![VertexLayout::default_mesh()])
+                .with_bind_group_layouts(vec![
+                    object_layout,
+                    texture_layout,
+                    texture_layout,
+                    lights_layout,
+                ])
+                // Skirts face either way.
+                .with_cull_mode(CullMode::None),
+        )?;
+        let sampler = r.create_sampler(SamplerDesc {
+            min_filter: FilterMode::Linear,
+            mag_filter: FilterMode::Linear,
+            mip_filter: FilterMode::Linear,
+            address_u: AddressMode::ClampToEdge,
+            address_v: AddressMode::ClampToEdge,
+            address_w: AddressMode::ClampToEdge,
+            label: Some("terrain_sampler"),
+        })?;
+        Ok(Self {
+            object_layout,
+            texture_layout,
+            lights_layout,
+            pipeline,
+            sampler,
+        })
+    }
+
+    fn destroy(self, r: &mut dyn RenderApi) {
+        r.destroy_sampler(self.sampler);
+        r.destroy_pipeline(self.pipeline);
+        r.destroy_bind_group_layout(self.lights_layout);
+        r.destroy_bind_group_layout(self.texture_layout);
+        r.destroy_bind_group_layout(self.object_layout);
+    }
+
+    fn texture_bind_group(
+        &self,
+        r: &mut dyn RenderApi,
+        image: &RgbaImage,
+        label: &'static str,
+    ) -> EngineResult<(TextureId, BindGroupId)> {
+        let tex = r.create_texture(
+            TextureDesc::new(
+                Extent2D::new(image.width, image.height),
+                TextureFormat::Rgba8Unorm,
+                TextureUsage::Sampled,
+            )
+            .with_label(label),
+        )?;
+        let bg = match r.write_texture(tex, &image.rgba) {
+            Ok(()) => r.create_bind_group(
+                BindGroupDesc::new(self.texture_layout)
+                    .with_label(label)
+                    .with_texture0(tex)
+                    .with_sampler0(self.sampler),
+            ),
+            Err(e) => Err(e),
+        };
+        match bg {
+            Ok(bg) => Ok((tex, bg)),
+            Err(e) => {
+                r.destroy_texture(tex);
+                Err(e)
+            }
+        }
+    }
+}
+
+struct TerrainInstance {
+    component: Terrain,
+    /// Built once the heightmap and textures are in; dropped with the component.
+    mesh: Option<TerrainMesh>,
+    gpu: Option<TerrainGpu>,
+}
+
+struct TerrainWorld {
+    terrains: HashMap<TerrainId, TerrainInstance>,
+    heightmaps: HashMap<String, Arc<HeightmapAsset>>,
+    /// Splat maps and layer textures; `None` for images that failed to load, drawn as if
+    /// absent.
+    images: HashMap<String, Option<Arc<RgbaImage>>>,
+    /// Assets referenced by terrains that the module has not been asked to load yet.
+    requests: Vec<(String, AssetKind)>,
+    requested: HashSet<String>,
+    shared: Option<SharedGpu>,
+    /// GPU objects of replaced or removed terrains, destroyed on the next render.
+    stale: Vec<TerrainGpu>,
+    draws: Vec<ChunkDraw>,
+    /// Distance up to which chunks draw at full detail.
+    lod_distance: f32,
+    stats: TerrainStats,
+}
+
+impl TerrainWorld {
+    fn request(&mut self, terrain: &Terrain) {
+        for asset in terrain.assets() {
+            let known = self.heightmaps.contains_key(asset) || self.images.contains_key(asset);
+            if !known && self.requested.insert(asset.to_string()) {
+                let kind = match asset == terrain.heightmap {
+                    true => AssetKind::Heightmap,
+                    false => AssetKind::Image,
+                };
+                self.requests.push((asset.to_string(), kind));
+            }
+        }
+    }
+
+    fn drop_gpu(&mut self, id: TerrainId) {
+        if let Some(gpu) = self.terrains.get_mut(&id).and_then(|t| t.gpu.take()) {
+            self.stale.push(gpu);
+        }
+    }
+
+    /// Whether every asset of `terrain` has loaded or failed, with the heightmap loaded.
+    fn ready(&self, terrain: &Terrain) -> bool {
+        self.heightmaps.contains_key(&terrain.heightmap)
+            && terrain
+                .assets()
+                .skip(1)
+                .all(|a| self.images.contains_key(a))
+    }
+}
+
+/// Shared handle to the terrains, registered as `terrain.api`.
+///
+/// Terrains are components keyed by caller-picked [`TerrainId`]s. The module loads their
+/// heightmap, splat map and layer textures on first use and builds the chunk mesh once they
+/// are in; whoever owns the frame (the host render controller) calls
+/// [`TerrainApiRef::render`] between `begin_frame` and `end_frame` with its camera.
+#[derive(Clone)]
+pub struct TerrainApiRef(Arc<Mutex<TerrainWorld>>);
+
+impl TerrainApiRef {
+    pub(crate) fn new(lod_distance: f32) -> Self {
+        Self(Arc::new(Mutex::new(TerrainWorld {
+            terrains: HashMap::new(),
+            heightmaps: HashMap::new(),
+            images: HashMap::new(),
+            requests: Vec::new(),
+            requested: HashSet::new(),
+            shared: None,
+            stale: Vec::new(),
+            draws: Vec::new(),
+            lod_distance,
+            stats: TerrainStats::default(),
+        })))
+    }
+
+    /// Adds or replaces the terrain of `id`.
+    pub fn insert(&self, id: TerrainId, terrain: Terrain) {
+        let mut w = self.0.lock();
+        w.request(&terrain);
+        w.drop_gpu(id);
+        w.terrains.insert(
+            id,
+            TerrainInstance {
+                component: terrain,
+                mesh: None,
+                gpu: None,
+            },
+        );
+    }
+
+    /// Removes the terrain. Returns false if there was none.
+    pub fn remove(&self, id: TerrainId) -> bool {
+        let mut w = self.0.lock();
+        w.drop_gpu(id);
+        w.terrains.remove(&id).is_some()
+    }
+
+    #[inline]
+    pub fn contains(&self, id: TerrainId) -> bool {
+        self.0.lock().terrains.contains_key(&id)
+    }
+
+    pub fn terrain(&self, id: TerrainId) -> Option<Terrain> {
+        self.0.lock().terrains.get(&id).map(|t| t.component.clone())
+    }
+
+    /// Whether the terrain's mesh is built.
+    pub fn is_loaded(&self, id: TerrainId) -> bool {
+        self.0
+            .lock()
+            .terrains
+            .get(&id)
+            .is_some_and(|t| t.mesh.is_some())
+    }
+
+    /// Moves the terrain; cheap, the mesh is relative to the position.
+    pub fn set_position(&self, id: TerrainId, position: [f32; 3]) -> bool {
+        self.with_terrain(id, |t| t.component.position = position)
+    }
+
+    pub fn set_visible(&self, id: TerrainId, visible: bool) -> bool {
+        self.with_terrain(id, |t| t.component.visible = visible)
+    }
+
+    /// World height of the terrain surface under world `x`, `z`; `None` outside the terrain
+    /// or before its mesh is built.
+    pub fn height_at(&self, id: TerrainId, x: f32, z: f32) -> Option<f32> {
+        let w = self.0.lock();
+        let t = w.terrains.get(&id)?;
+        let [px, py, pz] = t.component.position;
+        t.mesh.as_ref()?.height_at(x - px, z - pz).map(|h| h + py)
+    }
+
+    #[inline]
+    pub fn stats(&self) -> TerrainStats {
+        self.0.lock().stats
+    }
+
+    /// Draws every loaded, visible terrain into the current frame. `view_proj` is the
+    /// column-major camera matrix and `eye` the camera position, which picks each chunk's
+    /// detail level. `lights` is the frame's light list (`BindGroupLayoutDesc::lights`).
+    /// Uploads newly built meshes first. Leaves the viewport and scissor covering `extent`.
+    pub fn render(
+        &self,
+        r: &mut dyn RenderApi,
+        extent: Extent2D,
+        view_proj: [f32; 16],
+        eye: [f32; 3],
+        lights: BindGroupId,
+    ) -> EngineResult<TerrainStats> {
+        let mut guard = self.0.lock();
+        let w = &mut *guard;
+        for gpu in w.stale.drain(..) {
+            gpu.destroy(r);
+        }
+
+        w.stats = TerrainStats {
+            terrains: w.terrains.len(),
+            ..Default::default()
+        };
+        if w.terrains.is_empty() || extent.width == 0 || extent.height == 0 {
+            return Ok(w.stats);
+        }
+        if w.shared.is_none() {
+            w.shared = Some(SharedGpu::create(r)?);
+        }
+        let Some(shared) = w.shared.as_ref() else {
+            return Ok(w.stats);
+        };
+
+        let view_proj_m = Mat4::from_cols_array(&view_proj);
+        let eye = Vec3::from_array(eye);
+        let mut started = false;
+        let mut draws = std::mem::take(&mut w.draws);
+
+        for t in w.terrains.values_mut() {
+            let Some(mesh) = t.mesh.as_mut() else {
+                w.stats.loading += 1;
+                continue;
+            };
+            if !t.component.visible {
+                continue;
+            }
+            if t.gpu.is_none() {
+                t.gpu = Some(upload(r, shared, mesh, &t.component, &w.images)?);
+            }
+            let Some(gpu) = t.gpu.as_ref() else {
+                continue;
+            };
+
+            let origin = Vec3::from_array(t.component.position);
+            draws.clear();
+            w.stats.culled += mesh.select(origin, view_proj_m, eye, w.lod_distance, &mut draws);
+            if draws.is_empty() {
+                continue;
+            }
+
+            let model = Mat4::from_translation(origin);
+            r.write_buffer(gpu.ubo, 0, &object_uniform(view_proj, model, &t.component))?;
+            if !started {
+                r.set_viewport(Viewport::full(extent))?;
+                r.set_scissor(RectI32::new(
+                    0,
+                    0,
+                    extent.width as i32,
+                    extent.height as i32,
+                ))?;
+                r.set_pipeline(shared.pipeline)?;
+                r.set_bind_group(LIGHTS_BIND_GROUP, lights)?;
+                started = true;
+            }
+            r.set_bind_group(0, gpu.object_bg)?;
+            r.set_bind_group(TERRAIN_SPLAT_BIND_GROUP, gpu.splat_bg)?;
+            r.set_bind_group(TERRAIN_LAYERS_BIND_GROUP, gpu.layers_bg)?;
+            r.set_vertex_buffer(0, BufferSlice::new(gpu.vb, 0))?;
+            r.set_index_buffer(BufferSlice::new(gpu.ib, 0), IndexFormat::U32)?;
+            for d in &draws {
+                r.draw_indexed(DrawIndexedArgs {
+                    first_index: d.lod.first_index,
+                    vertex_offset: d.vertex_offset,
+                    ..DrawIndexedArgs::new(d.lod.index_count)
+                })?;
+                w.stats.triangles += d.lod.index_count as usize / 3;
+            }
+            w.stats.chunks += draws.len();
+        }
+
+        w.draws = draws;
+        Ok(w.stats)
+    }
+
+    /// Destroys the backend objects; the next render recreates them.
+    pub fn release(&self, r: &mut dyn RenderApi) {
+        let mut w = self.0.lock();
+        let mut gpus: Vec<TerrainGpu> = w.stale.drain(..).collect();
+        gpus.extend(w.terrains.values_mut().filter_map(|t| t.gpu.take()));
+        for gpu in gpus {
+            gpu.destroy(r);
+        }
+        if let Some(shared) = w.shared.take() {
+            shared.destroy(r);
+        }
+    }
+
+    /// Removes every terrain and loaded asset and forgets backend objects without destroying
+    /// them (the backend is gone).
+    pub fn clear(&self) {
+        let mut w = self.0.lock();
+        w.terrains.clear();
+        w.heightmaps.clear();
+        w.images.clear();
+        w.requests.clear();
+        w.requested.clear();
+        w.shared = None;
+        w.stale.clear();
+        w.stats = TerrainStats::default();
+    }
+
+    /// Assets that nobody has loaded yet.
+    pub(crate) fn take_requests(&self) -> Vec<(String, AssetKind)> {
+        std::mem::take(&mut self.0.lock().requests)
+    }
+
+    pub(crate) fn set_heightmap(&self, asset: String, heightmap: Arc<HeightmapAsset>) {
+        let mut w = self.0.lock();
+        w.requested.remove(&asset);
+        w.heightmaps.insert(asset, heightmap);
+    }
+
+    /// Stores a splat map or layer texture; `None` records a failed load.
+    pub(crate) fn set_image(&self, asset: String, image: Option<Arc<RgbaImage>>) {
+        let mut w = self.0.lock();
+        w.requested.remove(&asset);
+        w.images.insert(asset, image);
+    }
+
+    /// Forgets a failed heightmap load so that inserting a terrain with it again retries it.
+    pub(crate) fn load_failed(&self, asset: &str) {
+        self.0.lock().requested.remove(asset);
+    }
+
+    /// Builds the chunk mesh of every terrain whose assets are in.
+    pub(crate) fn build_meshes(&self) {
+        let mut guard = self.0.lock();
+        let w = &mut *guard;
+        for (id, t) in w.terrains.iter_mut() {
+            if t.mesh.is_some() || !w.ready(&t.component) {
+                continue;
+            }
+            let Some(heightmap) = w.heightmaps.get(&t.component.heightmap) else {
+                continue;
+            };
+            let mesh = TerrainMesh::build(heightmap, &t.component);
+            log::info!(
+                target: "terrain",
+                "terrain.built id={id} heightmap='{}' lods={} vertices={}",
+                t.component.heightmap,
+                mesh.lods.len(),
+                mesh.vertices.len() / crate::mesh::VERTEX_SIZE
+            );
+            t.mesh = Some(mesh);
+        }
+    }
+
+    fn with_terrain(&self, id: TerrainId, f: impl FnOnce(&mut TerrainInstance)) -> bool {
+        match self.0.lock().terrains.get_mut(&id) {
+            Some(t) => {
+                f(t);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Objects created by an [`upload`] that failed halfway.
+#[derive(Default)]
+struct Partial {
+    buffers: Vec<BufferId>,
+    textures: Vec<TextureId>,
+    bind_groups: Vec<BindGroupId>,
+}
+
+impl Partial {
+    fn buffer(
+        &mut self,
+        r: &mut dyn RenderApi,
+        data: &[u8],
+        usage: BufferUsage,
+        label: &'static str,
+    ) -> EngineResult<BufferId> {
+        let buffer = r.create_buffer(
+            BufferDesc::new(data.len() as u64, usage, MemoryHint::CpuToGpu).with_label(label),
+        )?;
+        self.buffers.push(buffer);
+        r.write_buffer(buffer, 0, data)?;
+        Ok(buffer)
+    }
+
+    fn destroy(self, r: &mut dyn RenderApi) {
+        for bg in self.bind_groups {
+            r.destroy_bind_group(bg);
+        }
+        for tex in self.textures {
+            r.destroy_texture(tex);
+        }
+        for buffer in self.buffers {
+            r.destroy_buffer(buffer);
+        }
+    }
+}
+
+/// Uploads `mesh` and the terrain's splat map and layer atlas. The CPU copy of the mesh is
+/// dropped once on the GPU.
+fn upload(
+    r: &mut dyn RenderApi,
+    shared: &SharedGpu,
+    mesh: &mut TerrainMesh,
+    terrain: &Terrain,
+    images: &HashMap<String, Option<Arc<RgbaImage>>>,
+) -> EngineResult<TerrainGpu> {
+    // Without a splat map, layer 0 covers everything.
+    let default_splat = RgbaImage {
+        width: 1,
+        height: 1,
+        rgba: vec![255, 0, 0, 0],
+    };
+    let splat = loaded_image(images, terrain.splat.as_deref()).unwrap_or(&default_splat);
+    let layers = layer_atlas(terrain, images);
+    let indices: Vec<u8> = mesh.indices.iter().flat_map(|i| i.to_ne_bytes()).collect();
+
+    let mut partial = Partial::default();
+    let result = (|| -> EngineResult<TerrainGpu> {
+        let vb = partial.buffer(r, &mesh.vertices, BufferUsage::Vertex, "terrain_vb")?;
+        let ib = partial.buffer(r, &indices, BufferUsage::Index, "terrain_ib")?;
+        let ubo = partial.buffer(
+            r,
+            &object_uniform([0.0; 16], Mat4::IDENTITY, terrain),
+            BufferUsage::Uniform,
+            "terrain_ubo",
+        )?;
+        let object_bg = r.create_bind_group(
+            BindGroupDesc::new(shared.object_layout)
+                .with_label("terrain_object_bg")
+                .with_uniform0(BufferBinding::new(ubo, 0, TERRAIN_OBJECT_UBO_SIZE)),
+        )?;
+        partial.bind_groups.push(object_bg);
+
+        let (splat, splat_bg) = shared.texture_bind_group(r, splat, "terrain_splat")?;
+        partial.textures.push(splat);
+        partial.bind_groups.push(splat_bg);
+        let (layers, layers_bg) = shared.texture_bind_group(r, &layers, "terrain_layers")?;
+        Ok(TerrainGpu {
+            vb,
+            ib,
+            ubo,
+            object_bg,
+            splat,
+            splat_bg,
+            layers,
+            layers_bg,
+        })
+    })();
+
+    match result {
+        Ok(gpu) => {
+            mesh.vertices = Vec::new();
+            mesh.indices = Vec::new();
+            Ok(gpu)
+        }
+        Err(e) => {
+            partial.destroy(r);
+            Err(e)
+        }
+    }
+}
+
+/// 2x2 atlas of the layer textures; layers without one (or whose texture failed) are white,
+/// leaving their tint.
+fn layer_atlas(terrain: &Terrain, images: &HashMap<String, Option<Arc<RgbaImage>>>) -> RgbaImage {
+    let side = ATLAS_CELL * 2;
+    let mut rgba = vec![255u8; (side * side * 4) as usize];
+    for (i, layer) in terrain.layers().iter().enumerate() {
+        if let Some(tex) = loaded_image(images, layer.texture.as_deref()) {
+            let origin = [(i as u32 % 2) * ATLAS_CELL, (i as u32 / 2) * ATLAS_CELL];
+            tex.blit_resized(&mut rgba, side, origin, ATLAS_CELL);
+        }
+    }
+    RgbaImage {
+        width: side,
+        height: side,
+        rgba,
+    }
+}
+
+#[inline]
+fn loaded_image<'a>(
+    images: &'a HashMap<String, Option<Arc<RgbaImage>>>,
+    path: Option<&str>,
+) -> Option<&'a RgbaImage> {
+    path.and_then(|p| images.get(p)?.as_deref())
+}
+
+/// [`DefaultMaterial::TerrainSplat`] object uniform bytes.
+fn object_uniform(view_proj: [f32; 16], model: Mat4, terrain: &Terrain) -> Vec<u8> {
+    let mut floats: Vec<f32> = Vec::with_capacity(TERRAIN_OBJECT_UBO_SIZE as usize / 4);
+    floats.extend_from_slice(&view_proj);
+    floats.extend_from_slice(&model.to_cols_array());
+    floats.extend_from_slice(&[1.0; 4]);
+    floats.extend_from_slice(&[terrain.tiling[0], terrain.tiling[1], 0.0, 0.0]);
+    for i in 0..MAX_TERRAIN_LAYERS {
+        let [r, g, b] = terrain.layers().get(i).map_or([1.0; 3], |l| l.tint);
+        floats.extend_from_slice(&[r, g, b, 1.0]);
+    }
+    floats.iter().flat_map(|f| f.to_ne_bytes()).collect()
+}
@@ -0,0 +1,148 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+/// Layers a splat map can blend: one per RGBA channel.
+pub const MAX_TERRAIN_LAYERS: usize = 4;
+
+/// Surface layer of a terrain, weighted by one splat map channel.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TerrainLayer {
+    /// Logical path of a PNG texture; `None` draws the tint alone.
+    pub texture: Option<String>,
+    /// Multiplies the texture (linear RGB).
+    pub tint: [f32; 3],
+}
+
+impl TerrainLayer {
+    #[inline]
+    pub fn new(texture: impl Into<String>) -> Self {
+        Self {
+            texture: Some(texture.into()),
+            tint: [1.0; 3],
+        }
+    }
+
+    /// Untextured layer of a single color.
+    #[inline]
+    pub fn color(tint: [f32; 3]) -> Self {
+        Self {
+            texture: None,
+            tint,
+        }
+    }
+
+    #[inline]
+    pub fn with_tint(mut self, tint: [f32; 3]) -> Self {
+        self.tint = tint;
+        self
+    }
+}
+
+/// Terrain component: a heightmap stretched over a rectangle of the xz plane.
+///
+/// Heightmap columns run along +x and rows along +z. Sample 0 lies at `position.y`, sample
+/// `u16::MAX` at `position.y + height`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Terrain {
+    /// Logical path of the heightmap: `.r16` / `.raw`, or a 16-bit grayscale `.png`.
+    pub heightmap: String,
+    /// World position of the corner at heightmap sample (0, 0).
+    pub position: [f32; 3],
+    /// World extent along x and z.
+    pub size: [f32; 2],
+    /// World height of the highest sample.
+    pub height: f32,
+    /// Logical path of the splat map PNG whose R, G, B, A weight layers 0..3; `None` draws
+    /// layer 0 everywhere.
+    pub splat: Option<String>,
+    /// Up to [`MAX_TERRAIN_LAYERS`] layers; extra ones are ignored.
+    pub layers: Vec<TerrainLayer>,
+    /// Times each layer texture repeats across the terrain, along x and z.
+    pub tiling: [f32; 2],
+    /// Quads along each side of a chunk at full detail; rounded to a power of two in 4..=128.
+    pub chunk_quads: u32,
+    pub visible: bool,
+}
+
+impl Terrain {
+    #[inline]
+    pub fn new(heightmap: impl Into<String>) -> Self {
+        Self {
+            heightmap: heightmap.into(),
+            position: [0.0; 3],
+            size: [256.0, 256.0],
+            height: 32.0,
+            splat: None,
+            layers: vec![TerrainLayer::color([0.35, 0.5, 0.25])],
+            tiling: [32.0, 32.0],
+            chunk_quads: 32,
+            visible: true,
+        }
+    }
+
+    #[inline]
+    pub fn with_position(mut self, position: [f32; 3]) -> Self {
+        self.position = position;
+        self
+    }
+
+    #[inline]
+    pub fn with_size(mut self, size: [f32; 2]) -> Self {
+        self.size = size;
+        self
+    }
+
+    #[inline]
+    pub fn with_height(mut self, height: f32) -> Self {
+        self.height = height;
+        self
+    }
+
+    #[inline]
+    pub fn with_splat(mut self, splat: impl Into<String>) -> Self {
+        self.splat = Some(splat.into());
+        self
+    }
+
+    #[inline]
+    pub fn with_layers(mut self, layers: Vec<TerrainLayer>) -> Self {
+        self.layers = layers;
+        self
+    }
+
+    #[inline]
+    pub fn with_tiling(mut self, tiling: [f32; 2]) -> Self {
+        self.tiling = tiling;
+        self
+    }
+
+    #[inline]
+    pub fn with_chunk_quads(mut self, quads: u32) -> Self {
+        self.chunk_quads = quads;
+        self
+    }
+
+    #[inline]
+    pub fn with_visible(mut self, visible: bool) -> Self {
+        self.visible = visible;
+        self
+    }
+
+    /// `chunk_quads` as actually used.
+    #[inline]
+    pub(crate) fn chunk_quads(&self) -> u32 {
+        self.chunk_quads.clamp(4, 128).next_power_of_two().min(128)
+    }
+
+    /// Layers the material draws, at most [`MAX_TERRAIN_LAYERS`].
+    #[inline]
+    pub(crate) fn layers(&self) -> &[TerrainLayer] {
+        &self.layers[..self.layers.len().min(MAX_TERRAIN_LAYERS)]
+    }
+
+    /// Every asset the terrain draws from: heightmap, splat map, layer textures.
+    pub(crate) fn assets(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.heightmap.as_str())
+            .chain(self.splat.as_deref())
+            .chain(self.layers().iter().filter_map(|l| l.texture.as_deref()))
+    }
+}
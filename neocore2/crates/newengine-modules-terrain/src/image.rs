@@ -0,0 +1,131 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use newengine_assets::HeightmapAsset;
+use std::io::Cursor;
+
+/// Upper bound on decoded image texels.
+const MAX_TEXELS: u64 = 8192 * 8192;
+
+/// Decoded RGBA8 image, rows top to bottom.
+#[derive(Debug, Clone)]
+pub(crate) struct RgbaImage {
+    pub width: u32,
+    pub height: u32,
+    pub rgba: Vec<u8>,
+}
+
+impl RgbaImage {
+    /// Bilinear texel at `u`, `v` in `0.0..=1.0`, clamped to the edges.
+    fn sample(&self, u: f32, v: f32) -> [f32; 4] {
+        let fx = (u * self.width as f32 - 0.5).clamp(0.0, (self.width - 1) as f32);
+        let fy = (v * self.height as f32 - 0.5).clamp(0.0, (self.height - 1) as f32);
+        let (x0, y0) = (fx as u32, fy as u32);
+        let (x1, y1) = ((x0 + 1).min(self.width - 1), (y0 + 1).min(self.height - 1));
+        let (tx, ty) = (fx - x0 as f32, fy - y0 as f32);
+
+        let texel = |x: u32, y: u32| {
+            let i = (y * self.width + x) as usize * 4;
+            let p = &self.rgba[i..i + 4];
+            [p[0] as f32, p[1] as f32, p[2] as f32, p[3] as f32]
+        };
+        let (a, b, c, d) = (texel(x0, y0), texel(x1, y0), texel(x0, y1), texel(x1, y1));
+        std::array::from_fn(|k| {
+            let top = a[k] + (b[k] - a[k]) * tx;
+            let bottom = c[k] + (d[k] - c[k]) * tx;
+            top + (bottom - top) * ty
+        })
+    }
+
+    /// Resamples the image into the `size` x `size` cell at texel `origin` of `out`, an RGBA8
+    /// image `stride` texels wide.
+    pub fn blit_resized(&self, out: &mut [u8], stride: u32, origin: [u32; 2], size: u32) {
+        for y in 0..size {
+            let v = (y as f32 + 0.5) / size as f32;
+            for x in 0..size {
+                let u = (x as f32 + 0.5) / size as f32;
+                let texel = self.sample(u, v);
+                let i = ((origin[1] + y) * stride + origin[0] + x) as usize * 4;
+                for (dst, c) in out[i..i + 4].iter_mut().zip(texel) {
+                    *dst = c.round().clamp(0.0, 255.0) as u8;
+                }
+            }
+        }
+    }
+}
+
+/// Decodes a PNG into RGBA8 rows, top to bottom.
+pub(crate) fn decode_png_rgba8(bytes: &[u8]) -> Result<RgbaImage, String> {
+    let mut decoder = png::Decoder::new(Cursor::new(bytes));
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info().map_err(|e| format!("png: {e}"))?;
+
+    let (width, height) = (reader.info().width, reader.info().height);
+    if width as u64 * height as u64 > MAX_TEXELS {
+        return Err(format!("png: {width}x{height} is too large"));
+    }
+
+    let size = reader
+        .output_buffer_size()
+        .ok_or_else(|| "png: image too large".to_string())?;
+    let mut buf = vec![0u8; size];
+    let frame = reader
+        .next_frame(&mut buf)
+        .map_err(|e| format!("png: {e}"))?;
+    buf.truncate(frame.buffer_size());
+
+    let texels = (width * height) as usize;
+    let rgba = match frame.color_type {
+        png::ColorType::Rgba => buf,
+        png::ColorType::Rgb => buf
+            .chunks_exact(3)
+            .flat_map(|c| [c[0], c[1], c[2], 255])
+            .collect(),
+        png::ColorType::GrayscaleAlpha => buf
+            .chunks_exact(2)
+            .flat_map(|c| [c[0], c[0], c[0], c[1]])
+            .collect(),
+        png::ColorType::Grayscale => buf.iter().flat_map(|&g| [g, g, g, 255]).collect(),
+        png::ColorType::Indexed => return Err("png: palette was not expanded".to_string()),
+    };
+    if rgba.len() != texels * 4 {
+        return Err(format!("png: unexpected row layout for {width}x{height}"));
+    }
+    Ok(RgbaImage {
+        width,
+        height,
+        rgba,
+    })
+}
+
+/// Decodes a PNG heightmap, keeping 16 bits per sample. Color images use their first channel;
+/// 8-bit images are stretched to the 16-bit range.
+pub(crate) fn decode_png_heightmap(bytes: &[u8]) -> Result<HeightmapAsset, String> {
+    let mut decoder = png::Decoder::new(Cursor::new(bytes));
+    decoder.set_transformations(png::Transformations::EXPAND);
+    let mut reader = decoder.read_info().map_err(|e| format!("png: {e}"))?;
+
+    let (width, height) = (reader.info().width, reader.info().height);
+    if width as u64 * height as u64 > MAX_TEXELS {
+        return Err(format!("png: {width}x{height} is too large"));
+    }
+
+    let size = reader
+        .output_buffer_size()
+        .ok_or_else(|| "png: image too large".to_string())?;
+    let mut buf = vec![0u8; size];
+    let frame = reader
+        .next_frame(&mut buf)
+        .map_err(|e| format!("png: {e}"))?;
+    buf.truncate(frame.buffer_size());
+
+    let wide = frame.bit_depth == png::BitDepth::Sixteen;
+    let texel_bytes = frame.color_type.samples() * if wide { 2 } else { 1 };
+    let samples: Vec<u16> = buf
+        .chunks_exact(texel_bytes)
+        .map(|t| match wide {
+            true => u16::from_be_bytes([t[0], t[1]]),
+            false => t[0] as u16 * 257,
+        })
+        .collect();
+    HeightmapAsset::new(width, height, samples).map_err(|e| format!("png: {e}"))
+}
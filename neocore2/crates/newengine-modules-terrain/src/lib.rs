@@ -0,0 +1,27 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+mod api;
+mod component;
+mod image;
+mod mesh;
+mod module;
+
+pub use api::{TerrainApiRef, TerrainStats};
+pub use component::{Terrain, TerrainLayer, MAX_TERRAIN_LAYERS};
+pub use module::{TerrainConfig, TerrainModule};
+
+use newengine_core::{ApiProvide, ApiVersion};
+
+pub const TERRAIN_API_ID: &str = "terrain.api";
+pub const TERRAIN_API_VERSION: ApiVersion = ApiVersion::new(0, 1, 0);
+pub const TERRAIN_API_PROVIDE: ApiProvide = ApiProvide::new(TERRAIN_API_ID, TERRAIN_API_VERSION);
+
+/// Key of a terrain component; callers pick the ids, as with physics entities.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TerrainId(pub u64);
+
+impl std::fmt::Display for TerrainId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
@@ -0,0 +1,261 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use glam::{Mat4, Vec3};
+use newengine_assets::HeightmapAsset;
+use newengine_camera::Frustum;
+
+use crate::component::Terrain;
+
+/// Grid quads per terrain side above which the heightmap is resampled down.
+const MAX_GRID_QUADS: u32 = 1024;
+
+/// Bytes per vertex: position, normal, uv (`VertexLayout::default_mesh`).
+pub(crate) const VERTEX_SIZE: usize = 32;
+
+/// Index range of one detail level, shared by every chunk.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct LodRange {
+    pub first_index: u32,
+    pub index_count: u32,
+}
+
+/// Chunk draw picked by [`TerrainMesh::select`].
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ChunkDraw {
+    pub lod: LodRange,
+    pub vertex_offset: i32,
+}
+
+/// CPU side of a terrain: a grid of equally sized chunks over the heightmap.
+///
+/// Every chunk owns a block of `(n + 1)^2` grid vertices followed by `4 * (n + 1)` skirt
+/// vertices hanging below its border, which hide the cracks between neighbours drawn at
+/// different detail. Level `l` steps over `2^l` quads, so all chunks draw from the same index
+/// lists and only differ by vertex offset. Positions are relative to the terrain position.
+pub(crate) struct TerrainMesh {
+    /// Quads along a chunk side at full detail.
+    quads: u32,
+    /// Chunks along x and z.
+    chunks: [u32; 2],
+    /// Heights of the grid vertices, `(gx + 1) * (gz + 1)`, row-major along z.
+    heights: Vec<f32>,
+    spacing: [f32; 2],
+    /// Local bounds of every chunk, row-major along z.
+    bounds: Vec<(Vec3, Vec3)>,
+    pub lods: Vec<LodRange>,
+    /// Upload data; emptied once on the GPU.
+    pub vertices: Vec<u8>,
+    pub indices: Vec<u32>,
+}
+
+impl TerrainMesh {
+    pub fn build(heightmap: &HeightmapAsset, terrain: &Terrain) -> Self {
+        let n = terrain.chunk_quads();
+        let chunks_along = |samples: u32| {
+            let quads = (samples - 1).min(MAX_GRID_QUADS);
+            quads.div_ceil(n).max(1)
+        };
+        let chunks = [
+            chunks_along(heightmap.width),
+            chunks_along(heightmap.height),
+        ];
+        let (gx, gz) = (chunks[0] * n, chunks[1] * n);
+        let spacing = [
+            terrain.size[0].max(0.0) / gx as f32,
+            terrain.size[1].max(0.0) / gz as f32,
+        ];
+
+        let row = (gx + 1) as usize;
+        let mut heights = Vec::with_capacity(row * (gz + 1) as usize);
+        for j in 0..=gz {
+            for i in 0..=gx {
+                let h = heightmap.sample_bilinear(i as f32 / gx as f32, j as f32 / gz as f32);
+                heights.push(h * terrain.height);
+            }
+        }
+
+        let mut mesh = Self {
+            quads: n,
+            chunks,
+            heights,
+            spacing,
+            bounds: Vec::with_capacity((chunks[0] * chunks[1]) as usize),
+            lods: Vec::new(),
+            vertices: Vec::new(),
+            indices: Vec::new(),
+        };
+        let skirt = terrain.height.abs() * 0.05 + spacing[0].max(spacing[1]);
+        mesh.vertices
+            .reserve(mesh.chunk_vertex_count() * mesh.bounds.capacity() * VERTEX_SIZE);
+        for cz in 0..chunks[1] {
+            for cx in 0..chunks[0] {
+                mesh.push_chunk(cx * n, cz * n, [gx, gz], skirt);
+            }
+        }
+        mesh.build_lods();
+        mesh
+    }
+
+    #[inline]
+    fn chunk_vertex_count(&self) -> usize {
+        let side = (self.quads + 1) as usize;
+        side * side + 4 * side
+    }
+
+    #[inline]
+    fn grid_height(&self, i: i64, j: i64) -> f32 {
+        let gx = (self.chunks[0] * self.quads) as i64;
+        let gz = (self.chunks[1] * self.quads) as i64;
+        let (i, j) = (i.clamp(0, gx) as usize, j.clamp(0, gz) as usize);
+        self.heights[j * (gx as usize + 1) + i]
+    }
+
+    fn normal(&self, i: i64, j: i64) -> Vec3 {
+        let [sx, sz] = self.spacing;
+        let dx = (self.grid_height(i + 1, j) - self.grid_height(i - 1, j)) / (2.0 * sx.max(1e-6));
+        let dz = (self.grid_height(i, j + 1) - self.grid_height(i, j - 1)) / (2.0 * sz.max(1e-6));
+        Vec3::new(-dx, 1.0, -dz).normalize_or(Vec3::Y)
+    }
+
+    fn push_chunk(&mut self, i0: u32, j0: u32, grid: [u32; 2], skirt: f32) {
+        let n = self.quads;
+        let mut lo = Vec3::splat(f32::MAX);
+        let mut hi = Vec3::splat(f32::MIN);
+        let mut vertex = |mesh: &mut Self, i: u32, j: u32, drop: f32| {
+            let h = mesh.grid_height(i as i64, j as i64);
+            let p = Vec3::new(
+                i as f32 * mesh.spacing[0],
+                h - drop,
+                j as f32 * mesh.spacing[1],
+            );
+            let nrm = mesh.normal(i as i64, j as i64);
+            let uv = [i as f32 / grid[0] as f32, j as f32 / grid[1] as f32];
+            for f in p.to_array().into_iter().chain(nrm.to_array()).chain(uv) {
+                mesh.vertices.extend_from_slice(&f.to_ne_bytes());
+            }
+            lo = lo.min(p);
+            hi = hi.max(p);
+        };
+
+        for j in 0..=n {
+            for i in 0..=n {
+                vertex(self, i0 + i, j0 + j, 0.0);
+            }
+        }
+        // Skirts: near edge (z = 0), far edge (z = n), left edge (x = 0), right edge (x = n).
+        for k in 0..=n {
+            vertex(self, i0 + k, j0, skirt);
+        }
+        for k in 0..=n {
+            vertex(self, i0 + k, j0 + n, skirt);
+        }
+        for k in 0..=n {
+            vertex(self, i0, j0 + k, skirt);
+        }
+        for k in 0..=n {
+            vertex(self, i0 + n, j0 + k, skirt);
+        }
+        self.bounds.push((lo, hi));
+    }
+
+    /// One index list per detail level, from full detail to a single quad per chunk.
+    fn build_lods(&mut self) {
+        let n = self.quads;
+        let side = n + 1;
+        let grid = |i: u32, j: u32| j * side + i;
+        let skirt = |edge: u32, k: u32| side * side + edge * side + k;
+
+        let mut step = 1;
+        while step <= n {
+            let first_index = self.indices.len() as u32;
+            let out = &mut self.indices;
+            for j in (0..n).step_by(step as usize) {
+                for i in (0..n).step_by(step as usize) {
+                    let (a, b) = (grid(i, j), grid(i + step, j));
+                    let (c, d) = (grid(i, j + step), grid(i + step, j + step));
+                    out.extend_from_slice(&[a, c, b, b, c, d]);
+                }
+            }
+            for k in (0..n).step_by(step as usize) {
+                let edges = [
+                    (grid(k, 0), grid(k + step, 0), 0),
+                    (grid(k, n), grid(k + step, n), 1),
+                    (grid(0, k), grid(0, k + step), 2),
+                    (grid(n, k), grid(n, k + step), 3),
+                ];
+                for (a, b, edge) in edges {
+                    let (sa, sb) = (skirt(edge, k), skirt(edge, k + step));
+                    out.extend_from_slice(&[a, b, sa, b, sb, sa]);
+                }
+            }
+            self.lods.push(LodRange {
+                first_index,
+                index_count: self.indices.len() as u32 - first_index,
+            });
+            step *= 2;
+        }
+    }
+
+    /// Height of the terrain surface at local `x`, `z`; `None` outside the terrain.
+    pub fn height_at(&self, x: f32, z: f32) -> Option<f32> {
+        let [sx, sz] = self.spacing;
+        if sx <= 0.0 || sz <= 0.0 {
+            return None;
+        }
+        let (fx, fz) = (x / sx, z / sz);
+        let (gx, gz) = (self.chunks[0] * self.quads, self.chunks[1] * self.quads);
+        if !(0.0..=gx as f32).contains(&fx) || !(0.0..=gz as f32).contains(&fz) {
+            return None;
+        }
+        let (i, j) = (fx.floor() as i64, fz.floor() as i64);
+        let (tx, tz) = (fx - i as f32, fz - j as f32);
+        let top = lerp(self.grid_height(i, j), self.grid_height(i + 1, j), tx);
+        let bottom = lerp(
+            self.grid_height(i, j + 1),
+            self.grid_height(i + 1, j + 1),
+            tx,
+        );
+        Some(lerp(top, bottom, tz))
+    }
+
+    /// Chunks inside the frustum of `view_proj`, each at the detail level of its distance to
+    /// `eye`: full detail within `lod_distance`, one level coarser every time the distance
+    /// doubles. `origin` is the terrain position. Returns the draws and the culled count.
+    pub fn select(
+        &self,
+        origin: Vec3,
+        view_proj: Mat4,
+        eye: Vec3,
+        lod_distance: f32,
+        out: &mut Vec<ChunkDraw>,
+    ) -> usize {
+        let frustum = Frustum::from_view_proj(view_proj);
+        let per_chunk = self.chunk_vertex_count();
+        let coarsest = self.lods.len().saturating_sub(1);
+        let mut culled = 0;
+
+        for (index, &(lo, hi)) in self.bounds.iter().enumerate() {
+            let (lo, hi) = (lo + origin, hi + origin);
+            if !frustum.contains_aabb(lo, hi) {
+                culled += 1;
+                continue;
+            }
+            let distance = eye.clamp(lo, hi).distance(eye);
+            let lod = if distance <= lod_distance || lod_distance <= 0.0 {
+                0
+            } else {
+                ((distance / lod_distance).log2().floor() as usize + 1).min(coarsest)
+            };
+            out.push(ChunkDraw {
+                lod: self.lods[lod],
+                vertex_offset: (index * per_chunk) as i32,
+            });
+        }
+        culled
+    }
+}
+
+#[inline]
+fn lerp(a: f32, b: f32, t: f32) -> f32 {
+    a + (b - a) * t
+}
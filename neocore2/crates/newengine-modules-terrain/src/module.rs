@@ -0,0 +1,183 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use newengine_assets::{AssetId, AssetState, HeightmapAsset};
+use newengine_core::assets::AssetManager;
+use newengine_core::render::{RenderApiRef, RENDER_API_ID};
+use newengine_core::{ApiProvide, EngineResult, Module, ModuleCtx};
+use std::sync::Arc;
+
+use crate::api::{AssetKind, TerrainApiRef};
+use crate::image::{decode_png_heightmap, decode_png_rgba8};
+use crate::{TERRAIN_API_ID, TERRAIN_API_PROVIDE};
+
+#[derive(Debug, Clone)]
+pub struct TerrainConfig {
+    /// Camera distance up to which chunks draw at full detail; detail halves every time the
+    /// distance doubles past it.
+    pub lod_distance: f32,
+}
+
+impl TerrainConfig {
+    #[inline]
+    pub fn new() -> Self {
+        Self { lod_distance: 64.0 }
+    }
+
+    #[inline]
+    pub fn with_lod_distance(mut self, distance: f32) -> Self {
+        self.lod_distance = distance;
+        self
+    }
+}
+
+impl Default for TerrainConfig {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Asset being loaded for one or more terrains.
+struct PendingAsset {
+    path: String,
+    kind: AssetKind,
+    id: AssetId,
+}
+
+/// Draws heightmap terrains and exposes them as `terrain.api`.
+///
+/// Heightmaps (`.r16` / `.raw` through the heightmap importer, or 16-bit grayscale PNGs),
+/// splat maps and layer textures (PNG) load through the AssetManager when a terrain first
+/// names them; each `update` builds the chunk meshes of terrains whose assets are in. The
+/// module does not own the frame: the host calls [`TerrainApiRef::render`] from its render
+/// controller with its camera.
+pub struct TerrainModule {
+    api: TerrainApiRef,
+    pending: Vec<PendingAsset>,
+}
+
+impl TerrainModule {
+    #[inline]
+    pub fn new(config: TerrainConfig) -> Self {
+        Self {
+            api: TerrainApiRef::new(config.lod_distance),
+            pending: Vec::new(),
+        }
+    }
+
+    /// Handle for consumers living outside the engine.
+    #[inline]
+    pub fn api(&self) -> TerrainApiRef {
+        self.api.clone()
+    }
+
+    fn load_requested(&mut self, am: &AssetManager) {
+        for (path, kind) in self.api.take_requests() {
+            match am.store().load_path(&path) {
+                Ok(id) => self.pending.push(PendingAsset { path, kind, id }),
+                Err(e) => {
+                    log::warn!(target: "terrain", "asset.load rejected path='{path}' err='{e}'");
+                    self.fail(&path, kind);
+                }
+            }
+        }
+    }
+
+    /// Hands every finished asset to the API.
+    fn advance(&mut self, am: &AssetManager) {
+        let mut pending = std::mem::take(&mut self.pending);
+        pending.retain(|p| {
+            let done = match am.state(p.id) {
+                AssetState::Unloaded | AssetState::Loading => return true,
+                AssetState::Failed(e) => Err(e.to_string()),
+                AssetState::Ready => self.finish(am, p),
+            };
+            if let Err(e) = done {
+                log::warn!(target: "terrain", "asset.load failed path='{}' err='{e}'", p.path);
+                self.fail(&p.path, p.kind);
+            }
+            false
+        });
+        self.pending = pending;
+    }
+
+    fn finish(&self, am: &AssetManager, p: &PendingAsset) -> Result<(), String> {
+        match p.kind {
+            AssetKind::Heightmap if is_png(&p.path) => {
+                let blob = am
+                    .get_blob(p.id)
+                    .ok_or_else(|| "blob missing".to_string())?;
+                let heightmap = decode_png_heightmap(&blob.payload)?;
+                self.api.set_heightmap(p.path.clone(), Arc::new(heightmap));
+            }
+            AssetKind::Heightmap => {
+                let heightmap = am
+                    .get_typed::<HeightmapAsset>(p.id)
+                    .map_err(|e| e.to_string())?;
+                self.api.set_heightmap(p.path.clone(), heightmap);
+            }
+            AssetKind::Image => {
+                let blob = am
+                    .get_blob(p.id)
+                    .ok_or_else(|| "blob missing".to_string())?;
+                let image = decode_png_rgba8(&blob.payload)?;
+                self.api.set_image(p.path.clone(), Some(Arc::new(image)));
+            }
+        }
+        Ok(())
+    }
+
+    /// A terrain without its heightmap stays unbuilt; missing textures draw as if absent.
+    fn fail(&self, path: &str, kind: AssetKind) {
+        match kind {
+            AssetKind::Heightmap => self.api.load_failed(path),
+            AssetKind::Image => self.api.set_image(path.to_string(), None),
+        }
+    }
+}
+
+#[inline]
+fn is_png(path: &str) -> bool {
+    path.rsplit('.')
+        .next()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("png"))
+}
+
+impl<E: Send + 'static> Module<E> for TerrainModule {
+    fn id(&self) -> &'static str {
+        "terrain"
+    }
+
+    fn provides(&self) -> &'static [ApiProvide] {
+        &[TERRAIN_API_PROVIDE]
+    }
+
+    fn init(&mut self, ctx: &mut ModuleCtx<'_, E>) -> EngineResult<()> {
+        ctx.resources_mut()
+            .register_api(TERRAIN_API_ID, self.api.clone())
+    }
+
+    fn update(&mut self, ctx: &mut ModuleCtx<'_, E>) -> EngineResult<()> {
+        if let Some(am) = ctx.resources().get::<AssetManager>() {
+            self.load_requested(am);
+            if !self.pending.is_empty() {
+                self.advance(am);
+            }
+        }
+        self.api.build_meshes();
+        Ok(())
+    }
+
+    fn shutdown(&mut self, ctx: &mut ModuleCtx<'_, E>) -> EngineResult<()> {
+        match ctx.api::<RenderApiRef>(RENDER_API_ID) {
+            Some(render) => self.api.release(&mut **render.lock()),
+            None => self.api.clear(),
+        }
+        let _ = ctx
+            .resources_mut()
+            .unregister_api::<TerrainApiRef>(TERRAIN_API_ID);
+        self.pending.clear();
+        self.api.clear();
+        Ok(())
+    }
+}
@@ -0,0 +1,276 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use newengine_assets::{TilemapLayer, TilemapObject};
+use newengine_modules_physics::{EntityId, PhysicsApiRef, RigidBody};
+use newengine_modules_sprite2d::{Sprite, Sprite2dApiRef};
+use parking_lot::Mutex;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use crate::collision::build_colliders;
+use crate::component::Tilemap;
+use crate::map::LoadedMap;
+use crate::TilemapId;
+
+/// Counters of the last draw.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TilemapStats {
+    pub maps: usize,
+    /// Maps whose asset, tilesets or images have not loaded (or failed to).
+    pub loading: usize,
+    /// Chunks drawn.
+    pub chunks: usize,
+    /// Chunks skipped as outside the camera view.
+    pub culled: usize,
+    pub tiles: usize,
+}
+
+struct MapInstance {
+    component: Tilemap,
+    /// Physics entity currently holding this map's collision.
+    exported: Option<EntityId>,
+    /// Collision must be (re)built from the component.
+    collision_dirty: bool,
+}
+
+struct TilemapWorld {
+    maps: HashMap<TilemapId, MapInstance>,
+    loaded: HashMap<String, Arc<LoadedMap>>,
+    /// Assets referenced by maps that the module has not been asked to load yet.
+    requests: Vec<String>,
+    requested: HashSet<String>,
+    /// Exported physics entities no map owns any more.
+    stale: Vec<EntityId>,
+    sprites: Vec<Sprite>,
+    stats: TilemapStats,
+}
+
+impl TilemapWorld {
+    fn request(&mut self, asset: &str) {
+        if !self.loaded.contains_key(asset) && self.requested.insert(asset.to_string()) {
+            self.requests.push(asset.to_string());
+        }
+    }
+
+    fn drop_collision(&mut self, id: TilemapId) {
+        if let Some(entity) = self.maps.get_mut(&id).and_then(|m| m.exported.take()) {
+            self.stale.push(entity);
+        }
+    }
+}
+
+/// Shared handle to the tilemaps, registered as `tilemap.api`.
+///
+/// Tilemaps are components keyed by caller-picked [`TilemapId`]s. The module loads a map's
+/// `.tmx`, its tilesets and their images on first use; until then the map draws nothing and
+/// exports no collision.
+#[derive(Clone)]
+pub struct TilemapApiRef(Arc<Mutex<TilemapWorld>>);
+
+impl TilemapApiRef {
+    pub(crate) fn new() -> Self {
+        Self(Arc::new(Mutex::new(TilemapWorld {
+            maps: HashMap::new(),
+            loaded: HashMap::new(),
+            requests: Vec::new(),
+            requested: HashSet::new(),
+            stale: Vec::new(),
+            sprites: Vec::new(),
+            stats: TilemapStats::default(),
+        })))
+    }
+
+    /// Adds or replaces the tilemap of `id`.
+    pub fn insert(&self, id: TilemapId, tilemap: Tilemap) {
+        let mut w = self.0.lock();
+        w.request(&tilemap.asset);
+        w.drop_collision(id);
+        w.maps.insert(
+            id,
+            MapInstance {
+                component: tilemap,
+                exported: None,
+                collision_dirty: true,
+            },
+        );
+    }
+
+    /// Removes the tilemap and its exported collision. Returns false if there was none.
+    pub fn remove(&self, id: TilemapId) -> bool {
+        let mut w = self.0.lock();
+        w.drop_collision(id);
+        w.maps.remove(&id).is_some()
+    }
+
+    #[inline]
+    pub fn contains(&self, id: TilemapId) -> bool {
+        self.0.lock().maps.contains_key(&id)
+    }
+
+    pub fn tilemap(&self, id: TilemapId) -> Option<Tilemap> {
+        self.0.lock().maps.get(&id).map(|m| m.component.clone())
+    }
+
+    /// Whether the map's asset, tilesets and images are all loaded.
+    pub fn is_loaded(&self, id: TilemapId) -> bool {
+        let w = self.0.lock();
+        w.maps
+            .get(&id)
+            .is_some_and(|m| w.loaded.contains_key(&m.component.asset))
+    }
+
+    /// Moves the map; its collision body follows on the next update.
+    pub fn set_position(&self, id: TilemapId, position: [f32; 2]) -> bool {
+        self.with_map(id, |m| {
+            m.component.position = position;
+            m.collision_dirty = true;
+        })
+    }
+
+    pub fn set_visible(&self, id: TilemapId, visible: bool) -> bool {
+        self.with_map(id, |m| m.component.visible = visible)
+    }
+
+    /// Objects of the map's object layers called `layer`, or of all of them for `None`, in
+    /// map pixels. Empty until the map has loaded.
+    pub fn objects(&self, id: TilemapId, layer: Option<&str>) -> Vec<TilemapObject> {
+        let w = self.0.lock();
+        let Some(loaded) = w
+            .maps
+            .get(&id)
+            .and_then(|m| w.loaded.get(&m.component.asset))
+        else {
+            return Vec::new();
+        };
+        loaded
+            .map
+            .layers
+            .iter()
+            .filter_map(|l| match l {
+                TilemapLayer::Objects(o) if layer.is_none_or(|name| o.name == name) => Some(o),
+                _ => None,
+            })
+            .flat_map(|o| o.objects.iter().cloned())
+            .collect()
+    }
+
+    #[inline]
+    pub fn stats(&self) -> TilemapStats {
+        self.0.lock().stats
+    }
+
+    /// Queues the visible tiles of every loaded, visible map as sprites, culling chunks
+    /// outside the last rendered camera view.
+    pub fn draw(&self, sprites: &Sprite2dApiRef) {
+        let view = sprites.view_bounds();
+        let mut guard = self.0.lock();
+        let w = &mut *guard;
+        let mut stats = TilemapStats {
+            maps: w.maps.len(),
+            ..Default::default()
+        };
+        for m in w.maps.values() {
+            let Some(loaded) = w.loaded.get(&m.component.asset) else {
+                stats.loading += 1;
+                continue;
+            };
+            if !m.component.visible {
+                continue;
+            }
+            let counts = loaded.sprites(&m.component, view, &mut w.sprites);
+            stats.chunks += counts.chunks;
+            stats.culled += counts.culled;
+            stats.tiles += counts.tiles;
+        }
+        w.stats = stats;
+        sprites.draw_all(w.sprites.drain(..));
+    }
+
+    /// Removes every tilemap and loaded map; the module takes their collision bodies out of
+    /// the physics world on its next update.
+    pub fn clear(&self) {
+        let mut w = self.0.lock();
+        let exported: Vec<EntityId> = w
+            .maps
+            .values_mut()
+            .filter_map(|m| m.exported.take())
+            .collect();
+        w.stale.extend(exported);
+        w.maps.clear();
+        w.loaded.clear();
+        w.requests.clear();
+        w.requested.clear();
+        w.stats = TilemapStats::default();
+    }
+
+    pub(crate) fn set_loaded(&self, asset: String, map: LoadedMap) {
+        let mut w = self.0.lock();
+        w.requested.remove(&asset);
+        for m in w.maps.values_mut() {
+            if m.component.asset == asset {
+                m.collision_dirty = true;
+            }
+        }
+        w.loaded.insert(asset, Arc::new(map));
+    }
+
+    /// Map assets that nobody has loaded yet.
+    pub(crate) fn take_requests(&self) -> Vec<String> {
+        std::mem::take(&mut self.0.lock().requests)
+    }
+
+    /// Forgets a failed load so that inserting a map with the asset again retries it.
+    pub(crate) fn load_failed(&self, asset: &str) {
+        self.0.lock().requested.remove(asset);
+    }
+
+    /// Brings the physics world in line with the maps: removes bodies of replaced or removed
+    /// maps and (re)inserts a fixed body with the colliders of every changed loaded map.
+    pub(crate) fn export_collision(&self, physics: &PhysicsApiRef, depth: f32) {
+        let mut guard = self.0.lock();
+        let w = &mut *guard;
+        for entity in w.stale.drain(..) {
+            physics.remove(entity);
+        }
+
+        for m in w.maps.values_mut() {
+            if !m.collision_dirty {
+                continue;
+            }
+            let Some(loaded) = w.loaded.get(&m.component.asset) else {
+                continue;
+            };
+            m.collision_dirty = false;
+            let Some(entity) = m.component.collision else {
+                continue;
+            };
+
+            let colliders = build_colliders(loaded, &m.component, depth);
+            let [x, y] = m.component.position;
+            physics.insert(
+                entity,
+                &RigidBody::fixed().with_position([x, y, 0.0]),
+                &colliders,
+            );
+            m.exported = Some(entity);
+        }
+    }
+
+    /// Physics entities holding tilemap collision, for shutdown.
+    pub(crate) fn take_exported(&self) -> Vec<EntityId> {
+        let mut w = self.0.lock();
+        let mut out = std::mem::take(&mut w.stale);
+        out.extend(w.maps.values_mut().filter_map(|m| m.exported.take()));
+        out
+    }
+
+    fn with_map(&self, id: TilemapId, f: impl FnOnce(&mut MapInstance)) -> bool {
+        match self.0.lock().maps.get_mut(&id) {
+            Some(m) => {
+                f(m);
+                true
+            }
+            None => false,
+        }
+    }
+}
@@ -0,0 +1,220 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use newengine_assets::{
+    ObjectShape, TileLayer, TilemapLayer, TilemapObject, TilemapProperties, GID_FLIP_D, GID_FLIP_H,
+    GID_FLIP_V, GID_MASK,
+};
+use newengine_modules_physics::Collider;
+use std::collections::HashMap;
+
+use crate::component::Tilemap;
+use crate::map::LoadedMap;
+
+/// Layer property (bool) that turns every tile of a tile layer, or every object of an object
+/// layer, into collision. Object layers named `collision` count too.
+pub(crate) const COLLISION_PROPERTY: &str = "collision";
+
+/// Colliders of `map` placed as `comp`, relative to `comp.position`; `depth` is their extent
+/// along z.
+///
+/// - tile layers with `collision = true`: solid cells, merged into as few boxes as the rows
+///   allow;
+/// - other tile layers: the rectangles drawn in Tiled's collision editor for their tiles;
+/// - collision object layers: rectangles as boxes, circles as balls, anything else (rotated
+///   shapes, polygons, polylines, ellipses) as its bounding box. Points are skipped.
+pub(crate) fn build_colliders(map: &LoadedMap, comp: &Tilemap, depth: f32) -> Vec<Collider> {
+    let mut out = Vec::new();
+    let half_z = depth.max(0.0) * 0.5;
+    let placer = Placer { comp, half_z };
+
+    for layer in &map.map.layers {
+        match layer {
+            TilemapLayer::Tiles(tiles) if flag(&tiles.properties) => {
+                solid_cells(map, tiles, &placer, &mut out)
+            }
+            TilemapLayer::Tiles(tiles) => tile_shapes(map, tiles, &placer, &mut out),
+            TilemapLayer::Objects(objects)
+                if flag(&objects.properties)
+                    || objects.name.eq_ignore_ascii_case(COLLISION_PROPERTY) =>
+            {
+                for o in &objects.objects {
+                    placer.object(o, objects.offset, &mut out);
+                }
+            }
+            TilemapLayer::Objects(_) => {}
+        }
+    }
+    out
+}
+
+#[inline]
+fn flag(props: &TilemapProperties) -> bool {
+    props
+        .get(COLLISION_PROPERTY)
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false)
+}
+
+/// Turns map pixel shapes (y down) into colliders relative to the component position.
+struct Placer<'a> {
+    comp: &'a Tilemap,
+    half_z: f32,
+}
+
+impl Placer<'_> {
+    /// Box over map pixels `[x0, y0, x1, y1]`.
+    fn rect(&self, b: [f32; 4], out: &mut Vec<Collider>) {
+        let ps = self.comp.pixel_size;
+        let (w, h) = ((b[2] - b[0]).abs() * ps, (b[3] - b[1]).abs() * ps);
+        if w <= 0.0 || h <= 0.0 {
+            return;
+        }
+        let center = [(b[0] + b[2]) * 0.5 * ps, -(b[1] + b[3]) * 0.5 * ps, 0.0];
+        out.push(Collider::cuboid([w * 0.5, h * 0.5, self.half_z]).with_offset(center));
+    }
+
+    fn object(&self, o: &TilemapObject, offset: [f32; 2], out: &mut Vec<Collider>) {
+        let (x, y) = (o.x + offset[0], o.y + offset[1]);
+        // Tile objects hang from their bottom-left corner.
+        let top = if o.gid.is_some() { y - o.height } else { y };
+
+        match o.shape {
+            ObjectShape::Point => {}
+            ObjectShape::Rect if o.rotation == 0.0 => {
+                self.rect([x, top, x + o.width, top + o.height], out)
+            }
+            ObjectShape::Ellipse if o.rotation == 0.0 && o.width == o.height => {
+                let ps = self.comp.pixel_size;
+                let r = o.width * 0.5;
+                if r > 0.0 {
+                    let center = [(x + r) * ps, -(top + r) * ps, 0.0];
+                    out.push(Collider::ball(r * ps).with_offset(center));
+                }
+            }
+            ObjectShape::Rect | ObjectShape::Ellipse => {
+                let corners = [
+                    [0.0, top - y],
+                    [o.width, top - y],
+                    [0.0, top - y + o.height],
+                    [o.width, top - y + o.height],
+                ];
+                self.rect(rotated_bounds(x, y, o.rotation, &corners), out)
+            }
+            ObjectShape::Polygon | ObjectShape::Polyline => {
+                if !o.points.is_empty() {
+                    self.rect(rotated_bounds(x, y, o.rotation, &o.points), out)
+                }
+            }
+        }
+    }
+}
+
+/// Bounds of `points` (relative to `x`, `y`) turned `degrees` clockwise around `x`, `y`.
+fn rotated_bounds(x: f32, y: f32, degrees: f32, points: &[[f32; 2]]) -> [f32; 4] {
+    let (sin, cos) = degrees.to_radians().sin_cos();
+    points
+        .iter()
+        .fold([f32::MAX, f32::MAX, f32::MIN, f32::MIN], |b, &[px, py]| {
+            // With y down, this turns clockwise on screen.
+            let (rx, ry) = (x + px * cos - py * sin, y + px * sin + py * cos);
+            [b[0].min(rx), b[1].min(ry), b[2].max(rx), b[3].max(ry)]
+        })
+}
+
+/// Every non-empty cell of a collision tile layer, as row runs stacked into boxes.
+fn solid_cells(map: &LoadedMap, layer: &TileLayer, placer: &Placer<'_>, out: &mut Vec<Collider>) {
+    let cs = map.map.chunk_size as i32;
+    let mut cells: Vec<(i32, i32)> = Vec::new();
+    for chunk in &layer.chunks {
+        let gids = map.asset.chunk_gids(map.map.chunk_size, chunk);
+        for (i, &raw) in gids.iter().enumerate() {
+            if raw & GID_MASK != 0 {
+                cells.push((chunk.y + i as i32 / cs, chunk.x + i as i32 % cs));
+            }
+        }
+    }
+    cells.sort_unstable();
+
+    // Horizontal runs per row, then runs spanning the same columns in consecutive rows merge.
+    let mut runs: Vec<(i32, i32, i32)> = Vec::new();
+    for (y, x) in cells {
+        match runs.last_mut() {
+            Some((ry, _, x1)) if *ry == y && *x1 + 1 == x => *x1 = x,
+            _ => runs.push((y, x, x)),
+        }
+    }
+    let mut open: HashMap<(i32, i32), (i32, i32)> = HashMap::new();
+    let mut boxes: Vec<[i32; 4]> = Vec::new();
+    for (y, x0, x1) in runs {
+        match open.get_mut(&(x0, x1)) {
+            Some((_, last)) if *last + 1 == y => *last = y,
+            _ => {
+                if let Some((first, last)) = open.insert((x0, x1), (y, y)) {
+                    boxes.push([x0, first, x1, last]);
+                }
+            }
+        }
+    }
+    boxes.extend(
+        open.into_iter()
+            .map(|((x0, x1), (y0, y1))| [x0, y0, x1, y1]),
+    );
+
+    let (tw, th) = (map.map.tile_width as f32, map.map.tile_height as f32);
+    for [x0, y0, x1, y1] in boxes {
+        placer.rect(
+            [
+                x0 as f32 * tw + layer.offset[0],
+                y0 as f32 * th + layer.offset[1],
+                (x1 + 1) as f32 * tw + layer.offset[0],
+                (y1 + 1) as f32 * th + layer.offset[1],
+            ],
+            out,
+        );
+    }
+}
+
+/// Collision-editor rectangles of the tiles in a regular tile layer.
+fn tile_shapes(map: &LoadedMap, layer: &TileLayer, placer: &Placer<'_>, out: &mut Vec<Collider>) {
+    let cs = map.map.chunk_size as i32;
+    for chunk in &layer.chunks {
+        let gids = map.asset.chunk_gids(map.map.chunk_size, chunk);
+        for (i, &raw) in gids.iter().enumerate() {
+            // Gid 0 (empty) precedes every tileset's `first_gid`.
+            let Some((ts, local)) = map.tileset_for(raw & GID_MASK) else {
+                continue;
+            };
+            let Some(tile) = ts.tileset.tile(local).filter(|t| !t.collision.is_empty()) else {
+                continue;
+            };
+            let (tx, ty) = (chunk.x + i as i32 % cs, chunk.y + i as i32 / cs);
+            let [x, top, w, h] = map.tile_image_rect(layer, ts, tx, ty);
+
+            for shape in &tile.collision {
+                if shape.shape != ObjectShape::Rect || shape.rotation != 0.0 {
+                    continue;
+                }
+                let [rx, ry, rw, rh] =
+                    flip_rect([shape.x, shape.y, shape.width, shape.height], [w, h], raw);
+                placer.rect([x + rx, top + ry, x + rx + rw, top + ry + rh], out);
+            }
+        }
+    }
+}
+
+/// Applies the gid's flip flags to rectangle `[x, y, w, h]` inside a tile of `size`.
+fn flip_rect(r: [f32; 4], size: [f32; 2], raw_gid: u32) -> [f32; 4] {
+    let [mut x, mut y, mut w, mut h] = r;
+    let [mut tw, mut th] = size;
+    if raw_gid & GID_FLIP_D != 0 {
+        (x, y, w, h) = (y, x, h, w);
+        (tw, th) = (th, tw);
+    }
+    if raw_gid & GID_FLIP_H != 0 {
+        x = tw - x - w;
+    }
+    if raw_gid & GID_FLIP_V != 0 {
+        y = th - y - h;
+    }
+    [x, y, w, h]
+}
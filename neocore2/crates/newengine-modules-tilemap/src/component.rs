@@ -0,0 +1,72 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use newengine_modules_physics::EntityId;
+
+/// Tilemap component: a Tiled map placed in the 2D world.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tilemap {
+    /// Logical path of the `.tmx` asset.
+    pub asset: String,
+    /// World position of the map's top-left corner (tile 0, 0).
+    pub position: [f32; 2],
+    /// World units per map pixel; 1 matches a `Camera2D` zoom of 1 pixel for pixel.
+    pub pixel_size: f32,
+    /// Sprite layer of the bottom tile layer; each tile layer above it draws one layer higher.
+    pub layer: i32,
+    pub visible: bool,
+    /// Physics entity that receives the map's collision as a fixed body; `None` exports none.
+    pub collision: Option<EntityId>,
+}
+
+impl Tilemap {
+    #[inline]
+    pub fn new(asset: impl Into<String>) -> Self {
+        Self {
+            asset: asset.into(),
+            position: [0.0, 0.0],
+            pixel_size: 1.0,
+            layer: 0,
+            visible: true,
+            collision: None,
+        }
+    }
+
+    #[inline]
+    pub fn with_position(mut self, position: [f32; 2]) -> Self {
+        self.position = position;
+        self
+    }
+
+    #[inline]
+    pub fn with_pixel_size(mut self, pixel_size: f32) -> Self {
+        self.pixel_size = pixel_size;
+        self
+    }
+
+    #[inline]
+    pub fn with_layer(mut self, layer: i32) -> Self {
+        self.layer = layer;
+        self
+    }
+
+    #[inline]
+    pub fn with_visible(mut self, visible: bool) -> Self {
+        self.visible = visible;
+        self
+    }
+
+    #[inline]
+    pub fn with_collision(mut self, entity: EntityId) -> Self {
+        self.collision = Some(entity);
+        self
+    }
+
+    /// World position of map pixel `p` (x right, y down).
+    #[inline]
+    pub(crate) fn to_world(&self, p: [f32; 2]) -> [f32; 2] {
+        [
+            self.position[0] + p[0] * self.pixel_size,
+            self.position[1] - p[1] * self.pixel_size,
+        ]
+    }
+}
@@ -0,0 +1,46 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use std::io::Cursor;
+
+/// Upper bound on tileset image texels.
+const MAX_TEXELS: u64 = 8192 * 8192;
+
+/// Decodes a PNG tileset image into RGBA8 rows, top to bottom.
+pub(crate) fn decode_png_rgba8(bytes: &[u8]) -> Result<(u32, u32, Vec<u8>), String> {
+    let mut decoder = png::Decoder::new(Cursor::new(bytes));
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info().map_err(|e| format!("png: {e}"))?;
+
+    let (width, height) = (reader.info().width, reader.info().height);
+    if width as u64 * height as u64 > MAX_TEXELS {
+        return Err(format!("png: {width}x{height} is too large"));
+    }
+
+    let size = reader
+        .output_buffer_size()
+        .ok_or_else(|| "png: image too large".to_string())?;
+    let mut buf = vec![0u8; size];
+    let frame = reader
+        .next_frame(&mut buf)
+        .map_err(|e| format!("png: {e}"))?;
+    buf.truncate(frame.buffer_size());
+
+    let texels = (width * height) as usize;
+    let rgba = match frame.color_type {
+        png::ColorType::Rgba => buf,
+        png::ColorType::Rgb => buf
+            .chunks_exact(3)
+            .flat_map(|c| [c[0], c[1], c[2], 255])
+            .collect(),
+        png::ColorType::GrayscaleAlpha => buf
+            .chunks_exact(2)
+            .flat_map(|c| [c[0], c[0], c[0], c[1]])
+            .collect(),
+        png::ColorType::Grayscale => buf.iter().flat_map(|&g| [g, g, g, 255]).collect(),
+        png::ColorType::Indexed => return Err("png: palette was not expanded".to_string()),
+    };
+    if rgba.len() != texels * 4 {
+        return Err(format!("png: unexpected row layout for {width}x{height}"));
+    }
+    Ok((width, height, rgba))
+}
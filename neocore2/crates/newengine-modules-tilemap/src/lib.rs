@@ -0,0 +1,28 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+mod api;
+mod collision;
+mod component;
+mod image;
+mod map;
+mod module;
+
+pub use api::{TilemapApiRef, TilemapStats};
+pub use component::Tilemap;
+pub use module::{TilemapConfig, TilemapModule};
+
+use newengine_core::{ApiProvide, ApiVersion};
+
+pub const TILEMAP_API_ID: &str = "tilemap.api";
+pub const TILEMAP_API_VERSION: ApiVersion = ApiVersion::new(0, 1, 0);
+pub const TILEMAP_API_PROVIDE: ApiProvide = ApiProvide::new(TILEMAP_API_ID, TILEMAP_API_VERSION);
+
+/// Key of a tilemap component; callers pick the ids, as with physics entities.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TilemapId(pub u64);
+
+impl std::fmt::Display for TilemapId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
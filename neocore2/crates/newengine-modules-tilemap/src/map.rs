@@ -0,0 +1,240 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use newengine_assets::{
+    TileLayer, TiledMap, TilemapAsset, TilemapLayer, Tileset, GID_FLIP_D, GID_FLIP_H, GID_FLIP_V,
+    GID_MASK,
+};
+use newengine_core::render::TextureId;
+use newengine_modules_sprite2d::Sprite;
+use std::sync::Arc;
+
+use crate::component::Tilemap;
+
+/// Tileset of a loaded map with its image on the GPU.
+pub(crate) struct ResolvedTileset {
+    pub(crate) first_gid: u32,
+    pub(crate) tileset: Tileset,
+    pub(crate) texture: TextureId,
+    /// Decoded image size; UVs use it rather than the size Tiled recorded.
+    pub(crate) image_size: [u32; 2],
+}
+
+/// A map whose tilesets and textures are all available.
+pub(crate) struct LoadedMap {
+    pub(crate) asset: Arc<TilemapAsset>,
+    pub(crate) map: TiledMap,
+    /// Sorted by `first_gid`.
+    pub(crate) tilesets: Vec<ResolvedTileset>,
+}
+
+/// Per-layer sprite settings.
+struct LayerDraw<'a> {
+    layer: &'a TileLayer,
+    color: [f32; 4],
+    sprite_layer: i32,
+}
+
+/// Counters of one [`LoadedMap::sprites`] call.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct DrawCounts {
+    pub(crate) chunks: usize,
+    pub(crate) culled: usize,
+    pub(crate) tiles: usize,
+}
+
+impl LoadedMap {
+    pub(crate) fn new(
+        asset: Arc<TilemapAsset>,
+        map: TiledMap,
+        mut tilesets: Vec<ResolvedTileset>,
+    ) -> Self {
+        tilesets.sort_by_key(|t| t.first_gid);
+        Self {
+            asset,
+            map,
+            tilesets,
+        }
+    }
+
+    /// Tileset owning `gid` (flags cleared) and the tile's index in it.
+    pub(crate) fn tileset_for(&self, gid: u32) -> Option<(&ResolvedTileset, u32)> {
+        let i = self.tilesets.partition_point(|t| t.first_gid <= gid);
+        let ts = self.tilesets.get(i.checked_sub(1)?)?;
+        Some((ts, gid - ts.first_gid))
+    }
+
+    pub(crate) fn tile_layers(&self) -> impl Iterator<Item = &TileLayer> {
+        self.map.layers.iter().filter_map(|l| match l {
+            TilemapLayer::Tiles(t) => Some(t),
+            TilemapLayer::Objects(_) => None,
+        })
+    }
+
+    /// Pixel box `[x, y, w, h]` (y down) the image of the tile in cell (`tx`, `ty`) covers:
+    /// Tiled aligns tile images to the bottom-left of their cell.
+    pub(crate) fn tile_image_rect(
+        &self,
+        layer: &TileLayer,
+        ts: &ResolvedTileset,
+        tx: i32,
+        ty: i32,
+    ) -> [f32; 4] {
+        let (w, h) = (ts.tileset.tile_width as f32, ts.tileset.tile_height as f32);
+        let x =
+            tx as f32 * self.map.tile_width as f32 + layer.offset[0] + ts.tileset.tile_offset[0];
+        let bottom = (ty + 1) as f32 * self.map.tile_height as f32
+            + layer.offset[1]
+            + ts.tileset.tile_offset[1];
+        [x, bottom - h, w, h]
+    }
+
+    /// Appends a sprite per tile of every visible tile layer whose chunk overlaps `view`
+    /// (world `[min_x, min_y, max_x, max_y]`; `None` draws everything).
+    pub(crate) fn sprites(
+        &self,
+        comp: &Tilemap,
+        view: Option<[f32; 4]>,
+        out: &mut Vec<Sprite>,
+    ) -> DrawCounts {
+        let mut counts = DrawCounts::default();
+        let cs = self.map.chunk_size as i32;
+        let (tw, th) = (self.map.tile_width as f32, self.map.tile_height as f32);
+        // Tiles larger than the grid reach up and right out of their chunk.
+        let overhang = self.tilesets.iter().fold([0.0f32; 2], |acc, t| {
+            [
+                acc[0].max(t.tileset.tile_width as f32 - tw + t.tileset.tile_offset[0].abs()),
+                acc[1].max(t.tileset.tile_height as f32 - th + t.tileset.tile_offset[1].abs()),
+            ]
+        });
+
+        for (index, layer) in self.tile_layers().enumerate() {
+            if !layer.visible || layer.opacity <= 0.0 {
+                continue;
+            }
+            let draw = LayerDraw {
+                layer,
+                color: [1.0, 1.0, 1.0, layer.opacity.clamp(0.0, 1.0)],
+                sprite_layer: comp.layer.saturating_add(index as i32),
+            };
+
+            for chunk in &layer.chunks {
+                let x0 = chunk.x as f32 * tw + layer.offset[0];
+                let y0 = chunk.y as f32 * th + layer.offset[1];
+                let bounds = [
+                    x0 - overhang[0],
+                    y0 - overhang[1],
+                    x0 + cs as f32 * tw + overhang[0],
+                    y0 + cs as f32 * th + overhang[1],
+                ];
+                if let Some(view) = view {
+                    if !overlaps(world_box(comp, bounds), view) {
+                        counts.culled += 1;
+                        continue;
+                    }
+                }
+                counts.chunks += 1;
+
+                let gids = self.asset.chunk_gids(self.map.chunk_size, chunk);
+                for (i, &raw) in gids.iter().enumerate() {
+                    let gid = raw & GID_MASK;
+                    if gid == 0 {
+                        continue;
+                    }
+                    let Some((ts, local)) = self.tileset_for(gid) else {
+                        continue;
+                    };
+                    let cell = [chunk.x + i as i32 % cs, chunk.y + i as i32 / cs];
+                    out.push(self.tile_sprite(comp, &draw, ts, local, raw, cell));
+                    counts.tiles += 1;
+                }
+            }
+        }
+        counts
+    }
+
+    fn tile_sprite(
+        &self,
+        comp: &Tilemap,
+        draw: &LayerDraw<'_>,
+        ts: &ResolvedTileset,
+        local: u32,
+        raw_gid: u32,
+        [tx, ty]: [i32; 2],
+    ) -> Sprite {
+        let [rx, ry, rw, rh] = ts.tileset.tile_rect(local).map(|v| v as f32);
+        let (iw, ih) = (
+            ts.image_size[0].max(1) as f32,
+            ts.image_size[1].max(1) as f32,
+        );
+        let mut uv = [rx / iw, ry / ih, (rx + rw) / iw, (ry + rh) / ih];
+
+        // Tiled applies the diagonal flip first; as a sprite that is a 90 degree clockwise turn
+        // of the image with the other two flips exchanged (and the vertical one inverted).
+        let (h, v, d) = (
+            raw_gid & GID_FLIP_H != 0,
+            raw_gid & GID_FLIP_V != 0,
+            raw_gid & GID_FLIP_D != 0,
+        );
+        let (flip_u, flip_v, rotation) = if d {
+            (v, !h, -std::f32::consts::FRAC_PI_2)
+        } else {
+            (h, v, 0.0)
+        };
+        if flip_u {
+            uv.swap(0, 2);
+        }
+        if flip_v {
+            uv.swap(1, 3);
+        }
+
+        // A turned tile shows its image transposed: as wide as it was tall.
+        let [x, top, _, _] = self.tile_image_rect(draw.layer, ts, tx, ty);
+        let bottom = top + rh;
+        let (vis_w, vis_h) = if d { (rh, rw) } else { (rw, rh) };
+        let center = [x + vis_w * 0.5, bottom - vis_h * 0.5];
+
+        Sprite::new([rw * comp.pixel_size, rh * comp.pixel_size])
+            .with_texture(ts.texture)
+            .with_uv_rect(uv)
+            .with_position(comp.to_world(center))
+            .with_rotation(rotation)
+            .with_color(draw.color)
+            .with_layer(draw.sprite_layer)
+    }
+}
+
+/// World box of map pixel box `[x0, y0, x1, y1]` (y down).
+#[inline]
+fn world_box(comp: &Tilemap, b: [f32; 4]) -> [f32; 4] {
+    let [ax, ay] = comp.to_world([b[0], b[1]]);
+    let [bx, by] = comp.to_world([b[2], b[3]]);
+    [ax.min(bx), ay.min(by), ax.max(bx), ay.max(by)]
+}
+
+#[inline]
+fn overlaps(a: [f32; 4], b: [f32; 4]) -> bool {
+    a[0] <= b[2] && b[0] <= a[2] && a[1] <= b[3] && b[1] <= a[3]
+}
+
+/// Resolves `rel`, as written in the asset file `base`, into a logical path.
+pub(crate) fn resolve_path(base: &str, rel: &str) -> String {
+    let rel = rel.replace('\\', "/");
+    let mut parts: Vec<&str> = if rel.starts_with('/') {
+        Vec::new()
+    } else {
+        base.rsplit_once('/')
+            .map(|(dir, _)| dir.split('/').collect())
+            .unwrap_or_default()
+    };
+    for part in rel.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            p => parts.push(p),
+        }
+    }
+    parts.retain(|p| !p.is_empty());
+    parts.join("/")
+}
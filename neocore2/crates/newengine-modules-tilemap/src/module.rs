@@ -0,0 +1,376 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use newengine_assets::{AssetId, AssetState, TiledMap, TilemapAsset, TilemapDoc, Tileset};
+use newengine_core::assets::AssetManager;
+use newengine_core::render::{RenderApi, RenderApiRef, TextureId, RENDER_API_ID};
+use newengine_core::{ApiProvide, EngineResult, Module, ModuleCtx};
+use newengine_modules_physics::{PhysicsApiRef, PHYSICS_API_ID};
+use newengine_modules_sprite2d::{Sprite2dApiRef, SPRITE2D_API_ID};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::api::TilemapApiRef;
+use crate::image::decode_png_rgba8;
+use crate::map::{resolve_path, LoadedMap, ResolvedTileset};
+use crate::{TILEMAP_API_ID, TILEMAP_API_PROVIDE};
+
+#[derive(Debug, Clone)]
+pub struct TilemapConfig {
+    /// Extent along z of the exported collision boxes, in world units.
+    pub collision_depth: f32,
+}
+
+impl TilemapConfig {
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            collision_depth: 1.0,
+        }
+    }
+
+    #[inline]
+    pub fn with_collision_depth(mut self, depth: f32) -> Self {
+        self.collision_depth = depth;
+        self
+    }
+}
+
+impl Default for TilemapConfig {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Outcome of polling one asset.
+enum Poll<T> {
+    Pending,
+    Ready(T),
+    Failed(String),
+}
+
+fn poll_asset(am: &AssetManager, id: AssetId) -> Poll<Arc<TilemapAsset>> {
+    match am.state(id) {
+        AssetState::Ready => match am.get_typed::<TilemapAsset>(id) {
+            Ok(asset) => Poll::Ready(asset),
+            Err(e) => Poll::Failed(e.to_string()),
+        },
+        AssetState::Failed(e) => Poll::Failed(e.to_string()),
+        AssetState::Unloaded | AssetState::Loading => Poll::Pending,
+    }
+}
+
+/// Tileset image shared by every map that uses it.
+enum ImageEntry {
+    Loading(AssetId),
+    Ready { texture: TextureId, size: [u32; 2] },
+    Failed,
+}
+
+/// Tileset of a map being loaded.
+struct PendingTileset {
+    first_gid: u32,
+    /// External `.tsx` still loading.
+    source: Option<(String, AssetId)>,
+    /// The tileset and the logical path of its image, once known.
+    tileset: Option<(Tileset, String)>,
+}
+
+/// Map whose `.tmx`, tilesets or images are still loading.
+struct PendingMap {
+    path: String,
+    id: AssetId,
+    map: Option<(Arc<TilemapAsset>, TiledMap)>,
+    tilesets: Vec<PendingTileset>,
+}
+
+/// Draws Tiled maps through the sprite layer and exposes them as `tilemap.api`.
+///
+/// Map assets load through the AssetManager when a tilemap first names them, followed by
+/// their external tilesets and tileset images (PNG), which become sprite textures shared
+/// across maps. Each `update` exports changed map collision to `physics.api` (when present)
+/// and queues the tiles of the chunks inside the camera view.
+pub struct TilemapModule {
+    config: TilemapConfig,
+    api: TilemapApiRef,
+    pending: Vec<PendingMap>,
+    images: HashMap<String, ImageEntry>,
+}
+
+impl TilemapModule {
+    #[inline]
+    pub fn new(config: TilemapConfig) -> Self {
+        Self {
+            config,
+            api: TilemapApiRef::new(),
+            pending: Vec::new(),
+            images: HashMap::new(),
+        }
+    }
+
+    /// Handle for consumers living outside the engine.
+    #[inline]
+    pub fn api(&self) -> TilemapApiRef {
+        self.api.clone()
+    }
+
+    fn load_requested(&mut self, am: &AssetManager) {
+        for path in self.api.take_requests() {
+            match am.store().load_path(&path) {
+                Ok(id) => self.pending.push(PendingMap {
+                    path,
+                    id,
+                    map: None,
+                    tilesets: Vec::new(),
+                }),
+                Err(e) => {
+                    log::warn!(target: "tilemap", "map.load rejected path='{path}' err='{e}'");
+                    self.api.load_failed(&path);
+                }
+            }
+        }
+    }
+
+    /// Advances every pending map; finished ones move to the API.
+    fn advance(&mut self, am: &AssetManager, render: &mut dyn RenderApi, sprites: &Sprite2dApiRef) {
+        let mut pending = std::mem::take(&mut self.pending);
+        pending.retain_mut(|p| match self.advance_map(am, render, sprites, p) {
+            Poll::Pending => true,
+            Poll::Ready(map) => {
+                log::info!(
+                    target: "tilemap",
+                    "map.loaded path='{}' layers={} tilesets={}",
+                    p.path,
+                    map.map.layers.len(),
+                    map.tilesets.len()
+                );
+                self.api.set_loaded(p.path.clone(), map);
+                false
+            }
+            Poll::Failed(e) => {
+                log::warn!(target: "tilemap", "map.load failed path='{}' err='{e}'", p.path);
+                self.api.load_failed(&p.path);
+                false
+            }
+        });
+        self.pending = pending;
+    }
+
+    fn advance_map(
+        &mut self,
+        am: &AssetManager,
+        render: &mut dyn RenderApi,
+        sprites: &Sprite2dApiRef,
+        p: &mut PendingMap,
+    ) -> Poll<LoadedMap> {
+        if p.map.is_none() {
+            let asset = match poll_asset(am, p.id) {
+                Poll::Ready(asset) => asset,
+                Poll::Pending => return Poll::Pending,
+                Poll::Failed(e) => return Poll::Failed(e),
+            };
+            let map = match &asset.doc {
+                TilemapDoc::Map(map) => map.clone(),
+                TilemapDoc::Tileset { .. } => {
+                    return Poll::Failed("asset is a tileset, not a map".to_string())
+                }
+            };
+            if map.orientation != "orthogonal" {
+                return Poll::Failed(format!("unsupported orientation '{}'", map.orientation));
+            }
+
+            for r in &map.tilesets {
+                let mut ts = PendingTileset {
+                    first_gid: r.first_gid,
+                    source: None,
+                    tileset: None,
+                };
+                match (&r.tileset, &r.source) {
+                    (Some(tileset), _) => {
+                        let image = resolve_path(&p.path, &tileset.image);
+                        ts.tileset = Some((tileset.clone(), image));
+                    }
+                    (None, Some(source)) => {
+                        let source = resolve_path(&p.path, source);
+                        match am.store().load_path(&source) {
+                            Ok(id) => ts.source = Some((source, id)),
+                            Err(e) => return Poll::Failed(format!("tileset '{source}': {e}")),
+                        }
+                    }
+                    (None, None) => {
+                        return Poll::Failed(format!("tileset {} has no source", r.first_gid))
+                    }
+                }
+                p.tilesets.push(ts);
+            }
+            p.map = Some((asset, map));
+        }
+
+        let mut ready = true;
+        for ts in &mut p.tilesets {
+            if let Some((source, id)) = &ts.source {
+                let asset = match poll_asset(am, *id) {
+                    Poll::Ready(asset) => asset,
+                    Poll::Pending => {
+                        ready = false;
+                        continue;
+                    }
+                    Poll::Failed(e) => return Poll::Failed(format!("tileset '{source}': {e}")),
+                };
+                let TilemapDoc::Tileset { tileset } = &asset.doc else {
+                    return Poll::Failed(format!("'{source}' is not a tileset"));
+                };
+                let image = resolve_path(source, &tileset.image);
+                ts.tileset = Some((tileset.clone(), image));
+                ts.source = None;
+            }
+
+            let Some((_, image)) = &ts.tileset else {
+                continue;
+            };
+            match self.poll_image(am, render, sprites, image) {
+                Poll::Ready(_) => {}
+                Poll::Pending => ready = false,
+                Poll::Failed(e) => return Poll::Failed(format!("image '{image}': {e}")),
+            }
+        }
+        if !ready {
+            return Poll::Pending;
+        }
+
+        let Some((asset, map)) = p.map.take() else {
+            return Poll::Pending;
+        };
+        let mut tilesets = Vec::with_capacity(p.tilesets.len());
+        for ts in p.tilesets.drain(..) {
+            let Some((tileset, image)) = ts.tileset else {
+                continue;
+            };
+            if let Some(ImageEntry::Ready { texture, size }) = self.images.get(&image) {
+                tilesets.push(ResolvedTileset {
+                    first_gid: ts.first_gid,
+                    tileset,
+                    texture: *texture,
+                    image_size: *size,
+                });
+            }
+        }
+        Poll::Ready(LoadedMap::new(asset, map, tilesets))
+    }
+
+    /// Loads a tileset image and uploads it as a sprite texture, once per path.
+    fn poll_image(
+        &mut self,
+        am: &AssetManager,
+        render: &mut dyn RenderApi,
+        sprites: &Sprite2dApiRef,
+        path: &str,
+    ) -> Poll<()> {
+        let id = match self.images.get(path) {
+            Some(ImageEntry::Ready { .. }) => return Poll::Ready(()),
+            Some(ImageEntry::Failed) => return Poll::Failed("failed earlier".to_string()),
+            Some(ImageEntry::Loading(id)) => *id,
+            None => match am.store().load_path(path) {
+                Ok(id) => {
+                    self.images
+                        .insert(path.to_string(), ImageEntry::Loading(id));
+                    id
+                }
+                Err(e) => {
+                    self.images.insert(path.to_string(), ImageEntry::Failed);
+                    return Poll::Failed(e.to_string());
+                }
+            },
+        };
+
+        let uploaded = match am.state(id) {
+            AssetState::Unloaded | AssetState::Loading => return Poll::Pending,
+            AssetState::Failed(e) => Err(e.to_string()),
+            AssetState::Ready => am
+                .get_blob(id)
+                .ok_or_else(|| "blob missing".to_string())
+                .and_then(|blob| decode_png_rgba8(&blob.payload))
+                .and_then(|(w, h, rgba)| {
+                    sprites
+                        .create_texture(render, w, h, &rgba)
+                        .map(|texture| (texture, [w, h]))
+                        .map_err(|e| e.to_string())
+                }),
+        };
+        match uploaded {
+            Ok((texture, size)) => {
+                self.images
+                    .insert(path.to_string(), ImageEntry::Ready { texture, size });
+                Poll::Ready(())
+            }
+            Err(e) => {
+                self.images.insert(path.to_string(), ImageEntry::Failed);
+                Poll::Failed(e)
+            }
+        }
+    }
+}
+
+impl<E: Send + 'static> Module<E> for TilemapModule {
+    fn id(&self) -> &'static str {
+        "tilemap"
+    }
+
+    fn provides(&self) -> &'static [ApiProvide] {
+        &[TILEMAP_API_PROVIDE]
+    }
+
+    fn init(&mut self, ctx: &mut ModuleCtx<'_, E>) -> EngineResult<()> {
+        ctx.resources_mut()
+            .register_api(TILEMAP_API_ID, self.api.clone())
+    }
+
+    fn update(&mut self, ctx: &mut ModuleCtx<'_, E>) -> EngineResult<()> {
+        let Some(sprites) = ctx.api::<Sprite2dApiRef>(SPRITE2D_API_ID).cloned() else {
+            return Ok(());
+        };
+
+        if let Some(am) = ctx.resources().get::<AssetManager>() {
+            self.load_requested(am);
+            if !self.pending.is_empty() {
+                if let Some(render) = ctx.api::<RenderApiRef>(RENDER_API_ID) {
+                    self.advance(am, &mut **render.lock(), &sprites);
+                }
+            }
+        }
+
+        if let Some(physics) = ctx.api::<PhysicsApiRef>(PHYSICS_API_ID) {
+            self.api
+                .export_collision(physics, self.config.collision_depth);
+        }
+        self.api.draw(&sprites);
+        Ok(())
+    }
+
+    fn shutdown(&mut self, ctx: &mut ModuleCtx<'_, E>) -> EngineResult<()> {
+        let exported = self.api.take_exported();
+        if let Some(physics) = ctx.api::<PhysicsApiRef>(PHYSICS_API_ID) {
+            for entity in exported {
+                physics.remove(entity);
+            }
+        }
+
+        let sprites = ctx.api::<Sprite2dApiRef>(SPRITE2D_API_ID).cloned();
+        if let (Some(sprites), Some(render)) = (sprites, ctx.api::<RenderApiRef>(RENDER_API_ID)) {
+            let mut render = render.lock();
+            for image in self.images.values() {
+                if let ImageEntry::Ready { texture, .. } = image {
+                    sprites.destroy_texture(&mut **render, *texture);
+                }
+            }
+        }
+
+        let _ = ctx
+            .resources_mut()
+            .unregister_api::<TilemapApiRef>(TILEMAP_API_ID);
+        self.pending.clear();
+        self.images.clear();
+        self.api.clear();
+        Ok(())
+    }
+}
@@ -0,0 +1,496 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::io::ErrorKind;
+use std::net::{SocketAddr, UdpSocket};
+use std::sync::Arc;
+use std::time::Instant;
+
+use crate::connection::{ConnState, Connection, PendingReliable};
+use crate::error::NetError;
+use crate::message::{DisconnectReason, NetEvent, NetMessage, NetReceived};
+use crate::module::NetConfig;
+use crate::protocol::{decode_data_body, encode_data_body, Packet, PacketKind, MAX_PACKET_BYTES};
+use crate::{ConnectionId, NetChannel};
+
+/// Transport counters since the socket was bound.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NetStats {
+    pub packets_sent: u64,
+    pub packets_received: u64,
+    pub bytes_sent: u64,
+    pub bytes_received: u64,
+    pub resends: u64,
+    pub dropped_malformed: u64,
+}
+
+/// Snapshot of one peer.
+#[derive(Debug, Clone)]
+pub struct ConnectionInfo {
+    pub id: ConnectionId,
+    pub addr: SocketAddr,
+    pub connected: bool,
+    pub unacked: usize,
+}
+
+pub(crate) struct NetState {
+    config: NetConfig,
+    socket: Option<UdpSocket>,
+    accepting: bool,
+    conns: HashMap<ConnectionId, Connection>,
+    by_addr: HashMap<SocketAddr, ConnectionId>,
+    next_id: u32,
+    stats: NetStats,
+    events: Vec<NetEvent>,
+}
+
+impl NetState {
+    fn new(config: NetConfig) -> Self {
+        Self {
+            config,
+            socket: None,
+            accepting: false,
+            conns: HashMap::new(),
+            by_addr: HashMap::new(),
+            next_id: 0,
+            stats: NetStats::default(),
+            events: Vec::new(),
+        }
+    }
+
+    fn bind(&mut self, addr: SocketAddr) -> Result<SocketAddr, NetError> {
+        let socket = UdpSocket::bind(addr)?;
+        socket.set_nonblocking(true)?;
+        let local = socket.local_addr()?;
+        self.socket = Some(socket);
+        log::info!(target: "net", "socket.bind addr={local}");
+        Ok(local)
+    }
+
+    fn ensure_bound(&mut self) -> Result<(), NetError> {
+        if self.socket.is_none() {
+            self.bind(SocketAddr::from(([0, 0, 0, 0], 0)))?;
+        }
+        Ok(())
+    }
+
+    fn send_raw(&mut self, addr: SocketAddr, bytes: &[u8]) {
+        let Some(socket) = self.socket.as_ref() else {
+            return;
+        };
+        match socket.send_to(bytes, addr) {
+            Ok(n) => {
+                self.stats.packets_sent += 1;
+                self.stats.bytes_sent += n as u64;
+            }
+            // Full send buffer: reliable data is resent, unreliable data is allowed to drop.
+            Err(e) if e.kind() == ErrorKind::WouldBlock => {}
+            Err(e) => log::debug!(target: "net", "send failed addr={addr} err='{e}'"),
+        }
+    }
+
+    fn send_control(&mut self, addr: SocketAddr, kind: PacketKind) {
+        let bytes = Packet::control(kind).encode();
+        self.send_raw(addr, &bytes);
+    }
+
+    fn alloc_id(&mut self) -> ConnectionId {
+        self.next_id = self.next_id.wrapping_add(1).max(1);
+        ConnectionId(self.next_id)
+    }
+
+    fn add_connection(&mut self, addr: SocketAddr, state: ConnState, now: Instant) -> ConnectionId {
+        let id = self.alloc_id();
+        self.conns.insert(id, Connection::new(id, addr, state, now));
+        self.by_addr.insert(addr, id);
+        id
+    }
+
+    fn drop_connection(&mut self, id: ConnectionId, reason: DisconnectReason) {
+        let Some(conn) = self.conns.remove(&id) else {
+            return;
+        };
+        self.by_addr.remove(&conn.addr);
+        log::info!(target: "net", "conn.closed id={id} addr={} reason={reason:?}", conn.addr);
+        self.events.push(NetEvent::Disconnected {
+            conn: id,
+            addr: conn.addr,
+            reason,
+        });
+    }
+
+    fn send_data(
+        &mut self,
+        id: ConnectionId,
+        channel: NetChannel,
+        kind: &str,
+        payload: &[u8],
+    ) -> Result<(), NetError> {
+        let body = encode_data_body(kind, payload)?;
+        let now = Instant::now();
+
+        let conn = self
+            .conns
+            .get_mut(&id)
+            .ok_or(NetError::UnknownConnection(id))?;
+
+        let seq = match channel {
+            NetChannel::Reliable => conn.take_reliable_seq(),
+            NetChannel::Unreliable => conn.take_unreliable_seq(),
+        };
+        let bytes = Packet {
+            kind: PacketKind::Data,
+            channel,
+            seq,
+            ack: 0,
+            body: &body,
+        }
+        .encode();
+
+        conn.last_send = now;
+        let addr = conn.addr;
+        let connected = conn.state == ConnState::Connected;
+
+        if channel == NetChannel::Reliable {
+            conn.unacked.insert(
+                seq,
+                PendingReliable {
+                    bytes: bytes.clone(),
+                    sent_at: now,
+                    tries: u32::from(connected),
+                },
+            );
+        }
+
+        // Reliable data queued while connecting goes out on the first resend after `Accept`.
+        if connected {
+            self.send_raw(addr, &bytes);
+        }
+        Ok(())
+    }
+
+    fn handle_datagram(&mut self, addr: SocketAddr, buf: &[u8], now: Instant) {
+        let packet = match Packet::decode(buf) {
+            Ok(p) => p,
+            Err(e) => {
+                self.stats.dropped_malformed += 1;
+                log::debug!(target: "net", "drop addr={addr} err='{e}'");
+                return;
+            }
+        };
+
+        let known = self.by_addr.get(&addr).copied();
+
+        let Some(id) = known else {
+            if packet.kind == PacketKind::Connect {
+                self.accept_incoming(addr, now);
+            }
+            return;
+        };
+
+        let Some(conn) = self.conns.get_mut(&id) else {
+            return;
+        };
+        conn.last_recv = now;
+
+        match packet.kind {
+            PacketKind::Connect => {
+                // Our `Accept` was lost; answer again.
+                self.send_control(addr, PacketKind::Accept);
+            }
+            PacketKind::Accept => {
+                if conn.state == ConnState::Connecting {
+                    conn.state = ConnState::Connected;
+                    log::info!(target: "net", "conn.open id={id} addr={addr} side=client");
+                    self.events.push(NetEvent::Connected { conn: id, addr });
+                }
+            }
+            PacketKind::Disconnect => self.drop_connection(id, DisconnectReason::Remote),
+            PacketKind::Ack => {
+                conn.unacked.remove(&packet.ack);
+            }
+            PacketKind::Ping => {}
+            PacketKind::Data => self.handle_data(id, addr, &packet),
+        }
+    }
+
+    fn accept_incoming(&mut self, addr: SocketAddr, now: Instant) {
+        if !self.accepting {
+            return;
+        }
+        if self.conns.len() >= self.config.max_connections {
+            log::warn!(target: "net", "conn.rejected addr={addr} reason=server_full");
+            self.send_control(addr, PacketKind::Disconnect);
+            return;
+        }
+
+        let id = self.add_connection(addr, ConnState::Connected, now);
+        self.send_control(addr, PacketKind::Accept);
+        log::info!(target: "net", "conn.open id={id} addr={addr} side=server");
+        self.events.push(NetEvent::Connected { conn: id, addr });
+    }
+
+    fn handle_data(&mut self, id: ConnectionId, addr: SocketAddr, packet: &Packet<'_>) {
+        let (kind, payload) = match decode_data_body(packet.body) {
+            Ok(v) => v,
+            Err(e) => {
+                self.stats.dropped_malformed += 1;
+                log::debug!(target: "net", "drop id={id} err='{e}'");
+                return;
+            }
+        };
+
+        let Some(conn) = self.conns.get_mut(&id) else {
+            return;
+        };
+
+        let delivered = match packet.channel {
+            NetChannel::Unreliable => {
+                if conn.accept_unreliable(packet.seq) {
+                    vec![(kind, payload)]
+                } else {
+                    Vec::new()
+                }
+            }
+            NetChannel::Reliable => {
+                let ack = Packet {
+                    ack: packet.seq,
+                    ..Packet::control(PacketKind::Ack)
+                }
+                .encode();
+                match conn.accept_reliable(packet.seq, kind, payload) {
+                    Some(msgs) => {
+                        self.send_raw(addr, &ack);
+                        msgs
+                    }
+                    None => {
+                        self.drop_connection(id, DisconnectReason::Unreachable);
+                        return;
+                    }
+                }
+            }
+        };
+
+        for (kind, payload) in delivered {
+            self.events.push(NetEvent::Message(NetReceived {
+                conn: id,
+                channel: packet.channel,
+                kind,
+                payload,
+            }));
+        }
+    }
+
+    /// Receives pending datagrams, drives resends/keepalives/timeouts and returns new events.
+    fn pump(&mut self, now: Instant) -> Vec<NetEvent> {
+        let mut buf = [0u8; MAX_PACKET_BYTES];
+        while let Some(socket) = self.socket.as_ref() {
+            match socket.recv_from(&mut buf) {
+                Ok((n, addr)) => {
+                    self.stats.packets_received += 1;
+                    self.stats.bytes_received += n as u64;
+                    self.handle_datagram(addr, &buf[..n], now);
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                // Windows reports ICMP port unreachable as ConnectionReset on UDP sockets.
+                Err(e) if e.kind() == ErrorKind::ConnectionReset => continue,
+                Err(e) => {
+                    log::warn!(target: "net", "recv failed err='{e}'");
+                    break;
+                }
+            }
+        }
+
+        self.service_connections(now);
+        std::mem::take(&mut self.events)
+    }
+
+    fn service_connections(&mut self, now: Instant) {
+        let cfg = self.config.clone();
+        let mut out: Vec<(SocketAddr, Vec<u8>)> = Vec::new();
+        let mut dead: Vec<(ConnectionId, DisconnectReason)> = Vec::new();
+        let mut resends = 0u64;
+
+        for conn in self.conns.values_mut() {
+            if now.duration_since(conn.last_recv) >= cfg.timeout {
+                dead.push((conn.id, DisconnectReason::Timeout));
+                continue;
+            }
+
+            match conn.state {
+                ConnState::Connecting => {
+                    if now.duration_since(conn.last_send) >= cfg.connect_retry {
+                        out.push((conn.addr, Packet::control(PacketKind::Connect).encode()));
+                        conn.last_send = now;
+                    }
+                }
+                ConnState::Connected => {
+                    for pending in conn.unacked.values_mut() {
+                        if pending.tries > 0
+                            && now.duration_since(pending.sent_at) < cfg.resend_interval
+                        {
+                            continue;
+                        }
+                        if pending.tries >= cfg.max_resends {
+                            dead.push((conn.id, DisconnectReason::Unreachable));
+                            break;
+                        }
+                        if pending.tries > 0 {
+                            resends += 1;
+                        }
+                        pending.tries += 1;
+                        pending.sent_at = now;
+                        out.push((conn.addr, pending.bytes.clone()));
+                        conn.last_send = now;
+                    }
+
+                    if now.duration_since(conn.last_send) >= cfg.keepalive {
+                        out.push((conn.addr, Packet::control(PacketKind::Ping).encode()));
+                        conn.last_send = now;
+                    }
+                }
+            }
+        }
+
+        self.stats.resends += resends;
+        for (addr, bytes) in out {
+            self.send_raw(addr, &bytes);
+        }
+        for (id, reason) in dead {
+            self.drop_connection(id, reason);
+        }
+    }
+}
+
+/// Shared handle registered in `Resources` under [`crate::NET_API_ID`].
+///
+/// All calls are non-blocking; received data and connection changes surface as [`NetEvent`]s
+/// on the engine `EventHub` when `NetModule` pumps the socket.
+#[derive(Clone)]
+pub struct NetApiRef(Arc<Mutex<NetState>>);
+
+impl NetApiRef {
+    pub(crate) fn new(config: NetConfig) -> Self {
+        Self(Arc::new(Mutex::new(NetState::new(config))))
+    }
+
+    /// Binds `addr` and accepts incoming connections (server side).
+    pub fn listen(&self, addr: SocketAddr) -> Result<SocketAddr, NetError> {
+        let mut g = self.0.lock();
+        let local = g.bind(addr)?;
+        g.accepting = true;
+        Ok(local)
+    }
+
+    /// Starts connecting to `addr`; `NetEvent::Connected` follows once the peer accepts.
+    /// Binds an ephemeral port if nothing is bound yet.
+    pub fn connect(&self, addr: SocketAddr) -> Result<ConnectionId, NetError> {
+        let mut g = self.0.lock();
+        g.ensure_bound()?;
+
+        if let Some(&id) = g.by_addr.get(&addr) {
+            return Ok(id);
+        }
+
+        let id = g.add_connection(addr, ConnState::Connecting, Instant::now());
+        g.send_control(addr, PacketKind::Connect);
+        log::info!(target: "net", "conn.connecting id={id} addr={addr}");
+        Ok(id)
+    }
+
+    /// Sends a disconnect to the peer and forgets it.
+    pub fn disconnect(&self, id: ConnectionId) {
+        let mut g = self.0.lock();
+        if let Some(addr) = g.conns.get(&id).map(|c| c.addr) {
+            g.send_control(addr, PacketKind::Disconnect);
+            g.drop_connection(id, DisconnectReason::Local);
+        }
+    }
+
+    /// Sends raw bytes tagged with `kind`.
+    #[inline]
+    pub fn send(
+        &self,
+        id: ConnectionId,
+        channel: NetChannel,
+        kind: &str,
+        payload: &[u8],
+    ) -> Result<(), NetError> {
+        self.0.lock().send_data(id, channel, kind, payload)
+    }
+
+    #[inline]
+    pub fn send_message<M: NetMessage>(&self, id: ConnectionId, msg: &M) -> Result<(), NetError> {
+        let payload = msg.encode()?;
+        self.send(id, M::CHANNEL, M::KIND, &payload)
+    }
+
+    /// Sends `msg` to every connected peer. Returns the number of peers it was queued for.
+    pub fn broadcast_message<M: NetMessage>(&self, msg: &M) -> Result<usize, NetError> {
+        let payload = msg.encode()?;
+        let mut g = self.0.lock();
+
+        let ids: Vec<ConnectionId> = g
+            .conns
+            .values()
+            .filter(|c| c.state == ConnState::Connected)
+            .map(|c| c.id)
+            .collect();
+
+        for &id in &ids {
+            g.send_data(id, M::CHANNEL, M::KIND, &payload)?;
+        }
+        Ok(ids.len())
+    }
+
+    pub fn connections(&self) -> Vec<ConnectionInfo> {
+        let g = self.0.lock();
+        let mut out: Vec<ConnectionInfo> = g
+            .conns
+            .values()
+            .map(|c| ConnectionInfo {
+                id: c.id,
+                addr: c.addr,
+                connected: c.state == ConnState::Connected,
+                unacked: c.unacked.len(),
+            })
+            .collect();
+        out.sort_by_key(|c| c.id);
+        out
+    }
+
+    #[inline]
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        self.0
+            .lock()
+            .socket
+            .as_ref()
+            .and_then(|s| s.local_addr().ok())
+    }
+
+    #[inline]
+    pub fn stats(&self) -> NetStats {
+        self.0.lock().stats
+    }
+
+    #[inline]
+    pub(crate) fn pump(&self) -> Vec<NetEvent> {
+        self.0.lock().pump(Instant::now())
+    }
+
+    /// Disconnects every peer and closes the socket.
+    pub(crate) fn close(&self) {
+        let mut g = self.0.lock();
+        let ids: Vec<ConnectionId> = g.conns.keys().copied().collect();
+        for id in ids {
+            if let Some(addr) = g.conns.get(&id).map(|c| c.addr) {
+                g.send_control(addr, PacketKind::Disconnect);
+            }
+            g.drop_connection(id, DisconnectReason::Local);
+        }
+        g.events.clear();
+        g.socket = None;
+        g.accepting = false;
+    }
+}
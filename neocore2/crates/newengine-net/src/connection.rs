@@ -0,0 +1,113 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use std::collections::BTreeMap;
+use std::net::SocketAddr;
+use std::time::Instant;
+
+use crate::protocol::seq_newer;
+use crate::ConnectionId;
+
+/// Out-of-order reliable packets buffered per connection before the gap is considered fatal.
+const MAX_REORDER: usize = 256;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ConnState {
+    /// Client side, waiting for `Accept`.
+    Connecting,
+    Connected,
+}
+
+#[derive(Debug)]
+pub(crate) struct PendingReliable {
+    pub bytes: Vec<u8>,
+    pub sent_at: Instant,
+    pub tries: u32,
+}
+
+#[derive(Debug)]
+pub(crate) struct Connection {
+    pub id: ConnectionId,
+    pub addr: SocketAddr,
+    pub state: ConnState,
+
+    pub last_recv: Instant,
+    pub last_send: Instant,
+
+    pub next_reliable_seq: u32,
+    pub next_unreliable_seq: u32,
+    pub unacked: BTreeMap<u32, PendingReliable>,
+
+    recv_reliable_next: u32,
+    recv_buffer: BTreeMap<u32, (String, Vec<u8>)>,
+    recv_unreliable_last: u32,
+}
+
+impl Connection {
+    pub fn new(id: ConnectionId, addr: SocketAddr, state: ConnState, now: Instant) -> Self {
+        Self {
+            id,
+            addr,
+            state,
+            last_recv: now,
+            last_send: now,
+            next_reliable_seq: 1,
+            next_unreliable_seq: 1,
+            unacked: BTreeMap::new(),
+            recv_reliable_next: 1,
+            recv_buffer: BTreeMap::new(),
+            recv_unreliable_last: 0,
+        }
+    }
+
+    #[inline]
+    pub fn take_reliable_seq(&mut self) -> u32 {
+        let seq = self.next_reliable_seq;
+        self.next_reliable_seq = self.next_reliable_seq.wrapping_add(1);
+        seq
+    }
+
+    #[inline]
+    pub fn take_unreliable_seq(&mut self) -> u32 {
+        let seq = self.next_unreliable_seq;
+        self.next_unreliable_seq = self.next_unreliable_seq.wrapping_add(1);
+        seq
+    }
+
+    /// Accepts a reliable packet and returns messages now deliverable in order.
+    ///
+    /// Returns `None` when the reorder window overflowed (the connection cannot recover).
+    pub fn accept_reliable(
+        &mut self,
+        seq: u32,
+        kind: String,
+        payload: Vec<u8>,
+    ) -> Option<Vec<(String, Vec<u8>)>> {
+        if seq != self.recv_reliable_next && !seq_newer(seq, self.recv_reliable_next) {
+            // Duplicate of something already delivered (our ack was lost).
+            return Some(Vec::new());
+        }
+
+        self.recv_buffer.entry(seq).or_insert((kind, payload));
+        if self.recv_buffer.len() > MAX_REORDER {
+            return None;
+        }
+
+        let mut out = Vec::new();
+        while let Some(msg) = self.recv_buffer.remove(&self.recv_reliable_next) {
+            out.push(msg);
+            self.recv_reliable_next = self.recv_reliable_next.wrapping_add(1);
+        }
+        Some(out)
+    }
+
+    /// Sequenced unreliable delivery: drops packets older than the newest seen.
+    #[inline]
+    pub fn accept_unreliable(&mut self, seq: u32) -> bool {
+        if self.recv_unreliable_last == 0 || seq_newer(seq, self.recv_unreliable_last) {
+            self.recv_unreliable_last = seq;
+            true
+        } else {
+            false
+        }
+    }
+}
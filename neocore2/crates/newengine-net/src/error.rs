@@ -0,0 +1,41 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use crate::ConnectionId;
+
+#[derive(Debug)]
+pub enum NetError {
+    Io(std::io::Error),
+    UnknownConnection(ConnectionId),
+    TooLarge {
+        size: usize,
+    },
+    KindTooLong,
+    Malformed(&'static str),
+    Codec(String),
+}
+
+impl std::fmt::Display for NetError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NetError::Io(e) => write!(f, "net: io error: {e}"),
+            NetError::UnknownConnection(id) => write!(f, "net: unknown connection {id}"),
+            NetError::TooLarge { size } => write!(
+                f,
+                "net: message of {size} bytes exceeds {} byte datagram limit",
+                crate::MAX_PACKET_BYTES
+            ),
+            NetError::KindTooLong => write!(f, "net: message kind longer than 255 bytes"),
+            NetError::Malformed(what) => write!(f, "net: malformed packet: {what}"),
+            NetError::Codec(e) => write!(f, "net: message codec failed: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for NetError {}
+
+impl From<std::io::Error> for NetError {
+    #[inline]
+    fn from(e: std::io::Error) -> Self {
+        NetError::Io(e)
+    }
+}
@@ -0,0 +1,65 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+mod api;
+mod connection;
+mod error;
+mod message;
+mod module;
+mod protocol;
+
+pub use api::{ConnectionInfo, NetApiRef, NetStats};
+pub use error::NetError;
+pub use message::{DisconnectReason, NetEvent, NetMessage, NetReceived};
+pub use module::{NetConfig, NetModule};
+pub use protocol::MAX_PACKET_BYTES;
+
+use newengine_core::{ApiProvide, ApiVersion};
+
+pub const NET_API_ID: &str = "net.api";
+pub const NET_API_VERSION: ApiVersion = ApiVersion::new(0, 1, 0);
+pub const NET_API_PROVIDE: ApiProvide = ApiProvide::new(NET_API_ID, NET_API_VERSION);
+
+/// Peer handle; unique for the lifetime of a `NetModule`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ConnectionId(pub(crate) u32);
+
+impl ConnectionId {
+    #[inline]
+    pub fn get(self) -> u32 {
+        self.0
+    }
+}
+
+impl std::fmt::Display for ConnectionId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Delivery guarantee for a message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NetChannel {
+    /// Sequenced: may drop, never delivers an older packet after a newer one (state sync).
+    Unreliable,
+    /// Acked, resent and delivered in order (events, RPCs).
+    Reliable,
+}
+
+impl NetChannel {
+    #[inline]
+    pub(crate) fn to_u8(self) -> u8 {
+        match self {
+            Self::Unreliable => 0,
+            Self::Reliable => 1,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn from_u8(v: u8) -> Option<Self> {
+        match v {
+            0 => Some(Self::Unreliable),
+            1 => Some(Self::Reliable),
+            _ => None,
+        }
+    }
+}
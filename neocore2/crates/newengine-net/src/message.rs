@@ -0,0 +1,83 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::net::SocketAddr;
+
+use crate::error::NetError;
+use crate::{ConnectionId, NetChannel};
+
+/// Typed gameplay message. `KIND` routes it on the receiving side; keep it short and stable.
+///
+/// ```ignore
+/// #[derive(Serialize, Deserialize)]
+/// struct PlayerInput { dx: f32, dy: f32 }
+///
+/// impl NetMessage for PlayerInput {
+///     const KIND: &'static str = "game.input";
+///     const CHANNEL: NetChannel = NetChannel::Unreliable;
+/// }
+/// ```
+pub trait NetMessage: Serialize + DeserializeOwned + Send + 'static {
+    const KIND: &'static str;
+    const CHANNEL: NetChannel = NetChannel::Reliable;
+
+    #[inline]
+    fn encode(&self) -> Result<Vec<u8>, NetError> {
+        serde_json::to_vec(self).map_err(|e| NetError::Codec(e.to_string()))
+    }
+
+    #[inline]
+    fn decode(payload: &[u8]) -> Result<Self, NetError> {
+        serde_json::from_slice(payload).map_err(|e| NetError::Codec(e.to_string()))
+    }
+}
+
+/// Message delivered from a peer.
+#[derive(Debug, Clone)]
+pub struct NetReceived {
+    pub conn: ConnectionId,
+    pub channel: NetChannel,
+    pub kind: String,
+    pub payload: Vec<u8>,
+}
+
+impl NetReceived {
+    #[inline]
+    pub fn is<M: NetMessage>(&self) -> bool {
+        self.kind == M::KIND
+    }
+
+    /// Decodes the payload as `M`; `None` when the kind does not match.
+    #[inline]
+    pub fn decode<M: NetMessage>(&self) -> Option<Result<M, NetError>> {
+        self.is::<M>().then(|| M::decode(&self.payload))
+    }
+}
+
+/// Published on the engine `EventHub` by `NetModule`.
+#[derive(Debug, Clone)]
+pub enum NetEvent {
+    Connected {
+        conn: ConnectionId,
+        addr: SocketAddr,
+    },
+    Disconnected {
+        conn: ConnectionId,
+        addr: SocketAddr,
+        reason: DisconnectReason,
+    },
+    Message(NetReceived),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DisconnectReason {
+    /// Closed by this side.
+    Local,
+    /// The peer sent a disconnect.
+    Remote,
+    /// No packets within the configured timeout (includes unanswered connect attempts).
+    Timeout,
+    /// Reliable data could not be delivered after the resend limit.
+    Unreachable,
+}
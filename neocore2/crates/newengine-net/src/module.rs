@@ -0,0 +1,123 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use newengine_core::{ApiProvide, EngineError, EngineResult, Module, ModuleCtx};
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use crate::api::NetApiRef;
+use crate::{NET_API_ID, NET_API_PROVIDE};
+
+#[derive(Debug, Clone)]
+pub struct NetConfig {
+    /// Address to listen on at init (server). `None` leaves the socket unbound until
+    /// `NetApiRef::listen` or `NetApiRef::connect` is called.
+    pub listen: Option<SocketAddr>,
+    pub max_connections: usize,
+    /// Peer is dropped after this long without any packet.
+    pub timeout: Duration,
+    pub connect_retry: Duration,
+    pub resend_interval: Duration,
+    /// Reliable packets are abandoned (and the peer dropped) after this many sends.
+    pub max_resends: u32,
+    /// Idle connections send a ping after this long so the peer does not time out.
+    pub keepalive: Duration,
+}
+
+impl NetConfig {
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            listen: None,
+            max_connections: 64,
+            timeout: Duration::from_secs(10),
+            connect_retry: Duration::from_millis(250),
+            resend_interval: Duration::from_millis(150),
+            max_resends: 40,
+            keepalive: Duration::from_secs(1),
+        }
+    }
+
+    #[inline]
+    pub fn with_listen(mut self, addr: SocketAddr) -> Self {
+        self.listen = Some(addr);
+        self
+    }
+
+    #[inline]
+    pub fn with_max_connections(mut self, n: usize) -> Self {
+        self.max_connections = n;
+        self
+    }
+
+    #[inline]
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+}
+
+impl Default for NetConfig {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Owns the UDP socket and exposes it as `net.api`.
+///
+/// The socket is pumped in `fixed_update` so the dedicated server profile (which skips
+/// `update`) is served too; received messages and connection changes are published as
+/// [`crate::NetEvent`] on the engine `EventHub`.
+pub struct NetModule {
+    config: NetConfig,
+    api: NetApiRef,
+}
+
+impl NetModule {
+    #[inline]
+    pub fn new(config: NetConfig) -> Self {
+        let api = NetApiRef::new(config.clone());
+        Self { config, api }
+    }
+
+    /// Handle for consumers living outside the engine.
+    #[inline]
+    pub fn api(&self) -> NetApiRef {
+        self.api.clone()
+    }
+}
+
+impl<E: Send + 'static> Module<E> for NetModule {
+    fn id(&self) -> &'static str {
+        "net"
+    }
+
+    fn provides(&self) -> &'static [ApiProvide] {
+        &[NET_API_PROVIDE]
+    }
+
+    fn init(&mut self, ctx: &mut ModuleCtx<'_, E>) -> EngineResult<()> {
+        if let Some(addr) = self.config.listen {
+            self.api
+                .listen(addr)
+                .map_err(|e| EngineError::other(e.to_string()))?;
+        }
+
+        ctx.resources_mut()
+            .register_api(NET_API_ID, self.api.clone())?;
+        Ok(())
+    }
+
+    fn fixed_update(&mut self, ctx: &mut ModuleCtx<'_, E>) -> EngineResult<()> {
+        for ev in self.api.pump() {
+            ctx.events().publish(ev)?;
+        }
+        Ok(())
+    }
+
+    fn shutdown(&mut self, ctx: &mut ModuleCtx<'_, E>) -> EngineResult<()> {
+        self.api.close();
+        let _ = ctx.resources_mut().unregister_api::<NetApiRef>(NET_API_ID);
+        Ok(())
+    }
+}
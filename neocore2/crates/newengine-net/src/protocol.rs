@@ -0,0 +1,149 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+//! Wire format: a fixed 13-byte header followed by the packet body.
+//!
+//! ```text
+//! magic "NE" | version u8 | kind u8 | channel u8 | seq u32 LE | ack u32 LE | body
+//! ```
+//!
+//! `Data` bodies carry `kind_len u8 | kind utf8 | payload`.
+
+use crate::error::NetError;
+use crate::NetChannel;
+
+pub(crate) const MAGIC: [u8; 2] = *b"NE";
+pub(crate) const PROTOCOL_VERSION: u8 = 1;
+pub(crate) const HEADER_LEN: usize = 13;
+
+/// Conservative datagram size that avoids IP fragmentation on common paths.
+pub const MAX_PACKET_BYTES: usize = 1200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PacketKind {
+    Connect,
+    Accept,
+    Disconnect,
+    Data,
+    Ack,
+    Ping,
+}
+
+impl PacketKind {
+    #[inline]
+    fn to_u8(self) -> u8 {
+        match self {
+            Self::Connect => 1,
+            Self::Accept => 2,
+            Self::Disconnect => 3,
+            Self::Data => 4,
+            Self::Ack => 5,
+            Self::Ping => 6,
+        }
+    }
+
+    #[inline]
+    fn from_u8(v: u8) -> Option<Self> {
+        match v {
+            1 => Some(Self::Connect),
+            2 => Some(Self::Accept),
+            3 => Some(Self::Disconnect),
+            4 => Some(Self::Data),
+            5 => Some(Self::Ack),
+            6 => Some(Self::Ping),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Packet<'a> {
+    pub kind: PacketKind,
+    pub channel: NetChannel,
+    pub seq: u32,
+    pub ack: u32,
+    pub body: &'a [u8],
+}
+
+impl<'a> Packet<'a> {
+    #[inline]
+    pub fn control(kind: PacketKind) -> Self {
+        Self {
+            kind,
+            channel: NetChannel::Unreliable,
+            seq: 0,
+            ack: 0,
+            body: &[],
+        }
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(HEADER_LEN + self.body.len());
+        out.extend_from_slice(&MAGIC);
+        out.push(PROTOCOL_VERSION);
+        out.push(self.kind.to_u8());
+        out.push(self.channel.to_u8());
+        out.extend_from_slice(&self.seq.to_le_bytes());
+        out.extend_from_slice(&self.ack.to_le_bytes());
+        out.extend_from_slice(self.body);
+        out
+    }
+
+    pub fn decode(buf: &'a [u8]) -> Result<Self, NetError> {
+        if buf.len() < HEADER_LEN || buf[0..2] != MAGIC {
+            return Err(NetError::Malformed("bad header"));
+        }
+        if buf[2] != PROTOCOL_VERSION {
+            return Err(NetError::Malformed("protocol version mismatch"));
+        }
+
+        let kind = PacketKind::from_u8(buf[3]).ok_or(NetError::Malformed("unknown packet kind"))?;
+        let channel = NetChannel::from_u8(buf[4]).ok_or(NetError::Malformed("unknown channel"))?;
+        let seq = u32::from_le_bytes([buf[5], buf[6], buf[7], buf[8]]);
+        let ack = u32::from_le_bytes([buf[9], buf[10], buf[11], buf[12]]);
+
+        Ok(Self {
+            kind,
+            channel,
+            seq,
+            ack,
+            body: &buf[HEADER_LEN..],
+        })
+    }
+}
+
+/// Builds a `Data` body. Fails if the message would not fit a single datagram.
+pub(crate) fn encode_data_body(kind: &str, payload: &[u8]) -> Result<Vec<u8>, NetError> {
+    let kind_len = u8::try_from(kind.len()).map_err(|_| NetError::KindTooLong)?;
+
+    let size = HEADER_LEN + 1 + kind.len() + payload.len();
+    if size > MAX_PACKET_BYTES {
+        return Err(NetError::TooLarge { size });
+    }
+
+    let mut body = Vec::with_capacity(1 + kind.len() + payload.len());
+    body.push(kind_len);
+    body.extend_from_slice(kind.as_bytes());
+    body.extend_from_slice(payload);
+    Ok(body)
+}
+
+pub(crate) fn decode_data_body(body: &[u8]) -> Result<(String, Vec<u8>), NetError> {
+    let Some((&kind_len, rest)) = body.split_first() else {
+        return Err(NetError::Malformed("empty data body"));
+    };
+
+    let kind_len = kind_len as usize;
+    if rest.len() < kind_len {
+        return Err(NetError::Malformed("truncated message kind"));
+    }
+
+    let kind = std::str::from_utf8(&rest[..kind_len])
+        .map_err(|_| NetError::Malformed("message kind is not utf8"))?;
+    Ok((kind.to_string(), rest[kind_len..].to_vec()))
+}
+
+/// Wrap-around aware "a is newer than b" for sequence numbers.
+#[inline]
+pub(crate) fn seq_newer(a: u32, b: u32) -> bool {
+    a != b && a.wrapping_sub(b) < u32::MAX / 2
+}
@@ -0,0 +1,56 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use newengine_core::ClipboardBackend;
+use newengine_ui::UiClipboard;
+
+/// System clipboard via `arboard`.
+struct ArboardClipboard {
+    inner: arboard::Clipboard,
+}
+
+impl ClipboardBackend for ArboardClipboard {
+    #[inline]
+    fn get_text(&mut self) -> Result<String, String> {
+        self.inner.get_text().map_err(|e| e.to_string())
+    }
+
+    #[inline]
+    fn set_text(&mut self, text: &str) -> Result<(), String> {
+        self.inner.set_text(text).map_err(|e| e.to_string())
+    }
+}
+
+/// Installs the system clipboard as the engine clipboard backend.
+/// On failure the engine keeps its process-local fallback.
+pub(crate) fn install_system_clipboard() {
+    match arboard::Clipboard::new() {
+        Ok(inner) => {
+            newengine_core::install_clipboard_backend(Box::new(ArboardClipboard { inner }));
+            log::info!("clipboard: system clipboard installed");
+        }
+        Err(e) => {
+            log::warn!("clipboard: system clipboard unavailable, using in-process buffer: {e}");
+        }
+    }
+}
+
+/// UI provider clipboard routed through the engine clipboard, so UI and plugins share it.
+pub(crate) struct HostUiClipboard;
+
+impl UiClipboard for HostUiClipboard {
+    fn get_text(&mut self) -> Option<String> {
+        match newengine_core::clipboard_get() {
+            Ok(text) => Some(text),
+            Err(e) => {
+                log::warn!("clipboard.get failed: {e}");
+                None
+            }
+        }
+    }
+
+    fn set_text(&mut self, text: String) {
+        if let Err(e) = newengine_core::clipboard_set(&text) {
+            log::warn!("clipboard.set failed: {e}");
+        }
+    }
+}
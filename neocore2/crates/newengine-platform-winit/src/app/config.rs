@@ -1,6 +1,7 @@
 #![forbid(unsafe_op_in_unsafe_fn)]
 
 use newengine_core::startup::UiBackend;
+use std::path::PathBuf;
 
 /// Window placement policy.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -46,8 +47,16 @@ pub struct WinitAppConfig {
     pub placement: WinitWindowPlacement,
     pub ui_backend: UiBackend,
 
+    /// Frame rate cap while the window is unfocused; 0 keeps running at full rate.
+    pub unfocused_fps: u32,
+
     /// Optional window icon.
     pub icon: Option<WinitAppIcon>,
+
+    /// Record input events to this file from the first frame (see `input.record`).
+    pub input_record: Option<PathBuf>,
+    /// Replay this input recording from the first frame (see `input.replay`).
+    pub input_replay: Option<PathBuf>,
 }
 
 impl Default for WinitAppConfig {
@@ -58,7 +67,10 @@ impl Default for WinitAppConfig {
             size: (1280, 720),
             placement: WinitWindowPlacement::Centered { offset: (0, 0) },
             ui_backend: UiBackend::Egui,
+            unfocused_fps: 0,
             icon: None,
+            input_record: None,
+            input_replay: None,
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,59 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use newengine_core::{CursorGrab, CursorIcon, CursorState};
+use winit::window::{CursorGrabMode, Window};
+
+/// Applies a cursor request from `WindowApi` to the window.
+pub(crate) fn apply_cursor(window: &Window, cursor: CursorState) {
+    window.set_cursor(map_icon(cursor.icon));
+    window.set_cursor_visible(cursor.visible);
+
+    let mode = match cursor.grab {
+        CursorGrab::None => CursorGrabMode::None,
+        CursorGrab::Confined => CursorGrabMode::Confined,
+        CursorGrab::Locked => CursorGrabMode::Locked,
+    };
+
+    // Platforms support only one of Confined/Locked (X11/Windows: confined, macOS: locked);
+    // fall back to the other so mouse capture still works.
+    let res = window.set_cursor_grab(mode).or_else(|e| match mode {
+        CursorGrabMode::Locked => window.set_cursor_grab(CursorGrabMode::Confined),
+        CursorGrabMode::Confined => window.set_cursor_grab(CursorGrabMode::Locked),
+        CursorGrabMode::None => Err(e),
+    });
+
+    if let Err(e) = res {
+        log::warn!(
+            "window: cursor grab failed mode={:?} err='{e}'",
+            cursor.grab
+        );
+    }
+}
+
+#[inline]
+fn map_icon(icon: CursorIcon) -> winit::window::CursorIcon {
+    use winit::window::CursorIcon as W;
+
+    match icon {
+        CursorIcon::Default => W::Default,
+        CursorIcon::Pointer => W::Pointer,
+        CursorIcon::Text => W::Text,
+        CursorIcon::Crosshair => W::Crosshair,
+        CursorIcon::Move => W::Move,
+        CursorIcon::Grab => W::Grab,
+        CursorIcon::Grabbing => W::Grabbing,
+        CursorIcon::NotAllowed => W::NotAllowed,
+        CursorIcon::Wait => W::Wait,
+        CursorIcon::Progress => W::Progress,
+        CursorIcon::Help => W::Help,
+        CursorIcon::EwResize => W::EwResize,
+        CursorIcon::NsResize => W::NsResize,
+        CursorIcon::NeswResize => W::NeswResize,
+        CursorIcon::NwseResize => W::NwseResize,
+        CursorIcon::ColResize => W::ColResize,
+        CursorIcon::RowResize => W::RowResize,
+        CursorIcon::AllScroll => W::AllScroll,
+        CursorIcon::ZoomIn => W::ZoomIn,
+        CursorIcon::ZoomOut => W::ZoomOut,
+    }
+}
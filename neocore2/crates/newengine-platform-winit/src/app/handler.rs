@@ -1,26 +1,36 @@
 #![forbid(unsafe_op_in_unsafe_fn)]
 
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use newengine_core::host_events::{HostEvent, WindowHostEvent};
 use newengine_core::startup::UiBackend;
-use newengine_core::{Engine, EngineError, EngineResult};
+use newengine_core::{window_api, Engine, EngineError, EngineResult, HOST_PHASE_WINDOW};
 use raw_window_handle::{HasDisplayHandle, HasWindowHandle};
 use winit::{
     application::ApplicationHandler,
     dpi::{PhysicalPosition, PhysicalSize},
     event::{ElementState, Ime, MouseScrollDelta, WindowEvent},
-    event_loop::ActiveEventLoop,
-    keyboard::PhysicalKey,
+    event_loop::{ActiveEventLoop, ControlFlow},
+    keyboard::{ModifiersState, PhysicalKey},
     window::{Icon, Window, WindowAttributes, WindowId},
 };
 
 use newengine_ui::draw::UiDrawList;
 use newengine_ui::{create_provider, UiBuildFn, UiFrameDesc, UiProvider, UiProviderKind, UiProviderOptions};
 
+use crate::app::clipboard::{install_system_clipboard, HostUiClipboard};
 use crate::app::config::{WinitAppConfig, WinitWindowPlacement};
+use crate::app::cursor::apply_cursor;
 use crate::app::input_bridge::{emit_plugin_json, poll_input_frame};
+use crate::app::input_record::{
+    register_input_record_service, request_input_record, request_input_replay, InputRecorder,
+};
 use crate::app::resources::{WinitWindowHandles, WinitWindowInitSize};
+use crate::app::window_mode::{apply_window_mode, enumerate_monitors};
+
+/// Engine step interval while the window is minimized; nothing is rendered, but simulation,
+/// asset imports and plugins keep running slowly.
+const MINIMIZED_FRAME_INTERVAL: Duration = Duration::from_millis(100);
 
 pub(crate) struct App<E, F>
 where
@@ -35,11 +45,20 @@ where
 
     window: Option<Window>,
     last_cursor_pos: Option<(f32, f32)>,
+    /// Keys pressed since the last frame, as winit key names with held modifiers
+    /// (`ctrl+KeyZ`; console `bind` hotkeys).
+    pending_hotkeys: Vec<String>,
+    modifiers: ModifiersState,
+    /// Records or replays the input events forwarded to the input plugin.
+    input_rec: InputRecorder,
 
     ui: Box<dyn UiProvider>,
     ui_build: Option<Box<dyn UiBuildFn>>,
 
     last_frame_instant: Option<Instant>,
+    /// A frame ran from `Resized` since the last `about_to_wait`.
+    resize_frame: bool,
+    focused: bool,
     shutting_down: bool,
 }
 
@@ -73,7 +92,18 @@ where
             );
         }
 
-        let ui = create_provider(UiProviderOptions { kind });
+        install_system_clipboard();
+
+        let mut ui = create_provider(UiProviderOptions { kind });
+        ui.set_clipboard(Box::new(HostUiClipboard));
+
+        register_input_record_service();
+        if let Some(path) = config.input_record.clone() {
+            request_input_record(path);
+        }
+        if let Some(path) = config.input_replay.clone() {
+            request_input_replay(path);
+        }
 
         Self {
             engine,
@@ -83,9 +113,14 @@ where
             fatal: None,
             window: None,
             last_cursor_pos: None,
+            pending_hotkeys: Vec::new(),
+            modifiers: ModifiersState::empty(),
+            input_rec: InputRecorder::new(),
             ui,
             ui_build,
             last_frame_instant: None,
+            resize_frame: false,
+            focused: true,
             shutting_down: false,
         }
     }
@@ -134,13 +169,32 @@ where
         }
     }
 
+    /// Keeps the event loop spinning; skipped while throttled, where `about_to_wait` wakes the
+    /// loop on a timer instead.
     #[inline]
     fn request_redraw(&self) {
+        if self.frame_interval().is_some() {
+            return;
+        }
         if let Some(w) = &self.window {
             w.request_redraw();
         }
     }
 
+    /// Minimum time between frames: while minimized, and while unfocused when
+    /// `unfocused_fps` is set. `None` runs frames back to back.
+    fn frame_interval(&self) -> Option<Duration> {
+        let w = self.window.as_ref()?;
+        let size = w.inner_size();
+        if w.is_minimized() == Some(true) || size.width == 0 || size.height == 0 {
+            return Some(MINIMIZED_FRAME_INTERVAL);
+        }
+        if !self.focused && self.config.unfocused_fps > 0 {
+            return Some(Duration::from_secs_f64(1.0 / self.config.unfocused_fps as f64));
+        }
+        None
+    }
+
     #[inline]
     fn window_size(&self) -> Option<(u32, u32)> {
         self.window.as_ref().map(|w| {
@@ -189,6 +243,62 @@ where
         let _ = self.engine.emit(HostEvent::Window(WindowHostEvent::Focused(focused)));
     }
 
+    /// Records the new DPI scale and forwards it to modules and plugins. The UI reads it
+    /// from `WindowApi` on the next frame; the swapchain follows via `Resized`.
+    fn emit_scale_factor(&mut self, scale_factor: f64) {
+        log::info!("winit: scale_factor={scale_factor}");
+        window_api().set_scale_factor(scale_factor);
+        emit_plugin_json(
+            "winit.scale_factor_changed",
+            serde_json::json!({ "scale_factor": scale_factor }),
+        );
+        let _ = self
+            .engine
+            .emit(HostEvent::Window(WindowHostEvent::ScaleFactorChanged { scale_factor }));
+    }
+
+    /// Publishes a drag-and-drop event on the engine bus and forwards it to plugins.
+    fn emit_file_event(&mut self, topic: &'static str, event: WindowHostEvent) {
+        let path = match &event {
+            WindowHostEvent::FileHovered(p) | WindowHostEvent::FileDropped(p) => {
+                Some(p.to_string_lossy().into_owned())
+            }
+            _ => None,
+        };
+
+        log::debug!("winit: {topic} path={path:?}");
+        emit_plugin_json(topic, serde_json::json!({ "path": path }));
+        let _ = self.engine.emit(HostEvent::Window(event));
+    }
+
+    /// Applies cursor and window mode changes requested through `WindowApi` / `HostApiV1`
+    /// (console, plugins, modules) since the last frame. Runs on the event loop thread.
+    fn apply_window_requests(&mut self) {
+        let Some(w) = self.window.as_ref() else { return; };
+        let api = window_api();
+
+        if let Some(cursor) = api.take_cursor() {
+            apply_cursor(w, cursor);
+        }
+
+        if let Some(mode) = api.take_mode() {
+            // Monitors may have been plugged in since startup.
+            api.set_monitors(enumerate_monitors(w));
+            api.confirm_mode(apply_window_mode(w, mode));
+        }
+    }
+
+    /// Runs console key bindings for this frame's key presses, unless the UI is taking text.
+    fn dispatch_hotkeys(&mut self, ui_wants_keyboard: bool) {
+        if ui_wants_keyboard {
+            self.pending_hotkeys.clear();
+            return;
+        }
+        for key in self.pending_hotkeys.drain(..) {
+            newengine_core::console::run_key_binding(&key);
+        }
+    }
+
     #[inline]
     fn frame_dt_seconds(&mut self) -> f32 {
         let now = Instant::now();
@@ -226,6 +336,44 @@ where
         }
     }
 
+    /// Runs one engine frame: input, UI, `engine.step()` (which renders) and window requests.
+    fn run_frame(&mut self, event_loop: &ActiveEventLoop) {
+        let dt = self.frame_dt_seconds();
+        // Before dispatch, so replayed events reach the input plugin this frame.
+        let dt = self.input_rec.begin_frame(dt, &mut self.pending_hotkeys);
+
+        // Deliver this iteration's window events to the input plugin before the UI samples it;
+        // otherwise they would only be dispatched by `engine.step()` and show up a frame late.
+        newengine_core::plugins::dispatch_events();
+        let input = poll_input_frame(&self.engine);
+        let mut ui_wants_keyboard = false;
+
+        if let (Some(w), Some(build)) = (self.window.as_ref(), self.ui_build.as_deref_mut()) {
+            let mut desc = UiFrameDesc::new(dt).with_pixels_per_point(window_api().scale_factor() as f32);
+            if let Some(inp) = input {
+                desc = desc.with_input(inp);
+            }
+
+            let out = self.ui.run_frame(w, desc, build);
+            ui_wants_keyboard = out.wants_keyboard;
+            self.engine.resources_mut().insert::<UiDrawList>(out.draw_list);
+        }
+
+        self.dispatch_hotkeys(ui_wants_keyboard);
+
+        let step = self.engine.step();
+        self.apply_window_requests();
+
+        match step {
+            Ok(_) => self.request_redraw(),
+            Err(EngineError::ExitRequested) => self.shutdown_and_exit(event_loop),
+            Err(e) => {
+                log::error!("engine.step failed: {e}");
+                self.shutdown_and_exit(event_loop);
+            }
+        }
+    }
+
     fn set_fatal_and_exit(&mut self, event_loop: &ActiveEventLoop, e: EngineError) {
         log::error!("winit host fatal: {e}");
         self.fatal = Some(e);
@@ -270,6 +418,13 @@ where
 
         self.install_window_handles_resource();
         self.install_window_init_size_resource();
+        self.engine.mark_host_phase(HOST_PHASE_WINDOW);
+        self.engine.resources_mut().insert(window_api());
+        if let Some(w) = self.window.as_ref() {
+            window_api().set_monitors(enumerate_monitors(w));
+            window_api().set_scale_factor(w.scale_factor());
+        }
+        self.apply_window_requests();
 
         if let Some(after) = self.after_window.take() {
             if let Err(e) = after(&mut self.engine) {
@@ -303,25 +458,69 @@ where
 
             WindowEvent::Resized(PhysicalSize { width, height }) => {
                 self.emit_resized(width, height);
+
+                // On Windows a resize drag runs a modal loop that starves `about_to_wait`; only
+                // window events get through. Render from here so the swapchain, clear color and
+                // UI follow the window instead of showing stretched stale content.
+                let can_step = self.started && !self.shutting_down && self.fatal.is_none();
+                if can_step && width > 0 && height > 0 {
+                    self.run_frame(event_loop);
+                    self.resize_frame = true;
+                }
             }
 
-            WindowEvent::ScaleFactorChanged { .. } => {
+            WindowEvent::ScaleFactorChanged { scale_factor, .. } => {
+                self.emit_scale_factor(scale_factor);
                 if let Some((w, h)) = self.window_size() {
                     self.emit_resized(w, h);
                 }
             }
 
             WindowEvent::Focused(focused) => {
+                self.focused = focused;
                 self.emit_focused(focused);
             }
 
+            WindowEvent::HoveredFile(path) => {
+                self.emit_file_event("winit.file_hovered", WindowHostEvent::FileHovered(path));
+            }
+
+            WindowEvent::HoveredFileCancelled => {
+                self.emit_file_event(
+                    "winit.file_hover_cancelled",
+                    WindowHostEvent::FileHoverCancelled,
+                );
+            }
+
+            WindowEvent::DroppedFile(path) => {
+                self.emit_file_event("winit.file_dropped", WindowHostEvent::FileDropped(path));
+            }
+
             // forward-only to input plugin
             WindowEvent::KeyboardInput { event, .. } => {
                 let key = Self::key_u32_from_physical_key(&event.physical_key);
                 let state = Self::map_state_str(event.state);
                 let repeat = event.repeat;
 
-                emit_plugin_json(
+                if event.state == ElementState::Pressed && !repeat {
+                    if let PhysicalKey::Code(c) = event.physical_key {
+                        let m = self.modifiers;
+                        let mut name = String::new();
+                        for (held, prefix) in [
+                            (m.control_key(), "ctrl+"),
+                            (m.alt_key(), "alt+"),
+                            (m.shift_key(), "shift+"),
+                        ] {
+                            if held {
+                                name.push_str(prefix);
+                            }
+                        }
+                        name.push_str(&format!("{c:?}"));
+                        self.pending_hotkeys.push(name);
+                    }
+                }
+
+                self.input_rec.emit(
                     "winit.key",
                     serde_json::json!({
                         "key": key,
@@ -333,7 +532,7 @@ where
 
                 if let Some(text) = event.text.as_ref() {
                     for ch in text.chars() {
-                        emit_plugin_json(
+                        self.input_rec.emit(
                             "winit.text_char",
                             serde_json::json!({
                                 "cp": ch as u32
@@ -343,11 +542,15 @@ where
                 }
             }
 
+            WindowEvent::ModifiersChanged(m) => {
+                self.modifiers = m.state();
+            }
+
             WindowEvent::MouseInput { state, button, .. } => {
                 let b = Self::map_mouse_button_u32(button);
                 let st = Self::map_state_str(state);
 
-                emit_plugin_json(
+                self.input_rec.emit(
                     "winit.mouse_button",
                     serde_json::json!({
                         "button": b,
@@ -362,7 +565,7 @@ where
                     MouseScrollDelta::PixelDelta(p) => (p.x as f32, p.y as f32),
                 };
 
-                emit_plugin_json(
+                self.input_rec.emit(
                     "winit.mouse_wheel",
                     serde_json::json!({
                         "dx": dx,
@@ -376,7 +579,7 @@ where
                 let y = position.y as f32;
 
                 if let Some((px, py)) = self.last_cursor_pos {
-                    emit_plugin_json(
+                    self.input_rec.emit(
                         "winit.mouse_delta",
                         serde_json::json!({
                             "dx": x - px,
@@ -387,7 +590,7 @@ where
 
                 self.last_cursor_pos = Some((x, y));
 
-                emit_plugin_json(
+                self.input_rec.emit(
                     "winit.mouse_move",
                     serde_json::json!({
                         "x": x,
@@ -398,22 +601,38 @@ where
 
             WindowEvent::Ime(ime) => match ime {
                 Ime::Commit(text) => {
-                    emit_plugin_json(
+                    self.input_rec.emit(
                         "winit.ime_commit",
                         serde_json::json!({
                             "text": text
                         }),
                     );
                 }
-                Ime::Preedit(text, _) => {
-                    emit_plugin_json(
+                Ime::Preedit(text, cursor) => {
+                    self.input_rec.emit(
                         "winit.ime_preedit",
                         serde_json::json!({
-                            "text": text
+                            "text": text,
+                            "cursor": cursor.map(|(a, b)| [a, b])
+                        }),
+                    );
+                }
+                Ime::Enabled => {
+                    self.input_rec.emit(
+                        "winit.ime_enabled",
+                        serde_json::json!({
+                            "enabled": true
+                        }),
+                    );
+                }
+                Ime::Disabled => {
+                    self.input_rec.emit(
+                        "winit.ime_enabled",
+                        serde_json::json!({
+                            "enabled": false
                         }),
                     );
                 }
-                Ime::Enabled | Ime::Disabled => {}
             },
 
             _ => {}
@@ -438,26 +657,27 @@ where
             return;
         }
 
-        let dt = self.frame_dt_seconds();
-        let input = poll_input_frame(&self.engine);
-
-        if let (Some(w), Some(build)) = (self.window.as_ref(), self.ui_build.as_deref_mut()) {
-            let mut desc = UiFrameDesc::new(dt);
-            if let Some(inp) = input {
-                desc = desc.with_input(inp);
-            }
-
-            let out = self.ui.run_frame(w, desc, build);
-            self.engine.resources_mut().insert::<UiDrawList>(out.draw_list);
+        // The resize already stepped the engine for this iteration.
+        if std::mem::take(&mut self.resize_frame) {
+            self.request_redraw();
+            return;
         }
 
-        match self.engine.step() {
-            Ok(_) => self.request_redraw(),
-            Err(EngineError::ExitRequested) => self.shutdown_and_exit(event_loop),
-            Err(e) => {
-                log::error!("engine.step failed: {e}");
-                self.shutdown_and_exit(event_loop);
+        // Throttled: sleep until the next frame is due instead of spinning. Restoring or
+        // focusing the window wakes the loop and returns to back-to-back frames.
+        let Some(interval) = self.frame_interval() else {
+            event_loop.set_control_flow(ControlFlow::Wait);
+            self.run_frame(event_loop);
+            return;
+        };
+        if let Some(last) = self.last_frame_instant {
+            let due = last + interval;
+            if Instant::now() < due {
+                event_loop.set_control_flow(ControlFlow::WaitUntil(due));
+                return;
             }
         }
+        self.run_frame(event_loop);
+        event_loop.set_control_flow(ControlFlow::WaitUntil(Instant::now() + interval));
     }
 }
\ No newline at end of file
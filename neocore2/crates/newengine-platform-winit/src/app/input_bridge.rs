@@ -119,6 +119,18 @@ pub fn poll_input_frame(engine: &Engine<impl Send + 'static>) -> Option<UiInputF
         if let Some(s) = text.get("ime_preedit").and_then(|x| x.as_str()) {
             out.ime_preedit.push_str(s);
         }
+        if let Some(arr) = text.get("ime_preedit_cursor").and_then(|x| x.as_array()) {
+            if let (Some(a), Some(b)) = (
+                arr.first().and_then(|v| v.as_u64()),
+                arr.get(1).and_then(|v| v.as_u64()),
+            ) {
+                out.ime_preedit_cursor = Some((a as usize, b as usize));
+            }
+        }
+        out.ime_enabled = text
+            .get("ime_enabled")
+            .and_then(|x| x.as_bool())
+            .unwrap_or(false);
     }
 
     Some(out)
@@ -0,0 +1,443 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use abi_stable::std_types::{RResult, RString};
+use newengine_plugin_api::{
+    guarded_service, Blob, CapabilityId, MethodName, ServiceV1, ServiceV1Dyn,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::app::input_bridge::emit_plugin_json;
+
+pub const INPUT_RECORD_SERVICE_ID: &str = "winit.input_record";
+/// First line of a recording file.
+const RECORDING_FORMAT: &str = "newengine.input";
+const RECORDING_VERSION: u32 = 1;
+/// Used by `input.record` / `input.replay` without a path.
+pub const DEFAULT_INPUT_RECORDING: &str = "input.nerec";
+
+pub mod method {
+    pub const RECORD: &str = "input.record";
+    pub const REPLAY: &str = "input.replay";
+    pub const STOP: &str = "input.stop";
+    pub const STATUS_JSON: &str = "input.status_json";
+}
+
+#[derive(Debug, Clone)]
+enum Request {
+    Record(PathBuf),
+    Replay(PathBuf),
+    Stop,
+}
+
+/// Process-wide so console commands reach the host loop; applied at the next host frame.
+static REQUESTS: Mutex<Vec<Request>> = Mutex::new(Vec::new());
+static STATUS: Mutex<InputRecordStatus> = Mutex::new(InputRecordStatus::OFF);
+
+fn push_request(req: Request) {
+    if let Ok(mut g) = REQUESTS.lock() {
+        g.push(req);
+    }
+}
+
+/// Starts capturing input events to `path` (JSON lines), replacing any recording or replay.
+pub fn request_input_record(path: impl Into<PathBuf>) {
+    push_request(Request::Record(path.into()));
+}
+
+/// Feeds the recording at `path` back through the input pipeline, one recorded frame per host
+/// frame. Live input is ignored until the replay ends.
+pub fn request_input_replay(path: impl Into<PathBuf>) {
+    push_request(Request::Replay(path.into()));
+}
+
+/// Stops the current recording or replay.
+pub fn request_input_stop() {
+    push_request(Request::Stop);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InputRecordMode {
+    Off,
+    Recording,
+    Replaying,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InputRecordStatus {
+    pub mode: InputRecordMode,
+    pub path: Option<String>,
+    /// Frames written or replayed so far.
+    pub frame: u64,
+    /// Frames left to replay.
+    pub remaining: usize,
+    pub error: Option<String>,
+}
+
+impl InputRecordStatus {
+    const OFF: Self = Self {
+        mode: InputRecordMode::Off,
+        path: None,
+        frame: 0,
+        remaining: 0,
+        error: None,
+    };
+}
+
+pub fn input_record_status() -> InputRecordStatus {
+    STATUS
+        .lock()
+        .map(|g| g.clone())
+        .unwrap_or(InputRecordStatus::OFF)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct RecordingHeader {
+    format: String,
+    version: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RecordedEvent {
+    topic: String,
+    payload: serde_json::Value,
+}
+
+/// One host frame: its UI delta, the input events delivered before it and its hotkeys.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct RecordedFrame {
+    frame: u64,
+    dt: f32,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    events: Vec<RecordedEvent>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    hotkeys: Vec<String>,
+}
+
+enum Mode {
+    Off,
+    Recording {
+        path: PathBuf,
+        out: BufWriter<File>,
+        /// Events captured since the last host frame.
+        pending: Vec<RecordedEvent>,
+    },
+    Replaying {
+        path: PathBuf,
+        frames: VecDeque<RecordedFrame>,
+    },
+}
+
+/// Host-side input recorder and player.
+///
+/// Recording captures every input event the host forwards to the input plugin, grouped by host
+/// frame together with the frame's UI delta and console hotkeys. Replay re-emits them through
+/// the same topics, frame by frame, and hands the recorded delta to the UI, so UI input plays
+/// back identically. Engine time still follows the wall clock; use the `time.*` controls or a
+/// headless fixed step for simulation-exact reproduction.
+pub(crate) struct InputRecorder {
+    mode: Mode,
+    frame: u64,
+}
+
+impl InputRecorder {
+    pub(crate) fn new() -> Self {
+        Self {
+            mode: Mode::Off,
+            frame: 0,
+        }
+    }
+
+    /// Forwards a live input event to the input plugin, recording it when active.
+    /// Live input is dropped during replay.
+    pub(crate) fn emit(&mut self, topic: &'static str, payload: serde_json::Value) {
+        match &mut self.mode {
+            Mode::Replaying { .. } => return,
+            Mode::Recording { pending, .. } => pending.push(RecordedEvent {
+                topic: topic.to_string(),
+                payload: payload.clone(),
+            }),
+            Mode::Off => {}
+        }
+        emit_plugin_json(topic, payload);
+    }
+
+    /// Closes a host frame: applies queued requests, then writes the frame or injects the next
+    /// recorded one. Returns the UI delta to use. `hotkeys` are this frame's console hotkeys;
+    /// during replay they are replaced by the recorded ones.
+    pub(crate) fn begin_frame(&mut self, dt: f32, hotkeys: &mut Vec<String>) -> f32 {
+        let requests = REQUESTS
+            .lock()
+            .map(|mut g| std::mem::take(&mut *g))
+            .unwrap_or_default();
+        for req in requests {
+            self.apply(req);
+        }
+
+        let frame = self.frame;
+        let dt = match &mut self.mode {
+            Mode::Off => return dt,
+            Mode::Recording { out, pending, .. } => {
+                let rec = RecordedFrame {
+                    frame,
+                    dt,
+                    events: std::mem::take(pending),
+                    hotkeys: hotkeys.clone(),
+                };
+                if let Err(e) = write_line(out, &rec) {
+                    self.fail(format!("write failed: {e}"));
+                    return dt;
+                }
+                dt
+            }
+            Mode::Replaying { frames, .. } => {
+                let Some(rec) = frames.pop_front() else {
+                    log::info!("input: replay finished frames={frame}");
+                    self.stop();
+                    return dt;
+                };
+                for ev in rec.events {
+                    let topic = RString::from(ev.topic);
+                    if let Ok(bytes) = serde_json::to_vec(&ev.payload) {
+                        let _ = newengine_core::plugins::host_context::emit_plugin_event(
+                            topic,
+                            Blob::from(bytes),
+                        );
+                    }
+                }
+                *hotkeys = rec.hotkeys;
+                rec.dt
+            }
+        };
+
+        self.frame += 1;
+        self.publish_status(None);
+        dt
+    }
+
+    fn apply(&mut self, req: Request) {
+        self.stop();
+        let res = match req {
+            Request::Record(path) => self.start_recording(path),
+            Request::Replay(path) => self.start_replay(path),
+            Request::Stop => Ok(()),
+        };
+        if let Err(e) = res {
+            self.fail(e);
+        }
+    }
+
+    fn start_recording(&mut self, path: PathBuf) -> Result<(), String> {
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir).map_err(|e| format!("{}: {e}", dir.display()))?;
+        }
+        let file = File::create(&path).map_err(|e| format!("{}: {e}", path.display()))?;
+        let mut out = BufWriter::new(file);
+        let header = RecordingHeader {
+            format: RECORDING_FORMAT.to_string(),
+            version: RECORDING_VERSION,
+        };
+        write_line(&mut out, &header).map_err(|e| format!("{}: {e}", path.display()))?;
+
+        log::info!("input: recording path='{}'", path.display());
+        self.mode = Mode::Recording {
+            path,
+            out,
+            pending: Vec::new(),
+        };
+        self.publish_status(None);
+        Ok(())
+    }
+
+    fn start_replay(&mut self, path: PathBuf) -> Result<(), String> {
+        let frames = read_recording(&path).map_err(|e| format!("{}: {e}", path.display()))?;
+        log::info!(
+            "input: replaying path='{}' frames={}",
+            path.display(),
+            frames.len()
+        );
+        self.mode = Mode::Replaying { path, frames };
+        self.publish_status(None);
+        Ok(())
+    }
+
+    /// Ends recording (flushing the file) or replay.
+    pub(crate) fn stop(&mut self) {
+        match std::mem::replace(&mut self.mode, Mode::Off) {
+            Mode::Recording { path, mut out, .. } => {
+                if let Err(e) = out.flush() {
+                    log::warn!("input: flush failed path='{}' err='{e}'", path.display());
+                }
+                log::info!(
+                    "input: recording stopped path='{}' frames={}",
+                    path.display(),
+                    self.frame
+                );
+            }
+            Mode::Replaying { .. } | Mode::Off => {}
+        }
+        self.frame = 0;
+        self.publish_status(None);
+    }
+
+    fn fail(&mut self, err: String) {
+        log::warn!("input: {err}");
+        self.stop();
+        self.publish_status(Some(err));
+    }
+
+    fn publish_status(&self, error: Option<String>) {
+        let (mode, path, remaining) = match &self.mode {
+            Mode::Off => (InputRecordMode::Off, None, 0),
+            Mode::Recording { path, .. } => (InputRecordMode::Recording, Some(path.as_path()), 0),
+            Mode::Replaying { path, frames } => (
+                InputRecordMode::Replaying,
+                Some(path.as_path()),
+                frames.len(),
+            ),
+        };
+        if let Ok(mut g) = STATUS.lock() {
+            *g = InputRecordStatus {
+                mode,
+                path: path.map(|p| p.display().to_string()),
+                frame: self.frame,
+                remaining,
+                error,
+            };
+        }
+    }
+}
+
+impl Drop for InputRecorder {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+fn write_line(out: &mut impl Write, value: &impl Serialize) -> std::io::Result<()> {
+    serde_json::to_writer(&mut *out, value)?;
+    out.write_all(b"\n")
+}
+
+fn read_recording(path: &Path) -> Result<VecDeque<RecordedFrame>, String> {
+    let file = File::open(path).map_err(|e| e.to_string())?;
+    let mut lines = BufReader::new(file).lines();
+
+    let header = lines
+        .next()
+        .ok_or("empty recording")?
+        .map_err(|e| e.to_string())?;
+    let header: RecordingHeader =
+        serde_json::from_str(&header).map_err(|e| format!("bad header: {e}"))?;
+    if header.format != RECORDING_FORMAT || header.version != RECORDING_VERSION {
+        return Err(format!(
+            "unsupported recording {}@{} (expected {RECORDING_FORMAT}@{RECORDING_VERSION})",
+            header.format, header.version
+        ));
+    }
+
+    let mut frames = VecDeque::new();
+    for (i, line) in lines.enumerate() {
+        let line = line.map_err(|e| e.to_string())?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let frame: RecordedFrame =
+            serde_json::from_str(&line).map_err(|e| format!("line {}: {e}", i + 2))?;
+        frames.push_back(frame);
+    }
+    Ok(frames)
+}
+
+struct InputRecordService;
+
+impl InputRecordService {
+    fn path_arg(arg: &str) -> PathBuf {
+        match arg.trim() {
+            "" => PathBuf::from(DEFAULT_INPUT_RECORDING),
+            p => PathBuf::from(p),
+        }
+    }
+}
+
+impl ServiceV1 for InputRecordService {
+    fn id(&self) -> CapabilityId {
+        RString::from(INPUT_RECORD_SERVICE_ID)
+    }
+
+    fn describe(&self) -> RString {
+        let d = json!({
+          "id": INPUT_RECORD_SERVICE_ID,
+          "version": 1,
+          "methods": [
+            { "name": method::RECORD, "payload": "utf8 '[path]'", "returns": "json InputRecordStatus" },
+            { "name": method::REPLAY, "payload": "utf8 '[path]'", "returns": "json InputRecordStatus" },
+            { "name": method::STOP, "payload": "empty", "returns": "json InputRecordStatus" },
+            { "name": method::STATUS_JSON, "payload": "empty", "returns": "json InputRecordStatus" }
+          ],
+          "console": {
+            "commands": [
+              {
+                "name": "input.record",
+                "help": format!("Record input events to a file (default {DEFAULT_INPUT_RECORDING})"),
+                "usage": "input.record [path]",
+                "kind": "service_call",
+                "service_id": INPUT_RECORD_SERVICE_ID,
+                "method": method::RECORD,
+                "payload": "raw"
+              },
+              {
+                "name": "input.replay",
+                "help": format!("Replay recorded input, ignoring live input (default {DEFAULT_INPUT_RECORDING})"),
+                "usage": "input.replay [path]",
+                "kind": "service_call",
+                "service_id": INPUT_RECORD_SERVICE_ID,
+                "method": method::REPLAY,
+                "payload": "raw"
+              },
+              {
+                "name": "input.stop",
+                "help": "Stop input recording or replay",
+                "kind": "service_call",
+                "service_id": INPUT_RECORD_SERVICE_ID,
+                "method": method::STOP,
+                "payload": "empty"
+              }
+            ]
+          }
+        });
+
+        RString::from(d.to_string())
+    }
+
+    fn call(&self, method: MethodName, payload: Blob) -> RResult<Blob, RString> {
+        let m = method.to_string();
+        let arg = String::from_utf8_lossy(payload.as_slice());
+
+        match m.as_str() {
+            method::RECORD => request_input_record(Self::path_arg(&arg)),
+            method::REPLAY => request_input_replay(Self::path_arg(&arg)),
+            method::STOP => request_input_stop(),
+            method::STATUS_JSON => {}
+            _ => return RResult::RErr(RString::from(format!("unknown method: {m}"))),
+        }
+
+        let resp = serde_json::to_vec(&input_record_status());
+        RResult::ROk(Blob::from(resp.unwrap_or_default()))
+    }
+}
+
+pub(crate) fn register_input_record_service() {
+    let dyn_svc: ServiceV1Dyn<'static> = guarded_service(InputRecordService);
+
+    if let Err(e) = newengine_core::register_service_v1(dyn_svc) {
+        log::warn!("input: register {INPUT_RECORD_SERVICE_ID} failed: {e}");
+    }
+}
@@ -1,11 +1,19 @@
 #![forbid(unsafe_op_in_unsafe_fn)]
 
+mod clipboard;
 pub mod config;
+mod cursor;
 mod handler;
 mod input_bridge;
+pub mod input_record;
 mod resources;
 mod runner;
+mod window_mode;
 
 pub use config::{WinitAppConfig, WinitWindowPlacement};
+pub use input_record::{
+    input_record_status, request_input_record, request_input_replay, request_input_stop,
+    InputRecordMode, InputRecordStatus,
+};
 pub use resources::{WinitWindowHandles, WinitWindowInitSize};
 pub use runner::{run_winit_app, run_winit_app_with_config};
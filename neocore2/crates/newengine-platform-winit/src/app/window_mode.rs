@@ -0,0 +1,82 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use newengine_core::{MonitorInfo, WindowMode};
+use winit::monitor::MonitorHandle;
+use winit::window::{Fullscreen, Window};
+
+/// Lists monitors in `available_monitors()` order; `MonitorInfo::index` matches `WindowMode`.
+pub(crate) fn enumerate_monitors(window: &Window) -> Vec<MonitorInfo> {
+    let primary = window.primary_monitor();
+
+    window
+        .available_monitors()
+        .enumerate()
+        .map(|(index, m)| {
+            let size = m.size();
+            let pos = m.position();
+            MonitorInfo {
+                index,
+                name: m.name().unwrap_or_else(|| format!("monitor-{index}")),
+                width: size.width,
+                height: size.height,
+                x: pos.x,
+                y: pos.y,
+                scale_factor: m.scale_factor(),
+                refresh_mhz: m.refresh_rate_millihertz(),
+                primary: primary.as_ref() == Some(&m),
+            }
+        })
+        .collect()
+}
+
+/// Applies `mode` and returns the mode actually in effect (exclusive falls back to borderless
+/// when the monitor reports no video modes).
+pub(crate) fn apply_window_mode(window: &Window, mode: WindowMode) -> WindowMode {
+    let monitor = resolve_monitor(window, mode.monitor());
+
+    let (fullscreen, applied) = match mode {
+        WindowMode::Windowed => (None, mode),
+        WindowMode::Borderless { .. } => (Some(Fullscreen::Borderless(monitor)), mode),
+        WindowMode::Exclusive { monitor: index } => {
+            // Largest resolution first, then highest refresh rate.
+            let best = monitor.as_ref().and_then(|m| {
+                m.video_modes().max_by_key(|v| {
+                    let s = v.size();
+                    (
+                        s.width as u64 * s.height as u64,
+                        v.refresh_rate_millihertz(),
+                    )
+                })
+            });
+            match best {
+                Some(v) => (Some(Fullscreen::Exclusive(v)), mode),
+                None => {
+                    log::warn!("window: no video modes for exclusive fullscreen; using borderless");
+                    (
+                        Some(Fullscreen::Borderless(monitor)),
+                        WindowMode::Borderless { monitor: index },
+                    )
+                }
+            }
+        }
+    };
+
+    window.set_fullscreen(fullscreen);
+    log::info!(
+        "window: mode={} monitor={:?}",
+        applied.as_str(),
+        applied.monitor()
+    );
+    applied
+}
+
+#[inline]
+fn resolve_monitor(window: &Window, index: Option<usize>) -> Option<MonitorHandle> {
+    match index {
+        Some(i) => window
+            .available_monitors()
+            .nth(i)
+            .or_else(|| window.current_monitor()),
+        None => window.current_monitor(),
+    }
+}
@@ -4,6 +4,7 @@ pub use egui;
 pub use newengine_ui::UiBuildFn;
 
 pub use app::{
-    run_winit_app, run_winit_app_with_config, WinitAppConfig, WinitWindowHandles,
-    WinitWindowInitSize, WinitWindowPlacement,
+    input_record_status, request_input_record, request_input_replay, request_input_stop,
+    run_winit_app, run_winit_app_with_config, InputRecordMode, InputRecordStatus, WinitAppConfig,
+    WinitWindowHandles, WinitWindowInitSize, WinitWindowPlacement,
 };
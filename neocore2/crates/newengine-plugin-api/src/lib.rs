@@ -3,9 +3,11 @@
 
 use abi_stable::library::RootModule;
 use abi_stable::sabi_trait;
+use abi_stable::sabi_trait::TD_Opaque;
 use abi_stable::sabi_types::VersionStrings;
-use abi_stable::std_types::{RResult, RString, RVec};
+use abi_stable::std_types::{ROption, RResult, RString, RVec};
 use abi_stable::StableAbi;
+use std::panic::{catch_unwind, AssertUnwindSafe};
 
 pub type Blob = RVec<u8>;
 pub type CapabilityId = RString;
@@ -15,15 +17,46 @@ pub type MethodName = RString;
    Generic service: semantics fully owned by provider plugin
    ============================================================================================= */
 
+/// `describe()` returns a JSON object; a top-level `"version"` (u32, default 1) is the
+/// interface version callers can require with `id@>=N`. A `"methods"` entry may carry a
+/// `"schema"` (JSON Schema subset) the console validates payloads against before calling.
 #[sabi_trait]
 pub trait ServiceV1: Send + Sync {
     fn id(&self) -> CapabilityId;
     fn describe(&self) -> RString;
     fn call(&self, method: MethodName, payload: Blob) -> RResult<Blob, RString>;
+
+    /// `call` as the host runs it; [`PanicGuard`] overrides it to flag a caught panic.
+    fn call_checked(&self, method: MethodName, payload: Blob) -> CallOutcomeV1<Blob> {
+        self.call(method, payload).into()
+    }
 }
 
 pub type ServiceV1Dyn<'a> = ServiceV1_TO<'a, abi_stable::std_types::RBox<()>>;
 
+/// Paging convention for methods whose results can be large (marked `"paged": true` in
+/// `describe()`): the utf8 payload may carry `cursor=<opaque>` and `limit=<n>` tokens, and
+/// the JSON response carries [`PAGE_NEXT_FIELD`], a string to pass back as the cursor or
+/// `null` on the last page.
+pub const PAGE_CURSOR_ARG: &str = "cursor";
+pub const PAGE_LIMIT_ARG: &str = "limit";
+pub const PAGE_NEXT_FIELD: &str = "next_cursor";
+
+/// Splits the paging tokens off a utf8 payload: `(cursor, limit, remaining tokens)`.
+pub fn split_page_args(payload: &str) -> (Option<&str>, Option<usize>, Vec<&str>) {
+    let mut cursor = None;
+    let mut limit = None;
+    let mut rest = Vec::new();
+    for tok in payload.split_whitespace() {
+        match tok.split_once('=') {
+            Some((PAGE_CURSOR_ARG, v)) if !v.is_empty() => cursor = Some(v),
+            Some((PAGE_LIMIT_ARG, v)) if v.parse::<usize>().is_ok() => limit = v.parse().ok(),
+            _ => rest.push(tok),
+        }
+    }
+    (cursor, limit, rest)
+}
+
 #[sabi_trait]
 pub trait EventSinkV1: Send + Sync {
     fn on_event(&mut self, topic: RString, payload: Blob);
@@ -31,6 +64,78 @@ pub trait EventSinkV1: Send + Sync {
 
 pub type EventSinkV1Dyn<'a> = EventSinkV1_TO<'a, abi_stable::std_types::RBox<()>>;
 
+/* =============================================================================================
+   Asset API: the engine asset store, as seen by plugins
+   ============================================================================================= */
+
+/// Asset id across the ABI (`u128` has no stable C layout).
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, StableAbi)]
+pub struct AssetIdV1 {
+    pub lo: u64,
+    pub hi: u64,
+}
+
+impl AssetIdV1 {
+    #[inline]
+    pub fn from_u128(v: u128) -> Self {
+        Self {
+            lo: v as u64,
+            hi: (v >> 64) as u64,
+        }
+    }
+
+    #[inline]
+    pub fn to_u128(self) -> u128 {
+        ((self.hi as u128) << 64) | self.lo as u128
+    }
+}
+
+/// 32 hex digits, as in `asset.manager` responses and `asset.*` event payloads.
+impl std::fmt::Display for AssetIdV1 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:032x}", self.to_u128())
+    }
+}
+
+#[repr(u8)]
+#[derive(Debug, Clone, PartialEq, Eq, StableAbi)]
+pub enum AssetStateV1 {
+    Unloaded,
+    Loading,
+    Ready,
+    Failed(RString),
+}
+
+/// Imported asset; `payload` is copied out of the host on every `get_blob`.
+#[repr(C)]
+#[derive(Debug, Clone, StableAbi)]
+pub struct AssetBlobV1 {
+    pub type_id: RString,
+    pub format: RString,
+    pub meta_json: RString,
+    pub payload: Blob,
+}
+
+/// Engine asset store, from [`HostApiV1::asset_api_v1`].
+///
+/// Loads are asynchronous: `load` queues the import and returns the id right away; poll
+/// `state` or `subscribe` to find out when it is ready.
+#[sabi_trait]
+pub trait AssetApiV1: Send + Sync {
+    /// Requests `logical_path` (`ui/icon.png`, `mods://ui/icon.png`). Requesting an asset
+    /// again returns the same id.
+    fn load(&self, logical_path: RString) -> RResult<AssetIdV1, RString>;
+    fn state(&self, id: AssetIdV1) -> AssetStateV1;
+    /// `None` until the asset is ready.
+    fn get_blob(&self, id: AssetIdV1) -> ROption<AssetBlobV1>;
+    /// Receives `asset.ready`, `asset.reloaded`, `asset.failed` and `asset.cancelled`
+    /// (JSON payloads with a hex `id`) through the regular per-frame event dispatch.
+    fn subscribe(&self, sink: EventSinkV1Dyn<'static>) -> RResult<(), RString>;
+}
+
+pub type AssetApiV1Dyn<'a> = AssetApiV1_TO<'a, abi_stable::std_types::RBox<()>>;
+
 /* =============================================================================================
    Host API: pure bridge
    ============================================================================================= */
@@ -46,12 +151,64 @@ pub struct HostApiV1 {
 
     /// Call an already registered service by id.
     /// This avoids returning service objects across ABI and avoids Clone requirements.
+    /// The id may carry a version requirement (`asset.manager@>=2`, see [`ServiceRef`]); the
+    /// call fails with a descriptive error when no registered provider satisfies it.
     pub call_service_v1: extern "C" fn(CapabilityId, MethodName, Blob) -> RResult<Blob, RString>,
 
+    /// Queue an event; the host fans events out once per frame, before plugin
+    /// `fixed_update`/`update`. Fails when the payload or the queue exceeds host limits.
     pub emit_event_v1: extern "C" fn(RString, Blob) -> RResult<(), RString>,
+    /// Receive every event.
     pub subscribe_events_v1: extern "C" fn(EventSinkV1Dyn<'static>) -> RResult<(), RString>,
+    /// Receive events whose topic matches a pattern: exact (`winit.key`), prefix (`winit.*`)
+    /// or everything (`*`).
+    pub subscribe_topic_v1: extern "C" fn(RString, EventSinkV1Dyn<'static>) -> RResult<(), RString>,
+
+    /// System clipboard (UTF-8 text). Hosts without a platform clipboard use a process-local buffer.
+    pub clipboard_get: extern "C" fn() -> RResult<RString, RString>,
+    pub clipboard_set: extern "C" fn(RString) -> RResult<(), RString>,
+
+    /// Mouse cursor. Icon names are CSS cursor keywords (`default`, `pointer`, `text`,
+    /// `ew-resize`, ...); grab modes are `none`, `confined` and `locked`.
+    /// Requests are applied by the platform on the next frame.
+    pub set_cursor_icon: extern "C" fn(RString) -> RResult<(), RString>,
+    pub set_cursor_visible: extern "C" fn(bool),
+    pub set_cursor_grab: extern "C" fn(RString) -> RResult<(), RString>,
+
+    /// Engine asset store; `None` on hosts without one.
+    pub asset_api_v1: extern "C" fn() -> ROption<AssetApiV1Dyn<'static>>,
+
+    /// The host frame in progress (the one last passed to [`PluginModule::begin_frame`]).
+    pub frame_info_v1: extern "C" fn() -> FrameInfoAbi,
+
+    /// Runs `job` on the host job system. With `frame_scoped` the job is finished before the
+    /// current host frame ends (before [`PluginModule::end_frame`]); otherwise it runs whenever
+    /// a worker is free. Jobs must not block on other jobs.
+    pub submit_job: extern "C" fn(JobV1Dyn<'static>, bool) -> RResult<(), RString>,
+
+    /// True once the asset import running on the calling thread was cancelled. Importer
+    /// services poll it between stages and fail early; always false outside an import.
+    pub import_cancelled: extern "C" fn() -> bool,
 }
 
+/* =============================================================================================
+   Jobs
+   ============================================================================================= */
+
+/// Work item for `HostApiV1::submit_job`, run once on a host worker thread. An `RErr` (or a
+/// panic caught by [`guarded_job`]) counts as a fault of the submitting plugin.
+#[sabi_trait]
+pub trait JobV1: Send {
+    fn run(&mut self) -> RResult<(), RString>;
+
+    /// `run` as the host runs it; [`PanicGuard`] overrides it to flag a caught panic.
+    fn run_checked(&mut self) -> CallOutcomeV1<()> {
+        self.run().into()
+    }
+}
+
+pub type JobV1Dyn<'a> = JobV1_TO<'a, abi_stable::std_types::RBox<()>>;
+
 /* =============================================================================================
    Plugin module ABI
    ============================================================================================= */
@@ -62,8 +219,31 @@ pub struct PluginInfo {
     pub id: RString,
     pub name: RString,
     pub version: RString,
+    /// Host capabilities (`host.clipboard`) and services (`asset.manager@>=1`) the plugin needs,
+    /// in [`ServiceRef`] syntax. Host capabilities are checked before `init`, services before
+    /// `start`; a plugin with an unmet requirement is not run.
+    pub requires: RVec<RString>,
+}
+
+/// Host frame passed to [`PluginModule::begin_frame`].
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, StableAbi)]
+pub struct FrameInfoAbi {
+    /// Monotonic variable-frame index, the same one `update`/`render` run in.
+    pub frame_index: u64,
+    /// Variable-frame delta in seconds.
+    pub dt: f32,
+    /// Seconds since the host engine started.
+    pub wall_time: f64,
+    /// Presentation waits for the display refresh; false for headless hosts.
+    pub vsync: bool,
+    /// How far `update`/`render` are between the last two fixed ticks, in `[0..1)`, for
+    /// interpolating fixed-step state. `0.0` until this frame's fixed steps ran.
+    pub fixed_alpha: f32,
 }
 
+/// Per frame the host calls `begin_frame`, `fixed_update` (zero or more times), `update`,
+/// `render` and `end_frame`, in that order.
 #[sabi_trait]
 pub trait PluginModule: Send + Sync {
     fn info(&self) -> PluginInfo;
@@ -76,10 +256,249 @@ pub trait PluginModule: Send + Sync {
     fn render(&mut self, dt: f32) -> RResult<(), RString>;
 
     fn shutdown(&mut self);
+
+    /// Runs before the frame's events are delivered.
+    fn begin_frame(&mut self, frame: FrameInfoAbi) -> RResult<(), RString> {
+        let _ = frame;
+        RResult::ROk(())
+    }
+
+    /// Runs last in the host frame; the place for per-frame cleanup.
+    fn end_frame(&mut self) -> RResult<(), RString> {
+        RResult::ROk(())
+    }
 }
 
 pub type PluginModuleDyn<'a> = PluginModule_TO<'a, abi_stable::std_types::RBox<()>>;
 
+/* =============================================================================================
+   Panic guards
+   ============================================================================================= */
+
+/// Wraps a plugin object so a panic in it becomes an `RErr` on its own side of the ABI.
+///
+/// Trait objects are called through `extern "C"` shims, and a panic unwinding out of one
+/// aborts the process; a `catch_unwind` in the caller never sees it. Build every
+/// [`PluginModuleDyn`], [`ServiceV1Dyn`], [`EventSinkV1Dyn`] and [`JobV1Dyn`] through
+/// [`guarded_module`] and friends instead of `from_value`. `info`, `id` and `describe` are
+/// forwarded unguarded and must not panic. Needs `panic = "unwind"` in the building profile.
+///
+/// The host calls services and jobs through `call_checked`/`run_checked`, whose
+/// [`CallOutcomeV1::panicked`] tells a caught panic apart from an error the plugin returned.
+pub struct PanicGuard<T>(pub T);
+
+/// Result of a [`ServiceV1::call_checked`] or [`JobV1::run_checked`].
+#[repr(C)]
+#[derive(Debug, Clone, StableAbi)]
+pub struct CallOutcomeV1<T> {
+    pub result: RResult<T, RString>,
+    /// The callee panicked; `result` is an error describing the panic.
+    pub panicked: bool,
+}
+
+impl<T> From<RResult<T, RString>> for CallOutcomeV1<T> {
+    #[inline]
+    fn from(result: RResult<T, RString>) -> Self {
+        Self {
+            result,
+            panicked: false,
+        }
+    }
+}
+
+impl<T: PluginModule> PluginModule for PanicGuard<T> {
+    fn info(&self) -> PluginInfo {
+        self.0.info()
+    }
+
+    fn init(&mut self, host: HostApiV1) -> RResult<(), RString> {
+        guard("init", || self.0.init(host)).result
+    }
+
+    fn start(&mut self) -> RResult<(), RString> {
+        guard("start", || self.0.start()).result
+    }
+
+    fn fixed_update(&mut self, dt: f32) -> RResult<(), RString> {
+        guard("fixed_update", || self.0.fixed_update(dt)).result
+    }
+
+    fn update(&mut self, dt: f32) -> RResult<(), RString> {
+        guard("update", || self.0.update(dt)).result
+    }
+
+    fn render(&mut self, dt: f32) -> RResult<(), RString> {
+        guard("render", || self.0.render(dt)).result
+    }
+
+    fn shutdown(&mut self) {
+        let _ = guard("shutdown", || {
+            self.0.shutdown();
+            RResult::ROk(())
+        });
+    }
+
+    fn begin_frame(&mut self, frame: FrameInfoAbi) -> RResult<(), RString> {
+        guard("begin_frame", || self.0.begin_frame(frame)).result
+    }
+
+    fn end_frame(&mut self) -> RResult<(), RString> {
+        guard("end_frame", || self.0.end_frame()).result
+    }
+}
+
+impl<T: ServiceV1> ServiceV1 for PanicGuard<T> {
+    fn id(&self) -> CapabilityId {
+        self.0.id()
+    }
+
+    fn describe(&self) -> RString {
+        self.0.describe()
+    }
+
+    fn call(&self, method: MethodName, payload: Blob) -> RResult<Blob, RString> {
+        self.call_checked(method, payload).result
+    }
+
+    fn call_checked(&self, method: MethodName, payload: Blob) -> CallOutcomeV1<Blob> {
+        guard("call", || self.0.call(method, payload))
+    }
+}
+
+impl<T: EventSinkV1> EventSinkV1 for PanicGuard<T> {
+    fn on_event(&mut self, topic: RString, payload: Blob) {
+        let _ = guard("on_event", || {
+            self.0.on_event(topic, payload);
+            RResult::ROk(())
+        });
+    }
+}
+
+impl<T: JobV1> JobV1 for PanicGuard<T> {
+    fn run(&mut self) -> RResult<(), RString> {
+        self.run_checked().result
+    }
+
+    fn run_checked(&mut self) -> CallOutcomeV1<()> {
+        guard("run", || self.0.run())
+    }
+}
+
+#[inline]
+pub fn guarded_module<T: PluginModule + 'static>(module: T) -> PluginModuleDyn<'static> {
+    PluginModule_TO::from_value(PanicGuard(module), TD_Opaque)
+}
+
+#[inline]
+pub fn guarded_service<T: ServiceV1 + 'static>(service: T) -> ServiceV1Dyn<'static> {
+    ServiceV1_TO::from_value(PanicGuard(service), TD_Opaque)
+}
+
+#[inline]
+pub fn guarded_sink<T: EventSinkV1 + 'static>(sink: T) -> EventSinkV1Dyn<'static> {
+    EventSinkV1_TO::from_value(PanicGuard(sink), TD_Opaque)
+}
+
+#[inline]
+pub fn guarded_job<T: JobV1 + 'static>(job: T) -> JobV1Dyn<'static> {
+    JobV1_TO::from_value(PanicGuard(job), TD_Opaque)
+}
+
+fn guard<R>(op: &str, f: impl FnOnce() -> RResult<R, RString>) -> CallOutcomeV1<R> {
+    match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(r) => r.into(),
+        Err(payload) => {
+            let msg = payload
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "non-string payload".to_string());
+            CallOutcomeV1 {
+                result: RResult::RErr(RString::from(format!("panic in {op}: {msg}"))),
+                panicked: true,
+            }
+        }
+    }
+}
+
+/* =============================================================================================
+   Versioned service references
+   ============================================================================================= */
+
+/// Version constraint of a [`ServiceRef`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VersionReq {
+    Any,
+    Exact(u32),
+    AtLeast(u32),
+}
+
+impl VersionReq {
+    #[inline]
+    pub fn matches(self, version: u32) -> bool {
+        match self {
+            VersionReq::Any => true,
+            VersionReq::Exact(v) => version == v,
+            VersionReq::AtLeast(v) => version >= v,
+        }
+    }
+}
+
+impl std::fmt::Display for VersionReq {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VersionReq::Any => Ok(()),
+            VersionReq::Exact(v) => write!(f, "@{v}"),
+            VersionReq::AtLeast(v) => write!(f, "@>={v}"),
+        }
+    }
+}
+
+/// A capability or service id with an optional version requirement:
+/// `asset.manager`, `asset.manager@2` (exactly 2) or `asset.manager@>=2`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ServiceRef<'a> {
+    pub id: &'a str,
+    pub req: VersionReq,
+}
+
+impl<'a> ServiceRef<'a> {
+    pub fn parse(s: &'a str) -> Result<Self, String> {
+        let s = s.trim();
+        let (id, req) = match s.split_once('@') {
+            None => (s, VersionReq::Any),
+            Some((id, v)) => {
+                let v = v.trim();
+                let (at_least, num) = match v.strip_prefix(">=") {
+                    Some(n) => (true, n),
+                    None => (false, v.strip_prefix('=').unwrap_or(v)),
+                };
+                let n: u32 = num
+                    .trim()
+                    .parse()
+                    .map_err(|_| format!("invalid version requirement in '{s}'"))?;
+                let req = if at_least {
+                    VersionReq::AtLeast(n)
+                } else {
+                    VersionReq::Exact(n)
+                };
+                (id.trim(), req)
+            }
+        };
+
+        if id.is_empty() {
+            return Err(format!("empty id in '{s}'"));
+        }
+        Ok(Self { id, req })
+    }
+}
+
+impl std::fmt::Display for ServiceRef<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}", self.id, self.req)
+    }
+}
+
 /* =============================================================================================
    Root module ABI
    ============================================================================================= */
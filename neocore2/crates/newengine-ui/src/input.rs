@@ -20,9 +20,15 @@ pub struct UiInputFrame {
     /// Text typed since last `text_take_json` in input plugin.
     pub text: String,
 
-    /// IME preedit is typically frame-local. Provided if you choose to expose it later.
+    /// Current IME composition (stateful; empty when nothing is being composed).
     pub ime_preedit: String,
 
+    /// Cursor/selection inside `ime_preedit` as byte offsets, when the IME reports one.
+    pub ime_preedit_cursor: Option<(usize, usize)>,
+
+    /// Platform IME is enabled for the window.
+    pub ime_enabled: bool,
+
     /// IME commit text (taken via `ime_commit_take_json`).
     pub ime_commit: String,
 }
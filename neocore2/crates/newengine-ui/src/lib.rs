@@ -1,6 +1,7 @@
 #![forbid(unsafe_op_in_unsafe_fn)]
 
 pub mod draw;
+pub mod painter;
 pub mod texture;
 
 pub mod input;
@@ -10,8 +11,10 @@ pub mod providers;
 pub mod markup;
 
 pub use input::UiInputFrame;
+pub use painter::{ui_color, UiPainter};
 pub use provider::{
-    UiBuildFn, UiFrameDesc, UiFrameOutput, UiProvider, UiProviderKind, UiProviderOptions,
+    UiBuildFn, UiClipboard, UiFrameDesc, UiFrameOutput, UiProvider, UiProviderKind,
+    UiProviderOptions,
 };
 pub use providers::create_provider;
 
@@ -54,13 +54,36 @@ pub(crate) fn parse_actions_for(
     }
 }
 
+/// Action lists are `|`- or `,`-separated. A `call:`/`set:` action runs to the next `|`, so
+/// its payload may contain commas (JSON): `on_click="a,b|call:svc/m:{"x":1,"y":2}"`.
 #[inline]
 fn split_actions_into(s: &str, out: &mut SmallVec<[String; 2]>) {
-    for part in s.split(|c| c == ',' || c == '|') {
-        let p = part.trim();
-        if p.is_empty() {
-            continue;
+    for part in s.split('|') {
+        let mut rest = part;
+        loop {
+            let head = rest.trim_start();
+            if head.starts_with("call:") || head.starts_with("set:") {
+                push_action(head, out);
+                break;
+            }
+            match rest.split_once(',') {
+                Some((action, tail)) => {
+                    push_action(action, out);
+                    rest = tail;
+                }
+                None => {
+                    push_action(rest, out);
+                    break;
+                }
+            }
         }
-        out.push(p.to_string());
     }
-}
\ No newline at end of file
+}
+
+#[inline]
+fn push_action(s: &str, out: &mut SmallVec<[String; 2]>) {
+    let s = s.trim();
+    if !s.is_empty() {
+        out.push(s.to_string());
+    }
+}
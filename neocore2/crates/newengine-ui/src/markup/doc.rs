@@ -5,7 +5,7 @@ use std::time::{Duration, Instant};
 
 use roxmltree::Document;
 
-use newengine_assets::{AssetKey, AssetState, AssetStore, TextReader};
+use newengine_assets::{AssetBlob, AssetKey, AssetState, AssetStore, TextReader};
 
 use crate::markup::error::UiMarkupError;
 use crate::markup::parser::{parse_theme, parse_ui_root};
@@ -64,6 +64,11 @@ impl UiMarkupDoc {
 
         let blob = store.get_blob(id).ok_or(UiMarkupError::BlobMissing)?;
 
+        Self::from_blob(&blob)
+    }
+
+    /// Parses a document from an already imported text blob (e.g. after a reload).
+    pub fn from_blob(blob: &AssetBlob) -> Result<Self, UiMarkupError> {
         let doc = TextReader::from_blob_parts(&blob.meta_json, &blob.payload)
             .map_err(|e| UiMarkupError::TextRead(e.to_string()))?;
 
@@ -85,6 +90,13 @@ impl UiMarkupDoc {
         crate::markup::egui_render::render_doc(self, ctx, state);
     }
 
+    /// Renders without applying this document's theme, for fragments shown alongside a
+    /// themed host document (e.g. plugin panels in the editor).
+    #[cfg(feature = "egui")]
+    pub fn render_fragment(&self, ctx: &egui::Context, state: &mut crate::markup::UiState) {
+        crate::markup::egui_render::render_doc_unthemed(self, ctx, state);
+    }
+
     #[inline]
     pub fn theme(&self) -> &UiThemeDesc {
         &self.theme
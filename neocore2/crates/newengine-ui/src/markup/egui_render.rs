@@ -1,7 +1,7 @@
 #![forbid(unsafe_op_in_unsafe_fn)]
 
 #[cfg(feature = "egui")]
-use crate::markup::substitute::substitute_vars;
+use crate::markup::substitute::{localize, substitute_vars};
 #[cfg(feature = "egui")]
 use crate::markup::theme::{UiDensity, UiThemeDesc, UiVisuals};
 #[cfg(feature = "egui")]
@@ -15,6 +15,11 @@ pub(crate) fn render_doc(doc: &UiMarkupDoc, ctx: &egui::Context, state: &mut UiS
     render_root(&doc.root, ctx, state);
 }
 
+#[cfg(feature = "egui")]
+pub(crate) fn render_doc_unthemed(doc: &UiMarkupDoc, ctx: &egui::Context, state: &mut UiState) {
+    render_root(&doc.root, ctx, state);
+}
+
 #[cfg(feature = "egui")]
 fn render_root(root: &UiNode, ctx: &egui::Context, state: &mut UiState) {
     match root {
@@ -33,16 +38,22 @@ fn render_root(root: &UiNode, ctx: &egui::Context, state: &mut UiState) {
             });
         }
         UiNode::Window {
+            id,
             title,
             open,
             children,
         } => {
-            let mut is_open = *open;
-            egui::Window::new(title).open(&mut is_open).show(ctx, |ui| {
-                for c in children {
-                    render_in_ui(c, ui, state);
-                }
-            });
+            let mut is_open = state.panel_open(id).unwrap_or(*open);
+            let title = substitute_vars(localize(title, &state.texts), &state.vars).into_owned();
+            egui::Window::new(title)
+                .id(egui::Id::new(("ui_window", id.as_str())))
+                .open(&mut is_open)
+                .show(ctx, |ui| {
+                    for c in children {
+                        render_in_ui(c, ui, state);
+                    }
+                });
+            state.panels.insert(id.clone(), is_open);
         }
         _ => {}
     }
@@ -73,13 +84,13 @@ fn render_in_ui(node: &UiNode, ui: &mut egui::Ui, state: &mut UiState) {
                     .map(String::as_str)
                     .unwrap_or(text.as_str())
             } else {
-                text.as_str()
+                localize(text, &state.texts)
             };
             let s = substitute_vars(base, &state.vars);
             ui.label(s.as_ref());
         }
         UiNode::Button { id, text, on_click } => {
-            let s = substitute_vars(text, &state.vars);
+            let s = substitute_vars(localize(text, &state.texts), &state.vars);
             if ui.button(s.as_ref()).clicked() {
                 state.clicked.insert(id.clone(), true);
 
@@ -101,7 +112,7 @@ fn render_in_ui(node: &UiNode, ui: &mut egui::Ui, state: &mut UiState) {
             on_change,
             on_submit,
         } => {
-            let hint = substitute_vars(hint, &state.vars);
+            let hint = substitute_vars(localize(hint, &state.texts), &state.vars).into_owned();
 
             let (changed, submit_now, value_snapshot) = {
                 let entry = state.strings.entry(bind.clone()).or_default();
@@ -109,13 +120,13 @@ fn render_in_ui(node: &UiNode, ui: &mut egui::Ui, state: &mut UiState) {
                 let resp = if *multiline {
                     ui.add(
                         egui::TextEdit::multiline(entry)
-                            .hint_text(hint.as_ref())
+                            .hint_text(hint.as_str())
                             .desired_width(f32::INFINITY),
                     )
                 } else {
                     ui.add(
                         egui::TextEdit::singleline(entry)
-                            .hint_text(hint.as_ref())
+                            .hint_text(hint.as_str())
                             .desired_width(f32::INFINITY),
                     )
                 };
@@ -5,6 +5,7 @@ mod doc;
 mod egui_render;
 mod error;
 mod parser;
+mod router;
 mod state;
 mod substitute;
 mod theme;
@@ -12,5 +13,6 @@ mod ui_node;
 
 pub use doc::UiMarkupDoc;
 pub use error::UiMarkupError;
+pub use router::{UiAction, UiActionRouter, UiServiceCallFn, ACTION_ERROR_VAR, ACTION_RESULT_VAR};
 pub use state::{UiEvent, UiEventKind, UiState};
 pub use theme::{UiDensity, UiThemeDesc, UiVisuals};
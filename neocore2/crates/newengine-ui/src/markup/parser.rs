@@ -63,11 +63,13 @@ fn parse_node(n: Node) -> Result<UiNode, String> {
         }),
         "window" => {
             let title = attr(n, "title").unwrap_or_else(|| "Window".to_string());
+            let id = attr(n, "id").unwrap_or_else(|| title.clone());
             let open = attr(n, "open")
                 .map(|v| v == "true" || v == "1" || v == "yes")
                 .unwrap_or(true);
 
             Ok(UiNode::Window {
+                id,
                 title,
                 open,
                 children: parse_children(n)?,
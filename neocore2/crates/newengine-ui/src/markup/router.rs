@@ -0,0 +1,149 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use std::borrow::Cow;
+
+use crate::markup::state::{UiEvent, UiState};
+use crate::markup::substitute::substitute_vars;
+
+/// Service call hook used by `call:` actions: `(service_id, method, payload) -> response`.
+///
+/// The UI crate does not depend on the host; apps pass `newengine_core::call_service_v1`.
+pub type UiServiceCallFn = dyn FnMut(&str, &str, &[u8]) -> Result<Vec<u8>, String> + Send;
+
+/// Var that receives the last `call:` response (utf-8 lossy).
+pub const ACTION_RESULT_VAR: &str = "action.result";
+/// Var that receives the last action error; cleared on success.
+pub const ACTION_ERROR_VAR: &str = "action.error";
+
+/// Parsed form of a standard action string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UiAction<'a> {
+    /// `call:<service>/<method>[:<payload>]`
+    Call {
+        service: &'a str,
+        method: &'a str,
+        payload: &'a str,
+    },
+    /// `set:$var=value` (the leading `$` is optional)
+    Set { var: &'a str, value: &'a str },
+    /// Anything else; left for the app to interpret.
+    Custom(&'a str),
+}
+
+impl<'a> UiAction<'a> {
+    pub fn parse(s: &'a str) -> Self {
+        let s = s.trim();
+
+        if let Some(rest) = s.strip_prefix("call:") {
+            let (target, payload) = rest.split_once(':').unwrap_or((rest, ""));
+            if let Some((service, method)) = target.split_once('/') {
+                let (service, method) = (service.trim(), method.trim());
+                if !service.is_empty() && !method.is_empty() {
+                    return UiAction::Call {
+                        service,
+                        method,
+                        payload,
+                    };
+                }
+            }
+            return UiAction::Custom(s);
+        }
+
+        if let Some(rest) = s.strip_prefix("set:") {
+            if let Some((var, value)) = rest.split_once('=') {
+                let var = var.trim().trim_start_matches('$');
+                if !var.is_empty() {
+                    return UiAction::Set { var, value };
+                }
+            }
+            return UiAction::Custom(s);
+        }
+
+        UiAction::Custom(s)
+    }
+}
+
+/// Dispatches standard `call:` / `set:` actions from `UiState` events.
+///
+/// `$name` references in payloads and values are substituted from `UiState::vars`;
+/// `$value` refers to the event value (text box contents for change/submit).
+pub struct UiActionRouter {
+    call: Box<UiServiceCallFn>,
+}
+
+impl UiActionRouter {
+    #[inline]
+    pub fn new<F>(call: F) -> Self
+    where
+        F: FnMut(&str, &str, &[u8]) -> Result<Vec<u8>, String> + Send + 'static,
+    {
+        Self {
+            call: Box::new(call),
+        }
+    }
+
+    /// Drains pending events and runs their standard actions.
+    ///
+    /// Returns events that still carry custom actions, with only those actions left.
+    pub fn dispatch(&mut self, state: &mut UiState) -> Vec<UiEvent> {
+        let mut rest = Vec::new();
+
+        for mut ev in state.drain_events() {
+            let mut custom = smallvec::SmallVec::<[String; 2]>::new();
+
+            for action in ev.actions.iter() {
+                match UiAction::parse(action) {
+                    UiAction::Call {
+                        service,
+                        method,
+                        payload,
+                    } => {
+                        let payload = expand(payload, ev.value.as_deref(), state);
+                        match (self.call)(service, method, payload.as_bytes()) {
+                            Ok(bytes) => {
+                                state.set_var(
+                                    ACTION_RESULT_VAR,
+                                    String::from_utf8_lossy(&bytes).to_string(),
+                                );
+                                state.vars.remove(ACTION_ERROR_VAR);
+                            }
+                            Err(e) => {
+                                state.set_var(ACTION_ERROR_VAR, format!("{service}/{method}: {e}"));
+                            }
+                        }
+                    }
+                    UiAction::Set { var, value } => {
+                        let value = expand(value, ev.value.as_deref(), state).into_owned();
+                        state.set_var(var, value);
+                    }
+                    UiAction::Custom(_) => custom.push(action.clone()),
+                }
+            }
+
+            if !custom.is_empty() {
+                ev.actions = custom;
+                rest.push(ev);
+            }
+        }
+
+        rest
+    }
+}
+
+impl std::fmt::Debug for UiActionRouter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UiActionRouter").finish_non_exhaustive()
+    }
+}
+
+#[inline]
+fn expand<'a>(src: &'a str, value: Option<&str>, state: &UiState) -> Cow<'a, str> {
+    match value {
+        Some(v) if src.contains("$value") => {
+            let mut vars = state.vars.clone();
+            vars.insert("value".to_string(), v.to_string());
+            Cow::Owned(substitute_vars(src, &vars).into_owned())
+        }
+        _ => substitute_vars(src, &state.vars),
+    }
+}
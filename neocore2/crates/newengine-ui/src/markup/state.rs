@@ -24,6 +24,10 @@ pub struct UiState {
     pub clicked: AHashMap<String, bool>,
     pub vars: AHashMap<String, String>,
     pub unknown_tags: AHashMap<String, u32>,
+    /// Open/closed state of markup windows keyed by window id (falls back to title).
+    pub panels: AHashMap<String, bool>,
+    /// Localized strings used to resolve `text="@key"` references.
+    pub texts: AHashMap<String, String>,
 
     events: Vec<UiEvent>,
 }
@@ -39,6 +43,27 @@ impl UiState {
         self.vars.insert(k.into(), v.into());
     }
 
+    /// Returns the last known open state of a markup window, if it was rendered or set.
+    #[inline]
+    pub fn panel_open(&self, id: &str) -> Option<bool> {
+        self.panels.get(id).copied()
+    }
+
+    #[inline]
+    pub fn set_panel_open(&mut self, id: impl Into<String>, open: bool) {
+        self.panels.insert(id.into(), open);
+    }
+
+    /// Replaces the localized string table (e.g. after a locale switch).
+    #[inline]
+    pub fn set_texts<I>(&mut self, texts: I)
+    where
+        I: IntoIterator<Item = (String, String)>,
+    {
+        self.texts.clear();
+        self.texts.extend(texts);
+    }
+
     #[inline]
     pub fn drain_events(&mut self) -> Vec<UiEvent> {
         std::mem::take(&mut self.events)
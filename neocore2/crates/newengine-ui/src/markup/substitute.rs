@@ -40,4 +40,18 @@ pub fn substitute_vars<'a>(src: &'a str, vars: &AHashMap<String, String>) -> Cow
 #[inline]
 fn is_var_char(c: u8) -> bool {
     matches!(c, b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'_' | b'.' | b'-')
-}
\ No newline at end of file
+}
+
+/// Resolves a `@key` reference against a localized string table.
+///
+/// Unknown keys resolve to the key itself; `@@text` escapes a literal leading '@'.
+#[inline]
+pub fn localize<'a>(src: &'a str, texts: &'a AHashMap<String, String>) -> &'a str {
+    let Some(key) = src.strip_prefix('@') else {
+        return src;
+    };
+    if key.starts_with('@') {
+        return key;
+    }
+    texts.get(key).map(String::as_str).unwrap_or(key)
+}
@@ -11,6 +11,7 @@ pub(crate) enum UiNode {
         children: Vec<UiNode>,
     },
     Window {
+        id: String,
         title: String,
         open: bool,
         children: Vec<UiNode>,
@@ -0,0 +1,245 @@
+#![forbid(unsafe_op_in_unsafe_fn)]
+
+use crate::draw::{UiDrawCmd, UiDrawList, UiRect, UiTexId, UiTexture, UiVertex};
+use crate::texture::reserved;
+
+mod font8x8 {
+    include!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/src/font8x8_ascii.inl"
+    ));
+
+    #[inline]
+    pub(super) fn table() -> &'static [[u8; 8]] {
+        &FONT8X8_ASCII
+    }
+}
+
+const GLYPH_PX: u32 = 8;
+const ATLAS_COLS: u32 = 16;
+const ATLAS_ROWS: u32 = 6;
+/// 95 printable ASCII glyphs followed by one solid cell used for fills.
+const SOLID_CELL: u32 = 95;
+
+/// Premultiplied RGBA in the vertex color layout used by [`UiVertex`].
+#[inline]
+pub fn ui_color(r: u8, g: u8, b: u8, a: u8) -> u32 {
+    let pm = |c: u8| ((c as u32 * a as u32 + 127) / 255) as u8;
+    u32::from_le_bytes([pm(r), pm(g), pm(b), a])
+}
+
+/// Immediate-mode shapes and 8x8 bitmap text appended to a [`UiDrawList`].
+///
+/// Uses its own font texture ([`reserved::PAINTER_FONT`]) so it works the same under any
+/// provider; call [`UiPainter::upload_font`] once before the first painted frame reaches the
+/// backend.
+pub struct UiPainter<'a> {
+    list: &'a mut UiDrawList,
+    clip: UiRect,
+}
+
+impl<'a> UiPainter<'a> {
+    #[inline]
+    pub fn new(list: &'a mut UiDrawList) -> Self {
+        let [w, h] = list.screen_size_px;
+        Self {
+            list,
+            clip: UiRect {
+                min_x: 0.0,
+                min_y: 0.0,
+                max_x: w as f32,
+                max_y: h as f32,
+            },
+        }
+    }
+
+    /// Adds the font atlas to the list's texture delta.
+    pub fn upload_font(list: &mut UiDrawList) {
+        let (w, h) = (ATLAS_COLS * GLYPH_PX, ATLAS_ROWS * GLYPH_PX);
+        let mut rgba8 = vec![0u8; (w * h * 4) as usize];
+
+        let mut put = |x: u32, y: u32, on: bool| {
+            let i = ((y * w + x) * 4) as usize;
+            rgba8[i..i + 4].copy_from_slice(&[255, 255, 255, if on { 255 } else { 0 }]);
+        };
+
+        for (cell, glyph) in font8x8::table().iter().enumerate() {
+            let (cx, cy) = cell_origin(cell as u32);
+            for (row, bits) in glyph.iter().enumerate() {
+                for col in 0..GLYPH_PX {
+                    put(cx + col, cy + row as u32, bits & (1 << col) != 0);
+                }
+            }
+        }
+        let (sx, sy) = cell_origin(SOLID_CELL);
+        for y in 0..GLYPH_PX {
+            for x in 0..GLYPH_PX {
+                put(sx + x, sy + y, true);
+            }
+        }
+
+        list.texture_delta.set.insert(
+            reserved::PAINTER_FONT,
+            UiTexture {
+                size: [w, h],
+                rgba8,
+            },
+        );
+    }
+
+    /// Pixel size of `text` drawn at `scale` (glyphs are `8 * scale` px).
+    #[inline]
+    pub fn text_size(text: &str, scale: f32) -> [f32; 2] {
+        let px = GLYPH_PX as f32 * scale;
+        let cols = text.lines().map(|l| l.chars().count()).max().unwrap_or(0);
+        let rows = text.lines().count().max(1);
+        [cols as f32 * px, rows as f32 * px]
+    }
+
+    pub fn rect_filled(&mut self, min: [f32; 2], max: [f32; 2], color: u32) {
+        let uv = solid_uv();
+        self.quad(min, max, [uv, uv], color);
+    }
+
+    /// Draws the whole of `texture` stretched over the rect, modulated by `tint`.
+    pub fn image(&mut self, min: [f32; 2], max: [f32; 2], texture: UiTexId, tint: u32) {
+        self.push_tex(
+            texture,
+            &[
+                vertex(min, [0.0, 0.0], tint),
+                vertex([max[0], min[1]], [1.0, 0.0], tint),
+                vertex(max, [1.0, 1.0], tint),
+                vertex([min[0], max[1]], [0.0, 1.0], tint),
+            ],
+            &[0, 1, 2, 0, 2, 3],
+        );
+    }
+
+    /// Straight segment of the given pixel width.
+    pub fn line(&mut self, a: [f32; 2], b: [f32; 2], width: f32, color: u32) {
+        let (dx, dy) = (b[0] - a[0], b[1] - a[1]);
+        let len = (dx * dx + dy * dy).sqrt();
+        if len <= f32::EPSILON {
+            return;
+        }
+        let (nx, ny) = (-dy / len * width * 0.5, dx / len * width * 0.5);
+        let uv = solid_uv();
+
+        self.push(
+            &[
+                vertex([a[0] + nx, a[1] + ny], uv, color),
+                vertex([b[0] + nx, b[1] + ny], uv, color),
+                vertex([b[0] - nx, b[1] - ny], uv, color),
+                vertex([a[0] - nx, a[1] - ny], uv, color),
+            ],
+            &[0, 1, 2, 0, 2, 3],
+        );
+    }
+
+    pub fn polyline(&mut self, points: &[[f32; 2]], width: f32, color: u32) {
+        for w in points.windows(2) {
+            self.line(w[0], w[1], width, color);
+        }
+    }
+
+    /// Draws `text` with its top-left corner at `pos`; `\n` starts a new line.
+    pub fn text(&mut self, pos: [f32; 2], text: &str, scale: f32, color: u32) {
+        let px = GLYPH_PX as f32 * scale;
+        let (mut x, mut y) = (pos[0], pos[1]);
+
+        for ch in text.chars() {
+            if ch == '\n' {
+                x = pos[0];
+                y += px;
+                continue;
+            }
+            let code = ch as u32;
+            let cell = if (32..127).contains(&code) {
+                code - 32
+            } else {
+                '?' as u32 - 32
+            };
+            if cell != 0 {
+                self.quad([x, y], [x + px, y + px], cell_uv(cell), color);
+            }
+            x += px;
+        }
+    }
+
+    #[inline]
+    fn quad(&mut self, min: [f32; 2], max: [f32; 2], uv: [[f32; 2]; 2], color: u32) {
+        self.push(
+            &[
+                vertex(min, uv[0], color),
+                vertex([max[0], min[1]], [uv[1][0], uv[0][1]], color),
+                vertex(max, uv[1], color),
+                vertex([min[0], max[1]], [uv[0][0], uv[1][1]], color),
+            ],
+            &[0, 1, 2, 0, 2, 3],
+        );
+    }
+
+    #[inline]
+    fn push(&mut self, vertices: &[UiVertex], indices: &[u32]) {
+        self.push_tex(reserved::PAINTER_FONT, vertices, indices);
+    }
+
+    /// Appends geometry, extending the last command when it already uses `texture`.
+    fn push_tex(&mut self, texture: UiTexId, vertices: &[UiVertex], indices: &[u32]) {
+        let mesh = &mut self.list.mesh;
+        let base_v = mesh.vertices.len() as u32;
+        let base_i = mesh.indices.len() as u32;
+
+        mesh.vertices.extend_from_slice(vertices);
+        mesh.indices.extend(indices.iter().map(|i| base_v + i));
+        let end = mesh.indices.len() as u32;
+
+        match mesh.cmds.last_mut() {
+            Some(c)
+                if c.texture == texture
+                    && c.clip_rect == self.clip
+                    && c.index_range.end == base_i =>
+            {
+                c.index_range.end = end;
+            }
+            _ => mesh.cmds.push(UiDrawCmd {
+                texture,
+                clip_rect: self.clip,
+                index_range: base_i..end,
+            }),
+        }
+    }
+}
+
+#[inline]
+fn vertex(pos: [f32; 2], uv: [f32; 2], color: u32) -> UiVertex {
+    UiVertex { pos, uv, color }
+}
+
+#[inline]
+fn cell_origin(cell: u32) -> (u32, u32) {
+    (
+        (cell % ATLAS_COLS) * GLYPH_PX,
+        (cell / ATLAS_COLS) * GLYPH_PX,
+    )
+}
+
+#[inline]
+fn cell_uv(cell: u32) -> [[f32; 2]; 2] {
+    let (x, y) = cell_origin(cell);
+    let (w, h) = (
+        (ATLAS_COLS * GLYPH_PX) as f32,
+        (ATLAS_ROWS * GLYPH_PX) as f32,
+    );
+    [
+        [x as f32 / w, y as f32 / h],
+        [(x + GLYPH_PX) as f32 / w, (y + GLYPH_PX) as f32 / h],
+    ]
+}
+
+/// Center of the solid cell, so filtering never reaches transparent texels.
+#[inline]
+fn solid_uv() -> [f32; 2] {
+    let [min, max] = cell_uv(SOLID_CELL);
+    [(min[0] + max[0]) * 0.5, (min[1] + max[1]) * 0.5]
+}
@@ -11,12 +11,19 @@ pub struct UiFrameDesc {
 
     /// Input snapshot provided by the host (must originate from INPUT plugin).
     pub input: Option<UiInputFrame>,
+
+    /// Window scale factor (physical px per logical px). `None` keeps the provider's own value.
+    pub pixels_per_point: Option<f32>,
 }
 
 impl UiFrameDesc {
     #[inline]
     pub fn new(dt_sec: f32) -> Self {
-        Self { dt_sec, input: None }
+        Self {
+            dt_sec,
+            input: None,
+            pixels_per_point: None,
+        }
     }
 
     #[inline]
@@ -24,12 +31,20 @@ impl UiFrameDesc {
         self.input = Some(input);
         self
     }
+
+    #[inline]
+    pub fn with_pixels_per_point(mut self, pixels_per_point: f32) -> Self {
+        self.pixels_per_point = Some(pixels_per_point);
+        self
+    }
 }
 
 /// Output of a UI frame.
 #[derive(Debug, Clone)]
 pub struct UiFrameOutput {
     pub draw_list: UiDrawList,
+    /// UI has keyboard focus (e.g. a text field); hosts should not treat keys as hotkeys.
+    pub wants_keyboard: bool,
 }
 
 impl UiFrameOutput {
@@ -37,6 +52,7 @@ impl UiFrameOutput {
     pub fn empty() -> Self {
         Self {
             draw_list: UiDrawList::new(),
+            wants_keyboard: false,
         }
     }
 }
@@ -47,6 +63,13 @@ pub trait UiBuildFn {
     fn build(&mut self, ctx_any: &mut dyn Any);
 }
 
+/// Text clipboard access for providers.
+/// Installed by the host so the UI crate stays free of platform clipboard dependencies.
+pub trait UiClipboard: Send {
+    fn get_text(&mut self) -> Option<String>;
+    fn set_text(&mut self, text: String);
+}
+
 /// Provider kind selection.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum UiProviderKind {
@@ -84,6 +107,9 @@ pub trait UiProvider: Send {
     /// IMPORTANT: UI must not consume platform input directly; input must come from INPUT plugin.
     fn on_platform_event(&mut self, _window: &dyn Any, _event: &dyn Any) {}
 
+    /// Install clipboard used for copy/cut/paste (optional).
+    fn set_clipboard(&mut self, _clipboard: Box<dyn UiClipboard>) {}
+
     /// Run one UI frame.
     fn run_frame(
         &mut self,
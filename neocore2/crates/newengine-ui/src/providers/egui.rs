@@ -2,15 +2,22 @@
 
 use crate::draw::UiDrawList;
 use crate::input::UiInputFrame;
-use crate::provider::{UiBuildFn, UiFrameDesc, UiFrameOutput, UiProvider, UiProviderKind};
+use crate::provider::{
+    UiBuildFn, UiClipboard, UiFrameDesc, UiFrameOutput, UiProvider, UiProviderKind,
+};
 use std::any::Any;
 
+mod ime;
 mod translate;
 
+use ime::ImeBridge;
+
 pub struct EguiUiProvider {
     ctx: egui::Context,
     state: Option<egui_winit::State>,
     draw_list: UiDrawList,
+    ime: ImeBridge,
+    clipboard: Option<Box<dyn UiClipboard>>,
 }
 
 impl EguiUiProvider {
@@ -20,6 +27,8 @@ impl EguiUiProvider {
             ctx: egui::Context::default(),
             state: None,
             draw_list: UiDrawList::new(),
+            ime: ImeBridge::default(),
+            clipboard: None,
         }
     }
 
@@ -102,12 +111,63 @@ impl EguiUiProvider {
         }
     }
 
-    fn inject_input_events(raw: &mut egui::RawInput, input: &UiInputFrame) {
+    /// Ctrl+C / Ctrl+X / Ctrl+V as egui clipboard events (letter keys are not mapped to `egui::Key`).
+    fn push_clipboard_events(
+        raw: &mut egui::RawInput,
+        input: &UiInputFrame,
+        clipboard: Option<&mut Box<dyn UiClipboard>>,
+    ) {
+        if !raw.modifiers.command {
+            return;
+        }
+
+        if input.is_key_pressed(winit::keyboard::KeyCode::KeyC as u32) {
+            raw.events.push(egui::Event::Copy);
+        }
+        if input.is_key_pressed(winit::keyboard::KeyCode::KeyX as u32) {
+            raw.events.push(egui::Event::Cut);
+        }
+        if input.is_key_pressed(winit::keyboard::KeyCode::KeyV as u32) {
+            if let Some(text) = clipboard.and_then(|c| c.get_text()) {
+                if !text.is_empty() {
+                    raw.events.push(egui::Event::Paste(text));
+                }
+            }
+        }
+    }
+
+    /// Overrides the scale egui_winit read from the window, e.g. right after
+    /// `ScaleFactorChanged` when the window may still report the old value.
+    fn apply_native_scale(
+        raw: &mut egui::RawInput,
+        native: f32,
+        zoom: f32,
+        size: winit::dpi::PhysicalSize<u32>,
+    ) {
+        let native = native.max(0.0001);
+        let ppp = native * zoom;
+
+        if let Some(vp) = raw.viewports.get_mut(&raw.viewport_id) {
+            vp.native_pixels_per_point = Some(native);
+        }
+        raw.screen_rect = Some(egui::Rect::from_min_size(
+            egui::Pos2::ZERO,
+            egui::vec2(size.width as f32 / ppp, size.height as f32 / ppp),
+        ));
+    }
+
+    fn inject_input_events(
+        raw: &mut egui::RawInput,
+        input: &UiInputFrame,
+        pixels_per_point: f32,
+        ime: &mut ImeBridge,
+        clipboard: Option<&mut Box<dyn UiClipboard>>,
+    ) {
         raw.modifiers = Self::compute_modifiers(input);
 
         // egui expects positions in "points" (logical units).
         // INPUT plugin usually reports physical pixels.
-        let ppp = raw.viewport().native_pixels_per_point.unwrap_or(1.0).max(0.0001);
+        let ppp = pixels_per_point.max(0.0001);
         let to_pt = |v: f32| v / ppp;
 
         let mouse_pos_pt = input
@@ -185,18 +245,16 @@ impl EguiUiProvider {
             }
         }
 
-        if !input.text.is_empty() {
-            raw.events.push(egui::Event::Text(input.text.clone()));
-        }
+        Self::push_clipboard_events(raw, input, clipboard);
 
-        if !input.ime_commit.is_empty() {
-            raw.events.push(egui::Event::Text(input.ime_commit.clone()));
+        // Shortcuts like Ctrl+V also deliver a control character as text; drop those.
+        let text: String = input.text.chars().filter(|c| !c.is_control()).collect();
+        if !text.is_empty() {
+            raw.events.push(egui::Event::Text(text));
         }
 
-        if !input.ime_preedit.is_empty() {
-            raw.events
-                .push(egui::Event::Ime(egui::ImeEvent::Preedit(input.ime_preedit.clone())));
-        }
+        // IME: composition replaces itself in the focused TextEdit, commit finalizes it.
+        ime.push_events(input, &mut raw.events);
     }
 }
 
@@ -220,6 +278,11 @@ impl UiProvider for EguiUiProvider {
         // HARD NOOP: input must come exclusively from INPUT plugin.
     }
 
+    #[inline]
+    fn set_clipboard(&mut self, clipboard: Box<dyn UiClipboard>) {
+        self.clipboard = Some(clipboard);
+    }
+
     fn run_frame(
         &mut self,
         window: &dyn Any,
@@ -236,14 +299,45 @@ impl UiProvider for EguiUiProvider {
             state.take_egui_input(w)
         };
 
+        let size = w.inner_size();
+        if let Some(native) = frame.pixels_per_point {
+            Self::apply_native_scale(&mut raw_input, native, self.ctx.zoom_factor(), size);
+        }
+
+        // Points include the user zoom on top of the native scale.
+        let ppp = raw_input
+            .viewport()
+            .native_pixels_per_point
+            .unwrap_or(1.0)
+            * self.ctx.zoom_factor();
+
         // Inject canonical input from INPUT plugin snapshot.
         if let Some(ref input) = frame.input {
-            Self::inject_input_events(&mut raw_input, input);
+            Self::inject_input_events(
+                &mut raw_input,
+                input,
+                ppp,
+                &mut self.ime,
+                self.clipboard.as_mut(),
+            );
         }
 
         self.ctx.begin_pass(raw_input);
         build.build(&mut self.ctx);
-        let full_output = self.ctx.end_pass();
+        let mut full_output = self.ctx.end_pass();
+
+        // IME allow/cursor area is handled by the bridge (caret-anchored candidate window);
+        // egui_winit never sees `ime`, so it does not fight over the window state.
+        let ime_output = full_output.platform_output.ime.take();
+        self.ime.apply_output(w, ime_output, full_output.pixels_per_point);
+
+        // Copied text goes to the host clipboard when installed; otherwise egui_winit's own.
+        if let Some(clipboard) = self.clipboard.as_mut() {
+            let copied = std::mem::take(&mut full_output.platform_output.copied_text);
+            if !copied.is_empty() {
+                clipboard.set_text(copied);
+            }
+        }
 
         {
             let state = self.ensure_state(w);
@@ -252,9 +346,12 @@ impl UiProvider for EguiUiProvider {
 
         self.draw_list.clear();
         translate::egui_output_to_draw_list(&self.ctx, full_output, &mut self.draw_list);
+        // Match the swapchain exactly; points * ppp can round one pixel off at fractional scales.
+        self.draw_list.screen_size_px = [size.width, size.height];
 
         UiFrameOutput {
             draw_list: self.draw_list.clone(),
+            wants_keyboard: self.ctx.wants_keyboard_input(),
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,102 @@
+use crate::input::UiInputFrame;
+
+/// Bridges IME state from the INPUT plugin snapshot into egui, and egui's IME output back to
+/// the window.
+///
+/// egui's `TextEdit` expects the same event sequence `egui_winit` would produce:
+/// `Enabled -> Preedit* -> Commit -> Disabled`, with `Preedit` only on change (each one
+/// replaces the previously inserted composition). Since input is not fed through
+/// `egui_winit::State`, this type reproduces that sequencing from the polled snapshot.
+#[derive(Debug, Default)]
+pub(super) struct ImeBridge {
+    /// `Enabled` was sent to egui and not yet matched by `Disabled`.
+    sent_enabled: bool,
+    /// Last platform-reported enabled flag (edge detection).
+    platform_enabled: bool,
+    /// Last preedit forwarded to egui.
+    preedit: String,
+
+    /// Last value passed to `Window::set_ime_allowed`.
+    allowed: bool,
+    /// Last cursor area (physical px) passed to `Window::set_ime_cursor_area`.
+    cursor_area_px: Option<egui::Rect>,
+}
+
+impl ImeBridge {
+    pub(super) fn push_events(&mut self, input: &UiInputFrame, events: &mut Vec<egui::Event>) {
+        let enabled_edge = input.ime_enabled && !self.platform_enabled;
+        self.platform_enabled = input.ime_enabled;
+
+        let preedit_changed = input.ime_preedit != self.preedit;
+        let has_commit = !input.ime_commit.is_empty();
+
+        if enabled_edge || (preedit_changed && !input.ime_preedit.is_empty()) || has_commit {
+            self.enable(events);
+        }
+
+        if preedit_changed {
+            self.preedit.clear();
+            self.preedit.push_str(&input.ime_preedit);
+            if self.sent_enabled {
+                events.push(egui::Event::Ime(egui::ImeEvent::Preedit(
+                    self.preedit.clone(),
+                )));
+            }
+        }
+
+        if has_commit {
+            events.push(egui::Event::Ime(egui::ImeEvent::Commit(
+                input.ime_commit.clone(),
+            )));
+            self.preedit.clear();
+            self.disable(events);
+        } else if !input.ime_enabled && input.ime_preedit.is_empty() {
+            self.disable(events);
+        }
+    }
+
+    /// Applies `PlatformOutput::ime`: toggles IME on the window and positions the candidate
+    /// window at the text cursor. Calls are debounced; winit forwards them to the OS.
+    pub(super) fn apply_output(
+        &mut self,
+        window: &winit::window::Window,
+        ime: Option<egui::output::IMEOutput>,
+        pixels_per_point: f32,
+    ) {
+        let allowed = ime.is_some();
+        if self.allowed != allowed {
+            self.allowed = allowed;
+            window.set_ime_allowed(allowed);
+        }
+
+        let Some(ime) = ime else {
+            self.cursor_area_px = None;
+            return;
+        };
+
+        let area = pixels_per_point * ime.cursor_rect;
+        if self.cursor_area_px != Some(area) {
+            self.cursor_area_px = Some(area);
+            window.set_ime_cursor_area(
+                winit::dpi::PhysicalPosition::new(area.min.x, area.min.y),
+                winit::dpi::PhysicalSize::new(area.width(), area.height()),
+            );
+        }
+    }
+
+    #[inline]
+    fn enable(&mut self, events: &mut Vec<egui::Event>) {
+        if !self.sent_enabled {
+            events.push(egui::Event::Ime(egui::ImeEvent::Enabled));
+            self.sent_enabled = true;
+        }
+    }
+
+    #[inline]
+    fn disable(&mut self, events: &mut Vec<egui::Event>) {
+        if self.sent_enabled {
+            events.push(egui::Event::Ime(egui::ImeEvent::Disabled));
+            self.sent_enabled = false;
+        }
+    }
+}
@@ -4,7 +4,11 @@ pub mod reserved {
     use super::UiTexId;
 
     pub const FONT_ATLAS: UiTexId = UiTexId(1);
+    /// Bitmap font of [`crate::painter::UiPainter`].
+    pub const PAINTER_FONT: UiTexId = UiTexId(2);
     pub const USER_BEGIN: u32 = 16;
+    /// Streaming video textures; [`super::UiTexAllocator`] ids stay below this.
+    pub const MEDIA_BEGIN: u32 = 0x0100_0000;
 }
 
 #[derive(Debug, Default)]